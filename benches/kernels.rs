@@ -0,0 +1,140 @@
+//! Benchmarks of the hot paths of a wave channel simulation
+//!
+//! Run with `cargo bench`; criterion keeps the previous results in `target/criterion`
+//! and reports regressions against them.
+
+use coastal_engineering_platform::analysis::spectral::{fft, variance_density};
+use coastal_engineering_platform::solver::{RightBoundary, ShallowWaterSolver};
+use coastal_engineering_platform::units::{Length, Time};
+use coastal_engineering_platform::waves::{DEFAULT_SEED, DispersionMode, DispersionSolver, SeededRng, WaveForcing, WaveParameters, WaveSource, WaveSpectrum};
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use std::f64::consts::PI;
+
+/// Regular wave of the default wave channel: H = 0.5 m, T = 8 s in 10 m of water
+fn channel_wave() -> WaveParameters {
+    DispersionSolver::new().solve_wave_parameters(Length::meters(0.5), Time::seconds(8.0), Length::meters(10.0)).unwrap()
+}
+
+/// Wave number from the dispersion relation, from shallow to deep water
+fn dispersion(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dispersion");
+    for mode in [DispersionMode::OneLayer, DispersionMode::TwoLayer] {
+        let solver = DispersionSolver::new().with_mode(mode);
+        group.bench_function(BenchmarkId::new("solve_wave_number", mode), |b| {
+            b.iter(|| {
+                for period in [2.0, 4.0, 8.0, 16.0] {
+                    black_box(solver.solve_wave_number(2.0 * PI / black_box(period), black_box(10.0)).unwrap());
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Wavemaker boundary signal over one wave period sampled every 0.05 s
+fn boundary_signal(c: &mut Criterion) {
+    let mut forcing = WaveForcing::new();
+    forcing.add_source(WaveSource::boundary(channel_wave()).with_generation_duration(80.0));
+    forcing.add_source(WaveSource::internal(channel_wave(), 50.0).with_phase(PI / 2.0).with_generation_duration(80.0));
+    let x_points: Vec<f64> = (0..200).map(|i| i as f64 * 0.5).collect();
+
+    let mut group = c.benchmark_group("boundary_signal");
+    group.bench_function("boundary_velocity", |b| {
+        b.iter(|| {
+            for step in 0..160 {
+                forcing.update_time(step as f64 * 0.05);
+                black_box(forcing.boundary_velocity());
+                black_box(forcing.boundary_surface_elevation());
+            }
+        })
+    });
+    group.bench_function("surface_elevation_series", |b| {
+        forcing.update_time(4.0);
+        b.iter(|| black_box(forcing.surface_elevation_series(black_box(&x_points))))
+    });
+    group.finish();
+}
+
+/// Forward and inverse FFT and Welch spectrum of gauge records of increasing length, and synthesis of
+/// irregular waves from a JONSWAP spectrum: its components, then the surface of a record summing them
+fn spectral(c: &mut Criterion) {
+    let mut group = c.benchmark_group("spectral");
+    for length in [1024, 8192, 65536] {
+        let signal: Vec<f64> = (0..length)
+            .map(|i| {
+                let time = i as f64 * 0.05;
+                (2.0 * PI * time / 8.0).sin() + 0.3 * (2.0 * PI * time / 3.1).cos()
+            })
+            .collect();
+        group.bench_with_input(BenchmarkId::new("fft", length), &signal, |b, signal| {
+            b.iter(|| {
+                let mut re = signal.clone();
+                let mut im = vec![0.0; signal.len()];
+                fft(&mut re, &mut im, false);
+                black_box((re, im))
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("inverse_fft", length), &signal, |b, signal| {
+            let mut re = signal.clone();
+            let mut im = vec![0.0; signal.len()];
+            fft(&mut re, &mut im, false);
+            b.iter(|| {
+                let (mut re, mut im) = (re.clone(), im.clone());
+                fft(&mut re, &mut im, true);
+                black_box((re, im))
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("variance_density", length), &signal, |b, signal| {
+            b.iter(|| black_box(variance_density(signal, 0.05, 512)))
+        });
+    }
+
+    let spectrum = WaveSpectrum::jonswap(0.5, 8.0, 3.3).unwrap();
+    let rng = SeededRng::new(DEFAULT_SEED);
+    for count in [64, 256, 1024] {
+        group.bench_with_input(BenchmarkId::new("components", count), &count, |b, &count| {
+            b.iter(|| black_box(spectrum.components(black_box(count), &rng)))
+        });
+        let components = spectrum.components(count, &rng);
+        group.bench_with_input(BenchmarkId::new("synthesis", count), &components, |b, components| {
+            b.iter(|| {
+                let surface: Vec<f64> = (0..8192)
+                    .map(|i| {
+                        let time = i as f64 * 0.05;
+                        components
+                            .iter()
+                            .map(|component| component.amplitude * (2.0 * PI * component.frequency * time + component.phase).cos())
+                            .sum()
+                    })
+                    .collect();
+                black_box(surface)
+            })
+        });
+    }
+    group.finish();
+}
+
+/// One 0.05 s animation step of the solver at several grid resolutions
+fn solver_step(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solver_step");
+    group.sample_size(20);
+    for mode in [None, Some(DispersionMode::OneLayer), Some(DispersionMode::TwoLayer)] {
+        let name = mode.map_or("hydrostatic".to_string(), |mode| mode.to_string());
+        for cells in [200, 1000, 5000] {
+            let mut solver = ShallowWaterSolver::new(1000.0, cells, 10.0, RightBoundary::default())
+                .unwrap()
+                .with_non_hydrostatic(mode);
+            let mut forcing = WaveForcing::new();
+            forcing.add_source(WaveSource::boundary(channel_wave()));
+            // Start from a developed wave field rather than still water
+            solver.advance(20.0, &mut forcing);
+            group.bench_function(BenchmarkId::new(name.as_str(), cells), |b| {
+                b.iter(|| black_box(solver.advance(0.05, &mut forcing)))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, dispersion, boundary_signal, spectral, solver_step);
+criterion_main!(benches);
@@ -0,0 +1,101 @@
+use crate::analysis::Transect;
+use crate::solver::{BasinSolver, Berth};
+
+/// Wave agitation along a berth: the wave heights sampled along the line the moored ship lies along
+///
+/// Agitation is judged against the highest wave height the ships at the berth accept for loading and
+/// unloading, Hs ≈ 0.3 m for small craft and ferries to 1 to 1.5 m for large bulk carriers (PIANC, 1995).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BerthAgitation {
+    pub name: String,       // Label of the berth
+    pub distances: Vec<f64>, // Positions of the samples in the water along the berth line [m]
+    pub heights: Vec<f64>,  // Wave height at the samples [m]
+    pub limit: f64,         // Highest acceptable wave height [m]
+    pub offshore: f64,      // Wave height generated offshore [m]
+}
+
+impl BerthAgitation {
+    /// Mean wave height along the berth [m]
+    pub fn mean(&self) -> f64 {
+        if self.heights.is_empty() {
+            return 0.0;
+        }
+        self.heights.iter().sum::<f64>() / self.heights.len() as f64
+    }
+
+    /// Lowest wave height along the berth [m]
+    pub fn min(&self) -> f64 {
+        self.heights.iter().copied().fold(f64::INFINITY, f64::min)
+    }
+
+    /// Highest wave height along the berth [m]
+    pub fn max(&self) -> f64 {
+        self.heights.iter().copied().fold(0.0, f64::max)
+    }
+
+    /// Agitation coefficient, the ratio of the mean wave height along the berth to the offshore wave height
+    pub fn coefficient(&self) -> f64 {
+        self.mean() / self.offshore
+    }
+
+    /// Share of the berth where the wave height exceeds the acceptable one
+    pub fn exceedance(&self) -> f64 {
+        if self.heights.is_empty() {
+            return 0.0;
+        }
+        self.heights.iter().filter(|&&height| height > self.limit).count() as f64 / self.heights.len() as f64
+    }
+
+    /// Check if the wave height stays acceptable all along the berth
+    pub fn is_acceptable(&self) -> bool {
+        self.max() <= self.limit
+    }
+}
+
+/// Agitation at each berth from wave heights over the cells of a basin, about one sample per cell along
+/// each berth; samples falling in a structure are left out, the others take the height of their cell
+pub fn berth_agitation(solver: &BasinSolver, heights: &[f64], berths: &[Berth], offshore: f64) -> Vec<BerthAgitation> {
+    let grid = solver.grid();
+    berths
+        .iter()
+        .map(|berth| {
+            let (mut distances, mut values) = (Vec::new(), Vec::new());
+            if let Ok(line) = Transect::new(berth.start, berth.end) {
+                for distance in line.distances(line.samples(grid)) {
+                    let [x, y] = line.point(distance);
+                    let (i, j) = grid.cell_at(x, y);
+                    if !solver.is_solid(i, j) {
+                        distances.push(distance);
+                        values.push(heights[grid.index(i, j)]);
+                    }
+                }
+            }
+            BerthAgitation { name: berth.name.clone(), distances, heights: values, limit: berth.limit, offshore }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::HarborStructure;
+
+    #[test]
+    fn test_agitation_along_a_berth() {
+        // Heights growing along x, a quay over the last fifth of the basin
+        let quay = HarborStructure::new("Quay", vec![[8.0, 0.0], [10.0, 0.0], [10.0, 2.0], [8.0, 2.0]], 0.9);
+        let solver = BasinSolver::new(10.0, 2.0, 10, 2, 0.5).unwrap().with_structures(vec![quay]).unwrap();
+        let heights: Vec<f64> = (0..solver.grid().cells()).map(|n| 0.01 * (n % 10) as f64).collect();
+        let berth = Berth { name: "Berth".to_string(), start: [0.5, 0.5], end: [9.5, 0.5], limit: 0.045 };
+
+        let agitation = berth_agitation(&solver, &heights, &[berth], 0.1);
+        assert_eq!(agitation.len(), 1);
+        let berth = &agitation[0];
+        // Ten samples one cell apart, the last two in the quay
+        assert_eq!(berth.heights, vec![0.0, 0.01, 0.02, 0.03, 0.04, 0.05, 0.06, 0.07]);
+        assert!((berth.mean() - 0.035).abs() < 1e-12 && (berth.coefficient() - 0.35).abs() < 1e-12);
+        assert_eq!((berth.min(), berth.max()), (0.0, 0.07));
+        assert_eq!(berth.exceedance(), 0.375);
+        assert!(!berth.is_acceptable());
+    }
+}
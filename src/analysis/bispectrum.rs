@@ -0,0 +1,200 @@
+use crate::analysis::spectral::{fft, mean};
+use std::f64::consts::PI;
+
+/// Segment-averaged bispectrum and bicoherence of a record
+///
+/// The bispectrum B(f₁, f₂) = E[X(f₁) X(f₂) X*(f₁ + f₂)] is non-zero only when the phases of the three
+/// components are coupled, as in triad interactions and harmonic generation over bars and in shallow water.
+/// The bicoherence b² = |B|² / (E[|X(f₁) X(f₂)|²] E[|X(f₁ + f₂)|²]) normalizes it between 0 and 1 (Kim & Powers, 1979).
+#[derive(Debug, Clone)]
+pub struct Bispectrum {
+    /// Frequencies of the analysed bins, starting at zero [Hz]
+    frequencies: Vec<f64>,
+    /// Squared bicoherence, indexed by [f₁ bin][f₂ bin]
+    bicoherence: Vec<Vec<f64>>,
+    /// Biphase arg B, indexed by [f₁ bin][f₂ bin] [rad]
+    biphase: Vec<Vec<f64>>,
+    /// Number of averaged segments
+    segments: usize,
+}
+
+impl Bispectrum {
+    /// Average over Hann-windowed segments with 50% overlap, up to a maximum frequency
+    pub fn new(signal: &[f64], sample_interval: f64, segment_length: usize, max_frequency: f64) -> Result<Self, String> {
+        if sample_interval <= 0.0 {
+            return Err("Sample interval must be positive".to_string());
+        }
+        if !segment_length.is_power_of_two() || segment_length < 8 {
+            return Err("Segment length must be a power of two of at least 8 samples".to_string());
+        }
+        if signal.len() < segment_length {
+            return Err("Record is shorter than one segment".to_string());
+        }
+
+        let resolution = 1.0 / (segment_length as f64 * sample_interval);
+        // Both frequencies and their sum must stay below the Nyquist frequency
+        let bins = ((max_frequency / resolution).floor() as usize + 1).min(segment_length / 4 + 1);
+        if bins < 2 {
+            return Err("Maximum frequency is below the frequency resolution".to_string());
+        }
+
+        let window: Vec<f64> = (0..segment_length)
+            .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f64 / segment_length as f64).cos())
+            .collect();
+        let step = segment_length / 2;
+        let starts: Vec<usize> = (0..=signal.len() - segment_length).step_by(step).collect();
+
+        let mut triple = vec![vec![(0.0, 0.0); bins]; bins];
+        let mut pair_power = vec![vec![0.0; bins]; bins];
+        let mut sum_power = vec![vec![0.0; bins]; bins];
+        for &start in &starts {
+            let segment = &signal[start..start + segment_length];
+            let segment_mean = mean(segment);
+            let mut re: Vec<f64> = segment.iter().zip(&window).map(|(value, w)| (value - segment_mean) * w).collect();
+            let mut im = vec![0.0; segment_length];
+            fft(&mut re, &mut im, false);
+
+            for i in 0..bins {
+                for j in 0..bins {
+                    // X(f₁) X(f₂)
+                    let pair = (re[i] * re[j] - im[i] * im[j], re[i] * im[j] + im[i] * re[j]);
+                    // times X*(f₁ + f₂)
+                    let (sum_re, sum_im) = (re[i + j], -im[i + j]);
+                    triple[i][j].0 += pair.0 * sum_re - pair.1 * sum_im;
+                    triple[i][j].1 += pair.0 * sum_im + pair.1 * sum_re;
+                    pair_power[i][j] += pair.0 * pair.0 + pair.1 * pair.1;
+                    sum_power[i][j] += sum_re * sum_re + sum_im * sum_im;
+                }
+            }
+        }
+
+        let mut bicoherence = vec![vec![0.0; bins]; bins];
+        let mut biphase = vec![vec![0.0; bins]; bins];
+        for i in 0..bins {
+            for j in 0..bins {
+                let (b_re, b_im) = triple[i][j];
+                let denominator = pair_power[i][j] * sum_power[i][j];
+                if denominator > 0.0 {
+                    bicoherence[i][j] = (b_re * b_re + b_im * b_im) / denominator;
+                }
+                biphase[i][j] = b_im.atan2(b_re);
+            }
+        }
+
+        Ok(Self {
+            frequencies: (0..bins).map(|i| i as f64 * resolution).collect(),
+            bicoherence,
+            biphase,
+            segments: starts.len(),
+        })
+    }
+
+    /// Get frequencies of the analysed bins [Hz]
+    pub fn frequencies(&self) -> &[f64] {
+        &self.frequencies
+    }
+
+    /// Get squared bicoherence, indexed by [f₁ bin][f₂ bin]
+    pub fn bicoherence(&self) -> &[Vec<f64>] {
+        &self.bicoherence
+    }
+
+    /// Get biphase, indexed by [f₁ bin][f₂ bin] [rad]
+    pub fn biphase(&self) -> &[Vec<f64>] {
+        &self.biphase
+    }
+
+    /// Number of averaged segments
+    pub fn segments(&self) -> usize {
+        self.segments
+    }
+
+    /// Frequency resolution [Hz]
+    pub fn resolution(&self) -> f64 {
+        self.frequencies[1]
+    }
+
+    /// Squared bicoherence at the bins nearest to two frequencies
+    pub fn bicoherence_at(&self, f1: f64, f2: f64) -> f64 {
+        let bin = |f: f64| ((f / self.resolution()).round().max(0.0) as usize).min(self.frequencies.len() - 1);
+        self.bicoherence[bin(f1)][bin(f2)]
+    }
+
+    /// 95% significance level of the squared bicoherence for uncoupled waves, 6/ν with ν = 2 × segments
+    pub fn significance_level(&self) -> f64 {
+        3.0 / self.segments as f64
+    }
+
+    /// Strongest triad interaction, excluding the mean: (f₁, f₂, b²) with f₁ ≥ f₂
+    pub fn strongest_interaction(&self) -> Option<(f64, f64, f64)> {
+        let bins = self.frequencies.len();
+        (1..bins)
+            .flat_map(|i| (1..=i).map(move |j| (i, j)))
+            .map(|(i, j)| (self.frequencies[i], self.frequencies[j], self.bicoherence[i][j]))
+            .max_by(|a, b| a.2.total_cmp(&b.2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Narrow-banded random wave from a deterministic generator
+    fn random_wave(samples: usize, dt: f64) -> Vec<f64> {
+        let mut state: u64 = 12345;
+        let components: Vec<(f64, f64)> = (0..40)
+            .map(|n| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                let phase = (state >> 11) as f64 / (1u64 << 53) as f64 * 2.0 * PI;
+                (0.2 + 0.001 * n as f64, phase)
+            })
+            .collect();
+        (0..samples)
+            .map(|i| {
+                let t = i as f64 * dt;
+                components.iter().map(|(f, phase)| 0.05 * (2.0 * PI * f * t + phase).cos()).sum()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_harmonic_generation_is_phase_coupled() {
+        let dt = 0.25;
+        let linear = random_wave(8192, dt);
+        // Quadratic nonlinearity creates a bound harmonic phase locked to the primary waves
+        let nonlinear: Vec<f64> = linear.iter().map(|eta| eta + 0.3 * eta * eta).collect();
+
+        let coupled = Bispectrum::new(&nonlinear, dt, 256, 0.5).unwrap();
+        let uncoupled = Bispectrum::new(&linear, dt, 256, 0.5).unwrap();
+        assert!(coupled.segments() > 50);
+
+        let b_coupled = coupled.bicoherence_at(0.22, 0.22);
+        let b_uncoupled = uncoupled.bicoherence_at(0.22, 0.22);
+        assert!(b_coupled > 0.5, "Coupled b² = {:.3}", b_coupled);
+        assert!(b_uncoupled < 2.0 * uncoupled.significance_level(), "Uncoupled b² = {:.3}", b_uncoupled);
+
+        // Positive quadratic coupling gives crests sharper than troughs: biphase near zero
+        let bin = (0.22 / coupled.resolution()).round() as usize;
+        assert!(coupled.biphase()[bin][bin].abs() < 0.3);
+    }
+
+    #[test]
+    fn test_strongest_interaction() {
+        let dt = 0.25;
+        let linear = random_wave(4096, dt);
+        let nonlinear: Vec<f64> = linear.iter().map(|eta| eta + 0.3 * eta * eta).collect();
+        let bispectrum = Bispectrum::new(&nonlinear, dt, 256, 0.5).unwrap();
+
+        let (f1, f2, b) = bispectrum.strongest_interaction().unwrap();
+        assert!(f1 >= f2);
+        assert!((f1 - 0.22).abs() < 0.05 && (f2 - 0.22).abs() < 0.05, "({:.3}, {:.3}) Hz", f1, f2);
+        assert!(b > bispectrum.significance_level());
+    }
+
+    #[test]
+    fn test_invalid_bispectrum() {
+        assert!(Bispectrum::new(&[0.0; 100], 0.1, 100, 1.0).is_err());
+        assert!(Bispectrum::new(&[0.0; 100], 0.1, 128, 1.0).is_err());
+        assert!(Bispectrum::new(&[0.0; 256], 0.1, 64, 0.01).is_err());
+    }
+}
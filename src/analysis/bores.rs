@@ -0,0 +1,288 @@
+use crate::analysis::snapshots::Snapshot;
+use serde::{Deserialize, Serialize};
+
+/// Water depth ahead of a front below which it runs onto a dry bed, a run-up tongue rather than a bore [m]
+const MIN_FRONT_DEPTH: f64 = 1e-3;
+
+/// Celerity below which a front counts as standing, as a fraction of the long wave speed √(gh) ahead of it
+const STATIONARY_CELERITY: f64 = 0.1;
+
+/// Thresholds a steep front of the surface must pass to be detected as a bore
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BoreCriteria {
+    /// Smallest surface slope |∂η/∂x| of the front
+    pub min_gradient: f64,
+    /// Smallest Froude number of the jump between the depths on both sides of the front
+    pub min_froude: f64,
+}
+
+impl Default for BoreCriteria {
+    fn default() -> Self {
+        Self {
+            min_gradient: 0.1,
+            min_froude: 1.2,
+        }
+    }
+}
+
+/// Kind of a tracked front, from its speed over the bed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrontKind {
+    /// Front travelling along the channel, as a broken wave in the surf zone or a dam-break bore
+    Bore,
+    /// Front standing still where a supercritical flow slows down, as in the backwash of the swash zone
+    HydraulicJump,
+}
+
+impl std::fmt::Display for FrontKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrontKind::Bore => write!(f, "Bore"),
+            FrontKind::HydraulicJump => write!(f, "Hydraulic jump"),
+        }
+    }
+}
+
+/// Bore front found in one surface profile
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoreObservation {
+    /// Snapshot time [s]
+    pub time: f64,
+    /// Position of the steepest part of the front [m]
+    pub position: f64,
+    /// Water depth ahead of the front, on its shallow side [m]
+    pub depth: f64,
+    /// Rise of the surface across the front [m]
+    pub height: f64,
+    /// Froude number of the jump from the depths on both sides
+    pub froude: f64,
+    /// Whether the shallow side is shoreward, as for a bore running up the channel
+    pub facing_shoreward: bool,
+}
+
+/// Bore followed through consecutive snapshots
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoreTrack {
+    /// Observations in time order
+    observations: Vec<BoreObservation>,
+}
+
+impl BoreTrack {
+    /// Get observations in time order
+    pub fn observations(&self) -> &[BoreObservation] {
+        &self.observations
+    }
+
+    /// First observation
+    pub fn first(&self) -> &BoreObservation {
+        &self.observations[0]
+    }
+
+    /// Last observation
+    pub fn last(&self) -> &BoreObservation {
+        &self.observations[self.observations.len() - 1]
+    }
+
+    /// Time over which the front was followed [s]
+    pub fn duration(&self) -> f64 {
+        self.last().time - self.first().time
+    }
+
+    /// Front speed from a least-squares fit of position against time, positive shoreward [m/s]
+    pub fn celerity(&self) -> Option<f64> {
+        if self.observations.len() < 3 || self.duration() <= 0.0 {
+            return None;
+        }
+        let n = self.observations.len() as f64;
+        let mean_time = self.observations.iter().map(|o| o.time).sum::<f64>() / n;
+        let mean_position = self.observations.iter().map(|o| o.position).sum::<f64>() / n;
+        let covariance: f64 = self.observations.iter().map(|o| (o.time - mean_time) * (o.position - mean_position)).sum();
+        let variance: f64 = self.observations.iter().map(|o| (o.time - mean_time).powi(2)).sum();
+        Some(covariance / variance)
+    }
+
+    /// Largest jump Froude number along the track
+    pub fn max_froude(&self) -> f64 {
+        self.observations.iter().map(|o| o.froude).fold(0.0, f64::max)
+    }
+
+    /// Front observed at a snapshot time
+    pub fn at(&self, time: f64) -> Option<&BoreObservation> {
+        self.observations.iter().find(|o| (o.time - time).abs() < 1e-9)
+    }
+
+    /// Hydraulic jump when the front stands still compared with the long wave speed ahead of it, bore otherwise
+    pub fn kind(&self) -> FrontKind {
+        let depth = self.observations.iter().map(|o| o.depth).sum::<f64>() / self.observations.len() as f64;
+        match self.celerity() {
+            Some(celerity) if celerity.abs() < STATIONARY_CELERITY * (9.81 * depth).sqrt() => FrontKind::HydraulicJump,
+            _ => FrontKind::Bore,
+        }
+    }
+}
+
+/// Froude number of a hydraulic jump from the ratio of the depths behind and ahead of it (Bélanger)
+/// Fr = √(r (r + 1) / 2), the speed of the flow into the jump relative to the long wave speed ahead
+pub fn jump_froude(depth_ratio: f64) -> f64 {
+    (0.5 * depth_ratio * (depth_ratio + 1.0)).sqrt()
+}
+
+/// Bore fronts of one surface profile over a bed of known still water depth
+///
+/// A front is a run of consecutive points where the surface slope has the same sign and exceeds
+/// the gradient threshold. The surface rise across it gives the depth ratio of the jump, and hence
+/// its Froude number, which must exceed the Froude threshold. Fronts running onto a dry bed are
+/// run-up tongues and are left out.
+pub fn find_bores(time: f64, surface_elevation: &[f64], still_water_depth: &[f64], grid_spacing: f64, criteria: BoreCriteria) -> Vec<BoreObservation> {
+    let n = surface_elevation.len().min(still_water_depth.len());
+    let slope = |i: usize| (surface_elevation[i + 1] - surface_elevation[i]) / grid_spacing;
+    let mut fronts = Vec::new();
+    let mut i = 0;
+    while i + 1 < n {
+        let sign = slope(i).signum();
+        if slope(i).abs() < criteria.min_gradient {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        let mut steepest = i;
+        while i + 1 < n && slope(i).abs() >= criteria.min_gradient && slope(i).signum() == sign {
+            if slope(i).abs() > slope(steepest).abs() {
+                steepest = i;
+            }
+            i += 1;
+        }
+        // The front spans the points from start to i, its deep side behind the steep slope
+        let facing_shoreward = sign < 0.0;
+        let (behind, ahead) = if facing_shoreward { (start, i) } else { (i, start) };
+        let depth = still_water_depth[ahead] + surface_elevation[ahead];
+        let height = surface_elevation[behind] - surface_elevation[ahead];
+        if depth < MIN_FRONT_DEPTH {
+            continue;
+        }
+        let froude = jump_froude((depth + height) / depth);
+        if froude >= criteria.min_froude {
+            fronts.push(BoreObservation {
+                time,
+                position: (steepest as f64 + 0.5) * grid_spacing,
+                depth,
+                height,
+                froude,
+                facing_shoreward,
+            });
+        }
+    }
+    fronts
+}
+
+/// Follow bores through consecutive snapshots
+///
+/// Each front is matched to the nearest front facing the same way in the next snapshot that is
+/// within reach at the maximum celerity, in either direction since bores reflect and hydraulic jumps
+/// stand still. Fronts without a match start new tracks and tracks without a match end.
+pub fn track_bores<'a>(
+    snapshots: impl IntoIterator<Item = &'a Snapshot>,
+    still_water_depth: &[f64],
+    grid_spacing: f64,
+    criteria: BoreCriteria,
+    max_celerity: f64,
+) -> Vec<BoreTrack> {
+    let mut finished = Vec::new();
+    let mut active: Vec<BoreTrack> = Vec::new();
+    let mut previous_time: Option<f64> = None;
+
+    for snapshot in snapshots {
+        let fronts = find_bores(snapshot.time, &snapshot.surface_elevation, still_water_depth, grid_spacing, criteria);
+        let reach = previous_time.map_or(0.0, |time| max_celerity * (snapshot.time - time)) + grid_spacing;
+
+        let mut claimed = vec![false; fronts.len()];
+        let mut continuing = Vec::new();
+        for mut track in active.drain(..) {
+            let last = *track.last();
+            let matched = fronts
+                .iter()
+                .enumerate()
+                .filter(|(index, front)| !claimed[*index] && front.facing_shoreward == last.facing_shoreward && (front.position - last.position).abs() <= reach)
+                .min_by(|(_, a), (_, b)| (a.position - last.position).abs().total_cmp(&(b.position - last.position).abs()))
+                .map(|(index, _)| index);
+            match matched {
+                Some(index) => {
+                    claimed[index] = true;
+                    track.observations.push(fronts[index]);
+                    continuing.push(track);
+                }
+                None => finished.push(track),
+            }
+        }
+        for (front, claimed) in fronts.into_iter().zip(claimed) {
+            if !claimed {
+                continuing.push(BoreTrack { observations: vec![front] });
+            }
+        }
+        active = continuing;
+        previous_time = Some(snapshot.time);
+    }
+
+    finished.extend(active);
+    finished.sort_by(|a, b| a.first().time.total_cmp(&b.first().time).then(a.first().position.total_cmp(&b.first().position)));
+    finished
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Surface rising by a height over two grid spacings behind a front, over a 1 m depth
+    fn step(front: f64, height: f64, facing_shoreward: bool) -> Vec<f64> {
+        (0..201)
+            .map(|i| {
+                let behind = if facing_shoreward { front - i as f64 * 0.1 } else { i as f64 * 0.1 - front };
+                height * (behind / 0.2 + 0.5).clamp(0.0, 1.0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_find_bores() {
+        let depth = vec![1.0; 201];
+        let bores = find_bores(0.0, &step(8.0, 0.5, true), &depth, 0.1, BoreCriteria::default());
+        assert_eq!(bores.len(), 1);
+        assert!((bores[0].position - 8.0).abs() <= 0.1, "{}", bores[0].position);
+        assert!((bores[0].height - 0.5).abs() < 1e-9 && (bores[0].depth - 1.0).abs() < 1e-9);
+        assert!((bores[0].froude - jump_froude(1.5)).abs() < 1e-9);
+        assert!(bores[0].facing_shoreward);
+        assert!(!find_bores(0.0, &step(8.0, 0.5, false), &depth, 0.1, BoreCriteria::default())[0].facing_shoreward);
+
+        // A low jump, a gentle slope or a front onto a dry bed is not a bore
+        assert!(find_bores(0.0, &step(8.0, 0.1, true), &depth, 0.1, BoreCriteria::default()).is_empty());
+        let swell: Vec<f64> = (0..201).map(|i| 0.05 * (i as f64 * 0.1).sin()).collect();
+        assert!(find_bores(0.0, &swell, &depth, 0.1, BoreCriteria::default()).is_empty());
+        let dry: Vec<f64> = (0..201).map(|i| if i < 80 { 1.0 } else { 0.0 }).collect();
+        assert!(find_bores(0.0, &step(8.0, 0.5, true), &dry, 0.1, BoreCriteria::default()).is_empty());
+        assert_eq!(jump_froude(1.0), 1.0);
+    }
+
+    #[test]
+    fn test_track_bores() {
+        let depth = vec![1.0; 201];
+        let snapshots = |celerity: f64| -> Vec<Snapshot> {
+            (0..40)
+                .map(|n| {
+                    let time = n as f64 * 0.05;
+                    Snapshot { time, surface_elevation: step(5.0 + celerity * time, 0.5, true) }
+                })
+                .collect()
+        };
+
+        let tracks = track_bores(&snapshots(4.0), &depth, 0.1, BoreCriteria::default(), 8.0);
+        assert_eq!(tracks.len(), 1);
+        assert!((tracks[0].celerity().unwrap() - 4.0).abs() < 0.1);
+        assert_eq!(tracks[0].kind(), FrontKind::Bore);
+        assert_eq!(tracks[0].observations().len(), 40);
+        assert!(tracks[0].at(1.0).is_some());
+
+        let tracks = track_bores(&snapshots(0.0), &depth, 0.1, BoreCriteria::default(), 8.0);
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].kind(), FrontKind::HydraulicJump);
+    }
+}
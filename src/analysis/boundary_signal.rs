@@ -0,0 +1,219 @@
+use crate::analysis::expression::Expression;
+use crate::analysis::spectral::fft;
+use crate::waves::SpectralComponent;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Samples per wave period of an expression signal
+const SAMPLES_PER_PERIOD: f64 = 32.0;
+
+/// Most samples resolved by the Fourier decomposition of a signal
+const MAX_SAMPLES: usize = 8192;
+
+/// Most components generating a signal, the largest ones
+const MAX_COMPONENTS: usize = 256;
+
+/// Amplitude relative to the largest component below which a component is dropped
+const COMPONENT_THRESHOLD: f64 = 1e-3;
+
+/// Variables of an expression signal
+pub const SIGNAL_VARIABLES: [&str; 3] = ["t", "H", "T"];
+
+/// Surface elevation prescribed at the wavemaker, bypassing the wave theories
+///
+/// The signal is an expression η(t) of the time t [s], the wave height H [m] and period T [s], lasting
+/// the generation time of the wavemaker waves, or a measured or designed time series. It is generated as
+/// its Fourier series over its duration: the signal is resampled to a power of two of evenly spaced
+/// points and every harmonic of 1/duration becomes a regular component with its amplitude and phase,
+/// so the components add up to the signal at the wavemaker and each travels at its own celerity. The
+/// mean of the signal is left out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BoundarySignal {
+    Expression(Expression),
+    TimeSeries {
+        times: Vec<f64>,     // From zero, increasing [s]
+        elevation: Vec<f64>, // At the times [m]
+    },
+}
+
+impl BoundarySignal {
+    /// Signal of an expression of t, H and T
+    pub fn expression(source: &str) -> Result<Self, String> {
+        let expression = Expression::parse(source)?;
+        if let Some(name) = expression.variables().into_iter().find(|name| !SIGNAL_VARIABLES.contains(name)) {
+            return Err(format!("Unknown variable {}: the signal may use t, H and T", name));
+        }
+        Ok(BoundarySignal::Expression(expression))
+    }
+
+    /// Signal of a time series, its times shifted to start at zero
+    pub fn time_series(times: Vec<f64>, elevation: Vec<f64>) -> Result<Self, String> {
+        if times.len() < 4 || times.len() != elevation.len() {
+            return Err("Time series needs at least four samples, each with a time and an elevation".to_string());
+        }
+        if times.iter().chain(&elevation).any(|value| !value.is_finite()) || times.windows(2).any(|pair| pair[1] <= pair[0]) {
+            return Err("Time series times must be increasing and its values finite".to_string());
+        }
+        let start = times[0];
+        Ok(BoundarySignal::TimeSeries {
+            times: times.iter().map(|time| time - start).collect(),
+            elevation,
+        })
+    }
+
+    /// Time series of a text file with the columns time (s) and elevation (m)
+    /// Columns are separated by commas, semicolons or spaces; a header line and lines starting with # are skipped
+    pub fn parse_time_series(content: &str) -> Result<Self, String> {
+        let mut times = Vec::new();
+        let mut elevation = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(|c: char| c == ',' || c == ';' || c.is_whitespace()).filter(|field| !field.is_empty()).collect();
+            let values: Result<Vec<f64>, _> = fields.iter().take(2).map(|field| field.parse::<f64>()).collect();
+            match values {
+                Ok(values) if values.len() == 2 => {
+                    times.push(values[0]);
+                    elevation.push(values[1]);
+                }
+                // Column names before the data
+                Err(_) if times.is_empty() => {}
+                _ => return Err(format!("Line {} needs a time and an elevation", number + 1)),
+            }
+        }
+        Self::time_series(times, elevation)
+    }
+
+    /// Time series of a file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        Self::parse_time_series(&content)
+    }
+
+    /// Length of the signal: the time series, or the generation time of the wavemaker waves for an expression [s]
+    /// A time series lasts one sample interval beyond its last sample, so it repeats at its own rate
+    pub fn duration(&self, generation_duration: f64) -> f64 {
+        match self {
+            BoundarySignal::Expression(_) => generation_duration,
+            BoundarySignal::TimeSeries { times, .. } => times[times.len() - 1] * times.len() as f64 / (times.len() - 1) as f64,
+        }
+    }
+
+    /// Elevation of the signal at a time, interpolated linearly in a time series and zero after it [m]
+    pub fn elevation(&self, time: f64, wave_height: f64, wave_period: f64) -> Result<f64, String> {
+        match self {
+            BoundarySignal::Expression(expression) => {
+                let value = expression.evaluate(&[("t", time), ("H", wave_height), ("T", wave_period)])?;
+                if !value.is_finite() {
+                    return Err(format!("Signal is not a finite number at t = {} s", time));
+                }
+                Ok(value)
+            }
+            BoundarySignal::TimeSeries { times, elevation } => {
+                let index = times.partition_point(|&t| t < time);
+                Ok(match index {
+                    0 => if time == 0.0 { elevation[0] } else { 0.0 },
+                    index if index == times.len() => 0.0,
+                    index => {
+                        let weight = (time - times[index - 1]) / (times[index] - times[index - 1]);
+                        elevation[index - 1] + (elevation[index] - elevation[index - 1]) * weight
+                    }
+                })
+            }
+        }
+    }
+
+    /// Regular components generating the signal over its duration
+    /// The largest components are kept, down to a thousandth of the largest
+    pub fn components(&self, wave_height: f64, wave_period: f64, generation_duration: f64) -> Result<Vec<SpectralComponent>, String> {
+        let duration = self.duration(generation_duration);
+        if duration <= 0.0 || wave_period <= 0.0 {
+            return Err("Signal needs a positive duration".to_string());
+        }
+        let samples = match self {
+            BoundarySignal::Expression(_) => (duration / wave_period * SAMPLES_PER_PERIOD).ceil() as usize,
+            BoundarySignal::TimeSeries { times, .. } => times.len(),
+        };
+        let count = samples.max(4).next_power_of_two().min(MAX_SAMPLES);
+        let interval = duration / count as f64;
+        let last = match self {
+            BoundarySignal::TimeSeries { times, .. } => times[times.len() - 1],
+            BoundarySignal::Expression(_) => duration,
+        };
+        let mut re = (0..count)
+            .map(|j| {
+                // Time series hold their last value until they repeat
+                let time = (j as f64 * interval).min(last);
+                self.elevation(time, wave_height, wave_period)
+            })
+            .collect::<Result<Vec<f64>, String>>()?;
+        let mut im = vec![0.0; count];
+        fft(&mut re, &mut im, false);
+
+        // η(t) = Σ a cos(2π f t + θ), generated by sources of phase −θ
+        let mut components: Vec<SpectralComponent> = (1..count / 2)
+            .map(|bin| SpectralComponent {
+                frequency: bin as f64 / duration,
+                amplitude: 2.0 * re[bin].hypot(im[bin]) / count as f64,
+                phase: -im[bin].atan2(re[bin]),
+            })
+            .collect();
+        components.sort_by(|a, b| b.amplitude.total_cmp(&a.amplitude));
+        let largest = components.first().map_or(0.0, |component| component.amplitude);
+        if largest <= 0.0 {
+            return Err("Signal holds no waves".to_string());
+        }
+        components.truncate(MAX_COMPONENTS);
+        components.retain(|component| component.amplitude >= COMPONENT_THRESHOLD * largest);
+        components.sort_by(|a, b| a.frequency.total_cmp(&b.frequency));
+        Ok(components)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sum of the components at a time, as generated at the wavemaker
+    fn synthesized(components: &[SpectralComponent], time: f64) -> f64 {
+        components
+            .iter()
+            .map(|component| component.amplitude * (2.0 * std::f64::consts::PI * component.frequency * time - component.phase).cos())
+            .sum()
+    }
+
+    #[test]
+    fn test_expression_signal_components() {
+        let signal = BoundarySignal::expression("H / 2 * cos(2 * pi * t / T) + 0.1 * sin(4 * pi * t / T)").unwrap();
+        let components = signal.components(0.5, 2.0, 20.0).unwrap();
+        assert_eq!(components.len(), 2);
+        assert!((components[0].period() - 2.0).abs() < 1e-9 && (components[0].amplitude - 0.25).abs() < 1e-9);
+        assert!((components[1].period() - 1.0).abs() < 1e-9 && (components[1].amplitude - 0.1).abs() < 1e-9);
+        for time in [0.0, 0.3, 1.7, 13.1] {
+            let expected = signal.elevation(time, 0.5, 2.0).unwrap();
+            assert!((synthesized(&components, time) - expected).abs() < 1e-9, "{}", time);
+        }
+
+        assert!(BoundarySignal::expression("H * cos(omega * t)").is_err());
+        assert!(BoundarySignal::expression("0 * t").unwrap().components(0.5, 2.0, 20.0).is_err());
+        assert!(BoundarySignal::expression("sqrt(t - 1)").unwrap().components(0.5, 2.0, 20.0).is_err());
+    }
+
+    #[test]
+    fn test_time_series_signal() {
+        let content = "time,eta\n# measured at the paddle\n10.0, 0.0\n10.5, 0.1\n11.0, 0.0\n11.5, -0.1\n";
+        let signal = BoundarySignal::parse_time_series(content).unwrap();
+        assert_eq!(signal.duration(100.0), 2.0);
+        assert_eq!(signal.elevation(0.25, 0.0, 1.0), Ok(0.05));
+        assert_eq!(signal.elevation(5.0, 0.0, 1.0), Ok(0.0));
+        let components = signal.components(0.0, 1.0, 100.0).unwrap();
+        assert_eq!(components.len(), 1);
+        assert!((components[0].period() - 2.0).abs() < 1e-9 && (components[0].amplitude - 0.1).abs() < 1e-9);
+        assert!((synthesized(&components, 0.5) - 0.1).abs() < 1e-9);
+
+        assert!(BoundarySignal::parse_time_series("0 0\n1 0\nx y\n2 0\n3 0\n").is_err());
+        assert!(BoundarySignal::parse_time_series("0 0\n1 0\n1 0\n2 0\n").is_err());
+    }
+}
@@ -0,0 +1,256 @@
+use crate::analysis::snapshots::Snapshot;
+
+/// One crest of a tracked wave in one snapshot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CrestObservation {
+    /// Snapshot time [s]
+    pub time: f64,
+    /// Crest position, refined between display points [m]
+    pub position: f64,
+    /// Crest elevation above still water [m]
+    pub elevation: f64,
+    /// Wave height, crest minus the trough just upstream of it [m]
+    pub height: f64,
+}
+
+/// Crest of an individual wave followed down the channel
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrestTrack {
+    /// Observations in time order
+    observations: Vec<CrestObservation>,
+}
+
+impl CrestTrack {
+    /// Get observations in time order
+    pub fn observations(&self) -> &[CrestObservation] {
+        &self.observations
+    }
+
+    /// First observation
+    pub fn first(&self) -> &CrestObservation {
+        &self.observations[0]
+    }
+
+    /// Last observation
+    pub fn last(&self) -> &CrestObservation {
+        &self.observations[self.observations.len() - 1]
+    }
+
+    /// Time over which the crest was followed [s]
+    pub fn duration(&self) -> f64 {
+        self.last().time - self.first().time
+    }
+
+    /// Crest celerity from a least-squares fit of position against time [m/s]
+    pub fn celerity(&self) -> Option<f64> {
+        if self.observations.len() < 3 || self.duration() <= 0.0 {
+            return None;
+        }
+        let n = self.observations.len() as f64;
+        let mean_time = self.observations.iter().map(|o| o.time).sum::<f64>() / n;
+        let mean_position = self.observations.iter().map(|o| o.position).sum::<f64>() / n;
+        let covariance: f64 = self.observations.iter().map(|o| (o.time - mean_time) * (o.position - mean_position)).sum();
+        let variance: f64 = self.observations.iter().map(|o| (o.time - mean_time).powi(2)).sum();
+        Some(covariance / variance)
+    }
+
+    /// Crest observed at a snapshot time
+    pub fn at(&self, time: f64) -> Option<&CrestObservation> {
+        self.observations.iter().find(|o| (o.time - time).abs() < 1e-9)
+    }
+
+    /// First observation where the depth-limited breaking criterion H ≥ γd is met
+    pub fn breaking_point(&self, depth: f64, breaker_index: f64) -> Option<&CrestObservation> {
+        self.observations.iter().find(|o| o.height >= breaker_index * depth)
+    }
+}
+
+/// Crests of one surface profile, with their wave heights
+///
+/// A crest is a local maximum above a minimum elevation, refined by a parabola through the three
+/// neighbouring points. Its height is measured down to the lowest point between it and the
+/// next crest upstream, the trough that follows it at a fixed position for waves travelling downstream.
+pub fn find_crests(surface_elevation: &[f64], grid_spacing: f64, min_elevation: f64) -> Vec<(f64, f64, f64)> {
+    let mut crests = Vec::new();
+    visit_crests(surface_elevation, grid_spacing, min_elevation, |position, elevation, height| crests.push((position, elevation, height)));
+    crests
+}
+
+/// Troughs of one surface profile, as (position, elevation)
+///
+/// A trough is a local minimum below a maximum elevation, refined like the crests.
+pub fn find_troughs(surface_elevation: &[f64], grid_spacing: f64, max_elevation: f64) -> Vec<(f64, f64)> {
+    let mut troughs = Vec::new();
+    visit_troughs(surface_elevation, grid_spacing, max_elevation, |position, elevation| troughs.push((position, elevation)));
+    troughs
+}
+
+/// Pass the crests of find_crests to a closure as (position, elevation, height), in order, without collecting them
+pub fn visit_crests(surface_elevation: &[f64], grid_spacing: f64, min_elevation: f64, visit: impl FnMut(f64, f64, f64)) {
+    visit_maxima(|i| surface_elevation[i], surface_elevation.len(), grid_spacing, min_elevation, visit);
+}
+
+/// Pass the troughs of find_troughs to a closure as (position, elevation), in order, without collecting them
+pub fn visit_troughs(surface_elevation: &[f64], grid_spacing: f64, max_elevation: f64, mut visit: impl FnMut(f64, f64)) {
+    visit_maxima(|i| -surface_elevation[i], surface_elevation.len(), grid_spacing, -max_elevation, |position, depression, _| visit(position, -depression));
+}
+
+/// Refined local maxima of a profile of `len` values above a minimum, with their height above the lowest value since the previous one
+fn visit_maxima(value: impl Fn(usize) -> f64, len: usize, grid_spacing: f64, min_elevation: f64, mut visit: impl FnMut(f64, f64, f64)) {
+    let mut upstream = 0;
+    for i in 1..len.saturating_sub(1) {
+        let (a, b, c) = (value(i - 1), value(i), value(i + 1));
+        if !(b > min_elevation && b > a && b >= c) {
+            continue;
+        }
+        let curvature = a - 2.0 * b + c;
+        let offset = if curvature < 0.0 { 0.5 * (a - c) / curvature } else { 0.0 };
+        let trough = (upstream..i).map(&value).fold(f64::INFINITY, f64::min);
+        let crest = b - 0.25 * (a - c) * offset;
+        visit((i as f64 + offset) * grid_spacing, crest, crest - trough);
+        upstream = i;
+    }
+}
+
+/// Follow individual crests through consecutive snapshots
+///
+/// Each crest is matched to the nearest crest downstream of it in the next snapshot that is within reach
+/// at the maximum celerity, handling the most downstream crests first. Crests without a match start
+/// new tracks and tracks without a match end. The snapshots must be close enough in time for a crest
+/// to move less than half a wavelength between them, such as the full-resolution window of a snapshot store.
+pub fn track_crests<'a>(
+    snapshots: impl IntoIterator<Item = &'a Snapshot>,
+    grid_spacing: f64,
+    min_elevation: f64,
+    max_celerity: f64,
+) -> Vec<CrestTrack> {
+    let mut finished = Vec::new();
+    let mut active: Vec<CrestTrack> = Vec::new();
+    let mut previous_time: Option<f64> = None;
+
+    for snapshot in snapshots {
+        let crests: Vec<CrestObservation> = find_crests(&snapshot.surface_elevation, grid_spacing, min_elevation)
+            .into_iter()
+            .map(|(position, elevation, height)| CrestObservation {
+                time: snapshot.time,
+                position,
+                elevation,
+                height,
+            })
+            .collect();
+        let reach = previous_time.map_or(0.0, |time| max_celerity * (snapshot.time - time)) + grid_spacing;
+
+        let mut claimed = vec![false; crests.len()];
+        let mut continuing = Vec::new();
+        active.sort_by(|a, b| b.last().position.total_cmp(&a.last().position));
+        for mut track in active.drain(..) {
+            let position = track.last().position;
+            let matched = crests
+                .iter()
+                .enumerate()
+                .filter(|(index, crest)| !claimed[*index] && crest.position >= position - grid_spacing && crest.position <= position + reach)
+                .min_by(|(_, a), (_, b)| (a.position - position).abs().total_cmp(&(b.position - position).abs()))
+                .map(|(index, _)| index);
+            match matched {
+                Some(index) => {
+                    claimed[index] = true;
+                    track.observations.push(crests[index]);
+                    continuing.push(track);
+                }
+                None => finished.push(track),
+            }
+        }
+        for (crest, claimed) in crests.into_iter().zip(claimed) {
+            if !claimed {
+                continuing.push(CrestTrack { observations: vec![crest] });
+            }
+        }
+        active = continuing;
+        previous_time = Some(snapshot.time);
+    }
+
+    finished.extend(active);
+    finished.sort_by(|a, b| a.first().time.total_cmp(&b.first().time).then(a.first().position.total_cmp(&b.first().position)));
+    finished
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    /// Snapshots of a progressive wave train filling the channel
+    fn wave_train(celerity: f64, wavelength: f64, amplitude: impl Fn(f64) -> f64, duration: f64, dt: f64) -> Vec<Snapshot> {
+        let dx = 0.25;
+        let k = 2.0 * PI / wavelength;
+        (1..=(duration / dt).round() as usize)
+            .map(|step| {
+                let time = step as f64 * dt;
+                let surface_elevation = (0..201)
+                    .map(|i| {
+                        let x = i as f64 * dx;
+                        amplitude(x) * (k * (x - celerity * time)).cos()
+                    })
+                    .collect();
+                Snapshot { time, surface_elevation }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_find_crests() {
+        let surface: Vec<f64> = (0..101).map(|i| 0.3 * (2.0 * PI * i as f64 / 40.0).sin()).collect();
+        let crests = find_crests(&surface, 0.1, 0.05);
+        assert_eq!(crests.len(), 3);
+        for (n, (position, elevation, height)) in crests.iter().enumerate() {
+            assert!((position - (1.0 + 4.0 * n as f64)).abs() < 0.02, "{}", position);
+            assert!((elevation - 0.3).abs() < 0.01);
+            assert!((height - 0.6).abs() < 0.02 || n == 0);
+        }
+        assert!(find_crests(&[0.0; 10], 0.1, 0.05).is_empty());
+    }
+
+    #[test]
+    fn test_find_troughs() {
+        let surface: Vec<f64> = (0..101).map(|i| 0.3 * (2.0 * PI * i as f64 / 40.0).sin()).collect();
+        let troughs = find_troughs(&surface, 0.1, -0.05);
+        assert_eq!(troughs.len(), 2);
+        for (n, (position, elevation)) in troughs.iter().enumerate() {
+            assert!((position - (3.0 + 4.0 * n as f64)).abs() < 0.02, "{}", position);
+            assert!((elevation + 0.3).abs() < 0.01);
+        }
+        assert!(find_troughs(&surface, 0.1, -0.5).is_empty());
+    }
+
+    #[test]
+    fn test_tracked_celerity() {
+        let snapshots = wave_train(2.5, 10.0, |_| 0.2, 20.0, 0.05);
+        let tracks = track_crests(&snapshots, 0.25, 0.05, 5.0);
+
+        let long_tracks: Vec<&CrestTrack> = tracks.iter().filter(|track| track.duration() > 10.0).collect();
+        assert!(long_tracks.len() >= 2);
+        for track in long_tracks {
+            assert!((track.celerity().unwrap() - 2.5).abs() < 0.05, "{:?}", track.celerity());
+            assert!(track.observations().windows(2).all(|pair| pair[1].position >= pair[0].position - 0.25));
+        }
+    }
+
+    #[test]
+    fn test_height_evolution_and_breaking() {
+        // Amplitude growing along the channel, as on a shoal
+        let snapshots = wave_train(2.0, 8.0, |x| 0.1 + 0.01 * x, 24.0, 0.05);
+        let tracks = track_crests(&snapshots, 0.25, 0.05, 4.0);
+        let track = tracks.iter().max_by(|a, b| a.duration().total_cmp(&b.duration())).unwrap();
+
+        let first = track.first();
+        let last = track.last();
+        assert!(last.position > first.position + 20.0);
+        assert!(last.height > first.height + 0.3);
+
+        // H = a(x) + a(x − L/2) = 0.78 d is reached at x = 31 m for a 1 m depth
+        let breaking = track.breaking_point(1.0, 0.78).unwrap();
+        assert!((breaking.position - 31.0).abs() < 1.0, "{}", breaking.position);
+        assert!(track.breaking_point(10.0, 0.78).is_none());
+        assert_eq!(track.at(breaking.time), Some(breaking));
+    }
+}
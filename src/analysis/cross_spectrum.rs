@@ -0,0 +1,245 @@
+use crate::analysis::gauge::WaveGauge;
+use crate::analysis::spectral::{fft, mean};
+use std::f64::consts::PI;
+
+/// Segment-averaged cross-spectrum between two records at a known separation
+///
+/// The coherence γ² = |S_ab|² / (S_aa S_bb) tells which frequencies are linearly related at both positions,
+/// the phase lag φ = −arg S_ab of the downstream record gives the wave number k = φ/Δx
+/// and the celerity c = 2πf Δx/φ, an empirical measure of the dispersion relation.
+/// Phase lags are wrapped to [0, 2π), so the separation must stay below one wavelength of the waves of interest.
+#[derive(Debug, Clone)]
+pub struct CrossSpectrum {
+    /// Distance from the upstream to the downstream record [m]
+    separation: f64,
+    /// Frequencies of the bins, excluding the mean [Hz]
+    frequencies: Vec<f64>,
+    /// Squared coherence per frequency
+    coherence: Vec<f64>,
+    /// Phase lag of the downstream record per frequency, in [0, 2π) [rad]
+    phase_lag: Vec<f64>,
+    /// Number of averaged segments
+    segments: usize,
+}
+
+impl CrossSpectrum {
+    /// Average Hann-windowed segments with 50% overlap of two simultaneous records
+    pub fn new(upstream: &[f64], downstream: &[f64], sample_interval: f64, segment_length: usize, separation: f64) -> Result<Self, String> {
+        if upstream.len() != downstream.len() {
+            return Err("Records must have the same length".to_string());
+        }
+        if sample_interval <= 0.0 {
+            return Err("Sample interval must be positive".to_string());
+        }
+        if separation <= 0.0 {
+            return Err("Separation must be positive".to_string());
+        }
+        if !segment_length.is_power_of_two() || segment_length < 8 {
+            return Err("Segment length must be a power of two of at least 8 samples".to_string());
+        }
+        if upstream.len() < segment_length {
+            return Err("Records are shorter than one segment".to_string());
+        }
+
+        let window: Vec<f64> = (0..segment_length)
+            .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f64 / segment_length as f64).cos())
+            .collect();
+        let windowed_spectrum = |segment: &[f64]| {
+            let segment_mean = mean(segment);
+            let mut re: Vec<f64> = segment.iter().zip(&window).map(|(value, w)| (value - segment_mean) * w).collect();
+            let mut im = vec![0.0; segment_length];
+            fft(&mut re, &mut im, false);
+            (re, im)
+        };
+
+        let bins = segment_length / 2;
+        let mut auto_a = vec![0.0; bins];
+        let mut auto_b = vec![0.0; bins];
+        let mut cross = vec![(0.0, 0.0); bins];
+        let starts: Vec<usize> = (0..=upstream.len() - segment_length).step_by(segment_length / 2).collect();
+        for &start in &starts {
+            let (a_re, a_im) = windowed_spectrum(&upstream[start..start + segment_length]);
+            let (b_re, b_im) = windowed_spectrum(&downstream[start..start + segment_length]);
+            for bin in 0..bins {
+                // S_ab = A* B
+                let k = bin + 1;
+                auto_a[bin] += a_re[k] * a_re[k] + a_im[k] * a_im[k];
+                auto_b[bin] += b_re[k] * b_re[k] + b_im[k] * b_im[k];
+                cross[bin].0 += a_re[k] * b_re[k] + a_im[k] * b_im[k];
+                cross[bin].1 += a_re[k] * b_im[k] - a_im[k] * b_re[k];
+            }
+        }
+
+        let resolution = 1.0 / (segment_length as f64 * sample_interval);
+        let coherence = (0..bins)
+            .map(|bin| {
+                let denominator = auto_a[bin] * auto_b[bin];
+                if denominator > 0.0 {
+                    (cross[bin].0 * cross[bin].0 + cross[bin].1 * cross[bin].1) / denominator
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        let phase_lag = cross.iter().map(|(re, im)| (-im.atan2(*re)).rem_euclid(2.0 * PI)).collect();
+
+        Ok(Self {
+            separation,
+            frequencies: (1..=bins).map(|k| k as f64 * resolution).collect(),
+            coherence,
+            phase_lag,
+            segments: starts.len(),
+        })
+    }
+
+    /// Cross-spectrum between two gauges, from the upstream to the downstream one
+    /// Only the common end of the records is used, in case a gauge was added during the run
+    pub fn from_gauges(first: &WaveGauge, second: &WaveGauge, segment_length: usize) -> Result<Self, String> {
+        let (upstream, downstream) = if first.position() <= second.position() { (first, second) } else { (second, first) };
+        let sample_interval = upstream.sample_interval().ok_or("Gauge record is empty")?;
+        let samples = upstream.len().min(downstream.len());
+        Self::new(
+            &upstream.elevation()[upstream.len() - samples..],
+            &downstream.elevation()[downstream.len() - samples..],
+            sample_interval,
+            segment_length,
+            downstream.position() - upstream.position(),
+        )
+    }
+
+    /// Get distance between the records [m]
+    pub fn separation(&self) -> f64 {
+        self.separation
+    }
+
+    /// Get frequencies of the bins [Hz]
+    pub fn frequencies(&self) -> &[f64] {
+        &self.frequencies
+    }
+
+    /// Get squared coherence per frequency
+    pub fn coherence(&self) -> &[f64] {
+        &self.coherence
+    }
+
+    /// Get phase lag of the downstream record per frequency [rad]
+    pub fn phase_lag(&self) -> &[f64] {
+        &self.phase_lag
+    }
+
+    /// Number of averaged segments
+    pub fn segments(&self) -> usize {
+        self.segments
+    }
+
+    /// 95% significance level of the squared coherence for unrelated records, 1 − 0.05^(1/(segments − 1))
+    pub fn significance_level(&self) -> f64 {
+        if self.segments < 2 {
+            return 1.0;
+        }
+        1.0 - 0.05_f64.powf(1.0 / (self.segments - 1) as f64)
+    }
+
+    /// Estimated celerity per frequency, c = 2πf Δx/φ [m/s]
+    pub fn celerity(&self) -> Vec<f64> {
+        self.frequencies
+            .iter()
+            .zip(&self.phase_lag)
+            .map(|(frequency, phase)| if *phase > 0.0 { 2.0 * PI * frequency * self.separation / phase } else { f64::INFINITY })
+            .collect()
+    }
+
+    /// Celerity at the frequencies whose coherence exceeds a threshold: (f [Hz], c [m/s])
+    pub fn coherent_celerity(&self, threshold: f64) -> Vec<(f64, f64)> {
+        self.frequencies
+            .iter()
+            .zip(self.celerity())
+            .zip(&self.coherence)
+            .filter(|(_, coherence)| **coherence > threshold)
+            .map(|((&frequency, celerity), _)| (frequency, celerity))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Random deep-water wave field, η(x, t) = Σ a cos(kx − ωt + φ) with ω² = gk
+    fn wave_field(position: f64, samples: usize, dt: f64) -> Vec<f64> {
+        let mut state: u64 = 42;
+        let components: Vec<(f64, f64)> = (0..60)
+            .map(|n| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                let phase = (state >> 11) as f64 / (1u64 << 53) as f64 * 2.0 * PI;
+                (0.15 + 0.004 * n as f64, phase)
+            })
+            .collect();
+        (0..samples)
+            .map(|i| {
+                let t = i as f64 * dt;
+                components
+                    .iter()
+                    .map(|(frequency, phase)| {
+                        let omega = 2.0 * PI * frequency;
+                        let k = omega * omega / 9.81;
+                        0.02 * (k * position - omega * t + phase).cos()
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_celerity_follows_dispersion_relation() {
+        let dt = 0.2;
+        let upstream = wave_field(10.0, 4096, dt);
+        let downstream = wave_field(13.0, 4096, dt);
+        let spectrum = CrossSpectrum::new(&upstream, &downstream, dt, 256, 3.0).unwrap();
+
+        // Within the band of the generated components
+        let coherent: Vec<(f64, f64)> = spectrum
+            .coherent_celerity(0.9)
+            .into_iter()
+            .filter(|(frequency, _)| (0.16..0.38).contains(frequency))
+            .collect();
+        assert!(coherent.len() >= 10);
+        for (frequency, celerity) in coherent {
+            // Deep water: c = g/ω
+            let expected = 9.81 / (2.0 * PI * frequency);
+            assert!((celerity - expected).abs() < 0.05 * expected, "{:.3} m/s vs {:.3} m/s at {:.3} Hz", celerity, expected, frequency);
+        }
+    }
+
+    #[test]
+    fn test_unrelated_records_are_incoherent() {
+        let dt = 0.2;
+        let upstream = wave_field(0.0, 4096, dt);
+        let downstream: Vec<f64> = wave_field(0.0, 4096 + 997, dt).split_off(997);
+        let spectrum = CrossSpectrum::new(&upstream, &downstream, dt, 128, 5.0).unwrap();
+        let mean_coherence = mean(spectrum.coherence());
+        assert!(mean_coherence < 2.0 * spectrum.significance_level(), "γ² = {:.3}", mean_coherence);
+    }
+
+    #[test]
+    fn test_from_gauges_orders_by_position() {
+        let dt = 0.2;
+        let mut upstream = WaveGauge::new(10.0);
+        let mut downstream = WaveGauge::new(13.0);
+        for (i, (a, b)) in wave_field(10.0, 1024, dt).into_iter().zip(wave_field(13.0, 1024, dt)).enumerate() {
+            upstream.record(i as f64 * dt, a);
+            downstream.record(i as f64 * dt, b);
+        }
+        let forward = CrossSpectrum::from_gauges(&upstream, &downstream, 256).unwrap();
+        let backward = CrossSpectrum::from_gauges(&downstream, &upstream, 256).unwrap();
+        assert_eq!(forward.separation(), 3.0);
+        assert_eq!(forward.phase_lag(), backward.phase_lag());
+    }
+
+    #[test]
+    fn test_invalid_cross_spectrum() {
+        assert!(CrossSpectrum::new(&[0.0; 256], &[0.0; 128], 0.1, 64, 1.0).is_err());
+        assert!(CrossSpectrum::new(&[0.0; 256], &[0.0; 256], 0.1, 64, 0.0).is_err());
+        assert!(CrossSpectrum::new(&[0.0; 256], &[0.0; 256], 0.1, 512, 1.0).is_err());
+    }
+}
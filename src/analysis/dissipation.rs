@@ -0,0 +1,57 @@
+use crate::solver::{Dissipation, DissipationMechanism};
+use serde::{Deserialize, Serialize};
+
+/// Time series of the wave energy dissipated by each mechanism since the start of the run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DissipationRecord {
+    /// Sample times [s]
+    times: Vec<f64>,
+    /// Energy per unit width and density dissipated so far at each sample [m³/s²]
+    values: Vec<Dissipation>,
+}
+
+impl DissipationRecord {
+    /// Append a sample to the record
+    pub fn record(&mut self, time: f64, dissipation: Dissipation) {
+        self.times.push(time);
+        self.values.push(dissipation);
+    }
+
+    /// Get sample times [s]
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    /// Energy dissipated so far by a mechanism at each sample [m³/s²]
+    pub fn series(&self, mechanism: DissipationMechanism) -> Vec<f64> {
+        self.values.iter().map(|value| value.get(mechanism)).collect()
+    }
+
+    /// Energy dissipated by each mechanism over the whole record [m³/s²]
+    pub fn last(&self) -> Option<Dissipation> {
+        self.values.last().copied()
+    }
+
+    /// Share of the energy dissipated over the record taken by each mechanism, None before any is dissipated
+    pub fn shares(&self) -> Option<Vec<(DissipationMechanism, f64)>> {
+        let last = self.last()?;
+        let total = last.total();
+        (total > 0.0).then(|| DissipationMechanism::ALL.iter().map(|&mechanism| (mechanism, last.get(mechanism) / total)).collect())
+    }
+
+    /// Number of recorded samples
+    pub fn len(&self) -> usize {
+        self.times.len()
+    }
+
+    /// Check if nothing was recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty()
+    }
+
+    /// Discard the record
+    pub fn clear(&mut self) {
+        self.times.clear();
+        self.values.clear();
+    }
+}
@@ -0,0 +1,413 @@
+use serde::{Deserialize, Serialize};
+
+/// Mathematical function callable in an expression
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Function {
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Atan2,
+    Sinh,
+    Cosh,
+    Tanh,
+    Exp,
+    Ln,
+    Log10,
+    Sqrt,
+    Abs,
+    Floor,
+    Ceil,
+    Min,
+    Max,
+    Pow,
+}
+
+impl Function {
+    /// Every function with its name and number of arguments
+    const ALL: [(Function, &'static str, usize); 20] = [
+        (Function::Sin, "sin", 1),
+        (Function::Cos, "cos", 1),
+        (Function::Tan, "tan", 1),
+        (Function::Asin, "asin", 1),
+        (Function::Acos, "acos", 1),
+        (Function::Atan, "atan", 1),
+        (Function::Atan2, "atan2", 2),
+        (Function::Sinh, "sinh", 1),
+        (Function::Cosh, "cosh", 1),
+        (Function::Tanh, "tanh", 1),
+        (Function::Exp, "exp", 1),
+        (Function::Ln, "ln", 1),
+        (Function::Log10, "log10", 1),
+        (Function::Sqrt, "sqrt", 1),
+        (Function::Abs, "abs", 1),
+        (Function::Floor, "floor", 1),
+        (Function::Ceil, "ceil", 1),
+        (Function::Min, "min", 2),
+        (Function::Max, "max", 2),
+        (Function::Pow, "pow", 2),
+    ];
+
+    fn apply(self, arguments: &[f64]) -> f64 {
+        let x = arguments[0];
+        match self {
+            Function::Sin => x.sin(),
+            Function::Cos => x.cos(),
+            Function::Tan => x.tan(),
+            Function::Asin => x.asin(),
+            Function::Acos => x.acos(),
+            Function::Atan => x.atan(),
+            Function::Atan2 => x.atan2(arguments[1]),
+            Function::Sinh => x.sinh(),
+            Function::Cosh => x.cosh(),
+            Function::Tanh => x.tanh(),
+            Function::Exp => x.exp(),
+            Function::Ln => x.ln(),
+            Function::Log10 => x.log10(),
+            Function::Sqrt => x.sqrt(),
+            Function::Abs => x.abs(),
+            Function::Floor => x.floor(),
+            Function::Ceil => x.ceil(),
+            Function::Min => x.min(arguments[1]),
+            Function::Max => x.max(arguments[1]),
+            Function::Pow => x.powf(arguments[1]),
+        }
+    }
+}
+
+/// Names of the functions expressions may call, for help texts
+pub fn function_names() -> Vec<&'static str> {
+    Function::ALL.iter().map(|(_, name, _)| *name).collect()
+}
+
+/// Node of the syntax tree of an expression
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Number(f64),
+    Variable(String),
+    Negate(Box<Node>),
+    Binary(char, Box<Node>, Box<Node>),
+    Call(Function, Vec<Node>),
+}
+
+impl Node {
+    fn evaluate(&self, variables: &[(&str, f64)]) -> Result<f64, String> {
+        match self {
+            Node::Number(value) => Ok(*value),
+            Node::Variable(name) => variables
+                .iter()
+                .find(|(variable, _)| variable == name)
+                .map(|(_, value)| *value)
+                .ok_or_else(|| format!("Unknown variable {}", name)),
+            Node::Negate(operand) => Ok(-operand.evaluate(variables)?),
+            Node::Binary(operator, left, right) => {
+                let (left, right) = (left.evaluate(variables)?, right.evaluate(variables)?);
+                Ok(match operator {
+                    '+' => left + right,
+                    '-' => left - right,
+                    '*' => left * right,
+                    '/' => left / right,
+                    _ => left.powf(right),
+                })
+            }
+            Node::Call(function, arguments) => {
+                let values = arguments.iter().map(|argument| argument.evaluate(variables)).collect::<Result<Vec<_>, _>>()?;
+                Ok(function.apply(&values))
+            }
+        }
+    }
+
+    fn collect_variables<'a>(&'a self, names: &mut Vec<&'a str>) {
+        match self {
+            Node::Number(_) => {}
+            Node::Variable(name) => {
+                if !names.contains(&name.as_str()) {
+                    names.push(name);
+                }
+            }
+            Node::Negate(operand) => operand.collect_variables(names),
+            Node::Binary(_, left, right) => {
+                left.collect_variables(names);
+                right.collect_variables(names);
+            }
+            Node::Call(_, arguments) => arguments.iter().for_each(|argument| argument.collect_variables(names)),
+        }
+    }
+}
+
+/// Token of the source of an expression
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Symbol(char),
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut end = start;
+            let mut previous = ' ';
+            // Digits, a decimal point and an exponent with its sign, e.g. 1.5e-3
+            while let Some(&(i, c)) = chars.peek() {
+                let exponent_sign = (c == '-' || c == '+') && (previous == 'e' || previous == 'E');
+                if !(c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || exponent_sign) {
+                    break;
+                }
+                end = i + c.len_utf8();
+                previous = c;
+                chars.next();
+            }
+            let text = &source[start..end];
+            tokens.push(Token::Number(text.parse().map_err(|_| format!("Invalid number {}", text))?));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_alphanumeric() || c == '_') {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            tokens.push(Token::Name(source[start..end].to_string()));
+        } else if "+-*/^(),".contains(c) {
+            tokens.push(Token::Symbol(c));
+            chars.next();
+        } else {
+            return Err(format!("Unexpected character '{}'", c));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent parser, by increasing precedence: sums, products, signs, powers
+struct Parser {
+    tokens: Vec<Token>,
+    next: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next)
+    }
+
+    fn accept(&mut self, symbol: char) -> bool {
+        let accepted = self.peek() == Some(&Token::Symbol(symbol));
+        if accepted {
+            self.next += 1;
+        }
+        accepted
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), String> {
+        if self.accept(symbol) { Ok(()) } else { Err(format!("Expected '{}'", symbol)) }
+    }
+
+    fn sum(&mut self) -> Result<Node, String> {
+        let mut node = self.product()?;
+        while let Some(&Token::Symbol(operator @ ('+' | '-'))) = self.peek() {
+            self.next += 1;
+            node = Node::Binary(operator, Box::new(node), Box::new(self.product()?));
+        }
+        Ok(node)
+    }
+
+    fn product(&mut self) -> Result<Node, String> {
+        let mut node = self.sign()?;
+        while let Some(&Token::Symbol(operator @ ('*' | '/'))) = self.peek() {
+            self.next += 1;
+            node = Node::Binary(operator, Box::new(node), Box::new(self.sign()?));
+        }
+        Ok(node)
+    }
+
+    fn sign(&mut self) -> Result<Node, String> {
+        if self.accept('-') {
+            return Ok(Node::Negate(Box::new(self.sign()?)));
+        }
+        if self.accept('+') {
+            return self.sign();
+        }
+        self.power()
+    }
+
+    /// Powers bind tighter than signs and group to the right: -x^2 = -(x^2), 2^3^2 = 2^9
+    fn power(&mut self) -> Result<Node, String> {
+        let base = self.primary()?;
+        if self.accept('^') {
+            return Ok(Node::Binary('^', Box::new(base), Box::new(self.sign()?)));
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<Node, String> {
+        let token = self.peek().cloned().ok_or("Expression ends too early")?;
+        self.next += 1;
+        match token {
+            Token::Number(value) => Ok(Node::Number(value)),
+            Token::Symbol('(') => {
+                let node = self.sum()?;
+                self.expect(')')?;
+                Ok(node)
+            }
+            Token::Symbol(symbol) => Err(format!("Unexpected '{}'", symbol)),
+            Token::Name(name) if self.accept('(') => {
+                let &(function, _, arity) = Function::ALL
+                    .iter()
+                    .find(|(_, function, _)| *function == name)
+                    .ok_or_else(|| format!("Unknown function {}", name))?;
+                let mut arguments = vec![self.sum()?];
+                while self.accept(',') {
+                    arguments.push(self.sum()?);
+                }
+                self.expect(')')?;
+                if arguments.len() != arity {
+                    return Err(format!("{} takes {} argument{}", name, arity, if arity == 1 { "" } else { "s" }));
+                }
+                Ok(Node::Call(function, arguments))
+            }
+            Token::Name(name) => Ok(match name.as_str() {
+                "pi" => Node::Number(std::f64::consts::PI),
+                "e" => Node::Number(std::f64::consts::E),
+                _ => Node::Variable(name),
+            }),
+        }
+    }
+}
+
+/// Arithmetic expression of named variables, e.g. "0.5 * H / sqrt(H / L0)"
+///
+/// Expressions combine numbers, variables, + - * / and ^ (powers, grouped to the right), parentheses
+/// and the usual functions (sin, cos, tan, their inverses, atan2, sinh, cosh, tanh, exp, ln, log10,
+/// sqrt, abs, floor, ceil, min, max, pow), with the constants pi and e. They are parsed once and
+/// evaluated for any values of their variables; projects store their source.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Expression {
+    source: String,
+    root: Node,
+}
+
+impl Expression {
+    /// Parse the source of an expression
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut parser = Parser { tokens: tokenize(source)?, next: 0 };
+        if parser.tokens.is_empty() {
+            return Err("Expression is empty".to_string());
+        }
+        let root = parser.sum()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("Unexpected {:?} after the expression", token));
+        }
+        Ok(Self { source: source.trim().to_string(), root })
+    }
+
+    /// Get source of the expression
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Names of the variables of the expression, in order of appearance
+    pub fn variables(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        self.root.collect_variables(&mut names);
+        names
+    }
+
+    /// Value of the expression for the values of its variables
+    pub fn evaluate(&self, variables: &[(&str, f64)]) -> Result<f64, String> {
+        self.root.evaluate(variables)
+    }
+}
+
+impl TryFrom<String> for Expression {
+    type Error = String;
+
+    fn try_from(source: String) -> Result<Self, String> {
+        Self::parse(&source)
+    }
+}
+
+impl From<Expression> for String {
+    fn from(expression: Expression) -> Self {
+        expression.source
+    }
+}
+
+/// Empirical relationship of the user, evaluated with the computed quantities of a case
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomFormula {
+    pub name: String,
+    pub expression: Expression,
+    pub unit: String, // Shown after the value, may be empty
+}
+
+impl CustomFormula {
+    /// Create new formula, parsing its expression
+    pub fn new(name: &str, source: &str, unit: &str) -> Result<Self, String> {
+        if name.trim().is_empty() {
+            return Err("Formula needs a name".to_string());
+        }
+        Ok(Self {
+            name: name.trim().to_string(),
+            expression: Expression::parse(source)?,
+            unit: unit.trim().to_string(),
+        })
+    }
+
+    /// Value of the formula, failing if it uses a quantity that is not available or is not a number
+    pub fn evaluate(&self, variables: &[(&str, f64)]) -> Result<f64, String> {
+        let value = self.expression.evaluate(variables)?;
+        if !value.is_finite() {
+            return Err(format!("{} is not a finite number", self.name));
+        }
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expression_precedence() {
+        let evaluate = |source: &str| Expression::parse(source).unwrap().evaluate(&[("x", 3.0), ("L0", 2.0)]).unwrap();
+        assert_eq!(evaluate("1 + 2 * 3"), 7.0);
+        assert_eq!(evaluate("(1 + 2) * 3"), 9.0);
+        assert_eq!(evaluate("-x^2"), -9.0);
+        assert_eq!(evaluate("2^3^2"), 512.0);
+        assert_eq!(evaluate("2^-1"), 0.5);
+        assert_eq!(evaluate("10 / 4 / 5"), 0.5);
+        assert_eq!(evaluate("1.5e-1 * L0"), 0.3);
+        assert_eq!(evaluate("max(x, L0) + min(x, L0)"), 5.0);
+        assert!((evaluate("sin(pi / 2) + ln(e)") - 2.0).abs() < 1e-12);
+        assert!((evaluate("atan2(1, 1)") - std::f64::consts::FRAC_PI_4).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_expression_errors() {
+        for source in ["", "1 +", "(1 + 2", "1 2", "foo(1)", "sqrt(1, 2)", "2 $ 3", "1..2"] {
+            assert!(Expression::parse(source).is_err(), "{}", source);
+        }
+        let expression = Expression::parse("H / sqrt(H / L0) + H").unwrap();
+        assert_eq!(expression.variables(), ["H", "L0"]);
+        assert_eq!(expression.evaluate(&[("H", 1.0)]), Err("Unknown variable L0".to_string()));
+
+        // Projects store the source
+        let json = serde_json::to_string(&expression).unwrap();
+        assert_eq!(json, "\"H / sqrt(H / L0) + H\"");
+        assert_eq!(serde_json::from_str::<Expression>(&json).unwrap(), expression);
+        assert!(serde_json::from_str::<Expression>("\"sqrt(\"").is_err());
+
+        let formula = CustomFormula::new("ratio", "H / h", "").unwrap();
+        assert_eq!(formula.evaluate(&[("H", 1.0), ("h", 2.0)]), Ok(0.5));
+        assert!(formula.evaluate(&[("H", 1.0), ("h", 0.0)]).is_err());
+        assert!(CustomFormula::new(" ", "H", "m").is_err());
+    }
+}
@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+
+/// Virtual wave gauge recording the surface elevation at a fixed position
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WaveGauge {
+    /// Gauge position along the channel [m]
+    position: f64,
+    /// Sample times [s]
+    times: Vec<f64>,
+    /// Recorded surface elevation [m]
+    elevation: Vec<f64>,
+}
+
+impl WaveGauge {
+    /// Create new gauge with an empty record
+    pub fn new(position: f64) -> Self {
+        Self {
+            position,
+            times: Vec::new(),
+            elevation: Vec::new(),
+        }
+    }
+
+    /// Get gauge position [m]
+    pub fn position(&self) -> f64 {
+        self.position
+    }
+
+    /// Move the gauge, discarding its record
+    pub fn set_position(&mut self, position: f64) {
+        if position != self.position {
+            self.position = position;
+            self.clear();
+        }
+    }
+
+    /// Append a sample to the record
+    pub fn record(&mut self, time: f64, elevation: f64) {
+        self.times.push(time);
+        self.elevation.push(elevation);
+    }
+
+    /// Get sample times [s]
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    /// Get recorded surface elevation [m]
+    pub fn elevation(&self) -> &[f64] {
+        &self.elevation
+    }
+
+    /// Number of recorded samples
+    pub fn len(&self) -> usize {
+        self.elevation.len()
+    }
+
+    /// Check if nothing was recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.elevation.is_empty()
+    }
+
+    /// Record duration [s]
+    pub fn duration(&self) -> f64 {
+        match (self.times.first(), self.times.last()) {
+            (Some(first), Some(last)) => last - first,
+            _ => 0.0,
+        }
+    }
+
+    /// Mean interval between samples [s], None with fewer than two samples
+    pub fn sample_interval(&self) -> Option<f64> {
+        (self.len() >= 2).then(|| self.duration() / (self.len() - 1) as f64)
+    }
+
+    /// Copy of the gauge with the last part of the record only, the whole record if the duration is not positive [s]
+    pub fn window(&self, duration: f64) -> WaveGauge {
+        let start = match self.times.last() {
+            Some(last) if duration > 0.0 => self.times.partition_point(|time| *time < last - duration - 1e-9),
+            _ => 0,
+        };
+        WaveGauge {
+            position: self.position,
+            times: self.times[start..].to_vec(),
+            elevation: self.elevation[start..].to_vec(),
+        }
+    }
+
+    /// Discard the record
+    pub fn clear(&mut self) {
+        self.times.clear();
+        self.elevation.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gauge_recording() {
+        let mut gauge = WaveGauge::new(12.5);
+        assert!(gauge.is_empty());
+        assert_eq!(gauge.sample_interval(), None);
+
+        for i in 0..5 {
+            gauge.record(i as f64 * 0.1, i as f64);
+        }
+        assert_eq!(gauge.len(), 5);
+        assert!((gauge.duration() - 0.4).abs() < 1e-12);
+        assert!((gauge.sample_interval().unwrap() - 0.1).abs() < 1e-12);
+        assert_eq!(gauge.elevation()[3], 3.0);
+    }
+
+    #[test]
+    fn test_record_window() {
+        let mut gauge = WaveGauge::new(5.0);
+        for i in 0..11 {
+            gauge.record(i as f64 * 0.5, i as f64);
+        }
+        let window = gauge.window(2.0);
+        assert_eq!(window.position(), 5.0);
+        assert_eq!(window.times(), &[3.0, 3.5, 4.0, 4.5, 5.0]);
+        assert_eq!(window.elevation()[0], 6.0);
+        assert_eq!(gauge.window(0.0), gauge);
+        assert_eq!(gauge.window(100.0), gauge);
+    }
+
+    #[test]
+    fn test_moving_gauge_clears_record() {
+        let mut gauge = WaveGauge::new(10.0);
+        gauge.record(0.0, 0.1);
+        gauge.set_position(10.0);
+        assert_eq!(gauge.len(), 1);
+
+        gauge.set_position(20.0);
+        assert_eq!(gauge.position(), 20.0);
+        assert!(gauge.is_empty());
+    }
+}
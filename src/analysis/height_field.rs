@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+/// Wave height at every point of a field from the variance of the surface over a recording window
+///
+/// The window opens once the waves have settled over the field and closes when the wavemaker stops,
+/// so the statistics describe the steady wave pattern rather than the first fronts. The height of
+/// regular waves is H = 2√2 σ, that of the sinusoid with the variance σ² of the record, and the
+/// significant height of irregular waves Hm0 = 4σ.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WaveHeightField {
+    /// Time the recording starts [s]
+    start_time: f64,
+    /// Time the recording stops [s]
+    end_time: f64,
+    /// Number of recorded fields
+    count: usize,
+    /// Sum of the recorded elevations at each point [m]
+    sum: Vec<f64>,
+    /// Sum of the squared recorded elevations at each point [m²]
+    sum_of_squares: Vec<f64>,
+}
+
+impl WaveHeightField {
+    /// Create new empty record of a number of points over a window of time
+    pub fn new(points: usize, start_time: f64, end_time: f64) -> Self {
+        Self {
+            start_time,
+            end_time,
+            count: 0,
+            sum: vec![0.0; points],
+            sum_of_squares: vec![0.0; points],
+        }
+    }
+
+    /// Time the recording starts [s]
+    pub fn start_time(&self) -> f64 {
+        self.start_time
+    }
+
+    /// Time the recording stops [s]
+    pub fn end_time(&self) -> f64 {
+        self.end_time
+    }
+
+    /// Number of recorded fields
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Record the surface elevation at every point, if the time falls in the window
+    pub fn record(&mut self, time: f64, elevation: &[f64]) {
+        if time < self.start_time || time > self.end_time || elevation.len() != self.sum.len() {
+            return;
+        }
+        for ((sum, sum_of_squares), &eta) in self.sum.iter_mut().zip(&mut self.sum_of_squares).zip(elevation) {
+            *sum += eta;
+            *sum_of_squares += eta * eta;
+        }
+        self.count += 1;
+    }
+
+    /// Discard the recorded fields
+    pub fn clear(&mut self) {
+        self.count = 0;
+        self.sum.iter_mut().for_each(|sum| *sum = 0.0);
+        self.sum_of_squares.iter_mut().for_each(|sum| *sum = 0.0);
+    }
+
+    /// Standard deviation of the surface at every point, None before two fields are recorded [m]
+    pub fn standard_deviations(&self) -> Option<Vec<f64>> {
+        if self.count < 2 {
+            return None;
+        }
+        let count = self.count as f64;
+        Some(
+            self.sum
+                .iter()
+                .zip(&self.sum_of_squares)
+                .map(|(sum, sum_of_squares)| (sum_of_squares / count - (sum / count).powi(2)).max(0.0).sqrt())
+                .collect(),
+        )
+    }
+
+    /// Height of regular waves at every point, H = 2√2 σ [m]
+    pub fn regular_heights(&self) -> Option<Vec<f64>> {
+        self.standard_deviations().map(|deviations| deviations.into_iter().map(|sigma| 2.0 * std::f64::consts::SQRT_2 * sigma).collect())
+    }
+
+    /// Significant wave height at every point, Hm0 = 4σ [m]
+    pub fn significant_heights(&self) -> Option<Vec<f64>> {
+        self.standard_deviations().map(|deviations| deviations.into_iter().map(|sigma| 4.0 * sigma).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heights_over_the_window() {
+        // Sinusoids of heights 0.1 and 0.2 m about a mean level, then a disturbance after the window
+        let mut field = WaveHeightField::new(2, 1.0, 11.0);
+        for step in 0..1500 {
+            let time = step as f64 * 0.01;
+            let phase = 2.0 * std::f64::consts::PI * time;
+            let elevation = if time > 11.0 { [1.0, 1.0] } else { [0.05 * phase.sin() + 0.3, 0.1 * phase.cos()] };
+            field.record(time, &elevation);
+        }
+        assert_eq!(field.count(), 1001);
+        let heights = field.regular_heights().unwrap();
+        assert!((heights[0] - 0.1).abs() < 1e-3 && (heights[1] - 0.2).abs() < 1e-3);
+        assert!((field.significant_heights().unwrap()[1] - 0.2 * std::f64::consts::SQRT_2).abs() < 1e-3);
+
+        field.clear();
+        assert!(field.standard_deviations().is_none());
+    }
+}
@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+
+/// Ratio of the peak force to the quasi-static force above which a load is impulsive (Oumeraci et al., 2001, PROVERBS)
+pub const IMPULSIVE_FORCE_RATIO: f64 = 2.5;
+
+/// Time series of the force per unit length on a structure
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ForceRecord {
+    /// Sample times [s]
+    times: Vec<f64>,
+    /// Recorded force [N/m]
+    force: Vec<f64>,
+}
+
+impl ForceRecord {
+    /// Append a sample to the record
+    pub fn record(&mut self, time: f64, force: f64) {
+        self.times.push(time);
+        self.force.push(force);
+    }
+
+    /// Get sample times [s]
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    /// Get recorded force [N/m]
+    pub fn force(&self) -> &[f64] {
+        &self.force
+    }
+
+    /// Number of recorded samples
+    pub fn len(&self) -> usize {
+        self.force.len()
+    }
+
+    /// Check if nothing was recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.force.is_empty()
+    }
+
+    /// Largest recorded force, 0 for an empty record [N/m]
+    pub fn max(&self) -> f64 {
+        self.force.iter().copied().fold(0.0, f64::max)
+    }
+
+    /// Discard the record
+    pub fn clear(&mut self) {
+        self.times.clear();
+        self.force.clear();
+    }
+}
+
+/// Load of an impact event relative to the quasi-static force of the waves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadClass {
+    /// Slowly varying load, close to the pulsating force of non-breaking waves
+    QuasiStatic,
+    /// Short, high impact of a breaking wave
+    Impulsive,
+}
+
+impl std::fmt::Display for LoadClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadClass::QuasiStatic => write!(f, "Quasi-static"),
+            LoadClass::Impulsive => write!(f, "Impulsive"),
+        }
+    }
+}
+
+/// Exceedance of the threshold by the force record, from its up-crossing to its down-crossing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImpactEvent {
+    pub start: f64,     // Up-crossing of the threshold [s]
+    pub end: f64,       // Down-crossing of the threshold [s]
+    pub peak_time: f64, // Time of the maximum [s]
+    pub peak: f64,      // Maximum force [N/m]
+    pub impulse: f64,   // Force integrated over the event ∫F dt [N·s/m]
+}
+
+impl ImpactEvent {
+    /// Time from the up-crossing to the maximum [s]
+    pub fn rise_time(&self) -> f64 {
+        self.peak_time - self.start
+    }
+
+    /// Time above the threshold [s]
+    pub fn duration(&self) -> f64 {
+        self.end - self.start
+    }
+
+    /// Impulsive if the peak exceeds the quasi-static force by the PROVERBS ratio
+    pub fn class(&self, quasi_static_force: f64) -> LoadClass {
+        if self.peak > IMPULSIVE_FORCE_RATIO * quasi_static_force {
+            LoadClass::Impulsive
+        } else {
+            LoadClass::QuasiStatic
+        }
+    }
+}
+
+/// Peak-over-threshold detection of impacts in a force record
+///
+/// Every excursion above the threshold is one event, so successive peaks are independent as long as
+/// the force falls below the threshold between waves. Crossing times are interpolated between samples
+/// and the impulse integrates the force with the trapezoidal rule between them.
+pub fn detect_impacts(record: &ForceRecord, threshold: f64) -> Vec<ImpactEvent> {
+    let (times, force) = (record.times(), record.force());
+    let crossing = |i: usize| times[i - 1] + (times[i] - times[i - 1]) * (threshold - force[i - 1]) / (force[i] - force[i - 1]);
+    let mut events = Vec::new();
+    let mut current: Option<ImpactEvent> = None;
+    for i in 0..force.len() {
+        let above = force[i] > threshold;
+        match (&mut current, above) {
+            (None, true) => {
+                // Events under way when the record starts have no up-crossing and are left out
+                if i > 0 {
+                    let start = crossing(i);
+                    current = Some(ImpactEvent {
+                        start,
+                        end: start,
+                        peak_time: times[i],
+                        peak: force[i],
+                        impulse: 0.5 * (threshold + force[i]) * (times[i] - start),
+                    });
+                }
+            }
+            (Some(event), true) => {
+                event.impulse += 0.5 * (force[i - 1] + force[i]) * (times[i] - times[i - 1]);
+                if force[i] > event.peak {
+                    event.peak = force[i];
+                    event.peak_time = times[i];
+                }
+            }
+            (Some(event), false) => {
+                let end = crossing(i);
+                event.impulse += 0.5 * (force[i - 1] + threshold) * (end - times[i - 1]);
+                event.end = end;
+                events.push(*event);
+                current = None;
+            }
+            (None, false) => {}
+        }
+    }
+    events
+}
+
+/// Histogram of the impact maxima: lower bound of each bin and the number of peaks in it
+/// The bins span the peaks evenly, the largest peak falling in the last bin
+pub fn peak_histogram(events: &[ImpactEvent], bins: usize) -> Vec<(f64, usize)> {
+    let Some((low, high)) = events.iter().map(|event| event.peak).fold(None, |range: Option<(f64, f64)>, peak| {
+        Some(range.map_or((peak, peak), |(low, high)| (low.min(peak), high.max(peak))))
+    }) else {
+        return Vec::new();
+    };
+    let bins = bins.max(1);
+    let width = ((high - low) / bins as f64).max(f64::MIN_POSITIVE);
+    let mut counts = vec![0; bins];
+    for event in events {
+        counts[(((event.peak - low) / width) as usize).min(bins - 1)] += 1;
+    }
+    counts.into_iter().enumerate().map(|(bin, count)| (low + bin as f64 * width, count)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_impacts() {
+        // Two triangular pulses of 10 and 30 N/m over a threshold of 5 N/m
+        let mut record = ForceRecord::default();
+        for (i, force) in [0.0, 10.0, 0.0, 0.0, 10.0, 30.0, 20.0, 0.0].into_iter().enumerate() {
+            record.record(i as f64, force);
+        }
+        let events = detect_impacts(&record, 5.0);
+        assert_eq!(events.len(), 2);
+        assert_eq!((events[0].start, events[0].end, events[0].peak), (0.5, 1.5, 10.0));
+        assert_eq!(events[0].rise_time(), 0.5);
+        assert!((events[0].impulse - 7.5).abs() < 1e-12);
+        assert_eq!((events[1].peak, events[1].peak_time), (30.0, 5.0));
+        assert!((events[1].duration() - 3.25).abs() < 1e-12);
+        assert_eq!(events[0].class(5.0), LoadClass::QuasiStatic);
+        assert_eq!(events[1].class(5.0), LoadClass::Impulsive);
+
+        // A record starting above the threshold, or ending above it, has no complete event there
+        let mut open = ForceRecord::default();
+        for (i, force) in [8.0, 2.0, 9.0].into_iter().enumerate() {
+            open.record(i as f64, force);
+        }
+        assert!(detect_impacts(&open, 5.0).is_empty());
+    }
+
+    #[test]
+    fn test_peak_histogram() {
+        let event = |peak| ImpactEvent { start: 0.0, end: 1.0, peak_time: 0.5, peak, impulse: 0.0 };
+        let events: Vec<ImpactEvent> = [1.0, 2.0, 2.5, 5.0].into_iter().map(event).collect();
+        assert_eq!(peak_histogram(&events, 2), vec![(1.0, 3), (3.0, 1)]);
+        assert!(peak_histogram(&[], 4).is_empty());
+        assert_eq!(peak_histogram(&events[..1], 3).iter().map(|(_, count)| count).sum::<usize>(), 1);
+    }
+}
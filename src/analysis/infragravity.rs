@@ -0,0 +1,191 @@
+use crate::analysis::gauge::WaveGauge;
+use crate::analysis::spectral::{band_pass, correlation, envelope, peak_frequency, significant_wave_height};
+
+/// Separation of a surface elevation record into sea-swell and infragravity components
+///
+/// Frequencies above the split frequency (half the peak frequency by default) are sea-swell,
+/// those below are infragravity. The short-wave envelope, low-passed to the same band,
+/// reveals bound long waves through a negative correlation with the infragravity signal.
+#[derive(Debug, Clone)]
+pub struct InfragravityAnalysis {
+    /// Sample interval of the record [s]
+    sample_interval: f64,
+    /// Frequency separating infragravity from sea-swell waves [Hz]
+    split_frequency: f64,
+    /// High-passed sea-swell component [m]
+    sea_swell: Vec<f64>,
+    /// Low-passed infragravity component [m]
+    infragravity: Vec<f64>,
+    /// Short-wave envelope low-passed to the infragravity band [m]
+    envelope: Vec<f64>,
+}
+
+impl InfragravityAnalysis {
+    /// Analyse a record sampled at a constant interval with the given split frequency
+    pub fn new(elevation: &[f64], sample_interval: f64, split_frequency: f64) -> Result<Self, String> {
+        if elevation.len() < 4 {
+            return Err("Record is too short for infragravity analysis".to_string());
+        }
+        if sample_interval <= 0.0 {
+            return Err("Sample interval must be positive".to_string());
+        }
+        let nyquist = 0.5 / sample_interval;
+        if split_frequency <= 0.0 || split_frequency >= nyquist {
+            return Err(format!("Split frequency must be between 0 and the Nyquist frequency ({:.3} Hz)", nyquist));
+        }
+
+        let sea_swell = band_pass(elevation, sample_interval, split_frequency, f64::INFINITY);
+        let infragravity = band_pass(elevation, sample_interval, 0.0, split_frequency);
+        let raw_envelope = envelope(&sea_swell);
+        let envelope = band_pass(&raw_envelope, sample_interval, 0.0, split_frequency);
+
+        Ok(Self {
+            sample_interval,
+            split_frequency,
+            sea_swell,
+            infragravity,
+            envelope,
+        })
+    }
+
+    /// Analyse a record with the split at half its peak frequency
+    pub fn with_peak_split(elevation: &[f64], sample_interval: f64) -> Result<Self, String> {
+        let peak = peak_frequency(elevation, sample_interval).ok_or("Record has no spectral peak")?;
+        Self::new(elevation, sample_interval, 0.5 * peak)
+    }
+
+    /// Analyse the record of a wave gauge with the given split frequency
+    pub fn from_gauge(gauge: &WaveGauge, split_frequency: f64) -> Result<Self, String> {
+        let sample_interval = gauge.sample_interval().ok_or("Gauge record is empty")?;
+        Self::new(gauge.elevation(), sample_interval, split_frequency)
+    }
+
+    /// Get frequency separating infragravity from sea-swell waves [Hz]
+    pub fn split_frequency(&self) -> f64 {
+        self.split_frequency
+    }
+
+    /// Get sea-swell component [m]
+    pub fn sea_swell(&self) -> &[f64] {
+        &self.sea_swell
+    }
+
+    /// Get infragravity component, including the mean water level [m]
+    pub fn infragravity(&self) -> &[f64] {
+        &self.infragravity
+    }
+
+    /// Get short-wave envelope in the infragravity band [m]
+    pub fn envelope(&self) -> &[f64] {
+        &self.envelope
+    }
+
+    /// Significant wave height of the sea-swell component [m]
+    pub fn hm0_sea_swell(&self) -> f64 {
+        significant_wave_height(&self.sea_swell)
+    }
+
+    /// Significant wave height of the infragravity component [m]
+    pub fn hm0_infragravity(&self) -> f64 {
+        significant_wave_height(&self.infragravity)
+    }
+
+    /// Correlation between the infragravity signal and the short-wave envelope at zero lag
+    /// Close to -1 for long waves bound under wave groups
+    pub fn envelope_correlation(&self) -> f64 {
+        correlation(&self.envelope, &self.infragravity, 0)
+    }
+
+    /// Strongest correlation between envelope and infragravity signal within ±max_lag [s]
+    /// Returns the lag [s] and the correlation; a positive lag means the infragravity signal lags the envelope
+    pub fn lagged_envelope_correlation(&self, max_lag: f64) -> (f64, f64) {
+        let max_samples = (max_lag / self.sample_interval).round() as isize;
+        (-max_samples..=max_samples)
+            .map(|lag| (lag, correlation(&self.envelope, &self.infragravity, lag)))
+            .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+            .map_or((0.0, 0.0), |(lag, r)| (lag as f64 * self.sample_interval, r))
+    }
+}
+
+/// Cross-shore profile of infragravity wave height from a set of gauges, sorted by position
+/// Returns (position [m], infragravity Hm0 [m]) for every gauge whose record can be analysed
+pub fn infragravity_profile(gauges: &[WaveGauge], split_frequency: f64) -> Vec<(f64, f64)> {
+    let mut profile: Vec<(f64, f64)> = gauges
+        .iter()
+        .filter_map(|gauge| {
+            InfragravityAnalysis::from_gauge(gauge, split_frequency)
+                .ok()
+                .map(|analysis| (gauge.position(), analysis.hm0_infragravity()))
+        })
+        .collect();
+    profile.sort_by(|a, b| a.0.total_cmp(&b.0));
+    profile
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    /// Bichromatic wave group with a bound long wave in antiphase with the group envelope
+    fn wave_group(samples: usize, dt: f64) -> Vec<f64> {
+        (0..samples)
+            .map(|i| {
+                let t = i as f64 * dt;
+                let short = 0.5 * (2.0 * PI * 0.20 * t).cos() + 0.5 * (2.0 * PI * 0.22 * t).cos();
+                let bound = -0.05 * (2.0 * PI * 0.02 * t).cos();
+                short + bound
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_split_wave_heights() {
+        let dt = 0.25;
+        let analysis = InfragravityAnalysis::with_peak_split(&wave_group(4096, dt), dt).unwrap();
+        assert!((analysis.split_frequency() - 0.1).abs() < 0.01);
+
+        // Two unit-amplitude-half components: σ² = 2 × 0.5²/2; bound wave: σ² = 0.05²/2
+        let expected_swell = 4.0 * 0.25_f64.sqrt();
+        let expected_ig = 4.0 * (0.05_f64.powi(2) / 2.0).sqrt();
+        assert!((analysis.hm0_sea_swell() - expected_swell).abs() < 0.05 * expected_swell);
+        assert!((analysis.hm0_infragravity() - expected_ig).abs() < 0.1 * expected_ig);
+    }
+
+    #[test]
+    fn test_bound_wave_anticorrelated_with_envelope() {
+        let dt = 0.25;
+        let analysis = InfragravityAnalysis::new(&wave_group(4096, dt), dt, 0.1).unwrap();
+        assert!(analysis.envelope_correlation() < -0.9, "r = {:.3}", analysis.envelope_correlation());
+
+        let (lag, r) = analysis.lagged_envelope_correlation(10.0);
+        assert!(lag.abs() <= 1.0 && r < -0.9, "r = {:.3} at {:.2} s", r, lag);
+    }
+
+    #[test]
+    fn test_invalid_analysis() {
+        assert!(InfragravityAnalysis::new(&[0.0; 3], 0.1, 0.1).is_err());
+        assert!(InfragravityAnalysis::new(&[0.0; 100], 0.1, 6.0).is_err());
+        assert!(InfragravityAnalysis::from_gauge(&WaveGauge::new(0.0), 0.1).is_err());
+    }
+
+    #[test]
+    fn test_infragravity_profile() {
+        let dt = 0.25;
+        let record = wave_group(2048, dt);
+        let mut gauges = Vec::new();
+        for (position, scale) in [(30.0, 2.0), (10.0, 1.0), (20.0, 1.5)] {
+            let mut gauge = WaveGauge::new(position);
+            for (i, value) in record.iter().enumerate() {
+                gauge.record(i as f64 * dt, scale * value);
+            }
+            gauges.push(gauge);
+        }
+        gauges.push(WaveGauge::new(40.0));
+
+        let profile = infragravity_profile(&gauges, 0.1);
+        assert_eq!(profile.len(), 3);
+        assert_eq!(profile[0].0, 10.0);
+        assert!(profile[2].1 > profile[1].1 && profile[1].1 > profile[0].1);
+    }
+}
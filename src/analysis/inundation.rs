@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+
+/// Envelope of the flooding of a channel by a long wave: highest water level, deepest water and arrival time at every cell
+///
+/// A cell is wet where the water stands more than the wet depth above the bed. Cells under the still water
+/// level see the wave arrive when the surface first departs from it by the arrival threshold; land cells,
+/// above the still water level, when they are first wet. The inundation limit is the farthest land cell
+/// wetted from the sea, the run-up the bed elevation there above the still water level.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InundationRecord {
+    /// Cell centres [m]
+    positions: Vec<f64>,
+    /// Still water depth at the cell centres, negative on land (d) [m]
+    still_water_depth: Vec<f64>,
+    /// Water depth above the bed under which a cell is dry [m]
+    wet_depth: f64,
+    /// Departure of the surface from the still water level marking the arrival of the wave [m]
+    threshold: f64,
+    /// Last sample time, None for an empty record [s]
+    last_time: Option<f64>,
+    /// Highest surface elevation above the still water level at each cell, None where never wet [m]
+    max_elevation: Vec<Option<f64>>,
+    /// Largest water depth above the bed at each cell, zero where never wet [m]
+    max_depth: Vec<f64>,
+    /// Time of arrival of the wave at each cell, None before it arrives [s]
+    arrival: Vec<Option<f64>>,
+}
+
+impl InundationRecord {
+    /// Create new empty record over cells at the given centres and still water depths
+    pub fn new(positions: &[f64], still_water_depth: &[f64], wet_depth: f64, threshold: f64) -> Self {
+        let n = positions.len().min(still_water_depth.len());
+        Self {
+            positions: positions[..n].to_vec(),
+            still_water_depth: still_water_depth[..n].to_vec(),
+            wet_depth,
+            threshold,
+            last_time: None,
+            max_elevation: vec![None; n],
+            max_depth: vec![0.0; n],
+            arrival: vec![None; n],
+        }
+    }
+
+    /// Add a sample of the surface elevation at the cell centres
+    pub fn record(&mut self, time: f64, eta: &[f64]) {
+        for (i, &eta) in eta.iter().take(self.positions.len()).enumerate() {
+            let depth = self.still_water_depth[i] + eta;
+            if depth <= self.wet_depth {
+                continue;
+            }
+            self.max_elevation[i] = Some(self.max_elevation[i].map_or(eta, |max| max.max(eta)));
+            self.max_depth[i] = self.max_depth[i].max(depth);
+            let arrived = if self.still_water_depth[i] > 0.0 { eta.abs() >= self.threshold } else { true };
+            if arrived && self.arrival[i].is_none() {
+                self.arrival[i] = Some(time);
+            }
+        }
+        self.last_time = Some(time);
+    }
+
+    /// Get cell centres [m]
+    pub fn positions(&self) -> &[f64] {
+        &self.positions
+    }
+
+    /// Get still water depth at the cell centres [m]
+    pub fn still_water_depth(&self) -> &[f64] {
+        &self.still_water_depth
+    }
+
+    /// Get highest surface elevation at the cell centres, None where never wet [m]
+    pub fn max_elevation(&self) -> &[Option<f64>] {
+        &self.max_elevation
+    }
+
+    /// Get largest water depth at the cell centres [m]
+    pub fn max_depth(&self) -> &[f64] {
+        &self.max_depth
+    }
+
+    /// Get arrival time of the wave at the cell centres [s]
+    pub fn arrival(&self) -> &[Option<f64>] {
+        &self.arrival
+    }
+
+    /// Last sample time, None for an empty record [s]
+    pub fn last_time(&self) -> Option<f64> {
+        self.last_time
+    }
+
+    /// Check if nothing was recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.last_time.is_none()
+    }
+
+    /// Arrival time of the wave at position x, from the nearest cell, None before it arrives [s]
+    pub fn arrival_at(&self, x: f64) -> Option<f64> {
+        let nearest = (0..self.positions.len()).min_by(|&a, &b| (self.positions[a] - x).abs().total_cmp(&(self.positions[b] - x).abs()))?;
+        self.arrival[nearest]
+    }
+
+    /// Shoreline at rest: centre of the first land cell, None for a channel under water to its end [m]
+    pub fn still_shoreline(&self) -> Option<f64> {
+        let first = self.still_water_depth.iter().position(|&depth| depth <= 0.0)?;
+        Some(self.positions[first])
+    }
+
+    /// Last land cell of the flooded stretch joined to the sea, None if no land was wetted
+    fn limit(&self) -> Option<usize> {
+        let first = self.still_water_depth.iter().position(|&depth| depth <= 0.0)?;
+        let wetted = self.max_depth[first..].iter().take_while(|&&depth| depth > self.wet_depth).count();
+        (wetted > 0).then(|| first + wetted - 1)
+    }
+
+    /// Farthest position reached by the water on land, None if no land was wetted [m]
+    pub fn inundation_limit(&self) -> Option<f64> {
+        self.limit().map(|limit| self.positions[limit])
+    }
+
+    /// Horizontal distance flooded beyond the shoreline at rest, zero if no land was wetted [m]
+    pub fn inundation_extent(&self) -> f64 {
+        match (self.still_shoreline(), self.inundation_limit()) {
+            (Some(shoreline), Some(limit)) => limit - shoreline,
+            _ => 0.0,
+        }
+    }
+
+    /// Maximum run-up: bed elevation above the still water level at the inundation limit, zero if no land was wetted [m]
+    pub fn max_run_up(&self) -> f64 {
+        self.limit().map_or(0.0, |limit| -self.still_water_depth[limit])
+    }
+
+    /// Largest water depth over the land, with its position, None if no land was wetted [m]
+    pub fn max_inundation_depth(&self) -> Option<(f64, f64)> {
+        let first = self.still_water_depth.iter().position(|&depth| depth <= 0.0)?;
+        (first..=self.limit()?).map(|i| (self.positions[i], self.max_depth[i])).max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flooding_envelope() {
+        // Bed at 1 m depth offshore rising 0.5 m per cell, land from x = 3
+        let positions = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+        let still_water_depth = [1.0, 1.0, 0.5, 0.0, -0.5, -1.0];
+        let mut record = InundationRecord::new(&positions, &still_water_depth, 1e-3, 0.01);
+        assert!(record.is_empty() && record.inundation_limit().is_none());
+        assert_eq!(record.still_shoreline(), Some(3.0));
+
+        // Dry land cells hold the surface at the bed
+        record.record(1.0, &[0.05, 0.005, 0.0, 0.0, 0.5, 1.0]);
+        record.record(2.0, &[-0.05, 0.2, 0.3, 0.4, 0.5, 1.0]);
+        record.record(3.0, &[0.0, 0.1, 0.6, 0.7, 0.6, 1.0]);
+        assert_eq!(record.arrival(), &[Some(1.0), Some(2.0), Some(2.0), Some(2.0), Some(3.0), None]);
+        assert_eq!(record.arrival_at(0.9), Some(2.0));
+        assert_eq!((record.max_elevation()[0], record.max_elevation()[5]), (Some(0.05), None));
+        assert!((record.max_depth()[4] - 0.1).abs() < 1e-12);
+        assert_eq!(record.inundation_limit(), Some(4.0));
+        assert_eq!((record.inundation_extent(), record.max_run_up()), (1.0, 0.5));
+        assert_eq!(record.max_inundation_depth(), Some((3.0, 0.7)));
+    }
+}
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+/// Time series of the rise of the mean water level in the channel, a diagnostic of the mass balance
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LevelDriftRecord {
+    /// Sample times [s]
+    times: Vec<f64>,
+    /// Rise of the mean water level since the start of the run at each sample [m]
+    drift: Vec<f64>,
+}
+
+impl LevelDriftRecord {
+    /// Append a sample to the record
+    pub fn record(&mut self, time: f64, drift: f64) {
+        self.times.push(time);
+        self.drift.push(drift);
+    }
+
+    /// Get sample times [s]
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    /// Get the rise of the mean water level at each sample [m]
+    pub fn drift(&self) -> &[f64] {
+        &self.drift
+    }
+
+    /// Rise of the mean water level at the last sample [m]
+    pub fn last(&self) -> Option<f64> {
+        self.drift.last().copied()
+    }
+
+    /// Least-squares rate of rise of the mean water level over the record, None with fewer than two samples [m/s]
+    pub fn rate(&self) -> Option<f64> {
+        if self.len() < 2 {
+            return None;
+        }
+        let n = self.len() as f64;
+        let mean_time = self.times.iter().sum::<f64>() / n;
+        let mean_drift = self.drift.iter().sum::<f64>() / n;
+        let (covariance, variance) = self.times.iter().zip(&self.drift).fold((0.0, 0.0), |(covariance, variance), (time, drift)| {
+            (covariance + (time - mean_time) * (drift - mean_drift), variance + (time - mean_time).powi(2))
+        });
+        (variance > 0.0).then(|| covariance / variance)
+    }
+
+    /// Number of recorded samples
+    pub fn len(&self) -> usize {
+        self.times.len()
+    }
+
+    /// Check if nothing was recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty()
+    }
+
+    /// Discard the record
+    pub fn clear(&mut self) {
+        self.times.clear();
+        self.drift.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drift_rate() {
+        let mut record = LevelDriftRecord::default();
+        record.record(0.0, 0.0);
+        assert_eq!(record.rate(), None);
+        for n in 1..=10 {
+            record.record(n as f64, 2e-4 * n as f64 + 1e-5 * (n % 2) as f64);
+        }
+        assert!((record.rate().unwrap() - 2e-4).abs() < 1e-5);
+        assert_eq!(record.last(), Some(2e-3));
+
+        record.clear();
+        assert!(record.is_empty());
+    }
+}
@@ -0,0 +1,52 @@
+pub mod spectral;
+pub mod gauge;
+pub mod agitation;
+pub mod bispectrum;
+pub mod bores;
+pub mod boundary_signal;
+pub mod crest_tracking;
+pub mod cross_spectrum;
+pub mod dissipation;
+pub mod expression;
+pub mod height_field;
+pub mod impacts;
+pub mod infragravity;
+pub mod inundation;
+pub mod level_drift;
+pub mod monte_carlo;
+pub mod optimization;
+pub mod overtopping;
+pub mod pressure_sensor;
+pub mod reflection;
+pub mod snapshot_file;
+pub mod snapshots;
+pub mod swash;
+pub mod transect;
+pub mod undertow;
+pub mod wavelet;
+
+pub use gauge::WaveGauge;
+pub use agitation::{BerthAgitation, berth_agitation};
+pub use bispectrum::Bispectrum;
+pub use boundary_signal::{BoundarySignal, SIGNAL_VARIABLES};
+pub use bores::{BoreCriteria, BoreObservation, BoreTrack, FrontKind, find_bores, jump_froude, track_bores};
+pub use crest_tracking::{CrestObservation, CrestTrack, find_crests, find_troughs, track_crests, visit_crests, visit_troughs};
+pub use cross_spectrum::CrossSpectrum;
+pub use dissipation::DissipationRecord;
+pub use expression::{CustomFormula, Expression, function_names};
+pub use height_field::WaveHeightField;
+pub use impacts::{ForceRecord, ImpactEvent, LoadClass, detect_impacts, peak_histogram};
+pub use infragravity::{InfragravityAnalysis, infragravity_profile};
+pub use inundation::InundationRecord;
+pub use level_drift::LevelDriftRecord;
+pub use monte_carlo::{ConvergencePoint, Distribution, OutputDistribution};
+pub use optimization::{SearchRange, grid_points, nelder_mead, pareto_front};
+pub use overtopping::OvertoppingTank;
+pub use pressure_sensor::PressureSensor;
+pub use reflection::{EnergyBudget, ReflectionAnalysis};
+pub use snapshot_file::SnapshotFile;
+pub use snapshots::{Snapshot, SnapshotStore};
+pub use swash::{ShorelineRecord, SwashAnalysis, SwashVelocity};
+pub use transect::Transect;
+pub use undertow::{MeanFlowRecord, UndertowProfile};
+pub use wavelet::Scalogram;
@@ -0,0 +1,280 @@
+use crate::waves::SeededRng;
+use serde::{Deserialize, Serialize};
+
+/// Euler-Mascheroni constant, the mean of the standard Gumbel distribution
+const EULER_GAMMA: f64 = 0.577_215_664_901_532_9;
+
+/// Probability distribution of an uncertain input
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Distribution {
+    /// Equally likely values between two bounds
+    Uniform { low: f64, high: f64 },
+    /// Gaussian around a mean
+    Normal { mean: f64, std_dev: f64 },
+    /// Positive values whose logarithm is Gaussian, given by the mean and standard deviation of the values
+    LogNormal { mean: f64, std_dev: f64 },
+    /// Extreme value type I, for annual maxima of wave heights and water levels
+    Gumbel { location: f64, scale: f64 },
+}
+
+impl Distribution {
+    /// Names of the distributions, in the order of the selection list
+    pub const NAMES: [&'static str; 4] = ["Uniform", "Normal", "Log-normal", "Gumbel"];
+
+    /// Distribution of a kind with the same mean and standard deviation, for switching kinds in the editor
+    pub fn with_kind(&self, name: &str) -> Self {
+        let (mean, std_dev) = (self.mean(), self.std_dev());
+        match name {
+            "Uniform" => {
+                let half_width = 3.0_f64.sqrt() * std_dev;
+                Distribution::Uniform { low: mean - half_width, high: mean + half_width }
+            }
+            "Log-normal" => Distribution::LogNormal { mean, std_dev },
+            "Gumbel" => {
+                let scale = std_dev * 6.0_f64.sqrt() / std::f64::consts::PI;
+                Distribution::Gumbel { location: mean - EULER_GAMMA * scale, scale }
+            }
+            _ => Distribution::Normal { mean, std_dev },
+        }
+    }
+
+    /// Name of the kind of distribution
+    pub fn name(&self) -> &'static str {
+        match self {
+            Distribution::Uniform { .. } => Self::NAMES[0],
+            Distribution::Normal { .. } => Self::NAMES[1],
+            Distribution::LogNormal { .. } => Self::NAMES[2],
+            Distribution::Gumbel { .. } => Self::NAMES[3],
+        }
+    }
+
+    /// Check that the bounds and spreads define a distribution
+    pub fn validate(&self) -> Result<(), String> {
+        let valid = match *self {
+            Distribution::Uniform { low, high } => low.is_finite() && high.is_finite() && high >= low,
+            Distribution::Normal { mean, std_dev } => mean.is_finite() && std_dev.is_finite() && std_dev >= 0.0,
+            Distribution::LogNormal { mean, std_dev } => mean.is_finite() && mean > 0.0 && std_dev.is_finite() && std_dev >= 0.0,
+            Distribution::Gumbel { location, scale } => location.is_finite() && scale.is_finite() && scale >= 0.0,
+        };
+        if valid { Ok(()) } else { Err(format!("Invalid {} distribution", self.name())) }
+    }
+
+    /// Mean of the distribution
+    pub fn mean(&self) -> f64 {
+        match *self {
+            Distribution::Uniform { low, high } => 0.5 * (low + high),
+            Distribution::Normal { mean, .. } | Distribution::LogNormal { mean, .. } => mean,
+            Distribution::Gumbel { location, scale } => location + EULER_GAMMA * scale,
+        }
+    }
+
+    /// Standard deviation of the distribution
+    pub fn std_dev(&self) -> f64 {
+        match *self {
+            Distribution::Uniform { low, high } => (high - low) / 12.0_f64.sqrt(),
+            Distribution::Normal { std_dev, .. } | Distribution::LogNormal { std_dev, .. } => std_dev,
+            Distribution::Gumbel { scale, .. } => std::f64::consts::PI * scale / 6.0_f64.sqrt(),
+        }
+    }
+
+    /// Draw a value
+    pub fn sample(&self, rng: &mut SeededRng) -> f64 {
+        match *self {
+            Distribution::Uniform { low, high } => rng.uniform(low, high),
+            Distribution::Normal { mean, std_dev } => mean + std_dev * rng.normal(),
+            Distribution::LogNormal { mean, std_dev } => {
+                // Parameters of the underlying normal distribution
+                let sigma2 = (1.0 + (std_dev / mean).powi(2)).ln();
+                let mu = mean.ln() - 0.5 * sigma2;
+                (mu + sigma2.sqrt() * rng.normal()).exp()
+            }
+            Distribution::Gumbel { location, scale } => {
+                // 1 − u lies in (0, 1], keeping the logarithms finite
+                let u = 1.0 - rng.next_f64();
+                location - scale * (-(u.max(f64::MIN_POSITIVE)).ln()).ln()
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Distribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Distribution::Uniform { low, high } => write!(f, "U({:.3}, {:.3})", low, high),
+            Distribution::Normal { mean, std_dev } => write!(f, "N({:.3}, {:.3})", mean, std_dev),
+            Distribution::LogNormal { mean, std_dev } => write!(f, "LN({:.3}, {:.3})", mean, std_dev),
+            Distribution::Gumbel { location, scale } => write!(f, "Gumbel({:.3}, {:.3})", location, scale),
+        }
+    }
+}
+
+/// Estimates of the output distribution after a number of realizations
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConvergencePoint {
+    pub samples: usize,       // Realizations included
+    pub mean: f64,            // Running mean
+    pub standard_error: f64,  // Standard error of the running mean
+    pub quantile: f64,        // Running value of the tracked quantile
+}
+
+/// Values of an output over the realizations of a Monte Carlo study, in the order they were drawn
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OutputDistribution {
+    values: Vec<f64>,
+}
+
+impl OutputDistribution {
+    pub fn new(values: Vec<f64>) -> Self {
+        Self { values }
+    }
+
+    /// Output values, in the order of the realizations
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Number of realizations
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Check if no realization completed
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Sample mean
+    pub fn mean(&self) -> f64 {
+        self.values.iter().sum::<f64>() / self.values.len().max(1) as f64
+    }
+
+    /// Sample standard deviation, 0 with fewer than two realizations
+    pub fn std_dev(&self) -> f64 {
+        let n = self.values.len();
+        if n < 2 {
+            return 0.0;
+        }
+        let mean = self.mean();
+        (self.values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / (n - 1) as f64).sqrt()
+    }
+
+    /// Standard error of the sample mean
+    pub fn standard_error(&self) -> f64 {
+        self.std_dev() / (self.values.len().max(1) as f64).sqrt()
+    }
+
+    /// Quantile of non-exceedance probability p, interpolated linearly between the sorted values
+    pub fn quantile(&self, p: f64) -> f64 {
+        quantile(&sorted(&self.values), p)
+    }
+
+    /// Exceedance curve: (value, probability that the value is exceeded)
+    /// Weibull plotting positions i/(n + 1), so the largest value of n has a probability of 1/(n + 1)
+    pub fn exceedance_curve(&self) -> Vec<[f64; 2]> {
+        let n = self.values.len();
+        sorted(&self.values)
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| [value, (n - i) as f64 / (n + 1) as f64])
+            .collect()
+    }
+
+    /// Running estimates after each realization, to judge whether more are needed
+    pub fn convergence(&self, p: f64) -> Vec<ConvergencePoint> {
+        (1..=self.values.len())
+            .map(|samples| {
+                let part = OutputDistribution::new(self.values[..samples].to_vec());
+                ConvergencePoint {
+                    samples,
+                    mean: part.mean(),
+                    standard_error: part.standard_error(),
+                    quantile: part.quantile(p),
+                }
+            })
+            .collect()
+    }
+
+    /// Check if the standard error of the mean is below a fraction of the mean
+    /// At least ten realizations are needed to trust the estimate of the spread
+    pub fn is_converged(&self, relative_tolerance: f64) -> bool {
+        self.values.len() >= 10 && self.standard_error() <= relative_tolerance * self.mean().abs()
+    }
+}
+
+/// Values sorted in increasing order
+fn sorted(values: &[f64]) -> Vec<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    sorted
+}
+
+/// Quantile of sorted values, NaN if there are none
+fn quantile(sorted: &[f64], p: f64) -> f64 {
+    match sorted.len() {
+        0 => f64::NAN,
+        1 => sorted[0],
+        n => {
+            let position = p.clamp(0.0, 1.0) * (n - 1) as f64;
+            let lower = (position.floor() as usize).min(n - 2);
+            let weight = position - lower as f64;
+            sorted[lower] * (1.0 - weight) + sorted[lower + 1] * weight
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moments(distribution: Distribution) -> (f64, f64) {
+        let mut rng = SeededRng::new(7);
+        let values: Vec<f64> = (0..20000).map(|_| distribution.sample(&mut rng)).collect();
+        let output = OutputDistribution::new(values);
+        (output.mean(), output.std_dev())
+    }
+
+    #[test]
+    fn test_sampled_moments() {
+        let distributions = [
+            Distribution::Uniform { low: 1.0, high: 3.0 },
+            Distribution::Normal { mean: 2.0, std_dev: 0.5 },
+            Distribution::LogNormal { mean: 2.0, std_dev: 0.5 },
+            Distribution::Gumbel { location: 1.5, scale: 0.4 },
+        ];
+        for distribution in distributions {
+            let (mean, std_dev) = moments(distribution);
+            assert!((mean - distribution.mean()).abs() < 0.02, "{} mean {:.3}", distribution, mean);
+            assert!((std_dev - distribution.std_dev()).abs() < 0.02, "{} spread {:.3}", distribution, std_dev);
+            // Switching kinds keeps the moments
+            for name in Distribution::NAMES {
+                let switched = distribution.with_kind(name);
+                assert_eq!(switched.name(), name);
+                assert!((switched.mean() - distribution.mean()).abs() < 1e-9);
+                assert!((switched.std_dev() - distribution.std_dev()).abs() < 1e-9);
+            }
+        }
+        assert!(Distribution::LogNormal { mean: 0.0, std_dev: 0.1 }.validate().is_err());
+        assert!(Distribution::Uniform { low: 2.0, high: 1.0 }.validate().is_err());
+    }
+
+    #[test]
+    fn test_exceedance_and_convergence() {
+        let output = OutputDistribution::new(vec![3.0, 1.0, 4.0, 2.0]);
+        assert_eq!(output.exceedance_curve(), vec![[1.0, 0.8], [2.0, 0.6], [3.0, 0.4], [4.0, 0.2]]);
+        assert_eq!(output.quantile(0.5), 2.5);
+        assert_eq!(output.quantile(1.0), 4.0);
+
+        let convergence = output.convergence(0.5);
+        assert_eq!(convergence.len(), 4);
+        assert_eq!((convergence[0].mean, convergence[0].standard_error), (3.0, 0.0));
+        assert_eq!(convergence[1].quantile, 2.0);
+        assert!(!output.is_converged(0.5));
+
+        // The standard error shrinks with the square root of the realizations
+        let mut rng = SeededRng::new(3);
+        let many = OutputDistribution::new((0..400).map(|_| 1.0 + 0.1 * rng.normal()).collect());
+        assert!(many.is_converged(0.01));
+        let points = many.convergence(0.9);
+        assert!((points[99].standard_error / points[399].standard_error - 2.0).abs() < 0.3);
+    }
+}
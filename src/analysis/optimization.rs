@@ -0,0 +1,145 @@
+/// Bounds and number of values of a parameter in a grid search
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchRange {
+    pub min: f64,
+    pub max: f64,
+    pub steps: usize, // Values from min to max, 1 to hold the parameter at min
+}
+
+impl SearchRange {
+    /// Values of the range, evenly spaced from min to max
+    pub fn values(&self) -> Vec<f64> {
+        if self.steps <= 1 {
+            return vec![self.min];
+        }
+        (0..self.steps).map(|i| self.min + (self.max - self.min) * i as f64 / (self.steps - 1) as f64).collect()
+    }
+
+    /// Whether the parameter takes more than one value
+    pub fn is_varied(&self) -> bool {
+        self.steps > 1 && self.max > self.min
+    }
+}
+
+/// Every combination of the values of the ranges, the last range varying fastest
+pub fn grid_points(ranges: &[SearchRange]) -> Vec<Vec<f64>> {
+    ranges.iter().fold(vec![Vec::new()], |points, range| {
+        points
+            .iter()
+            .flat_map(|point| {
+                range.values().into_iter().map(move |value| {
+                    let mut extended = point.clone();
+                    extended.push(value);
+                    extended
+                })
+            })
+            .collect()
+    })
+}
+
+/// Minimum of a function with the Nelder–Mead downhill simplex (Nelder & Mead, 1965)
+///
+/// The simplex starts at a point with one vertex per dimension offset by the step of that
+/// dimension, and stops when the function values of its vertices agree within a tolerance or
+/// after a number of evaluations. Returns the best point and its value.
+pub fn nelder_mead(mut f: impl FnMut(&[f64]) -> f64, start: &[f64], steps: &[f64], tolerance: f64, max_evaluations: usize) -> (Vec<f64>, f64) {
+    let n = start.len();
+    let mut simplex: Vec<(Vec<f64>, f64)> = Vec::with_capacity(n + 1);
+    simplex.push((start.to_vec(), f(start)));
+    for i in 0..n {
+        let mut vertex = start.to_vec();
+        vertex[i] += steps[i];
+        let value = f(&vertex);
+        simplex.push((vertex, value));
+    }
+    let mut evaluations = n + 1;
+
+    let blend = |a: &[f64], b: &[f64], weight: f64| -> Vec<f64> { a.iter().zip(b).map(|(a, b)| a + weight * (b - a)).collect() };
+    while n > 0 && evaluations < max_evaluations {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let (best, worst) = (simplex[0].1, simplex[n].1);
+        if (worst - best).abs() <= tolerance * (best.abs() + tolerance) {
+            break;
+        }
+        let centroid: Vec<f64> = (0..n).map(|d| simplex[..n].iter().map(|(vertex, _)| vertex[d]).sum::<f64>() / n as f64).collect();
+        let reflected = blend(&centroid, &simplex[n].0, -1.0);
+        let reflected_value = f(&reflected);
+        evaluations += 1;
+        if reflected_value < best {
+            let expanded = blend(&centroid, &simplex[n].0, -2.0);
+            let expanded_value = f(&expanded);
+            evaluations += 1;
+            simplex[n] = if expanded_value < reflected_value { (expanded, expanded_value) } else { (reflected, reflected_value) };
+        } else if reflected_value < simplex[n - 1].1 {
+            simplex[n] = (reflected, reflected_value);
+        } else {
+            let contracted = blend(&centroid, &simplex[n].0, 0.5);
+            let contracted_value = f(&contracted);
+            evaluations += 1;
+            if contracted_value < worst {
+                simplex[n] = (contracted, contracted_value);
+            } else {
+                // Shrink towards the best vertex
+                for i in 1..=n {
+                    let shrunk = blend(&simplex[0].0, &simplex[i].0, 0.5);
+                    let value = f(&shrunk);
+                    simplex[i] = (shrunk, value);
+                }
+                evaluations += n;
+            }
+        }
+    }
+    simplex.into_iter().min_by(|a, b| a.1.total_cmp(&b.1)).expect("simplex has a vertex")
+}
+
+/// Indices of the points no other point improves on in both coordinates, by increasing first coordinate
+/// Both coordinates are minimized, e.g. overtopping discharge and crest height
+pub fn pareto_front(points: &[[f64; 2]]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..points.len()).filter(|&i| points[i].iter().all(|value| value.is_finite())).collect();
+    order.sort_by(|&a, &b| points[a][0].total_cmp(&points[b][0]).then(points[a][1].total_cmp(&points[b][1])));
+    let mut front = Vec::new();
+    let mut lowest = f64::INFINITY;
+    for i in order {
+        if points[i][1] < lowest {
+            lowest = points[i][1];
+            front.push(i);
+        }
+    }
+    front
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_points() {
+        let ranges = [SearchRange { min: 0.0, max: 1.0, steps: 3 }, SearchRange { min: 5.0, max: 9.0, steps: 1 }];
+        assert_eq!(grid_points(&ranges), vec![vec![0.0, 5.0], vec![0.5, 5.0], vec![1.0, 5.0]]);
+        assert!(ranges[0].is_varied() && !ranges[1].is_varied());
+    }
+
+    #[test]
+    fn test_nelder_mead_finds_minimum() {
+        let mut calls = 0;
+        let rosenbrock = |x: &[f64]| {
+            calls += 1;
+            (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0] * x[0]).powi(2)
+        };
+        let (point, value) = nelder_mead(rosenbrock, &[-1.0, 1.5], &[0.5, 0.5], 1e-12, 2000);
+        assert!(value < 1e-8, "Minimum {:.2e} at {:?}", value, point);
+        assert!((point[0] - 1.0).abs() < 1e-3 && (point[1] - 1.0).abs() < 1e-3);
+        assert!(calls <= 2000 + 2);
+
+        // The evaluation budget is respected
+        let mut limited = 0;
+        nelder_mead(|x: &[f64]| { limited += 1; x[0] * x[0] }, &[3.0], &[1.0], 0.0, 10);
+        assert!(limited <= 12);
+    }
+
+    #[test]
+    fn test_pareto_front() {
+        let points = [[1.0, 5.0], [2.0, 3.0], [2.5, 4.0], [3.0, 1.0], [0.5, 6.0], [4.0, 1.0], [f64::NAN, 0.0]];
+        assert_eq!(pareto_front(&points), vec![4, 0, 1, 3]);
+    }
+}
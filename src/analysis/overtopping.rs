@@ -0,0 +1,159 @@
+use crate::units::Discharge;
+use serde::{Deserialize, Serialize};
+
+/// Discharge below which the tank counts as not filling, separating overtopping waves [m²/s]
+const FILLING_DISCHARGE: f64 = 1e-6;
+
+/// Collection tank behind a crest, recording the overtopped volume as in a laboratory experiment
+///
+/// Overtopping water falls through a chute into the tank, whose level rises with the collected
+/// volume. As in the laboratory, the instantaneous discharge follows from the rate of rise of the
+/// tank level and individual overtopping waves from the jumps of the volume between calm periods.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OvertoppingTank {
+    /// Plan area of the tank per unit width of the chute [m]
+    plan_length: f64,
+    /// Sample times [s]
+    times: Vec<f64>,
+    /// Collected volume per unit width of the chute [m²]
+    volume: Vec<f64>,
+}
+
+impl OvertoppingTank {
+    /// Create new empty tank with a plan area per unit width of the chute [m]
+    pub fn new(plan_length: f64) -> Result<Self, String> {
+        let mut tank = Self {
+            plan_length: 1.0,
+            times: Vec::new(),
+            volume: Vec::new(),
+        };
+        tank.set_plan_length(plan_length)?;
+        Ok(tank)
+    }
+
+    /// Get plan area of the tank per unit width of the chute [m]
+    pub fn plan_length(&self) -> f64 {
+        self.plan_length
+    }
+
+    /// Resize the tank, keeping its record
+    pub fn set_plan_length(&mut self, plan_length: f64) -> Result<(), String> {
+        if !(plan_length.is_finite() && plan_length > 0.0) {
+            return Err("Tank plan length must be positive".to_string());
+        }
+        self.plan_length = plan_length;
+        Ok(())
+    }
+
+    /// Append a sample of the collected volume [m²]
+    pub fn record(&mut self, time: f64, volume: f64) {
+        self.times.push(time);
+        self.volume.push(volume);
+    }
+
+    /// Get sample times [s]
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    /// Get collected volume per unit width [m²]
+    pub fn volume(&self) -> &[f64] {
+        &self.volume
+    }
+
+    /// Number of recorded samples
+    pub fn len(&self) -> usize {
+        self.volume.len()
+    }
+
+    /// Check if nothing was recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.volume.is_empty()
+    }
+
+    /// Volume collected by the last sample, 0 for an empty record [m²]
+    pub fn total_volume(&self) -> f64 {
+        self.volume.last().copied().unwrap_or(0.0)
+    }
+
+    /// Water level in the tank above its empty bottom [m]
+    pub fn level(&self) -> Vec<f64> {
+        self.volume.iter().map(|volume| volume / self.plan_length).collect()
+    }
+
+    /// Instantaneous discharge from the rise of the tank between samples, centred inside the record [m²/s]
+    pub fn discharge(&self) -> Vec<f64> {
+        let n = self.len();
+        (0..n)
+            .map(|i| {
+                let (before, after) = (i.saturating_sub(1), (i + 1).min(n - 1));
+                let duration = self.times[after] - self.times[before];
+                if duration > 0.0 { (self.volume[after] - self.volume[before]) / duration } else { 0.0 }
+            })
+            .collect()
+    }
+
+    /// Mean discharge over the run, the tank being empty at its start
+    pub fn mean_discharge(&self) -> Discharge {
+        match self.times.last() {
+            Some(&duration) if duration > 0.0 => Discharge::square_meters_per_second(self.total_volume() / duration),
+            _ => Discharge::ZERO,
+        }
+    }
+
+    /// Volumes of the individual overtopping waves: each filling of the tank between calm periods [m²]
+    pub fn wave_volumes(&self) -> Vec<f64> {
+        let mut volumes = Vec::new();
+        let mut filling = false;
+        for (i, pair) in self.volume.windows(2).enumerate() {
+            let rise = pair[1] - pair[0];
+            if rise > FILLING_DISCHARGE * (self.times[i + 1] - self.times[i]) {
+                if !filling {
+                    volumes.push(0.0);
+                    filling = true;
+                }
+                if let Some(volume) = volumes.last_mut() {
+                    *volume += rise;
+                }
+            } else {
+                filling = false;
+            }
+        }
+        volumes
+    }
+
+    /// Empty the tank and discard its record
+    pub fn clear(&mut self) {
+        self.times.clear();
+        self.volume.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tank_record() {
+        // Two overtopping waves of 0.02 and 0.03 m² separated by a calm second
+        let mut tank = OvertoppingTank::new(0.5).unwrap();
+        for (i, volume) in [0.0, 0.01, 0.02, 0.02, 0.02, 0.035, 0.05, 0.05].into_iter().enumerate() {
+            tank.record(i as f64 + 1.0, volume);
+        }
+        assert_eq!(tank.total_volume(), 0.05);
+        assert_eq!(tank.level()[7], 0.1);
+        assert!((tank.mean_discharge().in_square_meters_per_second() - 0.05 / 8.0).abs() < 1e-12);
+        let discharge = tank.discharge();
+        assert!((discharge[0] - 0.01).abs() < 1e-12);
+        assert!((discharge[1] - 0.01).abs() < 1e-12);
+        assert_eq!(discharge[3], 0.0);
+        let waves = tank.wave_volumes();
+        assert_eq!(waves.len(), 2);
+        assert!((waves[0] - 0.02).abs() < 1e-12 && (waves[1] - 0.03).abs() < 1e-12);
+
+        assert!(OvertoppingTank::new(0.0).is_err());
+        assert!(tank.set_plan_length(-1.0).is_err());
+        tank.clear();
+        assert!(tank.is_empty() && tank.discharge().is_empty() && tank.mean_discharge() == Discharge::ZERO);
+    }
+}
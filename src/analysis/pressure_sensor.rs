@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+/// Virtual pressure sensor recording the pressure at a fixed point of a structure face
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PressureSensor {
+    /// Sensor position along the channel [m]
+    position: f64,
+    /// Sensor height above the offshore bed [m]
+    elevation: f64,
+    /// Sample times [s]
+    times: Vec<f64>,
+    /// Recorded pressure [Pa]
+    pressure: Vec<f64>,
+}
+
+impl PressureSensor {
+    /// Create new sensor with an empty record
+    pub fn new(position: f64, elevation: f64) -> Self {
+        Self {
+            position,
+            elevation,
+            times: Vec::new(),
+            pressure: Vec::new(),
+        }
+    }
+
+    /// Get sensor position [m]
+    pub fn position(&self) -> f64 {
+        self.position
+    }
+
+    /// Get sensor height above the offshore bed [m]
+    pub fn elevation(&self) -> f64 {
+        self.elevation
+    }
+
+    /// Move the sensor, discarding its record
+    pub fn set_location(&mut self, position: f64, elevation: f64) {
+        if position != self.position || elevation != self.elevation {
+            self.position = position;
+            self.elevation = elevation;
+            self.clear();
+        }
+    }
+
+    /// Append a sample to the record
+    pub fn record(&mut self, time: f64, pressure: f64) {
+        self.times.push(time);
+        self.pressure.push(pressure);
+    }
+
+    /// Get sample times [s]
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    /// Get recorded pressure [Pa]
+    pub fn pressure(&self) -> &[f64] {
+        &self.pressure
+    }
+
+    /// Number of recorded samples
+    pub fn len(&self) -> usize {
+        self.pressure.len()
+    }
+
+    /// Check if nothing was recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.pressure.is_empty()
+    }
+
+    /// Impact maximum: the highest recorded pressure and its time, None before the first sample
+    pub fn peak(&self) -> Option<(f64, f64)> {
+        self.times
+            .iter()
+            .zip(&self.pressure)
+            .map(|(&time, &pressure)| (time, pressure))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Pressure exceeded by a fraction of the samples, e.g. 0.004 for the p₁/₂₅₀ of impact studies [Pa]
+    pub fn exceeded_pressure(&self, fraction: f64) -> Option<f64> {
+        if self.pressure.is_empty() {
+            return None;
+        }
+        let mut sorted = self.pressure.clone();
+        sorted.sort_by(|a, b| b.total_cmp(a));
+        let index = ((fraction.clamp(0.0, 1.0) * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len()) - 1;
+        Some(sorted[index])
+    }
+
+    /// Discard the record
+    pub fn clear(&mut self) {
+        self.times.clear();
+        self.pressure.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sensor_peaks() {
+        let mut sensor = PressureSensor::new(40.0, 1.5);
+        assert_eq!(sensor.peak(), None);
+        for (i, pressure) in [1000.0, 5000.0, 2000.0, 3000.0].into_iter().enumerate() {
+            sensor.record(i as f64 * 0.1, pressure);
+        }
+        assert_eq!(sensor.peak(), Some((0.1, 5000.0)));
+        assert_eq!(sensor.exceeded_pressure(0.5), Some(3000.0));
+        assert_eq!(sensor.exceeded_pressure(0.0), Some(5000.0));
+
+        sensor.set_location(40.0, 1.5);
+        assert_eq!(sensor.len(), 4);
+        sensor.set_location(40.0, 1.0);
+        assert!(sensor.is_empty());
+    }
+}
@@ -0,0 +1,405 @@
+use crate::analysis::spectral::{fft, mean};
+use crate::waves::WATER_DENSITY;
+use std::f64::consts::PI;
+
+/// Smallest conditioning 1 − |Σ e^(−2ikx)|²/P² of the least-squares separation at a resolved frequency
+/// For two gauges it is sin²(kΔx), keeping the spacing within 0.05 < Δx/L < 0.45 (Goda & Suzuki, 1976)
+const MIN_CONDITIONING: f64 = 0.1;
+
+/// Share of the incident energy at unresolved frequencies above which the budget is flagged
+const UNRESOLVED_WARNING: f64 = 0.1;
+
+/// Incident and reflected variance spectra separated from simultaneous records of an array of gauges
+///
+/// At each frequency the Fourier coefficients of the P records, B_p = Z_I e^(−ikx_p) + Z_R e^(ikx_p),
+/// are fitted by least squares (Mansard & Funke, 1980), which reduces to the two-gauge method of
+/// Goda & Suzuki (1976) for P = 2. Frequencies where the spacing is close to a multiple of half a
+/// wavelength cannot be separated and are left out of the energies. A single gauge is taken as
+/// measuring incident waves only.
+#[derive(Debug, Clone)]
+pub struct ReflectionAnalysis {
+    /// Frequencies of the bins, excluding the mean [Hz]
+    frequencies: Vec<f64>,
+    /// Variance density of the waves travelling in the direction of increasing x [m²/Hz]
+    incident: Vec<f64>,
+    /// Variance density of the waves travelling back [m²/Hz]
+    reflected: Vec<f64>,
+    /// Group velocity per frequency [m/s]
+    group_velocity: Vec<f64>,
+    /// Whether the gauge spacing separates the waves at each frequency
+    resolved: Vec<bool>,
+    /// Number of gauges of the array
+    gauges: usize,
+    /// Number of averaged segments
+    segments: usize,
+}
+
+impl ReflectionAnalysis {
+    /// Average Hann-windowed segments with 50% overlap of simultaneous records at known positions
+    /// The wave number k(f) [rad/m] follows the dispersion relation of the waves at the array
+    pub fn new(records: &[&[f64]], positions: &[f64], sample_interval: f64, segment_length: usize, wave_number: impl Fn(f64) -> f64) -> Result<Self, String> {
+        if records.is_empty() {
+            return Err("No gauge in the array".to_string());
+        }
+        if records.len() != positions.len() {
+            return Err("Every record needs a position".to_string());
+        }
+        let samples = records[0].len();
+        if records.iter().any(|record| record.len() != samples) {
+            return Err("Records must have the same length".to_string());
+        }
+        if sample_interval <= 0.0 {
+            return Err("Sample interval must be positive".to_string());
+        }
+        if !segment_length.is_power_of_two() || segment_length < 8 {
+            return Err("Segment length must be a power of two of at least 8 samples".to_string());
+        }
+        if samples < segment_length {
+            return Err("Records are shorter than one segment".to_string());
+        }
+
+        let window: Vec<f64> = (0..segment_length)
+            .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f64 / segment_length as f64).cos())
+            .collect();
+        let window_power: f64 = window.iter().map(|w| w * w).sum();
+        let bins = segment_length / 2;
+        let resolution = 1.0 / (segment_length as f64 * sample_interval);
+        let frequencies: Vec<f64> = (1..=bins).map(|k| k as f64 * resolution).collect();
+        let wave_numbers: Vec<f64> = frequencies.iter().map(|&frequency| wave_number(frequency)).collect();
+
+        // Phase factors a_p = e^(−ikx_p) and the conditioning of the normal equations per frequency
+        let count = records.len() as f64;
+        let phases: Vec<Vec<(f64, f64)>> = wave_numbers
+            .iter()
+            .map(|k| positions.iter().map(|x| ((k * x).cos(), -(k * x).sin())).collect())
+            .collect();
+        let conditioning: Vec<f64> = phases
+            .iter()
+            .map(|phase| {
+                // S = Σ a_p²
+                let s = phase.iter().fold((0.0, 0.0), |s, (re, im)| (s.0 + re * re - im * im, s.1 + 2.0 * re * im));
+                1.0 - (s.0 * s.0 + s.1 * s.1) / (count * count)
+            })
+            .collect();
+
+        let mut incident = vec![0.0; bins];
+        let mut reflected = vec![0.0; bins];
+        let starts: Vec<usize> = (0..=samples - segment_length).step_by(segment_length / 2).collect();
+        for &start in &starts {
+            let spectra: Vec<(Vec<f64>, Vec<f64>)> = records
+                .iter()
+                .map(|record| {
+                    let segment = &record[start..start + segment_length];
+                    let segment_mean = mean(segment);
+                    let mut re: Vec<f64> = segment.iter().zip(&window).map(|(value, w)| (value - segment_mean) * w).collect();
+                    let mut im = vec![0.0; segment_length];
+                    fft(&mut re, &mut im, false);
+                    (re, im)
+                })
+                .collect();
+            for bin in 0..bins {
+                let k = bin + 1;
+                let fold = if k == segment_length / 2 { 1.0 } else { 2.0 };
+                let scale = fold * sample_interval / window_power;
+                let (z_incident, z_reflected) = if records.len() == 1 {
+                    (spectra[0].0[k].hypot(spectra[0].1[k]), 0.0)
+                } else {
+                    let (z_incident, z_reflected) = least_squares(&phases[bin], spectra.iter().map(|(re, im)| (re[k], im[k])));
+                    (z_incident.0.hypot(z_incident.1), z_reflected.0.hypot(z_reflected.1))
+                };
+                incident[bin] += scale * z_incident * z_incident;
+                reflected[bin] += scale * z_reflected * z_reflected;
+            }
+        }
+        incident.iter_mut().chain(reflected.iter_mut()).for_each(|value| *value /= starts.len() as f64);
+
+        // Group velocity dω/dk from the wave numbers just around each frequency
+        let group_velocity = frequencies
+            .iter()
+            .map(|&frequency| {
+                let delta = 1e-3 * frequency;
+                2.0 * PI * 2.0 * delta / (wave_number(frequency + delta) - wave_number(frequency - delta))
+            })
+            .collect();
+
+        Ok(Self {
+            frequencies,
+            incident,
+            reflected,
+            group_velocity,
+            resolved: conditioning.iter().map(|&c| records.len() == 1 || c >= MIN_CONDITIONING).collect(),
+            gauges: records.len(),
+            segments: starts.len(),
+        })
+    }
+
+    /// Get frequencies of the bins [Hz]
+    pub fn frequencies(&self) -> &[f64] {
+        &self.frequencies
+    }
+
+    /// Get incident variance density [m²/Hz]
+    pub fn incident(&self) -> &[f64] {
+        &self.incident
+    }
+
+    /// Get reflected variance density [m²/Hz]
+    pub fn reflected(&self) -> &[f64] {
+        &self.reflected
+    }
+
+    /// Whether the incident and reflected waves are separated at each frequency
+    pub fn resolved(&self) -> &[bool] {
+        &self.resolved
+    }
+
+    /// Number of gauges of the array
+    pub fn gauges(&self) -> usize {
+        self.gauges
+    }
+
+    /// Number of averaged segments
+    pub fn segments(&self) -> usize {
+        self.segments
+    }
+
+    /// Frequency resolution [Hz]
+    fn resolution(&self) -> f64 {
+        self.frequencies[0]
+    }
+
+    /// Incident wave height Hm0 = 4√m0 over the resolved frequencies [m]
+    pub fn incident_height(&self) -> f64 {
+        4.0 * self.variance(&self.incident).sqrt()
+    }
+
+    /// Reflection coefficient Kr = √(m0_R / m0_I) over the resolved frequencies
+    pub fn reflection_coefficient(&self) -> f64 {
+        let incident = self.variance(&self.incident);
+        if incident > 0.0 { (self.variance(&self.reflected) / incident).sqrt() } else { 0.0 }
+    }
+
+    /// Mean energy flux of the incident waves ρg Σ S_I c_g Δf over the resolved frequencies [W/m]
+    pub fn incident_flux(&self) -> f64 {
+        self.flux(&self.incident)
+    }
+
+    /// Mean energy flux of the reflected waves [W/m]
+    pub fn reflected_flux(&self) -> f64 {
+        self.flux(&self.reflected)
+    }
+
+    /// Share of the incident variance at frequencies the array cannot separate
+    pub fn unresolved_fraction(&self) -> f64 {
+        let total: f64 = self.incident.iter().sum();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        self.incident.iter().zip(&self.resolved).filter(|(_, resolved)| !**resolved).map(|(value, _)| value).sum::<f64>() / total
+    }
+
+    fn variance(&self, density: &[f64]) -> f64 {
+        density.iter().zip(&self.resolved).filter(|(_, resolved)| **resolved).map(|(value, _)| value).sum::<f64>() * self.resolution()
+    }
+
+    fn flux(&self, density: &[f64]) -> f64 {
+        let sum: f64 = density
+            .iter()
+            .zip(&self.group_velocity)
+            .zip(&self.resolved)
+            .filter(|(_, resolved)| **resolved)
+            .map(|((value, celerity), _)| value * celerity)
+            .sum();
+        WATER_DENSITY * 9.81 * sum * self.resolution()
+    }
+}
+
+/// Least-squares incident and reflected coefficients from the Fourier coefficients of the gauges
+/// Solves the normal equations [[P, S*], [S, P]] [Z_I, Z_R] = [Σ a_p* B_p, Σ a_p B_p] with S = Σ a_p²
+fn least_squares(phases: &[(f64, f64)], coefficients: impl Iterator<Item = (f64, f64)>) -> ((f64, f64), (f64, f64)) {
+    let count = phases.len() as f64;
+    let mut s = (0.0, 0.0);
+    let mut r_incident = (0.0, 0.0);
+    let mut r_reflected = (0.0, 0.0);
+    for (&(a_re, a_im), (b_re, b_im)) in phases.iter().zip(coefficients) {
+        s.0 += a_re * a_re - a_im * a_im;
+        s.1 += 2.0 * a_re * a_im;
+        r_incident.0 += a_re * b_re + a_im * b_im;
+        r_incident.1 += a_re * b_im - a_im * b_re;
+        r_reflected.0 += a_re * b_re - a_im * b_im;
+        r_reflected.1 += a_re * b_im + a_im * b_re;
+    }
+    let determinant = count * count - (s.0 * s.0 + s.1 * s.1);
+    if determinant.abs() < f64::EPSILON * count * count {
+        return ((0.0, 0.0), (0.0, 0.0));
+    }
+    // Z_I = (P r_I − S* r_R) / det, Z_R = (P r_R − S r_I) / det
+    let z_incident = (
+        (count * r_incident.0 - (s.0 * r_reflected.0 + s.1 * r_reflected.1)) / determinant,
+        (count * r_incident.1 - (s.0 * r_reflected.1 - s.1 * r_reflected.0)) / determinant,
+    );
+    let z_reflected = (
+        (count * r_reflected.0 - (s.0 * r_incident.0 - s.1 * r_incident.1)) / determinant,
+        (count * r_reflected.1 - (s.0 * r_incident.1 + s.1 * r_incident.0)) / determinant,
+    );
+    (z_incident, z_reflected)
+}
+
+/// Split of the incident wave energy flux at a structure into reflected, transmitted and dissipated parts
+///
+/// The incident and reflected fluxes come from an array of gauges seaward of the structure, the
+/// transmitted flux from the incident waves of an array behind it. Whatever is neither reflected nor
+/// transmitted is taken as dissipated, so the dissipation also collects the errors of the separation.
+#[derive(Debug, Clone)]
+pub struct EnergyBudget {
+    pub incident: f64,    // Incident energy flux [W/m]
+    pub reflected: f64,   // Reflected energy flux [W/m]
+    pub transmitted: f64, // Transmitted energy flux, 0 without gauges behind the structure [W/m]
+    pub notes: Vec<String>, // Limitations of the estimate, for the reader of the budget
+}
+
+impl EnergyBudget {
+    /// Budget from the seaward array and, if there are gauges behind the structure, the landward array
+    pub fn new(seaward: &ReflectionAnalysis, landward: Option<&ReflectionAnalysis>) -> Result<Self, String> {
+        if seaward.gauges() < 2 {
+            return Err("Place at least two gauges seaward of the structure to separate the reflected waves".to_string());
+        }
+        let incident = seaward.incident_flux();
+        if incident <= 0.0 {
+            return Err("No incident waves reach the seaward gauges".to_string());
+        }
+
+        let mut notes = Vec::new();
+        if seaward.gauges() == 2 {
+            notes.push("Two-gauge separation is sensitive to noise and nonlinearity; a third seaward gauge makes the fit overdetermined.".to_string());
+        }
+        for (side, array) in [("seaward", Some(seaward)), ("landward", landward)] {
+            let Some(array) = array else { continue };
+            let unresolved = array.unresolved_fraction();
+            if unresolved > UNRESOLVED_WARNING {
+                notes.push(format!(
+                    "{:.0}% of the {} energy is at frequencies where the gauge spacing is close to a multiple of half a wavelength and is left out.",
+                    100.0 * unresolved,
+                    side
+                ));
+            }
+            if array.segments() < 4 {
+                notes.push(format!("Only {} averaged segments at the {} gauges: run longer for steadier spectra.", array.segments(), side));
+            }
+        }
+        let transmitted = match landward {
+            None => {
+                notes.push("No gauge behind the structure: the transmitted energy is counted as zero.".to_string());
+                0.0
+            }
+            Some(array) => {
+                if array.gauges() == 1 {
+                    notes.push("A single gauge behind the structure also measures waves reflected further landward, overestimating the transmission.".to_string());
+                }
+                array.incident_flux()
+            }
+        };
+
+        let mut budget = Self {
+            incident,
+            reflected: seaward.reflected_flux(),
+            transmitted,
+            notes,
+        };
+        if budget.dissipation() < -0.05 {
+            budget
+                .notes
+                .push("Reflected and transmitted energy exceed the incident energy: bound harmonics, standing waves or too short records bias the separation.".to_string());
+        }
+        Ok(budget)
+    }
+
+    /// Reflected share of the incident energy, Kr²
+    pub fn reflection(&self) -> f64 {
+        self.reflected / self.incident
+    }
+
+    /// Transmitted share of the incident energy, Kt²
+    pub fn transmission(&self) -> f64 {
+        self.transmitted / self.incident
+    }
+
+    /// Dissipated share of the incident energy, 1 − Kr² − Kt²
+    pub fn dissipation(&self) -> f64 {
+        1.0 - self.reflection() - self.transmission()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records of a regular incident and reflected wave in 1 m of shallow water
+    fn partial_standing_wave(positions: &[f64], height: f64, reflection: f64) -> (Vec<Vec<f64>>, impl Fn(f64) -> f64) {
+        let celerity = 9.81_f64.sqrt();
+        let wave_number = move |frequency: f64| 2.0 * PI * frequency / celerity;
+        let (dt, frequency) = (0.0625, 0.25);
+        let k = wave_number(frequency);
+        let records = positions
+            .iter()
+            .map(|&x| {
+                (0..4096)
+                    .map(|i| {
+                        let t = i as f64 * dt;
+                        let omega_t = 2.0 * PI * frequency * t;
+                        0.5 * height * ((omega_t - k * x).cos() + reflection * (omega_t + k * x + 0.7).cos())
+                    })
+                    .collect()
+            })
+            .collect();
+        (records, wave_number)
+    }
+
+    #[test]
+    fn test_separates_incident_and_reflected_waves() {
+        // Wavelength 12.5 m, spacings of L/10 and L/4
+        let positions = [10.0, 11.25, 13.125];
+        let (records, wave_number) = partial_standing_wave(&positions, 0.2, 0.4);
+        let records: Vec<&[f64]> = records.iter().map(Vec::as_slice).collect();
+        let analysis = ReflectionAnalysis::new(&records, &positions, 0.0625, 512, &wave_number).unwrap();
+        assert!((analysis.reflection_coefficient() - 0.4).abs() < 0.02, "Kr = {:.3}", analysis.reflection_coefficient());
+        assert!((analysis.incident_height() - 0.2 * 2.0_f64.sqrt()).abs() < 0.01, "Hm0 = {:.3}", analysis.incident_height());
+        assert!(analysis.unresolved_fraction() < 0.01);
+
+        // Shallow water: the group velocity is the celerity √(gd)
+        let expected = WATER_DENSITY * 9.81 * 0.5 * 0.1_f64.powi(2) * 9.81_f64.sqrt();
+        assert!((analysis.incident_flux() / expected - 1.0).abs() < 0.05);
+
+        // Gauges half a wavelength apart cannot tell the waves apart
+        let (records, wave_number) = partial_standing_wave(&[10.0, 16.25], 0.2, 0.4);
+        let records: Vec<&[f64]> = records.iter().map(Vec::as_slice).collect();
+        let analysis = ReflectionAnalysis::new(&records, &[10.0, 16.25], 0.0625, 512, &wave_number).unwrap();
+        assert!(!analysis.resolved()[7]);
+        assert!(analysis.unresolved_fraction() > 0.5);
+    }
+
+    #[test]
+    fn test_energy_budget() {
+        let seaward_positions = [10.0, 11.25, 13.125];
+        let (records, wave_number) = partial_standing_wave(&seaward_positions, 0.2, 0.5);
+        let records: Vec<&[f64]> = records.iter().map(Vec::as_slice).collect();
+        let seaward = ReflectionAnalysis::new(&records, &seaward_positions, 0.0625, 512, &wave_number).unwrap();
+
+        let landward_positions = [40.0, 41.25];
+        let (records, wave_number) = partial_standing_wave(&landward_positions, 0.2 * 0.6, 0.0);
+        let records: Vec<&[f64]> = records.iter().map(Vec::as_slice).collect();
+        let landward = ReflectionAnalysis::new(&records, &landward_positions, 0.0625, 512, &wave_number).unwrap();
+
+        let budget = EnergyBudget::new(&seaward, Some(&landward)).unwrap();
+        assert!((budget.reflection() - 0.25).abs() < 0.02);
+        assert!((budget.transmission() - 0.36).abs() < 0.02);
+        assert!((budget.dissipation() - 0.39).abs() < 0.03);
+
+        let without_landward = EnergyBudget::new(&seaward, None).unwrap();
+        assert_eq!(without_landward.transmitted, 0.0);
+        assert!(without_landward.notes.iter().any(|note| note.contains("No gauge behind")));
+
+        let single = ReflectionAnalysis::new(&records[..1], &landward_positions[..1], 0.0625, 512, &wave_number).unwrap();
+        assert!(EnergyBudget::new(&single, None).is_err());
+    }
+}
@@ -0,0 +1,326 @@
+use crate::analysis::snapshots::Snapshot;
+use memmap2::Mmap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// File signature and format version
+const MAGIC: &[u8; 8] = b"CEPSNAP1";
+
+/// Header size: signature and number of points per frame [bytes]
+const HEADER_SIZE: usize = 16;
+
+/// Temporary files created by this process, for unique names
+static TEMPORARY_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// Surface snapshots streamed to a memory-mapped binary file
+///
+/// Frames of fixed size (time then elevations, little-endian f64) follow a 16-byte header, so frame i
+/// starts at 16 + i·8·(points + 1). Only the index of frame times is kept in memory; frames are read
+/// through the memory map, letting the operating system page in the parts being scrubbed. Writes are
+/// buffered and become readable after `sync`.
+pub struct SnapshotFile {
+    /// Location of the file
+    path: PathBuf,
+    /// Elevations per frame
+    points: usize,
+    /// Buffered writer appending frames
+    writer: BufWriter<File>,
+    /// Time of every written frame [s]
+    times: Vec<f64>,
+    /// Read-only map of the synced part of the file
+    map: Option<Mmap>,
+    /// Number of frames readable through the map
+    mapped_frames: usize,
+    /// Remove the file when dropped
+    temporary: bool,
+}
+
+impl std::fmt::Debug for SnapshotFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SnapshotFile")
+            .field("path", &self.path)
+            .field("points", &self.points)
+            .field("frames", &self.times.len())
+            .finish()
+    }
+}
+
+impl Drop for SnapshotFile {
+    fn drop(&mut self) {
+        if self.temporary {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+impl SnapshotFile {
+    /// Create an empty file for frames of a number of points, replacing any existing file
+    pub fn create(path: impl Into<PathBuf>, points: usize) -> Result<Self, String> {
+        let path = path.into();
+        if points == 0 {
+            return Err("Frames must have at least one point".to_string());
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Cannot create snapshot directory: {}", e))?;
+        }
+        // Readable as well, for the memory map
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)
+            .map_err(|e| format!("Cannot create snapshot file: {}", e))?;
+        let mut writer = BufWriter::new(file);
+        writer.write_all(MAGIC).map_err(|e| format!("Cannot write snapshot file: {}", e))?;
+        writer.write_all(&(points as u64).to_le_bytes()).map_err(|e| format!("Cannot write snapshot file: {}", e))?;
+        Ok(Self {
+            path,
+            points,
+            writer,
+            times: Vec::new(),
+            map: None,
+            mapped_frames: 0,
+            temporary: false,
+        })
+    }
+
+    /// Create a file in the system temporary directory, removed when dropped
+    pub fn temporary(points: usize) -> Result<Self, String> {
+        let name = format!("coastal_snapshots_{}_{}.bin", std::process::id(), TEMPORARY_FILES.fetch_add(1, Ordering::Relaxed));
+        let mut file = Self::create(std::env::temp_dir().join(name), points)?;
+        file.temporary = true;
+        Ok(file)
+    }
+
+    /// Open an existing file for reading and appending, rebuilding the time index; a truncated last frame is discarded
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, String> {
+        let path = path.into();
+        let mut file = OpenOptions::new().read(true).append(true).open(&path).map_err(|e| format!("Cannot open snapshot file: {}", e))?;
+        let mut header = [0u8; HEADER_SIZE];
+        file.read_exact(&mut header).map_err(|_| "Snapshot file has no header".to_string())?;
+        if &header[..8] != MAGIC {
+            return Err("Not a snapshot file".to_string());
+        }
+        let points = u64::from_le_bytes(header[8..].try_into().unwrap());
+        if points == 0 {
+            return Err("Snapshot file has empty frames".to_string());
+        }
+        // The header is not trusted: a corrupt count must not overflow the frame size
+        let frame_size = usize::try_from(points).ok().and_then(|points| points.checked_add(1)?.checked_mul(8));
+        let Some(frame_size) = frame_size else {
+            return Err("Snapshot file has an invalid number of points".to_string());
+        };
+        let (points, frame_size) = (points as usize, frame_size as u64);
+
+        let length = file.metadata().map_err(|e| format!("Cannot open snapshot file: {}", e))?.len();
+        let frames = (length - HEADER_SIZE as u64) / frame_size;
+        file.set_len(HEADER_SIZE as u64 + frames * frame_size).map_err(|e| format!("Cannot repair snapshot file: {}", e))?;
+
+        let mut snapshot_file = Self {
+            path,
+            points,
+            writer: BufWriter::new(file),
+            times: Vec::new(),
+            map: None,
+            mapped_frames: 0,
+            temporary: false,
+        };
+        snapshot_file.remap()?;
+        let frames = snapshot_file.mapped_frames;
+        snapshot_file.times = (0..frames).map(|frame| snapshot_file.read_value(frame, 0)).collect();
+        Ok(snapshot_file)
+    }
+
+    /// Get file location
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get elevations per frame
+    pub fn points(&self) -> usize {
+        self.points
+    }
+
+    /// Number of written frames
+    pub fn len(&self) -> usize {
+        self.times.len()
+    }
+
+    /// Check if no frame was written yet
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty()
+    }
+
+    /// Get time of every written frame [s]
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    /// Number of frames readable until the next sync
+    pub fn mapped_len(&self) -> usize {
+        self.mapped_frames
+    }
+
+    /// Size of the written frames on disk [bytes]
+    pub fn size_bytes(&self) -> u64 {
+        (HEADER_SIZE + self.times.len() * self.frame_size()) as u64
+    }
+
+    /// Size on disk of a number of frames of a number of points [bytes]
+    pub fn projected_size(points: usize, frames: usize) -> u64 {
+        (HEADER_SIZE + frames * 8 * (points + 1)) as u64
+    }
+
+    /// Frame size [bytes]
+    fn frame_size(&self) -> usize {
+        8 * (self.points + 1)
+    }
+
+    /// Append a frame; times must increase
+    pub fn record(&mut self, time: f64, surface_elevation: &[f64]) -> Result<(), String> {
+        if surface_elevation.len() != self.points {
+            return Err(format!("Frame has {} points instead of {}", surface_elevation.len(), self.points));
+        }
+        let mut frame = Vec::with_capacity(self.frame_size());
+        frame.extend_from_slice(&time.to_le_bytes());
+        for value in surface_elevation {
+            frame.extend_from_slice(&value.to_le_bytes());
+        }
+        self.writer.write_all(&frame).map_err(|e| format!("Cannot write snapshot file: {}", e))?;
+        self.times.push(time);
+        Ok(())
+    }
+
+    /// Flush written frames and map them for reading
+    pub fn sync(&mut self) -> Result<(), String> {
+        if self.mapped_frames == self.times.len() {
+            return Ok(());
+        }
+        self.writer.flush().map_err(|e| format!("Cannot write snapshot file: {}", e))?;
+        self.remap()
+    }
+
+    /// Map the whole file
+    fn remap(&mut self) -> Result<(), String> {
+        // SAFETY: the file is only appended to by this struct, so mapped bytes never change while the map lives
+        let map = unsafe { Mmap::map(self.writer.get_ref()) }.map_err(|e| format!("Cannot map snapshot file: {}", e))?;
+        self.mapped_frames = map.len().saturating_sub(HEADER_SIZE) / self.frame_size();
+        self.map = Some(map);
+        Ok(())
+    }
+
+    /// Value at a position in a mapped frame, 0 being the time
+    fn read_value(&self, frame: usize, position: usize) -> f64 {
+        let map = self.map.as_ref().expect("frame is mapped");
+        let offset = HEADER_SIZE + frame * self.frame_size() + 8 * position;
+        f64::from_le_bytes(map[offset..offset + 8].try_into().unwrap())
+    }
+
+    /// Frame at an index, None if it was not synced yet
+    pub fn frame(&self, index: usize) -> Option<Snapshot> {
+        if index >= self.mapped_frames {
+            return None;
+        }
+        Some(Snapshot {
+            time: self.read_value(index, 0),
+            surface_elevation: (1..=self.points).map(|position| self.read_value(index, position)).collect(),
+        })
+    }
+
+    /// Synced frame closest in time
+    pub fn nearest(&self, time: f64) -> Option<Snapshot> {
+        let times = &self.times[..self.mapped_frames];
+        let index = times.partition_point(|t| *t < time);
+        let nearest = [index.checked_sub(1), (index < times.len()).then_some(index)]
+            .into_iter()
+            .flatten()
+            .min_by(|&a, &b| (times[a] - time).abs().total_cmp(&(times[b] - time).abs()))?;
+        self.frame(nearest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temporary_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("coastal_snapshot_file_{}_{}.bin", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_frames_readable_after_sync() {
+        let path = temporary_path("sync");
+        let mut file = SnapshotFile::create(&path, 3).unwrap();
+        for i in 0..10 {
+            let time = i as f64 * 0.5;
+            file.record(time, &[time, -time, 1.0]).unwrap();
+        }
+        assert_eq!(file.len(), 10);
+        assert!(file.nearest(2.0).is_none());
+        assert!(file.record(5.0, &[0.0]).is_err());
+
+        file.sync().unwrap();
+        assert_eq!(file.mapped_len(), 10);
+        assert_eq!(file.size_bytes(), std::fs::metadata(&path).unwrap().len());
+        let frame = file.nearest(2.1).unwrap();
+        assert_eq!(frame.time, 2.0);
+        assert_eq!(frame.surface_elevation, vec![2.0, -2.0, 1.0]);
+        assert_eq!(file.nearest(100.0).unwrap().time, 4.5);
+        assert!(file.frame(10).is_none());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_reopen_file() {
+        let path = temporary_path("reopen");
+        let mut file = SnapshotFile::create(&path, 2).unwrap();
+        for i in 0..4 {
+            file.record(i as f64, &[i as f64, 0.5]).unwrap();
+        }
+        file.sync().unwrap();
+        drop(file);
+
+        let mut reopened = SnapshotFile::open(&path).unwrap();
+        assert_eq!(reopened.points(), 2);
+        assert_eq!(reopened.times(), &[0.0, 1.0, 2.0, 3.0]);
+        reopened.record(4.0, &[4.0, 0.5]).unwrap();
+        reopened.sync().unwrap();
+        assert_eq!(reopened.frame(4).unwrap().surface_elevation, vec![4.0, 0.5]);
+        drop(reopened);
+
+        // A frame cut short by a crash is dropped
+        let length = std::fs::metadata(&path).unwrap().len();
+        std::fs::OpenOptions::new().write(true).open(&path).unwrap().set_len(length - 5).unwrap();
+        let repaired = SnapshotFile::open(&path).unwrap();
+        assert_eq!(repaired.len(), 4);
+        assert_eq!(repaired.size_bytes(), length - 24);
+        drop(repaired);
+
+        std::fs::write(&path, b"not a snapshot file").unwrap();
+        assert!(SnapshotFile::open(&path).is_err());
+
+        // A corrupt header whose frame size overflows
+        let mut header = MAGIC.to_vec();
+        header.extend_from_slice(&u64::MAX.to_le_bytes());
+        std::fs::write(&path, header).unwrap();
+        assert!(SnapshotFile::open(&path).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_temporary_file_removed_on_drop() {
+        let mut first = SnapshotFile::temporary(4).unwrap();
+        let second = SnapshotFile::temporary(4).unwrap();
+        assert_ne!(first.path(), second.path());
+        first.record(0.0, &[0.0; 4]).unwrap();
+        first.sync().unwrap();
+
+        let path = first.path().to_path_buf();
+        assert!(path.exists());
+        drop(first);
+        assert!(!path.exists());
+    }
+}
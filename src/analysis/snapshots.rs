@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Surface elevation along the channel at one instant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Simulation time [s]
+    pub time: f64,
+    /// Surface elevation at the display points [m]
+    pub surface_elevation: Vec<f64>,
+}
+
+/// Tiered record of surface snapshots for playback of long simulations
+///
+/// The most recent window is kept at full resolution. Older snapshots move to a decimated history
+/// whose interval doubles (dropping every other snapshot) whenever it exceeds its capacity,
+/// so memory stays bounded however long the run while the whole record remains scrubbable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotStore {
+    /// Duration kept at full resolution [s]
+    recent_window: f64,
+    /// Maximum number of history snapshots before the history is decimated
+    history_capacity: usize,
+    /// Current interval between history snapshots [s]
+    history_interval: f64,
+    /// Initial interval between history snapshots [s]
+    base_interval: f64,
+    /// Decimated snapshots older than the recent window, in time order
+    history: Vec<Snapshot>,
+    /// Full-resolution snapshots of the recent window, in time order
+    recent: VecDeque<Snapshot>,
+}
+
+impl Default for SnapshotStore {
+    fn default() -> Self {
+        Self::new(60.0, 0.5, 2000)
+    }
+}
+
+impl SnapshotStore {
+    /// Create an empty store with a full-resolution window, an initial history interval and a history capacity
+    pub fn new(recent_window: f64, history_interval: f64, history_capacity: usize) -> Self {
+        Self {
+            recent_window,
+            history_capacity: history_capacity.max(2),
+            history_interval,
+            base_interval: history_interval,
+            history: Vec::new(),
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Append a snapshot; times must increase
+    pub fn record(&mut self, time: f64, surface_elevation: &[f64]) {
+        self.recent.push_back(Snapshot {
+            time,
+            surface_elevation: surface_elevation.to_vec(),
+        });
+
+        // Move snapshots leaving the recent window to the history, keeping one per interval
+        while self.recent.front().is_some_and(|snapshot| snapshot.time < time - self.recent_window) {
+            let Some(snapshot) = self.recent.pop_front() else {
+                break;
+            };
+            let due = self.history.last().is_none_or(|last| snapshot.time - last.time >= self.history_interval - 1e-9);
+            if due {
+                self.history.push(snapshot);
+            }
+        }
+
+        // Coarsen the history instead of growing it
+        if self.history.len() > self.history_capacity {
+            let mut index = 0;
+            self.history.retain(|_| {
+                index += 1;
+                index % 2 == 1
+            });
+            self.history_interval *= 2.0;
+        }
+    }
+
+    /// Number of stored snapshots
+    pub fn len(&self) -> usize {
+        self.history.len() + self.recent.len()
+    }
+
+    /// Check if nothing was recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Time of the first stored snapshot [s]
+    pub fn start_time(&self) -> Option<f64> {
+        self.history.first().or(self.recent.front()).map(|snapshot| snapshot.time)
+    }
+
+    /// Time of the last stored snapshot [s]
+    pub fn end_time(&self) -> Option<f64> {
+        self.recent.back().or(self.history.last()).map(|snapshot| snapshot.time)
+    }
+
+    /// Start of the full-resolution window [s]
+    pub fn recent_start(&self) -> Option<f64> {
+        self.recent.front().map(|snapshot| snapshot.time)
+    }
+
+    /// Current interval between history snapshots [s]
+    pub fn history_interval(&self) -> f64 {
+        self.history_interval
+    }
+
+    /// All snapshots in time order
+    pub fn snapshots(&self) -> impl Iterator<Item = &Snapshot> {
+        self.history.iter().chain(self.recent.iter())
+    }
+
+    /// Snapshot closest in time
+    pub fn nearest(&self, time: f64) -> Option<&Snapshot> {
+        let nearest_in = |snapshots: &[Snapshot]| -> Option<usize> {
+            let index = snapshots.partition_point(|snapshot| snapshot.time < time);
+            [index.checked_sub(1), (index < snapshots.len()).then_some(index)]
+                .into_iter()
+                .flatten()
+                .min_by(|&a, &b| (snapshots[a].time - time).abs().total_cmp(&(snapshots[b].time - time).abs()))
+        };
+        let (front, back) = self.recent.as_slices();
+        [&self.history[..], front, back]
+            .into_iter()
+            .filter_map(|snapshots| nearest_in(snapshots).map(|index| &snapshots[index]))
+            .min_by(|a, b| (a.time - time).abs().total_cmp(&(b.time - time).abs()))
+    }
+
+    /// Index of the snapshot closest in time, counted from the first stored snapshot
+    pub fn frame_index(&self, time: f64) -> Option<usize> {
+        let nearest = self.nearest(time)?.time;
+        Some(self.snapshots().take_while(|snapshot| snapshot.time < nearest).count())
+    }
+
+    /// First snapshot after, or last snapshot before, a time, for frame stepping
+    pub fn step(&self, time: f64, forward: bool) -> Option<&Snapshot> {
+        if forward {
+            self.snapshots().find(|snapshot| snapshot.time > time + 1e-9)
+        } else {
+            self.snapshots().filter(|snapshot| snapshot.time < time - 1e-9).last()
+        }
+    }
+
+    /// Spacing of the stored snapshots around a time [s]
+    pub fn resolution_at(&self, time: f64) -> f64 {
+        let in_recent = self.recent_start().is_some_and(|start| time >= start);
+        let snapshots: Vec<&Snapshot> = if in_recent { self.recent.iter().collect() } else { self.history.iter().collect() };
+        if snapshots.len() < 2 {
+            return if in_recent { 0.0 } else { self.history_interval };
+        }
+        (snapshots[snapshots.len() - 1].time - snapshots[0].time) / (snapshots.len() - 1) as f64
+    }
+
+    /// Approximate number of snapshots stored after a run of a duration with snapshots at an interval
+    pub fn projected_len(&self, duration: f64, interval: f64) -> usize {
+        let recent = (duration.min(self.recent_window) / interval).floor() as usize + 1;
+        let span = (duration - self.recent_window).max(0.0);
+        let mut history_interval = self.base_interval.max(interval);
+        let mut history = (span / history_interval).floor() as usize;
+        while history > self.history_capacity {
+            history_interval *= 2.0;
+            history = (span / history_interval).floor() as usize;
+        }
+        recent + history
+    }
+
+    /// Discard every snapshot and restore the initial history interval
+    pub fn clear(&mut self) {
+        self.history.clear();
+        self.recent.clear();
+        self.history_interval = self.base_interval;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record_run(store: &mut SnapshotStore, duration: f64, dt: f64) {
+        let steps = (duration / dt).round() as usize;
+        for i in 1..=steps {
+            let time = i as f64 * dt;
+            store.record(time, &[time, -time]);
+        }
+    }
+
+    #[test]
+    fn test_recent_window_at_full_resolution() {
+        let mut store = SnapshotStore::new(10.0, 1.0, 100);
+        record_run(&mut store, 30.0, 0.1);
+
+        assert!((store.end_time().unwrap() - 30.0).abs() < 1e-9);
+        assert!((store.recent_start().unwrap() - 20.0).abs() < 0.1 + 1e-9);
+        assert!((store.resolution_at(25.0) - 0.1).abs() < 1e-6);
+        assert!((store.resolution_at(5.0) - 1.0).abs() < 0.05);
+        // 100 recent snapshots and one per second before
+        assert!(store.len() < 101 + 21);
+        assert!(store.snapshots().zip(store.snapshots().skip(1)).all(|(a, b)| a.time < b.time));
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut store = SnapshotStore::new(5.0, 0.5, 50);
+        record_run(&mut store, 1000.0, 0.05);
+
+        assert!(store.len() <= 101 + 51);
+        assert!(store.history_interval() >= 16.0);
+        // The whole run stays reachable
+        assert!(store.start_time().unwrap() < store.history_interval());
+        let snapshot = store.nearest(500.0).unwrap();
+        assert!((snapshot.time - 500.0).abs() <= store.history_interval());
+        assert_eq!(snapshot.surface_elevation, vec![snapshot.time, -snapshot.time]);
+    }
+
+    #[test]
+    fn test_projected_len() {
+        for duration in [5.0, 30.0, 400.0] {
+            let mut store = SnapshotStore::new(10.0, 0.5, 50);
+            record_run(&mut store, duration, 0.1);
+            let projected = store.projected_len(duration, 0.1);
+            assert!(projected.abs_diff(store.len()) <= 2 + store.len() / 10, "{}: {} vs {}", duration, projected, store.len());
+        }
+    }
+
+    #[test]
+    fn test_nearest_and_step() {
+        let mut store = SnapshotStore::new(2.0, 1.0, 100);
+        assert!(store.nearest(1.0).is_none());
+        record_run(&mut store, 10.0, 0.5);
+
+        assert!((store.nearest(9.2).unwrap().time - 9.0).abs() < 1e-9);
+        assert!((store.nearest(-3.0).unwrap().time - store.start_time().unwrap()).abs() < 1e-9);
+        assert!((store.step(9.0, true).unwrap().time - 9.5).abs() < 1e-9);
+        assert!((store.step(9.0, false).unwrap().time - 8.5).abs() < 1e-9);
+        assert!(store.step(10.0, true).is_none());
+        assert_eq!(store.frame_index(10.0), Some(store.len() - 1));
+        assert_eq!(store.frame_index(-3.0), Some(0));
+
+        store.clear();
+        assert!(store.is_empty());
+    }
+}
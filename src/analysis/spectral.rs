@@ -0,0 +1,294 @@
+/// In-place radix-2 fast Fourier transform of a complex signal
+/// The length must be a power of two; the inverse transform includes the 1/n normalization
+pub fn fft(re: &mut [f64], im: &mut [f64], inverse: bool) {
+    let n = re.len();
+    assert_eq!(n, im.len(), "Real and imaginary parts must have the same length");
+    assert!(n.is_power_of_two(), "FFT length must be a power of two");
+
+    // Bit-reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    // Butterflies of doubling length
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut length = 2;
+    while length <= n {
+        let angle = sign * 2.0 * std::f64::consts::PI / length as f64;
+        for start in (0..n).step_by(length) {
+            for k in 0..length / 2 {
+                let (w_im, w_re) = (angle * k as f64).sin_cos();
+                let (a, b) = (start + k, start + k + length / 2);
+                let t_re = re[b] * w_re - im[b] * w_im;
+                let t_im = re[b] * w_im + im[b] * w_re;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        length *= 2;
+    }
+
+    if inverse {
+        for (re, im) in re.iter_mut().zip(im.iter_mut()) {
+            *re /= n as f64;
+            *im /= n as f64;
+        }
+    }
+}
+
+/// Mean of a signal
+pub fn mean(signal: &[f64]) -> f64 {
+    if signal.is_empty() {
+        return 0.0;
+    }
+    signal.iter().sum::<f64>() / signal.len() as f64
+}
+
+/// Spectrum of the mean-removed signal, zero-padded to the next power of two
+/// Returns the real parts, imaginary parts and the frequency resolution [Hz]
+fn padded_spectrum(signal: &[f64], sample_interval: f64) -> (Vec<f64>, Vec<f64>, f64) {
+    let n = signal.len().next_power_of_two();
+    let signal_mean = mean(signal);
+    let mut re: Vec<f64> = signal.iter().map(|value| value - signal_mean).collect();
+    re.resize(n, 0.0);
+    let mut im = vec![0.0; n];
+    fft(&mut re, &mut im, false);
+    (re, im, 1.0 / (n as f64 * sample_interval))
+}
+
+/// Absolute frequency of a bin of an n-point spectrum [Hz]
+fn bin_frequency(bin: usize, n: usize, resolution: f64) -> f64 {
+    bin.min(n - bin) as f64 * resolution
+}
+
+/// Keep only the frequencies low < f ≤ high of a signal sampled at a constant interval
+/// The mean is kept when low is zero; use f64::INFINITY as high for a high-pass filter
+pub fn band_pass(signal: &[f64], sample_interval: f64, low: f64, high: f64) -> Vec<f64> {
+    if signal.is_empty() {
+        return Vec::new();
+    }
+    let (mut re, mut im, resolution) = padded_spectrum(signal, sample_interval);
+    let n = re.len();
+    for bin in 0..n {
+        let frequency = bin_frequency(bin, n, resolution);
+        if frequency <= low || frequency > high {
+            re[bin] = 0.0;
+            im[bin] = 0.0;
+        }
+    }
+    fft(&mut re, &mut im, true);
+
+    let offset = if low <= 0.0 { mean(signal) } else { 0.0 };
+    re.truncate(signal.len());
+    re.iter().map(|value| value + offset).collect()
+}
+
+/// Envelope of a narrow-banded signal, the modulus of its analytic signal (Hilbert transform)
+pub fn envelope(signal: &[f64]) -> Vec<f64> {
+    if signal.is_empty() {
+        return Vec::new();
+    }
+    // The sample interval does not matter for the Hilbert transform
+    let (mut re, mut im, _) = padded_spectrum(signal, 1.0);
+    let n = re.len();
+    for bin in 1..n {
+        let weight = match bin.cmp(&(n / 2)) {
+            std::cmp::Ordering::Less => 2.0,
+            std::cmp::Ordering::Equal => 1.0,
+            std::cmp::Ordering::Greater => 0.0,
+        };
+        re[bin] *= weight;
+        im[bin] *= weight;
+    }
+    fft(&mut re, &mut im, true);
+    re.iter().zip(&im).take(signal.len()).map(|(re, im)| re.hypot(*im)).collect()
+}
+
+/// Frequency of the largest periodogram peak, excluding the mean [Hz]
+pub fn peak_frequency(signal: &[f64], sample_interval: f64) -> Option<f64> {
+    if signal.len() < 4 {
+        return None;
+    }
+    let (re, im, resolution) = padded_spectrum(signal, sample_interval);
+    let n = re.len();
+    (1..=n / 2)
+        .map(|bin| (bin, re[bin] * re[bin] + im[bin] * im[bin]))
+        .filter(|&(_, power)| power > 0.0)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(bin, _)| bin as f64 * resolution)
+}
+
+/// Welch estimate of the one-sided variance density spectrum, averaging Hann-windowed segments with 50% overlap
+/// Returns the frequencies [Hz], excluding the mean, and the variance density [m²/Hz]; empty if the record is shorter than a segment
+pub fn variance_density(signal: &[f64], sample_interval: f64, segment_length: usize) -> (Vec<f64>, Vec<f64>) {
+    if segment_length < 2 || !segment_length.is_power_of_two() || signal.len() < segment_length {
+        return (Vec::new(), Vec::new());
+    }
+    let window: Vec<f64> = (0..segment_length)
+        .map(|i| 0.5 - 0.5 * (2.0 * std::f64::consts::PI * i as f64 / segment_length as f64).cos())
+        .collect();
+    let window_power: f64 = window.iter().map(|w| w * w).sum();
+
+    let bins = segment_length / 2;
+    let mut density = vec![0.0; bins];
+    let starts: Vec<usize> = (0..=signal.len() - segment_length).step_by(segment_length / 2).collect();
+    for &start in &starts {
+        let segment = &signal[start..start + segment_length];
+        let segment_mean = mean(segment);
+        let mut re: Vec<f64> = segment.iter().zip(&window).map(|(value, w)| (value - segment_mean) * w).collect();
+        let mut im = vec![0.0; segment_length];
+        fft(&mut re, &mut im, false);
+        for (bin, value) in density.iter_mut().enumerate() {
+            let k = bin + 1;
+            // One-sided: negative frequencies fold onto positive ones, except at the Nyquist frequency
+            let fold = if k == segment_length / 2 { 1.0 } else { 2.0 };
+            *value += fold * (re[k] * re[k] + im[k] * im[k]) * sample_interval / window_power;
+        }
+    }
+    density.iter_mut().for_each(|value| *value /= starts.len() as f64);
+
+    let resolution = 1.0 / (segment_length as f64 * sample_interval);
+    ((1..=bins).map(|k| k as f64 * resolution).collect(), density)
+}
+
+/// Significant wave height from the zeroth spectral moment, Hm0 = 4√m0 [m]
+pub fn significant_wave_height(signal: &[f64]) -> f64 {
+    let signal_mean = mean(signal);
+    let variance = mean(&signal.iter().map(|value| (value - signal_mean).powi(2)).collect::<Vec<_>>());
+    4.0 * variance.sqrt()
+}
+
+/// Pearson correlation of two signals with b shifted by a lag in samples: corr(a(t), b(t + lag))
+pub fn correlation(a: &[f64], b: &[f64], lag: isize) -> f64 {
+    let pairs: Vec<(f64, f64)> = (0..a.len())
+        .filter_map(|i| {
+            let j = i as isize + lag;
+            (j >= 0 && (j as usize) < b.len()).then(|| (a[i], b[j as usize]))
+        })
+        .collect();
+    if pairs.len() < 2 {
+        return 0.0;
+    }
+
+    let count = pairs.len() as f64;
+    let mean_a = pairs.iter().map(|pair| pair.0).sum::<f64>() / count;
+    let mean_b = pairs.iter().map(|pair| pair.1).sum::<f64>() / count;
+    let (mut covariance, mut variance_a, mut variance_b) = (0.0, 0.0, 0.0);
+    for (x, y) in &pairs {
+        covariance += (x - mean_a) * (y - mean_b);
+        variance_a += (x - mean_a).powi(2);
+        variance_b += (y - mean_b).powi(2);
+    }
+    if variance_a <= 0.0 || variance_b <= 0.0 {
+        return 0.0;
+    }
+    covariance / (variance_a * variance_b).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn sine(frequency: f64, amplitude: f64, samples: usize, dt: f64) -> Vec<f64> {
+        (0..samples).map(|i| amplitude * (2.0 * PI * frequency * i as f64 * dt).sin()).collect()
+    }
+
+    #[test]
+    fn test_fft_round_trip() {
+        let original = [1.0, -2.0, 3.5, 0.0, 0.25, 4.0, -1.0, 2.0];
+        let mut re = original.to_vec();
+        let mut im = vec![0.0; 8];
+        fft(&mut re, &mut im, false);
+        assert!((re[0] - original.iter().sum::<f64>()).abs() < 1e-12);
+
+        fft(&mut re, &mut im, true);
+        for (value, expected) in re.iter().zip(&original) {
+            assert!((value - expected).abs() < 1e-12);
+        }
+        assert!(im.iter().all(|value| value.abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_band_pass_separates_components() {
+        let dt = 0.1;
+        let slow = sine(0.02, 0.1, 2000, dt);
+        let fast = sine(0.5, 0.5, 2000, dt);
+        let signal: Vec<f64> = slow.iter().zip(&fast).map(|(a, b)| a + b).collect();
+
+        let low = band_pass(&signal, dt, 0.0, 0.1);
+        let high = band_pass(&signal, dt, 0.1, f64::INFINITY);
+        // Away from the record ends, where zero padding leaks
+        for i in 200..1800 {
+            assert!((low[i] - slow[i]).abs() < 0.01, "Low {} at {}", low[i], i);
+            assert!((high[i] - fast[i]).abs() < 0.01, "High {} at {}", high[i], i);
+        }
+    }
+
+    #[test]
+    fn test_envelope_of_modulated_carrier() {
+        let dt = 0.05;
+        let signal: Vec<f64> = (0..4096)
+            .map(|i| {
+                let t = i as f64 * dt;
+                (1.0 + 0.5 * (2.0 * PI * 0.02 * t).cos()) * (2.0 * PI * 1.0 * t).cos()
+            })
+            .collect();
+        let envelope = envelope(&signal);
+        for i in (400..3600).step_by(100) {
+            let expected = 1.0 + 0.5 * (2.0 * PI * 0.02 * i as f64 * dt).cos();
+            assert!((envelope[i] - expected).abs() < 0.05, "Envelope {} vs {}", envelope[i], expected);
+        }
+    }
+
+    #[test]
+    fn test_peak_frequency_and_wave_height() {
+        let dt = 0.1;
+        let signal = sine(0.25, 1.0, 4096, dt);
+        let peak = peak_frequency(&signal, dt).unwrap();
+        assert!((peak - 0.25).abs() < 0.01);
+
+        // Hm0 = 4σ = 4a/√2 for a sine wave
+        assert!((significant_wave_height(&signal) - 4.0 / 2.0_f64.sqrt()).abs() < 0.01);
+        assert!(peak_frequency(&[1.0, 2.0], dt).is_none());
+    }
+
+    #[test]
+    fn test_variance_density() {
+        let dt = 0.1;
+        let signal = sine(0.25, 1.0, 4096, dt);
+        let (frequencies, density) = variance_density(&signal, dt, 512);
+        assert_eq!(frequencies.len(), 256);
+
+        // m0 = Σ S Δf recovers the variance a²/2, concentrated around the wave frequency
+        let resolution = frequencies[0];
+        let m0: f64 = density.iter().sum::<f64>() * resolution;
+        assert!((m0 - 0.5).abs() < 0.01, "m0 = {:.4}", m0);
+        let peak = (0..density.len()).max_by(|&a, &b| density[a].total_cmp(&density[b])).unwrap();
+        assert!((frequencies[peak] - 0.25).abs() < resolution);
+        assert!(variance_density(&signal[..100], dt, 512).0.is_empty());
+    }
+
+    #[test]
+    fn test_correlation() {
+        let a = sine(0.1, 1.0, 200, 0.1);
+        let negative: Vec<f64> = a.iter().map(|value| -2.0 * value).collect();
+        assert!((correlation(&a, &a, 0) - 1.0).abs() < 1e-12);
+        assert!((correlation(&a, &negative, 0) + 1.0).abs() < 1e-12);
+
+        // Shifting by half a period (50 samples) flips the sign
+        assert!((correlation(&a, &a, 50) + 1.0).abs() < 1e-9);
+    }
+}
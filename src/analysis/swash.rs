@@ -0,0 +1,247 @@
+use crate::analysis::spectral::{band_pass, mean, significant_wave_height, variance_density};
+use serde::{Deserialize, Serialize};
+
+/// Time series of the wet/dry front on the beach, the instantaneous shoreline
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShorelineRecord {
+    /// Sample times [s]
+    times: Vec<f64>,
+    /// Shoreline position along the channel [m]
+    position: Vec<f64>,
+    /// Bed elevation at the shoreline above the still water level [m]
+    elevation: Vec<f64>,
+}
+
+impl ShorelineRecord {
+    /// Append a sample to the record
+    pub fn record(&mut self, time: f64, position: f64, elevation: f64) {
+        self.times.push(time);
+        self.position.push(position);
+        self.elevation.push(elevation);
+    }
+
+    /// Get sample times [s]
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    /// Get shoreline positions [m]
+    pub fn position(&self) -> &[f64] {
+        &self.position
+    }
+
+    /// Get shoreline elevations above the still water level [m]
+    pub fn elevation(&self) -> &[f64] {
+        &self.elevation
+    }
+
+    /// Number of recorded samples
+    pub fn len(&self) -> usize {
+        self.times.len()
+    }
+
+    /// Check if nothing was recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty()
+    }
+
+    /// Copy of the record with its last part only, the whole record if the duration is not positive [s]
+    pub fn window(&self, duration: f64) -> ShorelineRecord {
+        let start = match self.times.last() {
+            Some(last) if duration > 0.0 => self.times.partition_point(|time| *time < last - duration - 1e-9),
+            _ => 0,
+        };
+        ShorelineRecord {
+            times: self.times[start..].to_vec(),
+            position: self.position[start..].to_vec(),
+            elevation: self.elevation[start..].to_vec(),
+        }
+    }
+
+    /// Discard the record
+    pub fn clear(&mut self) {
+        self.times.clear();
+        self.position.clear();
+        self.elevation.clear();
+    }
+}
+
+/// Statistics of the shoreline speed in one direction [m/s]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SwashVelocity {
+    /// Mean speed over the samples moving in that direction [m/s]
+    pub mean: f64,
+    /// Largest speed [m/s]
+    pub max: f64,
+}
+
+impl SwashVelocity {
+    /// Statistics of the magnitudes of a set of speeds, zero when empty
+    fn from_speeds(speeds: impl Iterator<Item = f64>) -> Self {
+        let speeds: Vec<f64> = speeds.map(f64::abs).collect();
+        Self {
+            mean: mean(&speeds),
+            max: speeds.iter().copied().fold(0.0, f64::max),
+        }
+    }
+}
+
+/// Swash statistics of a shoreline record, as measured by run-up wires or video on a beach
+///
+/// The vertical excursion of the shoreline gives the setup (its mean), the significant swash height
+/// (4σ) split into incident and infragravity bands at the split frequency, and the run-up maxima
+/// between mean up-crossings from which R₂% follows. The shoreline speed along the slope separates
+/// uprush from backwash, and the foreshore slope is fitted to the swash excursion itself.
+#[derive(Debug, Clone)]
+pub struct SwashAnalysis {
+    /// Mean shoreline elevation above the still water level ⟨η⟩ [m]
+    pub setup: f64,
+    /// Significant swash height 4σ of the shoreline elevation [m]
+    pub significant_swash: f64,
+    /// Significant swash height above the split frequency S_inc [m]
+    pub incident_swash: f64,
+    /// Significant swash height below the split frequency S_IG [m]
+    pub infragravity_swash: f64,
+    /// Highest shoreline elevation between successive up-crossings of the setup [m]
+    pub run_up_maxima: Vec<f64>,
+    /// Run-up exceeded by 2% of the maxima R₂%, the setup when no swash cycle was complete [m]
+    pub run_up_2: f64,
+    /// Shoreline moving up the beach [m/s]
+    pub uprush: SwashVelocity,
+    /// Shoreline moving down the beach [m/s]
+    pub backwash: SwashVelocity,
+    /// Frequencies of the swash spectrum [Hz]
+    pub frequencies: Vec<f64>,
+    /// Variance density of the shoreline elevation [m²/Hz]
+    pub density: Vec<f64>,
+    /// Foreshore slope over the swash zone, rise of the shoreline elevation per metre of run along the channel
+    pub foreshore_slope: Option<f64>,
+}
+
+impl SwashAnalysis {
+    /// Analyse a shoreline record sampled at a constant interval
+    /// with the incident and infragravity bands split at a frequency [Hz], and a spectrum of segments of a power-of-two length
+    pub fn new(record: &ShorelineRecord, split_frequency: f64, segment_length: usize) -> Result<Self, String> {
+        let n = record.len();
+        if n < 4 {
+            return Err("Shoreline record is too short for swash analysis".to_string());
+        }
+        let sample_interval = (record.times[n - 1] - record.times[0]) / (n - 1) as f64;
+        if sample_interval <= 0.0 {
+            return Err("Shoreline record must span a positive duration".to_string());
+        }
+        let nyquist = 0.5 / sample_interval;
+        if split_frequency <= 0.0 || split_frequency >= nyquist {
+            return Err(format!("Split frequency must be between 0 and the Nyquist frequency ({:.3} Hz)", nyquist));
+        }
+
+        let elevation = &record.elevation;
+        let setup = mean(elevation);
+        let incident = band_pass(elevation, sample_interval, split_frequency, f64::INFINITY);
+        let infragravity = band_pass(elevation, sample_interval, 0.0, split_frequency);
+
+        // Maxima between mean up-crossings, leaving out the incomplete swash cycles at both ends
+        let up_crossings: Vec<usize> = (1..n).filter(|&i| elevation[i - 1] < setup && elevation[i] >= setup).collect();
+        let run_up_maxima: Vec<f64> = up_crossings
+            .windows(2)
+            .map(|pair| elevation[pair[0]..pair[1]].iter().copied().fold(f64::NEG_INFINITY, f64::max))
+            .collect();
+        let run_up_2 = exceedance(&run_up_maxima, 0.02).unwrap_or(setup);
+
+        // Shoreline speed along the channel by central differences
+        let speed: Vec<f64> = (0..n)
+            .map(|i| {
+                let (before, after) = (i.saturating_sub(1), (i + 1).min(n - 1));
+                (record.position[after] - record.position[before]) / (record.times[after] - record.times[before])
+            })
+            .collect();
+        let uprush = SwashVelocity::from_speeds(speed.iter().copied().filter(|&speed| speed > 0.0));
+        let backwash = SwashVelocity::from_speeds(speed.iter().copied().filter(|&speed| speed < 0.0));
+
+        let (frequencies, density) = variance_density(elevation, sample_interval, segment_length);
+
+        Ok(Self {
+            setup,
+            significant_swash: significant_wave_height(elevation),
+            incident_swash: significant_wave_height(&incident),
+            infragravity_swash: significant_wave_height(&infragravity),
+            run_up_maxima,
+            run_up_2,
+            uprush,
+            backwash,
+            frequencies,
+            density,
+            foreshore_slope: foreshore_slope(&record.position, elevation, setup, significant_wave_height(elevation)),
+        })
+    }
+}
+
+/// Value exceeded by a fraction of the samples, interpolated between the sorted samples
+fn exceedance(samples: &[f64], fraction: f64) -> Option<f64> {
+    if samples.is_empty() {
+        return None;
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let rank = (1.0 - fraction) * (sorted.len() - 1) as f64;
+    let below = rank.floor() as usize;
+    let above = (below + 1).min(sorted.len() - 1);
+    Some(sorted[below] + (rank - below as f64) * (sorted[above] - sorted[below]))
+}
+
+/// Least-squares slope of the shoreline elevation against its position, over the swash excursion within setup ± S/2
+fn foreshore_slope(position: &[f64], elevation: &[f64], setup: f64, significant_swash: f64) -> Option<f64> {
+    let pairs: Vec<(f64, f64)> = position
+        .iter()
+        .zip(elevation)
+        .filter(|(_, elevation)| (*elevation - setup).abs() <= 0.5 * significant_swash)
+        .map(|(&x, &z)| (x, z))
+        .collect();
+    if pairs.len() < 2 {
+        return None;
+    }
+    let count = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let mean_z = pairs.iter().map(|(_, z)| z).sum::<f64>() / count;
+    let covariance: f64 = pairs.iter().map(|(x, z)| (x - mean_x) * (z - mean_z)).sum();
+    let variance: f64 = pairs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    (variance > 0.0).then(|| covariance / variance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swash_analysis() {
+        // Shoreline swinging 0.1 m about a 0.02 m setup at 0.5 Hz on a 1:10 beach, sampled at 8 Hz over 32 periods
+        let mut record = ShorelineRecord::default();
+        for i in 0..512 {
+            let time = i as f64 * 0.125;
+            let elevation = 0.02 + 0.1 * (std::f64::consts::PI * time).sin();
+            record.record(time, 40.0 + 10.0 * elevation, elevation);
+        }
+        let swash = SwashAnalysis::new(&record, 0.25, 128).unwrap();
+        assert!((swash.setup - 0.02).abs() < 1e-3);
+        assert!((swash.significant_swash - 4.0 * 0.1 / 2.0_f64.sqrt()).abs() < 0.01);
+        assert!((swash.incident_swash - swash.significant_swash).abs() < 0.01);
+        assert!(swash.infragravity_swash < 1e-6);
+        assert_eq!(swash.run_up_maxima.len(), 30);
+        assert!((swash.run_up_2 - 0.12).abs() < 1e-3);
+
+        // Shoreline speed 10 × 0.1 π m/s at most, 2/π of it on average, slightly smoothed by the central differences
+        let peak = std::f64::consts::PI;
+        assert!((swash.uprush.max - peak).abs() < 0.1 && (swash.backwash.max - peak).abs() < 0.1);
+        assert!((swash.uprush.mean - 2.0).abs() < 0.05);
+        assert!((swash.foreshore_slope.unwrap() - 0.1).abs() < 1e-9);
+
+        let peak_bin = swash.density.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)).unwrap().0;
+        assert!((swash.frequencies[peak_bin] - 0.5).abs() < 0.1);
+
+        let window = record.window(10.0);
+        assert_eq!(window.len(), 81);
+        assert_eq!(record.window(0.0), record);
+        assert!(SwashAnalysis::new(&window, 5.0, 64).is_err());
+        assert!(SwashAnalysis::new(&ShorelineRecord::default(), 0.25, 64).is_err());
+    }
+}
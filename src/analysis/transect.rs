@@ -0,0 +1,126 @@
+use crate::analysis::{SnapshotStore, WaveGauge};
+use crate::solver::{BasinField, BasinFrame, Grid2D};
+use serde::{Deserialize, Serialize};
+
+/// Straight line across a basin along which its fields are extracted
+///
+/// Profiles along the line and records at points of it are one-dimensional, like those of the
+/// wave channel, so the crest detection, wave statistics and spectra of the channel apply to them.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Transect {
+    pub start: [f64; 2], // First end of the line (x, y) [m]
+    pub end: [f64; 2],   // Second end of the line (x, y) [m]
+}
+
+impl Transect {
+    /// Create a transect between two points
+    pub fn new(start: [f64; 2], end: [f64; 2]) -> Result<Self, String> {
+        let transect = Self { start, end };
+        if !(transect.length().is_finite() && transect.length() > 0.0) {
+            return Err("Transect ends must be distinct points".to_string());
+        }
+        Ok(transect)
+    }
+
+    /// Length of the line [m]
+    pub fn length(&self) -> f64 {
+        (self.end[0] - self.start[0]).hypot(self.end[1] - self.start[1])
+    }
+
+    /// Point at a distance along the line from its start [m]
+    pub fn point(&self, distance: f64) -> [f64; 2] {
+        let fraction = distance / self.length();
+        [self.start[0] + fraction * (self.end[0] - self.start[0]), self.start[1] + fraction * (self.end[1] - self.start[1])]
+    }
+
+    /// Number of samples along the line, about one per cell of the grid
+    pub fn samples(&self, grid: &Grid2D) -> usize {
+        (self.length() / grid.dx().min(grid.dy())).ceil() as usize + 1
+    }
+
+    /// Distance between neighbouring samples [m]
+    pub fn spacing(&self, samples: usize) -> f64 {
+        self.length() / (samples.max(2) - 1) as f64
+    }
+
+    /// Distances of the samples along the line [m]
+    pub fn distances(&self, samples: usize) -> Vec<f64> {
+        let spacing = self.spacing(samples);
+        (0..samples.max(2)).map(|n| n as f64 * spacing).collect()
+    }
+
+    /// Field of a frame sampled along the line
+    pub fn profile(&self, frame: &BasinFrame, field: BasinField, samples: usize) -> Vec<f64> {
+        self.distances(samples)
+            .into_iter()
+            .map(|distance| {
+                let [x, y] = self.point(distance);
+                frame.value_at(field, x, y)
+            })
+            .collect()
+    }
+
+    /// Surface elevation records of gauges spread along the line, from the stored snapshots of a basin
+    /// The position of each gauge is its distance along the line
+    pub fn gauges(&self, grid: &Grid2D, snapshots: &SnapshotStore, samples: usize) -> Vec<WaveGauge> {
+        let mut gauges: Vec<WaveGauge> = self.distances(samples).into_iter().map(WaveGauge::new).collect();
+        for snapshot in snapshots.snapshots() {
+            let Some(frame) = BasinFrame::new(grid, &snapshot.surface_elevation) else {
+                continue;
+            };
+            for gauge in &mut gauges {
+                let [x, y] = self.point(gauge.position());
+                gauge.record(snapshot.time, frame.value_at(BasinField::SurfaceElevation, x, y));
+            }
+        }
+        gauges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transect_geometry() {
+        let transect = Transect::new([1.0, 1.0], [4.0, 5.0]).unwrap();
+        assert_eq!(transect.length(), 5.0);
+        assert_eq!(transect.point(2.5), [2.5, 3.0]);
+        assert_eq!(transect.distances(6), vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert!(Transect::new([1.0, 1.0], [1.0, 1.0]).is_err());
+
+        let grid = Grid2D::new(10.0, 10.0, 20, 10).unwrap();
+        assert_eq!(transect.samples(&grid), 11);
+    }
+
+    #[test]
+    fn test_profiles_and_records_along_the_line() {
+        // A plane surface η = 0.01 x moving with u = 0.1 m/s, v = 0
+        let grid = Grid2D::new(10.0, 4.0, 20, 8).unwrap();
+        let frame_values = |time: f64| -> Vec<f64> {
+            let mut values: Vec<f64> = (0..grid.cells()).map(|n| 0.01 * grid.center(n % 20, n / 20).0 + time).collect();
+            values.extend(std::iter::repeat_n(0.1, grid.cells()));
+            values.extend(std::iter::repeat_n(0.0, grid.cells()));
+            values
+        };
+        let transect = Transect::new([2.0, 2.0], [8.0, 2.0]).unwrap();
+        let values = frame_values(0.0);
+        let frame = BasinFrame::new(&grid, &values).unwrap();
+        let profile = transect.profile(&frame, BasinField::SurfaceElevation, 4);
+        for (eta, expected) in profile.iter().zip([0.02, 0.04, 0.06, 0.08]) {
+            assert!((eta - expected).abs() < 1e-12);
+        }
+        assert!(transect.profile(&frame, BasinField::Speed, 4).iter().all(|speed| (speed - 0.1).abs() < 1e-12));
+
+        let mut snapshots = SnapshotStore::default();
+        for step in 0..5 {
+            let time = step as f64 * 0.5;
+            snapshots.record(time, &frame_values(time));
+        }
+        let gauges = transect.gauges(&grid, &snapshots, 4);
+        assert_eq!(gauges.len(), 4);
+        assert_eq!(gauges[1].position(), 2.0);
+        assert_eq!(gauges[1].times(), &[0.0, 0.5, 1.0, 1.5, 2.0]);
+        assert!((gauges[1].elevation()[4] - 2.04).abs() < 1e-12);
+    }
+}
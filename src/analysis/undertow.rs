@@ -0,0 +1,318 @@
+use crate::waves::WATER_DENSITY;
+use crate::waves::wavemaker::linear_wave_number;
+use serde::{Deserialize, Serialize};
+
+/// Depth below the wave troughs under which a cell counts as dry for the mean flow [m]
+const MIN_MEAN_DEPTH: f64 = 1e-2;
+
+/// Running time averages of the flow at every cell of the channel
+///
+/// Storing the velocity field at every sample would take as much memory as the snapshots, so the
+/// surface elevation η and the depth-averaged velocity u are accumulated as the sums of η, η², u,
+/// the discharge (d + η) u, the correlation η u and the momentum flux (d + η) u², enough for their
+/// means, wave statistics and radiation stress.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MeanFlowRecord {
+    /// Cell centres [m]
+    positions: Vec<f64>,
+    /// Still water depth at the cell centres (d) [m]
+    still_water_depth: Vec<f64>,
+    /// First and last sample times [s]
+    span: Option<(f64, f64)>,
+    /// Number of accumulated samples
+    samples: usize,
+    /// Sums of the surface elevation [m]
+    sum_eta: Vec<f64>,
+    /// Sums of the squared surface elevation [m²]
+    sum_eta_squared: Vec<f64>,
+    /// Sums of the depth-averaged velocity [m/s]
+    sum_velocity: Vec<f64>,
+    /// Sums of the discharge per unit width [m²/s]
+    sum_discharge: Vec<f64>,
+    /// Sums of the product of surface elevation and velocity [m²/s]
+    sum_eta_velocity: Vec<f64>,
+    /// Sums of the momentum flux per unit width and water density [m³/s²], empty in records saved without it
+    #[serde(default)]
+    sum_momentum_flux: Vec<f64>,
+}
+
+impl MeanFlowRecord {
+    /// Create new empty record over cells at the given centres and still water depths
+    pub fn new(positions: &[f64], still_water_depth: &[f64]) -> Self {
+        let n = positions.len().min(still_water_depth.len());
+        Self {
+            positions: positions[..n].to_vec(),
+            still_water_depth: still_water_depth[..n].to_vec(),
+            span: None,
+            samples: 0,
+            sum_eta: vec![0.0; n],
+            sum_eta_squared: vec![0.0; n],
+            sum_velocity: vec![0.0; n],
+            sum_discharge: vec![0.0; n],
+            sum_eta_velocity: vec![0.0; n],
+            sum_momentum_flux: vec![0.0; n],
+        }
+    }
+
+    /// Add a sample of the surface elevation and the depth-averaged velocity at the cell centres
+    pub fn record(&mut self, time: f64, eta: &[f64], velocity: &[f64]) {
+        for (i, (&eta, &velocity)) in eta.iter().zip(velocity).take(self.positions.len()).enumerate() {
+            self.sum_eta[i] += eta;
+            self.sum_eta_squared[i] += eta * eta;
+            self.sum_velocity[i] += velocity;
+            let depth = (self.still_water_depth[i] + eta).max(0.0);
+            self.sum_discharge[i] += depth * velocity;
+            self.sum_eta_velocity[i] += eta * velocity;
+            if let Some(sum) = self.sum_momentum_flux.get_mut(i) {
+                *sum += depth * velocity * velocity;
+            }
+        }
+        self.span = Some(self.span.map_or((time, time), |(start, _)| (start, time)));
+        self.samples += 1;
+    }
+
+    /// Get cell centres [m]
+    pub fn positions(&self) -> &[f64] {
+        &self.positions
+    }
+
+    /// Get still water depth at the cell centres [m]
+    pub fn still_water_depth(&self) -> &[f64] {
+        &self.still_water_depth
+    }
+
+    /// First and last sample times, None for an empty record [s]
+    pub fn span(&self) -> Option<(f64, f64)> {
+        self.span
+    }
+
+    /// Number of accumulated samples
+    pub fn samples(&self) -> usize {
+        self.samples
+    }
+
+    /// Check if nothing was recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.samples == 0
+    }
+
+    /// Mean of accumulated sums over the samples
+    fn mean(&self, sums: &[f64]) -> Vec<f64> {
+        let samples = self.samples.max(1) as f64;
+        sums.iter().map(|sum| sum / samples).collect()
+    }
+
+    /// Mean surface elevation, the wave setup or setdown [m]
+    pub fn setup(&self) -> Vec<f64> {
+        self.mean(&self.sum_eta)
+    }
+
+    /// Root-mean-square wave height from the variance of the surface elevation, H_rms = √8 σ [m]
+    pub fn wave_height(&self) -> Vec<f64> {
+        let setup = self.setup();
+        self.mean(&self.sum_eta_squared).iter().zip(&setup).map(|(square, mean)| (8.0 * (square - mean * mean).max(0.0)).sqrt()).collect()
+    }
+
+    /// Mean depth-averaged (Eulerian) velocity [m/s]
+    pub fn mean_velocity(&self) -> Vec<f64> {
+        self.mean(&self.sum_velocity)
+    }
+
+    /// Mean discharge per unit width, zero in a closed channel once the waves are steady [m²/s]
+    pub fn mean_discharge(&self) -> Vec<f64> {
+        self.mean(&self.sum_discharge)
+    }
+
+    /// Mass flux of the waves per unit width and water density, the correlation of the surface
+    /// elevation and the velocity, carried between the troughs and the crests [m²/s]
+    pub fn wave_mass_flux(&self) -> Vec<f64> {
+        let (setup, velocity) = (self.setup(), self.mean_velocity());
+        self.mean(&self.sum_eta_velocity).iter().zip(setup.iter().zip(&velocity)).map(|(product, (eta, u))| product - eta * u).collect()
+    }
+
+    /// Radiation stress of the simulated flow, the mean momentum flux of the waves [N/m]
+    ///
+    /// The depth-integrated momentum flux of the shallow water equations, ρ(d + η)u² + ½ρg(d + η)²,
+    /// averaged over the record, less that of the mean flow, ρq̄²/h̄ + ½ρgh̄² with h̄ = d + η̄:
+    /// S_xx = ρ(⟨(d + η)u²⟩ − q̄²/h̄) + ½ρgσ². Empty for records saved without the momentum flux;
+    /// zero where the mean depth is dry.
+    pub fn radiation_stress(&self) -> Vec<f64> {
+        if self.sum_momentum_flux.len() != self.positions.len() {
+            return Vec::new();
+        }
+        let (setup, wave_height, discharge) = (self.setup(), self.wave_height(), self.mean_discharge());
+        let momentum_flux = self.mean(&self.sum_momentum_flux);
+        (0..self.positions.len())
+            .map(|i| {
+                let mean_depth = self.still_water_depth[i] + setup[i];
+                if mean_depth < MIN_MEAN_DEPTH {
+                    return 0.0;
+                }
+                let variance = wave_height[i] * wave_height[i] / 8.0;
+                WATER_DENSITY * (momentum_flux[i] - discharge[i] * discharge[i] / mean_depth + 0.5 * 9.81 * variance)
+            })
+            .collect()
+    }
+
+    /// Discard the accumulated samples, keeping the cells
+    pub fn clear(&mut self) {
+        *self = Self::new(&self.positions, &self.still_water_depth);
+    }
+}
+
+/// Cross-shore profile of the undertow from the mean flow of the waves
+///
+/// The waves carry a mass flux Q_w between their troughs and crests. Where no water leaves the
+/// channel, it returns below the trough level as the undertow U = (q̄ − Q_w) / h_t, the mean
+/// discharge q̄ less the wave mass flux spread over the depth h_t = d + η̄ − H_rms/2 below the
+/// troughs (Svendsen, 1984). The radiation stress S_xx of the simulated flow drives the setup
+/// through the momentum balance dη̄/dx = −(1/ρgh) dS_xx/dx, whose integral from the first cell is
+/// compared with the simulated setup. Linear theory gives S_xx = E (2n − ½) from the measured wave
+/// heights, also used for records saved without the momentum flux. Cells dry for part of the time
+/// are left out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UndertowProfile {
+    /// Cell centres of the wet cells [m]
+    pub positions: Vec<f64>,
+    /// Simulated mean surface elevation [m]
+    pub setup: Vec<f64>,
+    /// Root-mean-square wave height [m]
+    pub wave_height: Vec<f64>,
+    /// Radiation stress of the simulated flow [N/m]
+    pub radiation_stress: Vec<f64>,
+    /// Radiation stress of linear waves of the measured heights [N/m]
+    pub linear_radiation_stress: Vec<f64>,
+    /// Setup balancing the radiation stress gradient, from the simulated setup at the first cell [m]
+    pub balanced_setup: Vec<f64>,
+    /// Wave mass flux per unit width and water density [m²/s]
+    pub wave_mass_flux: Vec<f64>,
+    /// Mean return flow below the wave troughs, negative offshore [m/s]
+    pub undertow: Vec<f64>,
+}
+
+impl UndertowProfile {
+    /// Undertow profile of a mean flow record of waves of the given period [s]
+    pub fn new(record: &MeanFlowRecord, wave_period: f64) -> Result<Self, String> {
+        if record.is_empty() {
+            return Err("The mean flow has not been recorded yet".to_string());
+        }
+        if wave_period <= 0.0 {
+            return Err("Wave period must be positive".to_string());
+        }
+        let omega = 2.0 * std::f64::consts::PI / wave_period;
+        let (setup, wave_height, discharge, mass_flux) = (record.setup(), record.wave_height(), record.mean_discharge(), record.wave_mass_flux());
+        let simulated_stress = record.radiation_stress();
+
+        let mut profile = Self {
+            positions: Vec::new(),
+            setup: Vec::new(),
+            wave_height: Vec::new(),
+            radiation_stress: Vec::new(),
+            linear_radiation_stress: Vec::new(),
+            balanced_setup: Vec::new(),
+            wave_mass_flux: Vec::new(),
+            undertow: Vec::new(),
+        };
+        // Mean depth, radiation stress and balanced setup of the last wet cell
+        let mut previous: Option<(f64, f64, f64)> = None;
+        for i in 0..record.positions.len() {
+            let mean_depth = record.still_water_depth[i] + setup[i];
+            let trough_depth = mean_depth - 0.5 * wave_height[i];
+            if trough_depth < MIN_MEAN_DEPTH {
+                continue;
+            }
+            let kh = linear_wave_number(omega, mean_depth, 9.81)? * mean_depth;
+            let group_ratio = 0.5 * (1.0 + 2.0 * kh / (2.0 * kh).sinh());
+            let energy = WATER_DENSITY * 9.81 * wave_height[i] * wave_height[i] / 8.0;
+            let linear_radiation_stress = energy * (2.0 * group_ratio - 0.5);
+            let radiation_stress = simulated_stress.get(i).copied().unwrap_or(linear_radiation_stress);
+
+            let balanced_setup = match previous {
+                Some((previous_depth, previous_stress, previous_setup)) => {
+                    previous_setup - (radiation_stress - previous_stress) / (WATER_DENSITY * 9.81 * 0.5 * (mean_depth + previous_depth))
+                }
+                None => setup[i],
+            };
+            previous = Some((mean_depth, radiation_stress, balanced_setup));
+
+            profile.positions.push(record.positions[i]);
+            profile.setup.push(setup[i]);
+            profile.wave_height.push(wave_height[i]);
+            profile.radiation_stress.push(radiation_stress);
+            profile.linear_radiation_stress.push(linear_radiation_stress);
+            profile.balanced_setup.push(balanced_setup);
+            profile.wave_mass_flux.push(mass_flux[i]);
+            profile.undertow.push((discharge[i] - mass_flux[i]) / trough_depth);
+        }
+        if profile.positions.is_empty() {
+            return Err("The channel has no wet cells".to_string());
+        }
+        Ok(profile)
+    }
+
+    /// Strongest return flow and its position: (position [m], undertow [m/s])
+    pub fn strongest(&self) -> Option<(f64, f64)> {
+        self.positions.iter().copied().zip(self.undertow.iter().copied()).min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undertow_of_progressive_waves() {
+        // Long waves of 0.1 m in 1 m of water with u = η c/d, sampled over 10 periods
+        let (depth, height, period) = (1.0, 0.1, 20.0);
+        let celerity = (9.81_f64 * depth).sqrt();
+        let positions = [0.0, 1.0, 2.0];
+        let mut record = MeanFlowRecord::new(&positions, &[depth; 3]);
+        for n in 0..2000 {
+            let time = n as f64 * 0.1;
+            let eta: Vec<f64> = positions.iter().map(|x| 0.5 * height * (2.0 * std::f64::consts::PI * (x / (celerity * period) - time / period)).cos()).collect();
+            // The mean flow returns the wave mass flux, keeping the mean discharge zero
+            let velocity: Vec<f64> = eta.iter().map(|eta| eta * celerity / depth - 9.81 * height * height / (8.0 * celerity * depth)).collect();
+            record.record(time, &eta, &velocity);
+        }
+        assert_eq!(record.samples(), 2000);
+        assert_eq!(record.span(), Some((0.0, 199.9)));
+        assert!(record.setup().iter().all(|eta| eta.abs() < 1e-9));
+        assert!(record.wave_height().iter().all(|h| (h - height).abs() < 1e-6));
+
+        // Linear mass flux Q_w = g H² / 8c, returned below the troughs at a depth d − H/2
+        let mass_flux = 9.81 * height * height / (8.0 * celerity);
+        let profile = UndertowProfile::new(&record, period).unwrap();
+        assert_eq!(profile.positions.len(), 3);
+        for i in 0..3 {
+            assert!((profile.wave_mass_flux[i] - mass_flux).abs() < 1e-4 * mass_flux);
+            assert!((profile.undertow[i] + mass_flux / (depth - 0.5 * height)).abs() < 1e-3 * mass_flux, "{}", profile.undertow[i]);
+            assert!((profile.balanced_setup[i] - profile.setup[i]).abs() < 1e-6);
+        }
+        // Shallow water waves: n = 1 and S_xx = 3E/2, from the flow as from linear theory
+        let energy = WATER_DENSITY * 9.81 * height * height / 8.0;
+        assert!((profile.linear_radiation_stress[0] / (1.5 * energy) - 1.0).abs() < 0.01);
+        assert!(profile.radiation_stress.iter().all(|stress| (stress / (1.5 * energy) - 1.0).abs() < 0.01), "{:?}", profile.radiation_stress);
+        assert!(profile.strongest().unwrap().1 < 0.0);
+
+        record.clear();
+        assert!(record.is_empty() && record.positions().len() == 3);
+        assert!(UndertowProfile::new(&record, period).is_err());
+    }
+
+    #[test]
+    fn test_records_without_momentum_flux_use_linear_radiation_stress() {
+        let mut record = MeanFlowRecord::new(&[0.0, 1.0], &[1.0, 1.0]);
+        for n in 0..100 {
+            let eta = 0.05 * (n as f64 * 0.3).cos();
+            record.record(n as f64 * 0.1, &[eta, eta], &[3.0 * eta, 3.0 * eta]);
+        }
+        let mut saved = serde_json::to_value(&record).unwrap();
+        saved.as_object_mut().unwrap().remove("sum_momentum_flux");
+        let saved: MeanFlowRecord = serde_json::from_value(saved).unwrap();
+        assert!(saved.radiation_stress().is_empty());
+        assert_eq!(record.radiation_stress().len(), 2);
+
+        let profile = UndertowProfile::new(&saved, 4.0).unwrap();
+        assert_eq!(profile.radiation_stress, profile.linear_radiation_stress);
+    }
+}
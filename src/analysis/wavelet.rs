@@ -0,0 +1,185 @@
+use crate::analysis::spectral::{fft, mean};
+use std::f64::consts::PI;
+
+/// Non-dimensional frequency of the Morlet wavelet (ω₀)
+const MORLET_OMEGA: f64 = 6.0;
+
+/// Continuous wavelet transform of a record with the Morlet wavelet (Torrence & Compo, 1998)
+///
+/// The wavelet power |W(s, t)|² / σ² localizes energy in both time and frequency,
+/// so transient events such as wave groups, breaking or the onset of seiching stand out.
+/// Power is normalized by the record variance: white noise has unit expected power.
+#[derive(Debug, Clone)]
+pub struct Scalogram {
+    /// Sample interval of the record [s]
+    sample_interval: f64,
+    /// Record length in samples
+    samples: usize,
+    /// Fourier frequencies of the wavelet scales, from high to low [Hz]
+    frequencies: Vec<f64>,
+    /// Normalized wavelet power, one row per frequency and one column per sample
+    power: Vec<Vec<f64>>,
+}
+
+impl Scalogram {
+    /// Transform a record over a frequency range with a number of log-spaced scales
+    pub fn new(signal: &[f64], sample_interval: f64, min_frequency: f64, max_frequency: f64, scales: usize) -> Result<Self, String> {
+        if signal.len() < 4 {
+            return Err("Record is too short for wavelet analysis".to_string());
+        }
+        if sample_interval <= 0.0 {
+            return Err("Sample interval must be positive".to_string());
+        }
+        if min_frequency <= 0.0 || min_frequency >= max_frequency {
+            return Err("Frequency range must be positive and increasing".to_string());
+        }
+        if scales < 2 {
+            return Err("At least 2 scales are required".to_string());
+        }
+
+        // Spectrum of the mean-removed record, zero-padded against wrap-around
+        let n = (2 * signal.len()).next_power_of_two();
+        let signal_mean = mean(signal);
+        let mut spectrum_re: Vec<f64> = signal.iter().map(|value| value - signal_mean).collect();
+        let variance = spectrum_re.iter().map(|value| value * value).sum::<f64>() / signal.len() as f64;
+        spectrum_re.resize(n, 0.0);
+        let mut spectrum_im = vec![0.0; n];
+        fft(&mut spectrum_re, &mut spectrum_im, false);
+
+        let ratio = (max_frequency / min_frequency).powf(1.0 / (scales - 1) as f64);
+        let frequencies: Vec<f64> = (0..scales).map(|j| max_frequency / ratio.powi(j as i32)).collect();
+        let power = frequencies
+            .iter()
+            .map(|&frequency| {
+                let scale = Self::scale_of(frequency);
+                let normalization = (2.0 * PI * scale / sample_interval).sqrt() * PI.powf(-0.25);
+                let mut re = vec![0.0; n];
+                let mut im = vec![0.0; n];
+                // Analytic wavelet: only positive angular frequencies contribute
+                for k in 1..=n / 2 {
+                    let omega = 2.0 * PI * k as f64 / (n as f64 * sample_interval);
+                    let daughter = normalization * (-0.5 * (scale * omega - MORLET_OMEGA).powi(2)).exp();
+                    re[k] = spectrum_re[k] * daughter;
+                    im[k] = spectrum_im[k] * daughter;
+                }
+                fft(&mut re, &mut im, true);
+                re.iter()
+                    .zip(&im)
+                    .take(signal.len())
+                    .map(|(re, im)| if variance > 0.0 { (re * re + im * im) / variance } else { 0.0 })
+                    .collect()
+            })
+            .collect();
+
+        Ok(Self {
+            sample_interval,
+            samples: signal.len(),
+            frequencies,
+            power,
+        })
+    }
+
+    /// Morlet scale whose Fourier frequency is f [s]
+    fn scale_of(frequency: f64) -> f64 {
+        Self::scale_frequency_factor() / frequency
+    }
+
+    /// Get frequencies of the scales, from high to low [Hz]
+    pub fn frequencies(&self) -> &[f64] {
+        &self.frequencies
+    }
+
+    /// Get normalized wavelet power, indexed by [frequency][sample]
+    pub fn power(&self) -> &[Vec<f64>] {
+        &self.power
+    }
+
+    /// Record duration covered by the scalogram [s]
+    pub fn duration(&self) -> f64 {
+        (self.samples - 1) as f64 * self.sample_interval
+    }
+
+    /// Largest normalized power
+    pub fn max_power(&self) -> f64 {
+        self.power.iter().flatten().fold(0.0, |max: f64, &value| max.max(value))
+    }
+
+    /// Frequency with the largest power at a given sample [Hz]
+    pub fn dominant_frequency(&self, sample: usize) -> f64 {
+        self.frequencies
+            .iter()
+            .zip(&self.power)
+            .max_by(|a, b| a.1[sample].total_cmp(&b.1[sample]))
+            .map_or(0.0, |(&frequency, _)| frequency)
+    }
+
+    /// Lowest frequency free of edge effects at a time, from the e-folding time √2 s of the Morlet wavelet [Hz]
+    /// Power below this frequency (outside the cone of influence) is affected by the zero padding
+    pub fn cone_of_influence(&self, time: f64) -> f64 {
+        let distance = time.min(self.duration() - time).max(self.sample_interval);
+        Self::scale_frequency_factor() * 2.0_f64.sqrt() / distance
+    }
+
+    /// Product of the Morlet scale and its Fourier frequency
+    fn scale_frequency_factor() -> f64 {
+        (MORLET_OMEGA + (2.0 + MORLET_OMEGA * MORLET_OMEGA).sqrt()) / (4.0 * PI)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_localizes_frequency_change() {
+        // 0.5 Hz during the first half, 0.2 Hz during the second half
+        let dt = 0.1;
+        let signal: Vec<f64> = (0..2000)
+            .map(|i| {
+                let t = i as f64 * dt;
+                let frequency = if i < 1000 { 0.5 } else { 0.2 };
+                (2.0 * PI * frequency * t).sin()
+            })
+            .collect();
+        let scalogram = Scalogram::new(&signal, dt, 0.05, 2.0, 60).unwrap();
+        assert_eq!(scalogram.power().len(), 60);
+        assert_eq!(scalogram.power()[0].len(), 2000);
+
+        let early = scalogram.dominant_frequency(500);
+        let late = scalogram.dominant_frequency(1500);
+        assert!((early - 0.5).abs() < 0.05, "Early {:.3} Hz", early);
+        assert!((late - 0.2).abs() < 0.02, "Late {:.3} Hz", late);
+    }
+
+    #[test]
+    fn test_sine_power_normalization() {
+        // |W|² = (A/2)² 2πs/Δt π^(-1/2) at the matching scale; σ² = A²/2
+        let dt = 0.1;
+        let frequency = 0.25;
+        let signal: Vec<f64> = (0..4000).map(|i| (2.0 * PI * frequency * i as f64 * dt).cos()).collect();
+        let scalogram = Scalogram::new(&signal, dt, frequency, 2.0 * frequency, 2).unwrap();
+
+        let scale = Scalogram::scale_of(frequency);
+        let omega = 2.0 * PI * frequency;
+        let response = (-0.5 * (scale * omega - MORLET_OMEGA).powi(2)).exp();
+        let expected = 0.25 * 2.0 * PI * scale / dt / PI.sqrt() * response * response / 0.5;
+        let actual = scalogram.power()[1][2000];
+        assert!((actual - expected).abs() < 0.02 * expected, "Power {:.2} vs {:.2}", actual, expected);
+    }
+
+    #[test]
+    fn test_cone_of_influence() {
+        let signal = vec![1.0, -1.0, 0.5, 0.0, -0.5, 1.0, 0.0, -1.0, 0.5];
+        let scalogram = Scalogram::new(&signal, 1.0, 0.1, 0.5, 4).unwrap();
+        // Edge effects reach highest frequencies near the record ends
+        assert!(scalogram.cone_of_influence(0.0) > scalogram.cone_of_influence(4.0));
+        assert_eq!(scalogram.cone_of_influence(1.0), scalogram.cone_of_influence(7.0));
+    }
+
+    #[test]
+    fn test_invalid_scalogram() {
+        assert!(Scalogram::new(&[0.0; 3], 0.1, 0.1, 1.0, 10).is_err());
+        assert!(Scalogram::new(&[0.0; 100], 0.1, 1.0, 0.1, 10).is_err());
+        assert!(Scalogram::new(&[0.0; 100], 0.1, 0.1, 1.0, 1).is_err());
+    }
+}
@@ -0,0 +1,36 @@
+use crate::waves::parameters::BreakingCriterion;
+use thiserror::Error;
+
+/// Error of the wave computations, structured so callers can react to its cause
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum CoastalError {
+    /// An iterative solution did not converge, with the residual of its last iterate
+    #[error("Failed to converge after {iterations} iterations: residual = {residual:.2e}")]
+    NonConvergence { iterations: usize, residual: f64 },
+    /// An input outside its physical range, e.g. a negative wave period
+    #[error("{name} must be {constraint}, got {value}")]
+    InvalidParameter { name: &'static str, value: f64, constraint: &'static str },
+    /// Wave higher than the limit of the governing breaking criterion, with its height over the depth H/d
+    #[error("Wave may break: H = {height:.3} m (H/d = {ratio:.3}) exceeds the {criterion} limit of {limit:.3} m")]
+    Breaking { ratio: f64, criterion: BreakingCriterion, height: f64, limit: f64 },
+    /// A computed result failing a consistency check, with its relative or absolute error
+    #[error("{check} violated: error = {error:.2e}")]
+    Inconsistent { check: &'static str, error: f64 },
+    /// Waves unable to travel against an opposing current faster than their group velocity [m/s]
+    #[error("Waves blocked by the opposing current U = {current:.3} m/s")]
+    Blocked { current: f64 },
+}
+
+impl CoastalError {
+    /// Error for a parameter that must be strictly positive
+    pub fn not_positive(name: &'static str, value: f64) -> Self {
+        CoastalError::InvalidParameter { name, value, constraint: "positive" }
+    }
+}
+
+/// Message of the error, for the functions still reporting errors as text
+impl From<CoastalError> for String {
+    fn from(error: CoastalError) -> String {
+        error.to_string()
+    }
+}
@@ -1,41 +1,754 @@
 use eframe::egui;
+use crate::solver::InitialCondition;
+use crate::waves::seed_from_clock;
+use std::path::PathBuf;
 
+mod alerts;
+mod annotations;
+mod autosave;
+mod background;
+mod basin_compute;
+mod clipboard;
+mod command_palette;
+mod comparison;
+mod dashboard;
+mod design_optimizer;
+mod dispersion_explorer;
 mod equations;
+mod exercises;
+mod gallery;
+mod help;
+mod live_edits;
+mod measurement;
+mod monte_carlo;
+mod openfoam;
+mod performance;
+mod plot_buffers;
+mod plot_style;
+mod preferences;
+mod project;
+mod remote_api;
+mod repaint;
+mod result_cache;
+mod results_table;
+mod run_metadata;
+mod run_output;
+mod run_preview;
+mod session;
+mod shortcuts;
+mod timeline;
+mod transects;
+mod wave_basin;
 mod wave_channel;
+mod wave_climate;
+mod workers;
+pub use alerts::{AlertCondition, AlertEvent, AlertRule, AlertToasts, TOAST_DURATION};
+pub use annotations::{AnnotatedPlot, Annotation, AnnotationShape};
+pub use autosave::{AUTOSAVE_INTERVAL, Autosave};
+pub use clipboard::{CopyableValues, plot_image, value_of};
+pub use command_palette::{Command, CommandPalette, fuzzy_score};
+pub use comparison::ComparisonApp;
+pub use dashboard::{Dashboard, DashboardQuantity, StudyProgress};
+pub use design_optimizer::{DesignEvaluation, DesignObjective, DesignOptimizerApp, DesignParameter, DesignResults, DesignStudy};
+pub use dispersion_explorer::{DispersionCurve, DispersionExplorer, DispersionPoint, DispersionQuantity};
 pub use equations::EquationRenderer;
-pub use wave_channel::WaveChannelApp;
+pub use exercises::{Assignment, AssignmentResults, Check, CheckResult, Exercise, ExerciseResult, ExercisesApp, Quantity};
+pub use gallery::{GalleryCapture, GalleryLayout, SnapshotGallery};
+pub use help::{Glossary, GlossaryEntry, HelpPane, HelpTopic};
+pub use live_edits::{LiveParameter, ParameterChange, ParameterLog};
+pub use measurement::{MeasureTool, SlopeMeasurement, WaveMeasurement, WavelengthReading};
+pub use monte_carlo::{MonteCarloApp, MonteCarloResults, MonteCarloStudy, Realization, UncertainInput, UncertainParameter};
+pub use openfoam::OpenFoamCase;
+pub use performance::{PERFORMANCE_WINDOW, PerformanceMonitor};
+pub use plot_buffers::{ChannelSeries, refill};
+pub use plot_style::{Colormap, Palette, PlotStyle};
+pub use preferences::{MAX_RECENT_PROJECTS, Preferences, Theme, UnitSystem};
+pub use project::Project;
+pub use remote_api::{DEFAULT_REMOTE_ADDRESS, RemoteApi, RemoteCommand, websocket_accept};
+pub use repaint::{ANIMATION_FPS, RepaintPolicy};
+pub use result_cache::{CachedResult, ResultCache};
+pub use results_table::{ResultsTable, TableCell, TableView};
+pub use run_metadata::{RunMetadata, format_utc};
+pub use run_output::RunOutput;
+pub use run_preview::{RunPreview, format_bytes, format_duration};
+pub use session::{SessionEntry, SessionEvent, SessionRecorder, SessionRecording, SessionState};
+pub use shortcuts::{KeyBinding, ShortcutBinding, Shortcuts};
+pub use timeline::Timeline;
+pub use transects::{Transect, TransectRun, TransectStudy, TransectsApp};
+pub use wave_basin::{BasinGauge, WaveBasinApp};
+pub use wave_channel::{PropagationModel, SetupMethod, SourceSettings, WaveChannelApp};
+pub use wave_climate::{CellRun, ClimateResults, ClimateStudy, WaveClimateApp};
+pub use workers::{DEFAULT_WORKER_ADDRESS, JobQueue, WorkerMessage, WorkerStatus, new_token, run_worker, run_workers};
+
+/// Tool shown in the platform window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum PlatformTab {
+    WaveChannel,
+    DispersionExplorer,
+    Comparison,
+    MonteCarlo,
+    DesignOptimizer,
+    WaveClimate,
+    Transects,
+    WaveBasin,
+    Exercises,
+}
+
+impl PlatformTab {
+    /// Every tool, in the order of the tab bar
+    pub const ALL: [PlatformTab; 9] = [
+        PlatformTab::WaveChannel,
+        PlatformTab::DispersionExplorer,
+        PlatformTab::Comparison,
+        PlatformTab::MonteCarlo,
+        PlatformTab::DesignOptimizer,
+        PlatformTab::WaveClimate,
+        PlatformTab::Transects,
+        PlatformTab::WaveBasin,
+        PlatformTab::Exercises,
+    ];
+}
+
+impl std::fmt::Display for PlatformTab {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlatformTab::WaveChannel => write!(f, "Wave Channel"),
+            PlatformTab::DispersionExplorer => write!(f, "Dispersion Explorer"),
+            PlatformTab::Comparison => write!(f, "Comparison"),
+            PlatformTab::MonteCarlo => write!(f, "Monte Carlo"),
+            PlatformTab::DesignOptimizer => write!(f, "Design"),
+            PlatformTab::WaveClimate => write!(f, "Wave Climate"),
+            PlatformTab::Transects => write!(f, "Transects"),
+            PlatformTab::WaveBasin => write!(f, "Wave Basin"),
+            PlatformTab::Exercises => write!(f, "Exercises"),
+        }
+    }
+}
 
 pub struct PlatformApp {
+    active_tab: PlatformTab,
     wave_channel_app: WaveChannelApp,
+    dispersion_explorer: DispersionExplorer,
+    comparison: ComparisonApp,
+    monte_carlo: MonteCarloApp,
+    design_optimizer: DesignOptimizerApp,
+    wave_climate: WaveClimateApp,
+    transects: TransectsApp,
+    dashboard: Dashboard,
+    alert_toasts: AlertToasts,
+    wave_basin: WaveBasinApp,
+    exercises: ExercisesApp,
     equation_renderer: EquationRenderer,
+    autosave: Autosave,
+    restore_prompt: Option<Project>, // Project left by a session that did not exit cleanly
+    preferences: Preferences,
+    preferences_path: Option<PathBuf>, // None if there is no configuration directory
+    show_preferences: bool,
+    project_path: String,              // File the project is opened from and saved to
+    command_palette: CommandPalette,
+    capturing_shortcut: Option<Command>, // Command waiting for its new key in the preferences
+    help_pane: HelpPane,
+    session: SessionRecorder,
+    session_path: String,              // File sessions are recorded to and replayed from
+    remote_api: Option<RemoteApi>,     // Embedded control server, None while stopped
+    remote_api_error: Option<String>,  // Why the server could not start
 }
 
 impl PlatformApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         let mut equation_renderer = EquationRenderer::new();
+        let autosave = Autosave::temporary();
         if let Err(e) = equation_renderer.load_equations() {
             eprintln!("Failed to load equations: {}", e);
         }
 
+        let glossary = Glossary::load(std::path::Path::new("scripts/glossary.json")).unwrap_or_else(|e| {
+            eprintln!("Failed to load glossary: {}", e);
+            Glossary::new()
+        });
+
+        let preferences_path = Preferences::default_path();
+        let preferences = preferences_path.as_deref().map_or_else(Preferences::default, Preferences::load);
+        preferences.apply(&cc.egui_ctx);
+        let mut wave_channel_app = WaveChannelApp::new().with_result_cache(ResultCache::new(".cache/simulations"));
+        if let Some(project) = &preferences.default_project {
+            wave_channel_app.load_project(project);
+        }
+        wave_channel_app.unit_system = preferences.unit_system;
+        wave_channel_app.plot_style = preferences.plot_style;
+        wave_channel_app.repaint_policy = preferences.repaint_policy;
+        wave_channel_app.help_mode = preferences.show_help;
+
         Self {
-            wave_channel_app: WaveChannelApp::new(),
+            active_tab: PlatformTab::WaveChannel,
+            wave_channel_app,
+            dispersion_explorer: DispersionExplorer::new(),
+            comparison: ComparisonApp::new(),
+            monte_carlo: MonteCarloApp::new(),
+            design_optimizer: DesignOptimizerApp::new(),
+            wave_climate: WaveClimateApp::new(),
+            transects: TransectsApp::new(),
+            dashboard: Dashboard::new(),
+            alert_toasts: AlertToasts::new(),
+            wave_basin: WaveBasinApp::new(),
+            exercises: ExercisesApp::new(),
             equation_renderer,
+            restore_prompt: autosave.load(),
+            autosave,
+            preferences,
+            preferences_path,
+            show_preferences: false,
+            project_path: "project.json".to_string(),
+            command_palette: CommandPalette::new(),
+            capturing_shortcut: None,
+            help_pane: HelpPane::new(glossary),
+            session: SessionRecorder::new(),
+            session_path: "session.json".to_string(),
+            remote_api: None,
+            remote_api_error: None,
+        }
+    }
+
+    /// Run a command of the palette or of a keyboard shortcut
+    fn execute(&mut self, command: Command, ctx: &egui::Context) {
+        let channel = &mut self.wave_channel_app;
+        match command {
+            Command::PlayPause => channel.toggle_simulation(),
+            Command::Reset => channel.reset_simulation(),
+            Command::AddGauge => channel.add_gauge(),
+            Command::GaugeArray => channel.add_gauge_array(),
+            Command::ToggleLinearOverlay => channel.show_linear_overlay = !channel.show_linear_overlay,
+            Command::ToggleCrestTracking => channel.track_crests = !channel.track_crests,
+            Command::ToggleHelp => self.set_help_visible(!self.preferences.show_help),
+            Command::TogglePerformanceHud => {
+                self.preferences.show_performance_hud = !self.preferences.show_performance_hud;
+                self.save_preferences();
+            }
+            Command::ToggleDashboard => {
+                self.preferences.show_dashboard = !self.preferences.show_dashboard;
+                self.save_preferences();
+            }
+            Command::WavemakerScenario => channel.initial_condition = None,
+            Command::DamBreakScenario => {
+                channel.initial_condition = Some(InitialCondition::dam_break(channel.channel_length, channel.still_water_level));
+            }
+            Command::GaussianHumpScenario => {
+                channel.initial_condition = Some(InitialCondition::gaussian_hump(channel.channel_length, channel.still_water_level));
+            }
+            Command::TsunamiScenario => channel.load_tsunami_preset(),
+            Command::BreakwaterDiffractionScenario => {
+                self.wave_basin.load_diffraction_preset();
+                self.active_tab = PlatformTab::WaveBasin;
+            }
+            Command::HarborAgitationScenario => {
+                self.wave_basin.load_harbor_preset();
+                self.active_tab = PlatformTab::WaveBasin;
+            }
+            Command::NewSeed => channel.random_seed = seed_from_clock(),
+            Command::CopyRunMetadata => {
+                if let Some(report) = channel.run_report() {
+                    ctx.copy_text(report);
+                }
+            }
+            Command::SaveProject => self.save_project(),
+            Command::OpenPreferences => self.show_preferences = true,
+            Command::OpenTab(tab) => self.active_tab = tab,
+        }
+        if matches!(command, Command::WavemakerScenario | Command::DamBreakScenario | Command::GaussianHumpScenario | Command::TsunamiScenario) {
+            self.active_tab = PlatformTab::WaveChannel;
+            self.wave_channel_app.reset_simulation();
+        }
+    }
+
+    /// Write the preferences to the configuration directory
+    fn save_preferences(&self) {
+        if let Some(path) = &self.preferences_path
+            && let Err(message) = self.preferences.save(path)
+        {
+            eprintln!("{}", message);
+        }
+    }
+
+    /// Open a project file in the wave channel and remember it among the recent projects
+    fn open_project(&mut self, path: PathBuf) {
+        match Project::load(&path) {
+            Ok(project) => {
+                self.wave_channel_app.load_project(&project);
+                self.project_path = path.display().to_string();
+                self.preferences.add_recent_project(path);
+                self.save_preferences();
+            }
+            Err(message) => eprintln!("{}", message),
+        }
+    }
+
+    /// Save the wave channel project to the project file and remember it among the recent projects
+    fn save_project(&mut self) {
+        let path = PathBuf::from(&self.project_path);
+        match self.wave_channel_app.project().save(&path) {
+            Ok(()) => {
+                self.preferences.add_recent_project(path);
+                self.save_preferences();
+            }
+            Err(message) => eprintln!("{}", message),
+        }
+    }
+
+    /// State compared by the session recorder
+    fn session_state(&self) -> SessionState {
+        SessionState {
+            project: self.wave_channel_app.project(),
+            running: self.wave_channel_app.simulation_running,
+            simulation_time: self.wave_channel_app.simulation_time,
+            tab: self.active_tab,
+        }
+    }
+
+    /// Apply an event of a replayed session
+    fn apply_session_event(&mut self, event: SessionEvent) {
+        match event {
+            SessionEvent::Configure(project) => self.wave_channel_app.apply_project(&project),
+            SessionEvent::Running(running) => self.wave_channel_app.simulation_running = running,
+            SessionEvent::Reset => self.wave_channel_app.reset_simulation(),
+            SessionEvent::OpenTab(tab) => self.active_tab = tab,
+        }
+    }
+
+    /// Session recording and replay controls
+    fn show_session_bar(&mut self, ui: &mut egui::Ui) {
+        let time = ui.input(|i| i.time);
+        ui.horizontal(|ui| {
+            ui.label("Session:");
+            ui.add(egui::TextEdit::singleline(&mut self.session_path).desired_width(250.0));
+            if let Some((elapsed, events)) = self.session.recording_progress(time) {
+                if ui.button("⏹ Stop Recording").clicked()
+                    && let Some(recording) = self.session.stop_recording()
+                    && let Err(message) = recording.save(&PathBuf::from(&self.session_path))
+                {
+                    eprintln!("{}", message);
+                }
+                ui.colored_label(egui::Color32::RED, format!("⏺ {:.0} s, {} events", elapsed, events));
+            } else if let Some((elapsed, duration)) = self.session.replay_progress(time) {
+                if ui.button("⏹ Stop Replay").clicked() {
+                    self.session.stop_replay();
+                }
+                ui.label(format!("▶ Replaying {:.0} / {:.0} s", elapsed, duration));
+            } else {
+                if ui.button("⏺ Record").on_hover_text("Record every parameter change and run control to the session file").clicked() {
+                    self.session.start_recording(time, self.session_state());
+                }
+                if ui.button("▶ Replay").on_hover_text("Replay the session file as it was recorded").clicked() {
+                    match SessionRecording::load(&PathBuf::from(&self.session_path)) {
+                        Ok(recording) => self.session.start_replay(time, recording),
+                        Err(message) => eprintln!("{}", message),
+                    }
+                }
+            }
+        });
+    }
+
+    /// Remote control server: address, start and stop, and the connected live clients
+    fn show_remote_api_bar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Remote API:");
+            if let Some(api) = &self.remote_api {
+                let address = api.address();
+                let clients = api.clients();
+                let mut token = api.token().to_string();
+                if ui.button("⏹ Stop Server").clicked() {
+                    self.remote_api = None;
+                }
+                ui.label(format!("Serving http://{}, {} live clients, token", address, clients));
+                ui.add(egui::TextEdit::singleline(&mut token).desired_width(260.0))
+                    .on_hover_text("Send it with every request as Authorization: Bearer <token>, or as ?token=<token> when a browser opens the WebSocket");
+            } else {
+                ui.add(egui::TextEdit::singleline(&mut self.preferences.remote_api_address).desired_width(150.0));
+                let hover = "Let dashboards, notebooks or classroom systems drive the wave channel: \
+                             GET /status, /surface, /gauges and /project, POST /run/start, /run/pause and /run/reset, \
+                             PUT /project, and a WebSocket on /ws streaming the live surface. \
+                             Clients present the token shown once the server runs and name it by its IP address or localhost. \
+                             The simulation advances while the wave channel is shown";
+                if ui.button("▶ Start Server").on_hover_text(hover).clicked() {
+                    match RemoteApi::start(&self.preferences.remote_api_address, Some(ui.ctx().clone())) {
+                        Ok(api) => {
+                            self.remote_api = Some(api);
+                            self.remote_api_error = None;
+                            self.save_preferences();
+                        }
+                        Err(message) => self.remote_api_error = Some(message),
+                    }
+                }
+                if let Some(message) = &self.remote_api_error {
+                    ui.colored_label(egui::Color32::RED, message);
+                }
+            }
+        });
+    }
+
+    /// Dock or hide the help pane, remembering the choice in the preferences
+    fn set_help_visible(&mut self, visible: bool) {
+        self.preferences.show_help = visible;
+        self.wave_channel_app.help_mode = visible;
+        self.save_preferences();
+    }
+
+    /// Project file controls: path, open, save and the recently opened projects
+    fn show_project_bar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Project:");
+            ui.add(egui::TextEdit::singleline(&mut self.project_path).desired_width(250.0));
+            if ui.button("📂 Open").clicked() {
+                self.open_project(PathBuf::from(&self.project_path));
+            }
+            if ui.button("💾 Save").clicked() {
+                self.save_project();
+            }
+            let mut opened = None;
+            egui::ComboBox::from_id_salt("recent_projects")
+                .selected_text("Recent")
+                .show_ui(ui, |ui| {
+                    if self.preferences.recent_projects.is_empty() {
+                        ui.label("No recent projects");
+                    }
+                    for path in &self.preferences.recent_projects {
+                        if ui.selectable_label(false, path.display().to_string()).clicked() {
+                            opened = Some(path.clone());
+                        }
+                    }
+                });
+            if let Some(path) = opened {
+                self.open_project(path);
+            }
+            ui.add_space(10.0);
+            if ui.button("⚙ Preferences").clicked() {
+                self.show_preferences = true;
+            }
+        });
+    }
+
+    /// Preferences dialog: theme, units, default scenario and recent projects
+    fn show_preferences_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_preferences;
+        let previous = self.preferences.clone();
+        egui::Window::new("Preferences")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Theme:");
+                    for theme in [Theme::System, Theme::Light, Theme::Dark] {
+                        ui.selectable_value(&mut self.preferences.theme, theme, theme.to_string());
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Units:");
+                    for unit_system in [UnitSystem::Metric, UnitSystem::Imperial] {
+                        ui.selectable_value(&mut self.preferences.unit_system, unit_system, unit_system.to_string());
+                    }
+                });
+                ui.checkbox(&mut self.preferences.show_help, "Docked help pane instead of info popups");
+                ui.checkbox(&mut self.preferences.show_performance_hud, "Performance overlay (frame time, solver rate, time step, memory)");
+                ui.checkbox(&mut self.preferences.show_dashboard, "Dashboard of pinned quantities");
+                self.preferences.repaint_policy.show_editor(ui);
+                ui.horizontal(|ui| {
+                    ui.label("Default Parameters:");
+                    ui.label(if self.preferences.default_project.is_some() { "Custom" } else { "Built-in" });
+                    if ui.button("Use Current Scenario").clicked() {
+                        self.preferences.default_project = Some(self.wave_channel_app.project());
+                    }
+                    if ui.button("Reset").clicked() {
+                        self.preferences.default_project = None;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(format!("Recent Projects: {}", self.preferences.recent_projects.len()));
+                    if ui.button("Clear").clicked() {
+                        self.preferences.recent_projects.clear();
+                    }
+                });
+                ui.separator();
+                ui.collapsing("Plot Style", |ui| {
+                    self.preferences.plot_style.show_editor(ui);
+                });
+                ui.separator();
+                self.show_shortcut_settings(ui);
+                ui.separator();
+                match &self.preferences_path {
+                    Some(path) => ui.label(format!("Stored in {}", path.display())),
+                    None => ui.label("⚠ No configuration directory, preferences are not kept"),
+                };
+            });
+        self.show_preferences = open;
+        if !open {
+            self.capturing_shortcut = None;
+        }
+
+        if self.preferences != previous {
+            self.preferences.apply(ctx);
+            self.wave_channel_app.unit_system = self.preferences.unit_system;
+            self.wave_channel_app.plot_style = self.preferences.plot_style;
+            self.wave_channel_app.repaint_policy = self.preferences.repaint_policy;
+            self.wave_channel_app.help_mode = self.preferences.show_help;
+            self.save_preferences();
+        }
+    }
+
+    /// Keyboard shortcut table, with a button per command to press its new key
+    fn show_shortcut_settings(&mut self, ui: &mut egui::Ui) {
+        ui.strong("Keyboard Shortcuts");
+        if let Some(command) = self.capturing_shortcut {
+            // Escape cancels, any other key with its modifiers becomes the new binding
+            let pressed = ui.input(|i| {
+                i.events.iter().find_map(|event| match event {
+                    egui::Event::Key { key, pressed: true, modifiers, .. } => Some((*key, *modifiers)),
+                    _ => None,
+                })
+            });
+            if let Some((key, modifiers)) = pressed {
+                if key != egui::Key::Escape {
+                    self.preferences.shortcuts.set_binding(command, Some(KeyBinding::from_modifiers(key, modifiers)));
+                }
+                self.capturing_shortcut = None;
+            }
+        }
+
+        egui::Grid::new("shortcuts").num_columns(3).striped(true).show(ui, |ui| {
+            for command in Command::all() {
+                ui.label(command.label());
+                let text = if self.capturing_shortcut == Some(command) {
+                    "Press a key…".to_string()
+                } else {
+                    self.preferences.shortcuts.binding(command).map_or("—".to_string(), |binding| binding.to_string())
+                };
+                if ui.button(text).on_hover_text("Click, then press the new shortcut").clicked() {
+                    self.capturing_shortcut = Some(command);
+                }
+                if ui.small_button("✖").on_hover_text("Remove shortcut").clicked() {
+                    self.preferences.shortcuts.set_binding(command, None);
+                }
+                ui.end_row();
+            }
+        });
+        if ui.add_enabled(!self.preferences.shortcuts.is_default(), egui::Button::new("Restore Default Shortcuts")).clicked() {
+            self.preferences.shortcuts.reset();
+        }
+    }
+
+    /// Progress of the studies running in the background
+    fn running_studies(&self) -> Vec<StudyProgress> {
+        let studies = [
+            ("Monte Carlo", self.monte_carlo.is_running(), self.monte_carlo.progress()),
+            ("Wave Climate", self.wave_climate.is_running(), self.wave_climate.progress()),
+            ("Transects", self.transects.is_running(), self.transects.progress()),
+        ];
+        studies.into_iter().filter(|(_, running, _)| *running).map(|(name, _, fraction)| StudyProgress { name, fraction }).collect()
+    }
+
+    /// Window of the quantities pinned to the dashboard, saving the preferences when it is closed
+    fn show_dashboard_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.preferences.show_dashboard;
+        let studies = self.running_studies();
+        egui::Window::new("Dashboard").open(&mut open).default_width(520.0).show(ctx, |ui| {
+            self.dashboard.show(ui, &mut self.wave_channel_app, &studies);
+        });
+        if !open {
+            self.preferences.show_dashboard = false;
+            self.save_preferences();
+        }
+    }
+
+    /// Offer to restore the project autosaved by a session that did not exit cleanly
+    fn show_restore_prompt(&mut self, ctx: &egui::Context) {
+        let Some(project) = &self.restore_prompt else {
+            return;
+        };
+        let mut decision = None;
+        egui::Window::new("Restore Previous Session")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("The previous session did not exit cleanly. Restore the scenario it was working on?");
+                ui.label(format!(
+                    "{:.1} m channel, {:.2} m depth, H = {:.2} m, T = {:.1} s, {} gauges",
+                    project.channel_length,
+                    project.still_water_level,
+                    project.wave_height,
+                    project.wave_period,
+                    project.gauge_positions.len()
+                ));
+                ui.horizontal(|ui| {
+                    if ui.button("Restore").clicked() {
+                        decision = Some(true);
+                    }
+                    if ui.button("Discard").clicked() {
+                        decision = Some(false);
+                    }
+                });
+            });
+
+        match decision {
+            Some(true) => {
+                self.wave_channel_app.load_project(project);
+                self.restore_prompt = None;
+            }
+            Some(false) => {
+                self.restore_prompt = None;
+                if let Err(message) = self.autosave.discard() {
+                    eprintln!("{}", message);
+                }
+            }
+            None => {}
         }
     }
 }
 
 impl eframe::App for PlatformApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let frame_start = std::time::Instant::now();
+        // Keep the crash file of the previous session until the user answers the prompt
+        if self.restore_prompt.is_some() {
+            self.show_restore_prompt(ctx);
+        } else if let Err(message) = self.autosave.save_if_due(ctx.input(|i| i.time), &self.wave_channel_app.project()) {
+            eprintln!("{}", message);
+        }
+
+        if ctx.input_mut(|i| i.consume_shortcut(&egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::P))) {
+            self.command_palette.open();
+        }
+        clipboard::copy_plot_images(ctx);
+        let time = ctx.input(|i| i.time);
+        for event in self.session.due_events(time) {
+            self.apply_session_event(event);
+        }
+        if self.session.is_replaying() {
+            self.preferences.repaint_policy.request_frame(ctx);
+        }
+        if let Some(api) = &mut self.remote_api {
+            api.poll(&mut self.wave_channel_app, time);
+        }
+
+        // Shortcuts are off while the palette or the shortcut settings take the keyboard
+        let command = self.command_palette.show(ctx, &self.preferences.shortcuts).or_else(|| {
+            (!self.command_palette.open && self.capturing_shortcut.is_none())
+                .then(|| self.preferences.shortcuts.pressed(ctx))
+                .flatten()
+        });
+        if let Some(command) = command {
+            self.execute(command, ctx);
+        }
+        if self.show_preferences {
+            self.show_preferences_window(ctx);
+        }
+        if self.preferences.show_dashboard {
+            self.show_dashboard_window(ctx);
+        }
+        if let Some(rect) = ctx.input(|i| i.viewport().inner_rect) {
+            self.preferences.window_size = [rect.width(), rect.height()];
+        }
+
+        if self.preferences.show_help {
+            egui::SidePanel::right("help_pane")
+                .resizable(true)
+                .default_width(320.0)
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.strong("Help");
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.small_button("✖").clicked() {
+                                self.set_help_visible(false);
+                            }
+                            if ui.small_button("Overview").clicked() {
+                                self.wave_channel_app.help_topic = None;
+                            }
+                        });
+                    });
+                    // Controls of the wave channel only feed the pane while it is the selected tool
+                    let topic = match self.active_tab {
+                        PlatformTab::WaveChannel => self.wave_channel_app.help_topic.as_ref(),
+                        _ => None,
+                    };
+                    self.help_pane.show(ui, ctx, self.active_tab, topic, &mut self.equation_renderer);
+                });
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
             egui::ScrollArea::vertical()
                 .auto_shrink([false; 2])
                 .show(ui, |ui| {
-                    ui.heading("Coastal Engineering Platform");
+                    ui.horizontal(|ui| {
+                        ui.heading("Coastal Engineering Platform");
+                        if ui.small_button("🔍 Commands (Ctrl+P)").clicked() {
+                            self.command_palette.open();
+                        }
+                        if ui.selectable_label(self.preferences.show_help, "❓ Help").clicked() {
+                            self.set_help_visible(!self.preferences.show_help);
+                        }
+                    });
+                    self.show_project_bar(ui);
+                    self.show_session_bar(ui);
+                    self.show_remote_api_bar(ui);
+                    ui.horizontal(|ui| {
+                        for tab in PlatformTab::ALL {
+                            ui.selectable_value(&mut self.active_tab, tab, tab.to_string());
+                        }
+                    });
                     ui.separator();
 
-                    self.wave_channel_app
-                        .show(ui, ctx, &mut self.equation_renderer);
+                    match self.active_tab {
+                        PlatformTab::WaveChannel => {
+                            self.wave_channel_app
+                                .show(ui, ctx, &mut self.equation_renderer);
+                        }
+                        PlatformTab::DispersionExplorer => {
+                            let channel = &mut self.wave_channel_app;
+                            self.dispersion_explorer
+                                .show(ui, &mut channel.still_water_level, &mut channel.wave_period);
+                        }
+                        PlatformTab::Comparison => {
+                            self.comparison.show(ui, &mut self.wave_channel_app);
+                        }
+                        PlatformTab::MonteCarlo => {
+                            self.monte_carlo.show(ui, &self.wave_channel_app);
+                        }
+                        PlatformTab::DesignOptimizer => {
+                            self.design_optimizer.show(ui, &mut self.wave_channel_app);
+                        }
+                        PlatformTab::WaveClimate => {
+                            self.wave_climate.show(ui, &mut self.wave_channel_app);
+                        }
+                        PlatformTab::Transects => {
+                            self.transects.show(ui, &mut self.wave_channel_app);
+                        }
+                        PlatformTab::WaveBasin => {
+                            let channel = &self.wave_channel_app;
+                            self.wave_basin.show(ui, channel.plot_style, &channel.repaint_policy);
+                        }
+                        PlatformTab::Exercises => {
+                            self.exercises.show(ui, &self.wave_channel_app.project());
+                        }
+                    }
                 });
         });
+
+        if self.session.is_recording() {
+            self.session.record(time, self.session_state());
+        }
+
+        self.alert_toasts.show(ctx, &self.wave_channel_app.alert_log);
+        let channel = &mut self.wave_channel_app;
+        channel.performance.record_frame(ctx.input(|i| i.unstable_dt) as f64, frame_start.elapsed().as_secs_f64());
+        if self.preferences.show_performance_hud {
+            channel.performance.show_overlay(ctx, channel.stored_bytes());
+        }
+    }
+
+    /// A clean exit leaves no autosave behind, so no restore is offered at the next start
+    /// The preferences are saved with the final window size
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        if let Err(message) = self.autosave.discard() {
+            eprintln!("{}", message);
+        }
+        self.save_preferences();
     }
 }
@@ -0,0 +1,148 @@
+use super::dashboard::DashboardQuantity;
+use super::wave_channel::WaveChannelApp;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Time a triggered alert stays on screen [s]
+pub const TOAST_DURATION: f64 = 6.0;
+
+/// Side of the threshold on which an alert triggers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlertCondition {
+    /// The quantity rises above the threshold
+    Above,
+    /// The quantity falls below the threshold
+    Below,
+}
+
+impl AlertCondition {
+    pub const ALL: [AlertCondition; 2] = [AlertCondition::Above, AlertCondition::Below];
+
+    /// Check if a value is on the alerting side of the threshold
+    pub fn holds(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            AlertCondition::Above => value > threshold,
+            AlertCondition::Below => value < threshold,
+        }
+    }
+}
+
+impl std::fmt::Display for AlertCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlertCondition::Above => write!(f, ">"),
+            AlertCondition::Below => write!(f, "<"),
+        }
+    }
+}
+
+/// Rule of the user raising an alert when a quantity of the wave channel crosses a threshold during a run
+///
+/// The rule is checked after every step. It triggers when its condition starts to hold and is
+/// rearmed once the condition no longer holds, so a quantity staying beyond the threshold raises a
+/// single alert instead of one per step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub quantity: DashboardQuantity,
+    pub condition: AlertCondition,
+    pub threshold: f64, // In the unit of the quantity
+    pub pause: bool,    // Pause the simulation when the alert triggers
+    #[serde(skip)]
+    active: bool,       // Whether the condition held at the last check
+}
+
+impl AlertRule {
+    pub fn new(quantity: DashboardQuantity, condition: AlertCondition, threshold: f64, pause: bool) -> Self {
+        Self { quantity, condition, threshold, pause, active: false }
+    }
+
+    /// Rule as shown to the user, e.g. "Overtopping q > 10.000 l/s/m"
+    pub fn describe(&self, channel: &WaveChannelApp) -> String {
+        format!("{} {} {:.3} {}", self.quantity.label(), self.condition, self.threshold, self.quantity.unit(channel)).trim_end().to_string()
+    }
+
+    /// Value of the quantity if the condition started to hold since the last check
+    /// Quantities not available yet leave the rule as it was
+    pub fn check(&mut self, channel: &WaveChannelApp) -> Option<f64> {
+        let value = self.quantity.value(channel).ok()?;
+        let holds = self.condition.holds(value, self.threshold);
+        let triggered = holds && !self.active;
+        self.active = holds;
+        triggered.then_some(value)
+    }
+
+    /// Let the rule trigger again, when the run restarts
+    pub fn rearm(&mut self) {
+        self.active = false;
+    }
+}
+
+/// Alert triggered during a run
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertEvent {
+    pub time: f64,    // Simulation time of the alert [s]
+    pub rule: String, // Description of the rule
+    pub value: f64,   // Value of the quantity when the rule triggered
+    pub unit: String, // Unit of the value
+    pub paused: bool, // Whether the alert paused the simulation
+}
+
+impl std::fmt::Display for AlertEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "t = {:.1} s: {} (reached {:.3} {})", self.time, self.rule, self.value, self.unit)?;
+        if self.paused {
+            write!(f, ", simulation paused")?;
+        }
+        Ok(())
+    }
+}
+
+/// Notifications of the alerts triggered during the run, in the bottom right corner of the window
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AlertToasts {
+    shown: usize,               // Alerts of the log already notified
+    toasts: Vec<(String, f64)>, // Notifications on screen and when they expire [s]
+}
+
+impl AlertToasts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Notify the alerts added to the log since the last frame, and show those not expired yet
+    pub fn show(&mut self, ctx: &egui::Context, log: &[AlertEvent]) {
+        let time = ctx.input(|i| i.time);
+        // A reset clears the log, whose alerts are then all new
+        if log.len() < self.shown {
+            self.shown = 0;
+        }
+        self.toasts.extend(log[self.shown..].iter().map(|event| (event.to_string(), time + TOAST_DURATION)));
+        self.shown = log.len();
+        self.toasts.retain(|(_, expiry)| *expiry > time);
+        let Some(next_expiry) = self.toasts.iter().map(|(_, expiry)| *expiry).reduce(f64::min) else {
+            return;
+        };
+
+        egui::Area::new(egui::Id::new("alert_toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-10.0, -10.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                let mut dismissed = None;
+                for (i, (message, _)) in self.toasts.iter().enumerate() {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.colored_label(egui::Color32::from_rgb(200, 80, 0), format!("⚠ {}", message));
+                            if ui.small_button("✖").clicked() {
+                                dismissed = Some(i);
+                            }
+                        });
+                    });
+                }
+                if let Some(i) = dismissed {
+                    self.toasts.remove(i);
+                }
+            });
+        ctx.request_repaint_after(Duration::from_secs_f64((next_expiry - time).max(0.0)));
+    }
+}
@@ -0,0 +1,133 @@
+use eframe::egui;
+use egui_plot::{Arrows, LineStyle, PlotPoint, PlotUi, Text, VLine};
+use serde::{Deserialize, Serialize};
+
+/// Plot an annotation is drawn on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnnotatedPlot {
+    /// Channel view, positions along the channel [m] and elevations [m]
+    Channel,
+    /// Gauge records, times [s] and surface elevations [m]
+    GaugeRecords,
+}
+
+impl std::fmt::Display for AnnotatedPlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnnotatedPlot::Channel => write!(f, "Channel"),
+            AnnotatedPlot::GaugeRecords => write!(f, "Gauge Records"),
+        }
+    }
+}
+
+impl AnnotatedPlot {
+    /// Name and unit of the horizontal axis
+    pub fn axis(&self) -> (&'static str, &'static str) {
+        match self {
+            AnnotatedPlot::Channel => ("x", "m"),
+            AnnotatedPlot::GaugeRecords => ("t", "s"),
+        }
+    }
+}
+
+/// How an annotation is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AnnotationShape {
+    /// Text centered on the point
+    Label,
+    /// Text at the point with an arrow to the tip
+    Arrow { tip: [f64; 2] },
+    /// Vertical line at the horizontal coordinate of the point, with the text at its top
+    Marker,
+}
+
+impl std::fmt::Display for AnnotationShape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnnotationShape::Label => write!(f, "Label"),
+            AnnotationShape::Arrow { .. } => write!(f, "Arrow"),
+            AnnotationShape::Marker => write!(f, "Marker"),
+        }
+    }
+}
+
+/// Note of the user on a plot, saved with the project
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    pub plot: AnnotatedPlot,
+    pub shape: AnnotationShape,
+    pub position: [f64; 2], // Point of the text, in the units of the plot
+    pub text: String,
+}
+
+impl Annotation {
+    /// Text at a point of a plot
+    pub fn label(plot: AnnotatedPlot, position: [f64; 2], text: &str) -> Self {
+        Self {
+            plot,
+            shape: AnnotationShape::Label,
+            position,
+            text: text.to_string(),
+        }
+    }
+
+    /// Text at a point with an arrow to another
+    pub fn arrow(plot: AnnotatedPlot, position: [f64; 2], tip: [f64; 2], text: &str) -> Self {
+        Self {
+            plot,
+            shape: AnnotationShape::Arrow { tip },
+            position,
+            text: text.to_string(),
+        }
+    }
+
+    /// Vertical marker at a position along the channel or a time
+    pub fn marker(plot: AnnotatedPlot, at: f64, text: &str) -> Self {
+        Self {
+            plot,
+            shape: AnnotationShape::Marker,
+            position: [at, 0.0],
+            text: text.to_string(),
+        }
+    }
+
+    /// One line describing the annotation, for reports
+    pub fn describe(&self) -> String {
+        let (axis, unit) = self.plot.axis();
+        let [x, y] = self.position;
+        let place = match self.shape {
+            AnnotationShape::Label => format!("label at {} = {:.2} {}, z = {:.3} m", axis, x, unit, y),
+            AnnotationShape::Arrow { tip } => format!("arrow from {} = {:.2} {}, z = {:.3} m to {} = {:.2} {}, z = {:.3} m", axis, x, unit, y, axis, tip[0], unit, tip[1]),
+            AnnotationShape::Marker => format!("marker at {} = {:.2} {}", axis, x, unit),
+        };
+        format!("{}: {} \"{}\"", self.plot, place, self.text)
+    }
+
+    /// Draw the annotation in a plot
+    pub fn draw(&self, plot_ui: &mut PlotUi, color: egui::Color32) {
+        let [x, y] = self.position;
+        match self.shape {
+            AnnotationShape::Label => {
+                plot_ui.text(Text::new(PlotPoint::new(x, y), &self.text).color(color));
+            }
+            AnnotationShape::Arrow { tip } => {
+                plot_ui.arrows(Arrows::new(vec![[x, y]], vec![tip]).color(color).tip_length(10.0));
+                // Text on the side away from the tip
+                let anchor = if tip[1] < y { egui::Align2::CENTER_BOTTOM } else { egui::Align2::CENTER_TOP };
+                plot_ui.text(Text::new(PlotPoint::new(x, y), &self.text).color(color).anchor(anchor));
+            }
+            AnnotationShape::Marker => {
+                plot_ui.vline(VLine::new(x).color(color).style(LineStyle::dashed_loose()));
+                let top = plot_ui.plot_bounds().max()[1];
+                plot_ui.text(Text::new(PlotPoint::new(x, top), &self.text).color(color).anchor(egui::Align2::LEFT_TOP));
+            }
+        }
+    }
+}
+
+/// Draw the annotations of one plot
+pub fn draw_annotations(plot_ui: &mut PlotUi, annotations: &[Annotation], plot: AnnotatedPlot, color: egui::Color32) {
+    for annotation in annotations.iter().filter(|annotation| annotation.plot == plot) {
+        annotation.draw(plot_ui, color);
+    }
+}
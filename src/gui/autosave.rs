@@ -0,0 +1,90 @@
+use super::project::Project;
+use std::path::{Path, PathBuf};
+
+/// Seconds between two autosaves
+pub const AUTOSAVE_INTERVAL: f64 = 30.0;
+
+/// Periodic copy of the current project, left behind when the application does not exit cleanly
+///
+/// The project is written to a file in the temporary directory every autosave interval, skipping
+/// unchanged projects. A clean exit removes the file, so finding it on startup means the previous
+/// session crashed or the machine died, and the user is offered to restore it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Autosave {
+    path: PathBuf,
+    interval: f64,
+    last_save: Option<f64>,
+    last_content: Option<String>,
+}
+
+impl Autosave {
+    /// Autosave to a file, every autosave interval
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            interval: AUTOSAVE_INTERVAL,
+            last_save: None,
+            last_content: None,
+        }
+    }
+
+    /// Autosave to the platform file in the temporary directory
+    pub fn temporary() -> Self {
+        Self::new(std::env::temp_dir().join("coastal_engineering_platform_autosave.json"))
+    }
+
+    /// Set seconds between two autosaves
+    pub fn with_interval(mut self, interval: f64) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Get autosave file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Project left by a previous session, None if it exited cleanly or the file is unreadable
+    pub fn load(&self) -> Option<Project> {
+        let content = std::fs::read_to_string(&self.path).ok()?;
+        Project::from_json(&content).ok()
+    }
+
+    /// Write the project if the interval elapsed since the last save and it changed
+    /// Time is any monotonic clock in seconds, such as the egui input time
+    /// Returns whether the project was written
+    pub fn save_if_due(&mut self, time: f64, project: &Project) -> Result<bool, String> {
+        if self.last_save.is_some_and(|last| time - last < self.interval) {
+            return Ok(false);
+        }
+        self.last_save = Some(time);
+        self.save(project)
+    }
+
+    /// Write the project now, unless it is unchanged since the last save
+    pub fn save(&mut self, project: &Project) -> Result<bool, String> {
+        let content = project.to_json()?;
+        if self.last_content.as_ref() == Some(&content) {
+            return Ok(false);
+        }
+        if let Some(directory) = self.path.parent() {
+            std::fs::create_dir_all(directory).map_err(|e| format!("Cannot create autosave directory: {}", e))?;
+        }
+        // Write a sibling file first, so a crash while saving never leaves a truncated autosave
+        let partial = self.path.with_extension("json.partial");
+        std::fs::write(&partial, &content).map_err(|e| format!("Cannot write autosave: {}", e))?;
+        std::fs::rename(&partial, &self.path).map_err(|e| format!("Cannot write autosave: {}", e))?;
+        self.last_content = Some(content);
+        Ok(true)
+    }
+
+    /// Remove the autosave, on clean exit or when the user declines to restore it
+    pub fn discard(&mut self) -> Result<(), String> {
+        self.last_content = None;
+        match std::fs::remove_file(&self.path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("Cannot remove autosave: {}", e)),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+/// Work running on a background thread, sending its results as they complete
+///
+/// The work gets a cancel flag to check between results; the user interface polls the results
+/// every frame so it stays responsive while many simulations run.
+pub struct BackgroundRun<T> {
+    receiver: Receiver<T>,
+    cancel: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+impl<T: Send + 'static> BackgroundRun<T> {
+    /// Start work on a new thread
+    pub fn spawn(work: impl FnOnce(&AtomicBool, &Sender<T>) + Send + 'static) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&cancel);
+        let handle = std::thread::spawn(move || work(&flag, &sender));
+        Self { receiver, cancel, handle }
+    }
+
+    /// Results completed since the last poll, and whether the work is finished
+    pub fn poll(&self) -> (Vec<T>, bool) {
+        // The thread finishes before the channel is drained for the last time
+        let finished = self.handle.is_finished();
+        (self.receiver.try_iter().collect(), finished)
+    }
+
+    /// Stop the work and wait for the thread, returning the results completed in the meantime
+    pub fn cancel(self) -> Vec<T> {
+        self.cancel.store(true, Ordering::Relaxed);
+        let _ = self.handle.join();
+        self.receiver.try_iter().collect()
+    }
+}
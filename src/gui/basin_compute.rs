@@ -0,0 +1,98 @@
+use crate::solver::{BasinSolver, BufferReader, sub_step, triple_buffer};
+use crate::waves::WaveForcing;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread::JoinHandle;
+
+/// State of the basin published by the compute thread after every step
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BasinState {
+    pub time: f64,        // Simulation time [s]
+    pub values: Vec<f64>, // η, u and v at the cell centers (see `BasinFrame`)
+    pub steps: usize,     // Time steps taken since the thread started
+}
+
+/// Solver of a basin run stepped on its own thread, towards a time the interface moves on every frame
+///
+/// The thread publishes its state after each step through a triple buffer and the interface reads
+/// the latest one, so a frame never waits for a step to finish and the solver never waits for a
+/// frame to be drawn, even when the steps are much shorter than a frame.
+pub struct BasinCompute {
+    state: BufferReader<BasinState>,
+    target: Arc<AtomicU64>, // Time to step the solver up to [s], as bits
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<(BasinSolver, WaveForcing)>>,
+}
+
+impl BasinCompute {
+    /// Move a solver and its wavemaker signal to a new thread, waiting for a target time
+    pub fn spawn(mut solver: BasinSolver, mut forcing: WaveForcing) -> Self {
+        let initial = BasinState { time: solver.time(), values: solver.snapshot(), steps: 0 };
+        let (mut writer, state) = triple_buffer(initial);
+        let target = Arc::new(AtomicU64::new(solver.time().to_bits()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (thread_target, thread_stop) = (Arc::clone(&target), Arc::clone(&stop));
+        let handle = std::thread::spawn(move || {
+            let mut steps = 0;
+            while !thread_stop.load(Ordering::Acquire) {
+                let target = f64::from_bits(thread_target.load(Ordering::Acquire));
+                let remaining = target - solver.time();
+                if remaining <= 1e-12 * target.max(1.0) {
+                    // Woken by a new target or the stop, checked again above
+                    std::thread::park();
+                    continue;
+                }
+                // A basin that blew up has no stable step left: stop rather than spin without advancing
+                let Some(dt) = sub_step(remaining, solver.stable_time_step()) else {
+                    break;
+                };
+                solver.step(dt, &mut forcing);
+                steps += 1;
+                let state = writer.input();
+                state.time = solver.time();
+                state.steps = steps;
+                solver.fill_snapshot(&mut state.values);
+                writer.publish();
+            }
+            (solver, forcing)
+        });
+        Self { state, target, stop, handle: Some(handle) }
+    }
+
+    /// Let the solver step up to a time [s]
+    pub fn advance_to(&self, time: f64) {
+        self.target.store(time.to_bits(), Ordering::Release);
+        self.wake();
+    }
+
+    /// Take the latest state published by the thread, returning whether it is newer than the current one
+    pub fn update(&mut self) -> bool {
+        self.state.update()
+    }
+
+    /// State taken at the last update
+    pub fn current(&self) -> &BasinState {
+        self.state.current()
+    }
+
+    /// Stop the thread after its current step and take the solver and its signal back, None if the thread panicked
+    pub fn finish(mut self) -> Option<(BasinSolver, WaveForcing)> {
+        self.stop.store(true, Ordering::Release);
+        self.wake();
+        self.handle.take()?.join().ok()
+    }
+
+    fn wake(&self) {
+        if let Some(handle) = &self.handle {
+            handle.thread().unpark();
+        }
+    }
+}
+
+impl Drop for BasinCompute {
+    /// A discarded run stops its thread without waiting for it
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        self.wake();
+    }
+}
@@ -0,0 +1,86 @@
+use eframe::egui;
+
+/// Screenshot requested to copy the image of a plot, with the area of the plot on the screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PlotScreenshot(egui::Rect);
+
+/// Value of a computed value line "Name (symbol): value unit", with its unit
+/// Lines without a name are returned whole
+pub fn value_of(line: &str) -> &str {
+    line.rsplit_once(": ").map_or(line, |(_, value)| value).trim()
+}
+
+/// Lines of a block of computed values, each copied from its context menu and all of them together
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CopyableValues {
+    pub lines: Vec<String>,
+}
+
+impl CopyableValues {
+    /// Show a computed value line, with a context menu copying its value or the whole line
+    pub fn label(&mut self, ui: &mut egui::Ui, line: String) -> egui::Response {
+        let response = ui.add(egui::Label::new(&line).sense(egui::Sense::click()));
+        value_menu(&response, &line);
+        self.lines.push(line);
+        response
+    }
+
+    /// Every line of the block, one per row
+    pub fn to_text(&self) -> String {
+        self.lines.iter().map(|line| format!("{}\n", line)).collect()
+    }
+}
+
+/// Context menu copying the value of a computed value line with its unit, or the whole line
+pub fn value_menu(response: &egui::Response, line: &str) {
+    response.context_menu(|ui| {
+        if ui.button("📋 Copy Value").clicked() {
+            ui.ctx().copy_text(value_of(line).to_string());
+            ui.close_menu();
+        }
+        if ui.button("📋 Copy Line").clicked() {
+            ui.ctx().copy_text(line.to_string());
+            ui.close_menu();
+        }
+    });
+}
+
+/// Context menu copying the image of a plot, taken from a screenshot of the next frame
+pub fn plot_menu(response: &egui::Response) {
+    response.context_menu(|ui| {
+        if ui.button("📋 Copy Plot Image").clicked() {
+            let screenshot = PlotScreenshot(response.rect);
+            ui.ctx().send_viewport_cmd(egui::ViewportCommand::Screenshot(egui::UserData::new(screenshot)));
+            ui.close_menu();
+        }
+    });
+}
+
+/// Copy the plot images of the screenshots received this frame
+pub fn copy_plot_images(ctx: &egui::Context) {
+    let images: Vec<egui::ColorImage> = ctx.input(|input| {
+        input
+            .raw
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                egui::Event::Screenshot { user_data, image, .. } => {
+                    let PlotScreenshot(rect) = *user_data.data.as_ref()?.downcast_ref::<PlotScreenshot>()?;
+                    plot_image(image, rect, input.pixels_per_point)
+                }
+                _ => None,
+            })
+            .collect()
+    });
+    for image in images {
+        ctx.copy_image(image);
+    }
+}
+
+/// Area of a plot in a screenshot of the window, clipped to the window, None if it is off the window
+pub fn plot_image(screenshot: &egui::ColorImage, rect: egui::Rect, pixels_per_point: f32) -> Option<egui::ColorImage> {
+    let [width, height] = screenshot.size;
+    let window = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(width as f32, height as f32) / pixels_per_point);
+    let visible = rect.intersect(window);
+    visible.is_positive().then(|| screenshot.region(&visible, Some(pixels_per_point)))
+}
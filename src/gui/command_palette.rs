@@ -0,0 +1,206 @@
+use super::PlatformTab;
+use super::shortcuts::Shortcuts;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+/// Action that can be run from the command palette
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Command {
+    PlayPause,
+    Reset,
+    AddGauge,
+    GaugeArray,
+    ToggleLinearOverlay,
+    ToggleCrestTracking,
+    ToggleHelp,
+    TogglePerformanceHud,
+    ToggleDashboard,
+    WavemakerScenario,
+    DamBreakScenario,
+    GaussianHumpScenario,
+    TsunamiScenario,
+    BreakwaterDiffractionScenario,
+    HarborAgitationScenario,
+    NewSeed,
+    CopyRunMetadata,
+    SaveProject,
+    OpenPreferences,
+    OpenTab(PlatformTab),
+}
+
+impl Command {
+    /// Every command, in the order listed when the palette opens
+    pub fn all() -> Vec<Command> {
+        vec![
+            Command::PlayPause,
+            Command::Reset,
+            Command::AddGauge,
+            Command::GaugeArray,
+            Command::ToggleLinearOverlay,
+            Command::ToggleCrestTracking,
+            Command::ToggleHelp,
+            Command::TogglePerformanceHud,
+            Command::ToggleDashboard,
+            Command::WavemakerScenario,
+            Command::DamBreakScenario,
+            Command::GaussianHumpScenario,
+            Command::TsunamiScenario,
+            Command::BreakwaterDiffractionScenario,
+            Command::HarborAgitationScenario,
+            Command::NewSeed,
+            Command::CopyRunMetadata,
+            Command::SaveProject,
+            Command::OpenPreferences,
+        ]
+        .into_iter()
+        .chain(PlatformTab::ALL.map(Command::OpenTab))
+        .collect()
+    }
+
+    /// Name shown in the palette and searched
+    pub fn label(&self) -> String {
+        match self {
+            Command::PlayPause => "Simulation: Play / Pause".to_string(),
+            Command::Reset => "Simulation: Reset".to_string(),
+            Command::AddGauge => "Gauges: Add Gauge".to_string(),
+            Command::GaugeArray => "Gauges: Place Gauge Array".to_string(),
+            Command::ToggleLinearOverlay => "View: Toggle Linear Theory Overlay".to_string(),
+            Command::ToggleCrestTracking => "View: Toggle Wave Crest Tracking".to_string(),
+            Command::ToggleHelp => "View: Toggle Help Pane".to_string(),
+            Command::TogglePerformanceHud => "View: Toggle Performance Overlay".to_string(),
+            Command::ToggleDashboard => "View: Toggle Dashboard".to_string(),
+            Command::WavemakerScenario => "Preset: Wavemaker".to_string(),
+            Command::DamBreakScenario => "Preset: Dam Break".to_string(),
+            Command::GaussianHumpScenario => "Preset: Gaussian Hump".to_string(),
+            Command::TsunamiScenario => "Preset: Tsunami Inundation".to_string(),
+            Command::BreakwaterDiffractionScenario => "Preset: Breakwater Diffraction".to_string(),
+            Command::HarborAgitationScenario => "Preset: Harbor Agitation".to_string(),
+            Command::NewSeed => "Run: Draw New Random Seed".to_string(),
+            Command::CopyRunMetadata => "Export: Copy Run Metadata".to_string(),
+            Command::SaveProject => "Project: Save".to_string(),
+            Command::OpenPreferences => "Settings: Preferences".to_string(),
+            Command::OpenTab(tab) => format!("Open Tab: {}", tab),
+        }
+    }
+}
+
+/// Score of a fuzzy match of the query against a text, None if the query is not a subsequence of it
+///
+/// Matching is case-insensitive. Every matched character scores, with bonuses for characters
+/// that follow the previous match directly or start a word, so "dam" ranks "Preset: Dam Break" first.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+    for character in query.to_lowercase().chars().filter(|character| !character.is_whitespace()) {
+        let found = (position..text.len()).find(|&i| text[i] == character)?;
+        score += 1;
+        if previous.is_some_and(|previous| found == previous + 1) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+/// Ctrl+P overlay listing every command, filtered by fuzzy search
+#[derive(Debug, Clone, Default)]
+pub struct CommandPalette {
+    pub open: bool,
+    pub query: String,
+    selected: usize, // Index of the highlighted command among the matches
+}
+
+impl CommandPalette {
+    /// Create new closed palette
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open the palette with an empty query
+    pub fn open(&mut self) {
+        self.open = true;
+        self.query.clear();
+        self.selected = 0;
+    }
+
+    /// Commands matching the query, best match first, ties in listing order
+    pub fn matches(&self) -> Vec<Command> {
+        let mut scored: Vec<(i32, Command)> = Command::all()
+            .into_iter()
+            .filter_map(|command| fuzzy_score(&self.query, &command.label()).map(|score| (score, command)))
+            .collect();
+        scored.sort_by_key(|(score, _)| -score);
+        scored.into_iter().map(|(_, command)| command).collect()
+    }
+
+    /// Show the palette if open, with the shortcut of each command, returning the command chosen with Enter or a click
+    pub fn show(&mut self, ctx: &egui::Context, shortcuts: &Shortcuts) -> Option<Command> {
+        if !self.open {
+            return None;
+        }
+        let matches = self.matches();
+        let (up, down, enter, escape) = ctx.input(|i| {
+            (
+                i.key_pressed(egui::Key::ArrowUp),
+                i.key_pressed(egui::Key::ArrowDown),
+                i.key_pressed(egui::Key::Enter),
+                i.key_pressed(egui::Key::Escape),
+            )
+        });
+        if down {
+            self.selected += 1;
+        }
+        if up {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        self.selected = self.selected.min(matches.len().saturating_sub(1));
+
+        let mut chosen = None;
+        egui::Window::new("Command Palette")
+            .title_bar(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 60.0])
+            .fixed_size([420.0, 0.0])
+            .show(ctx, |ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.query)
+                        .hint_text("Type a command…")
+                        .desired_width(f32::INFINITY),
+                );
+                response.request_focus();
+                if response.changed() {
+                    self.selected = 0;
+                }
+                ui.separator();
+                if matches.is_empty() {
+                    ui.label("No matching command");
+                }
+                for (i, command) in matches.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui.selectable_label(i == self.selected, command.label()).clicked() {
+                            chosen = Some(*command);
+                        }
+                        if let Some(binding) = shortcuts.binding(*command) {
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                ui.weak(binding.to_string());
+                            });
+                        }
+                    });
+                }
+            });
+
+        if enter {
+            chosen = chosen.or_else(|| matches.get(self.selected).copied());
+        }
+        if chosen.is_some() || escape {
+            self.open = false;
+        }
+        chosen
+    }
+}
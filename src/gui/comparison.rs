@@ -0,0 +1,354 @@
+use super::plot_style::PlotStyle;
+use super::wave_channel::{PropagationModel, WaveChannelApp};
+use crate::analysis::WaveGauge;
+use crate::analysis::spectral::significant_wave_height;
+use crate::solver::RightBoundary;
+use crate::waves::DispersionMode;
+use eframe::egui;
+use egui_plot::{HLine, Line, LineStyle, Plot, PlotPoints};
+
+/// Time step of both cases per frame [s]
+const COMPARISON_TIME_STEP: f64 = 0.05;
+
+/// Two configurations of the wave channel run in lockstep and shown side by side
+///
+/// Case A is the wave channel itself, case B a copy of its scenario that can be modified independently.
+/// Both cases advance with the same time step, so their surfaces and gauge spectra can be compared at equal times.
+pub struct ComparisonApp {
+    pub case_b: Option<WaveChannelApp>, // Second configuration, cloned from the wave channel
+    pub comparison_gauge: usize,        // Gauge whose spectra are compared
+}
+
+impl Default for ComparisonApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ComparisonApp {
+    pub fn new() -> Self {
+        Self {
+            case_b: None,  // Nothing to compare until the scenario is cloned
+            comparison_gauge: 0,
+        }
+    }
+
+    /// Copy the scenario of case A into case B, restarting both from rest
+    pub fn clone_case(&mut self, case_a: &mut WaveChannelApp) {
+        case_a.reset_simulation();
+        self.case_b = Some(case_a.duplicate_scenario());
+        self.comparison_gauge = 0;
+    }
+
+    /// Whether the comparison is running
+    pub fn is_running(&self, case_a: &WaveChannelApp) -> bool {
+        case_a.simulation_running && self.case_b.as_ref().is_some_and(|case_b| case_b.simulation_running)
+    }
+
+    /// Start or resume both cases
+    pub fn start(&mut self, case_a: &mut WaveChannelApp) {
+        if let Some(case_b) = self.case_b.as_mut() {
+            case_a.start_simulation();
+            case_b.start_simulation();
+        }
+    }
+
+    /// Pause both cases
+    pub fn pause(&mut self, case_a: &mut WaveChannelApp) {
+        case_a.pause_simulation();
+        if let Some(case_b) = self.case_b.as_mut() {
+            case_b.pause_simulation();
+        }
+    }
+
+    /// Reset both cases to rest
+    pub fn reset(&mut self, case_a: &mut WaveChannelApp) {
+        case_a.reset_simulation();
+        if let Some(case_b) = self.case_b.as_mut() {
+            case_b.reset_simulation();
+        }
+    }
+
+    /// Advance both cases by one time step, pausing both as soon as one completes
+    pub fn advance(&mut self, case_a: &mut WaveChannelApp, dt: f64) {
+        let Some(case_b) = self.case_b.as_mut() else {
+            return;
+        };
+        case_a.advance_simulation(dt);
+        case_b.advance_simulation(dt);
+        if !case_a.simulation_running || !case_b.simulation_running {
+            case_a.pause_simulation();
+            case_b.pause_simulation();
+        }
+    }
+
+    /// Whether both cases are at the same simulation time
+    pub fn is_synchronized(&self, case_a: &WaveChannelApp) -> bool {
+        self.case_b
+            .as_ref()
+            .is_some_and(|case_b| (case_a.simulation_time - case_b.simulation_time).abs() < 1e-9)
+    }
+
+    /// Surface elevation of case B minus case A at the display points of case A: (x [m], Δη [m])
+    /// Case B is interpolated linearly when the grids differ
+    pub fn surface_difference(&self, case_a: &WaveChannelApp) -> Option<Vec<[f64; 2]>> {
+        let case_b = self.case_b.as_ref()?;
+        let dx_a = case_a.grid_spacing();
+        let dx_b = case_b.grid_spacing();
+        let last_b = case_b.surface_elevation.len().checked_sub(1)?;
+        let difference = case_a
+            .surface_elevation
+            .iter()
+            .enumerate()
+            .map(|(i, eta_a)| {
+                let x = i as f64 * dx_a;
+                let position = (x / dx_b).clamp(0.0, last_b as f64);
+                let left = (position.floor() as usize).min(last_b.saturating_sub(1));
+                let right = (left + 1).min(last_b);
+                let weight = position - left as f64;
+                let eta_b = case_b.surface_elevation[left] * (1.0 - weight) + case_b.surface_elevation[right] * weight;
+                [x, eta_b - eta_a]
+            })
+            .collect();
+        Some(difference)
+    }
+
+    /// Show the comparison of the wave channel (case A) with case B
+    pub fn show(&mut self, ui: &mut egui::Ui, case_a: &mut WaveChannelApp) {
+        ui.heading("Scenario Comparison");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            if ui.button("📋 Clone Current Scenario into Case B").clicked() {
+                self.clone_case(case_a);
+            }
+            ui.label("Case A is the wave channel; case B starts as a copy and can be modified below.");
+        });
+        if self.case_b.is_none() {
+            ui.label("Clone the wave channel scenario to start a comparison.");
+            return;
+        }
+
+        // Simulation controls for both cases
+        ui.horizontal(|ui| {
+            if self.is_running(case_a) {
+                if ui.button("⏸ Pause Both").clicked() {
+                    self.pause(case_a);
+                }
+            } else if ui.button("▶ Run Both").clicked() {
+                self.start(case_a);
+            }
+            if ui.button("⏹ Reset Both").clicked() {
+                self.reset(case_a);
+            }
+            if !self.is_synchronized(case_a) {
+                ui.label("⚠ Cases are at different times; reset both to synchronize.");
+            }
+        });
+        if self.is_running(case_a) {
+            self.advance(case_a, COMPARISON_TIME_STEP);
+            case_a.repaint_policy.request_frame(ui.ctx());
+        }
+
+        // Case B parameters; any change restarts both cases to keep them in step
+        let Some(case_b) = self.case_b.as_mut() else {
+            return;
+        };
+        let mut changed = false;
+        egui::CollapsingHeader::new("Case B Parameters").default_open(true).show(ui, |ui| {
+            changed = show_case_editor(ui, case_b);
+        });
+        if changed {
+            self.reset(case_a);
+        }
+        let Some(case_b) = self.case_b.as_ref() else {
+            return;
+        };
+
+        // Synchronized channel views
+        let style = case_a.plot_style;
+        ui.columns(2, |columns| {
+            for (ui, (name, case)) in columns.iter_mut().zip([("Case A", &*case_a), ("Case B", case_b)]) {
+                ui.heading(name);
+                ui.label(case_summary(case));
+                channel_plot(ui, style, case, name);
+            }
+        });
+
+        ui.separator();
+        ui.heading("Surface Difference");
+        if let Some(difference) = self.surface_difference(case_a) {
+            let largest = difference.iter().fold(0.0_f64, |max, point| max.max(point[1].abs()));
+            ui.label(format!("Largest |η_B − η_A|: {:.3} m", largest));
+            let style = case_a.plot_style;
+            let plot = Plot::new("comparison_difference")
+                .height(200.0)
+                .width((ui.available_width() - 40.0).max(400.0))
+                .x_axis_label("Distance (m)")
+                .y_axis_label("η_B − η_A (m)")
+                .include_x(0)
+                .include_x(case_a.channel_length)
+                .include_y(-0.1)
+                .include_y(0.1);
+            style.show_plot(ui, plot, |plot_ui| {
+                plot_ui.hline(HLine::new(0.0).color(egui::Color32::GRAY).style(LineStyle::dashed_dense()));
+                plot_ui.line(Line::new(PlotPoints::from(difference)).color(style.alert()).width(style.width(1.5)).name("Difference"));
+            });
+        }
+
+        self.show_gauge_spectra(ui, case_a);
+    }
+
+    /// Variance density spectra of one gauge in both cases, side by side
+    fn show_gauge_spectra(&mut self, ui: &mut egui::Ui, case_a: &WaveChannelApp) {
+        let Some(case_b) = self.case_b.as_ref() else {
+            return;
+        };
+        ui.separator();
+        ui.heading("Gauge Spectra");
+        let gauges = case_a.gauges.len().min(case_b.gauges.len());
+        if gauges == 0 {
+            ui.label("Place gauges in the wave channel before cloning to compare spectra.");
+            return;
+        }
+        self.comparison_gauge = self.comparison_gauge.min(gauges - 1);
+
+        let positions: Vec<f64> = case_a.gauges.iter().map(WaveGauge::position).collect();
+        ui.horizontal(|ui| {
+            ui.label("Comparison Gauge:");
+            egui::ComboBox::from_id_salt("comparison_gauge")
+                .selected_text(format!("Gauge {} (x = {:.1} m)", self.comparison_gauge + 1, positions[self.comparison_gauge]))
+                .show_ui(ui, |ui| {
+                    for (i, position) in positions.iter().take(gauges).enumerate() {
+                        ui.selectable_value(&mut self.comparison_gauge, i, format!("Gauge {} (x = {:.1} m)", i + 1, position));
+                    }
+                });
+        });
+        if self.is_running(case_a) {
+            ui.label("Pause the comparison to compute the spectra.");
+            return;
+        }
+
+        let gauge = self.comparison_gauge;
+        let style = case_a.plot_style;
+        ui.columns(2, |columns| {
+            for (ui, (name, case)) in columns.iter_mut().zip([("Case A", case_a), ("Case B", case_b)]) {
+                match case.gauge_spectrum(gauge) {
+                    Ok((frequencies, density)) => {
+                        ui.label(format!("{} Hm0: {:.3} m", name, significant_wave_height(case.gauges[gauge].elevation())));
+                        let points: Vec<[f64; 2]> = frequencies.into_iter().zip(density).map(|(f, s)| [f, s]).collect();
+                        let plot = Plot::new(("comparison_spectrum", name))
+                            .height(200.0)
+                            .x_axis_label("Frequency (Hz)")
+                            .y_axis_label("S(f) (m²/Hz)")
+                            .include_x(0)
+                            .include_y(0)
+                            .link_axis("comparison_spectrum", [true, true]);
+                        style.show_plot(ui, plot, |plot_ui| {
+                            plot_ui.line(Line::new(PlotPoints::from(points)).color(style.series(0)).width(style.width(1.5)).name(name));
+                        });
+                    }
+                    Err(message) => {
+                        ui.label(format!("{}: ⚠ {}", name, message));
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Key parameters of case B, returns whether any changed
+fn show_case_editor(ui: &mut egui::Ui, case: &mut WaveChannelApp) -> bool {
+    let previous = (
+        case.wave_height,
+        case.wave_period,
+        case.still_water_level,
+        case.propagation_model,
+        case.dispersion_mode,
+        case.right_boundary,
+    );
+
+    ui.horizontal(|ui| {
+        ui.label("Wave Height:");
+        ui.add(egui::Slider::new(&mut case.wave_height, 0.1..=3.0).suffix(" m").step_by(0.1));
+        ui.label("Wave Period:");
+        ui.add(egui::Slider::new(&mut case.wave_period, 1.0..=20.0).suffix(" s").step_by(0.1));
+        ui.label("Still Water Level:");
+        ui.add(egui::Slider::new(&mut case.still_water_level, 0.1..=5.0).suffix(" m").step_by(0.01));
+    });
+    ui.horizontal(|ui| {
+        ui.label("Propagation Model:");
+        egui::ComboBox::from_id_salt("case_b_propagation_model")
+            .selected_text(case.propagation_model.to_string())
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut case.propagation_model, PropagationModel::Analytical, "Analytical");
+                ui.selectable_value(&mut case.propagation_model, PropagationModel::Numerical, "Numerical");
+            });
+        if case.is_numerical() {
+            ui.label("Accuracy Mode:");
+            egui::ComboBox::from_id_salt("case_b_dispersion_mode")
+                .selected_text(case.dispersion_mode.to_string())
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut case.dispersion_mode, DispersionMode::OneLayer, "One Layer");
+                    ui.selectable_value(&mut case.dispersion_mode, DispersionMode::TwoLayer, "Two Layers");
+                });
+            ui.label("Right Boundary:");
+            egui::ComboBox::from_id_salt("case_b_right_boundary")
+                .selected_text(case.right_boundary.to_string())
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut case.right_boundary, RightBoundary::Wall, "Solid Wall");
+                    ui.selectable_value(&mut case.right_boundary, RightBoundary::default(), "Sponge Layer");
+                    ui.selectable_value(&mut case.right_boundary, RightBoundary::Radiation, "Radiation (Sommerfeld)");
+                    ui.selectable_value(&mut case.right_boundary, RightBoundary::Periodic, "Periodic");
+                });
+        }
+    });
+
+    previous
+        != (
+            case.wave_height,
+            case.wave_period,
+            case.still_water_level,
+            case.propagation_model,
+            case.dispersion_mode,
+            case.right_boundary,
+        )
+}
+
+/// One-line description of a case
+fn case_summary(case: &WaveChannelApp) -> String {
+    let scenario = match case.initial_condition {
+        Some(condition) => condition.to_string(),
+        None => format!("H = {:.2} m, T = {:.1} s", case.wave_height, case.wave_period),
+    };
+    format!(
+        "{}, d = {:.2} m, {} — t = {:.1} s",
+        scenario, case.still_water_level, case.propagation_model, case.simulation_time
+    )
+}
+
+/// Water surface of a case, with axes linked between the panes
+fn channel_plot(ui: &mut egui::Ui, style: PlotStyle, case: &WaveChannelApp, name: &str) {
+    let dx = case.grid_spacing();
+    let surface: PlotPoints = case
+        .surface_elevation
+        .iter()
+        .enumerate()
+        .map(|(i, eta)| [i as f64 * dx, case.still_water_level + eta])
+        .collect();
+    let bottom: PlotPoints = vec![[0.0, 0.0], [case.channel_length, 0.0]].into();
+
+    let plot = Plot::new(("comparison_channel", name))
+        .height(250.0)
+        .x_axis_label("Distance (m)")
+        .y_axis_label("Elevation (m)")
+        .include_x(0)
+        .include_x(case.channel_length)
+        .include_y(0)
+        .include_y(case.still_water_level)
+        .link_axis("comparison_channel", [true, true]);
+    style.show_plot(ui, plot, |plot_ui| {
+        plot_ui.line(Line::new(bottom).color(style.bed()).width(style.width(3.0)).name("Channel Bottom"));
+        plot_ui.line(Line::new(surface).color(style.water()).width(style.width(2.0)).name("Water Surface"));
+    });
+}
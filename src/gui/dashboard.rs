@@ -0,0 +1,221 @@
+use super::wave_channel::WaveChannelApp;
+use crate::analysis::spectral::{peak_frequency, significant_wave_height};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+/// Computed or analyzed quantity of the wave channel pinned to the dashboard
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DashboardQuantity {
+    /// Time reached by the simulation
+    SimulationTime,
+    /// Wavelength of the wavemaker period at the still water depth, from linear theory (L)
+    Wavelength,
+    /// Phase velocity of the wavemaker waves, from linear theory (c)
+    Celerity,
+    /// Largest surface elevation recorded by a gauge
+    MaxElevation { gauge: usize },
+    /// Significant wave height 4√m0 of a gauge over the statistics window
+    SignificantWaveHeight { gauge: usize },
+    /// Peak period of a gauge over the statistics window
+    PeakPeriod { gauge: usize },
+    /// Run-up exceeded by 2% of the run-up maxima of the swash
+    RunUp2,
+    /// Latest discharge into the overtopping tank, per metre of crest (q)
+    OvertoppingDischarge,
+    /// Courant number of the last time step of the numerical solver (CFL)
+    Courant,
+    /// Reflection coefficient of the structure (Kr)
+    Reflection,
+    /// Transmission coefficient of the structure (Kt)
+    Transmission,
+    /// Tide, surge and wave setup above mean sea level
+    TotalWaterLevel,
+    /// Custom formula of the user, by name
+    Formula(String),
+}
+
+impl DashboardQuantity {
+    /// Name of the quantity, with its gauge
+    pub fn label(&self) -> String {
+        match self {
+            DashboardQuantity::SimulationTime => "Simulation Time".to_string(),
+            DashboardQuantity::Wavelength => "Wavelength L".to_string(),
+            DashboardQuantity::Celerity => "Celerity c".to_string(),
+            DashboardQuantity::MaxElevation { gauge } => format!("Max η at Gauge {}", gauge + 1),
+            DashboardQuantity::SignificantWaveHeight { gauge } => format!("Hm0 at Gauge {}", gauge + 1),
+            DashboardQuantity::PeakPeriod { gauge } => format!("Tp at Gauge {}", gauge + 1),
+            DashboardQuantity::RunUp2 => "Run-up R2%".to_string(),
+            DashboardQuantity::OvertoppingDischarge => "Overtopping q".to_string(),
+            DashboardQuantity::Courant => "CFL".to_string(),
+            DashboardQuantity::Reflection => "Reflection Kr".to_string(),
+            DashboardQuantity::Transmission => "Transmission Kt".to_string(),
+            DashboardQuantity::TotalWaterLevel => "Total Water Level".to_string(),
+            DashboardQuantity::Formula(name) => name.clone(),
+        }
+    }
+
+    /// Unit of the quantity
+    pub fn unit(&self, channel: &WaveChannelApp) -> String {
+        match self {
+            DashboardQuantity::SimulationTime | DashboardQuantity::PeakPeriod { .. } => "s".to_string(),
+            DashboardQuantity::Celerity => "m/s".to_string(),
+            DashboardQuantity::OvertoppingDischarge => "l/s/m".to_string(),
+            DashboardQuantity::Reflection | DashboardQuantity::Transmission | DashboardQuantity::Courant => String::new(),
+            DashboardQuantity::Formula(name) => channel.custom_formulas.iter().find(|formula| &formula.name == name).map_or(String::new(), |formula| formula.unit.clone()),
+            _ => "m".to_string(),
+        }
+    }
+
+    /// Quantities of a wave channel: the linear wave properties, the statistics of its gauges, the
+    /// analyses of the swash, the overtopping tank, the structure and the water level, the stability
+    /// of the solver, and its custom formulas
+    pub fn available(channel: &WaveChannelApp) -> Vec<DashboardQuantity> {
+        let gauges = (0..channel.gauges.len()).flat_map(|gauge| {
+            [
+                DashboardQuantity::MaxElevation { gauge },
+                DashboardQuantity::SignificantWaveHeight { gauge },
+                DashboardQuantity::PeakPeriod { gauge },
+            ]
+        });
+        let tank = channel.overtopping_tank.as_ref().map(|_| DashboardQuantity::OvertoppingDischarge);
+        let formulas = channel.custom_formulas.iter().map(|formula| DashboardQuantity::Formula(formula.name.clone()));
+        [DashboardQuantity::SimulationTime, DashboardQuantity::Wavelength, DashboardQuantity::Celerity]
+            .into_iter()
+            .chain(gauges)
+            .chain([DashboardQuantity::RunUp2])
+            .chain(tank)
+            .chain([
+                DashboardQuantity::Reflection,
+                DashboardQuantity::Transmission,
+                DashboardQuantity::TotalWaterLevel,
+                DashboardQuantity::Courant,
+            ])
+            .chain(formulas)
+            .collect()
+    }
+
+    /// Current value of the quantity, or why it is not available yet
+    pub fn value(&self, channel: &WaveChannelApp) -> Result<f64, String> {
+        let record = |gauge: usize| {
+            let record = channel.statistics_record(gauge).ok_or(format!("Gauge {} is not in the channel", gauge + 1))?;
+            if record.is_empty() { Err("No record yet".to_string()) } else { Ok(record) }
+        };
+        match self {
+            DashboardQuantity::SimulationTime => Ok(channel.simulation_time),
+            DashboardQuantity::Wavelength => Ok(WaveChannelApp::linear_wavelength(channel.wave_period, channel.still_water_level)),
+            DashboardQuantity::Celerity => Ok(WaveChannelApp::linear_wavelength(channel.wave_period, channel.still_water_level) / channel.wave_period),
+            DashboardQuantity::MaxElevation { gauge } => Ok(record(*gauge)?.elevation().iter().copied().fold(f64::NEG_INFINITY, f64::max)),
+            DashboardQuantity::SignificantWaveHeight { gauge } => Ok(significant_wave_height(record(*gauge)?.elevation())),
+            DashboardQuantity::PeakPeriod { gauge } => {
+                let record = record(*gauge)?;
+                let interval = record.sample_interval().ok_or("Record too short")?;
+                peak_frequency(record.elevation(), interval).map(|frequency| 1.0 / frequency).ok_or("No spectral peak".to_string())
+            }
+            DashboardQuantity::RunUp2 => Ok(channel.swash_analysis()?.run_up_2),
+            DashboardQuantity::OvertoppingDischarge => {
+                let tank = channel.overtopping_tank.as_ref().ok_or("No overtopping tank behind the crest")?;
+                if tank.len() < 2 {
+                    return Err("No tank record yet".to_string());
+                }
+                Ok(1000.0 * tank.discharge()[tank.len() - 1])
+            }
+            DashboardQuantity::Courant => channel.performance.time_step().map(|(_, courant)| courant).ok_or("No solver step yet".to_string()),
+            DashboardQuantity::Reflection => Ok(channel.energy_budget()?.reflection().max(0.0).sqrt()),
+            DashboardQuantity::Transmission => Ok(channel.energy_budget()?.transmission().max(0.0).sqrt()),
+            DashboardQuantity::TotalWaterLevel => Ok(channel.water_level_budget()?.total()),
+            DashboardQuantity::Formula(name) => {
+                let formula = channel.custom_formulas.iter().find(|formula| &formula.name == name).ok_or(format!("No formula named {}", name))?;
+                formula.evaluate(&channel.formula_variables())
+            }
+        }
+    }
+}
+
+/// Progress of a study running in the background, shown on the dashboard while it runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct StudyProgress {
+    pub name: &'static str,
+    pub fraction: f64, // Share of the runs completed
+}
+
+/// Large live readouts of the quantities pinned by the user, for demonstrations and monitoring runs
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Dashboard {
+    adding: Option<DashboardQuantity>, // Quantity selected to pin next
+}
+
+impl Dashboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show the pinned quantities of the wave channel and the progress of the running studies
+    pub fn show(&mut self, ui: &mut egui::Ui, channel: &mut WaveChannelApp, studies: &[StudyProgress]) {
+        let available: Vec<DashboardQuantity> = DashboardQuantity::available(channel).into_iter().filter(|quantity| !channel.dashboard.contains(quantity)).collect();
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("dashboard_quantity")
+                .selected_text(self.adding.as_ref().map_or("Quantity...".to_string(), DashboardQuantity::label))
+                .show_ui(ui, |ui| {
+                    for quantity in &available {
+                        ui.selectable_value(&mut self.adding, Some(quantity.clone()), quantity.label());
+                    }
+                });
+            let pinnable = self.adding.as_ref().is_some_and(|quantity| available.contains(quantity));
+            if ui.add_enabled(pinnable, egui::Button::new("📌 Pin")).clicked()
+                && let Some(quantity) = self.adding.take()
+            {
+                channel.dashboard.push(quantity);
+            }
+        });
+        if channel.dashboard.is_empty() {
+            ui.label("Pin quantities to follow them in large print while the simulation runs.");
+        }
+
+        let (mut removed, mut raised) = (None, None);
+        ui.horizontal_wrapped(|ui| {
+            for (i, quantity) in channel.dashboard.iter().enumerate() {
+                egui::Frame::group(ui.style()).show(ui, |ui| {
+                    ui.set_min_width(150.0);
+                    ui.vertical(|ui| {
+                        ui.horizontal(|ui| {
+                            ui.small(quantity.label());
+                            if i > 0 && ui.small_button("⏴").on_hover_text("Move left").clicked() {
+                                raised = Some(i);
+                            }
+                            if ui.small_button("✖").on_hover_text("Unpin").clicked() {
+                                removed = Some(i);
+                            }
+                        });
+                        match quantity.value(channel) {
+                            Ok(value) => {
+                                ui.horizontal(|ui| {
+                                    ui.label(egui::RichText::new(format!("{:.3}", value)).size(32.0).strong());
+                                    ui.label(quantity.unit(channel));
+                                });
+                            }
+                            Err(message) => {
+                                ui.label(egui::RichText::new("—").size(32.0)).on_hover_text(message);
+                            }
+                        }
+                    });
+                });
+            }
+        });
+        if let Some(i) = raised {
+            channel.dashboard.swap(i - 1, i);
+        }
+        if let Some(i) = removed {
+            channel.dashboard.remove(i);
+        }
+
+        if channel.simulation_running {
+            ui.add(egui::ProgressBar::new(channel.simulation_progress() as f32).text(format!("Simulation t = {:.1} s", channel.simulation_time)));
+        }
+        for study in studies {
+            ui.add(egui::ProgressBar::new(study.fraction as f32).text(format!("{} {:.0}%", study.name, 100.0 * study.fraction)));
+        }
+        if !studies.is_empty() {
+            channel.repaint_policy.request_progress(ui.ctx());
+        }
+    }
+}
@@ -0,0 +1,557 @@
+use super::background::BackgroundRun;
+use super::clipboard::plot_menu;
+use super::project::Project;
+use super::results_table::{ResultsTable, TableCell, TableView};
+use super::run_output::RunOutput;
+use super::wave_channel::WaveChannelApp;
+use crate::analysis::optimization::{SearchRange, grid_points, nelder_mead, pareto_front};
+use crate::solver::{DikeDesign, RightBoundary};
+use eframe::egui;
+use egui_plot::{HLine, Legend, Line, LineStyle, Plot, PlotPoints, Points, VLine};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
+
+/// Weight of a relative exceedance of the target against the relative cost in the refinement
+const TARGET_PENALTY: f64 = 10.0;
+
+/// Geometry parameter of the dike varied by the optimizer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DesignParameter {
+    /// Crest level above the still water level (Rc)
+    CrestFreeboard,
+    /// Outer slope 1:m
+    Slope,
+    /// Width of the berm at the still water level
+    BermWidth,
+}
+
+impl DesignParameter {
+    pub const ALL: [DesignParameter; 3] = [DesignParameter::CrestFreeboard, DesignParameter::Slope, DesignParameter::BermWidth];
+
+    /// Unit of the parameter
+    pub fn unit(&self) -> &'static str {
+        match self {
+            DesignParameter::CrestFreeboard | DesignParameter::BermWidth => "m",
+            DesignParameter::Slope => "(1:m)",
+        }
+    }
+
+    /// Value of the parameter in a design
+    pub fn value(&self, design: &DikeDesign) -> f64 {
+        match self {
+            DesignParameter::CrestFreeboard => design.crest_freeboard,
+            DesignParameter::Slope => design.slope,
+            DesignParameter::BermWidth => design.berm_width,
+        }
+    }
+
+    /// Design with the values of every parameter, in the order of ALL
+    pub fn design(values: &[f64]) -> DikeDesign {
+        DikeDesign { crest_freeboard: values[0], slope: values[1], berm_width: values[2] }
+    }
+}
+
+impl std::fmt::Display for DesignParameter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DesignParameter::CrestFreeboard => write!(f, "Crest Freeboard"),
+            DesignParameter::Slope => write!(f, "Outer Slope"),
+            DesignParameter::BermWidth => write!(f, "Berm Width"),
+        }
+    }
+}
+
+/// Cost of a design, minimized among the designs meeting the target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DesignObjective {
+    /// Height of the crest above the offshore bed [m]
+    CrestHeight,
+    /// Volume of the dike per metre [m³/m]
+    Volume,
+}
+
+impl DesignObjective {
+    /// Cost of a design in a channel with a still water depth at the wavemaker
+    pub fn cost(&self, design: &DikeDesign, depth: f64) -> f64 {
+        match self {
+            DesignObjective::CrestHeight => design.crest_height(depth),
+            DesignObjective::Volume => design.volume(depth),
+        }
+    }
+
+    /// Unit of the cost
+    pub fn unit(&self) -> &'static str {
+        match self {
+            DesignObjective::CrestHeight => "m",
+            DesignObjective::Volume => "m³/m",
+        }
+    }
+}
+
+impl std::fmt::Display for DesignObjective {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DesignObjective::CrestHeight => write!(f, "Crest Height"),
+            DesignObjective::Volume => write!(f, "Volume"),
+        }
+    }
+}
+
+/// Run of one design
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesignEvaluation {
+    pub design: DikeDesign,
+    pub output: Result<f64, String>, // Output of the run, or why the design could not be run
+    pub cost: f64,
+    pub refined: bool,               // Found by the Nelder–Mead refinement rather than the grid search
+}
+
+impl DesignEvaluation {
+    /// Whether the run met the target
+    pub fn meets(&self, limit: f64) -> bool {
+        self.output.as_ref().is_ok_and(|&output| output <= limit)
+    }
+}
+
+/// Search for the cheapest dike meeting a target on the output of a run
+///
+/// Every design of a grid over the parameter ranges replaces the cross-section of the wave channel
+/// scenario and runs to its end, several in parallel, with a wall behind the polder so no sponge
+/// layer damps the waves on the dike. The Nelder–Mead refinement then starts from
+/// the cheapest design meeting the target, or the one closest to it, and minimizes the relative
+/// cost plus a penalty proportional to the relative exceedance of the limit, within the ranges.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesignStudy {
+    pub ranges: [SearchRange; 3],  // Ranges of the parameters, in the order of DesignParameter::ALL
+    pub target: RunOutput,         // Output that must not exceed the limit
+    pub limit: f64,
+    pub objective: DesignObjective,
+    pub refine: bool,              // Refine the best grid design with Nelder–Mead
+    pub max_refinements: usize,    // Runs of the refinement
+    pub threads: usize,            // Designs of the grid run in parallel
+}
+
+impl Default for DesignStudy {
+    fn default() -> Self {
+        Self {
+            ranges: [
+                SearchRange { min: 0.1, max: 0.5, steps: 5 },
+                SearchRange { min: 2.0, max: 4.0, steps: 3 },
+                SearchRange { min: 0.0, max: 0.0, steps: 1 },
+            ],
+            target: RunOutput::Overtopping,
+            limit: 1.0,
+            objective: DesignObjective::CrestHeight,
+            refine: false,
+            max_refinements: 20,
+            threads: std::thread::available_parallelism().map_or(1, |threads| threads.get()),
+        }
+    }
+}
+
+impl DesignStudy {
+    /// Check the ranges and the target against the scenario
+    pub fn validate(&self, scenario: &Project) -> Result<(), String> {
+        for (i, (parameter, range)) in DesignParameter::ALL.iter().zip(&self.ranges).enumerate() {
+            if !(range.min.is_finite() && range.max >= range.min && range.steps > 0) {
+                return Err(format!("Invalid range of {}", parameter));
+            }
+            for value in [range.min, range.max] {
+                let mut values: Vec<f64> = self.ranges.iter().map(|range| range.min).collect();
+                values[i] = value;
+                DesignParameter::design(&values).validate()?;
+            }
+        }
+        if !(self.limit.is_finite() && self.limit > 0.0) {
+            return Err("Target limit must be positive".to_string());
+        }
+        if self.threads == 0 {
+            return Err("The study needs at least one thread".to_string());
+        }
+        // The designs provide the cross-section
+        let with_section = Project {
+            cross_section: Some(self.grid_designs()[0].cross_section(scenario.channel_length, scenario.still_water_level)?),
+            ..scenario.clone()
+        };
+        self.target.validate(&with_section)
+    }
+
+    /// Designs of the grid search
+    pub fn grid_designs(&self) -> Vec<DikeDesign> {
+        grid_points(&self.ranges).iter().map(|values| DesignParameter::design(values)).collect()
+    }
+
+    /// Run a design in the scenario
+    pub fn evaluate(&self, scenario: &Project, design: DikeDesign, refined: bool) -> DesignEvaluation {
+        let cost = self.objective.cost(&design, scenario.still_water_level);
+        let output = design.cross_section(scenario.channel_length, scenario.still_water_level).and_then(|section| {
+            let mut channel = WaveChannelApp::new();
+            channel.load_project(scenario);
+            channel.cross_section = Some(section);
+            channel.right_boundary = RightBoundary::Wall;
+            self.target.evaluate(&mut channel)
+        });
+        DesignEvaluation { design, output, cost, refined }
+    }
+
+    /// Run the grid on the threads of the study, then the refinement, sending each evaluation as it completes
+    /// Stops early when cancelled or when the receiver is gone
+    pub fn run(&self, scenario: &Project, cancel: &AtomicBool, sender: &Sender<DesignEvaluation>) {
+        let designs = self.grid_designs();
+        let next = AtomicUsize::new(0);
+        let (grid_sender, grid_receiver) = mpsc::channel();
+        std::thread::scope(|scope| {
+            for _ in 0..self.threads.max(1) {
+                let grid_sender = grid_sender.clone();
+                let (designs, next) = (&designs, &next);
+                scope.spawn(move || {
+                    while !cancel.load(Ordering::Relaxed) {
+                        let Some(&design) = designs.get(next.fetch_add(1, Ordering::Relaxed)) else {
+                            break;
+                        };
+                        let evaluation = self.evaluate(scenario, design, false);
+                        if grid_sender.send(evaluation.clone()).is_err() || sender.send(evaluation).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        drop(grid_sender);
+        let grid: Vec<DesignEvaluation> = grid_receiver.try_iter().collect();
+        if self.refine && !cancel.load(Ordering::Relaxed) {
+            self.refine(scenario, &grid, cancel, sender);
+        }
+    }
+
+    /// Nelder–Mead over the varied parameters, scaled to their ranges, from the best design of the grid
+    fn refine(&self, scenario: &Project, grid: &[DesignEvaluation], cancel: &AtomicBool, sender: &Sender<DesignEvaluation>) {
+        let varied: Vec<usize> = (0..self.ranges.len()).filter(|&i| self.ranges[i].is_varied()).collect();
+        let Some(start) = best_or_closest(grid, self.limit) else {
+            return;
+        };
+        if varied.is_empty() {
+            return;
+        }
+        let cost_scale = start.cost.abs().max(1e-9);
+        let to_design = |scaled: &[f64]| {
+            let mut values: Vec<f64> = DesignParameter::ALL.iter().map(|parameter| parameter.value(&start.design)).collect();
+            for (&i, &fraction) in varied.iter().zip(scaled) {
+                let range = self.ranges[i];
+                values[i] = range.min + fraction.clamp(0.0, 1.0) * (range.max - range.min);
+            }
+            DesignParameter::design(&values)
+        };
+        let objective = |scaled: &[f64]| {
+            if cancel.load(Ordering::Relaxed) {
+                return f64::INFINITY;
+            }
+            let evaluation = self.evaluate(scenario, to_design(scaled), true);
+            let value = match evaluation.output {
+                Ok(output) => evaluation.cost / cost_scale + TARGET_PENALTY * (output / self.limit - 1.0).max(0.0),
+                Err(_) => f64::INFINITY,
+            };
+            let _ = sender.send(evaluation);
+            value
+        };
+        let scaled_start: Vec<f64> = varied
+            .iter()
+            .map(|&i| (DesignParameter::ALL[i].value(&start.design) - self.ranges[i].min) / (self.ranges[i].max - self.ranges[i].min))
+            .collect();
+        // Half a grid step, towards the inside of the ranges
+        let steps: Vec<f64> = varied
+            .iter()
+            .zip(&scaled_start)
+            .map(|(&i, &position)| {
+                let step = 0.5 / (self.ranges[i].steps - 1) as f64;
+                if position + step > 1.0 { -step } else { step }
+            })
+            .collect();
+        nelder_mead(objective, &scaled_start, &steps, 1e-3, self.max_refinements);
+    }
+
+    /// Run the whole study, waiting for the results
+    pub fn run_to_completion(&self, scenario: &Project) -> DesignResults {
+        let (sender, receiver) = mpsc::channel();
+        self.run(scenario, &AtomicBool::new(false), &sender);
+        drop(sender);
+        let mut results = DesignResults::default();
+        results.evaluations.extend(receiver.try_iter());
+        results
+    }
+}
+
+/// Cheapest evaluation meeting the limit, or the one with the lowest output if none does
+fn best_or_closest(evaluations: &[DesignEvaluation], limit: f64) -> Option<&DesignEvaluation> {
+    let feasible = evaluations.iter().filter(|evaluation| evaluation.meets(limit)).min_by(|a, b| a.cost.total_cmp(&b.cost));
+    feasible.or_else(|| {
+        evaluations
+            .iter()
+            .filter_map(|evaluation| evaluation.output.as_ref().ok().map(|output| (evaluation, *output)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(evaluation, _)| evaluation)
+    })
+}
+
+/// Evaluated designs, in the order they completed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DesignResults {
+    pub evaluations: Vec<DesignEvaluation>,
+}
+
+impl DesignResults {
+    /// Cheapest design meeting the limit
+    pub fn best(&self, limit: f64) -> Option<&DesignEvaluation> {
+        self.evaluations.iter().filter(|evaluation| evaluation.meets(limit)).min_by(|a, b| a.cost.total_cmp(&b.cost))
+    }
+
+    /// Designs no other design improves on in both output and cost, by increasing output
+    pub fn pareto_set(&self) -> Vec<&DesignEvaluation> {
+        let points: Vec<[f64; 2]> = self
+            .evaluations
+            .iter()
+            .map(|evaluation| [evaluation.output.clone().unwrap_or(f64::NAN), evaluation.cost])
+            .collect();
+        pareto_front(&points).into_iter().map(|i| &self.evaluations[i]).collect()
+    }
+}
+
+/// Optimizer of the dike geometry of the wave channel scenario
+pub struct DesignOptimizerApp {
+    pub study: DesignStudy,
+    pub results: DesignResults,
+    running: Option<BackgroundRun<DesignEvaluation>>,
+    status: Option<String>, // Why the last study could not start
+    table: TableView,       // Sorting and filtering of the evaluations table
+}
+
+impl Default for DesignOptimizerApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DesignOptimizerApp {
+    pub fn new() -> Self {
+        Self {
+            study: DesignStudy::default(),
+            results: DesignResults::default(),
+            running: None,
+            status: None,
+            table: TableView::default(),
+        }
+    }
+
+    /// Start the study of a scenario on background threads, forgetting previous results
+    pub fn start(&mut self, scenario: Project) -> Result<(), String> {
+        self.study.validate(&scenario)?;
+        self.cancel();
+        self.results.evaluations.clear();
+        let study = self.study.clone();
+        self.running = Some(BackgroundRun::spawn(move |cancel, sender| study.run(&scenario, cancel, sender)));
+        Ok(())
+    }
+
+    /// Stop the study, keeping the completed evaluations
+    pub fn cancel(&mut self) {
+        if let Some(running) = self.running.take() {
+            self.results.evaluations.extend(running.cancel());
+        }
+    }
+
+    /// Collect the evaluations completed since the last poll
+    pub fn poll(&mut self) {
+        let Some(running) = &self.running else {
+            return;
+        };
+        let (evaluations, finished) = running.poll();
+        self.results.evaluations.extend(evaluations);
+        if finished {
+            self.running = None;
+        }
+    }
+
+    /// Whether a study is running
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// Show the search setup and the evaluated designs for the scenario of the wave channel
+    /// The chosen design can be applied to the wave channel
+    pub fn show(&mut self, ui: &mut egui::Ui, channel: &mut WaveChannelApp) {
+        self.poll();
+        ui.heading("Design Optimization");
+        ui.label("Searches the dike geometry with the lowest cost whose run keeps the target output under its limit. Each design replaces the cross-section of the wave channel scenario and runs to its end.");
+        ui.separator();
+
+        ui.add_enabled_ui(!self.is_running(), |ui| self.show_settings(ui, channel));
+        ui.horizontal(|ui| {
+            let grid = self.study.grid_designs().len();
+            if self.is_running() {
+                if ui.button("⏹ Cancel").clicked() {
+                    self.cancel();
+                }
+                ui.spinner();
+                channel.repaint_policy.request_progress(ui.ctx());
+            } else if ui.button("▶ Optimize").clicked() {
+                self.status = self.start(channel.project()).err();
+            }
+            let refinements = if self.study.refine { format!(" + up to {} refinement", self.study.max_refinements) } else { String::new() };
+            ui.label(format!("{} runs: {} grid designs{}", self.results.evaluations.len(), grid, refinements));
+        });
+        if let Some(status) = &self.status {
+            ui.colored_label(egui::Color32::from_rgb(200, 80, 0), format!("⚠ {}", status));
+        }
+        self.show_results(ui, channel);
+    }
+
+    /// Parameter ranges, target, objective and refinement
+    fn show_settings(&mut self, ui: &mut egui::Ui, channel: &WaveChannelApp) {
+        let study = &mut self.study;
+        egui::Grid::new("design_ranges").num_columns(4).show(ui, |ui| {
+            ui.strong("Parameter");
+            ui.strong("Min");
+            ui.strong("Max");
+            ui.strong("Values");
+            ui.end_row();
+            for (parameter, range) in DesignParameter::ALL.iter().zip(&mut study.ranges) {
+                ui.label(format!("{} {}", parameter, parameter.unit()));
+                ui.add(egui::DragValue::new(&mut range.min).range(0.0..=100.0).speed(0.01));
+                ui.add(egui::DragValue::new(&mut range.max).range(range.min..=100.0).speed(0.01));
+                ui.add(egui::DragValue::new(&mut range.steps).range(1..=20));
+                ui.end_row();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Target:");
+            egui::ComboBox::from_id_salt("design_target")
+                .selected_text(study.target.label())
+                .width(200.0)
+                .show_ui(ui, |ui| {
+                    for output in RunOutput::available(channel.gauges.len()) {
+                        ui.selectable_value(&mut study.target, output, output.label());
+                    }
+                });
+            ui.label("≤");
+            ui.add(egui::DragValue::new(&mut study.limit).range(0.0..=1000.0).speed(0.01).suffix(format!(" {}", study.target.unit())));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Minimize:");
+            for objective in [DesignObjective::CrestHeight, DesignObjective::Volume] {
+                ui.radio_value(&mut study.objective, objective, objective.to_string());
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut study.refine, "Refine with Nelder–Mead");
+            ui.add_enabled(study.refine, egui::DragValue::new(&mut study.max_refinements).range(3..=500).suffix(" runs"));
+            ui.label("Threads:");
+            ui.add(egui::DragValue::new(&mut study.threads).range(1..=256));
+        });
+    }
+
+    /// Evaluated designs, a row per run in the order they completed
+    pub fn results_table(&self) -> ResultsTable {
+        let (target, objective) = (self.study.target, self.study.objective);
+        let mut table = ResultsTable::new([
+            "Rc (m)".to_string(),
+            "Slope 1:m".to_string(),
+            "Berm (m)".to_string(),
+            format!("{} ({})", target.label(), target.unit()),
+            format!("{} ({})", objective, objective.unit()),
+            "Search".to_string(),
+            "Meets Limit".to_string(),
+        ]);
+        for evaluation in &self.results.evaluations {
+            table.push(vec![
+                TableCell::Number(evaluation.design.crest_freeboard),
+                TableCell::Number(evaluation.design.slope),
+                TableCell::Number(evaluation.design.berm_width),
+                TableCell::output(&evaluation.output),
+                TableCell::Number(evaluation.cost),
+                TableCell::Text(if evaluation.refined { "Refinement" } else { "Grid" }.to_string()),
+                TableCell::Text(if evaluation.meets(self.study.limit) { "Yes" } else { "No" }.to_string()),
+            ]);
+        }
+        table
+    }
+
+    /// Best design, Pareto set, every evaluated design and the trade-off between the output and the cost
+    fn show_results(&mut self, ui: &mut egui::Ui, channel: &mut WaveChannelApp) {
+        if self.results.evaluations.is_empty() {
+            return;
+        }
+        ui.separator();
+        let (limit, target, objective) = (self.study.limit, self.study.target, self.study.objective);
+        let describe = |evaluation: &DesignEvaluation| {
+            let output = evaluation.output.as_ref().map_or_else(|message| format!("⚠ {}", message), |output| format!("{:.3} {}", output, target.unit()));
+            format!(
+                "Rc {:.2} m, 1:{:.1}, berm {:.2} m → {}, {} {:.3} {}",
+                evaluation.design.crest_freeboard,
+                evaluation.design.slope,
+                evaluation.design.berm_width,
+                output,
+                objective,
+                evaluation.cost,
+                objective.unit()
+            )
+        };
+        match self.results.best(limit) {
+            Some(best) => {
+                ui.horizontal(|ui| {
+                    ui.colored_label(egui::Color32::from_rgb(0, 150, 0), format!("✔ Best: {}", describe(best)));
+                    if ui.button("Apply to Wave Channel").clicked() {
+                        channel.cross_section = best.design.cross_section(channel.channel_length, channel.still_water_level).ok();
+                        channel.right_boundary = RightBoundary::Wall;
+                    }
+                });
+            }
+            None => {
+                ui.label(format!("No design keeps the {} under {:.3} {}; widen the ranges.", target.label(), limit, target.unit()));
+            }
+        }
+
+        let pareto = self.results.pareto_set();
+        egui::CollapsingHeader::new(format!("Pareto Set ({} designs)", pareto.len())).default_open(true).show(ui, |ui| {
+            for evaluation in &pareto {
+                let marker = if evaluation.meets(limit) { "✔" } else { "✖" };
+                ui.label(format!("{} {}", marker, describe(evaluation)));
+            }
+        });
+
+        let split = |refined: bool| -> Vec<[f64; 2]> {
+            self.results
+                .evaluations
+                .iter()
+                .filter(|evaluation| evaluation.refined == refined)
+                .filter_map(|evaluation| evaluation.output.as_ref().ok().map(|output| [*output, evaluation.cost]))
+                .collect()
+        };
+        let front: Vec<[f64; 2]> = pareto.iter().filter_map(|evaluation| evaluation.output.as_ref().ok().map(|output| [*output, evaluation.cost])).collect();
+        let tradeoff = Plot::new("design_tradeoff")
+            .height(280.0)
+            .width((ui.available_width() - 40.0).max(400.0))
+            .legend(Legend::default())
+            .x_axis_label(format!("{} ({})", target.label(), target.unit()))
+            .y_axis_label(format!("{} ({})", objective, objective.unit()))
+            .show(ui, |plot_ui| {
+                plot_ui.points(Points::new(PlotPoints::from(split(false))).radius(3.0).color(egui::Color32::GRAY).name("Grid"));
+                plot_ui.points(Points::new(PlotPoints::from(split(true))).radius(3.0).color(egui::Color32::from_rgb(255, 140, 0)).name("Refinement"));
+                plot_ui.line(Line::new(PlotPoints::from(front)).color(egui::Color32::from_rgb(0, 100, 200)).width(2.0).name("Pareto Set"));
+                plot_ui.vline(VLine::new(limit).color(egui::Color32::from_rgb(220, 20, 60)).style(LineStyle::dashed_dense()).name("Limit"));
+                if let Some(best) = self.results.best(limit) {
+                    plot_ui.hline(HLine::new(best.cost).color(egui::Color32::from_rgb(0, 150, 0)).style(LineStyle::dashed_dense()).name("Best"));
+                }
+            });
+        plot_menu(&tradeoff.response);
+        let table = self.results_table();
+        egui::CollapsingHeader::new(format!("All Designs ({})", self.results.evaluations.len())).show(ui, |ui| {
+            table.show(ui, "design_evaluations", &mut self.table);
+        });
+    }
+}
+
+impl Drop for DesignOptimizerApp {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
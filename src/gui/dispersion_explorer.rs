@@ -0,0 +1,274 @@
+use super::clipboard::plot_menu;
+use crate::waves::wavemaker::linear_wave_number;
+use crate::waves::{DispersionMode, DispersionSolver};
+use eframe::egui;
+use egui_plot::{Legend, Line, LineStyle, Plot, PlotPoints, Points, VLine};
+use std::f64::consts::PI;
+
+/// Gravitational acceleration [m/s²]
+const GRAVITY: f64 = 9.81;
+
+/// Shortest wave period on the curves [s]
+const MIN_PERIOD: f64 = 1.0;
+
+/// Celerity, group velocity and wavelength of a wave at one period and depth
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DispersionPoint {
+    pub celerity: f64,       // Phase velocity (c) [m/s]
+    pub group_velocity: f64, // Group velocity (Cg) [m/s]
+    pub wavelength: f64,     // Wavelength (L) [m]
+}
+
+/// Dispersion relation drawn in the explorer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DispersionCurve {
+    /// Exact linear theory, ω² = gk tanh(kd)
+    LinearTheory,
+    /// Shallow water asymptote, c = Cg = √(gd)
+    ShallowWater,
+    /// Deep water asymptote, c = gT/2π and Cg = c/2
+    DeepWater,
+    /// Depth-averaged non-hydrostatic model (one-layer SWASH), without solution beyond ω²d/g = 4
+    OneLayer,
+}
+
+impl std::fmt::Display for DispersionCurve {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DispersionCurve::LinearTheory => write!(f, "Linear Theory"),
+            DispersionCurve::ShallowWater => write!(f, "Shallow Water"),
+            DispersionCurve::DeepWater => write!(f, "Deep Water"),
+            DispersionCurve::OneLayer => write!(f, "One-Layer SWASH"),
+        }
+    }
+}
+
+impl DispersionCurve {
+    /// Wave properties at a period [s] and depth [m], None if the relation cannot be solved
+    pub fn point(&self, period: f64, depth: f64) -> Option<DispersionPoint> {
+        if period <= 0.0 || depth <= 0.0 {
+            return None;
+        }
+        let omega = 2.0 * PI / period;
+        match self {
+            DispersionCurve::LinearTheory => {
+                let k = linear_wave_number(omega, depth, GRAVITY).ok()?;
+                let kd = k * depth;
+                let shoaling = 0.5 * (1.0 + 2.0 * kd / (2.0 * kd).sinh());
+                Some(DispersionPoint {
+                    celerity: omega / k,
+                    group_velocity: shoaling * omega / k,
+                    wavelength: 2.0 * PI / k,
+                })
+            }
+            DispersionCurve::ShallowWater => {
+                let celerity = (GRAVITY * depth).sqrt();
+                Some(DispersionPoint {
+                    celerity,
+                    group_velocity: celerity,
+                    wavelength: celerity * period,
+                })
+            }
+            DispersionCurve::DeepWater => {
+                let celerity = GRAVITY * period / (2.0 * PI);
+                Some(DispersionPoint {
+                    celerity,
+                    group_velocity: 0.5 * celerity,
+                    wavelength: celerity * period,
+                })
+            }
+            DispersionCurve::OneLayer => {
+                let solver = DispersionSolver::new().with_mode(DispersionMode::OneLayer);
+                let k = solver.solve_wave_number(omega, depth).ok()?;
+                Some(DispersionPoint {
+                    celerity: solver.phase_velocity(k, depth),
+                    group_velocity: solver.group_velocity(k, depth),
+                    wavelength: 2.0 * PI / k,
+                })
+            }
+        }
+    }
+
+    /// Line color in the explorer plots
+    fn color(&self) -> egui::Color32 {
+        match self {
+            DispersionCurve::LinearTheory => egui::Color32::from_rgb(0, 100, 200),
+            DispersionCurve::ShallowWater => egui::Color32::from_rgb(34, 139, 34),
+            DispersionCurve::DeepWater => egui::Color32::from_rgb(128, 0, 128),
+            DispersionCurve::OneLayer => egui::Color32::from_rgb(255, 140, 0),
+        }
+    }
+}
+
+/// Quantity plotted against the wave period
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DispersionQuantity {
+    /// Phase velocity c [m/s]
+    Celerity,
+    /// Group velocity Cg [m/s]
+    GroupVelocity,
+    /// Wavelength L [m]
+    Wavelength,
+}
+
+impl std::fmt::Display for DispersionQuantity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DispersionQuantity::Celerity => write!(f, "Celerity (m/s)"),
+            DispersionQuantity::GroupVelocity => write!(f, "Group Velocity (m/s)"),
+            DispersionQuantity::Wavelength => write!(f, "Wavelength (m)"),
+        }
+    }
+}
+
+impl DispersionQuantity {
+    /// Value of this quantity at a point of a dispersion curve
+    pub fn value(&self, point: &DispersionPoint) -> f64 {
+        match self {
+            DispersionQuantity::Celerity => point.celerity,
+            DispersionQuantity::GroupVelocity => point.group_velocity,
+            DispersionQuantity::Wavelength => point.wavelength,
+        }
+    }
+}
+
+/// Interactive plots of c(T), Cg(T) and L(T) at the channel depth
+///
+/// The marker sits at the wave period of the channel; clicking or dragging in a plot moves it and updates the channel.
+pub struct DispersionExplorer {
+    pub max_period: f64,        // Longest wave period on the curves [s]
+    pub show_asymptotes: bool,  // Draw the shallow and deep water asymptotes
+    pub show_one_layer: bool,   // Draw the one-layer SWASH relation
+}
+
+impl Default for DispersionExplorer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DispersionExplorer {
+    pub fn new() -> Self {
+        Self {
+            max_period: 20.0,      // Longest period of the wave channel
+            show_asymptotes: true,
+            show_one_layer: true,
+        }
+    }
+
+    /// Curves currently drawn, exact linear theory first
+    pub fn visible_curves(&self) -> Vec<DispersionCurve> {
+        let mut curves = vec![DispersionCurve::LinearTheory];
+        if self.show_asymptotes {
+            curves.extend([DispersionCurve::ShallowWater, DispersionCurve::DeepWater]);
+        }
+        if self.show_one_layer {
+            curves.push(DispersionCurve::OneLayer);
+        }
+        curves
+    }
+
+    /// Sample a curve over the period range for one quantity
+    pub fn curve_points(&self, curve: DispersionCurve, quantity: DispersionQuantity, depth: f64) -> Vec<[f64; 2]> {
+        let samples = 200;
+        (0..=samples)
+            .filter_map(|i| {
+                let period = MIN_PERIOD + (self.max_period - MIN_PERIOD) * i as f64 / samples as f64;
+                curve.point(period, depth).map(|point| [period, quantity.value(&point)])
+            })
+            .collect()
+    }
+
+    /// Show the explorer for the channel depth and wave period, both editable
+    pub fn show(&mut self, ui: &mut egui::Ui, depth: &mut f64, period: &mut f64) {
+        ui.heading("Dispersion Relation Explorer");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Water Depth:");
+            ui.add(egui::Slider::new(depth, 0.1..=5.0).suffix(" m").step_by(0.01));
+            ui.label("Wave Period:");
+            ui.add(egui::Slider::new(period, MIN_PERIOD..=20.0).suffix(" s").step_by(0.1));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Period Range:");
+            ui.add(egui::Slider::new(&mut self.max_period, 2.0..=30.0).prefix("up to ").suffix(" s"));
+            ui.checkbox(&mut self.show_asymptotes, "Show Asymptotes");
+            ui.checkbox(&mut self.show_one_layer, "Show One-Layer SWASH");
+        });
+        ui.label("Depth and period are shared with the wave channel. Click or drag in a plot to move the marker.");
+
+        // Values at the marker
+        if let Some(point) = DispersionCurve::LinearTheory.point(*period, *depth) {
+            let relative_depth = *depth / point.wavelength;
+            let regime = if relative_depth < 0.05 {
+                "shallow water"
+            } else if relative_depth > 0.5 {
+                "deep water"
+            } else {
+                "intermediate depth"
+            };
+            ui.label(format!(
+                "L = {:.2} m, c = {:.2} m/s, Cg = {:.2} m/s, kd = {:.3}, d/L = {:.3} ({})",
+                point.wavelength,
+                point.celerity,
+                point.group_velocity,
+                2.0 * PI * relative_depth,
+                relative_depth,
+                regime
+            ));
+            if let Some(one_layer) = DispersionCurve::OneLayer.point(*period, *depth) {
+                ui.label(format!("One-Layer SWASH Celerity Error: {:+.2} %", (one_layer.celerity / point.celerity - 1.0) * 100.0));
+            }
+        }
+
+        let curves = self.visible_curves();
+        let mut selected_period = None;
+        for quantity in [DispersionQuantity::Celerity, DispersionQuantity::GroupVelocity, DispersionQuantity::Wavelength] {
+            let marker = DispersionCurve::LinearTheory.point(*period, *depth).map(|point| [*period, quantity.value(&point)]);
+            let lines: Vec<(DispersionCurve, Vec<[f64; 2]>)> =
+                curves.iter().map(|&curve| (curve, self.curve_points(curve, quantity, *depth))).collect();
+            // Deep water asymptotes grow without bound, so the view follows linear theory
+            let top = lines[0].1.iter().fold(0.0_f64, |max, point| max.max(point[1]));
+
+            let plot = Plot::new(("dispersion_explorer", quantity.to_string()))
+                .height(200.0)
+                .width((ui.available_width() - 40.0).max(400.0))
+                .x_axis_label("Wave Period (s)")
+                .y_axis_label(quantity.to_string())
+                .include_x(0)
+                .include_x(self.max_period)
+                .include_y(0)
+                .include_y(1.1 * top)
+                .allow_drag(false)
+                .allow_zoom(false)
+                .allow_scroll(false)
+                .legend(Legend::default())
+                .show(ui, |plot_ui| {
+                    for (curve, points) in lines {
+                        let line = Line::new(PlotPoints::from(points)).color(curve.color()).name(curve.to_string());
+                        let line = match curve {
+                            DispersionCurve::LinearTheory => line.width(2.0),
+                            DispersionCurve::OneLayer => line.style(LineStyle::dashed_loose()),
+                            _ => line.style(LineStyle::dotted_loose()),
+                        };
+                        plot_ui.line(line);
+                    }
+                    plot_ui.vline(VLine::new(*period).color(egui::Color32::GRAY).style(LineStyle::dashed_dense()));
+                    if let Some(marker) = marker {
+                        plot_ui.points(Points::new(vec![marker]).radius(6.0).color(egui::Color32::from_rgb(220, 20, 60)).name("Channel"));
+                    }
+
+                    let response = plot_ui.response();
+                    if response.clicked() || response.dragged() {
+                        selected_period = plot_ui.pointer_coordinate().map(|pointer| pointer.x);
+                    }
+                });
+            plot_menu(&plot.response);
+        }
+
+        if let Some(selected) = selected_period {
+            *period = ((selected.clamp(MIN_PERIOD, 20.0)) * 10.0).round() / 10.0;
+        }
+    }
+}
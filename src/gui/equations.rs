@@ -128,6 +128,40 @@ impl EquationRenderer {
         self.textures.get(equation_id)
     }
 
+    /// Get an equation definition by ID
+    pub fn get_equation(&self, equation_id: &str) -> Option<&Equation> {
+        self.equations.get(equation_id)
+    }
+
+    /// Show a rendered equation, scaled to the body font size and at most max_width wide
+    pub fn show_equation(&mut self, ui: &mut egui::Ui, ctx: &Context, equation_id: &str, max_width: f32) {
+        if let Err(e) = self.load_equation_texture(ctx, equation_id) {
+            eprintln!("Failed to load equation texture for {}: {}", equation_id, e);
+            ui.label(format!("[Equation {} failed to load]", equation_id));
+        } else if let Some(texture) = self.get_texture(equation_id) {
+            let size = texture.size_vec2();
+
+            // Scale equation to match current font size
+            let font_size = ui.text_style_height(&egui::TextStyle::Body);
+            let base_equation_height = 12.0; // Base height from LaTeX template (12pt)
+            let font_scale = font_size / base_equation_height;
+
+            // Apply font scaling with additional reduction factor for better text matching
+            let font_scaled_size = size * font_scale * 0.15;
+            let max_width = ui.available_width().min(max_width);
+            let width_scale = if font_scaled_size.x > max_width {
+                max_width / font_scaled_size.x
+            } else {
+                1.0
+            };
+            let display_size = font_scaled_size * width_scale;
+
+            ui.add_space(5.0);
+            ui.image((texture.id(), display_size));
+            ui.add_space(5.0);
+        }
+    }
+
 }
 
 impl Default for EquationRenderer {
@@ -0,0 +1,377 @@
+use super::dispersion_explorer::DispersionCurve;
+use super::project::Project;
+use super::run_metadata::{format_utc, unix_time};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Quantity of a wave channel configuration an exercise can check
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Quantity {
+    WaveHeight,
+    WavePeriod,
+    WaterDepth,
+    ChannelLength,
+    GridSpacing,
+    GaugeCount,
+    /// Linear theory wavelength
+    Wavelength,
+    /// Linear theory phase velocity
+    Celerity,
+    /// Depth to wavelength ratio h/L
+    RelativeDepth,
+    /// Wave steepness H/L
+    Steepness,
+}
+
+impl std::fmt::Display for Quantity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Quantity::WaveHeight => write!(f, "wave height H [m]"),
+            Quantity::WavePeriod => write!(f, "wave period T [s]"),
+            Quantity::WaterDepth => write!(f, "water depth h [m]"),
+            Quantity::ChannelLength => write!(f, "channel length [m]"),
+            Quantity::GridSpacing => write!(f, "grid spacing Δx [m]"),
+            Quantity::GaugeCount => write!(f, "number of wave gauges"),
+            Quantity::Wavelength => write!(f, "wavelength L [m]"),
+            Quantity::Celerity => write!(f, "celerity c [m/s]"),
+            Quantity::RelativeDepth => write!(f, "relative depth h/L"),
+            Quantity::Steepness => write!(f, "steepness H/L"),
+        }
+    }
+}
+
+impl Quantity {
+    /// Value of the quantity for a configuration, None if the dispersion relation cannot be solved
+    pub fn value(&self, project: &Project) -> Option<f64> {
+        let wave = || DispersionCurve::LinearTheory.point(project.wave_period, project.still_water_level);
+        match self {
+            Quantity::WaveHeight => Some(project.wave_height),
+            Quantity::WavePeriod => Some(project.wave_period),
+            Quantity::WaterDepth => Some(project.still_water_level),
+            Quantity::ChannelLength => Some(project.channel_length),
+            Quantity::GridSpacing => Some(project.channel_length / (project.grid_resolution as f64 - 1.0)),
+            Quantity::GaugeCount => Some(project.gauge_positions.len() as f64),
+            Quantity::Wavelength => wave().map(|point| point.wavelength),
+            Quantity::Celerity => wave().map(|point| point.celerity),
+            Quantity::RelativeDepth => wave().map(|point| project.still_water_level / point.wavelength),
+            Quantity::Steepness => wave().map(|point| project.wave_height / point.wavelength),
+        }
+    }
+}
+
+/// Condition checked when grading an exercise
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Check {
+    /// The configuration sets a quantity to a target, within a relative tolerance
+    Configure { quantity: Quantity, target: f64, tolerance: f64 },
+    /// A quantity of the configuration lies in a range, e.g. h/L > 0.5 for deep water
+    Range {
+        quantity: Quantity,
+        #[serde(default)]
+        min: Option<f64>,
+        #[serde(default)]
+        max: Option<f64>,
+    },
+    /// The student answers the value of a quantity of their configuration, within a relative tolerance
+    Answer { quantity: Quantity, tolerance: f64 },
+}
+
+impl Check {
+    /// Condition in words
+    pub fn description(&self) -> String {
+        match self {
+            Check::Configure { quantity, target, tolerance } => {
+                format!("Set the {} to {} (± {}%)", quantity, target, tolerance * 100.0)
+            }
+            Check::Range { quantity, min, max } => match (min, max) {
+                (Some(min), Some(max)) => format!("Keep the {} between {} and {}", quantity, min, max),
+                (Some(min), None) => format!("Keep the {} above {}", quantity, min),
+                (None, Some(max)) => format!("Keep the {} below {}", quantity, max),
+                (None, None) => format!("Any {}", quantity),
+            },
+            Check::Answer { quantity, tolerance } => {
+                format!("Find the {} (± {}%)", quantity, tolerance * 100.0)
+            }
+        }
+    }
+
+    /// Check a configuration and the answer given, if the check asks for one
+    pub fn grade(&self, project: &Project, answer: Option<f64>) -> CheckResult {
+        let (quantity, expected, actual, passed) = match self {
+            Check::Configure { quantity, target, tolerance } => {
+                let actual = quantity.value(project);
+                let passed = actual.is_some_and(|actual| within(actual, *target, *tolerance));
+                (quantity, Some(*target), actual, passed)
+            }
+            Check::Range { quantity, min, max } => {
+                let actual = quantity.value(project);
+                let passed = actual.is_some_and(|actual| min.is_none_or(|min| actual >= min) && max.is_none_or(|max| actual <= max));
+                (quantity, None, actual, passed)
+            }
+            Check::Answer { quantity, tolerance } => {
+                let expected = quantity.value(project);
+                let passed = matches!((answer, expected), (Some(answer), Some(expected)) if within(answer, expected, *tolerance));
+                (quantity, expected, answer, passed)
+            }
+        };
+        CheckResult {
+            description: self.description(),
+            quantity: *quantity,
+            expected,
+            actual,
+            passed,
+        }
+    }
+
+    /// Check if the student has to type a value
+    pub fn needs_answer(&self) -> bool {
+        matches!(self, Check::Answer { .. })
+    }
+}
+
+/// Check if a value is within a relative tolerance of a target, or an absolute one for a zero target
+fn within(value: f64, target: f64, tolerance: f64) -> bool {
+    (value - target).abs() <= tolerance * target.abs().max(f64::EPSILON)
+}
+
+/// Outcome of one check
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckResult {
+    pub description: String,
+    pub quantity: Quantity,
+    pub expected: Option<f64>, // Target or correct answer, None for ranges
+    pub actual: Option<f64>,   // Value of the configuration or answer given
+    pub passed: bool,
+}
+
+/// Task authored by an instructor, graded by its checks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Exercise {
+    pub id: String,
+    pub title: String,
+    pub prompt: String,
+    pub checks: Vec<Check>,
+    #[serde(default = "default_points")]
+    pub points: f64, // Awarded when every check passes
+}
+
+fn default_points() -> f64 {
+    1.0
+}
+
+impl Exercise {
+    /// Grade a configuration with the answers to the checks, by check index
+    pub fn grade(&self, project: &Project, answers: &[Option<f64>]) -> ExerciseResult {
+        let checks: Vec<CheckResult> = self
+            .checks
+            .iter()
+            .enumerate()
+            .map(|(i, check)| check.grade(project, answers.get(i).copied().flatten()))
+            .collect();
+        let passed = checks.iter().all(|check| check.passed);
+        ExerciseResult {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            passed,
+            points: if passed { self.points } else { 0.0 },
+            max_points: self.points,
+            checks,
+            configuration: project.clone(),
+        }
+    }
+}
+
+/// Outcome of one exercise, with the configuration it was graded on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExerciseResult {
+    pub id: String,
+    pub title: String,
+    pub passed: bool,
+    pub points: f64,
+    pub max_points: f64,
+    pub checks: Vec<CheckResult>,
+    pub configuration: Project,
+}
+
+/// Set of exercises, read from a JSON file written by the instructor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Assignment {
+    pub title: String,
+    pub exercises: Vec<Exercise>,
+}
+
+impl Assignment {
+    /// Assignment from JSON
+    pub fn from_json(content: &str) -> Result<Self, String> {
+        serde_json::from_str(content).map_err(|e| format!("Invalid assignment file: {}", e))
+    }
+
+    /// Read an assignment from a file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        Self::from_json(&content)
+    }
+}
+
+/// Gradeable results of an assignment, written by the student and handed in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssignmentResults {
+    pub assignment: String,
+    pub student: String,
+    pub submitted_at: u64, // Unix time [s]
+    pub points: f64,
+    pub max_points: f64,
+    pub exercises: Vec<ExerciseResult>,
+}
+
+impl AssignmentResults {
+    /// Results of the exercises graded so far
+    pub fn new(assignment: &Assignment, student: &str, exercises: Vec<ExerciseResult>) -> Self {
+        Self {
+            assignment: assignment.title.clone(),
+            student: student.to_string(),
+            submitted_at: unix_time(),
+            points: exercises.iter().map(|exercise| exercise.points).sum(),
+            max_points: assignment.exercises.iter().map(|exercise| exercise.points).sum(),
+            exercises,
+        }
+    }
+
+    /// Write the results as pretty-printed JSON
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self).map_err(|e| format!("Cannot serialize results: {}", e))?;
+        std::fs::write(path, content).map_err(|e| format!("Cannot write {}: {}", path.display(), e))
+    }
+}
+
+/// Exercises tab: loads an assignment, checks the wave channel configuration and answers, writes the results
+pub struct ExercisesApp {
+    pub assignment_path: String,
+    pub results_path: String,
+    pub student: String,
+    pub assignment: Option<Assignment>,
+    pub answers: Vec<Vec<String>>,                   // Answer typed for each check of each exercise
+    pub results: Vec<Option<ExerciseResult>>,        // Last grading of each exercise
+    status: Option<String>,                          // Outcome of the last load or submission
+}
+
+impl ExercisesApp {
+    pub fn new() -> Self {
+        Self {
+            assignment_path: "assignment.json".to_string(),
+            results_path: "results.json".to_string(),
+            student: String::new(),
+            assignment: None,
+            answers: Vec::new(),
+            results: Vec::new(),
+            status: None,
+        }
+    }
+
+    /// Start an assignment, discarding previous answers
+    pub fn set_assignment(&mut self, assignment: Assignment) {
+        self.answers = assignment.exercises.iter().map(|exercise| vec![String::new(); exercise.checks.len()]).collect();
+        self.results = vec![None; assignment.exercises.len()];
+        self.assignment = Some(assignment);
+    }
+
+    /// Grade an exercise on a configuration, keeping the result for submission
+    /// Answers that are not numbers count as missing
+    pub fn grade(&mut self, index: usize, project: &Project) -> Option<&ExerciseResult> {
+        let exercise = self.assignment.as_ref()?.exercises.get(index)?;
+        let answers: Vec<Option<f64>> = self.answers[index].iter().map(|answer| answer.trim().parse().ok()).collect();
+        self.results[index] = Some(exercise.grade(project, &answers));
+        self.results[index].as_ref()
+    }
+
+    /// Results of the graded exercises
+    pub fn submission(&self) -> Option<AssignmentResults> {
+        let assignment = self.assignment.as_ref()?;
+        Some(AssignmentResults::new(assignment, &self.student, self.results.iter().flatten().cloned().collect()))
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, project: &Project) {
+        ui.heading("Exercises");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Assignment:");
+            ui.add(egui::TextEdit::singleline(&mut self.assignment_path).desired_width(250.0));
+            if ui.button("📂 Load").clicked() {
+                match Assignment::load(&PathBuf::from(&self.assignment_path)) {
+                    Ok(assignment) => {
+                        self.status = Some(format!("Loaded {} exercises", assignment.exercises.len()));
+                        self.set_assignment(assignment);
+                    }
+                    Err(message) => self.status = Some(message),
+                }
+            }
+        });
+        if let Some(status) = &self.status {
+            ui.label(status);
+        }
+        let Some(assignment) = self.assignment.clone() else {
+            ui.label("Load an assignment written by your instructor to start.");
+            return;
+        };
+
+        ui.heading(&assignment.title);
+        ui.label("Configure the wave channel as asked, type your answers and check each exercise.");
+        for (index, exercise) in assignment.exercises.iter().enumerate() {
+            ui.separator();
+            ui.strong(format!("{}. {} ({} pt)", index + 1, exercise.title, exercise.points));
+            ui.label(&exercise.prompt);
+            for (i, check) in exercise.checks.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(format!("• {}", check.description()));
+                    if check.needs_answer() {
+                        ui.add(egui::TextEdit::singleline(&mut self.answers[index][i]).desired_width(80.0).hint_text("answer"));
+                    }
+                });
+            }
+            if ui.button("✔ Check").clicked() {
+                self.grade(index, project);
+            }
+            if let Some(result) = &self.results[index] {
+                for check in &result.checks {
+                    let (symbol, color) = if check.passed {
+                        ("✔", egui::Color32::GREEN)
+                    } else {
+                        ("✖", egui::Color32::RED)
+                    };
+                    let actual = check.actual.map_or("missing".to_string(), |actual| format!("{:.4}", actual));
+                    ui.colored_label(color, format!("{} {}: {}", symbol, check.description, actual));
+                }
+            }
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("Student:");
+            ui.add(egui::TextEdit::singleline(&mut self.student).desired_width(150.0));
+            ui.label("Results:");
+            ui.add(egui::TextEdit::singleline(&mut self.results_path).desired_width(200.0));
+            if ui.button("📤 Submit Results").clicked()
+                && let Some(submission) = self.submission()
+            {
+                self.status = Some(match submission.save(&PathBuf::from(&self.results_path)) {
+                    Ok(()) => format!(
+                        "Submitted {} / {} points at {}",
+                        submission.points,
+                        submission.max_points,
+                        format_utc(submission.submitted_at)
+                    ),
+                    Err(message) => message,
+                });
+            }
+        });
+    }
+}
+
+impl Default for ExercisesApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,141 @@
+use super::plot_style::PlotStyle;
+use eframe::egui;
+use egui_plot::{Legend, Line, LineStyle, Plot, PlotPoints};
+
+/// Channel state kept in the gallery for before/after comparisons
+#[derive(Debug, Clone, PartialEq)]
+pub struct GalleryCapture {
+    pub name: String,
+    pub time: f64,              // Time of the captured frame [s]
+    pub surface: Vec<[f64; 2]>, // Water surface along the channel [m]
+    pub bed: Vec<[f64; 2]>,     // Bed and structures under it [m]
+    pub metadata: String,       // Wave conditions and propagation model of the case
+    pub selected: bool,         // Shown in the comparison strip
+}
+
+/// How the selected captures are compared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GalleryLayout {
+    /// All surfaces on one plot
+    #[default]
+    Overlay,
+    /// One plot per capture, one above the other with linked axes
+    Stack,
+}
+
+impl std::fmt::Display for GalleryLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GalleryLayout::Overlay => write!(f, "Overlay"),
+            GalleryLayout::Stack => write!(f, "Stack"),
+        }
+    }
+}
+
+/// Captures of the channel view taken during the session
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SnapshotGallery {
+    pub captures: Vec<GalleryCapture>,
+    pub layout: GalleryLayout,
+}
+
+impl SnapshotGallery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a capture, selected and named after its place in the gallery
+    pub fn capture(&mut self, time: f64, surface: Vec<[f64; 2]>, bed: Vec<[f64; 2]>, metadata: String) {
+        let number = self.captures.len() + 1;
+        self.captures.push(GalleryCapture {
+            name: format!("Snapshot {}", number),
+            time,
+            surface,
+            bed,
+            metadata,
+            selected: true,
+        });
+    }
+
+    /// Captures shown in the comparison strip, in capture order
+    pub fn selected(&self) -> Vec<&GalleryCapture> {
+        self.captures.iter().filter(|capture| capture.selected).collect()
+    }
+
+    /// Whether the selected captures share one bed, so it is drawn once
+    pub fn shares_bed(&self) -> bool {
+        let selected = self.selected();
+        selected.windows(2).all(|pair| pair[0].bed == pair[1].bed)
+    }
+
+    /// List of the captures and the comparison strip of the selected ones
+    pub fn show(&mut self, ui: &mut egui::Ui, style: PlotStyle) {
+        let mut removed = None;
+        for (i, capture) in self.captures.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut capture.selected, "");
+                ui.add(egui::TextEdit::singleline(&mut capture.name).desired_width(120.0));
+                ui.colored_label(style.series(i), format!("t = {:.2} s", capture.time)).on_hover_text(&capture.metadata);
+                if ui.small_button("✖").on_hover_text("Remove snapshot").clicked() {
+                    removed = Some(i);
+                }
+            });
+        }
+        if let Some(i) = removed {
+            self.captures.remove(i);
+        }
+        if self.captures.is_empty() {
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Compare:");
+            for layout in [GalleryLayout::Overlay, GalleryLayout::Stack] {
+                ui.selectable_value(&mut self.layout, layout, layout.to_string());
+            }
+            if ui.small_button("Clear").clicked() {
+                self.captures.clear();
+            }
+        });
+        let width = (ui.available_width() - 40.0).max(400.0);
+        let shares_bed = self.shares_bed();
+        let selected: Vec<(usize, &GalleryCapture)> = self.captures.iter().enumerate().filter(|(_, capture)| capture.selected).collect();
+        match self.layout {
+            GalleryLayout::Overlay => {
+                let plot = Plot::new("gallery_overlay")
+                    .height(220.0)
+                    .width(width)
+                    .x_axis_label("Distance (m)")
+                    .y_axis_label("Elevation (m)")
+                    .legend(Legend::default());
+                style.show_plot(ui, plot, |plot_ui| {
+                    for (n, (i, capture)) in selected.iter().enumerate() {
+                        // Beds differing between captures, such as other structures, take the color of their surface
+                        if n == 0 && shares_bed {
+                            plot_ui.line(Line::new(PlotPoints::from(capture.bed.clone())).color(style.bed()).width(style.width(2.0)).name("Channel Bottom"));
+                        } else if !shares_bed {
+                            plot_ui.line(Line::new(PlotPoints::from(capture.bed.clone())).color(style.series(*i)).width(style.width(1.5)).style(LineStyle::dashed_loose()).name(&capture.name));
+                        }
+                        plot_ui.line(Line::new(PlotPoints::from(capture.surface.clone())).color(style.series(*i)).width(style.width(2.0)).name(&capture.name));
+                    }
+                });
+            }
+            GalleryLayout::Stack => {
+                for (i, capture) in selected {
+                    ui.label(format!("{}: t = {:.2} s, {}", capture.name, capture.time, capture.metadata));
+                    let plot = Plot::new(("gallery_stack", i))
+                        .height(140.0)
+                        .width(width)
+                        .link_axis("gallery_stack", [true, true])
+                        .link_cursor("gallery_stack", [true, false])
+                        .x_axis_label("Distance (m)")
+                        .y_axis_label("Elevation (m)");
+                    style.show_plot(ui, plot, |plot_ui| {
+                        plot_ui.line(Line::new(PlotPoints::from(capture.bed.clone())).color(style.bed()).width(style.width(2.0)));
+                        plot_ui.line(Line::new(PlotPoints::from(capture.surface.clone())).color(style.series(i)).width(style.width(2.0)));
+                    });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use super::PlatformTab;
+use super::equations::EquationRenderer;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Extended explanation of a control or tool, from the glossary registry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GlossaryEntry {
+    pub id: String,             // Identifier of the control or tool, as used by its info button
+    pub term: String,           // Title shown in the help pane
+    pub explanation: String,    // Paragraph shown after the short description of the control
+    #[serde(default)]
+    pub equations: Vec<String>, // Identifiers of related equations in the equation registry
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GlossaryRegistry {
+    entries: Vec<GlossaryEntry>,
+}
+
+/// Glossary of controls and tools, keyed by identifier
+#[derive(Debug, Clone, Default)]
+pub struct Glossary {
+    entries: HashMap<String, GlossaryEntry>,
+}
+
+impl Glossary {
+    /// Create empty glossary
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Glossary from a registry file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| format!("Cannot read glossary: {}", e))?;
+        Self::from_json(&content)
+    }
+
+    /// Glossary from the JSON registry format, {"entries": [...]}
+    pub fn from_json(content: &str) -> Result<Self, String> {
+        let registry: GlossaryRegistry = serde_json::from_str(content).map_err(|e| format!("Invalid glossary: {}", e))?;
+        Ok(Self {
+            entries: registry.entries.into_iter().map(|entry| (entry.id.clone(), entry)).collect(),
+        })
+    }
+
+    /// Get an entry by identifier
+    pub fn get(&self, id: &str) -> Option<&GlossaryEntry> {
+        self.entries.get(id)
+    }
+
+    /// Title of a topic: the glossary term, or the identifier in words, e.g. "Still water level"
+    pub fn title(&self, id: &str) -> String {
+        if let Some(entry) = self.get(id) {
+            return entry.term.clone();
+        }
+        let words = id.trim_end_matches("_tooltip").replace('_', " ");
+        let mut characters = words.chars();
+        characters
+            .next()
+            .map_or(String::new(), |first| first.to_uppercase().chain(characters).collect())
+    }
+}
+
+/// Control whose explanation is shown in the help pane
+#[derive(Debug, Clone, PartialEq)]
+pub struct HelpTopic {
+    pub id: String,               // Identifier of the info button of the control
+    pub text: String,             // Short description, as in the info popup
+    pub equation: Option<String>, // Equation shown after the description, for controls with an equation popup
+    pub text_after: String,       // Text following the equation
+}
+
+impl HelpTopic {
+    /// Topic of a control with a text description
+    pub fn new(id: &str, text: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            text: text.to_string(),
+            equation: None,
+            text_after: String::new(),
+        }
+    }
+
+    /// Topic of a control described by an equation between two texts
+    pub fn with_equation(id: &str, equation: &str, text_parts: (&str, &str)) -> Self {
+        Self {
+            id: id.to_string(),
+            text: text_parts.0.to_string(),
+            equation: Some(equation.to_string()),
+            text_after: text_parts.1.to_string(),
+        }
+    }
+}
+
+/// Docked panel explaining the control under the pointer or with keyboard focus, or the selected tool
+pub struct HelpPane {
+    pub glossary: Glossary,
+}
+
+impl HelpPane {
+    /// Create help pane with a glossary
+    pub fn new(glossary: Glossary) -> Self {
+        Self { glossary }
+    }
+
+    /// Identifier of the overview entry of a tool
+    pub fn tool_id(tab: PlatformTab) -> &'static str {
+        match tab {
+            PlatformTab::WaveChannel => "wave_channel",
+            PlatformTab::DispersionExplorer => "dispersion_explorer",
+            PlatformTab::Comparison => "comparison",
+            PlatformTab::MonteCarlo => "monte_carlo",
+            PlatformTab::DesignOptimizer => "design_optimizer",
+            PlatformTab::WaveClimate => "scatter_diagram",
+            PlatformTab::Transects => "transects",
+            PlatformTab::WaveBasin => "wave_basin",
+            PlatformTab::Exercises => "exercises",
+        }
+    }
+
+    /// Show the topic, or the overview of the tool if there is none
+    pub fn show(&self, ui: &mut egui::Ui, ctx: &egui::Context, tab: PlatformTab, topic: Option<&HelpTopic>, equation_renderer: &mut EquationRenderer) {
+        let id = topic.map_or(Self::tool_id(tab), |topic| topic.id.as_str());
+        ui.heading(self.glossary.title(id));
+        ui.separator();
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            if let Some(topic) = topic {
+                if !topic.text.is_empty() {
+                    ui.label(&topic.text);
+                }
+                if let Some(equation) = &topic.equation {
+                    equation_renderer.show_equation(ui, ctx, equation, ui.available_width());
+                }
+                if !topic.text_after.is_empty() {
+                    ui.label(&topic.text_after);
+                }
+            }
+            let Some(entry) = self.glossary.get(id) else {
+                return;
+            };
+            if !entry.explanation.is_empty() {
+                ui.add_space(5.0);
+                ui.label(&entry.explanation);
+            }
+            // Related equations not already shown with the topic
+            let shown = topic.and_then(|topic| topic.equation.as_deref());
+            for equation in entry.equations.iter().filter(|equation| Some(equation.as_str()) != shown) {
+                ui.add_space(5.0);
+                match equation_renderer.get_equation(equation) {
+                    Some(definition) => ui.strong(&definition.description),
+                    None => ui.strong(equation),
+                };
+                equation_renderer.show_equation(ui, ctx, equation, ui.available_width());
+                if let Some(definition) = equation_renderer.get_equation(equation) {
+                    ui.weak(&definition.usage);
+                }
+            }
+        });
+    }
+}
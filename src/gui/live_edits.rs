@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+/// Parameter that can be changed during a run without restarting the solver
+///
+/// The stem drag of the vegetation is the only friction of the channel solver, which has no bed
+/// friction, and the solver has no wind forcing, so neither can be changed during a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LiveParameter {
+    /// Height of the generated waves, reached over the ramp time
+    WaveHeight,
+    /// Damping strength of the sponge layer
+    SpongeStrength,
+    /// Bulk drag coefficient of the vegetation patch, the friction of the stems
+    VegetationDrag,
+}
+
+impl LiveParameter {
+    pub const ALL: [LiveParameter; 3] = [LiveParameter::WaveHeight, LiveParameter::SpongeStrength, LiveParameter::VegetationDrag];
+
+    /// Unit of the parameter, empty if dimensionless
+    pub fn unit(&self) -> &'static str {
+        match self {
+            LiveParameter::WaveHeight => "m",
+            LiveParameter::SpongeStrength => "1/s",
+            LiveParameter::VegetationDrag => "",
+        }
+    }
+}
+
+impl std::fmt::Display for LiveParameter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LiveParameter::WaveHeight => write!(f, "Wave Height"),
+            LiveParameter::SpongeStrength => write!(f, "Sponge Strength"),
+            LiveParameter::VegetationDrag => write!(f, "Vegetation Drag"),
+        }
+    }
+}
+
+/// Change of a parameter during a run
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ParameterChange {
+    pub time: f64, // Simulation time of the change [s]
+    pub parameter: LiveParameter,
+    pub from: f64, // Value before the change
+    pub to: f64,   // Value after the change
+}
+
+impl std::fmt::Display for ParameterChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "t = {:.1} s: {} {:.3} → {:.3} {}", self.time, self.parameter, self.from, self.to, self.parameter.unit())
+    }
+}
+
+/// Parameters changed while a simulation ran or was paused, in time order
+///
+/// The values are observed before every step and compared with those of the previous step; a
+/// difference after the start of the run is logged as a change. Dragging a slider while the run
+/// goes on changes a value at several steps in a row, which are merged into a single change.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParameterLog {
+    changes: Vec<ParameterChange>,
+    observed: Vec<(LiveParameter, f64)>, // Values at the previous step
+    adjusting: Vec<LiveParameter>,       // Parameters changed at the previous step
+}
+
+impl ParameterLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Logged changes, in time order
+    pub fn changes(&self) -> &[ParameterChange] {
+        &self.changes
+    }
+
+    /// Check if no change was logged
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Compare the values at a time with those of the previous step, logging the differences
+    /// Values at the start of the run are the reference and never logged
+    pub fn observe(&mut self, time: f64, values: &[(LiveParameter, f64)]) {
+        let mut adjusting = Vec::new();
+        for &(parameter, value) in values {
+            let previous = self.observed.iter().find(|(observed, _)| *observed == parameter).map(|&(_, value)| value);
+            let Some(from) = previous.filter(|&previous| previous != value && time > 0.0) else {
+                continue;
+            };
+            adjusting.push(parameter);
+            let merged = self.adjusting.contains(&parameter).then(|| self.changes.iter_mut().rev().find(|change| change.parameter == parameter)).flatten();
+            match merged {
+                Some(change) => change.to = value,
+                None => self.changes.push(ParameterChange { time, parameter, from, to: value }),
+            }
+        }
+        // A slider dragged back to where it started leaves nothing to log
+        self.changes.retain(|change| change.from != change.to);
+        self.observed = values.to_vec();
+        self.adjusting = adjusting;
+    }
+
+    /// Value of a parameter at a time, ramped linearly over a duration after each change [s]
+    /// Parameters never changed keep their current value
+    pub fn value_at(&self, parameter: LiveParameter, time: f64, ramp: f64, current: f64) -> f64 {
+        let mut changes = self.changes.iter().filter(|change| change.parameter == parameter);
+        let Some(first) = changes.clone().next() else {
+            return current;
+        };
+        if time < first.time {
+            return first.from;
+        }
+        let Some(change) = changes.rfind(|change| change.time <= time) else {
+            return current;
+        };
+        if ramp > 0.0 && time < change.time + ramp {
+            change.from + (change.to - change.from) * (time - change.time) / ramp
+        } else {
+            change.to
+        }
+    }
+
+    /// Forget the changes and the observed values, before a new run
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
@@ -0,0 +1,171 @@
+/// Interactive measurement picked by clicking on the plots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MeasureTool {
+    #[default]
+    Off,
+    /// Two points on the channel view: horizontal distance, rise and slope
+    Slope,
+    /// Two crests on the gauge records: period and wave height
+    Wave,
+}
+
+impl std::fmt::Display for MeasureTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MeasureTool::Off => write!(f, "Off"),
+            MeasureTool::Slope => write!(f, "Distance and Slope"),
+            MeasureTool::Wave => write!(f, "Period and Height"),
+        }
+    }
+}
+
+/// Distance and slope between two points of the channel view
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlopeMeasurement {
+    pub start: [f64; 2], // Position along the channel and elevation [m]
+    pub end: [f64; 2],
+}
+
+impl SlopeMeasurement {
+    /// Horizontal distance between the points [m]
+    pub fn distance(&self) -> f64 {
+        (self.end[0] - self.start[0]).abs()
+    }
+
+    /// Rise of the elevation from the seaward to the landward point [m]
+    pub fn rise(&self) -> f64 {
+        let (left, right) = if self.start[0] <= self.end[0] { (self.start, self.end) } else { (self.end, self.start) };
+        right[1] - left[1]
+    }
+
+    /// Gradient rise / distance, positive upward toward the end of the channel
+    pub fn slope(&self) -> Option<f64> {
+        (self.distance() > 0.0).then(|| self.rise() / self.distance())
+    }
+
+    /// One line of the measurement, for the clipboard
+    pub fn to_text(&self) -> String {
+        let slope = match self.slope() {
+            Some(slope) if slope != 0.0 => format!("{:.4} (1:{:.1})", slope, 1.0 / slope.abs()),
+            Some(_) => "0 (flat)".to_string(),
+            None => "vertical".to_string(),
+        };
+        format!(
+            "Δx = {:.3} m, Δz = {:.3} m, slope = {} from x = {:.2} to {:.2} m",
+            self.distance(),
+            self.rise(),
+            slope,
+            self.start[0].min(self.end[0]),
+            self.start[0].max(self.end[0])
+        )
+    }
+}
+
+/// Period and height of a wave between two crests of a gauge record
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WaveMeasurement {
+    pub crests: [[f64; 2]; 2], // Time [s] and elevation [m] of both crests, earliest first
+    pub trough: [f64; 2],      // Lowest sample between the crests
+}
+
+impl WaveMeasurement {
+    /// Wave between the crests of a record nearest to two picked times
+    /// Each pick moves uphill along the record to the top of its crest
+    pub fn from_record(times: &[f64], elevation: &[f64], first: f64, second: f64) -> Result<Self, String> {
+        if times.len() < 3 || times.len() != elevation.len() {
+            return Err("The gauge has no record yet".to_string());
+        }
+        let crest = |time: f64| {
+            let mut i = times.partition_point(|&t| t < time).min(times.len() - 1);
+            if i > 0 && time - times[i - 1] < times[i] - time {
+                i -= 1;
+            }
+            loop {
+                if i + 1 < elevation.len() && elevation[i + 1] > elevation[i] {
+                    i += 1;
+                } else if i > 0 && elevation[i - 1] > elevation[i] {
+                    i -= 1;
+                } else {
+                    return i;
+                }
+            }
+        };
+        let (a, b) = (crest(first), crest(second));
+        let (a, b) = (a.min(b), a.max(b));
+        if a == b {
+            return Err("Both picks lead to the same crest; pick two different crests".to_string());
+        }
+        let lowest = (a..=b).min_by(|&i, &j| elevation[i].total_cmp(&elevation[j])).unwrap();
+        Ok(Self {
+            crests: [[times[a], elevation[a]], [times[b], elevation[b]]],
+            trough: [times[lowest], elevation[lowest]],
+        })
+    }
+
+    /// Time between the crests [s]
+    pub fn period(&self) -> f64 {
+        self.crests[1][0] - self.crests[0][0]
+    }
+
+    /// Mean crest elevation above the trough [m]
+    pub fn height(&self) -> f64 {
+        0.5 * (self.crests[0][1] + self.crests[1][1]) - self.trough[1]
+    }
+
+    /// One line of the measurement, for the clipboard
+    pub fn to_text(&self) -> String {
+        format!(
+            "T = {:.3} s, H = {:.4} m from crests at t = {:.2} and {:.2} s",
+            self.period(),
+            self.height(),
+            self.crests[0][0],
+            self.crests[1][0]
+        )
+    }
+}
+
+/// Crest-to-crest distance along the channel view compared with the linear wavelength
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WavelengthReading {
+    pub measured: f64,    // Mean distance between neighbouring crests [m]
+    pub theoretical: f64, // Mean linear wavelength at the depths midway between the same crests [m]
+    pub pairs: usize,     // Neighbouring crests averaged
+}
+
+impl WavelengthReading {
+    /// Mean of the distances between neighbouring crests and of the wavelengths there
+    /// `wavelength` gives the linear wavelength at a position, None where the channel is dry
+    pub fn from_crests(crests: impl IntoIterator<Item = f64>, wavelength: impl Fn(f64) -> Option<f64>) -> Option<Self> {
+        let (mut measured, mut theoretical, mut pairs) = (0.0, 0.0, 0);
+        let mut crests = crests.into_iter();
+        let mut previous = crests.next()?;
+        for crest in crests {
+            if let Some(length) = wavelength(0.5 * (previous + crest)) {
+                measured += crest - previous;
+                theoretical += length;
+                pairs += 1;
+            }
+            previous = crest;
+        }
+        (pairs > 0).then(|| Self {
+            measured: measured / pairs as f64,
+            theoretical: theoretical / pairs as f64,
+            pairs,
+        })
+    }
+
+    /// Measured minus theoretical wavelength, relative to the theoretical
+    pub fn difference(&self) -> f64 {
+        (self.measured - self.theoretical) / self.theoretical
+    }
+
+    /// Overlay of the channel view
+    pub fn to_text(&self) -> String {
+        format!(
+            "L measured = {:.2} m\nL linear = {:.2} m ({:+.1}%)",
+            self.measured,
+            self.theoretical,
+            100.0 * self.difference()
+        )
+    }
+}
@@ -0,0 +1,602 @@
+use super::background::BackgroundRun;
+use super::clipboard::plot_menu;
+use super::project::Project;
+use super::results_table::{ResultsTable, TableCell, TableView};
+use super::run_output::RunOutput;
+use super::wave_channel::WaveChannelApp;
+use super::workers::{DEFAULT_WORKER_ADDRESS, JobQueue, QUEUE_WAIT, WorkerList, WorkerStatus, new_token, serve_workers};
+use crate::analysis::monte_carlo::{ConvergencePoint, Distribution, OutputDistribution};
+use crate::waves::{DEFAULT_SEED, SeededRng};
+use eframe::egui;
+use egui_plot::{Legend, Line, LineStyle, Plot, PlotPoints, VLine};
+use serde::{Deserialize, Serialize};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+
+/// Input of the wave channel drawn from a distribution in every realization
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum UncertainParameter {
+    /// Height of the generated waves (H)
+    WaveHeight,
+    /// Period of the generated waves (T)
+    WavePeriod,
+    /// Still water level above the bed at the wavemaker
+    WaterLevel,
+    /// Bulk drag coefficient of the vegetation patch (C_D)
+    #[serde(alias = "Friction")]
+    VegetationDrag,
+}
+
+impl UncertainParameter {
+    pub const ALL: [UncertainParameter; 4] = [
+        UncertainParameter::WaveHeight,
+        UncertainParameter::WavePeriod,
+        UncertainParameter::WaterLevel,
+        UncertainParameter::VegetationDrag,
+    ];
+
+    /// Unit of the parameter, empty if dimensionless
+    pub fn unit(&self) -> &'static str {
+        match self {
+            UncertainParameter::WaveHeight | UncertainParameter::WaterLevel => "m",
+            UncertainParameter::WavePeriod => "s",
+            UncertainParameter::VegetationDrag => "",
+        }
+    }
+
+    /// Value of the parameter in a wave channel scenario, None if it has no vegetation to set the drag of
+    pub fn value(&self, channel: &WaveChannelApp) -> Option<f64> {
+        match self {
+            UncertainParameter::WaveHeight => Some(channel.wave_height),
+            UncertainParameter::WavePeriod => Some(channel.wave_period),
+            UncertainParameter::WaterLevel => Some(channel.still_water_level),
+            UncertainParameter::VegetationDrag => channel.vegetation.map(|vegetation| vegetation.drag_coefficient),
+        }
+    }
+
+    /// Set the parameter of a wave channel scenario to a drawn value
+    pub fn apply(&self, channel: &mut WaveChannelApp, value: f64) -> Result<(), String> {
+        if !value.is_finite() || value <= 0.0 {
+            return Err(format!("Drawn {} {:.4} {} is not positive", self, value, self.unit()));
+        }
+        match self {
+            UncertainParameter::WaveHeight => channel.wave_height = value,
+            UncertainParameter::WavePeriod => channel.wave_period = value,
+            UncertainParameter::WaterLevel => channel.still_water_level = value,
+            UncertainParameter::VegetationDrag => match channel.vegetation.as_mut() {
+                Some(vegetation) => vegetation.drag_coefficient = value,
+                None => return Err("Vegetation drag needs a vegetation patch in the channel".to_string()),
+            },
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for UncertainParameter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UncertainParameter::WaveHeight => write!(f, "Wave Height"),
+            UncertainParameter::WavePeriod => write!(f, "Wave Period"),
+            UncertainParameter::WaterLevel => write!(f, "Water Level"),
+            UncertainParameter::VegetationDrag => write!(f, "Vegetation Drag (C_D)"),
+        }
+    }
+}
+
+/// Uncertain parameter and the distribution it is drawn from
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct UncertainInput {
+    pub parameter: UncertainParameter,
+    pub distribution: Distribution,
+}
+
+/// Drawn inputs and output of one realization
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Realization {
+    pub index: usize,
+    pub inputs: Vec<f64>,               // Drawn values, in the order of the study inputs
+    pub output: Result<f64, String>,    // Output value, or why the realization failed
+}
+
+/// Probabilistic study of the wave channel scenario
+///
+/// Every realization copies the scenario, draws the uncertain inputs from their distributions and
+/// runs the simulation to its end. Realization i draws from stream i of the seeded generator, so
+/// the inputs do not depend on the number of threads or the order the realizations complete in,
+/// and a study with the same seed is reproduced exactly.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonteCarloStudy {
+    pub inputs: Vec<UncertainInput>,
+    pub output: RunOutput,
+    pub realizations: usize,      // Number of realizations to run
+    pub seed: u64,                // Seed of the drawn inputs
+    pub threads: usize,           // Realizations run in parallel
+    pub quantile: f64,            // Non-exceedance probability tracked in the convergence diagnostics
+    pub tolerance: f64,           // Standard error of the mean, relative to the mean, considered converged
+}
+
+impl Default for MonteCarloStudy {
+    fn default() -> Self {
+        Self {
+            inputs: Vec::new(),
+            output: RunOutput::MaxElevation { gauge: 0 },
+            realizations: 100,
+            seed: DEFAULT_SEED,
+            threads: std::thread::available_parallelism().map_or(1, |threads| threads.get()),
+            quantile: 0.9,
+            tolerance: 0.02,
+        }
+    }
+}
+
+impl MonteCarloStudy {
+    /// Check the study against the scenario it varies
+    pub fn validate(&self, scenario: &Project) -> Result<(), String> {
+        if self.inputs.is_empty() {
+            return Err("Select at least one uncertain input".to_string());
+        }
+        if self.realizations == 0 || self.threads == 0 {
+            return Err("The study needs at least one realization and one thread".to_string());
+        }
+        for input in &self.inputs {
+            input.distribution.validate().map_err(|message| format!("{}: {}", input.parameter, message))?;
+        }
+        let uses_drag = self.inputs.iter().any(|input| input.parameter == UncertainParameter::VegetationDrag);
+        if uses_drag && scenario.vegetation.is_none() {
+            return Err("Vegetation drag needs a vegetation patch in the channel".to_string());
+        }
+        self.output.validate(scenario)
+    }
+
+    /// Drawn inputs of a realization
+    pub fn draw(&self, index: usize) -> Vec<f64> {
+        let mut rng = SeededRng::new(self.seed).stream(index as u64);
+        self.inputs.iter().map(|input| input.distribution.sample(&mut rng)).collect()
+    }
+
+    /// Run one realization of a scenario to its end
+    pub fn realization(&self, scenario: &Project, index: usize) -> Realization {
+        let inputs = self.draw(index);
+        let output = self.simulate(scenario, &inputs);
+        Realization { index, inputs, output }
+    }
+
+    /// Output of a scenario with drawn inputs
+    fn simulate(&self, scenario: &Project, inputs: &[f64]) -> Result<f64, String> {
+        let mut channel = WaveChannelApp::new();
+        channel.load_project(scenario);
+        for (input, &value) in self.inputs.iter().zip(inputs) {
+            input.parameter.apply(&mut channel, value)?;
+        }
+        self.output.evaluate(&mut channel)
+    }
+
+    /// Run the realizations of a scenario on the threads of the study, sending each as it completes
+    /// Stops early when cancelled or when the receiver is gone
+    pub fn run(&self, scenario: &Project, cancel: &AtomicBool, sender: &Sender<Realization>) {
+        self.run_queue(scenario, &JobQueue::new(self.realizations), cancel, sender);
+    }
+
+    /// Run the realizations of a scenario on the threads of the study and on the remote workers connecting
+    /// to a listener, sending each as it completes
+    pub fn run_distributed(&self, scenario: &Project, listener: &TcpListener, token: &str, workers: &WorkerList, cancel: &AtomicBool, sender: &Sender<Realization>) {
+        let queue = JobQueue::new(self.realizations);
+        std::thread::scope(|scope| {
+            scope.spawn(|| serve_workers(listener, token, self, scenario, &queue, workers, cancel, sender));
+            self.run_queue(scenario, &queue, cancel, sender);
+        });
+    }
+
+    /// Run the jobs of a queue on the threads of the study until every job is done
+    /// Threads finding no job wait for those running elsewhere, which may be given back
+    fn run_queue(&self, scenario: &Project, queue: &JobQueue, cancel: &AtomicBool, sender: &Sender<Realization>) {
+        std::thread::scope(|scope| {
+            for _ in 0..self.threads.max(1) {
+                scope.spawn(|| {
+                    while !cancel.load(Ordering::Relaxed) {
+                        match queue.take() {
+                            Some(index) => {
+                                let realization = self.realization(scenario, index);
+                                queue.finish();
+                                if sender.send(realization).is_err() {
+                                    break;
+                                }
+                            }
+                            None if queue.is_finished() => break,
+                            None => std::thread::sleep(QUEUE_WAIT),
+                        }
+                    }
+                });
+            }
+        });
+    }
+
+    /// Run every realization of a scenario, waiting for the results
+    pub fn run_to_completion(&self, scenario: &Project) -> MonteCarloResults {
+        let (sender, receiver) = mpsc::channel();
+        self.run(scenario, &AtomicBool::new(false), &sender);
+        drop(sender);
+        let mut results = MonteCarloResults::default();
+        results.extend(receiver.try_iter());
+        results
+    }
+}
+
+/// Realizations of a study, by index
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MonteCarloResults {
+    realizations: Vec<Realization>,
+}
+
+impl MonteCarloResults {
+    /// Add completed realizations, keeping the index order
+    pub fn extend(&mut self, realizations: impl IntoIterator<Item = Realization>) {
+        self.realizations.extend(realizations);
+        self.realizations.sort_by_key(|realization| realization.index);
+    }
+
+    /// Completed realizations, by index
+    pub fn realizations(&self) -> &[Realization] {
+        &self.realizations
+    }
+
+    /// Number of completed realizations, failed ones included
+    pub fn len(&self) -> usize {
+        self.realizations.len()
+    }
+
+    /// Check if no realization completed
+    pub fn is_empty(&self) -> bool {
+        self.realizations.is_empty()
+    }
+
+    /// Outputs of the successful realizations, by index
+    pub fn output(&self) -> OutputDistribution {
+        OutputDistribution::new(self.realizations.iter().filter_map(|realization| realization.output.as_ref().ok().copied()).collect())
+    }
+
+    /// Realizations that failed, with the reason
+    pub fn failures(&self) -> impl Iterator<Item = &Realization> {
+        self.realizations.iter().filter(|realization| realization.output.is_err())
+    }
+
+    /// Forget the realizations, before a new study
+    pub fn clear(&mut self) {
+        self.realizations.clear();
+    }
+}
+
+/// Monte Carlo runner of the wave channel scenario, with the output distribution and its convergence
+pub struct MonteCarloApp {
+    pub study: MonteCarloStudy,
+    pub results: MonteCarloResults,
+    pub accept_workers: bool,    // Hand realizations to headless instances on other machines too
+    pub worker_address: String,  // Address the workers connect to
+    pub worker_token: String,    // Secret the workers present before receiving the study
+    running: Option<BackgroundRun<Realization>>,
+    listening: Option<SocketAddr>, // Address of the running study accepting workers
+    workers: WorkerList,           // Workers of the running study
+    status: Option<String>,        // Why the last study could not start
+    table: TableView,              // Sorting and filtering of the realizations table
+}
+
+impl Default for MonteCarloApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MonteCarloApp {
+    pub fn new() -> Self {
+        Self {
+            study: MonteCarloStudy::default(),
+            results: MonteCarloResults::default(),
+            accept_workers: false,
+            worker_address: DEFAULT_WORKER_ADDRESS.to_string(),
+            worker_token: new_token(),
+            running: None,
+            listening: None,
+            workers: WorkerList::default(),
+            status: None,
+            table: TableView::default(),
+        }
+    }
+
+    /// Start the study of a scenario on background threads, and on remote workers if they are accepted,
+    /// forgetting previous results
+    pub fn start(&mut self, scenario: Project) -> Result<(), String> {
+        self.study.validate(&scenario)?;
+        self.cancel();
+        self.results.clear();
+        let study = self.study.clone();
+        if !self.accept_workers {
+            self.running = Some(BackgroundRun::spawn(move |cancel, sender| study.run(&scenario, cancel, sender)));
+            return Ok(());
+        }
+        let listener = TcpListener::bind(&self.worker_address).map_err(|e| format!("Cannot listen for workers on {}: {}", self.worker_address, e))?;
+        self.listening = listener.local_addr().ok();
+        let workers = Arc::new(Mutex::new(Vec::new()));
+        self.workers = Arc::clone(&workers);
+        let token = self.worker_token.clone();
+        self.running = Some(BackgroundRun::spawn(move |cancel, sender| study.run_distributed(&scenario, &listener, &token, &workers, cancel, sender)));
+        Ok(())
+    }
+
+    /// Address the running study accepts workers on, with the port picked if it was 0
+    pub fn listening_address(&self) -> Option<SocketAddr> {
+        self.listening.filter(|_| self.is_running())
+    }
+
+    /// Workers that connected to the last study, with the realizations each completed
+    pub fn workers(&self) -> Vec<WorkerStatus> {
+        self.workers.lock().unwrap().clone()
+    }
+
+    /// Stop the study, keeping the completed realizations
+    pub fn cancel(&mut self) {
+        if let Some(running) = self.running.take() {
+            self.results.extend(running.cancel());
+        }
+    }
+
+    /// Collect the realizations completed since the last poll
+    pub fn poll(&mut self) {
+        let Some(running) = &self.running else {
+            return;
+        };
+        let (realizations, finished) = running.poll();
+        self.results.extend(realizations);
+        if finished {
+            self.running = None;
+        }
+    }
+
+    /// Whether a study is running
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// Fraction of the realizations completed
+    pub fn progress(&self) -> f64 {
+        self.results.len() as f64 / self.study.realizations.max(1) as f64
+    }
+
+    /// Show the study setup and results for the scenario of the wave channel
+    pub fn show(&mut self, ui: &mut egui::Ui, channel: &WaveChannelApp) {
+        self.poll();
+        ui.heading("Monte Carlo Study");
+        ui.label("Draws the uncertain inputs of the wave channel scenario from their distributions, runs every realization to its end and reports the distribution of the output.");
+        ui.separator();
+
+        ui.add_enabled_ui(!self.is_running(), |ui| {
+            self.show_inputs(ui, channel);
+            ui.separator();
+            self.show_settings(ui, channel);
+        });
+
+        ui.horizontal(|ui| {
+            if self.is_running() {
+                if ui.button("⏹ Cancel").clicked() {
+                    self.cancel();
+                }
+                ui.add(egui::ProgressBar::new(self.progress() as f32).desired_width(200.0).show_percentage());
+                channel.repaint_policy.request_progress(ui.ctx());
+            } else if ui.button("▶ Run Study").clicked() {
+                self.status = self.start(channel.project()).err();
+            }
+            ui.label(format!("{} / {} realizations", self.results.len(), self.study.realizations));
+        });
+        self.show_workers(ui);
+        if let Some(status) = &self.status {
+            ui.colored_label(egui::Color32::from_rgb(200, 80, 0), format!("⚠ {}", status));
+        }
+        self.show_results(ui);
+    }
+
+    /// Uncertain inputs and their distributions
+    fn show_inputs(&mut self, ui: &mut egui::Ui, channel: &WaveChannelApp) {
+        ui.strong("Uncertain Inputs");
+        egui::Grid::new("monte_carlo_inputs").num_columns(4).striped(true).show(ui, |ui| {
+            for parameter in UncertainParameter::ALL {
+                let Some(base) = parameter.value(channel) else {
+                    ui.add_enabled(false, egui::Checkbox::new(&mut false, parameter.to_string()))
+                        .on_disabled_hover_text("Add a vegetation patch to vary its drag coefficient");
+                    ui.end_row();
+                    continue;
+                };
+                let position = self.study.inputs.iter().position(|input| input.parameter == parameter);
+                let mut enabled = position.is_some();
+                if ui.checkbox(&mut enabled, parameter.to_string()).changed() {
+                    match position {
+                        Some(i) => {
+                            self.study.inputs.remove(i);
+                        }
+                        None => self.study.inputs.push(UncertainInput {
+                            parameter,
+                            distribution: Distribution::Normal { mean: base, std_dev: 0.1 * base },
+                        }),
+                    }
+                }
+                let Some(input) = self.study.inputs.iter_mut().find(|input| input.parameter == parameter) else {
+                    ui.end_row();
+                    continue;
+                };
+                egui::ComboBox::from_id_salt(("monte_carlo_distribution", parameter))
+                    .selected_text(input.distribution.name())
+                    .show_ui(ui, |ui| {
+                        for name in Distribution::NAMES {
+                            if ui.selectable_label(input.distribution.name() == name, name).clicked() {
+                                input.distribution = input.distribution.with_kind(name);
+                            }
+                        }
+                    });
+                let unit = format!(" {}", parameter.unit());
+                let speed = 0.01 * base.max(0.01);
+                ui.horizontal(|ui| {
+                    let (first, second, labels) = match &mut input.distribution {
+                        Distribution::Uniform { low, high } => (low, high, ["Low", "High"]),
+                        Distribution::Normal { mean, std_dev } | Distribution::LogNormal { mean, std_dev } => (mean, std_dev, ["Mean", "Std. Dev."]),
+                        Distribution::Gumbel { location, scale } => (location, scale, ["Location", "Scale"]),
+                    };
+                    ui.label(labels[0]);
+                    ui.add(egui::DragValue::new(first).speed(speed).suffix(unit.clone()));
+                    ui.label(labels[1]);
+                    ui.add(egui::DragValue::new(second).speed(speed).suffix(unit));
+                });
+                ui.label(format!("mean {:.3}, σ {:.3}", input.distribution.mean(), input.distribution.std_dev()));
+                ui.end_row();
+            }
+        });
+    }
+
+    /// Remote workers of the study and the realizations they completed
+    fn show_workers(&self, ui: &mut egui::Ui) {
+        let workers = self.workers();
+        if let Some(address) = self.listening_address() {
+            let connected = workers.iter().filter(|worker| worker.connected).count();
+            ui.label(format!("Accepting workers on port {}: {} connected", address.port(), connected));
+        }
+        if workers.is_empty() {
+            return;
+        }
+        let local = self.results.len() - workers.iter().map(|worker| worker.completed).sum::<usize>().min(self.results.len());
+        egui::CollapsingHeader::new(format!("Workers ({})", workers.len())).id_salt("monte_carlo_workers").show(ui, |ui| {
+            ui.label(format!("This machine: {} realizations", local));
+            for worker in &workers {
+                let state = if worker.connected { "" } else { " (disconnected)" };
+                ui.label(format!("{}: {} realizations{}", worker.address, worker.completed, state));
+            }
+        });
+    }
+
+    /// Output, number of realizations, seed and diagnostics settings
+    fn show_settings(&mut self, ui: &mut egui::Ui, channel: &WaveChannelApp) {
+        let study = &mut self.study;
+        ui.horizontal(|ui| {
+            ui.label("Output:");
+            egui::ComboBox::from_id_salt("monte_carlo_output")
+                .selected_text(study.output.label())
+                .width(200.0)
+                .show_ui(ui, |ui| {
+                    for output in RunOutput::available(channel.gauges.len()) {
+                        ui.selectable_value(&mut study.output, output, output.label());
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            ui.label("Realizations:");
+            ui.add(egui::DragValue::new(&mut study.realizations).range(1..=100_000));
+            ui.label("Threads:");
+            ui.add(egui::DragValue::new(&mut study.threads).range(1..=256));
+            ui.label("Seed:");
+            ui.add(egui::DragValue::new(&mut study.seed));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Tracked Quantile:");
+            ui.add(egui::DragValue::new(&mut study.quantile).range(0.5..=0.999).speed(0.001).fixed_decimals(3));
+            ui.label("Convergence Tolerance:");
+            ui.add(egui::DragValue::new(&mut study.tolerance).range(0.001..=0.5).speed(0.001).custom_formatter(|value, _| format!("{:.1}%", 100.0 * value)));
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.accept_workers, "Accept remote workers on");
+            ui.add_enabled(self.accept_workers, egui::TextEdit::singleline(&mut self.worker_address).desired_width(140.0));
+            ui.label("Token:");
+            ui.add_enabled(self.accept_workers, egui::TextEdit::singleline(&mut self.worker_token).desired_width(260.0));
+        })
+        .response
+        .on_hover_text("Let headless instances on other machines, e.g. the computers of a classroom, run realizations of the study: start them with --worker HOST:PORT --token TOKEN, HOST being this machine. The default address only accepts workers on this machine; enter the address of its network interface, or 0.0.0.0 for all of them, to reach the others. Only workers presenting the token receive the scenario and may send results. Each pulls realizations over TCP as this machine does with its threads and sends back the results, which are the same as if they ran here. A worker can join or leave at any time; a realization it leaves unfinished is run again.");
+    }
+
+    /// Completed realizations, a row per realization with its drawn inputs
+    pub fn results_table(&self) -> ResultsTable {
+        let inputs = self.study.inputs.iter().map(|input| format!("{} ({})", input.parameter, input.parameter.unit()));
+        let output = format!("{} ({})", self.study.output.label(), self.study.output.unit());
+        let mut table = ResultsTable::new(std::iter::once("#".to_string()).chain(inputs).chain([output]));
+        for realization in self.results.realizations() {
+            let index = TableCell::Number((realization.index + 1) as f64);
+            let inputs = realization.inputs.iter().map(|&value| TableCell::Number(value));
+            table.push(std::iter::once(index).chain(inputs).chain([TableCell::output(&realization.output)]).collect());
+        }
+        table
+    }
+
+    /// Output statistics, exceedance curve and convergence of the estimates, and the realizations
+    fn show_results(&mut self, ui: &mut egui::Ui) {
+        let output = self.results.output();
+        let failures: Vec<&Realization> = self.results.failures().collect();
+        if !failures.is_empty() {
+            let reason = failures[0].output.as_ref().err().cloned().unwrap_or_default();
+            ui.label(format!("⚠ {} realizations failed, e.g. #{}: {}", failures.len(), failures[0].index + 1, reason));
+        }
+        if output.is_empty() {
+            return;
+        }
+        ui.separator();
+        let p = self.study.quantile;
+        ui.label(format!(
+            "{}: mean {:.4} ± {:.4} (standard error), σ {:.4}, median {:.4}, P{:.0} {:.4}, max {:.4} {}",
+            self.study.output.label(),
+            output.mean(),
+            output.standard_error(),
+            output.std_dev(),
+            output.quantile(0.5),
+            100.0 * p,
+            output.quantile(p),
+            output.quantile(1.0),
+            self.study.output.unit(),
+        ));
+        if output.is_converged(self.study.tolerance) {
+            ui.colored_label(egui::Color32::from_rgb(0, 150, 0), "✔ Mean converged within the tolerance");
+        } else {
+            ui.label("Mean not converged yet: run more realizations to reduce the standard error.");
+        }
+
+        let width = (ui.available_width() - 40.0).max(400.0);
+        ui.columns(2, |columns| {
+            columns[0].strong("Exceedance Curve");
+            let curve: Vec<[f64; 2]> = output.exceedance_curve().into_iter().map(|[value, probability]| [value, probability.log10()]).collect();
+            let exceedance = Plot::new("monte_carlo_exceedance")
+                .height(250.0)
+                .width(0.5 * width)
+                .x_axis_label(format!("{} ({})", self.study.output.label(), self.study.output.unit()))
+                .y_axis_label("log₁₀ P(exceedance)")
+                .show(&mut columns[0], |plot_ui| {
+                    plot_ui.line(Line::new(PlotPoints::from(curve)).color(egui::Color32::from_rgb(0, 100, 200)).name("Exceedance"));
+                    plot_ui.vline(VLine::new(output.quantile(p)).color(egui::Color32::GRAY).style(LineStyle::dashed_dense()));
+                });
+            plot_menu(&exceedance.response);
+
+            columns[1].strong("Convergence");
+            let convergence = output.convergence(p);
+            let series = |value: fn(&ConvergencePoint) -> f64| -> Vec<[f64; 2]> {
+                convergence.iter().map(|point| [point.samples as f64, value(point)]).collect()
+            };
+            let estimates = Plot::new("monte_carlo_convergence")
+                .height(250.0)
+                .width(0.5 * width)
+                .legend(Legend::default())
+                .x_axis_label("Realizations")
+                .y_axis_label(format!("Estimate ({})", self.study.output.unit()))
+                .show(&mut columns[1], |plot_ui| {
+                    plot_ui.line(Line::new(PlotPoints::from(series(|point| point.mean))).color(egui::Color32::from_rgb(0, 100, 200)).name("Mean"));
+                    let band = egui::Color32::from_rgb(100, 160, 230);
+                    plot_ui.line(Line::new(PlotPoints::from(series(|point| point.mean + 1.96 * point.standard_error))).color(band).style(LineStyle::dashed_dense()).name("95% interval"));
+                    plot_ui.line(Line::new(PlotPoints::from(series(|point| point.mean - 1.96 * point.standard_error))).color(band).style(LineStyle::dashed_dense()));
+                    plot_ui.line(Line::new(PlotPoints::from(series(|point| point.quantile))).color(egui::Color32::from_rgb(255, 140, 0)).name(format!("P{:.0}", 100.0 * p)));
+                });
+            plot_menu(&estimates.response);
+        });
+        let table = self.results_table();
+        egui::CollapsingHeader::new(format!("Realizations ({})", self.results.len())).show(ui, |ui| {
+            table.show(ui, "monte_carlo_realizations", &mut self.table);
+        });
+    }
+}
+
+impl Drop for MonteCarloApp {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
@@ -0,0 +1,496 @@
+use super::wave_channel::WaveChannelApp;
+use crate::analysis::WaveGauge;
+use crate::solver::RightBoundary;
+use crate::waves::{WATER_DENSITY, WaveTheory};
+use crate::waves::wavemaker::linear_wave_number;
+use std::path::Path;
+
+/// Cells per wave length along the flume
+const CELLS_PER_WAVELENGTH: f64 = 100.0;
+
+/// Cells per wave height over the depth
+const CELLS_PER_WAVE_HEIGHT: f64 = 10.0;
+
+/// Height of the flume walls above the still water level or the crest, in wave heights
+const FREEBOARD_WAVE_HEIGHTS: f64 = 3.0;
+
+/// Thickness of the single cell across the 2D flume [m]
+const FLUME_THICKNESS: f64 = 0.1;
+
+/// Skeleton of an olaFlow (OpenFOAM) wave flume case reproducing the channel
+///
+/// The flume is a 2D mesh, one cell thick, from the wavemaker to the end of the channel and from the
+/// bed to a freeboard of three wave heights above the still water level or the crest. One hex block
+/// follows each segment of the cross-section, with 100 cells per wave length of the shortest
+/// generated waves and 10 cells per wave height over the depth. The wavemaker waves are generated
+/// at the inlet in the generation theory of the channel when regular, or as the first-order Stokes
+/// components of the boundary spectrum, with active absorption as configured; the wave gauges become interface height probes. Features
+/// without an olaFlow counterpart in the skeleton are listed in the README of the case.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenFoamCase {
+    files: Vec<(String, String)>, // Path relative to the case directory and content
+    notes: Vec<String>,           // Channel settings left out of the case
+    cells: usize,                 // Number of cells of the mesh
+}
+
+impl OpenFoamCase {
+    /// Case reproducing the geometry, waves and gauges of the channel
+    pub fn new(app: &WaveChannelApp) -> Result<Self, String> {
+        if app.initial_condition.is_some() {
+            return Err("The OpenFOAM export generates the wavemaker waves; switch from the initial-value scenario".to_string());
+        }
+        let components = app.spectral_components();
+        let shortest_period = components.iter().map(|component| component.period()).fold(f64::INFINITY, f64::min);
+        let (wave_height, shortest_period) = match (&app.boundary_signal, &app.boundary_spectrum) {
+            // Significant wave height of the signal, Hm0 = 4√m0 with m0 = Σ a²/2
+            (Some(_), _) if !components.is_empty() => {
+                let variance: f64 = components.iter().map(|component| 0.5 * component.amplitude * component.amplitude).sum();
+                (4.0 * variance.sqrt(), shortest_period)
+            }
+            (None, Some(spectrum)) if !components.is_empty() => (spectrum.significant_wave_height(), shortest_period),
+            _ => (app.wave_height, app.wave_period),
+        };
+        if wave_height <= 0.0 || shortest_period <= 0.0 {
+            return Err("Wave height and period must be positive".to_string());
+        }
+        let depth = app.still_water_level;
+        let wavelength = 2.0 * std::f64::consts::PI / linear_wave_number(2.0 * std::f64::consts::PI / shortest_period, depth, 9.81)?;
+
+        // Stations along the flume at the ends of the cross-section segments
+        let mut stations: Vec<[f64; 2]> = match &app.cross_section {
+            Some(section) => section.breakpoints().into_iter().filter(|point| point[0] < app.channel_length - 1e-9).collect(),
+            None => vec![[0.0, 0.0]],
+        };
+        stations.push([app.channel_length, app.bed_elevation(app.channel_length)]);
+        let crest = stations.iter().map(|point| point[1]).fold(0.0, f64::max);
+        let top = depth.max(crest) + FREEBOARD_WAVE_HEIGHTS * wave_height;
+        let vertical_cells = (top / (wave_height / CELLS_PER_WAVE_HEIGHT)).ceil() as usize;
+        let horizontal_cells: Vec<usize> = stations
+            .windows(2)
+            .map(|pair| ((pair[1][0] - pair[0][0]) / (wavelength / CELLS_PER_WAVELENGTH)).ceil().max(1.0) as usize)
+            .collect();
+        let cells = vertical_cells * horizontal_cells.iter().sum::<usize>();
+
+        let mut notes = Vec::new();
+        if !app.forcing_schedule.is_empty() {
+            notes.push("The forcing schedule is not exported: the inlet generates the initial wavemaker waves throughout".to_string());
+        }
+        if app.storm_hydrograph.is_some() {
+            notes.push("The storm hydrograph is not exported: the inlet generates the wavemaker waves at the still water level".to_string());
+        }
+        if !app.additional_sources.is_empty() {
+            notes.push("Internal wave sources are not exported; olaFlow generates waves at the boundaries only".to_string());
+        }
+        if app.vegetation.is_some() {
+            notes.push("The vegetation patch is not exported; model it as a porous zone (constant/porosityDict)".to_string());
+        }
+        if app.ambient_current.is_some() {
+            notes.push("The ambient current is not exported; add it as a current velocity of the inlet (constant/waveDict)".to_string());
+        }
+        if app.overtopping_tank.is_some() {
+            notes.push("The overtopping tank is not exported; water passing the crest leaves through the outlet".to_string());
+        }
+        let theory = app.generation_theory();
+        if components.is_empty() && theory == WaveTheory::StreamFunction {
+            notes.push("The stream function coefficients (uMean, Bjs, Ejs) are not exported; compute them with the StreamFunction tool of olaFlow and add them to constant/waveDict".to_string());
+        }
+        if let Some(selected) = app.wave_theory.filter(|&theory| !components.is_empty() && theory != WaveTheory::Linear) {
+            notes.push(format!("The {} theory is not exported for irregular waves, generated as first-order Stokes components", selected));
+        }
+        match app.right_boundary {
+            RightBoundary::Sponge { .. } => notes.push("The sponge layer is replaced by active absorption at the outlet".to_string()),
+            RightBoundary::Periodic => notes.push("The periodic boundary is replaced by a wall at the outlet".to_string()),
+            RightBoundary::Wall | RightBoundary::Radiation => {}
+        }
+
+        let absorbing_outlet = matches!(app.right_boundary, RightBoundary::Sponge { .. } | RightBoundary::Radiation);
+        let gauges: Vec<[f64; 3]> = app.gauges.iter().map(WaveGauge::position).map(|x| [x, 0.5 * FLUME_THICKNESS, app.bed_elevation(x)]).collect();
+        let end_time = app.total_simulation_time();
+        let write_interval = app.snapshot_interval;
+
+        let files = vec![
+            ("system/blockMeshDict".to_string(), block_mesh_dict(&stations, top, &horizontal_cells, vertical_cells)),
+            ("system/controlDict".to_string(), control_dict(end_time, write_interval, &gauges)),
+            ("system/fvSchemes".to_string(), fv_schemes()),
+            ("system/fvSolution".to_string(), fv_solution()),
+            ("system/setFieldsDict".to_string(), set_fields_dict(app.channel_length, top, depth)),
+            ("constant/g".to_string(), gravity()),
+            ("constant/transportProperties".to_string(), transport_properties()),
+            ("constant/turbulenceProperties".to_string(), turbulence_properties()),
+            ("constant/waveDict".to_string(), wave_dict(app, &components, theory)),
+            ("0/alpha.water".to_string(), alpha_field()),
+            ("0/U".to_string(), velocity_field(absorbing_outlet)),
+            ("0/p_rgh".to_string(), pressure_field()),
+            ("README".to_string(), readme(cells, wavelength, &notes)),
+        ];
+        Ok(Self { files, notes, cells })
+    }
+
+    /// Get files of the case, relative to the case directory, with their content
+    pub fn files(&self) -> &[(String, String)] {
+        &self.files
+    }
+
+    /// Content of a file of the case
+    pub fn file(&self, name: &str) -> Option<&str> {
+        self.files.iter().find(|(path, _)| path == name).map(|(_, content)| content.as_str())
+    }
+
+    /// Get channel settings left out of the case
+    pub fn notes(&self) -> &[String] {
+        &self.notes
+    }
+
+    /// Get number of cells of the mesh
+    pub fn cells(&self) -> usize {
+        self.cells
+    }
+
+    /// Write the case to a directory, creating it and its subdirectories
+    pub fn write(&self, directory: &Path) -> Result<(), String> {
+        for (name, content) in &self.files {
+            let path = directory.join(name);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| format!("Cannot create {}: {}", parent.display(), e))?;
+            }
+            std::fs::write(&path, content).map_err(|e| format!("Cannot write {}: {}", path.display(), e))?;
+        }
+        Ok(())
+    }
+}
+
+/// FoamFile header of a dictionary or field file
+fn header(class: &str, location: &str, object: &str) -> String {
+    format!(
+        "/*--------------------------------*- C++ -*----------------------------------*\\\n  Generated by the Coastal Engineering Platform {}\n\\*---------------------------------------------------------------------------*/\n\
+         FoamFile\n{{\n    format      ascii;\n    class       {};\n    location    \"{}\";\n    object      {};\n}}\n\n",
+        env!("CARGO_PKG_VERSION"),
+        class,
+        location,
+        object
+    )
+}
+
+/// Coordinates of a point, in the OpenFOAM notation
+fn point(x: f64, y: f64, z: f64) -> String {
+    format!("({} {} {})", x, y, z)
+}
+
+/// Mesh of hex blocks between the stations along the flume
+/// Every station has four vertices: 4i and 4i + 1 on the bed at the front and back, 4i + 2 and 4i + 3 at the top
+fn block_mesh_dict(stations: &[[f64; 2]], top: f64, horizontal_cells: &[usize], vertical_cells: usize) -> String {
+    let mut dict = header("dictionary", "system", "blockMeshDict");
+    dict.push_str("convertToMeters 1;\n\nvertices\n(\n");
+    for &[x, bed] in stations {
+        for (y, z) in [(0.0, bed), (FLUME_THICKNESS, bed), (0.0, top), (FLUME_THICKNESS, top)] {
+            dict.push_str(&format!("    {}\n", point(x, y, z)));
+        }
+    }
+    dict.push_str(");\n\nblocks\n(\n");
+    for (i, cells) in horizontal_cells.iter().enumerate() {
+        let (a, b) = (4 * i, 4 * (i + 1));
+        dict.push_str(&format!(
+            "    hex ({} {} {} {} {} {} {} {}) ({} 1 {}) simpleGrading (1 1 1)\n",
+            a,
+            b,
+            b + 1,
+            a + 1,
+            a + 2,
+            b + 2,
+            b + 3,
+            a + 3,
+            cells,
+            vertical_cells
+        ));
+    }
+    let last = 4 * horizontal_cells.len();
+    let faces = |face: &dyn Fn(usize, usize) -> [usize; 4]| -> String {
+        (0..horizontal_cells.len())
+            .map(|i| {
+                let [p, q, r, s] = face(4 * i, 4 * (i + 1));
+                format!("            ({} {} {} {})\n", p, q, r, s)
+            })
+            .collect()
+    };
+    dict.push_str(");\n\nedges\n(\n);\n\nboundary\n(\n");
+    dict.push_str("    inlet\n    {\n        type patch;\n        faces\n        (\n            (0 2 3 1)\n        );\n    }\n");
+    dict.push_str(&format!(
+        "    outlet\n    {{\n        type patch;\n        faces\n        (\n            ({} {} {} {})\n        );\n    }}\n",
+        last,
+        last + 1,
+        last + 3,
+        last + 2
+    ));
+    dict.push_str(&format!("    bottom\n    {{\n        type wall;\n        faces\n        (\n{}        );\n    }}\n", faces(&|a, b| [a, a + 1, b + 1, b])));
+    dict.push_str(&format!("    atmosphere\n    {{\n        type patch;\n        faces\n        (\n{}        );\n    }}\n", faces(&|a, b| [a + 2, b + 2, b + 3, a + 3])));
+    let front_back = faces(&|a, b| [a, b, b + 2, a + 2]) + &faces(&|a, b| [a + 1, a + 3, b + 3, b + 1]);
+    dict.push_str(&format!("    frontBack\n    {{\n        type empty;\n        faces\n        (\n{}        );\n    }}\n", front_back));
+    dict.push_str(");\n\nmergePatchPairs\n(\n);\n");
+    dict
+}
+
+/// Run control of olaFoam, with the gauges as interface height probes
+fn control_dict(end_time: f64, write_interval: f64, gauges: &[[f64; 3]]) -> String {
+    let mut dict = header("dictionary", "system", "controlDict");
+    dict.push_str(&format!(
+        "application     olaFoam;\n\nstartFrom       startTime;\nstartTime       0;\nstopAt          endTime;\nendTime         {:.3};\n\n\
+         deltaT          0.001;\nwriteControl    adjustableRunTime;\nwriteInterval   {};\npurgeWrite      0;\nwriteFormat     ascii;\nwritePrecision  6;\n\
+         writeCompression off;\ntimeFormat      general;\ntimePrecision   6;\nrunTimeModifiable yes;\n\n\
+         adjustTimeStep  yes;\nmaxCo           0.45;\nmaxAlphaCo      0.45;\nmaxDeltaT       0.025;\n",
+        end_time, write_interval
+    ));
+    if !gauges.is_empty() {
+        let locations: Vec<String> = gauges.iter().map(|&[x, y, z]| format!("            {}", point(x, y, z))).collect();
+        dict.push_str(&format!(
+            "\nfunctions\n{{\n    waveGauges\n    {{\n        type            interfaceHeight;\n        libs            (\"libfieldFunctionObjects.so\");\n        alpha           alpha.water;\n        locations\n        (\n{}\n        );\n    }}\n}}\n",
+            locations.join("\n")
+        ));
+    }
+    dict
+}
+
+/// Discretization schemes of the interFoam tutorials
+fn fv_schemes() -> String {
+    header("dictionary", "system", "fvSchemes")
+        + r#"ddtSchemes
+{
+    default         Euler;
+}
+
+gradSchemes
+{
+    default         Gauss linear;
+}
+
+divSchemes
+{
+    div(rhoPhi,U)   Gauss linearUpwind grad(U);
+    div(phi,alpha)  Gauss vanLeer;
+    div(phirb,alpha) Gauss linear;
+    div(((rho*nuEff)*dev2(T(grad(U))))) Gauss linear;
+}
+
+laplacianSchemes
+{
+    default         Gauss linear corrected;
+}
+
+interpolationSchemes
+{
+    default         linear;
+}
+
+snGradSchemes
+{
+    default         corrected;
+}
+"#
+}
+
+/// Linear solvers and PIMPLE settings of the interFoam tutorials
+fn fv_solution() -> String {
+    header("dictionary", "system", "fvSolution")
+        + r#"solvers
+{
+    "alpha.water.*"
+    {
+        nAlphaCorr      1;
+        nAlphaSubCycles 3;
+        cAlpha          1;
+        MULESCorr       no;
+        nLimiterIter    3;
+    }
+
+    "pcorr.*"
+    {
+        solver          PCG;
+        preconditioner  DIC;
+        tolerance       1e-05;
+        relTol          0;
+    }
+
+    p_rgh
+    {
+        solver          PCG;
+        preconditioner  DIC;
+        tolerance       1e-07;
+        relTol          0.05;
+    }
+
+    p_rghFinal
+    {
+        $p_rgh;
+        relTol          0;
+    }
+
+    U
+    {
+        solver          smoothSolver;
+        smoother        symGaussSeidel;
+        tolerance       1e-06;
+        relTol          0;
+    }
+}
+
+PIMPLE
+{
+    momentumPredictor no;
+    nOuterCorrectors 1;
+    nCorrectors     3;
+    nNonOrthogonalCorrectors 0;
+}
+"#
+}
+
+/// Water below the still water level
+fn set_fields_dict(length: f64, top: f64, depth: f64) -> String {
+    header("dictionary", "system", "setFieldsDict")
+        + &format!(
+            "defaultFieldValues\n(\n    volScalarFieldValue alpha.water 0\n);\n\n\
+             regions\n(\n    boxToCell\n    {{\n        box {} {};\n        fieldValues\n        (\n            volScalarFieldValue alpha.water 1\n        );\n    }}\n);\n",
+            point(-1.0, -1.0, -1.0),
+            point(length + 1.0, FLUME_THICKNESS + 1.0, depth.min(top))
+        )
+}
+
+fn gravity() -> String {
+    header("uniformDimensionedVectorField", "constant", "g") + "dimensions      [0 1 -2 0 0 0 0];\nvalue           (0 0 -9.81);\n"
+}
+
+/// Sea water and air
+fn transport_properties() -> String {
+    header("dictionary", "constant", "transportProperties")
+        + &format!(
+            "phases (water air);\n\n\
+             water\n{{\n    transportModel  Newtonian;\n    nu              1e-06;\n    rho             {};\n}}\n\n\
+             air\n{{\n    transportModel  Newtonian;\n    nu              1.48e-05;\n    rho             1;\n}}\n\n\
+             sigma           0.07;\n",
+            WATER_DENSITY
+        )
+}
+
+fn turbulence_properties() -> String {
+    header("dictionary", "constant", "turbulenceProperties") + "simulationType  laminar;\n"
+}
+
+/// Name of a wave theory in olaFlow
+fn olaflow_theory(theory: WaveTheory) -> &'static str {
+    match theory {
+        WaveTheory::Linear => "StokesI",
+        WaveTheory::StokesSecond => "StokesII",
+        WaveTheory::StokesFifth => "StokesV",
+        WaveTheory::Cnoidal => "cnoidal",
+        WaveTheory::StreamFunction => "streamFunction",
+    }
+}
+
+/// Waves generated at the inlet, regular in a wave theory or the components of the boundary spectrum
+fn wave_dict(app: &WaveChannelApp, components: &[crate::waves::SpectralComponent], theory: WaveTheory) -> String {
+    let mut dict = header("dictionary", "constant", "waveDict");
+    let absorption = u8::from(app.active_absorption);
+    if components.is_empty() {
+        dict.push_str(&format!(
+            "waveType        regular;\nwaveTheory      {};\ngenAbs          {};\nabsDir          0.0;\nnPaddle         1;\n\n\
+             wavePeriod      {};\nwaveHeight      {};\nwaveDir         0.0;\nwavePhase       0.0;\n",
+            olaflow_theory(theory), absorption, app.wave_period, app.wave_height
+        ));
+        return dict;
+    }
+    let list = |values: Vec<f64>| -> String {
+        let values: Vec<String> = values.iter().map(|value| format!("    {:.6}", value)).collect();
+        format!("{}\n(\n{}\n)", values.len(), values.join("\n"))
+    };
+    dict.push_str(&format!(
+        "waveType        irregular;\nwaveTheory      StokesI;\ngenAbs          {};\nabsDir          0.0;\nnPaddle         1;\n\n\
+         waveHeights     {};\n\nwavePeriods     {};\n\nwavePhases      {};\n\nwaveDirs        {};\n",
+        absorption,
+        list(components.iter().map(|component| 2.0 * component.amplitude).collect()),
+        list(components.iter().map(|component| component.period()).collect()),
+        list(components.iter().map(|component| component.phase).collect()),
+        list(vec![0.0; components.len()])
+    ));
+    dict
+}
+
+/// Boundary conditions of a field on the patches of the flume
+fn field(class: &str, object: &str, dimensions: &str, internal: &str, patches: [(&str, &str); 4]) -> String {
+    let mut file = header(class, "0", object);
+    file.push_str(&format!("dimensions      {};\n\ninternalField   uniform {};\n\nboundaryField\n{{\n", dimensions, internal));
+    for (patch, condition) in patches {
+        file.push_str(&format!("    {}\n    {{\n{}    }}\n", patch, condition));
+    }
+    file.push_str("    frontBack\n    {\n        type            empty;\n    }\n}\n");
+    file
+}
+
+fn alpha_field() -> String {
+    field(
+        "volScalarField",
+        "alpha.water",
+        "[0 0 0 0 0 0 0]",
+        "0",
+        [
+            ("inlet", "        type            waveAlpha;\n        value           uniform 0;\n"),
+            ("outlet", "        type            zeroGradient;\n"),
+            ("bottom", "        type            zeroGradient;\n"),
+            ("atmosphere", "        type            inletOutlet;\n        inletValue      uniform 0;\n        value           uniform 0;\n"),
+        ],
+    )
+}
+
+fn velocity_field(absorbing_outlet: bool) -> String {
+    let outlet = if absorbing_outlet {
+        "        type            waveAbsorption2DVelocity;\n        value           uniform (0 0 0);\n"
+    } else {
+        "        type            noSlip;\n"
+    };
+    field(
+        "volVectorField",
+        "U",
+        "[0 1 -1 0 0 0 0]",
+        "(0 0 0)",
+        [
+            ("inlet", "        type            waveVelocity;\n        value           uniform (0 0 0);\n"),
+            ("outlet", outlet),
+            ("bottom", "        type            noSlip;\n"),
+            ("atmosphere", "        type            pressureInletOutletVelocity;\n        value           uniform (0 0 0);\n"),
+        ],
+    )
+}
+
+fn pressure_field() -> String {
+    field(
+        "volScalarField",
+        "p_rgh",
+        "[1 -1 -2 0 0 0 0]",
+        "0",
+        [
+            ("inlet", "        type            fixedFluxPressure;\n        value           uniform 0;\n"),
+            ("outlet", "        type            fixedFluxPressure;\n        value           uniform 0;\n"),
+            ("bottom", "        type            fixedFluxPressure;\n        value           uniform 0;\n"),
+            ("atmosphere", "        type            totalPressure;\n        p0              uniform 0;\n        U               U;\n        phi             phi;\n        rho             rho;\n        psi             none;\n        gamma           1;\n        value           uniform 0;\n"),
+        ],
+    )
+}
+
+/// How to run the case, its resolution and the settings left out
+fn readme(cells: usize, wavelength: f64, notes: &[String]) -> String {
+    let mut text = format!(
+        "olaFlow wave flume generated by the Coastal Engineering Platform {}\n\n\
+         Run:\n    blockMesh\n    setFields\n    olaFoam\n\n\
+         Mesh: {} cells, {:.0} cells per wave length of {:.3} m and {:.0} cells per wave height.\n\
+         Check the resolution and the time step before production runs.\n",
+        env!("CARGO_PKG_VERSION"),
+        cells,
+        CELLS_PER_WAVELENGTH,
+        wavelength,
+        CELLS_PER_WAVE_HEIGHT
+    );
+    if !notes.is_empty() {
+        text.push_str("\nNot exported:\n");
+        for note in notes {
+            text.push_str(&format!("- {}\n", note));
+        }
+    }
+    text
+}
@@ -0,0 +1,96 @@
+use super::run_preview::format_bytes;
+use eframe::egui;
+use std::collections::VecDeque;
+
+/// Frames and solver advances averaged by the performance overlay, about a second at 60 frames per second
+pub const PERFORMANCE_WINDOW: usize = 60;
+
+/// Recent frame and solver timings, for the performance overlay
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PerformanceMonitor {
+    frames: VecDeque<(f64, f64)>,     // Interval since the previous frame and time building the frame [s]
+    advances: VecDeque<(usize, f64)>, // Solver steps of an advance and computing time they took [s]
+    time_step: Option<(f64, f64)>,    // Last solver time step [s] and its Courant number
+}
+
+impl PerformanceMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a frame of the interface, dropping the oldest beyond the window
+    pub fn record_frame(&mut self, interval: f64, build_time: f64) {
+        self.frames.push_back((interval, build_time));
+        if self.frames.len() > PERFORMANCE_WINDOW {
+            self.frames.pop_front();
+        }
+    }
+
+    /// Record an advance of the numerical solver, with its mean time step [s] and Courant number
+    pub fn record_advance(&mut self, steps: usize, compute_time: f64, time_step: f64, courant: f64) {
+        self.advances.push_back((steps, compute_time));
+        if self.advances.len() > PERFORMANCE_WINDOW {
+            self.advances.pop_front();
+        }
+        self.time_step = Some((time_step, courant));
+    }
+
+    /// Forget the solver timings, when the run is reset
+    pub fn clear_solver(&mut self) {
+        self.advances.clear();
+        self.time_step = None;
+    }
+
+    /// Mean time spent building a frame [s]
+    pub fn frame_time(&self) -> Option<f64> {
+        (!self.frames.is_empty()).then(|| self.frames.iter().map(|(_, build)| build).sum::<f64>() / self.frames.len() as f64)
+    }
+
+    /// Frames drawn per second of wall-clock time
+    pub fn frames_per_second(&self) -> Option<f64> {
+        let elapsed: f64 = self.frames.iter().map(|(interval, _)| interval).sum();
+        (elapsed > 0.0).then(|| self.frames.len() as f64 / elapsed)
+    }
+
+    /// Solver steps per second of computing time
+    pub fn steps_per_second(&self) -> Option<f64> {
+        let steps: usize = self.advances.iter().map(|(steps, _)| steps).sum();
+        let elapsed: f64 = self.advances.iter().map(|(_, time)| time).sum();
+        (elapsed > 0.0).then(|| steps as f64 / elapsed)
+    }
+
+    /// Last solver time step [s] and its Courant number
+    pub fn time_step(&self) -> Option<(f64, f64)> {
+        self.time_step
+    }
+
+    /// Lines of the overlay, with the memory held by the stored results [bytes]
+    pub fn report(&self, stored_bytes: u64) -> Vec<String> {
+        let unknown = || "–".to_string();
+        vec![
+            format!("Frame: {}", self.frame_time().map_or_else(unknown, |time| format!("{:.1} ms", 1000.0 * time))),
+            format!("FPS: {}", self.frames_per_second().map_or_else(unknown, |fps| format!("{:.0}", fps))),
+            format!("Solver: {}", self.steps_per_second().map_or_else(unknown, |rate| format!("{:.0} steps/s", rate))),
+            match self.time_step {
+                Some((dt, courant)) => format!("dt: {:.2e} s, CFL: {:.2}", dt, courant),
+                None => "dt: –".to_string(),
+            },
+            format!("Stored Results: {}", format_bytes(stored_bytes)),
+        ]
+    }
+
+    /// Overlay in the top right corner of the window
+    pub fn show_overlay(&self, ctx: &egui::Context, stored_bytes: u64) {
+        egui::Area::new(egui::Id::new("performance_hud"))
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-10.0, 10.0))
+            .interactable(false)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    for line in self.report(stored_bytes) {
+                        ui.monospace(line);
+                    }
+                });
+            });
+    }
+}
@@ -0,0 +1,35 @@
+use super::measurement::WavelengthReading;
+use egui_plot::PlotPoint;
+use std::ops::Range;
+
+/// Refill a buffer in place with new values, keeping its memory for the next frame
+pub fn refill<T>(buffer: &mut Vec<T>, values: impl IntoIterator<Item = T>) {
+    buffer.clear();
+    buffer.extend(values);
+}
+
+/// Series of the channel view, refilled in place every frame and lent to the plot instead of copied
+///
+/// Their memory is kept between frames, so once the channel has been drawn at its resolution the
+/// animation no longer allocates them. The overlays are empty when they are hidden.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChannelSeries {
+    pub surface: Vec<PlotPoint>,               // Water surface, resting on the bed where it is dry
+    pub bed: Vec<PlotPoint>,                   // Channel bottom
+    pub breaking: Vec<bool>,                   // Whether each point of the surface is on a breaking front
+    pub foam: Vec<Range<usize>>,               // Stretches of the surface over the breaking fronts, as indices
+    pub crests: Vec<PlotPoint>,                // Crests of the surface in the wet channel
+    pub troughs: Vec<PlotPoint>,               // Troughs of the surface in the wet channel
+    pub wavelength: Option<WavelengthReading>, // Distance between the crests against the linear wavelength
+}
+
+impl ChannelSeries {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Points of a series as pairs, for the plots keeping their own copy
+    pub fn pairs(series: &[PlotPoint]) -> Vec<[f64; 2]> {
+        series.iter().map(|point| [point.x, point.y]).collect()
+    }
+}
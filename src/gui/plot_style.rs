@@ -0,0 +1,314 @@
+use super::clipboard::plot_menu;
+use eframe::egui::{self, Color32};
+use egui_plot::{Plot, PlotResponse, PlotUi};
+use serde::{Deserialize, Serialize};
+
+/// Colors given to the quantities drawn in the plots
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Palette {
+    /// Original colors of the platform
+    #[default]
+    Classic,
+    /// Okabe & Ito (2008), distinguishable with every common color vision deficiency
+    OkabeIto,
+    /// Bright qualitative scheme of Tol (2021), also safe for color vision deficiencies
+    TolBright,
+}
+
+impl std::fmt::Display for Palette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Palette::Classic => write!(f, "Classic"),
+            Palette::OkabeIto => write!(f, "Okabe-Ito"),
+            Palette::TolBright => write!(f, "Tol Bright"),
+        }
+    }
+}
+
+/// Colors of a palette by role
+struct Swatch {
+    water: [u8; 3],
+    bed: [u8; 3],
+    alert: [u8; 3],     // Breaking, bores and thresholds
+    reference: [u8; 3], // Linear theory and design values
+    marker: [u8; 3],    // Tracked crests
+    sensor: [u8; 3],
+    vegetation: [u8; 3],
+    long_wave: [u8; 3], // Infragravity and swash motions
+    series: [[u8; 3]; 6],
+}
+
+const CLASSIC: Swatch = Swatch {
+    water: [30, 144, 255],
+    bed: [139, 69, 19],
+    alert: [220, 20, 60],
+    reference: [255, 140, 0],
+    marker: [255, 215, 0],
+    sensor: [138, 43, 226],
+    vegetation: [34, 139, 34],
+    long_wave: [128, 0, 128],
+    series: [[0, 100, 200], [220, 20, 60], [34, 139, 34], [255, 140, 0], [138, 43, 226], [0, 128, 128]],
+};
+
+const OKABE_ITO: Swatch = Swatch {
+    water: [0, 114, 178],
+    bed: [230, 159, 0],
+    alert: [213, 94, 0],
+    reference: [204, 121, 167],
+    marker: [240, 228, 66],
+    sensor: [0, 158, 115],
+    vegetation: [0, 158, 115],
+    long_wave: [86, 180, 233],
+    series: [[0, 114, 178], [213, 94, 0], [0, 158, 115], [230, 159, 0], [204, 121, 167], [86, 180, 233]],
+};
+
+const TOL_BRIGHT: Swatch = Swatch {
+    water: [68, 119, 170],
+    bed: [187, 187, 187],
+    alert: [238, 102, 119],
+    reference: [170, 51, 119],
+    marker: [204, 187, 68],
+    sensor: [102, 204, 238],
+    vegetation: [34, 136, 51],
+    long_wave: [102, 204, 238],
+    series: [[68, 119, 170], [238, 102, 119], [34, 136, 51], [204, 187, 68], [170, 51, 119], [102, 204, 238]],
+};
+
+impl Palette {
+    fn swatch(&self) -> &'static Swatch {
+        match self {
+            Palette::Classic => &CLASSIC,
+            Palette::OkabeIto => &OKABE_ITO,
+            Palette::TolBright => &TOL_BRIGHT,
+        }
+    }
+}
+
+/// Sequential colormap of the images, from low to high values
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Colormap {
+    #[default]
+    Viridis,
+    /// Optimized for color vision deficiencies (Nuñez et al., 2018)
+    Cividis,
+    Magma,
+    Grayscale,
+}
+
+impl std::fmt::Display for Colormap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Colormap::Viridis => write!(f, "Viridis"),
+            Colormap::Cividis => write!(f, "Cividis"),
+            Colormap::Magma => write!(f, "Magma"),
+            Colormap::Grayscale => write!(f, "Grayscale"),
+        }
+    }
+}
+
+impl Colormap {
+    /// Color of a value between 0 and 1, interpolated between a few anchor colors
+    pub fn color(&self, value: f64) -> Color32 {
+        const VIRIDIS: [[f64; 3]; 5] = [[68.0, 1.0, 84.0], [59.0, 82.0, 139.0], [33.0, 145.0, 140.0], [94.0, 201.0, 98.0], [253.0, 231.0, 37.0]];
+        const CIVIDIS: [[f64; 3]; 5] = [[0.0, 32.0, 77.0], [65.0, 77.0, 107.0], [124.0, 123.0, 120.0], [188.0, 175.0, 111.0], [255.0, 234.0, 70.0]];
+        const MAGMA: [[f64; 3]; 5] = [[0.0, 0.0, 4.0], [81.0, 18.0, 124.0], [183.0, 55.0, 121.0], [252.0, 137.0, 97.0], [252.0, 253.0, 191.0]];
+        const GRAYSCALE: [[f64; 3]; 2] = [[0.0, 0.0, 0.0], [255.0, 255.0, 255.0]];
+        let anchors: &[[f64; 3]] = match self {
+            Colormap::Viridis => &VIRIDIS,
+            Colormap::Cividis => &CIVIDIS,
+            Colormap::Magma => &MAGMA,
+            Colormap::Grayscale => &GRAYSCALE,
+        };
+        let position = value.clamp(0.0, 1.0) * (anchors.len() - 1) as f64;
+        let i = (position.floor() as usize).min(anchors.len() - 2);
+        let weight = position - i as f64;
+        let channel = |c: usize| (anchors[i][c] * (1.0 - weight) + anchors[i + 1][c] * weight).round() as u8;
+        Color32::from_rgb(channel(0), channel(1), channel(2))
+    }
+}
+
+/// Look of the plots: colors, line widths, fills, background and colormap
+/// Kept in the user preferences and shared by the channel, gauge and spectral plots
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlotStyle {
+    pub palette: Palette,
+    pub colormap: Colormap,
+    pub line_scale: f32,             // Factor on the widths of the lines
+    pub fill_opacity: f32,           // Opacity of filled areas, 0 to 1
+    pub background: Option<[u8; 3]>, // Background of the plots, None for the theme
+}
+
+impl Default for PlotStyle {
+    fn default() -> Self {
+        Self {
+            palette: Palette::Classic,
+            colormap: Colormap::Viridis,
+            line_scale: 1.0,
+            fill_opacity: 0.47,
+            background: None,
+        }
+    }
+}
+
+fn rgb(color: [u8; 3]) -> Color32 {
+    Color32::from_rgb(color[0], color[1], color[2])
+}
+
+impl PlotStyle {
+    /// Presets safe for color vision deficiencies
+    pub fn colorblind_safe() -> Self {
+        Self {
+            palette: Palette::OkabeIto,
+            colormap: Colormap::Cividis,
+            ..Self::default()
+        }
+    }
+
+    /// Presets for printed figures: thicker lines on white
+    pub fn print() -> Self {
+        Self {
+            palette: Palette::OkabeIto,
+            colormap: Colormap::Cividis,
+            line_scale: 1.5,
+            fill_opacity: 0.3,
+            background: Some([255, 255, 255]),
+        }
+    }
+
+    /// Water surface and wave heights
+    pub fn water(&self) -> Color32 {
+        rgb(self.palette.swatch().water)
+    }
+
+    /// Sky above the water, a pale tint of the water color
+    pub fn sky(&self) -> Color32 {
+        let water = self.water();
+        let tint = |channel: u8| (channel as f32 + 0.75 * (255.0 - channel as f32)).round() as u8;
+        Color32::from_rgb(tint(water.r()), tint(water.g()), tint(water.b()))
+    }
+
+    /// Foam on breaking fronts
+    pub fn foam(&self) -> Color32 {
+        Color32::from_rgb(245, 250, 255)
+    }
+
+    /// Channel bottom and structures
+    pub fn bed(&self) -> Color32 {
+        rgb(self.palette.swatch().bed)
+    }
+
+    /// Breaking, bores and exceeded limits
+    pub fn alert(&self) -> Color32 {
+        rgb(self.palette.swatch().alert)
+    }
+
+    /// Linear theory and design values compared with the simulation
+    pub fn reference(&self) -> Color32 {
+        rgb(self.palette.swatch().reference)
+    }
+
+    /// Tracked crests
+    pub fn marker(&self) -> Color32 {
+        rgb(self.palette.swatch().marker)
+    }
+
+    /// Pressure sensors
+    pub fn sensor(&self) -> Color32 {
+        rgb(self.palette.swatch().sensor)
+    }
+
+    pub fn vegetation(&self) -> Color32 {
+        rgb(self.palette.swatch().vegetation)
+    }
+
+    /// Annotations of the user
+    pub fn annotation(&self) -> Color32 {
+        self.series(4)
+    }
+
+    /// Infragravity and swash motions
+    pub fn long_wave(&self) -> Color32 {
+        rgb(self.palette.swatch().long_wave)
+    }
+
+    /// Color of the i-th of several records, repeating after six
+    pub fn series(&self, i: usize) -> Color32 {
+        let series = &self.palette.swatch().series;
+        rgb(series[i % series.len()])
+    }
+
+    /// Translucent fill of an area of a color
+    pub fn fill(&self, color: Color32) -> Color32 {
+        let alpha = (self.fill_opacity.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Color32::from_rgba_unmultiplied(color.r(), color.g(), color.b(), alpha)
+    }
+
+    /// Width of a line drawn `width` points wide in the default style
+    pub fn width(&self, width: f32) -> f32 {
+        width * self.line_scale
+    }
+
+    /// Show a plot on the background of the style, with a context menu copying its image
+    pub fn show_plot<'a, R>(&self, ui: &mut egui::Ui, plot: Plot<'a>, build: impl FnOnce(&mut PlotUi<'a>) -> R + 'a) -> PlotResponse<R> {
+        let shown = ui
+            .scope(|ui| {
+                if let Some(background) = self.background {
+                    ui.visuals_mut().extreme_bg_color = rgb(background);
+                }
+                plot.show(ui, build)
+            })
+            .inner;
+        plot_menu(&shown.response);
+        shown
+    }
+
+    /// Editor of the style, returning whether it changed
+    pub fn show_editor(&mut self, ui: &mut egui::Ui) -> bool {
+        let previous = *self;
+        ui.horizontal(|ui| {
+            ui.label("Presets:");
+            if ui.button("Classic").clicked() {
+                *self = Self::default();
+            }
+            if ui.button("Colorblind Safe").clicked() {
+                *self = Self::colorblind_safe();
+            }
+            if ui.button("Print").clicked() {
+                *self = Self::print();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Palette:");
+            for palette in [Palette::Classic, Palette::OkabeIto, Palette::TolBright] {
+                ui.selectable_value(&mut self.palette, palette, palette.to_string());
+            }
+        });
+        ui.horizontal(|ui| {
+            for i in 0..6 {
+                let (rect, _) = ui.allocate_exact_size(egui::vec2(16.0, 16.0), egui::Sense::hover());
+                ui.painter().rect_filled(rect, 2.0, self.series(i));
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Colormap:");
+            for colormap in [Colormap::Viridis, Colormap::Cividis, Colormap::Magma, Colormap::Grayscale] {
+                ui.selectable_value(&mut self.colormap, colormap, colormap.to_string());
+            }
+        });
+        ui.add(egui::Slider::new(&mut self.line_scale, 0.5..=3.0).text("Line Width Factor"));
+        ui.add(egui::Slider::new(&mut self.fill_opacity, 0.0..=1.0).text("Fill Opacity"));
+        ui.horizontal(|ui| {
+            let mut custom = self.background.is_some();
+            ui.checkbox(&mut custom, "Custom Background");
+            if custom {
+                let mut color = self.background.unwrap_or([255, 255, 255]);
+                ui.color_edit_button_srgb(&mut color);
+                self.background = Some(color);
+            } else {
+                self.background = None;
+            }
+        });
+        *self != previous
+    }
+}
@@ -0,0 +1,174 @@
+use super::plot_style::PlotStyle;
+use super::project::Project;
+use super::remote_api::DEFAULT_REMOTE_ADDRESS;
+use super::repaint::RepaintPolicy;
+use super::shortcuts::Shortcuts;
+use crate::units::{Length, Velocity};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Number of recently opened projects remembered
+pub const MAX_RECENT_PROJECTS: usize = 10;
+
+/// Color theme of the interface
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Theme {
+    /// Follow the operating system
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl std::fmt::Display for Theme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Theme::System => write!(f, "System"),
+            Theme::Light => write!(f, "Light"),
+            Theme::Dark => write!(f, "Dark"),
+        }
+    }
+}
+
+impl From<Theme> for egui::ThemePreference {
+    fn from(theme: Theme) -> Self {
+        match theme {
+            Theme::System => egui::ThemePreference::System,
+            Theme::Light => egui::ThemePreference::Light,
+            Theme::Dark => egui::ThemePreference::Dark,
+        }
+    }
+}
+
+/// Units in which lengths and speeds are displayed; computations always use SI units
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum UnitSystem {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+impl std::fmt::Display for UnitSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnitSystem::Metric => write!(f, "Metric (m, m/s)"),
+            UnitSystem::Imperial => write!(f, "Imperial (ft, ft/s)"),
+        }
+    }
+}
+
+impl UnitSystem {
+    /// Length converted to the display unit
+    pub fn length(&self, length: Length) -> f64 {
+        match self {
+            UnitSystem::Metric => length.in_meters(),
+            UnitSystem::Imperial => length.in_feet(),
+        }
+    }
+
+    /// Symbol of the length unit
+    pub fn length_unit(&self) -> &'static str {
+        match self {
+            UnitSystem::Metric => "m",
+            UnitSystem::Imperial => "ft",
+        }
+    }
+
+    /// Speed converted to the display unit
+    pub fn speed(&self, speed: Velocity) -> f64 {
+        match self {
+            UnitSystem::Metric => speed.in_meters_per_second(),
+            UnitSystem::Imperial => speed.in_feet_per_second(),
+        }
+    }
+
+    /// Symbol of the speed unit
+    pub fn speed_unit(&self) -> &'static str {
+        match self {
+            UnitSystem::Metric => "m/s",
+            UnitSystem::Imperial => "ft/s",
+        }
+    }
+}
+
+/// User preferences kept between sessions in the platform configuration directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    pub window_size: [f32; 2],              // Inner size of the main window [points]
+    pub theme: Theme,
+    pub unit_system: UnitSystem,
+    pub plot_style: PlotStyle,
+    pub repaint_policy: RepaintPolicy,      // Frame rate of the animations and battery saver
+    pub recent_projects: Vec<PathBuf>,      // Most recent first
+    pub default_project: Option<Project>,   // Settings of a new wave channel, None for the built-in defaults
+    pub shortcuts: Shortcuts,
+    pub show_help: bool,                    // Keep the help pane docked instead of using info popups
+    pub show_performance_hud: bool,         // Overlay of frame time, solver rate, time step and stored memory
+    pub show_dashboard: bool,               // Window of the quantities pinned to the dashboard
+    pub remote_api_address: String,         // Address the remote control server listens on
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            window_size: [1200.0, 800.0],
+            theme: Theme::System,
+            unit_system: UnitSystem::Metric,
+            plot_style: PlotStyle::default(),
+            repaint_policy: RepaintPolicy::default(),
+            recent_projects: Vec::new(),
+            default_project: None,
+            shortcuts: Shortcuts::new(),
+            show_help: false,
+            show_performance_hud: false,
+            show_dashboard: false,
+            remote_api_address: DEFAULT_REMOTE_ADDRESS.to_string(),
+        }
+    }
+}
+
+impl Preferences {
+    /// Preferences file in the configuration directory of the platform, e.g. ~/.config/coastal-engineering-platform on Linux
+    /// None if the home directory cannot be determined
+    pub fn default_path() -> Option<PathBuf> {
+        directories::ProjectDirs::from("", "", "coastal-engineering-platform")
+            .map(|directories| directories.config_dir().join("preferences.json"))
+    }
+
+    /// Preferences stored in a file, the defaults if it does not exist or is unreadable
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Preferences stored in the default file, the defaults if there is none
+    pub fn load_default() -> Self {
+        Self::default_path().map_or_else(Self::default, |path| Self::load(&path))
+    }
+
+    /// Write the preferences, creating the configuration directory if needed
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory).map_err(|e| format!("Cannot create configuration directory: {}", e))?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(|e| format!("Cannot serialize preferences: {}", e))?;
+        std::fs::write(path, content).map_err(|e| format!("Cannot write preferences: {}", e))
+    }
+
+    /// Move a project to the top of the recent projects, dropping the oldest beyond the limit
+    pub fn add_recent_project(&mut self, path: impl Into<PathBuf>) {
+        let path = path.into();
+        self.recent_projects.retain(|recent| *recent != path);
+        self.recent_projects.insert(0, path);
+        self.recent_projects.truncate(MAX_RECENT_PROJECTS);
+    }
+
+    /// Apply the theme to the interface
+    pub fn apply(&self, ctx: &egui::Context) {
+        ctx.set_theme(self.theme);
+    }
+}
@@ -0,0 +1,149 @@
+use super::alerts::AlertRule;
+use super::annotations::Annotation;
+use super::dashboard::DashboardQuantity;
+use super::transects::Transect;
+use super::wave_channel::{PropagationModel, SetupMethod, SourceSettings};
+use crate::analysis::{BoreCriteria, BoundarySignal, CustomFormula};
+use crate::solver::{AdvectionScheme, AutoRefinement, CrossSection, InitialCondition, ReferenceLevels, RefinementZone, RightBoundary, TimeIntegration, VegetationField, VerticalDatum};
+use crate::waves::{AmbientCurrent, DEFAULT_SEED, DirectionalSpreading, DispersionMode, ForcingSchedule, PaddleType, SeaState, StillWaterComponents, StormHydrograph, WaveEnergyConverter, WaveSpectrum, WaveTheory};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Everything the user configured in the wave channel, without the simulation state
+///
+/// Saved as JSON, so a scenario can be autosaved, reopened in a later session or shared.
+/// Fields added after the first release default to their initial value when absent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Project {
+    pub channel_length: f64,
+    pub grid_resolution: usize,
+    pub still_water_level: f64,
+    pub wave_height: f64,
+    pub wave_period: f64,
+    pub number_of_waves: usize,
+    #[serde(default)]
+    pub forcing_schedule: ForcingSchedule,
+    #[serde(default)]
+    pub storm_hydrograph: Option<StormHydrograph>,
+    #[serde(default)]
+    pub boundary_spectrum: Option<WaveSpectrum>,
+    #[serde(default)]
+    pub boundary_spreading: Option<DirectionalSpreading>,
+    #[serde(default)]
+    pub boundary_signal: Option<BoundarySignal>,
+    pub paddle_type: Option<PaddleType>,
+    pub paddle_stroke: f64,
+    #[serde(default)]
+    pub wave_theory: Option<WaveTheory>,
+    #[serde(default = "default_stream_function_harmonics")]
+    pub stream_function_harmonics: usize,
+    #[serde(default = "default_stream_function_steps")]
+    pub stream_function_steps: usize,
+    pub additional_sources: Vec<SourceSettings>,
+    pub initial_condition: Option<InitialCondition>,
+    pub gauge_positions: Vec<f64>,
+    pub analysis_gauge: usize,
+    pub cross_spectrum_gauges: [usize; 2],
+    #[serde(default)]
+    pub pressure_sensor_locations: Vec<[f64; 2]>,
+    #[serde(default = "default_impact_threshold")]
+    pub impact_threshold: f64,
+    #[serde(default)]
+    pub bore_criteria: BoreCriteria,
+    pub gauge_sampling_interval: f64,
+    pub snapshot_interval: f64,
+    pub stream_snapshots: bool,
+    pub statistics_window: f64,
+    #[serde(default)]
+    pub mean_flow_periods: f64,
+    #[serde(default = "default_seed")]
+    pub random_seed: u64,
+    #[serde(default = "default_height_ramp")]
+    pub height_ramp: f64,
+    pub propagation_model: PropagationModel,
+    pub dispersion_mode: DispersionMode,
+    pub right_boundary: RightBoundary,
+    pub active_absorption: bool,
+    #[serde(default)]
+    pub mass_compensation: bool,
+    #[serde(default)]
+    pub ambient_current: Option<AmbientCurrent>,
+    #[serde(default)]
+    pub advection_scheme: AdvectionScheme,
+    #[serde(default)]
+    pub time_integration: TimeIntegration,
+    pub vegetation: Option<VegetationField>,
+    #[serde(default)]
+    pub cross_section: Option<CrossSection>,
+    #[serde(default)]
+    pub tank_plan_length: Option<f64>,
+    #[serde(default)]
+    pub energy_converter: Option<WaveEnergyConverter>,
+    #[serde(default)]
+    pub wave_climate: Vec<SeaState>,
+    #[serde(default)]
+    pub grid_refinement: Vec<RefinementZone>,
+    #[serde(default)]
+    pub auto_refinement: AutoRefinement,
+    #[serde(default)]
+    pub datum: VerticalDatum,
+    #[serde(default)]
+    pub reference_levels: ReferenceLevels,
+    #[serde(default)]
+    pub water_levels: StillWaterComponents,
+    #[serde(default)]
+    pub setup_method: SetupMethod,
+    #[serde(default)]
+    pub custom_formulas: Vec<CustomFormula>,
+    #[serde(default)]
+    pub annotations: Vec<Annotation>,
+    #[serde(default)]
+    pub transects: Vec<Transect>,
+    #[serde(default)]
+    pub dashboard: Vec<DashboardQuantity>,
+    #[serde(default)]
+    pub alerts: Vec<AlertRule>,
+}
+
+fn default_seed() -> u64 {
+    DEFAULT_SEED
+}
+
+fn default_height_ramp() -> f64 {
+    5.0
+}
+
+fn default_impact_threshold() -> f64 {
+    0.5
+}
+
+fn default_stream_function_harmonics() -> usize {
+    16
+}
+
+fn default_stream_function_steps() -> usize {
+    4
+}
+
+impl Project {
+    /// Project as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Cannot serialize project: {}", e))
+    }
+
+    /// Project from JSON
+    pub fn from_json(content: &str) -> Result<Self, String> {
+        serde_json::from_str(content).map_err(|e| format!("Invalid project file: {}", e))
+    }
+
+    /// Write the project to a file
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        std::fs::write(path, self.to_json()?).map_err(|e| format!("Cannot write {}: {}", path.display(), e))
+    }
+
+    /// Read a project from a file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        Self::from_json(&content)
+    }
+}
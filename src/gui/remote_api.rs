@@ -0,0 +1,655 @@
+use super::project::Project;
+use super::wave_channel::WaveChannelApp;
+use super::workers::new_token;
+use eframe::egui;
+use serde_json::{Value, json};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Address the server listens on unless another is set in the preferences
+pub const DEFAULT_REMOTE_ADDRESS: &str = "127.0.0.1:8787";
+
+/// Wall time between live frames sent to WebSocket clients [s]
+const FRAME_INTERVAL: f64 = 0.1;
+
+/// Time a request waits for the interface to answer before failing
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time a client may take to send its request before the connection is closed
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time a live frame may take to reach a slow client before it is dropped
+const WRITE_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Pause of the accept loop between checks for new connections and for a stop
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Largest request body or WebSocket message accepted, enough for any project [bytes]
+const MAX_PAYLOAD: usize = 4 << 20;
+
+/// Longest request line or header line accepted [bytes]
+const MAX_LINE: usize = 8 << 10;
+
+/// Most headers accepted in a request
+const MAX_HEADERS: usize = 64;
+
+/// Suffix of the WebSocket key hashed into the handshake answer (RFC 6455)
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Request of a remote client to the wave channel
+#[derive(Debug, Clone, PartialEq)]
+pub enum RemoteCommand {
+    Status,
+    Start,
+    Pause,
+    Reset,
+    Surface,
+    Gauges,
+    GetProject,
+    SetProject(Box<Project>),
+}
+
+impl RemoteCommand {
+    /// Command of a WebSocket message such as {"command": "start"}
+    /// New settings come with the set_project command as {"command": "set_project", "project": {...}}
+    pub fn from_message(message: &str) -> Result<Self, String> {
+        let value: Value = serde_json::from_str(message).map_err(|e| format!("Invalid message: {}", e))?;
+        let name = value.get("command").and_then(Value::as_str).ok_or("Message has no command")?;
+        match name {
+            "status" => Ok(RemoteCommand::Status),
+            "start" => Ok(RemoteCommand::Start),
+            "pause" => Ok(RemoteCommand::Pause),
+            "reset" => Ok(RemoteCommand::Reset),
+            "surface" => Ok(RemoteCommand::Surface),
+            "gauges" => Ok(RemoteCommand::Gauges),
+            "project" => Ok(RemoteCommand::GetProject),
+            "set_project" => {
+                let project = value.get("project").cloned().ok_or("set_project needs a project")?;
+                let project = serde_json::from_value(project).map_err(|e| format!("Invalid project: {}", e))?;
+                Ok(RemoteCommand::SetProject(Box::new(project)))
+            }
+            _ => Err(format!("Unknown command {}", name)),
+        }
+    }
+
+    /// Command of a REST route, or the HTTP status and message of a request that has none
+    fn route(method: &str, path: &str, body: &str) -> Result<Self, (u16, String)> {
+        let command = match (method, path) {
+            ("GET", "/status") => RemoteCommand::Status,
+            ("POST", "/run/start") => RemoteCommand::Start,
+            ("POST", "/run/pause") => RemoteCommand::Pause,
+            ("POST", "/run/reset") => RemoteCommand::Reset,
+            ("GET", "/surface") => RemoteCommand::Surface,
+            ("GET", "/gauges") => RemoteCommand::Gauges,
+            ("GET", "/project") => RemoteCommand::GetProject,
+            ("PUT", "/project") => {
+                let project = serde_json::from_str(body).map_err(|e| (400, format!("Invalid project: {}", e)))?;
+                RemoteCommand::SetProject(Box::new(project))
+            }
+            (_, "/status" | "/run/start" | "/run/pause" | "/run/reset" | "/surface" | "/gauges" | "/project") => {
+                return Err((405, format!("{} is not allowed on {}", method, path)));
+            }
+            _ => return Err((404, format!("No route {}", path))),
+        };
+        Ok(command)
+    }
+
+    /// Apply the command to the wave channel and describe the result
+    /// Run controls and new settings answer with the status that follows them
+    pub fn apply(self, app: &mut WaveChannelApp) -> Result<Value, String> {
+        match self {
+            RemoteCommand::Status => {}
+            RemoteCommand::Start => {
+                if app.is_simulation_complete() {
+                    app.reset_simulation();
+                }
+                app.start_simulation();
+            }
+            RemoteCommand::Pause => app.pause_simulation(),
+            RemoteCommand::Reset => app.reset_simulation(),
+            RemoteCommand::Surface => return Ok(surface(app)),
+            RemoteCommand::Gauges => return Ok(gauges(app)),
+            RemoteCommand::GetProject => {
+                return serde_json::to_value(app.project()).map_err(|e| format!("Cannot serialize project: {}", e));
+            }
+            RemoteCommand::SetProject(project) => app.apply_project(&project),
+        }
+        Ok(status(app))
+    }
+}
+
+/// Run state and main wave parameters of the channel
+fn status(app: &WaveChannelApp) -> Value {
+    json!({
+        "time": app.simulation_time,
+        "total_time": app.total_simulation_time(),
+        "running": app.simulation_running,
+        "complete": app.is_simulation_complete(),
+        "channel_length": app.channel_length,
+        "still_water_level": app.still_water_level,
+        "wave_height": app.wave_height,
+        "wave_period": app.wave_period,
+        "gauges": app.gauges.len(),
+    })
+}
+
+/// Surface elevation displayed along the channel
+fn surface(app: &WaveChannelApp) -> Value {
+    let elevation = app.displayed_surface();
+    let positions: Vec<f64> = (0..elevation.len()).map(|i| i as f64 * app.grid_spacing()).collect();
+    json!({
+        "time": app.simulation_time,
+        "positions": positions,
+        "elevation": elevation.as_ref(),
+    })
+}
+
+/// Records of the wave gauges
+fn gauges(app: &WaveChannelApp) -> Value {
+    app.gauges
+        .iter()
+        .map(|gauge| json!({ "position": gauge.position(), "times": gauge.times(), "elevation": gauge.elevation() }))
+        .collect()
+}
+
+/// Command waiting for the interface, with the channel its answer goes back through
+struct RemoteRequest {
+    command: RemoteCommand,
+    reply: Sender<Result<Value, String>>,
+}
+
+/// Connection of a WebSocket client, shared by its reader thread and the live frames
+type SharedStream = Arc<Mutex<TcpStream>>;
+
+/// Embedded server exposing the run control and the live results of the wave channel over REST and WebSocket
+///
+/// Connections are served on background threads, which hand every command to the interface: the
+/// commands are applied to the wave channel by `poll`, called once per frame, so remote clients act
+/// exactly like the controls of the interface. The REST routes are GET /status, /surface, /gauges and
+/// /project, POST /run/start, /run/pause and /run/reset, and PUT /project with a project file as the
+/// body. GET /ws opens a WebSocket taking the same commands as JSON messages and streaming a frame with
+/// the status and the surface while the simulation advances, which it does while the wave channel is
+/// shown. The server stops when dropped.
+///
+/// Every request presents the session token of the server, as `Authorization: Bearer <token>` or, for
+/// WebSockets opened by browsers, as a `token` query parameter. Requests must also name the server by
+/// an IP address or localhost, so a site rebinding its own name to this machine is refused.
+pub struct RemoteApi {
+    address: SocketAddr,
+    token: String, // Secret the clients present with every request
+    requests: Receiver<RemoteRequest>,
+    clients: Arc<Mutex<Vec<SharedStream>>>, // WebSocket connections receiving live frames
+    stopped: Arc<AtomicBool>,
+    last_frame: Option<(f64, f64)>, // Wall time and simulation time of the last live frame
+}
+
+impl RemoteApi {
+    /// Listen on an address such as 127.0.0.1:8787, port 0 picking a free port
+    /// The context, if any, is woken up when a command arrives so an idle interface answers at once
+    pub fn start(address: &str, ctx: Option<egui::Context>) -> Result<Self, String> {
+        let listener = TcpListener::bind(address).map_err(|e| format!("Cannot listen on {}: {}", address, e))?;
+        let local_address = listener.local_addr().map_err(|e| format!("Cannot read server address: {}", e))?;
+        listener.set_nonblocking(true).map_err(|e| format!("Cannot configure server: {}", e))?;
+
+        let (sender, requests) = mpsc::channel();
+        let clients = Arc::new(Mutex::new(Vec::new()));
+        let stopped = Arc::new(AtomicBool::new(false));
+        let token = new_token();
+        let server_clients = Arc::clone(&clients);
+        let server_stopped = Arc::clone(&stopped);
+        let server_token = token.clone();
+        std::thread::spawn(move || {
+            while !server_stopped.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        let sender = sender.clone();
+                        let clients = Arc::clone(&server_clients);
+                        let ctx = ctx.clone();
+                        let token = server_token.clone();
+                        std::thread::spawn(move || serve(stream, &token, sender, clients, ctx));
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => std::thread::sleep(ACCEPT_INTERVAL),
+                    Err(e) => eprintln!("Remote API connection failed: {}", e),
+                }
+            }
+        });
+
+        Ok(Self {
+            address: local_address,
+            token,
+            requests,
+            clients,
+            stopped,
+            last_frame: None,
+        })
+    }
+
+    /// Get address the server listens on
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Get session token the clients present with their requests
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Number of connected WebSocket clients
+    pub fn clients(&self) -> usize {
+        self.clients.lock().map_or(0, |clients| clients.len())
+    }
+
+    /// Apply the pending commands to the wave channel and send a live frame if one is due
+    /// Called once per frame of the interface, at wall time `time` [s]
+    pub fn poll(&mut self, app: &mut WaveChannelApp, time: f64) {
+        while let Ok(request) = self.requests.try_recv() {
+            // The client may have given up waiting
+            request.reply.send(request.command.apply(app)).ok();
+        }
+
+        let due = match self.last_frame {
+            None => true,
+            Some((sent, simulation_time)) => time - sent >= FRAME_INTERVAL && app.simulation_time != simulation_time,
+        };
+        if !due {
+            return;
+        }
+        let Ok(mut clients) = self.clients.lock() else {
+            return;
+        };
+        if clients.is_empty() {
+            return;
+        }
+        let frame = json!({ "type": "frame", "status": status(app), "surface": surface(app) }).to_string();
+        clients.retain(|client| client.lock().is_ok_and(|mut stream| write_frame(&mut *stream, OPCODE_TEXT, frame.as_bytes()).is_ok()));
+        self.last_frame = Some((time, app.simulation_time));
+    }
+}
+
+impl Drop for RemoteApi {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        if let Ok(clients) = self.clients.lock() {
+            for client in clients.iter() {
+                if let Ok(stream) = client.lock() {
+                    stream.shutdown(Shutdown::Both).ok();
+                }
+            }
+        }
+    }
+}
+
+/// Answer of the WebSocket handshake to the key of a client, base64(SHA-1(key + GUID))
+pub fn websocket_accept(key: &str) -> String {
+    base64(&sha1(format!("{}{}", key.trim(), WEBSOCKET_GUID).as_bytes()))
+}
+
+/// HTTP request line, headers and body
+struct HttpRequest {
+    method: String,
+    path: String,
+    query: String,
+    headers: Vec<(String, String)>, // Names in lowercase
+    body: String,
+}
+
+impl HttpRequest {
+    /// Read a request, the path apart from its query
+    fn read(reader: &mut impl BufRead) -> Result<Self, String> {
+        let mut line = String::new();
+        read_line(reader, &mut line)?;
+        let mut fields = line.split_whitespace();
+        let (Some(method), Some(target)) = (fields.next(), fields.next()) else {
+            return Err("Invalid request line".to_string());
+        };
+        let method = method.to_uppercase();
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let (path, query) = (path.to_string(), query.to_string());
+
+        let mut headers = Vec::new();
+        loop {
+            line.clear();
+            read_line(reader, &mut line)?;
+            let header = line.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if headers.len() == MAX_HEADERS {
+                return Err("Request has too many headers".to_string());
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+            }
+        }
+
+        let request = Self { method, path, query, headers, body: String::new() };
+        let length: usize = request.header("content-length").map_or(Ok(0), str::parse).map_err(|_| "Invalid content length")?;
+        if length > MAX_PAYLOAD {
+            return Err("Request body is too large".to_string());
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).map_err(|e| format!("Cannot read request body: {}", e))?;
+        let body = String::from_utf8(body).map_err(|_| "Request body is not UTF-8")?;
+        Ok(Self { body, ..request })
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(header, _)| header == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Check if the client asks to open a WebSocket
+    fn is_websocket(&self) -> bool {
+        self.header("upgrade").is_some_and(|upgrade| upgrade.eq_ignore_ascii_case("websocket"))
+    }
+
+    /// Check if the request comes from a program, which sends no origin, or from a page served by this server
+    /// Pages of other sites open in a browser on this machine must not drive or read the wave channel
+    fn is_same_origin(&self) -> bool {
+        let Some(origin) = self.header("origin") else {
+            return true;
+        };
+        let host = origin.split_once("://").map_or(origin, |(_, host)| host);
+        self.header("host").is_some_and(|expected| host.eq_ignore_ascii_case(expected))
+    }
+
+    /// Check if the request names the server by an IP address or localhost
+    /// A page whose own name was rebound to this machine sends that name and is refused
+    fn is_direct_host(&self) -> bool {
+        let Some(host) = self.header("host") else {
+            return false;
+        };
+        let name = match host.strip_prefix('[') {
+            Some(bracketed) => bracketed.split(']').next().unwrap_or_default(),
+            None => host.rsplit_once(':').map_or(host, |(name, _)| name),
+        };
+        name.eq_ignore_ascii_case("localhost") || name.parse::<IpAddr>().is_ok()
+    }
+
+    /// Check if the request presents the token, in the authorization header or the query
+    fn has_token(&self, token: &str) -> bool {
+        let bearer = self.header("authorization").and_then(|value| value.strip_prefix("Bearer ")).map(str::trim);
+        let query = self.query.split('&').find_map(|pair| pair.strip_prefix("token="));
+        bearer.or(query) == Some(token)
+    }
+}
+
+/// Read one line of a request, refusing lines longer than MAX_LINE rather than growing without bound
+fn read_line(reader: &mut impl BufRead, line: &mut String) -> Result<(), String> {
+    let read = reader.take(MAX_LINE as u64 + 1).read_line(line).map_err(|e| format!("Cannot read request: {}", e))?;
+    if read > MAX_LINE {
+        return Err("Request line is too long".to_string());
+    }
+    Ok(())
+}
+
+/// Serve one connection: a REST request, or a WebSocket until the client leaves
+fn serve(stream: TcpStream, token: &str, sender: Sender<RemoteRequest>, clients: Arc<Mutex<Vec<SharedStream>>>, ctx: Option<egui::Context>) {
+    // Accepted connections may inherit the non-blocking listener, and idle clients must not keep their thread
+    if stream.set_nonblocking(false).is_err() || stream.set_read_timeout(Some(REQUEST_TIMEOUT)).is_err() {
+        return;
+    }
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    let mut reader = BufReader::new(stream);
+    let request = match HttpRequest::read(&mut reader) {
+        Ok(request) => request,
+        Err(message) => {
+            write_response(&mut writer, 400, &json!({ "error": message })).ok();
+            return;
+        }
+    };
+
+    // Also covers WebSocket handshakes, which browsers open to any site without a preflight
+    if !request.is_same_origin() {
+        write_response(&mut writer, 403, &json!({ "error": "Requests from other sites are not allowed" })).ok();
+        return;
+    }
+    if !request.is_direct_host() {
+        write_response(&mut writer, 403, &json!({ "error": "Requests must name the server by its IP address or localhost" })).ok();
+        return;
+    }
+    if !request.has_token(token) {
+        write_response(&mut writer, 401, &json!({ "error": "Requests must present the token of the server" })).ok();
+        return;
+    }
+    if request.path == "/ws" && request.is_websocket() {
+        serve_websocket(reader, writer, &request, &sender, &clients, ctx.as_ref());
+        return;
+    }
+
+    let (code, body) = match RemoteCommand::route(&request.method, &request.path, &request.body) {
+        Ok(command) => match submit(command, &sender, ctx.as_ref()) {
+            Ok(value) => (200, value),
+            Err((code, message)) => (code, json!({ "error": message })),
+        },
+        Err((code, message)) => (code, json!({ "error": message })),
+    };
+    write_response(&mut writer, code, &body).ok();
+}
+
+/// Hand a command to the interface and wait for its answer
+fn submit(command: RemoteCommand, sender: &Sender<RemoteRequest>, ctx: Option<&egui::Context>) -> Result<Value, (u16, String)> {
+    let (reply, answer) = mpsc::channel();
+    sender
+        .send(RemoteRequest { command, reply })
+        .map_err(|_| (503, "Server is stopping".to_string()))?;
+    if let Some(ctx) = ctx {
+        ctx.request_repaint();
+    }
+    match answer.recv_timeout(REPLY_TIMEOUT) {
+        Ok(result) => result.map_err(|message| (400, message)),
+        Err(_) => Err((503, "Interface did not answer".to_string())),
+    }
+}
+
+/// JSON response closing the connection
+fn write_response(stream: &mut TcpStream, code: u16, body: &Value) -> std::io::Result<()> {
+    let reason = match code {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Service Unavailable",
+    };
+    let body = body.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        code,
+        reason,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xA;
+
+/// Complete the handshake, then answer the messages of the client until it closes the connection
+fn serve_websocket(
+    mut reader: BufReader<TcpStream>,
+    mut writer: TcpStream,
+    request: &HttpRequest,
+    sender: &Sender<RemoteRequest>,
+    clients: &Mutex<Vec<SharedStream>>,
+    ctx: Option<&egui::Context>,
+) {
+    let Some(key) = request.header("sec-websocket-key") else {
+        write_response(&mut writer, 400, &json!({ "error": "WebSocket key is missing" })).ok();
+        return;
+    };
+    let handshake = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        websocket_accept(key)
+    );
+    // Live clients may stay quiet for as long as they watch
+    if writer.write_all(handshake.as_bytes()).is_err() || writer.set_write_timeout(Some(WRITE_TIMEOUT)).is_err() || writer.set_read_timeout(None).is_err() {
+        return;
+    }
+    let stream = Arc::new(Mutex::new(writer));
+    if let Ok(mut clients) = clients.lock() {
+        clients.push(Arc::clone(&stream));
+    }
+    // The interface may have woken up before the client joined
+    if let Some(ctx) = ctx {
+        ctx.request_repaint();
+    }
+
+    while let Ok((opcode, payload)) = read_frame(&mut reader) {
+        let answer = match opcode {
+            OPCODE_TEXT => {
+                let result = String::from_utf8(payload)
+                    .map_err(|_| "Message is not UTF-8".to_string())
+                    .and_then(|message| RemoteCommand::from_message(&message))
+                    .and_then(|command| submit(command, sender, ctx).map_err(|(_, message)| message));
+                let message = match result {
+                    Ok(value) => json!({ "type": "reply", "result": value }),
+                    Err(message) => json!({ "type": "error", "message": message }),
+                };
+                (OPCODE_TEXT, message.to_string().into_bytes())
+            }
+            OPCODE_PING => (OPCODE_PONG, payload),
+            OPCODE_CLOSE => break,
+            _ => continue,
+        };
+        let Ok(mut stream) = stream.lock() else {
+            break;
+        };
+        if write_frame(&mut *stream, answer.0, &answer.1).is_err() {
+            break;
+        }
+    }
+
+    if let Ok(mut stream) = stream.lock() {
+        write_frame(&mut *stream, OPCODE_CLOSE, &[]).ok();
+        stream.shutdown(Shutdown::Both).ok();
+    }
+    if let Ok(mut clients) = clients.lock() {
+        clients.retain(|client| !Arc::ptr_eq(client, &stream));
+    }
+}
+
+/// Read a frame of the client, unmasking its payload
+/// Fragmented messages are not used by the commands and end the connection
+fn read_frame(reader: &mut impl Read) -> Result<(u8, Vec<u8>), String> {
+    let mut header = [0; 2];
+    reader.read_exact(&mut header).map_err(|e| e.to_string())?;
+    if header[0] & 0x80 == 0 || header[0] & 0x0F == 0 {
+        return Err("Fragmented WebSocket messages are not supported".to_string());
+    }
+    let opcode = header[0] & 0x0F;
+    let length = match header[1] & 0x7F {
+        126 => {
+            let mut bytes = [0; 2];
+            reader.read_exact(&mut bytes).map_err(|e| e.to_string())?;
+            u16::from_be_bytes(bytes) as usize
+        }
+        127 => {
+            let mut bytes = [0; 8];
+            reader.read_exact(&mut bytes).map_err(|e| e.to_string())?;
+            u64::from_be_bytes(bytes) as usize
+        }
+        length => length as usize,
+    };
+    if length > MAX_PAYLOAD {
+        return Err("WebSocket message is too large".to_string());
+    }
+    let mut mask = [0; 4];
+    if header[1] & 0x80 != 0 {
+        reader.read_exact(&mut mask).map_err(|e| e.to_string())?;
+    }
+    let mut payload = vec![0; length];
+    reader.read_exact(&mut payload).map_err(|e| e.to_string())?;
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+    Ok((opcode, payload))
+}
+
+/// Write an unmasked frame holding a whole message, as servers send them
+fn write_frame(stream: &mut impl Write, opcode: u8, payload: &[u8]) -> std::io::Result<()> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        length @ 0..=125 => frame.push(length as u8),
+        length @ 126..=0xFFFF => {
+            frame.push(126);
+            frame.extend_from_slice(&(length as u16).to_be_bytes());
+        }
+        length => {
+            frame.push(127);
+            frame.extend_from_slice(&(length as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    stream.write_all(&frame)?;
+    stream.flush()
+}
+
+/// SHA-1 digest (FIPS 180-4), only used by the WebSocket handshake
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut state: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut words = [0u32; 80];
+        for (word, bytes) in words.iter_mut().zip(block.chunks(4)) {
+            *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        for i in 16..80 {
+            words[i] = (words[i - 3] ^ words[i - 8] ^ words[i - 14] ^ words[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (i, &word) in words.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let next = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(word);
+            (e, d, c, b, a) = (d, c, b.rotate_left(30), a, next);
+        }
+        for (value, added) in state.iter_mut().zip([a, b, c, d, e]) {
+            *value = value.wrapping_add(added);
+        }
+    }
+
+    let mut digest = [0; 20];
+    for (bytes, value) in digest.chunks_mut(4).zip(state) {
+        bytes.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
+
+/// Standard base64 with padding
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], chunk.get(1).copied().unwrap_or(0), chunk.get(2).copied().unwrap_or(0)];
+        let group = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(group >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
@@ -0,0 +1,65 @@
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Display rate of the animations when the battery saver is off [frames/s]
+pub const ANIMATION_FPS: f32 = 60.0;
+
+/// Rate at which the progress of background studies is refreshed when the battery saver is off [frames/s]
+const PROGRESS_FPS: f32 = 10.0;
+
+/// When the interface is redrawn
+///
+/// Idle, the interface is only redrawn on input events. While a simulation, a replay or a session runs,
+/// frames are requested at a fixed rate, capped by the battery saver; while a background study runs,
+/// its progress is refreshed at a lower rate.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RepaintPolicy {
+    pub battery_saver: bool,
+    pub battery_saver_fps: f32, // Most animation frames per second with the battery saver on
+}
+
+impl Default for RepaintPolicy {
+    fn default() -> Self {
+        Self {
+            battery_saver: false,
+            battery_saver_fps: 20.0,
+        }
+    }
+}
+
+impl RepaintPolicy {
+    /// Frames per second of the animations
+    pub fn animation_fps(&self) -> f32 {
+        if self.battery_saver { self.battery_saver_fps.clamp(1.0, ANIMATION_FPS) } else { ANIMATION_FPS }
+    }
+
+    /// Frames per second of the progress of background studies, never above the animations
+    pub fn progress_fps(&self) -> f32 {
+        PROGRESS_FPS.min(self.animation_fps())
+    }
+
+    /// Request the next frame of an animation
+    pub fn request_frame(&self, ctx: &egui::Context) {
+        ctx.request_repaint_after(Duration::from_secs_f32(1.0 / self.animation_fps()));
+    }
+
+    /// Request the next refresh of the progress of a background study
+    pub fn request_progress(&self, ctx: &egui::Context) {
+        ctx.request_repaint_after(Duration::from_secs_f32(1.0 / self.progress_fps()));
+    }
+
+    /// Battery saver toggle and frame cap, returning whether they changed
+    pub fn show_editor(&mut self, ui: &mut egui::Ui) -> bool {
+        let previous = *self;
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.battery_saver, "Battery saver");
+            ui.add_enabled(
+                self.battery_saver,
+                egui::Slider::new(&mut self.battery_saver_fps, 5.0..=ANIMATION_FPS).text("Max Animation FPS"),
+            );
+        });
+        *self != previous
+    }
+}
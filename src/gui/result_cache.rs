@@ -0,0 +1,94 @@
+use super::run_metadata::RunMetadata;
+use crate::analysis::{DissipationRecord, ForceRecord, InundationRecord, LevelDriftRecord, MeanFlowRecord, OvertoppingTank, PressureSensor, ShorelineRecord, SnapshotStore, WaveGauge};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Outputs of a completed simulation, enough to show it again without recomputing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedResult {
+    pub configuration: String,       // Full configuration the result was computed for
+    pub simulation_time: f64,        // Final simulation time [s]
+    pub surface_elevation: Vec<f64>, // Final surface elevation at the display points [m]
+    pub gauges: Vec<WaveGauge>,      // Gauge records over the whole run
+    #[serde(default)]
+    pub pressure_sensors: Vec<PressureSensor>, // Pressure sensor records, empty in results cached before sensors existed
+    #[serde(default)]
+    pub wall_force: ForceRecord,     // Wave force on the downstream wall, empty in results cached before it was recorded
+    #[serde(default)]
+    pub overtopping_tank: Option<OvertoppingTank>, // Overtopping tank record, None without a tank or in results cached before it existed
+    #[serde(default)]
+    pub shoreline: ShorelineRecord,  // Shoreline record on the beach, empty in results cached before it was recorded
+    #[serde(default)]
+    pub mean_flow: MeanFlowRecord,   // Time-averaged flow along the channel, empty in results cached before it was recorded
+    #[serde(default)]
+    pub inundation: InundationRecord, // Flooding envelope over land, empty in results cached before it was recorded
+    #[serde(default)]
+    pub dissipation: DissipationRecord, // Energy dissipated by each mechanism, empty in results cached before it was recorded
+    #[serde(default)]
+    pub level_drift: LevelDriftRecord, // Rise of the mean water level, empty in results cached before it was recorded
+    #[serde(default)]
+    pub snapshots: SnapshotStore,    // Surface history for playback, empty in results cached before it was stored
+    #[serde(default)]
+    pub metadata: Option<RunMetadata>, // Provenance of the run, None in results cached before it was recorded
+}
+
+/// On-disk cache of completed simulations, one JSON file per configuration
+///
+/// Files are named after a 64-bit FNV-1a hash of the configuration, which is also stored
+/// in the file so that hash collisions are detected instead of restoring the wrong case.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultCache {
+    directory: PathBuf,
+}
+
+impl ResultCache {
+    /// Cache stored in a directory, created on the first write
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    /// Get cache directory
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// File holding the result of a configuration
+    fn path(&self, configuration: &str) -> PathBuf {
+        self.directory.join(format!("{:016x}.json", fnv1a(configuration.as_bytes())))
+    }
+
+    /// Store a completed simulation, replacing any previous result of the same configuration
+    pub fn store(&self, result: &CachedResult) -> Result<(), String> {
+        std::fs::create_dir_all(&self.directory).map_err(|e| format!("Cannot create cache directory: {}", e))?;
+        let content = serde_json::to_string(result).map_err(|e| format!("Cannot serialize result: {}", e))?;
+        std::fs::write(self.path(&result.configuration), content).map_err(|e| format!("Cannot write cached result: {}", e))
+    }
+
+    /// Result of a configuration, None if it was never stored or the file is unreadable
+    pub fn load(&self, configuration: &str) -> Option<CachedResult> {
+        let content = std::fs::read_to_string(self.path(configuration)).ok()?;
+        let result: CachedResult = serde_json::from_str(&content).ok()?;
+        (result.configuration == configuration).then_some(result)
+    }
+
+    /// Remove every cached result
+    pub fn clear(&self) -> Result<(), String> {
+        let Ok(entries) = std::fs::read_dir(&self.directory) else {
+            return Ok(());
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|extension| extension == "json") {
+                std::fs::remove_file(&path).map_err(|e| format!("Cannot remove {}: {}", path.display(), e))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 64-bit FNV-1a hash, stable across platforms and compiler versions unlike the standard hasher
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
@@ -0,0 +1,154 @@
+use eframe::egui;
+use std::cmp::Ordering;
+
+/// Value in a cell of a results table
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableCell {
+    Number(f64),
+    Text(String),
+    /// Missing value, with why it is missing
+    Failed(String),
+}
+
+impl TableCell {
+    /// Value of a run output, or why the run failed
+    pub fn output(output: &Result<f64, String>) -> Self {
+        match output {
+            Ok(value) => TableCell::Number(*value),
+            Err(message) => TableCell::Failed(message.clone()),
+        }
+    }
+
+    /// Text shown in the table, numbers rounded
+    pub fn display(&self) -> String {
+        match self {
+            TableCell::Number(value) => format!("{:.4}", value),
+            TableCell::Text(text) => text.clone(),
+            TableCell::Failed(_) => "⚠".to_string(),
+        }
+    }
+
+    /// Text copied to the clipboard, numbers at full precision and missing values empty
+    pub fn tsv(&self) -> String {
+        match self {
+            TableCell::Number(value) => value.to_string(),
+            TableCell::Text(text) => text.replace(['\t', '\n', '\r'], " "),
+            TableCell::Failed(_) => String::new(),
+        }
+    }
+
+    /// Order of two cells of a column: numbers, then texts, then missing values
+    fn compare(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (TableCell::Number(a), TableCell::Number(b)) => a.total_cmp(b),
+            (TableCell::Text(a), TableCell::Text(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+            (TableCell::Number(_), _) | (TableCell::Text(_), TableCell::Failed(_)) => Ordering::Less,
+            (TableCell::Failed(_), TableCell::Failed(_)) => Ordering::Equal,
+            _ => Ordering::Greater,
+        }
+    }
+}
+
+/// Sorting and filtering of a results table, kept between frames
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TableView {
+    pub sort: Option<(usize, bool)>, // Sorted column and whether it is descending, None in the order of the results
+    pub filter: String,              // Words every shown row contains, in any of its cells
+}
+
+impl TableView {
+    /// Sort by a column, ascending first and descending when it is clicked again
+    pub fn toggle_sort(&mut self, column: usize) {
+        self.sort = match self.sort {
+            Some((sorted, false)) if sorted == column => Some((column, true)),
+            _ => Some((column, false)),
+        };
+    }
+}
+
+/// Results as rows and columns, browsed by sorting and filtering and copied as tab-separated values
+///
+/// Copied tables paste into spreadsheets one value per cell: the header, then the shown rows in their
+/// order, with numbers at full precision and failed values left empty.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResultsTable {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<TableCell>>,
+}
+
+impl ResultsTable {
+    /// Create new empty table with the given column names
+    pub fn new<S: Into<String>>(columns: impl IntoIterator<Item = S>) -> Self {
+        Self { columns: columns.into_iter().map(Into::into).collect(), rows: Vec::new() }
+    }
+
+    /// Add a row, one cell per column
+    pub fn push(&mut self, row: Vec<TableCell>) {
+        self.rows.push(row);
+    }
+
+    /// Indices of the rows matching the filter of a view, in its order
+    /// Missing values stay last whichever the direction of the sort
+    pub fn visible_rows(&self, view: &TableView) -> Vec<usize> {
+        let words: Vec<String> = view.filter.split_whitespace().map(str::to_lowercase).collect();
+        let mut rows: Vec<usize> = (0..self.rows.len())
+            .filter(|&i| {
+                let texts: Vec<String> = self.rows[i].iter().map(|cell| cell.display().to_lowercase()).collect();
+                words.iter().all(|word| texts.iter().any(|text| text.contains(word.as_str())))
+            })
+            .collect();
+        if let Some((column, descending)) = view.sort {
+            let missing = TableCell::Failed(String::new());
+            let cell = |i: usize| self.rows[i].get(column).unwrap_or(&missing);
+            rows.sort_by(|&a, &b| match (cell(a), cell(b)) {
+                (a @ TableCell::Failed(_), b) | (a, b @ TableCell::Failed(_)) => a.compare(b),
+                (a, b) if descending => b.compare(a),
+                (a, b) => a.compare(b),
+            });
+        }
+        rows
+    }
+
+    /// Header and rows as tab-separated values, one line per row
+    pub fn to_tsv(&self, rows: &[usize]) -> String {
+        let header = self.columns.iter().map(|column| TableCell::Text(column.clone()).tsv()).collect::<Vec<_>>().join("\t");
+        let lines = rows.iter().map(|&i| self.rows[i].iter().map(TableCell::tsv).collect::<Vec<_>>().join("\t"));
+        std::iter::once(header).chain(lines).map(|line| line + "\n").collect()
+    }
+
+    /// Filter box, copy button and the table with clickable column headers
+    pub fn show(&self, ui: &mut egui::Ui, id: &str, view: &mut TableView) {
+        let rows = self.visible_rows(view);
+        ui.horizontal(|ui| {
+            ui.label("Filter:");
+            ui.add(egui::TextEdit::singleline(&mut view.filter).hint_text("words in any column").desired_width(160.0));
+            ui.label(format!("{} of {} rows", rows.len(), self.rows.len()));
+            if ui.small_button("📋 Copy TSV").on_hover_text("Copy the shown rows as tab-separated values, to paste into a spreadsheet").clicked() {
+                ui.ctx().copy_text(self.to_tsv(&rows));
+            }
+        });
+        egui::ScrollArea::both().id_salt(id).max_height(300.0).show(ui, |ui| {
+            egui::Grid::new(id).num_columns(self.columns.len()).striped(true).show(ui, |ui| {
+                for (column, name) in self.columns.iter().enumerate() {
+                    let arrow = match view.sort {
+                        Some((sorted, descending)) if sorted == column => if descending { " ⏷" } else { " ⏶" },
+                        _ => "",
+                    };
+                    if ui.add(egui::Button::new(egui::RichText::new(format!("{}{}", name, arrow)).strong()).frame(false)).on_hover_text("Sort by this column").clicked() {
+                        view.toggle_sort(column);
+                    }
+                }
+                ui.end_row();
+                for &i in &rows {
+                    for cell in &self.rows[i] {
+                        match cell {
+                            TableCell::Failed(message) => ui.label(cell.display()).on_hover_text(message),
+                            _ => ui.label(cell.display()),
+                        };
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+    }
+}
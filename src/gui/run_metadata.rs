@@ -0,0 +1,90 @@
+use super::result_cache::fnv1a;
+use serde::{Deserialize, Serialize};
+
+/// Provenance of a simulation run, stored with its results so they can be traced back
+/// to the exact build, configuration and machine that produced them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunMetadata {
+    pub crate_version: String,      // Version of the platform that computed the run
+    pub configuration_hash: String, // FNV-1a hash of the full configuration, as 16 hexadecimal digits
+    pub solver: String,             // Propagation model and solver options
+    pub random_seed: u64,           // Seed of the stochastic features
+    pub started_at: u64,            // Start of the run, Unix time [s]
+    pub completed_at: Option<u64>,  // End of the run, None while it is running [s]
+    pub host: String,               // Host name, operating system and architecture
+}
+
+impl RunMetadata {
+    /// Metadata of a run starting now
+    pub fn new(configuration: &str, solver: String, random_seed: u64) -> Self {
+        Self {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            configuration_hash: format!("{:016x}", fnv1a(configuration.as_bytes())),
+            solver,
+            random_seed,
+            started_at: unix_time(),
+            completed_at: None,
+            host: host_description(),
+        }
+    }
+
+    /// Mark the run as completed now
+    pub fn complete(&mut self) {
+        self.completed_at = Some(unix_time());
+    }
+
+    /// Check if the run was computed for a configuration
+    pub fn matches(&self, configuration: &str) -> bool {
+        self.configuration_hash == format!("{:016x}", fnv1a(configuration.as_bytes()))
+    }
+
+    /// Metadata as "Key: value" lines, for reports and the clipboard
+    pub fn to_text(&self) -> String {
+        let completed = self.completed_at.map_or("running".to_string(), format_utc);
+        [
+            format!("Platform Version: {}", self.crate_version),
+            format!("Configuration Hash: {}", self.configuration_hash),
+            format!("Solver: {}", self.solver),
+            format!("Random Seed: {}", self.random_seed),
+            format!("Started: {}", format_utc(self.started_at)),
+            format!("Completed: {}", completed),
+            format!("Host: {}", self.host),
+        ]
+        .join("\n")
+    }
+}
+
+/// Seconds since the Unix epoch, 0 if the clock is set before it
+pub(crate) fn unix_time() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// Host name from the environment, with the operating system and architecture of the build
+fn host_description() -> String {
+    let name = ["HOSTNAME", "COMPUTERNAME"]
+        .iter()
+        .find_map(|variable| std::env::var(variable).ok().filter(|name| !name.is_empty()))
+        .unwrap_or_else(|| "unknown host".to_string());
+    format!("{} ({} {})", name, std::env::consts::OS, std::env::consts::ARCH)
+}
+
+/// Unix time as an ISO 8601 UTC timestamp, e.g. 2024-06-01T12:00:00Z
+/// Calendar conversion with Hinnant's civil-from-days algorithm, valid for any date after the epoch
+pub fn format_utc(seconds: u64) -> String {
+    let days = seconds / 86400;
+    let (hour, minute, second) = (seconds % 86400 / 3600, seconds % 3600 / 60, seconds % 60);
+
+    // Shift the epoch to 0000-03-01 so leap days fall at the end of 400-year eras
+    let z = days + 719468;
+    let era = z / 146097;
+    let day_of_era = z % 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, hour, minute, second)
+}
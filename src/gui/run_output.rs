@@ -0,0 +1,132 @@
+use super::project::Project;
+use super::wave_channel::{PropagationModel, WaveChannelApp};
+use crate::analysis::spectral::significant_wave_height;
+use serde::{Deserialize, Serialize};
+
+/// Time step of the runs evaluated in the background [s]
+const RUN_TIME_STEP: f64 = 0.05;
+
+/// Water depth above the bed counted as wet for the run-up [m]
+pub(super) const WET_DEPTH: f64 = 1e-3;
+
+/// Result of a complete run of the wave channel, the output of probabilistic and design studies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RunOutput {
+    /// Largest surface elevation recorded by a gauge
+    MaxElevation { gauge: usize },
+    /// Significant wave height 4√m0 over the statistics window of a gauge
+    SignificantWaveHeight { gauge: usize },
+    /// Highest bed elevation above the still water level reached by the water
+    RunUp,
+    /// Mean discharge over the landward edge of the crest, averaged over the run
+    Overtopping,
+}
+
+impl RunOutput {
+    /// Name of the output, with its gauge
+    pub fn label(&self) -> String {
+        match self {
+            RunOutput::MaxElevation { gauge } => format!("Max Elevation at Gauge {}", gauge + 1),
+            RunOutput::SignificantWaveHeight { gauge } => format!("Hm0 at Gauge {}", gauge + 1),
+            RunOutput::RunUp => "Run-up".to_string(),
+            RunOutput::Overtopping => "Overtopping Discharge".to_string(),
+        }
+    }
+
+    /// Unit of the output values
+    pub fn unit(&self) -> &'static str {
+        match self {
+            RunOutput::Overtopping => "l/s/m",
+            _ => "m",
+        }
+    }
+
+    /// Gauge the output is measured at, if any
+    pub fn gauge(&self) -> Option<usize> {
+        match *self {
+            RunOutput::MaxElevation { gauge } | RunOutput::SignificantWaveHeight { gauge } => Some(gauge),
+            RunOutput::RunUp | RunOutput::Overtopping => None,
+        }
+    }
+
+    /// Outputs of a scenario with a number of gauges
+    pub fn available(gauges: usize) -> Vec<RunOutput> {
+        (0..gauges)
+            .flat_map(|gauge| [RunOutput::MaxElevation { gauge }, RunOutput::SignificantWaveHeight { gauge }])
+            .chain([RunOutput::RunUp, RunOutput::Overtopping])
+            .collect()
+    }
+
+    /// Check that a scenario can produce the output
+    /// Run-up and overtopping need the numerical solver over a cross-section
+    pub fn validate(&self, scenario: &Project) -> Result<(), String> {
+        match self.gauge() {
+            Some(gauge) if gauge >= scenario.gauge_positions.len() => Err(format!("Gauge {} is not in the channel", gauge + 1)),
+            Some(_) => Ok(()),
+            None if scenario.propagation_model != PropagationModel::Numerical => Err(format!("{} needs the numerical solver", self.label())),
+            None if scenario.cross_section.is_none() => Err(format!("{} needs a cross-section rising above the still water level", self.label())),
+            None => Ok(()),
+        }
+    }
+
+    /// Run a wave channel scenario to its end and measure the output
+    pub fn evaluate(&self, channel: &mut WaveChannelApp) -> Result<f64, String> {
+        Self::evaluate_all(&[*self], channel).remove(0)
+    }
+
+    /// Run a wave channel scenario to its end once and measure each of the outputs
+    pub fn evaluate_all(outputs: &[RunOutput], channel: &mut WaveChannelApp) -> Vec<Result<f64, String>> {
+        // Nobody watches a batch run, and an alert pausing it would end it early
+        channel.alerts.clear();
+        channel.start_simulation();
+        let mut run_up = 0.0_f64;
+        let mut discharge_gauge = None;
+        while channel.simulation_running {
+            channel.advance_simulation(RUN_TIME_STEP);
+            if outputs.contains(&RunOutput::RunUp) {
+                run_up = run_up.max(highest_wet_bed(channel));
+            }
+            // The solver is built on the first step, before any water reaches the crest
+            if outputs.contains(&RunOutput::Overtopping) && discharge_gauge.is_none() {
+                let crest = channel.cross_section.as_ref().map(|section| section.crest_position());
+                if let (Some(solver), Some(crest)) = (channel.solver.as_mut(), crest) {
+                    discharge_gauge = Some(solver.add_discharge_gauge(crest));
+                }
+            }
+        }
+        if channel.surface_elevation.iter().any(|elevation| !elevation.is_finite()) {
+            return outputs.iter().map(|_| Err("Simulation diverged".to_string())).collect();
+        }
+        let measure = |output: RunOutput| match output {
+            RunOutput::MaxElevation { gauge } => {
+                let gauge = channel.gauges.get(gauge).ok_or("Gauge is not in the channel")?;
+                Ok(gauge.elevation().iter().copied().fold(f64::NEG_INFINITY, f64::max))
+            }
+            RunOutput::SignificantWaveHeight { gauge } => {
+                let record = channel.statistics_record(gauge).ok_or("Gauge is not in the channel")?;
+                Ok(significant_wave_height(record.elevation()))
+            }
+            RunOutput::RunUp => Ok(run_up),
+            RunOutput::Overtopping => {
+                let solver = channel.solver.as_ref().ok_or("Overtopping needs the numerical solver")?;
+                let gauge = discharge_gauge.ok_or("Overtopping needs a cross-section")?;
+                // m³/s per metre to l/s per metre
+                Ok(1000.0 * solver.discharged_volume(gauge) / channel.simulation_time.max(RUN_TIME_STEP))
+            }
+        };
+        outputs.iter().map(|&output| measure(output)).collect()
+    }
+}
+
+/// Highest bed elevation above the still water level covered by water, 0 if the water stays below it [m]
+fn highest_wet_bed(channel: &WaveChannelApp) -> f64 {
+    let dx = channel.grid_spacing();
+    channel
+        .surface_elevation
+        .iter()
+        .enumerate()
+        .map(|(i, eta)| (channel.bed_elevation(i as f64 * dx) - channel.still_water_level, eta))
+        .filter(|(bed, eta)| **eta - bed > WET_DEPTH)
+        .map(|(bed, _)| bed)
+        .fold(0.0, f64::max)
+}
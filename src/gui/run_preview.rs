@@ -0,0 +1,57 @@
+use super::repaint::ANIMATION_FPS;
+
+/// Cost of a run estimated before launching it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunPreview {
+    pub duration: f64,               // Simulated time [s]
+    pub frames: usize,               // Animation steps of the run
+    pub solver_steps: Option<usize>, // Time steps of the numerical solver, None for the analytical model
+    pub cells: usize,                // Computational points of the channel
+    pub snapshot_bytes: u64,         // Surface history kept in memory for playback [bytes]
+    pub gauge_bytes: u64,            // Gauge records kept in memory [bytes]
+    pub stream_bytes: Option<u64>,   // Snapshot file on disk, None when not streaming [bytes]
+    pub seconds_per_step: f64,       // Measured computing time of a solver step, or of an analytical frame [s]
+}
+
+impl RunPreview {
+    /// Memory held by the stored outputs at the end of the run [bytes]
+    pub fn memory_bytes(&self) -> u64 {
+        self.snapshot_bytes + self.gauge_bytes
+    }
+
+    /// Projected computing time of the whole run [s]
+    pub fn compute_time(&self) -> f64 {
+        self.solver_steps.unwrap_or(self.frames) as f64 * self.seconds_per_step
+    }
+
+    /// Projected wall-clock time of the animated run [s]
+    /// The animation advances one step per displayed frame, so short runs are bound by the frame rate
+    pub fn wall_clock_time(&self) -> f64 {
+        self.compute_time().max(self.frames as f64 / ANIMATION_FPS as f64)
+    }
+}
+
+/// Byte count with a binary prefix, e.g. "1.5 MiB"
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 { format!("{} B", bytes) } else { format!("{:.1} {}", value, UNITS[unit]) }
+}
+
+/// Duration in the largest fitting units, e.g. "2 min 05 s"
+pub fn format_duration(seconds: f64) -> String {
+    if seconds < 1.0 {
+        return format!("{:.0} ms", seconds * 1000.0);
+    }
+    if seconds < 60.0 {
+        return format!("{:.1} s", seconds);
+    }
+    let total = seconds.round() as u64;
+    let (hours, minutes, seconds) = (total / 3600, total % 3600 / 60, total % 60);
+    if hours == 0 { format!("{} min {:02} s", minutes, seconds) } else { format!("{} h {:02} min", hours, minutes) }
+}
@@ -0,0 +1,203 @@
+use super::PlatformTab;
+use super::project::Project;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Change made by the user during a recorded session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SessionEvent {
+    /// Wave channel settings after a parameter change
+    Configure(Box<Project>),
+    /// Simulation started or paused
+    Running(bool),
+    /// Simulation reset to its initial state
+    Reset,
+    /// Tool selected
+    OpenTab(PlatformTab),
+}
+
+/// Event with its time since the start of the recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionEntry {
+    pub time: f64, // Time since the start of the recording [s]
+    pub event: SessionEvent,
+}
+
+/// State of the platform the recorder compares from one frame to the next
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionState {
+    pub project: Project,
+    pub running: bool,
+    pub simulation_time: f64, // Going back in time means the simulation was reset [s]
+    pub tab: PlatformTab,
+}
+
+/// Timestamped interactions of a session, saved as JSON so it can be replayed or submitted
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct SessionRecording {
+    pub entries: Vec<SessionEntry>,
+}
+
+impl SessionRecording {
+    /// Time of the last event [s]
+    pub fn duration(&self) -> f64 {
+        self.entries.last().map_or(0.0, |entry| entry.time)
+    }
+
+    /// Recording as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Cannot serialize session: {}", e))
+    }
+
+    /// Recording from JSON
+    pub fn from_json(content: &str) -> Result<Self, String> {
+        serde_json::from_str(content).map_err(|e| format!("Invalid session file: {}", e))
+    }
+
+    /// Write the recording to a file
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        std::fs::write(path, self.to_json()?).map_err(|e| format!("Cannot write {}: {}", path.display(), e))
+    }
+
+    /// Read a recording from a file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        Self::from_json(&content)
+    }
+}
+
+/// Recording in progress
+#[derive(Debug, Clone, PartialEq)]
+struct Recording {
+    start: f64,
+    recording: SessionRecording,
+    last_state: SessionState,
+}
+
+/// Replay in progress
+#[derive(Debug, Clone, PartialEq)]
+struct Replay {
+    start: f64,
+    recording: SessionRecording,
+    next: usize, // Index of the next entry to replay
+}
+
+/// Recorder and player of user sessions, for prepared classroom demonstrations
+///
+/// While recording, the state of the platform is compared every frame with the previous one and
+/// every difference is stored as an event: a new project when a parameter changed, starting,
+/// pausing or resetting the simulation, and selecting a tool. Replaying applies the events at the
+/// same times since the start, so the session unfolds as it was recorded.
+/// Times are any monotonic clock in seconds, such as the egui input time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionRecorder {
+    recording: Option<Recording>,
+    replay: Option<Replay>,
+}
+
+impl SessionRecorder {
+    /// Create idle recorder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check if a session is being recorded
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Check if a session is being replayed
+    pub fn is_replaying(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    /// Start recording from the current state, which is recorded as the first events
+    pub fn start_recording(&mut self, time: f64, state: SessionState) {
+        self.replay = None;
+        let mut recording = SessionRecording::default();
+        for event in [
+            SessionEvent::OpenTab(state.tab),
+            SessionEvent::Configure(Box::new(state.project.clone())),
+            SessionEvent::Reset,
+            SessionEvent::Running(state.running),
+        ] {
+            recording.entries.push(SessionEntry { time: 0.0, event });
+        }
+        self.recording = Some(Recording { start: time, recording, last_state: state });
+    }
+
+    /// Record the differences between the state and the previous one
+    pub fn record(&mut self, time: f64, state: SessionState) {
+        let Some(recording) = &mut self.recording else {
+            return;
+        };
+        let previous = &recording.last_state;
+        let mut events = Vec::new();
+        if state.tab != previous.tab {
+            events.push(SessionEvent::OpenTab(state.tab));
+        }
+        if state.project != previous.project {
+            events.push(SessionEvent::Configure(Box::new(state.project.clone())));
+        }
+        if state.simulation_time < previous.simulation_time {
+            events.push(SessionEvent::Reset);
+        }
+        if state.running != previous.running {
+            events.push(SessionEvent::Running(state.running));
+        }
+        let elapsed = time - recording.start;
+        recording
+            .recording
+            .entries
+            .extend(events.into_iter().map(|event| SessionEntry { time: elapsed, event }));
+        recording.last_state = state;
+    }
+
+    /// Stop recording, returning the recorded session
+    pub fn stop_recording(&mut self) -> Option<SessionRecording> {
+        self.recording.take().map(|recording| recording.recording)
+    }
+
+    /// Start replaying a recorded session
+    pub fn start_replay(&mut self, time: f64, recording: SessionRecording) {
+        self.recording = None;
+        self.replay = Some(Replay { start: time, recording, next: 0 });
+    }
+
+    /// Stop replaying
+    pub fn stop_replay(&mut self) {
+        self.replay = None;
+    }
+
+    /// Events of the replayed session due by now, in order; the replay ends after the last one
+    pub fn due_events(&mut self, time: f64) -> Vec<SessionEvent> {
+        let Some(replay) = &mut self.replay else {
+            return Vec::new();
+        };
+        let elapsed = time - replay.start;
+        let due = replay.recording.entries[replay.next..]
+            .iter()
+            .take_while(|entry| entry.time <= elapsed)
+            .map(|entry| entry.event.clone())
+            .collect::<Vec<_>>();
+        replay.next += due.len();
+        if replay.next == replay.recording.entries.len() {
+            self.replay = None;
+        }
+        due
+    }
+
+    /// Elapsed and total time of the replay [s]
+    pub fn replay_progress(&self, time: f64) -> Option<(f64, f64)> {
+        self.replay
+            .as_ref()
+            .map(|replay| ((time - replay.start).min(replay.recording.duration()), replay.recording.duration()))
+    }
+
+    /// Elapsed time and number of events of the recording [s]
+    pub fn recording_progress(&self, time: f64) -> Option<(f64, usize)> {
+        self.recording
+            .as_ref()
+            .map(|recording| (time - recording.start, recording.recording.entries.len()))
+    }
+}
@@ -0,0 +1,229 @@
+use super::PlatformTab;
+use super::command_palette::Command;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+/// Key combination triggering a command, stored in the preferences as text such as "Ctrl+Shift+G"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct KeyBinding {
+    pub key: egui::Key,
+    pub command: bool, // Ctrl, or Cmd on macOS
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl KeyBinding {
+    /// Key pressed without modifiers
+    pub fn key(key: egui::Key) -> Self {
+        Self { key, command: false, shift: false, alt: false }
+    }
+
+    /// Key pressed with Ctrl, or Cmd on macOS
+    pub fn command(key: egui::Key) -> Self {
+        Self { command: true, ..Self::key(key) }
+    }
+
+    /// Key pressed with Shift
+    pub fn shift(key: egui::Key) -> Self {
+        Self { shift: true, ..Self::key(key) }
+    }
+
+    /// Binding of a key pressed with modifiers
+    pub fn from_modifiers(key: egui::Key, modifiers: egui::Modifiers) -> Self {
+        Self {
+            key,
+            command: modifiers.command,
+            shift: modifiers.shift,
+            alt: modifiers.alt,
+        }
+    }
+
+    /// Binding as an egui shortcut
+    pub fn shortcut(&self) -> egui::KeyboardShortcut {
+        let modifiers = egui::Modifiers {
+            alt: self.alt,
+            shift: self.shift,
+            command: self.command,
+            ..egui::Modifiers::NONE
+        };
+        egui::KeyboardShortcut::new(modifiers, self.key)
+    }
+
+    /// Number of modifiers held with the key
+    fn modifier_count(&self) -> usize {
+        [self.command, self.shift, self.alt].iter().filter(|held| **held).count()
+    }
+}
+
+impl std::fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.command {
+            write!(f, "Ctrl+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        write!(f, "{}", self.key.name())
+    }
+}
+
+impl std::str::FromStr for KeyBinding {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        // A trailing empty part is the plus key itself, as in "Ctrl++"
+        if parts.len() > 1 && parts[parts.len() - 1].is_empty() {
+            parts.pop();
+            *parts.last_mut().unwrap() = "+";
+        }
+        let (key, modifiers) = parts.split_last().ok_or_else(|| "Empty key binding".to_string())?;
+        let key = egui::Key::from_name(key).ok_or_else(|| format!("Unknown key: {}", key))?;
+        let mut binding = Self::key(key);
+        for modifier in modifiers {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" | "cmd" => binding.command = true,
+                "shift" => binding.shift = true,
+                "alt" => binding.alt = true,
+                _ => return Err(format!("Unknown modifier: {}", modifier)),
+            }
+        }
+        Ok(binding)
+    }
+}
+
+impl TryFrom<String> for KeyBinding {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+impl From<KeyBinding> for String {
+    fn from(binding: KeyBinding) -> Self {
+        binding.to_string()
+    }
+}
+
+/// Key binding of a command, None if the user removed it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ShortcutBinding {
+    pub command: Command,
+    pub binding: Option<KeyBinding>,
+}
+
+/// Registry of the keyboard shortcuts of the platform commands
+///
+/// Only the bindings differing from the defaults are stored, so commands added in a later
+/// version get their default shortcut even with preferences saved by an earlier one.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Shortcuts {
+    overrides: Vec<ShortcutBinding>,
+}
+
+impl Shortcuts {
+    /// Registry with the default shortcuts
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shortcut of a command before any remapping
+    pub fn default_binding(command: Command) -> Option<KeyBinding> {
+        use egui::Key;
+        match command {
+            Command::PlayPause => Some(KeyBinding::key(Key::Space)),
+            Command::Reset => Some(KeyBinding::key(Key::R)),
+            Command::AddGauge => Some(KeyBinding::key(Key::G)),
+            Command::GaugeArray => Some(KeyBinding::shift(Key::G)),
+            Command::ToggleLinearOverlay => Some(KeyBinding::key(Key::L)),
+            Command::ToggleCrestTracking => Some(KeyBinding::key(Key::C)),
+            Command::ToggleHelp => Some(KeyBinding::key(Key::F1)),
+            Command::TogglePerformanceHud => Some(KeyBinding::key(Key::F3)),
+            Command::ToggleDashboard => Some(KeyBinding::key(Key::F4)),
+            Command::NewSeed => Some(KeyBinding::key(Key::N)),
+            Command::SaveProject => Some(KeyBinding::command(Key::S)),
+            Command::OpenPreferences => Some(KeyBinding::command(Key::Comma)),
+            Command::OpenTab(PlatformTab::WaveChannel) => Some(KeyBinding::command(Key::Num1)),
+            Command::OpenTab(PlatformTab::DispersionExplorer) => Some(KeyBinding::command(Key::Num2)),
+            Command::OpenTab(PlatformTab::Comparison) => Some(KeyBinding::command(Key::Num3)),
+            Command::OpenTab(PlatformTab::Exercises) => Some(KeyBinding::command(Key::Num4)),
+            Command::OpenTab(PlatformTab::MonteCarlo) => Some(KeyBinding::command(Key::Num5)),
+            Command::OpenTab(PlatformTab::DesignOptimizer) => Some(KeyBinding::command(Key::Num6)),
+            Command::OpenTab(PlatformTab::WaveClimate) => Some(KeyBinding::command(Key::Num7)),
+            Command::OpenTab(PlatformTab::WaveBasin) => Some(KeyBinding::command(Key::Num8)),
+            Command::OpenTab(PlatformTab::Transects)
+            | Command::WavemakerScenario
+            | Command::DamBreakScenario
+            | Command::GaussianHumpScenario
+            | Command::TsunamiScenario
+            | Command::BreakwaterDiffractionScenario
+            | Command::HarborAgitationScenario
+            | Command::CopyRunMetadata => None,
+        }
+    }
+
+    /// Current shortcut of a command
+    pub fn binding(&self, command: Command) -> Option<KeyBinding> {
+        self.overrides
+            .iter()
+            .find(|entry| entry.command == command)
+            .map_or_else(|| Self::default_binding(command), |entry| entry.binding)
+    }
+
+    /// Bind a command to a key, removing the key from any other command
+    /// None removes the shortcut of the command
+    pub fn set_binding(&mut self, command: Command, binding: Option<KeyBinding>) {
+        if binding.is_some() {
+            for other in Command::all() {
+                if other != command && self.binding(other) == binding {
+                    self.set_override(other, None);
+                }
+            }
+        }
+        self.set_override(command, binding);
+    }
+
+    /// Record a binding, dropping the override if it is the default
+    fn set_override(&mut self, command: Command, binding: Option<KeyBinding>) {
+        self.overrides.retain(|entry| entry.command != command);
+        if binding != Self::default_binding(command) {
+            self.overrides.push(ShortcutBinding { command, binding });
+        }
+    }
+
+    /// Restore every default shortcut
+    pub fn reset(&mut self) {
+        self.overrides.clear();
+    }
+
+    /// Check if every shortcut is the default one
+    pub fn is_default(&self) -> bool {
+        self.overrides.is_empty()
+    }
+
+    /// Command whose shortcut was pressed this frame, consuming the key press
+    ///
+    /// Keys without Ctrl are ignored while a text field has focus, so typing does not trigger them.
+    /// Shortcuts with more modifiers are checked first, so Shift+G is not taken for G.
+    pub fn pressed(&self, ctx: &egui::Context) -> Option<Command> {
+        let typing = ctx.wants_keyboard_input();
+        let mut bindings: Vec<(Command, KeyBinding)> = Command::all()
+            .into_iter()
+            .filter_map(|command| self.binding(command).map(|binding| (command, binding)))
+            .filter(|(_, binding)| binding.command || !typing)
+            .collect();
+        bindings.sort_by_key(|(_, binding)| std::cmp::Reverse(binding.modifier_count()));
+        ctx.input_mut(|i| {
+            bindings
+                .into_iter()
+                .find(|(_, binding)| i.consume_shortcut(&binding.shortcut()))
+                .map(|(command, _)| command)
+        })
+    }
+}
@@ -0,0 +1,306 @@
+use super::repaint::RepaintPolicy;
+use crate::analysis::{Snapshot, SnapshotStore};
+use eframe::egui;
+use egui_plot::{Line, LineStyle, Plot, PlotPoints, Points, Polygon, VLine};
+
+/// Playback position, loop range and replay state over a snapshot store
+///
+/// The cursor is kept as a continuous time so that replay advances smoothly through decimated history;
+/// the frame shown is always the stored snapshot nearest to it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Timeline {
+    cursor: Option<f64>,              // Requested playback time [s], None to follow the simulation
+    loop_range: Option<(f64, f64)>,   // Start and end of the replayed range [s]
+    playing: bool,                    // Replay the stored snapshots in real time
+    exact_scrubbing: bool,            // Keep the requested time when scrubbing, for frames read from a finer record
+}
+
+impl Timeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get playback time, None when following the simulation [s]
+    pub fn cursor(&self) -> Option<f64> {
+        self.cursor
+    }
+
+    /// Get loop range [s]
+    pub fn loop_range(&self) -> Option<(f64, f64)> {
+        self.loop_range
+    }
+
+    /// Whether the stored snapshots are being replayed
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Keep the requested time when scrubbing instead of snapping to the nearest stored snapshot
+    pub fn set_exact_scrubbing(&mut self, exact: bool) {
+        self.exact_scrubbing = exact;
+    }
+
+    /// Snapshot shown at the cursor, None when following the simulation
+    pub fn displayed_snapshot<'a>(&self, snapshots: &'a SnapshotStore) -> Option<&'a Snapshot> {
+        self.cursor.and_then(|time| snapshots.nearest(time))
+    }
+
+    /// Move the cursor to the snapshot nearest to a time, or to the time itself within the record when scrubbing exactly
+    pub fn scrub_to(&mut self, snapshots: &SnapshotStore, time: f64) {
+        if let (true, Some(start), Some(end)) = (self.exact_scrubbing, snapshots.start_time(), snapshots.end_time()) {
+            self.cursor = Some(time.clamp(start, end));
+        } else if let Some(snapshot) = snapshots.nearest(time) {
+            self.cursor = Some(snapshot.time);
+        }
+    }
+
+    /// Move the cursor one stored frame forward or backward, from the last frame when following the simulation
+    pub fn step(&mut self, snapshots: &SnapshotStore, forward: bool) {
+        self.playing = false;
+        let Some(time) = self.displayed_time(snapshots) else {
+            return;
+        };
+        match snapshots.step(time, forward) {
+            Some(snapshot) => self.cursor = Some(snapshot.time),
+            None if self.cursor.is_some() => self.cursor = Some(time),
+            None => {}
+        }
+    }
+
+    /// Start or stop the replay, from the loop start if the cursor is outside the loop
+    pub fn toggle_replay(&mut self, snapshots: &SnapshotStore) {
+        if self.playing {
+            self.playing = false;
+            return;
+        }
+        let Some((start, end)) = self.replay_range(snapshots) else {
+            return;
+        };
+        if self.cursor.is_none_or(|time| time < start || time >= end) {
+            self.cursor = Some(start);
+        }
+        self.playing = true;
+    }
+
+    /// Advance the replay by a time step, wrapping within the loop range or stopping at the last frame
+    pub fn advance(&mut self, snapshots: &SnapshotStore, dt: f64) {
+        if !self.playing {
+            return;
+        }
+        let Some((start, end)) = self.replay_range(snapshots) else {
+            self.playing = false;
+            return;
+        };
+        let time = self.cursor.unwrap_or(start).max(start) + dt;
+        if time <= end {
+            self.cursor = Some(time);
+        } else if self.loop_range.is_some() {
+            self.cursor = Some(start + (time - end).min(end - start));
+        } else {
+            self.cursor = Some(end);
+            self.playing = false;
+        }
+    }
+
+    /// Set the loop start at the displayed frame, keeping the loop end after it
+    pub fn set_loop_start(&mut self, snapshots: &SnapshotStore) {
+        let (Some(time), Some(last)) = (self.displayed_time(snapshots), snapshots.end_time()) else {
+            return;
+        };
+        let end = self.loop_range.map_or(last, |(_, end)| end);
+        if time < end {
+            self.loop_range = Some((time, end));
+        }
+    }
+
+    /// Set the loop end at the displayed frame, keeping the loop start before it
+    pub fn set_loop_end(&mut self, snapshots: &SnapshotStore) {
+        let (Some(time), Some(first)) = (self.displayed_time(snapshots), snapshots.start_time()) else {
+            return;
+        };
+        let start = self.loop_range.map_or(first, |(start, _)| start);
+        if time > start {
+            self.loop_range = Some((start, time));
+        }
+    }
+
+    /// Replay the whole record again
+    pub fn clear_loop(&mut self) {
+        self.loop_range = None;
+    }
+
+    /// Return to the simulation, keeping the loop range
+    pub fn follow_live(&mut self) {
+        self.cursor = None;
+        self.playing = false;
+    }
+
+    /// Time of the frame shown, the last frame when following the simulation [s]
+    fn displayed_time(&self, snapshots: &SnapshotStore) -> Option<f64> {
+        self.displayed_snapshot(snapshots).map(|snapshot| snapshot.time).or(snapshots.end_time())
+    }
+
+    /// Range covered by the replay, the loop if set [s]
+    fn replay_range(&self, snapshots: &SnapshotStore) -> Option<(f64, f64)> {
+        let range = (snapshots.start_time()?, snapshots.end_time()?);
+        Some(self.loop_range.unwrap_or(range))
+    }
+
+    /// Apply the keyboard shortcuts, unless a text field has the focus
+    fn handle_keys(&mut self, ctx: &egui::Context, snapshots: &SnapshotStore) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let pressed = |key| ctx.input(|input| input.key_pressed(key));
+        if pressed(egui::Key::ArrowLeft) {
+            self.step(snapshots, false);
+        }
+        if pressed(egui::Key::ArrowRight) {
+            self.step(snapshots, true);
+        }
+        if pressed(egui::Key::Home) {
+            self.playing = false;
+            self.scrub_to(snapshots, f64::NEG_INFINITY);
+        }
+        if pressed(egui::Key::End) {
+            self.playing = false;
+            self.scrub_to(snapshots, f64::INFINITY);
+        }
+        if pressed(egui::Key::Space) {
+            self.toggle_replay(snapshots);
+        }
+        if pressed(egui::Key::I) {
+            self.set_loop_start(snapshots);
+        }
+        if pressed(egui::Key::O) {
+            self.set_loop_end(snapshots);
+        }
+        if pressed(egui::Key::Escape) {
+            self.follow_live();
+        }
+    }
+
+    /// Show the transport controls and the zoomable timeline of the stored snapshots, with events marked at their times
+    pub fn show(&mut self, ui: &mut egui::Ui, snapshots: &SnapshotStore, events: &[(f64, String)], repaint_policy: RepaintPolicy) {
+        let (Some(start), Some(end)) = (snapshots.start_time(), snapshots.end_time()) else {
+            return;
+        };
+        self.handle_keys(ui.ctx(), snapshots);
+
+        if self.playing {
+            self.advance(snapshots, ui.input(|input| input.stable_dt) as f64);
+            repaint_policy.request_frame(ui.ctx());
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("⏮").on_hover_text("First frame (Home)").clicked() {
+                self.playing = false;
+                self.scrub_to(snapshots, start);
+            }
+            if ui.button("⏪").on_hover_text("Previous frame (←)").clicked() {
+                self.step(snapshots, false);
+            }
+            let replay_label = if self.playing { "⏸ Replay" } else { "▶ Replay" };
+            if ui.button(replay_label).on_hover_text("Replay the stored snapshots (Space)").clicked() {
+                self.toggle_replay(snapshots);
+            }
+            if ui.button("⏩").on_hover_text("Next frame (→)").clicked() {
+                self.step(snapshots, true);
+            }
+            if ui.button("⏭").on_hover_text("Last frame (End)").clicked() {
+                self.playing = false;
+                self.scrub_to(snapshots, end);
+            }
+
+            ui.add_space(10.0);
+            if ui.button("Loop In").on_hover_text("Start the loop at the displayed frame (I)").clicked() {
+                self.set_loop_start(snapshots);
+            }
+            if ui.button("Loop Out").on_hover_text("End the loop at the displayed frame (O)").clicked() {
+                self.set_loop_end(snapshots);
+            }
+            if self.loop_range.is_some() && ui.button("✖ Clear Loop").clicked() {
+                self.clear_loop();
+            }
+            if self.cursor.is_some() && ui.button("📡 Live").on_hover_text("Follow the simulation (Esc)").clicked() {
+                self.follow_live();
+            }
+        });
+
+        match self.displayed_snapshot(snapshots) {
+            Some(snapshot) => {
+                let frame = snapshots.frame_index(snapshot.time).unwrap_or(0);
+                ui.label(format!(
+                    "Frame {} of {} at {:.2}s, snapshots every {:.2}s here",
+                    frame + 1,
+                    snapshots.len(),
+                    snapshot.time,
+                    snapshots.resolution_at(snapshot.time)
+                ));
+            }
+            None => {
+                ui.label(format!("{} snapshots over {:.1}s", snapshots.len(), end - start));
+            }
+        }
+        if let Some((loop_start, loop_end)) = self.loop_range {
+            ui.label(format!("Loop: {:.2}s to {:.2}s", loop_start, loop_end));
+        }
+
+        let recent_start = snapshots.recent_start().unwrap_or(end);
+        let history: Vec<[f64; 2]> = snapshots
+            .snapshots()
+            .filter(|snapshot| snapshot.time < recent_start)
+            .map(|snapshot| [snapshot.time, 0.0])
+            .collect();
+        let recent = vec![[recent_start, 0.0], [end, 0.0]];
+        let shown_time = self.displayed_time(snapshots).unwrap_or(end);
+        let loop_range = self.loop_range;
+        let mut scrubbed_time = None;
+
+        Plot::new("playback_bar")
+            .height(60.0)
+            .width((ui.available_width() - 40.0).max(400.0))
+            .show_y(false)
+            .show_axes([true, false])
+            .y_axis_min_width(0.0)
+            .include_x(start)
+            .include_x(end)
+            .include_y(-1.0)
+            .include_y(1.0)
+            .allow_drag(false)
+            .allow_zoom([true, false])
+            .allow_scroll([true, false])
+            .allow_boxed_zoom(true)
+            .auto_bounds([false, false])
+            .show(ui, |plot_ui| {
+                // Loop range as a shaded band
+                if let Some((loop_start, loop_end)) = loop_range {
+                    let band = vec![[loop_start, -1.0], [loop_end, -1.0], [loop_end, 1.0], [loop_start, 1.0]];
+                    plot_ui.polygon(
+                        Polygon::new(PlotPoints::from(band))
+                            .fill_color(egui::Color32::from_rgba_unmultiplied(255, 215, 0, 60)) // Translucent gold
+                            .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(218, 165, 32)))
+                            .name("Loop"),
+                    );
+                }
+
+                // Decimated history as ticks, the full-resolution window as a solid bar
+                plot_ui.points(Points::new(history).radius(2.0).color(egui::Color32::from_gray(140)).name("Decimated History"));
+                plot_ui.line(Line::new(PlotPoints::from(recent)).width(6.0).color(egui::Color32::from_rgb(30, 144, 255)).name("Full Resolution"));
+                for (time, name) in events {
+                    plot_ui.vline(VLine::new(*time).color(egui::Color32::from_rgb(255, 140, 0)).style(LineStyle::dashed_loose()).name(name));
+                }
+                plot_ui.vline(VLine::new(shown_time).color(egui::Color32::from_rgb(220, 20, 60)).width(2.0));
+
+                let response = plot_ui.response();
+                if response.clicked() || response.dragged() {
+                    scrubbed_time = plot_ui.pointer_coordinate().map(|pointer| pointer.x);
+                }
+            });
+
+        if let Some(time) = scrubbed_time {
+            self.playing = false;
+            self.scrub_to(snapshots, time);
+        }
+    }
+}
@@ -0,0 +1,384 @@
+use super::background::BackgroundRun;
+use super::project::Project;
+use super::results_table::{ResultsTable, TableCell, TableView};
+use super::run_output::RunOutput;
+use super::wave_channel::WaveChannelApp;
+use crate::solver::CrossSection;
+use eframe::egui;
+use egui_plot::{Legend, Line, MarkerShape, Plot, PlotPoints, Points};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
+
+/// Named cross-shore profile of the project, at a chainage along the coast
+///
+/// Transects share the waves, solver and gauges of the wave channel scenario and bring their own
+/// length, still water depth and bed, so the same storm can be run along a stretch of coast.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transect {
+    pub name: String,
+    pub chainage: f64,                       // Distance along the coast [m]
+    pub channel_length: f64,                 // Length of the profile from its offshore end [m]
+    pub still_water_level: f64,              // Still water depth at the offshore end [m]
+    pub cross_section: Option<CrossSection>, // Bed and structures, None for a flat bottom
+}
+
+impl Transect {
+    /// Transect of the current profile of the wave channel
+    pub fn from_channel(name: &str, chainage: f64, channel: &WaveChannelApp) -> Self {
+        Self {
+            name: name.trim().to_string(),
+            chainage,
+            channel_length: channel.channel_length,
+            still_water_level: channel.still_water_level,
+            cross_section: channel.cross_section.clone(),
+        }
+    }
+
+    /// Check that the transect has a name and a valid profile
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.is_empty() {
+            return Err("Transect needs a name".to_string());
+        }
+        if !(self.channel_length > 0.0 && self.still_water_level > 0.0) {
+            return Err(format!("{}: length and still water depth must be positive", self.name));
+        }
+        if let Some(section) = &self.cross_section {
+            section.validate().map_err(|message| format!("{}: {}", self.name, message))?;
+        }
+        Ok(())
+    }
+
+    /// Scenario of the project with the profile of the transect
+    pub fn scenario(&self, shared: &Project) -> Project {
+        Project {
+            channel_length: self.channel_length,
+            still_water_level: self.still_water_level,
+            cross_section: self.cross_section.clone(),
+            ..shared.clone()
+        }
+    }
+
+    /// Make the transect the profile of the wave channel, to edit or run it there
+    pub fn apply(&self, channel: &mut WaveChannelApp) {
+        channel.channel_length = self.channel_length;
+        channel.still_water_level = self.still_water_level;
+        channel.cross_section = self.cross_section.clone();
+        channel.reset_simulation();
+    }
+}
+
+/// Outputs of the wave channel run over a transect
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransectRun {
+    pub transect: usize,                   // Index of the transect in the project
+    pub outputs: Vec<Result<f64, String>>, // Output values in the order of the study, or why they failed
+}
+
+/// Runs of the wave channel scenario over every transect of the project
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransectStudy {
+    pub outputs: Vec<RunOutput>, // Outputs measured in each run
+    pub threads: usize,          // Transects run in parallel
+}
+
+impl Default for TransectStudy {
+    fn default() -> Self {
+        Self {
+            outputs: vec![RunOutput::RunUp, RunOutput::Overtopping],
+            threads: std::thread::available_parallelism().map_or(1, |threads| threads.get()),
+        }
+    }
+}
+
+impl TransectStudy {
+    /// Check the study against the transects of the scenario
+    /// Outputs a transect cannot produce are reported in its row instead
+    pub fn validate(&self, scenario: &Project) -> Result<(), String> {
+        if self.threads == 0 {
+            return Err("The study needs at least one thread".to_string());
+        }
+        if self.outputs.is_empty() {
+            return Err("Select at least one output".to_string());
+        }
+        if scenario.transects.is_empty() {
+            return Err("Add a transect first".to_string());
+        }
+        scenario.transects.iter().try_for_each(Transect::validate)
+    }
+
+    /// Run the scenario over a transect
+    pub fn transect_run(&self, scenario: &Project, transect: usize) -> TransectRun {
+        let scenario = scenario.transects[transect].scenario(scenario);
+        let mut channel = WaveChannelApp::new();
+        channel.load_project(&scenario);
+        let valid: Vec<Result<(), String>> = self.outputs.iter().map(|output| output.validate(&scenario)).collect();
+        let runnable: Vec<RunOutput> = self.outputs.iter().zip(&valid).filter(|(_, valid)| valid.is_ok()).map(|(&output, _)| output).collect();
+        let mut values = RunOutput::evaluate_all(&runnable, &mut channel).into_iter();
+        let outputs = valid.into_iter().map(|valid| valid.and_then(|_| values.next().unwrap_or(Err("Output not measured".to_string())))).collect();
+        TransectRun { transect, outputs }
+    }
+
+    /// Run the transects of a scenario on the threads of the study, sending each as it completes
+    /// Stops early when cancelled or when the receiver is gone
+    pub fn run(&self, scenario: &Project, cancel: &AtomicBool, sender: &Sender<TransectRun>) {
+        let next = AtomicUsize::new(0);
+        std::thread::scope(|scope| {
+            for _ in 0..self.threads.max(1) {
+                scope.spawn(|| {
+                    while !cancel.load(Ordering::Relaxed) {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        if index >= scenario.transects.len() || sender.send(self.transect_run(scenario, index)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+    }
+
+    /// Run every transect of a scenario, waiting for the results
+    pub fn run_to_completion(&self, scenario: &Project) -> Vec<TransectRun> {
+        let (sender, receiver) = mpsc::channel();
+        self.run(scenario, &AtomicBool::new(false), &sender);
+        drop(sender);
+        let mut runs: Vec<TransectRun> = receiver.try_iter().collect();
+        runs.sort_by_key(|run| run.transect);
+        runs
+    }
+}
+
+/// Manager of the transects of the project, run as a group with their outputs summarized in a table
+pub struct TransectsApp {
+    pub study: TransectStudy,
+    pub runs: Vec<TransectRun>,
+    results_of: Vec<(String, f64)>,       // Names and chainages of the transects the runs were made for
+    outputs_of: Vec<RunOutput>,           // Outputs of the runs, as the study may change after them
+    name: String,                         // Name of the next transect
+    chainage: f64,                        // Chainage of the next transect [m]
+    running: Option<BackgroundRun<TransectRun>>,
+    status: Option<String>,               // Why the last study could not start
+    table: TableView,                     // Sorting and filtering of the results table
+}
+
+impl Default for TransectsApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TransectsApp {
+    pub fn new() -> Self {
+        Self {
+            study: TransectStudy::default(),
+            runs: Vec::new(),
+            results_of: Vec::new(),
+            outputs_of: Vec::new(),
+            name: "T1".to_string(),
+            chainage: 0.0,
+            running: None,
+            status: None,
+            table: TableView::default(),
+        }
+    }
+
+    /// Start the runs of the transects of a scenario on background threads, forgetting previous results
+    pub fn start(&mut self, scenario: Project) -> Result<(), String> {
+        self.study.validate(&scenario)?;
+        self.cancel();
+        self.runs.clear();
+        self.results_of = scenario.transects.iter().map(|transect| (transect.name.clone(), transect.chainage)).collect();
+        self.outputs_of = self.study.outputs.clone();
+        let study = self.study.clone();
+        self.running = Some(BackgroundRun::spawn(move |cancel, sender| study.run(&scenario, cancel, sender)));
+        Ok(())
+    }
+
+    /// Stop the runs, keeping the completed ones
+    pub fn cancel(&mut self) {
+        if let Some(running) = self.running.take() {
+            self.add_runs(running.cancel());
+        }
+    }
+
+    /// Collect the runs completed since the last poll
+    pub fn poll(&mut self) {
+        let Some(running) = &self.running else {
+            return;
+        };
+        let (runs, finished) = running.poll();
+        self.add_runs(runs);
+        if finished {
+            self.running = None;
+        }
+    }
+
+    /// Add completed runs, keeping the order of the transects
+    fn add_runs(&mut self, runs: Vec<TransectRun>) {
+        self.runs.extend(runs);
+        self.runs.sort_by_key(|run| run.transect);
+    }
+
+    /// Whether the transects are running
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// Fraction of the transects completed
+    pub fn progress(&self) -> f64 {
+        self.runs.len() as f64 / self.results_of.len().max(1) as f64
+    }
+
+    /// Show the transects of the project, their runs and the table of their outputs
+    pub fn show(&mut self, ui: &mut egui::Ui, channel: &mut WaveChannelApp) {
+        self.poll();
+        ui.heading("Transects");
+        ui.label("Cross-shore profiles along the coast, each with its own bed and structures, run with the waves, solver and gauges of the wave channel scenario. Capture the profile of the wave channel as a transect, load a transect back to edit it, and run them all to compare their outputs.");
+        ui.separator();
+
+        ui.add_enabled_ui(!self.is_running(), |ui| self.show_transects(ui, channel));
+        ui.separator();
+        ui.add_enabled_ui(!self.is_running(), |ui| self.show_settings(ui, channel));
+        ui.horizontal(|ui| {
+            if self.is_running() {
+                if ui.button("⏹ Cancel").clicked() {
+                    self.cancel();
+                }
+                ui.add(egui::ProgressBar::new(self.progress() as f32).desired_width(200.0).show_percentage());
+                channel.repaint_policy.request_progress(ui.ctx());
+            } else if ui.button("▶ Run All Transects").clicked() {
+                self.status = self.start(channel.project()).err();
+            }
+            ui.label(format!("{} / {} transects", self.runs.len(), channel.transects.len()));
+        });
+        if let Some(status) = &self.status {
+            ui.colored_label(egui::Color32::from_rgb(200, 80, 0), format!("⚠ {}", status));
+        }
+        self.show_results(ui, channel);
+    }
+
+    /// Table of the transects of the project, with the capture of the current profile
+    fn show_transects(&mut self, ui: &mut egui::Ui, channel: &mut WaveChannelApp) {
+        ui.horizontal(|ui| {
+            ui.label("Name:");
+            ui.add(egui::TextEdit::singleline(&mut self.name).desired_width(120.0));
+            ui.label("Chainage:");
+            ui.add(egui::DragValue::new(&mut self.chainage).speed(1.0).suffix(" m"));
+            let duplicate = channel.transects.iter().any(|transect| transect.name == self.name.trim());
+            if ui.add_enabled(!self.name.trim().is_empty() && !duplicate, egui::Button::new("+ Capture Current Profile")).clicked() {
+                channel.transects.push(Transect::from_channel(&self.name, self.chainage, channel));
+                self.name = format!("T{}", channel.transects.len() + 1);
+            }
+        });
+        if channel.transects.is_empty() {
+            ui.label("No transects yet: set up a profile in the wave channel and capture it.");
+            return;
+        }
+
+        let (mut load, mut update, mut removed) = (None, None, None);
+        egui::Grid::new("transect_table").num_columns(7).striped(true).show(ui, |ui| {
+            for header in ["Name", "Chainage", "Length", "Depth", "Profile"] {
+                ui.strong(header);
+            }
+            ui.end_row();
+            for (i, transect) in channel.transects.iter_mut().enumerate() {
+                ui.add(egui::TextEdit::singleline(&mut transect.name).desired_width(100.0));
+                ui.add(egui::DragValue::new(&mut transect.chainage).speed(1.0).suffix(" m"));
+                ui.label(format!("{:.1} m", transect.channel_length));
+                ui.label(format!("{:.2} m", transect.still_water_level));
+                ui.label(match &transect.cross_section {
+                    Some(section) => format!("{} segments, crest {:+.2} m SWL", section.segments.len(), section.crest_elevation() - transect.still_water_level),
+                    None => "Flat".to_string(),
+                });
+                ui.horizontal(|ui| {
+                    if ui.small_button("Load").on_hover_text("Make this transect the profile of the wave channel").clicked() {
+                        load = Some(i);
+                    }
+                    if ui.small_button("Update").on_hover_text("Replace the profile of this transect by that of the wave channel").clicked() {
+                        update = Some(i);
+                    }
+                    if ui.small_button("✖").on_hover_text("Remove transect").clicked() {
+                        removed = Some(i);
+                    }
+                });
+                ui.end_row();
+            }
+        });
+        if let Some(i) = load {
+            channel.transects[i].clone().apply(channel);
+        }
+        if let Some(i) = update {
+            let transect = &channel.transects[i];
+            channel.transects[i] = Transect::from_channel(&transect.name, transect.chainage, channel);
+        }
+        if let Some(i) = removed {
+            channel.transects.remove(i);
+        }
+    }
+
+    /// Outputs of the runs and threads
+    fn show_settings(&mut self, ui: &mut egui::Ui, channel: &WaveChannelApp) {
+        let study = &mut self.study;
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Outputs:");
+            for output in RunOutput::available(channel.gauges.len()) {
+                let mut selected = study.outputs.contains(&output);
+                if ui.checkbox(&mut selected, output.label()).changed() {
+                    if selected {
+                        study.outputs.push(output);
+                    } else {
+                        study.outputs.retain(|&existing| existing != output);
+                    }
+                }
+            }
+            ui.label("Threads:");
+            ui.add(egui::DragValue::new(&mut study.threads).range(1..=256));
+        });
+    }
+
+    /// Outputs of the completed runs, a row per transect
+    pub fn results_table(&self) -> ResultsTable {
+        let outputs = self.outputs_of.iter().map(|output| format!("{} ({})", output.label(), output.unit()));
+        let mut table = ResultsTable::new(["Transect".to_string(), "Chainage (m)".to_string()].into_iter().chain(outputs));
+        for run in &self.runs {
+            let (name, chainage) = &self.results_of[run.transect];
+            let cells = [TableCell::Text(name.clone()), TableCell::Number(*chainage)];
+            table.push(cells.into_iter().chain(run.outputs.iter().map(TableCell::output)).collect());
+        }
+        table
+    }
+
+    /// Outputs of every transect in a table, and the first one along the coast
+    fn show_results(&mut self, ui: &mut egui::Ui, channel: &WaveChannelApp) {
+        if self.runs.is_empty() {
+            return;
+        }
+        self.results_table().show(ui, "transect_results", &mut self.table);
+
+        let Some(output) = self.outputs_of.first() else {
+            return;
+        };
+        let mut values: Vec<[f64; 2]> = self
+            .runs
+            .iter()
+            .filter_map(|run| run.outputs[0].as_ref().ok().map(|&value| [self.results_of[run.transect].1, value]))
+            .collect();
+        values.sort_by(|a, b| a[0].total_cmp(&b[0]));
+        let style = channel.plot_style;
+        let plot = Plot::new("transect_outputs")
+            .height(220.0)
+            .x_axis_label("Chainage (m)")
+            .y_axis_label(format!("{} ({})", output.label(), output.unit()))
+            .legend(Legend::default());
+        style.show_plot(ui, plot, |plot_ui| {
+            plot_ui.line(Line::new(PlotPoints::from(values.clone())).color(style.series(0)).width(style.width(1.5)).name(output.label()));
+            plot_ui.points(Points::new(PlotPoints::from(values)).shape(MarkerShape::Circle).radius(4.0).color(style.series(0)));
+        });
+    }
+}
+
+impl Drop for TransectsApp {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
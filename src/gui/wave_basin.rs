@@ -0,0 +1,1718 @@
+use super::basin_compute::BasinCompute;
+use super::plot_style::{Colormap, PlotStyle};
+use super::repaint::RepaintPolicy;
+use super::timeline::Timeline;
+use crate::analysis::spectral::{peak_frequency, significant_wave_height};
+use crate::analysis::{BerthAgitation, SnapshotStore, Transect, WaveGauge, WaveHeightField, berth_agitation, find_crests};
+use crate::solver::{
+    BasinField, BasinFrame, BasinSolver, BasinSponges, Berth, DepthRaster, GeoReference, Grid2D, HarborEntrance, HarborLayout, HarborStructure, Obstacle, RasterOptions, SurveyUnit, hillshade,
+};
+use crate::units::{Length, Time};
+use crate::waves::wavemaker::linear_wave_number;
+use crate::waves::{BreakwaterDiffraction, DEFAULT_SEED, DirectionalSpectrum, DirectionalSpreading, DispersionSolver, JONSWAP_GAMMA, SeededRng, WaveForcing, WaveParameters, WaveSource, WaveSpectrum, seed_from_clock};
+use eframe::egui;
+use egui_plot::{Arrows, Bar, BarChart, Legend, Line, LineStyle, MarkerShape, Plot, PlotImage, PlotPoint, PlotPoints, Points, Polygon, Text};
+use std::borrow::Cow;
+
+/// Simulated time advanced per frame while running [s]
+const ANIMATION_STEP: f64 = 0.05;
+
+/// Gravitational acceleration [m/s²]
+const GRAVITY: f64 = 9.81;
+
+/// Velocity arrows drawn along the length of the basin
+const QUIVER_COLUMNS: usize = 30;
+
+/// Frequencies of the irregular wavemaker waves
+const SPECTRUM_COMPONENTS: usize = 48;
+
+/// Sectors of the circle the energy of a directional sea is spread over, 15° each
+const SPREADING_SECTORS: usize = 24;
+
+/// The agitation map spans wave heights from 0 to this many times the offshore wave height
+const AGITATION_RANGE: f64 = 2.0;
+
+/// Most cells along the basin when an imported bathymetry sets the grid spacing
+const BATHYMETRY_CELLS: f64 = 400.0;
+
+/// Wave gauge at a point of the basin, recording the surface elevation like those of the channel
+/// The position of the record is the distance from the wavemaker (x)
+#[derive(Debug, Clone, PartialEq)]
+pub struct BasinGauge {
+    pub y: f64,            // Distance from the south wall [m]
+    pub record: WaveGauge, // Surface elevation record at x = record.position()
+}
+
+impl BasinGauge {
+    /// Create a gauge at point (x, y) with an empty record
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { y, record: WaveGauge::new(x) }
+    }
+}
+
+/// Rectangular wave basin in plan view: regular or irregular waves from a wavemaker along the west edge,
+/// at an angle or spread over the directions, sponge layers, obstacles, a harbor layout and a top-down
+/// color map of the surface elevation
+pub struct WaveBasinApp {
+    pub basin_length: f64,               // Basin length, away from the wavemaker (x) [m]
+    pub basin_width: f64,                // Basin width, along the wavemaker (y) [m]
+    pub water_depth: f64,                // Still water depth (d) [m]
+    pub grid_spacing: f64,               // Size of the square cells [m]
+    pub wave_height: f64,                // Wave height (H) [m]
+    pub wave_period: f64,                // Wave period (T) [s]
+    pub wave_direction: f64,             // Direction of the waves from the x axis, anticlockwise [rad]
+    pub number_of_waves: usize,          // Waves generated before the wavemaker stops
+    pub spectrum: Option<WaveSpectrum>,  // Irregular waves replacing the regular waves, None for regular waves
+    pub spreading: Option<f64>,          // Exponent s of the cos-2s spreading of the spectrum about the wave direction, None for long-crested waves
+    pub random_seed: u64,                // Seed of the random phases of the irregular waves
+    pub sponges: Option<BasinSponges>,   // Absorbing edges, None for reflecting walls all around
+    pub obstacles: Vec<Obstacle>,        // Breakwaters, piles and other solid obstacles
+    pub harbor: HarborLayout,            // Partially reflecting structures, entrances and berths of a harbor
+    pub bathymetry: Option<DepthRaster>, // Imported bathymetry giving the depth of each cell, None for a uniform depth
+    pub bathymetry_path: String,         // Raster file imported as the bathymetry
+    pub bathymetry_options: RasterOptions, // Units, datum and placement of the basin on the imported raster
+    pub georeference: Option<GeoReference>, // Projected coordinates of the basin, None for local coordinates only
+    pub world_axes: bool,                // Label the plan view in projected coordinates
+    pub export_path: String,             // CSV file the fields of the displayed frame are exported to
+    pub nonlinear: bool,                 // Solve the nonlinear shallow water equations
+    pub active_absorption: bool,         // Absorb the waves reflected back to the wavemaker
+    pub gauges: Vec<BasinGauge>,         // Surface elevation records at points of the basin
+    pub simulation_running: bool,
+    pub field: BasinField,               // Field of the color map
+    pub show_quivers: bool,              // Velocity arrows over the color map
+    pub show_coastline: bool,            // Mask the obstacles and outline their coastline
+    pub transect: Option<Transect>,      // Line the fields are extracted along, None for no transect
+    pub snapshot_interval: f64,          // Interval between stored frames [s]
+    pub show_diffraction: bool,          // Compare the wave heights behind a breakwater with the diffraction theory
+    pub show_agitation: bool,            // Color the plan view by the wave height relative to the offshore waves
+    pub background_compute: bool,        // Step the solver on its own thread while the run plays
+    solver: Option<BasinSolver>,         // Solution of the current run, None before it starts; only its geometry while computing in the background
+    compute: Option<BasinCompute>,       // Thread stepping the solution of the playing run, None when it steps with the frames
+    forcing: WaveForcing,                // Wavemaker signal of the current run
+    status: Option<String>,              // Why the run could not start
+    snapshots: SnapshotStore,            // Frames of the current run for playback, η, u and v per cell
+    height_field: WaveHeightField,       // Wave heights over the cells once the waves have settled
+    timeline: Timeline,                  // Playback position over the stored frames
+    reset_view: bool,                    // Zoom the plan view back onto the whole basin at the next frame
+    texture: Option<egui::TextureHandle>, // Color map of the displayed field
+    drawing: Option<usize>,              // Harbor structure whose corners are added by clicking the layout, None when not drawing
+    bathymetry_status: Option<String>,   // Outcome of the last bathymetry import
+    bathymetry_texture: Option<egui::TextureHandle>, // Shaded relief of the imported bathymetry, None to redraw it
+    export_status: Option<String>,       // Outcome of the last export of the fields
+}
+
+impl Default for WaveBasinApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WaveBasinApp {
+    pub fn new() -> Self {
+        Self {
+            basin_length: 30.0,
+            basin_width: 20.0,
+            water_depth: 0.5,
+            grid_spacing: 0.2,
+            wave_height: 0.05,
+            wave_period: 2.0,
+            wave_direction: 0.0,
+            number_of_waves: 20,
+            spectrum: None,
+            spreading: None,
+            random_seed: DEFAULT_SEED,
+            sponges: Some(BasinSponges::default()),
+            obstacles: vec![Obstacle::Rectangle { x_min: 10.0, x_max: 11.0, y_min: 0.0, y_max: 10.0 }],
+            harbor: HarborLayout::default(),
+            bathymetry: None,
+            bathymetry_path: "bathymetry.asc".to_string(),
+            bathymetry_options: RasterOptions::default(),
+            georeference: None,
+            world_axes: false,
+            export_path: "basin_fields.csv".to_string(),
+            nonlinear: false,
+            active_absorption: false,
+            gauges: vec![BasinGauge::new(15.0, 5.0), BasinGauge::new(15.0, 15.0)],
+            simulation_running: false,
+            field: BasinField::SurfaceElevation,
+            show_quivers: false,
+            show_coastline: true,
+            transect: Some(Transect { start: [0.0, 15.0], end: [30.0, 15.0] }),
+            snapshot_interval: 0.25,
+            show_diffraction: false,
+            show_agitation: false,
+            background_compute: true,
+            solver: None,
+            compute: None,
+            forcing: WaveForcing::new(),
+            status: None,
+            // Frames hold three fields per cell, so fewer are kept than along the channel
+            snapshots: SnapshotStore::new(5.0, 0.5, 120),
+            height_field: WaveHeightField::new(0, 0.0, 0.0),
+            timeline: Timeline::new(),
+            reset_view: false,
+            texture: None,
+            drawing: None,
+            bathymetry_status: None,
+            bathymetry_texture: None,
+            export_status: None,
+        }
+    }
+
+    /// Diffraction behind a semi-infinite breakwater: a thin breakwater from the south wall halfway across
+    /// the basin, long-crested regular waves of small steepness, sponge layers on the other edges and an absorbing wavemaker,
+    /// with gauges and a transect across the lee to compare with the theory
+    pub fn load_diffraction_preset(&mut self) {
+        self.reset_simulation();
+        self.basin_length = 30.0;
+        self.basin_width = 30.0;
+        self.water_depth = 0.5;
+        self.grid_spacing = 0.2;
+        self.wave_height = 0.02;
+        self.wave_period = 2.0;
+        self.wave_direction = 0.0;
+        self.number_of_waves = 20;
+        self.spectrum = None;
+        self.spreading = None;
+        self.sponges = Some(BasinSponges { width: 4.0, strength: 5.0, east: true, north: false, south: true });
+        self.obstacles = vec![Obstacle::Rectangle { x_min: 8.0, x_max: 8.4, y_min: 0.0, y_max: 15.0 }];
+        self.harbor = HarborLayout::default();
+        self.bathymetry = None;
+        self.georeference = None;
+        self.nonlinear = false;
+        // The theory knows no wavemaker to reflect the waves from the breakwater back again
+        self.active_absorption = true;
+        self.gauges = vec![BasinGauge::new(18.0, 10.0), BasinGauge::new(18.0, 15.0), BasinGauge::new(18.0, 20.0)];
+        self.field = BasinField::SurfaceElevation;
+        self.transect = Some(Transect { start: [18.0, 4.0], end: [18.0, 26.0] });
+        self.show_diffraction = true;
+        self.show_agitation = false;
+    }
+
+    /// Agitation in a harbor behind two rubble mound breakwaters: irregular waves through the entrance
+    /// between them, vertical quay walls and a pier inside, and three berths to report the wave heights at
+    pub fn load_harbor_preset(&mut self) {
+        self.reset_simulation();
+        self.basin_length = 50.0;
+        self.basin_width = 40.0;
+        self.water_depth = 0.5;
+        self.grid_spacing = 0.25;
+        self.wave_height = 0.03;
+        self.wave_period = 2.0;
+        self.wave_direction = 0.0;
+        self.number_of_waves = 40;
+        self.spectrum = WaveSpectrum::jonswap(std::f64::consts::SQRT_2 * self.wave_height, self.wave_period, JONSWAP_GAMMA).ok();
+        self.spreading = None;
+        // Walls along the waves reflect them unchanged; the wavemaker absorbs those reflected by the harbor
+        self.sponges = None;
+        self.active_absorption = true;
+        self.obstacles = Vec::new();
+        self.bathymetry = None;
+        self.georeference = None;
+        self.harbor = HarborLayout {
+            structures: vec![
+                HarborStructure::new("South Breakwater", vec![[25.0, 0.0], [28.0, 0.0], [28.0, 24.0], [25.0, 24.0]], 0.4),
+                HarborStructure::new("North Breakwater", vec![[25.0, 30.0], [28.0, 30.0], [28.0, 40.0], [25.0, 40.0]], 0.4),
+                HarborStructure::new("Pier", vec![[36.0, 0.0], [38.0, 0.0], [38.0, 14.0], [36.0, 14.0]], 0.9),
+                HarborStructure::new("Quay", vec![[45.0, 0.0], [50.0, 0.0], [50.0, 40.0], [45.0, 40.0]], 0.9),
+            ],
+            entrances: vec![HarborEntrance { name: "Entrance".to_string(), start: [26.5, 24.0], end: [26.5, 30.0] }],
+            berths: vec![
+                Berth { name: "Pier West".to_string(), start: [35.5, 2.0], end: [35.5, 12.0], limit: 0.015 },
+                Berth { name: "Quay South".to_string(), start: [44.5, 4.0], end: [44.5, 14.0], limit: 0.015 },
+                Berth { name: "Quay North".to_string(), start: [44.5, 26.0], end: [44.5, 36.0], limit: 0.015 },
+            ],
+        };
+        self.nonlinear = false;
+        self.gauges = vec![BasinGauge::new(15.0, 20.0), BasinGauge::new(40.0, 20.0)];
+        self.field = BasinField::SurfaceElevation;
+        self.transect = Some(Transect { start: [10.0, 27.0], end: [44.0, 27.0] });
+        self.show_diffraction = false;
+        self.show_agitation = true;
+    }
+
+    /// Number of cells along the length and the width of the basin
+    pub fn grid_size(&self) -> (usize, usize) {
+        let cells = |extent: f64| (extent / self.grid_spacing).round().max(2.0) as usize;
+        (cells(self.basin_length), cells(self.basin_width))
+    }
+
+    /// Solver of the basin as configured, at rest
+    pub fn build_solver(&self) -> Result<BasinSolver, String> {
+        if !(self.grid_spacing.is_finite() && self.grid_spacing > 0.0) {
+            return Err("Grid spacing must be positive".to_string());
+        }
+        self.harbor.validate()?;
+        let (nx, ny) = self.grid_size();
+        let mut solver = BasinSolver::new(self.basin_length, self.basin_width, nx, ny, self.water_depth)?;
+        if let Some(depths) = self.cell_depths()? {
+            solver = solver.with_depths(depths)?;
+        }
+        solver
+            .with_sponges(self.sponges)?
+            .with_obstacles(self.obstacles.clone())?
+            .with_structures(self.harbor.structures.clone())
+            .map(|solver| solver.with_nonlinear(self.nonlinear).with_active_absorption(self.active_absorption))
+    }
+
+    /// Still water depth of the cells from the imported bathymetry, None for the uniform water depth [m]
+    pub fn cell_depths(&self) -> Result<Option<Vec<f64>>, String> {
+        let Some(raster) = &self.bathymetry else {
+            return Ok(None);
+        };
+        let (nx, ny) = self.grid_size();
+        let grid = Grid2D::new(self.basin_length, self.basin_width, nx, ny)?;
+        raster.depths(&grid, &self.bathymetry_options).map(Some)
+    }
+
+    /// Mean still water depth in front of the wavemaker over the imported bathymetry [m]
+    pub fn wavemaker_depth(&self) -> Result<f64, String> {
+        let Some(depths) = self.cell_depths()? else {
+            return Ok(self.water_depth);
+        };
+        let (nx, _) = self.grid_size();
+        let wet: Vec<f64> = depths.chunks(nx).map(|row| row[0]).filter(|&depth| depth > 0.0).collect();
+        if wet.is_empty() {
+            return Err("The wavemaker stands on land: place the west edge of the basin under water".to_string());
+        }
+        Ok(wet.iter().sum::<f64>() / wet.len() as f64)
+    }
+
+    /// Read the raster file and place the basin over its whole extent, returning a summary
+    /// The grid spacing follows the raster cells, coarsened to keep the grid to a few hundred cells along the basin,
+    /// and the wavemaker generates the waves for the mean depth in front of it. The basin takes the coordinates
+    /// of the raster, in its CRS or the CRS entered before when the file gives none
+    pub fn import_bathymetry(&mut self) -> Result<String, String> {
+        let raster = DepthRaster::load(std::path::Path::new(&self.bathymetry_path))?;
+        let [x_min, y_min, x_max, y_max] = raster.extent();
+        let scale = self.bathymetry_options.horizontal_unit.to_meters();
+        let previous = (self.bathymetry.take(), self.bathymetry_options.origin, self.basin_length, self.basin_width, self.grid_spacing);
+        self.bathymetry_options.origin = [x_min, y_min];
+        self.basin_length = (x_max - x_min) * scale;
+        self.basin_width = (y_max - y_min) * scale;
+        self.grid_spacing = (raster.cell_size * scale).max(self.basin_length.max(self.basin_width) / BATHYMETRY_CELLS);
+        let message = format!(
+            "Imported {} × {} cells of {} {}: {:.1} × {:.1} m",
+            raster.ncols, raster.nrows, raster.cell_size, self.bathymetry_options.horizontal_unit, self.basin_length, self.basin_width
+        );
+        self.bathymetry = Some(raster);
+        match self.wavemaker_depth() {
+            Ok(depth) => {
+                self.water_depth = depth;
+                let crs = self.bathymetry.as_ref().and_then(|raster| raster.crs.clone());
+                let crs = crs.or_else(|| self.georeference.as_ref().map(|reference| reference.crs.clone())).unwrap_or_default();
+                self.georeference = Some(GeoReference { crs, unit: self.bathymetry_options.horizontal_unit, origin: self.bathymetry_options.origin });
+                self.bathymetry_texture = None;
+                self.reset_view = true;
+                Ok(format!("{}, {:.2} m deep at the wavemaker", message, depth))
+            }
+            Err(message) => {
+                (self.bathymetry, self.bathymetry_options.origin, self.basin_length, self.basin_width, self.grid_spacing) = previous;
+                Err(message)
+            }
+        }
+    }
+
+    /// Fields of the displayed frame at the wet cells as CSV: local and projected coordinates, η, u, v and,
+    /// once recorded, the wave height relative to the offshore waves
+    /// Comment lines give the time of the frame and the geographic reference of the basin
+    pub fn fields_csv(&self) -> Result<String, String> {
+        let (Some(solver), Some((time, values))) = (&self.solver, self.displayed_frame()) else {
+            return Err("Run the basin before exporting its fields".to_string());
+        };
+        let grid = solver.grid();
+        let frame = BasinFrame::new(grid, &values).ok_or("The displayed frame does not cover the basin")?;
+        let coefficients = self.diffraction_coefficients();
+        let mut csv = format!("# Wave basin fields at t = {:.3} s\n", time);
+        let mut header = vec!["x (m)", "y (m)"];
+        if let Some(reference) = &self.georeference {
+            reference.validate()?;
+            csv.push_str(&format!("# CRS: {}\n# Origin: easting {} {}, northing {} {}\n", reference.name(), reference.origin[0], reference.unit, reference.origin[1], reference.unit));
+            if let Some(code) = reference.epsg() {
+                csv.push_str(&format!("# EPSG: {}\n", code));
+            }
+            header.extend(["Easting", "Northing"]);
+        }
+        header.extend(["eta (m)", "u (m/s)", "v (m/s)"]);
+        if coefficients.is_some() {
+            header.push("H/H0");
+        }
+        csv.push_str(&header.join(","));
+        csv.push('\n');
+        for j in 0..grid.ny() {
+            for i in 0..grid.nx() {
+                if solver.is_solid(i, j) {
+                    continue;
+                }
+                let (x, y) = grid.center(i, j);
+                let n = grid.index(i, j);
+                let mut row = vec![format!("{:.3}", x), format!("{:.3}", y)];
+                if let Some(reference) = &self.georeference {
+                    let [easting, northing] = reference.to_world(x, y);
+                    row.extend([format!("{:.3}", easting), format!("{:.3}", northing)]);
+                }
+                row.extend([format!("{:.6}", frame.eta()[n]), format!("{:.6}", frame.u()[n]), format!("{:.6}", frame.v()[n])]);
+                if let Some(coefficients) = &coefficients {
+                    row.push(format!("{:.4}", coefficients[n]));
+                }
+                csv.push_str(&row.join(","));
+                csv.push('\n');
+            }
+        }
+        Ok(csv)
+    }
+
+    /// Write the fields of the displayed frame to the export file, returning the number of cells written
+    pub fn export_fields(&self) -> Result<usize, String> {
+        let csv = self.fields_csv()?;
+        std::fs::write(&self.export_path, &csv).map_err(|e| format!("Cannot write {}: {}", self.export_path, e))?;
+        Ok(csv.lines().filter(|line| !line.starts_with('#')).count() - 1)
+    }
+
+    /// Waves of the wavemaker, generated for the number of waves (of peak periods for irregular waves)
+    /// Irregular waves are synthesized from regular components of random phases, spread over sectors of
+    /// directions with a spreading; the components travelling back towards the wavemaker are left out
+    pub fn wave_forcing(&self) -> Result<WaveForcing, String> {
+        let mut forcing = WaveForcing::new();
+        let Some(spectrum) = &self.spectrum else {
+            let params = DispersionSolver::new()
+                .solve_wave_parameters(Length::meters(self.wave_height), Time::seconds(self.wave_period), Length::meters(self.water_depth))
+                .map_err(|e| e.to_string())?;
+            forcing.add_source(
+                WaveSource::boundary(params)
+                    .with_direction(self.wave_direction)
+                    .with_generation_duration(self.number_of_waves as f64 * self.wave_period),
+            );
+            return Ok(forcing);
+        };
+
+        let duration = self.number_of_waves as f64 * spectrum.peak_period();
+        let rng = SeededRng::new(self.random_seed);
+        let components: Vec<(f64, f64, f64, f64)> = match self.spreading {
+            Some(exponent) => DirectionalSpectrum::new(spectrum.clone(), DirectionalSpreading::new(self.wave_direction, exponent)?)
+                .components(SPECTRUM_COMPONENTS, SPREADING_SECTORS, &rng)
+                .into_iter()
+                .map(|component| (component.frequency, component.direction, component.amplitude, component.phase))
+                .collect(),
+            None => spectrum
+                .components(SPECTRUM_COMPONENTS, &rng)
+                .into_iter()
+                .map(|component| (component.frequency, self.wave_direction, component.amplitude, component.phase))
+                .collect(),
+        };
+        for (frequency, direction, amplitude, phase) in components {
+            if amplitude <= 0.0 || direction.cos() <= 0.0 {
+                continue;
+            }
+            let params = self.linear_wave_parameters(2.0 * amplitude, 1.0 / frequency)?;
+            forcing.add_source(WaveSource::boundary(params).with_direction(direction).with_phase(phase).with_generation_duration(duration));
+        }
+        Ok(forcing)
+    }
+
+    /// Linear wave of a height and period in the still water depth, without the breaking check of the regular waves
+    fn linear_wave_parameters(&self, wave_height: f64, wave_period: f64) -> Result<WaveParameters, String> {
+        let omega = 2.0 * std::f64::consts::PI / wave_period;
+        let k = linear_wave_number(omega, self.water_depth, GRAVITY)?;
+        Ok(WaveParameters {
+            k,
+            omega,
+            c: omega / k,
+            h: wave_height,
+            d: self.water_depth,
+            period: wave_period,
+            wavelength: 2.0 * std::f64::consts::PI / k,
+        })
+    }
+
+    /// Height of the generated waves: H of the regular waves, Hm0 of the irregular ones [m]
+    pub fn generated_wave_height(&self) -> f64 {
+        self.spectrum.as_ref().map_or(self.wave_height, WaveSpectrum::significant_wave_height)
+    }
+
+    /// Period of the generated waves: T of the regular waves, Tp of the irregular ones [s]
+    pub fn generated_wave_period(&self) -> f64 {
+        self.spectrum.as_ref().map_or(self.wave_period, WaveSpectrum::peak_period)
+    }
+
+    /// Duration of a run: the waves generated, then the time for the last one to cross the basin twice [s]
+    pub fn total_simulation_time(&self) -> f64 {
+        self.number_of_waves as f64 * self.generated_wave_period() + 2.0 * self.basin_length / (GRAVITY * self.water_depth).sqrt()
+    }
+
+    /// Current simulation time [s]
+    pub fn simulation_time(&self) -> f64 {
+        match &self.compute {
+            Some(compute) => compute.current().time,
+            None => self.solver.as_ref().map_or(0.0, BasinSolver::time),
+        }
+    }
+
+    /// Solution of the current run, None before it starts
+    /// While computing in the background its fields are those of the time the thread took it over
+    pub fn solver(&self) -> Option<&BasinSolver> {
+        self.solver.as_ref()
+    }
+
+    /// Start a run from still water, or resume the current one
+    pub fn start_simulation(&mut self) -> Result<(), String> {
+        if self.solver.is_none() {
+            let solver = self.build_solver()?;
+            self.forcing = self.wave_forcing()?;
+            self.gauges.iter_mut().for_each(|gauge| gauge.record.clear());
+            self.snapshots.clear();
+            self.snapshots.record(0.0, &solver.snapshot());
+            let (start, end) = self.height_window();
+            self.height_field = WaveHeightField::new(solver.grid().cells(), start, end);
+            self.solver = Some(solver);
+        }
+        self.timeline.follow_live();
+        self.simulation_running = true;
+        Ok(())
+    }
+
+    /// Discard the current run and its records
+    pub fn reset_simulation(&mut self) {
+        self.compute = None;
+        self.solver = None;
+        self.simulation_running = false;
+        self.gauges.iter_mut().for_each(|gauge| gauge.record.clear());
+        self.snapshots.clear();
+        self.height_field.clear();
+        self.timeline = Timeline::new();
+    }
+
+    /// Advance the run by a duration and record the gauges, stopping at its end
+    pub fn advance_simulation(&mut self, duration: f64) {
+        let end = self.total_simulation_time();
+        let Some(solver) = &mut self.solver else {
+            return;
+        };
+        if !self.simulation_running {
+            return;
+        }
+        solver.advance(duration, &mut self.forcing);
+        for gauge in &mut self.gauges {
+            gauge.record.record(solver.time(), solver.surface_elevation_at(gauge.record.position(), gauge.y));
+        }
+        self.height_field.record(solver.time(), solver.eta());
+        if self.snapshots.end_time().is_none_or(|last| solver.time() - last >= self.snapshot_interval - 1e-9) {
+            self.snapshots.record(solver.time(), &solver.snapshot());
+        }
+        if solver.time() >= end {
+            self.simulation_running = false;
+        }
+    }
+
+    /// Move the target of the compute thread a duration past its latest state, and record that state
+    /// The thread takes over the solver at the first call and steps it while the frames are drawn
+    pub fn advance_in_background(&mut self, duration: f64) {
+        let end = self.total_simulation_time();
+        if !self.simulation_running || self.solver.is_none() {
+            return;
+        }
+        if self.compute.is_none() {
+            // The interface keeps a copy of the solver at rest for the geometry of the plan view
+            let Ok(geometry) = self.build_solver() else {
+                return self.advance_simulation(duration);
+            };
+            let solver = self.solver.replace(geometry).expect("Checked above");
+            self.compute = Some(BasinCompute::spawn(solver, std::mem::take(&mut self.forcing)));
+        }
+        let (Some(compute), Some(solver)) = (&mut self.compute, &self.solver) else {
+            return;
+        };
+        let fresh = compute.update();
+        let state = compute.current();
+        if fresh && let Some(frame) = BasinFrame::new(solver.grid(), &state.values) {
+            for gauge in &mut self.gauges {
+                gauge.record.record(state.time, frame.value_at(BasinField::SurfaceElevation, gauge.record.position(), gauge.y));
+            }
+            self.height_field.record(state.time, frame.eta());
+            if self.snapshots.end_time().is_none_or(|last| state.time - last >= self.snapshot_interval - 1e-9) {
+                self.snapshots.record(state.time, &state.values);
+            }
+        }
+        if state.time >= end - 1e-9 {
+            self.simulation_running = false;
+            self.stop_background();
+        } else {
+            compute.advance_to((state.time + duration).min(end));
+        }
+    }
+
+    /// Take the solver back from the compute thread once the run stops playing, at the state it reached
+    pub fn stop_background(&mut self) {
+        if let Some((solver, forcing)) = self.compute.take().and_then(BasinCompute::finish) {
+            self.solver = Some(solver);
+            self.forcing = forcing;
+        }
+    }
+
+    /// Stored frames of the current run
+    pub fn snapshots(&self) -> &SnapshotStore {
+        &self.snapshots
+    }
+
+    /// Show the stored frame nearest to a time, pausing the run
+    pub fn scrub_to(&mut self, time: f64) {
+        self.timeline.scrub_to(&self.snapshots, time);
+        if self.timeline.cursor().is_some() {
+            self.simulation_running = false;
+            self.stop_background();
+        }
+    }
+
+    /// Return from playback to the current state of the run
+    pub fn follow_live(&mut self) {
+        self.timeline.follow_live();
+    }
+
+    /// Time and fields of the frame shown: the stored frame at the playback cursor, or the current state
+    pub fn displayed_frame(&self) -> Option<(f64, Cow<'_, [f64]>)> {
+        let solver = self.solver.as_ref()?;
+        match self.timeline.displayed_snapshot(&self.snapshots) {
+            Some(snapshot) => Some((snapshot.time, Cow::Borrowed(snapshot.surface_elevation.as_slice()))),
+            None => match &self.compute {
+                Some(compute) => Some((compute.current().time, Cow::Borrowed(compute.current().values.as_slice()))),
+                None => Some((solver.time(), Cow::Owned(solver.snapshot()))),
+            },
+        }
+    }
+
+    /// Distances along the transect and the displayed field sampled there, None without a run or a transect
+    pub fn transect_profile(&self) -> Option<(Vec<f64>, Vec<f64>)> {
+        let (solver, transect) = (self.solver.as_ref()?, self.transect.filter(|transect| transect.length() > 0.0)?);
+        let (_, values) = self.displayed_frame()?;
+        let frame = BasinFrame::new(solver.grid(), &values)?;
+        let samples = transect.samples(solver.grid());
+        Some((transect.distances(samples), transect.profile(&frame, self.field, samples)))
+    }
+
+    /// Surface elevation records along the transect from the stored frames, one gauge per sample
+    pub fn transect_gauges(&self) -> Vec<WaveGauge> {
+        match (&self.solver, self.transect) {
+            (Some(solver), Some(transect)) if transect.length() > 0.0 => transect.gauges(solver.grid(), &self.snapshots, transect.samples(solver.grid())),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Window the wave heights are recorded over: from the arrival of the waves at the far corner of the
+    /// basin, one period after the time to cross its diagonal, to the end of the generation [s]
+    pub fn height_window(&self) -> (f64, f64) {
+        let crossing = self.basin_length.hypot(self.basin_width) / (GRAVITY * self.water_depth).sqrt();
+        let period = self.generated_wave_period();
+        (crossing + period, self.number_of_waves as f64 * period)
+    }
+
+    /// Wave heights recorded over the cells
+    pub fn height_field(&self) -> &WaveHeightField {
+        &self.height_field
+    }
+
+    /// Wave heights over the cells, H of the regular waves or Hm0 of the irregular ones,
+    /// None before the waves have been recorded for a period [m]
+    pub fn wave_heights(&self) -> Option<Vec<f64>> {
+        let recorded = self.simulation_time().min(self.height_field.end_time()) - self.height_field.start_time();
+        if recorded < self.generated_wave_period() {
+            return None;
+        }
+        match self.spectrum {
+            Some(_) => self.height_field.significant_heights(),
+            None => self.height_field.regular_heights(),
+        }
+    }
+
+    /// Measured diffraction coefficients over the cells, the ratio of the wave height to that generated,
+    /// which is also the agitation coefficient of a harbor; None before the waves have been recorded for a period
+    pub fn diffraction_coefficients(&self) -> Option<Vec<f64>> {
+        let offshore = self.generated_wave_height();
+        self.wave_heights().map(|heights| heights.into_iter().map(|height| height / offshore).collect())
+    }
+
+    /// Wave heights along the berths of the harbor, empty before the waves have been recorded for a period
+    pub fn berth_agitation(&self) -> Vec<BerthAgitation> {
+        match (&self.solver, self.wave_heights()) {
+            (Some(solver), Some(heights)) => berth_agitation(solver, &heights, &self.harbor.berths, self.generated_wave_height()),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Theory of diffraction around the tip of the first rectangular obstacle joined to the south or north wall,
+    /// for the waves at the peak period travelling in the wave direction
+    /// The wave number is that of the long waves the basin solves, k = ω/√(gd), to validate the model
+    pub fn breakwater_diffraction(&self) -> Option<BreakwaterDiffraction> {
+        let (tip, orientation) = self.obstacles.iter().find_map(|obstacle| match *obstacle {
+            Obstacle::Rectangle { x_min, x_max, y_min, y_max } if y_min <= 0.0 && y_max < self.basin_width => Some(([0.5 * (x_min + x_max), y_max], -std::f64::consts::FRAC_PI_2)),
+            Obstacle::Rectangle { x_min, x_max, y_min, y_max } if y_max >= self.basin_width && y_min > 0.0 => Some(([0.5 * (x_min + x_max), y_min], std::f64::consts::FRAC_PI_2)),
+            _ => None,
+        })?;
+        let omega = 2.0 * std::f64::consts::PI / self.generated_wave_period();
+        BreakwaterDiffraction::new(tip, orientation, omega / (GRAVITY * self.water_depth).sqrt(), self.wave_direction).ok()
+    }
+
+    /// Distances along the transect with the measured and theoretical diffraction coefficients there
+    pub fn transect_diffraction(&self) -> Option<(Vec<f64>, Vec<f64>, Vec<f64>)> {
+        let (solver, transect) = (self.solver.as_ref()?, self.transect.filter(|transect| transect.length() > 0.0)?);
+        let (theory, measured) = (self.breakwater_diffraction()?, self.diffraction_coefficients()?);
+        let distances = transect.distances(transect.samples(solver.grid()));
+        let (mut measured_profile, mut theory_profile) = (Vec::with_capacity(distances.len()), Vec::with_capacity(distances.len()));
+        for &distance in &distances {
+            let [x, y] = transect.point(distance);
+            measured_profile.push(solver.grid().interpolate_centers(&measured, x, y));
+            theory_profile.push(theory.coefficient(x, y));
+        }
+        Some((distances, measured_profile, theory_profile))
+    }
+
+    /// Colors of the displayed field span this range: ±H for η, and up to the velocity under a crest twice as high for |u|
+    fn color_range(&self) -> f64 {
+        let range = match self.field {
+            BasinField::SurfaceElevation => self.generated_wave_height(),
+            BasinField::Speed => self.generated_wave_height() * (GRAVITY / self.water_depth).sqrt(),
+        };
+        range.max(1e-6)
+    }
+
+    /// Show the basin setup, the plan view of the surface and the gauge records
+    pub fn show(&mut self, ui: &mut egui::Ui, style: PlotStyle, repaint_policy: &RepaintPolicy) {
+        ui.heading("Wave Basin");
+        ui.label("Regular or irregular waves from a wavemaker along the west edge, at an angle or spread over the directions, travel over a rectangular basin, diffracting around obstacles, reflected by harbor structures and absorbed by sponge layers. The depth-averaged shallow water equations are solved on a 2D grid.");
+        ui.separator();
+
+        ui.add_enabled_ui(self.solver.is_none(), |ui| {
+            self.show_settings(ui);
+            self.show_harbor_editor(ui, style);
+            self.show_bathymetry_import(ui, style);
+            self.show_georeference(ui);
+        });
+
+        ui.horizontal(|ui| {
+            if self.simulation_running {
+                if ui.button("⏸ Pause").clicked() {
+                    self.simulation_running = false;
+                }
+            } else if self.simulation_time() < self.total_simulation_time() && ui.button("▶ Run").clicked() {
+                self.status = self.start_simulation().err();
+            }
+            if self.solver.is_some() && ui.button("⏹ Reset").clicked() {
+                self.reset_simulation();
+            }
+            let progress = (self.simulation_time() / self.total_simulation_time()).min(1.0);
+            ui.label(format!("Time: {:.1}s ({:.0}%)", self.simulation_time(), progress * 100.0));
+        });
+        if let Some(status) = &self.status {
+            ui.colored_label(egui::Color32::from_rgb(200, 80, 0), format!("⚠ {}", status));
+        }
+
+        if self.simulation_running {
+            if self.background_compute {
+                self.advance_in_background(ANIMATION_STEP);
+            } else {
+                self.advance_simulation(ANIMATION_STEP);
+            }
+            repaint_policy.request_frame(ui.ctx());
+        } else {
+            self.stop_background();
+        }
+        if self.solver.is_none() {
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("wave_basin_field").selected_text(self.field.to_string()).show_ui(ui, |ui| {
+                for field in [BasinField::SurfaceElevation, BasinField::Speed] {
+                    ui.selectable_value(&mut self.field, field, field.to_string());
+                }
+            });
+            ui.checkbox(&mut self.show_quivers, "Velocity Arrows");
+            ui.checkbox(&mut self.show_coastline, "Coastline Mask");
+            ui.checkbox(&mut self.show_agitation, "Agitation Map")
+                .on_hover_text("Color the plan view by the wave height relative to the offshore waves, H/H₀, recorded from the time the waves have settled over the basin until the wavemaker stops.");
+            ui.add_enabled(self.georeference.is_some(), egui::Checkbox::new(&mut self.world_axes, "World Coordinates"))
+                .on_hover_text("Label the axes in the projected coordinates of the basin, for figures of the site");
+            if ui.button("⟲ Reset View").clicked() {
+                self.reset_view = true;
+            }
+        })
+        .response
+        .on_hover_text("Drag to pan and scroll or box-zoom to zoom; the view keeps the aspect of the basin.");
+        ui.horizontal(|ui| {
+            ui.label("Playback:");
+            ui.add(egui::DragValue::new(&mut self.snapshot_interval).speed(0.05).range(0.05..=5.0).suffix(" s"))
+                .on_hover_text("Interval between stored frames. The last 5 s are kept at this interval; older frames are thinned out, twice as sparse whenever the history fills up.");
+        });
+        ui.horizontal(|ui| {
+            ui.label("Export:");
+            ui.add(egui::TextEdit::singleline(&mut self.export_path).desired_width(200.0));
+            if ui
+                .button("Export Fields")
+                .on_hover_text("CSV of η, u, v and, once recorded, H/H₀ at the wet cells of the displayed frame, with the easting and northing of the cells when the basin is geo-referenced")
+                .clicked()
+            {
+                self.export_status = Some(match self.export_fields() {
+                    Ok(cells) => format!("Exported {} cells to {}", cells, self.export_path),
+                    Err(message) => format!("⚠ {}", message),
+                });
+            }
+            if let Some(status) = &self.export_status {
+                ui.label(status);
+            }
+        });
+        self.timeline.show(ui, &self.snapshots, &[], *repaint_policy);
+        // Reviewing a stored instant pauses the run
+        if self.timeline.cursor().is_some() {
+            self.simulation_running = false;
+        }
+
+        self.show_surface(ui, style);
+        self.show_transect(ui, style);
+        self.show_diffraction(ui, style);
+        self.show_agitation(ui, style);
+        self.show_gauges(ui, style);
+    }
+
+    /// Basin dimensions, waves, absorbing edges and obstacles
+    fn show_settings(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Presets:");
+            if ui
+                .button("Breakwater Diffraction")
+                .on_hover_text("Regular waves past the tip of a thin breakwater, with gauges and a transect in its lee compared with the diffraction theory of Sommerfeld (1896) and Penney and Price (1952)")
+                .clicked()
+            {
+                self.load_diffraction_preset();
+            }
+            if ui
+                .button("Harbor Agitation")
+                .on_hover_text("Irregular waves through the entrance of a harbor between two rubble mound breakwaters, with vertical quay walls, a pier and three berths to report the agitation at")
+                .clicked()
+            {
+                self.load_harbor_preset();
+            }
+        });
+        egui::Grid::new("wave_basin_settings").num_columns(4).show(ui, |ui| {
+            ui.label("Length (m):");
+            ui.add(egui::DragValue::new(&mut self.basin_length).speed(0.5).range(5.0..=200.0).clamp_existing_to_range(false)); // Keep imported sites beyond the range
+            ui.label("Width (m):");
+            ui.add(egui::DragValue::new(&mut self.basin_width).speed(0.5).range(2.0..=200.0).clamp_existing_to_range(false));
+            ui.end_row();
+
+            if self.bathymetry.is_some() {
+                ui.label("Depth at Wavemaker (m):").on_hover_text("Depth the waves are generated for; the imported bathymetry gives the depth of each cell");
+            } else {
+                ui.label("Water Depth (m):");
+            }
+            ui.add(egui::DragValue::new(&mut self.water_depth).speed(0.01).range(0.05..=10.0).clamp_existing_to_range(false));
+            ui.label("Grid Spacing (m):");
+            ui.add(egui::DragValue::new(&mut self.grid_spacing).speed(0.01).range(0.02..=5.0).clamp_existing_to_range(false));
+            ui.end_row();
+
+            ui.label("Wave Height (m):");
+            ui.add(egui::DragValue::new(&mut self.wave_height).speed(0.005).range(0.001..=5.0));
+            ui.label("Wave Period (s):");
+            ui.add(egui::DragValue::new(&mut self.wave_period).speed(0.05).range(0.5..=30.0));
+            ui.end_row();
+
+            ui.label("Number of Waves:");
+            ui.add(egui::DragValue::new(&mut self.number_of_waves).range(1..=500));
+            ui.label("Direction (°):");
+            let mut degrees = self.wave_direction.to_degrees();
+            if ui
+                .add(egui::DragValue::new(&mut degrees).speed(1.0).range(-60.0..=60.0))
+                .on_hover_text("Angle of the waves from the x axis, anticlockwise. The paddles along the wavemaker move with a delay growing along it (snake principle), so the crests leave it at this angle.")
+                .changed()
+            {
+                self.wave_direction = degrees.to_radians();
+            }
+            ui.end_row();
+
+            ui.checkbox(&mut self.nonlinear, "Nonlinear")
+                .on_hover_text("Carry the total depth in the fluxes and advect the momentum, for steep waves and currents. Linear otherwise.");
+            ui.checkbox(&mut self.active_absorption, "Active Absorption")
+                .on_hover_text("The wavemaker absorbs the waves coming back to it, reflected by the obstacles, by adding the velocity they carry, so they do not build up between the paddles and the obstacles.");
+            ui.end_row();
+
+            ui.checkbox(&mut self.background_compute, "Compute in Background")
+                .on_hover_text("Step the solver on its own thread while the run plays. The plan view shows the latest state it reached, so drawing never slows the solver down and small time steps never freeze the window.");
+            ui.end_row();
+        });
+        let (nx, ny) = self.grid_size();
+        ui.label(format!("{} × {} cells", nx, ny));
+
+        ui.horizontal(|ui| {
+            let mut irregular = self.spectrum.is_some();
+            if ui.checkbox(&mut irregular, "Irregular Waves").on_hover_text("JONSWAP spectrum (γ = 3.3) of the same energy as the regular waves, Hs = √2 H and Tp = T").changed() {
+                self.spectrum = if irregular { WaveSpectrum::jonswap(std::f64::consts::SQRT_2 * self.wave_height, self.wave_period, JONSWAP_GAMMA).ok() } else { None };
+            }
+            let Some(spectrum) = &self.spectrum else {
+                return;
+            };
+            ui.label(format!("Hm0 = {:.3} m, Tp = {:.2} s", spectrum.significant_wave_height(), spectrum.peak_period()));
+            let mut directional = self.spreading.is_some();
+            if ui
+                .checkbox(&mut directional, "Directional Spreading")
+                .on_hover_text("Spreads the energy over the directions about the wave direction with the cos^2s((θ − θm)/2) function of Longuet-Higgins et al. (1963): s ≈ 10 for wind seas, 25 for young swell and 75 for swell. Short-crested waves from components in sectors of 15°; those travelling back towards the wavemaker are left out.")
+                .changed()
+            {
+                self.spreading = directional.then_some(DirectionalSpreading::default().exponent);
+            }
+            if let Some(exponent) = &mut self.spreading {
+                ui.label("s:");
+                ui.add(egui::DragValue::new(exponent).range(1.0..=100.0).speed(0.5));
+            }
+            ui.label("Random Seed:");
+            ui.add(egui::DragValue::new(&mut self.random_seed).speed(1.0));
+            if ui.small_button("🎲 New Seed").clicked() {
+                self.random_seed = seed_from_clock();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let mut absorbing = self.sponges.is_some();
+            if ui.checkbox(&mut absorbing, "Sponge Layers").changed() {
+                self.sponges = absorbing.then(BasinSponges::default);
+            }
+            if let Some(sponges) = &mut self.sponges {
+                ui.label("Width (m):");
+                ui.add(egui::DragValue::new(&mut sponges.width).speed(0.1).range(0.5..=50.0));
+                ui.label("Strength (1/s):");
+                ui.add(egui::DragValue::new(&mut sponges.strength).speed(0.1).range(0.0..=50.0));
+                ui.checkbox(&mut sponges.east, "East");
+                ui.checkbox(&mut sponges.north, "North");
+                ui.checkbox(&mut sponges.south, "South");
+            }
+        })
+        .response
+        .on_hover_text("Edges without a sponge layer are reflecting walls. The wavemaker stands along the west edge.");
+
+        ui.strong("Obstacles");
+        let mut removed = None;
+        for (i, obstacle) in self.obstacles.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(obstacle.to_string());
+                match obstacle {
+                    Obstacle::Rectangle { x_min, x_max, y_min, y_max } => {
+                        ui.label("x (m):");
+                        ui.add(egui::DragValue::new(x_min).speed(0.1));
+                        ui.add(egui::DragValue::new(x_max).speed(0.1));
+                        ui.label("y (m):");
+                        ui.add(egui::DragValue::new(y_min).speed(0.1));
+                        ui.add(egui::DragValue::new(y_max).speed(0.1));
+                    }
+                    Obstacle::Cylinder { x, y, radius } => {
+                        ui.label("Center (m):");
+                        ui.add(egui::DragValue::new(x).speed(0.1));
+                        ui.add(egui::DragValue::new(y).speed(0.1));
+                        ui.label("Radius (m):");
+                        ui.add(egui::DragValue::new(radius).speed(0.05).range(0.05..=50.0));
+                    }
+                }
+                if ui.small_button("🗑").clicked() {
+                    removed = Some(i);
+                }
+            });
+        }
+        if let Some(i) = removed {
+            self.obstacles.remove(i);
+        }
+        ui.horizontal(|ui| {
+            let (x, y) = (0.5 * self.basin_length, 0.5 * self.basin_width);
+            if ui.button("➕ Rectangle").clicked() {
+                self.obstacles.push(Obstacle::Rectangle { x_min: x - 0.5, x_max: x + 0.5, y_min: 0.0, y_max: y });
+            }
+            if ui.button("➕ Cylinder").clicked() {
+                self.obstacles.push(Obstacle::Cylinder { x, y, radius: 1.0 });
+            }
+        });
+
+        ui.strong("Gauges");
+        let mut removed = None;
+        for (i, gauge) in self.gauges.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                let mut x = gauge.record.position();
+                ui.label(format!("Gauge {}  x (m):", i + 1));
+                if ui.add(egui::DragValue::new(&mut x).speed(0.1).range(0.0..=self.basin_length)).changed() {
+                    gauge.record.set_position(x);
+                }
+                ui.label("y (m):");
+                ui.add(egui::DragValue::new(&mut gauge.y).speed(0.1).range(0.0..=self.basin_width));
+                if ui.small_button("🗑").clicked() {
+                    removed = Some(i);
+                }
+            });
+        }
+        if let Some(i) = removed {
+            self.gauges.remove(i);
+        }
+        if ui.button("➕ Gauge").clicked() {
+            self.gauges.push(BasinGauge::new(0.5 * self.basin_length, 0.5 * self.basin_width));
+        }
+    }
+
+    /// Top-down color map of the displayed field, with the coastline, sponge layers, velocity arrows, transect and gauges
+    fn show_surface(&mut self, ui: &mut egui::Ui, style: PlotStyle) {
+        let reset_view = std::mem::take(&mut self.reset_view);
+        let Some((time, values)) = self.displayed_frame().map(|(time, values)| (time, values.into_owned())) else {
+            return;
+        };
+        let Some(solver) = &self.solver else {
+            return;
+        };
+        let Some(frame) = BasinFrame::new(solver.grid(), &values) else {
+            return;
+        };
+        let range = self.color_range();
+        let land = self.show_coastline.then(|| style.bed());
+        // The agitation map replaces the field once the wave heights are recorded
+        let agitation = self.diffraction_coefficients().filter(|_| self.show_agitation);
+        let image = match (&agitation, self.field) {
+            (Some(coefficients), _) => field_image(solver, coefficients, |coefficient| coefficient / AGITATION_RANGE, style.colormap, land),
+            (None, BasinField::SurfaceElevation) => field_image(solver, &frame.field(self.field), |value| 0.5 + 0.5 * value / range, style.colormap, land),
+            (None, BasinField::Speed) => field_image(solver, &frame.field(self.field), |value| value / range, style.colormap, land),
+        };
+        match &mut self.texture {
+            Some(texture) => texture.set(image, egui::TextureOptions::NEAREST),
+            None => self.texture = Some(ui.ctx().load_texture("wave_basin_field", image, egui::TextureOptions::NEAREST)),
+        }
+        let Some(texture) = &self.texture else {
+            return;
+        };
+
+        ui.label(match self.field {
+            _ if agitation.is_some() => format!(
+                "Wave height relative to the offshore waves, H/H₀, from 0 (low end of the {} colormap) to {:.0} (high end), recorded from {:.1} to {:.1} s",
+                style.colormap,
+                AGITATION_RANGE,
+                self.height_field.start_time(),
+                self.height_field.end_time()
+            ),
+            // Colors span a crest to a trough of the generated waves, so the pattern keeps its contrast as they travel
+            BasinField::SurfaceElevation => format!(
+                "t = {:.2} s: surface elevation from −{:.3} m (low end of the {} colormap) to +{:.3} m (high end)",
+                time, range, style.colormap, range
+            ),
+            BasinField::Speed => format!("t = {:.2} s: current speed from 0 (low end of the {} colormap) to {:.3} m/s (high end)", time, style.colormap, range),
+        });
+        let grid = *solver.grid();
+        let (length, width) = (grid.length(), grid.width());
+        let mut plot = Plot::new("wave_basin_surface")
+            .height(400.0)
+            .width((ui.available_width() - 40.0).max(400.0))
+            .data_aspect(1.0)
+            .x_axis_label("x (m)")
+            .y_axis_label("y (m)")
+            .include_x(0.0)
+            .include_x(length)
+            .include_y(0.0)
+            .include_y(width)
+            .legend(Legend::default());
+        if let Some(reference) = self.georeference.as_ref().filter(|_| self.world_axes) {
+            plot = world_axes(plot, reference);
+        }
+        if reset_view {
+            plot = plot.reset();
+        }
+
+        // Arrows on a regular subset of the wet cells, the fastest as long as the spacing between them
+        let quivers = self.show_quivers.then(|| {
+            let stride = (grid.nx() / QUIVER_COLUMNS).max(1);
+            let cells: Vec<(usize, usize)> = (stride / 2..grid.ny())
+                .step_by(stride)
+                .flat_map(|j| (stride / 2..grid.nx()).step_by(stride).map(move |i| (i, j)))
+                .filter(|&(i, j)| !solver.is_solid(i, j))
+                .collect();
+            let fastest = cells.iter().map(|&(i, j)| frame.u()[grid.index(i, j)].hypot(frame.v()[grid.index(i, j)])).fold(0.0, f64::max);
+            let scale = if fastest > 1e-9 { stride as f64 * grid.dx().min(grid.dy()) / fastest } else { 0.0 };
+            let origins: Vec<[f64; 2]> = cells.iter().map(|&(i, j)| grid.center(i, j).into()).collect();
+            let tips: Vec<[f64; 2]> = cells
+                .iter()
+                .map(|&(i, j)| {
+                    let (x, y) = grid.center(i, j);
+                    let n = grid.index(i, j);
+                    [x + scale * frame.u()[n], y + scale * frame.v()[n]]
+                })
+                .collect();
+            (origins, tips)
+        });
+        let coastline = if self.show_coastline { solver.coastline() } else { Vec::new() };
+        let gauges: Vec<[f64; 2]> = self.gauges.iter().map(|gauge| [gauge.record.position(), gauge.y]).collect();
+        let (sponges, transect, harbor) = (self.sponges, self.transect, &self.harbor);
+        // Boundary of the geometric shadow, from the breakwater tip along the waves to the edge of the basin
+        let shadow_boundary = self.breakwater_diffraction().filter(|_| self.show_diffraction).map(|theory| {
+            let [x, y] = theory.tip();
+            let (dx, dy) = (theory.direction().cos(), theory.direction().sin());
+            let reach = [(length - x) / dx, if dy > 0.0 { (width - y) / dy } else if dy < 0.0 { -y / dy } else { f64::INFINITY }]
+                .into_iter()
+                .filter(|distance| *distance > 0.0)
+                .fold(f64::INFINITY, f64::min);
+            vec![[x, y], [x + reach * dx, y + reach * dy]]
+        });
+        style.show_plot(ui, plot, |plot_ui| {
+            plot_ui.image(PlotImage::new(texture.id(), PlotPoint::new(0.5 * length, 0.5 * width), [length as f32, width as f32]));
+            plot_ui.line(
+                Line::new(PlotPoints::from(vec![[0.0, 0.0], [0.0, width]]))
+                    .color(style.marker())
+                    .width(style.width(4.0))
+                    .name("Wavemaker"),
+            );
+            if let Some(sponges) = sponges {
+                let mut edges = Vec::new();
+                if sponges.east {
+                    edges.push(vec![[length - sponges.width, 0.0], [length - sponges.width, width]]);
+                }
+                if sponges.north {
+                    edges.push(vec![[0.0, width - sponges.width], [length, width - sponges.width]]);
+                }
+                if sponges.south {
+                    edges.push(vec![[0.0, sponges.width], [length, sponges.width]]);
+                }
+                for edge in edges {
+                    plot_ui.line(
+                        Line::new(PlotPoints::from(edge))
+                            .color(style.reference())
+                            .style(LineStyle::dashed_loose())
+                            .name("Sponge Layer"),
+                    );
+                }
+            }
+            for segment in coastline {
+                plot_ui.line(Line::new(PlotPoints::from(segment.to_vec())).color(style.foam()).width(style.width(1.5)).name("Coastline"));
+            }
+            if let Some(boundary) = shadow_boundary {
+                plot_ui.line(
+                    Line::new(PlotPoints::from(boundary))
+                        .color(style.alert())
+                        .style(LineStyle::dashed_dense())
+                        .width(style.width(1.5))
+                        .name("Shadow Boundary"),
+                );
+            }
+            show_harbor(plot_ui, harbor, style);
+            if let Some((origins, tips)) = quivers {
+                plot_ui.arrows(Arrows::new(origins, tips).color(style.foam()).tip_length(4.0).name("Velocity"));
+            }
+            if let Some(transect) = transect {
+                plot_ui.line(
+                    Line::new(PlotPoints::from(vec![transect.start, transect.end]))
+                        .color(style.annotation())
+                        .width(style.width(2.0))
+                        .name("Transect"),
+                );
+            }
+            plot_ui.points(
+                Points::new(PlotPoints::from(gauges))
+                    .shape(MarkerShape::Diamond)
+                    .radius(5.0)
+                    .color(style.sensor())
+                    .name("Gauges"),
+            );
+        });
+    }
+
+    /// Transect editor, the displayed field along it with its crests, and the wave height along it over the stored frames
+    fn show_transect(&mut self, ui: &mut egui::Ui, style: PlotStyle) {
+        ui.horizontal(|ui| {
+            let mut enabled = self.transect.is_some();
+            if ui.checkbox(&mut enabled, "Transect").changed() {
+                let y = 0.5 * self.basin_width;
+                self.transect = enabled.then_some(Transect { start: [0.0, y], end: [self.basin_length, y] });
+            }
+            if let Some(transect) = &mut self.transect {
+                let (length, width) = (self.basin_length, self.basin_width);
+                ui.label("From (m):");
+                ui.add(egui::DragValue::new(&mut transect.start[0]).speed(0.1).range(0.0..=length));
+                ui.add(egui::DragValue::new(&mut transect.start[1]).speed(0.1).range(0.0..=width));
+                ui.label("To (m):");
+                ui.add(egui::DragValue::new(&mut transect.end[0]).speed(0.1).range(0.0..=length));
+                ui.add(egui::DragValue::new(&mut transect.end[1]).speed(0.1).range(0.0..=width));
+            }
+        })
+        .response
+        .on_hover_text("Fields along a line across the basin, analysed like the surface along the wave channel: crests of the displayed profile, and the significant wave height of the records of the stored frames at each point.");
+        if self.transect.is_some_and(|transect| transect.length() <= 0.0) {
+            ui.colored_label(egui::Color32::from_rgb(200, 80, 0), "⚠ The transect ends must be distinct points");
+            return;
+        }
+        let Some((distances, profile)) = self.transect_profile() else {
+            return;
+        };
+
+        let crests = match self.field {
+            BasinField::SurfaceElevation => {
+                let spacing = distances.get(1).copied().unwrap_or(1.0);
+                find_crests(&profile, spacing, 0.1 * self.generated_wave_height())
+            }
+            BasinField::Speed => Vec::new(),
+        };
+        let gauges = self.transect_gauges();
+        let heights: Vec<[f64; 2]> = gauges.iter().filter(|gauge| gauge.len() > 1).map(|gauge| [gauge.position(), significant_wave_height(gauge.elevation())]).collect();
+        if !crests.is_empty() {
+            let mean_height = crests.iter().map(|&(_, _, height)| height).sum::<f64>() / crests.len() as f64;
+            ui.label(format!("{} crests along the transect, mean height {:.3} m", crests.len(), mean_height));
+        }
+        if let Some(middle) = gauges.get(gauges.len() / 2)
+            && let Some(frequency) = middle.sample_interval().and_then(|interval| peak_frequency(middle.elevation(), interval))
+        {
+            ui.label(format!("Peak period at the middle of the transect: {:.2} s", 1.0 / frequency));
+        }
+
+        let field = self.field;
+        let plot = Plot::new("wave_basin_transect")
+            .height(200.0)
+            .width((ui.available_width() - 40.0).max(400.0))
+            .x_axis_label("Distance along the transect (m)")
+            .y_axis_label(match field {
+                BasinField::SurfaceElevation => "η, Hs (m)",
+                BasinField::Speed => "|u| (m/s), Hs (m)",
+            })
+            .legend(Legend::default());
+        style.show_plot(ui, plot, |plot_ui| {
+            let points: PlotPoints = distances.iter().zip(&profile).map(|(&s, &value)| [s, value]).collect();
+            plot_ui.line(Line::new(points).color(style.water()).width(style.width(2.0)).name(field.to_string()));
+            if !crests.is_empty() {
+                let crests: Vec<[f64; 2]> = crests.iter().map(|&(s, eta, _)| [s, eta]).collect();
+                plot_ui.points(Points::new(PlotPoints::from(crests)).shape(MarkerShape::Up).radius(4.0).color(style.marker()).name("Crests"));
+            }
+            if !heights.is_empty() {
+                plot_ui.line(
+                    Line::new(PlotPoints::from(heights))
+                        .color(style.series(1))
+                        .style(LineStyle::dashed_loose())
+                        .name("Hs over the stored frames"),
+                );
+            }
+        });
+    }
+
+    /// Diffraction coefficients measured in the basin against the theory of a semi-infinite breakwater,
+    /// along the transect and at the gauges
+    fn show_diffraction(&mut self, ui: &mut egui::Ui, style: PlotStyle) {
+        let Some(theory) = self.breakwater_diffraction() else {
+            return;
+        };
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.show_diffraction, "Diffraction Theory").on_hover_text(
+                "Diffraction coefficient Kd, the ratio of the local wave height to the generated one, measured from the variance of the surface once the waves have settled and until the wavemaker stops, against the exact solution of Sommerfeld (1896) for a thin, rigid, fully reflecting semi-infinite breakwater, applied to water waves by Penney and Price (1952). The theory uses the wave number of the long waves the basin solves, k = ω/√(gd). The waves in the lit region keep Kd ≈ 1, the boundary of the geometric shadow, along the waves from the tip, receives half the height and the waves fade deeper behind the breakwater. The sponge layers and the width of the breakwater make the basin depart from the theory near the edges and the tip.",
+            );
+        });
+        if !self.show_diffraction {
+            return;
+        }
+        let [x, y] = theory.tip();
+        let (start, end) = self.height_window();
+        ui.label(format!(
+            "Breakwater tip at ({:.1}, {:.1}) m, L = {:.2} m: wave heights recorded from {:.1} to {:.1} s",
+            x,
+            y,
+            2.0 * std::f64::consts::PI / theory.wave_number(),
+            start,
+            end
+        ));
+        if start >= end {
+            ui.colored_label(egui::Color32::from_rgb(200, 80, 0), "⚠ The wavemaker stops before the waves settle over the basin: generate more waves");
+            return;
+        }
+        let Some(measured) = self.diffraction_coefficients() else {
+            ui.label("Waiting for the waves to settle over the basin…");
+            return;
+        };
+        if let Some(solver) = &self.solver {
+            for (i, gauge) in self.gauges.iter().enumerate() {
+                let (x, y) = (gauge.record.position(), gauge.y);
+                ui.label(format!(
+                    "Gauge {} at ({:.1}, {:.1}) m{}: Kd = {:.2} measured, {:.2} in theory",
+                    i + 1,
+                    x,
+                    y,
+                    if theory.in_shadow(x, y) { ", in the shadow" } else { "" },
+                    solver.grid().interpolate_centers(&measured, x, y),
+                    theory.coefficient(x, y)
+                ));
+            }
+        }
+
+        let Some((distances, measured, theory)) = self.transect_diffraction() else {
+            return;
+        };
+        let plot = Plot::new("wave_basin_diffraction")
+            .height(200.0)
+            .width((ui.available_width() - 40.0).max(400.0))
+            .include_y(0.0)
+            .x_axis_label("Distance along the transect (m)")
+            .y_axis_label("Kd")
+            .legend(Legend::default());
+        style.show_plot(ui, plot, |plot_ui| {
+            let points = |values: &[f64]| -> PlotPoints { distances.iter().zip(values).map(|(&s, &kd)| [s, kd]).collect() };
+            plot_ui.line(Line::new(points(&measured)).color(style.water()).width(style.width(2.0)).name("Basin"));
+            plot_ui.line(
+                Line::new(points(&theory))
+                    .color(style.reference())
+                    .style(LineStyle::dashed_loose())
+                    .width(style.width(1.5))
+                    .name("Sommerfeld / Penney and Price"),
+            );
+        });
+    }
+
+    /// Import of a bathymetry raster as the depth of the cells, with its shaded relief under the outline of the basin
+    /// Clicking the relief moves the south-west corner of the basin there
+    fn show_bathymetry_import(&mut self, ui: &mut egui::Ui, style: PlotStyle) {
+        egui::CollapsingHeader::new("Bathymetry").id_salt("wave_basin_bathymetry").show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("File:");
+                ui.add(egui::TextEdit::singleline(&mut self.bathymetry_path).desired_width(200.0));
+                if ui
+                    .button("Import")
+                    .on_hover_text("ESRI ASCII grid (.asc), or GeoTIFF (.tif) in builds with the geotiff feature. The values are resampled onto the grid of the basin, each cell averaging the raster over its area; cells above the still water level, outside the raster or without data become land.")
+                    .clicked()
+                {
+                    self.bathymetry_status = Some(match self.import_bathymetry() {
+                        Ok(message) => message,
+                        Err(message) => format!("⚠ {}", message),
+                    });
+                }
+                if self.bathymetry.is_some() && ui.button("Uniform Depth").on_hover_text("Discard the imported bathymetry").clicked() {
+                    self.bathymetry = None;
+                    self.bathymetry_status = None;
+                }
+            });
+            let options = &mut self.bathymetry_options;
+            let mut redraw = false;
+            ui.horizontal(|ui| {
+                for (label, unit, id) in [("Coordinates:", &mut options.horizontal_unit, "bathymetry_horizontal_unit"), ("Values:", &mut options.vertical_unit, "bathymetry_vertical_unit")] {
+                    ui.label(label);
+                    egui::ComboBox::from_id_salt(id).selected_text(unit.to_string()).width(50.0).show_ui(ui, |ui| {
+                        for option in SurveyUnit::ALL {
+                            redraw |= ui.selectable_value(unit, option, option.to_string()).changed();
+                        }
+                    });
+                }
+                redraw |= ui.checkbox(&mut options.positive_depths, "Depths positive downward").changed();
+            });
+            ui.horizontal(|ui| {
+                ui.label("Still Water Level:");
+                redraw |= ui.add(egui::DragValue::new(&mut options.water_level).speed(0.01)).on_hover_text("In the datum and the unit of the values").changed();
+                ui.label("Basin Corner (x, y):");
+                ui.add(egui::DragValue::new(&mut options.origin[0]).speed(1.0));
+                ui.add(egui::DragValue::new(&mut options.origin[1]).speed(1.0));
+            });
+            if redraw {
+                self.bathymetry_texture = None;
+            }
+            if let Some(status) = &self.bathymetry_status {
+                ui.label(status);
+            }
+            if self.bathymetry.is_some() {
+                match self.wavemaker_depth() {
+                    Ok(depth) if (depth - self.water_depth).abs() > 0.05 * depth => {
+                        ui.colored_label(egui::Color32::from_rgb(200, 80, 0), format!("⚠ The waves are generated for {:.2} m, the bathymetry is {:.2} m deep at the wavemaker", self.water_depth, depth));
+                    }
+                    Ok(_) => {}
+                    Err(message) => {
+                        ui.colored_label(egui::Color32::from_rgb(200, 80, 0), format!("⚠ {}", message));
+                    }
+                }
+            }
+            let Some(raster) = &self.bathymetry else {
+                return;
+            };
+
+            let options = self.bathymetry_options;
+            let texture = self.bathymetry_texture.get_or_insert_with(|| {
+                let image = relief_image(raster, &options, style.colormap, style.bed());
+                ui.ctx().load_texture("wave_basin_bathymetry", image, egui::TextureOptions::LINEAR)
+            });
+            let scale = options.horizontal_unit.to_meters();
+            let [x_min, y_min, x_max, y_max] = raster.extent();
+            // Raster corners in the coordinates of the basin [m]
+            let (west, south, east, north) = ((x_min - options.origin[0]) * scale, (y_min - options.origin[1]) * scale, (x_max - options.origin[0]) * scale, (y_max - options.origin[1]) * scale);
+            let (length, width) = (self.basin_length, self.basin_width);
+            ui.label(format!("Shaded relief, water from the still water level (low end of the {} colormap) to the deepest point (high end)", style.colormap));
+            let mut plot = Plot::new("wave_basin_relief")
+                .height(250.0)
+                .width((ui.available_width() - 40.0).max(400.0))
+                .data_aspect(1.0)
+                .x_axis_label("x (m)")
+                .y_axis_label("y (m)")
+                .include_x(west.min(0.0))
+                .include_x(east.max(length))
+                .include_y(south.min(0.0))
+                .include_y(north.max(width));
+            if let Some(reference) = self.georeference.as_ref().filter(|_| self.world_axes) {
+                plot = world_axes(plot, reference);
+            }
+            let clicked = style
+                .show_plot(ui, plot, |plot_ui| {
+                    plot_ui.image(PlotImage::new(texture.id(), PlotPoint::new(0.5 * (west + east), 0.5 * (south + north)), [(east - west) as f32, (north - south) as f32]));
+                    plot_ui.line(Line::new(PlotPoints::from(vec![[0.0, 0.0], [length, 0.0], [length, width], [0.0, width], [0.0, 0.0]])).color(style.annotation()).width(style.width(2.0)).name("Basin"));
+                    plot_ui.line(Line::new(PlotPoints::from(vec![[0.0, 0.0], [0.0, width]])).color(style.marker()).width(style.width(4.0)).name("Wavemaker"));
+                    plot_ui.response().clicked().then(|| plot_ui.pointer_coordinate()).flatten()
+                })
+                .inner;
+            if let Some(point) = clicked {
+                self.bathymetry_options.origin[0] += point.x / scale;
+                self.bathymetry_options.origin[1] += point.y / scale;
+            }
+            // The basin keeps the coordinates of the raster it is placed on
+            if let Some(reference) = &mut self.georeference {
+                (reference.origin, reference.unit) = (self.bathymetry_options.origin, self.bathymetry_options.horizontal_unit);
+            }
+        });
+    }
+
+    /// Coordinate reference system and projected coordinates of the south-west corner of the basin
+    fn show_georeference(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Geo-referencing").id_salt("wave_basin_georeference").show(ui, |ui| {
+            let mut referenced = self.georeference.is_some();
+            if ui
+                .checkbox(&mut referenced, "Geo-referenced")
+                .on_hover_text("Place the basin in a projected coordinate reference system, so exported fields and figures carry the coordinates of the site. Imported bathymetry sets the reference from the raster.")
+                .changed()
+            {
+                self.georeference = referenced.then(GeoReference::default);
+                self.world_axes &= referenced;
+            }
+            let placed = self.bathymetry.is_some();
+            let Some(reference) = &mut self.georeference else {
+                return;
+            };
+            ui.horizontal(|ui| {
+                ui.label("CRS:");
+                ui.add(egui::TextEdit::singleline(&mut reference.crs).hint_text("EPSG:32619").desired_width(200.0));
+                match reference.epsg() {
+                    Some(code) => ui.label(format!("{} (EPSG {})", reference.name(), code)),
+                    None => ui.label(reference.name()),
+                };
+            });
+            // Over an imported bathymetry the origin is the corner of the basin placed on the raster
+            ui.add_enabled_ui(!placed, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Unit:");
+                    egui::ComboBox::from_id_salt("wave_basin_georeference_unit").selected_text(reference.unit.to_string()).width(50.0).show_ui(ui, |ui| {
+                        for unit in SurveyUnit::ALL {
+                            ui.selectable_value(&mut reference.unit, unit, unit.to_string());
+                        }
+                    });
+                    ui.label("Origin Easting:");
+                    ui.add(egui::DragValue::new(&mut reference.origin[0]).speed(1.0));
+                    ui.label("Northing:");
+                    ui.add(egui::DragValue::new(&mut reference.origin[1]).speed(1.0));
+                })
+                .response
+                .on_disabled_hover_text("Set by the basin corner on the imported bathymetry");
+            });
+            let [east, north] = reference.to_world(self.basin_length, self.basin_width);
+            ui.label(format!("Basin from E {:.1} N {:.1} to E {:.1} N {:.1} {}", reference.origin[0], reference.origin[1], east, north, reference.unit));
+        });
+    }
+
+    /// Harbor structures as polygons with their reflection coefficients, entrances and berths, with a plan of the layout
+    /// Clicking the plan adds corners to the structure being drawn
+    fn show_harbor_editor(&mut self, ui: &mut egui::Ui, style: PlotStyle) {
+        egui::CollapsingHeader::new("Harbor Layout").id_salt("wave_basin_harbor").default_open(!self.harbor.is_empty()).show(ui, |ui| {
+            ui.label("Structures").on_hover_text(
+                "Breakwaters, quays, piers and land drawn as polygons. Their faces reflect a share R of the wave height running into them and let the rest through: R ≈ 0.9 to 1 for vertical walls, 0.3 to 0.5 for rubble mound slopes and 0.2 to 0.4 for wave absorbing quays (PIANC, 1995).",
+            );
+            let mut removed = None;
+            for (index, structure) in self.harbor.structures.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut structure.name).desired_width(120.0));
+                    ui.label("R:");
+                    ui.add(egui::DragValue::new(&mut structure.reflection).speed(0.01).range(0.0..=1.0));
+                    ui.label(format!("{} corners, {:.1} m²", structure.vertices.len(), structure.area()));
+                    let drawing = self.drawing == Some(index);
+                    if ui.selectable_label(drawing, "✏ Draw").on_hover_text("Click the plan below to add corners to this structure").clicked() {
+                        self.drawing = if drawing { None } else { Some(index) };
+                    }
+                    if ui.small_button("↩").on_hover_text("Remove the last corner").clicked() {
+                        structure.vertices.pop();
+                    }
+                    if ui.small_button("🗑").clicked() {
+                        removed = Some(index);
+                    }
+                });
+                egui::CollapsingHeader::new("Corners").id_salt(("wave_basin_structure", index)).show(ui, |ui| {
+                    for vertex in &mut structure.vertices {
+                        ui.horizontal(|ui| {
+                            ui.add(egui::DragValue::new(&mut vertex[0]).speed(0.1).prefix("x: "));
+                            ui.add(egui::DragValue::new(&mut vertex[1]).speed(0.1).prefix("y: "));
+                        });
+                    }
+                });
+            }
+            if let Some(index) = removed {
+                self.harbor.structures.remove(index);
+                self.drawing = None;
+            }
+            if ui.button("➕ Structure").clicked() {
+                let name = format!("Structure {}", self.harbor.structures.len() + 1);
+                self.harbor.structures.push(HarborStructure::new(&name, Vec::new(), 0.9));
+                self.drawing = Some(self.harbor.structures.len() - 1);
+            }
+
+            ui.label("Entrances");
+            let (length, width) = (self.basin_length, self.basin_width);
+            let mut removed = None;
+            for (index, entrance) in self.harbor.entrances.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut entrance.name).desired_width(120.0));
+                    ui.label("From (m):");
+                    ui.add(egui::DragValue::new(&mut entrance.start[0]).speed(0.1).range(0.0..=length));
+                    ui.add(egui::DragValue::new(&mut entrance.start[1]).speed(0.1).range(0.0..=width));
+                    ui.label("To (m):");
+                    ui.add(egui::DragValue::new(&mut entrance.end[0]).speed(0.1).range(0.0..=length));
+                    ui.add(egui::DragValue::new(&mut entrance.end[1]).speed(0.1).range(0.0..=width));
+                    ui.label(format!("{:.1} m wide", entrance.width()));
+                    if ui.small_button("🗑").clicked() {
+                        removed = Some(index);
+                    }
+                });
+            }
+            if let Some(index) = removed {
+                self.harbor.entrances.remove(index);
+            }
+            if ui.button("➕ Entrance").clicked() {
+                let (x, y) = (0.5 * length, 0.5 * width);
+                self.harbor.entrances.push(HarborEntrance { name: format!("Entrance {}", self.harbor.entrances.len() + 1), start: [x, y - 2.0], end: [x, y + 2.0] });
+            }
+
+            ui.label("Berths").on_hover_text("Line along which a moored ship lies, with the highest significant wave height acceptable for its operations");
+            let mut removed = None;
+            for (index, berth) in self.harbor.berths.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut berth.name).desired_width(120.0));
+                    ui.label("From (m):");
+                    ui.add(egui::DragValue::new(&mut berth.start[0]).speed(0.1).range(0.0..=length));
+                    ui.add(egui::DragValue::new(&mut berth.start[1]).speed(0.1).range(0.0..=width));
+                    ui.label("To (m):");
+                    ui.add(egui::DragValue::new(&mut berth.end[0]).speed(0.1).range(0.0..=length));
+                    ui.add(egui::DragValue::new(&mut berth.end[1]).speed(0.1).range(0.0..=width));
+                    ui.label("Limit (m):");
+                    ui.add(egui::DragValue::new(&mut berth.limit).speed(0.001).range(0.001..=10.0));
+                    if ui.small_button("🗑").clicked() {
+                        removed = Some(index);
+                    }
+                });
+            }
+            if let Some(index) = removed {
+                self.harbor.berths.remove(index);
+            }
+            if ui.button("➕ Berth").clicked() {
+                let (x, y) = (0.75 * length, 0.5 * width);
+                self.harbor.berths.push(Berth { name: format!("Berth {}", self.harbor.berths.len() + 1), start: [x, y - 2.0], end: [x, y + 2.0], limit: 0.5 * self.wave_height });
+            }
+            if let Err(message) = self.harbor.validate() {
+                ui.colored_label(egui::Color32::from_rgb(200, 80, 0), format!("⚠ {}", message));
+            }
+
+            let plot = Plot::new("wave_basin_layout")
+                .height(250.0)
+                .width((ui.available_width() - 40.0).max(400.0))
+                .data_aspect(1.0)
+                .x_axis_label("x (m)")
+                .y_axis_label("y (m)")
+                .include_x(0.0)
+                .include_x(length)
+                .include_y(0.0)
+                .include_y(width)
+                .allow_drag(self.drawing.is_none());
+            let harbor = &self.harbor;
+            let clicked = style
+                .show_plot(ui, plot, |plot_ui| {
+                    plot_ui.line(Line::new(PlotPoints::from(vec![[0.0, 0.0], [length, 0.0], [length, width], [0.0, width], [0.0, 0.0]])).color(style.reference()).name("Basin"));
+                    plot_ui.line(Line::new(PlotPoints::from(vec![[0.0, 0.0], [0.0, width]])).color(style.marker()).width(style.width(4.0)).name("Wavemaker"));
+                    show_harbor(plot_ui, harbor, style);
+                    plot_ui.response().clicked().then(|| plot_ui.pointer_coordinate()).flatten()
+                })
+                .inner;
+            if let (Some(point), Some(structure)) = (clicked, self.drawing.and_then(|index| self.harbor.structures.get_mut(index))) {
+                structure.vertices.push([point.x.clamp(0.0, length), point.y.clamp(0.0, width)]);
+            }
+        });
+    }
+
+    /// Wave heights along the berths against their acceptable heights, and at the entrances, under the offshore waves
+    fn show_agitation(&self, ui: &mut egui::Ui, style: PlotStyle) {
+        if self.harbor.berths.is_empty() && self.harbor.entrances.is_empty() {
+            return;
+        }
+        ui.strong("Harbor Agitation");
+        let offshore = match &self.spectrum {
+            Some(_) => format!(
+                "Offshore: Hm0 = {:.3} m, Tp = {:.2} s, θ = {:.0}°, {}",
+                self.generated_wave_height(),
+                self.generated_wave_period(),
+                self.wave_direction.to_degrees(),
+                self.spreading.map_or("long-crested".to_string(), |exponent| format!("spread with s = {:.0}", exponent))
+            ),
+            None => format!("Offshore: regular waves H = {:.3} m, T = {:.2} s, θ = {:.0}°", self.wave_height, self.wave_period, self.wave_direction.to_degrees()),
+        };
+        ui.label(offshore);
+        let (start, end) = self.height_window();
+        if start >= end {
+            ui.colored_label(egui::Color32::from_rgb(200, 80, 0), "⚠ The wavemaker stops before the waves settle over the basin: generate more waves");
+            return;
+        }
+        let (Some(solver), Some(heights)) = (&self.solver, self.wave_heights()) else {
+            ui.label(format!("Wave heights are recorded from {:.1} to {:.1} s, once the waves have settled over the basin", start, end));
+            return;
+        };
+        for entrance in &self.harbor.entrances {
+            let [x, y] = entrance.midpoint();
+            let (i, j) = solver.grid().cell_at(x, y);
+            let height = heights[solver.grid().index(i, j)];
+            ui.label(format!("{}: {:.3} m at the middle of the {:.1} m opening (H/H₀ = {:.2})", entrance.name, height, entrance.width(), height / self.generated_wave_height()));
+        }
+        let agitation = self.berth_agitation();
+        for berth in &agitation {
+            let text = format!(
+                "{}: mean {:.3} m (H/H₀ = {:.2}), from {:.3} to {:.3} m; limit {:.3} m exceeded along {:.0}% of the berth",
+                berth.name,
+                berth.mean(),
+                berth.coefficient(),
+                berth.min(),
+                berth.max(),
+                berth.limit,
+                100.0 * berth.exceedance()
+            );
+            if berth.is_acceptable() {
+                ui.label(format!("✔ {}", text));
+            } else {
+                ui.colored_label(egui::Color32::from_rgb(200, 80, 0), format!("⚠ {}", text));
+            }
+        }
+        if agitation.is_empty() {
+            return;
+        }
+
+        let plot = Plot::new("wave_basin_agitation")
+            .height(180.0)
+            .width((ui.available_width() - 40.0).max(400.0))
+            .include_y(0.0)
+            .x_axis_formatter(|mark, _| agitation.get((mark.value.round() as usize).wrapping_sub(1)).filter(|_| mark.value.fract() == 0.0).map_or(String::new(), |berth| berth.name.clone()))
+            .y_axis_label(if self.spectrum.is_some() { "Hm0 (m)" } else { "H (m)" })
+            .legend(Legend::default());
+        style.show_plot(ui, plot, |plot_ui| {
+            let bars = |value: fn(&BerthAgitation) -> f64, offset: f64| -> Vec<Bar> {
+                agitation.iter().enumerate().map(|(i, berth)| Bar::new((i + 1) as f64 + offset, value(berth)).width(0.3)).collect()
+            };
+            plot_ui.bar_chart(BarChart::new(bars(BerthAgitation::mean, -0.15)).color(style.series(0)).name("Mean"));
+            plot_ui.bar_chart(BarChart::new(bars(BerthAgitation::max, 0.15)).color(style.series(1)).name("Highest"));
+            for (i, berth) in agitation.iter().enumerate() {
+                let x = (i + 1) as f64;
+                plot_ui.line(Line::new(PlotPoints::from(vec![[x - 0.4, berth.limit], [x + 0.4, berth.limit]])).color(style.alert()).width(style.width(2.0)).name("Acceptable"));
+            }
+        });
+    }
+
+    /// Surface elevation records of the gauges, with their significant wave height
+    fn show_gauges(&self, ui: &mut egui::Ui, style: PlotStyle) {
+        if self.gauges.iter().all(|gauge| gauge.record.is_empty()) {
+            return;
+        }
+        for (i, gauge) in self.gauges.iter().enumerate().filter(|(_, gauge)| !gauge.record.is_empty()) {
+            ui.label(format!(
+                "Gauge {} at ({:.1}, {:.1}) m: Hs = {:.3} m",
+                i + 1,
+                gauge.record.position(),
+                gauge.y,
+                significant_wave_height(gauge.record.elevation())
+            ));
+        }
+        let plot = Plot::new("wave_basin_gauges")
+            .height(200.0)
+            .width((ui.available_width() - 40.0).max(400.0))
+            .x_axis_label("Time (s)")
+            .y_axis_label("η (m)")
+            .legend(Legend::default());
+        style.show_plot(ui, plot, |plot_ui| {
+            for (i, gauge) in self.gauges.iter().enumerate() {
+                let points: PlotPoints = gauge.record.times().iter().zip(gauge.record.elevation()).map(|(&t, &eta)| [t, eta]).collect();
+                plot_ui.line(Line::new(points).color(style.series(i)).width(style.width(1.5)).name(format!("Gauge {}", i + 1)));
+            }
+        });
+    }
+}
+
+/// Render a field of a frame as an image, the north wall on the top row
+/// Harbor structures as filled outlines, entrances as dashed lines and berths as thick lines, labelled
+fn show_harbor(plot_ui: &mut egui_plot::PlotUi, harbor: &HarborLayout, style: PlotStyle) {
+    for structure in &harbor.structures {
+        match structure.vertices.len() {
+            0 => {}
+            1 | 2 => plot_ui.line(Line::new(PlotPoints::from(structure.vertices.clone())).color(style.bed()).width(style.width(2.0)).name("Structures")),
+            _ => plot_ui.polygon(Polygon::new(PlotPoints::from(structure.vertices.clone())).fill_color(style.bed().gamma_multiply(0.6)).stroke(egui::Stroke::new(1.5, style.bed())).name("Structures")),
+        }
+    }
+    for entrance in &harbor.entrances {
+        plot_ui.line(Line::new(PlotPoints::from(vec![entrance.start, entrance.end])).color(style.reference()).style(LineStyle::dashed_dense()).width(style.width(2.0)).name("Entrances"));
+    }
+    for berth in &harbor.berths {
+        plot_ui.line(Line::new(PlotPoints::from(vec![berth.start, berth.end])).color(style.sensor()).width(style.width(4.0)).name("Berths"));
+        let [x, y] = [0.5 * (berth.start[0] + berth.end[0]), 0.5 * (berth.start[1] + berth.end[1])];
+        plot_ui.text(Text::new(PlotPoint::new(x, y), &berth.name).color(style.sensor()).anchor(egui::Align2::RIGHT_CENTER));
+    }
+}
+
+/// Plot of local coordinates labelled in the projected coordinates of a geographic reference
+fn world_axes<'a>(plot: Plot<'a>, reference: &GeoReference) -> Plot<'a> {
+    let scale = reference.unit.to_meters();
+    // Enough decimals to tell the grid marks apart
+    let decimals = move |step: f64| (-(step / scale).log10().floor()).clamp(0.0, 6.0) as usize;
+    let (x_reference, y_reference, hover) = (reference.clone(), reference.clone(), reference.clone());
+    plot.x_axis_label(format!("Easting ({})", reference.unit))
+        .y_axis_label(format!("Northing ({})", reference.unit))
+        .x_axis_formatter(move |mark, _| format!("{:.*}", decimals(mark.step_size), x_reference.to_world(mark.value, 0.0)[0]))
+        .y_axis_formatter(move |mark, _| format!("{:.*}", decimals(mark.step_size), y_reference.to_world(0.0, mark.value)[1]))
+        .label_formatter(move |name, point| {
+            let [easting, northing] = hover.to_world(point.x, point.y);
+            let position = format!("E {:.2}, N {:.2} {}", easting, northing, hover.unit);
+            if name.is_empty() { position } else { format!("{}\n{}", name, position) }
+        })
+}
+
+/// Raster cells colored by their depth below the still water level, land in the land color, shaded by the relief lit from the north-west
+fn relief_image(raster: &DepthRaster, options: &RasterOptions, colormap: Colormap, land: egui::Color32) -> egui::ColorImage {
+    let (horizontal, vertical) = (options.horizontal_unit.to_meters(), options.vertical_unit.to_meters());
+    // Bed elevations above the still water level [m]
+    let elevations: Vec<f64> = raster
+        .values
+        .iter()
+        .map(|&value| if options.positive_depths { -(options.water_level + value) * vertical } else { (value - options.water_level) * vertical })
+        .collect();
+    let deepest = elevations.iter().copied().filter(|elevation| !elevation.is_nan()).fold(0.0, f64::min).abs().max(1e-6);
+    let shading = hillshade(&elevations, raster.ncols, raster.nrows, raster.cell_size * horizontal, 315.0, 45.0);
+    let mut pixels = Vec::with_capacity(raster.ncols * raster.nrows);
+    for j in (0..raster.nrows).rev() {
+        for i in 0..raster.ncols {
+            let n = j * raster.ncols + i;
+            let elevation = elevations[n];
+            let color = if elevation.is_nan() {
+                egui::Color32::TRANSPARENT
+            } else if elevation >= 0.0 {
+                land
+            } else {
+                colormap.color(-elevation / deepest)
+            };
+            pixels.push(color.gamma_multiply((0.4 + 0.6 * shading[n]) as f32));
+        }
+    }
+    egui::ColorImage { size: [raster.ncols, raster.nrows], pixels }
+}
+
+/// Cell values placed on the colormap by a position from 0 to 1; obstacle cells take the land color when masked
+fn field_image(solver: &BasinSolver, values: &[f64], position: impl Fn(f64) -> f64, colormap: Colormap, land: Option<egui::Color32>) -> egui::ColorImage {
+    let grid = solver.grid();
+    let (nx, ny) = (grid.nx(), grid.ny());
+    let mut pixels = Vec::with_capacity(nx * ny);
+    for j in (0..ny).rev() {
+        for i in 0..nx {
+            pixels.push(match land {
+                Some(color) if solver.is_solid(i, j) => color,
+                _ => colormap.color(position(values[grid.index(i, j)])),
+            });
+        }
+    }
+    egui::ColorImage { size: [nx, ny], pixels }
+}
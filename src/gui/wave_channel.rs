@@ -1,8 +1,65 @@
 use eframe::egui;
-use egui_plot::{Line, Plot, PlotPoints};
+use egui_plot::{Bar, BarChart, HLine, Legend, Line, LineStyle, MarkerShape, Plot, PlotImage, PlotPoint, PlotPoints, Points, Polygon, Text, VLine};
+use super::alerts::{AlertCondition, AlertEvent, AlertRule};
+use super::annotations::{AnnotatedPlot, Annotation, AnnotationShape, draw_annotations};
+use super::clipboard::CopyableValues;
+use super::dashboard::DashboardQuantity;
 use super::equations::EquationRenderer;
+use super::help::HelpTopic;
+use super::live_edits::{LiveParameter, ParameterLog};
+use super::gallery::SnapshotGallery;
+use super::performance::PerformanceMonitor;
+use super::plot_buffers::{ChannelSeries, refill};
+use super::measurement::{MeasureTool, SlopeMeasurement, WaveMeasurement, WavelengthReading};
+use super::openfoam::OpenFoamCase;
+use super::plot_style::{Colormap, PlotStyle};
+use super::preferences::UnitSystem;
+use super::project::Project;
+use super::repaint::RepaintPolicy;
+use super::result_cache::{CachedResult, ResultCache};
+use super::results_table::{ResultsTable, TableCell, TableView};
+use super::run_metadata::RunMetadata;
+use super::run_output::WET_DEPTH;
+use super::run_preview::{RunPreview, format_bytes, format_duration};
+use super::timeline::Timeline;
+use super::transects::Transect;
+use crate::analysis::impacts::IMPULSIVE_FORCE_RATIO;
+use crate::analysis::spectral::{peak_frequency, significant_wave_height, variance_density};
+use crate::analysis::{Bispectrum, BoreCriteria, BoundarySignal, BoreTrack, CrestTrack, CrossSpectrum, CustomFormula, DissipationRecord, EnergyBudget, ForceRecord, FrontKind, ImpactEvent, InfragravityAnalysis, InundationRecord, LevelDriftRecord, LoadClass, MeanFlowRecord, OvertoppingTank, PressureSensor, ReflectionAnalysis, Scalogram, ShorelineRecord, Snapshot, SnapshotFile, SnapshotStore, SwashAnalysis, UndertowProfile, WaveGauge, detect_impacts, function_names, infragravity_profile, peak_histogram, track_bores, track_crests, visit_crests, visit_troughs};
+use crate::solver::{
+    AdvectionScheme, AutoRefinement, CrossSection, DissipationMechanism, Grid1D, InitialCondition, Precision, PrecisionComparison, ProfileSegment, ReferenceLevels, RefinementPlan, RefinementZone, RightBoundary,
+    ShallowWaterSolver, SurveyOptions, SurveyProfile, SurveyUnit, TimeIntegration, VegetationField, VerticalDatum, WaveSolver, estimated_runtime,
+    estimated_time_steps,
+};
+use crate::waves::{ActiveAbsorber, AmbientCurrent, BREAKER_INDEX, CurrentProfile, ConverterKind, ConverterResponse, DEFAULT_SEED, DirectionalSpectrum, DirectionalSpreading, DispersionMode, DispersionSolver, ForcingSchedule, HOURS_PER_YEAR, HydrographPoint, GodaPressure, PaddleType, ScheduledWaves, SeaState, StockdonSwash, StormHydrograph, SeededRng, SpectralComponent, SpectrumRecord, StreamFunctionSolver, StreamFunctionWave, TheoryBoundaries, URSELL_CNOIDAL, VelocityCalculator, WaveEnergyConverter, WaveForcing, WaveParameters, WaveSource, WaveSpectrum, WaveTheory, Wavemaker, JONSWAP_GAMMA, NWave, StillWaterComponents, Surge, WATER_DENSITY, WaterLevelBudget, climate_hours, deep_water_wave_height, hunt_run_up, load_spectra, seed_from_clock, ursell_number};
+use crate::error::CoastalError;
+use crate::units::{Length, Time, Velocity};
+use crate::waves::wavemaker::linear_wave_number;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashSet;
 
+/// Simulated time advanced per animation frame [s]
+const ANIMATION_STEP: f64 = 0.05;
+
+/// Departure of the surface from the still water level marking the arrival of a wave [m]
+const ARRIVAL_THRESHOLD: f64 = 2e-3;
+
+/// Simulated time of the runs comparing the solver precisions [s]
+const PRECISION_BENCHMARK_DURATION: f64 = 10.0;
+
+/// Time steps per wave period of the tracer particles of the Stokes drift demonstration
+const TRACER_STEPS_PER_PERIOD: f64 = 50.0;
+
+/// Regular components synthesizing the irregular waves of a boundary spectrum
+const SPECTRUM_COMPONENTS: usize = 48;
+
+/// Stream function waves kept between frames, for the windows of a forcing schedule
+const STREAM_FUNCTION_CACHE: usize = 16;
+
+/// Wave height, period and depth bits, Fourier modes and height steps of a stream function wave
+type StreamFunctionKey = (u64, u64, u64, usize, usize);
+
 // Import wave generation types directly since they are in the same crate
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum WaterDepthRegime {
@@ -21,6 +78,74 @@ impl std::fmt::Display for WaterDepthRegime {
     }
 }
 
+/// How waves are propagated along the channel
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PropagationModel {
+    /// Superposition of analytical linear waves radiated by each source
+    Analytical,
+    /// Numerical shallow water solver on the staggered grid
+    Numerical,
+}
+
+impl std::fmt::Display for PropagationModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PropagationModel::Analytical => write!(f, "Analytical"),
+            PropagationModel::Numerical => write!(f, "Numerical"),
+        }
+    }
+}
+
+/// Source of the wave setup added to the still water level in the total water level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SetupMethod {
+    /// Stockdon et al. (2006) from the deep water wave height, the period and the foreshore slope
+    #[default]
+    Stockdon,
+    /// Mean shoreline elevation of the simulated swash over the statistics window
+    Simulation,
+}
+
+impl std::fmt::Display for SetupMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SetupMethod::Stockdon => write!(f, "Stockdon (2006)"),
+            SetupMethod::Simulation => write!(f, "Simulation"),
+        }
+    }
+}
+
+/// Settings of an additional internal wave source
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceSettings {
+    pub position: f64,    // Source position along the channel [m]
+    pub wave_height: f64, // Wave height (H) [m]
+    pub wave_period: f64, // Wave period (T) [s]
+    pub phase: f64,       // Phase offset [deg]
+}
+
+/// Scalogram of a gauge record rendered to a texture, rebuilt when the record changes
+struct ScalogramView {
+    gauge: usize,           // Index of the analysed gauge
+    position: f64,          // Gauge position when the record was analysed [m]
+    samples: usize,         // Record length when the record was analysed
+    window: f64,            // Statistics window when the record was analysed [s]
+    colormap: Colormap,     // Colormap of the texture
+    scalogram: Scalogram,
+    texture: egui::TextureHandle,
+}
+
+/// Bicoherence of a gauge record rendered to a texture, rebuilt when the record changes
+struct BispectrumView {
+    gauge: usize,           // Index of the analysed gauge
+    position: f64,          // Gauge position when the record was analysed [m]
+    samples: usize,         // Record length when the record was analysed
+    window: f64,            // Statistics window when the record was analysed [s]
+    colormap: Colormap,     // Colormap of the texture
+    bispectrum: Bispectrum,
+    texture: egui::TextureHandle,
+}
+
 pub struct WaveChannelApp {
     pub channel_length: f64,
     pub grid_resolution: usize,
@@ -29,11 +154,130 @@ pub struct WaveChannelApp {
     pub wave_height: f64,            // Wave height (H)
     pub wave_period: f64,            // Wave period (T)
     pub number_of_waves: usize,      // Number of waves to generate
+    pub forcing_schedule: ForcingSchedule, // Wave conditions taking over from the wavemaker waves during the run
+    pub storm_hydrograph: Option<StormHydrograph>, // Sea state and surge through the run, replacing the wavemaker waves and schedule
+    pub hydrograph_path: String,     // Hydrograph file to import
+    hydrograph_status: Option<String>, // Outcome of the last hydrograph import
+    pub boundary_spectrum: Option<WaveSpectrum>, // Irregular waves replacing the wavemaker waves and schedule, unless a storm hydrograph is set
+    pub boundary_spreading: Option<DirectionalSpreading>, // Directional spreading of the boundary spectrum, None for long-crested waves down the flume
+    pub spectrum_path: String,       // Met-ocean file to import spectra from
+    spectrum_records: Vec<SpectrumRecord>, // Spectra of the last imported file
+    spectrum_status: Option<String>, // Outcome of the last spectrum import
+    pub boundary_signal: Option<BoundarySignal>, // Surface elevation prescribed at the wavemaker, replacing the spectrum, wavemaker waves and schedule unless a storm hydrograph is set
+    pub signal_source: String,       // Expression η(t) entered for the boundary signal
+    pub signal_path: String,         // Time series file to import as the boundary signal
+    signal_status: Option<String>,   // Outcome of the last boundary signal change
+    pub openfoam_path: String,       // Directory of the exported OpenFOAM case
+    openfoam_status: Option<String>, // Outcome of the last OpenFOAM export
+    pub paddle_type: Option<PaddleType>, // Emulated paddle, None to prescribe H and T directly
+    pub paddle_stroke: f64,          // Paddle stroke (S)
+    pub wave_theory: Option<WaveTheory>, // Theory of the generated regular waves, None for the recommended one
+    pub stream_function_harmonics: usize, // Fourier modes of the stream function waves
+    pub stream_function_steps: usize,    // Wave height steps of the stream function solver
+    pub additional_sources: Vec<SourceSettings>, // Internal wave sources besides the wavemaker
+    pub initial_condition: Option<InitialCondition>, // Initial-value scenario, None to drive the channel with the wavemaker
+    pub gauges: Vec<WaveGauge>,         // Virtual wave gauges recording the surface elevation
+    pub analysis_gauge: usize,          // Gauge shown in the infragravity analysis
+    pub cross_spectrum_gauges: [usize; 2], // Gauge pair compared in the cross-spectral analysis
+    pub pressure_sensors: Vec<PressureSensor>, // Virtual pressure sensors on the structure faces
+    pub wall_force: ForceRecord,        // Wave force on the downstream wall, recorded with a wall and the numerical solver
+    pub impact_threshold: f64,          // Impact detection level as a fraction of the largest wall force
+    pub shoreline: ShorelineRecord,     // Wet/dry front on the beach, recorded with the numerical solver over a cross-section
+    pub mean_flow: MeanFlowRecord,      // Time averages of the flow over the statistics window, recorded with the numerical solver
+    pub inundation: InundationRecord,   // Highest water, deepest flooding and wave arrival along the channel, recorded with the numerical solver over land
+    pub dissipation: DissipationRecord, // Wave energy dissipated by each mechanism, recorded with the numerical solver
+    pub level_drift: LevelDriftRecord,  // Rise of the mean water level in the channel, recorded with the numerical solver
+    pub gauge_sampling_interval: f64,   // Interval between gauge samples [s]
+    pub snapshot_interval: f64,         // Interval between stored surface snapshots [s]
+    pub stream_snapshots: bool,         // Also write every snapshot to a memory-mapped file for full-resolution playback
+    pub statistics_window: f64,         // Length of the end of the gauge records used in the analyses, 0 for the whole record [s]
+    pub mean_flow_periods: f64,         // Wave periods at the end of the run over which the mean flow is averaged, 0 for the statistics window
+    pub random_seed: u64,               // Seed of every stochastic feature, stored with the scenario so runs are reproducible
+    pub height_ramp: f64,               // Time over which a wave height changed during a run is reached [s]
     pub open_tooltips: HashSet<String>, // Track which tooltips are currently open
+    pub help_mode: bool,                // Explain controls in the help pane instead of popups
+    pub help_topic: Option<HelpTopic>,  // Control last hovered or focused while in help mode
+    pub unit_system: UnitSystem,        // Units of the computed lengths and speeds, from the user preferences
+    pub plot_style: PlotStyle,          // Colors and lines of the plots, from the user preferences
+    pub repaint_policy: RepaintPolicy,  // Frame rate of the animations, from the user preferences
+
+    // Solver settings
+    pub propagation_model: PropagationModel, // Analytical superposition or numerical solver
+    pub dispersion_mode: DispersionMode,     // Vertical layers of the non-hydrostatic solver
+    pub right_boundary: RightBoundary,       // Condition at the downstream end
+    pub active_absorption: bool,             // Absorb reflected waves at the wavemaker
+    pub mass_compensation: bool,             // Compensate the net mass brought in by the wavemaker waves
+    pub ambient_current: Option<AmbientCurrent>, // Steady background flow on which the waves ride
+    precision_status: Option<Result<PrecisionComparison, String>>, // Outcome of the last comparison of the precisions
+    pub advection_scheme: AdvectionScheme,   // Reconstruction of the advected depths and velocities
+    pub time_integration: TimeIntegration,   // Integrator of the continuity and momentum equations
+    pub vegetation: Option<VegetationField>, // Vegetation patch damping waves by stem drag
+    pub cross_section: Option<CrossSection>, // Composite bed profile, None for a flat bottom
+    pub overtopping_tank: Option<OvertoppingTank>, // Tank collecting the water over the crest, None to let it flow on
+    pub energy_converter: Option<WaveEnergyConverter>, // Wave energy converter assessed in the wavemaker waves
+    pub wave_climate: Vec<SeaState>,         // Sea states at the site and their hours per year, for the annual energy
+    pub grid_refinement: Vec<RefinementZone>, // Zones of finer cells of the numerical grid
+    pub auto_refinement: AutoRefinement,     // Target resolutions of the automatic grid refinement
+    pub datum: VerticalDatum,                // Reference of the entered and displayed elevations
+    pub reference_levels: ReferenceLevels,   // Datum and still water levels relative to mean sea level
+    pub water_levels: StillWaterComponents,  // Tide and surge of the flood assessment, relative to mean sea level
+    pub setup_method: SetupMethod,           // Source of the wave setup in the total water level
+    pub surge_path: String,                  // Surge time series file to import
+    surge_status: Option<String>,            // Outcome of the last surge import
+    pub custom_formulas: Vec<CustomFormula>, // Empirical relationships of the user, shown with the computed values
+    formula_name: String,                    // Name of the formula being entered
+    formula_source: String,                  // Expression of the formula being entered
+    formula_unit: String,                    // Unit of the formula being entered
+    formula_status: Option<String>,          // Why the last formula could not be added
+    pub annotations: Vec<Annotation>,        // Notes of the user on the channel and gauge record plots
+    pub transects: Vec<Transect>,            // Cross-shore profiles along the coast run with the scenario
+    pub dashboard: Vec<DashboardQuantity>,   // Quantities pinned to the dashboard, in their order
+    pub alerts: Vec<AlertRule>,              // Thresholds on quantities watched during the run
+    pub alert_log: Vec<AlertEvent>,          // Alerts triggered during the current run, in time order
+    alert_draft: AlertRule,                  // Alert rule being entered
+    annotation_draft: Annotation,            // Annotation being entered
+    pub measure_tool: MeasureTool,           // Measurement picked by clicking on the plots
+    measure_points: Vec<[f64; 2]>,           // Points picked for the measurement in progress
+    pub measurement: Option<Result<String, String>>, // Last measurement, or why it failed
+    pub survey_path: String,                 // Survey file imported as the bed profile
+    pub survey_datum: VerticalDatum,         // Datum of the elevations of the survey file
+    pub survey_options: SurveyOptions,       // Units and interpolation of the imported survey
+    survey_status: Option<String>,           // Outcome of the last survey import
+    gauge_table: TableView,                  // Sorting and filtering of the gauge statistics
+    computed_values: CopyableValues,         // Lines of the Computed Values block shown last, copied from its heading
+    series: ChannelSeries,                   // Surface and bed of the channel view, refilled every frame
     
     // Wave simulation state
     pub simulation_time: f64,
     pub simulation_running: bool,
+    pub solver: Option<ShallowWaterSolver>, // Numerical solver state, built on first step
+    pub engine: Option<Box<dyn WaveSolver>>, // Engine run instead of the built-in numerical solver, e.g. a third-party solver
+    pub snapshots: SnapshotStore,           // Surface history for playback, decimated for long runs
+    pub timeline: Timeline,                 // Playback cursor and loop range over the snapshots
+    snapshot_file: Option<SnapshotFile>,    // Every snapshot of the run on disk, when streaming
+    pub show_linear_overlay: bool,          // Overlay the linear-theory surface on the numerical solution
+    pub show_stream_function_overlay: bool, // Overlay the stream function surface on the numerical solution
+    pub show_grid_lines: bool,              // Draw the faces of the numerical grid under the channel
+    pub fill_water: bool,                   // Fill the water between the bed and the surface, and the sky above it
+    pub show_foam: bool,                    // Color the surface white on breaking fronts
+    pub show_extrema: bool,                 // Mark the crests and troughs of the shown surface, with the wavelength overlay
+    pub gallery: SnapshotGallery,           // Captures of the channel view for before/after comparisons
+    pub performance: PerformanceMonitor,    // Recent frame and solver timings of the performance overlay
+    pub track_crests: bool,                 // Follow individual wave crests through the snapshots
+    pub detect_bores: bool,                 // Detect bores and hydraulic jumps in the snapshots of the numerical solution
+    pub show_tracers: bool,                 // Animate tracer particles drifting along their orbits under the wavemaker waves
+    pub bore_criteria: BoreCriteria,        // Surface slope and Froude number thresholds of the bore detection
+    pub result_cache: Option<ResultCache>,  // Completed runs stored on disk, None to always recompute
+    pub restored_from_cache: bool,          // Whether the shown results were restored instead of computed
+    pub run_metadata: Option<RunMetadata>,  // Provenance of the current run, None before it starts
+    pub parameter_log: ParameterLog,        // Parameters changed during the current run
+    cache_checked: Option<String>,          // Configuration last looked up in the cache
+    scalogram_view: Option<ScalogramView>,  // Cached wavelet scalogram of the analysis gauge
+    bispectrum_view: Option<BispectrumView>, // Cached bicoherence of the analysis gauge
+    step_cost: Option<(String, f64)>,       // Configuration and measured cost of a step, for the run preview
+    budget_status: Option<String>,          // Outcome of the last placement of the energy budget gauges
+    sensor_status: Option<String>,          // Outcome of the last placement of a pressure sensor array
+    stream_functions: std::cell::RefCell<Vec<(StreamFunctionKey, Result<StreamFunctionWave, CoastalError>)>>, // Solved stream function waves, as the forcing is rebuilt every frame
 }
 
 impl Default for WaveChannelApp {
@@ -53,12 +297,283 @@ impl WaveChannelApp {
             wave_height: 0.5,                              // Default 0.5m wave height
             wave_period: 4.0,                              // Default 4s wave period
             number_of_waves: 50,                           // Default 50 waves
+            forcing_schedule: ForcingSchedule::default(),  // Same waves for the whole run
+            storm_hydrograph: None,
+            hydrograph_path: String::new(),
+            hydrograph_status: None,
+            boundary_spectrum: None,
+            boundary_spreading: None,
+            boundary_signal: None,
+            signal_source: "H / 2 * cos(2 * pi * t / T)".to_string(),
+            signal_path: "signal.csv".to_string(),
+            signal_status: None,
+            spectrum_path: String::new(),
+            spectrum_records: Vec::new(),
+            spectrum_status: None,
+            openfoam_path: "olaflow_case".to_string(),
+            openfoam_status: None,
+            paddle_type: None,                             // Prescribe H and T directly
+            paddle_stroke: 0.5,                            // Default 0.5m paddle stroke
+            wave_theory: None,                             // Recommended theory
+            stream_function_harmonics: 16,
+            stream_function_steps: 4,
+            additional_sources: Vec::new(),                // Wavemaker only
+            initial_condition: None,                       // Wavemaker scenario
+            gauges: Vec::new(),                            // No gauges placed
+            analysis_gauge: 0,                             // First gauge
+            cross_spectrum_gauges: [0, 1],                 // First two gauges
+            pressure_sensors: Vec::new(),                  // No sensors placed
+            wall_force: ForceRecord::default(),
+            impact_threshold: 0.5,                         // Half the largest force
+            shoreline: ShorelineRecord::default(),
+            dissipation: DissipationRecord::default(),
+            level_drift: LevelDriftRecord::default(),
+            mean_flow: MeanFlowRecord::default(),
+            inundation: InundationRecord::default(),
+            gauge_sampling_interval: 0.05,                 // Every animation step
+            snapshot_interval: 0.05,                       // Every animation step
+            stream_snapshots: false,                       // Memory only
+            statistics_window: 0.0,                        // Whole record
+            mean_flow_periods: 0.0,                        // Statistics window
+            height_ramp: 5.0,                              // A few waves
+            random_seed: DEFAULT_SEED,                     // Same realization in every session
             open_tooltips: HashSet::new(),                 // Initialize empty tooltip set
+            help_mode: false,
+            help_topic: None,
+            unit_system: UnitSystem::Metric,
+            plot_style: PlotStyle::default(),
+            repaint_policy: RepaintPolicy::default(),
+
+            // Solver settings
+            propagation_model: PropagationModel::Analytical,
+            dispersion_mode: DispersionMode::OneLayer,
+            right_boundary: RightBoundary::default(),      // Sponge layer
+            active_absorption: false,
+            mass_compensation: false,
+            ambient_current: None,
+            precision_status: None,
+            advection_scheme: AdvectionScheme::Upwind,
+            time_integration: TimeIntegration::ForwardBackward,
+            vegetation: None,                              // Bare bed
+            cross_section: None,                           // Flat bottom
+            overtopping_tank: None,                        // Water flows on behind the crest
+            energy_converter: None,                        // No device placed
+            wave_climate: Vec::new(),
+            grid_refinement: Vec::new(),                   // Uniform grid
+            auto_refinement: AutoRefinement::default(),
+            datum: VerticalDatum::ChannelBed,              // Elevations above the bed at the wavemaker
+            reference_levels: ReferenceLevels::default(),  // Every datum at mean sea level
+            water_levels: StillWaterComponents::default(), // Mean sea level, without surge
+            setup_method: SetupMethod::Stockdon,
+            surge_path: String::new(),
+            surge_status: None,
+            custom_formulas: Vec::new(),
+            formula_name: String::new(),
+            formula_source: String::new(),
+            formula_unit: String::new(),
+            formula_status: None,
+            annotations: Vec::new(),
+            transects: Vec::new(),
+            dashboard: Vec::new(),
+            alerts: Vec::new(),
+            alert_log: Vec::new(),
+            alert_draft: AlertRule::new(DashboardQuantity::Courant, AlertCondition::Above, 0.9, false),
+            annotation_draft: Annotation::label(AnnotatedPlot::Channel, [0.0, 0.0], ""),
+            measure_tool: MeasureTool::Off,
+            measure_points: Vec::new(),
+            measurement: None,
+            survey_path: "survey.csv".to_string(),
+            survey_datum: VerticalDatum::MeanSeaLevel,
+            survey_options: SurveyOptions::default(),
+            survey_status: None,
+            gauge_table: TableView::default(),
+            computed_values: CopyableValues::default(),
+            series: ChannelSeries::new(),
             
             // Wave simulation state
             simulation_time: 0.0,
             simulation_running: false,
+            solver: None,
+            engine: None,
+            snapshots: SnapshotStore::default(),          // Last minute at full resolution
+            timeline: Timeline::new(),
+            snapshot_file: None,
+            show_linear_overlay: false,
+            show_stream_function_overlay: false,
+            show_grid_lines: false,
+            fill_water: true,
+            show_foam: true,
+            show_extrema: true,
+            gallery: SnapshotGallery::new(),
+            performance: PerformanceMonitor::new(),
+            track_crests: false,
+            detect_bores: false,
+            show_tracers: false,
+            bore_criteria: BoreCriteria::default(),
+            result_cache: None,                            // Enabled by the platform
+            restored_from_cache: false,
+            run_metadata: None,
+            parameter_log: ParameterLog::new(),
+            cache_checked: None,
+            scalogram_view: None,
+            bispectrum_view: None,
+            step_cost: None,
+            budget_status: None,
+            sensor_status: None,
+            stream_functions: Default::default(),
+        }
+    }
+
+    /// Store completed runs in a result cache
+    pub fn with_result_cache(mut self, cache: ResultCache) -> Self {
+        self.result_cache = Some(cache);
+        self
+    }
+
+    /// Copy of the channel, scenario and solver settings at rest, with the gauges at the same positions
+    pub fn duplicate_scenario(&self) -> Self {
+        let mut duplicate = Self::new();
+        duplicate.load_project(&self.project());
+        duplicate
+    }
+
+    /// Channel, scenario, gauge and solver settings, without the simulation state
+    pub fn project(&self) -> Project {
+        Project {
+            channel_length: self.channel_length,
+            grid_resolution: self.grid_resolution,
+            still_water_level: self.still_water_level,
+            wave_height: self.wave_height,
+            wave_period: self.wave_period,
+            number_of_waves: self.number_of_waves,
+            forcing_schedule: self.forcing_schedule.clone(),
+            storm_hydrograph: self.storm_hydrograph.clone(),
+            boundary_spectrum: self.boundary_spectrum.clone(),
+            boundary_spreading: self.boundary_spreading,
+            boundary_signal: self.boundary_signal.clone(),
+            paddle_type: self.paddle_type,
+            paddle_stroke: self.paddle_stroke,
+            wave_theory: self.wave_theory,
+            stream_function_harmonics: self.stream_function_harmonics,
+            stream_function_steps: self.stream_function_steps,
+            additional_sources: self.additional_sources.clone(),
+            initial_condition: self.initial_condition,
+            gauge_positions: self.gauges.iter().map(WaveGauge::position).collect(),
+            analysis_gauge: self.analysis_gauge,
+            cross_spectrum_gauges: self.cross_spectrum_gauges,
+            pressure_sensor_locations: self.pressure_sensors.iter().map(|sensor| [sensor.position(), sensor.elevation()]).collect(),
+            impact_threshold: self.impact_threshold,
+            bore_criteria: self.bore_criteria,
+            gauge_sampling_interval: self.gauge_sampling_interval,
+            snapshot_interval: self.snapshot_interval,
+            stream_snapshots: self.stream_snapshots,
+            statistics_window: self.statistics_window,
+            mean_flow_periods: self.mean_flow_periods,
+            height_ramp: self.height_ramp,
+            random_seed: self.random_seed,
+            propagation_model: self.propagation_model,
+            dispersion_mode: self.dispersion_mode,
+            right_boundary: self.right_boundary,
+            active_absorption: self.active_absorption,
+            mass_compensation: self.mass_compensation,
+            ambient_current: self.ambient_current,
+            advection_scheme: self.advection_scheme,
+            time_integration: self.time_integration,
+            vegetation: self.vegetation,
+            cross_section: self.cross_section.clone(),
+            tank_plan_length: self.overtopping_tank.as_ref().map(OvertoppingTank::plan_length),
+            energy_converter: self.energy_converter,
+            wave_climate: self.wave_climate.clone(),
+            grid_refinement: self.grid_refinement.clone(),
+            auto_refinement: self.auto_refinement,
+            datum: self.datum,
+            reference_levels: self.reference_levels,
+            water_levels: self.water_levels.clone(),
+            setup_method: self.setup_method,
+            custom_formulas: self.custom_formulas.clone(),
+            annotations: self.annotations.clone(),
+            transects: self.transects.clone(),
+            dashboard: self.dashboard.clone(),
+            alerts: self.alerts.clone(),
+        }
+    }
+
+    /// Replace the settings with those of a project and reset the simulation
+    pub fn load_project(&mut self, project: &Project) {
+        self.apply_project(project);
+        self.reset_simulation();
+    }
+
+    /// Take the settings of a project without resetting the simulation, as when they are edited during a run
+    /// Gauge and pressure sensor records are only cleared if they moved
+    pub fn apply_project(&mut self, project: &Project) {
+        self.channel_length = project.channel_length;
+        self.grid_resolution = project.grid_resolution;
+        self.still_water_level = project.still_water_level;
+        self.wave_height = project.wave_height;
+        self.wave_period = project.wave_period;
+        self.number_of_waves = project.number_of_waves;
+        self.forcing_schedule = project.forcing_schedule.clone();
+        // A hydrograph without points has no sea state to interpolate, e.g. from a hand-edited project file
+        self.storm_hydrograph = project.storm_hydrograph.clone().filter(|hydrograph| !hydrograph.points.is_empty());
+        self.boundary_spectrum = project.boundary_spectrum.clone();
+        self.boundary_spreading = project.boundary_spreading;
+        self.boundary_signal = project.boundary_signal.clone();
+        self.paddle_type = project.paddle_type;
+        self.paddle_stroke = project.paddle_stroke;
+        self.wave_theory = project.wave_theory;
+        self.stream_function_harmonics = project.stream_function_harmonics;
+        self.stream_function_steps = project.stream_function_steps;
+        self.additional_sources = project.additional_sources.clone();
+        self.initial_condition = project.initial_condition;
+        if !self.gauges.iter().map(WaveGauge::position).eq(project.gauge_positions.iter().copied()) {
+            self.gauges = project.gauge_positions.iter().map(|&position| WaveGauge::new(position)).collect();
+        }
+        self.analysis_gauge = project.analysis_gauge;
+        self.cross_spectrum_gauges = project.cross_spectrum_gauges;
+        if !self.pressure_sensors.iter().map(|sensor| [sensor.position(), sensor.elevation()]).eq(project.pressure_sensor_locations.iter().copied()) {
+            self.pressure_sensors = project.pressure_sensor_locations.iter().map(|&[position, elevation]| PressureSensor::new(position, elevation)).collect();
         }
+        self.impact_threshold = project.impact_threshold;
+        self.bore_criteria = project.bore_criteria;
+        self.gauge_sampling_interval = project.gauge_sampling_interval;
+        self.snapshot_interval = project.snapshot_interval;
+        self.stream_snapshots = project.stream_snapshots;
+        self.statistics_window = project.statistics_window;
+        self.mean_flow_periods = project.mean_flow_periods;
+        self.height_ramp = project.height_ramp;
+        self.random_seed = project.random_seed;
+        self.propagation_model = project.propagation_model;
+        self.dispersion_mode = project.dispersion_mode;
+        self.right_boundary = project.right_boundary;
+        self.active_absorption = project.active_absorption;
+        self.mass_compensation = project.mass_compensation;
+        self.ambient_current = project.ambient_current;
+        self.advection_scheme = project.advection_scheme;
+        self.time_integration = project.time_integration;
+        self.vegetation = project.vegetation;
+        self.cross_section = project.cross_section.clone();
+        match (&mut self.overtopping_tank, project.tank_plan_length) {
+            // Resizing the tank keeps its record
+            (Some(tank), Some(plan_length)) => {
+                tank.set_plan_length(plan_length).ok();
+            }
+            (tank, plan_length) => *tank = plan_length.and_then(|plan_length| OvertoppingTank::new(plan_length).ok()),
+        }
+        self.energy_converter = project.energy_converter;
+        self.wave_climate = project.wave_climate.clone();
+        self.grid_refinement = project.grid_refinement.clone();
+        self.auto_refinement = project.auto_refinement;
+        self.datum = project.datum;
+        self.reference_levels = project.reference_levels;
+        self.water_levels = project.water_levels.clone();
+        self.setup_method = project.setup_method;
+        self.custom_formulas = project.custom_formulas.clone();
+        self.annotations = project.annotations.clone();
+        self.transects = project.transects.clone();
+        self.dashboard = project.dashboard.clone();
+        self.alerts = project.alerts.clone();
+        self.update_surface_elevation();
     }
 
     pub fn grid_spacing(&self) -> f64 {
@@ -71,36 +586,25 @@ impl WaveChannelApp {
             self.surface_elevation.resize(self.grid_resolution, 0.0);
         }
         
-        // Generate waves propagating from left to right
-        if self.simulation_running || self.simulation_time > 0.0 {
-            let dx = self.grid_spacing();
-            let wavelength = Self::calculate_wavelength_adaptive(self.wave_period, self.still_water_level, 9.81);
-            let k = 2.0 * std::f64::consts::PI / wavelength;
-            let amplitude = self.wave_height / 2.0;
-            let omega = 2.0 * std::f64::consts::PI / self.wave_period;
-            let celerity = Self::calculate_celerity_adaptive(self.wave_period, self.still_water_level, 9.81);
-            
-            // Duration for generating the specified number of waves
-            let generation_duration = self.number_of_waves as f64 * self.wave_period;
+        let dx = self.grid_spacing();
+        if let Some(solver) = engine_or_solver(&self.engine, &self.solver).filter(|_| self.is_numerical()) {
+            // Sample the numerical solution at the display points
+            for (i, elevation) in self.surface_elevation.iter_mut().enumerate() {
+                *elevation = solver.surface_elevation_at(i as f64 * dx);
+            }
+        } else if let Some(condition) = self.initial_condition {
+            // Initial state before the scenario is released
+            for (i, elevation) in self.surface_elevation.iter_mut().enumerate() {
+                *elevation = condition.surface_elevation(i as f64 * dx, self.still_water_level);
+            }
+        } else if self.propagation_model == PropagationModel::Analytical
+            && (self.simulation_running || self.simulation_time > 0.0)
+        {
+            // Superpose the waves radiated by every source
+            let forcing = self.wave_forcing();
             
             for (i, elevation) in self.surface_elevation.iter_mut().enumerate() {
-                let x = i as f64 * dx;
-                
-                // Wave generation: create waves at left boundary for the specified duration
-                // Wave propagation: waves continue to exist and propagate after generation stops
-                
-                // Time when the wave at position x would have been generated
-                let wave_generation_time = self.simulation_time - x / celerity;
-                
-                // Only show waves if:
-                // 1. The wave was generated within the generation period (wave_generation_time >= 0 and <= generation_duration)
-                // 2. The wave has had time to reach this position (self.simulation_time >= x / celerity)
-                if wave_generation_time >= 0.0 && wave_generation_time <= generation_duration && self.simulation_time >= x / celerity {
-                    let phase = k * x - omega * self.simulation_time;
-                    *elevation = amplitude * phase.cos();
-                } else {
-                    *elevation = 0.0;
-                }
+                *elevation = forcing.surface_elevation(i as f64 * dx);
             }
         } else {
             // Still water when not started
@@ -113,271 +617,5082 @@ impl WaveChannelApp {
     /// Start or resume wave simulation
     pub fn start_simulation(&mut self) {
         self.simulation_running = true;
+        self.timeline.follow_live();
     }
     
     /// Pause wave simulation
     pub fn pause_simulation(&mut self) {
         self.simulation_running = false;
     }
+
+    /// Pause a running simulation, or start it, from the beginning once complete
+    pub fn toggle_simulation(&mut self) {
+        if self.simulation_running {
+            self.pause_simulation();
+        } else {
+            if self.is_simulation_complete() {
+                self.reset_simulation();
+            }
+            self.start_simulation();
+        }
+    }
+
+    /// Tsunami inundation: a leading depression N-wave entering a long channel over a flat offshore bed,
+    /// shoaling up a 1:20 beach and flooding a 1:200 coastal plain backed by a hill, with gauges offshore,
+    /// at the toe of the beach and at the shoreline for the arrival times
+    pub fn load_tsunami_preset(&mut self) {
+        self.propagation_model = PropagationModel::Numerical;
+        self.initial_condition = None;
+        self.channel_length = 200.0;
+        self.grid_resolution = 2000;
+        self.grid_refinement.clear();
+        self.still_water_level = 1.0;
+        let wave = NWave { height: 0.05, period: 20.0, leading_depression: true };
+        self.wave_height = wave.height;
+        self.wave_period = wave.period;
+        self.number_of_waves = 1;
+        self.paddle_type = None;
+        self.storm_hydrograph = None;
+        self.boundary_spectrum = None;
+        self.boundary_spreading = None;
+        self.boundary_signal = wave.signal().ok();
+        self.signal_status = Some(format!("N-wave of {:.2} m and {:.0} s", wave.height, wave.period));
+        self.forcing_schedule = ForcingSchedule::default();
+        self.additional_sources.clear();
+        self.cross_section = Some(CrossSection {
+            segments: vec![
+                ProfileSegment::flat("Offshore", 100.0),
+                ProfileSegment::slope("Beach", 21.0, 20.0),
+                ProfileSegment::slope("Coastal Plain", 60.0, 200.0),
+                ProfileSegment::slope("Hill", 19.0, 10.0),
+            ],
+        });
+        self.vegetation = None;
+        self.overtopping_tank = None;
+        // The hill stops the flooding before the end of the channel; the wavemaker absorbs the reflected wave
+        self.right_boundary = RightBoundary::Wall;
+        self.active_absorption = true;
+        self.gauges = vec![WaveGauge::new(50.0), WaveGauge::new(100.0), WaveGauge::new(120.0)];
+        self.statistics_window = 0.0;
+        self.reset_simulation();
+    }
+
+    /// Add a gauge in the middle of the channel
+    pub fn add_gauge(&mut self) {
+        self.gauges.push(WaveGauge::new(self.channel_length / 2.0));
+    }
+
+    /// Replace the gauges by ten gauges spread evenly along the channel
+    pub fn add_gauge_array(&mut self) {
+        let count = 10;
+        self.gauges = (0..count)
+            .map(|i| WaveGauge::new((i as f64 + 0.5) * self.channel_length / count as f64))
+            .collect();
+    }
+
+    /// Add a pressure sensor on the bed in the middle of the structure, or of the channel without one
+    pub fn add_pressure_sensor(&mut self) {
+        let position = self.structure_extent().map_or(0.5 * self.channel_length, |(start, end)| 0.5 * (start + end));
+        self.pressure_sensors.push(PressureSensor::new(position, self.bed_elevation(position)));
+    }
+
+    /// Replace the pressure sensors by five on the downstream wall, from the bed to 1.5 wave heights above
+    /// the still water level, where the Goda design pressure vanishes
+    pub fn add_wall_sensor_array(&mut self) -> Result<(), String> {
+        if self.right_boundary != RightBoundary::Wall {
+            return Err("Wall sensors need a wall at the downstream end".to_string());
+        }
+        let bed = self.bed_elevation(self.channel_length);
+        let top = self.still_water_level + 1.5 * self.wave_height;
+        if top <= bed {
+            return Err("The wall stands above the reach of the waves".to_string());
+        }
+        let count = 5;
+        self.pressure_sensors = (0..count)
+            .map(|i| PressureSensor::new(self.channel_length, bed + (top - bed) * i as f64 / (count - 1) as f64))
+            .collect();
+        Ok(())
+    }
+
+    /// Replace the pressure sensors by five on the seaward face of the cross-section, evenly spaced from its toe to its crest
+    pub fn add_slope_sensor_array(&mut self) -> Result<(), String> {
+        let section = self.cross_section.as_ref().ok_or("Slope sensors need a cross-section")?;
+        let crest = section.crest_elevation();
+        let breakpoints = section.breakpoints();
+        let toe = breakpoints.windows(2).find(|pair| pair[1][1] > pair[0][1]).map(|pair| pair[0][0]);
+        let top = breakpoints.iter().find(|point| point[1] >= crest - 1e-9).map(|point| point[0]);
+        let (Some(toe), Some(top)) = (toe, top) else {
+            return Err("The cross-section has no rising face".to_string());
+        };
+        let count = 5;
+        self.pressure_sensors = (0..count)
+            .map(|i| {
+                let position = toe + (top - toe) * i as f64 / (count - 1) as f64;
+                PressureSensor::new(position, self.bed_elevation(position))
+            })
+            .collect();
+        Ok(())
+    }
     
     /// Reset wave simulation to initial state
     pub fn reset_simulation(&mut self) {
         self.simulation_running = false;
+        self.restored_from_cache = false;
+        self.run_metadata = None;
         self.simulation_time = 0.0;
+        self.solver = None;
+        if let Some(engine) = &mut self.engine {
+            engine.reset();
+        }
+        self.parameter_log.clear();
+        self.alert_log.clear();
+        self.alerts.iter_mut().for_each(AlertRule::rearm);
+        self.performance.clear_solver();
+        self.gauges.iter_mut().for_each(WaveGauge::clear);
+        self.pressure_sensors.iter_mut().for_each(PressureSensor::clear);
+        self.wall_force.clear();
+        self.shoreline.clear();
+        self.dissipation.clear();
+        self.level_drift.clear();
+        self.mean_flow = MeanFlowRecord::default();
+        self.inundation = InundationRecord::default();
+        if let Some(tank) = &mut self.overtopping_tank {
+            tank.clear();
+        }
+        self.clear_snapshots();
         self.update_surface_elevation();
     }
     
     /// Advance simulation by one time step
     pub fn advance_simulation(&mut self, dt: f64) {
         if self.simulation_running {
-            self.simulation_time += dt;
+            self.parameter_log.observe(self.simulation_time, &self.live_values());
+            if self.is_numerical() {
+                self.advance_solver(dt);
+            } else {
+                self.simulation_time += dt;
+            }
             self.update_surface_elevation();
-            
-            // Calculate when to stop: generation time + time for last wave to cross channel
-            let generation_duration = self.number_of_waves as f64 * self.wave_period;
-            let celerity = Self::calculate_celerity_adaptive(self.wave_period, self.still_water_level, 9.81);
-            let crossing_time = self.channel_length / celerity;
-            let total_simulation_time = generation_duration + crossing_time;
+            self.record_gauges();
+            self.record_snapshot();
+            self.check_alerts();
+            if self.run_metadata.is_none() {
+                self.run_metadata = Some(RunMetadata::new(&self.configuration_key(), self.solver_description(), self.random_seed));
+            }
             
             // Auto-stop when all waves have been generated and propagated across
-            if self.simulation_time >= total_simulation_time {
+            if self.simulation_time >= self.total_simulation_time() {
                 self.simulation_running = false;
+                if let Some(metadata) = &mut self.run_metadata {
+                    metadata.complete();
+                }
+                self.store_result();
             }
         }
     }
     
-    /// Get simulation progress as percentage (0.0 to 1.0)
-    pub fn simulation_progress(&self) -> f64 {
-        let generation_duration = self.number_of_waves as f64 * self.wave_period;
-        let celerity = Self::calculate_celerity_adaptive(self.wave_period, self.still_water_level, 9.81);
-        let crossing_time = self.channel_length / celerity;
-        let total_simulation_time = generation_duration + crossing_time;
-        
-        if total_simulation_time <= 0.0 {
-            return 0.0;
+    /// Check the alert rules after a step, logging the alerts triggered and pausing the run if their rule asks to
+    pub fn check_alerts(&mut self) {
+        let mut rules = std::mem::take(&mut self.alerts);
+        for rule in &mut rules {
+            if let Some(value) = rule.check(self) {
+                self.alert_log.push(AlertEvent {
+                    time: self.simulation_time,
+                    rule: rule.describe(self),
+                    value,
+                    unit: rule.quantity.unit(self),
+                    paused: rule.pause,
+                });
+                if rule.pause {
+                    self.simulation_running = false;
+                }
+            }
         }
-        
-        (self.simulation_time / total_simulation_time).min(1.0)
+        self.alerts = rules;
     }
-    
-    /// Check if simulation is complete
-    pub fn is_simulation_complete(&self) -> bool {
-        let generation_duration = self.number_of_waves as f64 * self.wave_period;
-        let celerity = Self::calculate_celerity_adaptive(self.wave_period, self.still_water_level, 9.81);
-        let crossing_time = self.channel_length / celerity;
-        let total_simulation_time = generation_duration + crossing_time;
-        
-        self.simulation_time >= total_simulation_time
+
+    /// Description of everything that determines the stored simulation outputs, used as cache key
+    /// A setting changing what a run records belongs here, and the version prefix changes with the outputs stored
+    pub fn configuration_key(&self) -> String {
+        let gauge_positions: Vec<f64> = self.gauges.iter().map(WaveGauge::position).collect();
+        let sensor_locations: Vec<[f64; 2]> = self.pressure_sensors.iter().map(|sensor| [sensor.position(), sensor.elevation()]).collect();
+        let engine = self.engine.as_ref().map(|engine| format!(" engine {}", engine.engine_name())).unwrap_or_default();
+        format!(
+            "v2 {:?}{}",
+            (
+                (self.channel_length, self.grid_resolution, &self.grid_refinement, self.still_water_level, &self.cross_section),
+                (self.wave_height, self.wave_period, self.number_of_waves, self.paddle_type, self.paddle_stroke, &self.forcing_schedule, &self.storm_hydrograph, &self.boundary_spectrum, &self.boundary_signal),
+                (self.boundary_spreading, self.generation_theory(), self.stream_function_harmonics, self.stream_function_steps),
+                (&self.additional_sources, self.initial_condition, gauge_positions, sensor_locations),
+                (self.gauge_sampling_interval, self.snapshot_interval, self.statistics_window, self.mean_flow_periods, self.random_seed, self.parameter_log.changes(), self.height_ramp),
+                (self.propagation_model, self.dispersion_mode, self.right_boundary, self.active_absorption, self.mass_compensation, self.ambient_current, self.vegetation, self.advection_scheme, self.time_integration),
+                self.overtopping_tank.as_ref().map(OvertoppingTank::plan_length),
+            ),
+            engine
+        )
     }
 
-    fn is_tooltip_open(&self, tooltip_id: &str) -> bool {
-        self.open_tooltips.contains(tooltip_id)
+    /// Propagation model and solver options, as recorded in the run metadata
+    pub fn solver_description(&self) -> String {
+        if !self.is_numerical() {
+            return "Analytical linear superposition".to_string();
+        }
+        if let Some(engine) = &self.engine {
+            return format!("Numerical {} (external engine)", engine.engine_name());
+        }
+        let mut options = vec![match self.solver_non_hydrostatic() {
+            Some(mode) => format!("non-hydrostatic {}", mode),
+            None => "hydrostatic".to_string(),
+        }];
+        options.push(format!("right boundary {}", self.right_boundary));
+        if self.active_absorption {
+            options.push("active absorption".to_string());
+        }
+        if self.mass_compensation {
+            options.push("mass compensation".to_string());
+        }
+        if let Some(current) = self.ambient_current {
+            options.push(format!("{} current {:+.2} m/s", current.profile.to_string().to_lowercase(), current.velocity));
+        }
+        if self.vegetation.is_some() {
+            options.push("vegetation".to_string());
+        }
+        if self.tank_position().is_ok() {
+            options.push("overtopping tank".to_string());
+        }
+        if !self.grid_refinement.is_empty() {
+            options.push("stretched grid".to_string());
+        }
+        if self.advection_scheme != AdvectionScheme::Upwind {
+            options.push(format!("{} advection", self.advection_scheme));
+        }
+        if self.time_integration != TimeIntegration::ForwardBackward {
+            options.push(format!("{} time integration", self.time_integration));
+        }
+        format!("Numerical shallow water ({})", options.join(", "))
     }
 
-    fn toggle_tooltip(&mut self, tooltip_id: &str) {
-        if self.open_tooltips.contains(tooltip_id) {
-            self.open_tooltips.remove(tooltip_id);
-        } else {
-            self.open_tooltips.insert(tooltip_id.to_string());
+    /// Random generator at the start of the sequence of the scenario seed
+    /// Stochastic features draw from it, or from its numbered streams, so a seed reproduces the run exactly
+    pub fn rng(&self) -> SeededRng {
+        SeededRng::new(self.random_seed)
+    }
+
+    /// Store the outputs of a completed run in the result cache
+    /// Runs of an external engine are not stored, as its own settings are not part of the configuration key
+    fn store_result(&mut self) {
+        let Some(cache) = self.result_cache.as_ref().filter(|_| self.engine.is_none()) else {
+            return;
+        };
+        let result = CachedResult {
+            configuration: self.configuration_key(),
+            simulation_time: self.simulation_time,
+            surface_elevation: self.surface_elevation.clone(),
+            gauges: self.gauges.clone(),
+            pressure_sensors: self.pressure_sensors.clone(),
+            wall_force: self.wall_force.clone(),
+            overtopping_tank: self.overtopping_tank.clone(),
+            shoreline: self.shoreline.clone(),
+            mean_flow: self.mean_flow.clone(),
+            inundation: self.inundation.clone(),
+            dissipation: self.dissipation.clone(),
+            level_drift: self.level_drift.clone(),
+            snapshots: self.snapshots.clone(),
+            metadata: self.run_metadata.clone(),
+        };
+        if let Err(message) = cache.store(&result) {
+            eprintln!("Failed to cache simulation result: {}", message);
         }
+        self.cache_checked = Some(result.configuration);
     }
 
-    fn close_tooltip(&mut self, tooltip_id: &str) {
-        self.open_tooltips.remove(tooltip_id);
+    /// Show the cached results of the current configuration, if it was run before
+    /// Each configuration is looked up once, so a reset case can be run again
+    pub fn restore_cached_result(&mut self) -> bool {
+        if self.simulation_running {
+            return false;
+        }
+        let Some(cache) = self.result_cache.as_ref().filter(|_| self.engine.is_none()) else {
+            return false;
+        };
+        let configuration = self.configuration_key();
+        if self.cache_checked.as_ref() == Some(&configuration) {
+            return false;
+        }
+        let result = cache.load(&configuration);
+        self.cache_checked = Some(configuration);
+        let Some(result) = result else {
+            return false;
+        };
+
+        self.solver = None;
+        self.simulation_time = result.simulation_time;
+        self.surface_elevation = result.surface_elevation;
+        self.gauges = result.gauges;
+        self.pressure_sensors = result.pressure_sensors;
+        self.wall_force = result.wall_force;
+        self.overtopping_tank = result.overtopping_tank;
+        self.shoreline = result.shoreline;
+        self.mean_flow = result.mean_flow;
+        self.inundation = result.inundation;
+        self.dissipation = result.dissipation;
+        self.level_drift = result.level_drift;
+        self.clear_snapshots();
+        self.snapshots = result.snapshots;
+        self.run_metadata = result.metadata;
+        self.restored_from_cache = true;
+        true
     }
 
-    fn info_button(&mut self, ui: &mut egui::Ui, tooltip_id: &str, tooltip_text: &str) {
-        ui.add_space(5.0);
-        let button_response = ui.small_button("?");
-        
-        if button_response.clicked() {
-            self.toggle_tooltip(tooltip_id);
+    /// Store the surface for playback once per snapshot interval, streaming it to disk if enabled
+    fn record_snapshot(&mut self) {
+        let snapshot_due = self.snapshots.end_time().is_none_or(|last| self.simulation_time - last >= self.snapshot_interval - 1e-9);
+        if !snapshot_due {
+            return;
         }
-        
-        if self.is_tooltip_open(tooltip_id) {
-            let popup_id = egui::Id::new(format!("tooltip_{}", tooltip_id));
-            let area_response = egui::Area::new(popup_id)
-                .fixed_pos(button_response.rect.right_top() + egui::vec2(5.0, 0.0))
-                .show(ui.ctx(), |ui| {
-                    egui::Frame::popup(ui.style())
-                        .inner_margin(egui::Margin::same(8))
-                        .show(ui, |ui| {
-                            ui.set_max_width(300.0);
-                            ui.vertical(|ui| {
-                                ui.horizontal(|ui| {
-                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
-                                        if ui.small_button("✖").clicked() {
-                                            self.close_tooltip(tooltip_id);
-                                        }
-                                    });
-                                });
-                                ui.label(tooltip_text);
-                            });
-                        });
-                });
-            
-            // Check for click outside to close tooltip
-            if ui.input(|i| i.pointer.any_click()) && !area_response.response.hovered() && !button_response.hovered() {
-                self.close_tooltip(tooltip_id);
+        self.snapshots.record(self.simulation_time, &self.surface_elevation);
+
+        // A new file when streaming starts or the grid changes
+        if !self.stream_snapshots || self.snapshot_file.as_ref().is_some_and(|file| file.points() != self.grid_resolution) {
+            self.snapshot_file = None;
+        }
+        if self.stream_snapshots && self.snapshot_file.is_none() {
+            match SnapshotFile::temporary(self.grid_resolution) {
+                Ok(file) => self.snapshot_file = Some(file),
+                Err(message) => {
+                    eprintln!("Failed to stream snapshots: {}", message);
+                    self.stream_snapshots = false;
+                    return;
+                }
             }
         }
+        if let Some(file) = &mut self.snapshot_file
+            && let Err(message) = file.record(self.simulation_time, &self.surface_elevation)
+        {
+            eprintln!("Failed to stream snapshots: {}", message);
+            self.snapshot_file = None;
+            self.stream_snapshots = false;
+        }
     }
 
-    fn equation_info_button(&mut self, ui: &mut egui::Ui, ctx: &egui::Context, equation_renderer: &mut EquationRenderer, tooltip_id: &str, equation_id: &str, text_parts: (&str, &str)) {
-        ui.add_space(5.0);
-        let button_response = ui.small_button("?");
-        
-        if button_response.clicked() {
-            self.toggle_tooltip(tooltip_id);
+    /// Discard the stored snapshots, removing the streamed file, and return to the live view
+    fn clear_snapshots(&mut self) {
+        self.snapshots.clear();
+        self.snapshot_file = None;
+        self.timeline = Timeline::new();
+    }
+
+    /// Get file of the streamed snapshots, None when not streaming
+    pub fn snapshot_file(&self) -> Option<&SnapshotFile> {
+        self.snapshot_file.as_ref()
+    }
+
+    /// Make the streamed snapshots readable
+    fn sync_snapshot_file(&mut self) {
+        if let Some(file) = &mut self.snapshot_file
+            && let Err(message) = file.sync()
+        {
+            eprintln!("Failed to read streamed snapshots: {}", message);
+            self.snapshot_file = None;
         }
-        
-        if self.is_tooltip_open(tooltip_id) {
-            let popup_id = egui::Id::new(format!("tooltip_{}", tooltip_id));
-            let area_response = egui::Area::new(popup_id)
-                .fixed_pos(button_response.rect.right_top() + egui::vec2(5.0, 0.0))
-                .show(ui.ctx(), |ui| {
-                    egui::Frame::popup(ui.style())
-                        .inner_margin(egui::Margin::same(8))
-                        .show(ui, |ui| {
-                            ui.set_max_width(450.0);
-                            ui.vertical(|ui| {
-                                // Close button at the top right
-                                ui.horizontal(|ui| {
-                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
-                                        if ui.small_button("✖").clicked() {
-                                            self.close_tooltip(tooltip_id);
-                                        }
-                                    });
-                                });
-                                
-                                // Show text before equation
-                                if !text_parts.0.is_empty() {
-                                    ui.label(text_parts.0);
-                                }
-                                
-                                // Show the equation inline with text
-                                if let Err(e) = equation_renderer.load_equation_texture(ctx, equation_id) {
-                                    eprintln!("Failed to load equation texture for {}: {}", equation_id, e);
-                                    ui.label(format!("[Equation {} failed to load]", equation_id));
-                                } else if let Some(texture) = equation_renderer.get_texture(equation_id) {
-                                    let size = texture.size_vec2();
-                                    
-                                    // Scale equation to match current font size
-                                    let font_size = ui.text_style_height(&egui::TextStyle::Body);
-                                    let base_equation_height = 12.0; // Base height from LaTeX template (12pt)
-                                    let font_scale = font_size / base_equation_height;
-                                    
-                                    // Apply font scaling with additional reduction factor for better text matching
-                                    let font_scaled_size = size * font_scale * 0.15;
-                                    let max_width = ui.available_width().min(400.0);
-                                    let width_scale = if font_scaled_size.x > max_width {
-                                        max_width / font_scaled_size.x
-                                    } else {
-                                        1.0
-                                    };
-                                    let display_size = font_scaled_size * width_scale;
-                                    
-                                    ui.add_space(5.0);
-                                    ui.image((texture.id(), display_size));
-                                    ui.add_space(5.0);
-                                }
-                                
-                                // Show text after equation
-                                if !text_parts.1.is_empty() {
-                                    ui.label(text_parts.1);
-                                }
-                            });
-                        });
+    }
+
+    /// Show the snapshot nearest to a time, pausing the simulation
+    /// When streaming, any recorded frame can be shown, not only those kept in memory
+    pub fn scrub_to(&mut self, time: f64) {
+        self.timeline.set_exact_scrubbing(self.snapshot_file.is_some());
+        self.timeline.scrub_to(&self.snapshots, time);
+        if self.timeline.cursor().is_some() {
+            self.simulation_running = false;
+            self.sync_snapshot_file();
+        }
+    }
+
+    /// Snapshot shown at the playback cursor, read from the streamed file when it holds a closer frame
+    fn displayed_snapshot(&self) -> Option<Cow<'_, Snapshot>> {
+        let cursor = self.timeline.cursor()?;
+        let stored = self.timeline.displayed_snapshot(&self.snapshots);
+        let streamed = self.snapshot_file.as_ref().and_then(|file| file.nearest(cursor));
+        match (stored, streamed) {
+            (Some(stored), Some(streamed)) if (stored.time - cursor).abs() < (streamed.time - cursor).abs() => Some(Cow::Borrowed(stored)),
+            (_, Some(streamed)) => Some(Cow::Owned(streamed)),
+            (stored, None) => stored.map(Cow::Borrowed),
+        }
+    }
+
+    /// Time of the surface currently shown [s]
+    pub fn displayed_time(&self) -> f64 {
+        self.displayed_snapshot().map_or(self.simulation_time, |snapshot| snapshot.time)
+    }
+
+    /// Surface elevation currently shown, the snapshot at the playback cursor during playback [m]
+    pub fn displayed_surface(&self) -> Cow<'_, [f64]> {
+        match self.displayed_snapshot().filter(|snapshot| snapshot.surface_elevation.len() == self.grid_resolution) {
+            Some(Cow::Borrowed(snapshot)) => Cow::Borrowed(&snapshot.surface_elevation),
+            Some(Cow::Owned(snapshot)) => Cow::Owned(snapshot.surface_elevation),
+            None => Cow::Borrowed(&self.surface_elevation),
+        }
+    }
+
+    /// Whether the numerical solver runs: always for initial-value scenarios, which have no analytical superposition
+    pub fn is_numerical(&self) -> bool {
+        self.propagation_model == PropagationModel::Numerical || self.initial_condition.is_some()
+    }
+
+    /// Run an engine instead of the built-in numerical solver, from the start
+    pub fn set_engine(&mut self, engine: Option<Box<dyn WaveSolver>>) {
+        self.engine = engine;
+        self.reset_simulation();
+    }
+
+    /// Numerical solution shown and recorded by the gauges: the engine if one is set, else the built-in solver
+    /// None with the analytical model
+    pub fn active_solver(&self) -> Option<&dyn WaveSolver> {
+        engine_or_solver(&self.engine, &self.solver).filter(|_| self.is_numerical())
+    }
+
+    /// Advance the numerical solver, restarting it if the channel, scenario or solver settings changed
+    /// An engine runs as set up by its owner, so only the wavemaker forcing is passed to it
+    fn advance_solver(&mut self, dt: f64) {
+        if let Some(mut engine) = self.engine.take() {
+            let mut forcing = self.wave_forcing();
+            let start = std::time::Instant::now();
+            let steps = engine.advance(dt, &mut forcing);
+            if steps > 0 {
+                let time_step = dt / steps as f64;
+                self.performance.record_advance(steps, start.elapsed().as_secs_f64(), time_step, engine.courant_number(time_step));
+            }
+            self.simulation_time = engine.time();
+            self.engine = Some(engine);
+            return;
+        }
+
+        // Sponge and vegetation drag edited during the run apply to the running solver
+        if let Some(solver) = &mut self.solver {
+            if let (RightBoundary::Sponge { width, .. }, RightBoundary::Sponge { width: current, .. }) = (self.right_boundary, solver.right_boundary())
+                && width == current
+            {
+                solver.set_right_boundary(self.right_boundary).ok();
+            }
+            if let (Some(field), Some(current)) = (self.vegetation, solver.vegetation())
+                && field.with_drag_coefficient(current.drag_coefficient) == current
+            {
+                solver.set_vegetation(self.vegetation).ok();
+            }
+        }
+
+        if !self.solver.as_ref().is_some_and(|solver| self.solver_matches(solver)) {
+            self.solver = self.build_solver().ok();
+            self.simulation_time = 0.0;
+            self.run_metadata = None;
+            self.parameter_log.clear();
+            self.alert_log.clear();
+            self.alerts.iter_mut().for_each(AlertRule::rearm);
+            self.gauges.iter_mut().for_each(WaveGauge::clear);
+            self.pressure_sensors.iter_mut().for_each(PressureSensor::clear);
+            self.wall_force.clear();
+            self.shoreline.clear();
+            self.dissipation.clear();
+            self.level_drift.clear();
+            self.mean_flow = MeanFlowRecord::default();
+            self.inundation = InundationRecord::default();
+            if let Some(tank) = &mut self.overtopping_tank {
+                tank.clear();
+            }
+            self.clear_snapshots();
+        }
+
+        let mut forcing = self.wave_forcing();
+        if let Some(solver) = &mut self.solver {
+            let start = std::time::Instant::now();
+            let steps = solver.advance(dt, &mut forcing);
+            if steps > 0 {
+                let time_step = dt / steps as f64;
+                self.performance.record_advance(steps, start.elapsed().as_secs_f64(), time_step, solver.courant_number(time_step));
+            }
+            self.simulation_time = solver.time();
+        }
+    }
+
+    /// Current values of the parameters that can change during a run
+    fn live_values(&self) -> Vec<(LiveParameter, f64)> {
+        let mut values = vec![(LiveParameter::WaveHeight, self.wave_height)];
+        if let RightBoundary::Sponge { strength, .. } = self.right_boundary {
+            values.push((LiveParameter::SpongeStrength, strength));
+        }
+        if let Some(field) = self.vegetation {
+            values.push((LiveParameter::VegetationDrag, field.drag_coefficient));
+        }
+        values
+    }
+
+    /// Height of the wavemaker waves at the current time, ramping towards heights changed during the run [m]
+    pub fn current_wave_height(&self) -> f64 {
+        self.parameter_log.value_at(LiveParameter::WaveHeight, self.simulation_time, self.height_ramp, self.wave_height)
+    }
+
+    /// Record the surface elevation at every gauge, and the pressure at every sensor, the wall force, the tank volume, the shoreline, the inundation and the mean flow, whose last sample is one sampling interval old
+    fn record_gauges(&mut self) {
+        let forcing = self.wave_forcing();
+        let solver = engine_or_solver(&self.engine, &self.solver).filter(|_| self.is_numerical());
+        for gauge in &mut self.gauges {
+            if gauge.times().last().is_some_and(|last| self.simulation_time - last < self.gauge_sampling_interval - 1e-9) {
+                continue;
+            }
+            let elevation = match solver {
+                Some(solver) => solver.surface_elevation_at(gauge.position()),
+                None => forcing.surface_elevation(gauge.position()),
+            };
+            gauge.record(self.simulation_time, elevation);
+        }
+
+        // Pressure needs the vertical structure of the flow, only known to the numerical solver
+        let Some(solver) = solver else {
+            return;
+        };
+        for sensor in &mut self.pressure_sensors {
+            if sensor.times().last().is_some_and(|last| self.simulation_time - last < self.gauge_sampling_interval - 1e-9) {
+                continue;
+            }
+            let pressure = WATER_DENSITY * solver.pressure_at(sensor.position(), sensor.elevation() - self.still_water_level);
+            sensor.record(self.simulation_time, pressure);
+        }
+
+        // Wave force on the wall: the force of the water column less its hydrostatic force at rest
+        let force_due = self.wall_force.times().last().is_none_or(|last| self.simulation_time - last >= self.gauge_sampling_interval - 1e-9);
+        if self.right_boundary == RightBoundary::Wall && force_due {
+            let depth = self.still_water_depth(self.channel_length).max(0.0);
+            let force = WATER_DENSITY * (solver.column_force(self.channel_length) - 0.5 * 9.81 * depth * depth);
+            self.wall_force.record(self.simulation_time, force);
+        }
+
+        if let Some(tank) = &mut self.overtopping_tank
+            && solver.overtopping_tank().is_some()
+            && tank.times().last().is_none_or(|last| self.simulation_time - last >= self.gauge_sampling_interval - 1e-9)
+        {
+            tank.record(self.simulation_time, solver.tank_volume());
+        }
+
+        // The shoreline is the wet/dry front on a bed rising above the still water level
+        let shoreline_due = self.shoreline.times().last().is_none_or(|last| self.simulation_time - last >= self.gauge_sampling_interval - 1e-9);
+        if shoreline_due
+            && self.cross_section.as_ref().is_some_and(|section| section.crest_elevation() > self.still_water_level)
+            && let Some(position) = solver.shoreline(WET_DEPTH)
+        {
+            let elevation = self.bed_elevation(position) - self.still_water_level;
+            self.shoreline.record(self.simulation_time, position, elevation);
+        }
+
+        // The flooding envelope covers the whole run, so the highest water and the first arrival are kept
+        let inundation_due = self.inundation.last_time().is_none_or(|last| self.simulation_time - last >= self.gauge_sampling_interval - 1e-9);
+        if inundation_due && self.cross_section.as_ref().is_some_and(|section| section.crest_elevation() > self.still_water_level) {
+            if self.inundation.is_empty() {
+                self.inundation = InundationRecord::new(solver.grid().x_centers(), solver.depth(), WET_DEPTH, ARRIVAL_THRESHOLD);
+            }
+            self.inundation.record(self.simulation_time, solver.eta());
+        }
+
+        let dissipation_due = self.dissipation.times().last().is_none_or(|last| self.simulation_time - last >= self.gauge_sampling_interval - 1e-9);
+        if dissipation_due && let Some(dissipation) = solver.dissipation() {
+            self.dissipation.record(self.simulation_time, dissipation);
+        }
+        let drift_due = self.level_drift.times().last().is_none_or(|last| self.simulation_time - last >= self.gauge_sampling_interval - 1e-9);
+        if drift_due && let Some(drift) = solver.mean_level_drift() {
+            self.level_drift.record(self.simulation_time, drift);
+        }
+
+        // The mean flow is averaged over the end of the run, once the waves are steady
+        let mean_flow_due = self.mean_flow.span().is_none_or(|(_, last)| self.simulation_time - last >= self.gauge_sampling_interval - 1e-9);
+        let window = self.mean_flow_window();
+        if mean_flow_due && (window <= 0.0 || self.simulation_time >= self.total_simulation_time() - window) {
+            if self.mean_flow.is_empty() {
+                self.mean_flow = MeanFlowRecord::new(solver.grid().x_centers(), solver.depth());
+            }
+            let velocity: Vec<f64> = solver.u().windows(2).map(|faces| 0.5 * (faces[0] + faces[1])).collect();
+            self.mean_flow.record(self.simulation_time, solver.eta(), &velocity);
+        }
+    }
+
+    /// Record of a gauge over the statistics window
+    pub fn statistics_record(&self, gauge: usize) -> Option<WaveGauge> {
+        self.gauges.get(gauge).map(|gauge| gauge.window(self.statistics_window))
+    }
+
+    /// Statistics of every gauge over the statistics window: mean level, extremes, Hm0 and Tp
+    pub fn gauge_statistics(&self) -> ResultsTable {
+        let mut table = ResultsTable::new(["Gauge", "x (m)", "Samples", "Mean η (m)", "Max η (m)", "Min η (m)", "Hm0 (m)", "Tp (s)"]);
+        for (i, gauge) in self.gauges.iter().enumerate() {
+            let record = gauge.window(self.statistics_window);
+            let elevation = record.elevation();
+            let statistic = |value: Option<f64>| value.map_or(TableCell::Failed("No record yet".to_string()), TableCell::Number);
+            let peak_period = record.sample_interval().and_then(|interval| peak_frequency(elevation, interval)).map(|frequency| 1.0 / frequency);
+            table.push(vec![
+                TableCell::Text(format!("Gauge {}", i + 1)),
+                TableCell::Number(gauge.position()),
+                TableCell::Number(elevation.len() as f64),
+                statistic((!elevation.is_empty()).then(|| elevation.iter().sum::<f64>() / elevation.len() as f64)),
+                statistic(elevation.iter().copied().reduce(f64::max)),
+                statistic(elevation.iter().copied().reduce(f64::min)),
+                statistic((!elevation.is_empty()).then(|| significant_wave_height(elevation))),
+                statistic(peak_period),
+            ]);
+        }
+        table
+    }
+
+    /// Shortest wave period generated in the channel, None for initial-value scenarios [s]
+    fn shortest_wave_period(&self) -> Option<f64> {
+        if self.initial_condition.is_some() {
+            return None;
+        }
+        if let Some(hydrograph) = &self.storm_hydrograph {
+            return Some(hydrograph.points.iter().map(|point| point.peak_period).fold(f64::INFINITY, f64::min));
+        }
+        let components = self.spectral_components();
+        if self.boundary_signal.is_some() && !components.is_empty() {
+            return Some(components.iter().map(SpectralComponent::period).fold(f64::INFINITY, f64::min));
+        }
+        if let Some(spectrum) = &self.boundary_spectrum {
+            return Some(1.0 / spectrum.band().1);
+        }
+        let scheduled = self.forcing_schedule.entries.iter().map(|entry| entry.wave_period);
+        Some(self.additional_sources.iter().map(|source| source.wave_period).chain(scheduled).fold(self.wave_period, f64::min))
+    }
+
+    /// Warnings about output rates too low for the generated waves
+    /// Sampling at or beyond half the period aliases the waves, fewer than ten samples per period
+    /// misses crests and harmonics, and statistics need about ten waves
+    pub fn sampling_warnings(&self) -> Vec<String> {
+        let Some(period) = self.shortest_wave_period() else {
+            return Vec::new();
+        };
+        let mut warnings = Vec::new();
+        for (name, interval) in [("Gauge sampling", self.gauge_sampling_interval), ("Snapshot", self.snapshot_interval)] {
+            if interval >= 0.5 * period {
+                warnings.push(format!(
+                    "{} interval of {:.2} s aliases the {:.2} s waves: it must stay below T/2 = {:.2} s",
+                    name,
+                    interval,
+                    period,
+                    0.5 * period
+                ));
+            } else if interval > 0.1 * period {
+                warnings.push(format!(
+                    "{} interval of {:.2} s gives only {:.0} samples per {:.2} s wave: crests and harmonics are poorly resolved",
+                    name,
+                    interval,
+                    period / interval,
+                    period
+                ));
+            }
+        }
+        if self.statistics_window > 0.0 && self.statistics_window < 10.0 * self.wave_period {
+            warnings.push(format!(
+                "Statistics window of {:.0} s holds fewer than 10 waves of {:.2} s",
+                self.statistics_window, self.wave_period
+            ));
+        }
+        warnings
+    }
+
+    /// Frequency separating infragravity from sea-swell waves, half the wavemaker frequency [Hz]
+    pub fn infragravity_split_frequency(&self) -> f64 {
+        0.5 / self.wave_period
+    }
+
+    /// Infragravity analysis of the selected gauge record
+    pub fn infragravity_analysis(&self) -> Result<InfragravityAnalysis, String> {
+        let gauge = self.statistics_record(self.analysis_gauge).ok_or("No gauge selected")?;
+        InfragravityAnalysis::from_gauge(&gauge, self.infragravity_split_frequency())
+    }
+
+    /// Swash analysis of the shoreline record over the statistics window
+    /// The incident and infragravity bands split at half the wavemaker frequency, as for the gauge records
+    pub fn swash_analysis(&self) -> Result<SwashAnalysis, String> {
+        let record = self.shoreline.window(self.statistics_window);
+        let sample_interval = match (record.times().first(), record.times().last()) {
+            (Some(first), Some(last)) if record.len() > 1 => (last - first) / (record.len() - 1) as f64,
+            _ => return Err("The shoreline has not been recorded yet".to_string()),
+        };
+        let segment_length = self.spectral_segment_length(record.len(), sample_interval);
+        SwashAnalysis::new(&record, self.infragravity_split_frequency(), segment_length)
+    }
+
+    /// Length of the end of the run over which the mean flow is averaged, 0 for the whole run [s]
+    pub fn mean_flow_window(&self) -> f64 {
+        if self.mean_flow_periods > 0.0 { self.mean_flow_periods * self.wave_period } else { self.statistics_window }
+    }
+
+    /// Cross-shore undertow profile from the mean flow of the wavemaker waves
+    pub fn undertow_profile(&self) -> Result<UndertowProfile, String> {
+        UndertowProfile::new(&self.mean_flow, self.wave_period)
+    }
+
+    /// Height of the wavemaker waves brought back to deep water with linear shoaling (H₀) [m]
+    pub fn deep_water_wave_height(&self) -> Result<f64, String> {
+        deep_water_wave_height(self.wave_height, self.wave_period, self.still_water_depth(0.0))
+    }
+
+    /// Quantities of the case that custom formulas may use, by symbol
+    /// Hs and Tp are measured at the analysis gauge over the statistics window once it has recorded, the
+    /// slope is the gradient of the steepest rising segment of the cross-section
+    pub fn formula_variables(&self) -> Vec<(&'static str, f64)> {
+        let gravity = 9.81;
+        let wavelength = Self::linear_wavelength(self.wave_period, self.still_water_level);
+        let mut variables = vec![
+            ("g", gravity),
+            ("H", self.wave_height),
+            ("T", self.wave_period),
+            ("h", self.still_water_level),
+            ("f", 1.0 / self.wave_period),
+            ("omega", 2.0 * std::f64::consts::PI / self.wave_period),
+            ("L", wavelength),
+            ("L0", gravity * self.wave_period * self.wave_period / (2.0 * std::f64::consts::PI)),
+            ("k", 2.0 * std::f64::consts::PI / wavelength),
+            ("c", wavelength / self.wave_period),
+        ];
+        let slope = self.cross_section.iter().flat_map(|section| &section.segments).map(ProfileSegment::gradient).fold(0.0, f64::max);
+        if slope > 0.0 {
+            variables.push(("slope", slope));
+        }
+        if let Some(gauge) = self.statistics_record(self.analysis_gauge)
+            && let Some(sample_interval) = gauge.sample_interval()
+        {
+            variables.push(("Hs", significant_wave_height(gauge.elevation())));
+            if let Some(frequency) = peak_frequency(gauge.elevation(), sample_interval) {
+                variables.push(("Tp", 1.0 / frequency));
+            }
+        }
+        variables
+    }
+
+    /// Add a custom formula shown with the computed values
+    pub fn add_custom_formula(&mut self, name: &str, source: &str, unit: &str) -> Result<(), String> {
+        let formula = CustomFormula::new(name, source, unit)?;
+        if self.custom_formulas.iter().any(|existing| existing.name == formula.name) {
+            return Err(format!("A formula named {} already exists", formula.name));
+        }
+        self.custom_formulas.push(formula);
+        Ok(())
+    }
+
+    /// Gradient of the cross-section segment rising through the still water level, None without one
+    pub fn foreshore_slope(&self) -> Option<f64> {
+        let breakpoints = self.cross_section.as_ref()?.breakpoints();
+        breakpoints
+            .windows(2)
+            .find(|pair| pair[0][1] <= self.still_water_level && pair[1][1] > self.still_water_level)
+            .map(|pair| (pair[1][1] - pair[0][1]) / (pair[1][0] - pair[0][0]))
+    }
+
+    /// Wave setup at the shoreline of the structure, from the chosen method [m]
+    pub fn wave_setup(&self) -> Result<f64, String> {
+        match self.setup_method {
+            SetupMethod::Stockdon => {
+                let slope = self.foreshore_slope().ok_or("The bed profile does not rise through the still water level")?;
+                Ok(StockdonSwash::new(self.deep_water_wave_height()?, self.wave_period, slope)?.setup)
+            }
+            SetupMethod::Simulation => Ok(self.swash_analysis()?.setup),
+        }
+    }
+
+    /// Total water level at the structure: tide, surge and wave setup above mean sea level
+    pub fn water_level_budget(&self) -> Result<WaterLevelBudget, String> {
+        self.water_levels.surge.validate()?;
+        Ok(WaterLevelBudget::new(&self.water_levels, self.wave_setup()?))
+    }
+
+    /// Raise the still water level of the channel to the tide and surge, keeping the bed in place
+    /// The waves of the simulation make their own setup, so it is left out
+    pub fn apply_still_water_components(&mut self) {
+        let rise = self.water_levels.still_water_level() - self.reference_levels.water_level(VerticalDatum::MeanSeaLevel, self.still_water_level);
+        self.reference_levels.still_water += rise;
+        self.still_water_level += rise;
+    }
+
+    /// Swash expected on a natural beach of the measured foreshore slope after Stockdon et al. (2006)
+    pub fn stockdon_swash(&self, analysis: &SwashAnalysis) -> Result<StockdonSwash, String> {
+        let slope = analysis.foreshore_slope.filter(|slope| *slope > 0.0).ok_or("The swash zone has no foreshore slope")?;
+        StockdonSwash::new(self.deep_water_wave_height()?, self.wave_period, slope)
+    }
+
+    /// Morlet wavelet scalogram of the selected gauge record
+    /// Scales span from a twentieth to four times the wavemaker frequency, within the resolvable band
+    pub fn scalogram(&self) -> Result<Scalogram, String> {
+        let gauge = self.statistics_record(self.analysis_gauge).ok_or("No gauge selected")?;
+        let sample_interval = gauge.sample_interval().ok_or("Gauge record is empty")?;
+        let peak_frequency = 1.0 / self.wave_period;
+        let max_frequency = (4.0 * peak_frequency).min(0.4 / sample_interval);
+        let min_frequency = (0.05 * peak_frequency).max(2.0 / gauge.duration());
+        Scalogram::new(gauge.elevation(), sample_interval, min_frequency, max_frequency, 48)
+    }
+
+    /// Segment length of the averaged spectral estimates for a record
+    /// Segments resolve an eighth of the wavemaker frequency, shortened to keep at least seven of them
+    fn spectral_segment_length(&self, samples: usize, sample_interval: f64) -> usize {
+        let longest = (samples / 4 + 1).next_power_of_two() / 2;
+        ((8.0 * self.wave_period / sample_interval).ceil() as usize).next_power_of_two().min(longest)
+    }
+
+    /// Bispectrum of the selected gauge record up to three times the wavemaker frequency
+    pub fn bispectrum(&self) -> Result<Bispectrum, String> {
+        let gauge = self.statistics_record(self.analysis_gauge).ok_or("No gauge selected")?;
+        let sample_interval = gauge.sample_interval().ok_or("Gauge record is empty")?;
+        let segment_length = self.spectral_segment_length(gauge.len(), sample_interval);
+        Bispectrum::new(gauge.elevation(), sample_interval, segment_length, 3.0 / self.wave_period)
+    }
+
+    /// Variance density spectrum of a gauge record: frequencies [Hz] and density [m²/Hz]
+    pub fn gauge_spectrum(&self, gauge: usize) -> Result<(Vec<f64>, Vec<f64>), String> {
+        let gauge = self.statistics_record(gauge).ok_or("No gauge selected")?;
+        let sample_interval = gauge.sample_interval().ok_or("Gauge record is empty")?;
+        let segment_length = self.spectral_segment_length(gauge.len(), sample_interval);
+        let spectrum = variance_density(gauge.elevation(), sample_interval, segment_length);
+        if spectrum.0.is_empty() {
+            return Err("Gauge record is too short for a spectrum".to_string());
+        }
+        Ok(spectrum)
+    }
+
+    /// Cross-spectrum between the selected pair of gauges
+    pub fn cross_spectrum(&self) -> Result<CrossSpectrum, String> {
+        let [first, second] = self.cross_spectrum_gauges;
+        if first == second {
+            return Err("Select two different gauges".to_string());
+        }
+        let first = self.statistics_record(first).ok_or("No gauge selected")?;
+        let second = self.statistics_record(second).ok_or("No gauge selected")?;
+        let sample_interval = first.sample_interval().ok_or("Gauge record is empty")?;
+        let segment_length = self.spectral_segment_length(first.len().min(second.len()), sample_interval);
+        CrossSpectrum::from_gauges(&first, &second, segment_length)
+    }
+
+    /// Part of the channel the energy budget is drawn up for: the vegetation patch and the sloping
+    /// part of the cross-section, None over a flat bare bed [m]
+    pub fn structure_extent(&self) -> Option<(f64, f64)> {
+        let vegetation = self.vegetation.map(|field| (field.start, field.end));
+        let section = self.cross_section.as_ref().and_then(|section| {
+            let breakpoints = section.breakpoints();
+            let mut sloping = breakpoints.windows(2).filter(|pair| (pair[1][1] - pair[0][1]).abs() > 1e-9);
+            let first = sloping.next()?;
+            let last = sloping.next_back().unwrap_or(first);
+            Some((first[0][0], last[1][0].min(self.channel_length)))
+        });
+        match (vegetation, section) {
+            (Some(patch), Some(section)) => Some((patch.0.min(section.0), patch.1.max(section.1))),
+            (patch, section) => patch.or(section),
+        }
+    }
+
+    /// Still water depth at a position, negative where the bed is dry [m]
+    fn still_water_depth(&self, x: f64) -> f64 {
+        self.still_water_level - self.bed_elevation(x)
+    }
+
+    /// Wave number of a frequency at a depth in the dispersion relation of the propagation model [rad/m]
+    fn array_wave_number(&self, frequency: f64, depth: f64) -> f64 {
+        let omega = 2.0 * std::f64::consts::PI * frequency;
+        let long_wave = omega / (9.81 * depth).sqrt();
+        if !self.is_numerical() {
+            return linear_wave_number(omega, depth, 9.81).unwrap_or(long_wave);
+        }
+        match self.solver_non_hydrostatic() {
+            None => long_wave,
+            Some(mode) => DispersionSolver::new().with_mode(mode).solve_wave_number(omega, depth).unwrap_or(long_wave),
+        }
+    }
+
+    /// Wavelength of the wavemaker waves at a depth in the dispersion relation of the propagation model [m]
+    fn array_wavelength(&self, depth: f64) -> f64 {
+        2.0 * std::f64::consts::PI / self.array_wave_number(1.0 / self.wave_period, depth)
+    }
+
+    /// Start of the sponge layer at the downstream end, the channel end for other boundaries [m]
+    fn sponge_start(&self) -> f64 {
+        match self.right_boundary {
+            RightBoundary::Sponge { width, .. } => self.channel_length - width,
+            _ => self.channel_length,
+        }
+    }
+
+    /// Gauges seaward and landward of the structure used in the energy budget
+    /// Landward gauges must stand in water and outside the sponge layer
+    pub fn budget_gauges(&self) -> Option<(Vec<usize>, Vec<usize>)> {
+        let (start, end) = self.structure_extent()?;
+        let positions: Vec<f64> = self.gauges.iter().map(WaveGauge::position).collect();
+        let seaward = (0..positions.len()).filter(|&i| positions[i] < start).collect();
+        let landward = (0..positions.len())
+            .filter(|&i| positions[i] > end && positions[i] < self.sponge_start() && self.still_water_depth(positions[i]) > 0.0)
+            .collect();
+        Some((seaward, landward))
+    }
+
+    /// Add gauge arrays in front of and behind the structure for the energy budget
+    /// Each array has three gauges at spacings of L/10 and L/4 of the wavemaker waves (Mansard & Funke, 1980),
+    /// a quarter wavelength away from the structure; the landward array is left out where it does not fit
+    pub fn add_budget_gauge_arrays(&mut self) -> Result<(), String> {
+        let (start, end) = self.structure_extent().ok_or("No structure in the channel")?;
+        let offsets = [0.0, 0.1, 0.25];
+
+        let wavelength = self.array_wavelength(self.still_water_depth(start));
+        let first = start - 0.5 * wavelength;
+        if first < 0.0 {
+            return Err(format!("The structure starts less than half a wavelength ({:.1} m) from the wavemaker", 0.5 * wavelength));
+        }
+        self.gauges.extend(offsets.iter().map(|offset| WaveGauge::new(first + offset * wavelength)));
+
+        let depth = self.still_water_depth(end);
+        if depth > 0.0 {
+            let wavelength = self.array_wavelength(depth);
+            let first = end + 0.25 * wavelength;
+            if first + 0.25 * wavelength < self.sponge_start() {
+                self.gauges.extend(offsets.iter().map(|offset| WaveGauge::new(first + offset * wavelength)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Incident and reflected spectra at an array of gauges
+    fn reflection_analysis(&self, gauges: &[usize]) -> Result<ReflectionAnalysis, String> {
+        let records: Vec<WaveGauge> = gauges.iter().filter_map(|&gauge| self.statistics_record(gauge)).collect();
+        let samples = records.iter().map(WaveGauge::len).min().unwrap_or(0);
+        if samples == 0 {
+            return Err("Gauge records are empty".to_string());
+        }
+        let sample_interval = records[0].sample_interval().ok_or("Gauge record is empty")?;
+        let positions: Vec<f64> = records.iter().map(WaveGauge::position).collect();
+        let depth = positions.iter().map(|&x| self.still_water_depth(x)).sum::<f64>() / positions.len() as f64;
+        // Only the common end of the records is used, in case a gauge was added during the run
+        let elevations: Vec<&[f64]> = records.iter().map(|record| &record.elevation()[record.len() - samples..]).collect();
+        let segment_length = self.spectral_segment_length(samples, sample_interval);
+        ReflectionAnalysis::new(&elevations, &positions, sample_interval, segment_length, |frequency| {
+            self.array_wave_number(frequency, depth)
+        })
+    }
+
+    /// Energy budget of the structure from the gauges on both sides
+    pub fn energy_budget(&self) -> Result<EnergyBudget, String> {
+        let (seaward, landward) = self.budget_gauges().ok_or("No structure in the channel")?;
+        if seaward.len() < 2 {
+            return Err("Place at least two gauges seaward of the structure to separate the reflected waves".to_string());
+        }
+        let seaward_array = self.reflection_analysis(&seaward)?;
+        let landward_array = if landward.is_empty() { None } else { Some(self.reflection_analysis(&landward)?) };
+        let mut budget = EnergyBudget::new(&seaward_array, landward_array.as_ref())?;
+
+        // Linear separation treats bound harmonics of steep long waves as free waves
+        let depth = seaward.iter().map(|&i| self.still_water_depth(self.gauges[i].position())).sum::<f64>() / seaward.len() as f64;
+        let ursell = ursell_number(seaward_array.incident_height(), self.array_wavelength(depth), depth);
+        if ursell > URSELL_CNOIDAL {
+            budget.notes.push(format!(
+                "Ursell number {:.0} at the seaward gauges: nonlinear waves, whose bound harmonics the linear separation counts as free waves.",
+                ursell
+            ));
+        }
+        let (_, end) = self.structure_extent().ok_or("No structure in the channel")?;
+        if self.gauges.iter().any(|gauge| gauge.position() > end && gauge.position() >= self.sponge_start()) {
+            budget.notes.push("Gauges inside the sponge layer are left out of the transmitted energy.".to_string());
+        }
+        Ok(budget)
+    }
+
+    /// Position of the overtopping tank: the landward edge of a crest above the still water level [m]
+    pub fn tank_position(&self) -> Result<f64, String> {
+        if self.overtopping_tank.is_none() {
+            return Err("No overtopping tank".to_string());
+        }
+        if self.right_boundary == RightBoundary::Periodic {
+            return Err("A periodic channel has no overtopping tank".to_string());
+        }
+        let section = self.cross_section.as_ref().ok_or("The overtopping tank needs a cross-section")?;
+        if section.crest_elevation() <= self.still_water_level {
+            return Err("The overtopping tank needs a crest above the still water level".to_string());
+        }
+        Ok(section.crest_position().min(self.channel_length))
+    }
+
+    /// Goda design pressures on the downstream wall for the wavemaker waves
+    /// The channel wall is not overtopped, so the pressure reaches η* above the still water level
+    pub fn goda_pressure(&self) -> Result<GodaPressure, String> {
+        if self.right_boundary != RightBoundary::Wall {
+            return Err("Goda pressures need a wall at the downstream end".to_string());
+        }
+        let depth = self.still_water_depth(self.channel_length);
+        let breaking_depth = self.still_water_depth((self.channel_length - 5.0 * self.wave_height).max(0.0));
+        GodaPressure::new(self.wave_height, self.wave_period, depth, breaking_depth, f64::INFINITY)
+    }
+
+    /// Impacts on the downstream wall: excursions of the wall force above the threshold fraction of its maximum
+    pub fn wall_impacts(&self) -> Vec<ImpactEvent> {
+        detect_impacts(&self.wall_force, self.impact_threshold * self.wall_force.max())
+    }
+
+    /// Highest wave pressure of a sensor above the hydrostatic pressure at rest, and the time it occurred
+    pub fn sensor_peak(&self, sensor: usize) -> Option<(f64, f64)> {
+        let sensor = self.pressure_sensors.get(sensor)?;
+        let at_rest = WATER_DENSITY * 9.81 * (self.still_water_level - sensor.elevation()).max(0.0);
+        sensor.peak().map(|(time, pressure)| (time, pressure - at_rest))
+    }
+
+    /// Phase velocity of a wave of frequency f in the dispersion relation of the numerical solver [m/s]
+    /// None for the analytical model, which follows linear theory
+    fn model_celerity(&self, frequency: f64) -> Option<f64> {
+        if !self.is_numerical() {
+            return None;
+        }
+        let omega = 2.0 * std::f64::consts::PI * frequency;
+        let long_wave_speed = (9.81 * self.still_water_level).sqrt();
+        Some(match self.solver_non_hydrostatic() {
+            None => long_wave_speed,
+            Some(mode) => DispersionSolver::new()
+                .with_mode(mode)
+                .solve_wave_number(omega, self.still_water_level)
+                .map_or(long_wave_speed, |k| omega / k),
+        })
+    }
+
+    /// Grid of the numerical solver, uniform or refined in the refinement zones
+    pub fn computational_grid(&self) -> Result<Grid1D, String> {
+        Grid1D::refined(self.channel_length, self.grid_resolution, &self.grid_refinement)
+    }
+
+    /// Grid refinement for the channel profile and the regular waves, from the automatic refinement targets
+    pub fn refinement_plan(&self) -> Result<RefinementPlan, String> {
+        self.auto_refinement.plan(
+            self.cross_section.as_ref(),
+            self.channel_length,
+            self.still_water_level,
+            self.wave_height,
+            self.wave_period,
+        )
+    }
+
+    /// Use the zones and cell count of a refinement plan for the numerical grid
+    pub fn apply_refinement_plan(&mut self, plan: &RefinementPlan) {
+        self.grid_refinement = plan.refinement_zones();
+        self.grid_resolution = plan.cells;
+        self.update_surface_elevation();
+    }
+
+    /// Still water depth over the deepest cell of a grid [m]
+    fn max_depth(&self, grid: &Grid1D) -> f64 {
+        let lowest_bed = grid.x_centers().iter().map(|&x| self.bed_elevation(x)).fold(0.0, f64::min);
+        self.still_water_level - lowest_bed
+    }
+
+    /// Estimated computing time of the numerical run on a grid [s]
+    pub fn estimated_runtime(&self, grid: &Grid1D) -> f64 {
+        estimated_runtime(
+            grid,
+            self.max_depth(grid),
+            self.wave_height,
+            self.total_simulation_time(),
+            self.solver_non_hydrostatic(),
+        )
+    }
+
+    /// Measured computing time of a solver step, or of an analytical frame, on this machine [s]
+    /// A freshly built solver is stepped under the current forcing for a few milliseconds, short
+    /// enough to measure again whenever the settings change
+    pub fn benchmark_step(&self) -> Result<f64, String> {
+        const BUDGET: std::time::Duration = std::time::Duration::from_millis(5);
+        let mut forcing = self.wave_forcing();
+        let mut steps = 0;
+        let start;
+        if self.is_numerical() {
+            let mut solver = self.build_solver()?;
+            let dt = solver.stable_time_step();
+            start = std::time::Instant::now();
+            while steps < 3 || (steps < 100 && start.elapsed() < BUDGET) {
+                solver.step(dt, &mut forcing);
+                steps += 1;
+            }
+        } else {
+            let dx = self.grid_spacing();
+            let mut total = 0.0;
+            start = std::time::Instant::now();
+            while steps < 3 || (steps < 100 && start.elapsed() < BUDGET) {
+                total += (0..self.grid_resolution).map(|i| forcing.surface_elevation(i as f64 * dx)).sum::<f64>();
+                steps += 1;
+            }
+            std::hint::black_box(total);
+        }
+        Ok(start.elapsed().as_secs_f64() / steps as f64)
+    }
+
+    /// Time steps, stored outputs and runtime of the run, for a measured cost of a step [s]
+    pub fn run_preview(&self, seconds_per_step: f64) -> RunPreview {
+        let duration = self.total_simulation_time();
+        let frames = (duration / ANIMATION_STEP).ceil() as usize;
+        // Outputs are recorded at most once per animation step
+        let snapshot_interval = self.snapshot_interval.max(ANIMATION_STEP);
+        let snapshot_size = std::mem::size_of::<Snapshot>() + 8 * self.grid_resolution;
+        let snapshots = self.snapshots.projected_len(duration, snapshot_interval);
+        let gauge_samples = (duration / self.gauge_sampling_interval.max(ANIMATION_STEP)).floor() as usize + 1;
+        let solver_steps = match self.computational_grid() {
+            Ok(grid) if self.is_numerical() => Some(estimated_time_steps(&grid, self.max_depth(&grid), self.wave_height, duration)),
+            _ => None,
+        };
+        RunPreview {
+            duration,
+            frames,
+            solver_steps,
+            cells: self.grid_resolution,
+            snapshot_bytes: (snapshots * snapshot_size) as u64,
+            gauge_bytes: (self.gauges.len() * gauge_samples * 16) as u64,
+            stream_bytes: self
+                .stream_snapshots
+                .then(|| SnapshotFile::projected_size(self.grid_resolution, (duration / snapshot_interval).floor() as usize + 1)),
+            seconds_per_step,
+        }
+    }
+
+    /// Memory held by the outputs kept for the session: snapshots, gauge and sensor records and gallery captures [bytes]
+    /// Snapshots streamed to disk are not counted
+    pub fn stored_bytes(&self) -> u64 {
+        let snapshots: usize = self.snapshots.snapshots().map(|snapshot| std::mem::size_of::<Snapshot>() + 8 * snapshot.surface_elevation.len()).sum();
+        let records: usize = self.gauges.iter().map(WaveGauge::len).chain(self.pressure_sensors.iter().map(PressureSensor::len)).sum();
+        let captures: usize = self.gallery.captures.iter().map(|capture| capture.surface.len() + capture.bed.len()).sum();
+        (snapshots + 16 * (records + captures)) as u64
+    }
+
+    /// Build the numerical solver for the current channel, scenario and solver settings
+    pub fn build_solver(&self) -> Result<ShallowWaterSolver, String> {
+        let mut solver = ShallowWaterSolver::from_grid(self.computational_grid()?, self.still_water_level, self.right_boundary)?
+            .with_non_hydrostatic(self.solver_non_hydrostatic())
+            .with_advection_scheme(self.advection_scheme)
+            .with_time_integration(self.time_integration)
+            .with_mass_compensation(self.mass_compensation);
+        if let Some(section) = &self.cross_section {
+            section.validate()?;
+            solver.set_depth_profile(|x| section.depth_at(x, self.still_water_level))?;
+        }
+        solver.set_initial_condition(self.initial_condition)?;
+        solver.set_vegetation(self.vegetation)?;
+        solver.set_current(self.ambient_current)?;
+        solver.set_overtopping_tank(self.tank_position().ok())?;
+        if self.active_absorption {
+            // Tune the absorber to the celerity of the generated wave
+            let celerity = solver.celerity(2.0 * std::f64::consts::PI / self.wave_period, self.still_water_level);
+            solver.set_absorber(Some(ActiveAbsorber::new(self.still_water_level)?.with_celerity(celerity)));
+        }
+        Ok(solver)
+    }
+
+    /// Run the case from the start with the state kept in double and in single precision and compare the surfaces after a duration [s]
+    pub fn precision_comparison(&self, duration: f64) -> Result<PrecisionComparison, String> {
+        let run = |precision: Precision| -> Result<Vec<f64>, String> {
+            let mut solver = self.build_solver()?.with_precision(precision);
+            solver.advance(duration, &mut self.wave_forcing());
+            Ok(solver.eta().to_vec())
+        };
+        PrecisionComparison::new(&run(Precision::Double)?, &run(Precision::Single)?)
+    }
+
+    /// Steady background flow along the channel, uniform or sheared over the depth
+    fn show_ambient_current(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut enabled = self.ambient_current.is_some();
+            if ui.checkbox(&mut enabled, "Ambient Current").changed() {
+                self.ambient_current = enabled.then(|| AmbientCurrent::uniform(-0.2));
+            }
+            self.info_button(ui, "ambient_current", "Steady current along the channel, positive with the waves and negative against them, entering at the wavemaker with the waves and leaving through the open end; the channel cannot end with a wall. The waves keep their absolute period at the paddle and take the wave number of the Doppler-shifted dispersion relation (ω − kU)² = gk tanh(kd): a following current lengthens them and lowers them, an opposing current shortens and steepens them, until it reaches their group velocity and blocks them. The solver carries the current in its velocities, so its advection terms shift the waves, and the discharge of the current is kept along the channel, speeding up over shoals. A power-law profile U(z) = (1 + p)Ū((z + d)/d)^p, p ≈ 1/7, is slower at the bed and faster at the surface; the waves feel it weighted over the depth by their orbital motion (Kirby and Chen, 1989), the surface current for short waves and the depth average for long ones, and the non-hydrostatic layers each carry their own velocity.");
+        });
+        let Some(current) = &mut self.ambient_current else {
+            return;
+        };
+        ui.horizontal(|ui| {
+            ui.label("Current Velocity (Ū):");
+            ui.add(egui::Slider::new(&mut current.velocity, -2.0..=2.0).suffix(" m/s").step_by(0.01));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Current Profile:");
+            let sheared = matches!(current.profile, CurrentProfile::PowerLaw { .. });
+            egui::ComboBox::from_id_salt("current_profile")
+                .selected_text(current.profile.to_string())
+                .show_ui(ui, |ui| {
+                    if ui.selectable_label(!sheared, CurrentProfile::Uniform.to_string()).clicked() {
+                        current.profile = CurrentProfile::Uniform;
+                    }
+                    if ui.selectable_label(sheared, "Power Law").clicked() && !sheared {
+                        current.profile = CurrentProfile::PowerLaw { exponent: 1.0 / 7.0 };
+                    }
+                });
+            if let CurrentProfile::PowerLaw { exponent } = &mut current.profile {
+                ui.add(egui::Slider::new(exponent, 0.05..=1.0).prefix("p = "));
+            }
+        });
+        let current = *current;
+        if self.right_boundary == RightBoundary::Wall && self.is_numerical() {
+            ui.colored_label(egui::Color32::YELLOW, "⚠ The current needs an open end: choose a sponge layer or radiation");
+        }
+        let omega = 2.0 * std::f64::consts::PI / self.wave_period;
+        if current.wave_number(&DispersionSolver::linear(), omega, self.still_water_level).is_err() {
+            ui.colored_label(egui::Color32::YELLOW, "⚠ The waves are blocked by the opposing current and are not generated");
+        }
+    }
+
+    /// Reconstruction of the advected quantities
+    fn show_advection_scheme(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Advection:");
+            egui::ComboBox::from_id_salt("advection_scheme")
+                .selected_text(self.advection_scheme.to_string())
+                .show_ui(ui, |ui| {
+                    for scheme in AdvectionScheme::options() {
+                        ui.selectable_value(&mut self.advection_scheme, scheme, scheme.to_string());
+                    }
+                });
+            self.info_button(ui, "advection_scheme", "How the water depth carried through a cell face and the velocity carried by the momentum flux are taken from the upwind side. First-order upwind copies the neighbouring value, which acts as a numerical diffusion smearing bores and dam-break fronts over several cells. MUSCL adds a slope from the next value upwind, cut back by the limiter near steep gradients so no new extrema appear: minmod is the most diffusive limiter and superbee the least, steepening smooth slopes. WENO3 blends two candidate stencils weighted by their smoothness. Next to dry cells every scheme falls back to upwind. To see the numerical diffusion, clone the case into the comparison view, change the scheme of case B and run a dam break side by side.");
+        });
+    }
+
+    /// Integrator of the continuity and momentum equations
+    fn show_time_integration(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Time Integration:");
+            egui::ComboBox::from_id_salt("time_integration")
+                .selected_text(self.time_integration.to_string())
+                .show_ui(ui, |ui| {
+                    for integration in TimeIntegration::ALL {
+                        ui.selectable_value(&mut self.time_integration, integration, integration.to_string());
+                    }
+                });
+            self.info_button(ui, "time_integration", "How the surface and the velocities are advanced over a time step. Forward-backward Euler updates the surface first and the velocities with the new surface: first order, but neutral for gravity waves up to a Courant number of one. RK2 (Heun) and RK4 combine forward Euler stages to second and fourth order; RK2 takes a quarter of the CFL time step and RK4 twice it. Semi-implicit weights the surface gradient and the discharge θ = 0.55 at the new time level and treats the vegetation drag implicitly, solving a tridiagonal system for the new surface each step, with up to four times the CFL time step as long as the flow crosses less than a cell. See the help pane for the stability regions.");
+        });
+    }
+
+    /// Round-off of an f32 solver state on the current case
+    fn show_solver_precision(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("Compare f32 with f64").clicked() {
+                self.precision_status = Some(self.precision_comparison(PRECISION_BENCHMARK_DURATION));
+            }
+            self.info_button(ui, "solver_precision", "Estimates whether a solver storing its fields in f32, as on graphics hardware, would be accurate enough for the current case. The case runs from rest for 10 s of simulated time twice: once as usual in f64, and once with the surface elevations, velocities and pressures rounded to f32 at the end of every time step. The largest and root-mean-square differences of the surface are reported, relative to the wave height. Each step is still computed in f64, so the runs here are always in double precision and no faster. Use the dam break or Gaussian hump presets as benchmark cases.");
+        });
+        match &self.precision_status {
+            Some(Ok(comparison)) => {
+                ui.label(format!(
+                    "f32 round-off: |Δη| max {:.2e} m ({:.2e} H), RMS {:.2e} m",
+                    comparison.max_difference,
+                    comparison.max_difference / self.wave_height,
+                    comparison.rms_difference
+                ));
+            }
+            Some(Err(message)) => {
+                ui.colored_label(egui::Color32::YELLOW, format!("⚠ {}", message));
+            }
+            None => {}
+        }
+    }
+
+    /// Non-hydrostatic layers of the solver
+    /// Initial-value scenarios solve the hydrostatic nonlinear shallow water equations of the analytical solutions
+    fn solver_non_hydrostatic(&self) -> Option<DispersionMode> {
+        match self.initial_condition {
+            Some(_) => None,
+            None => Some(self.dispersion_mode),
+        }
+    }
+
+    /// Group velocity of the numerical solver for a wave of angular frequency ω [m/s]
+    fn numerical_group_velocity(&self, omega: f64) -> f64 {
+        let solver = DispersionSolver::new().with_mode(self.dispersion_mode);
+        solver
+            .solve_wave_number(omega, self.still_water_level)
+            .map(|k| solver.group_velocity(k, self.still_water_level))
+            .unwrap_or((9.81 * self.still_water_level).sqrt())
+    }
+
+    /// Check if a solver was built with the current channel and solver settings
+    fn solver_matches(&self, solver: &ShallowWaterSolver) -> bool {
+        self.computational_grid().is_ok_and(|grid| grid.x_faces() == solver.grid().x_faces())
+            && solver
+                .grid()
+                .x_centers()
+                .iter()
+                .zip(solver.depth())
+                .all(|(&x, &depth)| depth == self.still_water_level - self.bed_elevation(x))
+            && solver.right_boundary() == self.right_boundary
+            && solver.absorber().is_some() == self.active_absorption
+            && solver.mass_compensation() == self.mass_compensation
+            && solver.current() == self.ambient_current
+            && solver.advection_scheme() == self.advection_scheme
+            && solver.time_integration() == self.time_integration
+            && solver.non_hydrostatic() == self.solver_non_hydrostatic()
+            && solver.initial_condition() == self.initial_condition
+            && solver.vegetation() == self.vegetation
+            && solver.overtopping_tank().is_some() == self.tank_position().is_ok()
+    }
+
+    /// Get simulation progress as percentage (0.0 to 1.0)
+    pub fn simulation_progress(&self) -> f64 {
+        let total_simulation_time = self.total_simulation_time();
+        
+        if total_simulation_time <= 0.0 {
+            return 0.0;
+        }
+        
+        (self.simulation_time / total_simulation_time).min(1.0)
+    }
+    
+    /// Check if simulation is complete
+    pub fn is_simulation_complete(&self) -> bool {
+        self.simulation_time >= self.total_simulation_time()
+    }
+    
+    /// Time for every source to generate its waves and for the last wave to leave the channel
+    /// The numerical model travels at the group velocity of its dispersion relation and allows a round trip so reflections are visible
+    /// Initial-value scenarios run for a round trip of the fastest long wave
+    pub fn total_simulation_time(&self) -> f64 {
+        if let Some(condition) = self.initial_condition {
+            let celerity = (9.81 * condition.max_depth(self.still_water_level)).sqrt();
+            return 2.0 * self.channel_length / celerity;
+        }
+
+        self.wave_forcing()
+            .sources()
+            .iter()
+            .map(|source| {
+                // Farthest distance a wave from this source has to travel
+                let distance = source.position().max(self.channel_length - source.position());
+                match self.propagation_model {
+                    PropagationModel::Analytical => source.end_time() + distance / source.parameters().c,
+                    PropagationModel::Numerical => {
+                        let group_velocity = self.numerical_group_velocity(source.parameters().omega);
+                        source.end_time() + 2.0 * self.channel_length / group_velocity
+                    }
+                }
+            })
+            .fold(0.0, f64::max)
+    }
+    
+    /// Build the wave forcing from the wavemaker and additional sources at the current time
+    /// Initial-value scenarios evolve freely without any source
+    pub fn wave_forcing(&self) -> WaveForcing {
+        let mut forcing = WaveForcing::new();
+        if self.initial_condition.is_some() {
+            forcing.update_time(self.simulation_time);
+            return forcing;
+        }
+
+        let windows = match (&self.storm_hydrograph, &self.boundary_signal, &self.boundary_spectrum) {
+            (Some(hydrograph), _, _) => {
+                forcing.set_water_level(hydrograph.at(self.simulation_time).surge);
+                hydrograph.windows()
+            }
+            (None, Some(signal), _) => {
+                // The components repeat the signal over its duration
+                let duration = signal.duration(self.generation_duration());
+                for component in self.spectral_components() {
+                    let params = self.linear_wave_parameters(2.0 * component.amplitude, component.period());
+                    forcing.add_source(WaveSource::boundary(params).with_phase(component.phase).with_generation_duration(duration));
+                }
+                Vec::new()
+            }
+            (None, None, Some(spectrum)) => {
+                // Irregular waves from the start of the run, for as many peak periods as waves
+                let duration = self.number_of_waves as f64 * spectrum.peak_period();
+                for component in self.spectral_components() {
+                    let params = self.linear_wave_parameters(2.0 * component.amplitude, component.period());
+                    forcing.add_source(WaveSource::boundary(params).with_phase(component.phase).with_generation_duration(duration));
+                }
+                Vec::new()
+            }
+            (None, None, None) => self.forcing_schedule.windows(self.current_wave_height(), self.wave_period, self.number_of_waves),
+        };
+        // The phase of the paddle motion stays continuous where the period changes
+        let mut phase = 0.0;
+        let mut previous_omega = None;
+        // Stream function waves where their solution converges, linear waves otherwise
+        let stream_function = self.generation_theory() == WaveTheory::StreamFunction;
+        for window in windows {
+            let params = self.linear_wave_parameters(window.wave_height, window.wave_period);
+            if let Some(omega) = previous_omega {
+                phase += (params.omega - omega) * window.start;
+            }
+            previous_omega = Some(params.omega);
+            let source = WaveSource::boundary(params)
+                .with_phase(phase)
+                .with_start_time(window.start)
+                .with_generation_duration(window.duration);
+            forcing.add_source(match self.stream_function_wave(window.wave_height, window.wave_period) {
+                Ok(wave) if stream_function => source.with_stream_function(wave),
+                _ => source,
+            });
+        }
+
+        for settings in &self.additional_sources {
+            let params = self.linear_wave_parameters(settings.wave_height, settings.wave_period);
+            forcing.add_source(
+                WaveSource::internal(params, settings.position)
+                    .with_phase(settings.phase.to_radians())
+                    .with_generation_duration(self.number_of_waves as f64 * settings.wave_period),
+            );
+        }
+        
+        forcing.update_time(self.simulation_time);
+        forcing
+    }
+    
+    /// Regular components of the boundary signal, or of the irregular waves of the boundary spectrum with the phases of the random seed
+    /// Empty without either, when a storm hydrograph replaces them, or when the signal cannot be evaluated
+    pub fn spectral_components(&self) -> Vec<SpectralComponent> {
+        match (&self.storm_hydrograph, &self.boundary_signal, &self.boundary_spectrum) {
+            (None, Some(signal), _) => signal.components(self.wave_height, self.wave_period, self.generation_duration()).unwrap_or_default(),
+            (None, None, Some(_)) => self.flume_spectrum().map(|spectrum| spectrum.components(SPECTRUM_COMPONENTS, &self.rng())).unwrap_or_default(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Boundary spectrum of the waves generated down the flume, projected from the directional sea when it has a spreading
+    pub fn flume_spectrum(&self) -> Option<WaveSpectrum> {
+        let spectrum = self.boundary_spectrum.clone()?;
+        Some(match self.boundary_spreading {
+            Some(spreading) => DirectionalSpectrum::new(spectrum, spreading).flume_spectrum(),
+            None => spectrum,
+        })
+    }
+
+    /// Time the wavemaker waves are generated for, and the length of an expression signal [s]
+    fn generation_duration(&self) -> f64 {
+        self.number_of_waves as f64 * self.wave_period
+    }
+
+    /// Emulated paddle, if the wavemaker is specified by stroke and frequency
+    pub fn wavemaker(&self) -> Option<Wavemaker> {
+        let paddle = self.paddle_type?;
+        Wavemaker::new(paddle, Length::meters(self.paddle_stroke), Time::seconds(self.wave_period), Length::meters(self.still_water_level)).ok()
+    }
+    
+    /// Derive the wave height from the emulated paddle
+    pub fn sync_paddle_wave_height(&mut self) {
+        if let Some(wavemaker) = self.wavemaker() {
+            self.wave_height = wavemaker.generated_wave_height();
+        }
+    }
+    
+    /// Wave parameters from linear theory, without breaking validation so steep waves can still be shown
+    /// Stream function wave of a height and period in the still water depth, with the solver settings
+    /// Solutions are kept, so the forcing rebuilt every frame only solves for new waves
+    pub fn stream_function_wave(&self, wave_height: f64, wave_period: f64) -> Result<StreamFunctionWave, CoastalError> {
+        let key = (wave_height.to_bits(), wave_period.to_bits(), self.still_water_level.to_bits(), self.stream_function_harmonics, self.stream_function_steps);
+        if let Some((_, solution)) = self.stream_functions.borrow().iter().find(|(solved, _)| *solved == key) {
+            return solution.clone();
+        }
+        let solution = StreamFunctionSolver::new()
+            .with_harmonics(self.stream_function_harmonics)
+            .with_height_steps(self.stream_function_steps)
+            .solve(Length::meters(wave_height), Time::seconds(wave_period), Length::meters(self.still_water_level));
+        let mut solved = self.stream_functions.borrow_mut();
+        if solved.len() == STREAM_FUNCTION_CACHE {
+            solved.drain(..1);
+        }
+        solved.push((key, solution.clone()));
+        solution
+    }
+
+    /// Linear waves of the wavemaker, on the ambient current if any, where they are not blocked
+    fn linear_wave_parameters(&self, wave_height: f64, wave_period: f64) -> WaveParameters {
+        let omega = 2.0 * std::f64::consts::PI / wave_period;
+        let k = match self.ambient_current.map(|current| current.wave_number(&DispersionSolver::linear(), omega, self.still_water_level)) {
+            Some(Ok(k)) => k,
+            _ => 2.0 * std::f64::consts::PI / Self::linear_wavelength(wave_period, self.still_water_level),
+        };
+        let wavelength = 2.0 * std::f64::consts::PI / k;
+        WaveParameters {
+            k,
+            omega,
+            c: omega / k,
+            h: wave_height,
+            d: self.still_water_level,
+            period: wave_period,
+            wavelength,
+        }
+    }
+
+    fn is_tooltip_open(&self, tooltip_id: &str) -> bool {
+        self.open_tooltips.contains(tooltip_id)
+    }
+
+    fn toggle_tooltip(&mut self, tooltip_id: &str) {
+        if self.open_tooltips.contains(tooltip_id) {
+            self.open_tooltips.remove(tooltip_id);
+        } else {
+            self.open_tooltips.insert(tooltip_id.to_string());
+        }
+    }
+
+    fn close_tooltip(&mut self, tooltip_id: &str) {
+        self.open_tooltips.remove(tooltip_id);
+    }
+
+    /// In help mode, make the control on the current row the help topic when it is hovered or focused
+    /// Returns whether help mode is on, in which case the info popup is not used
+    fn update_help_topic(&mut self, ui: &egui::Ui, topic: impl FnOnce() -> HelpTopic) -> bool {
+        if !self.help_mode {
+            return false;
+        }
+        let row = ui.max_rect();
+        let focused = ui
+            .ctx()
+            .memory(|memory| memory.focused())
+            .and_then(|id| ui.ctx().read_response(id))
+            .is_some_and(|response| row.contains(response.rect.center()));
+        if focused || ui.rect_contains_pointer(row) {
+            self.help_topic = Some(topic());
+        }
+        true
+    }
+
+    fn info_button(&mut self, ui: &mut egui::Ui, tooltip_id: &str, tooltip_text: &str) {
+        if self.update_help_topic(ui, || HelpTopic::new(tooltip_id, tooltip_text)) {
+            ui.add_space(5.0);
+            ui.small_button("?").on_hover_text("Explained in the help pane");
+            return;
+        }
+        ui.add_space(5.0);
+        let button_response = ui.small_button("?");
+        
+        if button_response.clicked() {
+            self.toggle_tooltip(tooltip_id);
+        }
+        
+        if self.is_tooltip_open(tooltip_id) {
+            let popup_id = egui::Id::new(format!("tooltip_{}", tooltip_id));
+            let area_response = egui::Area::new(popup_id)
+                .fixed_pos(button_response.rect.right_top() + egui::vec2(5.0, 0.0))
+                .show(ui.ctx(), |ui| {
+                    egui::Frame::popup(ui.style())
+                        .inner_margin(egui::Margin::same(8))
+                        .show(ui, |ui| {
+                            ui.set_max_width(300.0);
+                            ui.vertical(|ui| {
+                                ui.horizontal(|ui| {
+                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                                        if ui.small_button("✖").clicked() {
+                                            self.close_tooltip(tooltip_id);
+                                        }
+                                    });
+                                });
+                                ui.label(tooltip_text);
+                            });
+                        });
+                });
+            
+            // Check for click outside to close tooltip
+            if ui.input(|i| i.pointer.any_click()) && !area_response.response.hovered() && !button_response.hovered() {
+                self.close_tooltip(tooltip_id);
+            }
+        }
+    }
+
+    fn equation_info_button(&mut self, ui: &mut egui::Ui, ctx: &egui::Context, equation_renderer: &mut EquationRenderer, tooltip_id: &str, equation_id: &str, text_parts: (&str, &str)) {
+        if self.update_help_topic(ui, || HelpTopic::with_equation(tooltip_id, equation_id, text_parts)) {
+            ui.add_space(5.0);
+            ui.small_button("?").on_hover_text("Explained in the help pane");
+            return;
+        }
+        ui.add_space(5.0);
+        let button_response = ui.small_button("?");
+        
+        if button_response.clicked() {
+            self.toggle_tooltip(tooltip_id);
+        }
+        
+        if self.is_tooltip_open(tooltip_id) {
+            let popup_id = egui::Id::new(format!("tooltip_{}", tooltip_id));
+            let area_response = egui::Area::new(popup_id)
+                .fixed_pos(button_response.rect.right_top() + egui::vec2(5.0, 0.0))
+                .show(ui.ctx(), |ui| {
+                    egui::Frame::popup(ui.style())
+                        .inner_margin(egui::Margin::same(8))
+                        .show(ui, |ui| {
+                            ui.set_max_width(450.0);
+                            ui.vertical(|ui| {
+                                // Close button at the top right
+                                ui.horizontal(|ui| {
+                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                                        if ui.small_button("✖").clicked() {
+                                            self.close_tooltip(tooltip_id);
+                                        }
+                                    });
+                                });
+                                
+                                // Show text before equation
+                                if !text_parts.0.is_empty() {
+                                    ui.label(text_parts.0);
+                                }
+                                
+                                // Show the equation inline with text
+                                equation_renderer.show_equation(ui, ctx, equation_id, 400.0);
+
+                                // Show text after equation
+                                if !text_parts.1.is_empty() {
+                                    ui.label(text_parts.1);
+                                }
+                            });
+                        });
+                });
+            
+            // Check for click outside to close tooltip
+            if ui.input(|i| i.pointer.any_click()) && !area_response.response.hovered() && !button_response.hovered() {
+                self.close_tooltip(tooltip_id);
+            }
+        }
+    }
+
+    fn classify_water_depth(h: f64, wavelength: f64) -> WaterDepthRegime {
+        let ratio = h / wavelength;
+        if ratio < 1.0 / 20.0 {
+            WaterDepthRegime::Shallow
+        } else if ratio > 0.5 {
+            WaterDepthRegime::Deep
+        } else {
+            WaterDepthRegime::Intermediate
+        }
+    }
+
+    /// Wavelength of a wave period at a depth from the exact linear dispersion relation, the long-wave length if it has no solution [m]
+    pub(super) fn linear_wavelength(period: f64, depth: f64) -> f64 {
+        DispersionSolver::linear()
+            .wavelength(Time::seconds(period), Length::meters(depth))
+            .map_or(period * (9.81 * depth.max(0.0)).sqrt(), Length::in_meters)
+    }
+
+    /// Phase velocity of a wave period at a depth from the exact linear dispersion relation [m/s]
+    fn linear_celerity(period: f64, depth: f64) -> f64 {
+        Self::linear_wavelength(period, depth) / period
+    }
+
+    /// Refill the water surface and the channel bottom of the view in place
+    pub fn fill_plot_data(&mut self) {
+        let mut series = std::mem::take(&mut self.series);
+        let dx = self.grid_spacing();
+        // Water surface (still water level + surface elevation), resting on the bed where it is dry
+        let surface = self.displayed_surface();
+        refill(
+            &mut series.surface,
+            surface.iter().take(self.grid_resolution).enumerate().map(|(i, &eta)| {
+                let x = i as f64 * dx;
+                PlotPoint::new(x, (self.still_water_level + eta).max(self.bed_elevation(x)))
+            }),
+        );
+        // Channel bottom (offshore bed at 0)
+        refill(&mut series.bed, (0..self.grid_resolution).map(|i| PlotPoint::new(i as f64 * dx, self.bed_elevation(i as f64 * dx))));
+
+        series.foam.clear();
+        if self.show_foam {
+            self.fill_breaking_cells(&mut series.breaking);
+            for (i, &breaking) in series.breaking.iter().enumerate().take(series.surface.len()) {
+                match series.foam.last_mut() {
+                    Some(stretch) if breaking && stretch.end == i => stretch.end = i + 1,
+                    _ if breaking => series.foam.push(i..i + 1),
+                    _ => {}
+                }
+            }
+        }
+        if self.show_extrema {
+            self.fill_surface_extrema(&mut series.crests, &mut series.troughs);
+            series.wavelength = self.wavelength_from_crests(series.crests.iter().map(|crest| crest.x));
+        } else {
+            series.crests.clear();
+            series.troughs.clear();
+            series.wavelength = None;
+        }
+        self.series = series;
+    }
+
+    /// Surface and bed of the channel view as last filled
+    pub fn series(&self) -> &ChannelSeries {
+        &self.series
+    }
+
+    /// Height of the bed above the offshore bed at a position along the channel [m]
+    pub fn bed_elevation(&self, x: f64) -> f64 {
+        self.cross_section.as_ref().map_or(0.0, |section| section.bed_elevation(x))
+    }
+
+    /// Elevation in the selected datum of a level above the bed at the wavemaker [m]
+    pub fn level_in_datum(&self, elevation: f64) -> f64 {
+        self.reference_levels.to_datum(self.datum, elevation, self.still_water_level)
+    }
+
+    /// Set the still water level in the selected datum, keeping the bed in place
+    pub fn set_water_level_in_datum(&mut self, level: f64) {
+        let rise = level - self.reference_levels.water_level(self.datum, self.still_water_level);
+        self.reference_levels.still_water += rise;
+        self.still_water_level += rise;
+    }
+
+    /// Set the level of the bed at the wavemaker in the selected datum, keeping the still water level
+    pub fn set_bed_level_in_datum(&mut self, level: f64) {
+        if self.datum != VerticalDatum::ChannelBed {
+            self.still_water_level -= level - self.level_in_datum(0.0);
+        }
+    }
+
+    /// Labels of the cross-section segments, under the bed at the middle of each segment
+    fn segment_labels(&self) -> Vec<(PlotPoint, String)> {
+        let Some(section) = &self.cross_section else {
+            return Vec::new();
+        };
+        let mut start = 0.0;
+        let mut labels = Vec::new();
+        for segment in &section.segments {
+            let middle = (start + 0.5 * segment.length).min(self.channel_length);
+            if start < self.channel_length && !segment.label.is_empty() {
+                labels.push((PlotPoint::new(middle, section.bed_elevation(middle)), segment.label.clone()));
+            }
+            start += segment.length;
+        }
+        labels
+    }
+
+    /// Analytical solution of the initial-value scenario at the current time, if any
+    fn analytical_plot_data(&self) -> Option<PlotPoints<'static>> {
+        let condition = self.initial_condition?;
+        let points = (0..self.grid_resolution)
+            .map(|i| {
+                let x = i as f64 * self.grid_spacing();
+                let eta = condition.analytical_elevation(x, self.displayed_time(), self.still_water_level);
+                [x, self.still_water_level + eta]
+            })
+            .collect();
+        Some(points)
+    }
+
+    /// Timeline of the stored snapshots for reviewing any instant of the run
+    fn show_playback_bar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Playback:");
+            self.info_button(ui, "playback", "Every time step is kept for the most recent minute. Older snapshots are thinned out, one every 0.5 s at first and twice as sparse whenever the history fills up, so even runs of a thousand waves can be replayed with bounded memory. Click or drag on the timeline to show the surface at that time; scroll or box-zoom to scrub within a window. Shortcuts: ← and → step one frame, Home and End jump to the first and last frames, Space replays, I and O set the loop in and out points, Esc returns to the simulation.");
+        });
+        self.timeline.set_exact_scrubbing(self.snapshot_file.is_some());
+        let events: Vec<(f64, String)> = self.parameter_log.changes().iter().map(|change| (change.time, change.to_string())).collect();
+        self.timeline.show(ui, &self.snapshots, &events, self.repaint_policy);
+        if let (Some(start), Some(end)) = (self.snapshots.start_time(), self.snapshots.end_time()) {
+            self.show_hydrograph_curves(ui, "hydrograph_timeline", Some((start, end)));
+        }
+
+        // Reviewing a stored instant pauses the simulation
+        if self.timeline.cursor().is_some() {
+            self.simulation_running = false;
+            self.sync_snapshot_file();
+        }
+    }
+
+    /// Linear-theory surface of the wavemaker waves at the displayed time, η = a cos(kx − ωt) [m]
+    /// Defined where the wave train has arrived at the linear group velocity and not yet left,
+    /// None for initial-value scenarios and cases with additional sources
+    pub fn linear_theory_surface(&self) -> Option<Vec<Option<f64>>> {
+        let calculator = VelocityCalculator::new(self.linear_wave_parameters(self.wave_height, self.wave_period));
+        self.wave_train_surface(|x, time| calculator.surface_elevation(x, time))
+    }
+
+    /// Stream function surface of the wavemaker waves at the displayed time, over the same wave train as linear theory
+    /// None where linear theory has none, or when the stream function solution fails
+    pub fn stream_function_surface(&self) -> Option<Vec<Option<f64>>> {
+        let wave = self.stream_function_wave(self.wave_height, self.wave_period).ok()?;
+        self.wave_train_surface(|x, time| wave.surface_elevation(x, time))
+    }
+
+    /// Surface of a theory of the wavemaker waves over the part of the channel their train occupies at the displayed time
+    fn wave_train_surface(&self, surface_elevation: impl Fn(f64, f64) -> f64) -> Option<Vec<Option<f64>>> {
+        if self.initial_condition.is_some() || !self.additional_sources.is_empty() || !self.forcing_schedule.is_empty() || self.storm_hydrograph.is_some() || self.boundary_spectrum.is_some() || self.boundary_signal.is_some() {
+            return None;
+        }
+        let params = self.linear_wave_parameters(self.wave_height, self.wave_period);
+        let kd = params.k * params.d;
+        // The wave energy travels at the group velocity relative to the water, carried by the current
+        let current = self.ambient_current.map_or(0.0, |current| current.doppler_velocity(params.k, params.d));
+        let group_velocity = 0.5 * (1.0 + 2.0 * kd / (2.0 * kd).sinh()) * (params.c - current) + current;
+        let generation_duration = self.number_of_waves as f64 * self.wave_period;
+        let time = self.displayed_time();
+
+        let surface = (0..self.grid_resolution)
+            .map(|i| {
+                let x = i as f64 * self.grid_spacing();
+                let emission_time = time - x / group_velocity;
+                (0.0..=generation_duration).contains(&emission_time).then(|| surface_elevation(x, time))
+            })
+            .collect();
+        Some(surface)
+    }
+
+    /// Root-mean-square difference between the displayed surface and linear theory over the wave train [m]
+    pub fn linear_theory_difference(&self) -> Option<f64> {
+        self.theory_difference(&self.linear_theory_surface()?)
+    }
+
+    /// Root-mean-square difference between the displayed surface and stream function theory over the wave train [m]
+    pub fn stream_function_difference(&self) -> Option<f64> {
+        self.theory_difference(&self.stream_function_surface()?)
+    }
+
+    /// Root-mean-square difference between the displayed surface and the surface of a theory where it is defined [m]
+    fn theory_difference(&self, theory: &[Option<f64>]) -> Option<f64> {
+        let squares: Vec<f64> = self
+            .displayed_surface()
+            .iter()
+            .zip(theory)
+            .filter_map(|(eta, theory)| theory.map(|theory| (eta - theory).powi(2)))
+            .collect();
+        if squares.is_empty() {
+            return None;
+        }
+        Some((squares.iter().sum::<f64>() / squares.len() as f64).sqrt())
+    }
+
+    /// Cells of the displayed surface on a breaking front: wet, with a surface slope |∂η/∂x| steeper than the bore detection threshold
+    /// Only the numerical solution breaks; the linear superposition has none
+    pub fn breaking_cells(&self) -> Vec<bool> {
+        let mut cells = Vec::new();
+        self.fill_breaking_cells(&mut cells);
+        cells
+    }
+
+    /// Refill the breaking cells of the displayed surface in place
+    fn fill_breaking_cells(&self, cells: &mut Vec<bool>) {
+        let surface = self.displayed_surface();
+        cells.clear();
+        if !self.is_numerical() {
+            cells.resize(surface.len(), false);
+            return;
+        }
+        let dx = self.grid_spacing();
+        let last = surface.len().saturating_sub(1);
+        cells.extend((0..surface.len()).map(|i| {
+            let (left, right) = (i.saturating_sub(1), (i + 1).min(last));
+            let depth = self.still_water_level + surface[i] - self.bed_elevation(i as f64 * dx);
+            right > left && depth > WET_DEPTH && (surface[right] - surface[left]).abs() / ((right - left) as f64 * dx) >= self.bore_criteria.min_gradient
+        }));
+    }
+
+    /// Crests and troughs of the displayed surface in the wet channel, as plot points
+    /// Extrema within a tenth of the wave height of still water are ignored
+    pub fn surface_extrema(&self) -> (Vec<[f64; 2]>, Vec<[f64; 2]>) {
+        let (mut crests, mut troughs) = (Vec::new(), Vec::new());
+        self.fill_surface_extrema(&mut crests, &mut troughs);
+        (ChannelSeries::pairs(&crests), ChannelSeries::pairs(&troughs))
+    }
+
+    /// Refill the crests and troughs of the displayed surface in the wet channel in place
+    fn fill_surface_extrema(&self, crests: &mut Vec<PlotPoint>, troughs: &mut Vec<PlotPoint>) {
+        let surface = self.displayed_surface();
+        let threshold = 0.1 * self.wave_height;
+        let wet = |position: f64, eta: f64| self.still_water_level + eta - self.bed_elevation(position) > WET_DEPTH;
+        crests.clear();
+        troughs.clear();
+        visit_crests(&surface, self.grid_spacing(), threshold, |position, eta, _| {
+            if wet(position, eta) {
+                crests.push(PlotPoint::new(position, self.still_water_level + eta));
+            }
+        });
+        visit_troughs(&surface, self.grid_spacing(), -threshold, |position, eta| {
+            if wet(position, eta) {
+                troughs.push(PlotPoint::new(position, self.still_water_level + eta));
+            }
+        });
+    }
+
+    /// Mean distance between the crests of the displayed surface against the linear wavelength of the wave period
+    /// None for initial-value scenarios, which have no period, and with fewer than two crests
+    pub fn wavelength_reading(&self) -> Option<WavelengthReading> {
+        self.wavelength_from_crests(self.surface_extrema().0.iter().map(|crest| crest[0]))
+    }
+
+    /// Reading of the wavelength from the positions of the crests along the channel
+    fn wavelength_from_crests(&self, crests: impl IntoIterator<Item = f64>) -> Option<WavelengthReading> {
+        if self.initial_condition.is_some() {
+            return None;
+        }
+        let omega = 2.0 * std::f64::consts::PI / self.wave_period;
+        WavelengthReading::from_crests(crests, |x| {
+            let depth = self.still_water_depth(x);
+            (depth > 0.0).then(|| linear_wave_number(omega, depth, 9.81).ok()).flatten().map(|k| 2.0 * std::f64::consts::PI / k)
+        })
+    }
+
+    /// Surface elevation recorded by every gauge, with the annotations of the records
+    fn show_gauge_records(&mut self, ui: &mut egui::Ui) {
+        let style = self.plot_style;
+        let plot = Plot::new("gauge_records")
+            .height(220.0)
+            .width((ui.available_width() - 40.0).max(400.0))
+            .x_axis_label("Time (s)")
+            .y_axis_label("η (m)")
+            .legend(Legend::default());
+        let clicked = style.show_plot(ui, plot, |plot_ui| {
+            for (i, gauge) in self.gauges.iter().enumerate() {
+                let points: PlotPoints = gauge.times().iter().zip(gauge.elevation()).map(|(&time, &elevation)| [time, elevation]).collect();
+                plot_ui.line(Line::new(points).color(style.series(i)).width(style.width(1.5)).name(format!("Gauge {} (x = {:.1} m)", i + 1, gauge.position())));
+            }
+            draw_annotations(plot_ui, &self.annotations, AnnotatedPlot::GaugeRecords, style.annotation());
+            if self.measure_tool == MeasureTool::Wave {
+                plot_ui.points(Points::new(self.measure_points.clone()).shape(MarkerShape::Cross).radius(6.0).color(style.marker()));
+            }
+            plot_ui.response().clicked().then(|| plot_ui.pointer_coordinate()).flatten()
+        });
+        if let Some(point) = clicked.inner
+            && let Some(text) = self.measure_click(AnnotatedPlot::GaugeRecords, [point.x, point.y])
+        {
+            ui.ctx().copy_text(text);
+        }
+    }
+
+    /// Pick a point for the measurement tool on a plot, returning the measurement once its second point is picked
+    pub fn measure_click(&mut self, plot: AnnotatedPlot, point: [f64; 2]) -> Option<String> {
+        let measured_plot = match self.measure_tool {
+            MeasureTool::Off => return None,
+            MeasureTool::Slope => AnnotatedPlot::Channel,
+            MeasureTool::Wave => AnnotatedPlot::GaugeRecords,
+        };
+        if plot != measured_plot {
+            return None;
+        }
+        self.measure_points.push(point);
+        if self.measure_points.len() < 2 {
+            return None;
+        }
+        let (first, second) = (self.measure_points[0], self.measure_points[1]);
+        self.measure_points.clear();
+        let result = match self.measure_tool {
+            MeasureTool::Slope => Ok(SlopeMeasurement { start: first, end: second }.to_text()),
+            _ => self.wave_measurement(first, second),
+        };
+        self.measurement = Some(result.clone());
+        result.ok()
+    }
+
+    /// Wave between two crests picked on the records, on the gauge passing closest to the first pick
+    fn wave_measurement(&self, first: [f64; 2], second: [f64; 2]) -> Result<String, String> {
+        let distance = |gauge: &WaveGauge| {
+            let times = gauge.times();
+            let i = times.partition_point(|&t| t < first[0]).min(times.len() - 1);
+            (gauge.elevation()[i] - first[1]).abs()
+        };
+        let (i, gauge) = self
+            .gauges
+            .iter()
+            .enumerate()
+            .filter(|(_, gauge)| !gauge.is_empty())
+            .min_by(|(_, a), (_, b)| distance(a).total_cmp(&distance(b)))
+            .ok_or("The gauges have no records yet")?;
+        let wave = WaveMeasurement::from_record(gauge.times(), gauge.elevation(), first[0], second[0])?;
+        Ok(format!("Gauge {}: {}", i + 1, wave.to_text()))
+    }
+
+    /// Measurement tool selection and the last measurement
+    fn show_measure_controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Measure:");
+            for tool in [MeasureTool::Off, MeasureTool::Slope, MeasureTool::Wave] {
+                if ui.selectable_value(&mut self.measure_tool, tool, tool.to_string()).clicked() {
+                    self.measure_points.clear();
+                }
+            }
+            self.info_button(ui, "measure_tools", "Distance and Slope: click two points on the channel view to read the horizontal distance Δx, the rise Δz from the seaward to the landward point and the slope Δz/Δx, e.g. of a beach face or a structure. Period and Height: click two crests of a gauge record; each click moves to the top of its crest on the gauge closest to the first click, and the period is the time between the crests and the height the mean crest elevation above the lowest trough between them. Each completed measurement is copied to the clipboard.");
+        });
+        let hint = match (self.measure_tool, self.measure_points.len()) {
+            (MeasureTool::Off, _) => None,
+            (MeasureTool::Slope, 0) => Some("Click the first point on the channel view"),
+            (MeasureTool::Slope, _) => Some("Click the second point on the channel view"),
+            (MeasureTool::Wave, 0) => Some("Click a crest on the gauge records"),
+            (MeasureTool::Wave, _) => Some("Click another crest on the gauge records"),
+        };
+        if let Some(hint) = hint {
+            ui.label(hint);
+        }
+        match &self.measurement {
+            Some(Ok(text)) => {
+                ui.label(format!("📋 {}", text));
+            }
+            Some(Err(message)) => {
+                ui.colored_label(egui::Color32::YELLOW, format!("⚠ {}", message));
+            }
+            None => {}
+        }
+    }
+
+    /// Annotations of the channel and gauge record plots and the editor adding new ones
+    fn show_annotations(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Annotations").id_salt("annotations").show(ui, |ui| {
+            let mut removed = None;
+            for (i, annotation) in self.annotations.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut annotation.text).desired_width(140.0));
+                    let (axis, unit) = annotation.plot.axis();
+                    ui.label(format!("{} {}, {} = {:.2} {}", annotation.plot, annotation.shape, axis, annotation.position[0], unit));
+                    if ui.small_button("✖").on_hover_text("Remove annotation").clicked() {
+                        removed = Some(i);
+                    }
+                });
+            }
+            if let Some(i) = removed {
+                self.annotations.remove(i);
+            }
+
+            let draft = &mut self.annotation_draft;
+            ui.horizontal(|ui| {
+                ui.label("New:");
+                for plot in [AnnotatedPlot::Channel, AnnotatedPlot::GaugeRecords] {
+                    ui.selectable_value(&mut draft.plot, plot, plot.to_string());
+                }
+                ui.separator();
+                let [x, y] = draft.position;
+                for shape in [AnnotationShape::Label, AnnotationShape::Arrow { tip: [x + 1.0, y] }, AnnotationShape::Marker] {
+                    let selected = std::mem::discriminant(&draft.shape) == std::mem::discriminant(&shape);
+                    if ui.selectable_label(selected, shape.to_string()).clicked() && !selected {
+                        draft.shape = shape;
+                    }
+                }
+            });
+            let mut added = false;
+            ui.horizontal(|ui| {
+                let (axis, unit) = draft.plot.axis();
+                ui.add(egui::DragValue::new(&mut draft.position[0]).speed(0.1).prefix(format!("{} = ", axis)).suffix(format!(" {}", unit)));
+                if draft.shape != AnnotationShape::Marker {
+                    ui.add(egui::DragValue::new(&mut draft.position[1]).speed(0.01).prefix("z = ").suffix(" m"));
+                }
+                if let AnnotationShape::Arrow { tip } = &mut draft.shape {
+                    ui.label("→");
+                    ui.add(egui::DragValue::new(&mut tip[0]).speed(0.1).prefix(format!("{} = ", axis)).suffix(format!(" {}", unit)));
+                    ui.add(egui::DragValue::new(&mut tip[1]).speed(0.01).prefix("z = ").suffix(" m"));
+                }
+                ui.add(egui::TextEdit::singleline(&mut draft.text).hint_text("Text").desired_width(120.0));
+                added = ui.add_enabled(!draft.text.trim().is_empty(), egui::Button::new("➕ Add")).clicked();
+            });
+            if added {
+                self.annotations.push(self.annotation_draft.clone());
+                self.annotation_draft.text.clear();
+            }
+            self.info_button(ui, "annotations", "Notes on the channel view (positions x along the channel and elevations z above the bed) or on the gauge records (times t and surface elevations): a label at a point, a label with an arrow pointing at a feature, or a vertical marker at a position or time. Annotations are saved with the project and listed after the run metadata when it is copied for a report.");
+        });
+    }
+
+    /// Store the shown surface and bed in the gallery, with the conditions of the case
+    pub fn capture_snapshot(&mut self) {
+        self.fill_plot_data();
+        let metadata = format!(
+            "H = {:.3} m, T = {:.2} s, h = {:.2} m, {}",
+            self.wave_height,
+            self.wave_period,
+            self.still_water_level,
+            self.solver_description()
+        );
+        self.gallery.capture(self.displayed_time(), ChannelSeries::pairs(&self.series.surface), ChannelSeries::pairs(&self.series.bed), metadata);
+    }
+
+    /// Captured channel states and their comparison strip
+    fn show_gallery(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Snapshot Gallery").id_salt("snapshot_gallery").show(ui, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("📷 Capture Snapshot").clicked() {
+                    self.capture_snapshot();
+                }
+                self.info_button(ui, "snapshot_gallery", "Stores the channel view as shown, at the playback position when scrubbing, with its wave conditions and propagation model (hover over the time of a capture). Captures last for the session and survive changes of the case, so states before and after breaking, or with different structures, can be compared: overlaid on one plot, where beds that differ are dashed in the color of their surface, or stacked with linked axes.");
+            });
+            let style = self.plot_style;
+            self.gallery.show(ui, style);
+        });
+    }
+
+    /// Water fill and foam toggles of the channel view
+    fn show_water_display_controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.fill_water, "Fill Water");
+            ui.checkbox(&mut self.show_foam, "Foam on Breaking Fronts");
+            ui.checkbox(&mut self.show_extrema, "Crests and Troughs");
+            self.info_button(ui, "water_fill", "The water between the bed and the free surface is filled, with the sky above it, at the fill opacity of the plot style. Foam whitens the surface of the numerical solution where a front is breaking: where the water is wet and the surface slope |∂η/∂x| exceeds the slope threshold of the bore detection.");
+            self.info_button(ui, "surface_extrema", "Marks the crests (▲) and troughs (▼) of the shown surface, the local extrema more than a tenth of the wave height from still water, refined between grid points. The overlay compares the mean distance between neighbouring crests with the linear wavelength L = 2π/k of the wave period, from the exact dispersion relation ω² = gk tanh(kd) at the still water depth midway between them. A shorter measured wavelength reveals shoaling or numerical dispersion; irregular waves spread around the wavelength of the peak period.");
+        });
+    }
+
+    /// Linear-theory overlay toggle and difference readout for the numerical solution
+    fn show_linear_overlay_controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.show_linear_overlay, "Overlay Linear Theory");
+            if self.show_linear_overlay {
+                match self.linear_theory_difference() {
+                    Some(difference) => {
+                        let amplitude = 0.5 * self.wave_height;
+                        ui.label(format!("L2 Difference: {:.4} m ({:.1}% of amplitude)", difference, 100.0 * difference / amplitude));
+                    }
+                    None if self.linear_theory_surface().is_none() => {
+                        ui.label("Linear theory is only available for the wavemaker alone.");
+                    }
+                    None => {
+                        ui.label("No waves in the channel yet.");
+                    }
+                }
+            }
+            self.info_button(ui, "linear_overlay", "Draws the linear-theory surface η = (H/2) cos(kx − ωt) of the wavemaker waves, with k from the exact dispersion relation, over the part of the channel the wave train has reached at the linear group velocity. The L2 difference is the root-mean-square of η_numerical − η_linear over that part. A growing phase shift reveals numerical dispersion, a shrinking crest numerical dissipation. Works during playback too.");
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.show_stream_function_overlay, "Overlay Stream Function Theory");
+            if self.show_stream_function_overlay {
+                match self.stream_function_difference() {
+                    Some(difference) => {
+                        let amplitude = 0.5 * self.wave_height;
+                        ui.label(format!("L2 Difference: {:.4} m ({:.1}% of amplitude)", difference, 100.0 * difference / amplitude));
+                    }
+                    None if self.linear_theory_surface().is_none() => {
+                        ui.label("Stream function theory is only available for the wavemaker alone.");
+                    }
+                    None if self.stream_function_surface().is_none() => {
+                        ui.label("The stream function wave did not converge.");
+                    }
+                    None => {
+                        ui.label("No waves in the channel yet.");
+                    }
+                }
+            }
+            self.info_button(ui, "stream_function_overlay", "Draws the surface of the steady stream function wave of the wavemaker height and period, with its peaked crests and flat troughs and its nonlinear celerity, over the same part of the channel as the linear overlay. With the stream function theory generated at the wavemaker, the L2 difference shows how well the model keeps the wave form; with linear generation, how far the waves are from their steady nonlinear form.");
+        });
+    }
+
+    /// Contiguous stretches of the surface of a theory, as plot points
+    fn overlay_segments(&self, surface: Option<Vec<Option<f64>>>) -> Vec<Vec<[f64; 2]>> {
+        let Some(surface) = surface else {
+            return Vec::new();
+        };
+        let mut segments = vec![Vec::new()];
+        for (i, eta) in surface.into_iter().enumerate() {
+            match eta {
+                Some(eta) => segments.last_mut().unwrap().push([i as f64 * self.grid_spacing(), self.still_water_level + eta]),
+                None if !segments.last().unwrap().is_empty() => segments.push(Vec::new()),
+                None => {}
+            }
+        }
+        segments.retain(|segment| !segment.is_empty());
+        segments
+    }
+
+    /// Waves followed through the full-resolution snapshots for at least half a wave period
+    /// Crests lower than a tenth of the wave height are ignored
+    pub fn tracked_waves(&self) -> Vec<CrestTrack> {
+        let Some(recent_start) = self.snapshots.recent_start() else {
+            return Vec::new();
+        };
+        let max_celerity = 2.0 * (9.81 * self.still_water_level).sqrt();
+        track_crests(
+            self.snapshots.snapshots().filter(|snapshot| snapshot.time >= recent_start && snapshot.surface_elevation.len() == self.grid_resolution),
+            self.grid_spacing(),
+            0.1 * self.wave_height,
+            max_celerity,
+        )
+        .into_iter()
+        .filter(|track| track.duration() >= 0.5 * self.wave_period)
+        .collect()
+    }
+
+    /// Bores and hydraulic jumps followed through the full-resolution snapshots of the numerical solution
+    /// Fronts seen in fewer than three snapshots have no celerity and are ignored
+    pub fn detected_bores(&self) -> Vec<BoreTrack> {
+        let Some(recent_start) = self.snapshots.recent_start().filter(|_| self.is_numerical()) else {
+            return Vec::new();
+        };
+        let dx = self.grid_spacing();
+        let still_water_depth: Vec<f64> = (0..self.grid_resolution).map(|i| self.still_water_level - self.bed_elevation(i as f64 * dx)).collect();
+        track_bores(
+            self.snapshots.snapshots().filter(|snapshot| snapshot.time >= recent_start && snapshot.surface_elevation.len() == self.grid_resolution),
+            &still_water_depth,
+            dx,
+            self.bore_criteria,
+            2.0 * (9.81 * self.still_water_level).sqrt(),
+        )
+        .into_iter()
+        .filter(|track| track.celerity().is_some())
+        .collect()
+    }
+
+    /// Theory applicable to the wavemaker waves in the still water depth, from the Le Méhauté diagram
+    pub fn recommended_wave_theory(&self) -> Result<WaveTheory, CoastalError> {
+        WaveTheory::recommended(self.wave_height, self.wave_period, self.still_water_level)
+    }
+
+    /// Theory of the generated regular waves: the selected one, else the recommended one, else linear
+    pub fn generation_theory(&self) -> WaveTheory {
+        self.wave_theory.or(self.recommended_wave_theory().ok()).unwrap_or(WaveTheory::Linear)
+    }
+
+    /// Stream function wave of the wavemaker, with its convergence controls and its harmonics
+    fn show_stream_function(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Stream Function Wave").id_salt("stream_function").show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Fourier Modes:");
+                ui.add(egui::DragValue::new(&mut self.stream_function_harmonics).range(2..=64));
+                ui.label("Height Steps:");
+                ui.add(egui::DragValue::new(&mut self.stream_function_steps).range(1..=20));
+                self.info_button(ui, "stream_function", "Steady wave of the wavemaker height and period solved with the Fourier approximation method of Rienecker and Fenton (1981), the numerical stream function theory. In the frame moving with the crests the flow is steady and the stream function ψ = −ūz + Σ B_j sinh(jk(z + d))/cosh(jkd) cos(jkX) satisfies the Laplace equation and the bed condition; the surface, the coefficients B_j, the wave number and the celerity follow from the kinematic and Bernoulli conditions on the surface, solved with Newton's method with no mass transport, as in a closed flume. More Fourier modes resolve waves closer to breaking, and long waves in shallow water; the height is raised in steps from a linear wave, more steps helping the steepest waves converge. The table lists the amplitudes E_j of the harmonics of the surface elevation and the coefficients B_j: a last amplitude that is not small compared with the first calls for more modes. Generated at the wavemaker with the stream function theory.");
+            });
+            match self.stream_function_wave(self.wave_height, self.wave_period) {
+                Ok(wave) => {
+                    let linear = Self::linear_wavelength(self.wave_period, self.still_water_level);
+                    ui.label(format!(
+                        "Converged in {} Newton iterations: L = {:.3} m ({:+.1}% of linear), c = {:.3} m/s, crest {:.3} m, trough {:.3} m",
+                        wave.iterations(),
+                        wave.wavelength(),
+                        100.0 * (wave.wavelength() / linear - 1.0),
+                        wave.celerity(),
+                        wave.crest_elevation(),
+                        wave.trough_elevation()
+                    ));
+                    ui.label(format!("Eulerian current {:.4} m/s under the troughs, the return flow of the mass transport", wave.eulerian_current()));
+                    let harmonics = wave.harmonics();
+                    if let Some(last) = harmonics.last()
+                        && last.amplitude.abs() > 1e-3 * harmonics[0].amplitude.abs()
+                    {
+                        ui.colored_label(egui::Color32::from_rgb(200, 80, 0), "⚠ The last harmonic is not negligible: increase the Fourier modes");
+                    }
+                    egui::ScrollArea::vertical().id_salt("stream_function_harmonics").max_height(200.0).show(ui, |ui| {
+                        egui::Grid::new("stream_function_harmonics_table").striped(true).show(ui, |ui| {
+                            ui.strong("j");
+                            ui.strong("E_j (m)");
+                            ui.strong("E_j / E_1");
+                            ui.strong("B_j (m²/s)");
+                            ui.end_row();
+                            for harmonic in &harmonics {
+                                ui.label(harmonic.order.to_string());
+                                ui.label(format!("{:.3e}", harmonic.amplitude));
+                                ui.label(format!("{:.3e}", harmonic.amplitude / harmonics[0].amplitude));
+                                ui.label(format!("{:.3e}", harmonic.coefficient));
+                                ui.end_row();
+                            }
+                        });
+                    });
+                }
+                Err(error) => {
+                    ui.colored_label(egui::Color32::from_rgb(200, 80, 0), format!("⚠ No stream function wave: {}", error));
+                }
+            }
+        });
+    }
+
+    /// Le Méhauté diagram of the ranges of the wave theories, with the wavemaker waves plotted
+    fn show_theory_diagram(&mut self, ui: &mut egui::Ui) {
+        let depth = self.still_water_level;
+        let scale = 9.81 * self.wave_period * self.wave_period;
+        let wavelength = Self::linear_wavelength(self.wave_period, depth);
+        ui.horizontal(|ui| {
+            match self.recommended_wave_theory() {
+                Ok(theory) => ui.label(format!(
+                    "Ursell number Ur = {:.1}, d/gT² = {:.4}, H/gT² = {:.5}: {} theory applies",
+                    ursell_number(self.wave_height, wavelength, depth),
+                    depth / scale,
+                    self.wave_height / scale,
+                    theory
+                )),
+                Err(error) => ui.label(format!("No applicable theory: {}", error)),
+            };
+            self.info_button(ui, "theory_diagram", "Ranges of validity of the wave theories after Le Méhauté (1976), in the relative depth d/gT² and the relative height H/gT², on logarithmic axes. The Ursell number Ur = HL²/d³ compares the nonlinearity H/d with the dispersion (d/L)²: above Ur = 26, in shallow water, cnoidal theory describes the long flat troughs and peaked crests better than Stokes theory. Linear (Airy) theory suffices while the second harmonic of second-order Stokes theory stays below 5% of the first, ak < 0.1 in deep water. Fifth-order Stokes theory takes over from the second order from half the breaking height, and close to breaking, above 80% of it, only the numerical stream function theory of Dean (1965) is accurate. The breaking limit is the smaller of the depth-limited and the steepness-limited heights. The boundaries are approximate and the theories overlap near them. The recommendation sets the theory of the generated regular waves unless another is selected.");
+        });
+
+        let range = (-4.0, -0.7);
+        let samples = 120;
+        let mut curves: [Vec<[f64; 2]>; 5] = Default::default();
+        for i in 0..=samples {
+            let log_depth = range.0 + (range.1 - range.0) * i as f64 / samples as f64;
+            let Ok(boundaries) = TheoryBoundaries::at(10f64.powf(log_depth)) else {
+                continue;
+            };
+            let below_breaking = |height: f64| height.min(boundaries.breaking).log10();
+            curves[0].push([log_depth, boundaries.breaking.log10()]);
+            curves[1].push([log_depth, boundaries.stream_function.log10()]);
+            if boundaries.cnoidal < boundaries.stream_function {
+                curves[2].push([log_depth, boundaries.cnoidal.log10()]);
+            }
+            if boundaries.stokes_fifth < boundaries.cnoidal {
+                curves[3].push([log_depth, boundaries.stokes_fifth.log10()]);
+            }
+            curves[4].push([log_depth, below_breaking(boundaries.linear.min(boundaries.cnoidal))]);
+        }
+        let names = ["Breaking", "Stream Function", "Cnoidal (Ur = 26)", "Stokes 5th Order", "Linear Limit"];
+        let point = [(depth / scale).log10(), (self.wave_height / scale).log10()];
+        let style = self.plot_style;
+        let plot = Plot::new("theory_diagram")
+            .height(260.0)
+            .x_axis_label("log₁₀ d/gT²")
+            .y_axis_label("log₁₀ H/gT²")
+            .include_x(range.0)
+            .include_x(range.1)
+            .legend(Legend::default());
+        style.show_plot(ui, plot, |plot_ui| {
+            for (series, (curve, name)) in curves.into_iter().zip(names).enumerate() {
+                let color = if series == 0 { style.alert() } else { style.series(series - 1) };
+                plot_ui.line(Line::new(PlotPoints::from(curve)).color(color).width(style.width(1.5)).name(name));
+            }
+            // Shallow water below d/L = 1/20, where L = T√(gd), and deep water above d/L = 1/2
+            plot_ui.vline(VLine::new((1.0 / 400.0f64).log10()).color(egui::Color32::GRAY).style(LineStyle::dashed_loose()).name("Shallow / Deep Water"));
+            plot_ui.vline(VLine::new((1.0 / (4.0 * std::f64::consts::PI)).log10()).color(egui::Color32::GRAY).style(LineStyle::dashed_loose()).name("Shallow / Deep Water"));
+            plot_ui.points(Points::new(vec![point]).color(style.alert()).radius(5.0).name("Wavemaker Waves"));
+        });
+    }
+
+    /// Linear-theory kinematics of the wavemaker waves for the Stokes drift, with the exact Airy dispersion
+    /// the drift and transport formulas rest on; None for initial-value scenarios
+    pub fn stokes_drift_calculator(&self) -> Option<VelocityCalculator> {
+        if self.initial_condition.is_some() {
+            return None;
+        }
+        let mut params = WaveParameters::new(Length::meters(self.wave_height), Time::seconds(self.wave_period), Length::meters(self.still_water_level)).ok()?;
+        params.update_from_dispersion(linear_wave_number(params.omega, params.d, 9.81).ok()?);
+        Some(VelocityCalculator::new(params))
+    }
+
+    /// Paths (time, x, z) of the tracer particles of the Stokes drift demonstration up to a time,
+    /// released on a grid of half wavelengths and quarter depths under a uniform wave train
+    pub fn tracer_paths(&self, time: f64) -> Vec<Vec<(f64, f64, f64)>> {
+        let Some(calculator) = self.stokes_drift_calculator() else {
+            return Vec::new();
+        };
+        let params = calculator.parameters();
+        let time_step = params.period / TRACER_STEPS_PER_PERIOD;
+        (0..4)
+            .flat_map(|column| (1..4).map(move |row| (0.5 * column as f64 * params.wavelength, -0.25 * row as f64 * params.d)))
+            .map(|(x, z)| calculator.particle_path(x, z, 0.0, time.max(0.0), time_step))
+            .collect()
+    }
+
+    /// Stokes drift profile and mass transport of the wavemaker waves, with tracer particles drifting along their orbits
+    fn show_stokes_drift(&mut self, ui: &mut egui::Ui) {
+        let Some(calculator) = self.stokes_drift_calculator() else {
+            return;
+        };
+        let params = calculator.parameters();
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.show_tracers, "Tracer Particles");
+            self.info_button(ui, "stokes_drift", "Under linear waves the particles move on closed orbits at a fixed point, but not quite along them: a particle moves forward under the crest slightly faster, and for slightly longer, than it moves back under the trough, because it is then higher, where the orbital velocity is larger. The difference is the Stokes drift u_s = a²ωk cosh(2k(z + d)) / (2 sinh²(kd)), strongest at the surface and decaying with depth like the square of the orbital velocity. Integrated over the depth it gives the mass transport of the waves M = E/(ρc) = gH²/8c, shoreward. On an open coast it carries water towards the shore; in a closed channel or on a beach, the mean water level rises until an Eulerian return flow, the undertow, takes it back, so the depth-averaged net flow vanishes. The tracer particles are released on a grid under a uniform linear wave train at the wavemaker conditions and followed with the orbital velocities (RK4), drawn with their paths over the last wave period, wrapped into a window of two wavelengths; the measured drift of their orbit centres is plotted over the theoretical profile.");
+        });
+        ui.label(format!(
+            "Stokes drift: {:.2} cm/s at the surface, {:.2} cm/s at the bed, {:.2} cm/s depth-averaged; mass transport M = {:.2} l/s per metre",
+            100.0 * calculator.stokes_drift(0.0),
+            100.0 * calculator.stokes_drift(-params.d),
+            100.0 * calculator.mean_stokes_drift(),
+            1000.0 * calculator.mass_transport()
+        ));
+        ui.label(format!(
+            "Orbital velocity amplitude at the surface {:.2} m/s: surface particles advance {:.1} cm per wave period",
+            params.amplitude() * params.omega / (params.k * params.d).tanh(),
+            100.0 * calculator.stokes_drift(0.0) * params.period
+        ));
+
+        let time = self.displayed_time();
+        let paths = if self.show_tracers { self.tracer_paths(time) } else { Vec::new() };
+        // Drift of the orbit centres between the first and the last full wave period of each path
+        let steps = TRACER_STEPS_PER_PERIOD as usize;
+        let measured: Vec<[f64; 2]> = paths
+            .iter()
+            .filter(|path| path.len() > 2 * steps)
+            .map(|path| {
+                let centre = |orbit: &[(f64, f64, f64)]| {
+                    let n = orbit.len() as f64;
+                    (orbit.iter().map(|p| p.1).sum::<f64>() / n, orbit.iter().map(|p| p.2).sum::<f64>() / n)
+                };
+                let (first, last) = (centre(&path[..steps]), centre(&path[path.len() - steps..]));
+                let duration = path[path.len() - steps].0 - path[0].0;
+                [100.0 * (last.0 - first.0) / duration, 0.5 * (first.1 + last.1)]
+            })
+            .collect();
+
+        let profile: PlotPoints = (0..=50)
+            .map(|i| {
+                let z = -params.d * (1.0 - i as f64 / 50.0);
+                [100.0 * calculator.stokes_drift(z), z]
+            })
+            .collect();
+        let width = ((ui.available_width() - 60.0) / 2.0).max(300.0);
+        ui.horizontal(|ui| {
+            let style = self.plot_style;
+            let plot = Plot::new("stokes_drift_profile")
+                .height(240.0)
+                .width(if self.show_tracers { width } else { 2.0 * width })
+                .x_axis_label("Stokes Drift (cm/s)")
+                .y_axis_label("Elevation (m)")
+                .include_x(0)
+                .include_y(0)
+                .include_y(-params.d)
+                .legend(Legend::default());
+            style.show_plot(ui, plot, |plot_ui| {
+                plot_ui.line(Line::new(profile).color(style.series(0)).width(style.width(2.0)).name("Theory u_s(z)"));
+                plot_ui.vline(VLine::new(100.0 * calculator.mean_stokes_drift()).color(egui::Color32::GRAY).style(LineStyle::dashed_loose()).name("Depth-Averaged"));
+                if !measured.is_empty() {
+                    plot_ui.points(Points::new(PlotPoints::from(measured)).color(style.alert()).radius(4.0).name("Tracers"));
+                }
+            });
+            if !self.show_tracers {
+                return;
+            }
+
+            // Wave train and tracers wrapped into a window of two wavelengths, the surface being periodic
+            let window = 2.0 * params.wavelength;
+            let surface: PlotPoints = (0..=200)
+                .map(|i| {
+                    let x = i as f64 / 200.0 * window;
+                    [x, calculator.surface_elevation(x, time)]
+                })
+                .collect();
+            let plot = Plot::new("stokes_drift_tracers")
+                .height(240.0)
+                .width(width)
+                .x_axis_label("Distance (m)")
+                .y_axis_label("Elevation (m)")
+                .include_x(0)
+                .include_x(window)
+                .include_y(-params.d)
+                .include_y(params.amplitude());
+            style.show_plot(ui, plot, |plot_ui| {
+                plot_ui.line(Line::new(surface).color(style.series(0)).width(style.width(2.0)));
+                plot_ui.hline(HLine::new(-params.d).color(style.bed()));
+                for path in &paths {
+                    let &(_, x, z) = path.last().unwrap_or(&(0.0, 0.0, 0.0));
+                    let shift = (x / window).floor() * window;
+                    let trail: PlotPoints = path[path.len().saturating_sub(steps)..].iter().map(|&(_, x, z)| [x - shift, z]).collect();
+                    plot_ui.line(Line::new(trail).color(egui::Color32::GRAY).width(style.width(1.5)));
+                    plot_ui.points(Points::new(vec![[x - shift, z]]).color(style.alert()).radius(4.0));
+                }
+            });
+        });
+    }
+
+    /// Response of the wave energy converter to the wavemaker waves in the still water depth at its position
+    pub fn converter_response(&self) -> Result<ConverterResponse, String> {
+        let converter = self.energy_converter.as_ref().ok_or("No wave energy converter is placed")?;
+        if self.initial_condition.is_some() {
+            return Err("The converter is assessed in the wavemaker waves, not in an initial condition".to_string());
+        }
+        converter.response(self.wave_height, self.wave_period, self.still_water_depth(converter.position))
+    }
+
+    /// Energy absorbed by the wave energy converter over a year of the wave climate [J]
+    pub fn converter_annual_energy(&self) -> Result<f64, String> {
+        let converter = self.energy_converter.as_ref().ok_or("No wave energy converter is placed")?;
+        if self.wave_climate.is_empty() {
+            return Err("The wave climate table is empty".to_string());
+        }
+        converter.annual_energy(&self.wave_climate, self.still_water_depth(converter.position))
+    }
+
+    /// Wave energy converter settings, its response to the wavemaker waves and its yield over the wave climate
+    fn show_energy_converter(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut enabled = self.energy_converter.is_some();
+            if ui.checkbox(&mut enabled, "Wave Energy Converter").changed() {
+                let size = (0.2 * self.still_water_level).max(0.01);
+                self.energy_converter = if enabled {
+                    WaveEnergyConverter::new(ConverterKind::PointAbsorber, 0.5 * self.channel_length, size, size).ok()
+                } else {
+                    None
+                };
+            }
+            self.info_button(ui, "energy_converter", "Heaving wave energy converter assessed with linear theory in the frequency domain, a first estimate before a model test. A point absorber is a floating cylinder of radius a and draft D; an oscillating water column (OWC) is modelled by the water column inside its chamber, heaving as a rigid piston whose lip is at the depth D, the air turbine acting as the power take-off (PTO). The body has the mass m = ρπa²D, the added mass A ≈ ⅓πρa³ and the hydrostatic stiffness C = ρgπa²; it is excited by the Froude-Krylov force f = ρgπa² cosh(k(h − D))/cosh(kh) per metre of wave amplitude and radiates waves with the damping B = k f²/(4ρg c_g) (Haskind relation). A linear PTO of damping B_pto absorbs P = ½ B_pto ω²|X|², with the heave amplitude |X| = f a / |C − ω²(m + A) + iω(B + B_pto)|. The capture width P/J compares it with the energy flux J = ρg H² c_g/8 of the waves per metre of crest, and the capture width ratio divides it by the device width 2a. The most power is absorbed with B_pto = √(B² + (ω(m + A) − C/ω)²), and at resonance (T = T_n) the capture width reaches the limit 1/k = L/2π of an axisymmetric heaving body. Viscous losses, motion limits and interaction with the channel walls are ignored, so the results are upper bounds. The waves are those of the wavemaker, in the still water depth at the device.");
+        });
+        if self.energy_converter.is_none() {
+            return;
+        }
+
+        let response = self.converter_response();
+        let channel_length = self.channel_length;
+        let max_size = self.still_water_level.max(0.01);
+        if let Some(converter) = &mut self.energy_converter {
+            ui.horizontal(|ui| {
+                ui.label("Type:");
+                egui::ComboBox::from_id_salt("converter_kind")
+                    .selected_text(converter.kind.to_string())
+                    .show_ui(ui, |ui| {
+                        for kind in [ConverterKind::PointAbsorber, ConverterKind::OscillatingWaterColumn] {
+                            ui.selectable_value(&mut converter.kind, kind, kind.to_string());
+                        }
+                    });
+                ui.label("Position:");
+                ui.add(egui::DragValue::new(&mut converter.position).range(0.0..=channel_length).speed(0.1).suffix(" m"));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Radius (a):");
+                ui.add(egui::DragValue::new(&mut converter.radius).range(0.01..=10.0 * max_size).speed(0.01).suffix(" m"));
+                ui.label(if converter.kind == ConverterKind::PointAbsorber { "Draft (D):" } else { "Lip Submergence (D):" });
+                ui.add(egui::DragValue::new(&mut converter.draft).range(0.01..=max_size).speed(0.01).suffix(" m"));
+            });
+            ui.horizontal(|ui| {
+                ui.label("PTO Damping (B_pto):");
+                ui.add(egui::DragValue::new(&mut converter.pto_damping).range(0.0..=f64::MAX).speed(10.0).suffix(" N·s/m"));
+                if let Ok(response) = &response
+                    && ui.small_button("Optimal Damping").clicked()
+                {
+                    converter.pto_damping = response.optimal_damping;
+                }
+            });
+        }
+        let Some(converter) = self.energy_converter else {
+            return;
+        };
+
+        match self.converter_response() {
+            Ok(response) => {
+                let capture_width = response.capture_width();
+                ui.label(format!(
+                    "Absorbed power P = {:.2} W of J = {:.2} W/m: capture width {:.3} m, capture width ratio {:.2}",
+                    response.absorbed_power,
+                    response.incident_power,
+                    capture_width,
+                    capture_width / (2.0 * converter.radius)
+                ));
+                ui.label(format!(
+                    "Heave amplitude {:.3} m; natural period T_n = {:.2} s (T = {:.2} s); radiation damping B = {:.1} N·s/m, optimal PTO damping {:.1} N·s/m",
+                    response.heave_amplitude,
+                    converter.natural_period(),
+                    self.wave_period,
+                    response.radiation_damping,
+                    response.optimal_damping
+                ));
+            }
+            Err(message) => {
+                ui.colored_label(egui::Color32::YELLOW, format!("⚠ {}", message));
+            }
+        }
+
+        // Capture width ratio over periods around the natural period, at the set and at the optimal damping
+        let depth = self.still_water_depth(converter.position);
+        let natural_period = converter.natural_period();
+        let periods: Vec<f64> = (0..=200).map(|i| natural_period * (0.25 + 2.75 * i as f64 / 200.0)).collect();
+        let ratio = |converter: WaveEnergyConverter, period: f64| {
+            converter.response(self.wave_height, period, depth).map(|response| response.capture_width() / (2.0 * converter.radius))
+        };
+        let set: PlotPoints = periods.iter().filter_map(|&period| ratio(converter, period).ok().map(|ratio| [period, ratio])).collect();
+        let optimal: PlotPoints = periods
+            .iter()
+            .filter_map(|&period| {
+                let optimal = converter.response(self.wave_height, period, depth).ok()?.optimal_damping;
+                ratio(converter.with_pto_damping(optimal), period).ok().map(|ratio| [period, ratio])
+            })
+            .collect();
+        let energies: Vec<f64> = self
+            .wave_climate
+            .iter()
+            .map(|state| converter.annual_energy(std::slice::from_ref(state), depth).unwrap_or(0.0) / 3.6e6)
+            .collect();
+        let bars: Vec<Bar> = energies.iter().enumerate().map(|(i, &energy)| Bar::new((i + 1) as f64, energy).width(0.6)).collect();
+        let width = ((ui.available_width() - 60.0) / 2.0).max(300.0);
+        ui.horizontal(|ui| {
+            let style = self.plot_style;
+            let plot = Plot::new("converter_capture_width")
+                .height(240.0)
+                .width(width)
+                .x_axis_label("Wave Period (s)")
+                .y_axis_label("Capture Width Ratio")
+                .include_x(0)
+                .include_y(0)
+                .legend(Legend::default());
+            style.show_plot(ui, plot, |plot_ui| {
+                plot_ui.line(Line::new(set).color(style.series(0)).width(style.width(2.0)).name("Set PTO Damping"));
+                plot_ui.line(Line::new(optimal).color(egui::Color32::GRAY).style(LineStyle::dashed_loose()).width(style.width(1.5)).name("Optimal PTO Damping"));
+                plot_ui.vline(VLine::new(natural_period).color(style.alert()).style(LineStyle::dashed_loose()).name("T_n"));
+                if self.initial_condition.is_none() {
+                    plot_ui.vline(VLine::new(self.wave_period).color(style.series(2)).name("Wavemaker T"));
+                }
+            });
+            let plot = Plot::new("converter_annual_energy")
+                .height(240.0)
+                .width(width)
+                .x_axis_label("Sea State")
+                .y_axis_label("Energy per Year (kWh)")
+                .include_y(0);
+            style.show_plot(ui, plot, |plot_ui| {
+                plot_ui.bar_chart(BarChart::new(bars).color(style.series(0)).name("Absorbed Energy"));
+            });
+        });
+
+        self.show_wave_climate(ui, &energies);
+    }
+
+    /// Editable wave climate table with the energy absorbed in each sea state [kWh]
+    fn show_wave_climate(&mut self, ui: &mut egui::Ui, energies: &[f64]) {
+        ui.horizontal(|ui| {
+            ui.label("Wave Climate:");
+            if ui.small_button("➕ Add Wavemaker Waves").clicked() {
+                // Regular waves of height H carry the energy of a sea state of Hs = √2 H
+                if let Ok(state) = SeaState::new(std::f64::consts::SQRT_2 * self.wave_height, self.wave_period, 100.0) {
+                    self.wave_climate.push(state);
+                }
+            }
+            self.info_button(ui, "wave_climate", "Sea states at the site, from a scatter diagram or a hindcast, with the hours per year they occur. Each sea state of significant wave height Hs and energy period Te is represented by regular waves of the same energy, H = Hs/√2 and T = Te, and the annual energy sums the absorbed power times the hours over the table. Irregular waves spread their energy over a range of periods and are absorbed less well than regular waves at resonance, so the estimate is optimistic for a sharply tuned device. The hours should add up to at most a year (8766 h), the rest being calms.");
+        });
+
+        let mut removed = None;
+        egui::Grid::new("wave_climate_table").striped(true).show(ui, |ui| {
+            ui.label("#");
+            ui.label("Hs (m)");
+            ui.label("Te (s)");
+            ui.label("Hours per Year");
+            ui.label("Energy (kWh)");
+            ui.end_row();
+            for (i, state) in self.wave_climate.iter_mut().enumerate() {
+                ui.label(format!("{}", i + 1));
+                ui.add(egui::DragValue::new(&mut state.significant_height).range(0.01..=30.0).speed(0.01));
+                ui.add(egui::DragValue::new(&mut state.energy_period).range(0.1..=30.0).speed(0.1));
+                ui.add(egui::DragValue::new(&mut state.hours).range(0.0..=HOURS_PER_YEAR).speed(1.0));
+                ui.label(energies.get(i).map_or(String::new(), |energy| format!("{:.2}", energy)));
+                if ui.small_button("🗑").clicked() {
+                    removed = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(i) = removed {
+            self.wave_climate.remove(i);
+        }
+
+        let hours = climate_hours(&self.wave_climate);
+        match self.converter_annual_energy() {
+            Ok(energy) => {
+                ui.label(format!(
+                    "Annual energy: {:.1} kWh over {:.0} h, mean absorbed power {:.2} W over the year",
+                    energy / 3.6e6,
+                    hours,
+                    energy / (HOURS_PER_YEAR * 3600.0)
+                ));
+            }
+            Err(message) => {
+                ui.label(format!("⚠ {}", message));
+            }
+        }
+        if hours > HOURS_PER_YEAR {
+            ui.colored_label(egui::Color32::YELLOW, format!("⚠ The sea states add up to {:.0} h, more than a year ({:.0} h)", hours, HOURS_PER_YEAR));
+        }
+    }
+
+    /// Trajectories, speeds and Froude numbers of the detected bores and hydraulic jumps
+    fn show_bore_detection(&mut self, ui: &mut egui::Ui, tracks: &[BoreTrack]) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.detect_bores, "Detect Bores");
+            ui.label("Min Slope:");
+            ui.add(egui::DragValue::new(&mut self.bore_criteria.min_gradient).range(0.01..=2.0).speed(0.01));
+            ui.label("Min Froude:");
+            ui.add(egui::DragValue::new(&mut self.bore_criteria.min_froude).range(1.0..=5.0).speed(0.01));
+            self.info_button(ui, "bore_detection", "Bores and hydraulic jumps are found in the full-resolution snapshots of the numerical solution. A front is a run of points where the surface slope |∂η/∂x| exceeds the slope threshold. The surface rise across it gives the ratio r of the depths behind and ahead of the front, and the jump conditions its Froude number Fr = √(r(r + 1)/2) (Bélanger), the speed of the flow into the front relative to the long wave speed ahead of it. Fronts above the Froude threshold are bores; undular bores have Fr below about 1.3. Fronts running onto a dry bed are run-up tongues and are ignored. Fronts are matched from one snapshot to the next, in both directions, to log their trajectories. A front standing still (speed below a tenth of √(gh) ahead of it) is a hydraulic jump, as in the backwash of the swash zone. Detected fronts are marked on the channel plot, also during playback.");
+        });
+        if !self.detect_bores {
+            return;
+        }
+        if tracks.is_empty() {
+            ui.label("No bores detected yet.");
+            return;
+        }
+
+        egui::ScrollArea::vertical().id_salt("detected_bores").max_height(120.0).show(ui, |ui| {
+            for (i, track) in tracks.iter().enumerate() {
+                ui.label(format!(
+                    "{} {}: c = {:.2} m/s, x = {:.1} → {:.1} m from t = {:.2} to {:.2} s, height {:.3} → {:.3} m, max Fr = {:.2}",
+                    track.kind(),
+                    i + 1,
+                    track.celerity().unwrap_or(0.0),
+                    track.first().position,
+                    track.last().position,
+                    track.first().time,
+                    track.last().time,
+                    track.first().height,
+                    track.last().height,
+                    track.max_froude()
+                ));
+            }
+        });
+
+        let style = self.plot_style;
+        let plot = Plot::new("bore_trajectories")
+            .height(200.0)
+            .width((ui.available_width() - 40.0).max(400.0))
+            .x_axis_label("Distance (m)")
+            .y_axis_label("Time (s)")
+            .include_x(0)
+            .include_x(self.channel_length);
+        style.show_plot(ui, plot, |plot_ui| {
+            for track in tracks {
+                let points: Vec<[f64; 2]> = track.observations().iter().map(|o| [o.position, o.time]).collect();
+                let color = match track.kind() {
+                    FrontKind::Bore => style.alert(),
+                    FrontKind::HydraulicJump => style.reference(),
+                };
+                plot_ui.line(Line::new(PlotPoints::from(points)).color(color).width(style.width(1.5)));
+            }
+        });
+    }
+
+    /// Per-wave celerity, height evolution and breaking location of the tracked crests
+    fn show_wave_tracking(&mut self, ui: &mut egui::Ui, tracks: &[CrestTrack]) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.track_crests, "Track Wave Crests");
+            self.info_button(ui, "wave_tracking", "Crests (local maxima above a tenth of the wave height) are matched from one full-resolution snapshot to the next, so individual waves can be followed down the channel. The celerity of each wave is the slope of a least-squares fit of crest position against time. The wave height is measured from the crest down to the trough just upstream of it. A wave is marked as breaking where H ≥ 0.78 d (McCowan). Tracked crests are shown as markers on the channel plot, also during playback.");
+        });
+        if !self.track_crests {
+            return;
+        }
+        if tracks.is_empty() {
+            ui.label("No waves tracked yet.");
+            return;
+        }
+
+        let depth = self.still_water_level;
+        let celerities: Vec<f64> = tracks.iter().filter_map(CrestTrack::celerity).collect();
+        let breaking = tracks.iter().filter(|track| track.breaking_point(depth, BREAKER_INDEX).is_some()).count();
+        if !celerities.is_empty() {
+            ui.label(format!(
+                "Tracked Waves: {}, mean celerity {:.2} m/s (linear theory {:.2} m/s), {} breaking",
+                tracks.len(),
+                celerities.iter().sum::<f64>() / celerities.len() as f64,
+                Self::linear_celerity(self.wave_period, depth),
+                breaking
+            ));
+        }
+        egui::ScrollArea::vertical().id_salt("tracked_waves").max_height(120.0).show(ui, |ui| {
+            for (i, track) in tracks.iter().enumerate() {
+                let celerity = track.celerity().map_or("-".to_string(), |celerity| format!("{:.2} m/s", celerity));
+                let breaking = match track.breaking_point(depth, BREAKER_INDEX) {
+                    Some(point) => format!("breaks at x = {:.1} m", point.position),
+                    None => "no breaking".to_string(),
+                };
+                ui.label(format!(
+                    "Wave {}: c = {}, H = {:.3} → {:.3} m from x = {:.1} to {:.1} m, {}",
+                    i + 1,
+                    celerity,
+                    track.first().height,
+                    track.last().height,
+                    track.first().position,
+                    track.last().position,
+                    breaking
+                ));
+            }
+        });
+
+        let style = self.plot_style;
+        let plot = Plot::new("wave_height_evolution")
+            .height(200.0)
+            .width((ui.available_width() - 40.0).max(400.0))
+            .x_axis_label("Distance (m)")
+            .y_axis_label("Wave Height (m)")
+            .include_x(0)
+            .include_x(self.channel_length)
+            .include_y(0);
+        style.show_plot(ui, plot, |plot_ui| {
+            for track in tracks {
+                let points: Vec<[f64; 2]> = track.observations().iter().map(|o| [o.position, o.height]).collect();
+                plot_ui.line(Line::new(PlotPoints::from(points)).color(style.water()).width(style.width(1.0)));
+            }
+            plot_ui.hline(
+                HLine::new(BREAKER_INDEX * depth)
+                    .color(style.alert())
+                    .style(LineStyle::dashed_loose())
+                    .name("Breaking Limit (0.78 d)"),
+            );
+        });
+    }
+
+    /// Gauge placement controls
+    fn show_gauges(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Gauges:");
+            self.info_button(ui, "wave_gauges", "Virtual wave gauges record the surface elevation η(t) at fixed positions while the simulation runs, like resistive gauges in a laboratory flume. Records are cleared when the simulation is reset or a gauge is moved. A gauge array spreads gauges evenly along the channel for cross-shore profiles.");
+            if ui.small_button("➕ Add Gauge").clicked() {
+                self.add_gauge();
+            }
+            if ui.small_button("➕ Gauge Array").clicked() {
+                self.add_gauge_array();
+            }
+        });
+
+        let channel_length = self.channel_length;
+        let mut removed_gauge = None;
+        for (i, gauge) in self.gauges.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("Gauge {}:", i + 1));
+                let mut position = gauge.position().min(channel_length);
+                ui.add(
+                    egui::Slider::new(&mut position, 0.0..=channel_length)
+                        .prefix("x = ")
+                        .suffix(" m")
+                        .step_by(0.1),
+                );
+                gauge.set_position(position);
+                ui.label(format!("{} samples", gauge.len()));
+                if ui.small_button("✖").clicked() {
+                    removed_gauge = Some(i);
+                }
+            });
+        }
+        if let Some(i) = removed_gauge {
+            self.gauges.remove(i);
+        }
+        let last_gauge = self.gauges.len().saturating_sub(1);
+        self.analysis_gauge = self.analysis_gauge.min(last_gauge);
+        for gauge in &mut self.cross_spectrum_gauges {
+            *gauge = (*gauge).min(last_gauge);
+        }
+    }
+
+    /// Pressure sensor placement on the bed, the structure face or the downstream wall
+    fn show_pressure_sensors(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Pressure Sensors:");
+            self.info_button(ui, "pressure_sensors", "Virtual pressure sensors record the pressure p(t) at points of the structure faces, like the transducers of a laboratory model, at the gauge sampling interval. The pressure is hydrostatic below the surface plus the non-hydrostatic pressure of the solver layers, so short waves are felt less at depth (p ∝ cosh k(d+z) / cosh kd); sensors above the water read zero. The peak wave pressure above the hydrostatic pressure at rest is compared with the Goda (1974) design pressures on a vertical wall: p₁ = (α₁ + α₂) ρgH at the still water level, decreasing to zero at η* = 1.5H and to α₃p₁ at the bed. Depth-integrated layers smooth the short, high impact peaks of breaking waves, so measured maxima are quasi-static loads. Records need the numerical solver.");
+            if ui.small_button("➕ Add Sensor").clicked() {
+                self.add_pressure_sensor();
+            }
+            if ui.small_button("➕ Slope Array").clicked() {
+                self.sensor_status = self.add_slope_sensor_array().err();
+            }
+            if ui.small_button("➕ Wall Array").clicked() {
+                self.sensor_status = self.add_wall_sensor_array().err();
+            }
+        });
+        if let Some(message) = &self.sensor_status {
+            ui.label(format!("⚠ {}", message));
+        }
+
+        let channel_length = self.channel_length;
+        let top = self.still_water_level + 1.0;
+        let bed_levels: Vec<f64> = self.pressure_sensors.iter().map(|sensor| self.bed_elevation(sensor.position().min(channel_length))).collect();
+        let mut removed_sensor = None;
+        for (i, sensor) in self.pressure_sensors.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("Sensor {}:", i + 1));
+                let mut position = sensor.position().min(channel_length);
+                let mut elevation = sensor.elevation();
+                ui.add(egui::DragValue::new(&mut position).range(0.0..=channel_length).speed(0.05).prefix("x = ").suffix(" m"));
+                ui.add(egui::DragValue::new(&mut elevation).range(0.0..=top).speed(0.01).prefix("z = ").suffix(" m"));
+                if ui.small_button("On Bed").on_hover_text("Move the sensor down onto the bed").clicked() {
+                    elevation = bed_levels[i];
+                }
+                sensor.set_location(position, elevation);
+                ui.label(format!("{} samples", sensor.len()));
+                if ui.small_button("✖").clicked() {
+                    removed_sensor = Some(i);
+                }
+            });
+        }
+        if let Some(i) = removed_sensor {
+            self.pressure_sensors.remove(i);
+        }
+        if !self.pressure_sensors.is_empty() && !self.is_numerical() {
+            ui.label("⚠ Pressure sensors record with the numerical solver only.");
+        }
+    }
+
+    /// Output rates of the gauges and snapshots and the statistics window, with aliasing warnings
+    fn show_output_sampling(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Gauge Sampling:");
+            ui.add(egui::Slider::new(&mut self.gauge_sampling_interval, 0.05..=2.0).prefix("every ").suffix(" s").step_by(0.05));
+            ui.label("Snapshots:");
+            ui.add(egui::Slider::new(&mut self.snapshot_interval, 0.05..=2.0).prefix("every ").suffix(" s").step_by(0.05));
+            if ui.checkbox(&mut self.stream_snapshots, "Stream Snapshots to Disk").changed() && !self.stream_snapshots {
+                self.snapshot_file = None;
+            }
+            self.info_button(ui, "output_sampling", "Outputs are sampled independently of the 0.05 s simulation step: gauges record one sample per sampling interval and the surface is stored for playback once per snapshot interval, both multiples of the step. Coarser rates save memory for long runs. Streaming writes every snapshot to a memory-mapped file in the temporary directory, removed on reset, so gigabyte-scale runs can be scrubbed at full resolution while memory only holds the decimated playback history. An interval of T/2 or more aliases waves of period T (Nyquist); below ten samples per wave, crests, wave heights and harmonics are poorly resolved. The statistics window restricts the gauge analyses to the end of the records, for example to exclude the spin-up of the wave train.");
+        });
+        if let Some(file) = &self.snapshot_file {
+            ui.label(format!("Streamed: {} frames, {:.1} MB on disk", file.len(), file.size_bytes() as f64 / 1e6));
+        }
+        ui.horizontal(|ui| {
+            ui.label("Statistics Window:");
+            let mut whole_record = self.statistics_window <= 0.0;
+            if ui.checkbox(&mut whole_record, "Whole Record").changed() {
+                self.statistics_window = if whole_record { 0.0 } else { 20.0 * self.wave_period };
+            }
+            if !whole_record {
+                ui.add(egui::Slider::new(&mut self.statistics_window, 10.0..=1000.0).prefix("last ").suffix(" s").logarithmic(true));
+            }
+        });
+        for warning in self.sampling_warnings() {
+            ui.label(format!("⚠ {}", warning));
+        }
+    }
+
+    /// Time steps, output memory and projected runtime of the run, before it starts
+    fn show_run_preview(&mut self, ui: &mut egui::Ui) {
+        let key = self.configuration_key();
+        if self.step_cost.as_ref().is_none_or(|(measured, _)| *measured != key) {
+            match self.benchmark_step() {
+                Ok(cost) => self.step_cost = Some((key, cost)),
+                Err(_) => {
+                    self.step_cost = None;
+                    return;
+                }
+            }
+        }
+        let Some((_, cost)) = self.step_cost else {
+            return;
+        };
+        let preview = self.run_preview(cost);
+        ui.horizontal(|ui| {
+            let steps = match preview.solver_steps {
+                Some(steps) => format!("{} solver steps", steps),
+                None => "analytical".to_string(),
+            };
+            ui.label(format!(
+                "Run Preview: {:.1} s simulated in {} frames ({}) on {} points",
+                preview.duration, preview.frames, steps, preview.cells
+            ));
+            self.info_button(ui, "run_preview", "Estimated before the run starts. Solver steps follow the CFL condition in the smallest cell for long waves over the deepest water. Memory counts the playback snapshots, decimated as the history fills up, and the gauge records; streamed snapshots take disk space instead. The time per step is measured by stepping the solver on this machine for a few milliseconds, so the compute time is a projection that grows as the waves fill the channel. The animation advances one 0.05 s step per displayed frame, so the wall-clock time is at least the frame count at 60 frames per second.");
+        });
+        let mut memory = format!(
+            "Outputs: {} in memory ({} snapshots, {} gauges)",
+            format_bytes(preview.memory_bytes()),
+            format_bytes(preview.snapshot_bytes),
+            format_bytes(preview.gauge_bytes)
+        );
+        if let Some(stream_bytes) = preview.stream_bytes {
+            memory.push_str(&format!(", {} streamed to disk", format_bytes(stream_bytes)));
+        }
+        ui.label(memory);
+        ui.label(format!(
+            "Projected Time: {} of computation, {} wall clock ({:.0} µs per step)",
+            format_duration(preview.compute_time()),
+            format_duration(preview.wall_clock_time()),
+            preview.seconds_per_step * 1e6
+        ));
+    }
+
+    /// Ramp of the wave height changes and the parameters changed during the run
+    fn show_live_edits(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Live Edits:");
+            ui.label("Wave Height Ramp");
+            ui.add(egui::Slider::new(&mut self.height_ramp, 0.0..=60.0).suffix(" s"));
+            self.info_button(ui, "live_edits", "While the simulation runs or is paused, the wave height, the sponge strength and the vegetation drag coefficient can be changed without restarting it, for example to show what happens when a storm intensifies. The wavemaker reaches a new wave height linearly over the ramp time, so the paddle does not jump; the sponge and vegetation take their new values at the next step. The vegetation drag is the only friction of the solver, which has no bed friction or wind forcing, so there is no friction coefficient or wind to change. Every change is logged with its simulation time and marked on the playback timeline. Changing any other setting restarts the run, and a reset clears the log.");
+        });
+        for change in self.parameter_log.changes() {
+            ui.label(format!("  {}", change));
+        }
+        if self.parameter_log.is_empty() {
+            ui.label("  No parameter changed during this run");
+        }
+    }
+
+    /// Alert rules on the quantities of the channel, and the alerts triggered during the run
+    fn show_alerts(&mut self, ui: &mut egui::Ui) {
+        let mut removed = None;
+        for (i, rule) in self.alerts.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(rule.describe(self));
+                if rule.pause {
+                    ui.label("⏸");
+                }
+                if ui.small_button("✖").on_hover_text("Remove").clicked() {
+                    removed = Some(i);
+                }
+            });
+        }
+        if let Some(i) = removed {
+            self.alerts.remove(i);
+        }
+
+        let available = DashboardQuantity::available(self);
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("alert_quantity")
+                .selected_text(self.alert_draft.quantity.label())
+                .show_ui(ui, |ui| {
+                    for quantity in available {
+                        let label = quantity.label();
+                        ui.selectable_value(&mut self.alert_draft.quantity, quantity, label);
+                    }
+                });
+            egui::ComboBox::from_id_salt("alert_condition")
+                .width(40.0)
+                .selected_text(self.alert_draft.condition.to_string())
+                .show_ui(ui, |ui| {
+                    for condition in AlertCondition::ALL {
+                        ui.selectable_value(&mut self.alert_draft.condition, condition, condition.to_string());
+                    }
+                });
+            ui.add(egui::DragValue::new(&mut self.alert_draft.threshold).speed(0.01));
+            ui.label(self.alert_draft.quantity.unit(self));
+            ui.checkbox(&mut self.alert_draft.pause, "Pause");
+            if ui.button("➕ Add Alert").clicked() {
+                self.alerts.push(self.alert_draft.clone());
+            }
+            self.info_button(ui, "alerts", "Watch a quantity during the run, e.g. warn when the overtopping discharge q exceeds 10 l/s/m or the Courant number exceeds 0.9. Every rule is checked after each step: when the quantity crosses its threshold, a notification appears in the corner of the window and the alert is logged with its simulation time; with Pause, the simulation also pauses so the situation can be inspected before resuming. A rule triggers again only after the quantity has come back across the threshold. Quantities not available yet, such as gauge statistics before any record, are skipped.");
+        });
+
+        for event in &self.alert_log {
+            ui.label(format!("  {}", event));
+        }
+        if !self.alerts.is_empty() && self.alert_log.is_empty() {
+            ui.label("  No alert during this run");
+        }
+    }
+
+    /// Seed of the run, editable so a realization can be reproduced or redrawn, and the provenance of the current run
+    fn show_run_metadata(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Random Seed:");
+            ui.add(egui::DragValue::new(&mut self.random_seed).speed(1.0));
+            if ui.small_button("🎲 New Seed").clicked() {
+                self.random_seed = seed_from_clock();
+            }
+            self.info_button(ui, "random_seed", "Every stochastic feature (random phases of irregular waves, Monte Carlo samples) draws from a SplitMix64 generator started from this seed. The sequence only depends on the seed, so the same seed reproduces a run exactly across sessions and machines; note it with the results. Draw a new seed for another realization of the same sea state.");
+        });
+
+        let Some(metadata) = &self.run_metadata else {
+            ui.label("Metadata is recorded when the simulation starts.");
+            return;
+        };
+        for line in metadata.to_text().lines() {
+            ui.label(line);
+        }
+        let report = self.run_report().unwrap_or_default();
+        ui.horizontal(|ui| {
+            if ui.small_button("📋 Copy Metadata").clicked() {
+                ui.ctx().copy_text(report);
+            }
+            self.info_button(ui, "run_metadata", "Provenance recorded with every run and stored with its cached results: platform version, a hash of the full configuration (channel, waves, sources, scenario, gauges, sampling and solver settings), solver options, random seed, start and end times (UTC) and the host. Quote it with results, e.g. in a thesis appendix, so they can be traced back to the exact configuration and reproduced.");
+        });
+    }
+
+    /// Run metadata followed by the annotations of the plots, for reports and the clipboard
+    pub fn run_report(&self) -> Option<String> {
+        let mut report = self.run_metadata.as_ref()?.to_text();
+        if !self.annotations.is_empty() {
+            report.push_str("\n\nAnnotations:");
+            for annotation in &self.annotations {
+                report.push_str(&format!("\n- {}", annotation.describe()));
+            }
+        }
+        Some(report)
+    }
+
+    /// Export of the channel as an olaFlow case, to continue the study in CFD
+    fn show_openfoam_export(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Case Directory:");
+            ui.add(egui::TextEdit::singleline(&mut self.openfoam_path).desired_width(200.0));
+            if ui.button("Export OpenFOAM Case").clicked() {
+                let case = OpenFoamCase::new(self);
+                self.openfoam_status = Some(match case.and_then(|case| case.write(std::path::Path::new(&self.openfoam_path)).map(|()| case)) {
+                    Ok(case) => format!("Exported {} files, {} cells, {} settings not exported", case.files().len(), case.cells(), case.notes().len()),
+                    Err(message) => format!("⚠ {}", message),
+                });
+            }
+            self.info_button(ui, "openfoam_export", "Skeleton of an olaFlow wave flume case (OpenFOAM) reproducing the channel, for studies needing the full Navier-Stokes equations, e.g. breaking waves on structures. The 2D mesh (system/blockMeshDict) follows the cross-section with one block per segment, 100 cells per wave length and 10 cells per wave height, up to three wave heights above the still water level or the crest. The inlet generates the wavemaker waves (constant/waveDict), regular in the selected wave theory or as first-order Stokes components of the boundary spectrum with the same phases, with active absorption as configured; an absorbing end becomes an absorbing outlet. The run lasts as long as the simulation, writes fields at the snapshot interval and probes the surface at the wave gauges. Settings without a counterpart (schedule, storm, internal sources, vegetation, tank) are listed in the README of the case. Run blockMesh, setFields and olaFoam in the case directory, after checking the resolution.");
+        });
+        if let Some(status) = &self.openfoam_status {
+            ui.label(status);
+        }
+    }
+
+    /// Values of the custom formulas and the editor adding new ones
+    fn show_custom_formulas(&mut self, ui: &mut egui::Ui, values: &mut CopyableValues) {
+        let variables = if self.custom_formulas.is_empty() { Vec::new() } else { self.formula_variables() };
+        let mut removed = None;
+        for (i, formula) in self.custom_formulas.iter().enumerate() {
+            ui.horizontal(|ui| {
+                match formula.evaluate(&variables) {
+                    Ok(value) => values.label(ui, format!("{}: {:.4} {}", formula.name, value, formula.unit)),
+                    Err(message) => ui.colored_label(egui::Color32::YELLOW, format!("{}: ⚠ {}", formula.name, message)),
+                }
+                .on_hover_text(format!("{} = {}", formula.name, formula.expression.source()));
+                if ui.small_button("✖").on_hover_text("Remove formula").clicked() {
+                    removed = Some(i);
+                }
+            });
+        }
+        if let Some(i) = removed {
+            self.custom_formulas.remove(i);
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Formula:");
+            ui.add(egui::TextEdit::singleline(&mut self.formula_name).hint_text("Name").desired_width(80.0));
+            ui.label("=");
+            ui.add(egui::TextEdit::singleline(&mut self.formula_source).hint_text("slope / sqrt(H / L0)").desired_width(180.0));
+            ui.add(egui::TextEdit::singleline(&mut self.formula_unit).hint_text("Unit").desired_width(40.0));
+            if ui.button("➕ Add").clicked() {
+                let (name, source, unit) = (self.formula_name.clone(), self.formula_source.clone(), self.formula_unit.clone());
+                self.formula_status = self.add_custom_formula(&name, &source, &unit).err();
+                if self.formula_status.is_none() {
+                    self.formula_name.clear();
+                    self.formula_source.clear();
+                    self.formula_unit.clear();
+                }
+            }
+            let text = format!(
+                "Empirical relationships of your own, e.g. the Iribarren number slope / sqrt(H / L0), evaluated with the quantities of the case: g, the wave height H and period T, the still water depth h at the wavemaker, f, omega, the wave length L, wave number k and celerity c of linear theory, the deep water wave length L0, the gradient of the steepest rising segment of the cross-section as slope, and Hs and Tp measured at the analysis gauge over the statistics window once it has recorded. Expressions use + - * / ^, parentheses, the constants pi and e and the functions {}. Formulas are saved with the project.",
+                function_names().join(", ")
+            );
+            self.info_button(ui, "custom_formulas", &text);
+        });
+        if let Some(message) = &self.formula_status {
+            ui.colored_label(egui::Color32::YELLOW, format!("⚠ {}", message));
+        }
+    }
+
+    /// Sea-swell/infragravity split of the selected gauge and the cross-shore infragravity profile
+    fn show_infragravity_analysis(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Analysis Gauge:");
+            self.info_button(ui, "infragravity_analysis", "The gauge record is band-pass filtered (FFT) into sea-swell (f > f_split) and infragravity (f ≤ f_split) components, with f_split = f_p/2 half the wavemaker frequency. Hm0 = 4√m0 of each band. The short-wave envelope is the modulus of the analytic signal (Hilbert transform) of the sea-swell component, low-passed to the infragravity band; a correlation close to −1 indicates long waves bound under the wave groups, positive correlations at a lag indicate free or reflected long waves.");
+            let positions: Vec<f64> = self.gauges.iter().map(WaveGauge::position).collect();
+            egui::ComboBox::from_id_salt("analysis_gauge")
+                .selected_text(format!("Gauge {} (x = {:.1} m)", self.analysis_gauge + 1, positions[self.analysis_gauge]))
+                .show_ui(ui, |ui| {
+                    for (i, position) in positions.iter().enumerate() {
+                        ui.selectable_value(&mut self.analysis_gauge, i, format!("Gauge {} (x = {:.1} m)", i + 1, position));
+                    }
+                });
+        });
+
+        // Filtering long records every frame is costly, so results are shown while paused
+        if self.simulation_running {
+            ui.label("Pause the simulation to analyse the gauge records.");
+            return;
+        }
+
+        let split_frequency = self.infragravity_split_frequency();
+        match self.infragravity_analysis() {
+            Ok(analysis) => {
+                let (lag, lagged) = analysis.lagged_envelope_correlation(1.0 / split_frequency);
+                ui.label(format!("Split Frequency (f_split): {:.3} Hz", split_frequency));
+                ui.label(format!("Sea-Swell Hm0: {:.3} m", analysis.hm0_sea_swell()));
+                ui.label(format!("Infragravity Hm0: {:.3} m", analysis.hm0_infragravity()));
+                ui.label(format!("Envelope Correlation: {:+.2}", analysis.envelope_correlation()));
+                ui.label(format!("Strongest Envelope Correlation: {:+.2} at {:+.1} s", lagged, lag));
+            }
+            Err(message) => {
+                ui.label(format!("⚠ {}", message));
+            }
+        }
+
+        let records: Vec<WaveGauge> = self.gauges.iter().map(|gauge| gauge.window(self.statistics_window)).collect();
+        let profile = infragravity_profile(&records, split_frequency);
+        if profile.len() >= 2 {
+            let points: Vec<[f64; 2]> = profile.iter().map(|&(x, height)| [x, height]).collect();
+            let style = self.plot_style;
+            let plot = Plot::new("infragravity_profile")
+                .height(200.0)
+                .width((ui.available_width() - 40.0).max(400.0))
+                .x_axis_label("Distance (m)")
+                .y_axis_label("Infragravity Hm0 (m)")
+                .include_x(0)
+                .include_x(self.channel_length)
+                .include_y(0);
+            style.show_plot(ui, plot, |plot_ui| {
+                plot_ui.line(
+                    Line::new(PlotPoints::from(points.clone()))
+                        .color(style.long_wave())
+                        .width(style.width(2.0))
+                        .name("Infragravity Hm0"),
+                );
+                plot_ui.points(
+                    Points::new(PlotPoints::from(points))
+                        .color(style.long_wave())
+                        .radius(3.0),
+                );
+            });
+        }
+    }
+
+    /// Time-frequency map of the wavelet power of the analysis gauge, with the cone of influence
+    fn show_scalogram(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Scalogram:");
+            self.info_button(ui, "wavelet_scalogram", "Continuous wavelet transform of the analysis gauge record with the Morlet wavelet (ω₀ = 6): W(s,t) = Σ η(t') ψ*((t' − t)/s), shown as power |W|²/σ² against time and log frequency, f ≈ 1.03/s. Unlike a spectrum it localizes energy in time: wave groups appear as periodic bursts, breaking as a loss of power at the peak and a spread to harmonics, seiching as growing power at low frequencies. The dashed line is the cone of influence; below it power is affected by the record ends.");
+        });
+        if self.simulation_running {
+            ui.label("Pause the simulation to compute the scalogram.");
+            return;
+        }
+
+        // Rebuild the texture only when the analysed record or the colormap changed
+        let gauge = &self.gauges[self.analysis_gauge];
+        let up_to_date = self.scalogram_view.as_ref().is_some_and(|view| {
+            view.gauge == self.analysis_gauge
+                && view.position == gauge.position()
+                && view.samples == gauge.len()
+                && view.window == self.statistics_window
+                && view.colormap == self.plot_style.colormap
+        });
+        if !up_to_date {
+            let (position, samples) = (gauge.position(), gauge.len());
+            self.scalogram_view = match self.scalogram() {
+                Ok(scalogram) => {
+                    let texture = ui.ctx().load_texture("scalogram", scalogram_image(&scalogram, self.plot_style.colormap), egui::TextureOptions::LINEAR);
+                    Some(ScalogramView {
+                        gauge: self.analysis_gauge,
+                        position,
+                        samples,
+                        window: self.statistics_window,
+                        colormap: self.plot_style.colormap,
+                        scalogram,
+                        texture,
+                    })
+                }
+                Err(message) => {
+                    ui.label(format!("⚠ {}", message));
+                    None
+                }
+            };
+        }
+        let Some(view) = &self.scalogram_view else {
+            return;
+        };
+
+        let frequencies = view.scalogram.frequencies();
+        let top = frequencies[0].log10();
+        let bottom = frequencies[frequencies.len() - 1].log10();
+        let duration = view.scalogram.duration();
+        let cone: PlotPoints = (0..=200)
+            .map(|i| {
+                let time = i as f64 / 200.0 * duration;
+                [time, view.scalogram.cone_of_influence(time).log10().clamp(bottom, top)]
+            })
+            .collect();
+
+        let style = self.plot_style;
+        let plot = Plot::new("wavelet_scalogram")
+            .height(250.0)
+            .width((ui.available_width() - 40.0).max(400.0))
+            .x_axis_label("Time (s)")
+            .y_axis_label("log₁₀ Frequency (Hz)")
+            .include_x(0)
+            .include_x(duration)
+            .include_y(bottom)
+            .include_y(top);
+        style.show_plot(ui, plot, |plot_ui| {
+            plot_ui.image(PlotImage::new(
+                view.texture.id(),
+                PlotPoint::new(0.5 * duration, 0.5 * (top + bottom)),
+                [duration as f32, (top - bottom) as f32],
+            ));
+            plot_ui.line(
+                Line::new(cone)
+                    .color(egui::Color32::WHITE)
+                    .style(LineStyle::dashed_loose())
+                    .name("Cone of Influence"),
+            );
+        });
+    }
+
+    /// Frequency-frequency map of the bicoherence of the analysis gauge over the principal domain f₁ ≥ f₂
+    fn show_bispectrum(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Bicoherence:");
+            self.info_button(ui, "bispectrum", "Bispectrum B(f₁,f₂) = E[X(f₁) X(f₂) X*(f₁+f₂)] averaged over Hann-windowed segments of the analysis gauge record, shown as the squared bicoherence b² = |B|² / (E[|X(f₁)X(f₂)|²] E[|X(f₁+f₂)|²]) between 0 and 1 (Kim & Powers, 1979). High values mean the phases of the three waves are locked: a triad interaction transferring energy between f₁, f₂ and f₁+f₂ (sum) or f₁−f₂ (difference). Peaks on the diagonal at the peak frequency reveal harmonic generation in shallow water and over bars, peaks near f₂ ≈ 0 reveal bound infragravity waves. Values below the 95% significance level 6/ν (ν = 2 × segments) are indistinguishable from uncoupled waves.");
+        });
+        if self.simulation_running {
+            ui.label("Pause the simulation to compute the bispectrum.");
+            return;
+        }
+
+        // Rebuild the texture only when the analysed record or the colormap changed
+        let gauge = &self.gauges[self.analysis_gauge];
+        let up_to_date = self.bispectrum_view.as_ref().is_some_and(|view| {
+            view.gauge == self.analysis_gauge
+                && view.position == gauge.position()
+                && view.samples == gauge.len()
+                && view.window == self.statistics_window
+                && view.colormap == self.plot_style.colormap
+        });
+        if !up_to_date {
+            let (position, samples) = (gauge.position(), gauge.len());
+            self.bispectrum_view = match self.bispectrum() {
+                Ok(bispectrum) => {
+                    let texture = ui.ctx().load_texture("bispectrum", bicoherence_image(&bispectrum, self.plot_style.colormap), egui::TextureOptions::NEAREST);
+                    Some(BispectrumView {
+                        gauge: self.analysis_gauge,
+                        position,
+                        samples,
+                        window: self.statistics_window,
+                        colormap: self.plot_style.colormap,
+                        bispectrum,
+                        texture,
+                    })
+                }
+                Err(message) => {
+                    ui.label(format!("⚠ {}", message));
+                    None
+                }
+            };
+        }
+        let Some(view) = &self.bispectrum_view else {
+            return;
+        };
+
+        let bispectrum = &view.bispectrum;
+        let peak_frequency = 1.0 / self.wave_period;
+        ui.label(format!("Segments: {} (Δf = {:.4} Hz)", bispectrum.segments(), bispectrum.resolution()));
+        ui.label(format!("95% Significance Level: b² = {:.3}", bispectrum.significance_level()));
+        ui.label(format!("Self-Interaction at Peak: b² = {:.3}", bispectrum.bicoherence_at(peak_frequency, peak_frequency)));
+        if let Some((f1, f2, value)) = bispectrum.strongest_interaction() {
+            ui.label(format!("Strongest Interaction: b² = {:.3} at ({:.3}, {:.3}) Hz", value, f1, f2));
+        }
+
+        // Pixels are centered on the frequency bins
+        let frequencies = bispectrum.frequencies();
+        let resolution = bispectrum.resolution();
+        let extent = frequencies.len() as f64 * resolution;
+        let center = 0.5 * extent - 0.5 * resolution;
+        let style = self.plot_style;
+        let plot = Plot::new("bispectrum_plot")
+            .height(300.0)
+            .width(320.0)
+            .data_aspect(1.0)
+            .x_axis_label("f₁ (Hz)")
+            .y_axis_label("f₂ (Hz)")
+            .include_x(0)
+            .include_x(extent)
+            .include_y(0)
+            .include_y(extent);
+        style.show_plot(ui, plot, |plot_ui| {
+            plot_ui.image(PlotImage::new(view.texture.id(), PlotPoint::new(center, center), [extent as f32, extent as f32]));
+        });
+    }
+
+    /// Coherence and celerity per frequency between two gauges, against the dispersion relation
+    fn show_cross_spectrum(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Gauge Pair:");
+            self.info_button(ui, "cross_spectrum", "Cross-spectrum S_ab(f) = E[A*(f) B(f)] of two gauge records, averaged over Hann-windowed segments. The squared coherence γ² = |S_ab|² / (S_aa S_bb) measures how linearly related the records are at each frequency; only frequencies above the 95% significance level carry meaningful phase. The phase lag φ = kΔx of the downstream gauge gives the wave number, hence the celerity c = 2πf Δx/φ, an empirical dispersion relation to compare with linear theory (c = ω/k, ω² = gk tanh kd) and with the dispersion relation of the numerical model. Keep the gauges less than one wavelength apart, as phases are only known modulo 2π; reflections lower the coherence and bias the phase.");
+            let positions: Vec<f64> = self.gauges.iter().map(WaveGauge::position).collect();
+            for (side, gauge) in self.cross_spectrum_gauges.iter_mut().enumerate() {
+                egui::ComboBox::from_id_salt(("cross_spectrum_gauge", side))
+                    .selected_text(format!("Gauge {} (x = {:.1} m)", *gauge + 1, positions[*gauge]))
+                    .show_ui(ui, |ui| {
+                        for (i, position) in positions.iter().enumerate() {
+                            ui.selectable_value(gauge, i, format!("Gauge {} (x = {:.1} m)", i + 1, position));
+                        }
+                    });
+            }
+        });
+        if self.simulation_running {
+            ui.label("Pause the simulation to compute the cross-spectrum.");
+            return;
+        }
+
+        let spectrum = match self.cross_spectrum() {
+            Ok(spectrum) => spectrum,
+            Err(message) => {
+                ui.label(format!("⚠ {}", message));
+                return;
+            }
+        };
+        let threshold = spectrum.significance_level();
+        let max_frequency = 3.0 / self.wave_period;
+        ui.label(format!("Separation: {:.2} m, {} segments (Δf = {:.4} Hz)", spectrum.separation(), spectrum.segments(), spectrum.frequencies()[0]));
+        ui.label(format!("95% Significance Level: γ² = {:.3}", threshold));
+
+        // Readout at the bin nearest to the wavemaker frequency
+        let peak_frequency = 1.0 / self.wave_period;
+        let peak = spectrum
+            .frequencies()
+            .iter()
+            .enumerate()
+            .min_by(|a, b| (a.1 - peak_frequency).abs().total_cmp(&(b.1 - peak_frequency).abs()))
+            .map_or(0, |(bin, _)| bin);
+        let theory = Self::linear_celerity(1.0 / spectrum.frequencies()[peak], self.still_water_level);
+        ui.label(format!(
+            "Celerity at {:.3} Hz: {:.2} m/s measured, {:.2} m/s linear theory (γ² = {:.2})",
+            spectrum.frequencies()[peak],
+            spectrum.celerity()[peak],
+            theory,
+            spectrum.coherence()[peak]
+        ));
+
+        let in_range = |frequency: &f64| *frequency <= max_frequency;
+        let coherence: PlotPoints = spectrum
+            .frequencies()
+            .iter()
+            .zip(spectrum.coherence())
+            .filter(|(frequency, _)| in_range(frequency))
+            .map(|(&frequency, &coherence)| [frequency, coherence])
+            .collect();
+        let measured: Vec<[f64; 2]> = spectrum
+            .coherent_celerity(threshold)
+            .into_iter()
+            .filter(|(frequency, _)| in_range(frequency))
+            .map(|(frequency, celerity)| [frequency, celerity])
+            .collect();
+        let curve_frequencies: Vec<f64> = (1..=200).map(|i| i as f64 / 200.0 * max_frequency).collect();
+        let linear_theory: PlotPoints = curve_frequencies
+            .iter()
+            .map(|&frequency| [frequency, Self::linear_celerity(1.0 / frequency, self.still_water_level)])
+            .collect();
+        let model: Option<PlotPoints> = self.is_numerical().then(|| {
+            curve_frequencies
+                .iter()
+                .filter_map(|&frequency| self.model_celerity(frequency).map(|celerity| [frequency, celerity]))
+                .collect()
+        });
+        let long_wave_speed = (9.81 * self.still_water_level).sqrt();
+
+        let width = ((ui.available_width() - 60.0) / 2.0).max(300.0);
+        ui.horizontal(|ui| {
+            let style = self.plot_style;
+            let plot = Plot::new("cross_spectrum_coherence")
+                .height(220.0)
+                .width(width)
+                .x_axis_label("Frequency (Hz)")
+                .y_axis_label("Coherence γ²")
+                .include_x(0)
+                .include_y(0)
+                .include_y(1);
+            style.show_plot(ui, plot, |plot_ui| {
+                plot_ui.line(Line::new(coherence).color(style.series(0)).width(style.width(1.5)).name("Coherence"));
+                plot_ui.hline(
+                    HLine::new(threshold)
+                        .color(egui::Color32::GRAY)
+                        .style(LineStyle::dashed_loose())
+                        .name("95% Significance"),
+                );
+            });
+            let plot = Plot::new("cross_spectrum_celerity")
+                .height(220.0)
+                .width(width)
+                .x_axis_label("Frequency (Hz)")
+                .y_axis_label("Celerity (m/s)")
+                .include_x(0)
+                .include_y(0)
+                .include_y(1.2 * long_wave_speed)
+                .legend(Legend::default());
+            style.show_plot(ui, plot, |plot_ui| {
+                plot_ui.line(Line::new(linear_theory).color(style.alert()).width(style.width(1.5)).name("Linear Theory"));
+                if let Some(model) = model {
+                    plot_ui.line(
+                        Line::new(model)
+                            .color(style.reference())
+                            .width(style.width(1.5))
+                            .style(LineStyle::dashed_loose())
+                            .name("Model Dispersion"),
+                    );
+                }
+                plot_ui.points(Points::new(measured).radius(3.0).color(style.series(0)).name("Measured"));
+            });
+        });
+    }
+
+    /// Structure extent, gauge arrays and the energy budget bar with its uncertainty notes
+    fn show_energy_budget(&mut self, ui: &mut egui::Ui) {
+        let Some(((start, end), (seaward, landward))) = self.structure_extent().zip(self.budget_gauges()) else {
+            return;
+        };
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "Structure: x = {:.1}-{:.1} m, {} seaward and {} landward gauges",
+                start,
+                end,
+                seaward.len(),
+                landward.len()
+            ));
+            self.info_button(ui, "energy_budget", "Energy budget of the structure (vegetation patch or sloping bed). The incident and reflected waves in front of it are separated from three or more gauges by least squares (Mansard & Funke, 1980; Goda & Suzuki, 1976 for two gauges), assuming linear waves at each frequency: B_p = Z_I e^(−ikx_p) + Z_R e^(ikx_p). The energy fluxes E c_g = ρg Σ S(f) c_g(f) Δf give the reflected share Kr², the incident waves of the array behind the structure the transmitted share Kt², and the rest 1 − Kr² − Kt² is counted as dissipated by breaking, friction or drag. Gauges should be a quarter wavelength from the structure, spaced L/10 and L/4 apart; frequencies where the spacing nears a multiple of L/2 are left out. The dissipated share also collects the errors of the separation.");
+        });
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!self.simulation_running, egui::Button::new("Place Gauge Arrays")).clicked() {
+                self.budget_status = match self.add_budget_gauge_arrays() {
+                    Ok(()) => Some(format!("{} gauges in the channel; run the simulation to fill their records", self.gauges.len())),
+                    Err(message) => Some(format!("⚠ {}", message)),
+                };
+            }
+            if let Some(status) = &self.budget_status {
+                ui.label(status);
+            }
+        });
+        if self.simulation_running {
+            ui.label("Pause the simulation to compute the energy budget.");
+            return;
+        }
+
+        let budget = match self.energy_budget() {
+            Ok(budget) => budget,
+            Err(message) => {
+                ui.label(format!("⚠ {}", message));
+                return;
+            }
+        };
+        ui.label(format!(
+            "Incident flux: {:.1} W/m, Kr = {:.2}, Kt = {:.2}",
+            budget.incident,
+            budget.reflection().max(0.0).sqrt(),
+            budget.transmission().max(0.0).sqrt()
+        ));
+        draw_energy_budget(ui, &budget);
+        for note in &budget.notes {
+            ui.label(format!("⚠ {}", note));
+        }
+    }
+
+    /// Pressure records of the sensors and their peaks against the Goda design pressures
+    fn show_pressure_records(&mut self, ui: &mut egui::Ui) {
+        let goda = self.goda_pressure();
+        for i in 0..self.pressure_sensors.len() {
+            let sensor = &self.pressure_sensors[i];
+            let Some((time, peak)) = self.sensor_peak(i) else {
+                continue;
+            };
+            let z = sensor.elevation() - self.still_water_level;
+            let design = match (&goda, sensor.position() >= self.channel_length - 1e-9) {
+                (Ok(goda), true) => format!(", Goda {:.2} kPa", goda.pressure_at(z) / 1000.0),
+                _ => String::new(),
+            };
+            ui.label(format!(
+                "Sensor {} (x = {:.2} m, z = {:+.2} m): peak {:.2} kPa at t = {:.1} s, p1/250 {:.2} kPa{}",
+                i + 1,
+                sensor.position(),
+                z,
+                peak / 1000.0,
+                time,
+                sensor.exceeded_pressure(1.0 / 250.0).unwrap_or(0.0) / 1000.0,
+                design
+            ));
+        }
+
+        let peaks: Vec<[f64; 2]> = (0..self.pressure_sensors.len())
+            .filter_map(|i| self.sensor_peak(i).map(|(_, peak)| [peak / 1000.0, self.pressure_sensors[i].elevation() - self.still_water_level]))
+            .collect();
+        let width = ((ui.available_width() - 60.0) / 2.0).max(300.0);
+        ui.horizontal(|ui| {
+            let style = self.plot_style;
+            let plot = Plot::new("pressure_records")
+                .height(240.0)
+                .width(width)
+                .x_axis_label("Time (s)")
+                .y_axis_label("Pressure (kPa)")
+                .legend(Legend::default());
+            style.show_plot(ui, plot, |plot_ui| {
+                for (i, sensor) in self.pressure_sensors.iter().enumerate() {
+                    let points: PlotPoints = sensor.times().iter().zip(sensor.pressure()).map(|(&time, &pressure)| [time, pressure / 1000.0]).collect();
+                    plot_ui.line(Line::new(points).color(style.series(i)).width(style.width(1.5)).name(format!("Sensor {}", i + 1)));
+                }
+            });
+            let plot = Plot::new("pressure_profile")
+                .height(240.0)
+                .width(width)
+                .x_axis_label("Wave Pressure (kPa)")
+                .y_axis_label("Height above SWL (m)")
+                .include_x(0)
+                .legend(Legend::default());
+            style.show_plot(ui, plot, |plot_ui| {
+                if let Ok(goda) = &goda {
+                    let profile: PlotPoints = goda.profile().iter().map(|[pressure, z]| [pressure / 1000.0, *z]).collect();
+                    plot_ui.line(Line::new(profile).color(style.reference()).style(LineStyle::dashed_loose()).width(style.width(1.5)).name("Goda Design Pressure"));
+                }
+                plot_ui.points(Points::new(peaks).radius(4.0).color(style.series(0)).name("Measured Peaks"));
+            });
+        });
+        if let Err(message) = goda {
+            ui.label(format!("{}.", message));
+        }
+    }
+
+    /// Wall force record with the detected impacts, their statistics and the histogram of their maxima
+    fn show_wall_impacts(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Impact Threshold:");
+            ui.add(egui::Slider::new(&mut self.impact_threshold, 0.05..=0.95).custom_formatter(|value, _| format!("{:.0}% of the peak", 100.0 * value)));
+            self.info_button(ui, "wall_impacts", "The wave force on the downstream wall F(t) = ∫p dz − ρgd²/2 is the force of the water column above its hydrostatic force at rest. Peak-over-threshold detection counts every excursion above the threshold as one impact, from the up-crossing to the down-crossing: its maximum, rise time (up-crossing to maximum) and impulse ∫F dt over the excursion. Following PROVERBS (Oumeraci et al., 2001), an impact is impulsive when its maximum exceeds 2.5 times the quasi-static force, taken as the Goda design force of the wavemaker waves; otherwise the load is quasi-static (pulsating). Impulsive loads have short rise times compared with the wave period and call for a dynamic analysis of the structure. The depth-integrated solver smooths breaking impacts, so its maxima are a lower bound of laboratory impact forces.");
+        });
+
+        let events = self.wall_impacts();
+        let threshold = self.impact_threshold * self.wall_force.max();
+        let quasi_static = self.goda_pressure().ok().map(|goda| goda.horizontal_force());
+        if events.is_empty() {
+            ui.label("No complete impact above the threshold yet.");
+        } else {
+            let count = events.len() as f64;
+            let mean = |value: fn(&ImpactEvent) -> f64| events.iter().map(value).sum::<f64>() / count;
+            let largest = events.iter().map(|event| event.peak).fold(0.0, f64::max);
+            ui.label(format!(
+                "{} impacts: peak {:.2} kN/m (mean {:.2} kN/m), rise time {:.2} s ({:.2} T), impulse {:.2} kN·s/m, duration {:.2} s",
+                events.len(),
+                largest / 1000.0,
+                mean(|event| event.peak) / 1000.0,
+                mean(ImpactEvent::rise_time),
+                mean(ImpactEvent::rise_time) / self.wave_period,
+                mean(|event| event.impulse) / 1000.0,
+                mean(ImpactEvent::duration)
+            ));
+            match quasi_static {
+                Some(force) => {
+                    let impulsive = events.iter().filter(|event| event.class(force) == LoadClass::Impulsive).count();
+                    ui.label(format!(
+                        "Quasi-static force (Goda): {:.2} kN/m, F_max/F_qs = {:.2}: {} impulsive, {} quasi-static impacts",
+                        force / 1000.0,
+                        largest / force,
+                        impulsive,
+                        events.len() - impulsive
+                    ));
+                }
+                None => {
+                    ui.label("The Goda quasi-static force is unknown for this wall, so the impacts are not classified.");
+                }
+            }
+        }
+
+        let force: PlotPoints = self.wall_force.times().iter().zip(self.wall_force.force()).map(|(&time, &force)| [time, force / 1000.0]).collect();
+        let peaks: Vec<[f64; 2]> = events.iter().map(|event| [event.peak_time, event.peak / 1000.0]).collect();
+        let histogram = peak_histogram(&events, 10);
+        let bin_width = match histogram.as_slice() {
+            [first, second, ..] => (second.0 - first.0) / 1000.0,
+            _ => 0.1,
+        };
+        let bars: Vec<Bar> = histogram.iter().map(|&(lower, count)| Bar::new(lower / 1000.0 + 0.5 * bin_width, count as f64).width(bin_width)).collect();
+        let width = ((ui.available_width() - 60.0) / 2.0).max(300.0);
+        ui.horizontal(|ui| {
+            let style = self.plot_style;
+            let plot = Plot::new("wall_force")
+                .height(240.0)
+                .width(width)
+                .x_axis_label("Time (s)")
+                .y_axis_label("Wave Force (kN/m)")
+                .legend(Legend::default());
+            style.show_plot(ui, plot, |plot_ui| {
+                plot_ui.line(Line::new(force).color(style.series(0)).width(style.width(1.5)).name("Wall Force"));
+                plot_ui.hline(HLine::new(threshold / 1000.0).color(egui::Color32::GRAY).style(LineStyle::dashed_loose()).name("Threshold"));
+                if let Some(force) = quasi_static {
+                    plot_ui.hline(
+                        HLine::new(IMPULSIVE_FORCE_RATIO * force / 1000.0)
+                            .color(style.alert())
+                            .style(LineStyle::dashed_loose())
+                            .name("Impulsive Limit"),
+                    );
+                }
+                plot_ui.points(Points::new(peaks).radius(3.0).color(style.reference()).name("Impact Maxima"));
+            });
+            let plot = Plot::new("impact_histogram")
+                .height(240.0)
+                .width(width)
+                .x_axis_label("Impact Maximum (kN/m)")
+                .y_axis_label("Count")
+                .include_y(0);
+            style.show_plot(ui, plot, |plot_ui| {
+                plot_ui.bar_chart(BarChart::new(bars).color(style.series(0)).name("Impact Maxima"));
+            });
+        });
+    }
+
+    /// Tank level and overtopping discharge through the run, with the volumes of the overtopping waves
+    fn show_overtopping_tank(&mut self, ui: &mut egui::Ui) {
+        let Some(tank) = &self.overtopping_tank else {
+            return;
+        };
+        // Volumes per metre of crest in l/m, discharges in l/s/m
+        let waves = tank.wave_volumes();
+        let largest = waves.iter().copied().fold(0.0, f64::max);
+        ui.label(format!(
+            "Collected {:.2} l/m, tank level {:.2} cm, mean discharge q = {:.3} l/s/m",
+            1000.0 * tank.total_volume(),
+            100.0 * tank.total_volume() / tank.plan_length(),
+            tank.mean_discharge().in_liters_per_second_per_meter()
+        ));
+        if waves.is_empty() {
+            ui.label("No wave has overtopped the crest yet.");
+        } else {
+            ui.label(format!(
+                "{} overtopping waves: largest volume V_max = {:.2} l/m, mean {:.2} l/m",
+                waves.len(),
+                1000.0 * largest,
+                1000.0 * waves.iter().sum::<f64>() / waves.len() as f64
+            ));
+        }
+
+        let level: PlotPoints = tank.times().iter().zip(tank.level()).map(|(&time, level)| [time, 100.0 * level]).collect();
+        let discharge: PlotPoints = tank.times().iter().zip(tank.discharge()).map(|(&time, discharge)| [time, 1000.0 * discharge]).collect();
+        let mean = tank.mean_discharge().in_liters_per_second_per_meter();
+        let width = ((ui.available_width() - 60.0) / 2.0).max(300.0);
+        ui.horizontal(|ui| {
+            let style = self.plot_style;
+            let plot = Plot::new("tank_level")
+                .height(240.0)
+                .width(width)
+                .x_axis_label("Time (s)")
+                .y_axis_label("Tank Level (cm)")
+                .include_y(0);
+            style.show_plot(ui, plot, |plot_ui| {
+                plot_ui.line(Line::new(level).color(style.series(0)).width(style.width(1.5)).name("Tank Level"));
+            });
+            let plot = Plot::new("tank_discharge")
+                .height(240.0)
+                .width(width)
+                .x_axis_label("Time (s)")
+                .y_axis_label("Discharge (l/s/m)")
+                .include_y(0)
+                .legend(Legend::default());
+            style.show_plot(ui, plot, |plot_ui| {
+                plot_ui.line(Line::new(discharge).color(style.series(0)).width(style.width(1.5)).name("Instantaneous"));
+                plot_ui.hline(HLine::new(mean).color(egui::Color32::GRAY).style(LineStyle::dashed_loose()).name("Mean"));
+            });
+        });
+    }
+
+    /// Cross-shore profiles of the undertow and of the setup balancing the radiation stress
+    fn show_undertow(&mut self, ui: &mut egui::Ui, ctx: &egui::Context, equation_renderer: &mut EquationRenderer) {
+        ui.horizontal(|ui| {
+            ui.label("Averaging Window:");
+            let mut statistics_window = self.mean_flow_periods <= 0.0;
+            if ui.checkbox(&mut statistics_window, "Statistics Window").changed() {
+                self.mean_flow_periods = if statistics_window { 0.0 } else { 10.0 };
+            }
+            if !statistics_window {
+                ui.add(egui::Slider::new(&mut self.mean_flow_periods, 1.0..=100.0).prefix("last ").suffix(" periods").logarithmic(true));
+            }
+        });
+        let span = self.mean_flow.span().unwrap_or_default();
+        ui.horizontal(|ui| {
+            ui.label(format!("Averaged over {:.1} to {:.1} s ({} samples)", span.0, span.1, self.mean_flow.samples()));
+            self.info_button(ui, "undertow", "Mean flow of the numerical solution, averaged cell by cell over the end of the run, the statistics window or the chosen number of wave periods. The waves carry a mass flux Q_w = ⟨η'u'⟩ between their troughs and crests; in a closed channel it returns below the trough level as the undertow U = (q̄ − Q_w)/h_t, with q̄ the mean discharge and h_t = d + η̄ − H_rms/2 the depth below the troughs (Svendsen, 1984). It is strongest in the surf zone, where breaking waves and rollers carry the most mass shoreward. The radiation stress S_xx of the simulated flow drives the setup through the momentum balance dη̄/dx = −(1/ρgh) dS_xx/dx; the balanced setup integrates it from the wavemaker and departs from the simulated setup where other forces act, such as bed friction or the roller. Cells dry for part of the time, in the swash zone, are left out.");
+        });
+        let profile = match self.undertow_profile() {
+            Ok(profile) => profile,
+            Err(message) => {
+                ui.label(format!("⚠ {}", message));
+                return;
+            }
+        };
+        if let Some((position, undertow)) = profile.strongest() {
+            ui.label(format!("Strongest undertow: {:.1} cm/s at x = {:.1} m", 100.0 * undertow, position));
+        }
+
+        let points = |values: &[f64], scale: f64| -> PlotPoints {
+            profile.positions.iter().zip(values).map(|(&x, &value)| [x, scale * value]).collect()
+        };
+        let width = ((ui.available_width() - 60.0) / 2.0).max(300.0);
+        ui.horizontal(|ui| {
+            let style = self.plot_style;
+            let plot = Plot::new("undertow_profile")
+                .height(240.0)
+                .width(width)
+                .x_axis_label("Distance (m)")
+                .y_axis_label("Undertow (cm/s)")
+                .include_x(0)
+                .include_x(self.channel_length)
+                .include_y(0);
+            style.show_plot(ui, plot, |plot_ui| {
+                plot_ui.line(Line::new(points(&profile.undertow, 100.0)).color(style.series(3)).width(style.width(2.0)).name("Undertow"));
+                plot_ui.hline(HLine::new(0.0).color(egui::Color32::GRAY));
+            });
+            let plot = Plot::new("undertow_setup")
+                .height(240.0)
+                .width(width)
+                .x_axis_label("Distance (m)")
+                .y_axis_label("Mean Surface Elevation (cm)")
+                .include_x(0)
+                .include_x(self.channel_length)
+                .include_y(0)
+                .legend(Legend::default());
+            style.show_plot(ui, plot, |plot_ui| {
+                plot_ui.line(Line::new(points(&profile.setup, 100.0)).color(style.series(0)).width(style.width(2.0)).name("Simulated Setup"));
+                plot_ui.line(
+                    Line::new(points(&profile.balanced_setup, 100.0))
+                        .color(egui::Color32::GRAY)
+                        .width(style.width(1.5))
+                        .style(LineStyle::dashed_loose())
+                        .name("Radiation Stress Balance"),
+                );
+            });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Radiation Stress");
+            self.equation_info_button(ui, ctx, equation_renderer, "radiation_stress_tooltip", "radiation_stress", (
+                "The radiation stress is the excess flux of momentum due to the waves (Longuet-Higgins and Stewart, 1964): the depth-integrated flux of pressure and momentum, averaged over the waves, less that of the mean flow under the mean surface. For the depth-averaged flow of the solver:",
+                "with σ² = ⟨η'²⟩ the variance of the surface elevation, h̄ = d + η̄ the mean depth and q̄ the mean discharge, whose own momentum flux is removed. Linear theory gives E(2n − ½), with E = ρgH_rms²/8 and n = c_g/c the ratio of the group and phase speeds, tending to 3E/2 in shallow water. S_xx grows as the waves shoal and falls where they break, lowering the mean surface outside the surf zone (setdown) and raising it inside (setup), while the undertow returns the mass the broken waves carry shoreward. It is averaged over the same window as the mean flow.",
+            ));
+        });
+        let plot = Plot::new("radiation_stress")
+            .height(240.0)
+            .width(2.0 * width + 8.0)
+            .x_axis_label("Distance (m)")
+            .y_axis_label("Radiation Stress S_xx (N/m)")
+            .include_x(0)
+            .include_x(self.channel_length)
+            .include_y(0)
+            .legend(Legend::default());
+        let style = self.plot_style;
+        style.show_plot(ui, plot, |plot_ui| {
+            plot_ui.line(Line::new(points(&profile.radiation_stress, 1.0)).color(style.series(1)).width(style.width(2.0)).name("Simulated"));
+            plot_ui.line(
+                Line::new(points(&profile.linear_radiation_stress, 1.0))
+                    .color(egui::Color32::GRAY)
+                    .width(style.width(1.5))
+                    .style(LineStyle::dashed_loose())
+                    .name("Linear Theory E(2n − ½)"),
+            );
+        });
+    }
+
+    /// Shoreline excursion, swash statistics and spectrum, compared with empirical run-up formulas
+    fn show_swash_zone(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(format!("Shoreline: x = {:.2} m", self.shoreline.position().last().copied().unwrap_or(0.0)));
+            self.info_button(ui, "swash_zone", "The shoreline is the wet/dry front of the numerical solution, where the water depth falls to 1 mm, interpolated between the last wet and the first dry cell; its elevation is that of the bed there, above the still water level. Over the statistics window, the setup ⟨η⟩ is the mean shoreline elevation and the significant swash S = 4σ, split at half the wavemaker frequency into incident (S_inc) and infragravity (S_IG) bands. Run-up maxima are the highest elevations between up-crossings of the setup, R₂% the one exceeded by 2% of them. Uprush and backwash velocities are the shoreline speeds along the channel, up and down the beach. The foreshore slope β is fitted to the swash excursion. The comparison uses Stockdon et al. (2006), fitted on natural sandy beaches with the deep water wave height H₀ (the wavemaker height deshoaled with linear theory), and Hunt (1959), R = ξ₀ H₀ for regular waves on a plane slope, with the Iribarren number ξ₀ = β/√(H₀/L₀).");
+        });
+
+        // Filtering long records every frame is costly, so results are shown while paused
+        if self.simulation_running {
+            ui.label("Pause the simulation to analyse the swash.");
+            return;
+        }
+        let analysis = match self.swash_analysis() {
+            Ok(analysis) => analysis,
+            Err(message) => {
+                ui.label(format!("⚠ {}", message));
+                return;
+            }
+        };
+
+        ui.label(format!(
+            "Setup ⟨η⟩ = {:.3} m, S = {:.3} m (S_inc = {:.3} m, S_IG = {:.3} m), R₂% = {:.3} m over {} swash cycles",
+            analysis.setup,
+            analysis.significant_swash,
+            analysis.incident_swash,
+            analysis.infragravity_swash,
+            analysis.run_up_2,
+            analysis.run_up_maxima.len()
+        ));
+        ui.label(format!(
+            "Uprush: mean {:.2} m/s, max {:.2} m/s; backwash: mean {:.2} m/s, max {:.2} m/s",
+            analysis.uprush.mean, analysis.uprush.max, analysis.backwash.mean, analysis.backwash.max
+        ));
+        match self.stockdon_swash(&analysis) {
+            Ok(stockdon) => {
+                let slope = analysis.foreshore_slope.unwrap_or_default();
+                let hunt = self.deep_water_wave_height().map(|height| hunt_run_up(slope, height, self.wave_period)).ok();
+                ui.label(format!("Foreshore slope β = {:.3} (1:{:.1}), Iribarren number ξ₀ = {:.2}", slope, 1.0 / slope, stockdon.iribarren));
+                egui::Grid::new("swash_comparison").striped(true).show(ui, |ui| {
+                    ui.label("");
+                    ui.label("Model");
+                    ui.label("Stockdon (2006)");
+                    ui.label("Hunt (1959)");
+                    ui.end_row();
+                    for (name, model, empirical, hunt) in [
+                        ("Setup ⟨η⟩", analysis.setup, stockdon.setup, None),
+                        ("S_inc", analysis.incident_swash, stockdon.incident_swash, None),
+                        ("S_IG", analysis.infragravity_swash, stockdon.infragravity_swash, None),
+                        ("R₂%", analysis.run_up_2, stockdon.run_up_2, hunt),
+                    ] {
+                        ui.label(name);
+                        ui.label(format!("{:.3} m", model));
+                        ui.label(format!("{:.3} m", empirical));
+                        ui.label(hunt.map_or(String::new(), |run_up| format!("{:.3} m", run_up)));
+                        ui.end_row();
+                    }
+                });
+            }
+            Err(message) => {
+                ui.label(format!("⚠ No empirical comparison: {}", message));
+            }
+        }
+
+        let elevation: PlotPoints = self.shoreline.times().iter().zip(self.shoreline.elevation()).map(|(&time, &elevation)| [time, elevation]).collect();
+        let spectrum: PlotPoints = analysis.frequencies.iter().zip(&analysis.density).map(|(&frequency, &density)| [frequency, density]).collect();
+        let split_frequency = self.infragravity_split_frequency();
+        let width = ((ui.available_width() - 60.0) / 2.0).max(300.0);
+        ui.horizontal(|ui| {
+            let style = self.plot_style;
+            let plot = Plot::new("shoreline_elevation")
+                .height(240.0)
+                .width(width)
+                .x_axis_label("Time (s)")
+                .y_axis_label("Shoreline Elevation (m)")
+                .legend(Legend::default());
+            style.show_plot(ui, plot, |plot_ui| {
+                plot_ui.line(Line::new(elevation).color(style.series(0)).width(style.width(1.5)).name("Shoreline"));
+                plot_ui.hline(HLine::new(analysis.setup).color(egui::Color32::GRAY).style(LineStyle::dashed_loose()).name("Setup"));
+                plot_ui.hline(HLine::new(analysis.run_up_2).color(style.alert()).style(LineStyle::dashed_loose()).name("R₂%"));
+            });
+            let plot = Plot::new("swash_spectrum")
+                .height(240.0)
+                .width(width)
+                .x_axis_label("Frequency (Hz)")
+                .y_axis_label("Variance Density (m²/Hz)")
+                .include_y(0);
+            style.show_plot(ui, plot, |plot_ui| {
+                plot_ui.line(Line::new(spectrum).color(style.long_wave()).width(style.width(1.5)).name("Swash Spectrum"));
+                plot_ui.vline(VLine::new(split_frequency).color(egui::Color32::GRAY).style(LineStyle::dashed_loose()).name("f_split"));
+            });
+        });
+    }
+
+    /// Flooding of the land: run-up, inundation limit and depth, arrival times and the envelope of the highest water
+    fn show_inundation(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let record = &self.inundation;
+            match record.inundation_limit() {
+                Some(limit) => ui.label(format!(
+                    "Maximum run-up R = {:.3} m, inundation limit x = {:.2} m, {:.2} m beyond the shoreline",
+                    record.max_run_up(),
+                    limit,
+                    record.inundation_extent()
+                )),
+                None => ui.label("The land has not been flooded yet"),
+            };
+            self.info_button(ui, "inundation", "Envelope of the flooding over the whole run, recorded with the numerical solver wherever the bed rises above the still water level. A cell is flooded where the water stands more than 1 mm above the bed. The inundation limit is the farthest land cell flooded from the sea and the maximum run-up the bed elevation there above the still water level; the inundation extent is the horizontal distance from the shoreline at rest. The inundation depth is the largest water depth over the land. The wave arrives at a cell under water when its surface first departs 2 mm from the still water level, and at a land cell when the cell is first flooded. The Tsunami Inundation preset sends a leading depression N-wave (Tadepalli and Synolakis, 1994) onto a beach and a coastal plain; any other time series can be prescribed as the boundary signal.");
+        });
+        let record = &self.inundation;
+        if let Some((position, depth)) = record.max_inundation_depth() {
+            ui.label(format!("Maximum inundation depth: {:.3} m at x = {:.2} m", depth, position));
+        }
+        let arrival = |time: Option<f64>| time.map_or("not yet".to_string(), |time| format!("{:.2} s", time));
+        if let Some(shoreline) = record.still_shoreline() {
+            ui.label(format!("Arrival at the shoreline (x = {:.2} m): {}", shoreline, arrival(record.arrival_at(shoreline))));
+        }
+        for (i, gauge) in self.gauges.iter().enumerate() {
+            ui.label(format!("Arrival at gauge {} (x = {:.2} m): {}", i + 1, gauge.position(), arrival(record.arrival_at(gauge.position()))));
+        }
+
+        let bed: PlotPoints = record.positions().iter().zip(record.still_water_depth()).map(|(&x, &depth)| [x, -depth]).collect();
+        let envelope: PlotPoints = record
+            .positions()
+            .iter()
+            .zip(record.max_elevation())
+            .filter_map(|(&x, elevation)| elevation.map(|elevation| [x, elevation]))
+            .collect();
+        let arrivals: PlotPoints = record
+            .positions()
+            .iter()
+            .zip(record.arrival())
+            .filter_map(|(&x, time)| time.map(|time| [x, time]))
+            .collect();
+        let (shoreline, limit) = (record.still_shoreline(), record.inundation_limit());
+        let width = ((ui.available_width() - 60.0) / 2.0).max(300.0);
+        ui.horizontal(|ui| {
+            let style = self.plot_style;
+            let plot = Plot::new("inundation_envelope")
+                .height(240.0)
+                .width(width)
+                .x_axis_label("Distance (m)")
+                .y_axis_label("Elevation above SWL (m)")
+                .legend(Legend::default());
+            style.show_plot(ui, plot, |plot_ui| {
+                plot_ui.line(Line::new(bed).color(style.bed()).width(style.width(1.5)).name("Bed"));
+                plot_ui.line(Line::new(envelope).color(style.series(0)).width(style.width(1.5)).name("Highest Water"));
+                plot_ui.hline(HLine::new(0.0).color(egui::Color32::GRAY).style(LineStyle::dashed_loose()).name("SWL"));
+                if let Some(limit) = limit {
+                    plot_ui.vline(VLine::new(limit).color(style.alert()).style(LineStyle::dashed_loose()).name("Inundation Limit"));
+                }
+            });
+            let plot = Plot::new("inundation_arrival")
+                .height(240.0)
+                .width(width)
+                .x_axis_label("Distance (m)")
+                .y_axis_label("Arrival Time (s)")
+                .legend(Legend::default());
+            style.show_plot(ui, plot, |plot_ui| {
+                plot_ui.line(Line::new(arrivals).color(style.long_wave()).width(style.width(1.5)).name("Wave Arrival"));
+                if let Some(shoreline) = shoreline {
+                    plot_ui.vline(VLine::new(shoreline).color(egui::Color32::GRAY).style(LineStyle::dashed_loose()).name("Shoreline"));
+                }
+            });
+        });
+    }
+
+    /// Energy dissipated by each mechanism over the run, stacked so the top curve is the total
+    fn show_dissipation(&mut self, ui: &mut egui::Ui) {
+        let Some(last) = self.dissipation.last() else {
+            return;
+        };
+        ui.horizontal(|ui| {
+            let shares = self.dissipation.shares().unwrap_or_default();
+            let shares: Vec<String> = shares.iter().map(|(mechanism, share)| format!("{} {:.0}%", mechanism, 100.0 * share)).collect();
+            ui.label(format!("Dissipated {:.1} J/m: {}", WATER_DENSITY * last.total(), shares.join(", ")));
+            self.info_button(ui, "dissipation", "Wave energy per metre of crest E = ρ∫(½gη² + ½du²) dx removed by each mechanism since the start of the run, over the whole channel. The drag of the vegetation stems is the rate of work ½ C_D b_v N |u|³ over the layers in the canopy and the sponge the energy its relaxation takes from the surface and the velocities. Breaking is what remains of the energy balance of each step once the work of the wavemaker and the internal sources, the flux gηq leaving through the open end or into the overtopping tank, and those two sinks are accounted for: the energy lost in bores and steep fronts, with the numerical dissipation of the scheme and the errors of the balance. The solver has no bed friction besides the vegetation and no porous layers, so nothing else dissipates energy.");
+        });
+
+        let times = self.dissipation.times();
+        let mut stacked = vec![0.0; times.len()];
+        let mut layers = Vec::new();
+        for mechanism in DissipationMechanism::ALL {
+            for (total, value) in stacked.iter_mut().zip(self.dissipation.series(mechanism)) {
+                *total += WATER_DENSITY * value;
+            }
+            let points: Vec<[f64; 2]> = times.iter().zip(&stacked).map(|(&time, &energy)| [time, energy]).collect();
+            layers.push((mechanism, points));
+        }
+        let style = self.plot_style;
+        let plot = Plot::new("dissipation")
+            .height(240.0)
+            .width(ui.available_width().min(800.0))
+            .x_axis_label("Time (s)")
+            .y_axis_label("Energy Dissipated (J/m)")
+            .include_y(0)
+            .legend(Legend::default());
+        style.show_plot(ui, plot, |plot_ui| {
+            // From the top of the stack down, each area covering the lower part of the one above
+            for (i, (mechanism, points)) in layers.into_iter().enumerate().rev() {
+                plot_ui.line(Line::new(points).color(style.series(i)).width(style.width(1.5)).fill(0.0).fill_alpha(1.0).name(mechanism.to_string()));
+            }
+        });
+    }
+
+    /// Rise of the mean water level over the run, the net mass brought in by the boundaries
+    fn show_level_drift(&mut self, ui: &mut egui::Ui) {
+        let Some(last) = self.level_drift.last() else {
+            return;
+        };
+        ui.horizontal(|ui| {
+            let rate = self.level_drift.rate().unwrap_or_default();
+            ui.label(format!("Mean level {:+.2} mm, drifting {:+.3} mm/min", 1000.0 * last, 60_000.0 * rate));
+            self.info_button(ui, "level_drift", "Rise of the mean water level over the wet channel since the start of the run, besides the water level of the forcing: the net volume brought in through the wavemaker and the open end, spread over the wet length. In a closed flume, the waves of the wavemaker bring a net mass, their Stokes drift, and the level rises steadily; the mass compensation of the wavemaker removes it. The rate is the least-squares slope over the record. A sponge layer relaxes the surface towards the still water level and removes part of the drift near the end.");
+        });
+        let points: Vec<[f64; 2]> = self.level_drift.times().iter().zip(self.level_drift.drift()).map(|(&time, &drift)| [time, 1000.0 * drift]).collect();
+        let style = self.plot_style;
+        let plot = Plot::new("level_drift")
+            .height(200.0)
+            .width(ui.available_width().min(800.0))
+            .x_axis_label("Time (s)")
+            .y_axis_label("Mean Level Drift (mm)")
+            .include_y(0);
+        style.show_plot(ui, plot, |plot_ui| {
+            plot_ui.line(Line::new(points).color(style.series(0)).width(style.width(1.5)).name("Mean Level"));
+        });
+    }
+
+    /// Vegetation patch drawn as a band from the bed to the stem height
+    fn vegetation_plot_data(&self) -> Option<PlotPoints<'static>> {
+        let field = self.vegetation?;
+        let points = vec![
+            [field.start, 0.0],
+            [field.end, 0.0],
+            [field.end, field.stem_height],
+            [field.start, field.stem_height],
+        ];
+        Some(points.into())
+    }
+
+    /// Vegetation toggle and stem parameters
+    fn show_vegetation(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut enabled = self.vegetation.is_some();
+            if ui.checkbox(&mut enabled, "Vegetation").changed() {
+                self.vegetation = if enabled {
+                    VegetationField::new(0.4 * self.channel_length, 0.6 * self.channel_length).ok()
+                } else {
+                    None
+                };
+            }
+            self.info_button(ui, "vegetation", "Rigid vegetation patch (mangroves, seagrass, salt marsh) modelled as an array of cylinders. The stems exert a quadratic drag F = ½ C_D b_v N u|u| on the water inside the canopy, where N is the stem density, b_v the stem diameter and C_D the bulk drag coefficient. Typical C_D: 0.5-2. Mangroves: N ≈ 1-100 /m², b_v ≈ 0.05-0.2 m; seagrass: N ≈ 1000-5000 /m², b_v ≈ 0.005 m.");
+        });
+
+        let length = self.channel_length;
+        let Some(field) = &mut self.vegetation else {
+            return;
+        };
+        // Keep the patch inside the channel when the channel is shortened
+        field.end = field.end.min(length);
+        field.start = field.start.min(field.end - 0.1).max(0.0);
+        let end = field.end;
+        ui.horizontal(|ui| {
+            ui.label("Vegetation Start:");
+            ui.add(
+                egui::Slider::new(&mut field.start, 0.0..=end - 0.1)
+                    .suffix(" m")
+                    .step_by(0.1),
+            );
+        });
+        let start = field.start;
+        ui.horizontal(|ui| {
+            ui.label("Vegetation End:");
+            ui.add(
+                egui::Slider::new(&mut field.end, start + 0.1..=length)
+                    .suffix(" m")
+                    .step_by(0.1),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label("Stem Density (N):");
+            ui.add(
+                egui::Slider::new(&mut field.stem_density, 1.0..=5000.0)
+                    .logarithmic(true)
+                    .suffix(" /m²"),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label("Stem Diameter (b_v):");
+            ui.add(
+                egui::Slider::new(&mut field.stem_diameter, 0.001..=0.5)
+                    .logarithmic(true)
+                    .suffix(" m"),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label("Stem Height (h_v):");
+            ui.add(
+                egui::Slider::new(&mut field.stem_height, 0.05..=10.0)
+                    .suffix(" m")
+                    .step_by(0.01),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label("Drag Coefficient (C_D):");
+            ui.add(egui::Slider::new(&mut field.drag_coefficient, 0.0..=5.0).step_by(0.01));
+        });
+    }
+
+    /// Bed profile selector and the table editor of the composite cross-section segments
+    fn show_cross_section(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Bed Profile:");
+            if ui.selectable_label(self.cross_section.is_none(), "Flat").clicked() {
+                self.cross_section = None;
+            }
+            if ui.button("Beach").clicked() {
+                self.cross_section = Some(CrossSection::beach(self.channel_length, self.still_water_level));
+            }
+            if ui.button("Dike").clicked() {
+                self.cross_section = Some(CrossSection::dike(self.channel_length, self.still_water_level));
+            }
+            if ui.selectable_label(self.cross_section.is_some(), "Composite").clicked() && self.cross_section.is_none() {
+                self.cross_section = Some(CrossSection {
+                    segments: vec![ProfileSegment::flat("Offshore", self.channel_length)],
+                });
+            }
+            self.info_button(ui, "cross_section", "Composite cross-section built from straight segments, from the wavemaker shoreward: offshore flat, 1:m slopes, berms and structure slopes. Each segment has a label, a horizontal length and a slope m (1:m, rising shoreward; 0 for flat, negative for falling). The bed starts at the still water depth at the wavemaker and stays level beyond the last segment. Parts above the still water level are dry until waves run up on them. Used by the numerical solver; the analytical model assumes a flat bottom. Profiles are saved with the project, so a typical dike or beach section can be reused.");
+        });
+
+        let channel_length = self.channel_length;
+        let still_water_level = self.still_water_level;
+        let (datum, levels) = (self.datum, self.reference_levels);
+        let Some(section) = &mut self.cross_section else {
+            return;
+        };
+        let mut removed = None;
+        // Imported surveys have many segments
+        egui::ScrollArea::vertical().id_salt("cross_section_scroll").max_height(200.0).show(ui, |ui| {
+            egui::Grid::new("cross_section_segments").num_columns(6).striped(true).show(ui, |ui| {
+                ui.strong("Segment");
+                ui.strong("Length");
+                ui.strong("Slope 1:m");
+                ui.strong("Rise");
+                ui.strong(format!("End Level (m {})", datum.abbreviation()));
+                ui.end_row();
+                let mut start = 0.0;
+                for (i, segment) in section.segments.iter_mut().enumerate() {
+                    ui.add(egui::TextEdit::singleline(&mut segment.label).desired_width(90.0));
+                    ui.add(egui::DragValue::new(&mut segment.length).range(0.01..=channel_length).speed(0.1).suffix(" m").clamp_existing_to_range(false));
+                    ui.add(egui::DragValue::new(&mut segment.slope).range(-1000.0..=1000.0).speed(0.1).clamp_existing_to_range(false));
+                    ui.label(format!("{:+.2} m", segment.rise()));
+                    // Level entered in the datum sets the slope, e.g. a crest level from a design drawing
+                    let mut level = levels.to_datum(datum, start + segment.rise(), still_water_level);
+                    if ui.add(egui::DragValue::new(&mut level).speed(0.01).fixed_decimals(2)).changed() {
+                        let rise = levels.from_datum(datum, level, still_water_level) - start;
+                        segment.slope = if rise.abs() < 1e-9 { 0.0 } else { segment.length / rise };
+                    }
+                    start += segment.rise();
+                    if ui.small_button("✖").on_hover_text("Remove segment").clicked() {
+                        removed = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+        if let Some(i) = removed.filter(|_| section.segments.len() > 1) {
+            section.segments.remove(i);
+        }
+        ui.horizontal(|ui| {
+            if ui.button("+ Segment").clicked() {
+                section.segments.push(ProfileSegment::flat("Segment", 1.0));
+            }
+            ui.label(format!(
+                "Profile length {:.1} m, crest {:+.2} m above the still water level ({:+.2} m {})",
+                section.length(),
+                section.crest_elevation() - still_water_level,
+                levels.to_datum(datum, section.crest_elevation(), still_water_level),
+                datum.abbreviation()
+            ));
+        });
+        if section.length() > channel_length + 1e-9 {
+            ui.colored_label(egui::Color32::YELLOW, "⚠ The profile is longer than the channel and is cut at its end");
+        }
+        self.show_tank_settings(ui);
+        self.show_survey_import(ui);
+    }
+
+    /// Collection tank behind the crest and its size
+    fn show_tank_settings(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut enabled = self.overtopping_tank.is_some();
+            if ui.checkbox(&mut enabled, "Overtopping Tank").changed() {
+                self.overtopping_tank = if enabled { OvertoppingTank::new(1.0).ok() } else { None };
+            }
+            if let Some(tank) = &mut self.overtopping_tank {
+                ui.label("Plan Area per Unit Width:");
+                let mut plan_length = tank.plan_length();
+                if ui.add(egui::DragValue::new(&mut plan_length).range(0.01..=100.0).speed(0.01).suffix(" m")).changed() {
+                    tank.set_plan_length(plan_length).ok();
+                }
+            }
+            self.info_button(ui, "overtopping_tank", "Virtual collection tank behind the crest, as in overtopping experiments. The water reaching the landward edge of the crest falls freely (critical flow u = √(gh)) through a chute into the tank, leaves the channel and never flows back. The tank records the collected volume V(t) per metre of crest; its level is V divided by the plan area of the tank per unit width of the chute. As in the laboratory, the instantaneous discharge q(t) = dV/dt follows from the rise of the tank level, the mean discharge is the final volume over the duration of the run, and each filling of the tank between calm periods is the volume of one overtopping wave. Needs the numerical solver and a cross-section whose crest rises above the still water level.");
+        });
+        match self.tank_position() {
+            Err(message) if self.overtopping_tank.is_some() => {
+                ui.colored_label(egui::Color32::YELLOW, format!("⚠ {}", message));
+            }
+            Ok(position) if position > self.sponge_start() => {
+                ui.colored_label(egui::Color32::YELLOW, "⚠ The sponge layer reaches the crest and damps the overtopping flow");
+            }
+            _ => {}
+        }
+    }
+
+    /// Wave conditions switched on during the run, with their generation windows
+    fn show_forcing_schedule(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Forcing Schedule").id_salt("forcing_schedule").show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Wave conditions generated in sequence by the wavemaker");
+                self.info_button(ui, "forcing_schedule", "The run starts with the wavemaker waves above. Each condition of the schedule takes over at its start time and generates until the next one starts; the last one generates the set number of its waves. A run can begin with swell, switch to storm seas, then calm down, as needed for storm-sequence overtopping and morphology studies. The switch is instantaneous, so a short transient follows each start. The schedule can be edited while the run is paused or going on: later windows take the new conditions without restarting the solver.");
+            });
+            let mut removed = None;
+            egui::Grid::new("forcing_schedule_entries").num_columns(4).striped(true).show(ui, |ui| {
+                ui.strong("Start");
+                ui.strong("H");
+                ui.strong("T");
+                ui.end_row();
+                for (i, entry) in self.forcing_schedule.entries.iter_mut().enumerate() {
+                    ui.add(egui::DragValue::new(&mut entry.start).range(0.0..=f64::MAX).speed(1.0).suffix(" s"));
+                    ui.add(egui::DragValue::new(&mut entry.wave_height).range(0.01..=5.0).speed(0.01).suffix(" m"));
+                    ui.add(egui::DragValue::new(&mut entry.wave_period).range(1.0..=20.0).speed(0.1).suffix(" s"));
+                    if ui.small_button("✖").on_hover_text("Remove condition").clicked() {
+                        removed = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+            if let Some(i) = removed {
+                self.forcing_schedule.entries.remove(i);
+            }
+            ui.horizontal(|ui| {
+                if ui.button("+ Condition").clicked() {
+                    let last = self.forcing_schedule.entries.last().map_or((0.0, self.wave_height, self.wave_period), |entry| {
+                        (entry.start, entry.wave_height, entry.wave_period)
+                    });
+                    self.forcing_schedule.entries.push(ScheduledWaves {
+                        start: last.0 + self.number_of_waves as f64 * last.2,
+                        wave_height: last.1,
+                        wave_period: last.2,
+                    });
+                }
+                if ui.button("Storm Sequence").clicked() {
+                    let swell = 10.0 * self.wave_period;
+                    self.forcing_schedule = ForcingSchedule::storm_sequence(self.wave_height, self.wave_period, swell, 2.0 * swell);
+                }
+                if !self.forcing_schedule.is_empty() && ui.button("Clear").clicked() {
+                    self.forcing_schedule = ForcingSchedule::default();
+                }
+            });
+            if let Err(message) = self.forcing_schedule.validate() {
+                ui.colored_label(egui::Color32::from_rgb(200, 80, 0), format!("⚠ {}", message));
+            } else if !self.forcing_schedule.is_empty() {
+                let current = self.forcing_schedule.window_index(self.simulation_time);
+                let windows = self.forcing_schedule.windows(self.wave_height, self.wave_period, self.number_of_waves);
+                for (i, window) in windows.iter().enumerate() {
+                    let marker = if i == current && self.simulation_time > 0.0 { "▶" } else { " " };
+                    ui.label(format!(
+                        "{} {:.1} s to {:.1} s: H = {:.2} m, T = {:.1} s ({:.0} waves)",
+                        marker,
+                        window.start,
+                        window.end(),
+                        window.wave_height,
+                        window.wave_period,
+                        window.duration / window.wave_period
+                    ));
+                }
+            }
+        });
+    }
+
+    /// Storm builder: synthetic or imported hydrograph, its points and curves
+    fn show_storm_hydrograph(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Storm Hydrograph").id_salt("storm_hydrograph").show(ui, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Synthetic Storm").clicked() {
+                    let duration = (self.number_of_waves as f64 * self.wave_period).max(10.0 * self.wave_period);
+                    self.storm_hydrograph = Some(StormHydrograph::synthetic(duration, 2.0 * self.wave_height, self.wave_period, 0.1 * self.still_water_level));
+                }
+                if self.storm_hydrograph.is_some() && ui.button("Clear").clicked() {
+                    self.storm_hydrograph = None;
+                }
+                self.info_button(ui, "storm_hydrograph", "Significant wave height Hs, peak period Tp and surge through the run, interpolated linearly between the points. The wavemaker generates regular waves of the same energy as the sea state, H = Hs/√2 at the period Tp, updated at the chosen interval with a continuous paddle phase, instead of the wavemaker waves and the forcing schedule. The water level follows the surge: the numerical solver lets in the flow that fills the wet channel to the new level, assuming the surge varies slowly compared with the crossing time of the channel. Import a CSV file with the columns time (s), Hs (m), Tp (s) and surge (m), or start from a synthetic storm and edit its points.");
+            });
+            ui.horizontal(|ui| {
+                ui.label("File:");
+                ui.add(egui::TextEdit::singleline(&mut self.hydrograph_path).desired_width(200.0));
+                if ui.button("Import").clicked() {
+                    let interval = self.storm_hydrograph.as_ref().map_or(2.0 * self.wave_period, |hydrograph| hydrograph.update_interval);
+                    self.hydrograph_status = Some(match StormHydrograph::load(std::path::Path::new(&self.hydrograph_path), interval) {
+                        Ok(hydrograph) => {
+                            let message = format!("Imported {} points over {:.0} s", hydrograph.points.len(), hydrograph.duration());
+                            self.storm_hydrograph = Some(hydrograph);
+                            message
+                        }
+                        Err(message) => format!("⚠ {}", message),
+                    });
+                }
+            });
+            if let Some(status) = &self.hydrograph_status {
+                ui.label(status);
+            }
+            let Some(hydrograph) = &mut self.storm_hydrograph else {
+                return;
+            };
+            ui.horizontal(|ui| {
+                ui.label("Update Interval:");
+                ui.add(egui::DragValue::new(&mut hydrograph.update_interval).range(0.5..=600.0).speed(0.1).suffix(" s"));
+            });
+            let mut removed = None;
+            egui::ScrollArea::vertical().id_salt("hydrograph_points").max_height(200.0).show(ui, |ui| {
+                egui::Grid::new("hydrograph_table").num_columns(5).striped(true).show(ui, |ui| {
+                    ui.strong("Time");
+                    ui.strong("Hs");
+                    ui.strong("Tp");
+                    ui.strong("Surge");
+                    ui.end_row();
+                    for (i, point) in hydrograph.points.iter_mut().enumerate() {
+                        ui.add(egui::DragValue::new(&mut point.time).range(0.0..=f64::MAX).speed(1.0).suffix(" s"));
+                        ui.add(egui::DragValue::new(&mut point.significant_wave_height).range(0.01..=10.0).speed(0.01).suffix(" m"));
+                        ui.add(egui::DragValue::new(&mut point.peak_period).range(0.5..=30.0).speed(0.1).suffix(" s"));
+                        ui.add(egui::DragValue::new(&mut point.surge).range(-5.0..=5.0).speed(0.01).suffix(" m"));
+                        if ui.small_button("✖").on_hover_text("Remove point").clicked() {
+                            removed = Some(i);
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+            if let Some(i) = removed.filter(|_| hydrograph.points.len() > 2) {
+                hydrograph.points.remove(i);
+            }
+            if ui.button("+ Point").clicked() {
+                let point = match hydrograph.points.last() {
+                    Some(&last) => HydrographPoint { time: last.time + 10.0 * last.peak_period, ..last },
+                    None => HydrographPoint {
+                        time: 0.0,
+                        significant_wave_height: std::f64::consts::SQRT_2 * self.wave_height,
+                        peak_period: self.wave_period,
+                        surge: 0.0,
+                    },
+                };
+                hydrograph.points.push(point);
+            }
+            if let Err(message) = hydrograph.validate() {
+                ui.colored_label(egui::Color32::from_rgb(200, 80, 0), format!("⚠ {}", message));
+            }
+            self.show_hydrograph_curves(ui, "hydrograph_editor", None);
+        });
+    }
+
+    /// Hs, surge and Tp of the storm through time, with the displayed time marked
+    fn show_hydrograph_curves(&self, ui: &mut egui::Ui, id: &str, range: Option<(f64, f64)>) {
+        let Some(hydrograph) = &self.storm_hydrograph else {
+            return;
+        };
+        let (start, end) = range.unwrap_or((0.0, hydrograph.duration()));
+        let samples = 200;
+        let states: Vec<HydrographPoint> = (0..=samples).map(|i| hydrograph.at(start + (end - start) * i as f64 / samples as f64)).collect();
+        let curve = |value: fn(&HydrographPoint) -> f64| -> Vec<[f64; 2]> { states.iter().map(|state| [state.time, value(state)]).collect() };
+        let (heights, surges, periods) = (curve(|state| state.significant_wave_height), curve(|state| state.surge), curve(|state| state.peak_period));
+        let shown_time = self.displayed_time();
+        let width = (ui.available_width() - 40.0).max(400.0);
+        let link = egui::Id::new(id).with("link");
+        let style = self.plot_style;
+        let plot = Plot::new(format!("{}_levels", id))
+            .height(80.0)
+            .width(width)
+            .legend(Legend::default())
+            .link_axis(link, [true, false])
+            .include_x(start)
+            .include_x(end)
+            .include_y(0.0)
+            .y_axis_label("m");
+        style.show_plot(ui, plot, |plot_ui| {
+            plot_ui.line(Line::new(PlotPoints::from(heights)).color(style.water()).width(style.width(1.5)).name("Hs"));
+            plot_ui.line(Line::new(PlotPoints::from(surges)).color(style.series(5)).width(style.width(1.5)).name("Surge"));
+            plot_ui.vline(VLine::new(shown_time).color(style.alert()).width(2.0));
+        });
+        let plot = Plot::new(format!("{}_periods", id))
+            .height(60.0)
+            .width(width)
+            .legend(Legend::default())
+            .link_axis(link, [true, false])
+            .include_x(start)
+            .include_x(end)
+            .include_y(0.0)
+            .x_axis_label("Time (s)")
+            .y_axis_label("s");
+        style.show_plot(ui, plot, |plot_ui| {
+            plot_ui.line(Line::new(PlotPoints::from(periods)).color(style.reference()).width(style.width(1.5)).name("Tp"));
+            plot_ui.vline(VLine::new(shown_time).color(style.alert()).width(2.0));
+        });
+    }
+
+    /// Measured or parametric spectrum of the irregular wavemaker waves, with the met-ocean file import
+    fn show_boundary_spectrum(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Boundary Spectrum").id_salt("boundary_spectrum").show(ui, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("JONSWAP").on_hover_text("Spectrum of the same energy as the wavemaker waves, Hs = √2 H and Tp = T").clicked() {
+                    self.boundary_spectrum = WaveSpectrum::jonswap(std::f64::consts::SQRT_2 * self.wave_height, self.wave_period, JONSWAP_GAMMA).ok();
+                }
+                if self.boundary_spectrum.is_some() && ui.button("Clear").clicked() {
+                    self.boundary_spectrum = None;
+                }
+                self.info_button(ui, "boundary_spectrum", "Variance density spectrum S(f) of irregular waves generated at the wavemaker instead of the regular wavemaker waves and the forcing schedule, for as many peak periods as the number of waves. The waves are synthesized from regular components of equal frequency spacing over the part of the spectrum above 1% of the peak, with amplitudes a = √(2SΔf) and random phases drawn from the random seed. Import measured spectra from NDBC spectral wave density (swden) or realtime spectral (.data_spec) files, spectra computed offshore in SWAN from its 1D (.sp1) or 2D (.sp2) output, or sea states from an ERA5 CSV extract with the columns swh and pp1d (or mwp), turned into JONSWAP spectra (γ = 3.3). The format is recognized from the content. Imported spectra are integrated over the directions; a directional spreading can be set on top. A storm hydrograph takes precedence over the spectrum.");
+            });
+            ui.horizontal(|ui| {
+                ui.label("File:");
+                ui.add(egui::TextEdit::singleline(&mut self.spectrum_path).desired_width(200.0));
+                if ui.button("Import").clicked() {
+                    self.spectrum_status = Some(match load_spectra(std::path::Path::new(&self.spectrum_path)) {
+                        Ok((format, records)) => {
+                            let message = format!("Imported {} spectra ({})", records.len(), format);
+                            self.boundary_spectrum = records.first().map(|record| record.spectrum.clone());
+                            self.spectrum_records = records;
+                            message
+                        }
+                        Err(message) => format!("⚠ {}", message),
+                    });
+                }
+            });
+            if let Some(status) = &self.spectrum_status {
+                ui.label(status);
+            }
+            if !self.spectrum_records.is_empty() {
+                let selected = self.spectrum_records.iter().position(|record| Some(&record.spectrum) == self.boundary_spectrum.as_ref());
+                ui.horizontal(|ui| {
+                    ui.label("Record:");
+                    egui::ComboBox::from_id_salt("spectrum_record")
+                        .selected_text(selected.map_or("None", |index| self.spectrum_records[index].time.as_str()))
+                        .show_ui(ui, |ui| {
+                            for (index, record) in self.spectrum_records.iter().enumerate() {
+                                if ui.selectable_label(selected == Some(index), &record.time).clicked() {
+                                    self.boundary_spectrum = Some(record.spectrum.clone());
+                                }
+                            }
+                        });
+                });
+            }
+            if self.boundary_spectrum.is_none() {
+                return;
+            }
+            self.show_directional_spreading(ui);
+            let Some(spectrum) = &self.boundary_spectrum else {
+                return;
+            };
+            if self.storm_hydrograph.is_some() {
+                ui.colored_label(egui::Color32::from_rgb(200, 80, 0), "⚠ The storm hydrograph replaces the boundary spectrum");
+            } else if self.boundary_signal.is_some() {
+                ui.colored_label(egui::Color32::from_rgb(200, 80, 0), "⚠ The boundary signal replaces the boundary spectrum");
+            }
+            let (low, high) = spectrum.band();
+            ui.label(format!(
+                "Hm0 = {:.3} m, Tp = {:.2} s, Te = {:.2} s, {} components from {:.3} to {:.3} Hz",
+                spectrum.significant_wave_height(),
+                spectrum.peak_period(),
+                spectrum.energy_period(),
+                SPECTRUM_COMPONENTS,
+                low,
+                high
+            ));
+            let density: Vec<[f64; 2]> = spectrum.frequencies().iter().zip(spectrum.density()).map(|(&f, &s)| [f, s]).collect();
+            let flume_density: Option<Vec<[f64; 2]>> = self
+                .boundary_spreading
+                .and(self.flume_spectrum())
+                .map(|flume| flume.frequencies().iter().zip(flume.density()).map(|(&f, &s)| [f, s]).collect());
+            let style = self.plot_style;
+            let plot = Plot::new("boundary_spectrum_plot")
+                .height(150.0)
+                .width((ui.available_width() - 40.0).max(400.0))
+                .include_y(0.0)
+                .x_axis_label("Frequency (Hz)")
+                .y_axis_label("S(f) (m²/Hz)");
+            style.show_plot(ui, plot, |plot_ui| {
+                plot_ui.line(Line::new(PlotPoints::from(density)).color(style.water()).width(style.width(1.5)).name("Spectrum"));
+                if let Some(flume_density) = flume_density {
+                    plot_ui.line(Line::new(PlotPoints::from(flume_density)).color(style.series(1)).width(style.width(1.5)).name("Down the Flume"));
+                }
+                for frequency in [low, high] {
+                    plot_ui.vline(VLine::new(frequency).color(egui::Color32::GRAY).style(LineStyle::dashed_loose()));
+                }
+            });
+        });
+    }
+
+    /// Directional spreading of the boundary spectrum, with the share of the sea generated down the flume
+    fn show_directional_spreading(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut directional = self.boundary_spreading.is_some();
+            if ui.checkbox(&mut directional, "Directional Spreading").changed() {
+                self.boundary_spreading = directional.then(DirectionalSpreading::default);
+            }
+            if let Some(spreading) = &mut self.boundary_spreading {
+                ui.label("θm:");
+                let mut degrees = spreading.mean_direction.to_degrees();
+                if ui.add(egui::DragValue::new(&mut degrees).range(-90.0..=90.0).speed(1.0).suffix("°")).changed() {
+                    spreading.mean_direction = degrees.to_radians();
+                }
+                ui.label("s:");
+                ui.add(egui::DragValue::new(&mut spreading.exponent).range(1.0..=100.0).speed(0.5));
+            }
+            self.info_button(ui, "directional_spreading", "Spreads the energy of the boundary spectrum over the directions with the cos^2s((θ − θm)/2) function of Longuet-Higgins et al. (1963), θm the mean direction from the flume axis. The larger the exponent s, the narrower the spreading and the longer the crests: s ≈ 10 for wind seas, 25 for young swell and 75 for swell from distant storms (Goda, 2000). The flume only holds the waves travelling along its axis: it generates the spectrum scaled by the share ∫ D(θ) cos θ dθ of the energy flux of the directional sea crossing a section of the flume, so that the same energy enters it. The waves travelling back, beyond ±90°, bring none. The directional spectrum S(f, θ) = S(f) D(θ) is kept for a model of the sea in plan.");
+        });
+        let Some(spreading) = self.boundary_spreading else {
+            return;
+        };
+        let factor = spreading.flume_factor();
+        let flume_height = self.flume_spectrum().map_or(0.0, |spectrum| spectrum.significant_wave_height());
+        ui.label(format!(
+            "σθ = {:.1}°: {:.1}% of the energy flux travels down the flume, Hm0 = {:.3} m along it",
+            spreading.directional_spread().to_degrees(),
+            100.0 * factor,
+            flume_height
+        ));
+        let density: Vec<[f64; 2]> = (-180..=180).map(|degrees| [degrees as f64, spreading.density((degrees as f64).to_radians())]).collect();
+        let style = self.plot_style;
+        let plot = Plot::new("directional_spreading_plot")
+            .height(120.0)
+            .width((ui.available_width() - 40.0).max(400.0))
+            .include_y(0.0)
+            .x_axis_label("Direction from the flume axis (°)")
+            .y_axis_label("D(θ) (1/rad)");
+        style.show_plot(ui, plot, |plot_ui| {
+            plot_ui.line(Line::new(PlotPoints::from(density)).color(style.water()).width(style.width(1.5)).name("Spreading"));
+            for direction in [-90.0, 90.0] {
+                plot_ui.vline(VLine::new(direction).color(egui::Color32::GRAY).style(LineStyle::dashed_loose()));
+            }
+        });
+    }
+
+    /// Surface elevation prescribed at the wavemaker from an expression or a time series file, with its plot
+    fn show_boundary_signal(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Boundary Signal").id_salt("boundary_signal").show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("η(t) =");
+                ui.add(egui::TextEdit::singleline(&mut self.signal_source).desired_width(250.0));
+                if ui.button("Use Expression").clicked() {
+                    match BoundarySignal::expression(&self.signal_source) {
+                        Ok(signal) => {
+                            self.boundary_signal = Some(signal);
+                            self.signal_status = None;
+                        }
+                        Err(message) => self.signal_status = Some(format!("⚠ {}", message)),
+                    }
+                }
+                if self.boundary_signal.is_some() && ui.button("Clear").clicked() {
+                    self.boundary_signal = None;
+                    self.signal_status = None;
+                }
+                self.info_button(ui, "boundary_signal", "Surface elevation prescribed at the wavemaker instead of the wave theories, the boundary spectrum, the wavemaker waves and the forcing schedule. Type an expression of the time t (s), the wave height H (m) and the period T (s), e.g. H / 2 * cos(2 * pi * t / T) * min(t / (3 * T), 1) for a ramped wave, generated for the number of waves times T; or import a time series file with the columns time (s) and elevation (m), separated by commas, semicolons or spaces. The signal is generated as its Fourier series over its duration: every harmonic becomes a regular wave of its own amplitude, phase and celerity, so the elevation at the wavemaker follows the signal and each component disperses as in linear theory. The mean of the signal is left out, and the signal repeats if the run outlasts it. Components below a thousandth of the largest are dropped. A storm hydrograph takes precedence over the signal.");
+            });
+            ui.horizontal(|ui| {
+                ui.label("Time Series:");
+                ui.add(egui::TextEdit::singleline(&mut self.signal_path).desired_width(200.0));
+                if ui.button("Import").clicked() {
+                    match BoundarySignal::load(std::path::Path::new(&self.signal_path)) {
+                        Ok(signal) => {
+                            self.signal_status = Some(format!("Imported {:.1} s of signal", signal.duration(0.0)));
+                            self.boundary_signal = Some(signal);
+                        }
+                        Err(message) => self.signal_status = Some(format!("⚠ {}", message)),
+                    }
+                }
+            });
+            if let Some(status) = &self.signal_status {
+                ui.label(status);
+            }
+
+            let Some(signal) = &self.boundary_signal else {
+                return;
+            };
+            if self.storm_hydrograph.is_some() {
+                ui.colored_label(egui::Color32::from_rgb(200, 80, 0), "⚠ The storm hydrograph replaces the boundary signal");
+            }
+            let duration = signal.duration(self.generation_duration());
+            match signal.components(self.wave_height, self.wave_period, self.generation_duration()) {
+                Ok(components) => {
+                    let shortest = components.iter().map(SpectralComponent::period).fold(f64::INFINITY, f64::min);
+                    ui.label(format!("{:.1} s of signal, {} components, shortest period {:.2} s", duration, components.len(), shortest));
+                }
+                Err(message) => {
+                    ui.colored_label(egui::Color32::YELLOW, format!("⚠ {}", message));
+                    return;
+                }
+            }
+            let samples = 500;
+            let points: Vec<[f64; 2]> = (0..=samples)
+                .filter_map(|i| {
+                    let time = duration * i as f64 / samples as f64;
+                    signal.elevation(time, self.wave_height, self.wave_period).ok().map(|elevation| [time, elevation])
+                })
+                .collect();
+            let style = self.plot_style;
+            let plot = Plot::new("boundary_signal_plot")
+                .height(150.0)
+                .width((ui.available_width() - 40.0).max(400.0))
+                .x_axis_label("Time (s)")
+                .y_axis_label("η (m)");
+            style.show_plot(ui, plot, |plot_ui| {
+                plot_ui.line(Line::new(PlotPoints::from(points)).color(style.water()).width(style.width(1.5)).name("Signal"));
+            });
+        });
+    }
+
+    /// Refinement zones of the numerical grid and the grid line display
+    fn show_grid_refinement(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Grid Refinement").id_salt("grid_refinement").show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.show_grid_lines, "Show Grid Lines");
+                self.info_button(ui, "grid_refinement", "Stretched grid for the numerical solver. Each zone concentrates cells where they are needed, such as over a structure or in the surf zone, with a spacing 'factor' times finer than offshore; the spacing changes smoothly over half the zone length on each side. The number of cells stays the grid resolution, so refining a zone coarsens the rest of the channel. Continuity, momentum and the non-hydrostatic pressure use the local cell widths, and the time step follows the smallest cells.");
+            });
+            let channel_length = self.channel_length;
+            let mut removed = None;
+            egui::Grid::new("refinement_zones").num_columns(4).striped(true).show(ui, |ui| {
+                ui.strong("Start");
+                ui.strong("End");
+                ui.strong("Factor");
+                ui.end_row();
+                for (i, zone) in self.grid_refinement.iter_mut().enumerate() {
+                    ui.add(egui::DragValue::new(&mut zone.start).range(0.0..=channel_length).speed(0.1).suffix(" m"));
+                    ui.add(egui::DragValue::new(&mut zone.end).range(0.0..=channel_length).speed(0.1).suffix(" m"));
+                    ui.add(egui::DragValue::new(&mut zone.factor).range(1.0..=10.0).speed(0.05).prefix("×").clamp_existing_to_range(false));
+                    if ui.small_button("✖").on_hover_text("Remove zone").clicked() {
+                        removed = Some(i);
+                    }
+                    ui.end_row();
+                    if zone.end <= zone.start {
+                        zone.end = (zone.start + 0.1).min(channel_length);
+                        zone.start = zone.start.min(zone.end - 0.1);
+                    }
+                }
+            });
+            if let Some(i) = removed {
+                self.grid_refinement.remove(i);
+            }
+            ui.horizontal(|ui| {
+                if ui.button("+ Zone").clicked() {
+                    self.add_refinement_zone();
+                }
+                if !self.grid_refinement.is_empty() && ui.button("Uniform Grid").clicked() {
+                    self.grid_refinement.clear();
+                }
+            });
+            ui.separator();
+            self.show_auto_refinement(ui);
+        });
+    }
+
+    /// Add a refinement zone over the structure or shore of the profile, otherwise at the end of the channel
+    fn add_refinement_zone(&mut self) {
+        let channel_length = self.channel_length;
+        let start = self
+            .cross_section
+            .as_ref()
+            .and_then(|section| section.segments.first())
+            .map_or(0.8 * channel_length, |offshore| offshore.length.min(0.8 * channel_length));
+        self.grid_refinement.push(RefinementZone {
+            start,
+            end: channel_length,
+            factor: 2.0,
+        });
+    }
+
+    /// Targets of the automatic refinement, with the cell count and runtime of the planned grid
+    fn show_auto_refinement(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.strong("Automatic Refinement");
+            self.info_button(ui, "auto_refinement", "Chooses refinement zones and the number of cells from target resolutions. Offshore, the cells resolve the linear wavelength at the wavemaker with the chosen number of points per wavelength. Profile segments steeper than the structure threshold (1:m), such as dike and breakwater slopes, get the structure cell width. The surf zone, from half a wavelength before the depth where H/d reaches 0.78 up to where the bed rises one wave height above the still water level, gets the surf zone cell width. The runtime estimate follows from the number of cells and the time step in the smallest cell.");
+        });
+        let targets = &mut self.auto_refinement;
+        egui::Grid::new("auto_refinement_targets").num_columns(2).show(ui, |ui| {
+            ui.label("Points per Wavelength:");
+            ui.add(egui::DragValue::new(&mut targets.points_per_wavelength).range(5.0..=200.0).speed(1.0));
+            ui.end_row();
+            ui.label("Steep Slopes Above 1:");
+            ui.add(egui::DragValue::new(&mut targets.steep_slope).range(1.0..=100.0).speed(0.1));
+            ui.end_row();
+            ui.label("Structure Cell Width:");
+            ui.add(egui::DragValue::new(&mut targets.structure_spacing).range(0.001..=10.0).speed(0.001).suffix(" m"));
+            ui.end_row();
+            ui.label("Surf Zone Cell Width:");
+            ui.add(egui::DragValue::new(&mut targets.surf_zone_spacing).range(0.001..=10.0).speed(0.001).suffix(" m"));
+            ui.end_row();
+        });
+
+        let plan = match self.refinement_plan() {
+            Ok(plan) => plan,
+            Err(message) => {
+                ui.colored_label(egui::Color32::YELLOW, format!("⚠ {}", message));
+                return;
+            }
+        };
+        for (reason, zone) in &plan.zones {
+            ui.label(format!("{}: {:.1}–{:.1} m, ×{:.1} finer", reason, zone.start, zone.end, zone.factor));
+        }
+        if plan.zones.is_empty() {
+            ui.label("No steep bed or surf zone to refine");
+        }
+        let current = self.computational_grid().map(|grid| (grid.nx(), self.estimated_runtime(&grid)));
+        let planned = plan.grid(self.channel_length).map(|grid| self.estimated_runtime(&grid));
+        if let (Ok((cells, runtime)), Ok(planned_runtime)) = (current, planned) {
+            ui.label(format!(
+                "{} cells, offshore Δx {:.3} m, estimated runtime {:.1} s (now {} cells, {:.1} s)",
+                plan.cells, plan.offshore_spacing, planned_runtime, cells, runtime
+            ));
+        }
+        if ui.button("Apply Refinement").clicked() {
+            self.apply_refinement_plan(&plan);
+        }
+    }
+
+    /// Vertical datum of the elevations and the levels tying the datums together
+    fn show_datum(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Vertical Datum").id_salt("vertical_datum").show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Elevations in:");
+                egui::ComboBox::from_id_salt("vertical_datum_selector")
+                    .selected_text(self.datum.to_string())
+                    .show_ui(ui, |ui| {
+                        for datum in VerticalDatum::ALL {
+                            ui.selectable_value(&mut self.datum, datum, datum.to_string());
+                        }
+                    });
+                self.info_button(ui, "vertical_datum", "Reference of the elevations entered and shown for the channel. The computations measure elevations from the bed at the wavemaker; the chart datum (CD, near the lowest astronomical tide on nautical charts), mean sea level (MSL) and a user datum such as a local survey datum are placed relative to MSL, and the still water level gives the tide and surge. Bed levels, crest levels and the still water level can then be entered as surveyed, e.g. a crest at +5.0 m CD, and are converted consistently. The elevation axis of the channel plot is labelled in the selected datum.");
+            });
+            egui::Grid::new("reference_levels").num_columns(2).show(ui, |ui| {
+                ui.label("Chart Datum:");
+                ui.add(egui::DragValue::new(&mut self.reference_levels.chart_datum).speed(0.01).suffix(" m MSL"));
+                ui.end_row();
+                ui.label("User Datum:");
+                ui.add(egui::DragValue::new(&mut self.reference_levels.user_datum).speed(0.01).suffix(" m MSL"));
+                ui.end_row();
+
+                let suffix = format!(" m {}", self.datum.abbreviation());
+                ui.label("Still Water Level:");
+                let mut water_level = self.reference_levels.water_level(self.datum, self.still_water_level);
+                if ui.add(egui::DragValue::new(&mut water_level).speed(0.01).suffix(&suffix)).changed() {
+                    self.set_water_level_in_datum(water_level);
+                }
+                ui.end_row();
+                ui.label("Bed at Wavemaker:");
+                let mut bed_level = self.level_in_datum(0.0);
+                let response = ui.add_enabled(self.datum != VerticalDatum::ChannelBed, egui::DragValue::new(&mut bed_level).speed(0.01).suffix(&suffix));
+                if response.changed() {
+                    self.set_bed_level_in_datum(bed_level);
+                }
+                ui.end_row();
+            });
+        });
+    }
+
+    /// Tide, surge and wave setup of the total water level at the structure, stacked in a bar chart
+    fn show_water_level(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Total Water Level").id_salt("total_water_level").show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Tide:");
+                ui.add(egui::DragValue::new(&mut self.water_levels.tide).speed(0.01).range(-10.0..=10.0).suffix(" m MSL"));
+                self.info_button(ui, "total_water_level", "Decomposition of the total water level at the structure in a coastal flood assessment: the astronomical tide above mean sea level, the storm surge on top of it (a constant level, or a time series of the storm taken at its peak or at a chosen time) and the wave setup, the rise of the mean water level at the shoreline as the waves break. The setup follows Stockdon et al. (2006), ⟨η⟩ = 0.35 β √(H₀L₀), from the deep water height of the wavemaker waves and the slope β of the bed profile at the still water level, or the mean shoreline elevation of the simulated swash. Apply sets the still water level of the channel to the tide and surge; the simulated waves then make their own setup. The freeboard is the crest of the bed profile above the total water level. Surge files are CSV with the columns time (h) and surge (m).");
+            });
+            ui.horizontal(|ui| {
+                ui.label("Surge:");
+                let is_series = matches!(self.water_levels.surge, Surge::TimeSeries { .. });
+                if ui.selectable_label(!is_series, "Constant").clicked() && is_series {
+                    self.water_levels.surge = Surge::Static(self.water_levels.surge_level());
+                }
+                if ui.selectable_label(is_series, "Time Series").clicked() && !is_series {
+                    let level = self.water_levels.surge_level();
+                    self.water_levels.surge = Surge::TimeSeries { times: vec![0.0, 12.0, 24.0], levels: vec![0.0, level, 0.0] };
+                }
+                if let Surge::Static(level) = &mut self.water_levels.surge {
+                    ui.add(egui::DragValue::new(level).speed(0.01).range(-5.0..=10.0).suffix(" m"));
+                }
+            });
+            // Cloned, as an import replaces the series
+            if let Surge::TimeSeries { times, levels } = self.water_levels.surge.clone() {
+                ui.horizontal(|ui| {
+                    ui.label("File:");
+                    ui.add(egui::TextEdit::singleline(&mut self.surge_path).desired_width(200.0));
+                    if ui.button("Import").clicked() {
+                        self.surge_status = Some(match Surge::load(std::path::Path::new(&self.surge_path)) {
+                            Ok(surge) => {
+                                let (time, level) = surge.peak();
+                                self.water_levels.surge = surge;
+                                format!("Imported surge peaking at {:.2} m after {:.1} h", level, time)
+                            }
+                            Err(message) => format!("⚠ {}", message),
+                        });
+                    }
+                });
+                if let Some(status) = &self.surge_status {
+                    ui.label(status);
+                }
+                let (start, end) = (times[0], times[times.len() - 1]);
+                let series: PlotPoints = times.iter().zip(&levels).map(|(&time, &level)| [time, level]).collect();
+                ui.horizontal(|ui| {
+                    let mut at_peak = self.water_levels.surge_time.is_none();
+                    if ui.checkbox(&mut at_peak, "At Peak").changed() {
+                        self.water_levels.surge_time = if at_peak { None } else { Some(self.water_levels.surge.peak().0) };
+                    }
+                    if let Some(time) = &mut self.water_levels.surge_time {
+                        ui.label("Time:");
+                        ui.add(egui::Slider::new(time, start..=end).suffix(" h"));
+                    }
+                });
+                let time = self.water_levels.surge_time.unwrap_or(self.water_levels.surge.peak().0);
+                let style = self.plot_style;
+                let plot = Plot::new("surge_series").height(140.0).x_axis_label("Time (h)").y_axis_label("Surge (m)");
+                style.show_plot(ui, plot, |plot_ui| {
+                    plot_ui.line(Line::new(series).color(style.series(1)).width(style.width(1.5)).name("Surge"));
+                    plot_ui.vline(VLine::new(time).color(egui::Color32::GRAY).style(LineStyle::dashed_loose()));
+                });
+            }
+            ui.horizontal(|ui| {
+                ui.label("Wave Setup:");
+                egui::ComboBox::from_id_salt("setup_method").selected_text(self.setup_method.to_string()).show_ui(ui, |ui| {
+                    for method in [SetupMethod::Stockdon, SetupMethod::Simulation] {
+                        ui.selectable_value(&mut self.setup_method, method, method.to_string());
+                    }
+                });
+                if ui.button("Apply to Still Water Level").clicked() {
+                    self.apply_still_water_components();
+                }
+            });
+
+            // The simulated setup needs the swash analysis, too costly to redo every frame
+            if self.setup_method == SetupMethod::Simulation && self.simulation_running {
+                ui.label("Pause the simulation to use the simulated setup.");
+                return;
+            }
+            let budget = match self.water_level_budget() {
+                Ok(budget) => budget,
+                Err(message) => {
+                    ui.label(format!("⚠ {}", message));
+                    return;
+                }
+            };
+            let crest = self
+                .cross_section
+                .as_ref()
+                .map(|section| self.reference_levels.to_datum(VerticalDatum::MeanSeaLevel, section.crest_elevation(), self.still_water_level));
+            ui.label(format!(
+                "Still water level {:.2} m MSL, total water level {:.2} m MSL (tide {:.2} m + surge {:.2} m + setup {:.2} m)",
+                budget.still_water_level(),
+                budget.total(),
+                budget.tide,
+                budget.surge,
+                budget.wave_setup
+            ));
+            if let Some(crest) = crest {
+                let freeboard = crest - budget.total();
+                let text = format!("Crest {:.2} m MSL, freeboard {:.2} m", crest, freeboard);
+                if freeboard < 0.0 {
+                    ui.colored_label(self.plot_style.alert(), format!("⚠ {} (crest submerged)", text));
+                } else {
+                    ui.label(text);
+                }
+            }
+
+            let style = self.plot_style;
+            let plot = Plot::new("water_level_budget")
+                .height(220.0)
+                .width(260.0)
+                .y_axis_label("Level (m MSL)")
+                .show_x(false)
+                .x_axis_formatter(|_, _| String::new())
+                .include_y(0.0)
+                .legend(Legend::default());
+            style.show_plot(ui, plot, |plot_ui| {
+                let mut stack: Vec<BarChart> = Vec::new();
+                for (i, (name, value)) in budget.contributions().into_iter().enumerate() {
+                    let chart = BarChart::new(vec![Bar::new(0.0, value).width(0.5)]).color(style.series(i)).name(name);
+                    let below: Vec<&BarChart> = stack.iter().collect();
+                    let chart = chart.stack_on(&below);
+                    stack.push(chart);
+                }
+                for chart in stack {
+                    plot_ui.bar_chart(chart);
+                }
+                plot_ui.hline(HLine::new(budget.total()).color(egui::Color32::GRAY).style(LineStyle::dashed_loose()).name("Total"));
+                if let Some(crest) = crest {
+                    plot_ui.hline(HLine::new(crest).color(style.bed()).width(style.width(1.5)).name("Crest"));
+                }
+            });
+        });
+    }
+
+    /// Import of a cross-shore survey as the bed profile
+    fn show_survey_import(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Import Survey").id_salt("survey_import").show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("File:");
+                ui.add(egui::TextEdit::singleline(&mut self.survey_path).desired_width(200.0));
+                if ui.button("Import").clicked() {
+                    self.survey_status = Some(match self.import_survey() {
+                        Ok(message) => message,
+                        Err(message) => format!("⚠ {}", message),
+                    });
+                }
+                self.info_button(ui, "survey_import", "Cross-shore survey in CSV or XYZ format, one point per line. Two columns are the distance and the bed elevation; three columns are x, y and z, with the distance measured along the surveyed line. Elevations are referred to the still water level given in the survey datum, interpolated at the chosen spacing (a spacing near the grid spacing resolves the survey on the computational grid) and smoothed by a moving average, then joined by straight segments. The channel length and the still water depth at the wavemaker are set from the survey.");
+            });
+            let options = &mut self.survey_options;
+            ui.horizontal(|ui| {
+                for (label, unit, id) in [("Distances:", &mut options.distance_unit, "survey_distance_unit"), ("Elevations:", &mut options.elevation_unit, "survey_elevation_unit")] {
+                    ui.label(label);
+                    egui::ComboBox::from_id_salt(id).selected_text(unit.to_string()).width(50.0).show_ui(ui, |ui| {
+                        for option in SurveyUnit::ALL {
+                            ui.selectable_value(unit, option, option.to_string());
+                        }
+                    });
+                }
+                ui.checkbox(&mut options.landward_first, "Landward end first");
+            });
+            ui.horizontal(|ui| {
+                ui.label("Survey Datum:");
+                egui::ComboBox::from_id_salt("survey_datum").selected_text(self.survey_datum.to_string()).show_ui(ui, |ui| {
+                    for datum in VerticalDatum::ALL.into_iter().filter(|datum| *datum != VerticalDatum::ChannelBed) {
+                        ui.selectable_value(&mut self.survey_datum, datum, datum.to_string());
+                    }
+                });
+                ui.label(format!(
+                    "Still water at {:+.2} m {}",
+                    self.reference_levels.water_level(self.survey_datum, self.still_water_level),
+                    self.survey_datum.abbreviation()
+                ));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Interpolation Spacing:");
+                ui.add(egui::DragValue::new(&mut options.spacing).range(0.0..=100.0).speed(0.01).suffix(" m"));
+                if ui.small_button("Grid").on_hover_text("Use the grid spacing").clicked() {
+                    options.spacing = self.channel_length / self.grid_resolution as f64;
+                }
+                ui.label("Smoothing Width:");
+                ui.add(egui::DragValue::new(&mut options.smoothing).range(0.0..=1000.0).speed(0.1).suffix(" m"));
+            });
+            if let Some(status) = &self.survey_status {
+                ui.label(status);
+            }
+        });
+    }
+
+    /// Read the survey file and use it as the bed profile, returning a summary
+    pub fn import_survey(&mut self) -> Result<String, String> {
+        let survey = SurveyProfile::load(std::path::Path::new(&self.survey_path))?;
+        // Still water level in the survey datum and elevation unit
+        let water_level = self.reference_levels.water_level(self.survey_datum, self.still_water_level);
+        self.survey_options.water_level = water_level / self.survey_options.elevation_unit.to_meters();
+        let (section, depth) = survey.to_cross_section(&self.survey_options)?;
+        self.channel_length = section.length();
+        self.still_water_level = depth;
+        let message = format!(
+            "Imported {} points: {:.1} m long, {:.2} m deep at the wavemaker",
+            survey.points.len(),
+            section.length(),
+            depth
+        );
+        self.cross_section = Some(section);
+        self.update_surface_elevation();
+        Ok(message)
+    }
+
+    /// Scenario selector and the parameters of the initial-value scenario
+    fn show_scenario(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Scenario:");
+            self.info_button(ui, "scenario", "Wavemaker: periodic waves generated at the left boundary. Dam Break: water released from rest behind a dam at t = 0, compared with the Ritter (dry bed) or Stoker (wet bed) analytical solution of the nonlinear shallow water equations. Gaussian Hump: a tsunami-like surface hump released from rest, splitting into two long waves travelling at c = √(gh). Initial-value scenarios use the hydrostatic numerical solver. Tsunami Inundation: an N-wave prescribed at the wavemaker as a boundary signal, running up a beach onto a coastal plain.");
+            let selected_text = match self.initial_condition {
+                None => "Wavemaker".to_string(),
+                Some(condition) => condition.to_string(),
+            };
+            let (length, depth) = (self.channel_length, self.still_water_level);
+            let is_dam_break = matches!(self.initial_condition, Some(InitialCondition::DamBreak { .. }));
+            let is_hump = matches!(self.initial_condition, Some(InitialCondition::GaussianHump { .. }));
+            egui::ComboBox::from_id_salt("scenario")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.initial_condition, None, "Wavemaker");
+                    if ui.selectable_label(is_dam_break, "Dam Break").clicked() && !is_dam_break {
+                        self.initial_condition = Some(InitialCondition::dam_break(length, depth));
+                    }
+                    if ui.selectable_label(is_hump, "Gaussian Hump").clicked() && !is_hump {
+                        self.initial_condition = Some(InitialCondition::gaussian_hump(length, depth));
+                    }
+                });
+            if ui
+                .button("Tsunami Inundation")
+                .on_hover_text("Leading depression N-wave over a long flat offshore bed, flooding a beach and a coastal plain, with the inundation and arrival times recorded")
+                .clicked()
+            {
+                self.load_tsunami_preset();
+            }
+        });
+
+        let length = self.channel_length;
+        match &mut self.initial_condition {
+            Some(InitialCondition::DamBreak {
+                position,
+                upstream_depth,
+                downstream_depth,
+            }) => {
+                *position = position.clamp(0.01 * length, 0.99 * length);
+                ui.horizontal(|ui| {
+                    ui.label("Dam Position:");
+                    ui.add(
+                        egui::Slider::new(position, 0.01 * length..=0.99 * length)
+                            .suffix(" m")
+                            .step_by(0.1),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Upstream Depth:");
+                    ui.add(
+                        egui::Slider::new(upstream_depth, 0.0..=10.0)
+                            .suffix(" m")
+                            .step_by(0.01),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Downstream Depth:");
+                    ui.add(
+                        egui::Slider::new(downstream_depth, 0.0..=10.0)
+                            .suffix(" m")
+                            .step_by(0.01),
+                    );
+                });
+            }
+            Some(InitialCondition::GaussianHump {
+                center,
+                amplitude,
+                width,
+            }) => {
+                ui.horizontal(|ui| {
+                    ui.label("Hump Center:");
+                    ui.add(
+                        egui::Slider::new(center, 0.0..=length)
+                            .suffix(" m")
+                            .step_by(0.1),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Hump Amplitude:");
+                    ui.add(
+                        egui::Slider::new(amplitude, 0.01..=5.0)
+                            .suffix(" m")
+                            .step_by(0.01),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Hump Width:");
+                    ui.add(
+                        egui::Slider::new(width, 0.1..=0.5 * length)
+                            .suffix(" m")
+                            .step_by(0.1),
+                    );
                 });
-            
-            // Check for click outside to close tooltip
-            if ui.input(|i| i.pointer.any_click()) && !area_response.response.hovered() && !button_response.hovered() {
-                self.close_tooltip(tooltip_id);
             }
+            None => {}
         }
-    }
-
-    fn classify_water_depth(h: f64, wavelength: f64) -> WaterDepthRegime {
-        let ratio = h / wavelength;
-        if ratio < 1.0 / 20.0 {
-            WaterDepthRegime::Shallow
-        } else if ratio > 0.5 {
-            WaterDepthRegime::Deep
-        } else {
-            WaterDepthRegime::Intermediate
-        }
-    }
 
-    fn calculate_wavelength_adaptive(period: f64, depth: f64, gravity: f64) -> f64 {
-        // Start with shallow water approximation
-        let wavelength = period * (gravity * depth).sqrt();
-        
-        // Check regime and refine calculation
-        let regime = Self::classify_water_depth(depth, wavelength);
-        
-        match regime {
-            WaterDepthRegime::Shallow => {
-                // Already calculated correctly
-                wavelength
-            }
-            WaterDepthRegime::Deep => {
-                // Deep water formula: L = gT²/(2π)
-                gravity * period * period / (2.0 * std::f64::consts::PI)
-            }
-            WaterDepthRegime::Intermediate => {
-                // Iterative solution of dispersion relation
-                // L = (gT²/(2π)) * tanh(2πh/L)
-                let mut l_new = gravity * period * period / (2.0 * std::f64::consts::PI); // Deep water guess
-                
-                for _ in 0..20 { // Max 20 iterations
-                    let l_old = l_new;
-                    let k = 2.0 * std::f64::consts::PI / l_old;
-                    let tanh_kh = (k * depth).tanh();
-                    l_new = (gravity * period * period / (2.0 * std::f64::consts::PI)) * tanh_kh;
-                    
-                    // Check convergence
-                    if (l_new - l_old).abs() < 1e-6 {
-                        break;
-                    }
-                }
-                
-                l_new
-            }
+        if let Some(Err(message)) = self
+            .initial_condition
+            .map(|condition| condition.validate(self.channel_length, self.still_water_level))
+        {
+            ui.label(format!("⚠ {}", message));
         }
     }
 
-    fn calculate_celerity_adaptive(period: f64, depth: f64, gravity: f64) -> f64 {
-        let wavelength = Self::calculate_wavelength_adaptive(period, depth, gravity);
-        wavelength / period
-    }
-
-    fn generate_plot_data(&self) -> (PlotPoints, PlotPoints, PlotPoints) {
-        let x_positions: Vec<f64> = (0..self.grid_resolution)
-            .map(|i| i as f64 * self.grid_spacing())
-            .collect();
-
-        // Water surface (still water level + surface elevation)
-        let water_surface: PlotPoints = x_positions
-            .iter()
-            .zip(self.surface_elevation.iter())
-            .map(|(&x, &eta)| [x, self.still_water_level + eta])
-            .collect();
-
-        // Channel bottom (flat bottom at depth 0)
-        let channel_bottom: PlotPoints = x_positions.iter().map(|&x| [x, 0.0]).collect();
-
-        // Channel sides (vertical walls at start and end)
-        let channel_walls: PlotPoints = vec![
-            [0.0, 0.0],
-            [0.0, self.still_water_level + 1.0],
-            [self.channel_length, self.still_water_level + 1.0],
-            [self.channel_length, 0.0],
-        ]
-        .into();
-
-        (water_surface, channel_bottom, channel_walls)
-    }
-
     pub fn show(&mut self, ui: &mut egui::Ui, ctx: &egui::Context, equation_renderer: &mut EquationRenderer) {
         // Use full width available
         ui.allocate_ui_with_layout(
@@ -403,7 +5718,8 @@ impl WaveChannelApp {
                     ui.add(
                         egui::Slider::new(&mut self.channel_length, 1.0..=200.0)
                             .suffix(" m")
-                            .step_by(0.1),
+                            .step_by(0.1)
+                            .clamping(egui::SliderClamping::Edits), // Keep imported surveys beyond the range
                     );
                 });
 
@@ -412,7 +5728,9 @@ impl WaveChannelApp {
                     ui.label("Grid Resolution:");
                     self.info_button(ui, "grid_resolution", "Number of computational grid points along the channel. Higher resolution gives better accuracy but increases computation time. Rule of thumb: 20-50 points per wavelength for good accuracy.");
                     ui.add(
-                        egui::Slider::new(&mut self.grid_resolution, 10..=2000).suffix(" points"),
+                        egui::Slider::new(&mut self.grid_resolution, 10..=2000)
+                            .suffix(" points")
+                            .clamping(egui::SliderClamping::Edits), // Keep automatically refined grids beyond the range
                     );
                 });
 
@@ -423,9 +5741,14 @@ impl WaveChannelApp {
                     ui.add(
                         egui::Slider::new(&mut self.still_water_level, 0.1..=5.0)
                             .suffix(" m")
-                            .step_by(0.01),
+                            .step_by(0.01)
+                            .clamping(egui::SliderClamping::Edits), // Keep imported surveys beyond the range
                     );
                 });
+                self.show_cross_section(ui);
+                self.show_datum(ui);
+                self.show_water_level(ui);
+                self.show_grid_refinement(ui);
 
                 // Update surface elevation if grid resolution changed
                 if prev_grid_resolution != self.grid_resolution {
@@ -444,63 +5767,344 @@ impl WaveChannelApp {
                 // Wave parameters section
                 ui.heading("Wave Parameters");
 
-                // Wave height control
-                ui.horizontal(|ui| {
-                    ui.label("Wave Height (H):");
-                    self.info_button(ui, "wave_height", "Vertical distance from wave trough to wave crest. Determines wave energy (E ∝ H²). For linear waves, amplitude a = H/2. Breaking occurs when H/h ≈ 0.78 (depth-limited breaking).");
-                    ui.add(
-                        egui::Slider::new(&mut self.wave_height, 0.01..=5.0)
-                            .suffix(" m")
-                            .step_by(0.01),
-                    );
-                });
+                // Scenario: wavemaker or initial-value problem
+                let prev_initial_condition = self.initial_condition;
+                self.show_scenario(ui);
+                if prev_initial_condition != self.initial_condition {
+                    self.update_surface_elevation();
+                }
 
-                // Wave period control
-                ui.horizontal(|ui| {
-                    ui.label("Wave Period (T):");
-                    self.info_button(ui, "wave_period", "Time interval between successive wave crests passing a fixed point. Related to frequency by f = 1/T. Determines wavelength through dispersion relation. Typical ocean waves: T = 4-20s, wind waves: T = 1-8s.");
-                    ui.add(
-                        egui::Slider::new(&mut self.wave_period, 1.0..=20.0)
-                            .suffix(" s")
-                            .step_by(0.1),
-                    );
-                });
+                if self.initial_condition.is_none() {
+                    // Wave generation method
+                    ui.horizontal(|ui| {
+                        ui.label("Wave Generation:");
+                        self.info_button(ui, "wave_generation", "Choose how the wavemaker is specified. Either prescribe the target wave height and period directly, or emulate a laboratory piston or flap paddle by its stroke and frequency; the generated wave height then follows from Biésel wavemaker theory.");
+                        let selected_text = match self.paddle_type {
+                            None => "Wave Height & Period".to_string(),
+                            Some(paddle) => format!("{} Paddle", paddle),
+                        };
+                        egui::ComboBox::from_id_salt("wave_generation")
+                            .selected_text(selected_text)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.paddle_type, None, "Wave Height & Period");
+                                ui.selectable_value(&mut self.paddle_type, Some(PaddleType::Piston), "Piston Paddle");
+                                ui.selectable_value(&mut self.paddle_type, Some(PaddleType::Flap), "Flap Paddle");
+                            });
+                    });
+
+                    if self.paddle_type.is_some() {
+                        // Paddle stroke control
+                        ui.horizontal(|ui| {
+                            ui.label("Paddle Stroke (S):");
+                            self.info_button(ui, "paddle_stroke", "Peak-to-peak horizontal paddle displacement at the still water level. For a flap paddle hinged at the bed, the displacement decreases linearly to zero at the hinge.");
+                            ui.add(
+                                egui::Slider::new(&mut self.paddle_stroke, 0.01..=3.0)
+                                    .suffix(" m")
+                                    .step_by(0.01),
+                            );
+                        });
+
+                        // Paddle frequency control
+                        ui.horizontal(|ui| {
+                            ui.label("Paddle Frequency (f):");
+                            self.info_button(ui, "paddle_frequency", "Number of paddle oscillations per second. The generated wave period is T = 1/f.");
+                            let mut frequency = 1.0 / self.wave_period;
+                            let response = ui.add(
+                                egui::Slider::new(&mut frequency, 0.05..=1.0)
+                                    .suffix(" Hz")
+                                    .step_by(0.001),
+                            );
+                            if response.changed() {
+                                self.wave_period = 1.0 / frequency;
+                            }
+                        });
+
+                        self.sync_paddle_wave_height();
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Generated Wave Height (H): {:.3} m", self.wave_height));
+                            self.info_button(ui, "generated_wave_height", "Progressive wave height produced by the paddle, from Biésel transfer functions. Piston: H/S = 2(cosh 2kh − 1)/(sinh 2kh + 2kh). Flap: H/S = 4 (sinh kh/kh)(kh sinh kh − cosh kh + 1)/(sinh 2kh + 2kh). Shallow water: piston H/S ≈ kh, flap H/S ≈ kh/2. Deep water: both approach 2.");
+                        });
+                    } else {
+                        // Wave height control
+                        ui.horizontal(|ui| {
+                            ui.label("Wave Height (H):");
+                            self.info_button(ui, "wave_height", "Vertical distance from wave trough to wave crest. Determines wave energy (E ∝ H²). For linear waves, amplitude a = H/2. Breaking occurs when H/h ≈ 0.78 (depth-limited breaking).");
+                            ui.add(
+                                egui::Slider::new(&mut self.wave_height, 0.01..=5.0)
+                                    .suffix(" m")
+                                    .step_by(0.01),
+                            );
+                        });
+
+                        // Wave period control
+                        ui.horizontal(|ui| {
+                            ui.label("Wave Period (T):");
+                            self.info_button(ui, "wave_period", "Time interval between successive wave crests passing a fixed point. Related to frequency by f = 1/T. Determines wavelength through dispersion relation. Typical ocean waves: T = 4-20s, wind waves: T = 1-8s.");
+                            ui.add(
+                                egui::Slider::new(&mut self.wave_period, 1.0..=20.0)
+                                    .suffix(" s")
+                                    .step_by(0.1),
+                            );
+                        });
+                        if let Err(CoastalError::Breaking { criterion, limit, .. }) = WaveParameters::new(Length::meters(self.wave_height), Time::seconds(self.wave_period), Length::meters(self.still_water_level)) {
+                            ui.colored_label(egui::Color32::from_rgb(200, 80, 0), format!("⚠ Breaking at the wavemaker: H exceeds the {} limit of {:.3} m", criterion, limit));
+                        }
+                    }
+
+                    // Theory of the generated regular waves
+                    let recommended = self.recommended_wave_theory();
+                    ui.horizontal(|ui| {
+                        ui.label("Wave Theory:");
+                        self.info_button(ui, "wave_theory", "Theory of the regular waves generated at the inlet of the exported OpenFOAM case: linear (first-order Stokes), second- or fifth-order Stokes, cnoidal or stream function waves. By default the theory applicable to the wave height, period and depth is taken from the Le Méhauté diagram, shown with the wave properties. The numerical model of the channel and the analytical model generate stream function waves at the wavemaker when that theory applies, and linear waves for the other theories, letting the numerical model develop their nonlinearity as they propagate.");
+                        let automatic = match &recommended {
+                            Ok(theory) => format!("Recommended: {}", theory),
+                            Err(_) => "Recommended".to_string(),
+                        };
+                        egui::ComboBox::from_id_salt("wave_theory")
+                            .selected_text(self.wave_theory.map_or(automatic.clone(), |theory| theory.to_string()))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.wave_theory, None, automatic);
+                                for theory in WaveTheory::ALL {
+                                    ui.selectable_value(&mut self.wave_theory, Some(theory), theory.to_string());
+                                }
+                            });
+                    });
+                    if let (Some(selected), Ok(recommended)) = (self.wave_theory, recommended) && selected != recommended {
+                        ui.colored_label(egui::Color32::from_rgb(200, 80, 0), format!("⚠ {} theory is recommended for these waves", recommended));
+                    }
+
+                    // Number of waves control
+                    ui.horizontal(|ui| {
+                        ui.label("Number of Waves:");
+                        self.info_button(ui, "number_of_waves", "Total number of wave cycles to simulate. Determines simulation duration: t_sim = N × T. More waves show steady-state behavior and wave interactions. Typical studies use 10-50 waves for analysis.");
+                        ui.add(egui::Slider::new(&mut self.number_of_waves, 1..=1000).suffix(" waves"));
+                    });
+
+                    self.show_forcing_schedule(ui);
+                    self.show_storm_hydrograph(ui);
+                    self.show_boundary_spectrum(ui);
+                    self.show_boundary_signal(ui);
+
+                    // Additional internal sources
+                    let prev_sources = self.additional_sources.clone();
+                    ui.horizontal(|ui| {
+                        ui.label("Additional Sources:");
+                        self.info_button(ui, "additional_sources", "Internal wave sources placed inside the channel, each with its own height, period and phase. Internal sources radiate waves in both directions. Wave trains from different sources superpose, showing constructive and destructive interference and beating between close periods.");
+                        if ui.small_button("➕ Add Source").clicked() {
+                            self.additional_sources.push(SourceSettings {
+                                position: self.channel_length / 2.0,
+                                wave_height: self.wave_height,
+                                wave_period: self.wave_period,
+                                phase: 0.0,
+                            });
+                        }
+                    });
+
+                    let channel_length = self.channel_length;
+                    let mut removed_source = None;
+                    for (i, source) in self.additional_sources.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            // The wavemaker is source 1
+                            ui.label(format!("Source {}:", i + 2));
+                            ui.add(
+                                egui::Slider::new(&mut source.position, 0.0..=channel_length)
+                                    .prefix("x = ")
+                                    .suffix(" m")
+                                    .step_by(0.1),
+                            );
+                            ui.add(
+                                egui::Slider::new(&mut source.wave_height, 0.01..=5.0)
+                                    .prefix("H = ")
+                                    .suffix(" m")
+                                    .step_by(0.01),
+                            );
+                            ui.add(
+                                egui::Slider::new(&mut source.wave_period, 1.0..=20.0)
+                                    .prefix("T = ")
+                                    .suffix(" s")
+                                    .step_by(0.1),
+                            );
+                            ui.add(
+                                egui::Slider::new(&mut source.phase, -180.0..=180.0)
+                                    .prefix("φ = ")
+                                    .suffix("°")
+                                    .step_by(1.0),
+                            );
+                            if ui.small_button("✖").clicked() {
+                                removed_source = Some(i);
+                            }
+                        });
+                    }
+                    if let Some(i) = removed_source {
+                        self.additional_sources.remove(i);
+                    }
+                    if prev_sources != self.additional_sources {
+                        self.update_surface_elevation();
+                    }
+                }
+
+                ui.separator();
+
+                // Solver settings section
+                ui.heading("Solver Settings");
+
+                // Propagation model (initial-value scenarios always use the solver)
+                if self.initial_condition.is_none() {
+                    ui.horizontal(|ui| {
+                        ui.label("Propagation Model:");
+                        self.info_button(ui, "propagation_model", "Analytical: superposition of linear waves radiated by each source, no reflections. Numerical: staggered-grid shallow water solver (η at cell centers, u at faces) driven by the wavemaker, so waves interact with the right boundary. Frequency dispersion in the numerical solver follows the selected accuracy mode.");
+                        egui::ComboBox::from_id_salt("propagation_model")
+                            .selected_text(self.propagation_model.to_string())
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.propagation_model, PropagationModel::Analytical, "Analytical");
+                                ui.selectable_value(&mut self.propagation_model, PropagationModel::Numerical, "Numerical");
+                            });
+                    });
+                }
 
-                // Number of waves control
+                // Accuracy mode (vertical layers)
                 ui.horizontal(|ui| {
-                    ui.label("Number of Waves:");
-                    self.info_button(ui, "number_of_waves", "Total number of wave cycles to simulate. Determines simulation duration: t_sim = N × T. More waves show steady-state behavior and wave interactions. Typical studies use 10-50 waves for analysis.");
-                    ui.add(egui::Slider::new(&mut self.number_of_waves, 1..=1000).suffix(" waves"));
+                    ui.label("Accuracy Mode:");
+                    self.info_button(ui, "accuracy_mode", "Number of equidistant vertical layers in the non-hydrostatic solver. One layer: ω² = gk·kd/(1 + (kd)²/4), accurate for kd < 0.5. Two layers: ω² = gk·(kd + (kd)³/16)/(1 + 3(kd)²/8 + (kd)⁴/256), accurate up to kd ≈ 7 (deep water) at 1% phase velocity error, at roughly twice the cost.");
+                    egui::ComboBox::from_id_salt("accuracy_mode")
+                        .selected_text(self.dispersion_mode.to_string())
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.dispersion_mode, DispersionMode::OneLayer, "One Layer");
+                            ui.selectable_value(&mut self.dispersion_mode, DispersionMode::TwoLayer, "Two Layers");
+                        });
                 });
 
+                if self.is_numerical() {
+                    // Right boundary condition
+                    ui.horizontal(|ui| {
+                        ui.label("Right Boundary:");
+                        self.info_button(ui, "right_boundary", "Condition at the downstream end of the channel. Solid Wall: u = 0, full reflection (closed flume). Sponge Layer: η and u are relaxed to rest over a damping zone, increasing quadratically towards the wall. Radiation (Sommerfeld): outgoing long waves leave the domain with u = √(g/h) η (open coast). Periodic: the right end connects to the left end; the wavemaker boundary is not used, so waves come from internal sources.");
+                        let channel_length = self.channel_length;
+                        egui::ComboBox::from_id_salt("right_boundary")
+                            .selected_text(self.right_boundary.to_string())
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.right_boundary, RightBoundary::Wall, "Solid Wall");
+                                let is_sponge = matches!(self.right_boundary, RightBoundary::Sponge { .. });
+                                if ui.selectable_label(is_sponge, "Sponge Layer").clicked() && !is_sponge {
+                                    self.right_boundary = RightBoundary::Sponge {
+                                        width: 0.2 * channel_length,
+                                        strength: 5.0,
+                                    };
+                                }
+                                ui.selectable_value(&mut self.right_boundary, RightBoundary::Radiation, "Radiation (Sommerfeld)");
+                                ui.selectable_value(&mut self.right_boundary, RightBoundary::Periodic, "Periodic");
+                            });
+                    });
+
+                    if let RightBoundary::Sponge { width, strength } = &mut self.right_boundary {
+                        // Keep the sponge inside the channel when the channel is shortened
+                        *width = width.min(0.9 * self.channel_length);
+                        let channel_length = self.channel_length;
+                        ui.horizontal(|ui| {
+                            ui.label("Sponge Width:");
+                            ui.add(
+                                egui::Slider::new(width, 0.1..=0.9 * channel_length)
+                                    .suffix(" m")
+                                    .step_by(0.1),
+                            );
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Sponge Strength:");
+                            ui.add(
+                                egui::Slider::new(strength, 0.0..=50.0)
+                                    .suffix(" 1/s")
+                                    .step_by(0.1),
+                            );
+                        });
+                    }
+
+                    // Active absorption at the wavemaker
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.active_absorption, "Active Absorption");
+                        self.info_button(ui, "active_absorption", "Correct the wavemaker velocity for waves travelling back towards the paddle: u = u_target − (c/h)(η_measured − η_target). Prevents re-reflection at the wavemaker in closed flumes.");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.mass_compensation, "Mass Compensation");
+                        self.info_button(ui, "mass_compensation", "The wavemaker velocity u follows the linear target, but the water enters over the total depth d + η: more is pushed in under the crests than is drawn out under the troughs, a net mass flux ⟨ηu⟩ equal to the Stokes drift of the waves. In a closed flume it has nowhere to go and the mean level rises steadily over a long run. The compensation scales the velocity by d/(d + η), so the discharge d·u_target carries no net mass, as the return flow under the waves of a real flume does. The rise of the mean level is plotted in the results as a diagnostic.");
+                    });
+                    self.show_ambient_current(ui);
+                    self.show_advection_scheme(ui);
+                    self.show_time_integration(ui);
+                    self.show_solver_precision(ui);
+
+                    self.show_vegetation(ui);
+                }
+
+                ui.separator();
+
+                // Wave gauges section
+                ui.heading("Wave Gauges");
+                self.show_gauges(ui);
+                self.show_pressure_sensors(ui);
+                self.show_output_sampling(ui);
+
+                ui.separator();
+
+                // Alerts section
+                ui.heading("Alerts");
+                self.show_alerts(ui);
+
+                ui.separator();
+
+                // Run metadata section
+                ui.heading("Run Metadata");
+                self.show_run_metadata(ui);
+
+                ui.separator();
+
+                // CFD export section
+                ui.heading("CFD Export");
+                self.show_openfoam_export(ui);
+
                 ui.separator();
 
                 // Computed values section
-                ui.heading("Computed Values");
+                let heading = ui.heading("Computed Values");
+                heading.context_menu(|ui| {
+                    if ui.button("📋 Copy Computed Values").clicked() {
+                        ui.ctx().copy_text(self.computed_values.to_text());
+                        ui.close_menu();
+                    }
+                });
+                let mut values = CopyableValues::default();
 
                 // Grid spacing
                 ui.horizontal(|ui| {
-                    ui.label(format!("Grid Spacing (Δx): {:.3} m", self.grid_spacing()));
+                    let units = self.unit_system;
+                    values.label(ui, format!("Grid Spacing (Δx): {:.3} {}", units.length(Length::meters(self.grid_spacing())), units.length_unit()));
                     self.info_button(ui, "grid_spacing", "Distance between computational grid points. Formula: Δx = L/(N-1) where L is channel length and N is grid resolution. Smaller spacing improves accuracy but increases computational cost.");
                 });
+                if let Some(grid) = self.computational_grid().ok().filter(|grid| !grid.is_uniform()) {
+                    let units = self.unit_system;
+                    values.label(ui, format!(
+                        "Stretched Grid: Δx from {:.3} to {:.3} {}",
+                        units.length(Length::meters(grid.min_dx())),
+                        units.length(Length::meters(grid.max_dx())),
+                        units.length_unit()
+                    ));
+                }
 
-                // Wave properties using adaptive calculation
+                // Wave properties from the exact linear dispersion relation
                 let wave_frequency = 1.0 / self.wave_period;
                 let angular_frequency = 2.0 * std::f64::consts::PI * wave_frequency;
-                let gravity = 9.81;
-                let wavelength = Self::calculate_wavelength_adaptive(self.wave_period, self.still_water_level, gravity);
-                let celerity = Self::calculate_celerity_adaptive(self.wave_period, self.still_water_level, gravity);
+                let wavelength = Self::linear_wavelength(self.wave_period, self.still_water_level);
+                let celerity = wavelength / self.wave_period;
                 let water_regime = Self::classify_water_depth(self.still_water_level, wavelength);
 
                 ui.horizontal(|ui| {
-                    ui.label(format!("Wave Frequency (f): {:.3} Hz", wave_frequency));
+                    values.label(ui, format!("Wave Frequency (f): {:.3} Hz", wave_frequency));
                     self.equation_info_button(ui, ctx, equation_renderer, "wave_frequency_tooltip", "wave_frequency", (
                         "Number of wave cycles per second:", 
                         "where T is wave period. Fundamental parameter in wave kinematics and energy calculations. Units: Hertz (Hz) or cycles per second."
                     ));
                 });
                 ui.horizontal(|ui| {
-                    ui.label(format!(
+                    values.label(ui, format!(
                         "Angular Frequency (ω): {:.3} rad/s",
                         angular_frequency
                     ));
@@ -516,14 +6120,15 @@ impl WaveChannelApp {
                         WaterDepthRegime::Intermediate => "Intermediate Water", 
                         WaterDepthRegime::Deep => "Deep Water",
                     };
-                    ui.label(format!("Water Depth Regime: {}", regime_text));
+                    values.label(ui, format!("Water Depth Regime: {}", regime_text));
                     self.info_button(ui, "water_depth_regime", "Classification based on h/L ratio. Shallow: h/L < 1/20 (non-dispersive), Deep: h/L > 1/2 (fully dispersive), Intermediate: 1/20 ≤ h/L ≤ 1/2 (transitional). Determines which wave theory applies.");
                 });
 
                 ui.horizontal(|ui| {
-                    ui.label(format!(
-                        "Wave Celerity (c): {:.3} m/s",
-                        celerity
+                    values.label(ui, format!(
+                        "Wave Celerity (c): {:.3} {}",
+                        self.unit_system.speed(Velocity::meters_per_second(celerity)),
+                        self.unit_system.speed_unit()
                     ));
                     let (equation_id, text_before, text_after) = match water_regime {
                         WaterDepthRegime::Shallow => ("shallow_water_celerity", "Shallow water celerity:", "Independent of wave period. Applies when h/L < 1/20."),
@@ -533,9 +6138,10 @@ impl WaveChannelApp {
                     self.equation_info_button(ui, ctx, equation_renderer, "wave_celerity_tooltip", equation_id, (text_before, text_after));
                 });
                 ui.horizontal(|ui| {
-                    ui.label(format!(
-                        "Wavelength (L): {:.3} m",
-                        wavelength
+                    values.label(ui, format!(
+                        "Wavelength (L): {:.3} {}",
+                        self.unit_system.length(Length::meters(wavelength)),
+                        self.unit_system.length_unit()
                     ));
                     let (equation_id, text_before, text_after) = match water_regime {
                         WaterDepthRegime::Shallow => ("shallow_water_wavelength", "Shallow water wavelength:", "Independent of wave height, depends only on period and depth."),
@@ -547,12 +6153,98 @@ impl WaveChannelApp {
                 
                 // Wave parameters for future SWASH integration
                 ui.horizontal(|ui| {
-                    ui.label(format!("Wave Number (k): {:.3} rad/m", 2.0 * std::f64::consts::PI / wavelength));
+                    values.label(ui, format!("Wave Number (k): {:.3} rad/m", 2.0 * std::f64::consts::PI / wavelength));
                     self.info_button(ui, "wave_number", "Wave number k = 2π/L, fundamental parameter in wave equations. Will be computed using SWASH dispersion relation for enhanced accuracy.");
                 });
 
+                // Dispersion error of the selected accuracy mode
+                let kd = 2.0 * std::f64::consts::PI / wavelength * self.still_water_level;
+                let dispersion_error = DispersionSolver::new().with_mode(self.dispersion_mode).dispersion_error(kd);
+                ui.horizontal(|ui| {
+                    values.label(ui, format!("Dispersion Error ({}): {:+.2} %", self.dispersion_mode, dispersion_error * 100.0));
+                    self.info_button(ui, "dispersion_error", "Relative phase velocity error of the selected accuracy mode against linear theory at the current kd: ε = √(R(kd)/tanh(kd)) − 1, where ω² = gk·R(kd) is the layer-based dispersion relation. Negative values mean the simulated waves travel too slowly. Keep |ε| below about 1%; switch to two layers for deep water.");
+                });
+                ui.horizontal(|ui| {
+                    match DispersionSolver::new().with_mode(self.dispersion_mode).wavelength(Time::seconds(self.wave_period), Length::meters(self.still_water_level)) {
+                        Ok(swash_wavelength) => values.label(ui, format!(
+                            "Wavelength, SWASH {} vs linear: {:.3} vs {:.3} {} ({:+.2} %)",
+                            self.dispersion_mode,
+                            self.unit_system.length(swash_wavelength),
+                            self.unit_system.length(Length::meters(wavelength)),
+                            self.unit_system.length_unit(),
+                            (swash_wavelength.in_meters() / wavelength - 1.0) * 100.0
+                        )),
+                        Err(error) => values.label(ui, format!("Wavelength, SWASH {}: no solution ({})", self.dispersion_mode, error)),
+                    };
+                    self.info_button(ui, "dispersion_comparison", "Wavelengths of the wavemaker period from the two dispersion relations. The exact linear (Airy) relation ω² = gk tanh(kd) is solved by Newton-Raphson from the explicit approximation of Guo (2002), kd ≈ x²(1 − exp(−x^(5/2)))^(−2/5) with x = ω√(d/g), which is within 0.75% at any depth, so it converges in a few iterations. The SWASH relation replaces tanh(kd) by the rational function R(kd) of the selected number of layers, which the numerical solver reproduces. The wave properties above use the linear relation.");
+                });
+
+                // Waves of the wavemaker on the ambient current
+                if let Some(current) = self.ambient_current {
+                    let units = self.unit_system;
+                    match current.doppler_shift(&DispersionSolver::linear(), Time::seconds(self.wave_period), Length::meters(self.still_water_level)) {
+                        Ok(shift) => {
+                            ui.horizontal(|ui| {
+                                values.label(ui, format!(
+                                    "Wavelength on Current (L_U): {:.3} {} ({:+.2} %)",
+                                    units.length(shift.wavelength),
+                                    units.length_unit(),
+                                    shift.wavelength_change() * 100.0
+                                ));
+                                self.info_button(ui, "current_wavelength", "Wavelength of the wavemaker period on the ambient current, from the Doppler-shifted linear relation (ω − kU)² = gk tanh(kd), with U the current felt by the waves (the depth average for a uniform current, weighted by the orbital motion for a sheared one). The change is relative to the still-water wavelength above: a following current lengthens the waves, an opposing one shortens them. The wavelength is the same seen from the ground or moving with the water.");
+                            });
+                            ui.horizontal(|ui| {
+                                values.label(ui, format!("Relative Period (T_r): {:.3} s (absolute T = {:.3} s)", shift.relative_period.in_seconds(), shift.absolute_period.in_seconds()));
+                                self.info_button(ui, "relative_period", "Period counted by an observer drifting with the current, T_r = 2π/(ω − kU), against the absolute period T = 2π/ω set by the wavemaker at a fixed point. Waves on an opposing current pass the drifting observer more often, T_r < T.");
+                            });
+                            ui.horizontal(|ui| {
+                                values.label(ui, format!(
+                                    "Celerity on Current: {:.3} {} absolute, {:.3} {} relative",
+                                    units.speed(shift.absolute_celerity),
+                                    units.speed_unit(),
+                                    units.speed(shift.relative_celerity),
+                                    units.speed_unit()
+                                ));
+                                self.info_button(ui, "current_celerity", "Wave celerity at a fixed point, c = L/T, and relative to the water, c_r = L/T_r, differing by the current felt by the waves: c = c_r + U.");
+                            });
+                        }
+                        Err(error) => {
+                            values.label(ui, format!("Wavelength on Current: no solution ({})", error));
+                        }
+                    }
+                }
+
+                // Wave attenuation through the vegetation patch
+                if let (Some(field), None) = (self.vegetation, self.initial_condition) {
+                    let k = 2.0 * std::f64::consts::PI / wavelength;
+                    let attenuation = field.damping_coefficient(self.wave_height, k, self.still_water_level);
+                    let transmitted = field.wave_height_after(self.wave_height, k, self.still_water_level, field.length());
+                    ui.horizontal(|ui| {
+                        values.label(ui, format!("Vegetation Attenuation: {:.2} %/m", attenuation * 100.0));
+                        self.info_button(ui, "vegetation_attenuation", "Relative wave height decay per meter at the entrance of the vegetation patch, from Dalrymple et al. (1984): H(x) = H₀/(1 + β̃x), β̃ = (4/9π) C_D b_v N H₀ k (sinh³(kαh) + 3 sinh(kαh))/((sinh 2kh + 2kh) sinh kh), where α = h_v/h is the submerged stem fraction. Damping grows with wave height, stem frontal area per unit volume b_v N and canopy height.");
+                    });
+                    ui.horizontal(|ui| {
+                        values.label(ui, format!("Transmitted Wave Height: {:.3} m", transmitted));
+                        self.info_button(ui, "transmitted_wave_height", "Wave height leaving the vegetation patch, H = H₀/(1 + β̃ℓ) with ℓ the patch length, ignoring reflection at the patch edges.");
+                    });
+                }
+
+                // Evanescent modes near an emulated paddle
+                if let Some(wavemaker) = self.wavemaker() {
+                    ui.horizontal(|ui| {
+                        values.label(ui, format!("Paddle Spin-up Distance: {:.2} m", wavemaker.spin_up_distance(0.01)));
+                        self.info_button(ui, "spin_up_distance", "Distance from the paddle over which the evanescent (non-propagating) modes decay to 1% of their amplitude: x = ln(100)/κ₁, where κ₁ is the first root of ω² = −gκ tan(κh). Usually a few water depths; place gauges beyond this distance to measure the progressive wave only.");
+                    });
+                }
+
+                self.show_custom_formulas(ui, &mut values);
+                self.computed_values = values;
+
                 ui.separator();
 
+                // Switching back to a case that was run before shows its results at once
+                self.restore_cached_result();
+
                 // Wave channel visualization with controls
                 ui.horizontal(|ui| {
                     ui.heading("Channel Visualization");
@@ -583,22 +6275,85 @@ impl WaveChannelApp {
                     // Time and progress
                     let progress = self.simulation_progress();
                     ui.label(format!("Time: {:.1}s ({:.0}%)", self.simulation_time, progress * 100.0));
+
+                    if self.restored_from_cache {
+                        ui.label("⚡ Restored from cache");
+                    }
+                    if let Some(cache) = &self.result_cache {
+                        if ui.small_button("🗑 Clear Cache").clicked()
+                            && let Err(message) = cache.clear()
+                        {
+                            eprintln!("Failed to clear result cache: {}", message);
+                        }
+                        self.info_button(ui, "result_cache", "Completed runs are stored on disk, keyed by the full configuration (channel, waves, sources, scenario, gauges and solver settings). Switching back to a configuration that was run before restores its final surface and gauge records instantly instead of recomputing every time step. Reset to run the case again.");
+                    }
                 });
                 
+                if !self.simulation_running && self.simulation_time == 0.0 {
+                    self.show_run_preview(ui);
+                } else if !self.restored_from_cache {
+                    self.show_live_edits(ui);
+                }
+
                 // Advance simulation if running
                 if self.simulation_running {
-                    let dt = 0.05; // 50ms time step for smooth animation
-                    self.advance_simulation(dt);
-                    ui.ctx().request_repaint(); // Continuous repainting for animation
+                    self.advance_simulation(ANIMATION_STEP);
+                    self.repaint_policy.request_frame(ui.ctx());
                 }
 
-                let (water_surface, channel_bottom, _channel_walls) = self.generate_plot_data();
+                self.fill_plot_data();
+                let fill_water = self.fill_water;
+                let analytical_solution = self.analytical_plot_data();
+                let linear_overlay: Vec<Vec<[f64; 2]>> = if self.show_linear_overlay && self.is_numerical() {
+                    self.overlay_segments(self.linear_theory_surface())
+                } else {
+                    Vec::new()
+                };
+                let stream_function_overlay: Vec<Vec<[f64; 2]>> = if self.show_stream_function_overlay && self.is_numerical() {
+                    self.overlay_segments(self.stream_function_surface())
+                } else {
+                    Vec::new()
+                };
+                let vegetation_band = self.vegetation_plot_data();
+                let segment_labels = self.segment_labels();
+                let grid_lines: Vec<f64> = match self.computational_grid() {
+                    Ok(grid) if self.show_grid_lines => grid.x_faces().to_vec(),
+                    _ => Vec::new(),
+                };
+                let datum_offset = self.level_in_datum(0.0);
+                let datum_line = (self.datum != VerticalDatum::ChannelBed).then_some(-datum_offset);
+                let datum_name = self.datum.abbreviation();
+                let elevation_label = match self.datum {
+                    VerticalDatum::ChannelBed => "Elevation (m)".to_string(),
+                    datum => format!("Elevation (m {})", datum.abbreviation()),
+                };
+                let tracks = if self.track_crests { self.tracked_waves() } else { Vec::new() };
+                let bores = if self.detect_bores { self.detected_bores() } else { Vec::new() };
+                let shown_time = self.displayed_time();
+                let crest_markers: Vec<(bool, [f64; 2])> = tracks
+                    .iter()
+                    .filter_map(|track| {
+                        let crest = track.at(shown_time)?;
+                        let broken = track.breaking_point(self.still_water_level, BREAKER_INDEX).is_some_and(|point| point.time <= crest.time);
+                        Some((broken, [crest.position, self.still_water_level + crest.elevation]))
+                    })
+                    .collect();
+                let bore_markers: Vec<[f64; 2]> = bores
+                    .iter()
+                    .filter_map(|track| {
+                        let front = track.at(shown_time)?;
+                        Some([front.position, self.bed_elevation(front.position) + front.depth + 0.5 * front.height])
+                    })
+                    .collect();
+                let gauge_positions: Vec<f64> = self.gauges.iter().map(WaveGauge::position).collect();
+                let sensor_locations: Vec<[f64; 2]> = self.pressure_sensors.iter().map(|sensor| [sensor.position(), sensor.elevation()]).collect();
 
                 // Get available width and use most of it for the plot
                 let available_width = ui.available_width();
                 let plot_width = (available_width - 40.0).max(400.0); // Leave some margin, minimum 400px
 
-                Plot::new("wave_channel")
+                let style = self.plot_style;
+                let plot = Plot::new("wave_channel")
                     .height(350.0)
                     .width(plot_width)
                     .view_aspect(2.0)
@@ -609,30 +6364,397 @@ impl WaveChannelApp {
                     .allow_boxed_zoom(true)
                     .set_margin_fraction([0.0, 0.2].into())
                     .x_axis_label("Distance (m)")
-                    .y_axis_label("Elevation (m)")
+                    .y_axis_label(elevation_label)
+                    .y_axis_formatter(move |mark, _range| {
+                        let decimals = (-mark.step_size.log10()).ceil().max(0.0) as usize;
+                        format!("{:.*}", decimals, mark.value + datum_offset)
+                    })
                     .include_x(0)
                     .include_x(self.channel_length)
                     .include_y(0)
                     .include_y(self.still_water_level)
-                    .auto_bounds([false, true])
-                    .show(ui, |plot_ui| {
-                        // Channel bottom (seabed)
+                    .auto_bounds([false, true]);
+                let clicked = style.show_plot(ui, plot, |plot_ui| {
+                    // Sky above the surface, water down to the bed and the ground masking the water below it
+                    let bounds = plot_ui.plot_bounds();
+                    if fill_water && bounds.is_valid_y() {
+                        let (bottom, top) = (bounds.min()[1] as f32, bounds.max()[1] as f32);
+                        for (points, color, reference, opacity) in [
+                            (&self.series.surface, style.sky(), top, style.fill_opacity),
+                            (&self.series.surface, style.water(), bottom, style.fill_opacity),
+                            (&self.series.bed, style.bed(), bottom, 1.0),
+                        ] {
+                            plot_ui.line(Line::new(points.as_slice()).color(color).width(0.0).fill(reference).fill_alpha(opacity));
+                        }
+                    }
+
+                    // Vegetation patch on the bed
+                    if let Some(vegetation_band) = vegetation_band {
+                        plot_ui.polygon(
+                            Polygon::new(vegetation_band)
+                                .fill_color(style.fill(style.vegetation()))
+                                .stroke(egui::Stroke::new(style.width(1.0), style.vegetation()))
+                                .name("Vegetation"),
+                        );
+                    }
+
+                    // Channel bottom (seabed)
+                    plot_ui.line(
+                        Line::new(self.series.bed.as_slice())
+                            .color(style.bed())
+                            .width(style.width(3.0))
+                            .name("Channel Bottom"),
+                    );
+                    // Faces of the numerical grid
+                    for face in grid_lines {
+                        plot_ui.vline(
+                            VLine::new(face)
+                                .color(egui::Color32::from_rgba_unmultiplied(128, 128, 128, 60))
+                                .width(0.5)
+                                .name("Grid"),
+                        );
+                    }
+
+                    // Zero of the selected datum
+                    if let Some(level) = datum_line {
+                        plot_ui.hline(
+                            HLine::new(level)
+                                .color(egui::Color32::from_gray(150))
+                                .style(LineStyle::dotted_loose())
+                                .name(format!("0 m {}", datum_name)),
+                        );
+                    }
+
+                    for (position, label) in segment_labels {
+                        plot_ui.text(
+                            Text::new(position, label)
+                                .color(style.bed())
+                                .anchor(egui::Align2::CENTER_TOP),
+                        );
+                    }
+
+                    // Water surface
+                    plot_ui.line(
+                        Line::new(self.series.surface.as_slice())
+                            .color(style.water())
+                            .width(style.width(2.0))
+                            .name("Water Surface"),
+                    );
+                    for stretch in &self.series.foam {
+                        plot_ui.line(Line::new(&self.series.surface[stretch.clone()]).color(style.foam()).width(style.width(4.0)).name("Foam"));
+                    }
+                    for (points, shape, name) in [(&self.series.crests, MarkerShape::Up, "Crests"), (&self.series.troughs, MarkerShape::Down, "Troughs")] {
+                        if !points.is_empty() {
+                            plot_ui.points(Points::new(points.as_slice()).shape(shape).radius(4.0).color(style.water()).name(name));
+                        }
+                    }
+                    if let Some(reading) = self.series.wavelength
+                        && bounds.is_valid()
+                    {
+                        let corner = PlotPoint::new(bounds.max()[0], bounds.max()[1]);
+                        plot_ui.text(Text::new(corner, reading.to_text()).color(style.water()).anchor(egui::Align2::RIGHT_TOP));
+                    }
+
+                    // Wave gauge positions
+                    for gauge in &gauge_positions {
+                        plot_ui.vline(
+                            VLine::new(*gauge)
+                                .color(egui::Color32::from_gray(120))
+                                .style(LineStyle::dashed_dense())
+                                .name("Wave Gauges"),
+                        );
+                    }
+
+                    if !sensor_locations.is_empty() {
+                        plot_ui.points(
+                            Points::new(sensor_locations)
+                                .shape(MarkerShape::Diamond)
+                                .radius(4.0)
+                                .color(style.sensor())
+                                .name("Pressure Sensors"),
+                        );
+                    }
+
+                    // Analytical solution of the initial-value scenario
+                    if let Some(analytical_solution) = analytical_solution {
                         plot_ui.line(
-                            Line::new(channel_bottom)
-                                .color(egui::Color32::from_rgb(139, 69, 19)) // Brown for seabed
-                                .width(3.0)
-                                .name("Channel Bottom"),
+                            Line::new(analytical_solution)
+                                .color(style.alert())
+                                .width(style.width(1.5))
+                                .style(LineStyle::dashed_loose())
+                                .name("Analytical Solution"),
+                        );
+                    }
+
+                    // Crests of the tracked waves, red once broken
+                    for (broken, name, color) in [
+                        (false, "Tracked Crests", style.marker()),
+                        (true, "Breaking Crests", style.alert()),
+                    ] {
+                        let points: Vec<[f64; 2]> = crest_markers.iter().filter(|(b, _)| *b == broken).map(|(_, point)| *point).collect();
+                        if !points.is_empty() {
+                            plot_ui.points(Points::new(points).radius(4.0).color(color).name(name));
+                        }
+                    }
+                    if !bore_markers.is_empty() {
+                        plot_ui.points(
+                            Points::new(bore_markers)
+                                .shape(MarkerShape::Square)
+                                .radius(4.0)
+                                .color(style.alert())
+                                .name("Bores"),
                         );
+                    }
 
-                        // Water surface
+                    // Linear theory over the wave train
+                    for segment in linear_overlay {
+                        plot_ui.line(
+                            Line::new(PlotPoints::from(segment))
+                                .color(style.reference())
+                                .width(style.width(1.5))
+                                .style(LineStyle::dashed_loose())
+                                .name("Linear Theory"),
+                        );
+                    }
+                    for segment in stream_function_overlay {
                         plot_ui.line(
-                            Line::new(water_surface)
-                                .color(egui::Color32::from_rgb(30, 144, 255)) // Dodger blue for water
-                                .width(2.0)
-                                .name("Water Surface"),
+                            Line::new(PlotPoints::from(segment))
+                                .color(style.reference())
+                                .width(style.width(1.5))
+                                .style(LineStyle::dotted_loose())
+                                .name("Stream Function Theory"),
                         );
+                    }
+
+                    draw_annotations(plot_ui, &self.annotations, AnnotatedPlot::Channel, style.annotation());
+                    if self.measure_tool == MeasureTool::Slope {
+                        plot_ui.points(Points::new(self.measure_points.clone()).shape(MarkerShape::Cross).radius(6.0).color(style.marker()));
+                    }
+                    plot_ui.response().clicked().then(|| plot_ui.pointer_coordinate()).flatten()
+                });
+                if let Some(point) = clicked.inner
+                    && let Some(text) = self.measure_click(AnnotatedPlot::Channel, [point.x, point.y])
+                {
+                    ui.ctx().copy_text(text);
+                }
+
+                self.show_water_display_controls(ui);
+                self.show_measure_controls(ui);
+                if self.is_numerical() {
+                    self.show_linear_overlay_controls(ui);
+                }
+                self.show_annotations(ui);
+                self.show_gallery(ui);
+
+                if self.gauges.iter().any(|gauge| !gauge.is_empty()) {
+                    ui.separator();
+                    ui.heading("Gauge Records");
+                    self.show_gauge_records(ui);
+                    egui::CollapsingHeader::new("Gauge Statistics").show(ui, |ui| {
+                        self.gauge_statistics().show(ui, "gauge_statistics", &mut self.gauge_table);
                     });
+                }
+
+                if !self.snapshots.is_empty() {
+                    self.show_playback_bar(ui);
+
+                    ui.separator();
+                    ui.heading("Wave Tracking");
+                    self.show_wave_tracking(ui, &tracks);
+
+                    if self.is_numerical() {
+                        ui.separator();
+                        ui.heading("Bores and Hydraulic Jumps");
+                        self.show_bore_detection(ui, &bores);
+                    }
+                }
+
+                if self.initial_condition.is_none() {
+                    ui.separator();
+                    ui.heading("Stokes Drift and Mass Transport");
+                    self.show_stokes_drift(ui);
+
+                    ui.separator();
+                    ui.heading("Wave Theory Applicability");
+                    self.show_theory_diagram(ui);
+                    self.show_stream_function(ui);
+                }
+
+                ui.separator();
+                ui.heading("Wave Energy Converter");
+                self.show_energy_converter(ui);
+
+                if !self.gauges.is_empty() {
+                    ui.separator();
+                    ui.heading("Infragravity Analysis");
+                    self.show_infragravity_analysis(ui);
+
+                    ui.separator();
+                    ui.heading("Wavelet Scalogram");
+                    self.show_scalogram(ui);
+
+                    ui.separator();
+                    ui.heading("Bispectrum");
+                    self.show_bispectrum(ui);
+
+                    ui.separator();
+                    ui.heading("Cross-Spectral Analysis");
+                    if self.gauges.len() >= 2 {
+                        self.show_cross_spectrum(ui);
+                    } else {
+                        ui.label("Add a second gauge to compare records.");
+                    }
+                }
+
+                if self.structure_extent().is_some() {
+                    ui.separator();
+                    ui.heading("Energy Budget");
+                    self.show_energy_budget(ui);
+                }
+
+                if self.pressure_sensors.iter().any(|sensor| !sensor.is_empty()) {
+                    ui.separator();
+                    ui.heading("Structure Pressures");
+                    self.show_pressure_records(ui);
+                }
+
+                if !self.wall_force.is_empty() {
+                    ui.separator();
+                    ui.heading("Wall Force and Impacts");
+                    self.show_wall_impacts(ui);
+                }
+
+                if self.overtopping_tank.as_ref().is_some_and(|tank| !tank.is_empty()) {
+                    ui.separator();
+                    ui.heading("Overtopping Tank");
+                    self.show_overtopping_tank(ui);
+                }
+
+                if !self.mean_flow.is_empty() {
+                    ui.separator();
+                    ui.heading("Undertow");
+                    self.show_undertow(ui, ctx, equation_renderer);
+                }
+
+                if !self.shoreline.is_empty() {
+                    ui.separator();
+                    ui.heading("Swash Zone");
+                    self.show_swash_zone(ui);
+                }
+
+                if !self.inundation.is_empty() {
+                    ui.separator();
+                    ui.heading("Inundation");
+                    self.show_inundation(ui);
+                }
+
+                if !self.dissipation.is_empty() {
+                    ui.separator();
+                    ui.heading("Energy Dissipation");
+                    self.show_dissipation(ui);
+                }
+
+                if !self.level_drift.is_empty() {
+                    ui.separator();
+                    ui.heading("Mean Level Drift");
+                    self.show_level_drift(ui);
+                }
             },
         );
     }
 }
+
+/// Engine if one is set, else the built-in solver once built
+fn engine_or_solver<'a>(engine: &'a Option<Box<dyn WaveSolver>>, solver: &'a Option<ShallowWaterSolver>) -> Option<&'a dyn WaveSolver> {
+    match engine {
+        Some(engine) => Some(engine.as_ref()),
+        None => solver.as_ref().map(|solver| solver as &dyn WaveSolver),
+    }
+}
+
+/// Sankey-style bar of the energy budget: the incident flux on top, flowing into the reflected,
+/// transmitted and dissipated shares below; a negative dissipation is drawn as none
+fn draw_energy_budget(ui: &mut egui::Ui, budget: &EnergyBudget) {
+    let width = ui.available_width().min(640.0);
+    let (response, painter) = ui.allocate_painter(egui::vec2(width, 120.0), egui::Sense::hover());
+    let rect = response.rect;
+    let text_color = ui.visuals().text_color();
+    let font = egui::FontId::proportional(12.0);
+    let shares = [
+        ("Reflected", budget.reflection(), egui::Color32::from_rgb(255, 140, 0)),
+        ("Transmitted", budget.transmission(), egui::Color32::from_rgb(34, 139, 34)),
+        ("Dissipated", budget.dissipation(), egui::Color32::from_rgb(220, 20, 60)),
+    ];
+    let total: f64 = shares.iter().map(|(_, share, _)| share.max(0.0)).sum::<f64>().max(f64::MIN_POSITIVE);
+
+    let incident = egui::Rect::from_min_size(rect.min, egui::vec2(width, 22.0));
+    painter.rect_filled(incident, 2.0, egui::Color32::from_rgb(0, 100, 200));
+    painter.text(incident.center(), egui::Align2::CENTER_CENTER, format!("Incident {:.1} W/m", budget.incident), font.clone(), egui::Color32::WHITE);
+
+    let gap = 12.0;
+    let (top, bottom) = (incident.bottom(), rect.top() + 80.0);
+    let mut upper = rect.left();
+    let mut lower = rect.left();
+    for (name, share, color) in shares {
+        let fraction = (share.max(0.0) / total) as f32;
+        let upper_width = fraction * width;
+        let lower_width = fraction * (width - 2.0 * gap);
+        if fraction > 0.0 {
+            let flow = vec![
+                egui::pos2(upper, top),
+                egui::pos2(upper + upper_width, top),
+                egui::pos2(lower + lower_width, bottom),
+                egui::pos2(lower, bottom),
+            ];
+            painter.add(egui::Shape::convex_polygon(flow, color.gamma_multiply(0.35), egui::Stroke::NONE));
+            painter.rect_filled(egui::Rect::from_min_size(egui::pos2(lower, bottom), egui::vec2(lower_width, 18.0)), 2.0, color);
+        }
+        painter.text(
+            egui::pos2(lower + 0.5 * lower_width, bottom + 22.0),
+            egui::Align2::CENTER_TOP,
+            format!("{} {:.0}%", name, 100.0 * share),
+            font.clone(),
+            text_color,
+        );
+        upper += upper_width;
+        lower += lower_width + gap;
+    }
+}
+
+/// Render the scalogram power as an image, highest frequency on the top row
+/// Columns are decimated so long records stay within a reasonable texture size
+fn scalogram_image(scalogram: &Scalogram, colormap: Colormap) -> egui::ColorImage {
+    let power = scalogram.power();
+    let samples = power[0].len();
+    let columns = samples.min(800);
+    let max_power = scalogram.max_power().max(f64::MIN_POSITIVE);
+    let mut pixels = Vec::with_capacity(columns * power.len());
+    for row in power {
+        for column in 0..columns {
+            let sample = column * (samples - 1) / (columns - 1).max(1);
+            // Square-root scaling keeps weaker groups and harmonics visible next to the peak
+            pixels.push(colormap.color((row[sample] / max_power).sqrt()));
+        }
+    }
+    egui::ColorImage {
+        size: [columns, power.len()],
+        pixels,
+    }
+}
+
+/// Render the bicoherence as an image, f₁ along columns and f₂ along rows with the highest on top
+/// Only the principal domain f₁ ≥ f₂ is drawn, the symmetric half is left transparent
+fn bicoherence_image(bispectrum: &Bispectrum, colormap: Colormap) -> egui::ColorImage {
+    let bicoherence = bispectrum.bicoherence();
+    let bins = bicoherence.len();
+    let mut pixels = Vec::with_capacity(bins * bins);
+    for j in (0..bins).rev() {
+        for (i, row) in bicoherence.iter().enumerate() {
+            pixels.push(if i >= j { colormap.color(row[j]) } else { egui::Color32::TRANSPARENT });
+        }
+    }
+    egui::ColorImage {
+        size: [bins, bins],
+        pixels,
+    }
+}
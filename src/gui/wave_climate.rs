@@ -0,0 +1,417 @@
+use super::background::BackgroundRun;
+use super::project::Project;
+use super::results_table::{ResultsTable, TableCell, TableView};
+use super::run_output::RunOutput;
+use super::plot_style::PlotStyle;
+use super::wave_channel::WaveChannelApp;
+use crate::waves::{HOURS_PER_YEAR, ScatterCell, ScatterDiagram};
+use eframe::egui;
+use egui_plot::{Plot, PlotPoint, PlotPoints, Polygon, Text};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
+
+/// Output of the wave channel run in the sea state of a scatter diagram cell
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellRun {
+    pub cell: ScatterCell,
+    pub output: Result<f64, String>, // Output value, or why the run failed
+}
+
+/// Runs of the wave channel scenario over the sea states of a scatter diagram
+///
+/// Every cell copies the scenario and replaces the wavemaker waves by regular waves of the same
+/// energy as the sea state, H = Hs/√2 and T = Te, before running it to its end. The outputs,
+/// weighted by the probabilities of their cells, give the long-term mean of the output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClimateStudy {
+    pub output: RunOutput,
+    pub threads: usize,        // Cells run in parallel
+    pub min_probability: f64,  // Cells less likely than this are not run
+}
+
+impl Default for ClimateStudy {
+    fn default() -> Self {
+        Self {
+            output: RunOutput::MaxElevation { gauge: 0 },
+            threads: std::thread::available_parallelism().map_or(1, |threads| threads.get()),
+            min_probability: 0.0,
+        }
+    }
+}
+
+impl ClimateStudy {
+    /// Check the study against the scenario it runs
+    pub fn validate(&self, scenario: &Project) -> Result<(), String> {
+        if self.threads == 0 {
+            return Err("The study needs at least one thread".to_string());
+        }
+        if scenario.initial_condition.is_some() {
+            return Err("The sea states replace the wavemaker waves: select the wavemaker scenario".to_string());
+        }
+        self.output.validate(scenario)
+    }
+
+    /// Cells of a scatter diagram the study runs
+    pub fn cells(&self, scatter: &ScatterDiagram) -> Vec<ScatterCell> {
+        scatter.cells().into_iter().filter(|cell| cell.probability >= self.min_probability).collect()
+    }
+
+    /// Run a scenario in the sea state of a cell
+    pub fn cell_run(&self, scenario: &Project, cell: ScatterCell) -> CellRun {
+        let state = cell.sea_state();
+        let mut channel = WaveChannelApp::new();
+        channel.load_project(scenario);
+        channel.wave_height = state.equivalent_wave_height();
+        channel.wave_period = state.energy_period;
+        CellRun { cell, output: self.output.evaluate(&mut channel) }
+    }
+
+    /// Run the cells of a scatter diagram on the threads of the study, sending each as it completes
+    /// Stops early when cancelled or when the receiver is gone
+    pub fn run(&self, scenario: &Project, scatter: &ScatterDiagram, cancel: &AtomicBool, sender: &Sender<CellRun>) {
+        let cells = self.cells(scatter);
+        let next = AtomicUsize::new(0);
+        std::thread::scope(|scope| {
+            for _ in 0..self.threads.max(1) {
+                scope.spawn(|| {
+                    while !cancel.load(Ordering::Relaxed) {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        if index >= cells.len() || sender.send(self.cell_run(scenario, cells[index])).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+    }
+
+    /// Run every cell of a scatter diagram, waiting for the results
+    pub fn run_to_completion(&self, scenario: &Project, scatter: &ScatterDiagram) -> ClimateResults {
+        let (sender, receiver) = mpsc::channel();
+        self.run(scenario, scatter, &AtomicBool::new(false), &sender);
+        drop(sender);
+        let mut results = ClimateResults::default();
+        results.extend(receiver.try_iter());
+        results
+    }
+}
+
+/// Runs of a climate study, by cell
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClimateResults {
+    runs: Vec<CellRun>,
+}
+
+impl ClimateResults {
+    /// Add completed runs, keeping the order of the cells
+    pub fn extend(&mut self, runs: impl IntoIterator<Item = CellRun>) {
+        self.runs.extend(runs);
+        self.runs.sort_by_key(|run| (run.cell.row, run.cell.column));
+    }
+
+    /// Completed runs, by cell
+    pub fn runs(&self) -> &[CellRun] {
+        &self.runs
+    }
+
+    /// Number of completed runs, failed ones included
+    pub fn len(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Check if no run completed
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    /// Outputs of the successful runs: (row, column, output)
+    pub fn outputs(&self) -> Vec<(usize, usize, f64)> {
+        self.runs.iter().filter_map(|run| run.output.as_ref().ok().map(|&output| (run.cell.row, run.cell.column, output))).collect()
+    }
+
+    /// Runs that failed, with the reason
+    pub fn failures(&self) -> impl Iterator<Item = &CellRun> {
+        self.runs.iter().filter(|run| run.output.is_err())
+    }
+
+    /// Long-term mean of the output over the successful runs, with the probability they cover
+    pub fn annual_mean(&self, scatter: &ScatterDiagram) -> Option<(f64, f64)> {
+        scatter.weighted_mean(&self.outputs())
+    }
+
+    /// Forget the runs, before a new study
+    pub fn clear(&mut self) {
+        self.runs.clear();
+    }
+}
+
+/// Wave climate of the site as a scatter diagram, and the wave channel outputs annualized over it
+pub struct WaveClimateApp {
+    pub scatter: Option<ScatterDiagram>,
+    pub scatter_path: String,   // CSV file the scatter diagram is imported from
+    pub study: ClimateStudy,
+    pub results: ClimateResults,
+    running: Option<BackgroundRun<CellRun>>,
+    status: Option<String>,     // Outcome of the last import, or why the last study could not start
+    table: TableView,           // Sorting and filtering of the runs table
+}
+
+impl Default for WaveClimateApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WaveClimateApp {
+    pub fn new() -> Self {
+        Self {
+            scatter: None,
+            scatter_path: "scatter.csv".to_string(),
+            study: ClimateStudy::default(),
+            results: ClimateResults::default(),
+            running: None,
+            status: None,
+            table: TableView::default(),
+        }
+    }
+
+    /// Import a scatter diagram file, forgetting the runs of the previous one
+    pub fn import(&mut self, path: &Path) -> Result<(), String> {
+        let scatter = ScatterDiagram::load(path)?;
+        self.cancel();
+        self.results.clear();
+        self.scatter = Some(scatter);
+        Ok(())
+    }
+
+    /// Start the study of a scenario on background threads, forgetting previous results
+    pub fn start(&mut self, scenario: Project) -> Result<(), String> {
+        let scatter = self.scatter.clone().ok_or("Import a scatter diagram first")?;
+        self.study.validate(&scenario)?;
+        self.cancel();
+        self.results.clear();
+        let study = self.study.clone();
+        self.running = Some(BackgroundRun::spawn(move |cancel, sender| study.run(&scenario, &scatter, cancel, sender)));
+        Ok(())
+    }
+
+    /// Stop the study, keeping the completed runs
+    pub fn cancel(&mut self) {
+        if let Some(running) = self.running.take() {
+            self.results.extend(running.cancel());
+        }
+    }
+
+    /// Collect the runs completed since the last poll
+    pub fn poll(&mut self) {
+        let Some(running) = &self.running else {
+            return;
+        };
+        let (runs, finished) = running.poll();
+        self.results.extend(runs);
+        if finished {
+            self.running = None;
+        }
+    }
+
+    /// Whether a study is running
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// Fraction of the cells completed
+    pub fn progress(&self) -> f64 {
+        let cells = self.scatter.as_ref().map_or(0, |scatter| self.study.cells(scatter).len());
+        self.results.len() as f64 / cells.max(1) as f64
+    }
+
+    /// Show the scatter diagram and the study of the wave channel scenario over it
+    pub fn show(&mut self, ui: &mut egui::Ui, channel: &mut WaveChannelApp) {
+        self.poll();
+        ui.heading("Wave Climate");
+        ui.label("Imports the joint occurrence of significant wave heights and peak periods at the site, runs the wave channel scenario in each sea state and weights the outputs into long-term values.");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Scatter Diagram:");
+            ui.add(egui::TextEdit::singleline(&mut self.scatter_path).desired_width(250.0));
+            if ui.add_enabled(!self.is_running(), egui::Button::new("📂 Import")).clicked() {
+                let path = self.scatter_path.clone();
+                self.status = self.import(Path::new(&path)).err();
+            }
+        })
+        .response
+        .on_hover_text("CSV file: a first row with a label and the peak periods Tp (s), then one row per significant wave height Hs (m) with its occurrences, as counts, hours or percentages. Lines starting with # are comments.");
+        if let Some(status) = &self.status {
+            ui.colored_label(egui::Color32::from_rgb(200, 80, 0), format!("⚠ {}", status));
+        }
+        let Some(scatter) = self.scatter.clone() else {
+            return;
+        };
+
+        let cells = scatter.cells();
+        ui.label(format!(
+            "{} Hs × {} Tp classes, {} sea states occurring; mean deep water wave power {:.1} kW/m",
+            scatter.heights().len(),
+            scatter.periods().len(),
+            cells.len(),
+            scatter.mean_wave_power() / 1000.0
+        ));
+        let probabilities: Vec<(usize, usize, f64)> = cells.iter().map(|cell| (cell.row, cell.column, cell.probability)).collect();
+        let width = ((ui.available_width() - 60.0) / 2.0).max(300.0);
+        let style = channel.plot_style;
+        ui.horizontal(|ui| {
+            draw_scatter(ui, style, "scatter_occurrence", &scatter, &probabilities, width, |probability| format!("{:.1}", 100.0 * probability));
+            let outputs = self.results.outputs();
+            if !outputs.is_empty() {
+                draw_scatter(ui, style, "scatter_output", &scatter, &outputs, width, |output| format!("{:.3}", output));
+            }
+        });
+        ui.label("Left: occurrence (%); right: output of the run in each sea state.");
+
+        ui.horizontal(|ui| {
+            if ui.button("Use for the Wave Energy Converter").clicked() {
+                channel.wave_climate = scatter.sea_states();
+            }
+            ui.label(format!("Sea states as regular waves of the same energy, Te = 0.9 Tp, over {:.0} h per year", HOURS_PER_YEAR));
+        });
+        if channel.energy_converter.is_some() && channel.wave_climate == scatter.sea_states() {
+            match channel.converter_annual_energy() {
+                Ok(energy) => ui.label(format!("Annual energy of the wave energy converter: {:.1} kWh", energy / 3.6e6)),
+                Err(message) => ui.label(format!("⚠ {}", message)),
+            };
+        }
+        ui.separator();
+
+        ui.add_enabled_ui(!self.is_running(), |ui| {
+            self.show_settings(ui, channel);
+        });
+        ui.horizontal(|ui| {
+            if self.is_running() {
+                if ui.button("⏹ Cancel").clicked() {
+                    self.cancel();
+                }
+                ui.add(egui::ProgressBar::new(self.progress() as f32).desired_width(200.0).show_percentage());
+                channel.repaint_policy.request_progress(ui.ctx());
+            } else if ui.button("▶ Run Sea States").clicked() {
+                self.status = self.start(channel.project()).err();
+            }
+            ui.label(format!("{} / {} sea states", self.results.len(), self.study.cells(&scatter).len()));
+        });
+        self.show_results(ui, &scatter);
+    }
+
+    /// Output, threads and the least likely sea state to run
+    fn show_settings(&mut self, ui: &mut egui::Ui, channel: &WaveChannelApp) {
+        let study = &mut self.study;
+        ui.horizontal(|ui| {
+            ui.label("Output:");
+            egui::ComboBox::from_id_salt("climate_output")
+                .selected_text(study.output.label())
+                .width(200.0)
+                .show_ui(ui, |ui| {
+                    for output in RunOutput::available(channel.gauges.len()) {
+                        ui.selectable_value(&mut study.output, output, output.label());
+                    }
+                });
+            ui.label("Threads:");
+            ui.add(egui::DragValue::new(&mut study.threads).range(1..=256));
+            ui.label("Min Probability:");
+            ui.add(egui::DragValue::new(&mut study.min_probability).range(0.0..=0.5).speed(0.0001).custom_formatter(|value, _| format!("{:.2}%", 100.0 * value)));
+        });
+    }
+
+    /// Completed runs, a row per sea state
+    pub fn results_table(&self) -> ResultsTable {
+        let output = format!("{} ({})", self.study.output.label(), self.study.output.unit());
+        let mut table = ResultsTable::new(["Hs (m)".to_string(), "Tp (s)".to_string(), "Occurrence (%)".to_string(), output]);
+        for run in self.results.runs() {
+            table.push(vec![
+                TableCell::Number(run.cell.significant_height),
+                TableCell::Number(run.cell.peak_period),
+                TableCell::Number(100.0 * run.cell.probability),
+                TableCell::output(&run.output),
+            ]);
+        }
+        table
+    }
+
+    /// Long-term mean of the output, the annual volume of the overtopping discharge and the runs
+    fn show_results(&mut self, ui: &mut egui::Ui, scatter: &ScatterDiagram) {
+        let failures: Vec<&CellRun> = self.results.failures().collect();
+        if let Some(failure) = failures.first() {
+            let reason = failure.output.as_ref().err().cloned().unwrap_or_default();
+            ui.label(format!(
+                "⚠ {} sea states failed, e.g. Hs = {:.2} m, Tp = {:.1} s: {}",
+                failures.len(),
+                failure.cell.significant_height,
+                failure.cell.peak_period,
+                reason
+            ));
+        }
+        let Some((mean, coverage)) = self.results.annual_mean(scatter) else {
+            return;
+        };
+        ui.label(format!(
+            "Long-term mean {}: {:.4} {}, over sea states covering {:.1}% of the time",
+            self.study.output.label(),
+            mean,
+            self.study.output.unit(),
+            100.0 * coverage
+        ));
+        if self.study.output == RunOutput::Overtopping {
+            // l/s per metre over a year to m³ per metre
+            ui.label(format!("Annual overtopping volume: {:.1} m³ per metre of crest", mean * HOURS_PER_YEAR * 3.6));
+        }
+        egui::CollapsingHeader::new(format!("Sea State Runs ({})", self.results.len())).show(ui, |ui| {
+            self.results_table().show(ui, "climate_runs", &mut self.table);
+        });
+    }
+}
+
+impl Drop for WaveClimateApp {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// Edges of classes around their centres, halfway between neighbours and as wide at both ends
+fn class_edges(centres: &[f64]) -> Vec<f64> {
+    if centres.len() < 2 {
+        let centre = centres.first().copied().unwrap_or(1.0);
+        return vec![0.5 * centre, 1.5 * centre];
+    }
+    let n = centres.len();
+    let mut edges = vec![centres[0] - 0.5 * (centres[1] - centres[0])];
+    edges.extend(centres.windows(2).map(|pair| 0.5 * (pair[0] + pair[1])));
+    edges.push(centres[n - 1] + 0.5 * (centres[n - 1] - centres[n - 2]));
+    edges
+}
+
+/// Heat map of values of the scatter diagram cells, labelled with their values, periods across and heights up
+fn draw_scatter(ui: &mut egui::Ui, style: PlotStyle, id: &str, scatter: &ScatterDiagram, values: &[(usize, usize, f64)], width: f32, label: impl Fn(f64) -> String) {
+    let (period_edges, height_edges) = (class_edges(scatter.periods()), class_edges(scatter.heights()));
+    let (low, high) = values.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), &(_, _, value)| (low.min(value), high.max(value)));
+    let range = (high - low).max(f64::MIN_POSITIVE);
+    let plot = Plot::new(id)
+        .height(260.0)
+        .width(width)
+        .x_axis_label("Peak Period Tp (s)")
+        .y_axis_label("Significant Wave Height Hs (m)");
+    style.show_plot(ui, plot, |plot_ui| {
+        for &(row, column, value) in values {
+            let (left, right) = (period_edges[column], period_edges[column + 1]);
+            let (bottom, top) = (height_edges[row], height_edges[row + 1]);
+            let shade = (value - low) / range;
+            let color = style.colormap.color(shade);
+            plot_ui.polygon(
+                Polygon::new(PlotPoints::from(vec![[left, bottom], [right, bottom], [right, top], [left, top]]))
+                    .fill_color(color)
+                    .stroke(egui::Stroke::new(0.5, egui::Color32::GRAY)),
+            );
+            plot_ui.text(Text::new(PlotPoint::new(0.5 * (left + right), 0.5 * (bottom + top)), label(value)).color(if shade > 0.6 { egui::Color32::BLACK } else { egui::Color32::WHITE }));
+        }
+    });
+}
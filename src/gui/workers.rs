@@ -0,0 +1,288 @@
+use super::monte_carlo::{MonteCarloStudy, Realization};
+use super::project::Project;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Address the host listens on for workers unless another is set
+/// Only this machine can connect until the address of a network interface, or 0.0.0.0, is entered
+pub const DEFAULT_WORKER_ADDRESS: &str = "127.0.0.1:8788";
+
+/// Pause of a thread finding no job while others are still running theirs
+pub const QUEUE_WAIT: Duration = Duration::from_millis(100);
+
+/// Pause of the accept loop between checks for new workers and for the end of the study
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Time the host waits for a message of a worker before checking for a cancel
+const READ_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Longest message accepted from a worker before it presents the token, enough for hello [bytes]
+const MAX_HELLO: usize = 1 << 10;
+
+/// Longest message accepted from a worker, enough for any realization [bytes]
+const MAX_MESSAGE: usize = 1 << 20;
+
+/// Realizations of a study handed out to the local threads and the remote workers
+///
+/// Jobs are taken in index order. A job taken by a worker that disconnects is given back and taken
+/// again first, so every realization completes as long as a thread or a worker remains.
+#[derive(Debug, Default)]
+pub struct JobQueue {
+    state: Mutex<QueueState>,
+}
+
+#[derive(Debug, Default)]
+struct QueueState {
+    next: usize,          // First index never taken
+    count: usize,         // Number of jobs
+    returned: Vec<usize>, // Jobs given back, taken again before the next ones
+    running: usize,       // Jobs taken and not finished or given back
+}
+
+impl JobQueue {
+    pub fn new(count: usize) -> Self {
+        Self { state: Mutex::new(QueueState { count, ..QueueState::default() }) }
+    }
+
+    /// Index of the next job to run, None if every job is taken
+    pub fn take(&self) -> Option<usize> {
+        let mut state = self.state.lock().unwrap();
+        let index = match state.returned.pop() {
+            Some(index) => index,
+            None if state.next < state.count => {
+                state.next += 1;
+                state.next - 1
+            }
+            None => return None,
+        };
+        state.running += 1;
+        Some(index)
+    }
+
+    /// Record a job taken as done
+    pub fn finish(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.running -= 1;
+    }
+
+    /// Give back a job taken but not done, to run it again
+    pub fn give_back(&self, index: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.running -= 1;
+        state.returned.push(index);
+    }
+
+    /// Check if every job is done
+    pub fn is_finished(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state.next >= state.count && state.returned.is_empty() && state.running == 0
+    }
+}
+
+/// Secret a worker presents to the host of a study, 128 random bits in hexadecimal
+/// The bits come from the hash keys the standard library draws from the operating system
+pub fn new_token() -> String {
+    let half = || RandomState::new().build_hasher().finish();
+    format!("{:016x}{:016x}", half(), half())
+}
+
+/// Message between the host of a study and a worker, one JSON object per line
+///
+/// The worker presents the token of the study with hello when it connects, and the host answers with
+/// the study and its scenario, or closes the connection on a wrong token. The worker then asks for a
+/// job with next and answers each job with its result; the host answers next with a job, with wait
+/// while the last jobs run elsewhere and may be given back, or with done at the end of the study.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WorkerMessage {
+    Hello { token: String },
+    Study { study: MonteCarloStudy, scenario: Box<Project> },
+    Next,
+    Job { index: usize },
+    Wait,
+    Done,
+    Result { realization: Realization },
+}
+
+/// Connection of a worker to the host, with the realizations it completed
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkerStatus {
+    pub address: SocketAddr,
+    pub completed: usize,
+    pub connected: bool,
+}
+
+/// Workers of the running study, shared with the interface reporting them
+pub type WorkerList = Arc<Mutex<Vec<WorkerStatus>>>;
+
+/// Study handed out to the workers, with the queue of its jobs and where their realizations go
+struct Host<'a> {
+    token: &'a str,
+    study: &'a MonteCarloStudy,
+    scenario: &'a Project,
+    queue: &'a JobQueue,
+    workers: &'a WorkerList,
+    cancel: &'a AtomicBool,
+    sender: &'a Sender<Realization>,
+}
+
+/// Send a message as a line
+fn send(stream: &mut TcpStream, message: &WorkerMessage) -> Result<(), String> {
+    let mut line = serde_json::to_string(message).map_err(|e| format!("Cannot serialize message: {}", e))?;
+    line.push('\n');
+    stream.write_all(line.as_bytes()).map_err(|e| format!("Cannot send message: {}", e))
+}
+
+/// Receive the next message, waiting for it
+fn receive(reader: &mut BufReader<TcpStream>) -> Result<WorkerMessage, String> {
+    let mut line = String::new();
+    match reader.read_line(&mut line) {
+        Ok(0) => Err("The host closed the connection".to_string()),
+        Ok(_) => serde_json::from_str(&line).map_err(|e| format!("Invalid message: {}", e)),
+        Err(e) => Err(format!("Cannot receive message: {}", e)),
+    }
+}
+
+/// Hand the jobs of a study to the workers connecting to a listener, sending their realizations as they complete
+/// Returns once the study is finished or cancelled, after every worker is disconnected
+/// Only the workers presenting the token receive the study and may send realizations
+#[allow(clippy::too_many_arguments)]
+pub fn serve_workers(listener: &TcpListener, token: &str, study: &MonteCarloStudy, scenario: &Project, queue: &JobQueue, workers: &WorkerList, cancel: &AtomicBool, sender: &Sender<Realization>) {
+    if let Err(e) = listener.set_nonblocking(true) {
+        eprintln!("Cannot accept workers: {}", e);
+        return;
+    }
+    let host = Host { token, study, scenario, queue, workers, cancel, sender };
+    std::thread::scope(|scope| {
+        while !cancel.load(Ordering::Relaxed) && !queue.is_finished() {
+            match listener.accept() {
+                Ok((stream, address)) => {
+                    let slot = {
+                        let mut workers = workers.lock().unwrap();
+                        workers.push(WorkerStatus { address, completed: 0, connected: true });
+                        workers.len() - 1
+                    };
+                    let host = &host;
+                    scope.spawn(move || {
+                        if let Err(message) = serve_worker(host, stream, slot) {
+                            eprintln!("Worker {}: {}", address, message);
+                        }
+                        workers.lock().unwrap()[slot].connected = false;
+                    });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => std::thread::sleep(ACCEPT_INTERVAL),
+                Err(e) => eprintln!("Worker connection failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Answer a worker until the study is finished or cancelled, giving back its job if it disconnects while running it
+fn serve_worker(host: &Host, stream: TcpStream, slot: usize) -> Result<(), String> {
+    stream.set_nonblocking(false).and_then(|_| stream.set_read_timeout(Some(READ_TIMEOUT))).map_err(|e| format!("Cannot configure connection: {}", e))?;
+    let mut writer = stream.try_clone().map_err(|e| format!("Cannot configure connection: {}", e))?;
+
+    let mut reader = BufReader::new(stream);
+    // Bytes rather than text, as the timeout may cut the line inside a character
+    let mut line = Vec::new();
+    let mut authenticated = false;
+    let mut assigned = None;
+    let outcome = loop {
+        if host.cancel.load(Ordering::Relaxed) || (assigned.is_none() && host.queue.is_finished()) {
+            break send(&mut writer, &WorkerMessage::Done);
+        }
+        // A line cut by the timeout is completed by the next read, up to the longest message accepted
+        let limit = if authenticated { MAX_MESSAGE } else { MAX_HELLO };
+        match (&mut reader).take((limit + 1 - line.len()) as u64).read_until(b'\n', &mut line) {
+            Ok(0) => break Err("Disconnected".to_string()),
+            Ok(_) if line.len() > limit => break Err("Message is too long".to_string()),
+            Ok(_) => {}
+            Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => continue,
+            Err(e) => break Err(format!("Cannot receive message: {}", e)),
+        }
+        let message = serde_json::from_slice(&line);
+        line.clear();
+        let reply = match message {
+            Ok(WorkerMessage::Hello { token }) if !authenticated => {
+                if token != host.token {
+                    break Err("Wrong token".to_string());
+                }
+                authenticated = true;
+                WorkerMessage::Study { study: host.study.clone(), scenario: Box::new(host.scenario.clone()) }
+            }
+            _ if !authenticated => break Err("The worker did not present the token".to_string()),
+            // A worker runs one job at a time, so next before the result of its job is refused
+            Ok(WorkerMessage::Next) if assigned.is_none() => match host.queue.take() {
+                Some(index) => {
+                    assigned = Some(index);
+                    WorkerMessage::Job { index }
+                }
+                None if host.queue.is_finished() => WorkerMessage::Done,
+                None => WorkerMessage::Wait,
+            },
+            Ok(WorkerMessage::Result { realization }) if assigned == Some(realization.index) => {
+                assigned = None;
+                host.queue.finish();
+                host.workers.lock().unwrap()[slot].completed += 1;
+                if host.sender.send(realization).is_err() {
+                    break Ok(());
+                }
+                continue;
+            }
+            Ok(message) => break Err(format!("Unexpected message {:?}", message)),
+            Err(e) => break Err(format!("Invalid message: {}", e)),
+        };
+        let done = reply == WorkerMessage::Done;
+        if let Err(message) = send(&mut writer, &reply) {
+            break Err(message);
+        }
+        if done {
+            break Ok(());
+        }
+    };
+    if let Some(index) = assigned {
+        host.queue.give_back(index);
+    }
+    outcome
+}
+
+/// Run the jobs of the host at an address on one connection until its study is done, presenting its token
+/// Returns the number of realizations run
+pub fn run_worker(address: &str, token: &str) -> Result<usize, String> {
+    let stream = TcpStream::connect(address).map_err(|e| format!("Cannot connect to {}: {}", address, e))?;
+    let mut writer = stream.try_clone().map_err(|e| format!("Cannot configure connection: {}", e))?;
+    let mut reader = BufReader::new(stream);
+    send(&mut writer, &WorkerMessage::Hello { token: token.to_string() })?;
+    let WorkerMessage::Study { study, scenario } = receive(&mut reader)? else {
+        return Err("The host did not send a study".to_string());
+    };
+    let mut completed = 0;
+    loop {
+        send(&mut writer, &WorkerMessage::Next)?;
+        match receive(&mut reader)? {
+            WorkerMessage::Job { index } => {
+                send(&mut writer, &WorkerMessage::Result { realization: study.realization(&scenario, index) })?;
+                completed += 1;
+            }
+            WorkerMessage::Wait => std::thread::sleep(QUEUE_WAIT),
+            WorkerMessage::Done => return Ok(completed),
+            message => return Err(format!("Unexpected message {:?}", message)),
+        }
+    }
+}
+
+/// Run a worker per thread against the host at an address, as a headless instance does
+/// Returns the number of realizations run by all of them
+pub fn run_workers(address: &str, token: &str, threads: usize) -> Result<usize, String> {
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads.max(1)).map(|_| scope.spawn(|| run_worker(address, token))).collect();
+        handles.into_iter().map(|handle| handle.join().unwrap_or_else(|_| Err("Worker thread panicked".to_string()))).sum()
+    })
+}
@@ -1,5 +1,11 @@
+pub mod analysis;
+pub mod error;
 pub mod gui;
+pub mod numerics;
+pub mod solver;
+pub mod units;
 pub mod waves;
 
 // Re-export for easier access
+pub use error::CoastalError;
 pub use waves::*;
@@ -1,12 +1,36 @@
-mod gui;
-
+use coastal_engineering_platform::gui::{PlatformApp, Preferences, run_workers};
 use eframe::egui;
-use gui::PlatformApp;
 
 fn main() -> eframe::Result<()> {
+    // Headless worker of a study hosted by another instance: --worker HOST:PORT --token TOKEN [--threads N]
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(position) = args.iter().position(|arg| arg == "--worker") {
+        let Some(address) = args.get(position + 1) else {
+            eprintln!("--worker needs the address of the host, e.g. --worker 192.168.1.10:8788");
+            std::process::exit(2);
+        };
+        let Some(token) = args.iter().position(|arg| arg == "--token").and_then(|position| args.get(position + 1)) else {
+            eprintln!("--worker needs the token shown by the host next to its address, e.g. --token 3f2a...");
+            std::process::exit(2);
+        };
+        let threads = match args.iter().position(|arg| arg == "--threads") {
+            Some(position) => args.get(position + 1).and_then(|threads| threads.parse().ok()).unwrap_or(1),
+            None => std::thread::available_parallelism().map_or(1, |threads| threads.get()),
+        };
+        match run_workers(address, token, threads) {
+            Ok(completed) => println!("Ran {} realizations for {}", completed, address),
+            Err(message) => {
+                eprintln!("{}", message);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    let preferences = Preferences::load_default();
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
-            .with_inner_size([1200.0, 800.0])
+            .with_inner_size(preferences.window_size)
             .with_title("Coastal Engineering Platform"),
         ..Default::default()
     };
@@ -0,0 +1,489 @@
+/// Solve a tridiagonal system with the Thomas algorithm
+///
+/// Row i reads: lower[i] x[i-1] + diag[i] x[i] + upper[i] x[i+1] = rhs[i]; lower[0] and upper[n-1] are ignored.
+/// There is no pivoting, so the system should be diagonally dominant, as the implicit discretizations are.
+pub fn thomas(lower: &[f64], diag: &[f64], upper: &[f64], rhs: &[f64]) -> Result<Vec<f64>, String> {
+    let (mut x, mut reduced_upper) = (Vec::new(), Vec::new());
+    thomas_into(lower, diag, upper, rhs, &mut x, &mut reduced_upper)?;
+    Ok(x)
+}
+
+/// Solve a tridiagonal system with the Thomas algorithm into a solution buffer, with a work buffer for the
+/// reduced super-diagonal, both resized in place so repeated solves of the same size allocate no memory
+pub fn thomas_into(lower: &[f64], diag: &[f64], upper: &[f64], rhs: &[f64], x: &mut Vec<f64>, reduced_upper: &mut Vec<f64>) -> Result<(), String> {
+    check_lengths(lower, diag, upper, rhs)?;
+    let n = diag.len();
+    reduced_upper.clear();
+    reduced_upper.resize(n, 0.0);
+    x.clear();
+    x.resize(n, 0.0);
+
+    for i in 0..n {
+        let (previous_upper, previous_x) = if i > 0 { (reduced_upper[i - 1], x[i - 1]) } else { (0.0, 0.0) };
+        let pivot = diag[i] - if i > 0 { lower[i] * previous_upper } else { 0.0 };
+        if pivot.abs() < 1e-300 {
+            return Err(format!("Zero pivot in tridiagonal system at row {}", i));
+        }
+        reduced_upper[i] = if i + 1 < n { upper[i] / pivot } else { 0.0 };
+        x[i] = (rhs[i] - if i > 0 { lower[i] * previous_x } else { 0.0 }) / pivot;
+    }
+    for i in (0..n.saturating_sub(1)).rev() {
+        x[i] -= reduced_upper[i] * x[i + 1];
+    }
+    Ok(())
+}
+
+/// Solve a periodic tridiagonal system, where lower[0] couples the first row to x[n-1] and upper[n-1]
+/// couples the last row to x[0]
+///
+/// The corners are removed by a Sherman-Morrison correction, so the cost is two Thomas solves.
+pub fn cyclic_tridiagonal(lower: &[f64], diag: &[f64], upper: &[f64], rhs: &[f64]) -> Result<Vec<f64>, String> {
+    check_lengths(lower, diag, upper, rhs)?;
+    let n = diag.len();
+    if n < 3 {
+        // The corners fall on the band itself, so the system is small enough to solve densely
+        let mut matrix = vec![0.0; n * n];
+        for i in 0..n {
+            matrix[i * n + i] += diag[i];
+            matrix[i * n + (i + n - 1) % n] += lower[i];
+            matrix[i * n + (i + 1) % n] += upper[i];
+        }
+        return solve_dense(&matrix, rhs, n, 1);
+    }
+
+    // A = A' + u vᵀ with u = (γ, 0, …, 0, upper[n-1]) and v = (1, 0, …, 0, lower[0] / γ)
+    let gamma = if diag[0] != 0.0 { -diag[0] } else { -1.0 };
+    let corner_low = upper[n - 1];
+    let corner_high = lower[0];
+    let mut modified = diag.to_vec();
+    modified[0] -= gamma;
+    modified[n - 1] -= corner_low * corner_high / gamma;
+
+    let y = thomas(lower, &modified, upper, rhs)?;
+    let mut u = vec![0.0; n];
+    u[0] = gamma;
+    u[n - 1] = corner_low;
+    let z = thomas(lower, &modified, upper, &u)?;
+
+    // Dimensionless, so it vanishes up to round-off when the periodic system is singular
+    let denominator = 1.0 + z[0] + corner_high * z[n - 1] / gamma;
+    if denominator.abs() < 1e-12 {
+        return Err("Singular cyclic tridiagonal system".to_string());
+    }
+    let factor = (y[0] + corner_high * y[n - 1] / gamma) / denominator;
+    Ok(y.iter().zip(&z).map(|(y, z)| y - factor * z).collect())
+}
+
+fn check_lengths(lower: &[f64], diag: &[f64], upper: &[f64], rhs: &[f64]) -> Result<(), String> {
+    let n = diag.len();
+    if n == 0 {
+        return Err("Empty tridiagonal system".to_string());
+    }
+    if lower.len() != n || upper.len() != n || rhs.len() != n {
+        return Err(format!(
+            "Tridiagonal system of {} rows needs as many sub-diagonal, super-diagonal and right-hand side values (got {}, {}, {})",
+            n,
+            lower.len(),
+            upper.len(),
+            rhs.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Square band matrix with `lower_bands` sub-diagonals and `upper_bands` super-diagonals
+///
+/// Each row stores the columns row - lower_bands ..= row + lower_bands + upper_bands, the extra
+/// lower_bands columns on the right holding the fill-in of the row exchanges of the LU factorization.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BandedMatrix {
+    size: usize,
+    lower_bands: usize,
+    upper_bands: usize,
+    data: Vec<f64>,
+}
+
+impl BandedMatrix {
+    /// Create a zero matrix
+    pub fn new(size: usize, lower_bands: usize, upper_bands: usize) -> Self {
+        Self {
+            size,
+            lower_bands,
+            upper_bands,
+            data: vec![0.0; size * (2 * lower_bands + upper_bands + 1)],
+        }
+    }
+
+    /// Number of rows and columns
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Number of sub-diagonals
+    pub fn lower_bands(&self) -> usize {
+        self.lower_bands
+    }
+
+    /// Number of super-diagonals
+    pub fn upper_bands(&self) -> usize {
+        self.upper_bands
+    }
+
+    /// Whether an entry lies in the band
+    pub fn in_band(&self, row: usize, col: usize) -> bool {
+        row < self.size && col < self.size && col + self.lower_bands >= row && col <= row + self.upper_bands
+    }
+
+    /// Entry of the matrix, zero outside the band
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        if self.in_band(row, col) { self.data[self.index(row, col)] } else { 0.0 }
+    }
+
+    /// Set an entry of the band
+    pub fn set(&mut self, row: usize, col: usize, value: f64) -> Result<(), String> {
+        if !self.in_band(row, col) {
+            return Err(format!(
+                "Entry ({}, {}) is outside the band of a {}×{} matrix with {} sub- and {} super-diagonals",
+                row, col, self.size, self.size, self.lower_bands, self.upper_bands
+            ));
+        }
+        let index = self.index(row, col);
+        self.data[index] = value;
+        Ok(())
+    }
+
+    /// Product of the matrix with a vector
+    pub fn multiply(&self, x: &[f64]) -> Vec<f64> {
+        (0..self.size)
+            .map(|row| {
+                let first = row.saturating_sub(self.lower_bands);
+                let last = (row + self.upper_bands).min(self.size - 1);
+                (first..=last).map(|col| self.data[self.index(row, col)] * x[col]).sum()
+            })
+            .collect()
+    }
+
+    /// LU factorization with partial pivoting, reusable for several right-hand sides
+    pub fn lu(&self) -> Result<BandedLu, String> {
+        let n = self.size;
+        if n == 0 {
+            return Err("Empty band matrix".to_string());
+        }
+        let mut factors = self.clone();
+        let mut pivots = vec![0; n];
+        let reach = self.lower_bands + self.upper_bands;
+
+        for (k, exchange) in pivots.iter_mut().enumerate() {
+            let last_row = (k + self.lower_bands).min(n - 1);
+            let last_col = (k + reach).min(n - 1);
+            let pivot_row = (k..=last_row)
+                .max_by(|&i, &j| factors.at(i, k).abs().total_cmp(&factors.at(j, k).abs()))
+                .unwrap_or(k);
+            if factors.at(pivot_row, k).abs() < 1e-300 {
+                return Err(format!("Singular band matrix at column {}", k));
+            }
+            *exchange = pivot_row;
+            if pivot_row != k {
+                // Only the columns still to be eliminated move, the multipliers of earlier columns stay in place
+                for col in k..=last_col {
+                    let (a, b) = (factors.index(k, col), factors.index(pivot_row, col));
+                    factors.data.swap(a, b);
+                }
+            }
+
+            let pivot = factors.at(k, k);
+            for row in k + 1..=last_row {
+                let multiplier = factors.at(row, k) / pivot;
+                let index = factors.index(row, k);
+                factors.data[index] = multiplier;
+                if multiplier == 0.0 {
+                    continue;
+                }
+                for col in k + 1..=last_col {
+                    let value = factors.at(k, col);
+                    let index = factors.index(row, col);
+                    factors.data[index] -= multiplier * value;
+                }
+            }
+        }
+        Ok(BandedLu { factors, pivots })
+    }
+
+    /// Solve A x = rhs
+    pub fn solve(&self, rhs: &[f64]) -> Result<Vec<f64>, String> {
+        self.lu()?.solve(rhs)
+    }
+
+    /// Storage entry, including the fill-in columns right of the band
+    fn at(&self, row: usize, col: usize) -> f64 {
+        self.data[self.index(row, col)]
+    }
+
+    fn index(&self, row: usize, col: usize) -> usize {
+        let width = 2 * self.lower_bands + self.upper_bands + 1;
+        row * width + col + self.lower_bands - row
+    }
+}
+
+/// LU factors of a band matrix, with the unit lower factor below the diagonal and the row exchanges
+#[derive(Debug, Clone, PartialEq)]
+pub struct BandedLu {
+    factors: BandedMatrix,
+    pivots: Vec<usize>,
+}
+
+impl BandedLu {
+    /// Solve A x = rhs with the factors
+    pub fn solve(&self, rhs: &[f64]) -> Result<Vec<f64>, String> {
+        let a = &self.factors;
+        let n = a.size;
+        if rhs.len() != n {
+            return Err(format!("Band matrix of {} rows needs as many right-hand side values (got {})", n, rhs.len()));
+        }
+        let mut x = rhs.to_vec();
+
+        for k in 0..n {
+            x.swap(k, self.pivots[k]);
+            for row in k + 1..=(k + a.lower_bands).min(n - 1) {
+                x[row] -= a.at(row, k) * x[k];
+            }
+        }
+        let reach = a.lower_bands + a.upper_bands;
+        for k in (0..n).rev() {
+            let coupled: f64 = (k + 1..=(k + reach).min(n - 1)).map(|col| a.at(k, col) * x[col]).sum();
+            x[k] = (x[k] - coupled) / a.at(k, k);
+        }
+        Ok(x)
+    }
+}
+
+/// Solve a small dense system A X = B by Gaussian elimination with partial pivoting
+///
+/// A is n×n and B is n×columns, both row-major.
+pub fn solve_dense(a: &[f64], b: &[f64], n: usize, columns: usize) -> Result<Vec<f64>, String> {
+    let (mut a, mut b) = (a.to_vec(), b.to_vec());
+    solve_dense_in_place(&mut a, &mut b, n, columns)?;
+    Ok(b)
+}
+
+/// Solve a small dense system A X = B in place: A is overwritten by its elimination and B by the solution X
+pub fn solve_dense_in_place(a: &mut [f64], b: &mut [f64], n: usize, columns: usize) -> Result<(), String> {
+    for col in 0..n {
+        let pivot_row = (col..n)
+            .max_by(|&i, &j| a[i * n + col].abs().total_cmp(&a[j * n + col].abs()))
+            .unwrap_or(col);
+        if a[pivot_row * n + col].abs() < 1e-300 {
+            return Err("Singular block in linear system".to_string());
+        }
+        if pivot_row != col {
+            for c in 0..n {
+                a.swap(col * n + c, pivot_row * n + c);
+            }
+            for c in 0..columns {
+                b.swap(col * columns + c, pivot_row * columns + c);
+            }
+        }
+
+        for row in col + 1..n {
+            let factor = a[row * n + col] / a[col * n + col];
+            if factor == 0.0 {
+                continue;
+            }
+            for c in col..n {
+                a[row * n + c] -= factor * a[col * n + c];
+            }
+            for c in 0..columns {
+                b[row * columns + c] -= factor * b[col * columns + c];
+            }
+        }
+    }
+
+    for col in (0..n).rev() {
+        for c in 0..columns {
+            let mut value = b[col * columns + c];
+            for k in col + 1..n {
+                value -= a[col * n + k] * b[k * columns + c];
+            }
+            b[col * columns + c] = value / a[col * n + col];
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Multiply the (optionally cyclic) tridiagonal system by x
+    fn multiply_tridiagonal(lower: &[f64], diag: &[f64], upper: &[f64], x: &[f64], cyclic: bool) -> Vec<f64> {
+        let n = diag.len();
+        (0..n)
+            .map(|i| {
+                let mut value = diag[i] * x[i];
+                if i > 0 || cyclic {
+                    value += lower[i] * x[(i + n - 1) % n];
+                }
+                if i + 1 < n || cyclic {
+                    value += upper[i] * x[(i + 1) % n];
+                }
+                value
+            })
+            .collect()
+    }
+
+    fn assert_close(x: &[f64], expected: &[f64], tolerance: f64) {
+        assert_eq!(x.len(), expected.len());
+        for (i, (value, target)) in x.iter().zip(expected).enumerate() {
+            assert!((value - target).abs() < tolerance, "x[{}] = {} vs {}", i, value, target);
+        }
+    }
+
+    fn test_bands(n: usize) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+        let lower = (0..n).map(|i| 1.0 + 0.1 * (i as f64).sin()).collect();
+        let diag = (0..n).map(|i| -4.0 - 0.05 * i as f64).collect();
+        let upper = (0..n).map(|i| 0.8 + 0.2 * (i as f64).cos()).collect();
+        (lower, diag, upper)
+    }
+
+    #[test]
+    fn test_thomas() {
+        // Classic system [-2 1; 1 -2 1; 1 -2]
+        let x = thomas(&[0.0, 1.0, 1.0], &[-2.0; 3], &[1.0, 1.0, 0.0], &[-1.0, 0.0, -1.0]).unwrap();
+        assert_close(&x, &[1.0; 3], 1e-12);
+
+        let (lower, diag, upper) = test_bands(50);
+        let expected: Vec<f64> = (0..50).map(|i| (i as f64 * 0.3).sin()).collect();
+        let rhs = multiply_tridiagonal(&lower, &diag, &upper, &expected, false);
+        assert_close(&thomas(&lower, &diag, &upper, &rhs).unwrap(), &expected, 1e-12);
+
+        assert_close(&thomas(&[0.0], &[2.0], &[0.0], &[3.0]).unwrap(), &[1.5], 1e-15);
+    }
+
+    #[test]
+    fn test_thomas_errors() {
+        assert!(thomas(&[], &[], &[], &[]).is_err());
+        assert!(thomas(&[0.0, 1.0], &[1.0, 1.0], &[1.0, 0.0], &[1.0]).is_err());
+        // [1 1; 1 1] is singular
+        assert!(thomas(&[0.0, 1.0], &[1.0, 1.0], &[1.0, 0.0], &[1.0, 2.0]).is_err());
+    }
+
+    #[test]
+    fn test_cyclic_tridiagonal() {
+        for n in [1, 2, 3, 4, 9, 40] {
+            let (lower, diag, upper) = test_bands(n);
+            let expected: Vec<f64> = (0..n).map(|i| (i as f64 * 0.7).cos()).collect();
+            let rhs = multiply_tridiagonal(&lower, &diag, &upper, &expected, true);
+            let x = cyclic_tridiagonal(&lower, &diag, &upper, &rhs).unwrap();
+            assert_close(&x, &expected, 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_cyclic_zero_first_diagonal() {
+        // Periodic system with a zero first diagonal entry, still non-singular
+        let lower = [1.0, 1.0, 0.5, 1.0];
+        let diag = [0.0, 3.0, 4.0, 3.0];
+        let upper = [2.0, 0.5, 1.0, 1.0];
+        let expected = [1.0, -2.0, 0.5, 3.0];
+        let rhs = multiply_tridiagonal(&lower, &diag, &upper, &expected, true);
+        assert_close(&cyclic_tridiagonal(&lower, &diag, &upper, &rhs).unwrap(), &expected, 1e-12);
+    }
+
+    #[test]
+    fn test_cyclic_singular() {
+        // Periodic second difference: constants are in the null space
+        let n = 6;
+        assert!(cyclic_tridiagonal(&vec![1.0; n], &vec![-2.0; n], &vec![1.0; n], &vec![0.0; n]).is_err());
+    }
+
+    #[test]
+    fn test_banded_matrix_entries() {
+        let mut matrix = BandedMatrix::new(5, 1, 2);
+        assert!(matrix.set(2, 1, 3.0).is_ok());
+        assert!(matrix.set(2, 4, 4.0).is_ok());
+        assert!(matrix.set(2, 0, 1.0).is_err());
+        assert!(matrix.set(0, 3, 1.0).is_err());
+        assert!(matrix.set(5, 5, 1.0).is_err());
+        assert_eq!(matrix.get(2, 1), 3.0);
+        assert_eq!(matrix.get(2, 4), 4.0);
+        assert_eq!(matrix.get(2, 0), 0.0);
+        assert_eq!(matrix.multiply(&[1.0, 1.0, 1.0, 1.0, 2.0])[2], 11.0);
+    }
+
+    #[test]
+    fn test_banded_matches_thomas() {
+        let n = 30;
+        let (lower, diag, upper) = test_bands(n);
+        let mut matrix = BandedMatrix::new(n, 1, 1);
+        for i in 0..n {
+            matrix.set(i, i, diag[i]).unwrap();
+            if i > 0 {
+                matrix.set(i, i - 1, lower[i]).unwrap();
+            }
+            if i + 1 < n {
+                matrix.set(i, i + 1, upper[i]).unwrap();
+            }
+        }
+        let rhs: Vec<f64> = (0..n).map(|i| 1.0 + i as f64 * 0.1).collect();
+        assert_close(&matrix.solve(&rhs).unwrap(), &thomas(&lower, &diag, &upper, &rhs).unwrap(), 1e-12);
+    }
+
+    #[test]
+    fn test_banded_pivoting() {
+        // Pentadiagonal, not diagonally dominant and with zeros on the diagonal: needs row exchanges
+        let n = 12;
+        let mut matrix = BandedMatrix::new(n, 2, 2);
+        for i in 0..n {
+            for j in i.saturating_sub(2)..=(i + 2).min(n - 1) {
+                let value = if i == j && i % 3 == 0 { 0.0 } else { ((i * 7 + j * 3) % 11) as f64 - 5.0 };
+                matrix.set(i, j, value).unwrap();
+            }
+        }
+        let expected: Vec<f64> = (0..n).map(|i| (i as f64 + 1.0).ln()).collect();
+        let rhs = matrix.multiply(&expected);
+        let lu = matrix.lu().unwrap();
+        assert_close(&lu.solve(&rhs).unwrap(), &expected, 1e-10);
+
+        // The factors are reused for another right-hand side
+        let other: Vec<f64> = (0..n).map(|i| (i as f64).cos()).collect();
+        assert_close(&lu.solve(&matrix.multiply(&other)).unwrap(), &other, 1e-10);
+        assert!(lu.solve(&rhs[1..]).is_err());
+    }
+
+    #[test]
+    fn test_banded_asymmetric_bands() {
+        let n = 15;
+        let mut matrix = BandedMatrix::new(n, 3, 1);
+        for i in 0..n {
+            for j in i.saturating_sub(3)..=(i + 1).min(n - 1) {
+                matrix.set(i, j, if i == j { 6.0 } else { 1.0 + 0.1 * (i + j) as f64 }).unwrap();
+            }
+        }
+        let expected: Vec<f64> = (0..n).map(|i| i as f64 - 7.0).collect();
+        assert_close(&matrix.solve(&matrix.multiply(&expected)).unwrap(), &expected, 1e-10);
+    }
+
+    #[test]
+    fn test_banded_singular() {
+        let mut matrix = BandedMatrix::new(3, 1, 1);
+        for i in 0..3usize {
+            for j in i.saturating_sub(1)..=(i + 1).min(2) {
+                matrix.set(i, j, 1.0).unwrap();
+            }
+        }
+        // The first two rows are equal
+        matrix.set(1, 2, 0.0).unwrap();
+        assert!(matrix.lu().is_err());
+        assert!(BandedMatrix::new(0, 1, 1).lu().is_err());
+    }
+
+    #[test]
+    fn test_solve_dense() {
+        // Requires pivoting: the first diagonal entry is zero
+        let x = solve_dense(&[0.0, 1.0, 2.0, 1.0], &[3.0, 4.0], 2, 1).unwrap();
+        assert!((x[0] - 0.5).abs() < 1e-12);
+        assert!((x[1] - 3.0).abs() < 1e-12);
+
+        assert!(solve_dense(&[1.0, 2.0, 2.0, 4.0], &[1.0, 1.0], 2, 1).is_err());
+    }
+}
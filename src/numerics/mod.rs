@@ -0,0 +1,3 @@
+pub mod linalg;
+
+pub use linalg::{BandedLu, BandedMatrix, cyclic_tridiagonal, solve_dense, solve_dense_in_place, thomas, thomas_into};
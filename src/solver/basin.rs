@@ -0,0 +1,990 @@
+use crate::solver::grid::Grid2D;
+use crate::solver::harbor::HarborStructure;
+use crate::solver::wave_solver::sub_step;
+use crate::waves::WaveForcing;
+use serde::{Deserialize, Serialize};
+
+/// Minimum total water depth used in fluxes and celerities [m]
+const MIN_DEPTH: f64 = 1e-6;
+
+/// Sponge layers along the edges of a wave basin
+///
+/// The wavemaker stands along the west edge (x = 0); the other edges are walls, or absorb the waves
+/// over a relaxation zone in front of the wall, with a damping rate increasing quadratically towards it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BasinSponges {
+    pub width: f64,    // Width of the damping zones [m]
+    pub strength: f64, // Maximum damping rate at the walls [1/s]
+    pub east: bool,    // Absorb at the far end, opposite the wavemaker
+    pub north: bool,   // Absorb along the side y = width
+    pub south: bool,   // Absorb along the side y = 0
+}
+
+impl Default for BasinSponges {
+    fn default() -> Self {
+        Self {
+            width: 5.0,
+            strength: 5.0,
+            east: true,
+            north: false,
+            south: false,
+        }
+    }
+}
+
+impl BasinSponges {
+    /// Damping rate at point (x, y) of the basin [1/s], the largest of the overlapping zones
+    pub fn damping_rate(&self, x: f64, y: f64, grid: &Grid2D) -> f64 {
+        if self.width <= 0.0 {
+            return 0.0;
+        }
+        let rate = |distance: f64| {
+            let relative = (1.0 - distance / self.width).clamp(0.0, 1.0);
+            self.strength * relative * relative
+        };
+        let mut damping: f64 = 0.0;
+        if self.east {
+            damping = damping.max(rate(grid.length() - x));
+        }
+        if self.north {
+            damping = damping.max(rate(grid.width() - y));
+        }
+        if self.south {
+            damping = damping.max(rate(y));
+        }
+        damping
+    }
+
+    /// Validate the sponges against the basin they line
+    pub fn validate(&self, grid: &Grid2D) -> Result<(), String> {
+        let mut limit = if self.east { grid.length() } else { f64::INFINITY };
+        if self.north || self.south {
+            limit = limit.min(if self.north && self.south { 0.5 * grid.width() } else { grid.width() });
+        }
+        if !(self.width.is_finite() && self.width > 0.0 && self.width < limit) {
+            return Err(format!("Sponge width must be between 0 and {:.1} m", limit));
+        }
+        if !(self.strength.is_finite() && self.strength >= 0.0) {
+            return Err("Sponge strength must be non-negative".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Solid obstacle in a wave basin, such as a breakwater or a pile
+/// Cells whose center lies inside are removed from the computation
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Obstacle {
+    /// Rectangle aligned with the basin axes [m]
+    Rectangle { x_min: f64, x_max: f64, y_min: f64, y_max: f64 },
+    /// Vertical circular cylinder [m]
+    Cylinder { x: f64, y: f64, radius: f64 },
+}
+
+impl Obstacle {
+    /// Check if point (x, y) lies inside the obstacle
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        match *self {
+            Obstacle::Rectangle { x_min, x_max, y_min, y_max } => (x_min..=x_max).contains(&x) && (y_min..=y_max).contains(&y),
+            Obstacle::Cylinder { x: cx, y: cy, radius } => (x - cx).hypot(y - cy) <= radius,
+        }
+    }
+
+    /// Check that the obstacle has a positive size
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            Obstacle::Rectangle { x_min, x_max, y_min, y_max } => {
+                if !(x_max > x_min && y_max > y_min) {
+                    return Err("Obstacle rectangle must have a positive length and width".to_string());
+                }
+            }
+            Obstacle::Cylinder { radius, .. } => {
+                if !(radius.is_finite() && radius > 0.0) {
+                    return Err("Obstacle cylinder must have a positive radius".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Obstacle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Obstacle::Rectangle { .. } => write!(f, "Rectangle"),
+            Obstacle::Cylinder { .. } => write!(f, "Cylinder"),
+        }
+    }
+}
+
+/// Cell-centered field of a basin shown in plan view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BasinField {
+    /// Surface elevation (η) [m]
+    #[default]
+    SurfaceElevation,
+    /// Magnitude of the depth-averaged velocity (|u|) [m/s]
+    Speed,
+}
+
+impl std::fmt::Display for BasinField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BasinField::SurfaceElevation => write!(f, "Surface Elevation (η)"),
+            BasinField::Speed => write!(f, "Current Speed (|u|)"),
+        }
+    }
+}
+
+/// Fields of a basin at one instant: η, u and v at the cell centers, one field after the other
+/// This is the layout of the snapshots of a basin stored for playback
+#[derive(Debug, Clone, Copy)]
+pub struct BasinFrame<'a> {
+    grid: &'a Grid2D,
+    values: &'a [f64],
+}
+
+impl<'a> BasinFrame<'a> {
+    /// View the stored values of a basin on its grid, None if they do not cover its cells
+    pub fn new(grid: &'a Grid2D, values: &'a [f64]) -> Option<Self> {
+        (values.len() == 3 * grid.cells()).then_some(Self { grid, values })
+    }
+
+    /// Grid the fields are stored on
+    pub fn grid(&self) -> &'a Grid2D {
+        self.grid
+    }
+
+    /// Surface elevation at the cell centers [m]
+    pub fn eta(&self) -> &'a [f64] {
+        &self.values[..self.grid.cells()]
+    }
+
+    /// Velocity along x at the cell centers [m/s]
+    pub fn u(&self) -> &'a [f64] {
+        &self.values[self.grid.cells()..2 * self.grid.cells()]
+    }
+
+    /// Velocity along y at the cell centers [m/s]
+    pub fn v(&self) -> &'a [f64] {
+        &self.values[2 * self.grid.cells()..]
+    }
+
+    /// Values of a field at the cell centers
+    pub fn field(&self, field: BasinField) -> Vec<f64> {
+        match field {
+            BasinField::SurfaceElevation => self.eta().to_vec(),
+            BasinField::Speed => self.u().iter().zip(self.v()).map(|(u, v)| u.hypot(*v)).collect(),
+        }
+    }
+
+    /// Value of a field at point (x, y), bilinearly interpolated between the cell centers
+    pub fn value_at(&self, field: BasinField, x: f64, y: f64) -> f64 {
+        match field {
+            BasinField::SurfaceElevation => self.grid.interpolate_centers(self.eta(), x, y),
+            BasinField::Speed => {
+                let (u, v) = self.velocity_at(x, y);
+                u.hypot(v)
+            }
+        }
+    }
+
+    /// Depth-averaged velocity (u, v) at point (x, y), bilinearly interpolated between the cell centers [m/s]
+    pub fn velocity_at(&self, x: f64, y: f64) -> (f64, f64) {
+        (self.grid.interpolate_centers(self.u(), x, y), self.grid.interpolate_centers(self.v(), x, y))
+    }
+}
+
+/// Staggered-grid 2DH shallow water solver of a rectangular wave basin
+///
+/// Surface elevation (η) lives at cell centers, the depth-averaged velocities u and v on the faces
+/// normal to them (Arakawa C-grid). Time stepping is forward-backward: continuity is updated first,
+/// then momentum uses the new η. The wavemaker along the west edge drives the long wave velocity of
+/// the boundary sources of the wave forcing, each in its own direction; the other edges are walls,
+/// lined with sponge layers where selected. Harbor structures reflect a share of the waves running into
+/// them, obstacles all of it. With active absorption the wavemaker also absorbs the waves
+/// coming back to it, reflected by the obstacles. The still water depth may vary over the cells, as over an
+/// imported bathymetry; land, obstacle and structure cells are dry.
+/// The equations are linear unless nonlinear mode adds the total depth to the fluxes and upwinded advection.
+pub struct BasinSolver {
+    /// Computational grid
+    grid: Grid2D,
+    /// Still water depth at cell centers (d) [m]
+    depth: Vec<f64>,
+    /// Surface elevation at cell centers (η) [m]
+    eta: Vec<f64>,
+    /// Surface elevation at the start of the run [m]
+    initial_eta: Vec<f64>,
+    /// Velocity along x on the faces between columns, (nx + 1) per row [m/s]
+    u: Vec<f64>,
+    /// Velocity along y on the faces between rows, nx per row of faces [m/s]
+    v: Vec<f64>,
+    /// Cells inside an obstacle
+    solid: Vec<bool>,
+    /// Obstacles in the basin
+    obstacles: Vec<Obstacle>,
+    /// Harbor structures in the basin
+    structures: Vec<HarborStructure>,
+    /// Factor α of the velocity let into each solid cell by the waves running into it, zero for reflecting walls
+    absorption: Vec<f64>,
+    /// Sponge layers, None for reflecting walls all around
+    sponges: Option<BasinSponges>,
+    /// Include the total depth in the fluxes and the advection of momentum
+    nonlinear: bool,
+    /// Absorb the waves travelling back to the wavemaker
+    active_absorption: bool,
+    /// Gravitational acceleration [m/s²]
+    gravity: f64,
+    /// Courant number of the time step
+    cfl: f64,
+    /// Current simulation time [s]
+    time: f64,
+}
+
+impl BasinSolver {
+    /// Create a basin of given length (x) and width (y) with nx × ny cells and a uniform still water depth
+    pub fn new(length: f64, width: f64, nx: usize, ny: usize, depth: f64) -> Result<Self, String> {
+        let grid = Grid2D::new(length, width, nx, ny)?;
+        if !(depth.is_finite() && depth > 0.0) {
+            return Err("Water depth must be positive".to_string());
+        }
+        let cells = grid.cells();
+        Ok(Self {
+            grid,
+            depth: vec![depth; cells],
+            eta: vec![0.0; cells],
+            initial_eta: vec![0.0; cells],
+            u: vec![0.0; (nx + 1) * ny],
+            v: vec![0.0; nx * (ny + 1)],
+            solid: vec![false; cells],
+            obstacles: Vec::new(),
+            structures: Vec::new(),
+            absorption: vec![0.0; cells],
+            sponges: Some(BasinSponges::default()),
+            nonlinear: false,
+            active_absorption: false,
+            gravity: 9.81,
+            cfl: 0.5,
+            time: 0.0,
+        })
+    }
+
+    /// Replace the uniform still water depth by a depth per cell, row by row from the south-west corner
+    /// Cells with a depth of zero or less are land: dry, with reflecting faces like the obstacles
+    pub fn with_depths(mut self, depths: Vec<f64>) -> Result<Self, String> {
+        if depths.len() != self.grid.cells() {
+            return Err(format!("Basin of {} cells has {} depths", self.grid.cells(), depths.len()));
+        }
+        if depths.iter().any(|depth| !depth.is_finite()) {
+            return Err("Water depths must be finite".to_string());
+        }
+        self.depth = depths.into_iter().map(|depth| depth.max(0.0)).collect();
+        self.place_solids()?;
+        Ok(self)
+    }
+
+    /// Line the basin with sponge layers, or None for reflecting walls all around
+    pub fn with_sponges(mut self, sponges: Option<BasinSponges>) -> Result<Self, String> {
+        if let Some(sponges) = &sponges {
+            sponges.validate(&self.grid)?;
+        }
+        self.sponges = sponges;
+        Ok(self)
+    }
+
+    /// Place solid obstacles in the basin
+    pub fn with_obstacles(mut self, obstacles: Vec<Obstacle>) -> Result<Self, String> {
+        for obstacle in &obstacles {
+            obstacle.validate()?;
+        }
+        self.obstacles = obstacles;
+        self.place_solids()?;
+        Ok(self)
+    }
+
+    /// Place harbor structures in the basin, partially reflecting the waves
+    pub fn with_structures(mut self, structures: Vec<HarborStructure>) -> Result<Self, String> {
+        for structure in &structures {
+            structure.validate()?;
+        }
+        self.structures = structures;
+        self.place_solids()?;
+        Ok(self)
+    }
+
+    /// Switch between the linear and nonlinear shallow water equations
+    pub fn with_nonlinear(mut self, nonlinear: bool) -> Self {
+        self.nonlinear = nonlinear;
+        self
+    }
+
+    /// Switch the active absorption of the waves reaching the wavemaker on or off
+    pub fn with_active_absorption(mut self, active_absorption: bool) -> Self {
+        self.active_absorption = active_absorption;
+        self
+    }
+
+    /// Start from a surface elevation η(x, y) instead of still water
+    pub fn with_initial_surface(mut self, surface: impl Fn(f64, f64) -> f64) -> Self {
+        let nx = self.grid.nx();
+        self.initial_eta = (0..self.grid.cells())
+            .map(|n| {
+                let (x, y) = self.grid.center(n % nx, n / nx);
+                if self.solid[n] { 0.0 } else { surface(x, y) }
+            })
+            .collect();
+        self.reset();
+        self
+    }
+
+    /// Return to the initial surface at rest and time zero
+    pub fn reset(&mut self) {
+        self.eta = self.initial_eta.iter().zip(&self.solid).map(|(&eta, &solid)| if solid { 0.0 } else { eta }).collect();
+        self.u.iter_mut().for_each(|u| *u = 0.0);
+        self.v.iter_mut().for_each(|v| *v = 0.0);
+        self.time = 0.0;
+    }
+
+    /// Computational grid
+    pub fn grid(&self) -> &Grid2D {
+        &self.grid
+    }
+
+    /// Surface elevation at the cell centers, row by row from the south-west corner [m]
+    pub fn eta(&self) -> &[f64] {
+        &self.eta
+    }
+
+    /// Velocities along x on the faces between columns, (nx + 1) per row [m/s]
+    pub fn u(&self) -> &[f64] {
+        &self.u
+    }
+
+    /// Velocities along y on the faces between rows, nx per row of faces [m/s]
+    pub fn v(&self) -> &[f64] {
+        &self.v
+    }
+
+    /// Still water depth at the cell centers [m]
+    pub fn depth(&self) -> &[f64] {
+        &self.depth
+    }
+
+    /// Check if cell (i, j) lies inside an obstacle or on land
+    pub fn is_solid(&self, i: usize, j: usize) -> bool {
+        self.solid[self.grid.index(i, j)]
+    }
+
+    /// Obstacles in the basin
+    pub fn obstacles(&self) -> &[Obstacle] {
+        &self.obstacles
+    }
+
+    /// Sponge layers, None for reflecting walls all around
+    pub fn sponges(&self) -> Option<&BasinSponges> {
+        self.sponges.as_ref()
+    }
+
+    /// Harbor structures in the basin
+    pub fn structures(&self) -> &[HarborStructure] {
+        &self.structures
+    }
+
+    /// Check if the nonlinear equations are solved
+    pub fn is_nonlinear(&self) -> bool {
+        self.nonlinear
+    }
+
+    /// Check if the wavemaker absorbs the waves travelling back to it
+    pub fn has_active_absorption(&self) -> bool {
+        self.active_absorption
+    }
+
+    /// Current simulation time [s]
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Surface elevation at point (x, y), bilinearly interpolated between the cell centers [m]
+    pub fn surface_elevation_at(&self, x: f64, y: f64) -> f64 {
+        self.grid.interpolate_centers(&self.eta, x, y)
+    }
+
+    /// Depth-averaged velocity (u, v) at the center of the cell containing point (x, y) [m/s]
+    pub fn velocity_at(&self, x: f64, y: f64) -> (f64, f64) {
+        let (i, j) = self.grid.cell_at(x, y);
+        let u = 0.5 * (self.u[self.u_index(i, j)] + self.u[self.u_index(i + 1, j)]);
+        let v = 0.5 * (self.v[self.v_index(i, j)] + self.v[self.v_index(i, j + 1)]);
+        (u, v)
+    }
+
+    /// Water volume above the still water level [m³]
+    pub fn volume(&self) -> f64 {
+        let area = self.grid.dx() * self.grid.dy();
+        self.eta.iter().zip(&self.solid).filter(|&(_, &solid)| !solid).map(|(eta, _)| eta * area).sum()
+    }
+
+    /// Wave energy in the basin per unit water density, ½ g η² + ½ d (u² + v²) over the wet cells [m⁵/s²]
+    pub fn energy(&self) -> f64 {
+        let area = self.grid.dx() * self.grid.dy();
+        let (nx, ny) = (self.grid.nx(), self.grid.ny());
+        let mut energy = 0.0;
+        for j in 0..ny {
+            for i in 0..nx {
+                let n = self.grid.index(i, j);
+                if self.solid[n] {
+                    continue;
+                }
+                let u = 0.5 * (self.u[self.u_index(i, j)] + self.u[self.u_index(i + 1, j)]);
+                let v = 0.5 * (self.v[self.v_index(i, j)] + self.v[self.v_index(i, j + 1)]);
+                energy += 0.5 * (self.gravity * self.eta[n] * self.eta[n] + self.depth[n] * (u * u + v * v)) * area;
+            }
+        }
+        energy
+    }
+
+    /// η, u and v at the cell centers, one field after the other, to store for playback (see `BasinFrame`)
+    pub fn snapshot(&self) -> Vec<f64> {
+        let mut values = Vec::with_capacity(3 * self.grid.cells());
+        self.fill_snapshot(&mut values);
+        values
+    }
+
+    /// Refill a buffer in place with the snapshot of the basin, keeping its memory
+    pub fn fill_snapshot(&self, values: &mut Vec<f64>) {
+        let (nx, ny) = (self.grid.nx(), self.grid.ny());
+        values.clear();
+        values.extend_from_slice(&self.eta);
+        values.extend((0..ny).flat_map(|j| (0..nx).map(move |i| (i, j))).map(|(i, j)| 0.5 * (self.u[self.u_index(i, j)] + self.u[self.u_index(i + 1, j)])));
+        values.extend((0..ny).flat_map(|j| (0..nx).map(move |i| (i, j))).map(|(i, j)| 0.5 * (self.v[self.v_index(i, j)] + self.v[self.v_index(i, j + 1)])));
+    }
+
+    /// Outline of the obstacles: the cell edges between water and solid cells, merged into straight segments [m]
+    pub fn coastline(&self) -> Vec<[[f64; 2]; 2]> {
+        let (nx, ny) = (self.grid.nx(), self.grid.ny());
+        let (dx, dy) = (self.grid.dx(), self.grid.dy());
+        let mut segments = Vec::new();
+        // Runs of consecutive edges along a line of faces
+        let mut add_runs = |edges: &mut dyn Iterator<Item = bool>, segment: &dyn Fn(usize, usize) -> [[f64; 2]; 2]| {
+            let mut start = None;
+            let mut count = 0;
+            for (k, edge) in edges.enumerate() {
+                match (edge, start) {
+                    (true, None) => start = Some(k),
+                    (false, Some(first)) => {
+                        segments.push(segment(first, k));
+                        start = None;
+                    }
+                    _ => {}
+                }
+                count = k + 1;
+            }
+            if let Some(first) = start {
+                segments.push(segment(first, count));
+            }
+        };
+        for i in 1..nx {
+            let x = i as f64 * dx;
+            add_runs(&mut (0..ny).map(|j| self.is_solid(i - 1, j) != self.is_solid(i, j)), &|first, last| [[x, first as f64 * dy], [x, last as f64 * dy]]);
+        }
+        for j in 1..ny {
+            let y = j as f64 * dy;
+            add_runs(&mut (0..nx).map(|i| self.is_solid(i, j - 1) != self.is_solid(i, j)), &|first, last| [[first as f64 * dx, y], [last as f64 * dx, y]]);
+        }
+        segments
+    }
+
+    /// Largest stable time step for the current flow [s]
+    /// Long waves and the flow together cross less than a Courant number of cells in each direction
+    pub fn stable_time_step(&self) -> f64 {
+        let (nx, ny) = (self.grid.nx(), self.grid.ny());
+        let mut rate: f64 = 0.0;
+        for j in 0..ny {
+            for i in 0..nx {
+                let n = self.grid.index(i, j);
+                if self.solid[n] {
+                    continue;
+                }
+                let celerity = (self.gravity * (self.depth[n] + self.eta[n]).max(MIN_DEPTH)).sqrt();
+                let u = self.u[self.u_index(i, j)].abs().max(self.u[self.u_index(i + 1, j)].abs());
+                let v = self.v[self.v_index(i, j)].abs().max(self.v[self.v_index(i, j + 1)].abs());
+                rate = rate.max((celerity + u) / self.grid.dx() + (celerity + v) / self.grid.dy());
+            }
+        }
+        self.cfl / rate
+    }
+
+    /// Advance the solution by a duration, with as many stable time steps as needed
+    /// Returns the number of steps taken
+    pub fn advance(&mut self, duration: f64, forcing: &mut WaveForcing) -> usize {
+        let end = self.time + duration;
+        let mut steps = 0;
+        while end - self.time > 1e-12 * duration.max(1.0) {
+            let remaining = end - self.time;
+            let Some(dt) = sub_step(remaining, self.stable_time_step()) else {
+                break;
+            };
+            self.step(dt, forcing);
+            steps += 1;
+        }
+        steps
+    }
+
+    /// Advance the solution by a single time step
+    pub fn step(&mut self, dt: f64, forcing: &mut WaveForcing) {
+        forcing.update_time(self.time);
+        self.apply_wavemaker(forcing);
+        self.continuity(dt);
+        self.momentum(dt);
+        self.apply_sponges(dt);
+        self.time += dt;
+    }
+
+    /// Index of the x face west of cell (i, j); i = nx is the east wall
+    fn u_index(&self, i: usize, j: usize) -> usize {
+        j * (self.grid.nx() + 1) + i
+    }
+
+    /// Index of the y face south of cell (i, j); j = ny is the north wall
+    fn v_index(&self, i: usize, j: usize) -> usize {
+        j * self.grid.nx() + i
+    }
+
+    /// Paddle velocity along the west edge: the normal long wave velocity √(g/d) η cos θ of each linear boundary source,
+    /// or the depth-averaged velocity of its stream function wave, which is long-crested
+    /// Waves at an angle θ delay the signal of each paddle by ky y, with ky = k sin θ and k = ω/√(gd) the wave number of
+    /// the basin (snake principle), so the crests leave the wavemaker in the direction of their source
+    /// Active absorption takes the difference between the surface in front of the paddle and the target as a wave
+    /// travelling back to it, and absorbs it by adding the velocity it carries, −√(g/d) η_R, as in the channel
+    fn apply_wavemaker(&mut self, forcing: &WaveForcing) {
+        for j in 0..self.grid.ny() {
+            let n = self.grid.index(0, j);
+            let velocity = if self.solid[n] {
+                0.0
+            } else {
+                let (_, y) = self.grid.center(0, j);
+                let celerity = (self.gravity * self.depth[n]).sqrt();
+                let (velocity, target) = forcing
+                    .active_boundary_sources()
+                    .map(|source| match source.layer_velocity(0, 1) {
+                        Some(velocity) => (velocity, source.applicator().boundary_surface_elevation()),
+                        None => {
+                            let direction = source.direction();
+                            let wave_number = source.parameters().omega / celerity;
+                            let elevation = source.shifted_surface_elevation(wave_number * direction.sin() * y);
+                            (celerity / self.depth[n] * elevation * direction.cos(), elevation)
+                        }
+                    })
+                    .fold((0.0, 0.0), |(velocity, target), (u, eta)| (velocity + u, target + eta));
+                if self.active_absorption { velocity - celerity / self.depth[n] * (self.eta[n] - target) } else { velocity }
+            };
+            let face = self.u_index(0, j);
+            self.u[face] = velocity;
+        }
+    }
+
+    /// Remove the land cells and those covered by the obstacles and structures, fully reflecting land and obstacles over structures
+    fn place_solids(&mut self) -> Result<(), String> {
+        let (mut solid, mut absorption) = (vec![false; self.grid.cells()], vec![0.0; self.grid.cells()]);
+        for n in 0..self.grid.cells() {
+            let (x, y) = self.grid.center(n % self.grid.nx(), n / self.grid.nx());
+            if self.depth[n] <= 0.0 || self.obstacles.iter().any(|obstacle| obstacle.contains(x, y)) {
+                solid[n] = true;
+            } else if let Some(structure) = self.structures.iter().rev().find(|structure| structure.contains(x, y)) {
+                solid[n] = true;
+                absorption[n] = structure.absorption();
+            }
+        }
+        if solid.iter().all(|&solid| solid) {
+            return Err("Obstacles and land cover the whole basin".to_string());
+        }
+        (self.solid, self.absorption) = (solid, absorption);
+        self.reset();
+        Ok(())
+    }
+
+    /// Velocity through a face between a water cell and a solid one, b on the positive side of a
+    /// Zero on a reflecting wall; into a partially reflecting structure, the velocity α √(g/d) η of the
+    /// wave running into it from the water cell, so that a share R = (1 − α)/(1 + α) comes back
+    fn wall_velocity(&self, a: usize, b: usize) -> f64 {
+        let speed = |wet: usize, wall: usize| self.absorption[wall] * (self.gravity / self.depth[wet]).sqrt() * self.eta[wet];
+        match (self.solid[a], self.solid[b]) {
+            (false, true) => speed(a, b),
+            (true, false) => -speed(b, a),
+            _ => 0.0,
+        }
+    }
+
+    /// Water depth carried through a face between cells a and b by a velocity
+    /// Linear: the mean still water depth; nonlinear: the total depth of the upwind cell
+    fn face_depth(&self, a: usize, b: usize, velocity: f64) -> f64 {
+        if self.nonlinear {
+            let upwind = if velocity >= 0.0 { a } else { b };
+            (self.depth[upwind] + self.eta[upwind]).max(MIN_DEPTH)
+        } else {
+            0.5 * (self.depth[a] + self.depth[b])
+        }
+    }
+
+    /// Continuity ∂η/∂t + ∂(hu)/∂x + ∂(hv)/∂y = 0 over a time step from the current velocities
+    fn continuity(&mut self, dt: f64) {
+        let (nx, ny) = (self.grid.nx(), self.grid.ny());
+        let flux_x: Vec<f64> = (0..ny)
+            .flat_map(|j| (0..=nx).map(move |i| (i, j)))
+            .map(|(i, j)| {
+                let velocity = self.u[self.u_index(i, j)];
+                let (a, b) = (self.grid.index(i.saturating_sub(1), j), self.grid.index(i.min(nx - 1), j));
+                self.face_depth(a, b, velocity) * velocity
+            })
+            .collect();
+        let flux_y: Vec<f64> = (0..=ny)
+            .flat_map(|j| (0..nx).map(move |i| (i, j)))
+            .map(|(i, j)| {
+                let velocity = self.v[self.v_index(i, j)];
+                let (a, b) = (self.grid.index(i, j.saturating_sub(1)), self.grid.index(i, j.min(ny - 1)));
+                self.face_depth(a, b, velocity) * velocity
+            })
+            .collect();
+        let (dx, dy) = (self.grid.dx(), self.grid.dy());
+        for j in 0..ny {
+            for i in 0..nx {
+                let n = self.grid.index(i, j);
+                if self.solid[n] {
+                    continue;
+                }
+                let divergence = (flux_x[self.u_index(i + 1, j)] - flux_x[self.u_index(i, j)]) / dx
+                    + (flux_y[self.v_index(i, j + 1)] - flux_y[self.v_index(i, j)]) / dy;
+                self.eta[n] -= dt * divergence;
+            }
+        }
+    }
+
+    /// Momentum from the new surface elevation, with upwinded advection in nonlinear mode
+    /// Faces on the walls and next to an obstacle stay closed, those of partially reflecting structures let the incoming waves through
+    fn momentum(&mut self, dt: f64) {
+        let (nx, ny) = (self.grid.nx(), self.grid.ny());
+        let (dx, dy) = (self.grid.dx(), self.grid.dy());
+        let (u, v) = (self.u.clone(), self.v.clone());
+        let g = self.gravity;
+
+        for j in 0..ny {
+            for i in 1..nx {
+                let face = self.u_index(i, j);
+                let (west, east) = (self.grid.index(i - 1, j), self.grid.index(i, j));
+                if self.solid[west] || self.solid[east] {
+                    self.u[face] = self.wall_velocity(west, east);
+                    continue;
+                }
+                let mut acceleration = -g * (self.eta[east] - self.eta[west]) / dx;
+                if self.nonlinear {
+                    let velocity = u[face];
+                    let du_dx = (if velocity >= 0.0 { velocity - u[face - 1] } else { u[face + 1] - velocity }) / dx;
+                    let cross = 0.25 * (v[self.v_index(i - 1, j)] + v[self.v_index(i, j)] + v[self.v_index(i - 1, j + 1)] + v[self.v_index(i, j + 1)]);
+                    let du_dy = match (cross >= 0.0, j) {
+                        (true, 0) => 0.0,
+                        (true, _) => (velocity - u[self.u_index(i, j - 1)]) / dy,
+                        (false, j) if j == ny - 1 => 0.0,
+                        (false, _) => (u[self.u_index(i, j + 1)] - velocity) / dy,
+                    };
+                    acceleration -= velocity * du_dx + cross * du_dy;
+                }
+                self.u[face] += dt * acceleration;
+            }
+        }
+
+        for j in 1..ny {
+            for i in 0..nx {
+                let face = self.v_index(i, j);
+                let (south, north) = (self.grid.index(i, j - 1), self.grid.index(i, j));
+                if self.solid[south] || self.solid[north] {
+                    self.v[face] = self.wall_velocity(south, north);
+                    continue;
+                }
+                let mut acceleration = -g * (self.eta[north] - self.eta[south]) / dy;
+                if self.nonlinear {
+                    let velocity = v[face];
+                    let dv_dy = (if velocity >= 0.0 { velocity - v[face - nx] } else { v[face + nx] - velocity }) / dy;
+                    let cross = 0.25 * (u[self.u_index(i, j - 1)] + u[self.u_index(i + 1, j - 1)] + u[self.u_index(i, j)] + u[self.u_index(i + 1, j)]);
+                    let dv_dx = match (cross >= 0.0, i) {
+                        (true, 0) => 0.0,
+                        (true, _) => (velocity - v[face - 1]) / dx,
+                        (false, i) if i == nx - 1 => 0.0,
+                        (false, _) => (v[face + 1] - velocity) / dx,
+                    };
+                    acceleration -= velocity * dv_dy + cross * dv_dx;
+                }
+                self.v[face] += dt * acceleration;
+            }
+        }
+    }
+
+    /// Relax the surface towards still water and the velocities towards rest inside the sponge layers
+    fn apply_sponges(&mut self, dt: f64) {
+        let Some(sponges) = self.sponges else {
+            return;
+        };
+        let grid = self.grid;
+        let (nx, ny) = (grid.nx(), grid.ny());
+        let (dx, dy) = (grid.dx(), grid.dy());
+        for j in 0..ny {
+            for i in 0..nx {
+                let (x, y) = grid.center(i, j);
+                self.eta[grid.index(i, j)] *= (-sponges.damping_rate(x, y, &grid) * dt).exp();
+            }
+            for i in 0..=nx {
+                let face = self.u_index(i, j);
+                self.u[face] *= (-sponges.damping_rate(i as f64 * dx, (j as f64 + 0.5) * dy, &grid) * dt).exp();
+            }
+        }
+        for j in 0..=ny {
+            for i in 0..nx {
+                let face = self.v_index(i, j);
+                self.v[face] *= (-sponges.damping_rate((i as f64 + 0.5) * dx, j as f64 * dy, &grid) * dt).exp();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::WaveHeightField;
+    use crate::analysis::spectral::correlation;
+    use crate::units::{Length, Time};
+    use crate::waves::{DispersionSolver, WaveSource};
+
+    fn regular_waves(wave_height: f64, wave_period: f64, depth: f64, waves: f64) -> WaveForcing {
+        let params = DispersionSolver::new().solve_wave_parameters(Length::meters(wave_height), Time::seconds(wave_period), Length::meters(depth)).unwrap();
+        let mut forcing = WaveForcing::new();
+        forcing.add_source(WaveSource::boundary(params).with_generation_duration(waves * wave_period));
+        forcing
+    }
+
+    #[test]
+    fn test_still_water_stays_at_rest() {
+        let obstacles = vec![
+            Obstacle::Rectangle { x_min: 4.0, x_max: 5.0, y_min: 0.0, y_max: 3.0 },
+            Obstacle::Cylinder { x: 7.0, y: 4.0, radius: 0.5 },
+        ];
+        let mut solver = BasinSolver::new(10.0, 6.0, 40, 24, 0.5).unwrap().with_obstacles(obstacles).unwrap().with_nonlinear(true);
+        assert!(solver.is_solid(18, 2));
+        assert!(!solver.is_solid(18, 20));
+        solver.advance(5.0, &mut WaveForcing::new());
+        assert!(solver.eta().iter().all(|eta| eta.abs() < 1e-14));
+        assert!(solver.u().iter().chain(solver.v()).all(|velocity| velocity.abs() < 1e-14));
+    }
+
+    #[test]
+    fn test_closed_basin_conserves_volume() {
+        // A hump spreads as a ring wave, symmetric about the basin axis, without gaining or losing water
+        for nonlinear in [false, true] {
+            let mut solver = BasinSolver::new(10.0, 6.0, 50, 30, 0.5)
+                .unwrap()
+                .with_sponges(None)
+                .unwrap()
+                .with_nonlinear(nonlinear)
+                .with_initial_surface(|x, y| 0.05 * (-((x - 4.0).powi(2) + (y - 3.0).powi(2)) / 0.5).exp());
+            let volume = solver.volume();
+            solver.advance(3.0, &mut WaveForcing::new());
+            assert!((solver.volume() - volume).abs() < 1e-10 * volume, "Volume {:.6} became {:.6}", volume, solver.volume());
+            for (x, y) in [(2.0, 1.5), (6.5, 0.4), (8.0, 2.0)] {
+                let (south, north) = (solver.surface_elevation_at(x, y), solver.surface_elevation_at(x, 6.0 - y));
+                assert!((south - north).abs() < 1e-9, "Asymmetry at ({}, {}): {} vs {}", x, y, south, north);
+            }
+
+            solver.reset();
+            assert_eq!(solver.time(), 0.0);
+            assert!((solver.volume() - volume).abs() < 1e-15);
+        }
+    }
+
+    #[test]
+    fn test_wavemaker_generates_long_crested_waves() {
+        let (wave_height, depth) = (0.02, 0.5);
+        let mut forcing = regular_waves(wave_height, 5.0, depth, 20.0);
+        let mut solver = BasinSolver::new(60.0, 4.0, 240, 8, depth).unwrap().with_sponges(Some(BasinSponges { width: 20.0, ..BasinSponges::default() })).unwrap();
+
+        solver.advance(30.0, &mut forcing);
+        let (mut crest, mut trough): (f64, f64) = (0.0, 0.0);
+        for _ in 0..100 {
+            solver.advance(0.1, &mut forcing);
+            crest = crest.max(solver.surface_elevation_at(20.0, 2.0));
+            trough = trough.min(solver.surface_elevation_at(20.0, 2.0));
+            // Crests are parallel to the paddle
+            let (i, _) = solver.grid().cell_at(20.0, 0.0);
+            let row = |j: usize| solver.eta()[solver.grid().index(i, j)];
+            assert!((0..8).all(|j| (row(j) - row(0)).abs() < 1e-12));
+        }
+        assert!((crest - trough - wave_height).abs() < 0.1 * wave_height, "Height {:.4}", crest - trough);
+
+        // Once the wavemaker stops, the sponge absorbs the waves
+        let energy = solver.energy();
+        solver.advance(120.0, &mut forcing);
+        assert!(solver.energy() < 0.02 * energy, "Energy {:.3e} of {:.3e} left", solver.energy(), energy);
+    }
+
+    #[test]
+    fn test_breakwater_shelters_the_lee() {
+        let depth = 0.5;
+        let mut forcing = regular_waves(0.02, 2.0, depth, 30.0);
+        let breakwater = Obstacle::Rectangle { x_min: 10.0, x_max: 11.0, y_min: 0.0, y_max: 10.0 };
+        let sponges = BasinSponges { width: 5.0, east: true, north: true, south: false, ..BasinSponges::default() };
+        let mut solver = BasinSolver::new(30.0, 20.0, 150, 100, depth).unwrap().with_sponges(Some(sponges)).unwrap().with_obstacles(vec![breakwater]).unwrap();
+
+        solver.advance(25.0, &mut forcing);
+        let (mut sheltered, mut exposed): (f64, f64) = (0.0, 0.0);
+        for _ in 0..40 {
+            solver.advance(0.1, &mut forcing);
+            sheltered = sheltered.max(solver.surface_elevation_at(16.0, 4.0).abs());
+            exposed = exposed.max(solver.surface_elevation_at(16.0, 14.0).abs());
+        }
+        assert!(sheltered < 0.5 * exposed, "Sheltered {:.4}, exposed {:.4}", sheltered, exposed);
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(BasinSolver::new(10.0, 6.0, 40, 24, 0.0).is_err());
+        let solver = || BasinSolver::new(10.0, 6.0, 40, 24, 0.5).unwrap();
+        assert!(solver().with_sponges(Some(BasinSponges { width: 12.0, ..BasinSponges::default() })).is_err());
+        assert!(solver().with_sponges(Some(BasinSponges { width: 4.0, north: true, south: true, ..BasinSponges::default() })).is_err());
+        assert!(solver().with_obstacles(vec![Obstacle::Cylinder { x: 5.0, y: 3.0, radius: 0.0 }]).is_err());
+        assert!(solver().with_obstacles(vec![Obstacle::Rectangle { x_min: 0.0, x_max: 10.0, y_min: 0.0, y_max: 6.0 }]).is_err());
+    }
+
+    #[test]
+    fn test_sponge_damping_profile() {
+        let grid = Grid2D::new(30.0, 20.0, 30, 20).unwrap();
+        let sponges = BasinSponges { width: 5.0, strength: 4.0, east: true, north: false, south: true };
+        assert_eq!(sponges.damping_rate(10.0, 10.0, &grid), 0.0);
+        assert_eq!(sponges.damping_rate(27.5, 10.0, &grid), 1.0);
+        assert_eq!(sponges.damping_rate(30.0, 10.0, &grid), 4.0);
+        assert_eq!(sponges.damping_rate(10.0, 0.0, &grid), 4.0);
+        assert_eq!(sponges.damping_rate(10.0, 20.0, &grid), 0.0);
+    }
+
+    #[test]
+    fn test_snapshot_frames() {
+        let mut forcing = regular_waves(0.02, 2.0, 0.5, 5.0);
+        let mut solver = BasinSolver::new(20.0, 4.0, 80, 16, 0.5).unwrap();
+        solver.advance(4.0, &mut forcing);
+        let values = solver.snapshot();
+        let frame = BasinFrame::new(solver.grid(), &values).unwrap();
+        assert!(BasinFrame::new(solver.grid(), &values[1..]).is_none());
+        assert_eq!(frame.eta(), solver.eta());
+        for (x, y) in [(2.1, 1.3), (5.6, 2.0)] {
+            assert!((frame.value_at(BasinField::SurfaceElevation, x, y) - solver.surface_elevation_at(x, y)).abs() < 1e-15);
+            // Long waves carry u = √(g/d) η and no cross flow
+            let (u, v) = frame.velocity_at(x, y);
+            assert!((u - (9.81_f64 / 0.5).sqrt() * solver.surface_elevation_at(x, y)).abs() < 0.15 * 0.01 * (9.81_f64 / 0.5).sqrt());
+            assert!(v.abs() < 1e-12);
+            assert!((frame.value_at(BasinField::Speed, x, y) - u.abs()).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_coastline_outlines_the_obstacles() {
+        let breakwater = Obstacle::Rectangle { x_min: 4.0, x_max: 5.0, y_min: 0.0, y_max: 3.0 };
+        let solver = BasinSolver::new(10.0, 6.0, 10, 6, 0.5).unwrap().with_obstacles(vec![breakwater]).unwrap();
+        let mut coastline = solver.coastline();
+        coastline.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(coastline, vec![[[4.0, 0.0], [4.0, 3.0]], [[4.0, 3.0], [5.0, 3.0]], [[5.0, 0.0], [5.0, 3.0]]]);
+        assert!(BasinSolver::new(10.0, 6.0, 10, 6, 0.5).unwrap().coastline().is_empty());
+    }
+
+    #[test]
+    fn test_oblique_waves_leave_at_their_angle() {
+        let (depth, period, direction) = (0.5, 3.0, 20.0_f64.to_radians());
+        let params = DispersionSolver::new().solve_wave_parameters(Length::meters(0.02), Time::seconds(period), Length::meters(depth)).unwrap();
+        let mut forcing = WaveForcing::new();
+        forcing.add_source(WaveSource::boundary(params).with_direction(direction));
+        let sponges = BasinSponges { width: 6.0, east: true, north: true, ..BasinSponges::default() };
+        let mut solver = BasinSolver::new(40.0, 30.0, 160, 120, depth).unwrap().with_sponges(Some(sponges)).unwrap();
+
+        solver.advance(20.0, &mut forcing);
+        let (mut south, mut north) = (Vec::new(), Vec::new());
+        for _ in 0..300 {
+            solver.advance(0.02, &mut forcing);
+            south.push(solver.surface_elevation_at(8.0, 13.0));
+            north.push(solver.surface_elevation_at(8.0, 17.0));
+        }
+        // Crests reach the northern point later, by Δy sin θ / √(gd)
+        let lag = (0..60).max_by(|&a, &b| correlation(&south, &north, a).total_cmp(&correlation(&south, &north, b))).unwrap() as f64 * 0.02;
+        let expected = 4.0 * direction.sin() / (9.81 * depth).sqrt();
+        assert!((lag - expected).abs() < 0.05, "Lag {:.3} s, expected {:.3} s", lag, expected);
+        let height = south.iter().copied().fold(f64::MIN, f64::max) - south.iter().copied().fold(f64::MAX, f64::min);
+        assert!((height - 0.02).abs() < 0.15 * 0.02, "Height {:.4}", height);
+    }
+
+    #[test]
+    fn test_active_absorption_at_the_wavemaker() {
+        // A hump released in a closed basin: the wave reaching the paddle is absorbed instead of reflected
+        let hump = |x: f64, _: f64| 0.01 * (-((x - 10.0) / 1.0).powi(2)).exp();
+        let mut forcing = WaveForcing::new();
+        let mut reflecting = BasinSolver::new(20.0, 2.0, 100, 4, 0.5).unwrap().with_sponges(None).unwrap().with_initial_surface(hump);
+        let mut absorbing = BasinSolver::new(20.0, 2.0, 100, 4, 0.5).unwrap().with_sponges(None).unwrap().with_initial_surface(hump).with_active_absorption(true);
+        assert!(absorbing.has_active_absorption() && !reflecting.has_active_absorption());
+        // The western half of the hump travels back to the paddle in 10 m / √(gd) ≈ 4.5 s
+        reflecting.advance(7.0, &mut forcing);
+        absorbing.advance(7.0, &mut forcing);
+        let west_energy = |solver: &BasinSolver| -> f64 { (0..50).map(|i| solver.eta()[solver.grid().index(i, 1)].powi(2)).sum() };
+        assert!(west_energy(&absorbing) < 0.05 * west_energy(&reflecting), "{:.3e} vs {:.3e}", west_energy(&absorbing), west_energy(&reflecting));
+    }
+
+    #[test]
+    fn test_partially_reflecting_structures() {
+        // Regular waves against a quay across a narrow basin, absorbed again at the wavemaker
+        let (depth, period) = (0.5, 2.0);
+        let partial_standing_wave = |reflection: f64| -> f64 {
+            let quay = HarborStructure::new("Quay", vec![[30.0, -1.0], [41.0, -1.0], [41.0, 2.0], [30.0, 2.0]], reflection);
+            let mut solver = BasinSolver::new(40.0, 1.0, 200, 2, depth).unwrap().with_sponges(None).unwrap().with_structures(vec![quay]).unwrap().with_active_absorption(true);
+            assert!(solver.is_solid(160, 0) && !solver.is_solid(149, 1));
+            let mut forcing = regular_waves(0.02, period, depth, 100.0);
+            let mut heights = WaveHeightField::new(solver.grid().cells(), 40.0, 80.0);
+            while solver.time() < 80.0 {
+                solver.advance(0.05, &mut forcing);
+                heights.record(solver.time(), solver.eta());
+            }
+            // Antinodes of H (1 + R) and nodes of H (1 − R) in front of the quay
+            let heights = heights.regular_heights().unwrap();
+            let envelope: Vec<f64> = (100..148).map(|i| heights[solver.grid().index(i, 0)]).collect();
+            let (highest, lowest) = (envelope.iter().copied().fold(f64::MIN, f64::max), envelope.iter().copied().fold(f64::MAX, f64::min));
+            (highest - lowest) / (highest + lowest)
+        };
+        for reflection in [1.0, 0.5, 0.2] {
+            let measured = partial_standing_wave(reflection);
+            assert!((measured - reflection).abs() < 0.06, "R = {:.3} for {}", measured, reflection);
+        }
+    }
+
+    #[test]
+    fn test_shoaling_over_a_varying_depth() {
+        // Long waves over a slope from 0.8 to 0.2 m grow as d^(−1/4) (Green's law), land at the east end
+        let (deep, shallow, period) = (0.8, 0.2, 5.0);
+        let mut solver = BasinSolver::new(120.0, 1.0, 480, 2, deep).unwrap();
+        let depths = (0..solver.grid().cells())
+            .map(|n| {
+                let (x, _) = solver.grid().center(n % 480, n / 480);
+                if x > 115.0 { -0.5 } else { deep + (shallow - deep) * ((x - 20.0) / 40.0).clamp(0.0, 1.0) }
+            })
+            .collect();
+        solver = solver.with_depths(depths).unwrap().with_sponges(Some(BasinSponges { width: 40.0, east: true, north: false, south: false, ..BasinSponges::default() })).unwrap().with_active_absorption(true);
+        assert!(solver.is_solid(470, 0) && !solver.is_solid(450, 1));
+        assert_eq!(solver.depth()[solver.grid().index(470, 0)], 0.0);
+        assert!(BasinSolver::new(10.0, 1.0, 10, 2, deep).unwrap().with_depths(vec![-1.0; 20]).is_err());
+        assert!(BasinSolver::new(10.0, 1.0, 10, 2, deep).unwrap().with_depths(vec![1.0; 19]).is_err());
+
+        let mut forcing = regular_waves(0.01, period, deep, 100.0);
+        let mut heights = WaveHeightField::new(solver.grid().cells(), 60.0, 120.0);
+        while solver.time() < 120.0 {
+            solver.advance(0.1, &mut forcing);
+            heights.record(solver.time(), solver.eta());
+        }
+        let heights = heights.regular_heights().unwrap();
+        let mean = |from: f64, to: f64| -> f64 {
+            let cells: Vec<f64> = (0..480).filter(|&i| (from..to).contains(&solver.grid().center(i, 0).0)).map(|i| heights[solver.grid().index(i, 0)]).collect();
+            cells.iter().sum::<f64>() / cells.len() as f64
+        };
+        let ratio = mean(62.0, 72.0) / mean(4.0, 16.0);
+        assert!((ratio - (deep / shallow).powf(0.25)).abs() < 0.05, "Shoaling ratio {:.3}", ratio);
+    }
+}
@@ -0,0 +1,289 @@
+use serde::{Deserialize, Serialize};
+
+/// Straight part of a composite cross-section
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileSegment {
+    /// Name shown on the profile, e.g. "Berm"
+    pub label: String,
+    /// Horizontal length [m]
+    pub length: f64,
+    /// Slope as m in 1:m (horizontal over vertical), rising shoreward; 0 for a flat segment, negative for a falling one
+    pub slope: f64,
+}
+
+impl ProfileSegment {
+    /// Create new flat segment
+    pub fn flat(label: &str, length: f64) -> Self {
+        Self {
+            label: label.to_string(),
+            length,
+            slope: 0.0,
+        }
+    }
+
+    /// Create new segment with a 1:m slope
+    pub fn slope(label: &str, length: f64, slope: f64) -> Self {
+        Self {
+            label: label.to_string(),
+            length,
+            slope,
+        }
+    }
+
+    /// Bed gradient dz/dx
+    pub fn gradient(&self) -> f64 {
+        if self.slope == 0.0 { 0.0 } else { 1.0 / self.slope }
+    }
+
+    /// Rise of the bed over the segment [m]
+    pub fn rise(&self) -> f64 {
+        self.length * self.gradient()
+    }
+}
+
+/// Composite channel cross-section: offshore flat, slopes, berms and structure slopes in sequence
+///
+/// The bed starts at the still water depth of the wavemaker and follows the segments shoreward.
+/// Beyond the last segment it stays level. Parts of the bed above the still water level are dry
+/// until waves run up on them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrossSection {
+    pub segments: Vec<ProfileSegment>,
+}
+
+impl CrossSection {
+    /// Create new cross-section from its segments, offshore first
+    pub fn new(segments: Vec<ProfileSegment>) -> Result<Self, String> {
+        let section = Self { segments };
+        section.validate()?;
+        Ok(section)
+    }
+
+    /// Sandy beach in a channel: offshore flat, then a 1:20 slope rising above the still water level
+    pub fn beach(channel_length: f64, depth: f64) -> Self {
+        let slope_length = 20.0 * 1.25 * depth;
+        Self {
+            segments: vec![
+                ProfileSegment::flat("Offshore", (channel_length - slope_length).max(0.1 * channel_length)),
+                ProfileSegment::slope("Beach", slope_length, 20.0),
+            ],
+        }
+    }
+
+    /// Dike with a berm: offshore flat, 1:10 foreshore, berm, 1:3 outer slope and crest
+    pub fn dike(channel_length: f64, depth: f64) -> Self {
+        let foreshore = 10.0 * 0.5 * depth;
+        let berm = 2.0 * depth;
+        let outer_slope = 3.0 * depth;
+        let crest = depth;
+        let offshore = (channel_length - foreshore - berm - outer_slope - crest).max(0.1 * channel_length);
+        Self {
+            segments: vec![
+                ProfileSegment::flat("Offshore", offshore),
+                ProfileSegment::slope("Foreshore", foreshore, 10.0),
+                ProfileSegment::flat("Berm", berm),
+                ProfileSegment::slope("Outer Slope", outer_slope, 3.0),
+                ProfileSegment::flat("Crest", crest),
+            ],
+        }
+    }
+
+    /// Check that the cross-section has segments of positive, finite length and finite slopes
+    pub fn validate(&self) -> Result<(), String> {
+        if self.segments.is_empty() {
+            return Err("Cross-section needs at least one segment".to_string());
+        }
+        for segment in &self.segments {
+            if !(segment.length.is_finite() && segment.length > 0.0) {
+                return Err(format!("Segment {} must have a positive length", segment.label));
+            }
+            if !segment.slope.is_finite() {
+                return Err(format!("Segment {} must have a finite slope", segment.label));
+            }
+        }
+        Ok(())
+    }
+
+    /// Horizontal length of all segments [m]
+    pub fn length(&self) -> f64 {
+        self.segments.iter().map(|segment| segment.length).sum()
+    }
+
+    /// Height of the bed above the offshore bed at position x [m]
+    pub fn bed_elevation(&self, x: f64) -> f64 {
+        let mut start = 0.0;
+        let mut elevation = 0.0;
+        for segment in &self.segments {
+            if x <= start + segment.length {
+                return elevation + (x - start).max(0.0) * segment.gradient();
+            }
+            start += segment.length;
+            elevation += segment.rise();
+        }
+        elevation
+    }
+
+    /// Still water depth at position x for an offshore depth, negative where the bed is dry [m]
+    pub fn depth_at(&self, x: f64, offshore_depth: f64) -> f64 {
+        offshore_depth - self.bed_elevation(x)
+    }
+
+    /// Position and bed elevation at the ends of the segments, starting at the wavemaker
+    pub fn breakpoints(&self) -> Vec<[f64; 2]> {
+        let mut points = vec![[0.0, 0.0]];
+        let (mut x, mut elevation) = (0.0, 0.0);
+        for segment in &self.segments {
+            x += segment.length;
+            elevation += segment.rise();
+            points.push([x, elevation]);
+        }
+        points
+    }
+
+    /// Highest bed elevation above the offshore bed [m]
+    pub fn crest_elevation(&self) -> f64 {
+        self.breakpoints().iter().map(|point| point[1]).fold(0.0, f64::max)
+    }
+
+    /// Landward edge of the highest part of the bed, where overtopping water leaves the crest [m]
+    pub fn crest_position(&self) -> f64 {
+        let crest = self.crest_elevation();
+        self.breakpoints().iter().rev().find(|point| point[1] >= crest - 1e-9).map_or(0.0, |point| point[0])
+    }
+
+    /// Area between the bed and the offshore bed level over the segments [m²]
+    pub fn area(&self) -> f64 {
+        self.breakpoints().windows(2).map(|pair| 0.5 * (pair[0][1] + pair[1][1]) * (pair[1][0] - pair[0][0])).sum()
+    }
+}
+
+/// Dike varied by the design optimization
+///
+/// From the wavemaker: offshore flat, outer slope up to the still water level, berm at the still
+/// water level, outer slope up to the crest, crest as wide as the water depth, then a 1:3 inner
+/// slope down to a polder at half the freeboard, ten water depths long, where overtopping water
+/// is stored away from the crest.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DikeDesign {
+    pub crest_freeboard: f64, // Crest level above the still water level (Rc) [m]
+    pub slope: f64,           // Outer slope as m in 1:m
+    pub berm_width: f64,      // Width of the berm at the still water level, 0 for a straight slope [m]
+}
+
+impl DikeDesign {
+    /// Check that the crest is above the water and the slope rises shoreward
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.crest_freeboard.is_finite() && self.crest_freeboard > 0.0) {
+            return Err("Crest freeboard must be positive".to_string());
+        }
+        if !(self.slope.is_finite() && self.slope >= 1.0) {
+            return Err("Outer slope must be 1:1 or gentler".to_string());
+        }
+        if !(self.berm_width.is_finite() && self.berm_width >= 0.0) {
+            return Err("Berm width must not be negative".to_string());
+        }
+        Ok(())
+    }
+
+    /// Cross-section of the dike in a channel with a still water depth at the wavemaker
+    /// The offshore flat fills the channel in front of the dike and takes at least a quarter of it
+    pub fn cross_section(&self, channel_length: f64, depth: f64) -> Result<CrossSection, String> {
+        self.validate()?;
+        let mut structure = Vec::new();
+        if self.berm_width > 0.0 {
+            structure.push(ProfileSegment::slope("Lower Slope", self.slope * depth, self.slope));
+            structure.push(ProfileSegment::flat("Berm", self.berm_width));
+            structure.push(ProfileSegment::slope("Upper Slope", self.slope * self.crest_freeboard, self.slope));
+        } else {
+            structure.push(ProfileSegment::slope("Outer Slope", self.slope * (depth + self.crest_freeboard), self.slope));
+        }
+        structure.push(ProfileSegment::flat("Crest", depth));
+        structure.push(ProfileSegment::slope("Inner Slope", 3.0 * 0.5 * self.crest_freeboard, -3.0));
+        structure.push(ProfileSegment::flat("Polder", 10.0 * depth));
+        let offshore = channel_length - structure.iter().map(|segment| segment.length).sum::<f64>();
+        if offshore < 0.25 * channel_length {
+            return Err("Dike does not fit in the channel".to_string());
+        }
+        CrossSection::new(std::iter::once(ProfileSegment::flat("Offshore", offshore)).chain(structure).collect())
+    }
+
+    /// Height of the crest above the offshore bed [m]
+    pub fn crest_height(&self, depth: f64) -> f64 {
+        depth + self.crest_freeboard
+    }
+
+    /// Volume per metre of dike above the offshore bed [m³/m]
+    pub fn volume(&self, depth: f64) -> f64 {
+        let rise = depth + self.crest_freeboard;
+        let outer = 0.5 * self.slope * rise * rise + self.berm_width * depth;
+        let crest = depth * rise;
+        let inner = 1.5 * self.crest_freeboard * (rise - 0.25 * self.crest_freeboard);
+        outer + crest + inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bed_elevation() {
+        let section = CrossSection::new(vec![
+            ProfileSegment::flat("Offshore", 10.0),
+            ProfileSegment::slope("Slope", 6.0, 3.0),
+            ProfileSegment::flat("Berm", 4.0),
+            ProfileSegment::slope("Back", 2.0, -2.0),
+        ])
+        .unwrap();
+        assert_eq!(section.length(), 22.0);
+        assert_eq!(section.bed_elevation(5.0), 0.0);
+        assert!((section.bed_elevation(13.0) - 1.0).abs() < 1e-12);
+        assert!((section.bed_elevation(18.0) - 2.0).abs() < 1e-12);
+        assert!((section.bed_elevation(21.0) - 1.5).abs() < 1e-12);
+        // Level beyond the last segment
+        assert!((section.bed_elevation(30.0) - 1.0).abs() < 1e-12);
+        assert_eq!(section.crest_elevation(), 2.0);
+        assert!((section.depth_at(18.0, 1.5) + 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_presets() {
+        let beach = CrossSection::beach(100.0, 1.0);
+        assert!(beach.validate().is_ok());
+        assert_eq!(beach.length(), 100.0);
+        // The beach emerges from the water
+        assert!(beach.depth_at(100.0, 1.0) < 0.0);
+        assert!(beach.depth_at(0.0, 1.0) == 1.0);
+
+        let dike = CrossSection::dike(100.0, 1.0);
+        assert_eq!(dike.segments.len(), 5);
+        assert!((dike.length() - 100.0).abs() < 1e-9);
+        assert!((dike.crest_elevation() - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_dike_design() {
+        let design = DikeDesign { crest_freeboard: 0.5, slope: 4.0, berm_width: 2.0 };
+        let section = design.cross_section(60.0, 1.0).unwrap();
+        let labels: Vec<&str> = section.segments.iter().map(|segment| segment.label.as_str()).collect();
+        assert_eq!(labels, vec!["Offshore", "Lower Slope", "Berm", "Upper Slope", "Crest", "Inner Slope", "Polder"]);
+        assert!((section.crest_elevation() - design.crest_height(1.0)).abs() < 1e-12);
+        // Landward edge of the crest, then down to the polder
+        assert!((section.crest_position() - (60.0 - 10.0 - 0.75)).abs() < 1e-9);
+        assert!((section.bed_elevation(60.0) - 1.25).abs() < 1e-9);
+        assert!((section.area() - 10.0 * 1.25 - design.volume(1.0)).abs() < 1e-9);
+
+        let straight = DikeDesign { berm_width: 0.0, ..design };
+        assert_eq!(straight.cross_section(60.0, 1.0).unwrap().segments.len(), 5);
+        assert!(straight.volume(1.0) < design.volume(1.0));
+        assert!(DikeDesign { slope: 0.5, ..design }.validate().is_err());
+        assert!(DikeDesign { slope: 40.0, ..design }.cross_section(60.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(CrossSection::new(Vec::new()).is_err());
+        assert!(CrossSection::new(vec![ProfileSegment::flat("Zero", 0.0)]).is_err());
+        assert!(CrossSection::new(vec![ProfileSegment::slope("Vertical", 1.0, f64::NAN)]).is_err());
+    }
+}
@@ -0,0 +1,311 @@
+use crate::numerics::linalg::{solve_dense, solve_dense_in_place};
+
+/// Block tridiagonal linear system with small dense blocks
+///
+/// Row i reads: lower[i] x[i-1] + diag[i] x[i] + upper[i] x[i+1] = rhs[i].
+/// Blocks are stored row-major. lower[0] and upper[rows-1] are the corner blocks
+/// coupling the first and last rows, only used by the cyclic solve.
+#[derive(Debug, Clone, Default)]
+pub struct BlockTridiagonal {
+    /// Number of block rows
+    rows: usize,
+    /// Size of each square block
+    block_size: usize,
+    /// Sub-diagonal blocks
+    lower: Vec<f64>,
+    /// Diagonal blocks
+    diag: Vec<f64>,
+    /// Super-diagonal blocks
+    upper: Vec<f64>,
+}
+
+impl BlockTridiagonal {
+    /// Create a zero system with the given number of block rows and block size
+    pub fn new(rows: usize, block_size: usize) -> Self {
+        let mut system = Self::default();
+        system.reset(rows, block_size);
+        system
+    }
+
+    /// Make this a zero system of the given size, keeping the memory of the blocks
+    pub fn reset(&mut self, rows: usize, block_size: usize) {
+        let len = rows * block_size * block_size;
+        self.rows = rows;
+        self.block_size = block_size;
+        for blocks in [&mut self.lower, &mut self.diag, &mut self.upper] {
+            blocks.clear();
+            blocks.resize(len, 0.0);
+        }
+    }
+
+    /// Number of block rows
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Size of each square block
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Mutable sub-diagonal block of a row
+    pub fn lower_mut(&mut self, row: usize) -> &mut [f64] {
+        let range = self.block_range(row);
+        &mut self.lower[range]
+    }
+
+    /// Mutable diagonal block of a row
+    pub fn diag_mut(&mut self, row: usize) -> &mut [f64] {
+        let range = self.block_range(row);
+        &mut self.diag[range]
+    }
+
+    /// Mutable super-diagonal block of a row
+    pub fn upper_mut(&mut self, row: usize) -> &mut [f64] {
+        let range = self.block_range(row);
+        &mut self.upper[range]
+    }
+
+    /// Solve the system, ignoring the corner blocks
+    pub fn solve(&self, rhs: &[f64]) -> Result<Vec<f64>, String> {
+        let mut x = Vec::new();
+        self.solve_into(rhs, &mut x, &mut BlockWork::default())?;
+        Ok(x)
+    }
+
+    /// Solve the system into a solution buffer, ignoring the corner blocks
+    /// The buffers keep their memory, so repeated solves of the same size allocate nothing
+    pub fn solve_into(&self, rhs: &[f64], x: &mut Vec<f64>, work: &mut BlockWork) -> Result<(), String> {
+        thomas(&self.lower, &self.diag, &self.upper, rhs, self.block_size, 1, x, work)
+    }
+
+    /// Solve the periodic system, where the corner blocks couple the first and last rows
+    pub fn solve_cyclic(&self, rhs: &[f64]) -> Result<Vec<f64>, String> {
+        let n = self.block_size;
+        if self.rows < 2 {
+            return self.solve(rhs);
+        }
+
+        // Split x = (x0, y + Z x0): solve rows 1.. for the unknowns with x0 moved to the right-hand side
+        let inner = self.rows - 1;
+        let columns = 1 + n;
+        let mut inner_rhs = vec![0.0; inner * n * columns];
+        for i in 0..inner {
+            for r in 0..n {
+                inner_rhs[(i * n + r) * columns] = rhs[(i + 1) * n + r];
+            }
+        }
+        let first_coupling = self.block(&self.lower, 1);
+        let last_coupling = self.block(&self.upper, self.rows - 1);
+        for r in 0..n {
+            for c in 0..n {
+                inner_rhs[r * columns + 1 + c] -= first_coupling[r * n + c];
+                inner_rhs[((inner - 1) * n + r) * columns + 1 + c] -= last_coupling[r * n + c];
+            }
+        }
+
+        // The first sub-diagonal and last super-diagonal blocks of the inner rows are the couplings moved above
+        let rest = self.block_range(1).start..;
+        let mut solution = Vec::new();
+        thomas(&self.lower[rest.clone()], &self.diag[rest.clone()], &self.upper[rest], &inner_rhs, n, columns, &mut solution, &mut BlockWork::default())?;
+
+        // First row: D0 x0 + U0 x1 + L0 x_last = r0
+        let y = |i: usize, r: usize| solution[(i * n + r) * columns];
+        let z = |i: usize, r: usize, c: usize| solution[(i * n + r) * columns + 1 + c];
+        let d0 = self.block(&self.diag, 0);
+        let u0 = self.block(&self.upper, 0);
+        let l0 = self.block(&self.lower, 0);
+        let mut matrix = d0.to_vec();
+        let mut b0 = rhs[..n].to_vec();
+        for r in 0..n {
+            for m in 0..n {
+                b0[r] -= u0[r * n + m] * y(0, m) + l0[r * n + m] * y(inner - 1, m);
+                for c in 0..n {
+                    matrix[r * n + c] += u0[r * n + m] * z(0, m, c) + l0[r * n + m] * z(inner - 1, m, c);
+                }
+            }
+        }
+        let x0 = solve_dense(&matrix, &b0, n, 1)?;
+
+        let mut x = vec![0.0; self.rows * n];
+        x[..n].copy_from_slice(&x0);
+        for i in 0..inner {
+            for r in 0..n {
+                let coupled: f64 = (0..n).map(|c| z(i, r, c) * x0[c]).sum();
+                x[(i + 1) * n + r] = y(i, r) + coupled;
+            }
+        }
+        Ok(x)
+    }
+
+    fn block_range(&self, row: usize) -> std::ops::Range<usize> {
+        let size = self.block_size * self.block_size;
+        row * size..(row + 1) * size
+    }
+
+    fn block<'a>(&self, blocks: &'a [f64], row: usize) -> &'a [f64] {
+        &blocks[self.block_range(row)]
+    }
+}
+
+/// Work arrays of the block Thomas algorithm, resized in place by every solve
+#[derive(Debug, Clone, Default)]
+pub struct BlockWork {
+    reduced: Vec<f64>,   // [C | Y] of every row
+    pivot: Vec<f64>,     // Reduced diagonal block D' of the current row
+    augmented: Vec<f64>, // [U | R - L Y_prev] of the current row, then its [C | Y]
+}
+
+/// Block Thomas algorithm for a right-hand side with several columns (row-major, n rows per block)
+///
+/// The blocks of each band follow each other row by row. The sub-diagonal block of the first row and the
+/// super-diagonal block of the last row are ignored.
+#[allow(clippy::too_many_arguments)]
+fn thomas(lower: &[f64], diag: &[f64], upper: &[f64], rhs: &[f64], n: usize, columns: usize, x: &mut Vec<f64>, work: &mut BlockWork) -> Result<(), String> {
+    let size = n * n;
+    let rows = diag.len() / size;
+    let width = n + columns;
+    let block = |i: usize| i * size..(i + 1) * size;
+    let BlockWork { reduced, pivot, augmented } = work;
+    // Per row: [C | Y] with C = D'⁻¹ U and Y = D'⁻¹ (R - L Y_prev)
+    reduced.clear();
+    reduced.resize(rows * n * width, 0.0);
+
+    for i in 0..rows {
+        let (lower_i, upper_i) = (&lower[block(i)], &upper[block(i)]);
+        pivot.clear();
+        pivot.extend_from_slice(&diag[block(i)]);
+        augmented.clear();
+        augmented.resize(n * width, 0.0);
+        for r in 0..n {
+            for c in 0..n {
+                augmented[r * width + c] = if i + 1 < rows { upper_i[r * n + c] } else { 0.0 };
+            }
+            for c in 0..columns {
+                augmented[r * width + n + c] = rhs[(i * n + r) * columns + c];
+            }
+        }
+
+        if i > 0 {
+            let previous = &reduced[(i - 1) * n * width..i * n * width];
+            for r in 0..n {
+                for m in 0..n {
+                    let l = lower_i[r * n + m];
+                    if l == 0.0 {
+                        continue;
+                    }
+                    for c in 0..n {
+                        pivot[r * n + c] -= l * previous[m * width + c];
+                    }
+                    for c in 0..columns {
+                        augmented[r * width + n + c] -= l * previous[m * width + n + c];
+                    }
+                }
+            }
+        }
+
+        solve_dense_in_place(pivot, augmented, n, width)?;
+        reduced[i * n * width..(i + 1) * n * width].copy_from_slice(augmented);
+    }
+
+    // Back substitution: X_i = Y_i - C_i X_{i+1}
+    x.clear();
+    x.resize(rows * n * columns, 0.0);
+    for i in (0..rows).rev() {
+        let row = &reduced[i * n * width..(i + 1) * n * width];
+        for r in 0..n {
+            for c in 0..columns {
+                let mut value = row[r * width + n + c];
+                if i + 1 < rows {
+                    for m in 0..n {
+                        value -= row[r * width + m] * x[((i + 1) * n + m) * columns + c];
+                    }
+                }
+                x[(i * n + r) * columns + c] = value;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Multiply the (optionally cyclic) system by x
+    fn multiply(system: &BlockTridiagonal, x: &[f64], cyclic: bool) -> Vec<f64> {
+        let n = system.block_size;
+        let rows = system.rows;
+        let mut result = vec![0.0; rows * n];
+        for i in 0..rows {
+            let mut neighbours = vec![(&system.diag, i)];
+            if i > 0 || cyclic {
+                neighbours.push((&system.lower, (i + rows - 1) % rows));
+            }
+            if i + 1 < rows || cyclic {
+                neighbours.push((&system.upper, (i + 1) % rows));
+            }
+            for (blocks, j) in neighbours {
+                let block = system.block(blocks, i);
+                for r in 0..n {
+                    for c in 0..n {
+                        result[i * n + r] += block[r * n + c] * x[j * n + c];
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    fn test_system(rows: usize) -> BlockTridiagonal {
+        let mut system = BlockTridiagonal::new(rows, 2);
+        for i in 0..rows {
+            let shift = i as f64 * 0.1;
+            system.diag_mut(i).copy_from_slice(&[-4.0 - shift, 0.5, 1.0, -3.0]);
+            system.lower_mut(i).copy_from_slice(&[1.0, 0.2, 0.0, 1.0]);
+            system.upper_mut(i).copy_from_slice(&[1.0, 0.0, 0.3, 1.0]);
+        }
+        system
+    }
+
+    #[test]
+    fn test_block_tridiagonal_solve() {
+        let system = test_system(6);
+        let expected: Vec<f64> = (0..12).map(|i| (i as f64).sin()).collect();
+        let rhs = multiply(&system, &expected, false);
+
+        let x = system.solve(&rhs).unwrap();
+        for (value, target) in x.iter().zip(&expected) {
+            assert!((value - target).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_cyclic_solve() {
+        for rows in [2, 3, 7] {
+            let system = test_system(rows);
+            let expected: Vec<f64> = (0..2 * rows).map(|i| (i as f64 * 0.7).cos()).collect();
+            let rhs = multiply(&system, &expected, true);
+
+            let x = system.solve_cyclic(&rhs).unwrap();
+            for (value, target) in x.iter().zip(&expected) {
+                assert!((value - target).abs() < 1e-12, "{} rows: {} vs {}", rows, value, target);
+            }
+        }
+    }
+
+    #[test]
+    fn test_scalar_blocks() {
+        // Classic tridiagonal system [-2 1; 1 -2 1; 1 -2]
+        let mut system = BlockTridiagonal::new(3, 1);
+        for i in 0..3 {
+            system.diag_mut(i)[0] = -2.0;
+            system.lower_mut(i)[0] = 1.0;
+            system.upper_mut(i)[0] = 1.0;
+        }
+        let x = system.solve(&[-1.0, 0.0, -1.0]).unwrap();
+        for value in x {
+            assert!((value - 1.0).abs() < 1e-12);
+        }
+    }
+}
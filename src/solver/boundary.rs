@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+/// Condition applied at the right (downstream) end of the channel
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RightBoundary {
+    /// Solid wall: zero normal velocity, full reflection (closed flume)
+    Wall,
+    /// Sponge layer: waves are damped over a relaxation zone in front of a wall
+    Sponge {
+        /// Width of the damping zone [m]
+        width: f64,
+        /// Maximum damping rate at the wall [1/s]
+        strength: f64,
+    },
+    /// Sommerfeld radiation: outgoing long waves leave the domain (open coast)
+    Radiation,
+    /// Periodic: the right end connects to the left end (wavemaker boundary is not used)
+    Periodic,
+}
+
+impl Default for RightBoundary {
+    fn default() -> Self {
+        RightBoundary::Sponge {
+            width: 10.0,
+            strength: 5.0,
+        }
+    }
+}
+
+impl RightBoundary {
+    /// Damping rate applied at position x in a channel of the given length [1/s]
+    /// Only non-zero inside a sponge layer, increasing quadratically towards the wall
+    pub fn damping_rate(&self, x: f64, length: f64) -> f64 {
+        match *self {
+            RightBoundary::Sponge { width, strength } if width > 0.0 => {
+                let start = length - width;
+                if x <= start {
+                    0.0
+                } else {
+                    let relative = ((x - start) / width).min(1.0);
+                    strength * relative * relative
+                }
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Validate boundary parameters
+    pub fn validate(&self, length: f64) -> Result<(), String> {
+        if let RightBoundary::Sponge { width, strength } = *self {
+            if width <= 0.0 || width >= length {
+                return Err(format!("Sponge width must be between 0 and the channel length ({:.1} m)", length));
+            }
+            if strength < 0.0 {
+                return Err("Sponge strength must be non-negative".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for RightBoundary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RightBoundary::Wall => write!(f, "Solid Wall"),
+            RightBoundary::Sponge { .. } => write!(f, "Sponge Layer"),
+            RightBoundary::Radiation => write!(f, "Radiation (Sommerfeld)"),
+            RightBoundary::Periodic => write!(f, "Periodic"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sponge_damping_profile() {
+        let sponge = RightBoundary::Sponge {
+            width: 10.0,
+            strength: 4.0,
+        };
+        assert_eq!(sponge.damping_rate(30.0, 50.0), 0.0);
+        assert_eq!(sponge.damping_rate(45.0, 50.0), 1.0);
+        assert_eq!(sponge.damping_rate(50.0, 50.0), 4.0);
+    }
+
+    #[test]
+    fn test_no_damping_outside_sponge() {
+        for boundary in [RightBoundary::Wall, RightBoundary::Radiation, RightBoundary::Periodic] {
+            assert_eq!(boundary.damping_rate(49.0, 50.0), 0.0);
+        }
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(RightBoundary::default().validate(50.0).is_ok());
+        assert!(RightBoundary::Wall.validate(50.0).is_ok());
+
+        let too_wide = RightBoundary::Sponge {
+            width: 60.0,
+            strength: 1.0,
+        };
+        assert!(too_wide.validate(50.0).is_err());
+
+        let negative = RightBoundary::Sponge {
+            width: 5.0,
+            strength: -1.0,
+        };
+        assert!(negative.validate(50.0).is_err());
+    }
+}
@@ -0,0 +1,216 @@
+/// Analytical dam-break solution of the nonlinear shallow water equations
+///
+/// Ritter (1892) for a dry downstream bed, Stoker (1957) for a wet downstream bed.
+/// The deeper side collapses into a rarefaction fan; over a wet bed a bore runs into
+/// the shallower side, separated from the fan by a uniform middle state.
+#[derive(Debug, Clone)]
+pub struct DamBreakSolution {
+    /// Dam position (x₀) [m]
+    position: f64,
+    /// Water depth on the deeper side (h₀) [m]
+    deep_depth: f64,
+    /// Water depth on the shallower side (h₁) [m]
+    shallow_depth: f64,
+    /// Direction of the flow: +1 when the deeper side is upstream (left), -1 otherwise
+    direction: f64,
+    /// Depth of the uniform middle state (hₘ) [m]
+    middle_depth: f64,
+    /// Velocity of the uniform middle state (uₘ) [m/s]
+    middle_velocity: f64,
+    /// Bore speed (s) [m/s], zero over a dry bed
+    bore_speed: f64,
+    /// Gravitational acceleration [m/s²]
+    gravity: f64,
+}
+
+impl DamBreakSolution {
+    /// Create new dam-break solution from the depths on both sides of the dam
+    pub fn new(position: f64, upstream_depth: f64, downstream_depth: f64) -> Result<Self, String> {
+        if upstream_depth < 0.0 || downstream_depth < 0.0 {
+            return Err("Water depths must be non-negative".to_string());
+        }
+        if upstream_depth == downstream_depth {
+            return Err("Upstream and downstream depths must differ".to_string());
+        }
+
+        let gravity = 9.81;
+        let (deep_depth, shallow_depth, direction) = if upstream_depth > downstream_depth {
+            (upstream_depth, downstream_depth, 1.0)
+        } else {
+            (downstream_depth, upstream_depth, -1.0)
+        };
+
+        let mut solution = Self {
+            position,
+            deep_depth,
+            shallow_depth,
+            direction,
+            middle_depth: 0.0,
+            middle_velocity: 0.0,
+            bore_speed: 0.0,
+            gravity,
+        };
+        if shallow_depth > 0.0 {
+            solution.solve_middle_state();
+        } else {
+            // Ritter: the front runs at 2c₀ into the dry bed
+            solution.middle_velocity = 2.0 * solution.deep_celerity();
+        }
+        Ok(solution)
+    }
+
+    /// Depth of the uniform middle state [m]
+    pub fn middle_depth(&self) -> f64 {
+        self.middle_depth
+    }
+
+    /// Velocity of the uniform middle state [m/s], positive towards the shallow side
+    pub fn middle_velocity(&self) -> f64 {
+        self.middle_velocity
+    }
+
+    /// Bore speed [m/s], positive towards the shallow side, zero over a dry bed
+    pub fn bore_speed(&self) -> f64 {
+        self.bore_speed
+    }
+
+    /// Water depth at position x and time t [m]
+    pub fn depth(&self, x: f64, time: f64) -> f64 {
+        self.state(x, time).0
+    }
+
+    /// Depth-averaged velocity at position x and time t [m/s]
+    pub fn velocity(&self, x: f64, time: f64) -> f64 {
+        self.state(x, time).1
+    }
+
+    /// Depth and velocity at position x and time t
+    fn state(&self, x: f64, time: f64) -> (f64, f64) {
+        // Work in the frame where the deep side lies at negative ξ
+        let distance = self.direction * (x - self.position);
+        if time <= 0.0 {
+            let depth = if distance < 0.0 { self.deep_depth } else { self.shallow_depth };
+            return (depth, 0.0);
+        }
+
+        let xi = distance / time;
+        let c0 = self.deep_celerity();
+        let (depth, velocity) = if xi <= -c0 {
+            (self.deep_depth, 0.0)
+        } else if self.shallow_depth == 0.0 {
+            if xi < 2.0 * c0 {
+                self.rarefaction(xi)
+            } else {
+                (0.0, 0.0)
+            }
+        } else {
+            let middle_celerity = (self.gravity * self.middle_depth).sqrt();
+            if xi <= self.middle_velocity - middle_celerity {
+                self.rarefaction(xi)
+            } else if xi <= self.bore_speed {
+                (self.middle_depth, self.middle_velocity)
+            } else {
+                (self.shallow_depth, 0.0)
+            }
+        };
+        (depth, self.direction * velocity)
+    }
+
+    /// Self-similar rarefaction fan: h = (2c₀ - ξ)²/9g, u = 2(c₀ + ξ)/3
+    fn rarefaction(&self, xi: f64) -> (f64, f64) {
+        let c0 = self.deep_celerity();
+        let depth = (2.0 * c0 - xi).powi(2) / (9.0 * self.gravity);
+        (depth, 2.0 * (c0 + xi) / 3.0)
+    }
+
+    fn deep_celerity(&self) -> f64 {
+        (self.gravity * self.deep_depth).sqrt()
+    }
+
+    /// Match the rarefaction Riemann invariant uₘ = 2(c₀ - cₘ) with the bore jump conditions
+    fn solve_middle_state(&mut self) {
+        let g = self.gravity;
+        let h1 = self.shallow_depth;
+        let c0 = self.deep_celerity();
+        let mismatch = |hm: f64| {
+            let rarefaction = 2.0 * (c0 - (g * hm).sqrt());
+            let bore = (hm - h1) * (g * (hm + h1) / (2.0 * hm * h1)).sqrt();
+            rarefaction - bore
+        };
+
+        let mut lower = h1;
+        let mut upper = self.deep_depth;
+        for _ in 0..200 {
+            let mid = 0.5 * (lower + upper);
+            if mismatch(mid) > 0.0 {
+                lower = mid;
+            } else {
+                upper = mid;
+            }
+        }
+
+        self.middle_depth = 0.5 * (lower + upper);
+        self.middle_velocity = 2.0 * (c0 - (g * self.middle_depth).sqrt());
+        // Mass conservation across the bore: s (hₘ - h₁) = hₘ uₘ
+        self.bore_speed = self.middle_depth * self.middle_velocity / (self.middle_depth - h1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_dam_break() {
+        assert!(DamBreakSolution::new(0.0, 1.0, 1.0).is_err());
+        assert!(DamBreakSolution::new(0.0, -1.0, 0.5).is_err());
+    }
+
+    #[test]
+    fn test_ritter_solution() {
+        let solution = DamBreakSolution::new(10.0, 1.0, 0.0).unwrap();
+        let c0 = 9.81_f64.sqrt();
+
+        // Undisturbed reservoir, depth 4/9 h₀ at the dam, dry ahead of the 2c₀ front
+        assert_eq!(solution.depth(10.0 - 1.1 * c0, 1.0), 1.0);
+        assert!((solution.depth(10.0, 1.0) - 4.0 / 9.0).abs() < 1e-12);
+        assert!((solution.velocity(10.0, 1.0) - 2.0 * c0 / 3.0).abs() < 1e-12);
+        assert_eq!(solution.depth(10.0 + 2.1 * c0, 1.0), 0.0);
+    }
+
+    #[test]
+    fn test_stoker_jump_conditions() {
+        let solution = DamBreakSolution::new(0.0, 1.0, 0.1).unwrap();
+        let (hm, um, s) = (solution.middle_depth(), solution.middle_velocity(), solution.bore_speed());
+        assert!(hm > 0.1 && hm < 1.0);
+
+        // Momentum conservation across the bore
+        let g = 9.81;
+        let flux_jump = hm * um * (um - s) + 0.5 * g * hm * hm - 0.5 * g * 0.1 * 0.1;
+        assert!(flux_jump.abs() < 1e-9, "Momentum jump {:.2e}", flux_jump);
+
+        // Riemann invariant across the rarefaction
+        assert!((um + 2.0 * (g * hm).sqrt() - 2.0 * g.sqrt()).abs() < 1e-9);
+
+        // Known value for h₁/h₀ = 0.1: hₘ ≈ 0.396 h₀
+        assert!((hm - 0.396).abs() < 0.002, "hₘ = {:.4}", hm);
+    }
+
+    #[test]
+    fn test_mirrored_dam_break() {
+        let right = DamBreakSolution::new(0.0, 1.0, 0.2).unwrap();
+        let left = DamBreakSolution::new(0.0, 0.2, 1.0).unwrap();
+
+        for x in [-3.0, -1.0, 0.5, 2.0] {
+            assert!((right.depth(x, 1.0) - left.depth(-x, 1.0)).abs() < 1e-12);
+            assert!((right.velocity(x, 1.0) + left.velocity(-x, 1.0)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_initial_step() {
+        let solution = DamBreakSolution::new(5.0, 2.0, 0.5).unwrap();
+        assert_eq!(solution.depth(4.0, 0.0), 2.0);
+        assert_eq!(solution.depth(6.0, 0.0), 0.5);
+    }
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+/// Vertical reference of entered and displayed elevations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum VerticalDatum {
+    /// Bed at the wavemaker, the reference of the computations
+    #[default]
+    ChannelBed,
+    /// Chart datum of the nautical charts, usually near the lowest astronomical tide
+    ChartDatum,
+    MeanSeaLevel,
+    /// Local survey datum at a user-set level
+    User,
+}
+
+impl VerticalDatum {
+    pub const ALL: [VerticalDatum; 4] = [
+        VerticalDatum::ChannelBed,
+        VerticalDatum::ChartDatum,
+        VerticalDatum::MeanSeaLevel,
+        VerticalDatum::User,
+    ];
+
+    /// Short name for axis labels, e.g. "m CD"
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            VerticalDatum::ChannelBed => "bed",
+            VerticalDatum::ChartDatum => "CD",
+            VerticalDatum::MeanSeaLevel => "MSL",
+            VerticalDatum::User => "user datum",
+        }
+    }
+}
+
+impl std::fmt::Display for VerticalDatum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VerticalDatum::ChannelBed => write!(f, "Channel Bed"),
+            VerticalDatum::ChartDatum => write!(f, "Chart Datum (CD)"),
+            VerticalDatum::MeanSeaLevel => write!(f, "Mean Sea Level (MSL)"),
+            VerticalDatum::User => write!(f, "User Datum"),
+        }
+    }
+}
+
+/// Levels of the datums and of the still water, relative to mean sea level
+///
+/// Elevations in the solver are measured from the bed at the wavemaker, which lies one still water
+/// depth below the still water level. These levels tie that bed to the surveyed datums, so
+/// elevations can be converted from one datum to another.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct ReferenceLevels {
+    pub chart_datum: f64, // Level of chart datum, typically below MSL [m]
+    pub user_datum: f64,  // Level of the user datum [m]
+    pub still_water: f64, // Still water level, from the tide and surge [m]
+}
+
+impl ReferenceLevels {
+    /// Level of a datum relative to MSL, for the still water depth at the wavemaker
+    pub fn datum_level(&self, datum: VerticalDatum, depth: f64) -> f64 {
+        match datum {
+            VerticalDatum::ChannelBed => self.still_water - depth,
+            VerticalDatum::ChartDatum => self.chart_datum,
+            VerticalDatum::MeanSeaLevel => 0.0,
+            VerticalDatum::User => self.user_datum,
+        }
+    }
+
+    /// Elevation in a datum of a level above the bed at the wavemaker [m]
+    pub fn to_datum(&self, datum: VerticalDatum, elevation: f64, depth: f64) -> f64 {
+        elevation + self.datum_level(VerticalDatum::ChannelBed, depth) - self.datum_level(datum, depth)
+    }
+
+    /// Level above the bed at the wavemaker of an elevation in a datum [m]
+    pub fn from_datum(&self, datum: VerticalDatum, elevation: f64, depth: f64) -> f64 {
+        elevation + self.datum_level(datum, depth) - self.datum_level(VerticalDatum::ChannelBed, depth)
+    }
+
+    /// Still water level in a datum [m]
+    pub fn water_level(&self, datum: VerticalDatum, depth: f64) -> f64 {
+        self.still_water - self.datum_level(datum, depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        let levels = ReferenceLevels {
+            chart_datum: -1.5,
+            user_datum: 0.2,
+            still_water: 0.8,
+        };
+        let depth = 3.0;
+        // Bed at the wavemaker at -2.2 m MSL, which is -0.7 m CD
+        assert!((levels.to_datum(VerticalDatum::MeanSeaLevel, 0.0, depth) + 2.2).abs() < 1e-12);
+        assert!((levels.to_datum(VerticalDatum::ChartDatum, 0.0, depth) + 0.7).abs() < 1e-12);
+        assert!((levels.water_level(VerticalDatum::ChartDatum, depth) - 2.3).abs() < 1e-12);
+        assert!((levels.water_level(VerticalDatum::ChannelBed, depth) - depth).abs() < 1e-12);
+
+        for datum in VerticalDatum::ALL {
+            let elevation = levels.to_datum(datum, 1.25, depth);
+            assert!((levels.from_datum(datum, elevation, depth) - 1.25).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+/// Way the solver takes energy out of the waves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DissipationMechanism {
+    /// Bores and fronts, the remainder of the energy balance of each step
+    Breaking,
+    /// Drag of the vegetation stems, the only bed friction of the solver
+    Vegetation,
+    /// Relaxation towards rest in the sponge layer
+    Sponge,
+}
+
+impl DissipationMechanism {
+    pub const ALL: [DissipationMechanism; 3] = [DissipationMechanism::Breaking, DissipationMechanism::Vegetation, DissipationMechanism::Sponge];
+}
+
+impl std::fmt::Display for DissipationMechanism {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DissipationMechanism::Breaking => write!(f, "Breaking"),
+            DissipationMechanism::Vegetation => write!(f, "Vegetation"),
+            DissipationMechanism::Sponge => write!(f, "Sponge"),
+        }
+    }
+}
+
+/// Wave energy per unit width and density removed since time zero by each mechanism, integrated over the channel [m³/s²]
+///
+/// The vegetation drag and the sponge are accounted where they act. Breaking is what remains of the
+/// energy balance of each step once the work of the wavemaker and the internal sources, the energy
+/// carried out through the open boundaries and those two sinks are accounted: the energy lost in
+/// bores and fronts, with the numerical dissipation of the scheme. The solver has no bed friction
+/// or porous layer, so nothing else is dissipated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Dissipation {
+    pub breaking: f64,   // Remainder of the energy balance [m³/s²]
+    pub vegetation: f64, // Work of the stem drag [m³/s²]
+    pub sponge: f64,     // Energy relaxed away in the sponge layer [m³/s²]
+}
+
+impl Dissipation {
+    /// Energy removed by one mechanism [m³/s²]
+    pub fn get(&self, mechanism: DissipationMechanism) -> f64 {
+        match mechanism {
+            DissipationMechanism::Breaking => self.breaking,
+            DissipationMechanism::Vegetation => self.vegetation,
+            DissipationMechanism::Sponge => self.sponge,
+        }
+    }
+
+    /// Energy removed by every mechanism [m³/s²]
+    pub fn total(&self) -> f64 {
+        self.breaking + self.vegetation + self.sponge
+    }
+}
@@ -0,0 +1,104 @@
+use super::survey::SurveyUnit;
+use serde::{Deserialize, Serialize};
+
+/// Placement of a 2D domain in a projected coordinate reference system (CRS)
+///
+/// The domain keeps its local coordinates in meters from its south-west corner, x along the wavemaker
+/// normal towards the east and y towards the north; the reference only offsets them to the projected
+/// coordinates of the site. The CRS is an identifier such as `EPSG:32619` or the WKT of a `.prj` file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeoReference {
+    pub crs: String,      // Projected coordinate reference system, empty when unknown
+    pub unit: SurveyUnit, // Unit of the projected coordinates
+    pub origin: [f64; 2], // Easting and northing of the south-west corner of the domain, in the unit
+}
+
+impl Default for GeoReference {
+    fn default() -> Self {
+        Self { crs: String::new(), unit: SurveyUnit::Meters, origin: [0.0, 0.0] }
+    }
+}
+
+impl GeoReference {
+    /// Create new geographic reference
+    pub fn new(crs: &str, unit: SurveyUnit, origin: [f64; 2]) -> Result<Self, String> {
+        let reference = Self { crs: crs.trim().to_string(), unit, origin };
+        reference.validate()?;
+        Ok(reference)
+    }
+
+    /// Check that the origin is finite
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.origin[0].is_finite() && self.origin[1].is_finite()) {
+            return Err("Easting and northing of the origin must be finite".to_string());
+        }
+        Ok(())
+    }
+
+    /// Projected coordinates (easting, northing) of local point (x, y) [m]
+    pub fn to_world(&self, x: f64, y: f64) -> [f64; 2] {
+        let scale = self.unit.to_meters();
+        [self.origin[0] + x / scale, self.origin[1] + y / scale]
+    }
+
+    /// Local coordinates (x, y) [m] of projected point (easting, northing)
+    pub fn to_local(&self, easting: f64, northing: f64) -> [f64; 2] {
+        let scale = self.unit.to_meters();
+        [(easting - self.origin[0]) * scale, (northing - self.origin[1]) * scale]
+    }
+
+    /// EPSG code of the CRS, given as `EPSG:code` or as the authority of a WKT definition
+    pub fn epsg(&self) -> Option<u32> {
+        let upper = self.crs.to_ascii_uppercase();
+        if let Some(code) = upper.strip_prefix("EPSG:") {
+            return code.trim().parse().ok();
+        }
+        // The last authority of a WKT definition is that of the whole CRS
+        let start = upper.rfind("AUTHORITY[\"EPSG\",").map(|start| start + 17).or_else(|| upper.rfind("ID[\"EPSG\",").map(|start| start + 10))?;
+        upper[start..].trim_start_matches([' ', '"']).split(|c: char| !c.is_ascii_digit()).next()?.parse().ok()
+    }
+
+    /// Short name of the CRS: the name of a WKT definition, or the identifier as given
+    pub fn name(&self) -> &str {
+        let crs = self.crs.trim();
+        if crs.is_empty() {
+            return "Unknown CRS";
+        }
+        match (crs.find('"'), crs.starts_with(|c: char| c.is_ascii_alphabetic()) && crs.contains('[')) {
+            (Some(start), true) => crs[start + 1..].split('"').next().unwrap_or(crs),
+            _ => crs,
+        }
+    }
+}
+
+impl std::fmt::Display for GeoReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}, origin E {:.2} {} N {:.2} {}", self.name(), self.origin[0], self.unit, self.origin[1], self.unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_world_coordinates() {
+        let reference = GeoReference::new(" EPSG:2249 ", SurveyUnit::Feet, [775000.0, 2950000.0]).unwrap();
+        assert_eq!(reference.crs, "EPSG:2249");
+        assert_eq!(reference.to_world(30.48, 3.048), [775100.0, 2950010.0]);
+        let [x, y] = reference.to_local(775100.0, 2950010.0);
+        assert!((x - 30.48).abs() < 1e-9 && (y - 3.048).abs() < 1e-9);
+        assert!(GeoReference::new("", SurveyUnit::Meters, [f64::NAN, 0.0]).is_err());
+    }
+
+    #[test]
+    fn test_crs_names() {
+        let wkt = r#"PROJCS["WGS 84 / UTM zone 19N",GEOGCS["WGS 84",AUTHORITY["EPSG","4326"]],UNIT["metre",1],AUTHORITY["EPSG","32619"]]"#;
+        let reference = GeoReference { crs: wkt.to_string(), ..GeoReference::default() };
+        assert_eq!((reference.name(), reference.epsg()), ("WGS 84 / UTM zone 19N", Some(32619)));
+        let reference = GeoReference { crs: "epsg:32198".to_string(), ..GeoReference::default() };
+        assert_eq!((reference.name(), reference.epsg()), ("epsg:32198", Some(32198)));
+        assert_eq!(GeoReference::default().name(), "Unknown CRS");
+        assert_eq!(GeoReference::default().epsg(), None);
+    }
+}
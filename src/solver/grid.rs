@@ -0,0 +1,412 @@
+use serde::{Deserialize, Serialize};
+
+/// Part of the channel where the grid is refined, e.g. over a structure or the surf zone
+///
+/// Cells inside the zone are `factor` times smaller than offshore. The spacing changes smoothly over
+/// half the zone length on each side, so neighbouring cells differ little in size.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RefinementZone {
+    pub start: f64,  // Position of the start of the zone [m]
+    pub end: f64,    // Position of the end of the zone [m]
+    pub factor: f64, // Ratio of the offshore spacing to the spacing in the zone
+}
+
+impl RefinementZone {
+    /// Create new refinement zone
+    pub fn new(start: f64, end: f64, factor: f64) -> Result<Self, String> {
+        let zone = Self { start, end, factor };
+        zone.validate()?;
+        Ok(zone)
+    }
+
+    /// Check that the zone has a positive length and a refinement factor of at least 1
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.start.is_finite() && self.end.is_finite() && self.end > self.start) {
+            return Err("Refinement zone must end after it starts".to_string());
+        }
+        if !(self.factor.is_finite() && self.factor >= 1.0) {
+            return Err("Refinement factor must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// Relative number of cells per meter at position x: the factor inside, 1 far from the zone
+    fn density(&self, x: f64) -> f64 {
+        let transition = 0.5 * (self.end - self.start);
+        let smoothstep = |t: f64| {
+            let t = t.clamp(0.0, 1.0);
+            t * t * (3.0 - 2.0 * t)
+        };
+        let weight = smoothstep((x - self.start + transition) / transition) * smoothstep((self.end + transition - x) / transition);
+        1.0 + (self.factor - 1.0) * weight
+    }
+}
+
+/// Staggered 1D grid: water levels at cell centers, velocities at cell faces
+///
+/// Cells are uniform or stretched: a refined grid has the same number of cells, with more of them
+/// in the refinement zones and fewer offshore.
+#[derive(Debug, Clone)]
+pub struct Grid1D {
+    /// Number of cells
+    nx: usize,
+    /// Cell widths (Δx) [m]
+    widths: Vec<f64>,
+    /// Cell center coordinates [m]
+    x_centers: Vec<f64>,
+    /// Cell face coordinates [m]
+    x_faces: Vec<f64>,
+}
+
+impl Grid1D {
+    /// Create a uniform grid of nx cells over a channel of given length
+    pub fn new(length: f64, nx: usize) -> Result<Self, String> {
+        if length <= 0.0 {
+            return Err("Channel length must be positive".to_string());
+        }
+        if nx < 2 {
+            return Err("Grid must have at least 2 cells".to_string());
+        }
+
+        let dx = length / nx as f64;
+        Self::from_faces((0..=nx).map(|i| i as f64 * dx).collect())
+    }
+
+    /// Create a grid of nx cells over a channel, refined in the given zones
+    pub fn refined(length: f64, nx: usize, zones: &[RefinementZone]) -> Result<Self, String> {
+        if zones.is_empty() {
+            return Self::new(length, nx);
+        }
+        if length <= 0.0 {
+            return Err("Channel length must be positive".to_string());
+        }
+        if nx < 2 {
+            return Err("Grid must have at least 2 cells".to_string());
+        }
+        for zone in zones {
+            zone.validate()?;
+        }
+
+        let samples = 16 * nx;
+        let step = length / samples as f64;
+        let cumulative = cumulative_density(length, samples, zones);
+
+        // Faces at equal increments of the cumulative density
+        let total = cumulative[samples];
+        let mut faces = vec![0.0; nx + 1];
+        let mut j = 0;
+        for (i, face) in faces.iter_mut().enumerate().take(nx).skip(1) {
+            let target = total * i as f64 / nx as f64;
+            while cumulative[j + 1] < target {
+                j += 1;
+            }
+            let fraction = (target - cumulative[j]) / (cumulative[j + 1] - cumulative[j]);
+            *face = (j as f64 + fraction) * step;
+        }
+        faces[nx] = length;
+        Self::from_faces(faces)
+    }
+
+    /// Number of cells of a refined grid whose offshore cells have a given spacing
+    pub fn refined_cell_count(length: f64, spacing: f64, zones: &[RefinementZone]) -> usize {
+        let samples = ((length / spacing).ceil() as usize).clamp(1, 1_000_000) * 16;
+        (cumulative_density(length, samples, zones)[samples] / spacing).ceil().max(2.0) as usize
+    }
+
+    /// Create a grid from the positions of its faces, starting at the wavemaker
+    pub fn from_faces(x_faces: Vec<f64>) -> Result<Self, String> {
+        if x_faces.len() < 3 {
+            return Err("Grid must have at least 2 cells".to_string());
+        }
+        if x_faces[0] != 0.0 {
+            return Err("Grid must start at the wavemaker".to_string());
+        }
+        if x_faces.windows(2).any(|pair| !(pair[1] > pair[0] && pair[1].is_finite())) {
+            return Err("Grid faces must be increasing".to_string());
+        }
+
+        let widths = x_faces.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        let x_centers = x_faces.windows(2).map(|pair| 0.5 * (pair[0] + pair[1])).collect();
+        Ok(Self {
+            nx: x_faces.len() - 1,
+            widths,
+            x_centers,
+            x_faces,
+        })
+    }
+
+    /// Number of cells
+    pub fn nx(&self) -> usize {
+        self.nx
+    }
+
+    /// Mean grid spacing, the spacing of a uniform grid [m]
+    pub fn dx(&self) -> f64 {
+        self.length() / self.nx as f64
+    }
+
+    /// Width of every cell [m]
+    pub fn widths(&self) -> &[f64] {
+        &self.widths
+    }
+
+    /// Smallest cell width [m]
+    pub fn min_dx(&self) -> f64 {
+        self.widths.iter().copied().fold(f64::INFINITY, f64::min)
+    }
+
+    /// Largest cell width [m]
+    pub fn max_dx(&self) -> f64 {
+        self.widths.iter().copied().fold(0.0, f64::max)
+    }
+
+    /// Check if every cell has the same width
+    pub fn is_uniform(&self) -> bool {
+        self.max_dx() - self.min_dx() < 1e-9 * self.max_dx()
+    }
+
+    /// Distance between the centers of the cells on both sides of a face [m]
+    /// The end faces see half a cell, or the wrapped neighbour on a periodic domain
+    pub fn face_spacing(&self, face: usize, periodic: bool) -> f64 {
+        match face {
+            0 if periodic => 0.5 * (self.widths[0] + self.widths[self.nx - 1]),
+            0 => 0.5 * self.widths[0],
+            f if f == self.nx && periodic => 0.5 * (self.widths[0] + self.widths[self.nx - 1]),
+            f if f == self.nx => 0.5 * self.widths[self.nx - 1],
+            f => self.x_centers[f] - self.x_centers[f - 1],
+        }
+    }
+
+    /// Channel length [m]
+    pub fn length(&self) -> f64 {
+        self.x_faces[self.nx]
+    }
+
+    /// Cell center coordinates [m]
+    pub fn x_centers(&self) -> &[f64] {
+        &self.x_centers
+    }
+
+    /// Cell face coordinates [m]
+    pub fn x_faces(&self) -> &[f64] {
+        &self.x_faces
+    }
+
+    /// Index of the cell containing position x, clamped to the domain
+    pub fn cell_index(&self, x: f64) -> usize {
+        self.x_faces.partition_point(|&face| face <= x).clamp(1, self.nx) - 1
+    }
+
+    /// Linearly interpolate a cell-centered field at position x
+    /// Values beyond the outermost cell centers are held constant
+    pub fn interpolate_centers(&self, values: &[f64], x: f64) -> f64 {
+        let i = self.x_centers.partition_point(|&center| center <= x);
+        if i == 0 {
+            return values[0];
+        }
+        if i == self.nx {
+            return values[self.nx - 1];
+        }
+        let weight = (x - self.x_centers[i - 1]) / (self.x_centers[i] - self.x_centers[i - 1]);
+        values[i - 1] * (1.0 - weight) + values[i] * weight
+    }
+}
+
+/// Uniform staggered 2D grid of a rectangular basin
+///
+/// Water levels live at cell centers, x velocities on the faces between columns and y velocities on
+/// the faces between rows. Cells are numbered row by row from the south-west corner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Grid2D {
+    /// Number of cells along x
+    nx: usize,
+    /// Number of cells along y
+    ny: usize,
+    /// Basin length along x [m]
+    length: f64,
+    /// Basin width along y [m]
+    width: f64,
+}
+
+impl Grid2D {
+    /// Create a uniform grid of nx × ny cells over a basin of given length (x) and width (y)
+    pub fn new(length: f64, width: f64, nx: usize, ny: usize) -> Result<Self, String> {
+        if !(length > 0.0 && width > 0.0) {
+            return Err("Basin length and width must be positive".to_string());
+        }
+        if nx < 2 || ny < 2 {
+            return Err("Grid must have at least 2 cells in each direction".to_string());
+        }
+        Ok(Self { nx, ny, length, width })
+    }
+
+    /// Number of cells along x
+    pub fn nx(&self) -> usize {
+        self.nx
+    }
+
+    /// Number of cells along y
+    pub fn ny(&self) -> usize {
+        self.ny
+    }
+
+    /// Number of cells
+    pub fn cells(&self) -> usize {
+        self.nx * self.ny
+    }
+
+    /// Basin length along x [m]
+    pub fn length(&self) -> f64 {
+        self.length
+    }
+
+    /// Basin width along y [m]
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+
+    /// Grid spacing along x [m]
+    pub fn dx(&self) -> f64 {
+        self.length / self.nx as f64
+    }
+
+    /// Grid spacing along y [m]
+    pub fn dy(&self) -> f64 {
+        self.width / self.ny as f64
+    }
+
+    /// Coordinates of the center of cell (i, j) [m]
+    pub fn center(&self, i: usize, j: usize) -> (f64, f64) {
+        ((i as f64 + 0.5) * self.dx(), (j as f64 + 0.5) * self.dy())
+    }
+
+    /// Index of cell (i, j) in a cell-centered field
+    pub fn index(&self, i: usize, j: usize) -> usize {
+        j * self.nx + i
+    }
+
+    /// Column and row of the cell containing point (x, y), clamped to the basin
+    pub fn cell_at(&self, x: f64, y: f64) -> (usize, usize) {
+        let i = (x / self.dx()).floor().clamp(0.0, (self.nx - 1) as f64) as usize;
+        let j = (y / self.dy()).floor().clamp(0.0, (self.ny - 1) as f64) as usize;
+        (i, j)
+    }
+
+    /// Bilinearly interpolate a cell-centered field at point (x, y)
+    /// Values beyond the outermost cell centers are held constant
+    pub fn interpolate_centers(&self, values: &[f64], x: f64, y: f64) -> f64 {
+        let position = |coordinate: f64, spacing: f64, cells: usize| {
+            let s = (coordinate / spacing - 0.5).clamp(0.0, (cells - 1) as f64);
+            let lower = (s.floor() as usize).min(cells - 2);
+            (lower, s - lower as f64)
+        };
+        let (i, wx) = position(x, self.dx(), self.nx);
+        let (j, wy) = position(y, self.dy(), self.ny);
+        let value = |i: usize, j: usize| values[self.index(i, j)];
+        (value(i, j) * (1.0 - wx) + value(i + 1, j) * wx) * (1.0 - wy) + (value(i, j + 1) * (1.0 - wx) + value(i + 1, j + 1) * wx) * wy
+    }
+}
+
+/// Cell density of the refinement zones integrated from the wavemaker, with the midpoint rule
+fn cumulative_density(length: f64, samples: usize, zones: &[RefinementZone]) -> Vec<f64> {
+    let density = |x: f64| zones.iter().map(|zone| zone.density(x)).fold(1.0, f64::max);
+    let step = length / samples as f64;
+    let mut cumulative = vec![0.0; samples + 1];
+    for j in 0..samples {
+        cumulative[j + 1] = cumulative[j] + density((j as f64 + 0.5) * step) * step;
+    }
+    cumulative
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_creation() {
+        let grid = Grid1D::new(10.0, 5).unwrap();
+        assert_eq!(grid.nx(), 5);
+        assert_eq!(grid.dx(), 2.0);
+        assert_eq!(grid.length(), 10.0);
+        assert_eq!(grid.x_centers(), &[1.0, 3.0, 5.0, 7.0, 9.0]);
+        assert_eq!(grid.x_faces(), &[0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+    }
+
+    #[test]
+    fn test_invalid_grid() {
+        assert!(Grid1D::new(0.0, 10).is_err());
+        assert!(Grid1D::new(10.0, 1).is_err());
+    }
+
+    #[test]
+    fn test_cell_index() {
+        let grid = Grid1D::new(10.0, 5).unwrap();
+        assert_eq!(grid.cell_index(0.0), 0);
+        assert_eq!(grid.cell_index(4.5), 2);
+        assert_eq!(grid.cell_index(10.0), 4);
+        assert_eq!(grid.cell_index(-1.0), 0);
+    }
+
+    #[test]
+    fn test_refined_grid() {
+        let zone = RefinementZone::new(60.0, 80.0, 4.0).unwrap();
+        let grid = Grid1D::refined(100.0, 400, &[zone]).unwrap();
+        assert_eq!(grid.nx(), 400);
+        assert!((grid.length() - 100.0).abs() < 1e-12);
+        assert!(!grid.is_uniform());
+
+        // Four times finer in the zone than offshore
+        let offshore = grid.widths()[grid.cell_index(10.0)];
+        let refined = grid.widths()[grid.cell_index(70.0)];
+        assert!((offshore / refined - 4.0).abs() < 0.01, "{} / {}", offshore, refined);
+        // Smooth transition between neighbouring cells
+        assert!(grid.widths().windows(2).all(|pair| (pair[1] / pair[0] - 1.0).abs() < 0.1));
+
+        let total: f64 = grid.widths().iter().sum();
+        assert!((total - 100.0).abs() < 1e-9);
+        assert!(RefinementZone::new(10.0, 5.0, 2.0).is_err());
+        assert!(RefinementZone::new(5.0, 10.0, 0.5).is_err());
+    }
+
+    #[test]
+    fn test_stretched_interpolation() {
+        let grid = Grid1D::from_faces(vec![0.0, 1.0, 3.0, 7.0]).unwrap();
+        assert_eq!(grid.x_centers(), &[0.5, 2.0, 5.0]);
+        assert_eq!(grid.cell_index(2.5), 1);
+        assert_eq!(grid.cell_index(7.0), 2);
+        assert_eq!(grid.face_spacing(2, false), 3.0);
+        assert_eq!(grid.face_spacing(0, true), 2.5);
+        let values = [0.0, 3.0, 6.0];
+        assert_eq!(grid.interpolate_centers(&values, 3.5), 4.5);
+        assert!(Grid1D::from_faces(vec![0.0, 2.0, 1.0]).is_err());
+    }
+
+    #[test]
+    fn test_interpolate_centers() {
+        let grid = Grid1D::new(10.0, 5).unwrap();
+        let values = [0.0, 2.0, 4.0, 6.0, 8.0];
+        assert_eq!(grid.interpolate_centers(&values, 2.0), 1.0);
+        assert_eq!(grid.interpolate_centers(&values, 3.0), 2.0);
+        assert_eq!(grid.interpolate_centers(&values, 0.0), 0.0);
+        assert_eq!(grid.interpolate_centers(&values, 10.0), 8.0);
+    }
+
+    #[test]
+    fn test_grid_2d() {
+        let grid = Grid2D::new(10.0, 4.0, 5, 4).unwrap();
+        assert_eq!((grid.dx(), grid.dy()), (2.0, 1.0));
+        assert_eq!(grid.center(1, 2), (3.0, 2.5));
+        assert_eq!(grid.index(1, 2), 11);
+        assert_eq!(grid.cell_at(3.5, 0.2), (1, 0));
+        assert_eq!(grid.cell_at(20.0, -1.0), (4, 0));
+        assert!(Grid2D::new(10.0, 0.0, 5, 4).is_err());
+
+        // A plane is interpolated exactly between the cell centers
+        let values: Vec<f64> = (0..grid.cells()).map(|n| {
+            let (x, y) = grid.center(n % 5, n / 5);
+            x + 2.0 * y
+        }).collect();
+        assert!((grid.interpolate_centers(&values, 4.2, 1.7) - 7.6).abs() < 1e-12);
+        assert!((grid.interpolate_centers(&values, 0.0, 0.0) - 2.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+
+/// Solid structure of a harbor drawn as a polygon: a breakwater, a quay, a pier or the land behind them
+///
+/// The cells whose center lies inside are removed from the computation. Waves running into the faces
+/// of the structure are partially reflected: the face lets through the velocity α √(g/d) η of the
+/// incoming wave, α = (1 − R)/(1 + R), so that a share R of the wave height comes back at normal
+/// incidence. Vertical quay walls reflect R ≈ 0.9 to 1, rubble mound slopes R ≈ 0.3 to 0.5 and
+/// wave absorbing quays R ≈ 0.2 to 0.4 (PIANC, 1995).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarborStructure {
+    pub name: String,           // Label in the editor and the reports
+    pub vertices: Vec<[f64; 2]>, // Corners (x, y) in order around the outline [m]
+    pub reflection: f64,        // Reflection coefficient R of its faces, from 0 (absorbing) to 1 (reflecting)
+}
+
+impl HarborStructure {
+    /// Create new structure from its outline
+    pub fn new(name: &str, vertices: Vec<[f64; 2]>, reflection: f64) -> Self {
+        Self { name: name.to_string(), vertices, reflection }
+    }
+
+    /// Check if point (x, y) lies inside the outline, by the even-odd rule
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            if (a[1] > y) != (b[1] > y) && x < a[0] + (y - a[1]) / (b[1] - a[1]) * (b[0] - a[0]) {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+
+    /// Area enclosed by the outline [m²]
+    pub fn area(&self) -> f64 {
+        0.5 * self.edges().map(|(a, b)| a[0] * b[1] - b[0] * a[1]).sum::<f64>().abs()
+    }
+
+    /// Factor α = (1 − R)/(1 + R) of the velocity of the waves let through its faces
+    pub fn absorption(&self) -> f64 {
+        (1.0 - self.reflection) / (1.0 + self.reflection)
+    }
+
+    /// Check the outline and the reflection coefficient
+    pub fn validate(&self) -> Result<(), String> {
+        if self.vertices.len() < 3 {
+            return Err(format!("Structure \"{}\" needs at least three corners", self.name));
+        }
+        if self.vertices.iter().any(|vertex| !(vertex[0].is_finite() && vertex[1].is_finite())) {
+            return Err(format!("Structure \"{}\" has a corner that is not a number", self.name));
+        }
+        if self.area() <= 0.0 {
+            return Err(format!("Structure \"{}\" encloses no area", self.name));
+        }
+        if !(0.0..=1.0).contains(&self.reflection) {
+            return Err(format!("Reflection coefficient of \"{}\" must lie between 0 and 1", self.name));
+        }
+        Ok(())
+    }
+
+    /// Sides of the outline, closing back on the first corner
+    fn edges(&self) -> impl Iterator<Item = ([f64; 2], [f64; 2])> + '_ {
+        self.vertices.iter().zip(self.vertices.iter().cycle().skip(1)).map(|(&a, &b)| (a, b))
+    }
+}
+
+/// Opening between structures through which the waves enter the harbor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarborEntrance {
+    pub name: String,    // Label in the editor and the reports
+    pub start: [f64; 2], // One side of the opening (x, y) [m]
+    pub end: [f64; 2],   // Other side of the opening (x, y) [m]
+}
+
+impl HarborEntrance {
+    /// Width of the opening [m]
+    pub fn width(&self) -> f64 {
+        (self.end[0] - self.start[0]).hypot(self.end[1] - self.start[1])
+    }
+
+    /// Middle of the opening (x, y) [m]
+    pub fn midpoint(&self) -> [f64; 2] {
+        [0.5 * (self.start[0] + self.end[0]), 0.5 * (self.start[1] + self.end[1])]
+    }
+}
+
+/// Mooring place along a quay, where the agitation is reported
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Berth {
+    pub name: String,    // Label in the editor and the reports
+    pub start: [f64; 2], // One end of the line the moored ship lies along (x, y) [m]
+    pub end: [f64; 2],   // Other end of the line (x, y) [m]
+    pub limit: f64,      // Highest acceptable significant wave height for the ships at the berth [m]
+}
+
+/// Layout of a harbor in a wave basin: structures, entrances and berths
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HarborLayout {
+    pub structures: Vec<HarborStructure>,
+    pub entrances: Vec<HarborEntrance>,
+    pub berths: Vec<Berth>,
+}
+
+impl HarborLayout {
+    /// Check if the layout has no structures, entrances or berths
+    pub fn is_empty(&self) -> bool {
+        self.structures.is_empty() && self.entrances.is_empty() && self.berths.is_empty()
+    }
+
+    /// Structure covering point (x, y), the last drawn where they overlap
+    pub fn structure_at(&self, x: f64, y: f64) -> Option<&HarborStructure> {
+        self.structures.iter().rev().find(|structure| structure.contains(x, y))
+    }
+
+    /// Check every structure, entrance and berth
+    pub fn validate(&self) -> Result<(), String> {
+        for structure in &self.structures {
+            structure.validate()?;
+        }
+        for entrance in &self.entrances {
+            if !(entrance.width().is_finite() && entrance.width() > 0.0) {
+                return Err(format!("Entrance \"{}\" must have a positive width", entrance.name));
+            }
+        }
+        for berth in &self.berths {
+            let length = (berth.end[0] - berth.start[0]).hypot(berth.end[1] - berth.start[1]);
+            if !(length.is_finite() && length > 0.0) {
+                return Err(format!("Berth \"{}\" must have a positive length", berth.name));
+            }
+            if !(berth.limit.is_finite() && berth.limit > 0.0) {
+                return Err(format!("Acceptable wave height at berth \"{}\" must be positive", berth.name));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_structure_outline() {
+        // L-shaped quay
+        let quay = HarborStructure::new("Quay", vec![[0.0, 0.0], [4.0, 0.0], [4.0, 1.0], [1.0, 1.0], [1.0, 3.0], [0.0, 3.0]], 0.9);
+        assert_eq!(quay.area(), 6.0);
+        assert!(quay.contains(0.5, 2.5) && quay.contains(3.5, 0.5));
+        assert!(!quay.contains(2.0, 2.0) && !quay.contains(5.0, 0.5));
+        assert!(quay.validate().is_ok());
+        assert!((quay.absorption() - 0.1 / 1.9).abs() < 1e-12);
+
+        assert!(HarborStructure::new("Line", vec![[0.0, 0.0], [1.0, 1.0], [2.0, 2.0]], 1.0).validate().is_err());
+        assert!(HarborStructure::new("Quay", quay.vertices.clone(), 1.5).validate().is_err());
+    }
+
+    #[test]
+    fn test_layout_validation() {
+        let mut layout = HarborLayout::default();
+        assert!(layout.is_empty() && layout.validate().is_ok());
+        layout.structures.push(HarborStructure::new("A", vec![[0.0, 0.0], [2.0, 0.0], [2.0, 2.0]], 1.0));
+        layout.structures.push(HarborStructure::new("B", vec![[1.0, 0.0], [3.0, 0.0], [3.0, 2.0], [1.0, 2.0]], 0.4));
+        assert_eq!(layout.structure_at(1.5, 0.5).map(|structure| structure.name.as_str()), Some("B"));
+        assert!(layout.structure_at(0.5, 1.5).is_none());
+
+        let entrance = HarborEntrance { name: "Entrance".to_string(), start: [0.0, 5.0], end: [3.0, 9.0] };
+        assert_eq!((entrance.width(), entrance.midpoint()), (5.0, [1.5, 7.0]));
+        layout.berths.push(Berth { name: "Berth".to_string(), start: [0.0, 3.0], end: [3.0, 3.0], limit: 0.0 });
+        assert!(layout.validate().is_err());
+    }
+}
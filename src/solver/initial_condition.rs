@@ -0,0 +1,196 @@
+use crate::solver::dam_break::DamBreakSolution;
+use serde::{Deserialize, Serialize};
+
+/// Initial state of an initial-value problem, released from rest without a wavemaker
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum InitialCondition {
+    /// Water held behind a dam that is removed instantaneously
+    DamBreak {
+        /// Dam position [m]
+        position: f64,
+        /// Water depth on the left of the dam [m]
+        upstream_depth: f64,
+        /// Water depth on the right of the dam, zero for a dry bed [m]
+        downstream_depth: f64,
+    },
+    /// Gaussian surface hump η = A exp(-((x - x₀)/w)²), e.g. a tsunami source
+    GaussianHump {
+        /// Hump center (x₀) [m]
+        center: f64,
+        /// Hump amplitude (A) [m]
+        amplitude: f64,
+        /// Hump width (w) [m]
+        width: f64,
+    },
+}
+
+impl InitialCondition {
+    /// Dam break in the middle of the channel, releasing a reservoir twice the still water depth
+    pub fn dam_break(length: f64, depth: f64) -> Self {
+        InitialCondition::DamBreak {
+            position: 0.5 * length,
+            upstream_depth: 2.0 * depth,
+            downstream_depth: depth,
+        }
+    }
+
+    /// Gaussian hump in the middle of the channel with a tenth of the still water depth as amplitude
+    pub fn gaussian_hump(length: f64, depth: f64) -> Self {
+        InitialCondition::GaussianHump {
+            center: 0.5 * length,
+            amplitude: 0.1 * depth,
+            width: 0.05 * length,
+        }
+    }
+
+    /// Initial surface elevation at position x over a bed at the given still water depth [m]
+    pub fn surface_elevation(&self, x: f64, depth: f64) -> f64 {
+        match *self {
+            InitialCondition::DamBreak {
+                position,
+                upstream_depth,
+                downstream_depth,
+            } => {
+                let water_depth = if x < position { upstream_depth } else { downstream_depth };
+                water_depth - depth
+            }
+            InitialCondition::GaussianHump {
+                center,
+                amplitude,
+                width,
+            } => amplitude * (-((x - center) / width).powi(2)).exp(),
+        }
+    }
+
+    /// Analytical surface elevation at position x and time t, ignoring the channel ends [m]
+    /// Dam breaks follow Ritter/Stoker; a hump splits into two linear long waves travelling at √(gd)
+    pub fn analytical_elevation(&self, x: f64, time: f64, depth: f64) -> f64 {
+        match *self {
+            InitialCondition::DamBreak {
+                position,
+                upstream_depth,
+                downstream_depth,
+            } => DamBreakSolution::new(position, upstream_depth, downstream_depth)
+                .map_or_else(|_| self.surface_elevation(x, depth), |solution| solution.depth(x, time) - depth),
+            InitialCondition::GaussianHump { .. } => {
+                let travel = (9.81 * depth).sqrt() * time;
+                0.5 * (self.surface_elevation(x - travel, depth) + self.surface_elevation(x + travel, depth))
+            }
+        }
+    }
+
+    /// Largest water depth of the initial state [m]
+    pub fn max_depth(&self, depth: f64) -> f64 {
+        match *self {
+            InitialCondition::DamBreak {
+                upstream_depth,
+                downstream_depth,
+                ..
+            } => upstream_depth.max(downstream_depth),
+            InitialCondition::GaussianHump { amplitude, .. } => depth + amplitude.max(0.0),
+        }
+    }
+
+    /// Validate parameters for a channel of given length and still water depth
+    pub fn validate(&self, length: f64, depth: f64) -> Result<(), String> {
+        match *self {
+            InitialCondition::DamBreak {
+                position,
+                upstream_depth,
+                downstream_depth,
+            } => {
+                if position <= 0.0 || position >= length {
+                    return Err(format!("Dam position must be inside the channel (0 to {:.1} m)", length));
+                }
+                DamBreakSolution::new(position, upstream_depth, downstream_depth)?;
+            }
+            InitialCondition::GaussianHump {
+                center,
+                amplitude,
+                width,
+            } => {
+                if !(0.0..=length).contains(&center) {
+                    return Err(format!("Hump center must be inside the channel (0 to {:.1} m)", length));
+                }
+                if width <= 0.0 {
+                    return Err("Hump width must be positive".to_string());
+                }
+                if amplitude <= -depth {
+                    return Err("Hump trough must not reach the bed".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for InitialCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InitialCondition::DamBreak { .. } => write!(f, "Dam Break"),
+            InitialCondition::GaussianHump { .. } => write!(f, "Gaussian Hump"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dam_break_surface() {
+        let dam = InitialCondition::DamBreak {
+            position: 10.0,
+            upstream_depth: 2.0,
+            downstream_depth: 0.0,
+        };
+        assert_eq!(dam.surface_elevation(5.0, 1.0), 1.0);
+        assert_eq!(dam.surface_elevation(15.0, 1.0), -1.0);
+        assert_eq!(dam.max_depth(1.0), 2.0);
+
+        // Ritter: depth 4/9 h₀ at the dam once released
+        assert!((dam.analytical_elevation(10.0, 1.0, 1.0) - (8.0 / 9.0 - 1.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_gaussian_hump_splits() {
+        let hump = InitialCondition::GaussianHump {
+            center: 50.0,
+            amplitude: 0.2,
+            width: 2.0,
+        };
+        assert_eq!(hump.surface_elevation(50.0, 1.0), 0.2);
+        assert_eq!(hump.analytical_elevation(50.0, 0.0, 1.0), 0.2);
+
+        let travel = 9.81_f64.sqrt() * 5.0;
+        assert!((hump.analytical_elevation(50.0 + travel, 5.0, 1.0) - 0.1).abs() < 1e-9);
+        assert!((hump.analytical_elevation(50.0 - travel, 5.0, 1.0) - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(InitialCondition::dam_break(50.0, 1.0).validate(50.0, 1.0).is_ok());
+        assert!(InitialCondition::gaussian_hump(50.0, 1.0).validate(50.0, 1.0).is_ok());
+
+        let outside = InitialCondition::DamBreak {
+            position: 60.0,
+            upstream_depth: 2.0,
+            downstream_depth: 1.0,
+        };
+        assert!(outside.validate(50.0, 1.0).is_err());
+
+        let level = InitialCondition::DamBreak {
+            position: 25.0,
+            upstream_depth: 1.0,
+            downstream_depth: 1.0,
+        };
+        assert!(level.validate(50.0, 1.0).is_err());
+
+        let flat = InitialCondition::GaussianHump {
+            center: 25.0,
+            amplitude: 0.1,
+            width: 0.0,
+        };
+        assert!(flat.validate(50.0, 1.0).is_err());
+    }
+}
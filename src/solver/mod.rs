@@ -0,0 +1,43 @@
+pub mod grid;
+pub mod advection;
+pub mod basin;
+pub mod bathymetry;
+pub mod boundary;
+pub mod block_tridiagonal;
+pub mod dam_break;
+pub mod datum;
+pub mod dissipation;
+pub mod georeference;
+pub mod harbor;
+pub mod initial_condition;
+pub mod precision;
+pub mod raster;
+pub mod refinement;
+pub mod shallow_water;
+pub mod survey;
+pub mod time_integration;
+pub mod triple_buffer;
+pub mod vegetation;
+pub mod wave_solver;
+
+pub use grid::{Grid1D, Grid2D, RefinementZone};
+pub use advection::{AdvectionScheme, Limiter};
+pub use basin::{BasinField, BasinFrame, BasinSolver, BasinSponges, Obstacle};
+pub use bathymetry::{CrossSection, DikeDesign, ProfileSegment};
+pub use boundary::RightBoundary;
+pub use block_tridiagonal::{BlockTridiagonal, BlockWork};
+pub use dam_break::DamBreakSolution;
+pub use datum::{ReferenceLevels, VerticalDatum};
+pub use dissipation::{Dissipation, DissipationMechanism};
+pub use georeference::GeoReference;
+pub use harbor::{Berth, HarborEntrance, HarborLayout, HarborStructure};
+pub use initial_condition::InitialCondition;
+pub use precision::{Precision, PrecisionComparison};
+pub use raster::{DepthRaster, RasterOptions, hillshade};
+pub use refinement::{AutoRefinement, RefinementPlan, RefinementReason, estimated_runtime, estimated_time_steps};
+pub use shallow_water::ShallowWaterSolver;
+pub use survey::{SurveyOptions, SurveyProfile, SurveyUnit};
+pub use time_integration::{ForwardBackward, RungeKutta, SemiImplicit, StageState, TimeIntegration, TimeIntegrator};
+pub use triple_buffer::{BufferReader, BufferWriter, triple_buffer};
+pub use vegetation::VegetationField;
+pub use wave_solver::{WaveSolver, sub_step};
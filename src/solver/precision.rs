@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+
+/// Floating-point precision of the state carried by the solver from one time step to the next
+///
+/// In single precision the surface elevations, velocities and non-hydrostatic pressures are rounded
+/// to f32 at the end of every step, so the solution carries the round-off of a solver storing its
+/// fields in f32, as on graphics hardware. Each step is still computed in f64, so this estimates the
+/// accuracy of an f32 solver without being one, nor any faster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Precision {
+    #[default]
+    Double,
+    Single,
+}
+
+impl std::fmt::Display for Precision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Precision::Double => write!(f, "f64"),
+            Precision::Single => write!(f, "f32"),
+        }
+    }
+}
+
+impl Precision {
+    /// Round values to the precision
+    pub fn round(&self, values: &mut [f64]) {
+        if *self == Precision::Single {
+            values.iter_mut().for_each(|value| *value = *value as f32 as f64);
+        }
+    }
+}
+
+/// Difference between the surfaces of one case run in double and in single precision
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrecisionComparison {
+    pub max_difference: f64, // Largest |η_f32 − η_f64| over the cells [m]
+    pub rms_difference: f64, // Root-mean-square of η_f32 − η_f64 over the cells [m]
+}
+
+impl PrecisionComparison {
+    /// Compare the final surfaces of both runs
+    pub fn new(double: &[f64], single: &[f64]) -> Result<Self, String> {
+        if double.is_empty() || double.len() != single.len() {
+            return Err("Both runs need the same non-empty grid".to_string());
+        }
+        let differences: Vec<f64> = double.iter().zip(single).map(|(a, b)| b - a).collect();
+        Ok(Self {
+            max_difference: differences.iter().map(|difference| difference.abs()).fold(0.0, f64::max),
+            rms_difference: (differences.iter().map(|difference| difference * difference).sum::<f64>() / differences.len() as f64).sqrt(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{InitialCondition, RightBoundary, ShallowWaterSolver};
+    use crate::waves::WaveForcing;
+
+    #[test]
+    fn test_rounding() {
+        let mut values = [0.1, 1.0 / 3.0];
+        Precision::Double.round(&mut values);
+        assert_eq!(values, [0.1, 1.0 / 3.0]);
+        Precision::Single.round(&mut values);
+        assert_eq!(values, [0.1f32 as f64, (1.0f32 / 3.0) as f64]);
+    }
+
+    #[test]
+    fn test_single_precision_dam_break() {
+        let surface = |precision: Precision| {
+            let mut solver = ShallowWaterSolver::new(50.0, 200, 1.0, RightBoundary::Wall).unwrap().with_precision(precision);
+            solver.set_initial_condition(Some(InitialCondition::dam_break(50.0, 1.0))).unwrap();
+            solver.advance(5.0, &mut WaveForcing::new());
+            solver.eta().to_vec()
+        };
+        let double = surface(Precision::Double);
+        let single = surface(Precision::Single);
+        assert!(single.iter().all(|&eta| eta as f32 as f64 == eta));
+
+        let comparison = PrecisionComparison::new(&double, &single).unwrap();
+        assert!(comparison.max_difference > 0.0 && comparison.max_difference < 1e-4, "{}", comparison.max_difference);
+        assert!(comparison.rms_difference <= comparison.max_difference);
+        assert!(PrecisionComparison::new(&double, &single[1..]).is_err());
+    }
+}
@@ -0,0 +1,384 @@
+use super::grid::Grid2D;
+use super::survey::SurveyUnit;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Conversion of a bathymetry raster to the still water depths of a basin
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RasterOptions {
+    pub horizontal_unit: SurveyUnit, // Unit of the coordinates and the cell size
+    pub vertical_unit: SurveyUnit,   // Unit of the values
+    pub water_level: f64,            // Still water level in the raster datum, in the vertical unit
+    pub positive_depths: bool,       // Values are depths below the datum, positive downward, rather than elevations
+    pub origin: [f64; 2],            // Raster coordinates (x, y) of the south-west corner of the basin, in the horizontal unit
+}
+
+impl Default for RasterOptions {
+    fn default() -> Self {
+        Self {
+            horizontal_unit: SurveyUnit::Meters,
+            vertical_unit: SurveyUnit::Meters,
+            water_level: 0.0,
+            positive_depths: false,
+            origin: [0.0, 0.0],
+        }
+    }
+}
+
+/// Gridded bathymetry: bed elevations or depths over square cells, with gaps where there is no data
+///
+/// ESRI ASCII grids start with a header of `ncols`, `nrows`, `xllcorner` or `xllcenter`, `yllcorner`
+/// or `yllcenter`, `cellsize` and optionally `nodata_value`, followed by the values row by row from
+/// the north, with the coordinate reference system in a `.prj` file of the same name. GeoTIFF files,
+/// read with the `geotiff` feature, place the single band of values by their model tiepoint and pixel
+/// scale, mark missing values by the GDAL no data tag and give the EPSG code of the projection by a geokey.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthRaster {
+    pub ncols: usize,        // Columns, along x
+    pub nrows: usize,        // Rows, along y
+    pub x_corner: f64,       // x of the west edge of the raster
+    pub y_corner: f64,       // y of the south edge of the raster
+    pub cell_size: f64,      // Side of the square cells
+    pub values: Vec<f64>,    // Values row by row from the south-west corner, NaN where there is no data
+    pub crs: Option<String>, // Coordinate reference system of the coordinates, as WKT or `EPSG:code`, None when unknown
+}
+
+impl DepthRaster {
+    /// Raster from its values row by row from the south-west corner
+    pub fn new(ncols: usize, nrows: usize, x_corner: f64, y_corner: f64, cell_size: f64, values: Vec<f64>) -> Result<Self, String> {
+        if ncols < 2 || nrows < 2 {
+            return Err("Raster needs at least two rows and two columns".to_string());
+        }
+        if values.len() != ncols * nrows {
+            return Err(format!("Raster of {} × {} cells has {} values", ncols, nrows, values.len()));
+        }
+        if !(x_corner.is_finite() && y_corner.is_finite()) {
+            return Err("Raster corner must be finite".to_string());
+        }
+        if !(cell_size.is_finite() && cell_size > 0.0) {
+            return Err("Raster cell size must be positive".to_string());
+        }
+        if values.iter().all(|value| value.is_nan()) {
+            return Err("Raster has no data".to_string());
+        }
+        Ok(Self { ncols, nrows, x_corner, y_corner, cell_size, values, crs: None })
+    }
+
+    /// Read an ESRI ASCII grid, or a GeoTIFF file with the `geotiff` feature, by its extension
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or_default().to_ascii_lowercase();
+        if extension == "tif" || extension == "tiff" {
+            #[cfg(feature = "geotiff")]
+            {
+                let bytes = std::fs::read(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+                return Self::parse_geotiff(&bytes);
+            }
+            #[cfg(not(feature = "geotiff"))]
+            return Err("GeoTIFF import needs the platform built with the geotiff feature; export the raster as an ESRI ASCII grid".to_string());
+        }
+        let content = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        let mut raster = Self::parse_ascii_grid(&content)?;
+        raster.crs = std::fs::read_to_string(path.with_extension("prj")).ok().map(|wkt| wkt.trim().to_string()).filter(|wkt| !wkt.is_empty());
+        Ok(raster)
+    }
+
+    /// Raster from the content of an ESRI ASCII grid
+    pub fn parse_ascii_grid(content: &str) -> Result<Self, String> {
+        let mut tokens = content.split_whitespace().peekable();
+        let (mut ncols, mut nrows, mut cell_size, mut nodata) = (None, None, None, None);
+        let (mut x_corner, mut y_corner, mut x_center, mut y_center) = (None, None, None, None);
+        // Header keys until the first number
+        while let Some(key) = tokens.next_if(|token| token.parse::<f64>().is_err()) {
+            let value = tokens.next().ok_or_else(|| format!("Header key \"{}\" has no value", key))?;
+            let number = value.parse::<f64>().map_err(|_| format!("Invalid value \"{}\" of header key \"{}\"", value, key))?;
+            match key.to_ascii_lowercase().as_str() {
+                "ncols" => ncols = Some(number),
+                "nrows" => nrows = Some(number),
+                "xllcorner" => x_corner = Some(number),
+                "yllcorner" => y_corner = Some(number),
+                "xllcenter" => x_center = Some(number),
+                "yllcenter" => y_center = Some(number),
+                "cellsize" => cell_size = Some(number),
+                "nodata_value" => nodata = Some(number),
+                _ => return Err(format!("Unknown header key \"{}\"", key)),
+            }
+        }
+        let count = |value: Option<f64>, key: &str| match value {
+            Some(value) if value >= 1.0 && value.fract() == 0.0 => Ok(value as usize),
+            Some(_) => Err(format!("Header key \"{}\" must be a whole number", key)),
+            None => Err(format!("Header is missing \"{}\"", key)),
+        };
+        let (ncols, nrows) = (count(ncols, "ncols")?, count(nrows, "nrows")?);
+        let cell_size = cell_size.ok_or("Header is missing \"cellsize\"")?;
+        let corner = |corner: Option<f64>, center: Option<f64>, axis: &str| match (corner, center) {
+            (Some(corner), _) => Ok(corner),
+            (None, Some(center)) => Ok(center - 0.5 * cell_size),
+            (None, None) => Err(format!("Header is missing \"{}llcorner\"", axis)),
+        };
+        let (x_corner, y_corner) = (corner(x_corner, x_center, "x")?, corner(y_corner, y_center, "y")?);
+
+        let rows: Vec<f64> = tokens
+            .enumerate()
+            .map(|(index, token)| token.parse::<f64>().map_err(|_| format!("Invalid value \"{}\" at cell {} of the grid", token, index + 1)))
+            .collect::<Result<_, _>>()?;
+        if rows.len() != ncols * nrows {
+            return Err(format!("Grid of {} × {} cells has {} values", ncols, nrows, rows.len()));
+        }
+        // Rows of the file run from the north
+        let values = rows
+            .chunks(ncols)
+            .rev()
+            .flatten()
+            .map(|&value| if nodata == Some(value) { f64::NAN } else { value })
+            .collect();
+        Self::new(ncols, nrows, x_corner, y_corner, cell_size, values)
+    }
+
+    /// Raster from the content of a single band GeoTIFF file
+    #[cfg(feature = "geotiff")]
+    pub fn parse_geotiff(bytes: &[u8]) -> Result<Self, String> {
+        use tiff::decoder::{Decoder, DecodingResult};
+        use tiff::tags::Tag;
+
+        let error = |e: tiff::TiffError| format!("Invalid GeoTIFF: {}", e);
+        let mut decoder = Decoder::new(std::io::Cursor::new(bytes)).map_err(error)?;
+        let (ncols, nrows) = decoder.dimensions().map_err(error)?;
+        let scale = decoder.get_tag_f64_vec(Tag::ModelPixelScaleTag).map_err(|_| "GeoTIFF has no pixel scale".to_string())?;
+        let tiepoint = decoder.get_tag_f64_vec(Tag::ModelTiepointTag).map_err(|_| "GeoTIFF has no tiepoint".to_string())?;
+        if scale.len() < 2 || tiepoint.len() < 6 {
+            return Err("GeoTIFF pixel scale or tiepoint is incomplete".to_string());
+        }
+        if (scale[0] - scale[1]).abs() > 1e-9 * scale[0].abs() {
+            return Err(format!("GeoTIFF cells of {} × {} are not square", scale[0], scale[1]));
+        }
+        // Geokeys follow a header of four values by four: key, location, count and value; 3072 is the projected CRS, 32767 user-defined
+        let geokeys = decoder.get_tag_u16_vec(Tag::GeoKeyDirectoryTag).unwrap_or_default();
+        let crs = geokeys
+            .get(4..)
+            .unwrap_or_default()
+            .chunks_exact(4)
+            .find(|key| key[0] == 3072 && key[1] == 0 && key[3] != 32767)
+            .map(|key| format!("EPSG:{}", key[3]));
+        let nodata = decoder.get_tag_ascii_string(Tag::GdalNodata).ok().and_then(|text| text.trim_matches(char::from(0)).trim().parse::<f64>().ok());
+        let rows: Vec<f64> = match decoder.read_image().map_err(error)? {
+            DecodingResult::U8(values) => values.into_iter().map(f64::from).collect(),
+            DecodingResult::U16(values) => values.into_iter().map(f64::from).collect(),
+            DecodingResult::U32(values) => values.into_iter().map(f64::from).collect(),
+            DecodingResult::I8(values) => values.into_iter().map(f64::from).collect(),
+            DecodingResult::I16(values) => values.into_iter().map(f64::from).collect(),
+            DecodingResult::I32(values) => values.into_iter().map(f64::from).collect(),
+            DecodingResult::F32(values) => values.into_iter().map(f64::from).collect(),
+            DecodingResult::F64(values) => values,
+            _ => return Err("GeoTIFF values must be 8 to 32 bit integers or floats".to_string()),
+        };
+        let (ncols, nrows) = (ncols as usize, nrows as usize);
+        if rows.len() != ncols * nrows {
+            return Err("GeoTIFF must hold a single band".to_string());
+        }
+        // The tiepoint places raster point (i, j), counted from the north-west corner, at model point (x, y)
+        let cell_size = scale[0];
+        let x_corner = tiepoint[3] - tiepoint[0] * cell_size;
+        let y_corner = tiepoint[4] + tiepoint[1] * cell_size - nrows as f64 * cell_size;
+        let values = rows
+            .chunks(ncols)
+            .rev()
+            .flatten()
+            .map(|&value| if nodata.is_some_and(|nodata| (value - nodata).abs() < 1e-6 * nodata.abs().max(1.0)) { f64::NAN } else { value })
+            .collect();
+        Self::new(ncols, nrows, x_corner, y_corner, cell_size, values).map(|raster| Self { crs, ..raster })
+    }
+
+    /// Extent of the raster (x_min, y_min, x_max, y_max)
+    pub fn extent(&self) -> [f64; 4] {
+        [self.x_corner, self.y_corner, self.x_corner + self.ncols as f64 * self.cell_size, self.y_corner + self.nrows as f64 * self.cell_size]
+    }
+
+    /// Lowest and highest values with data
+    pub fn range(&self) -> (f64, f64) {
+        self.values.iter().filter(|value| !value.is_nan()).fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), &value| (low.min(value), high.max(value)))
+    }
+
+    /// Value at point (x, y), bilinearly interpolated between the cell centers over the neighbours with data,
+    /// None outside the raster or where none of them has data
+    pub fn value_at(&self, x: f64, y: f64) -> Option<f64> {
+        let [x_min, y_min, x_max, y_max] = self.extent();
+        if !(x >= x_min && x <= x_max && y >= y_min && y <= y_max) {
+            return None;
+        }
+        // Position among the cell centers, held at the outer centers over the outer half cells
+        let fx = ((x - x_min) / self.cell_size - 0.5).clamp(0.0, (self.ncols - 1) as f64);
+        let fy = ((y - y_min) / self.cell_size - 0.5).clamp(0.0, (self.nrows - 1) as f64);
+        let (i, j) = ((fx as usize).min(self.ncols - 2), (fy as usize).min(self.nrows - 2));
+        let (tx, ty) = (fx - i as f64, fy - j as f64);
+        let (mut sum, mut weights, mut mean, mut count) = (0.0, 0.0, 0.0, 0);
+        for (di, dj, weight) in [(0, 0, (1.0 - tx) * (1.0 - ty)), (1, 0, tx * (1.0 - ty)), (0, 1, (1.0 - tx) * ty), (1, 1, tx * ty)] {
+            let value = self.values[(j + dj) * self.ncols + i + di];
+            if !value.is_nan() {
+                sum += weight * value;
+                weights += weight;
+                mean += value;
+                count += 1;
+            }
+        }
+        // On the center of a cell without data, the neighbours take equal shares
+        if weights > 0.0 {
+            Some(sum / weights)
+        } else {
+            (count > 0).then(|| mean / count as f64)
+        }
+    }
+
+    /// Still water depth at the cell centers of a basin grid placed on the raster [m]
+    ///
+    /// Each cell averages the interpolated raster over as many points as raster cells it covers, so coarse
+    /// grids take the mean depth of their area rather than a single sample. Cells above the still water
+    /// level, outside the raster or without data are land, with a depth of zero or less.
+    pub fn depths(&self, grid: &Grid2D, options: &RasterOptions) -> Result<Vec<f64>, String> {
+        let (horizontal, vertical) = (options.horizontal_unit.to_meters(), options.vertical_unit.to_meters());
+        let cell_size = self.cell_size * horizontal;
+        let (sx, sy) = ((grid.dx() / cell_size).ceil().max(1.0) as usize, (grid.dy() / cell_size).ceil().max(1.0) as usize);
+        let mut depths = Vec::with_capacity(grid.cells());
+        for j in 0..grid.ny() {
+            for i in 0..grid.nx() {
+                let (x, y) = grid.center(i, j);
+                let (mut sum, mut count) = (0.0, 0);
+                for b in 0..sy {
+                    for a in 0..sx {
+                        let px = x + ((a as f64 + 0.5) / sx as f64 - 0.5) * grid.dx();
+                        let py = y + ((b as f64 + 0.5) / sy as f64 - 0.5) * grid.dy();
+                        if let Some(value) = self.value_at(options.origin[0] + px / horizontal, options.origin[1] + py / horizontal) {
+                            sum += value;
+                            count += 1;
+                        }
+                    }
+                }
+                depths.push(if count == 0 {
+                    0.0
+                } else {
+                    let value = sum / count as f64;
+                    let depth = if options.positive_depths { options.water_level + value } else { options.water_level - value };
+                    depth * vertical
+                });
+            }
+        }
+        if depths.iter().all(|&depth| depth <= 0.0) {
+            return Err("The basin lies on land: no cell is below the still water level".to_string());
+        }
+        Ok(depths)
+    }
+}
+
+/// Shading of a surface lit from a direction, from 0 (facing away) to 1 (facing the light)
+///
+/// Slopes are taken by the method of Horn (1981) over the eight neighbours of each point of a grid
+/// stored row by row from the south-west corner; missing values take the value of the point. The
+/// light comes from the azimuth, clockwise from the north, at an altitude above the horizon.
+pub fn hillshade(values: &[f64], nx: usize, ny: usize, spacing: f64, azimuth: f64, altitude: f64) -> Vec<f64> {
+    let (zenith, azimuth) = ((90.0 - altitude).to_radians(), azimuth.to_radians());
+    let mut shading = Vec::with_capacity(nx * ny);
+    for j in 0..ny {
+        for i in 0..nx {
+            let center = values[j * nx + i];
+            let z = |di: isize, dj: isize| {
+                let (a, b) = ((i as isize + di).clamp(0, nx as isize - 1) as usize, (j as isize + dj).clamp(0, ny as isize - 1) as usize);
+                let value = values[b * nx + a];
+                if value.is_nan() { center } else { value }
+            };
+            let dzdx = ((z(1, 1) + 2.0 * z(1, 0) + z(1, -1)) - (z(-1, 1) + 2.0 * z(-1, 0) + z(-1, -1))) / (8.0 * spacing);
+            let dzdy = ((z(-1, 1) + 2.0 * z(0, 1) + z(1, 1)) - (z(-1, -1) + 2.0 * z(0, -1) + z(1, -1))) / (8.0 * spacing);
+            if center.is_nan() || !(dzdx.is_finite() && dzdy.is_finite()) {
+                shading.push(zenith.cos());
+                continue;
+            }
+            let slope = dzdx.hypot(dzdy).atan();
+            // Direction the slope faces, clockwise from the north
+            let aspect = (-dzdx).atan2(-dzdy);
+            shading.push((zenith.cos() * slope.cos() + zenith.sin() * slope.sin() * (azimuth - aspect).cos()).max(0.0));
+        }
+    }
+    shading
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ascii_grid() {
+        let content = "ncols 3\nnrows 2\nxllcenter 100.5\nYLLCORNER 200\ncellsize 1\nNODATA_value -9999\n-1 -2 -3\n-4 -9999\n-6\n";
+        let raster = DepthRaster::parse_ascii_grid(content).unwrap();
+        assert_eq!((raster.ncols, raster.nrows, raster.cell_size), (3, 2, 1.0));
+        assert_eq!(raster.extent(), [100.0, 200.0, 103.0, 202.0]);
+        // The southern row comes last in the file
+        assert_eq!(raster.values[0], -4.0);
+        assert!(raster.values[1].is_nan());
+        assert_eq!(&raster.values[2..], &[-6.0, -1.0, -2.0, -3.0]);
+        assert_eq!(raster.range(), (-6.0, -1.0));
+
+        assert!(DepthRaster::parse_ascii_grid("ncols 2\nnrows 2\nxllcorner 0\nyllcorner 0\ncellsize 1\n1 2 3\n").is_err());
+        assert!(DepthRaster::parse_ascii_grid("ncols 2\nnrows 2\nxllcorner 0\ncellsize 1\n1 2 3 4\n").is_err());
+        assert!(DepthRaster::parse_ascii_grid("ncols 2\nnrows 2\nxllcorner 0\nyllcorner 0\ncellsize 1\n1 2 x 4\n").is_err());
+    }
+
+    #[test]
+    fn test_interpolation_and_depths() {
+        // Bed rising 0.1 ft per foot eastward from −5 ft, on cells of 2 ft
+        let values = (0..20).map(|n| -5.0 + 0.1 * (2.0 * (n % 5) as f64 + 1.0)).collect();
+        let raster = DepthRaster::new(5, 4, 0.0, 0.0, 2.0, values).unwrap();
+        assert!((raster.value_at(4.0, 3.0).unwrap() - -4.6).abs() < 1e-12);
+        // Held at the outer centers over the outer half cells
+        assert!((raster.value_at(0.5, 0.5).unwrap() - -4.9).abs() < 1e-12);
+        assert!(raster.value_at(10.5, 1.0).is_none());
+
+        // A basin 6 ft long from x = 2 ft, with the water level at −4.5 ft: land past x = 5 ft
+        let options = RasterOptions { horizontal_unit: SurveyUnit::Feet, vertical_unit: SurveyUnit::Feet, water_level: -4.5, origin: [2.0, 0.0], ..RasterOptions::default() };
+        let grid = Grid2D::new(6.0 * 0.3048, 2.0 * 0.3048, 6, 2).unwrap();
+        let depths = raster.depths(&grid, &options).unwrap();
+        for (i, depth) in depths[..6].iter().enumerate() {
+            let expected = (-4.5 - (-5.0 + 0.1 * (2.0 + i as f64 + 0.5))) * 0.3048;
+            assert!((depth - expected).abs() < 1e-9, "depth {} at column {}, {} expected", depth, i, expected);
+        }
+        assert!(depths[2] > 0.0 && depths[3] < 0.0);
+
+        // Depths positive downward
+        let depths = DepthRaster::new(2, 2, 0.0, 0.0, 1.0, vec![3.0; 4]).unwrap();
+        let positive = RasterOptions { water_level: 0.5, positive_depths: true, ..RasterOptions::default() };
+        assert!(depths.depths(&Grid2D::new(2.0, 2.0, 2, 2).unwrap(), &positive).unwrap().iter().all(|&depth| depth == 3.5));
+        let dry = RasterOptions { water_level: 1.0, ..RasterOptions::default() };
+        assert!(depths.depths(&Grid2D::new(2.0, 2.0, 2, 2).unwrap(), &dry).is_err());
+    }
+
+    #[test]
+    fn test_hillshade() {
+        // Flat ground is lit by the cosine of the zenith angle, a slope facing the light more, facing away less
+        let flat = hillshade(&[0.0; 9], 3, 3, 1.0, 315.0, 45.0);
+        assert!(flat.iter().all(|&shade| (shade - 45f64.to_radians().sin()).abs() < 1e-12));
+        let rising_east: Vec<f64> = (0..9).map(|n| (n % 3) as f64).collect();
+        let facing_west = hillshade(&rising_east, 3, 3, 1.0, 270.0, 45.0)[4];
+        let facing_away = hillshade(&rising_east, 3, 3, 1.0, 90.0, 45.0)[4];
+        assert!((facing_west - 1.0).abs() < 1e-12 && facing_away < flat[4]);
+    }
+
+    #[cfg(feature = "geotiff")]
+    #[test]
+    fn test_parse_geotiff() {
+        use tiff::encoder::{TiffEncoder, colortype::Gray32Float};
+        use tiff::tags::Tag;
+
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        let mut encoder = TiffEncoder::new(&mut bytes).unwrap();
+        let mut image = encoder.new_image::<Gray32Float>(3, 2).unwrap();
+        image.encoder().write_tag(Tag::ModelPixelScaleTag, &[5.0, 5.0, 0.0][..]).unwrap();
+        image.encoder().write_tag(Tag::ModelTiepointTag, &[0.0, 0.0, 0.0, 1000.0, 2010.0, 0.0][..]).unwrap();
+        image.encoder().write_tag(Tag::GdalNodata, "-9999").unwrap();
+        image.encoder().write_tag(Tag::GeoKeyDirectoryTag, &[1u16, 1, 0, 2, 1024, 0, 1, 1, 3072, 0, 1, 32619][..]).unwrap();
+        image.write_data(&[-1.0, -2.0, -3.0, -4.0, -9999.0, -6.0]).unwrap();
+
+        let raster = DepthRaster::parse_geotiff(bytes.get_ref()).unwrap();
+        assert_eq!(raster.extent(), [1000.0, 2000.0, 1015.0, 2010.0]);
+        assert_eq!(raster.values[0], -4.0);
+        assert!(raster.values[1].is_nan());
+        assert_eq!(&raster.values[2..], &[-6.0, -1.0, -2.0, -3.0]);
+        assert_eq!(raster.crs.as_deref(), Some("EPSG:32619"));
+    }
+}
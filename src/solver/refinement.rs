@@ -0,0 +1,195 @@
+use super::bathymetry::CrossSection;
+use super::grid::{Grid1D, RefinementZone};
+use crate::waves::{BREAKER_INDEX, DispersionMode, DispersionSolver};
+use serde::{Deserialize, Serialize};
+
+/// Courant number of the solver time steps
+const CFL: f64 = 0.5;
+
+/// Measured cost of one hydrostatic cell update [s]
+const SECONDS_PER_CELL_STEP: f64 = 6e-8;
+
+/// Measured cost of one non-hydrostatic cell update per layer [s]
+const SECONDS_PER_LAYER_STEP: f64 = 2.6e-7;
+
+/// Feature of the channel that needs finer cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefinementReason {
+    /// Bed slope steeper than the structure threshold, e.g. a dike or breakwater slope
+    SteepBed,
+    /// Predicted breaking and swash region
+    SurfZone,
+}
+
+impl std::fmt::Display for RefinementReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RefinementReason::SteepBed => write!(f, "Steep bed"),
+            RefinementReason::SurfZone => write!(f, "Surf zone"),
+        }
+    }
+}
+
+/// Target resolutions of the automatic grid refinement
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AutoRefinement {
+    pub points_per_wavelength: f64, // Cells per wavelength offshore
+    pub steep_slope: f64,           // Slopes steeper than 1:m are refined as structures
+    pub structure_spacing: f64,     // Cell width over steep slopes [m]
+    pub surf_zone_spacing: f64,     // Cell width from the breaking point to the run-up limit [m]
+}
+
+impl Default for AutoRefinement {
+    fn default() -> Self {
+        Self {
+            points_per_wavelength: 40.0,
+            steep_slope: 10.0,
+            structure_spacing: 0.05,
+            surf_zone_spacing: 0.1,
+        }
+    }
+}
+
+/// Zones and cell count chosen by the automatic refinement
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefinementPlan {
+    pub zones: Vec<(RefinementReason, RefinementZone)>,
+    pub offshore_spacing: f64, // Cell width away from the zones [m]
+    pub cells: usize,          // Number of cells of the refined grid
+}
+
+impl RefinementPlan {
+    /// Refinement zones of the grid
+    pub fn refinement_zones(&self) -> Vec<RefinementZone> {
+        self.zones.iter().map(|(_, zone)| *zone).collect()
+    }
+
+    /// Refined grid over a channel
+    pub fn grid(&self, length: f64) -> Result<Grid1D, String> {
+        Grid1D::refined(length, self.cells, &self.refinement_zones())
+    }
+}
+
+impl AutoRefinement {
+    /// Check that the targets are positive
+    pub fn validate(&self) -> Result<(), String> {
+        let targets = [self.points_per_wavelength, self.steep_slope, self.structure_spacing, self.surf_zone_spacing];
+        if targets.iter().any(|target| !(target.is_finite() && *target > 0.0)) {
+            return Err("Refinement targets must be positive".to_string());
+        }
+        Ok(())
+    }
+
+    /// Refinement of a channel for regular waves of a height and period
+    ///
+    /// Offshore, the spacing resolves the linear wavelength at the wavemaker depth. Segments of the
+    /// cross-section steeper than the structure threshold get the structure spacing. The surf zone
+    /// starts half a breaking wavelength before the depth where H/d reaches the breaker index and
+    /// ends where the bed rises one wave height above the still water level.
+    pub fn plan(&self, section: Option<&CrossSection>, length: f64, depth: f64, wave_height: f64, wave_period: f64) -> Result<RefinementPlan, String> {
+        self.validate()?;
+        if !(length > 0.0 && depth > 0.0 && wave_period > 0.0) {
+            return Err("Channel length, depth and wave period must be positive".to_string());
+        }
+        let omega = 2.0 * std::f64::consts::PI / wave_period;
+        let wavelength = 2.0 * std::f64::consts::PI / DispersionSolver::new().solve_wave_number(omega, depth)?;
+        let offshore_spacing = wavelength / self.points_per_wavelength;
+
+        let mut zones = Vec::new();
+        let mut add_zone = |reason, start: f64, end: f64, spacing: f64| {
+            let (start, end) = (start.max(0.0), end.min(length));
+            if end > start && spacing < offshore_spacing {
+                zones.push((reason, RefinementZone { start, end, factor: offshore_spacing / spacing }));
+            }
+        };
+
+        if let Some(section) = section {
+            let mut start = 0.0;
+            for segment in &section.segments {
+                if segment.gradient().abs() > 1.0 / self.steep_slope {
+                    add_zone(RefinementReason::SteepBed, start, start + segment.length, self.structure_spacing);
+                }
+                start += segment.length;
+            }
+        }
+
+        // Breaking point and run-up limit along the profile
+        let depth_at = |x: f64| section.map_or(depth, |section| section.depth_at(x, depth));
+        let samples = 1000;
+        let positions = (0..=samples).map(|i| length * i as f64 / samples as f64);
+        if let Some(breaking) = positions.clone().find(|&x| wave_height > BREAKER_INDEX * depth_at(x)) {
+            let breaking_wavelength = wave_period * (9.81 * wave_height / BREAKER_INDEX).sqrt();
+            let runup = positions.filter(|&x| x > breaking).find(|&x| depth_at(x) < -wave_height).unwrap_or(length);
+            add_zone(RefinementReason::SurfZone, breaking - 0.5 * breaking_wavelength, runup, self.surf_zone_spacing);
+        }
+
+        let refinement: Vec<RefinementZone> = zones.iter().map(|(_, zone)| *zone).collect();
+        Ok(RefinementPlan {
+            cells: Grid1D::refined_cell_count(length, offshore_spacing, &refinement),
+            zones,
+            offshore_spacing,
+        })
+    }
+}
+
+/// Estimated number of solver time steps of a run on a grid
+///
+/// The time step follows the CFL condition in the smallest cell for long waves over the deepest
+/// water plus the wave height.
+pub fn estimated_time_steps(grid: &Grid1D, max_depth: f64, wave_height: f64, duration: f64) -> usize {
+    let speed = (9.81 * (max_depth + wave_height).max(1e-6)).sqrt();
+    (duration / (CFL * grid.min_dx() / speed)).ceil() as usize
+}
+
+/// Estimated computing time of a run on a grid [s]
+///
+/// The cost per cell update was measured on the solver benchmark.
+pub fn estimated_runtime(grid: &Grid1D, max_depth: f64, wave_height: f64, duration: f64, mode: Option<DispersionMode>) -> f64 {
+    let steps = estimated_time_steps(grid, max_depth, wave_height, duration) as f64;
+    let cost = mode.map_or(SECONDS_PER_CELL_STEP, |mode| SECONDS_PER_LAYER_STEP * mode.layers() as f64);
+    steps * grid.nx() as f64 * cost
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flat_channel_is_not_refined() {
+        let plan = AutoRefinement::default().plan(None, 50.0, 2.0, 0.5, 4.0).unwrap();
+        assert!(plan.zones.is_empty());
+        // Uniform grid at 40 points per wavelength
+        let wavelength = 40.0 * plan.offshore_spacing;
+        assert!((plan.cells as f64 - 50.0 / wavelength * 40.0).abs() <= 1.0);
+    }
+
+    #[test]
+    fn test_dike_refines_slope_and_surf_zone() {
+        let dike = CrossSection::dike(100.0, 1.0);
+        let plan = AutoRefinement::default().plan(Some(&dike), 100.0, 1.0, 0.3, 3.0).unwrap();
+        let reasons: Vec<RefinementReason> = plan.zones.iter().map(|(reason, _)| *reason).collect();
+        assert_eq!(reasons, vec![RefinementReason::SteepBed, RefinementReason::SurfZone]);
+
+        // Outer slope of the dike
+        let (_, slope) = plan.zones[0];
+        assert!((slope.start - 96.0).abs() < 1e-9);
+        assert!((slope.factor - plan.offshore_spacing / 0.05).abs() < 1e-9);
+
+        // More cells than the uniform grid, and the finest ones match the targets
+        let grid = plan.grid(100.0).unwrap();
+        assert!(plan.cells as f64 > 100.0 / plan.offshore_spacing);
+        assert!(grid.min_dx() < 0.06, "Smallest cell {:.3}", grid.min_dx());
+        assert!((grid.max_dx() - plan.offshore_spacing).abs() < 0.05 * plan.offshore_spacing);
+    }
+
+    #[test]
+    fn test_runtime_grows_with_cells_and_layers() {
+        let coarse = Grid1D::new(100.0, 500).unwrap();
+        let fine = Grid1D::new(100.0, 1000).unwrap();
+        let hydrostatic = estimated_runtime(&coarse, 1.0, 0.1, 60.0, None);
+        // Twice the cells and half the time step
+        assert!((estimated_runtime(&fine, 1.0, 0.1, 60.0, None) / hydrostatic - 4.0).abs() < 0.01);
+        assert!(estimated_runtime(&coarse, 1.0, 0.1, 60.0, Some(DispersionMode::TwoLayer)) > hydrostatic);
+        assert!(AutoRefinement { structure_spacing: 0.0, ..AutoRefinement::default() }.validate().is_err());
+    }
+}
@@ -0,0 +1,2126 @@
+use crate::solver::advection::AdvectionScheme;
+use crate::numerics::linalg::{cyclic_tridiagonal, thomas_into};
+use crate::solver::block_tridiagonal::{BlockTridiagonal, BlockWork};
+use crate::solver::boundary::RightBoundary;
+use crate::solver::dissipation::Dissipation;
+use crate::solver::grid::Grid1D;
+use crate::solver::initial_condition::InitialCondition;
+use crate::solver::precision::Precision;
+use crate::solver::time_integration::{StageBuffers, StageState, TimeIntegration};
+use crate::solver::vegetation::VegetationField;
+use crate::solver::wave_solver::{WaveSolver, sub_step};
+use crate::waves::{ActiveAbsorber, AmbientCurrent, DispersionMode, DispersionSolver, SourceKind, WaveForcing};
+
+/// Minimum total water depth used in fluxes and celerities [m]
+const MIN_DEPTH: f64 = 1e-6;
+
+/// Total water depth below which a cell is considered dry [m]
+const DRY_DEPTH: f64 = 1e-4;
+
+/// Staggered-grid 1D shallow water solver
+///
+/// Surface elevation (η) lives at cell centers and depth-averaged velocity (u) at cell faces.
+/// Time stepping is forward-backward by default: continuity is updated first, then momentum uses the new η;
+/// Runge-Kutta and semi-implicit integrators can be selected instead.
+/// Nonlinear advection is momentum conservative so bores travel at the speed of the jump conditions,
+/// and cells drain to a dry bed without negative depths. The depths and velocities carried through the
+/// faces are upwinded, to first order or with a higher-order reconstruction.
+/// In non-hydrostatic mode the water column is split into equidistant layers and a pressure
+/// correction (Keller-box scheme) reproduces the layer-based SWASH dispersion relation.
+/// An ambient current is carried by the velocities themselves, entering at the wavemaker and leaving
+/// through the open end, so the nonlinear terms advect the waves with it.
+pub struct ShallowWaterSolver {
+    /// Computational grid
+    grid: Grid1D,
+    /// Still water depth at cell centers (d) [m]
+    depth: Vec<f64>,
+    /// Surface elevation at cell centers (η) [m]
+    eta: Vec<f64>,
+    /// Depth-averaged velocity at cell faces (u) [m/s]
+    u: Vec<f64>,
+    /// Layer velocities at cell faces, from the surface layer down [m/s]
+    layer_u: Vec<Vec<f64>>,
+    /// Gravitational acceleration [m/s²]
+    gravity: f64,
+    /// Current simulation time [s]
+    time: f64,
+    /// Courant number used for adaptive time steps
+    cfl: f64,
+    /// Include nonlinear advection and total-depth fluxes
+    nonlinear: bool,
+    /// Condition at the right end of the channel
+    right_boundary: RightBoundary,
+    /// Optional active absorption at the wavemaker
+    absorber: Option<ActiveAbsorber>,
+    /// Scale the wavemaker discharge to the still water depth, so the waves bring no net mass into the channel
+    mass_compensation: bool,
+    /// Non-hydrostatic layer configuration, None for hydrostatic long waves
+    non_hydrostatic: Option<DispersionMode>,
+    /// Initial state restored on reset, None to start from rest
+    initial_condition: Option<InitialCondition>,
+    /// Optional vegetation patch dissipating energy by stem drag
+    vegetation: Option<VegetationField>,
+    /// Steady background flow entering at the wavemaker, None in still water
+    current: Option<AmbientCurrent>,
+    /// Water level set by the forcing above the still water level, e.g. a storm surge [m]
+    water_level: f64,
+    /// Excess volume at time zero plus the inflow filling the channel to the forcing water level [m²]
+    reference_volume: f64,
+    /// Faces where the discharged volume is integrated, e.g. the crest of a dike, with the volume so far [m²]
+    discharge_gauges: Vec<(usize, f64)>,
+    /// Face where the water falls into an overtopping tank, with the volume collected so far [m²]
+    tank: Option<(usize, f64)>,
+    /// Non-hydrostatic pressure q/ρ at the lower interface of each layer, cell by cell, empty when hydrostatic [m²/s²]
+    pressure: Vec<f64>,
+    /// Precision of the state kept between time steps
+    precision: Precision,
+    /// Reconstruction of the advected depths and velocities
+    advection_scheme: AdvectionScheme,
+    /// Integration in time of the continuity and momentum equations
+    time_integration: TimeIntegration,
+    /// Energy per unit width removed by each dissipation mechanism since time zero [m³/s²]
+    dissipation: Dissipation,
+    /// Work arrays of the time step, kept between steps
+    scratch: StepScratch,
+}
+
+/// Work arrays of a time step, refilled in place at every step so a steady run allocates no memory
+#[derive(Debug, Clone, Default)]
+struct StepScratch {
+    start: Vec<Vec<f64>>,     // Layer velocities at the start of the step, for the non-hydrostatic correction [m/s]
+    fluxes: Vec<f64>,         // Discharge through each face [m²/s]
+    faces: Vec<usize>,        // Inner faces, where the momentum equation is solved
+    dry: Vec<bool>,           // Whether each inner face is dry
+    advection: Vec<Vec<f64>>, // Advection term of each layer at the inner faces [m/s²]
+    velocities: Vec<f64>,     // Wavemaker velocity of each layer [m/s]
+    supplied: f64,            // Energy added by the internal sources during the step [m³/s²]
+    eta: Vec<f64>,            // Surface elevation at the start of an explicit Euler stage [m]
+    stages: StageBuffers,     // States of the Runge-Kutta stages
+    implicit: ImplicitScratch,
+    pressure: PressureScratch,
+}
+
+/// Work arrays of the non-hydrostatic pressure correction
+/// Periodic channels still allocate in the cyclic block solve
+#[derive(Debug, Clone, Default)]
+struct PressureScratch {
+    cell_thickness: Vec<f64>, // Layer thickness at each cell center [m]
+    face_thickness: Vec<f64>, // Layer thickness at each face [m]
+    coupling: Vec<f64>,       // Layer coupling S = β P
+    system: BlockTridiagonal, // Pressure system, a block of layers per cell
+    rhs: Vec<f64>,            // Vertical velocity mismatch of each layer box [m/s]
+    predicted: Vec<f64>,      // Box velocities of the predicted layer velocities [m/s]
+    solution: Vec<f64>,       // Interface pressures of each cell, becoming the pressure of the solver [m²/s²]
+    work: BlockWork,
+}
+
+/// Work arrays of the semi-implicit step
+/// Periodic channels still allocate in the cyclic tridiagonal solve
+#[derive(Debug, Clone, Default)]
+struct ImplicitScratch {
+    fluxes: Vec<f64>,           // Discharge through each face, the old one until the new surface is solved [m²/s]
+    known: Vec<f64>,            // Known part of the discharge at the new time level [m²/s]
+    conductance: Vec<f64>,      // Response of the discharge to the new surface gradient [m²/s / m]
+    explicit: Vec<Vec<f64>>,    // Explicit part u* of the layer velocities [m/s]
+    relief: Vec<Vec<f64>>,      // Implicit vegetation drag factor 1/(1 + Δt f|u|) of each layer
+    implicit: Vec<bool>,        // Whether each face takes part in the implicit solve
+    lower: Vec<f64>,            // Tridiagonal system of the new surface
+    diag: Vec<f64>,
+    upper: Vec<f64>,
+    rhs: Vec<f64>,
+    surface: Vec<f64>,          // New surface elevation [m]
+    reduced: Vec<f64>,          // Reduced super-diagonal of the Thomas algorithm
+}
+
+impl ShallowWaterSolver {
+    /// Create new solver at rest over a flat bottom
+    pub fn new(length: f64, nx: usize, depth: f64, right_boundary: RightBoundary) -> Result<Self, String> {
+        Self::from_grid(Grid1D::new(length, nx)?, depth, right_boundary)
+    }
+
+    /// Create new solver at rest over a flat bottom on a given, possibly stretched, grid
+    pub fn from_grid(grid: Grid1D, depth: f64, right_boundary: RightBoundary) -> Result<Self, String> {
+        if depth <= 0.0 {
+            return Err("Water depth must be positive".to_string());
+        }
+        let nx = grid.nx();
+        right_boundary.validate(grid.length())?;
+
+        Ok(Self {
+            depth: vec![depth; nx],
+            eta: vec![0.0; nx],
+            u: vec![0.0; nx + 1],
+            layer_u: vec![vec![0.0; nx + 1]],
+            grid,
+            gravity: 9.81,
+            time: 0.0,
+            cfl: 0.5,
+            nonlinear: true,
+            right_boundary,
+            absorber: None,
+            mass_compensation: false,
+            non_hydrostatic: None,
+            initial_condition: None,
+            vegetation: None,
+            current: None,
+            water_level: 0.0,
+            reference_volume: 0.0,
+            discharge_gauges: Vec::new(),
+            tank: None,
+            pressure: Vec::new(),
+            precision: Precision::Double,
+            advection_scheme: AdvectionScheme::Upwind,
+            time_integration: TimeIntegration::ForwardBackward,
+            dissipation: Dissipation::default(),
+            scratch: StepScratch::default(),
+        })
+    }
+
+    /// Enable active absorption at the wavemaker
+    pub fn with_absorber(mut self, absorber: ActiveAbsorber) -> Self {
+        self.absorber = Some(absorber);
+        self
+    }
+
+    /// Compensate the net mass brought into the channel by the wavemaker waves
+    pub fn with_mass_compensation(mut self, mass_compensation: bool) -> Self {
+        self.mass_compensation = mass_compensation;
+        self
+    }
+
+    /// Set Courant number used for adaptive time steps
+    pub fn with_cfl(mut self, cfl: f64) -> Self {
+        self.cfl = cfl;
+        self
+    }
+
+    /// Set the precision of the state kept between time steps
+    pub fn with_precision(mut self, precision: Precision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Get precision of the state kept between time steps
+    pub fn precision(&self) -> Precision {
+        self.precision
+    }
+
+    /// Set the reconstruction of the advected depths and velocities
+    pub fn with_advection_scheme(mut self, scheme: AdvectionScheme) -> Self {
+        self.advection_scheme = scheme;
+        self
+    }
+
+    /// Get reconstruction of the advected depths and velocities
+    pub fn advection_scheme(&self) -> AdvectionScheme {
+        self.advection_scheme
+    }
+
+    /// Set the integration in time of the continuity and momentum equations
+    pub fn with_time_integration(mut self, integration: TimeIntegration) -> Self {
+        self.time_integration = integration;
+        self
+    }
+
+    /// Get integration in time of the continuity and momentum equations
+    pub fn time_integration(&self) -> TimeIntegration {
+        self.time_integration
+    }
+
+    /// Enable or disable nonlinear terms
+    pub fn with_nonlinear(mut self, nonlinear: bool) -> Self {
+        self.nonlinear = nonlinear;
+        self
+    }
+
+    /// Enable non-hydrostatic pressure with the given number of layers, or None for hydrostatic
+    pub fn with_non_hydrostatic(mut self, mode: Option<DispersionMode>) -> Self {
+        self.non_hydrostatic = mode;
+        let layers = mode.map_or(1, |mode| mode.layers());
+        self.layer_u = vec![self.u.clone(); layers];
+        self
+    }
+
+    /// Get non-hydrostatic layer configuration
+    pub fn non_hydrostatic(&self) -> Option<DispersionMode> {
+        self.non_hydrostatic
+    }
+
+    /// Phase velocity of a wave of angular frequency ω in the solver at the given depth [m/s]
+    /// Hydrostatic waves travel at √(gd); non-hydrostatic waves follow the layer-based dispersion relation
+    pub fn celerity(&self, omega: f64, depth: f64) -> f64 {
+        let long_wave_speed = (self.gravity * depth).sqrt();
+        match self.non_hydrostatic {
+            None => long_wave_speed,
+            Some(mode) => DispersionSolver::new()
+                .with_mode(mode)
+                .solve_wave_number(omega, depth)
+                .map_or(long_wave_speed, |k| omega / k),
+        }
+    }
+
+    /// Get computational grid
+    pub fn grid(&self) -> &Grid1D {
+        &self.grid
+    }
+
+    /// Get still water depth at cell centers [m]
+    pub fn depth(&self) -> &[f64] {
+        &self.depth
+    }
+
+    /// Get surface elevation at cell centers [m]
+    pub fn eta(&self) -> &[f64] {
+        &self.eta
+    }
+
+    /// Get velocity at cell faces [m/s]
+    pub fn u(&self) -> &[f64] {
+        &self.u
+    }
+
+    /// Get layer velocities at cell faces, from the surface layer down [m/s]
+    pub fn layer_velocities(&self) -> &[Vec<f64>] {
+        &self.layer_u
+    }
+
+    /// Get current simulation time [s]
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Get water level reached by filling the channel to the forcing level [m]
+    pub fn water_level(&self) -> f64 {
+        self.water_level
+    }
+
+    /// Get right boundary condition
+    pub fn right_boundary(&self) -> RightBoundary {
+        self.right_boundary
+    }
+
+    /// Set right boundary condition
+    pub fn set_right_boundary(&mut self, right_boundary: RightBoundary) -> Result<(), String> {
+        right_boundary.validate(self.grid.length())?;
+        if self.current.is_some() && right_boundary == RightBoundary::Wall {
+            return Err("An ambient current needs an open right end".to_string());
+        }
+        self.right_boundary = right_boundary;
+        Ok(())
+    }
+
+    /// Get active absorber, if any
+    pub fn absorber(&self) -> Option<&ActiveAbsorber> {
+        self.absorber.as_ref()
+    }
+
+    /// Set or remove active absorption at the wavemaker
+    pub fn set_absorber(&mut self, absorber: Option<ActiveAbsorber>) {
+        self.absorber = absorber;
+    }
+
+    /// Check if the wavemaker compensates the net mass of the waves
+    pub fn mass_compensation(&self) -> bool {
+        self.mass_compensation
+    }
+
+    /// Enable or disable the compensation of the net mass of the wavemaker waves
+    pub fn set_mass_compensation(&mut self, mass_compensation: bool) {
+        self.mass_compensation = mass_compensation;
+    }
+
+    /// Get initial condition, if any
+    pub fn initial_condition(&self) -> Option<InitialCondition> {
+        self.initial_condition
+    }
+
+    /// Set or remove the initial condition and restart from it
+    pub fn set_initial_condition(&mut self, initial_condition: Option<InitialCondition>) -> Result<(), String> {
+        if let Some(condition) = &initial_condition {
+            let max_depth = self.depth.iter().fold(0.0, |max: f64, &d| max.max(d));
+            condition.validate(self.grid.length(), max_depth)?;
+        }
+        self.initial_condition = initial_condition;
+        self.reset();
+        Ok(())
+    }
+
+    /// Set the still water depth along the channel, negative where the bed lies above the still water level
+    /// The depth at the wavemaker must be positive. The channel restarts from its initial condition.
+    pub fn set_depth_profile(&mut self, depth: impl Fn(f64) -> f64) -> Result<(), String> {
+        let profile: Vec<f64> = self.grid.x_centers().iter().map(|&x| depth(x)).collect();
+        if profile.iter().any(|d| !d.is_finite()) {
+            return Err("Water depth must be finite".to_string());
+        }
+        if profile[0] <= 0.0 {
+            return Err("Water depth at the wavemaker must be positive".to_string());
+        }
+        self.depth = profile;
+        self.reset();
+        Ok(())
+    }
+
+    /// Get vegetation patch, if any
+    pub fn vegetation(&self) -> Option<VegetationField> {
+        self.vegetation
+    }
+
+    /// Set or remove the vegetation patch
+    pub fn set_vegetation(&mut self, vegetation: Option<VegetationField>) -> Result<(), String> {
+        if let Some(field) = &vegetation {
+            field.validate(self.grid.length())?;
+        }
+        self.vegetation = vegetation;
+        Ok(())
+    }
+
+    /// Get ambient current, if any
+    pub fn current(&self) -> Option<AmbientCurrent> {
+        self.current
+    }
+
+    /// Set or remove the ambient current and restart from the initial condition
+    /// The current leaves through the right end, which must be open: a sponge layer, radiation or periodic
+    pub fn set_current(&mut self, current: Option<AmbientCurrent>) -> Result<(), String> {
+        if let Some(current) = &current {
+            current.validate()?;
+            if self.right_boundary == RightBoundary::Wall {
+                return Err("An ambient current needs an open right end".to_string());
+            }
+        }
+        self.current = current;
+        self.reset();
+        Ok(())
+    }
+
+    /// Initialize the surface elevation from a function of position, never below the bed
+    pub fn initialize_surface(&mut self, f: impl Fn(f64) -> f64) {
+        for ((eta, &x), &depth) in self.eta.iter_mut().zip(self.grid.x_centers()).zip(&self.depth) {
+            *eta = f(x).max(-depth);
+        }
+        self.reference_volume = self.volume();
+    }
+
+    /// Surface elevation interpolated at position x [m]
+    pub fn surface_elevation_at(&self, x: f64) -> f64 {
+        self.grid.interpolate_centers(&self.eta, x)
+    }
+
+    /// Surface elevation interpolated along a series of positions [m]
+    pub fn surface_elevation_series(&self, x_points: &[f64]) -> Vec<f64> {
+        x_points.iter().map(|&x| self.surface_elevation_at(x)).collect()
+    }
+
+    /// Integrate the discharge through the face nearest to position x from now on
+    /// Returns the index of the gauge
+    pub fn add_discharge_gauge(&mut self, x: f64) -> usize {
+        self.discharge_gauges.push((self.nearest_face(x), 0.0));
+        self.discharge_gauges.len() - 1
+    }
+
+    /// Index of the face nearest to position x
+    fn nearest_face(&self, x: f64) -> usize {
+        let face = self.grid.x_faces().partition_point(|&face| face < x).min(self.grid.nx());
+        if face > 0 && x - self.grid.x_faces()[face - 1] < self.grid.x_faces()[face] - x { face - 1 } else { face }
+    }
+
+    /// Position of the face of a discharge gauge [m]
+    pub fn discharge_gauge_position(&self, gauge: usize) -> f64 {
+        self.grid.x_faces()[self.discharge_gauges[gauge].0]
+    }
+
+    /// Net volume per unit width that passed a discharge gauge shoreward since it was added [m²]
+    pub fn discharged_volume(&self, gauge: usize) -> f64 {
+        self.discharge_gauges[gauge].1
+    }
+
+    /// Position of the face where the water falls into the overtopping tank, None without a tank [m]
+    pub fn overtopping_tank(&self) -> Option<f64> {
+        self.tank.map(|(face, _)| self.grid.x_faces()[face])
+    }
+
+    /// Route the water reaching the face nearest to position x into an overtopping tank, or remove the tank
+    /// Like the chute of a laboratory tank behind a crest, the face is a free overfall: the water leaves
+    /// the channel there and never flows back
+    pub fn set_overtopping_tank(&mut self, x: Option<f64>) -> Result<(), String> {
+        self.tank = match x {
+            Some(_) if self.right_boundary == RightBoundary::Periodic => return Err("A periodic channel has no overtopping tank".to_string()),
+            Some(x) if !(x > 0.0 && x <= self.grid.length()) => return Err("Overtopping tank must be in the channel, shoreward of the wavemaker".to_string()),
+            Some(x) => Some((self.nearest_face(x).max(1), 0.0)),
+            None => None,
+        };
+        Ok(())
+    }
+
+    /// Volume per unit width collected by the overtopping tank since the start [m²]
+    pub fn tank_volume(&self) -> f64 {
+        self.tank.map_or(0.0, |(_, volume)| volume)
+    }
+
+    /// Wet/dry front of the water connected to the wavemaker, None if the channel is wet to its end [m]
+    /// The front lies where the water depth falls to the wet depth, between the last wet and the first dry cell
+    pub fn shoreline(&self, wet_depth: f64) -> Option<f64> {
+        let centers = self.grid.x_centers();
+        let water = |i: usize| self.depth[i] + self.eta[i];
+        let dry = (0..self.grid.nx()).find(|&i| water(i) < wet_depth)?;
+        if dry == 0 {
+            return Some(centers[0]);
+        }
+        let (wet, dry_water) = (water(dry - 1), water(dry));
+        Some(centers[dry - 1] + (wet - wet_depth) / (wet - dry_water) * (centers[dry] - centers[dry - 1]))
+    }
+
+    /// Pressure divided by the water density at position x and height z above the still water level [m²/s²]
+    /// Hydrostatic below the surface plus the non-hydrostatic pressure interpolated between the layer
+    /// interfaces; points below the bed take the bed pressure and points above the surface none
+    pub fn pressure_at(&self, x: f64, z: f64) -> f64 {
+        let eta = self.surface_elevation_at(x);
+        let bed = -self.grid.interpolate_centers(&self.depth, x);
+        if z >= eta || eta - bed <= MIN_DEPTH {
+            return 0.0;
+        }
+        let z = z.max(bed);
+        let hydrostatic = self.gravity * (eta - z);
+
+        let layers = self.layer_u.len();
+        if self.pressure.len() != self.grid.nx() * layers {
+            return hydrostatic;
+        }
+        // Interface j lies j layer thicknesses below the surface, with q = 0 at the surface (j = 0)
+        let i = self.grid.cell_index(x);
+        let interface = |j: usize| if j == 0 { 0.0 } else { self.pressure[i * layers + j - 1] };
+        let position = (layers as f64 * (eta - z) / (eta - bed)).min(layers as f64);
+        let upper = (position.floor() as usize).min(layers - 1);
+        let weight = position - upper as f64;
+        hydrostatic + (1.0 - weight) * interface(upper) + weight * interface(upper + 1)
+    }
+
+    /// Horizontal force on a vertical wall across the water column at position x, divided by the water density [m³/s²]
+    /// The pressure is linear between the layer interfaces, so the trapezoidal rule over quarter layers is exact
+    pub fn column_force(&self, x: f64) -> f64 {
+        let eta = self.surface_elevation_at(x);
+        let bed = -self.grid.interpolate_centers(&self.depth, x);
+        let points = 4 * self.layer_u.len();
+        let dz = (eta - bed).max(0.0) / points as f64;
+        (0..points).map(|j| 0.5 * (self.pressure_at(x, bed + j as f64 * dz) + self.pressure_at(x, bed + (j + 1) as f64 * dz)) * dz).sum()
+    }
+
+    /// Energy per unit width removed by each dissipation mechanism since time zero [m³/s²]
+    pub fn dissipation(&self) -> Dissipation {
+        self.dissipation
+    }
+
+    /// Excess water volume per unit width ∫η dx [m²]
+    pub fn volume(&self) -> f64 {
+        self.eta.iter().zip(self.grid.widths()).map(|(eta, dx)| eta * dx).sum()
+    }
+
+    /// Rise of the mean water level over the wet channel since time zero, besides the forcing water level [m]
+    ///
+    /// In a closed flume the waves bring a net mass through the wavemaker, the drift of their Stokes
+    /// transport, and the level rises steadily over a long run unless it is compensated.
+    pub fn mean_level_drift(&self) -> f64 {
+        let wet_length = self.wet_length(self.water_level);
+        if wet_length <= 0.0 {
+            return 0.0;
+        }
+        (self.volume() - self.reference_volume) / wet_length
+    }
+
+    /// Length of the channel below a water level above the still water level [m]
+    fn wet_length(&self, level: f64) -> f64 {
+        self.depth.iter().zip(self.grid.widths()).filter(|&(&depth, _)| depth + level > 0.0).map(|(_, width)| width).sum()
+    }
+
+    /// Wave energy per unit width ∫(½gη² + ½du²) dx [m³/s²]
+    pub fn energy(&self) -> f64 {
+        (0..self.grid.nx())
+            .map(|i| {
+                let u_center = 0.5 * (self.u[i] + self.u[i + 1]);
+                let potential = 0.5 * self.gravity * self.eta[i] * self.eta[i];
+                let kinetic = 0.5 * self.depth[i].max(0.0) * u_center * u_center;
+                (potential + kinetic) * self.grid.widths()[i]
+            })
+            .sum()
+    }
+
+    /// Largest time step satisfying the CFL condition in every cell [s]
+    /// Integrators treating the gravity waves implicitly step further, as long as the flow crosses less than a cell
+    pub fn stable_time_step(&self) -> f64 {
+        let factor = self.time_integration.integrator().time_step_factor();
+        let wave_limit = factor * self.cfl * self.crossing_time(1.0);
+        if factor > 1.0 { wave_limit.min(self.cfl * self.crossing_time(0.0)) } else { wave_limit }
+    }
+
+    /// Courant number of a time step in the current flow, (√(gh) + |u|) dt / Δx in the most restrictive cell
+    pub fn courant_number(&self, dt: f64) -> f64 {
+        dt / self.crossing_time(1.0)
+    }
+
+    /// Shortest time for the flow, plus a share of the long wave speed, to cross a cell [s]
+    fn crossing_time(&self, wave_share: f64) -> f64 {
+        (0..self.grid.nx())
+            .map(|i| {
+                let h = (self.depth[i] + self.eta[i]).max(MIN_DEPTH);
+                let u = self
+                    .layer_u
+                    .iter()
+                    .map(|layer| layer[i].abs().max(layer[i + 1].abs()))
+                    .fold(0.0, f64::max);
+                self.grid.widths()[i] / (wave_share * (self.gravity * h).sqrt() + u)
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// Reset the channel to its initial condition, or to rest, at time zero
+    pub fn reset(&mut self) {
+        match self.initial_condition {
+            Some(condition) => {
+                for ((eta, &x), &depth) in self.eta.iter_mut().zip(self.grid.x_centers()).zip(&self.depth) {
+                    *eta = condition.surface_elevation(x, depth);
+                }
+            }
+            None => self.eta.iter_mut().for_each(|eta| *eta = 0.0),
+        }
+        // Dry bed above the still water level: the surface lies on the bed
+        for (eta, &depth) in self.eta.iter_mut().zip(&self.depth) {
+            *eta = eta.max(-depth);
+        }
+        // At rest, or flowing with the ambient current
+        for face in 0..=self.grid.nx() {
+            for k in 0..self.layer_u.len() {
+                self.layer_u[k][face] = self.current_velocity(face, k);
+            }
+        }
+        self.update_depth_averaged_velocity();
+        self.time = 0.0;
+        self.water_level = 0.0;
+        self.reference_volume = self.volume();
+        self.discharge_gauges.iter_mut().for_each(|(_, volume)| *volume = 0.0);
+        if let Some((_, volume)) = &mut self.tank {
+            *volume = 0.0;
+        }
+        self.pressure.clear();
+        self.dissipation = Dissipation::default();
+        if let Some(absorber) = &mut self.absorber {
+            absorber.reset();
+        }
+    }
+
+    /// Advance the solution by a duration using stable sub-steps
+    /// The sub-step adapts to the flow, which speeds up strongly after a dam break
+    /// Returns the number of sub-steps taken
+    pub fn advance(&mut self, duration: f64, forcing: &mut WaveForcing) -> usize {
+        let end = self.time + duration;
+        let mut steps = 0;
+        while end - self.time > 1e-12 * duration.max(1.0) {
+            let remaining = end - self.time;
+            let Some(dt) = sub_step(remaining, self.stable_time_step()) else {
+                break;
+            };
+            self.step(dt, forcing);
+            steps += 1;
+        }
+        steps
+    }
+
+    /// Advance the solution by a single time step
+    pub fn step(&mut self, dt: f64, forcing: &mut WaveForcing) {
+        forcing.update_time(self.time);
+
+        // Vertical velocities at the start of the step follow from the previous boundary velocities
+        let mut start = std::mem::take(&mut self.scratch.start);
+        if self.non_hydrostatic.is_some() {
+            start.clone_from(&self.layer_u);
+        }
+        self.apply_left_boundary(dt, forcing);
+        self.apply_right_boundary();
+        self.apply_overtopping_tank();
+        let energy = self.energy();
+        self.scratch.supplied = 0.0;
+
+        let integrator = self.time_integration.integrator();
+        integrator.integrate(self, dt, forcing);
+        if !integrator.implicit_friction() {
+            self.apply_vegetation(dt);
+        }
+        if self.non_hydrostatic.is_some() {
+            self.apply_non_hydrostatic_pressure(dt, &start);
+        }
+        self.scratch.start = start;
+
+        // Breaking takes what the boundaries, the sources and the drag do not account for
+        self.update_depth_averaged_velocity();
+        let drag = dt * self.vegetation_power();
+        let change = self.energy() - energy;
+        self.dissipation.breaking += dt * self.boundary_energy_flux() + self.scratch.supplied - change - drag;
+        self.dissipation.vegetation += drag;
+
+        self.apply_sponge(dt);
+        self.update_depth_averaged_velocity();
+        self.round_state();
+        self.time += dt;
+    }
+
+    /// Continuity ∂η/∂t + ∂(hu)/∂x = 0 over a time step from the current velocities, with the internal sources
+    pub fn continuity(&mut self, dt: f64, forcing: &WaveForcing) {
+        let mut fluxes = std::mem::take(&mut self.scratch.fluxes);
+        fluxes.clear();
+        fluxes.extend((0..=self.grid.nx()).map(|face| self.face_depth(face) * self.u[face]));
+        self.apply_fluxes(dt, &fluxes);
+        self.scratch.fluxes = fluxes;
+        self.apply_internal_sources(dt, forcing);
+    }
+
+    /// Momentum in each layer, ∂u/∂t + u ∂u/∂x + g ∂η/∂x = 0, over a time step at the inner faces
+    pub fn momentum(&mut self, dt: f64) {
+        let nx = self.grid.nx();
+        let periodic = self.right_boundary == RightBoundary::Periodic;
+        let (mut faces, mut dry) = (std::mem::take(&mut self.scratch.faces), std::mem::take(&mut self.scratch.dry));
+        self.fill_momentum_faces(&mut faces, &mut dry);
+        let mut advection = std::mem::take(&mut self.scratch.advection);
+        advection.resize_with(self.layer_u.len(), Vec::new);
+        for (terms, old) in advection.iter_mut().zip(&self.layer_u) {
+            terms.clear();
+            terms.extend(faces.iter().map(|&face| self.advection(old, face)));
+        }
+        for (layer, advection) in self.layer_u.iter_mut().zip(&advection) {
+            for (index, &face) in faces.iter().enumerate() {
+                if dry[index] {
+                    layer[face] = 0.0;
+                    continue;
+                }
+                let left = if face == 0 { nx - 1 } else { face - 1 };
+                let gradient = self.gravity * (self.eta[face % nx] - self.eta[left]) / self.grid.face_spacing(face, periodic);
+                layer[face] -= dt * (gradient + advection[index]);
+            }
+            if periodic {
+                layer[nx] = layer[0];
+            }
+        }
+        self.scratch.faces = faces;
+        self.scratch.dry = dry;
+        self.scratch.advection = advection;
+    }
+
+    /// Forward Euler step of continuity and momentum, both from the state at the start of the step
+    pub fn explicit_euler(&mut self, dt: f64, forcing: &WaveForcing) {
+        let mut other = std::mem::take(&mut self.scratch.eta);
+        other.clone_from(&self.eta);
+        self.continuity(dt, forcing);
+        // Momentum from the surface at the start of the step, then back to the new surface
+        std::mem::swap(&mut self.eta, &mut other);
+        self.momentum(dt);
+        std::mem::swap(&mut self.eta, &mut other);
+        self.scratch.eta = other;
+        self.update_depth_averaged_velocity();
+    }
+
+    /// θ-method step of continuity and momentum
+    ///
+    /// The surface gradient and the discharge are weighted θ at the new time level and the vegetation drag
+    /// is implicit, so the new surface solves a tridiagonal system; advection stays explicit.
+    pub fn semi_implicit(&mut self, dt: f64, forcing: &WaveForcing, theta: f64) {
+        let nx = self.grid.nx();
+        let layers = self.layer_u.len();
+        let periodic = self.right_boundary == RightBoundary::Periodic;
+        let tank_face = self.tank.map(|(face, _)| face);
+        let mut work = std::mem::take(&mut self.scratch.implicit);
+        let (mut faces, mut dry) = (std::mem::take(&mut self.scratch.faces), std::mem::take(&mut self.scratch.dry));
+        self.fill_momentum_faces(&mut faces, &mut dry);
+        let ImplicitScratch { fluxes, known, conductance, explicit, relief, implicit, lower, diag, upper, rhs, surface, reduced } = &mut work;
+        fluxes.clear();
+        fluxes.extend((0..=nx).map(|face| self.face_depth(face) * self.u[face]));
+
+        // Explicit part u* of the layer velocities and implicit drag factor 1/(1 + Δt f|u|)
+        explicit.clone_from(&self.layer_u);
+        relief.resize_with(layers, Vec::new);
+        for layer in relief.iter_mut() {
+            layer.clear();
+            layer.resize(nx + 1, 1.0);
+        }
+        implicit.clear();
+        implicit.resize(nx + 1, false);
+        for (index, &face) in faces.iter().enumerate() {
+            if dry[index] {
+                explicit.iter_mut().for_each(|layer| layer[face] = 0.0);
+                continue;
+            }
+            let left = if face == 0 { nx - 1 } else { face - 1 };
+            let gradient = (1.0 - theta) * self.gravity * (self.eta[face % nx] - self.eta[left]) / self.grid.face_spacing(face, periodic);
+            for k in 0..layers {
+                explicit[k][face] -= dt * (gradient + self.advection(&self.layer_u[k], face));
+                relief[k][face] = 1.0 / (1.0 + dt * self.vegetation_drag(face, k) * self.layer_u[k][face].abs());
+            }
+            // The overfall into the tank keeps its critical discharge
+            implicit[face] = tank_face != Some(face);
+        }
+        if periodic {
+            implicit[nx] = implicit[0];
+            for k in 0..layers {
+                explicit[k][nx] = explicit[k][0];
+                relief[k][nx] = relief[k][0];
+            }
+        }
+
+        // Discharge Q = known − θ B (η_R − η_L) at the implicit faces, the old discharge elsewhere
+        let mean = |value: &dyn Fn(usize) -> f64| (0..layers).map(value).sum::<f64>() / layers as f64;
+        known.clone_from(fluxes);
+        conductance.clear();
+        conductance.resize(nx + 1, 0.0);
+        for face in (0..=nx).filter(|&face| implicit[face]) {
+            let depth = self.face_depth(face);
+            let explicit_flux = depth * mean(&|k| relief[k][face] * explicit[k][face]);
+            known[face] = theta * explicit_flux + (1.0 - theta) * fluxes[face];
+            conductance[face] = theta * depth * self.gravity * theta * dt * mean(&|k| relief[k][face]) / self.grid.face_spacing(face, periodic);
+        }
+        // The water falling into the tank does not reach the cell behind it
+        if let Some(face) = tank_face {
+            known[face] = 0.0;
+        }
+
+        for (band, value) in [(&mut *lower, 0.0), (&mut *diag, 1.0), (&mut *upper, 0.0), (&mut *rhs, 0.0)] {
+            band.clear();
+            band.resize(nx, value);
+        }
+        for i in 0..nx {
+            let c = dt / self.grid.widths()[i];
+            let outflow = if tank_face == Some(i + 1) { fluxes[i + 1] } else { known[i + 1] };
+            lower[i] = -c * conductance[i];
+            upper[i] = -c * conductance[i + 1];
+            diag[i] += c * (conductance[i] + conductance[i + 1]);
+            rhs[i] = self.eta[i] - c * (outflow - known[i]);
+        }
+        let solved = if periodic {
+            cyclic_tridiagonal(lower, diag, upper, rhs).map(|solution| *surface = solution)
+        } else {
+            thomas_into(lower, diag, upper, rhs, surface, reduced)
+        };
+        if solved.is_err() {
+            self.scratch.implicit = work;
+            self.scratch.faces = faces;
+            self.scratch.dry = dry;
+            self.continuity(dt, forcing);
+            self.momentum(dt);
+            return;
+        }
+
+        // The old discharge becomes the new one at the implicit faces
+        for face in (0..=nx).filter(|&face| implicit[face]) {
+            let (left, right) = self.face_cells(face);
+            fluxes[face] = known[face] - conductance[face] * (surface[right] - surface[left]);
+        }
+        // Volumes follow from the fluxes, so the mass balance closes to round-off
+        self.apply_fluxes(dt, fluxes);
+
+        for (index, &face) in faces.iter().enumerate() {
+            let left = if face == 0 { nx - 1 } else { face - 1 };
+            let gradient = theta * self.gravity * (self.eta[face % nx] - self.eta[left]) / self.grid.face_spacing(face, periodic);
+            for k in 0..layers {
+                self.layer_u[k][face] = if dry[index] { 0.0 } else { relief[k][face] * (explicit[k][face] - dt * gradient) };
+            }
+        }
+        if periodic {
+            self.layer_u.iter_mut().for_each(|layer| layer[nx] = layer[0]);
+        }
+        self.scratch.implicit = work;
+        self.scratch.faces = faces;
+        self.scratch.dry = dry;
+        self.apply_internal_sources(dt, forcing);
+    }
+
+    /// Surface elevations, layer velocities and integrated volumes advanced by the time integrators
+    pub fn stage_state(&self) -> StageState {
+        let mut state = StageState::default();
+        self.fill_stage_state(&mut state);
+        state
+    }
+
+    /// Refill a stage state in place with the current surface elevations, layer velocities and integrated volumes
+    pub fn fill_stage_state(&self, state: &mut StageState) {
+        state.eta.clone_from(&self.eta);
+        state.layer_u.clone_from(&self.layer_u);
+        state.discharged.clear();
+        state.discharged.extend(self.discharge_gauges.iter().map(|(_, volume)| *volume));
+        state.tank = self.tank.map_or(0.0, |(_, volume)| volume);
+    }
+
+    /// Stage states of the Runge-Kutta integrators, lent for one step and given back with keep_stage_buffers
+    pub(crate) fn take_stage_buffers(&mut self) -> StageBuffers {
+        std::mem::take(&mut self.scratch.stages)
+    }
+
+    /// Keep the stage states for the next step
+    pub(crate) fn keep_stage_buffers(&mut self, buffers: StageBuffers) {
+        self.scratch.stages = buffers;
+    }
+
+    /// Restore a state combined by a time integrator, with no negative depth
+    pub fn set_stage_state(&mut self, state: &StageState) {
+        self.eta.copy_from_slice(&state.eta);
+        if self.nonlinear {
+            for (eta, &depth) in self.eta.iter_mut().zip(&self.depth) {
+                *eta = eta.max(-depth);
+            }
+        }
+        self.layer_u.clone_from(&state.layer_u);
+        for ((_, volume), &discharged) in self.discharge_gauges.iter_mut().zip(&state.discharged) {
+            *volume = discharged;
+        }
+        if let Some((_, volume)) = &mut self.tank {
+            *volume = state.tank;
+        }
+        self.update_depth_averaged_velocity();
+    }
+
+    /// Update the surface, the discharge gauges and the tank with the fluxes through the faces [m²/s]
+    fn apply_fluxes(&mut self, dt: f64, fluxes: &[f64]) {
+        for (face, volume) in &mut self.discharge_gauges {
+            *volume += dt * fluxes[*face];
+        }
+        if let Some((face, volume)) = &mut self.tank {
+            *volume += dt * fluxes[*face];
+        }
+        let tank_face = self.tank.map(|(face, _)| face);
+        for i in 0..self.grid.nx() {
+            // The water falling into the tank does not reach the cell behind it
+            let inflow = if tank_face == Some(i) { 0.0 } else { fluxes[i] };
+            self.eta[i] -= dt / self.grid.widths()[i] * (fluxes[i + 1] - inflow);
+            if self.nonlinear {
+                // Round-off from draining a cell must not leave a negative depth
+                self.eta[i] = self.eta[i].max(-self.depth[i]);
+            }
+        }
+    }
+
+    /// Refill the inner faces, where the momentum equation is solved, and whether each is dry in place
+    fn fill_momentum_faces(&self, faces: &mut Vec<usize>, dry: &mut Vec<bool>) {
+        let nx = self.grid.nx();
+        let periodic = self.right_boundary == RightBoundary::Periodic;
+        faces.clear();
+        faces.extend((0..=nx).filter(|&face| (1..nx).contains(&face) || (periodic && face == 0)));
+        dry.clear();
+        dry.extend(faces.iter().map(|&face| {
+            let (left, right) = self.face_cells(face);
+            let (left_dry, right_dry) = (self.total_depth(left) < DRY_DEPTH, self.total_depth(right) < DRY_DEPTH);
+            // Water only flows onto a dry cell whose bed lies below the neighbouring surface
+            match (left_dry, right_dry) {
+                (true, true) => true,
+                (false, true) => self.eta[left] <= -self.depth[right],
+                (true, false) => self.eta[right] <= -self.depth[left],
+                (false, false) => false,
+            }
+        }));
+    }
+
+    /// Round the state carried to the next step to the precision of the solver
+    fn round_state(&mut self) {
+        self.precision.round(&mut self.eta);
+        self.precision.round(&mut self.u);
+        self.layer_u.iter_mut().for_each(|layer| self.precision.round(layer));
+        self.precision.round(&mut self.pressure);
+    }
+
+    /// Prescribe the wavemaker velocity at the first face
+    /// A change of the forcing water level adds the inflow filling the wet channel to the new level
+    /// within the step, the quasi-static limit for surges much slower than a crossing of the channel
+    fn apply_left_boundary(&mut self, dt: f64, forcing: &WaveForcing) {
+        if self.right_boundary == RightBoundary::Periodic {
+            return;
+        }
+
+        // Layer-averaged linear inflow velocities of each boundary source, with k from the solver's wave speed,
+        // or those of its stream function wave
+        let depth = self.depth[0];
+        let layers = self.layer_u.len();
+        let mut velocities = std::mem::take(&mut self.scratch.velocities);
+        velocities.clear();
+        velocities.resize(layers, 0.0);
+        for source in forcing.active_boundary_sources() {
+            if source.stream_function().is_some() {
+                for (layer, velocity) in velocities.iter_mut().enumerate() {
+                    *velocity += source.layer_velocity(layer, layers).unwrap_or_default();
+                }
+                continue;
+            }
+            // On a current the orbital velocities turn at the frequency relative to the water
+            let (k, relative_omega) = self.doppler_wave_number(source.parameters().omega, depth);
+            let elevation = source.applicator().boundary_surface_elevation();
+            for (layer, velocity) in velocities.iter_mut().enumerate() {
+                *velocity += relative_omega * elevation * layer_profile(k, depth, layer, layers);
+            }
+        }
+
+        let target_elevation = forcing.boundary_surface_elevation();
+        let absorption = match &mut self.absorber {
+            Some(absorber) => absorber.correction_velocity(self.eta[0], target_elevation),
+            None => 0.0,
+        };
+        // The discharge h u of the waves carries a net mass ⟨η u⟩ into the channel; d u has none
+        let compensation = if self.mass_compensation && self.nonlinear { depth / self.total_depth(0) } else { 1.0 };
+        velocities.iter_mut().for_each(|velocity| *velocity *= compensation);
+        let mut correction = compensation * absorption;
+        let level = forcing.water_level();
+        if level != self.water_level {
+            let wet_length = self.wet_length(level);
+            correction += (level - self.water_level) * wet_length / (dt * self.total_depth(0));
+            self.reference_volume += (level - self.water_level) * wet_length;
+            self.water_level = level;
+        }
+        for (layer, velocity) in velocities.iter_mut().enumerate() {
+            *velocity += correction + self.current_velocity(0, layer);
+        }
+        for (layer, velocity) in self.layer_u.iter_mut().zip(&velocities) {
+            layer[0] = *velocity;
+        }
+        self.u[0] = velocities.iter().sum::<f64>() / layers as f64;
+        self.scratch.velocities = velocities;
+    }
+
+    /// Set the velocity at the last face according to the right boundary condition
+    fn apply_right_boundary(&mut self) {
+        let nx = self.grid.nx();
+        let velocity = match self.right_boundary {
+            RightBoundary::Wall | RightBoundary::Sponge { .. } => 0.0,
+            // Sommerfeld: outgoing long wave carries u = √(g/d) η
+            // A dry end, as behind a dike crest, reflects like a wall
+            RightBoundary::Radiation if self.depth[nx - 1] < DRY_DEPTH => 0.0,
+            RightBoundary::Radiation => (self.gravity / self.depth[nx - 1]).sqrt() * (self.eta[nx - 1] - self.water_level),
+            RightBoundary::Periodic => self.u[0],
+        };
+        self.set_face_velocity(nx, velocity);
+        // The ambient current leaves through the open end
+        if self.current.is_some() && self.right_boundary != RightBoundary::Periodic {
+            for k in 0..self.layer_u.len() {
+                self.layer_u[k][nx] += self.current_velocity(nx, k);
+            }
+            self.u[nx] = self.layer_u.iter().map(|layer| layer[nx]).sum::<f64>() / self.layer_u.len() as f64;
+        }
+    }
+
+    /// Wave number of a wave of angular frequency ω at a depth, with its angular frequency relative to the water
+    /// On an ambient current the wave number follows the Doppler-shifted dispersion relation of the solver;
+    /// waves blocked by an opposing current are not generated
+    fn doppler_wave_number(&self, omega: f64, depth: f64) -> (f64, f64) {
+        let Some(current) = self.current else {
+            return (omega / self.celerity(omega, depth), omega);
+        };
+        let long_wave_speed = (self.gravity * depth).sqrt();
+        let k = match self.non_hydrostatic {
+            // Long waves feel the depth-averaged current
+            None => (long_wave_speed + current.velocity > 0.0).then(|| omega / (long_wave_speed + current.velocity)),
+            Some(mode) => current.wave_number(&DispersionSolver::new().with_mode(mode), omega, depth).ok(),
+        };
+        match k {
+            Some(k) => (k, omega - k * current.doppler_velocity(k, depth)),
+            None => (omega / long_wave_speed, 0.0),
+        }
+    }
+
+    /// Velocity of the ambient current in a layer at a face, carrying the discharge it has at the wavemaker [m/s]
+    fn current_velocity(&self, face: usize, layer: usize) -> f64 {
+        let Some(current) = self.current else {
+            return 0.0;
+        };
+        let (left, right) = self.face_cells(face);
+        let depth = 0.5 * (self.depth[left] + self.depth[right]);
+        if depth < DRY_DEPTH {
+            return 0.0;
+        }
+        current.layer_velocity(layer, self.layer_u.len()) * self.depth[0] / depth
+    }
+
+    /// Free overfall into the overtopping tank: critical flow u = √(gh) out of the wet cell in front of it
+    fn apply_overtopping_tank(&mut self) {
+        let Some((face, _)) = self.tank else {
+            return;
+        };
+        let depth = self.total_depth(face - 1);
+        let velocity = if depth < DRY_DEPTH { 0.0 } else { (self.gravity * depth).sqrt() };
+        self.set_face_velocity(face, velocity);
+    }
+
+    /// Set a uniform velocity over every layer at a face
+    fn set_face_velocity(&mut self, face: usize, velocity: f64) {
+        self.u[face] = velocity;
+        for layer in &mut self.layer_u {
+            layer[face] = velocity;
+        }
+    }
+
+    /// Inject internal sources as mass fluxes radiating the target elevation both ways
+    fn apply_internal_sources(&mut self, dt: f64, forcing: &WaveForcing) {
+        for source in forcing.sources() {
+            if source.kind() != SourceKind::Internal || !source.is_generating() {
+                continue;
+            }
+            // A point flux Q radiates η = Q / 2c in each direction
+            let i = self.grid.cell_index(source.position());
+            let celerity = self.celerity(source.parameters().omega, self.depth[i]);
+            let flux = 2.0 * celerity * source.boundary_surface_elevation();
+            let rise = dt * flux / self.grid.widths()[i];
+            self.scratch.supplied += 0.5 * self.gravity * rise * (2.0 * self.eta[i] + rise) * self.grid.widths()[i];
+            self.eta[i] += rise;
+        }
+    }
+
+    /// Correct the layer velocities with the non-hydrostatic pressure
+    ///
+    /// Layer k has thickness Δ = h/N and pressure q_k at its lower interface (q = 0 at the surface).
+    /// The Keller box averages the vertical momentum over each layer,
+    /// ∂/∂t (w_k-1 + w_k)/2 = (q_k - q_k-1)/Δ, with w from layer continuity and w = 0 at the bed,
+    /// while the horizontal momentum feels the layer-averaged pressure gradient ∂/∂x (q_k-1 + q_k)/2.
+    fn apply_non_hydrostatic_pressure(&mut self, dt: f64, previous: &[Vec<f64>]) {
+        let nx = self.grid.nx();
+        let layers = self.layer_u.len();
+        let periodic = self.right_boundary == RightBoundary::Periodic;
+        let mut work = std::mem::take(&mut self.scratch.pressure);
+        let PressureScratch { cell_thickness, face_thickness, coupling, system, rhs, predicted, solution, work: block_work } = &mut work;
+        let widths = self.grid.widths();
+
+        cell_thickness.clear();
+        cell_thickness.extend((0..nx).map(|i| self.total_depth(i) / layers as f64));
+        face_thickness.clear();
+        face_thickness.extend((0..=nx).map(|face| {
+            let (left, right) = self.face_cells(face);
+            0.5 * (cell_thickness[left] + cell_thickness[right])
+        }));
+        let active = |face: usize| (1..nx).contains(&face) || periodic;
+
+        // Layer coupling S = β P: β sums the layers below plus half the own layer, P averages interface pressures
+        coupling.clear();
+        coupling.resize(layers * layers, 0.0);
+        for k in 0..layers {
+            for j in k..layers {
+                let weight = if j == k { 0.5 } else { 1.0 };
+                coupling[k * layers + j] += 0.5 * weight;
+                if j > 0 {
+                    coupling[k * layers + j - 1] += 0.5 * weight;
+                }
+            }
+        }
+
+        system.reset(nx, layers);
+        fill_box_velocities(previous, face_thickness, widths, rhs);
+        fill_box_velocities(&self.layer_u, face_thickness, widths, predicted);
+        for (value, predicted) in rhs.iter_mut().zip(predicted.iter()) {
+            *value -= predicted;
+        }
+
+        for i in 0..nx {
+            // Pressure gradients over the center spacing, divergence over the cell width
+            let spacing = |face: usize| widths[i] * self.grid.face_spacing(face, periodic);
+            let c_minus = if active(i) { face_thickness[i] / spacing(i) } else { 0.0 };
+            let c_plus = if active(i + 1) { face_thickness[i + 1] / spacing(i + 1) } else { 0.0 };
+            for (index, &s) in coupling.iter().enumerate() {
+                system.lower_mut(i)[index] = dt * c_minus * s;
+                system.upper_mut(i)[index] = dt * c_plus * s;
+                system.diag_mut(i)[index] = -dt * (c_minus + c_plus) * s;
+            }
+            let diag = system.diag_mut(i);
+            for k in 0..layers {
+                diag[k * layers + k] -= dt / cell_thickness[i];
+                if k > 0 {
+                    diag[k * layers + k - 1] += dt / cell_thickness[i];
+                }
+            }
+        }
+
+        let solved = if periodic { system.solve_cyclic(rhs).map(|pressure| *solution = pressure) } else { system.solve_into(rhs, solution, block_work) };
+        if solved.is_ok() {
+            // Layer-averaged pressure q̄_k = (q_k-1 + q_k)/2
+            let pressure = &*solution;
+            let averaged = |i: usize, k: usize| {
+                let above = if k > 0 { pressure[i * layers + k - 1] } else { 0.0 };
+                0.5 * (above + pressure[i * layers + k])
+            };
+            for face in (0..=nx).filter(|&face| active(face)) {
+                let (left, right) = self.face_cells(face);
+                let dx = self.grid.face_spacing(face, periodic);
+                for (k, layer) in self.layer_u.iter_mut().enumerate() {
+                    layer[face] -= dt * (averaged(right, k) - averaged(left, k)) / dx;
+                }
+            }
+            // The previous pressure keeps its memory for the next solve
+            std::mem::swap(&mut self.pressure, solution);
+        }
+        self.scratch.pressure = work;
+    }
+
+    /// Stem drag ½ C_D b_v N u|u| on the part of each layer inside the canopy
+    /// Treated implicitly, u ← u / (1 + Δt ½ C_D b_v N f |u|), so dense vegetation cannot reverse the flow
+    fn apply_vegetation(&mut self, dt: f64) {
+        let Some(field) = self.vegetation else {
+            return;
+        };
+        let nx = self.grid.nx();
+        for face in (0..=nx).filter(|&face| field.contains(self.grid.x_faces()[face])) {
+            for k in 0..self.layer_u.len() {
+                let drag = self.vegetation_drag(face, k);
+                let layer = &mut self.layer_u[k];
+                layer[face] /= 1.0 + dt * drag * layer[face].abs();
+            }
+        }
+    }
+
+    /// Rate of work of the stem drag, ½ C_D b_v N f |u| u² over the layers in the canopy, per unit width and density [m³/s³]
+    fn vegetation_power(&self) -> f64 {
+        let Some(field) = self.vegetation else {
+            return 0.0;
+        };
+        let periodic = self.right_boundary == RightBoundary::Periodic;
+        let layers = self.layer_u.len();
+        (0..=self.grid.nx())
+            .filter(|&face| field.contains(self.grid.x_faces()[face]))
+            .map(|face| {
+                let volume = self.face_depth(face) / layers as f64 * self.grid.face_spacing(face, periodic);
+                (0..layers).map(|k| self.vegetation_drag(face, k) * self.layer_u[k][face].abs().powi(3) * volume).sum::<f64>()
+            })
+            .sum()
+    }
+
+    /// Linear energy flux g η q entering through the wavemaker less that leaving through the right end and into the tank,
+    /// with the discharges of the last continuity update, per unit width and density [m³/s³]
+    fn boundary_energy_flux(&self) -> f64 {
+        let nx = self.grid.nx();
+        let fluxes = &self.scratch.fluxes;
+        if self.right_boundary == RightBoundary::Periodic || fluxes.len() != nx + 1 {
+            return 0.0;
+        }
+        let mut flux = self.gravity * (self.eta[0] * fluxes[0] - self.eta[nx - 1] * fluxes[nx]);
+        if let Some((face, _)) = self.tank {
+            flux -= self.gravity * self.eta[face - 1] * fluxes[face];
+        }
+        flux
+    }
+
+    /// Drag per unit velocity ½ C_D b_v N f of a layer at a face, zero outside the canopy [1/m]
+    fn vegetation_drag(&self, face: usize, layer: usize) -> f64 {
+        let Some(field) = self.vegetation.filter(|field| field.contains(self.grid.x_faces()[face])) else {
+            return 0.0;
+        };
+        let (left, right) = self.face_cells(face);
+        let depth = 0.5 * (self.total_depth(left) + self.total_depth(right));
+        let thickness = depth / self.layer_u.len() as f64;
+        // Layers are numbered from the surface; heights are measured from the bed
+        let top = depth - layer as f64 * thickness;
+        field.drag_per_length() * field.canopy_fraction(top - thickness, top)
+    }
+
+    /// Depth-averaged velocity as the mean of the layer velocities
+    fn update_depth_averaged_velocity(&mut self) {
+        let layers = self.layer_u.len() as f64;
+        for (face, u) in self.u.iter_mut().enumerate() {
+            *u = self.layer_u.iter().map(|layer| layer[face]).sum::<f64>() / layers;
+        }
+    }
+
+    /// Relax the solution towards the undisturbed state inside the sponge layer
+    /// The undisturbed surface is the initial one, e.g. the downstream level of a dam break
+    fn apply_sponge(&mut self, dt: f64) {
+        let length = self.grid.length();
+        if !matches!(self.right_boundary, RightBoundary::Sponge { .. }) {
+            return;
+        }
+        let mut removed = 0.0;
+        for i in 0..self.grid.nx() {
+            let x = self.grid.x_centers()[i];
+            let reference = self.initial_condition.map_or(0.0, |condition| condition.surface_elevation(x, self.depth[i])) + self.water_level;
+            let decay = (-self.right_boundary.damping_rate(x, length) * dt).exp();
+            let disturbance = self.eta[i] - reference;
+            removed += 0.5 * self.gravity * disturbance * disturbance * (1.0 - decay * decay) * self.grid.widths()[i];
+            self.eta[i] = reference + disturbance * decay;
+        }
+        // The velocities relax towards the ambient current, if any
+        let layers = self.layer_u.len();
+        for face in 0..=self.grid.nx() {
+            let decay = (-self.right_boundary.damping_rate(self.grid.x_faces()[face], length) * dt).exp();
+            let volume = self.face_depth(face) / layers as f64 * self.grid.face_spacing(face, false);
+            for k in 0..layers {
+                let current = self.current_velocity(face, k);
+                let disturbance = self.layer_u[k][face] - current;
+                removed += 0.5 * disturbance * disturbance * (1.0 - decay * decay) * volume;
+                self.layer_u[k][face] = current + disturbance * decay;
+            }
+        }
+        self.dissipation.sponge += removed;
+    }
+
+    /// Cells on the left and right of a face, wrapping around for periodic domains
+    fn face_cells(&self, face: usize) -> (usize, usize) {
+        let nx = self.grid.nx();
+        let periodic = self.right_boundary == RightBoundary::Periodic;
+        match face {
+            0 if periodic => (nx - 1, 0),
+            0 => (0, 0),
+            f if f == nx && periodic => (nx - 1, 0),
+            f if f == nx => (nx - 1, nx - 1),
+            f => (f - 1, f),
+        }
+    }
+
+    /// Water depth in a cell: total depth when nonlinear, still water depth otherwise
+    fn total_depth(&self, i: usize) -> f64 {
+        if self.nonlinear {
+            (self.depth[i] + self.eta[i]).max(MIN_DEPTH)
+        } else {
+            self.depth[i].max(MIN_DEPTH)
+        }
+    }
+
+    /// Momentum-conservative advection u ∂u/∂x at a face (Stelling & Duinmeijer, 2003)
+    /// Momentum fluxes at the neighbouring cell centers carry the upwind face velocity
+    fn advection(&self, u: &[f64], face: usize) -> f64 {
+        if !self.nonlinear {
+            return 0.0;
+        }
+        let (left, right) = self.face_cells(face);
+        let face_depth = 0.5 * (self.total_depth(left) + self.total_depth(right));
+        if face_depth < DRY_DEPTH {
+            return 0.0;
+        }
+
+        // Discharge at the center of a cell, averaged from its two faces, and its upwind velocity
+        let center = |cell: usize| {
+            let discharge = 0.5 * (self.face_depth(cell) * u[cell] + self.face_depth(cell + 1) * u[cell + 1]);
+            let (upwind, downwind, far) = if discharge >= 0.0 {
+                (cell, cell + 1, self.face_beyond(cell, false))
+            } else {
+                (cell + 1, cell, self.face_beyond(cell + 1, true))
+            };
+            let velocity = match far {
+                Some(far) => self.advection_scheme.reconstruct(u[far], u[upwind], u[downwind]),
+                None => u[upwind],
+            };
+            (discharge, velocity)
+        };
+        let (q_left, u_left) = center(left);
+        let (q_right, u_right) = center(right);
+        let dx = self.grid.face_spacing(face, self.right_boundary == RightBoundary::Periodic);
+        (q_right * u_right - q_left * u_left - u[face] * (q_right - q_left)) / (dx * face_depth)
+    }
+
+    /// Water depth at a face, upwinded on total depth when nonlinear
+    /// Next to a dry cell the depth is taken from the upwind cell alone, whatever the scheme
+    fn face_depth(&self, face: usize) -> f64 {
+        let (left, right) = self.face_cells(face);
+        if !self.nonlinear {
+            return 0.5 * (self.depth[left] + self.depth[right]).max(MIN_DEPTH);
+        }
+        let (upwind, downwind, far) = if self.u[face] >= 0.0 {
+            (left, right, self.cell_beyond(left, false))
+        } else {
+            (right, left, self.cell_beyond(right, true))
+        };
+        let upwind_depth = self.total_depth(upwind);
+        match far {
+            Some(far) if upwind != downwind => {
+                let stencil = [far, upwind, downwind].map(|cell| self.total_depth(cell));
+                if stencil.iter().any(|&depth| depth < DRY_DEPTH) {
+                    return upwind_depth;
+                }
+                self.advection_scheme.reconstruct(stencil[0], stencil[1], stencil[2])
+            }
+            _ => upwind_depth,
+        }
+    }
+
+    /// Next cell to the left of a cell, or to the right, wrapping around for periodic domains
+    fn cell_beyond(&self, cell: usize, right: bool) -> Option<usize> {
+        let nx = self.grid.nx();
+        let periodic = self.right_boundary == RightBoundary::Periodic;
+        match (right, cell) {
+            (false, 0) => periodic.then_some(nx - 1),
+            (false, c) => Some(c - 1),
+            (true, c) if c + 1 == nx => periodic.then_some(0),
+            (true, c) => Some(c + 1),
+        }
+    }
+
+    /// Next face to the left of a face, or to the right, wrapping around for periodic domains
+    fn face_beyond(&self, face: usize, right: bool) -> Option<usize> {
+        let nx = self.grid.nx();
+        let periodic = self.right_boundary == RightBoundary::Periodic;
+        match (right, face) {
+            (false, 0) => periodic.then_some(nx - 1),
+            (false, f) => Some(f - 1),
+            (true, f) if f == nx => periodic.then_some(1),
+            (true, f) => Some(f + 1),
+        }
+    }
+}
+
+impl WaveSolver for ShallowWaterSolver {
+    fn engine_name(&self) -> String {
+        match self.non_hydrostatic {
+            Some(mode) => format!("Non-hydrostatic ({})", mode),
+            None => "Shallow water".to_string(),
+        }
+    }
+
+    fn reset(&mut self) {
+        ShallowWaterSolver::reset(self)
+    }
+
+    fn step(&mut self, dt: f64, forcing: &mut WaveForcing) {
+        ShallowWaterSolver::step(self, dt, forcing)
+    }
+
+    fn stable_time_step(&self) -> f64 {
+        ShallowWaterSolver::stable_time_step(self)
+    }
+
+    fn time(&self) -> f64 {
+        self.time
+    }
+
+    fn grid(&self) -> &Grid1D {
+        &self.grid
+    }
+
+    fn depth(&self) -> &[f64] {
+        &self.depth
+    }
+
+    fn eta(&self) -> &[f64] {
+        &self.eta
+    }
+
+    fn u(&self) -> &[f64] {
+        &self.u
+    }
+
+    fn advance(&mut self, duration: f64, forcing: &mut WaveForcing) -> usize {
+        ShallowWaterSolver::advance(self, duration, forcing)
+    }
+
+    fn courant_number(&self, dt: f64) -> f64 {
+        ShallowWaterSolver::courant_number(self, dt)
+    }
+
+    fn pressure_at(&self, x: f64, z: f64) -> f64 {
+        ShallowWaterSolver::pressure_at(self, x, z)
+    }
+
+    fn column_force(&self, x: f64) -> f64 {
+        ShallowWaterSolver::column_force(self, x)
+    }
+
+    fn shoreline(&self, wet_depth: f64) -> Option<f64> {
+        ShallowWaterSolver::shoreline(self, wet_depth)
+    }
+
+    fn energy(&self) -> f64 {
+        ShallowWaterSolver::energy(self)
+    }
+
+    fn dissipation(&self) -> Option<Dissipation> {
+        Some(self.dissipation)
+    }
+
+    fn mean_level_drift(&self) -> Option<f64> {
+        Some(ShallowWaterSolver::mean_level_drift(self))
+    }
+
+    fn overtopping_tank(&self) -> Option<f64> {
+        ShallowWaterSolver::overtopping_tank(self)
+    }
+
+    fn tank_volume(&self) -> f64 {
+        ShallowWaterSolver::tank_volume(self)
+    }
+}
+
+/// Layer average of the linear velocity profile cosh(k(z + d)) / sinh(kd), divided by ω η
+/// Layers are numbered from the surface; a single layer gives the depth-averaged 1/(kd)
+fn layer_profile(k: f64, depth: f64, layer: usize, layers: usize) -> f64 {
+    let thickness = depth / layers as f64;
+    let top = depth - layer as f64 * thickness;
+    let bottom = top - thickness;
+    ((k * top).sinh() - (k * bottom).sinh()) / (k * thickness * (k * depth).sinh())
+}
+
+/// Layer-averaged vertical velocities (w_k-1 + w_k)/2 per cell, from layer continuity with w = 0 at the bed
+fn fill_box_velocities(layer_u: &[Vec<f64>], face_thickness: &[f64], widths: &[f64], boxes: &mut Vec<f64>) {
+    let layers = layer_u.len();
+    let nx = face_thickness.len() - 1;
+    boxes.clear();
+    boxes.resize(nx * layers, 0.0);
+    for i in 0..nx {
+        let mut w_below = 0.0;
+        for k in (0..layers).rev() {
+            let divergence = (face_thickness[i + 1] * layer_u[k][i + 1] - face_thickness[i] * layer_u[k][i]) / widths[i];
+            boxes[i * layers + k] = w_below - 0.5 * divergence;
+            w_below -= divergence;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::advection::Limiter;
+    use crate::solver::CrossSection;
+    use crate::solver::dissipation::DissipationMechanism;
+    use crate::solver::grid::RefinementZone;
+    use crate::units::{Length, Time};
+    use crate::waves::{DispersionSolver, WaveSource};
+
+    fn gaussian_hump(center: f64, amplitude: f64) -> impl Fn(f64) -> f64 {
+        move |x: f64| amplitude * (-((x - center) / 2.0).powi(2)).exp()
+    }
+
+    fn run(solver: &mut ShallowWaterSolver, duration: f64) {
+        let mut forcing = WaveForcing::new();
+        solver.advance(duration, &mut forcing);
+    }
+
+    #[test]
+    fn test_solver_creation() {
+        let solver = ShallowWaterSolver::new(50.0, 100, 1.0, RightBoundary::Wall).unwrap();
+        assert_eq!(solver.eta().len(), 100);
+        assert_eq!(solver.u().len(), 101);
+        assert_eq!(solver.time(), 0.0);
+
+        assert!(ShallowWaterSolver::new(50.0, 100, 0.0, RightBoundary::Wall).is_err());
+        let bad_sponge = RightBoundary::Sponge {
+            width: 80.0,
+            strength: 1.0,
+        };
+        assert!(ShallowWaterSolver::new(50.0, 100, 1.0, bad_sponge).is_err());
+    }
+
+    #[test]
+    fn test_still_water_remains_still() {
+        let mut solver = ShallowWaterSolver::new(20.0, 50, 1.0, RightBoundary::Radiation).unwrap();
+        run(&mut solver, 5.0);
+        assert!(solver.eta().iter().all(|eta| eta.abs() < 1e-14));
+        assert!((solver.time() - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_closed_flume_conserves_volume() {
+        let mut solver = ShallowWaterSolver::new(50.0, 200, 1.0, RightBoundary::Wall).unwrap();
+        solver.initialize_surface(gaussian_hump(25.0, 0.05));
+        let initial = solver.volume();
+
+        run(&mut solver, 20.0);
+        assert!((solver.volume() - initial).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_discharge_gauge_balances_volume() {
+        let mut solver = ShallowWaterSolver::new(50.0, 200, 1.0, RightBoundary::Wall).unwrap();
+        solver.initialize_surface(gaussian_hump(15.0, 0.05));
+        let gauge = solver.add_discharge_gauge(30.1);
+        assert_eq!(solver.discharge_gauge_position(gauge), 30.0);
+        let shoreward = |solver: &ShallowWaterSolver| {
+            solver.eta().iter().zip(solver.grid().x_centers()).filter(|(_, x)| **x > 30.0).map(|(eta, _)| eta * 0.25).sum::<f64>()
+        };
+
+        // The water passing the gauge is the water gained shoreward of it
+        run(&mut solver, 8.0);
+        assert!(solver.discharged_volume(gauge) > 0.01);
+        assert!((solver.discharged_volume(gauge) - shoreward(&solver)).abs() < 1e-10);
+        solver.reset();
+        assert_eq!(solver.discharged_volume(gauge), 0.0);
+    }
+
+    #[test]
+    fn test_overtopping_tank_collects_water() {
+        // Dam break in front of a crest 0.1 m above the still water level, ending at the channel end
+        let mut solver = ShallowWaterSolver::new(20.0, 200, 1.0, RightBoundary::Wall).unwrap();
+        solver.set_depth_profile(|x| 1.0 - 1.1 * ((x - 10.0) / 5.0).clamp(0.0, 1.0)).unwrap();
+        solver.initialize_surface(|x| if x < 8.0 { 0.3 } else { 0.0 });
+        assert!(solver.set_overtopping_tank(Some(25.0)).is_err());
+        solver.set_overtopping_tank(Some(20.0)).unwrap();
+        assert_eq!(solver.overtopping_tank(), Some(20.0));
+        let stored = |solver: &ShallowWaterSolver| solver.eta().iter().zip(solver.grid().widths()).map(|(eta, width)| eta * width).sum::<f64>();
+        let initial = stored(&solver);
+
+        // The water leaving the channel is in the tank, and none comes back
+        let mut collected = 0.0;
+        for _ in 0..20 {
+            run(&mut solver, 1.0);
+            assert!(solver.tank_volume() >= collected);
+            collected = solver.tank_volume();
+        }
+        assert!(collected > 0.05);
+        assert!((initial - stored(&solver) - collected).abs() < 1e-9);
+        solver.reset();
+        assert_eq!(solver.tank_volume(), 0.0);
+
+        // Without the tank, the wall keeps the water in the channel
+        solver.set_overtopping_tank(None).unwrap();
+        solver.initialize_surface(|x| if x < 8.0 { 0.3 } else { 0.0 });
+        run(&mut solver, 20.0);
+        assert!((initial - stored(&solver)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stretched_grid_conserves_volume() {
+        let zone = RefinementZone::new(30.0, 40.0, 4.0).unwrap();
+        let grid = Grid1D::refined(50.0, 200, &[zone]).unwrap();
+        let mut solver = ShallowWaterSolver::from_grid(grid, 1.0, RightBoundary::Wall).unwrap();
+        solver.initialize_surface(gaussian_hump(25.0, 0.05));
+        let initial = solver.volume();
+
+        run(&mut solver, 20.0);
+        assert!((solver.volume() - initial).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_hump_crosses_refined_zone() {
+        let zone = RefinementZone::new(60.0, 80.0, 3.0).unwrap();
+        let grid = Grid1D::refined(100.0, 500, &[zone]).unwrap();
+        let mut solver = ShallowWaterSolver::from_grid(grid, 1.0, RightBoundary::Wall)
+            .unwrap()
+            .with_nonlinear(false);
+        solver.initialize_surface(gaussian_hump(30.0, 0.01));
+
+        // The right-going half keeps its speed and amplitude through the change of spacing
+        let duration = 12.0;
+        run(&mut solver, duration);
+        let travel = 9.81_f64.sqrt() * duration;
+        let peak = solver.surface_elevation_at(30.0 + travel);
+        assert!((peak - 0.005).abs() < 0.0005, "Peak {:.4}", peak);
+        // Little reflection from the stretched cells
+        assert!(solver.surface_elevation_at(55.0).abs() < 2e-4);
+    }
+
+    #[test]
+    fn test_hump_splits_at_long_wave_speed() {
+        let mut solver = ShallowWaterSolver::new(100.0, 500, 1.0, RightBoundary::Wall)
+            .unwrap()
+            .with_nonlinear(false);
+        solver.initialize_surface(gaussian_hump(50.0, 0.01));
+
+        let duration = 5.0;
+        run(&mut solver, duration);
+
+        // Each half travels c = √(gd) and carries half the amplitude
+        let travel = 9.81_f64.sqrt() * duration;
+        let peak = solver.surface_elevation_at(50.0 + travel);
+        assert!((peak - 0.005).abs() < 0.001, "Peak {:.4}", peak);
+        assert!(solver.surface_elevation_at(50.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_open_boundaries_remove_energy() {
+        let mut boundaries = Vec::new();
+        for boundary in [
+            RightBoundary::Wall,
+            RightBoundary::Radiation,
+            RightBoundary::Sponge {
+                width: 20.0,
+                strength: 5.0,
+            },
+        ] {
+            let mut solver = ShallowWaterSolver::new(60.0, 300, 1.0, boundary).unwrap();
+            solver.initialize_surface(gaussian_hump(30.0, 0.01));
+            let initial = solver.energy();
+            run(&mut solver, 40.0);
+            boundaries.push(solver.energy() / initial);
+        }
+
+        assert!(boundaries[0] > 0.8, "Wall kept {:.3}", boundaries[0]);
+        assert!(boundaries[1] < 0.05, "Radiation kept {:.3}", boundaries[1]);
+        assert!(boundaries[2] < 0.05, "Sponge kept {:.3}", boundaries[2]);
+    }
+
+    #[test]
+    fn test_periodic_domain_wraps_around() {
+        let length = 40.0;
+        let mut solver = ShallowWaterSolver::new(length, 400, 1.0, RightBoundary::Periodic)
+            .unwrap()
+            .with_nonlinear(false);
+        solver.initialize_surface(gaussian_hump(20.0, 0.01));
+        let initial = solver.volume();
+
+        // Both halves cross the periodic boundary and meet again at the start after L/c
+        run(&mut solver, length / 9.81_f64.sqrt());
+        assert!((solver.volume() - initial).abs() < 1e-10);
+        assert!(solver.surface_elevation_at(20.0) > 0.008);
+    }
+
+    #[test]
+    fn test_wavemaker_generates_target_height() {
+        let params = DispersionSolver::new().solve_wave_parameters(Length::meters(0.02), Time::seconds(10.0), Length::meters(0.5)).unwrap();
+        let mut forcing = WaveForcing::single(params.clone());
+        let mut solver = ShallowWaterSolver::new(100.0, 400, 0.5, RightBoundary::Radiation)
+            .unwrap()
+            .with_nonlinear(false);
+
+        solver.advance(40.0, &mut forcing);
+        let mut max_eta: f64 = 0.0;
+        for _ in 0..100 {
+            solver.advance(0.1, &mut forcing);
+            max_eta = max_eta.max(solver.surface_elevation_at(30.0));
+        }
+        assert!((max_eta - params.amplitude()).abs() < 0.1 * params.amplitude(), "Crest {:.4}", max_eta);
+    }
+
+    #[test]
+    fn test_internal_source_radiates() {
+        let params = DispersionSolver::new().solve_wave_parameters(Length::meters(0.02), Time::seconds(10.0), Length::meters(0.5)).unwrap();
+        let mut forcing = WaveForcing::new();
+        forcing.add_source(WaveSource::internal(params.clone(), 50.0));
+        let mut solver = ShallowWaterSolver::new(100.0, 400, 0.5, RightBoundary::Radiation)
+            .unwrap()
+            .with_nonlinear(false);
+
+        solver.advance(20.0, &mut forcing);
+        let mut max_right: f64 = 0.0;
+        let mut max_left: f64 = 0.0;
+        for _ in 0..100 {
+            solver.advance(0.1, &mut forcing);
+            max_right = max_right.max(solver.surface_elevation_at(70.0));
+            max_left = max_left.max(solver.surface_elevation_at(30.0));
+        }
+        assert!((max_right - params.amplitude()).abs() < 0.15 * params.amplitude(), "Right {:.4}", max_right);
+        assert!((max_left - params.amplitude()).abs() < 0.15 * params.amplitude(), "Left {:.4}", max_left);
+    }
+
+    #[test]
+    fn test_active_absorption_reduces_reflection() {
+        let params = DispersionSolver::new().solve_wave_parameters(Length::meters(0.02), Time::seconds(10.0), Length::meters(0.5)).unwrap();
+        let duration = 2.0 * params.period;
+
+        let mut final_energy = Vec::new();
+        for absorbing in [false, true] {
+            let mut forcing = WaveForcing::new();
+            forcing.add_source(WaveSource::boundary(params.clone()).with_generation_duration(duration));
+            let mut solver = ShallowWaterSolver::new(60.0, 240, 0.5, RightBoundary::Wall)
+                .unwrap()
+                .with_nonlinear(false);
+            if absorbing {
+                solver.set_absorber(Some(ActiveAbsorber::new(0.5).unwrap()));
+            }
+            // Long enough for the wave train to reflect at the wall and return to the paddle
+            solver.advance(100.0, &mut forcing);
+            final_energy.push(solver.energy());
+        }
+
+        assert!(final_energy[1] < 0.1 * final_energy[0], "Energy {:.3e} vs {:.3e}", final_energy[1], final_energy[0]);
+    }
+
+    /// Surface elevation at the left wall after half a period of the first basin mode
+    fn standing_wave_half_period(mode: Option<DispersionMode>, depth: f64, omega: f64) -> f64 {
+        let length = 10.0;
+        let k = std::f64::consts::PI / length;
+        let mut solver = ShallowWaterSolver::new(length, 100, depth, RightBoundary::Wall)
+            .unwrap()
+            .with_nonlinear(false)
+            .with_non_hydrostatic(mode);
+        solver.initialize_surface(|x| 0.01 * (k * x).cos());
+        run(&mut solver, std::f64::consts::PI / omega);
+        solver.surface_elevation_at(0.0) / (0.01 * (k * solver.grid().x_centers()[0]).cos())
+    }
+
+    #[test]
+    fn test_non_hydrostatic_basin_modes() {
+        // First basin mode with kd = π/2, where hydrostatic waves are clearly too fast
+        let depth = 5.0;
+        let k = std::f64::consts::PI / 10.0;
+        for mode in [DispersionMode::OneLayer, DispersionMode::TwoLayer] {
+            let solver = DispersionSolver::new().with_mode(mode);
+            let omega = k * solver.phase_velocity(k, depth);
+            let ratio = standing_wave_half_period(Some(mode), depth, omega);
+            assert!((ratio + 1.0).abs() < 0.02, "{}: η/η₀ = {:.3}", mode, ratio);
+        }
+
+        let omega = k * DispersionSolver::new().phase_velocity(k, depth);
+        let hydrostatic = standing_wave_half_period(None, depth, omega);
+        assert!((hydrostatic + 1.0).abs() > 0.1);
+    }
+
+    #[test]
+    fn test_pressure_at_bed() {
+        // Still water: hydrostatic below the surface, nothing above it
+        let solver = ShallowWaterSolver::new(10.0, 100, 5.0, RightBoundary::Wall).unwrap();
+        assert!((solver.pressure_at(3.0, -2.0) - 9.81 * 2.0).abs() < 1e-12);
+        assert_eq!(solver.pressure_at(3.0, 0.1), 0.0);
+        assert!((solver.pressure_at(3.0, -8.0) - 9.81 * 5.0).abs() < 1e-12);
+        assert!((solver.column_force(3.0) - 0.5 * 9.81 * 25.0).abs() < 1e-9);
+
+        // First basin mode after half a period: the dynamic bed pressure of linear theory is gη / cosh kd
+        let (length, depth) = (10.0, 5.0);
+        let k = std::f64::consts::PI / length;
+        let omega = k * DispersionSolver::new().with_mode(DispersionMode::TwoLayer).phase_velocity(k, depth);
+        for (mode, expected) in [(Some(DispersionMode::TwoLayer), 1.0 / (k * depth).cosh()), (None, 1.0)] {
+            let mut solver = ShallowWaterSolver::new(length, 100, depth, RightBoundary::Wall)
+                .unwrap()
+                .with_nonlinear(false)
+                .with_non_hydrostatic(mode);
+            solver.initialize_surface(|x| 0.01 * (k * x).cos());
+            run(&mut solver, std::f64::consts::PI / omega);
+            let x = solver.grid().x_centers()[0];
+            let dynamic = solver.pressure_at(x, -depth) - 9.81 * depth;
+            let ratio = dynamic / (9.81 * solver.surface_elevation_at(x));
+            assert!((ratio - expected).abs() < 0.05, "{:?}: p/ρgη = {:.3}", mode, ratio);
+        }
+    }
+
+    #[test]
+    fn test_two_layer_deep_water_mode() {
+        // kd = π: two layers follow linear theory, one layer lags behind
+        let depth = 10.0;
+        let k = std::f64::consts::PI / 10.0;
+        let omega = (9.81 * k * (k * depth).tanh()).sqrt();
+
+        let two_layer = standing_wave_half_period(Some(DispersionMode::TwoLayer), depth, omega);
+        assert!((two_layer + 1.0).abs() < 0.02, "Two layers: η/η₀ = {:.3}", two_layer);
+
+        let one_layer = standing_wave_half_period(Some(DispersionMode::OneLayer), depth, omega);
+        assert!((one_layer + 1.0).abs() > (two_layer + 1.0).abs());
+    }
+
+    #[test]
+    fn test_non_hydrostatic_periodic_domain() {
+        let length = 20.0;
+        let depth = 4.0;
+        let k = 2.0 * std::f64::consts::PI / length;
+        let mut solver = ShallowWaterSolver::new(length, 100, depth, RightBoundary::Periodic)
+            .unwrap()
+            .with_nonlinear(false)
+            .with_non_hydrostatic(Some(DispersionMode::TwoLayer));
+        solver.initialize_surface(|x| 0.01 * (k * x).cos());
+        let initial = solver.volume();
+
+        // Standing wave reverses after half a period, also across the periodic boundary
+        let omega = k * DispersionSolver::new().with_mode(DispersionMode::TwoLayer).phase_velocity(k, depth);
+        run(&mut solver, std::f64::consts::PI / omega);
+        assert!((solver.volume() - initial).abs() < 1e-10);
+        let x0 = solver.grid().x_centers()[0];
+        let ratio = solver.surface_elevation_at(x0) / (0.01 * (k * x0).cos());
+        assert!((ratio + 1.0).abs() < 0.02, "η/η₀ = {:.3}", ratio);
+    }
+
+    #[test]
+    fn test_non_hydrostatic_wavemaker() {
+        // Deep water wave (kd ≈ 4) generated with two layers and absorbed by a sponge
+        let params = DispersionSolver::new()
+            .with_mode(DispersionMode::TwoLayer)
+            .solve_wave_parameters(Length::meters(0.02), Time::seconds(2.0), Length::meters(2.5))
+            .unwrap();
+        let mut forcing = WaveForcing::single(params.clone());
+        let sponge = RightBoundary::Sponge {
+            width: 12.0,
+            strength: 5.0,
+        };
+        let mut solver = ShallowWaterSolver::new(30.0, 300, 2.5, sponge)
+            .unwrap()
+            .with_nonlinear(false)
+            .with_non_hydrostatic(Some(DispersionMode::TwoLayer));
+
+        solver.advance(30.0, &mut forcing);
+        let mut max_eta: f64 = 0.0;
+        for _ in 0..100 {
+            solver.advance(0.05, &mut forcing);
+            max_eta = max_eta.max(solver.surface_elevation_at(10.0));
+        }
+        assert!((max_eta - params.amplitude()).abs() < 0.1 * params.amplitude(), "Crest {:.4}", max_eta);
+    }
+
+    #[test]
+    fn test_stream_function_wavemaker() {
+        // A steep wave in intermediate depth keeps the high crests and flat troughs of its stream function profile
+        let wave = crate::waves::StreamFunctionSolver::new().solve(Length::meters(0.12), Time::seconds(2.0), Length::meters(0.5)).unwrap();
+        let mut forcing = WaveForcing::new();
+        forcing.add_source(WaveSource::boundary(wave.parameters()).with_stream_function(wave.clone()));
+        let sponge = RightBoundary::Sponge {
+            width: 8.0,
+            strength: 5.0,
+        };
+        let mut solver = ShallowWaterSolver::new(20.0, 400, 0.5, sponge)
+            .unwrap()
+            .with_non_hydrostatic(Some(DispersionMode::TwoLayer));
+
+        solver.advance(20.0, &mut forcing);
+        let (mut crest, mut trough): (f64, f64) = (0.0, 0.0);
+        for _ in 0..200 {
+            solver.advance(0.02, &mut forcing);
+            crest = crest.max(solver.surface_elevation_at(1.0));
+            trough = trough.min(solver.surface_elevation_at(1.0));
+        }
+        assert!((crest - trough - wave.wave_height()).abs() < 0.15 * wave.wave_height(), "Height {:.4}", crest - trough);
+        assert!(crest > 1.2 * -trough, "Crest {:.4}, trough {:.4}", crest, trough);
+    }
+
+    #[test]
+    fn test_non_hydrostatic_celerity() {
+        let solver = ShallowWaterSolver::new(50.0, 50, 2.0, RightBoundary::Wall).unwrap();
+        let omega = 2.0;
+        assert_eq!(solver.celerity(omega, 2.0), (9.81_f64 * 2.0).sqrt());
+
+        let solver = solver.with_non_hydrostatic(Some(DispersionMode::TwoLayer));
+        assert_eq!(solver.layer_velocities().len(), 2);
+        let k = DispersionSolver::new().with_mode(DispersionMode::TwoLayer).solve_wave_number(omega, 2.0).unwrap();
+        assert!((solver.celerity(omega, 2.0) - omega / k).abs() < 1e-12);
+    }
+
+    /// Mean absolute depth error against the analytical dam-break solution
+    fn dam_break_error(upstream_depth: f64, downstream_depth: f64, duration: f64, scheme: AdvectionScheme) -> f64 {
+        let condition = InitialCondition::DamBreak {
+            position: 50.0,
+            upstream_depth,
+            downstream_depth,
+        };
+        let mut solver = ShallowWaterSolver::new(100.0, 500, 1.0, RightBoundary::Wall).unwrap().with_advection_scheme(scheme);
+        solver.set_initial_condition(Some(condition)).unwrap();
+        let initial = solver.volume();
+        run(&mut solver, duration);
+        assert!((solver.volume() - initial).abs() < 1e-9);
+
+        let errors: Vec<f64> = solver
+            .grid()
+            .x_centers()
+            .iter()
+            .zip(solver.eta())
+            .map(|(&x, &eta)| (eta - condition.analytical_elevation(x, duration, 1.0)).abs())
+            .collect();
+        errors.iter().sum::<f64>() / errors.len() as f64
+    }
+
+    #[test]
+    fn test_dam_break_matches_stoker() {
+        let error = dam_break_error(1.0, 0.3, 6.0, AdvectionScheme::Upwind);
+        assert!(error < 0.005, "Mean depth error {:.4}", error);
+    }
+
+    #[test]
+    fn test_dam_break_matches_ritter() {
+        let error = dam_break_error(1.0, 0.0, 6.0, AdvectionScheme::Upwind);
+        assert!(error < 0.005, "Mean depth error {:.4}", error);
+    }
+
+    #[test]
+    fn test_higher_order_advection_reduces_diffusion() {
+        let stoker = dam_break_error(1.0, 0.3, 6.0, AdvectionScheme::Upwind);
+        let ritter = dam_break_error(1.0, 0.0, 6.0, AdvectionScheme::Upwind);
+        for scheme in [AdvectionScheme::Muscl(Limiter::Minmod), AdvectionScheme::Muscl(Limiter::Superbee), AdvectionScheme::Weno3] {
+            assert!(dam_break_error(1.0, 0.3, 6.0, scheme) < stoker, "{}", scheme);
+            assert!(dam_break_error(1.0, 0.0, 6.0, scheme) < ritter, "{}", scheme);
+        }
+    }
+
+    #[test]
+    fn test_initial_condition_restored_on_reset() {
+        let mut solver = ShallowWaterSolver::new(50.0, 100, 1.0, RightBoundary::Wall).unwrap();
+        solver.set_initial_condition(Some(InitialCondition::gaussian_hump(50.0, 1.0))).unwrap();
+        let initial = solver.eta().to_vec();
+        assert!(initial[50] > 0.09);
+
+        run(&mut solver, 2.0);
+        solver.reset();
+        assert_eq!(solver.eta(), initial.as_slice());
+
+        let outside = InitialCondition::DamBreak {
+            position: 80.0,
+            upstream_depth: 2.0,
+            downstream_depth: 1.0,
+        };
+        assert!(solver.set_initial_condition(Some(outside)).is_err());
+    }
+
+    #[test]
+    fn test_vegetation_follows_dalrymple_decay() {
+        // Emergent stems in shallow water, patch shorter than the decay length
+        let (depth, period, height) = (0.5, 4.0, 0.05);
+        let params = DispersionSolver::new().solve_wave_parameters(Length::meters(height), Time::seconds(period), Length::meters(depth)).unwrap();
+        let field = VegetationField::new(40.0, 50.0).unwrap().with_stems(1000.0, 0.01, 1.0);
+        let sponge = RightBoundary::Sponge {
+            width: 20.0,
+            strength: 5.0,
+        };
+        let mut solver = ShallowWaterSolver::new(100.0, 500, depth, sponge)
+            .unwrap()
+            .with_non_hydrostatic(Some(DispersionMode::OneLayer));
+        solver.set_vegetation(Some(field)).unwrap();
+        assert!(solver.set_vegetation(Some(VegetationField::new(90.0, 120.0).unwrap())).is_err());
+
+        let mut forcing = WaveForcing::single(params.clone());
+        solver.advance(60.0, &mut forcing);
+        let (mut min_eta, mut max_eta) = (0.0_f64, 0.0_f64);
+        for _ in 0..200 {
+            solver.advance(0.05, &mut forcing);
+            let eta = solver.surface_elevation_at(55.0);
+            min_eta = min_eta.min(eta);
+            max_eta = max_eta.max(eta);
+        }
+
+        let expected = field.wave_height_after(height, params.k, depth, field.length());
+        let transmitted = max_eta - min_eta;
+        assert!(transmitted < 0.8 * height, "Transmitted {:.4}", transmitted);
+        assert!((transmitted - expected).abs() < 0.2 * expected, "Transmitted {:.4}, expected {:.4}", transmitted, expected);
+    }
+
+    #[test]
+    fn test_runup_on_beach() {
+        let section = CrossSection::beach(40.0, 0.5);
+        let mut solver = ShallowWaterSolver::new(40.0, 400, 0.5, RightBoundary::Wall).unwrap();
+        solver.set_depth_profile(|x| section.depth_at(x, 0.5)).unwrap();
+        let dry = solver.grid().cell_index(39.0);
+        assert!(solver.depth()[dry] < 0.0);
+        assert!((solver.eta()[dry] + solver.depth()[dry]).abs() < 1e-12);
+
+        // Still water stays still on the beach
+        run(&mut solver, 2.0);
+        assert!(solver.eta().iter().zip(solver.depth()).all(|(eta, depth)| eta.abs() < 1e-12 || (eta + depth).abs() < 1e-12));
+
+        // The still water line is where the beach rises above the still water level
+        let still_water_line = section.segments[0].length + 20.0 * 0.5;
+        assert!((solver.shoreline(1e-3).unwrap() - still_water_line).abs() < 0.1);
+
+        // A long wave runs up above the still water line without losing water
+        solver.initialize_surface(|x| 0.05 * (-((x - 8.0) / 3.0).powi(2)).exp());
+        let initial = solver.volume();
+        let shoreline = solver.grid().cell_index(still_water_line + 0.5);
+        let (mut wetted, mut highest) = (false, 0.0_f64);
+        for _ in 0..150 {
+            run(&mut solver, 0.1);
+            wetted |= solver.eta()[shoreline] + solver.depth()[shoreline] > 1e-3;
+            highest = highest.max(solver.shoreline(1e-3).unwrap());
+        }
+        assert!(solver.eta().iter().all(|eta| eta.is_finite()));
+        assert!(wetted);
+        assert!(highest > still_water_line + 0.5);
+        assert!((solver.volume() - initial).abs() < 1e-6 * initial.abs().max(1.0));
+        assert!(solver.set_depth_profile(|_| -1.0).is_err());
+    }
+
+    #[test]
+    fn test_rising_water_level_fills_channel() {
+        let mut solver = ShallowWaterSolver::new(20.0, 100, 0.5, RightBoundary::Wall).unwrap();
+        let mut forcing = WaveForcing::new();
+        // Surge rising smoothly by 0.1 m over 60 s, slow compared with the 18 s crossing
+        while solver.time() < 60.0 {
+            forcing.set_water_level(0.05 * (1.0 - (std::f64::consts::PI * solver.time() / 60.0).cos()));
+            solver.advance(0.1, &mut forcing);
+        }
+        assert!((solver.volume() - 0.1 * 20.0).abs() < 0.02 * 0.1 * 20.0, "Volume {:.4}", solver.volume());
+        let deviation = solver.eta().iter().map(|eta| (eta - 0.1).abs()).fold(0.0, f64::max);
+        assert!(deviation < 0.01, "Deviation {:.4}", deviation);
+        assert_eq!(solver.water_level(), forcing.water_level());
+    }
+
+    #[test]
+    fn test_mass_compensation_keeps_mean_level() {
+        // Steep waves in a closed flume: the wave crests bring more water in than the troughs take out
+        let params = DispersionSolver::new().solve_wave_parameters(Length::meters(0.1), Time::seconds(3.0), Length::meters(0.5)).unwrap();
+        let mut drift = Vec::new();
+        for compensation in [false, true] {
+            let mut forcing = WaveForcing::single(params.clone());
+            let mut solver = ShallowWaterSolver::new(60.0, 300, 0.5, RightBoundary::Wall).unwrap().with_mass_compensation(compensation);
+            assert_eq!(solver.mass_compensation(), compensation);
+            solver.advance(30.0, &mut forcing);
+            assert!((solver.mean_level_drift() - solver.volume() / 60.0).abs() < 1e-12);
+            drift.push(solver.mean_level_drift());
+        }
+        assert!(drift[0] > 1e-3, "Drift {:.2e}", drift[0]);
+        assert!(drift[1].abs() < 0.1 * drift[0], "Drift {:.2e} against {:.2e}", drift[1], drift[0]);
+
+        // The water level of the forcing is not a drift
+        let mut solver = ShallowWaterSolver::new(20.0, 100, 0.5, RightBoundary::Wall).unwrap();
+        let mut forcing = WaveForcing::new();
+        forcing.set_water_level(0.05);
+        solver.advance(20.0, &mut forcing);
+        assert!(solver.mean_level_drift().abs() < 1e-3, "Drift {:.2e}", solver.mean_level_drift());
+    }
+
+    #[test]
+    fn test_ambient_current_flows_through() {
+        let mut solver = ShallowWaterSolver::new(30.0, 150, 0.5, RightBoundary::Wall).unwrap();
+        assert!(solver.set_current(Some(AmbientCurrent::uniform(0.3))).is_err());
+        solver.set_right_boundary(RightBoundary::Radiation).unwrap();
+        solver.set_current(Some(AmbientCurrent::uniform(0.3))).unwrap();
+        assert!(solver.set_right_boundary(RightBoundary::Wall).is_err());
+        assert!(solver.u().iter().all(|&u| (u - 0.3).abs() < 1e-12));
+
+        // Without waves the current passes through unchanged
+        run(&mut solver, 20.0);
+        assert!(solver.eta().iter().all(|eta| eta.abs() < 1e-6), "{:?}", solver.eta().iter().cloned().fold(0.0, f64::max));
+        assert!(solver.u().iter().all(|&u| (u - 0.3).abs() < 1e-6));
+        solver.reset();
+        assert_eq!(solver.u()[75], 0.3);
+    }
+
+    #[test]
+    fn test_current_shifts_wavelength() {
+        // Long waves travel at √(gd) + U relative to the bed, so their wavelength is T(√(gd) + U)
+        let params = DispersionSolver::new().solve_wave_parameters(Length::meters(0.01), Time::seconds(4.0), Length::meters(0.5)).unwrap();
+        for velocity in [0.3, -0.3] {
+            let mut forcing = WaveForcing::single(params.clone());
+            let mut solver = ShallowWaterSolver::new(80.0, 800, 0.5, RightBoundary::Radiation).unwrap();
+            solver.set_current(Some(AmbientCurrent::uniform(velocity))).unwrap();
+            solver.advance(30.0, &mut forcing);
+
+            let x = solver.grid().x_centers();
+            let crossings: Vec<f64> = (1..x.len())
+                .filter(|&i| x[i] > 5.0 && x[i] < 40.0 && solver.eta()[i - 1] < 0.0 && solver.eta()[i] >= 0.0)
+                .map(|i| x[i])
+                .collect();
+            let wavelength = (crossings[crossings.len() - 1] - crossings[0]) / (crossings.len() - 1) as f64;
+            let expected = 4.0 * ((9.81_f64 * 0.5).sqrt() + velocity);
+            assert!((wavelength / expected - 1.0).abs() < 0.05, "U = {}: L = {:.2} against {:.2}", velocity, wavelength, expected);
+        }
+    }
+
+    #[test]
+    fn test_steps_reuse_work_arrays() {
+        let mut solver = ShallowWaterSolver::new(20.0, 100, 0.5, RightBoundary::Radiation).unwrap();
+        solver.initialize_surface(gaussian_hump(10.0, 0.05));
+        run(&mut solver, 0.5);
+        let buffers = (solver.scratch.fluxes.as_ptr(), solver.scratch.faces.as_ptr(), solver.scratch.advection[0].as_ptr());
+        run(&mut solver, 2.0);
+        assert_eq!((solver.scratch.fluxes.as_ptr(), solver.scratch.faces.as_ptr(), solver.scratch.advection[0].as_ptr()), buffers);
+        assert_eq!(solver.scratch.fluxes.len(), solver.u().len());
+    }
+
+    #[test]
+    fn test_dissipation_breakdown_closes_energy_balance() {
+        // Hump absorbed by the sponge, then by a vegetation patch in front of a wall, then breaking as a bore
+        let sponge = RightBoundary::Sponge {
+            width: 20.0,
+            strength: 5.0,
+        };
+        let mut solver = ShallowWaterSolver::new(60.0, 300, 1.0, sponge).unwrap();
+        solver.initialize_surface(gaussian_hump(30.0, 0.01));
+        let initial = solver.energy();
+        run(&mut solver, 40.0);
+        let dissipation = solver.dissipation();
+        assert!((dissipation.total() + solver.energy() - initial).abs() < 0.01 * initial);
+        assert!(dissipation.sponge > 0.95 * initial && dissipation.breaking.abs() < 0.01 * initial);
+
+        let mut solver = ShallowWaterSolver::new(60.0, 300, 1.0, RightBoundary::Wall).unwrap();
+        solver.set_vegetation(VegetationField::new(35.0, 55.0).ok().map(|field| field.with_stems(1000.0, 0.01, 1.0))).unwrap();
+        solver.initialize_surface(gaussian_hump(30.0, 0.01));
+        let initial = solver.energy();
+        run(&mut solver, 40.0);
+        let dissipation = solver.dissipation();
+        assert!((dissipation.total() + solver.energy() - initial).abs() < 0.01 * initial);
+        assert!(dissipation.vegetation > 0.3 * initial && dissipation.breaking.abs() < 0.01 * initial);
+        assert_eq!(dissipation.sponge, 0.0);
+
+        let mut solver = ShallowWaterSolver::new(60.0, 300, 1.0, RightBoundary::Wall).unwrap();
+        solver.initialize_surface(|x| if x < 20.0 { 0.4 } else { 0.0 });
+        let initial = solver.energy();
+        run(&mut solver, 10.0);
+        let dissipation = solver.dissipation();
+        assert!((dissipation.total() + solver.energy() - initial).abs() < 1e-6 * initial);
+        assert!(dissipation.breaking > 0.05 * initial);
+        assert_eq!(dissipation.get(DissipationMechanism::Breaking), dissipation.breaking);
+        solver.reset();
+        assert_eq!(solver.dissipation(), Dissipation::default());
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut solver = ShallowWaterSolver::new(20.0, 50, 1.0, RightBoundary::Wall).unwrap();
+        solver.initialize_surface(gaussian_hump(10.0, 0.05));
+        run(&mut solver, 1.0);
+        solver.reset();
+        assert_eq!(solver.time(), 0.0);
+        assert_eq!(solver.volume(), 0.0);
+    }
+}
@@ -0,0 +1,283 @@
+use super::bathymetry::{CrossSection, ProfileSegment};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Length unit of the columns of a survey file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SurveyUnit {
+    #[default]
+    Meters,
+    Centimeters,
+    Feet,
+}
+
+impl SurveyUnit {
+    pub const ALL: [SurveyUnit; 3] = [SurveyUnit::Meters, SurveyUnit::Centimeters, SurveyUnit::Feet];
+
+    /// Meters per unit
+    pub fn to_meters(&self) -> f64 {
+        match self {
+            SurveyUnit::Meters => 1.0,
+            SurveyUnit::Centimeters => 0.01,
+            SurveyUnit::Feet => 0.3048,
+        }
+    }
+}
+
+impl std::fmt::Display for SurveyUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SurveyUnit::Meters => write!(f, "m"),
+            SurveyUnit::Centimeters => write!(f, "cm"),
+            SurveyUnit::Feet => write!(f, "ft"),
+        }
+    }
+}
+
+/// Conversion of a survey to a channel bed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SurveyOptions {
+    pub distance_unit: SurveyUnit,  // Unit of the distances, or of the x and y coordinates
+    pub elevation_unit: SurveyUnit, // Unit of the elevations
+    pub water_level: f64,           // Still water level in the survey datum, in the elevation unit
+    pub landward_first: bool,       // Points run from the land to the sea, so the profile is reversed
+    pub spacing: f64,               // Spacing of the points interpolated from the survey, 0 to keep the surveyed points [m]
+    pub smoothing: f64,             // Width of the moving average over the interpolated points, 0 for none [m]
+}
+
+impl Default for SurveyOptions {
+    fn default() -> Self {
+        Self {
+            distance_unit: SurveyUnit::Meters,
+            elevation_unit: SurveyUnit::Meters,
+            water_level: 0.0,
+            landward_first: false,
+            spacing: 0.0,
+            smoothing: 0.0,
+        }
+    }
+}
+
+/// Cross-shore survey transect: bed elevation at distances along the profile
+///
+/// Files are CSV or whitespace-separated text with one point per line. Two columns are the distance
+/// and the elevation; three columns are x, y and z coordinates, and the distance is measured along
+/// the line joining the points. Lines starting with '#' and a header line are skipped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SurveyProfile {
+    pub points: Vec<[f64; 2]>, // Distance and bed elevation, by increasing distance
+}
+
+impl SurveyProfile {
+    /// Profile from points, sorted by distance, keeping the first of points at the same distance
+    pub fn new(mut points: Vec<[f64; 2]>) -> Result<Self, String> {
+        if points.iter().flatten().any(|value| !value.is_finite()) {
+            return Err("Survey points must be finite".to_string());
+        }
+        points.sort_by(|a, b| a[0].total_cmp(&b[0]));
+        points.dedup_by(|b, a| b[0] - a[0] < 1e-9);
+        if points.len() < 2 {
+            return Err("Survey needs at least two points at different distances".to_string());
+        }
+        Ok(Self { points })
+    }
+
+    /// Read a survey file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        Self::parse(&content)
+    }
+
+    /// Survey from the content of a CSV or XYZ file
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut rows = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let values: Result<Vec<f64>, _> = line
+                .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+                .filter(|field| !field.is_empty())
+                .map(str::parse::<f64>)
+                .collect();
+            match values {
+                Ok(values) if values.len() >= 2 => rows.push(values),
+                Ok(_) => return Err(format!("Line {} needs at least two columns", number + 1)),
+                // Column names before the first point
+                Err(_) if rows.is_empty() => continue,
+                Err(e) => return Err(format!("Invalid number on line {}: {}", number + 1, e)),
+            }
+        }
+        let points = if rows.iter().all(|row| row.len() >= 3) {
+            // Distance along the transect from the first point
+            let mut distance = 0.0;
+            rows.iter()
+                .enumerate()
+                .map(|(i, row)| {
+                    if i > 0 {
+                        distance += (row[0] - rows[i - 1][0]).hypot(row[1] - rows[i - 1][1]);
+                    }
+                    [distance, row[2]]
+                })
+                .collect()
+        } else {
+            rows.iter().map(|row| [row[0], row[1]]).collect()
+        };
+        Self::new(points)
+    }
+
+    /// Length of the profile [m]
+    pub fn length(&self) -> f64 {
+        self.points[self.points.len() - 1][0] - self.points[0][0]
+    }
+
+    /// Elevation interpolated linearly between the points, constant beyond the ends
+    pub fn elevation_at(&self, x: f64) -> f64 {
+        let index = self.points.partition_point(|point| point[0] < x);
+        if index == 0 {
+            return self.points[0][1];
+        }
+        if index == self.points.len() {
+            return self.points[index - 1][1];
+        }
+        let ([x0, z0], [x1, z1]) = (self.points[index - 1], self.points[index]);
+        z0 + (z1 - z0) * (x - x0) / (x1 - x0)
+    }
+
+    /// Profile in meters relative to the still water level, starting at 0 at the seaward end
+    pub fn adjusted(&self, options: &SurveyOptions) -> Result<Self, String> {
+        let (distance_scale, elevation_scale) = (options.distance_unit.to_meters(), options.elevation_unit.to_meters());
+        let (first, last) = (self.points[0][0], self.points[self.points.len() - 1][0]);
+        let points = self
+            .points
+            .iter()
+            .map(|&[x, z]| {
+                let distance = if options.landward_first { last - x } else { x - first };
+                [distance * distance_scale, (z - options.water_level) * elevation_scale]
+            })
+            .collect();
+        Self::new(points)
+    }
+
+    /// Profile interpolated at a regular spacing [m]
+    pub fn resampled(&self, spacing: f64) -> Result<Self, String> {
+        if !(spacing.is_finite() && spacing > 0.0) {
+            return Err("Interpolation spacing must be positive".to_string());
+        }
+        let start = self.points[0][0];
+        let count = (self.length() / spacing).ceil() as usize;
+        let points = (0..=count)
+            .map(|i| {
+                let x = (start + i as f64 * spacing).min(start + self.length());
+                [x, self.elevation_at(x)]
+            })
+            .collect();
+        Self::new(points)
+    }
+
+    /// Profile smoothed by a centered moving average over a width [m], keeping the end elevations
+    pub fn smoothed(&self, width: f64) -> Self {
+        let points = self
+            .points
+            .iter()
+            .map(|&[x, z]| {
+                let window: Vec<f64> = self
+                    .points
+                    .iter()
+                    .filter(|point| (point[0] - x).abs() <= 0.5 * width)
+                    .map(|point| point[1])
+                    .collect();
+                [x, if window.is_empty() { z } else { window.iter().sum::<f64>() / window.len() as f64 }]
+            })
+            .collect();
+        Self { points }
+    }
+
+    /// Channel bed from the survey and the still water depth at the wavemaker
+    ///
+    /// Elevations are converted to meters relative to the still water level, interpolated and smoothed,
+    /// then joined by straight segments. The seaward end must be under water.
+    pub fn to_cross_section(&self, options: &SurveyOptions) -> Result<(CrossSection, f64), String> {
+        let mut profile = self.adjusted(options)?;
+        if options.spacing > 0.0 {
+            profile = profile.resampled(options.spacing)?;
+        }
+        if options.smoothing > 0.0 {
+            profile = profile.smoothed(options.smoothing);
+        }
+        let offshore = profile.points[0][1];
+        if offshore >= 0.0 {
+            return Err(format!("Seaward end of the survey is {:.2} m above the still water level", offshore));
+        }
+        let segments = profile
+            .points
+            .windows(2)
+            .map(|pair| {
+                let (length, rise) = (pair[1][0] - pair[0][0], pair[1][1] - pair[0][1]);
+                let slope = if rise.abs() < 1e-12 { 0.0 } else { length / rise };
+                ProfileSegment::slope("", length, slope)
+            })
+            .collect();
+        Ok((CrossSection::new(segments)?, -offshore))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv_and_xyz() {
+        let csv = SurveyProfile::parse("distance,elevation\n0,-2\n# comment\n10;-1\n20 0.5\n").unwrap();
+        assert_eq!(csv.points, vec![[0.0, -2.0], [10.0, -1.0], [20.0, 0.5]]);
+
+        let xyz = SurveyProfile::parse("100 200 -3\n103 204 -2\n106 208 -1\n").unwrap();
+        assert_eq!(xyz.points, vec![[0.0, -3.0], [5.0, -2.0], [10.0, -1.0]]);
+
+        assert!(SurveyProfile::parse("0,-1\n").is_err());
+        assert!(SurveyProfile::parse("0,-1\n1,x\n").is_err());
+    }
+
+    #[test]
+    fn test_interpolation_and_smoothing() {
+        let survey = SurveyProfile::new(vec![[10.0, -1.0], [0.0, -2.0], [20.0, 1.0]]).unwrap();
+        assert_eq!(survey.elevation_at(5.0), -1.5);
+        assert_eq!(survey.elevation_at(-5.0), -2.0);
+        assert_eq!(survey.elevation_at(25.0), 1.0);
+
+        let resampled = survey.resampled(2.5).unwrap();
+        assert_eq!(resampled.points.len(), 9);
+        assert_eq!(resampled.points[6], [15.0, 0.0]);
+
+        // A spike is spread over the averaging window
+        let spiky = SurveyProfile::new((0..11).map(|i| [i as f64, if i == 5 { 3.0 } else { 0.0 }]).collect()).unwrap();
+        let smoothed = spiky.smoothed(2.0);
+        assert_eq!(smoothed.points[5][1], 1.0);
+        assert_eq!(smoothed.points[4][1], 1.0);
+        assert_eq!(smoothed.points[2][1], 0.0);
+    }
+
+    #[test]
+    fn test_cross_section_from_survey() {
+        // Landward-first survey in feet with the water level at +2 ft
+        let survey = SurveyProfile::new(vec![[0.0, 6.0], [50.0, 2.0], [100.0, -2.0], [150.0, -2.0]]).unwrap();
+        let options = SurveyOptions {
+            distance_unit: SurveyUnit::Feet,
+            elevation_unit: SurveyUnit::Feet,
+            water_level: 2.0,
+            landward_first: true,
+            ..SurveyOptions::default()
+        };
+        let (section, depth) = survey.to_cross_section(&options).unwrap();
+        assert!((depth - 4.0 * 0.3048).abs() < 1e-12);
+        assert!((section.length() - 150.0 * 0.3048).abs() < 1e-9);
+        assert_eq!(section.segments[0].slope, 0.0);
+        assert!((section.segments[1].slope - 12.5).abs() < 1e-9);
+        // Shoreline where the survey crosses the water level
+        assert!(section.depth_at(100.0 * 0.3048, depth).abs() < 1e-9);
+
+        let dry = SurveyOptions { water_level: -5.0, ..options };
+        assert!(survey.to_cross_section(&dry).is_err());
+    }
+}
@@ -0,0 +1,276 @@
+use crate::solver::shallow_water::ShallowWaterSolver;
+use crate::waves::WaveForcing;
+use serde::{Deserialize, Serialize};
+
+/// Implicitness of the semi-implicit scheme, slightly above ½ to damp the shortest waves
+pub const SEMI_IMPLICIT_THETA: f64 = 0.55;
+
+/// Time step of the semi-implicit scheme as a multiple of the explicit limit of the gravity waves
+pub const SEMI_IMPLICIT_STEP_FACTOR: f64 = 4.0;
+
+/// Integration in time of the continuity and momentum equations over one step
+///
+/// The boundary velocities are set before and the non-hydrostatic correction, the sponge and, for
+/// explicit schemes, the vegetation drag are applied after, whatever the integrator.
+pub trait TimeIntegrator {
+    /// Advance the surface elevations and the layer velocities at the inner faces by a time step [s]
+    fn integrate(&self, solver: &mut ShallowWaterSolver, dt: f64, forcing: &WaveForcing);
+
+    /// Time step as a multiple of the explicit limit of the gravity waves, the CFL time step
+    fn time_step_factor(&self) -> f64 {
+        1.0
+    }
+
+    /// Whether the vegetation drag is part of the implicit solve rather than applied after it
+    fn implicit_friction(&self) -> bool {
+        false
+    }
+}
+
+/// Forward-backward (symplectic) Euler: continuity first, then momentum with the new surface
+///
+/// First order, but neutrally stable for gravity waves up to a Courant number of one on the staggered grid.
+pub struct ForwardBackward;
+
+impl TimeIntegrator for ForwardBackward {
+    fn integrate(&self, solver: &mut ShallowWaterSolver, dt: f64, forcing: &WaveForcing) {
+        solver.continuity(dt, forcing);
+        solver.momentum(dt);
+    }
+}
+
+/// Explicit Runge-Kutta scheme built from forward Euler stages
+pub struct RungeKutta {
+    stages: &'static [&'static [f64]], // Coefficients a_ij of each stage on the previous increments
+    weights: &'static [f64],           // Weights b_j of the increments in the final update
+    time_step_factor: f64,             // Time step as a multiple of the CFL time step
+}
+
+impl RungeKutta {
+    /// Heun's method, second order
+    ///
+    /// Its stability region touches the imaginary axis only at the origin: undamped waves grow by
+    /// √(1 + (ωΔt)⁴/4) per step, so it runs at a quarter of the CFL time step, where the growth stays
+    /// below the damping of the upwind advection.
+    pub const SECOND_ORDER: RungeKutta = RungeKutta {
+        stages: &[&[], &[1.0]],
+        weights: &[0.5, 0.5],
+        time_step_factor: 0.25,
+    };
+
+    /// Classical fourth-order scheme
+    ///
+    /// Its stability region covers the imaginary axis up to ωΔt = 2√2, so it runs at twice the CFL time step.
+    pub const FOURTH_ORDER: RungeKutta = RungeKutta {
+        stages: &[&[], &[0.5], &[0.0, 0.5], &[0.0, 0.0, 1.0]],
+        weights: &[1.0 / 6.0, 1.0 / 3.0, 1.0 / 3.0, 1.0 / 6.0],
+        time_step_factor: 2.0,
+    };
+}
+
+impl TimeIntegrator for RungeKutta {
+    fn integrate(&self, solver: &mut ShallowWaterSolver, dt: f64, forcing: &WaveForcing) {
+        let mut buffers = solver.take_stage_buffers();
+        let StageBuffers { start, stage, increments } = &mut buffers;
+        solver.fill_stage_state(start);
+        // Increments Δt L(s_j) of each stage
+        increments.resize_with(self.stages.len(), StageState::default);
+        for (j, coefficients) in self.stages.iter().enumerate() {
+            stage.copy_from(start);
+            for (increment, &a) in increments[..j].iter().zip(*coefficients) {
+                stage.add(a, increment);
+            }
+            solver.set_stage_state(stage);
+            solver.explicit_euler(dt, forcing);
+            let increment = &mut increments[j];
+            solver.fill_stage_state(increment);
+            increment.add(-1.0, stage);
+        }
+        // The state at the end of the step is built over the one at its start
+        for (increment, &b) in increments.iter().zip(self.weights) {
+            start.add(b, increment);
+        }
+        solver.set_stage_state(start);
+        solver.keep_stage_buffers(buffers);
+    }
+
+    fn time_step_factor(&self) -> f64 {
+        self.time_step_factor
+    }
+}
+
+/// θ-method for the surface gradient and the discharge, with implicit vegetation drag
+///
+/// The new surface follows from a tridiagonal system (Casulli, 1990), so gravity waves are stable at
+/// any Courant number; advection stays explicit and limits the time step with the flow speed.
+pub struct SemiImplicit {
+    pub theta: f64, // Weight of the new time level, ½ to 1
+}
+
+impl TimeIntegrator for SemiImplicit {
+    fn integrate(&self, solver: &mut ShallowWaterSolver, dt: f64, forcing: &WaveForcing) {
+        solver.semi_implicit(dt, forcing, self.theta);
+    }
+
+    fn time_step_factor(&self) -> f64 {
+        SEMI_IMPLICIT_STEP_FACTOR
+    }
+
+    fn implicit_friction(&self) -> bool {
+        true
+    }
+}
+
+/// Time integrator selected in the solver settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TimeIntegration {
+    #[default]
+    ForwardBackward,
+    RungeKutta2,
+    RungeKutta4,
+    SemiImplicit,
+}
+
+impl std::fmt::Display for TimeIntegration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeIntegration::ForwardBackward => write!(f, "Forward-backward Euler"),
+            TimeIntegration::RungeKutta2 => write!(f, "RK2"),
+            TimeIntegration::RungeKutta4 => write!(f, "RK4"),
+            TimeIntegration::SemiImplicit => write!(f, "Semi-implicit"),
+        }
+    }
+}
+
+impl TimeIntegration {
+    pub const ALL: [TimeIntegration; 4] = [TimeIntegration::ForwardBackward, TimeIntegration::RungeKutta2, TimeIntegration::RungeKutta4, TimeIntegration::SemiImplicit];
+
+    /// Integrator implementing the selection, a constant so selecting it at every step allocates nothing
+    pub fn integrator(&self) -> &'static dyn TimeIntegrator {
+        match self {
+            TimeIntegration::ForwardBackward => &ForwardBackward,
+            TimeIntegration::RungeKutta2 => &RungeKutta::SECOND_ORDER,
+            TimeIntegration::RungeKutta4 => &RungeKutta::FOURTH_ORDER,
+            TimeIntegration::SemiImplicit => &SemiImplicit { theta: SEMI_IMPLICIT_THETA },
+        }
+    }
+}
+
+/// Prognostic variables combined linearly by the Runge-Kutta stages
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StageState {
+    pub eta: Vec<f64>,          // Surface elevation at cell centers [m]
+    pub layer_u: Vec<Vec<f64>>, // Layer velocities at cell faces [m/s]
+    pub discharged: Vec<f64>,   // Volume through each discharge gauge [m²]
+    pub tank: f64,              // Volume in the overtopping tank [m²]
+}
+
+impl StageState {
+    /// Copy another state, reusing the memory of this one
+    pub fn copy_from(&mut self, other: &StageState) {
+        self.eta.clone_from(&other.eta);
+        self.layer_u.clone_from(&other.layer_u);
+        self.discharged.clone_from(&other.discharged);
+        self.tank = other.tank;
+    }
+
+    /// Add a multiple of another state
+    pub fn add(&mut self, factor: f64, other: &StageState) {
+        let pairs = self.eta.iter_mut().zip(&other.eta).chain(self.layer_u.iter_mut().flatten().zip(other.layer_u.iter().flatten()));
+        for (value, other) in pairs.chain(self.discharged.iter_mut().zip(&other.discharged)) {
+            *value += factor * other;
+        }
+        self.tank += factor * other.tank;
+    }
+}
+
+/// States of the Runge-Kutta stages, kept by the solver so that a step reuses their memory
+#[derive(Debug, Clone, Default)]
+pub struct StageBuffers {
+    start: StageState,           // State at the start of the step, then at its end
+    stage: StageState,           // State the current stage starts from
+    increments: Vec<StageState>, // Increments Δt L(s_j) of the stages so far
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{InitialCondition, RightBoundary, VegetationField};
+    use crate::waves::DispersionMode;
+
+    #[test]
+    fn test_butcher_tableaux_are_consistent() {
+        for scheme in [RungeKutta::SECOND_ORDER, RungeKutta::FOURTH_ORDER] {
+            assert!((scheme.weights.iter().sum::<f64>() - 1.0).abs() < 1e-15);
+            assert_eq!(scheme.stages.len(), scheme.weights.len());
+        }
+    }
+
+    #[test]
+    fn test_stage_state_combination() {
+        let mut state = StageState { eta: vec![1.0, 2.0], layer_u: vec![vec![0.5; 3]], discharged: vec![1.0], tank: 2.0 };
+        let other = state.clone();
+        state.add(-0.5, &other);
+        assert_eq!(state.eta, vec![0.5, 1.0]);
+        assert_eq!(state.layer_u, vec![vec![0.25; 3]]);
+        assert_eq!((state.discharged[0], state.tank), (0.5, 1.0));
+    }
+
+    /// Largest surface elevation error of a standing wave after one period
+    fn standing_wave_error(integration: TimeIntegration) -> f64 {
+        let length = 20.0;
+        let mut solver = ShallowWaterSolver::new(length, 100, 1.0, RightBoundary::Wall).unwrap().with_nonlinear(false).with_time_integration(integration);
+        let mode = |x: f64| 0.01 * (std::f64::consts::PI * x / length).cos();
+        solver.initialize_surface(mode);
+        let period = 2.0 * length / 9.81f64.sqrt();
+        solver.advance(period, &mut WaveForcing::new());
+        solver.grid().x_centers().iter().zip(solver.eta()).map(|(&x, eta)| (eta - mode(x)).abs()).fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn test_integrators_reproduce_standing_wave() {
+        for integration in TimeIntegration::ALL {
+            let error = standing_wave_error(integration);
+            assert!(error < 1e-3, "{}: {:.2e}", integration, error);
+        }
+    }
+
+    #[test]
+    fn test_integrators_conserve_volume() {
+        for integration in TimeIntegration::ALL {
+            let mut solver = ShallowWaterSolver::new(100.0, 400, 1.0, RightBoundary::Wall).unwrap().with_time_integration(integration);
+            solver.set_initial_condition(Some(InitialCondition::dam_break(100.0, 0.5))).unwrap();
+            let volume = solver.volume();
+            solver.advance(10.0, &mut WaveForcing::new());
+            assert!((solver.volume() - volume).abs() < 1e-9, "{}", integration);
+            assert!(solver.eta().iter().all(|eta| eta.is_finite()), "{}", integration);
+        }
+    }
+
+    #[test]
+    fn test_integrators_with_layers_and_vegetation() {
+        for integration in TimeIntegration::ALL {
+            // A periodic hump crossing a vegetation patch in the two-layer non-hydrostatic solver
+            let mut solver = ShallowWaterSolver::new(40.0, 200, 1.0, RightBoundary::Periodic)
+                .unwrap()
+                .with_non_hydrostatic(Some(DispersionMode::TwoLayer))
+                .with_time_integration(integration);
+            solver.set_vegetation(Some(VegetationField::new(25.0, 35.0).unwrap().with_stems(1000.0, 0.01, 0.5))).unwrap();
+            solver.initialize_surface(|x| 0.05 * (-((x - 15.0) / 2.0).powi(2)).exp());
+            let volume = solver.volume();
+            let energy = solver.energy();
+            solver.advance(10.0, &mut WaveForcing::new());
+            assert!((solver.volume() - volume).abs() < 1e-9, "{}", integration);
+            assert!(solver.energy() < energy, "{}", integration);
+        }
+    }
+
+    #[test]
+    fn test_semi_implicit_takes_longer_steps() {
+        let solver = |integration| ShallowWaterSolver::new(100.0, 400, 1.0, RightBoundary::Wall).unwrap().with_time_integration(integration);
+        let explicit = solver(TimeIntegration::ForwardBackward);
+        let implicit = solver(TimeIntegration::SemiImplicit);
+        assert!((implicit.stable_time_step() / explicit.stable_time_step() - SEMI_IMPLICIT_STEP_FACTOR).abs() < 1e-12);
+        assert!(implicit.courant_number(implicit.stable_time_step()) > 1.0);
+    }
+}
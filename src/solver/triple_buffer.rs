@@ -0,0 +1,133 @@
+use std::cell::UnsafeCell;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Bit of the back slot index set when the writer published it and the reader has not taken it yet
+const FRESH: u8 = 0b100;
+
+/// Bits of the back slot index
+const INDEX: u8 = 0b011;
+
+/// Three slots handed between one writer and one reader
+///
+/// At any time the writer owns one slot, the reader another and the third is the back slot waiting
+/// to be taken. Both sides only swap their slot with the back slot, in a single atomic operation,
+/// so neither ever waits for the other however often the writer publishes.
+struct Slots<T> {
+    slots: [UnsafeCell<T>; 3],
+    back: AtomicU8, // Index of the back slot, with the fresh bit
+}
+
+// Each slot is only accessed by the side owning it, ownership passing through the atomic swaps
+unsafe impl<T: Send + Sync> Sync for Slots<T> {}
+
+/// Writing side of a triple buffer, on the thread producing the states
+pub struct BufferWriter<T> {
+    shared: Arc<Slots<T>>,
+    input: u8, // Slot being written
+}
+
+/// Reading side of a triple buffer, on the thread showing the states
+pub struct BufferReader<T> {
+    shared: Arc<Slots<T>>,
+    output: u8, // Slot being read
+}
+
+/// Lock-free handoff of the latest state from one thread to another
+/// The reader sees the last state published, states published in between being skipped
+pub fn triple_buffer<T: Clone + Send + Sync>(initial: T) -> (BufferWriter<T>, BufferReader<T>) {
+    let shared = Arc::new(Slots {
+        slots: [UnsafeCell::new(initial.clone()), UnsafeCell::new(initial.clone()), UnsafeCell::new(initial)],
+        back: AtomicU8::new(1),
+    });
+    (BufferWriter { shared: Arc::clone(&shared), input: 0 }, BufferReader { shared, output: 2 })
+}
+
+impl<T> BufferWriter<T> {
+    /// Slot to fill with the next state, holding the state published two times ago so its memory can be reused
+    pub fn input(&mut self) -> &mut T {
+        // The input slot is owned by the writer until published
+        unsafe { &mut *self.shared.slots[self.input as usize].get() }
+    }
+
+    /// Hand the filled slot to the reader, taking the back slot to fill next
+    pub fn publish(&mut self) {
+        let previous = self.shared.back.swap(self.input | FRESH, Ordering::AcqRel);
+        self.input = previous & INDEX;
+    }
+
+    /// Publish a state
+    pub fn write(&mut self, state: T) {
+        *self.input() = state;
+        self.publish();
+    }
+}
+
+impl<T> BufferReader<T> {
+    /// Take the last state published if newer than the one read, returning whether it was
+    pub fn update(&mut self) -> bool {
+        if self.shared.back.load(Ordering::Relaxed) & FRESH == 0 {
+            return false;
+        }
+        let previous = self.shared.back.swap(self.output, Ordering::AcqRel);
+        self.output = previous & INDEX;
+        true
+    }
+
+    /// Last state published
+    pub fn read(&mut self) -> &T {
+        self.update();
+        self.current()
+    }
+
+    /// State taken at the last read, without checking for a newer one
+    pub fn current(&self) -> &T {
+        // The output slot is owned by the reader until it swaps it back
+        unsafe { &*self.shared.slots[self.output as usize].get() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reader_sees_the_last_state() {
+        let (mut writer, mut reader) = triple_buffer(0);
+        assert_eq!(*reader.read(), 0);
+        assert!(!reader.update());
+        writer.write(1);
+        writer.write(2);
+        assert_eq!(*reader.current(), 0);
+        assert_eq!(*reader.read(), 2);
+        assert!(!reader.update());
+
+        // The writer fills a slot in place before publishing it
+        writer.input().clone_from(&3);
+        assert_eq!(*reader.read(), 2);
+        writer.publish();
+        assert_eq!(*reader.read(), 3);
+    }
+
+    #[test]
+    fn test_states_cross_threads_whole() {
+        let (mut writer, mut reader) = triple_buffer(vec![0usize; 64]);
+        let producer = std::thread::spawn(move || {
+            for value in 1..=20_000 {
+                let state = writer.input();
+                state.iter_mut().for_each(|element| *element = value);
+                writer.publish();
+            }
+        });
+        let mut last = 0;
+        while !producer.is_finished() || reader.update() {
+            let state = reader.read();
+            // A state is never seen half written, and never older than one already seen
+            assert!(state.iter().all(|element| *element == state[0]));
+            assert!(state[0] >= last);
+            last = state[0];
+        }
+        producer.join().unwrap();
+        assert_eq!(reader.read()[0], 20_000);
+    }
+}
@@ -0,0 +1,274 @@
+use crate::solver::dissipation::Dissipation;
+use crate::solver::grid::Grid1D;
+use crate::waves::WaveForcing;
+
+/// Gravitational acceleration of the default diagnostics [m/s²]
+const GRAVITY: f64 = 9.81;
+
+/// Time step covering a remaining duration in equal stable steps, rather than leaving a tiny final step
+/// None when the stable time step gives no finite positive step, as on a solution that blew up
+pub fn sub_step(remaining: f64, stable_time_step: f64) -> Option<f64> {
+    if stable_time_step.is_nan() || stable_time_step <= 0.0 {
+        return None;
+    }
+    let dt = remaining / (remaining / stable_time_step).ceil().max(1.0);
+    (dt.is_finite() && dt > 0.0).then_some(dt)
+}
+
+/// Numerical engine propagating waves along the channel
+///
+/// The wave channel, its gauges and the analyses only use this interface, so engines other than the
+/// built-in shallow water and non-hydrostatic solver can be run in their place. An engine keeps the
+/// surface elevation at the cell centers and the depth-averaged velocity at the cell faces of its grid;
+/// the diagnostics have hydrostatic defaults that engines with a vertical structure override.
+pub trait WaveSolver {
+    /// Name of the engine and its options, as recorded in the run metadata
+    fn engine_name(&self) -> String;
+
+    /// Return to the initial state at time zero
+    fn reset(&mut self);
+
+    /// Advance the solution by a single time step [s]
+    fn step(&mut self, dt: f64, forcing: &mut WaveForcing);
+
+    /// Largest stable time step in the current flow [s]
+    fn stable_time_step(&self) -> f64;
+
+    /// Current simulation time [s]
+    fn time(&self) -> f64;
+
+    /// Computational grid
+    fn grid(&self) -> &Grid1D;
+
+    /// Still water depth at cell centers [m]
+    fn depth(&self) -> &[f64];
+
+    /// Surface elevation at cell centers [m]
+    fn eta(&self) -> &[f64];
+
+    /// Depth-averaged velocity at cell faces [m/s]
+    fn u(&self) -> &[f64];
+
+    /// Advance the solution by a duration using stable sub-steps, returning the number of sub-steps
+    fn advance(&mut self, duration: f64, forcing: &mut WaveForcing) -> usize {
+        let end = self.time() + duration;
+        let mut steps = 0;
+        while end - self.time() > 1e-12 * duration.max(1.0) {
+            let remaining = end - self.time();
+            let Some(dt) = sub_step(remaining, self.stable_time_step()) else {
+                break;
+            };
+            self.step(dt, forcing);
+            steps += 1;
+        }
+        steps
+    }
+
+    /// Courant number of a time step, relative to the stable time step
+    fn courant_number(&self, dt: f64) -> f64 {
+        dt / self.stable_time_step()
+    }
+
+    /// Surface elevation interpolated at position x [m]
+    fn surface_elevation_at(&self, x: f64) -> f64 {
+        self.grid().interpolate_centers(self.eta(), x)
+    }
+
+    /// Pressure divided by the water density at position x and height z above the still water level [m²/s²]
+    /// Hydrostatic below the surface; points below the bed take the bed pressure and points above the surface none
+    fn pressure_at(&self, x: f64, z: f64) -> f64 {
+        let eta = self.surface_elevation_at(x);
+        let bed = -self.grid().interpolate_centers(self.depth(), x);
+        if z >= eta {
+            return 0.0;
+        }
+        GRAVITY * (eta - z.max(bed))
+    }
+
+    /// Pressure force of the water column at position x divided by the water density [m³/s²]
+    fn column_force(&self, x: f64) -> f64 {
+        let eta = self.surface_elevation_at(x);
+        let bed = -self.grid().interpolate_centers(self.depth(), x);
+        let points = 8;
+        let dz = (eta - bed).max(0.0) / points as f64;
+        (0..points).map(|j| 0.5 * (self.pressure_at(x, bed + j as f64 * dz) + self.pressure_at(x, bed + (j + 1) as f64 * dz)) * dz).sum()
+    }
+
+    /// Position of the first cell drier than a water depth, interpolated from the last wet cell [m]
+    fn shoreline(&self, wet_depth: f64) -> Option<f64> {
+        let centers = self.grid().x_centers();
+        let water = |i: usize| self.depth()[i] + self.eta()[i];
+        let dry = (0..self.grid().nx()).find(|&i| water(i) < wet_depth)?;
+        if dry == 0 {
+            return Some(centers[0]);
+        }
+        let (wet, dry_water) = (water(dry - 1), water(dry));
+        Some(centers[dry - 1] + (wet - wet_depth) / (wet - dry_water) * (centers[dry] - centers[dry - 1]))
+    }
+
+    /// Excess water volume per unit width ∫η dx [m²]
+    fn volume(&self) -> f64 {
+        self.eta().iter().zip(self.grid().widths()).map(|(eta, dx)| eta * dx).sum()
+    }
+
+    /// Wave energy per unit width ∫(½gη² + ½du²) dx [m³/s²]
+    fn energy(&self) -> f64 {
+        let (eta, u, depth) = (self.eta(), self.u(), self.depth());
+        (0..self.grid().nx())
+            .map(|i| {
+                let u_center = 0.5 * (u[i] + u[i + 1]);
+                (0.5 * GRAVITY * eta[i] * eta[i] + 0.5 * depth[i].max(0.0) * u_center * u_center) * self.grid().widths()[i]
+            })
+            .sum()
+    }
+
+    /// Energy removed by each dissipation mechanism since time zero, None if the engine does not account for it [m³/s²]
+    fn dissipation(&self) -> Option<Dissipation> {
+        None
+    }
+
+    /// Rise of the mean water level since time zero besides the forcing water level, None if the engine does not track it [m]
+    fn mean_level_drift(&self) -> Option<f64> {
+        None
+    }
+
+    /// Position of the overtopping tank, None if the engine has none [m]
+    fn overtopping_tank(&self) -> Option<f64> {
+        None
+    }
+
+    /// Volume collected in the overtopping tank per unit width [m²]
+    fn tank_volume(&self) -> f64 {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{RightBoundary, ShallowWaterSolver};
+    use crate::waves::DispersionMode;
+
+    /// Linear long waves over a flat bed, written against the trait only as a third-party engine would be
+    struct LinearLongWaves {
+        grid: Grid1D,
+        depth: Vec<f64>,
+        eta: Vec<f64>,
+        u: Vec<f64>,
+        time: f64,
+    }
+
+    impl LinearLongWaves {
+        fn new(length: f64, nx: usize, depth: f64, surface: impl Fn(f64) -> f64) -> Self {
+            let grid = Grid1D::new(length, nx).unwrap();
+            let eta = grid.x_centers().iter().map(|&x| surface(x)).collect();
+            LinearLongWaves { grid, depth: vec![depth; nx], eta, u: vec![0.0; nx + 1], time: 0.0 }
+        }
+    }
+
+    impl WaveSolver for LinearLongWaves {
+        fn engine_name(&self) -> String {
+            "Linear long waves".to_string()
+        }
+
+        fn reset(&mut self) {
+            self.eta.iter_mut().for_each(|eta| *eta = 0.0);
+            self.u.iter_mut().for_each(|u| *u = 0.0);
+            self.time = 0.0;
+        }
+
+        fn step(&mut self, dt: f64, _forcing: &mut WaveForcing) {
+            for i in 0..self.grid.nx() {
+                self.eta[i] -= dt * self.depth[i] * (self.u[i + 1] - self.u[i]) / self.grid.widths()[i];
+            }
+            for face in 1..self.grid.nx() {
+                self.u[face] -= dt * GRAVITY * (self.eta[face] - self.eta[face - 1]) / self.grid.face_spacing(face, false);
+            }
+            self.time += dt;
+        }
+
+        fn stable_time_step(&self) -> f64 {
+            0.9 * self.grid.min_dx() / (GRAVITY * self.depth[0]).sqrt()
+        }
+
+        fn time(&self) -> f64 {
+            self.time
+        }
+
+        fn grid(&self) -> &Grid1D {
+            &self.grid
+        }
+
+        fn depth(&self) -> &[f64] {
+            &self.depth
+        }
+
+        fn eta(&self) -> &[f64] {
+            &self.eta
+        }
+
+        fn u(&self) -> &[f64] {
+            &self.u
+        }
+    }
+
+    fn hump(x: f64) -> f64 {
+        0.01 * (-((x - 20.0) / 2.0).powi(2)).exp()
+    }
+
+    #[test]
+    fn test_engines_agree_through_the_trait() {
+        let solver = |mode| {
+            let mut solver = ShallowWaterSolver::new(40.0, 200, 1.0, RightBoundary::Wall).unwrap().with_nonlinear(false).with_non_hydrostatic(mode);
+            solver.initialize_surface(hump);
+            solver
+        };
+        let mut engines: Vec<Box<dyn WaveSolver>> = vec![
+            Box::new(LinearLongWaves::new(40.0, 200, 1.0, hump)),
+            Box::new(solver(None)),
+            Box::new(solver(Some(DispersionMode::OneLayer))),
+        ];
+        // The hump splits in two halves travelling at about √(gd)
+        let front = 20.0 + 3.0 * GRAVITY.sqrt();
+        for engine in &mut engines {
+            let (volume, energy) = (engine.volume(), engine.energy());
+            assert!(engine.advance(3.0, &mut WaveForcing::new()) > 1);
+            assert!((engine.time() - 3.0).abs() < 1e-9, "{}", engine.engine_name());
+            assert!((engine.volume() - volume).abs() < 1e-10, "{}", engine.engine_name());
+            assert!(engine.energy() < 1.01 * energy, "{}", engine.engine_name());
+            assert!(engine.surface_elevation_at(front) > 0.003, "{}: {:.4}", engine.engine_name(), engine.surface_elevation_at(front));
+            assert!(engine.courant_number(engine.stable_time_step()) <= 1.0 + 1e-12, "{}", engine.engine_name());
+        }
+        let names: Vec<String> = engines.iter().map(|engine| engine.engine_name()).collect();
+        assert!(names[1] != names[2], "{:?}", names);
+    }
+
+    #[test]
+    fn test_sub_step_splits_the_remainder() {
+        assert_eq!(sub_step(1.0, 0.3), Some(0.25));
+        assert_eq!(sub_step(0.2, 0.3), Some(0.2));
+        // Still water has no stable step limit
+        assert_eq!(sub_step(0.2, f64::INFINITY), Some(0.2));
+        assert_eq!(sub_step(1.0, 0.0), None);
+        assert_eq!(sub_step(1.0, f64::NAN), None);
+    }
+
+    #[test]
+    fn test_default_diagnostics() {
+        let mut engine = LinearLongWaves::new(10.0, 10, 1.0, |_| 0.0);
+        engine.eta[..5].iter_mut().for_each(|eta| *eta = 1.0);
+        // Hydrostatic pressure, with the bed pressure below the bed and none above the surface
+        assert!((engine.pressure_at(2.5, 0.5) - 0.5 * GRAVITY).abs() < 1e-12);
+        assert!((engine.pressure_at(2.5, -3.0) - 2.0 * GRAVITY).abs() < 1e-12);
+        assert_eq!(engine.pressure_at(7.5, 0.5), 0.0);
+        assert!((engine.column_force(2.5) - 0.5 * GRAVITY * 4.0).abs() < 1e-9);
+        assert!((engine.column_force(7.5) - 0.5 * GRAVITY).abs() < 1e-9);
+        assert!((engine.volume() - 5.0).abs() < 1e-12);
+        assert!(engine.shoreline(0.01).is_none());
+        assert!(engine.overtopping_tank().is_none());
+
+        engine.advance(1.0, &mut WaveForcing::new());
+        engine.reset();
+        assert_eq!((engine.time(), engine.volume()), (0.0, 0.0));
+    }
+}
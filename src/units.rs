@@ -0,0 +1,272 @@
+//! Physical quantities carrying their dimension in the type
+//!
+//! Each quantity wraps its value in SI units. Quantities of the same kind add and subtract, scale by
+//! plain numbers and divide into plain ratios; products and quotients of different kinds give the
+//! quantity of the resulting dimension, e.g. a length over a time is a velocity. Passing a period
+//! where a depth is expected is then a compile error instead of a silently wrong wave.
+
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+/// Gravitational acceleration [m/s²]
+const GRAVITY: f64 = 9.81;
+
+/// Meters per foot
+const METERS_PER_FOOT: f64 = 0.3048;
+
+/// Quantity of one dimension stored in its SI unit, with the arithmetic that keeps the dimension
+macro_rules! quantity {
+    ($(#[$doc:meta])* $name:ident, $constructor:ident, $getter:ident, $unit:literal) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(f64);
+
+        impl $name {
+            pub const ZERO: $name = $name(0.0);
+
+            #[doc = concat!("Quantity from its value in ", $unit)]
+            pub const fn $constructor(value: f64) -> Self {
+                $name(value)
+            }
+
+            #[doc = concat!("Value in ", $unit)]
+            pub const fn $getter(self) -> f64 {
+                self.0
+            }
+
+            /// Magnitude of the quantity
+            pub fn abs(self) -> Self {
+                $name(self.0.abs())
+            }
+
+            /// Smaller of two quantities
+            pub fn min(self, other: Self) -> Self {
+                $name(self.0.min(other.0))
+            }
+
+            /// Larger of two quantities
+            pub fn max(self, other: Self) -> Self {
+                $name(self.0.max(other.0))
+            }
+
+            /// Whether the value is neither infinite nor NaN
+            pub fn is_finite(self) -> bool {
+                self.0.is_finite()
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                std::fmt::Display::fmt(&self.0, f)?;
+                write!(f, " {}", $unit)
+            }
+        }
+
+        impl Add for $name {
+            type Output = $name;
+            fn add(self, other: $name) -> $name {
+                $name(self.0 + other.0)
+            }
+        }
+
+        impl Sub for $name {
+            type Output = $name;
+            fn sub(self, other: $name) -> $name {
+                $name(self.0 - other.0)
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, other: $name) {
+                self.0 += other.0;
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, other: $name) {
+                self.0 -= other.0;
+            }
+        }
+
+        impl Neg for $name {
+            type Output = $name;
+            fn neg(self) -> $name {
+                $name(-self.0)
+            }
+        }
+
+        impl Mul<f64> for $name {
+            type Output = $name;
+            fn mul(self, factor: f64) -> $name {
+                $name(self.0 * factor)
+            }
+        }
+
+        impl Mul<$name> for f64 {
+            type Output = $name;
+            fn mul(self, quantity: $name) -> $name {
+                $name(self * quantity.0)
+            }
+        }
+
+        impl Div<f64> for $name {
+            type Output = $name;
+            fn div(self, divisor: f64) -> $name {
+                $name(self.0 / divisor)
+            }
+        }
+
+        /// Dimensionless ratio of two quantities of the same kind
+        impl Div for $name {
+            type Output = f64;
+            fn div(self, other: $name) -> f64 {
+                self.0 / other.0
+            }
+        }
+
+        impl std::iter::Sum for $name {
+            fn sum<I: Iterator<Item = $name>>(iter: I) -> $name {
+                $name(iter.map(|quantity| quantity.0).sum())
+            }
+        }
+    };
+}
+
+/// Product or quotient of two quantities giving a third, in both orders for products
+macro_rules! relation {
+    ($left:ident * $right:ident = $product:ident) => {
+        impl Mul<$right> for $left {
+            type Output = $product;
+            fn mul(self, other: $right) -> $product {
+                $product(self.0 * other.0)
+            }
+        }
+
+        impl Mul<$left> for $right {
+            type Output = $product;
+            fn mul(self, other: $left) -> $product {
+                $product(self.0 * other.0)
+            }
+        }
+
+        impl Div<$right> for $product {
+            type Output = $left;
+            fn div(self, other: $right) -> $left {
+                $left(self.0 / other.0)
+            }
+        }
+
+        impl Div<$left> for $product {
+            type Output = $right;
+            fn div(self, other: $left) -> $right {
+                $right(self.0 / other.0)
+            }
+        }
+    };
+}
+
+quantity!(
+    /// Length, height, depth or position along the channel [m]
+    Length,
+    meters,
+    in_meters,
+    "m"
+);
+
+quantity!(
+    /// Time or duration, e.g. a wave period [s]
+    Time,
+    seconds,
+    in_seconds,
+    "s"
+);
+
+quantity!(
+    /// Velocity, e.g. a flow velocity or a wave celerity [m/s]
+    Velocity,
+    meters_per_second,
+    in_meters_per_second,
+    "m/s"
+);
+
+quantity!(
+    /// Discharge per unit width of the channel [m²/s]
+    Discharge,
+    square_meters_per_second,
+    in_square_meters_per_second,
+    "m²/s"
+);
+
+relation!(Velocity * Time = Length);
+relation!(Velocity * Length = Discharge);
+
+impl Length {
+    /// Length from its value in feet
+    pub fn feet(feet: f64) -> Self {
+        Length(feet * METERS_PER_FOOT)
+    }
+
+    /// Value in feet
+    pub fn in_feet(self) -> f64 {
+        self.0 / METERS_PER_FOOT
+    }
+}
+
+impl Velocity {
+    /// Speed √(gd) of long waves in water of a depth
+    pub fn long_wave(depth: Length) -> Self {
+        Velocity((GRAVITY * depth.0).sqrt())
+    }
+
+    /// Value in feet per second
+    pub fn in_feet_per_second(self) -> f64 {
+        self.0 / METERS_PER_FOOT
+    }
+}
+
+impl Discharge {
+    /// Value in liters per second per meter of width, the usual unit of overtopping discharges
+    pub fn in_liters_per_second_per_meter(self) -> f64 {
+        1000.0 * self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic_keeps_dimensions() {
+        let depth = Length::meters(2.0);
+        let period = Time::seconds(4.0);
+        let celerity: Velocity = 3.0 * depth / period;
+        assert_eq!(celerity, Velocity::meters_per_second(1.5));
+        assert_eq!(celerity * period, Length::meters(6.0));
+        assert_eq!(depth / celerity, Time::seconds(2.0 / 1.5));
+        let discharge: Discharge = celerity * depth;
+        assert_eq!(discharge / depth, celerity);
+        assert_eq!(discharge / celerity, depth);
+        assert_eq!(Length::meters(3.0) / depth, 1.5);
+        assert_eq!(depth - Length::meters(0.5) + Length::ZERO, Length::meters(1.5));
+        assert_eq!([depth, depth].into_iter().sum::<Length>(), Length::meters(4.0));
+        assert!(-depth < Length::ZERO && depth.max(-depth) == depth.abs());
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert!((Length::feet(1.0).in_meters() - 0.3048).abs() < 1e-15);
+        assert!((Length::meters(0.3048).in_feet() - 1.0).abs() < 1e-12);
+        assert!((Velocity::meters_per_second(0.3048).in_feet_per_second() - 1.0).abs() < 1e-12);
+        assert!((Velocity::long_wave(Length::meters(1.0)).in_meters_per_second() - 9.81f64.sqrt()).abs() < 1e-15);
+        assert!((Discharge::square_meters_per_second(0.002).in_liters_per_second_per_meter() - 2.0).abs() < 1e-12);
+        assert_eq!(format!("{:.2}", Length::meters(1.234)), "1.23 m");
+        assert_eq!(Time::seconds(2.0).to_string(), "2 s");
+    }
+
+    #[test]
+    fn test_serialized_as_plain_numbers() {
+        assert_eq!(serde_json::to_string(&Velocity::meters_per_second(1.5)).unwrap(), "1.5");
+        assert_eq!(serde_json::from_str::<Length>("2.0").unwrap(), Length::meters(2.0));
+    }
+}
@@ -0,0 +1,158 @@
+/// Active wave absorption at the wavemaker (AWACS-style)
+///
+/// The surface elevation measured at the paddle is compared with the target elevation;
+/// the difference is the wave travelling back towards the paddle, which is absorbed by
+/// adding the velocity that this wave would carry: u = -(c/h) η_R.
+pub struct ActiveAbsorber {
+    /// Water depth at the wavemaker (h) [m]
+    depth: f64,
+    /// Celerity used in the velocity correction [m/s]
+    celerity: f64,
+    /// Flag to enable/disable absorption
+    enabled: bool,
+    /// Last estimated reflected elevation (η_R) [m]
+    reflected_elevation: f64,
+    /// Sum of squared reflected elevations for RMS statistics [m²]
+    sum_squared: f64,
+    /// Number of recorded samples
+    samples: usize,
+}
+
+impl ActiveAbsorber {
+    /// Create new absorber using the shallow water celerity √(gh)
+    pub fn new(depth: f64) -> Result<Self, String> {
+        if depth <= 0.0 {
+            return Err("Water depth must be positive".to_string());
+        }
+
+        Ok(Self {
+            depth,
+            celerity: (9.81 * depth).sqrt(),
+            enabled: true,
+            reflected_elevation: 0.0,
+            sum_squared: 0.0,
+            samples: 0,
+        })
+    }
+
+    /// Tune the correction to the celerity of the dominant wave (e.g. from the dispersion relation)
+    pub fn with_celerity(mut self, celerity: f64) -> Self {
+        self.celerity = celerity;
+        self
+    }
+
+    /// Enable or disable absorption
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Check if absorption is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Get celerity used in the velocity correction [m/s]
+    pub fn celerity(&self) -> f64 {
+        self.celerity
+    }
+
+    /// Velocity correction absorbing the difference between measured and target elevation [m/s]
+    pub fn correction_velocity(&mut self, measured_elevation: f64, target_elevation: f64) -> f64 {
+        if !self.enabled {
+            return 0.0;
+        }
+
+        let reflected = measured_elevation - target_elevation;
+        self.reflected_elevation = reflected;
+        self.sum_squared += reflected * reflected;
+        self.samples += 1;
+
+        -(self.celerity / self.depth) * reflected
+    }
+
+    /// Target velocity corrected for waves travelling back towards the wavemaker [m/s]
+    pub fn corrected_velocity(&mut self, target_velocity: f64, measured_elevation: f64, target_elevation: f64) -> f64 {
+        target_velocity + self.correction_velocity(measured_elevation, target_elevation)
+    }
+
+    /// Last estimated reflected elevation [m]
+    pub fn reflected_elevation(&self) -> f64 {
+        self.reflected_elevation
+    }
+
+    /// Root-mean-square of the reflected elevation since the last reset [m]
+    pub fn reflected_rms(&self) -> f64 {
+        if self.samples == 0 {
+            return 0.0;
+        }
+        (self.sum_squared / self.samples as f64).sqrt()
+    }
+
+    /// Reset recorded statistics
+    pub fn reset(&mut self) {
+        self.reflected_elevation = 0.0;
+        self.sum_squared = 0.0;
+        self.samples = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_absorber_creation() {
+        let absorber = ActiveAbsorber::new(1.0).unwrap();
+        assert!(absorber.is_enabled());
+        assert!((absorber.celerity() - 9.81_f64.sqrt()).abs() < 1e-12);
+        assert_eq!(absorber.reflected_rms(), 0.0);
+
+        assert!(ActiveAbsorber::new(0.0).is_err());
+    }
+
+    #[test]
+    fn test_no_correction_without_reflection() {
+        let mut absorber = ActiveAbsorber::new(2.0).unwrap();
+        let velocity = absorber.corrected_velocity(0.3, 0.1, 0.1);
+        assert_eq!(velocity, 0.3);
+        assert_eq!(absorber.reflected_elevation(), 0.0);
+    }
+
+    #[test]
+    fn test_matches_leftgoing_wave_velocity() {
+        // A shallow water wave travelling towards -x carries u = -√(g/h) η
+        let depth = 0.5;
+        let mut absorber = ActiveAbsorber::new(depth).unwrap();
+        let eta_reflected = 0.05;
+        let expected = -(9.81_f64 / depth).sqrt() * eta_reflected;
+
+        let correction = absorber.correction_velocity(eta_reflected, 0.0);
+        assert!((correction - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_tuned_celerity() {
+        let mut absorber = ActiveAbsorber::new(1.0).unwrap().with_celerity(2.0);
+        assert!((absorber.correction_velocity(0.1, 0.0) + 0.2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_disabled_absorber() {
+        let mut absorber = ActiveAbsorber::new(1.0).unwrap();
+        absorber.set_enabled(false);
+        assert_eq!(absorber.correction_velocity(0.2, 0.0), 0.0);
+        assert_eq!(absorber.reflected_rms(), 0.0);
+    }
+
+    #[test]
+    fn test_reflected_statistics() {
+        let mut absorber = ActiveAbsorber::new(1.0).unwrap();
+        absorber.correction_velocity(0.1, 0.0);
+        absorber.correction_velocity(-0.1, 0.0);
+        assert!((absorber.reflected_rms() - 0.1).abs() < 1e-12);
+        assert_eq!(absorber.reflected_elevation(), -0.1);
+
+        absorber.reset();
+        assert_eq!(absorber.reflected_rms(), 0.0);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::waves::{WaveParameters, VelocityCalculator};
+use crate::waves::{ActiveAbsorber, WaveParameters, VelocityCalculator};
 
 /// Boundary condition applicator for wave generation
 pub struct BoundaryApplicator {
@@ -8,6 +8,8 @@ pub struct BoundaryApplicator {
     current_time: f64,
     /// Wave generation position (typically x = 0)
     generation_position: f64,
+    /// Phase offset of the generated signal [rad]
+    phase_offset: f64,
     /// Flag to enable/disable wave generation
     enabled: bool,
 }
@@ -19,6 +21,7 @@ impl BoundaryApplicator {
             velocity_calc: VelocityCalculator::new(params),
             current_time: 0.0,
             generation_position: 0.0,
+            phase_offset: 0.0,
             enabled: true,
         }
     }
@@ -33,6 +36,21 @@ impl BoundaryApplicator {
         self.generation_position = x;
     }
     
+    /// Get wave generation position
+    pub fn generation_position(&self) -> f64 {
+        self.generation_position
+    }
+    
+    /// Set phase offset of the generated signal [rad]
+    pub fn set_phase_offset(&mut self, phase: f64) {
+        self.phase_offset = phase;
+    }
+    
+    /// Get phase offset of the generated signal [rad]
+    pub fn phase_offset(&self) -> f64 {
+        self.phase_offset
+    }
+    
     /// Enable or disable wave generation
     pub fn set_enabled(&mut self, enabled: bool) {
         self.enabled = enabled;
@@ -59,7 +77,7 @@ impl BoundaryApplicator {
             return 0.0;
         }
         
-        self.velocity_calc.horizontal_velocity(self.generation_position, self.current_time)
+        self.velocity_calc.horizontal_velocity(self.generation_position, self.phase_time())
     }
     
     /// Compute boundary surface elevation at current time
@@ -68,7 +86,12 @@ impl BoundaryApplicator {
             return 0.0;
         }
         
-        self.velocity_calc.surface_elevation(self.generation_position, self.current_time)
+        self.velocity_calc.surface_elevation(self.generation_position, self.phase_time())
+    }
+    
+    /// Time shifted by the phase offset, so that kx - ωt gains +φ
+    fn phase_time(&self) -> f64 {
+        self.current_time - self.phase_offset / self.parameters().omega
     }
     
     /// Apply boundary conditions to a 1D grid
@@ -120,6 +143,20 @@ impl BoundaryApplicator {
         surface_elevations[0] = self.boundary_surface_elevation() * ramp_factor;
     }
     
+    /// Apply boundary velocity corrected by active absorption
+    /// The elevation at the first grid point is left free and used as the measurement
+    pub fn apply_absorbing_boundary_conditions(&self, velocities: &mut [f64], surface_elevations: &[f64], absorber: &mut ActiveAbsorber) {
+        if !self.enabled || velocities.is_empty() || surface_elevations.is_empty() {
+            return;
+        }
+        
+        velocities[0] = absorber.corrected_velocity(
+            self.boundary_velocity(),
+            surface_elevations[0],
+            self.boundary_surface_elevation(),
+        );
+    }
+    
     /// Compute boundary flux (velocity × depth) for mass conservation
     pub fn boundary_flux(&self) -> f64 {
         if !self.enabled {
@@ -214,11 +251,12 @@ impl BoundaryStatus {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::units::{Length, Time};
     use crate::waves::dispersion::DispersionSolver;
 
     fn create_test_boundary_applicator() -> BoundaryApplicator {
         let solver = DispersionSolver::new();
-        let params = solver.solve_wave_parameters(0.5, 4.0, 2.0).unwrap();
+        let params = solver.solve_wave_parameters(Length::meters(0.5), Time::seconds(4.0), Length::meters(2.0)).unwrap();
         BoundaryApplicator::new(params)
     }
     
@@ -227,9 +265,25 @@ mod tests {
         let applicator = create_test_boundary_applicator();
         assert_eq!(applicator.current_time, 0.0);
         assert_eq!(applicator.generation_position, 0.0);
+        assert_eq!(applicator.phase_offset, 0.0);
         assert!(applicator.enabled);
     }
     
+    #[test]
+    fn test_phase_offset() {
+        let mut applicator = create_test_boundary_applicator();
+        let eta0 = applicator.boundary_surface_elevation();
+        
+        // A half-period phase shift flips the signal
+        applicator.set_phase_offset(std::f64::consts::PI);
+        assert_eq!(applicator.phase_offset(), std::f64::consts::PI);
+        assert!((applicator.boundary_surface_elevation() + eta0).abs() < 1e-10);
+        
+        // A full-period phase shift restores it
+        applicator.set_phase_offset(2.0 * std::f64::consts::PI);
+        assert!((applicator.boundary_surface_elevation() - eta0).abs() < 1e-10);
+    }
+    
     #[test]
     fn test_time_advancement() {
         let mut applicator = create_test_boundary_applicator();
@@ -357,6 +411,24 @@ mod tests {
         assert_eq!(elevations[0], applicator.boundary_surface_elevation());
     }
     
+    #[test]
+    fn test_absorbing_boundary_conditions() {
+        let applicator = create_test_boundary_applicator();
+        let mut absorber = ActiveAbsorber::new(applicator.parameters().d).unwrap();
+        let mut velocities = vec![0.0; 10];
+        
+        // Measured elevation equals the target: velocity is the target velocity
+        let elevations = vec![applicator.boundary_surface_elevation(); 10];
+        applicator.apply_absorbing_boundary_conditions(&mut velocities, &elevations, &mut absorber);
+        assert!((velocities[0] - applicator.boundary_velocity()).abs() < 1e-12);
+        
+        // Excess elevation from a reflected wave is absorbed by moving backwards
+        let elevations = vec![applicator.boundary_surface_elevation() + 0.1; 10];
+        applicator.apply_absorbing_boundary_conditions(&mut velocities, &elevations, &mut absorber);
+        assert!(velocities[0] < applicator.boundary_velocity());
+        assert!((absorber.reflected_elevation() - 0.1).abs() < 1e-12);
+    }
+    
     #[test]
     fn test_boundary_flux() {
         let applicator = create_test_boundary_applicator();
@@ -0,0 +1,237 @@
+use crate::waves::WATER_DENSITY;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Trimmed fields of a row of a comma, semicolon or tab separated table
+pub(crate) fn table_fields(line: &str) -> impl Iterator<Item = &str> {
+    line.split([',', ';', '\t']).map(str::trim)
+}
+
+/// Hours in an average year, leap years included
+pub const HOURS_PER_YEAR: f64 = 8766.0;
+
+/// Ratio of the energy period to the peak period, Te/Tp, of a JONSWAP spectrum with γ = 3.3
+pub const ENERGY_PERIOD_RATIO: f64 = 0.9;
+
+/// Sea state of a wave climate table with its yearly duration
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SeaState {
+    pub significant_height: f64, // Significant wave height (Hs) [m]
+    pub energy_period: f64,      // Energy period (Te) [s]
+    pub hours: f64,              // Time per year with this sea state [h]
+}
+
+impl SeaState {
+    /// Create new sea state, checking that it holds waves for a non-negative time
+    pub fn new(significant_height: f64, energy_period: f64, hours: f64) -> Result<Self, String> {
+        if significant_height <= 0.0 || energy_period <= 0.0 {
+            return Err("Significant wave height and energy period must be positive".to_string());
+        }
+        if hours < 0.0 {
+            return Err("Hours per year cannot be negative".to_string());
+        }
+        Ok(Self { significant_height, energy_period, hours })
+    }
+
+    /// Height of the regular waves of the same energy, H = Hs/√2 [m]
+    pub fn equivalent_wave_height(&self) -> f64 {
+        self.significant_height / std::f64::consts::SQRT_2
+    }
+}
+
+/// Time per year covered by a wave climate table [h]
+pub fn climate_hours(climate: &[SeaState]) -> f64 {
+    climate.iter().map(|state| state.hours).sum()
+}
+
+/// Cell of a scatter diagram with its probability of occurrence
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScatterCell {
+    pub row: usize,              // Index of the significant wave height class
+    pub column: usize,           // Index of the peak period class
+    pub significant_height: f64, // Centre of the significant wave height class (Hs) [m]
+    pub peak_period: f64,        // Centre of the peak period class (Tp) [s]
+    pub probability: f64,        // Share of the time in this sea state
+}
+
+impl ScatterCell {
+    /// Sea state of the cell with its hours in an average year, Te = 0.9 Tp
+    pub fn sea_state(&self) -> SeaState {
+        SeaState {
+            significant_height: self.significant_height,
+            energy_period: ENERGY_PERIOD_RATIO * self.peak_period,
+            hours: self.probability * HOURS_PER_YEAR,
+        }
+    }
+}
+
+/// Joint occurrence of significant wave heights and peak periods at a site
+///
+/// The table comes from a wave buoy or a hindcast, with the significant wave height classes in
+/// rows and the peak period classes in columns. Occurrences may be counts, hours or percentages:
+/// they are normalized to probabilities, so outputs computed for each sea state can be weighted
+/// into long-term averages.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScatterDiagram {
+    heights: Vec<f64>,         // Centres of the significant wave height classes [m]
+    periods: Vec<f64>,         // Centres of the peak period classes [s]
+    occurrence: Vec<Vec<f64>>, // Occurrence of each height (row) and period (column)
+}
+
+impl ScatterDiagram {
+    /// Create new scatter diagram, checking the classes and the occurrences
+    pub fn new(heights: Vec<f64>, periods: Vec<f64>, occurrence: Vec<Vec<f64>>) -> Result<Self, String> {
+        if heights.is_empty() || periods.is_empty() {
+            return Err("Scatter diagram needs at least one height and one period class".to_string());
+        }
+        if heights.iter().chain(&periods).any(|&value| !(value.is_finite() && value > 0.0)) {
+            return Err("Height and period classes must be positive".to_string());
+        }
+        if occurrence.len() != heights.len() || occurrence.iter().any(|row| row.len() != periods.len()) {
+            return Err(format!("Occurrence table must have {} rows of {} values", heights.len(), periods.len()));
+        }
+        if occurrence.iter().flatten().any(|&value| !(value.is_finite() && value >= 0.0)) {
+            return Err("Occurrences cannot be negative".to_string());
+        }
+        let diagram = Self { heights, periods, occurrence };
+        if diagram.total() <= 0.0 {
+            return Err("Scatter diagram has no occurrence".to_string());
+        }
+        Ok(diagram)
+    }
+
+    /// Read a scatter diagram file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        Self::parse(&content)
+    }
+
+    /// Scatter diagram from the content of a CSV file
+    /// The first row holds a label and the peak periods, every other row a significant wave height and its occurrences
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut periods = None;
+        let mut heights = Vec::new();
+        let mut occurrence = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = table_fields(line).collect();
+            let values: Result<Vec<f64>, _> = fields[1..].iter().filter(|field| !field.is_empty()).map(|field| field.parse::<f64>()).collect();
+            let values = values.map_err(|e| format!("Invalid number on line {}: {}", number + 1, e))?;
+            if periods.is_none() {
+                // Header with the label of the height column, e.g. "Hs\Tp"
+                periods = Some(values);
+                continue;
+            }
+            let height = fields[0].parse::<f64>().map_err(|e| format!("Invalid wave height on line {}: {}", number + 1, e))?;
+            heights.push(height);
+            occurrence.push(values);
+        }
+        Self::new(heights, periods.unwrap_or_default(), occurrence)
+    }
+
+    /// Get centres of the significant wave height classes [m]
+    pub fn heights(&self) -> &[f64] {
+        &self.heights
+    }
+
+    /// Get centres of the peak period classes [s]
+    pub fn periods(&self) -> &[f64] {
+        &self.periods
+    }
+
+    /// Occurrence of a height and a period class, as in the table
+    pub fn occurrence(&self, row: usize, column: usize) -> f64 {
+        self.occurrence[row][column]
+    }
+
+    /// Sum of the occurrences of the table
+    pub fn total(&self) -> f64 {
+        self.occurrence.iter().flatten().sum()
+    }
+
+    /// Sea states that occur, with their probabilities
+    pub fn cells(&self) -> Vec<ScatterCell> {
+        let total = self.total();
+        let mut cells = Vec::new();
+        for (row, &significant_height) in self.heights.iter().enumerate() {
+            for (column, &peak_period) in self.periods.iter().enumerate() {
+                let occurrence = self.occurrence[row][column];
+                if occurrence > 0.0 {
+                    cells.push(ScatterCell {
+                        row,
+                        column,
+                        significant_height,
+                        peak_period,
+                        probability: occurrence / total,
+                    });
+                }
+            }
+        }
+        cells
+    }
+
+    /// Sea states that occur with their hours in an average year
+    pub fn sea_states(&self) -> Vec<SeaState> {
+        self.cells().iter().map(ScatterCell::sea_state).collect()
+    }
+
+    /// Mean of values computed for some of the cells, weighted by their probabilities
+    /// Returns the mean and the probability of the cells it covers, None if no cell occurs
+    pub fn weighted_mean(&self, values: &[(usize, usize, f64)]) -> Option<(f64, f64)> {
+        let total = self.total();
+        let (sum, coverage) = values
+            .iter()
+            .map(|&(row, column, value)| (value, self.occurrence[row][column] / total))
+            .filter(|&(_, probability)| probability > 0.0)
+            .fold((0.0, 0.0), |(sum, coverage), (value, probability)| (sum + value * probability, coverage + probability));
+        (coverage > 0.0).then(|| (sum / coverage, coverage))
+    }
+
+    /// Long-term mean energy flux of the waves in deep water, J = ρg² Hs² Te / 64π averaged over the sea states [W/m]
+    pub fn mean_wave_power(&self) -> f64 {
+        let factor = WATER_DENSITY * 9.81 * 9.81 / (64.0 * std::f64::consts::PI);
+        self.cells()
+            .iter()
+            .map(|cell| cell.probability * factor * cell.significant_height.powi(2) * cell.sea_state().energy_period)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scatter_diagram() {
+        let content = "# Buoy 2010-2020, hours per year\nHs\\Tp; 6; 8; 10\n0.5; 4000; 2000; 0\n1.5; 1000; 1500; 266\n";
+        let scatter = ScatterDiagram::parse(content).unwrap();
+        assert_eq!(scatter.heights(), [0.5, 1.5]);
+        assert_eq!(scatter.periods(), [6.0, 8.0, 10.0]);
+        assert_eq!(scatter.total(), HOURS_PER_YEAR);
+
+        // Empty classes are left out, the hours come back from the probabilities
+        let cells = scatter.cells();
+        assert_eq!(cells.len(), 5);
+        assert!((cells.iter().map(|cell| cell.probability).sum::<f64>() - 1.0).abs() < 1e-12);
+        let states = scatter.sea_states();
+        assert!((climate_hours(&states) - HOURS_PER_YEAR).abs() < 1e-9);
+        assert!((states[4].hours - 266.0).abs() < 1e-9 && (states[4].energy_period - 9.0).abs() < 1e-12);
+
+        // Weighted over the cells computed, with the probability they cover
+        let (mean, coverage) = scatter.weighted_mean(&[(0, 0, 1.0), (1, 1, 3.0), (0, 2, 100.0)]).unwrap();
+        assert!((coverage - 5500.0 / HOURS_PER_YEAR).abs() < 1e-12);
+        assert!((mean - (4000.0 + 3.0 * 1500.0) / 5500.0).abs() < 1e-12);
+        assert!(scatter.weighted_mean(&[(0, 2, 1.0)]).is_none());
+
+        // 1 m, Te = 9 s waves carry about 4.4 kW/m in deep water
+        let single = ScatterDiagram::new(vec![1.0], vec![10.0], vec![vec![1.0]]).unwrap();
+        assert!((single.mean_wave_power() - 4420.0).abs() < 10.0);
+
+        assert!(ScatterDiagram::parse("Hs, 6, 8\n0.5, 1, x\n").is_err());
+        assert!(ScatterDiagram::parse("Hs, 6, 8\n0.5, 1\n").is_err());
+        assert!(ScatterDiagram::new(vec![0.5], vec![6.0], vec![vec![0.0]]).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+use crate::error::CoastalError;
+use crate::units::{Length, Time, Velocity};
+use crate::waves::dispersion::{DispersionSolver, DopplerShift};
+use serde::{Deserialize, Serialize};
+
+/// Number of points of the depth integral of the Doppler velocity
+const DOPPLER_POINTS: usize = 200;
+
+/// Vertical distribution of an ambient current over the water column
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum CurrentProfile {
+    /// Same velocity from the bed to the surface
+    #[default]
+    Uniform,
+    /// Power law U(z) = (1 + p) Ū ((z + d)/d)^p of a turbulent flow, slowed by the bed; p ≈ 1/7
+    PowerLaw { exponent: f64 },
+}
+
+impl std::fmt::Display for CurrentProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CurrentProfile::Uniform => write!(f, "Uniform"),
+            CurrentProfile::PowerLaw { .. } => write!(f, "Power Law"),
+        }
+    }
+}
+
+/// Steady background flow along the channel, on which the waves ride
+///
+/// The current is positive in the direction of the generated waves: a following current lengthens
+/// them and an opposing one shortens and steepens them, up to blocking where it reaches their
+/// group velocity. The waves feel the current weighted over the depth by their orbital motion,
+/// U_eff = 2k/sinh(2kd) ∫ U(z) cosh(2k(z + d)) dz (Kirby and Chen, 1989), the surface velocity for
+/// short waves and the depth-averaged velocity for long ones.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AmbientCurrent {
+    /// Depth-averaged velocity at the wavemaker (Ū) [m/s]
+    pub velocity: f64,
+    /// Vertical distribution of the velocity
+    pub profile: CurrentProfile,
+}
+
+impl AmbientCurrent {
+    /// Create new current of the same velocity over the depth
+    pub fn uniform(velocity: f64) -> Self {
+        Self {
+            velocity,
+            profile: CurrentProfile::Uniform,
+        }
+    }
+
+    /// Set the vertical distribution of the velocity
+    pub fn with_profile(mut self, profile: CurrentProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Mean velocity between two heights above the bed, as fractions of the depth [m/s]
+    pub fn mean_velocity(&self, bottom: f64, top: f64) -> f64 {
+        match self.profile {
+            CurrentProfile::Uniform => self.velocity,
+            CurrentProfile::PowerLaw { exponent } if top > bottom => {
+                let power = exponent + 1.0;
+                self.velocity * (top.clamp(0.0, 1.0).powf(power) - bottom.clamp(0.0, 1.0).powf(power)) / (top - bottom)
+            }
+            CurrentProfile::PowerLaw { exponent } => self.velocity_at(top, exponent),
+        }
+    }
+
+    /// Mean velocity in a layer of a water column split into equidistant layers, numbered from the surface [m/s]
+    pub fn layer_velocity(&self, layer: usize, layers: usize) -> f64 {
+        let thickness = 1.0 / layers as f64;
+        let top = 1.0 - layer as f64 * thickness;
+        self.mean_velocity(top - thickness, top)
+    }
+
+    /// Velocity at the surface [m/s]
+    pub fn surface_velocity(&self) -> f64 {
+        self.mean_velocity(1.0, 1.0)
+    }
+
+    /// Velocity felt by waves of wave number k at a depth, U_eff = 2k/sinh(2kd) ∫ U(z) cosh(2k(z + d)) dz [m/s]
+    pub fn doppler_velocity(&self, k: f64, depth: f64) -> f64 {
+        let CurrentProfile::PowerLaw { exponent } = self.profile else {
+            return self.velocity;
+        };
+        let kd = 2.0 * k * depth;
+        if kd < 1e-6 {
+            return self.velocity;
+        }
+        // Midpoint rule over the depth, with the weight scaled by its largest value so deep water does not overflow
+        let dz = 1.0 / DOPPLER_POINTS as f64;
+        let (weighted, weights) = (0..DOPPLER_POINTS).fold((0.0, 0.0), |(weighted, weights), i| {
+            let height = (i as f64 + 0.5) * dz;
+            let weight = (kd * (height - 1.0)).exp() + (-kd * (height + 1.0)).exp();
+            (weighted + weight * self.velocity_at(height, exponent), weights + weight)
+        });
+        weighted / weights
+    }
+
+    /// Wave number of waves of absolute angular frequency ω on the current at a depth, from a dispersion relation
+    /// The Doppler velocity depends on the wave number, so the relation is solved again until they agree
+    pub fn wave_number(&self, solver: &DispersionSolver, omega: f64, depth: f64) -> Result<f64, CoastalError> {
+        let mut k = solver.solve_wave_number_with_current(omega, depth, self.velocity)?;
+        if self.profile == CurrentProfile::Uniform {
+            return Ok(k);
+        }
+        for _ in 0..50 {
+            let next = solver.solve_wave_number_with_current(omega, depth, self.doppler_velocity(k, depth))?;
+            if (next - k).abs() < 1e-10 * k {
+                return Ok(next);
+            }
+            k = next;
+        }
+        Ok(k)
+    }
+
+    /// Periods, wavelength and celerities of waves of an absolute period on the current at a depth
+    pub fn doppler_shift(&self, solver: &DispersionSolver, period: Time, depth: Length) -> Result<DopplerShift, CoastalError> {
+        if period.in_seconds() <= 0.0 {
+            return Err(CoastalError::not_positive("Wave period", period.in_seconds()));
+        }
+        let k = self.wave_number(solver, 2.0 * std::f64::consts::PI / period.in_seconds(), depth.in_meters())?;
+        solver.doppler_shift(period, depth, Velocity::meters_per_second(self.doppler_velocity(k, depth.in_meters())))
+    }
+
+    /// Validate the current
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.velocity.is_finite() {
+            return Err("Current velocity must be finite".to_string());
+        }
+        if let CurrentProfile::PowerLaw { exponent } = self.profile
+            && !(exponent > 0.0 && exponent <= 1.0)
+        {
+            return Err("Current profile exponent must be between 0 and 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// Velocity of the power law at a height above the bed, as a fraction of the depth [m/s]
+    fn velocity_at(&self, height: f64, exponent: f64) -> f64 {
+        (1.0 + exponent) * self.velocity * height.clamp(0.0, 1.0).powf(exponent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_power_law_profile() {
+        let current = AmbientCurrent::uniform(0.7).with_profile(CurrentProfile::PowerLaw { exponent: 1.0 / 7.0 });
+        assert!(current.validate().is_ok());
+        // The layers average back to the depth-averaged velocity, faster at the surface
+        let layers = 4;
+        let mean = (0..layers).map(|layer| current.layer_velocity(layer, layers)).sum::<f64>() / layers as f64;
+        assert!((mean - 0.7).abs() < 1e-12);
+        assert!(current.layer_velocity(0, layers) > current.layer_velocity(3, layers));
+        assert!((current.surface_velocity() - 0.8).abs() < 1e-12);
+        assert!((current.mean_velocity(0.0, 1.0) - 0.7).abs() < 1e-12);
+
+        assert!(AmbientCurrent::uniform(0.5).with_profile(CurrentProfile::PowerLaw { exponent: 0.0 }).validate().is_err());
+        assert!(AmbientCurrent::uniform(f64::NAN).validate().is_err());
+    }
+
+    #[test]
+    fn test_doppler_velocity_between_mean_and_surface() {
+        let uniform = AmbientCurrent::uniform(-0.4);
+        assert_eq!(uniform.doppler_velocity(3.0, 2.0), -0.4);
+        assert_eq!(uniform.layer_velocity(1, 2), -0.4);
+
+        let sheared = uniform.with_profile(CurrentProfile::PowerLaw { exponent: 1.0 / 7.0 });
+        // Long waves feel the depth average, short waves the surface current
+        assert!((sheared.doppler_velocity(1e-4, 2.0) + 0.4).abs() < 1e-3);
+        assert!((sheared.doppler_velocity(50.0, 2.0) - sheared.surface_velocity()).abs() < 0.01);
+        let intermediate = sheared.doppler_velocity(1.0, 2.0);
+        assert!(intermediate < -0.4 && intermediate > sheared.surface_velocity());
+    }
+
+    #[test]
+    fn test_wave_number_on_sheared_current() {
+        let solver = DispersionSolver::linear();
+        let omega = 2.0 * std::f64::consts::PI / 2.0;
+        let uniform = AmbientCurrent::uniform(-0.3);
+        let sheared = uniform.with_profile(CurrentProfile::PowerLaw { exponent: 1.0 / 7.0 });
+        let k = sheared.wave_number(&solver, omega, 3.0).unwrap();
+        let relative = omega - k * sheared.doppler_velocity(k, 3.0);
+        assert!((relative * relative - 9.81 * k * (k * 3.0).tanh()).abs() < 1e-6);
+        // The faster opposing surface current shortens the waves more than its depth average
+        assert!(k > uniform.wave_number(&solver, omega, 3.0).unwrap());
+    }
+}
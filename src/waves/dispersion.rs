@@ -1,4 +1,84 @@
+use crate::error::CoastalError;
+use crate::units::{Length, Time, Velocity};
 use crate::waves::parameters::WaveParameters;
+use serde::{Deserialize, Serialize};
+
+/// Number of equidistant vertical layers, setting the accuracy of the SWASH dispersion relation
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum DispersionMode {
+    /// Depth-averaged (one layer): accurate up to kd ≈ 0.5 at 1% phase velocity error
+    #[default]
+    OneLayer,
+    /// Two layers: accurate up to kd ≈ 7 at 1% phase velocity error
+    TwoLayer,
+}
+
+impl DispersionMode {
+    /// Number of vertical layers
+    pub fn layers(&self) -> usize {
+        match self {
+            DispersionMode::OneLayer => 1,
+            DispersionMode::TwoLayer => 2,
+        }
+    }
+}
+
+impl std::fmt::Display for DispersionMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DispersionMode::OneLayer => write!(f, "One Layer"),
+            DispersionMode::TwoLayer => write!(f, "Two Layers"),
+        }
+    }
+}
+
+/// Dispersion relation ω² = gk R(kd) solved for the wave number
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum DispersionRelation {
+    /// Layer-based SWASH approximation R(kd) of the selected number of layers
+    #[default]
+    Swash,
+    /// Exact linear (Airy) theory, R(kd) = tanh(kd)
+    Linear,
+}
+
+impl std::fmt::Display for DispersionRelation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DispersionRelation::Swash => write!(f, "SWASH"),
+            DispersionRelation::Linear => write!(f, "Linear (Airy)"),
+        }
+    }
+}
+
+/// Waves of a given absolute period riding on an ambient current, seen from the ground and from the water
+///
+/// The wavelength is the same in both frames; the current only changes the period an observer
+/// moving with the water counts, T_r = 2π/(ω − kU), and the celerity, c = c_r + U.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DopplerShift {
+    /// Ambient current felt by the waves (U), positive with the waves
+    pub current: Velocity,
+    /// Period at a fixed point, set by the wavemaker (T)
+    pub absolute_period: Time,
+    /// Period moving with the water (T_r)
+    pub relative_period: Time,
+    /// Wavelength on the current (L)
+    pub wavelength: Length,
+    /// Wavelength of the absolute period without the current
+    pub still_water_wavelength: Length,
+    /// Celerity at a fixed point (L/T)
+    pub absolute_celerity: Velocity,
+    /// Celerity relative to the water (L/T_r)
+    pub relative_celerity: Velocity,
+}
+
+impl DopplerShift {
+    /// Wavelength change due to the current, relative to still water
+    pub fn wavelength_change(&self) -> f64 {
+        self.wavelength / self.still_water_wavelength - 1.0
+    }
+}
 
 /// Dispersion relation solver for SWASH-style wave generation
 pub struct DispersionSolver {
@@ -8,6 +88,10 @@ pub struct DispersionSolver {
     tolerance: f64,
     /// Gravitational acceleration [m/s²]
     gravity: f64,
+    /// Layer-based dispersion relation
+    mode: DispersionMode,
+    /// Layer-based or exact relation
+    relation: DispersionRelation,
 }
 
 impl Default for DispersionSolver {
@@ -16,6 +100,8 @@ impl Default for DispersionSolver {
             max_iterations: 100,
             tolerance: 1e-10,
             gravity: 9.81,
+            mode: DispersionMode::OneLayer,
+            relation: DispersionRelation::Swash,
         }
     }
 }
@@ -32,16 +118,45 @@ impl DispersionSolver {
             max_iterations,
             tolerance,
             gravity,
+            mode: DispersionMode::OneLayer,
+            relation: DispersionRelation::Swash,
         }
     }
+
+    /// Create new solver of the exact linear dispersion relation
+    pub fn linear() -> Self {
+        Self::new().with_relation(DispersionRelation::Linear)
+    }
+
+    /// Select the layer-based dispersion relation
+    pub fn with_mode(mut self, mode: DispersionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Get the layer-based dispersion relation
+    pub fn mode(&self) -> DispersionMode {
+        self.mode
+    }
+
+    /// Select the layer-based or the exact linear relation
+    pub fn with_relation(mut self, relation: DispersionRelation) -> Self {
+        self.relation = relation;
+        self
+    }
+
+    /// Get the dispersion relation
+    pub fn relation(&self) -> DispersionRelation {
+        self.relation
+    }
     
-    /// Solve wave parameters using one-layer SWASH dispersion relation
-    pub fn solve_wave_parameters(&self, wave_height: f64, wave_period: f64, water_depth: f64) -> Result<WaveParameters, String> {
+    /// Solve wave parameters using the selected SWASH dispersion relation
+    pub fn solve_wave_parameters(&self, wave_height: Length, wave_period: Time, water_depth: Length) -> Result<WaveParameters, CoastalError> {
         // Create initial wave parameters
         let mut params = WaveParameters::new(wave_height, wave_period, water_depth)?;
         
         // Solve for wave number using Newton-Raphson iteration
-        let wave_number = self.solve_wave_number(params.omega, water_depth)?;
+        let wave_number = self.solve_wave_number(params.omega, params.d)?;
         
         // Update parameters with computed wave number
         params.update_from_dispersion(wave_number);
@@ -53,17 +168,32 @@ impl DispersionSolver {
     }
     
     /// Solve for wave number given angular frequency and depth
-    /// Uses one-layer SWASH dispersion relation: ω² = gk * (kd)/(1 + (kd)²/4)
-    fn solve_wave_number(&self, omega: f64, depth: f64) -> Result<f64, String> {
-        // Initial guess: deep water wave number
-        let mut k = omega * omega / self.gravity;
+    /// Uses the selected dispersion relation: ω² = gk R(kd)
+    pub fn solve_wave_number(&self, omega: f64, depth: f64) -> Result<f64, CoastalError> {
+        if omega <= 0.0 {
+            return Err(CoastalError::not_positive("Angular frequency", omega));
+        }
+        if depth <= 0.0 {
+            return Err(CoastalError::not_positive("Water depth", depth));
+        }
+
+        let mut k = match self.relation {
+            // Initial guess: deep water wave number
+            DispersionRelation::Swash => omega * omega / self.gravity,
+            // Guo (2002) explicit approximation, within 0.75% of the root at any depth: Newton converges in a few iterations
+            DispersionRelation::Linear => {
+                let x = omega * (depth / self.gravity).sqrt();
+                x * x / (1.0 - (-x.powf(2.5)).exp()).powf(0.4) / depth
+            }
+        };
         
-        for _iteration in 0..self.max_iterations {
+        for iteration in 0..self.max_iterations {
             let f = self.dispersion_function(k, omega, depth);
             let df_dk = self.dispersion_derivative(k, omega, depth);
             
+            // Newton-Raphson cannot continue on a flat dispersion function
             if df_dk.abs() < self.tolerance {
-                return Err("Derivative too small in Newton-Raphson iteration".to_string());
+                return Err(CoastalError::NonConvergence { iterations: iteration, residual: f });
             }
             
             let k_new = k - f / df_dk;
@@ -77,64 +207,153 @@ impl DispersionSolver {
             k = k_new.max(self.tolerance);
         }
         
-        Err(format!("Newton-Raphson failed to converge after {} iterations", self.max_iterations))
+        Err(CoastalError::NonConvergence { iterations: self.max_iterations, residual: self.dispersion_function(k, omega, depth) })
+    }
+
+    /// Solve for the wave number of waves of absolute angular frequency ω riding on a current U
+    ///
+    /// Doppler-shifted relation (ω − kU)² = gk R(kd), on the branch where the waves travel forward
+    /// relative to the water, ω − kU > 0. A following current lengthens the waves and an opposing
+    /// one shortens them, until they are blocked where U reaches minus their group velocity.
+    pub fn solve_wave_number_with_current(&self, omega: f64, depth: f64, current: f64) -> Result<f64, CoastalError> {
+        let still = self.solve_wave_number(omega, depth)?;
+        if current == 0.0 {
+            return Ok(still);
+        }
+        // f(k) = (ω − kU)² − gk R(kd) falls from positive to negative through the root
+        let f = |k: f64| {
+            let (ratio, _) = self.dispersion_ratio(k * depth);
+            (omega - k * current).powi(2) - self.gravity * k * ratio
+        };
+        let (mut lower, mut upper) = if current > 0.0 {
+            (0.0, still.min(omega / current))
+        } else {
+            // An opposing current shortens the waves: search upwards for the end of the bracket
+            let mut upper = still;
+            let mut lower = still;
+            loop {
+                upper *= 1.05;
+                if f(upper) < 0.0 {
+                    break;
+                }
+                lower = upper;
+                if upper * depth > 1e6 {
+                    return Err(CoastalError::Blocked { current });
+                }
+            }
+            (lower, upper)
+        };
+        for _ in 0..self.max_iterations {
+            let k = 0.5 * (lower + upper);
+            if f(k) > 0.0 {
+                lower = k;
+            } else {
+                upper = k;
+            }
+            if upper - lower < self.tolerance * upper {
+                return Ok(0.5 * (lower + upper));
+            }
+        }
+        Err(CoastalError::NonConvergence { iterations: self.max_iterations, residual: f(0.5 * (lower + upper)) })
+    }
+
+    /// Solve the Doppler-shifted relation for waves of an absolute period on a current U at a depth
+    pub fn doppler_shift(&self, period: Time, depth: Length, current: Velocity) -> Result<DopplerShift, CoastalError> {
+        if period.in_seconds() <= 0.0 {
+            return Err(CoastalError::not_positive("Wave period", period.in_seconds()));
+        }
+        let omega = 2.0 * std::f64::consts::PI / period.in_seconds();
+        let k = self.solve_wave_number_with_current(omega, depth.in_meters(), current.in_meters_per_second())?;
+        let relative_omega = omega - k * current.in_meters_per_second();
+        Ok(DopplerShift {
+            current,
+            absolute_period: period,
+            relative_period: Time::seconds(2.0 * std::f64::consts::PI / relative_omega),
+            wavelength: Length::meters(2.0 * std::f64::consts::PI / k),
+            still_water_wavelength: self.wavelength(period, depth)?,
+            absolute_celerity: Velocity::meters_per_second(omega / k),
+            relative_celerity: Velocity::meters_per_second(relative_omega / k),
+        })
+    }
+
+    /// Wavelength of a wave period at a depth [m]
+    pub fn wavelength(&self, period: Time, depth: Length) -> Result<Length, CoastalError> {
+        let period = period.in_seconds();
+        if period <= 0.0 {
+            return Err(CoastalError::not_positive("Wave period", period));
+        }
+        let k = self.solve_wave_number(2.0 * std::f64::consts::PI / period, depth.in_meters())?;
+        Ok(Length::meters(2.0 * std::f64::consts::PI / k))
+    }
+
+    /// Layer-based approximation of tanh(kd), or tanh(kd) itself for the linear relation, and its derivative with respect to kd
+    /// One layer:  R = kd / (1 + (kd)²/4)
+    /// Two layers: R = (kd + (kd)³/16) / (1 + 3(kd)²/8 + (kd)⁴/256)
+    fn dispersion_ratio(&self, kd: f64) -> (f64, f64) {
+        if self.relation == DispersionRelation::Linear {
+            let tanh = kd.tanh();
+            return (tanh, 1.0 - tanh * tanh);
+        }
+        let kd2 = kd * kd;
+        let (numerator, d_numerator, denominator, d_denominator) = match self.mode {
+            DispersionMode::OneLayer => (kd, 1.0, 1.0 + kd2 / 4.0, kd / 2.0),
+            DispersionMode::TwoLayer => (
+                kd + kd * kd2 / 16.0,
+                1.0 + 3.0 * kd2 / 16.0,
+                1.0 + 3.0 * kd2 / 8.0 + kd2 * kd2 / 256.0,
+                3.0 * kd / 4.0 + kd * kd2 / 64.0,
+            ),
+        };
+        let ratio = numerator / denominator;
+        let d_ratio = (d_numerator * denominator - numerator * d_denominator) / (denominator * denominator);
+        (ratio, d_ratio)
     }
     
-    /// One-layer SWASH dispersion function: f(k) = ω² - gk * (kd)/(1 + (kd)²/4)
+    /// SWASH dispersion function: f(k) = ω² - gk R(kd)
     fn dispersion_function(&self, k: f64, omega: f64, depth: f64) -> f64 {
-        let kd = k * depth;
-        let dispersion_rhs = self.gravity * k * kd / (1.0 + kd * kd / 4.0);
-        omega * omega - dispersion_rhs
+        let (ratio, _) = self.dispersion_ratio(k * depth);
+        omega * omega - self.gravity * k * ratio
     }
     
     /// Derivative of dispersion function with respect to k
     fn dispersion_derivative(&self, k: f64, _omega: f64, depth: f64) -> f64 {
+        // d/dk [gk R(kd)] = g [R(kd) + kd R'(kd)]
         let kd = k * depth;
-        let kd2 = kd * kd;
-        let denominator = 1.0 + kd2 / 4.0;
-        let denominator2 = denominator * denominator;
-        
-        // d/dk [gk * (kd)/(1 + (kd)²/4)]
-        // = g * [kd/(1 + (kd)²/4) + k * d * (1 + (kd)²/4 - kd * kd/2) / (1 + (kd)²/4)²]
-        // = g * [kd/(1 + (kd)²/4) + k * d * (1 - (kd)²/4) / (1 + (kd)²/4)²]
-        
-        let term1 = kd / denominator;
-        let term2 = k * depth * (1.0 - kd2 / 4.0) / denominator2;
-        
-        -self.gravity * (term1 + term2)
+        let (ratio, d_ratio) = self.dispersion_ratio(kd);
+        -self.gravity * (ratio + kd * d_ratio)
     }
     
     /// Compute phase velocity from dispersion relation
     pub fn phase_velocity(&self, k: f64, depth: f64) -> f64 {
-        let kd = k * depth;
-        let c_squared = self.gravity * kd / (k * (1.0 + kd * kd / 4.0));
-        c_squared.sqrt()
+        let (ratio, _) = self.dispersion_ratio(k * depth);
+        (self.gravity * ratio / k).sqrt()
     }
     
     /// Compute group velocity (∂ω/∂k)
     pub fn group_velocity(&self, k: f64, depth: f64) -> f64 {
         let kd = k * depth;
-        let kd2 = kd * kd;
-        let denominator = 1.0 + kd2 / 4.0;
-        
-        // For ω² = gk * (kd)/(1 + (kd)²/4), compute ∂ω/∂k
-        let omega_squared = self.gravity * k * kd / denominator;
-        let omega = omega_squared.sqrt();
+        let (ratio, d_ratio) = self.dispersion_ratio(kd);
+        let omega = (self.gravity * k * ratio).sqrt();
         
         // ∂ω/∂k = (1/2ω) * ∂(ω²)/∂k
-        let domega2_dk = self.gravity * depth * (1.0 - kd2 / 4.0) / denominator.powi(2);
-        
-        domega2_dk / (2.0 * omega)
+        self.gravity * (ratio + kd * d_ratio) / (2.0 * omega)
+    }
+
+    /// Relative phase velocity error against linear theory at dimensionless depth kd
+    /// Negative values mean the layer-based waves travel slower than linear waves
+    pub fn dispersion_error(&self, kd: f64) -> f64 {
+        let (ratio, _) = self.dispersion_ratio(kd);
+        (ratio / kd.tanh()).sqrt() - 1.0
     }
     
     /// Validate dispersion relation accuracy against linear theory
-    pub fn validate_dispersion(&self, k: f64, omega: f64, depth: f64) -> Result<f64, String> {
+    pub fn validate_dispersion(&self, k: f64, omega: f64, depth: f64) -> Result<f64, CoastalError> {
         // Compute dispersion relation residual
         let residual = self.dispersion_function(k, omega, depth);
         
         // Check if residual is small enough
         if residual.abs() > 1e-6 {
-            return Err(format!("Dispersion relation not satisfied: residual = {:.2e}", residual));
+            return Err(CoastalError::Inconsistent { check: "Dispersion relation", error: residual });
         }
         
         Ok(residual)
@@ -152,12 +371,13 @@ mod tests {
         assert_eq!(solver.max_iterations, 100);
         assert_eq!(solver.tolerance, 1e-10);
         assert_eq!(solver.gravity, 9.81);
+        assert_eq!(solver.mode(), DispersionMode::OneLayer);
     }
     
     #[test]
     fn test_shallow_water_limit() {
         let solver = DispersionSolver::new();
-        let params = solver.solve_wave_parameters(0.3, 4.0, 1.0).unwrap();
+        let params = solver.solve_wave_parameters(Length::meters(0.3), Time::seconds(4.0), Length::meters(1.0)).unwrap();
         
         // In shallow water, c ≈ √(gd)
         let expected_c = (solver.gravity * params.d).sqrt();
@@ -170,7 +390,7 @@ mod tests {
     #[test]
     fn test_deep_water_limit() {
         let solver = DispersionSolver::new();
-        let params = solver.solve_wave_parameters(1.0, 8.0, 20.0).unwrap();
+        let params = solver.solve_wave_parameters(Length::meters(1.0), Time::seconds(8.0), Length::meters(20.0)).unwrap();
         
         // In deep water, c = gT/(2π)
         let expected_c = solver.gravity * params.period / (2.0 * PI);
@@ -183,7 +403,7 @@ mod tests {
     #[test]
     fn test_dispersion_validation() {
         let solver = DispersionSolver::new();
-        let params = solver.solve_wave_parameters(1.0, 4.0, 2.0).unwrap();
+        let params = solver.solve_wave_parameters(Length::meters(1.0), Time::seconds(4.0), Length::meters(2.0)).unwrap();
         
         // Validate that dispersion relation is satisfied
         let result = solver.validate_dispersion(params.k, params.omega, params.d);
@@ -193,10 +413,60 @@ mod tests {
         assert!(residual.abs() < 1e-6, "Dispersion relation residual too large: {:.2e}", residual);
     }
     
+    #[test]
+    fn test_non_convergence_reports_residual() {
+        let solver = DispersionSolver::with_params(2, 1e-10, 9.81);
+        match solver.solve_wave_number(2.0 * PI / 8.0, 10.0) {
+            Err(CoastalError::NonConvergence { iterations, residual }) => {
+                assert_eq!(iterations, 2);
+                assert!(residual.abs() > 1e-10);
+            }
+            result => panic!("Expected non-convergence, got {:?}", result),
+        }
+        assert!(matches!(DispersionSolver::new().solve_wave_number(1.0, -1.0), Err(CoastalError::InvalidParameter { name: "Water depth", .. })));
+    }
+
+    #[test]
+    fn test_doppler_shifted_wave_number() {
+        let solver = DispersionSolver::linear();
+        let (omega, depth) = (2.0 * PI / 4.0, 2.0);
+        let still = solver.solve_wave_number(omega, depth).unwrap();
+        assert_eq!(solver.solve_wave_number_with_current(omega, depth, 0.0).unwrap(), still);
+        for current in [0.5, -0.3] {
+            let k = solver.solve_wave_number_with_current(omega, depth, current).unwrap();
+            let relative = omega - k * current;
+            assert!((relative * relative - 9.81 * k * (k * depth).tanh()).abs() < 1e-8, "U = {}", current);
+            // Longer waves on a following current, shorter on an opposing one
+            assert!(if current > 0.0 { k < still } else { k > still });
+        }
+        // Deep water blocking at U = −c/4 = −gT/8π
+        let blocking = -9.81 * 4.0 / (8.0 * PI);
+        assert!(DispersionSolver::linear().solve_wave_number_with_current(omega, 50.0, 0.9 * blocking).is_ok());
+        assert!(matches!(DispersionSolver::linear().solve_wave_number_with_current(omega, 50.0, 1.1 * blocking), Err(CoastalError::Blocked { .. })));
+    }
+
+    #[test]
+    fn test_doppler_shift_frames() {
+        let solver = DispersionSolver::linear();
+        let (period, depth) = (Time::seconds(4.0), Length::meters(2.0));
+        let opposing = solver.doppler_shift(period, depth, Velocity::meters_per_second(-0.3)).unwrap();
+        // Relative to the water the waves obey the still-water relation at their relative period
+        assert!((solver.wavelength(opposing.relative_period, depth).unwrap() - opposing.wavelength).abs().in_meters() < 1e-6);
+        assert!((opposing.absolute_celerity - opposing.relative_celerity - opposing.current).abs().in_meters_per_second() < 1e-12);
+        assert!(opposing.relative_period < opposing.absolute_period);
+        assert!(opposing.wavelength_change() < 0.0);
+
+        let following = solver.doppler_shift(period, depth, Velocity::meters_per_second(0.3)).unwrap();
+        assert!(following.relative_period > following.absolute_period);
+        assert!(following.wavelength_change() > 0.0);
+        assert_eq!(solver.doppler_shift(period, depth, Velocity::ZERO).unwrap().wavelength_change(), 0.0);
+        assert!(solver.doppler_shift(Time::ZERO, depth, Velocity::meters_per_second(0.3)).is_err());
+    }
+
     #[test]
     fn test_phase_velocity_consistency() {
         let solver = DispersionSolver::new();
-        let params = solver.solve_wave_parameters(1.0, 4.0, 2.0).unwrap();
+        let params = solver.solve_wave_parameters(Length::meters(1.0), Time::seconds(4.0), Length::meters(2.0)).unwrap();
         
         // Compute phase velocity directly from dispersion solver
         let c_direct = solver.phase_velocity(params.k, params.d);
@@ -205,4 +475,88 @@ mod tests {
         let relative_error = (params.c - c_direct).abs() / params.c;
         assert!(relative_error < 1e-6, "Phase velocity inconsistency: c = {:.6}, c_direct = {:.6}", params.c, c_direct);
     }
+
+    #[test]
+    fn test_two_layer_dispersion_validation() {
+        let solver = DispersionSolver::new().with_mode(DispersionMode::TwoLayer);
+        assert_eq!(solver.mode().layers(), 2);
+
+        let params = solver.solve_wave_parameters(Length::meters(0.5), Time::seconds(2.0), Length::meters(5.0)).unwrap();
+        let residual = solver.validate_dispersion(params.k, params.omega, params.d).unwrap();
+        assert!(residual.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_two_layer_deep_water_accuracy() {
+        // Deep water: kd ≈ 5.6, beyond the reach of the one-layer relation
+        let one_layer = DispersionSolver::new();
+        let two_layer = DispersionSolver::new().with_mode(DispersionMode::TwoLayer);
+        let expected_c = 9.81 * 2.0 / (2.0 * PI);
+
+        let params = two_layer.solve_wave_parameters(Length::meters(0.5), Time::seconds(2.0), Length::meters(5.6)).unwrap();
+        assert!((params.c - expected_c).abs() / expected_c < 0.01, "Two-layer c = {:.3}, expected = {:.3}", params.c, expected_c);
+        assert!(one_layer.solve_wave_parameters(Length::meters(0.5), Time::seconds(2.0), Length::meters(5.6)).is_err());
+    }
+
+    #[test]
+    fn test_dispersion_error() {
+        let one_layer = DispersionSolver::new();
+        let two_layer = DispersionSolver::new().with_mode(DispersionMode::TwoLayer);
+
+        // Both relations recover linear theory in shallow water
+        assert!(one_layer.dispersion_error(0.1).abs() < 1e-3);
+        assert!(two_layer.dispersion_error(0.1).abs() < 1e-3);
+
+        // Two layers stay within 1% up to kd ≈ 7, one layer does not
+        assert!(two_layer.dispersion_error(7.0).abs() < 0.01);
+        assert!(one_layer.dispersion_error(7.0).abs() > 0.2);
+    }
+
+    #[test]
+    fn test_linear_relation_is_exact() {
+        let solver = DispersionSolver::linear();
+        assert_eq!(solver.relation(), DispersionRelation::Linear);
+        for (period, depth) in [(30.0, 0.5), (8.0, 10.0), (2.0, 50.0)] {
+            let omega = 2.0 * PI / period;
+            let k = solver.solve_wave_number(omega, depth).unwrap();
+            assert!((9.81 * k * (k * depth).tanh() - omega * omega).abs() < 1e-9 * omega * omega);
+            assert!(solver.dispersion_error(k * depth).abs() < 1e-15);
+            assert!((solver.wavelength(Time::seconds(period), Length::meters(depth)).unwrap().in_meters() - 2.0 * PI / k).abs() < 1e-9);
+        }
+        // Deep water: L = gT²/2π
+        assert!((solver.wavelength(Time::seconds(2.0), Length::meters(50.0)).unwrap().in_meters() - 9.81 * 4.0 / (2.0 * PI)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_linear_initial_guess_converges_quickly() {
+        // Four Newton iterations suffice from the Guo approximation, from very shallow to very deep water
+        let solver = DispersionSolver::with_params(4, 1e-9, 9.81).with_relation(DispersionRelation::Linear);
+        for x in [0.01, 0.1, 0.5, 1.0, 2.0, 5.0, 20.0] {
+            // x = ω√(d/g) with d = 1 m
+            assert!(solver.solve_wave_number(x * 9.81f64.sqrt(), 1.0).is_ok(), "x = {}", x);
+        }
+    }
+
+    #[test]
+    fn test_swash_and_linear_wavelengths_agree_in_shallow_water() {
+        let linear = DispersionSolver::linear().wavelength(Time::seconds(20.0), Length::meters(1.0)).unwrap();
+        let swash = DispersionSolver::new().wavelength(Time::seconds(20.0), Length::meters(1.0)).unwrap();
+        assert!((swash / linear - 1.0).abs() < 1e-3);
+        // The one-layer relation has no solution for the short waves the two-layer relation still resolves
+        assert!(DispersionSolver::new().wavelength(Time::seconds(2.0), Length::meters(5.6)).is_err());
+        assert!(DispersionSolver::linear().wavelength(Time::seconds(0.0), Length::meters(5.6)).is_err());
+    }
+
+    #[test]
+    fn test_two_layer_group_velocity() {
+        let solver = DispersionSolver::new().with_mode(DispersionMode::TwoLayer);
+        let k = 1.0;
+        let depth = 3.0;
+
+        // Compare with a central difference of ω(k)
+        let omega = |k: f64| k * solver.phase_velocity(k, depth);
+        let dk = 1e-6;
+        let expected = (omega(k + dk) - omega(k - dk)) / (2.0 * dk);
+        assert!((solver.group_velocity(k, depth) - expected).abs() < 1e-6);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,169 @@
+use crate::waves::climate::SeaState;
+use crate::waves::WATER_DENSITY;
+use crate::waves::wavemaker::linear_wave_number;
+use serde::{Deserialize, Serialize};
+
+/// Kind of wave energy converter, both modelled as a single body heaving in the waves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConverterKind {
+    /// Floating vertical cylinder whose heave drives the power take-off
+    PointAbsorber,
+    /// Water column heaving in a chamber open below the surface, pumping air through a turbine
+    OscillatingWaterColumn,
+}
+
+impl std::fmt::Display for ConverterKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConverterKind::PointAbsorber => write!(f, "Point absorber"),
+            ConverterKind::OscillatingWaterColumn => write!(f, "Oscillating water column"),
+        }
+    }
+}
+
+/// Heaving wave energy converter in the linear frequency domain
+///
+/// The float, or the water column inside the chamber, of radius a and draft D has the mass
+/// m = ρπa²D, the added mass A ≈ ⅓πρa³ (half a hemisphere of water) and the hydrostatic stiffness
+/// C = ρgπa². The Froude-Krylov force on its base excites it, f = ρgπa² cosh(k(h − D))/cosh(kh) per
+/// metre of wave amplitude, and the Haskind relation gives the radiation damping B = k f²/(4ρg c_g)
+/// of an axisymmetric body. A linear power take-off of damping B_pto then absorbs P = ½ B_pto ω² |X|²
+/// with the heave amplitude |X| = f a_w / √((C − ω²(m + A))² + ω²(B + B_pto)²).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WaveEnergyConverter {
+    pub kind: ConverterKind, // Device type
+    pub position: f64,       // Position along the channel [m]
+    pub radius: f64,         // Float or chamber radius (a) [m]
+    pub draft: f64,          // Float draft, or submergence of the chamber lip (D) [m]
+    pub pto_damping: f64,    // Linear damping of the power take-off (B_pto) [N·s/m]
+}
+
+/// Response of a converter to regular waves
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConverterResponse {
+    pub incident_power: f64,     // Energy flux of the waves per metre of crest (J = E c_g) [W/m]
+    pub absorbed_power: f64,     // Power absorbed by the power take-off [W]
+    pub heave_amplitude: f64,    // Amplitude of the heave motion [m]
+    pub radiation_damping: f64,  // Damping of the radiated waves (B) [N·s/m]
+    pub optimal_damping: f64,    // Power take-off damping absorbing the most power in these waves [N·s/m]
+}
+
+impl ConverterResponse {
+    /// Width of wave crest whose energy flux the device absorbs, P/J [m]
+    pub fn capture_width(&self) -> f64 {
+        if self.incident_power > 0.0 { self.absorbed_power / self.incident_power } else { 0.0 }
+    }
+}
+
+impl WaveEnergyConverter {
+    /// Create new converter of a given size at a position along the channel, without power take-off damping
+    pub fn new(kind: ConverterKind, position: f64, radius: f64, draft: f64) -> Result<Self, String> {
+        if radius <= 0.0 || draft <= 0.0 {
+            return Err("Converter radius and draft must be positive".to_string());
+        }
+        Ok(Self {
+            kind,
+            position,
+            radius,
+            draft,
+            pto_damping: 0.0,
+        })
+    }
+
+    /// Set the damping of the power take-off [N·s/m]
+    pub fn with_pto_damping(mut self, pto_damping: f64) -> Self {
+        self.pto_damping = pto_damping.max(0.0);
+        self
+    }
+
+    /// Waterplane area of the float or the water column, πa² [m²]
+    pub fn waterplane_area(&self) -> f64 {
+        std::f64::consts::PI * self.radius * self.radius
+    }
+
+    /// Heaving mass and added mass, m + A [kg]
+    pub fn inertia(&self) -> f64 {
+        WATER_DENSITY * self.waterplane_area() * self.draft + WATER_DENSITY * std::f64::consts::PI * self.radius.powi(3) / 3.0
+    }
+
+    /// Natural period of the heave motion, 2π √((m + A)/C) [s]
+    pub fn natural_period(&self) -> f64 {
+        2.0 * std::f64::consts::PI * (self.inertia() / (WATER_DENSITY * 9.81 * self.waterplane_area())).sqrt()
+    }
+
+    /// Heave response and absorbed power in regular waves of height H and period T in a depth h
+    pub fn response(&self, wave_height: f64, wave_period: f64, depth: f64) -> Result<ConverterResponse, String> {
+        if wave_height <= 0.0 || wave_period <= 0.0 {
+            return Err("Wave height and period must be positive".to_string());
+        }
+        if self.draft >= depth {
+            return Err(format!("The converter draft ({:.2} m) must be less than the water depth ({:.2} m)", self.draft, depth));
+        }
+        let omega = 2.0 * std::f64::consts::PI / wave_period;
+        let k = linear_wave_number(omega, depth, 9.81)?;
+        let kh = k * depth;
+        let group_velocity = 0.5 * (1.0 + 2.0 * kh / (2.0 * kh).sinh()) * omega / k;
+        let amplitude = 0.5 * wave_height;
+
+        let stiffness = WATER_DENSITY * 9.81 * self.waterplane_area();
+        // cosh(k(h − D))/cosh(kh) written to stay finite in deep water
+        let attenuation = ((-k * self.draft).exp() + (-k * (2.0 * depth - self.draft)).exp()) / (1.0 + (-2.0 * kh).exp());
+        let excitation = stiffness * attenuation;
+        let radiation_damping = k * excitation * excitation / (4.0 * WATER_DENSITY * 9.81 * group_velocity);
+        let reactance = omega * self.inertia() - stiffness / omega;
+        let impedance = reactance.hypot(radiation_damping + self.pto_damping);
+        let heave_amplitude = excitation * amplitude / (omega * impedance);
+
+        Ok(ConverterResponse {
+            incident_power: 0.5 * WATER_DENSITY * 9.81 * amplitude * amplitude * group_velocity,
+            absorbed_power: 0.5 * self.pto_damping * (omega * heave_amplitude).powi(2),
+            heave_amplitude,
+            radiation_damping,
+            optimal_damping: reactance.hypot(radiation_damping),
+        })
+    }
+
+    /// Energy absorbed over a year of a wave climate, each sea state as regular waves of the same energy [J]
+    pub fn annual_energy(&self, climate: &[SeaState], depth: f64) -> Result<f64, String> {
+        climate
+            .iter()
+            .map(|state| Ok(self.response(state.equivalent_wave_height(), state.energy_period, depth)?.absorbed_power * state.hours * 3600.0))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_absorber_limit() {
+        // At resonance with the optimal damping a heaving axisymmetric body captures the width 1/k = L/2π
+        let depth = 10.0;
+        let converter = WaveEnergyConverter::new(ConverterKind::PointAbsorber, 0.0, 1.0, 1.0).unwrap();
+        let period = converter.natural_period();
+        let optimal = converter.response(0.5, period, depth).unwrap().optimal_damping;
+        let tuned = converter.with_pto_damping(optimal);
+        let response = tuned.response(0.5, period, depth).unwrap();
+        let k = linear_wave_number(2.0 * std::f64::consts::PI / period, depth, 9.81).unwrap();
+        assert!((response.optimal_damping - response.radiation_damping).abs() < 1e-6 * optimal);
+        assert!((response.capture_width() * k - 1.0).abs() < 1e-9);
+
+        // Detuned damping or period absorb less, and the power grows with the square of the height
+        for other in [0.5 * optimal, 2.0 * optimal] {
+            assert!(converter.with_pto_damping(other).response(0.5, period, depth).unwrap().absorbed_power < response.absorbed_power);
+        }
+        assert!(tuned.response(0.5, 2.0 * period, depth).unwrap().capture_width() < response.capture_width());
+        let higher = tuned.response(1.0, period, depth).unwrap();
+        assert!((higher.absorbed_power / response.absorbed_power - 4.0).abs() < 1e-9);
+        assert_eq!(converter.response(0.5, period, depth).unwrap().absorbed_power, 0.0);
+
+        // A year of the same sea state
+        let state = SeaState::new(0.5 * std::f64::consts::SQRT_2, period, 1000.0).unwrap();
+        let energy = tuned.annual_energy(&[state, state], depth).unwrap();
+        assert!((energy - 2.0 * response.absorbed_power * 1000.0 * 3600.0).abs() < 1e-6 * energy);
+
+        assert!(tuned.response(0.5, period, 0.5).is_err());
+        assert!(WaveEnergyConverter::new(ConverterKind::OscillatingWaterColumn, 0.0, 0.0, 1.0).is_err());
+    }
+}
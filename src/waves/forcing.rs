@@ -0,0 +1,603 @@
+use crate::waves::{ActiveAbsorber, BoundaryApplicator, StreamFunctionWave, WaveParameters};
+
+/// Kind of wave generation source
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SourceKind {
+    /// Wavemaker at the channel end, radiating in the positive x-direction only
+    Boundary,
+    /// Internal source region, radiating waves in both directions
+    Internal,
+}
+
+impl std::fmt::Display for SourceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SourceKind::Boundary => write!(f, "Boundary"),
+            SourceKind::Internal => write!(f, "Internal"),
+        }
+    }
+}
+
+/// A single wave generation source with independent parameters and phase
+pub struct WaveSource {
+    /// Boundary applicator computing the generated signal at the source position
+    applicator: BoundaryApplicator,
+    /// Radiation pattern of the source
+    kind: SourceKind,
+    /// Time at which the source starts emitting [s]
+    start_time: f64,
+    /// Duration over which waves are emitted [s]
+    generation_duration: f64,
+    /// Steady nonlinear wave emitted instead of the linear signal, if any
+    profile: Option<StreamFunctionWave>,
+    /// Direction of propagation from the x axis, anticlockwise [rad]; the channel only uses waves along its axis
+    direction: f64,
+}
+
+impl WaveSource {
+    /// Create a boundary source at x = 0
+    pub fn boundary(params: WaveParameters) -> Self {
+        Self {
+            applicator: BoundaryApplicator::new(params),
+            kind: SourceKind::Boundary,
+            start_time: 0.0,
+            generation_duration: f64::INFINITY,
+            profile: None,
+            direction: 0.0,
+        }
+    }
+
+    /// Create an internal source at the given position
+    pub fn internal(params: WaveParameters, position: f64) -> Self {
+        let mut applicator = BoundaryApplicator::new(params);
+        applicator.set_generation_position(position);
+        Self {
+            applicator,
+            kind: SourceKind::Internal,
+            start_time: 0.0,
+            generation_duration: f64::INFINITY,
+            profile: None,
+            direction: 0.0,
+        }
+    }
+
+    /// Set phase offset [rad]
+    pub fn with_phase(mut self, phase: f64) -> Self {
+        self.applicator.set_phase_offset(phase);
+        self
+    }
+
+    /// Set time at which the source starts emitting [s]
+    pub fn with_start_time(mut self, time: f64) -> Self {
+        self.start_time = time;
+        self
+    }
+
+    /// Set duration over which waves are emitted [s]
+    pub fn with_generation_duration(mut self, duration: f64) -> Self {
+        self.generation_duration = duration;
+        self
+    }
+
+    /// Set direction of propagation from the x axis, anticlockwise [rad]
+    pub fn with_direction(mut self, direction: f64) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Get direction of propagation from the x axis [rad]
+    pub fn direction(&self) -> f64 {
+        self.direction
+    }
+
+    /// Emit a stream function wave instead of the linear signal, travelling at its nonlinear celerity
+    pub fn with_stream_function(mut self, wave: StreamFunctionWave) -> Self {
+        self.applicator.update_parameters(wave.parameters());
+        self.profile = Some(wave);
+        self
+    }
+
+    /// Get the stream function wave emitted, if any
+    pub fn stream_function(&self) -> Option<&StreamFunctionWave> {
+        self.profile.as_ref()
+    }
+
+    /// Get source kind
+    pub fn kind(&self) -> SourceKind {
+        self.kind
+    }
+
+    /// Get source position [m]
+    pub fn position(&self) -> f64 {
+        self.applicator.generation_position()
+    }
+
+    /// Get start of the emission [s]
+    pub fn start_time(&self) -> f64 {
+        self.start_time
+    }
+
+    /// Get generation duration [s]
+    pub fn generation_duration(&self) -> f64 {
+        self.generation_duration
+    }
+
+    /// Get end of the emission [s]
+    pub fn end_time(&self) -> f64 {
+        self.start_time + self.generation_duration
+    }
+
+    /// Check if the source emits at a time
+    fn emits_at(&self, time: f64) -> bool {
+        time >= self.start_time && time <= self.end_time()
+    }
+
+    /// Get underlying boundary applicator
+    pub fn applicator(&self) -> &BoundaryApplicator {
+        &self.applicator
+    }
+
+    /// Get mutable underlying boundary applicator
+    pub fn applicator_mut(&mut self) -> &mut BoundaryApplicator {
+        &mut self.applicator
+    }
+
+    /// Get wave parameters
+    pub fn parameters(&self) -> &WaveParameters {
+        self.applicator.parameters()
+    }
+
+    /// Compute the surface elevation radiated by this source at position x
+    /// Waves leave the source at phase speed c and exist only for the emitted window
+    pub fn surface_elevation(&self, x: f64) -> f64 {
+        if !self.applicator.is_enabled() {
+            return 0.0;
+        }
+
+        let distance = x - self.position();
+        if self.kind == SourceKind::Boundary && distance < 0.0 {
+            return 0.0;
+        }
+
+        let params = self.parameters();
+        let travel_distance = distance.abs();
+        let time = self.applicator.current_time();
+
+        // Time at which the wave now at x left the source
+        let emission_time = time - travel_distance / params.c;
+        if emission_time < 0.0 || !self.emits_at(emission_time) {
+            return 0.0;
+        }
+
+        if let Some(wave) = &self.profile {
+            return wave.surface_elevation(self.position() + travel_distance, self.signal_time());
+        }
+        let source_phase = params.k * self.position() - params.omega * time + self.applicator.phase_offset();
+        params.amplitude() * (source_phase + params.k * travel_distance).cos()
+    }
+
+    /// Target linear surface elevation at the source at the current time, with the signal delayed by a phase [m]
+    /// A wavemaker of paddles along y delays each by ky y to send the waves at an angle (snake principle)
+    pub fn shifted_surface_elevation(&self, phase: f64) -> f64 {
+        if !self.applicator.is_enabled() {
+            return 0.0;
+        }
+        let params = self.parameters();
+        params.amplitude() * (params.k * self.position() - params.omega * self.applicator.current_time() + self.applicator.phase_offset() + phase).cos()
+    }
+
+    /// Target depth-averaged velocity at the source at the current time [m/s]
+    pub fn boundary_velocity(&self) -> f64 {
+        match &self.profile {
+            Some(wave) if self.applicator.is_enabled() => wave.depth_averaged_velocity(self.position(), self.signal_time()),
+            Some(_) => 0.0,
+            None => self.applicator.boundary_velocity(),
+        }
+    }
+
+    /// Target surface elevation at the source at the current time [m]
+    pub fn boundary_surface_elevation(&self) -> f64 {
+        match &self.profile {
+            Some(wave) if self.applicator.is_enabled() => wave.surface_elevation(self.position(), self.signal_time()),
+            Some(_) => 0.0,
+            None => self.applicator.boundary_surface_elevation(),
+        }
+    }
+
+    /// Target velocity averaged over one of equal layers of the water column at the source, numbered from
+    /// the surface, for a stream function wave whose profile departs from the linear one [m/s]
+    pub fn layer_velocity(&self, layer: usize, layers: usize) -> Option<f64> {
+        let wave = self.profile.as_ref()?;
+        Some(if self.applicator.is_enabled() { wave.layer_velocity(self.position(), self.signal_time(), layer, layers) } else { 0.0 })
+    }
+
+    /// Time of the signal, shifted by the phase offset so that kx - ωt gains +φ
+    fn signal_time(&self) -> f64 {
+        self.applicator.current_time() - self.applicator.phase_offset() / self.parameters().omega
+    }
+
+    /// Check if the source emits waves at the current time
+    pub fn is_generating(&self) -> bool {
+        self.applicator.current_time() >= self.start_time && self.applicator.should_generate_waves(self.end_time())
+    }
+}
+
+/// Aggregator managing every wave generation source along the channel
+#[derive(Default)]
+pub struct WaveForcing {
+    /// Wave generation sources
+    sources: Vec<WaveSource>,
+    /// Mean water level above the still water level, e.g. a storm surge [m]
+    water_level: f64,
+}
+
+impl WaveForcing {
+    /// Create empty wave forcing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create wave forcing with a single boundary source
+    pub fn single(params: WaveParameters) -> Self {
+        let mut forcing = Self::new();
+        forcing.add_source(WaveSource::boundary(params));
+        forcing
+    }
+
+    /// Add a source and return its index
+    pub fn add_source(&mut self, source: WaveSource) -> usize {
+        self.sources.push(source);
+        self.sources.len() - 1
+    }
+
+    /// Remove the source at the given index
+    pub fn remove_source(&mut self, index: usize) -> Option<WaveSource> {
+        if index < self.sources.len() {
+            Some(self.sources.remove(index))
+        } else {
+            None
+        }
+    }
+
+    /// Get all sources
+    pub fn sources(&self) -> &[WaveSource] {
+        &self.sources
+    }
+
+    /// Get mutable access to all sources
+    pub fn sources_mut(&mut self) -> &mut [WaveSource] {
+        &mut self.sources
+    }
+
+    /// Number of sources
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// Check if there are no sources
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Set mean water level above the still water level [m]
+    pub fn set_water_level(&mut self, level: f64) {
+        self.water_level = level;
+    }
+
+    /// Get mean water level above the still water level [m]
+    pub fn water_level(&self) -> f64 {
+        self.water_level
+    }
+
+    /// Update simulation time of every source
+    pub fn update_time(&mut self, time: f64) {
+        for source in &mut self.sources {
+            source.applicator.update_time(time);
+        }
+    }
+
+    /// Advance every source by one time step
+    pub fn advance_time(&mut self, dt: f64) {
+        for source in &mut self.sources {
+            source.applicator.advance_time(dt);
+        }
+    }
+
+    /// Reset every source to its initial state
+    pub fn reset(&mut self) {
+        for source in &mut self.sources {
+            source.applicator.reset();
+        }
+    }
+
+    /// Compute the superposed surface elevation of all sources at position x, on the mean water level
+    pub fn surface_elevation(&self, x: f64) -> f64 {
+        self.water_level + self.sources.iter().map(|source| source.surface_elevation(x)).sum::<f64>()
+    }
+
+    /// Compute the superposed surface elevation along a series of positions
+    pub fn surface_elevation_series(&self, x_points: &[f64]) -> Vec<f64> {
+        x_points.iter().map(|&x| self.surface_elevation(x)).collect()
+    }
+
+    /// Check if any source is still emitting waves
+    pub fn is_generating(&self) -> bool {
+        self.sources.iter().any(|source| source.is_generating())
+    }
+
+    /// Combined target velocity of the boundary sources still generating [m/s]
+    pub fn boundary_velocity(&self) -> f64 {
+        self.active_boundary_sources().map(|source| source.boundary_velocity()).sum()
+    }
+
+    /// Combined target elevation of the boundary sources still generating, on the mean water level [m]
+    pub fn boundary_surface_elevation(&self) -> f64 {
+        self.water_level + self.active_boundary_sources().map(|source| source.boundary_surface_elevation()).sum::<f64>()
+    }
+
+    /// Boundary sources generating at the current time
+    pub fn active_boundary_sources(&self) -> impl Iterator<Item = &WaveSource> {
+        self.sources
+            .iter()
+            .filter(|source| source.kind == SourceKind::Boundary && source.emits_at(source.applicator.current_time()))
+    }
+
+    /// Prescribe the combined boundary velocity corrected by active absorption
+    /// The elevation at the first grid point is left free and used as the measurement
+    pub fn apply_absorbing_boundary_conditions(&self, velocities: &mut [f64], surface_elevations: &[f64], absorber: &mut ActiveAbsorber) {
+        if velocities.is_empty() || surface_elevations.is_empty() {
+            return;
+        }
+
+        velocities[0] = absorber.corrected_velocity(
+            self.boundary_velocity(),
+            surface_elevations[0],
+            self.boundary_surface_elevation(),
+        );
+    }
+
+    /// Apply all sources to a 1D grid with spacing dx
+    /// Boundary sources prescribe the first grid point, internal sources add their signal at their grid point
+    pub fn apply_boundary_conditions(&self, velocities: &mut [f64], surface_elevations: &mut [f64], dx: f64) {
+        if velocities.is_empty() || surface_elevations.is_empty() || dx <= 0.0 {
+            return;
+        }
+
+        for source in &self.sources {
+            match source.kind {
+                SourceKind::Boundary if source.profile.is_some() => {
+                    velocities[0] = source.boundary_velocity();
+                    surface_elevations[0] = source.boundary_surface_elevation();
+                }
+                SourceKind::Boundary => {
+                    source.applicator.apply_boundary_conditions(velocities, surface_elevations);
+                }
+                SourceKind::Internal => {
+                    let index = (source.position() / dx).round() as usize;
+                    if index < velocities.len() && index < surface_elevations.len() {
+                        velocities[index] += source.boundary_velocity();
+                        surface_elevations[index] += source.boundary_surface_elevation();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::{Length, Time};
+    use crate::waves::dispersion::DispersionSolver;
+
+    fn create_test_parameters() -> WaveParameters {
+        let solver = DispersionSolver::new();
+        solver.solve_wave_parameters(Length::meters(0.5), Time::seconds(4.0), Length::meters(2.0)).unwrap()
+    }
+
+    #[test]
+    fn test_wave_forcing_creation() {
+        let forcing = WaveForcing::new();
+        assert!(forcing.is_empty());
+
+        let forcing = WaveForcing::single(create_test_parameters());
+        assert_eq!(forcing.len(), 1);
+        assert_eq!(forcing.sources()[0].kind(), SourceKind::Boundary);
+        assert_eq!(forcing.sources()[0].position(), 0.0);
+    }
+
+    #[test]
+    fn test_add_remove_sources() {
+        let mut forcing = WaveForcing::single(create_test_parameters());
+        let index = forcing.add_source(WaveSource::internal(create_test_parameters(), 20.0));
+        assert_eq!(index, 1);
+        assert_eq!(forcing.len(), 2);
+
+        let removed = forcing.remove_source(1).unwrap();
+        assert_eq!(removed.kind(), SourceKind::Internal);
+        assert_eq!(removed.position(), 20.0);
+        assert!(forcing.remove_source(5).is_none());
+    }
+
+    #[test]
+    fn test_boundary_source_matches_applicator() {
+        let mut forcing = WaveForcing::single(create_test_parameters());
+        forcing.update_time(1.3);
+
+        // At the source position the radiated field equals the boundary signal
+        let source = &forcing.sources()[0];
+        let expected = source.applicator().boundary_surface_elevation();
+        assert!((forcing.surface_elevation(0.0) - expected).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_boundary_source_radiates_forward_only() {
+        let mut forcing = WaveForcing::single(create_test_parameters());
+        forcing.update_time(10.0);
+
+        assert_eq!(forcing.surface_elevation(-1.0), 0.0);
+
+        // Waves have not yet reached a point beyond c * t
+        let c = forcing.sources()[0].parameters().c;
+        assert_eq!(forcing.surface_elevation(c * 10.0 + 1.0), 0.0);
+    }
+
+    #[test]
+    fn test_internal_source_radiates_symmetrically() {
+        let mut forcing = WaveForcing::new();
+        forcing.add_source(WaveSource::internal(create_test_parameters(), 25.0));
+        forcing.update_time(8.0);
+
+        for offset in [1.0, 3.5, 7.0] {
+            let left = forcing.surface_elevation(25.0 - offset);
+            let right = forcing.surface_elevation(25.0 + offset);
+            assert!((left - right).abs() < 1e-10, "Asymmetric radiation at offset {}", offset);
+        }
+    }
+
+    #[test]
+    fn test_generation_duration() {
+        let params = create_test_parameters();
+        let period = params.period;
+        let mut forcing = WaveForcing::new();
+        forcing.add_source(WaveSource::boundary(params).with_generation_duration(2.0 * period));
+
+        // Long after generation stopped, the region near the source is calm again
+        forcing.update_time(10.0 * period);
+        assert_eq!(forcing.surface_elevation(0.0), 0.0);
+        assert!(!forcing.is_generating());
+    }
+
+    #[test]
+    fn test_superposition_of_opposite_phases() {
+        let params = create_test_parameters();
+        let mut forcing = WaveForcing::new();
+        forcing.add_source(WaveSource::boundary(params.clone()));
+        forcing.add_source(WaveSource::boundary(params).with_phase(std::f64::consts::PI));
+        forcing.update_time(5.0);
+
+        // Two identical sources in antiphase cancel everywhere
+        for x in [0.0, 2.0, 5.0, 10.0] {
+            assert!(forcing.surface_elevation(x).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_combined_boundary_signal() {
+        let params = create_test_parameters();
+        let mut forcing = WaveForcing::single(params.clone());
+        forcing.add_source(WaveSource::boundary(params.clone()));
+        forcing.add_source(WaveSource::internal(params, 10.0));
+        forcing.update_time(0.4);
+
+        // Only the two boundary sources contribute at the wavemaker
+        let single = forcing.sources()[0].applicator();
+        assert!((forcing.boundary_velocity() - 2.0 * single.boundary_velocity()).abs() < 1e-12);
+        assert!((forcing.boundary_surface_elevation() - 2.0 * single.boundary_surface_elevation()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_apply_absorbing_boundary_conditions() {
+        let params = create_test_parameters();
+        let mut absorber = ActiveAbsorber::new(params.d).unwrap();
+        let mut forcing = WaveForcing::single(params);
+        forcing.update_time(0.9);
+
+        let mut velocities = vec![0.0; 5];
+        let elevations = vec![forcing.boundary_surface_elevation(); 5];
+        forcing.apply_absorbing_boundary_conditions(&mut velocities, &elevations, &mut absorber);
+        assert!((velocities[0] - forcing.boundary_velocity()).abs() < 1e-12);
+        assert_eq!(absorber.reflected_elevation(), 0.0);
+    }
+
+    #[test]
+    fn test_boundary_signal_stops_after_generation() {
+        let params = create_test_parameters();
+        let mut forcing = WaveForcing::new();
+        forcing.add_source(WaveSource::boundary(params).with_generation_duration(4.0));
+
+        forcing.update_time(2.0);
+        assert!(forcing.boundary_velocity() != 0.0);
+
+        forcing.update_time(4.5);
+        assert_eq!(forcing.boundary_velocity(), 0.0);
+        assert_eq!(forcing.boundary_surface_elevation(), 0.0);
+    }
+
+    #[test]
+    fn test_delayed_source() {
+        let params = create_test_parameters();
+        let mut forcing = WaveForcing::new();
+        forcing.add_source(WaveSource::boundary(params.clone()).with_start_time(10.0).with_generation_duration(4.0));
+        assert_eq!(forcing.sources()[0].end_time(), 14.0);
+
+        forcing.update_time(9.0);
+        assert_eq!(forcing.boundary_velocity(), 0.0);
+        assert_eq!(forcing.surface_elevation(0.0), 0.0);
+        forcing.update_time(12.3);
+        assert!(forcing.boundary_velocity() != 0.0);
+        assert!(forcing.sources()[0].is_generating());
+
+        // The front of the delayed train has travelled c·(t - start)
+        let front = params.c * 2.3;
+        assert_eq!(forcing.surface_elevation(front + 1.0), 0.0);
+        forcing.update_time(15.0);
+        assert!(!forcing.sources()[0].is_generating());
+    }
+
+    #[test]
+    fn test_apply_boundary_conditions() {
+        let params = create_test_parameters();
+        let mut forcing = WaveForcing::single(params.clone());
+        forcing.add_source(WaveSource::internal(params, 5.0));
+        forcing.update_time(0.7);
+
+        let mut velocities = vec![0.0; 11];
+        let mut elevations = vec![0.0; 11];
+        forcing.apply_boundary_conditions(&mut velocities, &mut elevations, 1.0);
+
+        let boundary = forcing.sources()[0].applicator();
+        let internal = forcing.sources()[1].applicator();
+        assert_eq!(velocities[0], boundary.boundary_velocity());
+        assert_eq!(elevations[0], boundary.boundary_surface_elevation());
+        assert_eq!(velocities[5], internal.boundary_velocity());
+        assert_eq!(elevations[5], internal.boundary_surface_elevation());
+        assert_eq!(elevations[3], 0.0);
+    }
+
+    #[test]
+    fn test_stream_function_source() {
+        let wave = crate::waves::StreamFunctionSolver::new().solve(Length::meters(0.4), Time::seconds(4.0), Length::meters(2.0)).unwrap();
+        let mut forcing = WaveForcing::new();
+        forcing.add_source(WaveSource::boundary(create_test_parameters()).with_stream_function(wave.clone()));
+        forcing.update_time(0.0);
+
+        // The crest leaves the wavemaker at time zero and travels at the nonlinear celerity
+        let source = &forcing.sources()[0];
+        assert_eq!(source.parameters().c, wave.celerity());
+        assert!((forcing.boundary_surface_elevation() - wave.crest_elevation()).abs() < 1e-12);
+        assert!((forcing.boundary_velocity() - wave.depth_averaged_velocity(0.0, 0.0)).abs() < 1e-12);
+        let layers: f64 = (0..3).map(|layer| source.layer_velocity(layer, 3).unwrap()).sum::<f64>() / 3.0;
+        assert!((layers - forcing.boundary_velocity()).abs() < 1e-12);
+        forcing.update_time(10.0);
+        let x = 10.0 * wave.celerity() - 2.0;
+        assert!((forcing.surface_elevation(x) - wave.surface_elevation(x, 10.0)).abs() < 1e-12);
+        assert!(WaveSource::boundary(create_test_parameters()).layer_velocity(0, 3).is_none());
+    }
+
+    #[test]
+    fn test_shifted_surface_elevation() {
+        let mut forcing = WaveForcing::new();
+        forcing.add_source(WaveSource::boundary(create_test_parameters()).with_phase(0.3).with_direction(0.5));
+        forcing.update_time(1.7);
+        let source = &forcing.sources()[0];
+        assert_eq!(source.direction(), 0.5);
+        assert!((source.shifted_surface_elevation(0.0) - source.boundary_surface_elevation()).abs() < 1e-12);
+        // Delaying the signal by a phase shows it as it was a fraction of a period earlier
+        let params = source.parameters();
+        let mut forcing = WaveForcing::new();
+        forcing.add_source(WaveSource::boundary(params.clone()).with_phase(0.3));
+        forcing.update_time(1.7 - 0.4 / params.omega);
+        assert!((source.shifted_surface_elevation(0.4) - forcing.boundary_surface_elevation()).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::waves::wavemaker::linear_wave_number;
+
+/// Density of sea water [kg/m³]
+pub const WATER_DENSITY: f64 = 1025.0;
+
+/// Design wave pressures on a vertical wall after Goda (1974, 2000)
+///
+/// The pressure is p₁ at the still water level, decreasing linearly to zero at the height
+/// η* = 1.5 H reached by the waves and to p₃ = α₃ p₁ at the bed, with p₁ = (α₁ + α₂) ρg H.
+/// The wall stands on the bed, without a rubble mound, and the waves arrive at normal incidence,
+/// so the depth in front of the wall is also the depth of its base.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GodaPressure {
+    /// Water depth at the wall (h) [m]
+    depth: f64,
+    /// Crest height of the wall above the still water level (h_c) [m]
+    crest_freeboard: f64,
+    /// Height above the still water level reached by the pressure (η*) [m]
+    reach: f64,
+    /// Pressure at the still water level (p₁) [Pa]
+    still_water_pressure: f64,
+    /// Pressure at the bed (p₃) [Pa]
+    bed_pressure: f64,
+}
+
+impl GodaPressure {
+    /// Design pressures for the highest wave H_max of period T in front of a wall in depth h,
+    /// with the depth h_b at five significant wave heights seaward of the wall
+    pub fn new(wave_height: f64, wave_period: f64, depth: f64, breaking_depth: f64, crest_freeboard: f64) -> Result<Self, String> {
+        if wave_height <= 0.0 || wave_period <= 0.0 {
+            return Err("Wave height and period must be positive".to_string());
+        }
+        if depth <= 0.0 || breaking_depth <= 0.0 {
+            return Err("The wall must stand in water".to_string());
+        }
+        let omega = 2.0 * std::f64::consts::PI / wave_period;
+        let kh = linear_wave_number(omega, depth, 9.81)? * depth;
+
+        // α₁ from the wave length, α₂ from the steepening over the approach, α₃ from the depth attenuation
+        let alpha_1 = 0.6 + 0.5 * (2.0 * kh / (2.0 * kh).sinh()).powi(2);
+        let alpha_2 = ((breaking_depth - depth) / (3.0 * breaking_depth) * (wave_height / depth).powi(2))
+            .min(2.0 * depth / wave_height)
+            .max(0.0);
+        let alpha_3 = 1.0 / kh.cosh();
+        let still_water_pressure = (alpha_1 + alpha_2) * WATER_DENSITY * 9.81 * wave_height;
+
+        Ok(Self {
+            depth,
+            crest_freeboard,
+            reach: 1.5 * wave_height,
+            still_water_pressure,
+            bed_pressure: alpha_3 * still_water_pressure,
+        })
+    }
+
+    /// Pressure at the still water level (p₁) [Pa]
+    pub fn still_water_pressure(&self) -> f64 {
+        self.still_water_pressure
+    }
+
+    /// Pressure at the bed (p₃) [Pa]
+    pub fn bed_pressure(&self) -> f64 {
+        self.bed_pressure
+    }
+
+    /// Height above the still water level where the pressure vanishes (η*) [m]
+    pub fn reach(&self) -> f64 {
+        self.reach
+    }
+
+    /// Top of the loaded part of the wall, its crest or η* [m]
+    fn top(&self) -> f64 {
+        self.reach.min(self.crest_freeboard)
+    }
+
+    /// Design pressure at a height above the still water level, zero off the wall [Pa]
+    pub fn pressure_at(&self, z: f64) -> f64 {
+        if z < -self.depth || z > self.top() {
+            0.0
+        } else if z <= 0.0 {
+            self.bed_pressure + (self.still_water_pressure - self.bed_pressure) * (z + self.depth) / self.depth
+        } else {
+            self.still_water_pressure * (1.0 - z / self.reach)
+        }
+    }
+
+    /// Pressure distribution over the wall as (pressure [Pa], height above the still water level [m])
+    pub fn profile(&self) -> Vec<[f64; 2]> {
+        [-self.depth, 0.0, self.top()].iter().map(|&z| [self.pressure_at(z), z]).collect()
+    }
+
+    /// Horizontal force per unit length of wall, the area of the pressure distribution [N/m]
+    pub fn horizontal_force(&self) -> f64 {
+        let top = self.top();
+        0.5 * (self.bed_pressure + self.still_water_pressure) * self.depth + 0.5 * (self.still_water_pressure + self.pressure_at(top)) * top.max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_goda_pressures() {
+        // Deep enough that α₂ vanishes on a flat approach: p₁ = α₁ ρgH
+        let goda = GodaPressure::new(2.0, 8.0, 10.0, 10.0, 5.0).unwrap();
+        let kh = linear_wave_number(2.0 * std::f64::consts::PI / 8.0, 10.0, 9.81).unwrap() * 10.0;
+        let alpha_1 = 0.6 + 0.5 * (2.0 * kh / (2.0 * kh).sinh()).powi(2);
+        assert!((goda.still_water_pressure() - alpha_1 * WATER_DENSITY * 9.81 * 2.0).abs() < 1e-6);
+        assert!((goda.bed_pressure() - goda.still_water_pressure() / kh.cosh()).abs() < 1e-6);
+        assert_eq!(goda.reach(), 3.0);
+        assert!((goda.pressure_at(1.5) - 0.5 * goda.still_water_pressure()).abs() < 1e-6);
+        assert_eq!(goda.pressure_at(3.5), 0.0);
+        assert_eq!(goda.pressure_at(-11.0), 0.0);
+
+        // Force: trapezoid below the still water level and triangle above it
+        let expected = 0.5 * (goda.bed_pressure() + goda.still_water_pressure()) * 10.0 + 0.5 * goda.still_water_pressure() * 3.0;
+        assert!((goda.horizontal_force() - expected).abs() < 1e-6);
+
+        // A low crest cuts the distribution, a shoaling approach raises the pressure
+        let low = GodaPressure::new(2.0, 8.0, 10.0, 10.0, 1.0).unwrap();
+        assert_eq!(low.profile().last().unwrap()[1], 1.0);
+        assert!(low.horizontal_force() < goda.horizontal_force());
+        let shoaling = GodaPressure::new(2.0, 8.0, 4.0, 6.0, 5.0).unwrap();
+        let flat = GodaPressure::new(2.0, 8.0, 4.0, 4.0, 5.0).unwrap();
+        assert!(shoaling.still_water_pressure() > flat.still_water_pressure());
+        assert!(GodaPressure::new(2.0, 8.0, 0.0, 4.0, 5.0).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+use super::schedule::WaveWindow;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Sea state and surge at a time of the storm
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HydrographPoint {
+    pub time: f64,                     // Time from the start of the run [s]
+    pub significant_wave_height: f64,  // Hs [m]
+    pub peak_period: f64,              // Tp [s]
+    pub surge: f64,                    // Water level above the still water level [m]
+}
+
+/// Storm hydrograph: significant wave height, peak period and surge through time
+///
+/// Values are interpolated linearly between the points and held beyond the ends. The wavemaker
+/// generates regular waves of the same energy as the sea state, H = Hs/√2 at the period Tp,
+/// updated at a fixed interval with a continuous phase, and the water level follows the surge.
+/// Files are CSV or whitespace-separated text with the columns time, Hs, Tp and surge; lines
+/// starting with '#' and a header line are skipped.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StormHydrograph {
+    pub points: Vec<HydrographPoint>, // By increasing time
+    pub update_interval: f64,         // Time between updates of the generated waves [s]
+}
+
+impl StormHydrograph {
+    /// Create new hydrograph from its points, sorted by time
+    pub fn new(mut points: Vec<HydrographPoint>, update_interval: f64) -> Result<Self, String> {
+        points.sort_by(|a, b| a.time.total_cmp(&b.time));
+        let hydrograph = Self { points, update_interval };
+        hydrograph.validate()?;
+        Ok(hydrograph)
+    }
+
+    /// Storm rising from calm to a peak and back with a cosine-squared shape
+    /// Calm conditions are a quarter of the peak wave height and 70% of the peak period, without surge
+    pub fn synthetic(duration: f64, peak_height: f64, peak_period: f64, peak_surge: f64) -> Self {
+        let samples = 12;
+        let points = (0..=samples)
+            .map(|i| {
+                let time = duration * i as f64 / samples as f64;
+                let intensity = (std::f64::consts::PI * time / duration).sin().powi(2);
+                HydrographPoint {
+                    time,
+                    significant_wave_height: peak_height * (0.25 + 0.75 * intensity),
+                    peak_period: peak_period * (0.7 + 0.3 * intensity),
+                    surge: peak_surge * intensity,
+                }
+            })
+            .collect();
+        Self {
+            points,
+            update_interval: 2.0 * peak_period,
+        }
+    }
+
+    /// Read a hydrograph file
+    pub fn load(path: &Path, update_interval: f64) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        Self::parse(&content, update_interval)
+    }
+
+    /// Hydrograph from the content of a CSV file with time, Hs, Tp and surge columns
+    pub fn parse(content: &str, update_interval: f64) -> Result<Self, String> {
+        let mut points = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let values: Result<Vec<f64>, _> = line
+                .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+                .filter(|field| !field.is_empty())
+                .map(str::parse::<f64>)
+                .collect();
+            match values {
+                Ok(values) if values.len() >= 4 => points.push(HydrographPoint {
+                    time: values[0],
+                    significant_wave_height: values[1],
+                    peak_period: values[2],
+                    surge: values[3],
+                }),
+                Ok(_) => return Err(format!("Line {} needs time, Hs, Tp and surge columns", number + 1)),
+                // Column names before the first point
+                Err(_) if points.is_empty() => continue,
+                Err(e) => return Err(format!("Invalid number on line {}: {}", number + 1, e)),
+            }
+        }
+        Self::new(points, update_interval)
+    }
+
+    /// Check that the hydrograph spans a positive time with positive wave heights and periods
+    pub fn validate(&self) -> Result<(), String> {
+        if self.points.len() < 2 {
+            return Err("Hydrograph needs at least two points".to_string());
+        }
+        if self.points[0].time < 0.0 || self.duration() <= 0.0 {
+            return Err("Hydrograph times must start at or after 0 s and span a positive duration".to_string());
+        }
+        for point in &self.points {
+            let values = [point.time, point.significant_wave_height, point.peak_period, point.surge];
+            if values.iter().any(|value| !value.is_finite()) {
+                return Err(format!("Hydrograph values at {:.1} s must be finite", point.time));
+            }
+            if point.significant_wave_height <= 0.0 || point.peak_period <= 0.0 {
+                return Err(format!("Hs and Tp at {:.1} s must be positive", point.time));
+            }
+        }
+        if !(self.update_interval.is_finite() && self.update_interval > 0.0) {
+            return Err("Update interval must be positive".to_string());
+        }
+        Ok(())
+    }
+
+    /// Time of the last point, when the storm ends [s]
+    pub fn duration(&self) -> f64 {
+        self.points.last().map_or(0.0, |point| point.time)
+    }
+
+    /// Sea state and surge interpolated at a time
+    pub fn at(&self, time: f64) -> HydrographPoint {
+        let index = self.points.partition_point(|point| point.time < time);
+        if index == 0 {
+            return HydrographPoint { time, ..self.points[0] };
+        }
+        if index == self.points.len() {
+            return HydrographPoint { time, ..self.points[index - 1] };
+        }
+        let (a, b) = (self.points[index - 1], self.points[index]);
+        let weight = (time - a.time) / (b.time - a.time);
+        let blend = |x: f64, y: f64| x + (y - x) * weight;
+        HydrographPoint {
+            time,
+            significant_wave_height: blend(a.significant_wave_height, b.significant_wave_height),
+            peak_period: blend(a.peak_period, b.peak_period),
+            surge: blend(a.surge, b.surge),
+        }
+    }
+
+    /// Generation windows of the regular waves following the sea state, from the start of the run to the end of the storm
+    pub fn windows(&self) -> Vec<WaveWindow> {
+        let duration = self.duration();
+        let count = (duration / self.update_interval).ceil().max(1.0) as usize;
+        (0..count)
+            .map(|i| {
+                let start = i as f64 * self.update_interval;
+                let end = (start + self.update_interval).min(duration);
+                let state = self.at(0.5 * (start + end));
+                WaveWindow {
+                    start,
+                    duration: end - start,
+                    wave_height: state.significant_wave_height / std::f64::consts::SQRT_2,
+                    wave_period: state.peak_period,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_interpolate() {
+        let hydrograph = StormHydrograph::parse("time,Hs,Tp,surge\n0,0.1,2,0\n# peak\n100;0.3;3;0.2\n", 10.0).unwrap();
+        assert_eq!(hydrograph.duration(), 100.0);
+        let middle = hydrograph.at(25.0);
+        assert!((middle.significant_wave_height - 0.15).abs() < 1e-12);
+        assert!((middle.peak_period - 2.25).abs() < 1e-12);
+        assert!((middle.surge - 0.05).abs() < 1e-12);
+        assert_eq!(hydrograph.at(150.0).surge, 0.2);
+
+        assert!(StormHydrograph::parse("0,0.1,2\n", 10.0).is_err());
+        assert!(StormHydrograph::parse("0,0.1,2,0\n10,0,2,0\n", 10.0).is_err());
+    }
+
+    #[test]
+    fn test_windows_follow_the_storm() {
+        let hydrograph = StormHydrograph::synthetic(120.0, 0.2, 3.0, 0.1);
+        assert!(hydrograph.validate().is_ok());
+        let windows = hydrograph.windows();
+        assert_eq!(windows.len(), 20);
+        assert_eq!(windows.last().unwrap().end(), 120.0);
+        // Energy-equivalent regular waves, highest and longest at the peak
+        let peak = windows.iter().max_by(|a, b| a.wave_height.total_cmp(&b.wave_height)).unwrap();
+        assert!((peak.start - 60.0).abs() <= 6.0);
+        assert!(peak.wave_height < 0.2 / std::f64::consts::SQRT_2 && peak.wave_height > 0.9 * 0.2 / std::f64::consts::SQRT_2);
+        assert!(peak.wave_period > windows[0].wave_period);
+        assert!(hydrograph.at(60.0).surge > 0.099);
+    }
+}
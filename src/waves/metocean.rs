@@ -0,0 +1,259 @@
+use crate::waves::climate::table_fields;
+use crate::waves::spectrum::WaveSpectrum;
+use crate::waves::swan::parse_swan;
+use std::path::Path;
+
+/// Peak enhancement of the JONSWAP spectra built from parametric sea states
+pub const JONSWAP_GAMMA: f64 = 3.3;
+
+/// Value NDBC writes for missing spectral densities
+const NDBC_MISSING: f64 = 999.0;
+
+/// Spectrum measured or hindcast at a time
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpectrumRecord {
+    pub time: String,           // Date and time as written in the file
+    pub spectrum: WaveSpectrum, // Directional-integrated variance density
+}
+
+/// Met-ocean data file holding wave spectra or the sea states to build them from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetoceanFormat {
+    /// NDBC historical spectral wave density (swden): a header of frequencies, then a row of densities per time
+    NdbcSpectral,
+    /// NDBC realtime spectral file (.data_spec): every row pairs the densities with their frequencies in brackets
+    NdbcRealtime,
+    /// ERA5 extract in CSV with the significant wave height (swh) and the peak (pp1d) or mean (mwp) wave period
+    Era5Csv,
+    /// SWAN spectral output (.sp1 or .sp2) at one or more locations, for one or more times
+    Swan,
+}
+
+impl std::fmt::Display for MetoceanFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetoceanFormat::NdbcSpectral => write!(f, "NDBC spectral density"),
+            MetoceanFormat::NdbcRealtime => write!(f, "NDBC realtime spectra"),
+            MetoceanFormat::Era5Csv => write!(f, "ERA5 CSV"),
+            MetoceanFormat::Swan => write!(f, "SWAN spectral"),
+        }
+    }
+}
+
+impl MetoceanFormat {
+    /// Recognize the format from the content of a file
+    pub fn detect(content: &str) -> Self {
+        let mut lines = content.lines().map(str::trim).filter(|line| !line.is_empty());
+        let header = lines.next().unwrap_or_default().to_lowercase();
+        if header.starts_with("swan") {
+            MetoceanFormat::Swan
+        } else if table_fields(&header).any(|name| name == "swh") {
+            MetoceanFormat::Era5Csv
+        } else if content.lines().any(|line| !line.trim_start().starts_with('#') && line.contains('(')) {
+            MetoceanFormat::NdbcRealtime
+        } else {
+            MetoceanFormat::NdbcSpectral
+        }
+    }
+
+    /// Spectra from the content of a file of this format
+    pub fn parse(&self, content: &str) -> Result<Vec<SpectrumRecord>, String> {
+        let records = match self {
+            MetoceanFormat::NdbcSpectral => parse_ndbc_spectral(content)?,
+            MetoceanFormat::NdbcRealtime => parse_ndbc_realtime(content)?,
+            MetoceanFormat::Era5Csv => parse_era5_csv(content)?,
+            MetoceanFormat::Swan => parse_swan(content)?,
+        };
+        if records.is_empty() {
+            return Err(format!("No complete spectrum in the {} file", self));
+        }
+        Ok(records)
+    }
+}
+
+/// Read a met-ocean file of a detected format
+pub fn load_spectra(path: &Path) -> Result<(MetoceanFormat, Vec<SpectrumRecord>), String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let format = MetoceanFormat::detect(&content);
+    Ok((format, format.parse(&content)?))
+}
+
+/// Date and time from the leading columns of an NDBC row, e.g. "2024-03-05 14:40"
+fn ndbc_time(fields: &[&str]) -> String {
+    match fields {
+        [year, month, day, hour, minute, ..] => format!("{}-{}-{} {}:{}", year, month, day, hour, minute),
+        [year, month, day, hour] => format!("{}-{}-{} {}:00", year, month, day, hour),
+        _ => fields.join(" "),
+    }
+}
+
+/// Spectra of an NDBC historical spectral wave density file
+/// The header names the date columns (YY MM DD hh [mm]) before the frequencies; rows with missing densities are skipped
+pub fn parse_ndbc_spectral(content: &str) -> Result<Vec<SpectrumRecord>, String> {
+    let mut header: Option<(usize, Vec<f64>)> = None;
+    let mut records = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || (line.starts_with('#') && header.is_some()) {
+            continue;
+        }
+        let fields: Vec<&str> = line.trim_start_matches('#').split_whitespace().collect();
+        let Some((date_columns, frequencies)) = &header else {
+            // Date column labels, then the frequencies
+            let date_columns = fields.iter().take_while(|field| field.parse::<f64>().is_err()).count();
+            let frequencies: Result<Vec<f64>, _> = fields[date_columns..].iter().map(|field| field.parse::<f64>()).collect();
+            let frequencies = frequencies.map_err(|e| format!("Invalid frequency on line {}: {}", number + 1, e))?;
+            if date_columns == 0 || frequencies.is_empty() {
+                return Err(format!("Line {} must name the date columns before the frequencies", number + 1));
+            }
+            header = Some((date_columns, frequencies));
+            continue;
+        };
+        if fields.len() != date_columns + frequencies.len() {
+            return Err(format!("Line {} must hold {} date columns and {} densities", number + 1, date_columns, frequencies.len()));
+        }
+        let density: Result<Vec<f64>, _> = fields[*date_columns..].iter().map(|field| field.parse::<f64>()).collect();
+        let density = density.map_err(|e| format!("Invalid number on line {}: {}", number + 1, e))?;
+        if density.iter().any(|&value| value >= NDBC_MISSING) {
+            continue;
+        }
+        // Calm records hold no energy
+        if let Ok(spectrum) = WaveSpectrum::new(frequencies.clone(), density) {
+            records.push(SpectrumRecord {
+                time: ndbc_time(&fields[..*date_columns]),
+                spectrum,
+            });
+        }
+    }
+    Ok(records)
+}
+
+/// Spectra of an NDBC realtime spectral file
+/// Each row holds the date (YY MM DD hh mm), the separation frequency, then pairs of a density and its frequency in brackets
+pub fn parse_ndbc_realtime(content: &str) -> Result<Vec<SpectrumRecord>, String> {
+    let mut records = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 8 || !(fields.len() - 6).is_multiple_of(2) {
+            return Err(format!("Line {} must hold the date, the separation frequency and pairs of density and (frequency)", number + 1));
+        }
+        let mut frequencies = Vec::new();
+        let mut density = Vec::new();
+        for pair in fields[6..].chunks(2) {
+            let value = pair[0].parse::<f64>().map_err(|e| format!("Invalid number on line {}: {}", number + 1, e))?;
+            let frequency = pair[1]
+                .trim_start_matches('(')
+                .trim_end_matches(')')
+                .parse::<f64>()
+                .map_err(|e| format!("Invalid frequency on line {}: {}", number + 1, e))?;
+            density.push(value);
+            frequencies.push(frequency);
+        }
+        if density.iter().any(|&value| value >= NDBC_MISSING) {
+            continue;
+        }
+        // Calm records hold no energy
+        if let Ok(spectrum) = WaveSpectrum::new(frequencies, density) {
+            records.push(SpectrumRecord {
+                time: ndbc_time(&fields[..5]),
+                spectrum,
+            });
+        }
+    }
+    Ok(records)
+}
+
+/// JONSWAP spectra of the sea states of an ERA5 CSV extract
+/// Columns are found by name: swh, and pp1d or else mwp, the energy period Te = m₋₁/m0 giving Tp through the JONSWAP shape.
+/// The time comes from a time, valid_time or date column; rows with missing values are skipped.
+pub fn parse_era5_csv(content: &str) -> Result<Vec<SpectrumRecord>, String> {
+    let split = |line: &str| -> Vec<String> { table_fields(line).map(str::to_string).collect() };
+    let mut lines = content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'));
+    let Some((_, header)) = lines.next() else {
+        return Err("ERA5 file is empty".to_string());
+    };
+    let names: Vec<String> = split(header).iter().map(|name| name.to_lowercase()).collect();
+    let column = |name: &str| names.iter().position(|candidate| candidate == name);
+    let height_column = column("swh").ok_or("ERA5 file needs a swh column")?;
+    let time_column = column("time").or_else(|| column("valid_time")).or_else(|| column("date"));
+    // Tp over Te of the JONSWAP shape, for extracts with the mean period only
+    let (period_column, period_ratio) = match (column("pp1d"), column("mwp")) {
+        (Some(peak), _) => (peak, 1.0),
+        (None, Some(mean)) => (mean, 1.0 / WaveSpectrum::jonswap(1.0, 1.0, JONSWAP_GAMMA)?.energy_period()),
+        (None, None) => return Err("ERA5 file needs a pp1d or mwp column".to_string()),
+    };
+
+    let mut records = Vec::new();
+    for (number, line) in lines {
+        let fields = split(line);
+        let value = |index: usize| -> Result<Option<f64>, String> {
+            match fields.get(index).map(String::as_str) {
+                None | Some("") => Ok(None),
+                Some(field) => field.parse::<f64>().map(|value| value.is_finite().then_some(value)).map_err(|e| format!("Invalid number on line {}: {}", number + 1, e)),
+            }
+        };
+        let (Some(height), Some(period)) = (value(height_column)?, value(period_column)?) else {
+            continue;
+        };
+        if height <= 0.0 || period <= 0.0 {
+            continue;
+        }
+        records.push(SpectrumRecord {
+            time: time_column.and_then(|index| fields.get(index).cloned()).unwrap_or_else(|| format!("Line {}", number + 1)),
+            spectrum: WaveSpectrum::jonswap(height, period * period_ratio, JONSWAP_GAMMA)?,
+        });
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ndbc_files() {
+        let historical = "#YY  MM DD hh mm   .0200  .0500  .1000  .1500\n#yr  mo dy hr mn  Hz\n2019 01 01 00 40   0.00   2.00   8.00   1.00\n2019 01 01 01 40 999.00 999.00 999.00 999.00\n2019 01 01 02 40   0.00   4.00  16.00   2.00\n";
+        assert_eq!(MetoceanFormat::detect(historical), MetoceanFormat::NdbcSpectral);
+        let records = MetoceanFormat::NdbcSpectral.parse(historical).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].time, "2019-01-01 00:40");
+        assert_eq!(records[0].spectrum.frequencies(), [0.02, 0.05, 0.1, 0.15]);
+        assert!((records[0].spectrum.peak_period() - 10.0).abs() < 1e-12);
+        // Doubled densities give √2 times the height
+        let ratio = records[1].spectrum.significant_wave_height() / records[0].spectrum.significant_wave_height();
+        assert!((ratio - std::f64::consts::SQRT_2).abs() < 1e-12);
+
+        let realtime = "#YY  MM DD hh mm Sep_Freq  < spec_1 (freq_1) spec_2 (freq_2) spec_3 (freq_3) >\n2024 03 05 14 40 9.999 0.000 (0.033) 3.000 (0.083) 1.000 (0.125)\n";
+        assert_eq!(MetoceanFormat::detect(realtime), MetoceanFormat::NdbcRealtime);
+        let records = MetoceanFormat::NdbcRealtime.parse(realtime).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].spectrum.frequencies(), [0.033, 0.083, 0.125]);
+        assert_eq!(records[0].spectrum.peak_frequency(), 0.083);
+
+        assert!(parse_ndbc_spectral("#YY MM DD hh .02 .05\n2019 01 01 00 1.0\n").is_err());
+        assert!(parse_ndbc_realtime("2024 03 05 14 40 9.999 1.0 (x) 2.0 (0.1)\n").is_err());
+        assert!(MetoceanFormat::NdbcSpectral.parse("#YY MM DD hh .02 .05\n").is_err());
+    }
+
+    #[test]
+    fn test_era5_csv() {
+        let content = "valid_time,latitude,longitude,swh,pp1d\n2020-01-01 00:00:00,45.0,-60.0,2.5,9.0\n2020-01-01 01:00:00,45.0,-60.0,,9.0\n2020-01-01 02:00:00,45.0,-60.0,3.0,10.0\n";
+        assert_eq!(MetoceanFormat::detect(content), MetoceanFormat::Era5Csv);
+        let records = MetoceanFormat::Era5Csv.parse(content).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].time, "2020-01-01 02:00:00");
+        assert!((records[0].spectrum.significant_wave_height() - 2.5).abs() < 1e-9);
+        assert!((records[0].spectrum.peak_period() - 9.0).abs() < 0.1);
+
+        // The energy period of the JONSWAP spectrum matches the mwp column
+        let mean = parse_era5_csv("time;swh;mwp\n0;1.0;6.0\n").unwrap();
+        assert!((mean[0].spectrum.energy_period() - 6.0).abs() < 1e-9);
+
+        assert!(parse_era5_csv("time,hs,tp\n0,1,6\n").is_err());
+        assert!(parse_era5_csv("time,swh,pp1d\n0,x,6\n").is_err());
+    }
+}
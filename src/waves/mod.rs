@@ -2,8 +2,45 @@ pub mod parameters;
 pub mod dispersion;
 pub mod velocity;
 pub mod boundary;
+pub mod forcing;
+pub mod goda;
+pub mod hydrograph;
+pub mod wavemaker;
+pub mod absorption;
+pub mod random;
+pub mod schedule;
+pub mod runup;
+pub mod climate;
+pub mod current;
+pub mod energy_converter;
+pub mod spectrum;
+pub mod metocean;
+pub mod swan;
+pub mod theory;
+pub mod stream_function;
+pub mod diffraction;
+pub mod tsunami;
+pub mod water_level;
 
-pub use parameters::WaveParameters;
-pub use dispersion::DispersionSolver;
-pub use velocity::VelocityCalculator;
-pub use boundary::BoundaryApplicator;
\ No newline at end of file
+pub use parameters::{BREAKER_INDEX, BreakingCriterion, LIMITING_STEEPNESS, WaveParameters, breaking_limit};
+pub use dispersion::{DispersionSolver, DispersionMode, DispersionRelation, DopplerShift};
+pub use velocity::{Stretching, VelocityCalculator};
+pub use boundary::BoundaryApplicator;
+pub use forcing::{WaveForcing, WaveSource, SourceKind};
+pub use diffraction::{BreakwaterDiffraction, fresnel_integrals};
+pub use goda::{GodaPressure, WATER_DENSITY};
+pub use hydrograph::{HydrographPoint, StormHydrograph};
+pub use wavemaker::{Wavemaker, PaddleType};
+pub use absorption::ActiveAbsorber;
+pub use random::{DEFAULT_SEED, SeededRng, seed_from_clock};
+pub use runup::{StockdonSwash, deep_water_wave_height, deep_water_wavelength, hunt_run_up, iribarren_number};
+pub use schedule::{ForcingSchedule, ScheduledWaves, WaveWindow};
+pub use current::{AmbientCurrent, CurrentProfile};
+pub use climate::{ENERGY_PERIOD_RATIO, HOURS_PER_YEAR, ScatterCell, ScatterDiagram, SeaState, climate_hours};
+pub use energy_converter::{ConverterKind, ConverterResponse, WaveEnergyConverter};
+pub use spectrum::{DirectionalComponent, DirectionalSpectrum, DirectionalSpreading, SpectralComponent, WaveSpectrum};
+pub use metocean::{JONSWAP_GAMMA, MetoceanFormat, SpectrumRecord, load_spectra, parse_era5_csv, parse_ndbc_realtime, parse_ndbc_spectral};
+pub use swan::parse_swan;
+pub use tsunami::NWave;
+pub use water_level::{StillWaterComponents, Surge, WaterLevelBudget};
+pub use theory::{TheoryBoundaries, URSELL_CNOIDAL, WaveTheory, ursell_number};pub use stream_function::{Harmonic, MeanCurrent, StreamFunctionSolver, StreamFunctionWave};
@@ -1,5 +1,52 @@
+use crate::error::CoastalError;
+use crate::units::{Length, Time};
+use crate::waves::dispersion::DispersionSolver;
+use crate::waves::runup::deep_water_wavelength;
 use std::f64::consts::PI;
 
+/// Ratio of breaking wave height to water depth
+pub const BREAKER_INDEX: f64 = 0.78;
+
+/// Limiting steepness H/L of a wave, Michell's 0.142 ≈ 1/7
+pub const LIMITING_STEEPNESS: f64 = 0.142;
+
+/// Criterion setting the largest wave height before breaking
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakingCriterion {
+    /// Depth-limited breaking, H ≤ 0.78 d
+    DepthLimited,
+    /// Miche's steepness limit in finite depth, H ≤ 0.142 L tanh(kd)
+    Miche,
+    /// Steepness limit in deep water, H ≤ 0.142 L₀
+    DeepWaterSteepness,
+}
+
+impl std::fmt::Display for BreakingCriterion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BreakingCriterion::DepthLimited => write!(f, "depth-limited (H/d = {})", BREAKER_INDEX),
+            BreakingCriterion::Miche => write!(f, "Miche (H/L = {} tanh kd)", LIMITING_STEEPNESS),
+            BreakingCriterion::DeepWaterSteepness => write!(f, "deep water steepness (H/L₀ = {})", LIMITING_STEEPNESS),
+        }
+    }
+}
+
+/// Largest wave height of a period in a depth and the criterion setting it
+/// The steepness limit follows Miche with the linear wavelength, or the deep water limit where d/L > ½
+pub fn breaking_limit(wave_period: Time, water_depth: Length) -> Result<(Length, BreakingCriterion), CoastalError> {
+    let wavelength = DispersionSolver::linear().wavelength(wave_period, water_depth)?.in_meters();
+    let (wave_period, water_depth) = (wave_period.in_seconds(), water_depth.in_meters());
+    let steepness = if water_depth / wavelength > 0.5 {
+        (LIMITING_STEEPNESS * deep_water_wavelength(wave_period), BreakingCriterion::DeepWaterSteepness)
+    } else {
+        let kd = 2.0 * PI * water_depth / wavelength;
+        (LIMITING_STEEPNESS * wavelength * kd.tanh(), BreakingCriterion::Miche)
+    };
+    let depth_limited = (BREAKER_INDEX * water_depth, BreakingCriterion::DepthLimited);
+    let (limit, criterion) = if depth_limited.0 <= steepness.0 { depth_limited } else { steepness };
+    Ok((Length::meters(limit), criterion))
+}
+
 /// Wave parameters structure for SWASH-style wave generation
 #[derive(Debug, Clone)]
 pub struct WaveParameters {
@@ -21,22 +68,24 @@ pub struct WaveParameters {
 
 impl WaveParameters {
     /// Create new wave parameters from basic inputs
-    pub fn new(wave_height: f64, wave_period: f64, water_depth: f64) -> Result<Self, String> {
+    pub fn new(wave_height: Length, wave_period: Time, water_depth: Length) -> Result<Self, CoastalError> {
+        let (wave_height, wave_period, water_depth) = (wave_height.in_meters(), wave_period.in_seconds(), water_depth.in_meters());
         // Validate inputs
         if wave_height <= 0.0 {
-            return Err("Wave height must be positive".to_string());
+            return Err(CoastalError::not_positive("Wave height", wave_height));
         }
         if wave_period <= 0.0 {
-            return Err("Wave period must be positive".to_string());
+            return Err(CoastalError::not_positive("Wave period", wave_period));
         }
         if water_depth <= 0.0 {
-            return Err("Water depth must be positive".to_string());
+            return Err(CoastalError::not_positive("Water depth", water_depth));
         }
         
-        // Check wave breaking criterion (H/d < 0.78 for depth-limited breaking)
-        let breaking_ratio = wave_height / water_depth;
-        if breaking_ratio > 0.78 {
-            return Err(format!("Wave may break: H/d = {:.3} > 0.78", breaking_ratio));
+        // Check the governing breaking criterion, depth-limited or steepness-limited
+        let (limit, criterion) = breaking_limit(Time::seconds(wave_period), Length::meters(water_depth))?;
+        let limit = limit.in_meters();
+        if wave_height > limit {
+            return Err(CoastalError::Breaking { ratio: wave_height / water_depth, criterion, height: wave_height, limit });
         }
         
         let omega = 2.0 * PI / wave_period;
@@ -65,6 +114,23 @@ impl WaveParameters {
         self.h / 2.0
     }
     
+    /// Largest wave height for the period and depth before breaking [m]
+    pub fn breaking_limit(&self) -> f64 {
+        self.breaking_criterion_and_limit().0
+    }
+    
+    /// Criterion setting the largest wave height for the period and depth
+    pub fn breaking_criterion(&self) -> BreakingCriterion {
+        self.breaking_criterion_and_limit().1
+    }
+    
+    /// Limit and criterion, depth-limited if the linear wavelength cannot be found
+    fn breaking_criterion_and_limit(&self) -> (f64, BreakingCriterion) {
+        breaking_limit(Time::seconds(self.period), Length::meters(self.d))
+            .map(|(limit, criterion)| (limit.in_meters(), criterion))
+            .unwrap_or((BREAKER_INDEX * self.d, BreakingCriterion::DepthLimited))
+    }
+    
     /// Get frequency (1/T)
     pub fn frequency(&self) -> f64 {
         1.0 / self.period
@@ -93,21 +159,21 @@ impl WaveParameters {
     }
     
     /// Validate wave parameters for physical consistency
-    pub fn validate(&self) -> Result<(), String> {
+    pub fn validate(&self) -> Result<(), CoastalError> {
         if self.k <= 0.0 {
-            return Err("Wave number must be positive".to_string());
+            return Err(CoastalError::not_positive("Wave number", self.k));
         }
         if self.omega <= 0.0 {
-            return Err("Angular frequency must be positive".to_string());
+            return Err(CoastalError::not_positive("Angular frequency", self.omega));
         }
         if self.c <= 0.0 {
-            return Err("Phase velocity must be positive".to_string());
+            return Err(CoastalError::not_positive("Phase velocity", self.c));
         }
         
         // Check if parameters are consistent
         let expected_c = self.omega / self.k;
         if (self.c - expected_c).abs() > 1e-6 {
-            return Err(format!("Inconsistent parameters: c = {:.6}, ω/k = {:.6}", self.c, expected_c));
+            return Err(CoastalError::Inconsistent { check: "Phase velocity c = ω/k", error: self.c - expected_c });
         }
         
         Ok(())
@@ -141,7 +207,7 @@ mod tests {
 
     #[test]
     fn test_wave_parameters_creation() {
-        let params = WaveParameters::new(1.0, 4.0, 2.0).unwrap();
+        let params = WaveParameters::new(Length::meters(1.0), Time::seconds(4.0), Length::meters(2.0)).unwrap();
         assert_eq!(params.h, 1.0);
         assert_eq!(params.period, 4.0);
         assert_eq!(params.d, 2.0);
@@ -152,18 +218,43 @@ mod tests {
     #[test]
     fn test_wave_breaking_validation() {
         // Should fail for waves that are too large
-        let result = WaveParameters::new(2.0, 4.0, 2.0); // H/d = 1.0 > 0.78
-        assert!(result.is_err());
+        let result = WaveParameters::new(Length::meters(2.0), Time::seconds(8.0), Length::meters(2.0)); // H/d = 1.0 > 0.78
+        assert!(matches!(result.unwrap_err(), CoastalError::Breaking { ratio: 1.0, criterion: BreakingCriterion::DepthLimited, height: 2.0, limit } if (limit - 1.56).abs() < 1e-12));
         
         // Should succeed for reasonable waves
-        let result = WaveParameters::new(1.0, 4.0, 2.0); // H/d = 0.5 < 0.78
+        let result = WaveParameters::new(Length::meters(1.0), Time::seconds(4.0), Length::meters(2.0)); // H/d = 0.5 < 0.78
         assert!(result.is_ok());
     }
     
+    #[test]
+    fn test_steepness_limited_breaking() {
+        // Short waves in a deep channel are limited by their steepness, not by the depth
+        let params = WaveParameters::new(Length::meters(0.1), Time::seconds(1.0), Length::meters(2.0)).unwrap();
+        assert_eq!(params.breaking_criterion(), BreakingCriterion::DeepWaterSteepness);
+        assert!((params.breaking_limit() - LIMITING_STEEPNESS * deep_water_wavelength(1.0)).abs() < 1e-12);
+        let error = WaveParameters::new(Length::meters(0.3), Time::seconds(1.0), Length::meters(2.0)).unwrap_err();
+        assert!(matches!(error, CoastalError::Breaking { criterion: BreakingCriterion::DeepWaterSteepness, .. }));
+        assert!(error.to_string().contains("deep water steepness"), "{}", error);
+        
+        // In intermediate depth Miche's criterion lies between the depth and the deep water limits
+        let (limit, criterion) = breaking_limit(Time::seconds(2.0), Length::meters(1.5)).unwrap();
+        let limit = limit.in_meters();
+        assert_eq!(criterion, BreakingCriterion::Miche);
+        assert!(limit < BREAKER_INDEX * 1.5 && limit < LIMITING_STEEPNESS * deep_water_wavelength(2.0));
+        
+        // Long waves in shallow water break on the depth
+        let params = WaveParameters::new(Length::meters(0.5), Time::seconds(10.0), Length::meters(1.0)).unwrap();
+        assert_eq!(params.breaking_criterion(), BreakingCriterion::DepthLimited);
+        assert!((params.breaking_limit() - BREAKER_INDEX).abs() < 1e-12);
+    }
+    
     #[test]
     fn test_invalid_parameters() {
-        assert!(WaveParameters::new(0.0, 4.0, 2.0).is_err()); // Zero height
-        assert!(WaveParameters::new(1.0, 0.0, 2.0).is_err()); // Zero period
-        assert!(WaveParameters::new(1.0, 4.0, 0.0).is_err()); // Zero depth
+        assert!(WaveParameters::new(Length::meters(0.0), Time::seconds(4.0), Length::meters(2.0)).is_err()); // Zero height
+        assert!(WaveParameters::new(Length::meters(1.0), Time::seconds(0.0), Length::meters(2.0)).is_err()); // Zero period
+        let error = WaveParameters::new(Length::meters(1.0), Time::seconds(-4.0), Length::meters(2.0)).unwrap_err();
+        assert!(matches!(error, CoastalError::InvalidParameter { name: "Wave period", value, .. } if value == -4.0));
+        assert_eq!(error.to_string(), "Wave period must be positive, got -4");
+        assert!(WaveParameters::new(Length::meters(1.0), Time::seconds(4.0), Length::meters(0.0)).is_err()); // Zero depth
     }
 }
\ No newline at end of file
@@ -0,0 +1,106 @@
+use crate::waves::wavemaker::linear_wave_number;
+
+/// Deep water wave length L₀ = gT²/2π [m]
+pub fn deep_water_wavelength(wave_period: f64) -> f64 {
+    9.81 * wave_period * wave_period / (2.0 * std::f64::consts::PI)
+}
+
+/// Deep water height of waves of height H and period T in a depth h, undoing linear shoaling H₀ = H / K_s [m]
+/// The shoaling coefficient K_s = √(c_g0 / c_g) compares the group velocities in deep water and in the depth h
+pub fn deep_water_wave_height(wave_height: f64, wave_period: f64, depth: f64) -> Result<f64, String> {
+    if wave_period <= 0.0 || depth <= 0.0 {
+        return Err("Wave period and depth must be positive".to_string());
+    }
+    let omega = 2.0 * std::f64::consts::PI / wave_period;
+    let kh = linear_wave_number(omega, depth, 9.81)? * depth;
+    let group_ratio = 0.5 * (1.0 + 2.0 * kh / (2.0 * kh).sinh()) * kh.tanh();
+    Ok(wave_height * (group_ratio / 0.5).sqrt())
+}
+
+/// Iribarren number (surf similarity) ξ₀ = tan β / √(H₀/L₀) of waves on a slope
+pub fn iribarren_number(slope: f64, wave_height: f64, wave_period: f64) -> f64 {
+    slope / (wave_height / deep_water_wavelength(wave_period)).sqrt()
+}
+
+/// Run-up of regular waves on a smooth plane slope after Hunt (1959), R = ξ₀ H [m]
+pub fn hunt_run_up(slope: f64, wave_height: f64, wave_period: f64) -> f64 {
+    iribarren_number(slope, wave_height, wave_period) * wave_height
+}
+
+/// Swash parameters of a natural beach after Stockdon et al. (2006)
+///
+/// Fitted to field measurements on sandy beaches, from the deep water significant wave height H₀,
+/// the peak period and the foreshore slope β. The swash splits at 0.05 Hz into an incident band,
+/// growing with the slope, and an infragravity band, independent of it. On dissipative beaches
+/// (ξ₀ < 0.3) the run-up depends on the wave conditions only.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StockdonSwash {
+    /// Iribarren number of the waves on the foreshore
+    pub iribarren: f64,
+    /// Mean water level at the shoreline above the still water level ⟨η⟩ [m]
+    pub setup: f64,
+    /// Significant swash height in the incident band S_inc [m]
+    pub incident_swash: f64,
+    /// Significant swash height in the infragravity band S_IG [m]
+    pub infragravity_swash: f64,
+    /// Run-up exceeded by 2% of the swash maxima R₂% [m]
+    pub run_up_2: f64,
+}
+
+impl StockdonSwash {
+    /// Swash parameters for deep water waves of significant height H₀ and peak period T on a foreshore slope β
+    pub fn new(wave_height: f64, wave_period: f64, beach_slope: f64) -> Result<Self, String> {
+        if wave_height <= 0.0 || wave_period <= 0.0 {
+            return Err("Wave height and period must be positive".to_string());
+        }
+        if beach_slope <= 0.0 {
+            return Err("Beach slope must be positive".to_string());
+        }
+        let scale = (wave_height * deep_water_wavelength(wave_period)).sqrt();
+        let iribarren = iribarren_number(beach_slope, wave_height, wave_period);
+        let setup = 0.35 * beach_slope * scale;
+        let incident_swash = 0.75 * beach_slope * scale;
+        let infragravity_swash = 0.06 * scale;
+        let run_up_2 = if iribarren < 0.3 {
+            0.043 * scale
+        } else {
+            1.1 * (setup + 0.5 * (scale * scale * (0.563 * beach_slope * beach_slope + 0.004)).sqrt())
+        };
+        Ok(Self {
+            iribarren,
+            setup,
+            incident_swash,
+            infragravity_swash,
+            run_up_2,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swash_parameterizations() {
+        // 1 m, 10 s waves on a 1:20 beach: L₀ = 156 m, ξ₀ = 0.62
+        assert!((deep_water_wavelength(10.0) - 156.13).abs() < 0.01);
+        let xi = iribarren_number(0.05, 1.0, 10.0);
+        assert!((xi - 0.625).abs() < 1e-3);
+        assert!((hunt_run_up(0.05, 1.0, 10.0) - xi).abs() < 1e-12);
+
+        // Deep water waves keep their height, shallow water waves shoal (K_s = 0.93 at h/L₀ = 0.1)
+        assert!((deep_water_wave_height(1.0, 10.0, 1000.0).unwrap() - 1.0).abs() < 1e-6);
+        assert!((deep_water_wave_height(1.0, 10.0, 15.613).unwrap() - 1.0 / 0.9327).abs() < 1e-3);
+
+        let swash = StockdonSwash::new(1.0, 10.0, 0.05).unwrap();
+        assert!((swash.setup - 0.219).abs() < 1e-3);
+        assert!((swash.incident_swash - 0.469).abs() < 1e-3);
+        assert!((swash.infragravity_swash - 0.750).abs() < 1e-3);
+        assert!((swash.run_up_2 - 0.746).abs() < 1e-3);
+
+        // Dissipative beaches follow the wave conditions only
+        let flat = StockdonSwash::new(1.0, 10.0, 0.01).unwrap();
+        assert!((flat.run_up_2 - 0.043 * 156.13_f64.sqrt()).abs() < 1e-3);
+        assert!(StockdonSwash::new(0.0, 10.0, 0.05).is_err() && StockdonSwash::new(1.0, 10.0, 0.0).is_err());
+    }
+}
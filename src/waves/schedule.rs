@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+
+/// Regular wave condition switched on at a time of the run
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledWaves {
+    pub start: f64,       // Time the wavemaker switches to these waves [s]
+    pub wave_height: f64, // Wave height (H) [m]
+    pub wave_period: f64, // Wave period (T) [s]
+}
+
+/// Generation window of one wave condition
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WaveWindow {
+    pub start: f64,       // Start of the generation [s]
+    pub duration: f64,    // Length of the generation [s]
+    pub wave_height: f64, // Wave height (H) [m]
+    pub wave_period: f64, // Wave period (T) [s]
+}
+
+impl WaveWindow {
+    /// End of the generation [s]
+    pub fn end(&self) -> f64 {
+        self.start + self.duration
+    }
+}
+
+/// Sequence of wave conditions generated one after the other by the wavemaker
+///
+/// The run starts with the wavemaker waves; each scheduled condition takes over at its start time
+/// and lasts until the next one starts. The last condition generates the set number of its waves,
+/// so a run can begin with swell, switch to storm seas and calm down again.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ForcingSchedule {
+    pub entries: Vec<ScheduledWaves>, // By increasing start time
+}
+
+impl ForcingSchedule {
+    /// Create new schedule from its conditions, by increasing start time
+    pub fn new(entries: Vec<ScheduledWaves>) -> Result<Self, String> {
+        let schedule = Self { entries };
+        schedule.validate()?;
+        Ok(schedule)
+    }
+
+    /// Swell, then storm seas twice as high and longer, then calm waves
+    pub fn storm_sequence(wave_height: f64, wave_period: f64, storm_start: f64, storm_duration: f64) -> Self {
+        Self {
+            entries: vec![
+                ScheduledWaves { start: storm_start, wave_height: 2.0 * wave_height, wave_period: 1.25 * wave_period },
+                ScheduledWaves { start: storm_start + storm_duration, wave_height: 0.5 * wave_height, wave_period },
+            ],
+        }
+    }
+
+    /// Check that the conditions start after the run, in order, with positive heights and periods
+    pub fn validate(&self) -> Result<(), String> {
+        let mut previous = 0.0;
+        for (i, entry) in self.entries.iter().enumerate() {
+            if !(entry.start.is_finite() && entry.start > previous) {
+                return Err(format!("Condition {} must start after {:.1} s", i + 1, previous));
+            }
+            if !(entry.wave_height > 0.0 && entry.wave_period > 0.0) {
+                return Err(format!("Condition {} must have a positive wave height and period", i + 1));
+            }
+            previous = entry.start;
+        }
+        Ok(())
+    }
+
+    /// Check if the wavemaker keeps one condition
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Generation windows, from the initial waves at the start of the run
+    pub fn windows(&self, wave_height: f64, wave_period: f64, number_of_waves: usize) -> Vec<WaveWindow> {
+        let initial = ScheduledWaves { start: 0.0, wave_height, wave_period };
+        let conditions: Vec<ScheduledWaves> = std::iter::once(initial).chain(self.entries.iter().copied()).collect();
+        conditions
+            .iter()
+            .enumerate()
+            .map(|(i, condition)| {
+                let end = conditions.get(i + 1).map_or(condition.start + number_of_waves as f64 * condition.wave_period, |next| next.start);
+                WaveWindow {
+                    start: condition.start,
+                    duration: end - condition.start,
+                    wave_height: condition.wave_height,
+                    wave_period: condition.wave_period,
+                }
+            })
+            .collect()
+    }
+
+    /// Index of the window generating at a time, 0 for the initial waves
+    pub fn window_index(&self, time: f64) -> usize {
+        self.entries.partition_point(|entry| entry.start <= time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows() {
+        let schedule = ForcingSchedule::storm_sequence(0.1, 4.0, 40.0, 60.0);
+        let windows = schedule.windows(0.1, 4.0, 10);
+        assert_eq!(windows.len(), 3);
+        assert_eq!((windows[0].start, windows[0].duration), (0.0, 40.0));
+        assert_eq!((windows[1].start, windows[1].end()), (40.0, 100.0));
+        assert_eq!(windows[1].wave_height, 0.2);
+        // Ten waves after the storm
+        assert_eq!(windows[2].duration, 40.0);
+
+        assert_eq!(schedule.window_index(10.0), 0);
+        assert_eq!(schedule.window_index(40.0), 1);
+        assert_eq!(schedule.window_index(150.0), 2);
+
+        let unscheduled = ForcingSchedule::default().windows(0.1, 4.0, 10);
+        assert_eq!(unscheduled, vec![WaveWindow { start: 0.0, duration: 40.0, wave_height: 0.1, wave_period: 4.0 }]);
+    }
+
+    #[test]
+    fn test_validation() {
+        let condition = |start, wave_height| ScheduledWaves { start, wave_height, wave_period: 5.0 };
+        assert!(ForcingSchedule::new(vec![condition(10.0, 0.1), condition(20.0, 0.2)]).is_ok());
+        assert!(ForcingSchedule::new(vec![condition(0.0, 0.1)]).is_err());
+        assert!(ForcingSchedule::new(vec![condition(20.0, 0.1), condition(10.0, 0.2)]).is_err());
+        assert!(ForcingSchedule::new(vec![condition(10.0, 0.0)]).is_err());
+    }
+}
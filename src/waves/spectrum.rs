@@ -0,0 +1,406 @@
+use crate::waves::random::SeededRng;
+use serde::{Deserialize, Serialize};
+
+/// Share of the peak density below which the tails of a spectrum generate no component
+const TAIL_THRESHOLD: f64 = 0.01;
+
+/// Intervals of the integration of a spreading function over the directions
+const DIRECTION_INTERVALS: usize = 720;
+
+/// Regular wave component synthesizing a part of a spectrum
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpectralComponent {
+    pub frequency: f64, // [Hz]
+    pub amplitude: f64, // [m]
+    pub phase: f64,     // [rad]
+}
+
+impl SpectralComponent {
+    /// Get period of the component [s]
+    pub fn period(&self) -> f64 {
+        1.0 / self.frequency
+    }
+}
+
+/// Variance density spectrum of the surface elevation, integrated over the directions
+///
+/// The density S(f) is given at increasing frequencies and interpolated linearly between them, with
+/// no energy outside. The irregular waves are synthesized as a sum of regular components of equal
+/// frequency spacing Δf and amplitudes a = √(2 S(f) Δf) with random phases, so the surface holds the
+/// variance of the spectrum. The sum repeats itself after 1/Δf.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WaveSpectrum {
+    frequencies: Vec<f64>, // By increasing frequency [Hz]
+    density: Vec<f64>,     // Variance density at the frequencies [m²/Hz]
+}
+
+impl WaveSpectrum {
+    /// Create new spectrum, checking the frequencies and the densities
+    pub fn new(frequencies: Vec<f64>, density: Vec<f64>) -> Result<Self, String> {
+        if frequencies.len() < 2 || frequencies.len() != density.len() {
+            return Err("Spectrum needs at least two frequencies, each with a density".to_string());
+        }
+        if frequencies.iter().any(|&f| !(f.is_finite() && f > 0.0)) || frequencies.windows(2).any(|pair| pair[1] <= pair[0]) {
+            return Err("Spectrum frequencies must be positive and increasing".to_string());
+        }
+        if density.iter().any(|&s| !(s.is_finite() && s >= 0.0)) {
+            return Err("Spectral densities cannot be negative".to_string());
+        }
+        let spectrum = Self { frequencies, density };
+        if spectrum.moment(0) <= 0.0 {
+            return Err("Spectrum holds no energy".to_string());
+        }
+        Ok(spectrum)
+    }
+
+    /// JONSWAP spectrum of significant wave height Hs, peak period Tp and peak enhancement γ
+    /// The shape of Hasselmann et al. (1973), scaled to 4√m0 = Hs, from half to five times the peak frequency
+    pub fn jonswap(significant_height: f64, peak_period: f64, gamma: f64) -> Result<Self, String> {
+        if significant_height <= 0.0 || peak_period <= 0.0 {
+            return Err("Significant wave height and peak period must be positive".to_string());
+        }
+        if gamma < 1.0 {
+            return Err("Peak enhancement factor must be at least 1".to_string());
+        }
+        let samples = 200;
+        let peak = 1.0 / peak_period;
+        let frequencies: Vec<f64> = (0..=samples).map(|i| peak * (0.5 + 4.5 * i as f64 / samples as f64)).collect();
+        let shape: Vec<f64> = frequencies
+            .iter()
+            .map(|&f| {
+                let width = if f <= peak { 0.07 } else { 0.09 };
+                let enhancement = gamma.powf((-(f - peak).powi(2) / (2.0 * width * width * peak * peak)).exp());
+                f.powi(-5) * (-1.25 * (peak / f).powi(4)).exp() * enhancement
+            })
+            .collect();
+        let unscaled = Self::new(frequencies, shape)?;
+        let scale = significant_height * significant_height / 16.0 / unscaled.moment(0);
+        Self::new(unscaled.frequencies, unscaled.density.iter().map(|s| s * scale).collect())
+    }
+
+    /// Get frequencies [Hz]
+    pub fn frequencies(&self) -> &[f64] {
+        &self.frequencies
+    }
+
+    /// Get variance densities at the frequencies [m²/Hz]
+    pub fn density(&self) -> &[f64] {
+        &self.density
+    }
+
+    /// Density interpolated at a frequency, zero outside the spectrum [m²/Hz]
+    pub fn density_at(&self, frequency: f64) -> f64 {
+        let index = self.frequencies.partition_point(|&f| f < frequency);
+        if index == 0 {
+            return if frequency == self.frequencies[0] { self.density[0] } else { 0.0 };
+        }
+        if index == self.frequencies.len() {
+            return 0.0;
+        }
+        let (f0, f1) = (self.frequencies[index - 1], self.frequencies[index]);
+        let weight = (frequency - f0) / (f1 - f0);
+        self.density[index - 1] + (self.density[index] - self.density[index - 1]) * weight
+    }
+
+    /// Spectral moment mₙ = ∫ fⁿ S(f) df, by the trapezoidal rule
+    pub fn moment(&self, order: i32) -> f64 {
+        self.frequencies
+            .windows(2)
+            .zip(self.density.windows(2))
+            .map(|(f, s)| 0.5 * (f[0].powi(order) * s[0] + f[1].powi(order) * s[1]) * (f[1] - f[0]))
+            .sum()
+    }
+
+    /// Significant wave height from the variance, Hm0 = 4√m0 [m]
+    pub fn significant_wave_height(&self) -> f64 {
+        4.0 * self.moment(0).sqrt()
+    }
+
+    /// Frequency of the highest density [Hz]
+    pub fn peak_frequency(&self) -> f64 {
+        let (index, _) = self.density.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)).expect("spectrum has densities");
+        self.frequencies[index]
+    }
+
+    /// Peak period Tp = 1/f_p [s]
+    pub fn peak_period(&self) -> f64 {
+        1.0 / self.peak_frequency()
+    }
+
+    /// Mean period Tm01 = m0/m1 [s]
+    pub fn mean_period(&self) -> f64 {
+        self.moment(0) / self.moment(1)
+    }
+
+    /// Energy period Te = m₋₁/m0, the period of the regular waves carrying the same energy flux in deep water [s]
+    pub fn energy_period(&self) -> f64 {
+        self.moment(-1) / self.moment(0)
+    }
+
+    /// Frequencies bounding the part of the spectrum above 1% of the peak density [Hz]
+    pub fn band(&self) -> (f64, f64) {
+        let threshold = TAIL_THRESHOLD * self.density.iter().fold(0.0, |max: f64, &s| max.max(s));
+        let first = self.density.iter().position(|&s| s >= threshold).unwrap_or(0);
+        let last = self.density.iter().rposition(|&s| s >= threshold).unwrap_or(self.density.len() - 1);
+        // Widened to the neighbouring frequencies, where the density falls below the threshold
+        (self.frequencies[first.saturating_sub(1)], self.frequencies[(last + 1).min(self.frequencies.len() - 1)])
+    }
+
+    /// Regular components of equal frequency spacing over the band of the spectrum, with random phases
+    /// Each component draws its phase from its own stream of the generator
+    pub fn components(&self, count: usize, rng: &SeededRng) -> Vec<SpectralComponent> {
+        let (low, high) = self.band();
+        let count = count.max(1);
+        let spacing = (high - low) / count as f64;
+        (0..count)
+            .map(|i| {
+                let frequency = low + (i as f64 + 0.5) * spacing;
+                SpectralComponent {
+                    frequency,
+                    amplitude: (2.0 * self.density_at(frequency) * spacing).sqrt(),
+                    phase: rng.stream(i as u64).phase(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Directional spreading function D(θ) = A cos^2s((θ − θm)/2) of Longuet-Higgins et al. (1963)
+///
+/// A = Γ(s + 1) / (2√π Γ(s + ½)) makes the integral over the circle one. The larger the exponent s, the
+/// narrower the spreading and the longer the crests: s ≈ 10 for wind seas, 25 for young swell and 75 for
+/// swell from distant storms (Goda, 2000). Directions are angles from the flume axis, positive
+/// anticlockwise, so a mean direction of zero sends the waves down the flume.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DirectionalSpreading {
+    pub mean_direction: f64, // Mean direction θm from the flume axis [rad]
+    pub exponent: f64,       // Spreading exponent s
+}
+
+impl Default for DirectionalSpreading {
+    fn default() -> Self {
+        Self {
+            mean_direction: 0.0,
+            exponent: 10.0,
+        }
+    }
+}
+
+impl DirectionalSpreading {
+    /// Create new spreading, checking the exponent
+    pub fn new(mean_direction: f64, exponent: f64) -> Result<Self, String> {
+        if !mean_direction.is_finite() {
+            return Err("Mean wave direction must be finite".to_string());
+        }
+        if !(exponent.is_finite() && exponent > 0.0) {
+            return Err("Spreading exponent must be positive".to_string());
+        }
+        Ok(Self { mean_direction, exponent })
+    }
+
+    /// Spreading density at a direction [1/rad]
+    pub fn density(&self, direction: f64) -> f64 {
+        let half_angle = 0.5 * (direction - self.mean_direction);
+        self.normalization() * half_angle.cos().abs().powf(2.0 * self.exponent)
+    }
+
+    /// Circular standard deviation of the directions σθ = √(2/(s + 1)) [rad]
+    pub fn directional_spread(&self) -> f64 {
+        (2.0 / (self.exponent + 1.0)).sqrt()
+    }
+
+    /// Share of the energy flux crossing a section of the flume, ∫ D(θ) cos θ dθ over the directions
+    /// travelling down the flume; the waves travelling back, beyond ±90°, carry none into it
+    pub fn flume_factor(&self) -> f64 {
+        let half_pi = 0.5 * std::f64::consts::PI;
+        let width = 2.0 * half_pi / DIRECTION_INTERVALS as f64;
+        // Simpson's rule, the integrand vanishing at both ends
+        (1..DIRECTION_INTERVALS)
+            .map(|i| {
+                let direction = -half_pi + i as f64 * width;
+                let weight = if i % 2 == 1 { 4.0 } else { 2.0 };
+                weight * self.density(direction) * direction.cos()
+            })
+            .sum::<f64>()
+            * width
+            / 3.0
+    }
+
+    /// Directions at the centres of equal sectors of the circle around the mean, with the share of the
+    /// energy in each sector, for the synthesis of a directional sea
+    pub fn directions(&self, count: usize) -> Vec<(f64, f64)> {
+        let count = count.max(1);
+        let width = 2.0 * std::f64::consts::PI / count as f64;
+        let directions: Vec<f64> = (0..count).map(|j| self.mean_direction - std::f64::consts::PI + (j as f64 + 0.5) * width).collect();
+        let densities: Vec<f64> = directions.iter().map(|&direction| self.density(direction)).collect();
+        let total: f64 = densities.iter().sum();
+        directions.into_iter().zip(densities).map(|(direction, density)| (direction, density / total)).collect()
+    }
+
+    /// Normalization A = Γ(s + 1) / (2√π Γ(s + ½))
+    fn normalization(&self) -> f64 {
+        (ln_gamma(self.exponent + 1.0) - ln_gamma(self.exponent + 0.5)).exp() / (2.0 * std::f64::consts::PI.sqrt())
+    }
+}
+
+/// Natural logarithm of the gamma function for positive arguments, Lanczos approximation (g = 7, n = 9)
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    let x = x - 1.0;
+    let sum = COEFFICIENTS[1..].iter().enumerate().fold(COEFFICIENTS[0], |sum, (i, c)| sum + c / (x + i as f64 + 1.0));
+    let t = x + 7.5;
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// Regular wave component of a directional sea, travelling at an angle to the flume axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DirectionalComponent {
+    pub frequency: f64, // [Hz]
+    pub direction: f64, // From the flume axis [rad]
+    pub amplitude: f64, // [m]
+    pub phase: f64,     // [rad]
+}
+
+/// Directional variance density spectrum S(f, θ) = S(f) D(θ), with the same spreading at every frequency
+///
+/// The flume generates the waves travelling along its axis only: its spectrum is the frequency
+/// spectrum scaled by the share of the energy flux crossing a section of the flume, so the energy
+/// entering the flume matches that of the directional sea. The directional components serve a
+/// model of the sea in plan.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DirectionalSpectrum {
+    pub spectrum: WaveSpectrum,          // Frequency spectrum, integrated over the directions
+    pub spreading: DirectionalSpreading, // Spreading over the directions
+}
+
+impl DirectionalSpectrum {
+    /// Create new directional spectrum
+    pub fn new(spectrum: WaveSpectrum, spreading: DirectionalSpreading) -> Self {
+        Self { spectrum, spreading }
+    }
+
+    /// Variance density at a frequency and direction [m²/Hz/rad]
+    pub fn density_at(&self, frequency: f64, direction: f64) -> f64 {
+        self.spectrum.density_at(frequency) * self.spreading.density(direction)
+    }
+
+    /// Spectrum of the waves along the flume axis, carrying the energy flux of the directional sea into the flume
+    pub fn flume_spectrum(&self) -> WaveSpectrum {
+        let factor = self.spreading.flume_factor();
+        WaveSpectrum {
+            frequencies: self.spectrum.frequencies.clone(),
+            density: self.spectrum.density.iter().map(|s| s * factor).collect(),
+        }
+    }
+
+    /// Components over the frequencies of the spectrum and equal sectors of directions, with random phases
+    /// Each frequency splits its variance over the sectors; each component draws its phase from its own stream
+    pub fn components(&self, frequencies: usize, directions: usize, rng: &SeededRng) -> Vec<DirectionalComponent> {
+        let sectors = self.spreading.directions(directions);
+        let mut components = Vec::with_capacity(frequencies * sectors.len());
+        for (i, component) in self.spectrum.components(frequencies, rng).iter().enumerate() {
+            for (j, &(direction, share)) in sectors.iter().enumerate() {
+                components.push(DirectionalComponent {
+                    frequency: component.frequency,
+                    direction,
+                    amplitude: component.amplitude * share.sqrt(),
+                    phase: rng.stream((i * sectors.len() + j) as u64).phase(),
+                });
+            }
+        }
+        components
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jonswap_moments() {
+        let spectrum = WaveSpectrum::jonswap(2.0, 10.0, 3.3).unwrap();
+        assert!((spectrum.significant_wave_height() - 2.0).abs() < 1e-9);
+        assert!((spectrum.peak_period() - 10.0).abs() < 0.1);
+        // Te ≈ 0.9 Tp and Tm01 ≈ 0.84 Tp for γ = 3.3
+        assert!((spectrum.energy_period() / 10.0 - 0.9).abs() < 0.01);
+        assert!((spectrum.mean_period() / 10.0 - 0.84).abs() < 0.01);
+        assert_eq!(spectrum.density_at(0.01), 0.0);
+        assert!(spectrum.density_at(0.1) > spectrum.density_at(0.15));
+
+        assert!(WaveSpectrum::new(vec![0.1, 0.1], vec![1.0, 1.0]).is_err());
+        assert!(WaveSpectrum::new(vec![0.1, 0.2], vec![0.0, 0.0]).is_err());
+        assert!(WaveSpectrum::jonswap(2.0, 10.0, 0.5).is_err());
+    }
+
+    #[test]
+    fn test_components_keep_the_variance() {
+        let spectrum = WaveSpectrum::jonswap(1.0, 5.0, 3.3).unwrap();
+        let rng = SeededRng::new(7);
+        let components = spectrum.components(64, &rng);
+        assert_eq!(components.len(), 64);
+        let (low, high) = spectrum.band();
+        assert!(components.iter().all(|component| component.frequency > low && component.frequency < high));
+        // Σ a²/2 = m0, less the tails below 1% of the peak
+        let variance: f64 = components.iter().map(|component| 0.5 * component.amplitude * component.amplitude).sum();
+        assert!((variance / spectrum.moment(0) - 1.0).abs() < 0.02, "{}", variance / spectrum.moment(0));
+        // Phases follow the seed
+        assert_eq!(spectrum.components(64, &rng), components);
+        assert_ne!(spectrum.components(64, &SeededRng::new(8))[0].phase, components[0].phase);
+    }
+
+    #[test]
+    fn test_spreading_is_normalized() {
+        let pi = std::f64::consts::PI;
+        assert!((ln_gamma(5.0) - 24f64.ln()).abs() < 1e-12);
+        assert!((ln_gamma(0.5) - pi.sqrt().ln()).abs() < 1e-12);
+        for exponent in [1.0, 10.0, 75.0] {
+            let spreading = DirectionalSpreading::new(0.3, exponent).unwrap();
+            let width = 2.0 * pi / 3600.0;
+            let total: f64 = (0..3600).map(|i| spreading.density(-pi + (i as f64 + 0.5) * width) * width).sum();
+            assert!((total - 1.0).abs() < 1e-9, "{}", total);
+            assert_eq!(spreading.density(0.3), spreading.density(0.3 + 2.0 * pi));
+            let spread: f64 = (0..3600).map(|i| -pi + (i as f64 + 0.5) * width).map(|theta| 2.0 * (1.0 - (theta - 0.3).cos()) * spreading.density(theta) * width).sum();
+            assert!((spread.sqrt() - spreading.directional_spread()).abs() < 1e-6);
+        }
+        // s = 1: D = (1 + cos θ)/2π and ∫ D cos θ over ±90° = 1/π + 1/4
+        let flux = DirectionalSpreading::new(0.0, 1.0).unwrap().flume_factor();
+        assert!((flux - (1.0 / pi + 0.25)).abs() < 1e-9, "{}", flux);
+        assert!(DirectionalSpreading::new(0.0, 0.0).is_err());
+        assert!(DirectionalSpreading::new(f64::NAN, 10.0).is_err());
+    }
+
+    #[test]
+    fn test_projection_onto_the_flume() {
+        let spectrum = WaveSpectrum::jonswap(1.0, 5.0, 3.3).unwrap();
+        let narrow = DirectionalSpectrum::new(spectrum.clone(), DirectionalSpreading::new(0.0, 75.0).unwrap());
+        let wide = DirectionalSpectrum::new(spectrum.clone(), DirectionalSpreading::new(0.0, 5.0).unwrap());
+        let oblique = DirectionalSpectrum::new(spectrum.clone(), DirectionalSpreading::new(std::f64::consts::FRAC_PI_4, 75.0).unwrap());
+        // Long-crested seas down the flume keep nearly all their energy, spread or oblique seas less
+        let height = |directional: &DirectionalSpectrum| directional.flume_spectrum().significant_wave_height();
+        assert!(height(&narrow) > 0.99 && height(&narrow) < 1.0);
+        assert!(height(&wide) < height(&narrow));
+        assert!((height(&oblique) / height(&narrow) - std::f64::consts::FRAC_PI_4.cos().sqrt()).abs() < 0.01);
+        assert_eq!(narrow.flume_spectrum().peak_period(), spectrum.peak_period());
+        assert!((narrow.density_at(0.2, 0.0) - spectrum.density_at(0.2) * narrow.spreading.density(0.0)).abs() < 1e-15);
+
+        // The directional components hold the variance of the frequency components
+        let rng = SeededRng::new(3);
+        let components = wide.components(32, 36, &rng);
+        assert_eq!(components.len(), 32 * 36);
+        let variance = |amplitudes: &mut dyn Iterator<Item = f64>| amplitudes.map(|a| 0.5 * a * a).sum::<f64>();
+        let frequency_variance = variance(&mut spectrum.components(32, &rng).iter().map(|component| component.amplitude));
+        assert!((variance(&mut components.iter().map(|component| component.amplitude)) / frequency_variance - 1.0).abs() < 1e-12);
+        assert!(components.iter().all(|component| (component.direction).abs() < std::f64::consts::PI));
+        assert_eq!(wide.components(32, 36, &rng), components);
+    }
+}
@@ -0,0 +1,537 @@
+use crate::error::CoastalError;
+use crate::numerics::solve_dense;
+use crate::units::{Length, Time};
+use crate::waves::dispersion::{DispersionRelation, DispersionSolver};
+use crate::waves::parameters::WaveParameters;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+/// Mean current of a steady wave, which its height and period alone leave undetermined
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum MeanCurrent {
+    /// Time-mean velocity at a fixed point below the troughs, Stokes' first definition [m/s]
+    Eulerian(f64),
+    /// Depth-averaged mass transport velocity, Stokes' second definition, zero in a closed flume [m/s]
+    MassTransport(f64),
+}
+
+impl Default for MeanCurrent {
+    fn default() -> Self {
+        MeanCurrent::MassTransport(0.0)
+    }
+}
+
+/// Steady wave solver of the Fourier approximation (stream function) method of Rienecker and Fenton (1981)
+///
+/// In the frame moving with the wave the flow is steady, and the stream function
+/// ψ = −ū y + Σ B_j sinh(jky)/cosh(jkd) cos(jkX) satisfies the Laplace equation and the bed condition
+/// exactly; the surface at N + 1 points over half a wavelength, the coefficients B_j, k, ū, the
+/// celerity c, the volume flux Q and the Bernoulli constant R follow from the kinematic and dynamic
+/// surface conditions at the points, the mean depth, the wave height, the period and the mean current,
+/// solved by Newton's method. The height is raised in steps from a linear wave, each solution
+/// extrapolated to start the next, so waves close to breaking converge.
+#[derive(Debug, Clone)]
+pub struct StreamFunctionSolver {
+    /// Number of Fourier modes N
+    harmonics: usize,
+    /// Number of steps of the wave height up to its target
+    height_steps: usize,
+    /// Maximum Newton iterations of each height step
+    max_iterations: usize,
+    /// Largest change of the unknowns at convergence, dimensionless with d and g
+    tolerance: f64,
+    /// Mean current of the wave
+    current: MeanCurrent,
+    /// Gravitational acceleration [m/s²]
+    gravity: f64,
+}
+
+impl Default for StreamFunctionSolver {
+    fn default() -> Self {
+        Self {
+            harmonics: 16,
+            height_steps: 4,
+            max_iterations: 50,
+            tolerance: 1e-9,
+            current: MeanCurrent::default(),
+            gravity: 9.81,
+        }
+    }
+}
+
+impl StreamFunctionSolver {
+    /// Create solver with 16 Fourier modes, 4 height steps and no mass transport
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of Fourier modes N, more for waves closer to breaking
+    pub fn with_harmonics(mut self, harmonics: usize) -> Self {
+        self.harmonics = harmonics;
+        self
+    }
+
+    /// Set the number of steps of the wave height up to its target
+    pub fn with_height_steps(mut self, height_steps: usize) -> Self {
+        self.height_steps = height_steps;
+        self
+    }
+
+    /// Set the maximum Newton iterations of each height step and the convergence tolerance
+    pub fn with_convergence(mut self, max_iterations: usize, tolerance: f64) -> Self {
+        self.max_iterations = max_iterations;
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Set the mean current of the wave
+    pub fn with_current(mut self, current: MeanCurrent) -> Self {
+        self.current = current;
+        self
+    }
+
+    /// Get the number of Fourier modes
+    pub fn harmonics(&self) -> usize {
+        self.harmonics
+    }
+
+    /// Get the number of height steps
+    pub fn height_steps(&self) -> usize {
+        self.height_steps
+    }
+
+    /// Solve the steady wave of a height and period in a depth
+    pub fn solve(&self, wave_height: Length, wave_period: Time, water_depth: Length) -> Result<StreamFunctionWave, CoastalError> {
+        let (height, period, depth) = (wave_height.in_meters(), wave_period.in_seconds(), water_depth.in_meters());
+        if height <= 0.0 {
+            return Err(CoastalError::not_positive("Wave height", height));
+        }
+        if period <= 0.0 {
+            return Err(CoastalError::not_positive("Wave period", period));
+        }
+        if depth <= 0.0 {
+            return Err(CoastalError::not_positive("Water depth", depth));
+        }
+        if self.harmonics < 2 {
+            return Err(CoastalError::InvalidParameter { name: "Number of harmonics", value: self.harmonics as f64, constraint: "at least 2" });
+        }
+        if self.height_steps == 0 {
+            return Err(CoastalError::not_positive("Number of height steps", 0.0));
+        }
+
+        // Dimensionless with the depth and gravity
+        let velocity_scale = (self.gravity * depth).sqrt();
+        let current = match self.current {
+            MeanCurrent::Eulerian(velocity) => MeanCurrent::Eulerian(velocity / velocity_scale),
+            MeanCurrent::MassTransport(velocity) => MeanCurrent::MassTransport(velocity / velocity_scale),
+        };
+        let mut problem = Problem { harmonics: self.harmonics, height: 0.0, period: period * (self.gravity / depth).sqrt(), current };
+
+        let mut solutions: Vec<Vec<f64>> = Vec::new();
+        let mut iterations = 0;
+        for step in 1..=self.height_steps {
+            problem.height = height / depth * step as f64 / self.height_steps as f64;
+            let mut unknowns = match solutions.as_slice() {
+                [] => problem.linear_guess()?,
+                [last] => last.clone(),
+                [.., before, last] => last.iter().zip(before).map(|(last, before)| 2.0 * last - before).collect(),
+            };
+            iterations += self.newton(&problem, &mut unknowns)?;
+            solutions.push(unknowns);
+        }
+        let unknowns = solutions.pop().unwrap_or_default();
+
+        // Cosine series of the surface through the points, η/d − 1 = Σ E_j cos(jkX)
+        let n = self.harmonics;
+        let surface_points = &unknowns[1..n + 2];
+        let surface = (0..=n)
+            .map(|j| {
+                let sum: f64 = surface_points
+                    .iter()
+                    .enumerate()
+                    .map(|(m, y)| {
+                        let weight = if m == 0 || m == n { 0.5 } else { 1.0 };
+                        weight * (y - 1.0) * (j as f64 * m as f64 * PI / n as f64).cos()
+                    })
+                    .sum();
+                if j == 0 || j == n { sum / n as f64 } else { 2.0 * sum / n as f64 }
+            })
+            .collect();
+
+        Ok(StreamFunctionWave {
+            depth,
+            gravity: self.gravity,
+            height,
+            period,
+            k: unknowns[0],
+            coefficients: unknowns[n + 2..2 * n + 2].to_vec(),
+            mean_velocity: unknowns[2 * n + 2],
+            celerity: unknowns[2 * n + 3],
+            flux: unknowns[2 * n + 4],
+            surface,
+            iterations,
+        })
+    }
+
+    /// Newton iterations with a finite difference Jacobian, returning the number of iterations
+    fn newton(&self, problem: &Problem, unknowns: &mut [f64]) -> Result<usize, CoastalError> {
+        let size = unknowns.len();
+        let largest = |values: &[f64]| values.iter().fold(0.0, |largest: f64, value| largest.max(value.abs()));
+        for iteration in 1..=self.max_iterations {
+            let residuals = problem.residuals(unknowns);
+            let mut jacobian = vec![0.0; size * size];
+            let mut shifted = unknowns.to_vec();
+            for col in 0..size {
+                let step = 1e-7 * unknowns[col].abs().max(1e-3);
+                shifted[col] = unknowns[col] + step;
+                for (row, value) in problem.residuals(&shifted).iter().enumerate() {
+                    jacobian[row * size + col] = (value - residuals[row]) / step;
+                }
+                shifted[col] = unknowns[col];
+            }
+            let rhs: Vec<f64> = residuals.iter().map(|value| -value).collect();
+            let failure = CoastalError::NonConvergence { iterations: iteration, residual: largest(&residuals) };
+            let change = solve_dense(&jacobian, &rhs, size, 1).map_err(|_| failure.clone())?;
+            if !change.iter().all(|value| value.is_finite()) {
+                return Err(failure);
+            }
+            for (value, change) in unknowns.iter_mut().zip(&change) {
+                *value += change;
+            }
+            if largest(&change) < self.tolerance {
+                return Ok(iteration);
+            }
+        }
+        Err(CoastalError::NonConvergence { iterations: self.max_iterations, residual: largest(&problem.residuals(unknowns)) })
+    }
+}
+
+/// Equations of a steady wave, dimensionless with the depth and gravity
+///
+/// The unknowns are k, the surface heights above the bed η_0..η_N from crest to trough,
+/// B_1..B_N, ū, c, Q and R.
+struct Problem {
+    harmonics: usize,
+    height: f64,
+    period: f64,
+    current: MeanCurrent,
+}
+
+impl Problem {
+    /// Linear wave of the height and period, the start of the first height step
+    fn linear_guess(&self) -> Result<Vec<f64>, CoastalError> {
+        let n = self.harmonics;
+        let omega = 2.0 * PI / self.period;
+        let k = DispersionSolver::with_params(100, 1e-12, 1.0).with_relation(DispersionRelation::Linear).solve_wave_number(omega, 1.0)?;
+        let celerity = omega / k;
+        let mean_velocity = celerity - match self.current {
+            MeanCurrent::Eulerian(velocity) | MeanCurrent::MassTransport(velocity) => velocity,
+        };
+        let amplitude = 0.5 * self.height;
+        let mut unknowns = vec![0.0; 2 * n + 6];
+        unknowns[0] = k;
+        for m in 0..=n {
+            unknowns[1 + m] = 1.0 + amplitude * (m as f64 * PI / n as f64).cos();
+        }
+        unknowns[n + 2] = mean_velocity * amplitude / k.tanh();
+        unknowns[2 * n + 2] = mean_velocity;
+        unknowns[2 * n + 3] = celerity;
+        unknowns[2 * n + 4] = mean_velocity;
+        unknowns[2 * n + 5] = 1.0 + 0.5 * mean_velocity * mean_velocity;
+        Ok(unknowns)
+    }
+
+    /// Residuals of the kinematic and dynamic conditions at each surface point, then of the mean depth,
+    /// the wave height, the period and the mean current
+    fn residuals(&self, unknowns: &[f64]) -> Vec<f64> {
+        let n = self.harmonics;
+        let k = unknowns[0];
+        let surface = &unknowns[1..n + 2];
+        let coefficients = &unknowns[n + 2..2 * n + 2];
+        let (mean_velocity, celerity, flux, bernoulli) = (unknowns[2 * n + 2], unknowns[2 * n + 3], unknowns[2 * n + 4], unknowns[2 * n + 5]);
+        let mut residuals = Vec::with_capacity(unknowns.len());
+        for (m, &y) in surface.iter().enumerate() {
+            let phase = m as f64 * PI / n as f64;
+            let (psi, u, v) = flow(k, coefficients, mean_velocity, phase, y);
+            residuals.push(psi + flux);
+            residuals.push(0.5 * (u * u + v * v) + y - bernoulli);
+        }
+        let mean = (0.5 * (surface[0] + surface[n]) + surface[1..n].iter().sum::<f64>()) / n as f64;
+        residuals.push(mean - 1.0);
+        residuals.push(surface[0] - surface[n] - self.height);
+        residuals.push(k * celerity * self.period - 2.0 * PI);
+        residuals.push(match self.current {
+            MeanCurrent::Eulerian(velocity) => celerity - mean_velocity - velocity,
+            MeanCurrent::MassTransport(velocity) => celerity - flux - velocity,
+        });
+        residuals
+    }
+}
+
+/// Stream function ψ and velocities (u, v) in the frame of the wave at phase kX and height y above the bed
+fn flow(k: f64, coefficients: &[f64], mean_velocity: f64, phase: f64, y: f64) -> (f64, f64, f64) {
+    let (mut psi, mut u, mut v) = (-mean_velocity * y, -mean_velocity, 0.0);
+    for (j, &coefficient) in coefficients.iter().enumerate() {
+        let order = (j + 1) as f64;
+        let (sinh, cosh) = depth_ratios(order * k, y);
+        let (sin, cos) = (order * phase).sin_cos();
+        psi += coefficient * sinh * cos;
+        u += order * k * coefficient * cosh * cos;
+        v += order * k * coefficient * sinh * sin;
+    }
+    (psi, u, v)
+}
+
+/// sinh(jky)/cosh(jkd) and cosh(jky)/cosh(jkd) with d = 1, without overflow for the short harmonics
+fn depth_ratios(jk: f64, y: f64) -> (f64, f64) {
+    let rising = (jk * (y - 1.0)).exp();
+    let falling = (-jk * (y + 1.0)).exp();
+    let norm = 1.0 + (-2.0 * jk).exp();
+    ((rising - falling) / norm, (rising + falling) / norm)
+}
+
+/// Harmonic of a stream function wave
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Harmonic {
+    pub order: usize,     // Order j, the multiple of the wave frequency
+    pub amplitude: f64,   // Amplitude E_j of the surface elevation [m]
+    pub coefficient: f64, // Coefficient B_j of the stream function [m²/s]
+}
+
+/// Steady nonlinear wave from the stream function solver, travelling in the positive x-direction
+/// with its crest at x = 0 at time zero, like a linear wave η = a cos(kx − ωt)
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamFunctionWave {
+    depth: f64,             // Still water depth d [m]
+    gravity: f64,           // Gravitational acceleration [m/s²]
+    height: f64,            // Wave height [m]
+    period: f64,            // Wave period [s]
+    k: f64,                 // Wave number, times d
+    coefficients: Vec<f64>, // B_j, over d√(gd)
+    mean_velocity: f64,     // Mean flow ū in the frame of the wave, over √(gd)
+    celerity: f64,          // Celerity, over √(gd)
+    flux: f64,              // Volume flux Q in the frame of the wave, over d√(gd)
+    surface: Vec<f64>,      // Cosine coefficients E_0..E_N of η/d − 1
+    iterations: usize,      // Newton iterations over all height steps
+}
+
+impl StreamFunctionWave {
+    /// Get wave height [m]
+    pub fn wave_height(&self) -> f64 {
+        self.height
+    }
+
+    /// Get wave period [s]
+    pub fn period(&self) -> f64 {
+        self.period
+    }
+
+    /// Get still water depth [m]
+    pub fn depth(&self) -> f64 {
+        self.depth
+    }
+
+    /// Wave number [rad/m]
+    pub fn wave_number(&self) -> f64 {
+        self.k / self.depth
+    }
+
+    /// Wavelength [m]
+    pub fn wavelength(&self) -> f64 {
+        2.0 * PI / self.wave_number()
+    }
+
+    /// Celerity, the speed of the crests [m/s]
+    pub fn celerity(&self) -> f64 {
+        self.celerity * self.velocity_scale()
+    }
+
+    /// Time-mean velocity at a fixed point below the troughs [m/s]
+    pub fn eulerian_current(&self) -> f64 {
+        (self.celerity - self.mean_velocity) * self.velocity_scale()
+    }
+
+    /// Depth-averaged mass transport velocity [m/s]
+    pub fn mass_transport_velocity(&self) -> f64 {
+        (self.celerity - self.flux) * self.velocity_scale()
+    }
+
+    /// Wave parameters of the steady wave, with its nonlinear wavelength and celerity
+    pub fn parameters(&self) -> WaveParameters {
+        WaveParameters {
+            k: self.wave_number(),
+            omega: 2.0 * PI / self.period,
+            c: self.celerity(),
+            h: self.height,
+            d: self.depth,
+            period: self.period,
+            wavelength: self.wavelength(),
+        }
+    }
+
+    /// Newton iterations over all height steps
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Harmonics of the surface elevation and the stream function, from the first to the N-th
+    pub fn harmonics(&self) -> Vec<Harmonic> {
+        let stream_scale = self.depth * self.velocity_scale();
+        self.coefficients
+            .iter()
+            .enumerate()
+            .map(|(j, coefficient)| Harmonic { order: j + 1, amplitude: self.surface[j + 1] * self.depth, coefficient: coefficient * stream_scale })
+            .collect()
+    }
+
+    /// Crest elevation above the still water level [m]
+    pub fn crest_elevation(&self) -> f64 {
+        self.surface_elevation(0.0, 0.0)
+    }
+
+    /// Trough elevation above the still water level, negative [m]
+    pub fn trough_elevation(&self) -> f64 {
+        self.surface_elevation(0.5 * self.wavelength(), 0.0)
+    }
+
+    /// Surface elevation at position x and time t above the still water level [m]
+    pub fn surface_elevation(&self, x: f64, time: f64) -> f64 {
+        self.depth * self.relative_surface(self.phase(x, time))
+    }
+
+    /// Horizontal and vertical velocity (u, w) at elevation z above the still water level, zero above the surface [m/s]
+    pub fn velocity_at(&self, x: f64, z: f64, time: f64) -> (f64, f64) {
+        let phase = self.phase(x, time);
+        let y = (z / self.depth + 1.0).max(0.0);
+        if y > 1.0 + self.relative_surface(phase) {
+            return (0.0, 0.0);
+        }
+        let (_, u, v) = flow(self.k, &self.coefficients, self.mean_velocity, phase, y);
+        ((self.celerity + u) * self.velocity_scale(), v * self.velocity_scale())
+    }
+
+    /// Average horizontal velocity over one of equal layers of the water column, numbered from the surface [m/s]
+    /// The volume between two heights is the difference of the stream function, so the average is exact
+    pub fn layer_velocity(&self, x: f64, time: f64, layer: usize, layers: usize) -> f64 {
+        let phase = self.phase(x, time);
+        let total_depth = 1.0 + self.relative_surface(phase);
+        let top = total_depth * (1.0 - layer as f64 / layers as f64);
+        let bottom = total_depth * (1.0 - (layer + 1) as f64 / layers as f64);
+        let psi = |y: f64| flow(self.k, &self.coefficients, self.mean_velocity, phase, y).0;
+        (self.celerity + (psi(top) - psi(bottom)) / (top - bottom)) * self.velocity_scale()
+    }
+
+    /// Depth-averaged horizontal velocity [m/s]
+    pub fn depth_averaged_velocity(&self, x: f64, time: f64) -> f64 {
+        self.layer_velocity(x, time, 0, 1)
+    }
+
+    /// Phase kX = k(x − ct) of a position and time
+    fn phase(&self, x: f64, time: f64) -> f64 {
+        self.wave_number() * (x - self.celerity() * time)
+    }
+
+    /// Surface elevation over the depth, η/d, at a phase
+    fn relative_surface(&self, phase: f64) -> f64 {
+        self.surface.iter().enumerate().map(|(j, amplitude)| amplitude * (j as f64 * phase).cos()).sum()
+    }
+
+    /// Velocity scale √(gd) [m/s]
+    fn velocity_scale(&self) -> f64 {
+        (self.gravity * self.depth).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve(height: f64, period: f64, depth: f64) -> StreamFunctionWave {
+        StreamFunctionSolver::new().solve(Length::meters(height), Time::seconds(period), Length::meters(depth)).unwrap()
+    }
+
+    #[test]
+    fn test_low_waves_are_linear() {
+        let wave = solve(0.001, 2.0, 1.0);
+        let linear = DispersionSolver::linear().wavelength(Time::seconds(2.0), Length::meters(1.0)).unwrap().in_meters();
+        assert!((wave.wavelength() / linear - 1.0).abs() < 1e-5, "{} {}", wave.wavelength(), linear);
+        assert!((wave.crest_elevation() - 0.0005).abs() < 1e-6);
+        assert!((wave.crest_elevation() - wave.trough_elevation() - 0.001).abs() < 1e-12);
+        let harmonics = wave.harmonics();
+        assert_eq!(harmonics.len(), 16);
+        assert!((harmonics[0].amplitude - 0.0005).abs() < 1e-6);
+        assert!(harmonics[1].amplitude.abs() < 0.01 * harmonics[0].amplitude);
+    }
+
+    #[test]
+    fn test_deep_water_stokes_limit() {
+        // ka = 0.05 in deep water without a mean current: at a given period Stokes theory has k = k₀/(1 + (ka)²),
+        // so c = c₀(1 + (ka)²), and a crest higher than the trough is deep by ka²
+        let period = 1.0;
+        let k0 = 4.0 * PI * PI / (9.81 * period * period);
+        let wave = StreamFunctionSolver::new().with_current(MeanCurrent::Eulerian(0.0)).solve(Length::meters(0.1 / k0), Time::seconds(period), Length::meters(5.0)).unwrap();
+        let (k, amplitude) = (wave.wave_number(), wave.harmonics()[0].amplitude);
+        let c0 = 9.81 * period / (2.0 * PI);
+        assert!(((wave.celerity() / c0 - 1.0) / (k * amplitude).powi(2) - 1.0).abs() < 0.01, "{}", wave.celerity() / c0);
+        let asymmetry = wave.crest_elevation() + wave.trough_elevation();
+        assert!((asymmetry / (k * amplitude * amplitude) - 1.0).abs() < 0.01, "{}", asymmetry / (k * amplitude * amplitude));
+        assert!((wave.harmonics()[1].amplitude / (0.5 * k * amplitude * amplitude) - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_surface_conditions_between_points() {
+        // A steep wave, H/d = 0.3 with a crest at 70% of the height, meets the surface conditions everywhere, not only at the points
+        let wave = solve(0.3, 4.0, 1.0);
+        assert!(wave.crest_elevation() > 0.69 * wave.wave_height());
+        let mut bernoulli = Vec::new();
+        for i in 0..50 {
+            let x = (i as f64 + 0.37) / 50.0 * wave.wavelength();
+            let eta = wave.surface_elevation(x, 0.0);
+            let (u, w) = wave.velocity_at(x, eta, 0.0);
+            bernoulli.push(0.5 * ((u - wave.celerity()).powi(2) + w * w) + 9.81 * eta);
+            // The surface is a streamline: its slope follows the flow relative to the crests
+            let slope = (wave.surface_elevation(x + 1e-5, 0.0) - wave.surface_elevation(x - 1e-5, 0.0)) / 2e-5;
+            assert!((w - (u - wave.celerity()) * slope).abs() < 1e-4, "{}", w - (u - wave.celerity()) * slope);
+        }
+        let spread = bernoulli.iter().fold(f64::NEG_INFINITY, |a: f64, &b| a.max(b)) - bernoulli.iter().fold(f64::INFINITY, |a: f64, &b| a.min(b));
+        assert!(spread < 1e-4 * 9.81 * wave.wave_height(), "{:.2e}", spread);
+        assert_eq!(wave.velocity_at(0.0, wave.crest_elevation() + 0.01, 0.0), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_mean_current() {
+        // No mass transport by default: the flux through a fixed section averages to zero over a period
+        let wave = solve(0.3, 4.0, 1.0);
+        assert!(wave.mass_transport_velocity().abs() < 1e-9);
+        let samples = 400;
+        let flux: f64 = (0..samples)
+            .map(|i| {
+                let time = i as f64 / samples as f64 * wave.period();
+                (1.0 + wave.surface_elevation(0.0, time)) * wave.depth_averaged_velocity(0.0, time)
+            })
+            .sum::<f64>() / samples as f64;
+        assert!(flux.abs() < 1e-6, "{:.2e}", flux);
+        // The return flow shows as a negative Eulerian current
+        assert!(wave.eulerian_current() < 0.0);
+        let layers: f64 = (0..4).map(|layer| wave.layer_velocity(1.0, 0.3, layer, 4)).sum::<f64>() / 4.0;
+        assert!((layers - wave.depth_averaged_velocity(1.0, 0.3)).abs() < 1e-12);
+
+        let still = StreamFunctionSolver::new().with_current(MeanCurrent::Eulerian(0.0)).solve(Length::meters(0.3), Time::seconds(4.0), Length::meters(1.0)).unwrap();
+        assert!(still.eulerian_current().abs() < 1e-9);
+        assert!(still.mass_transport_velocity() > 0.0);
+    }
+
+    #[test]
+    fn test_convergence_controls() {
+        let solver = StreamFunctionSolver::new().with_harmonics(24).with_height_steps(8);
+        assert_eq!((solver.harmonics(), solver.height_steps()), (24, 8));
+        let wave = solver.solve(Length::meters(0.6), Time::seconds(10.0), Length::meters(1.0)).unwrap();
+        assert!(wave.iterations() >= 8);
+        assert_eq!(wave.harmonics().len(), 24);
+
+        let error = StreamFunctionSolver::new().with_convergence(1, 1e-12).solve(Length::meters(0.3), Time::seconds(4.0), Length::meters(1.0)).unwrap_err();
+        assert!(matches!(error, CoastalError::NonConvergence { iterations: 1, .. }));
+        assert!(StreamFunctionSolver::new().with_harmonics(1).solve(Length::meters(0.3), Time::seconds(4.0), Length::meters(1.0)).is_err());
+        assert!(StreamFunctionSolver::new().solve(Length::meters(0.0), Time::seconds(4.0), Length::meters(1.0)).is_err());
+    }
+}
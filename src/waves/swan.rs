@@ -0,0 +1,260 @@
+use crate::waves::WATER_DENSITY;
+use crate::waves::metocean::SpectrumRecord;
+use crate::waves::spectrum::WaveSpectrum;
+
+/// Lines of a SWAN spectral file with their numbers, without the '$' comments
+///
+/// Keyword and value lines of the header are followed by a description, so only their first token is
+/// read. Tables of numbers may wrap over several lines and are read value by value.
+struct SwanLines<'a> {
+    lines: Vec<(usize, &'a str)>,
+    next: usize,
+}
+
+impl<'a> SwanLines<'a> {
+    fn new(content: &'a str) -> Self {
+        let lines = content
+            .lines()
+            .enumerate()
+            .map(|(number, line)| (number + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('$'))
+            .collect();
+        Self { lines, next: 0 }
+    }
+
+    fn is_done(&self) -> bool {
+        self.next >= self.lines.len()
+    }
+
+    /// Next line, with its number
+    fn line(&mut self) -> Result<(usize, &'a str), String> {
+        let line = *self.lines.get(self.next).ok_or("SWAN file ends before the last spectrum")?;
+        self.next += 1;
+        Ok(line)
+    }
+
+    /// First word of the next line, in capitals
+    fn keyword(&mut self) -> Result<(usize, String), String> {
+        let (number, line) = self.line()?;
+        Ok((number, line.split_whitespace().next().unwrap_or_default().to_uppercase()))
+    }
+
+    /// Value at the start of the next line
+    fn number<T: std::str::FromStr>(&mut self) -> Result<T, String>
+    where
+        T::Err: std::fmt::Display,
+    {
+        let (number, line) = self.line()?;
+        let field = line.split_whitespace().next().unwrap_or_default();
+        field.parse::<T>().map_err(|e| format!("Invalid number on line {}: {}", number, e))
+    }
+
+    /// Numbers of a table, over as many lines as needed
+    fn values(&mut self, count: usize) -> Result<Vec<f64>, String> {
+        let mut values = Vec::with_capacity(count);
+        while values.len() < count {
+            let (number, line) = self.line()?;
+            for field in line.split_whitespace() {
+                values.push(field.parse::<f64>().map_err(|e| format!("Invalid number on line {}: {}", number, e))?);
+            }
+        }
+        if values.len() > count {
+            return Err(format!("SWAN table ends with {} values instead of {}", values.len(), count));
+        }
+        Ok(values)
+    }
+
+    /// Value at the start of each of the next lines
+    fn column(&mut self, count: usize) -> Result<Vec<f64>, String> {
+        (0..count).map(|_| self.number::<f64>()).collect()
+    }
+}
+
+/// Spectra of a SWAN spectral output file, 1D (.sp1) or 2D (.sp2)
+///
+/// The header lists the output locations, the frequencies (AFREQ or RFREQ), the directions of 2D
+/// spectra (NDIR or CDIR) and the quantities with their exception values. Every location then
+/// holds a table per output time of nonstationary runs: one row of quantities per frequency for 1D
+/// spectra, or a FACTOR scaling integer densities per frequency and direction for 2D spectra, which
+/// are integrated over the directions. Energy densities (EnDens) are turned into variance densities
+/// by dividing by ρg. Locations without data (NODATA) or energy (ZERO) are skipped.
+pub fn parse_swan(content: &str) -> Result<Vec<SpectrumRecord>, String> {
+    let mut lines = SwanLines::new(content);
+    let (_, keyword) = lines.keyword()?;
+    if keyword != "SWAN" {
+        return Err("SWAN spectral files start with the SWAN keyword".to_string());
+    }
+
+    let mut time_dependent = false;
+    let mut locations = 1;
+    let mut frequencies = Vec::new();
+    let mut directions = Vec::new();
+    // Column of the density among the quantities of a 1D table, its exception value and its conversion to m²/Hz
+    let (quantities, density_column, exception, scale) = loop {
+        let (number, keyword) = lines.keyword()?;
+        match keyword.as_str() {
+            "TIME" => {
+                lines.number::<i32>()?;
+                time_dependent = true;
+            }
+            "LOCATIONS" | "LONLAT" => {
+                locations = lines.number::<usize>()?;
+                lines.column(locations)?;
+            }
+            "AFREQ" | "RFREQ" => {
+                let count = lines.number::<usize>()?;
+                frequencies = lines.column(count)?;
+            }
+            "NDIR" | "CDIR" => {
+                let count = lines.number::<usize>()?;
+                directions = lines.column(count)?;
+            }
+            "QUANT" => {
+                let count = lines.number::<usize>()?;
+                let mut density = None;
+                for column in 0..count {
+                    let (_, name) = lines.keyword()?;
+                    lines.line()?;
+                    let exception = lines.number::<f64>()?;
+                    match name.as_str() {
+                        "VADENS" => density = Some((column, exception, 1.0)),
+                        "ENDENS" => density = Some((column, exception, 1.0 / (WATER_DENSITY * 9.81))),
+                        _ => {}
+                    }
+                }
+                let (column, exception, scale) = density.ok_or("SWAN file holds no variance or energy density")?;
+                break (count, column, exception, scale);
+            }
+            _ => return Err(format!("Unknown SWAN keyword {} on line {}", keyword, number)),
+        }
+    };
+    if frequencies.is_empty() {
+        return Err("SWAN file lists no frequencies".to_string());
+    }
+    // Densities of 2D spectra are per degree
+    let direction_width = match directions.as_slice() {
+        [] => None,
+        [_] => Some(360.0),
+        [first, second, ..] => {
+            let spacing = (second - first).rem_euclid(360.0);
+            Some(spacing.min(360.0 - spacing))
+        }
+    };
+
+    let mut records = Vec::new();
+    while !lines.is_done() {
+        let time = if time_dependent { Some(lines.line()?.1.split_whitespace().next().unwrap_or_default().to_string()) } else { None };
+        for location in 1..=locations {
+            let label = match (&time, locations > 1) {
+                (Some(time), true) => format!("{} location {}", time, location),
+                (Some(time), false) => time.clone(),
+                (None, _) => format!("Location {}", location),
+            };
+            let (mut number, mut keyword) = lines.keyword()?;
+            if keyword == "LOCATION" && direction_width.is_some() {
+                (number, keyword) = lines.keyword()?;
+            }
+            let density = match (keyword.as_str(), direction_width) {
+                ("NODATA" | "ZERO", _) => continue,
+                ("LOCATION", None) => {
+                    let table = lines.values(frequencies.len() * quantities)?;
+                    let density: Vec<f64> = table.chunks(quantities).map(|row| row[density_column]).collect();
+                    if density.contains(&exception) {
+                        continue;
+                    }
+                    density.iter().map(|value| value * scale).collect()
+                }
+                ("FACTOR", Some(width)) => {
+                    let factor = lines.number::<f64>()?;
+                    let table = lines.values(frequencies.len() * directions.len())?;
+                    if table.iter().any(|&value| value < 0.0) {
+                        continue;
+                    }
+                    table.chunks(directions.len()).map(|row| row.iter().sum::<f64>() * factor * width * scale).collect()
+                }
+                _ => return Err(format!("Unexpected SWAN keyword {} on line {}", keyword, number)),
+            };
+            // Calm spectra hold no energy
+            if let Ok(spectrum) = WaveSpectrum::new(frequencies.clone(), density) {
+                records.push(SpectrumRecord { time: label, spectrum });
+            }
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::waves::metocean::MetoceanFormat;
+
+    #[test]
+    fn test_swan_1d_spectra() {
+        let content = "SWAN   1                                Swan standard spectral file, version
+$   Data produced by SWAN version 41.31
+TIME                                    time-dependent data
+     1                                  time coding option
+LONLAT                                  locations in spherical coordinates
+     2                                  number of locations
+   -4.000000    52.000000
+   -4.100000    52.000000
+AFREQ                                   absolute frequencies in Hz
+     3                                  number of frequencies
+    0.0500
+    0.1000
+    0.1500
+QUANT
+     3                                  number of quantities in table
+VaDens                                  variance densities in m2/Hz
+m2/Hz                                   unit
+   -0.9900E+02                          exception value
+CDIR                                    average Cartesian direction in degr
+degr                                    unit
+   -0.9990E+03                          exception value
+DSPRDEGR                                directional spreading
+degr                                    unit
+   -0.9000E+01                          exception value
+20200101.000000                         date and time
+LOCATION     1
+   0.1000E+00   270.0   30.0
+   0.4000E+00   265.0   25.0
+   0.2000E+00   260.0   28.0
+NODATA
+20200101.010000                         date and time
+LOCATION     1
+   0.2000E+00   270.0   30.0
+   0.8000E+00   265.0   25.0
+   0.4000E+00   260.0   28.0
+LOCATION     2
+   0.0000E+00   270.0   30.0
+   0.0000E+00   265.0   25.0
+   0.0000E+00   260.0   28.0
+";
+        assert_eq!(MetoceanFormat::detect(content), MetoceanFormat::Swan);
+        let records = parse_swan(content).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].time, "20200101.000000 location 1");
+        assert_eq!(records[0].spectrum.frequencies(), [0.05, 0.1, 0.15]);
+        assert_eq!(records[0].spectrum.density(), [0.1, 0.4, 0.2]);
+        assert_eq!(records[1].time, "20200101.010000 location 1");
+        assert!((records[1].spectrum.moment(0) / records[0].spectrum.moment(0) - 2.0).abs() < 1e-12);
+
+        assert!(parse_swan("SWAN 1\nAFREQ\n 2\n 0.1\n 0.2\nQUANT\n 1\nVaDens\nm2/Hz\n -99\nLOCATION 1\n 0.1\n").is_err());
+        assert!(parse_swan("SWN 1\n").is_err());
+    }
+
+    #[test]
+    fn test_swan_2d_spectra() {
+        // Stationary run: four directions 90° apart, densities per degree
+        let content = "SWAN 1\nLOCATIONS\n 1\n 100.0 0.0\nAFREQ\n 2\n 0.1\n 0.2\nNDIR\n 4\n 0.0\n 90.0\n 180.0\n 270.0\nQUANT\n 1\nVaDens\nm2/Hz/degr\n -99\nFACTOR\n 0.1E-02\n 1 2 3 4\n 0 0 5 5\n";
+        let records = parse_swan(content).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].time, "Location 1");
+        let density = records[0].spectrum.density();
+        assert!((density[0] - 10.0 * 1e-3 * 90.0).abs() < 1e-12 && (density[1] - 10.0 * 1e-3 * 90.0).abs() < 1e-12);
+
+        let energy = content.replace("VaDens", "EnDens");
+        let scaled = parse_swan(&energy).unwrap();
+        assert!((scaled[0].spectrum.density()[0] * WATER_DENSITY * 9.81 - density[0]).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,160 @@
+use crate::error::CoastalError;
+use crate::units::{Length, Time};
+use crate::waves::dispersion::DispersionSolver;
+use crate::waves::parameters::breaking_limit;
+use serde::{Deserialize, Serialize};
+
+/// Gravitational acceleration [m/s²]
+const GRAVITY: f64 = 9.81;
+
+/// Ursell number H L² / d³ above which cnoidal theory describes the waves better than Stokes theory
+pub const URSELL_CNOIDAL: f64 = 26.0;
+
+/// Second harmonic of Stokes theory relative to the first below which linear theory suffices
+const LINEAR_HARMONIC_RATIO: f64 = 0.05;
+
+/// Wave height relative to the breaking limit above which second-order Stokes theory is not enough
+const STOKES_FIFTH_HEIGHT_RATIO: f64 = 0.5;
+
+/// Wave height relative to the breaking limit above which only stream function theory is accurate
+const STREAM_FUNCTION_HEIGHT_RATIO: f64 = 0.8;
+
+/// Ursell number H L² / d³, the ratio of the nonlinearity H/d to the dispersion (d/L)²
+pub fn ursell_number(wave_height: f64, wavelength: f64, depth: f64) -> f64 {
+    wave_height * wavelength * wavelength / depth.powi(3)
+}
+
+/// Wave theory describing regular waves, as chosen for their generation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WaveTheory {
+    Linear,
+    StokesSecond,
+    StokesFifth,
+    Cnoidal,
+    StreamFunction,
+}
+
+impl std::fmt::Display for WaveTheory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WaveTheory::Linear => write!(f, "Linear (Airy)"),
+            WaveTheory::StokesSecond => write!(f, "Stokes 2nd order"),
+            WaveTheory::StokesFifth => write!(f, "Stokes 5th order"),
+            WaveTheory::Cnoidal => write!(f, "Cnoidal"),
+            WaveTheory::StreamFunction => write!(f, "Stream function"),
+        }
+    }
+}
+
+impl WaveTheory {
+    pub const ALL: [WaveTheory; 5] = [WaveTheory::Linear, WaveTheory::StokesSecond, WaveTheory::StokesFifth, WaveTheory::Cnoidal, WaveTheory::StreamFunction];
+
+    /// Theory applicable to waves of a height and period in a depth, following the regions of the
+    /// diagram of Le Méhauté (1976)
+    ///
+    /// Near breaking only stream function theory holds; otherwise cnoidal theory takes over from Stokes
+    /// theory at an Ursell number of 26, and linear theory suffices while the second harmonic of Stokes
+    /// theory stays below 5% of the first, ak < 0.1 in deep water.
+    pub fn recommended(wave_height: f64, wave_period: f64, depth: f64) -> Result<WaveTheory, CoastalError> {
+        if wave_height <= 0.0 {
+            return Err(CoastalError::not_positive("Wave height", wave_height));
+        }
+        let limit = breaking_limit(Time::seconds(wave_period), Length::meters(depth))?.0.in_meters();
+        let wavelength = DispersionSolver::linear().wavelength(Time::seconds(wave_period), Length::meters(depth))?.in_meters();
+        let relative_height = wave_height / limit;
+        Ok(if relative_height >= STREAM_FUNCTION_HEIGHT_RATIO {
+            WaveTheory::StreamFunction
+        } else if ursell_number(wave_height, wavelength, depth) >= URSELL_CNOIDAL {
+            WaveTheory::Cnoidal
+        } else if second_harmonic_ratio(wave_height, 2.0 * std::f64::consts::PI / wavelength, depth) < LINEAR_HARMONIC_RATIO {
+            WaveTheory::Linear
+        } else if relative_height < STOKES_FIFTH_HEIGHT_RATIO {
+            WaveTheory::StokesSecond
+        } else {
+            WaveTheory::StokesFifth
+        })
+    }
+}
+
+/// Amplitude of the second harmonic of Stokes theory relative to the first
+/// a₂/a = (ka/4) cosh(kd)(2 + cosh(2kd)) / sinh³(kd), ka/2 in deep water and 3Ur/32π² in shallow water
+fn second_harmonic_ratio(wave_height: f64, k: f64, depth: f64) -> f64 {
+    let kd = k * depth;
+    0.25 * k * 0.5 * wave_height * kd.cosh() * (2.0 + (2.0 * kd).cosh()) / kd.sinh().powi(3)
+}
+
+/// Boundaries of the regions of the Le Méhauté diagram at a relative depth d/gT², as relative heights H/gT²
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TheoryBoundaries {
+    pub linear: f64,          // Upper limit of linear theory
+    pub cnoidal: f64,         // Ursell number of 26, above which cnoidal theory applies
+    pub stokes_fifth: f64,    // Lower limit of fifth-order Stokes theory
+    pub stream_function: f64, // Lower limit of stream function theory
+    pub breaking: f64,        // Breaking limit
+}
+
+impl TheoryBoundaries {
+    /// Boundaries at a relative depth d/gT²
+    pub fn at(relative_depth: f64) -> Result<TheoryBoundaries, CoastalError> {
+        // The diagram is dimensionless, so a period of one second gives the heights directly over g
+        let depth = relative_depth * GRAVITY;
+        let breaking = breaking_limit(Time::seconds(1.0), Length::meters(depth))?.0.in_meters();
+        let wavelength = DispersionSolver::linear().wavelength(Time::seconds(1.0), Length::meters(depth))?.in_meters();
+        let k = 2.0 * std::f64::consts::PI / wavelength;
+        // The second harmonic ratio grows linearly with the height
+        let linear = LINEAR_HARMONIC_RATIO / second_harmonic_ratio(1.0, k, depth);
+        Ok(TheoryBoundaries {
+            linear: linear / GRAVITY,
+            cnoidal: URSELL_CNOIDAL * depth.powi(3) / (wavelength * wavelength) / GRAVITY,
+            stokes_fifth: STOKES_FIFTH_HEIGHT_RATIO * breaking / GRAVITY,
+            stream_function: STREAM_FUNCTION_HEIGHT_RATIO * breaking / GRAVITY,
+            breaking: breaking / GRAVITY,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ursell_number() {
+        assert!((ursell_number(0.1, 10.0, 1.0) - 10.0).abs() < 1e-12);
+        // The second harmonic ratio tends to 3Ur/32π² in shallow water and to ka/2 in deep water
+        let (depth, k) = (0.1, 0.01);
+        let ursell = ursell_number(0.01, 2.0 * std::f64::consts::PI / k, depth);
+        assert!((second_harmonic_ratio(0.01, k, depth) / (3.0 * ursell / (32.0 * std::f64::consts::PI.powi(2))) - 1.0).abs() < 1e-3);
+        assert!((second_harmonic_ratio(0.1, 1.0, 50.0) - 0.025).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_recommended_theory() {
+        // Low waves in deep water are linear, steeper ones need Stokes theory, the steepest stream functions
+        assert_eq!(WaveTheory::recommended(0.02, 2.0, 5.0).unwrap(), WaveTheory::Linear);
+        assert_eq!(WaveTheory::recommended(0.25, 2.0, 5.0).unwrap(), WaveTheory::StokesSecond);
+        assert_eq!(WaveTheory::recommended(0.5, 2.0, 5.0).unwrap(), WaveTheory::StokesFifth);
+        assert_eq!(WaveTheory::recommended(0.8, 2.0, 5.0).unwrap(), WaveTheory::StreamFunction);
+        // Long waves in shallow water are cnoidal
+        assert_eq!(WaveTheory::recommended(0.2, 10.0, 1.0).unwrap(), WaveTheory::Cnoidal);
+        assert_eq!(WaveTheory::recommended(0.7, 10.0, 1.0).unwrap(), WaveTheory::StreamFunction);
+        assert!(WaveTheory::recommended(0.0, 2.0, 5.0).is_err());
+        assert!(WaveTheory::recommended(0.1, 2.0, -1.0).is_err());
+    }
+
+    #[test]
+    fn test_boundaries_agree_with_recommendation() {
+        for relative_depth in [0.001, 0.005, 0.02, 0.1] {
+            let boundaries = TheoryBoundaries::at(relative_depth).unwrap();
+            assert!(boundaries.stokes_fifth < boundaries.stream_function && boundaries.stream_function < boundaries.breaking);
+            // Just below and above each boundary the recommendation changes, at a period of 2 s
+            let (period, depth) = (2.0, relative_depth * GRAVITY * 4.0);
+            let theory = |relative_height: f64| WaveTheory::recommended(relative_height * GRAVITY * 4.0, period, depth).unwrap();
+            assert_eq!(theory(0.99 * boundaries.stream_function), if boundaries.cnoidal < boundaries.stream_function { WaveTheory::Cnoidal } else { WaveTheory::StokesFifth });
+            assert_eq!(theory(1.01 * boundaries.stream_function), WaveTheory::StreamFunction);
+            if boundaries.linear < boundaries.cnoidal.min(boundaries.stream_function) {
+                assert_eq!(theory(0.99 * boundaries.linear), WaveTheory::Linear);
+                assert_ne!(theory(1.01 * boundaries.linear), WaveTheory::Linear);
+            }
+        }
+    }
+}
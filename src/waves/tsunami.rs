@@ -0,0 +1,88 @@
+use crate::analysis::BoundarySignal;
+use serde::{Deserialize, Serialize};
+
+/// Samples per period of the time series of an N-wave
+const SAMPLES_PER_PERIOD: f64 = 40.0;
+
+/// Periods between the start of the signal and the middle of the N-wave, so it starts from rest
+const LEAD_PERIODS: f64 = 2.0;
+
+/// Isosceles N-wave of Tadepalli and Synolakis (1994), the wave of a tsunami reaching the coast
+///
+/// The surface at the boundary follows η(t) = ±(3√3/2) H sech²(γ(t − t₀)) tanh(γ(t − t₀)): a crest and
+/// a trough of the same height H, half a period apart, with a trough first for a leading depression
+/// wave, as the drawdown seen before many tsunamis, and a crest first for a leading elevation wave.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NWave {
+    pub height: f64,              // Height of the crest and depth of the trough (H) [m]
+    pub period: f64,              // Period of the wave, twice the time from the crest to the trough (T) [s]
+    pub leading_depression: bool, // Trough first rather than crest first
+}
+
+impl NWave {
+    /// Create new N-wave
+    pub fn new(height: f64, period: f64, leading_depression: bool) -> Result<Self, String> {
+        let wave = Self { height, period, leading_depression };
+        wave.validate()?;
+        Ok(wave)
+    }
+
+    /// Check that the height and the period are positive
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.height.is_finite() && self.height > 0.0) {
+            return Err("N-wave height must be positive".to_string());
+        }
+        if !(self.period.is_finite() && self.period > 0.0) {
+            return Err("N-wave period must be positive".to_string());
+        }
+        Ok(())
+    }
+
+    /// Time of the middle of the wave, between the crest and the trough (t₀) [s]
+    pub fn center_time(&self) -> f64 {
+        LEAD_PERIODS * self.period
+    }
+
+    /// Surface elevation at the boundary at a time [m]
+    pub fn elevation(&self, time: f64) -> f64 {
+        // The extrema of sech² tanh lie at tanh = ±1/√3, half a period apart
+        let gamma = 4.0 * (1.0 / 3f64.sqrt()).atanh() / self.period;
+        let phase = gamma * (time - self.center_time());
+        let sign = if self.leading_depression { 1.0 } else { -1.0 };
+        sign * 1.5 * 3f64.sqrt() * self.height * phase.tanh() / phase.cosh().powi(2)
+    }
+
+    /// Time series of the wave at the boundary, from rest to rest over twice the lead time [s]
+    pub fn signal(&self) -> Result<BoundarySignal, String> {
+        self.validate()?;
+        let interval = self.period / SAMPLES_PER_PERIOD;
+        let samples = (2.0 * LEAD_PERIODS * SAMPLES_PER_PERIOD).round() as usize + 1;
+        let times: Vec<f64> = (0..samples).map(|i| i as f64 * interval).collect();
+        let elevation = times.iter().map(|&time| self.elevation(time)).collect();
+        BoundarySignal::time_series(times, elevation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_n_wave_shape() {
+        let wave = NWave::new(0.1, 20.0, true).unwrap();
+        let times: Vec<f64> = (0..=8000).map(|i| i as f64 * 0.01).collect();
+        let (trough, crest) = times.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), &time| (low.min(wave.elevation(time)), high.max(wave.elevation(time))));
+        assert!((crest - 0.1).abs() < 1e-6 && (trough + 0.1).abs() < 1e-6);
+        // Trough a quarter period before the middle, crest a quarter period after
+        assert!((wave.elevation(35.0) + 0.1).abs() < 1e-4 && (wave.elevation(45.0) - 0.1).abs() < 1e-4);
+        assert!(wave.elevation(0.0).abs() < 1e-3);
+        let elevation = NWave { leading_depression: false, ..wave };
+        assert_eq!(elevation.elevation(35.0), -wave.elevation(35.0));
+
+        // Sampled from rest to rest, with no mean
+        let BoundarySignal::TimeSeries { times, elevation } = wave.signal().unwrap() else { panic!("N-wave must be a time series") };
+        assert_eq!((times.len(), times[160]), (161, 80.0));
+        assert!(elevation.iter().sum::<f64>().abs() < 1e-9);
+        assert!(NWave::new(0.0, 20.0, true).is_err());
+    }
+}
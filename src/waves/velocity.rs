@@ -1,11 +1,24 @@
+use crate::error::CoastalError;
 use crate::waves::parameters::WaveParameters;
 
+/// Mapping of the linear depth functions to points between the still water level and the crest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Stretching {
+    /// Evaluate the depth functions at the actual height, continuing them above the still water level
+    #[default]
+    Extrapolation,
+    /// Stretch the water column from the bed to the instantaneous surface onto the still water depth (Wheeler, 1970)
+    Wheeler,
+}
+
 /// Velocity calculator for wave generation using linear wave theory
 pub struct VelocityCalculator {
     /// Wave parameters
     params: WaveParameters,
     /// Gravitational acceleration [m/s²]
     gravity: f64,
+    /// Mapping of the kinematics above the still water level
+    stretching: Stretching,
 }
 
 impl VelocityCalculator {
@@ -14,9 +27,21 @@ impl VelocityCalculator {
         Self {
             params,
             gravity: 9.81,
+            stretching: Stretching::default(),
         }
     }
     
+    /// Set the mapping of the kinematics above the still water level
+    pub fn with_stretching(mut self, stretching: Stretching) -> Self {
+        self.stretching = stretching;
+        self
+    }
+    
+    /// Get the mapping of the kinematics above the still water level
+    pub fn stretching(&self) -> Stretching {
+        self.stretching
+    }
+    
     /// Update wave parameters
     pub fn update_parameters(&mut self, params: WaveParameters) {
         self.params = params;
@@ -46,10 +71,71 @@ impl VelocityCalculator {
         amplitude * self.params.c * velocity_coeff * phase.cos()
     }
     
-    /// Compute vertical velocity at given position and time
-    /// For 1D horizontal wave propagation, w = 0 (no vertical motion)
-    pub fn vertical_velocity(&self, _x: f64, _time: f64) -> f64 {
-        0.0
+    /// Compute depth-averaged vertical velocity at given position and time
+    /// w̄ = aω (cosh(kd) − 1)/(kd sinh(kd)) sin(kx − ωt), half the surface value in shallow water
+    pub fn vertical_velocity(&self, x: f64, time: f64) -> f64 {
+        let phase = self.params.k * x - self.params.omega * time;
+        let kd = self.params.k * self.params.d;
+        self.params.amplitude() * self.params.omega * (kd.cosh() - 1.0) / (kd * kd.sinh()) * phase.sin()
+    }
+    
+    /// Height k(z + d) above the bed at which the depth functions are evaluated for elevation z
+    /// None above the instantaneous surface; points below the bed take the bed value
+    fn profile_height(&self, x: f64, z: f64, time: f64) -> Option<f64> {
+        let eta = self.surface_elevation(x, time);
+        let d = self.params.d;
+        if z > eta {
+            return None;
+        }
+        let z = match self.stretching {
+            Stretching::Extrapolation => z,
+            Stretching::Wheeler => d * (z - eta) / (d + eta),
+        };
+        Some(self.params.k * (z + d).max(0.0))
+    }
+    
+    /// Compute horizontal velocity at elevation z above the still water level, zero above the surface
+    /// u = aω cosh(k(z+d))/sinh(kd) cos(kx − ωt)
+    pub fn horizontal_velocity_at(&self, x: f64, z: f64, time: f64) -> f64 {
+        let Some(height) = self.profile_height(x, z, time) else {
+            return 0.0;
+        };
+        let phase = self.params.k * x - self.params.omega * time;
+        self.params.amplitude() * self.params.omega * height.cosh() / (self.params.k * self.params.d).sinh() * phase.cos()
+    }
+    
+    /// Compute vertical velocity at elevation z above the still water level, zero above the surface
+    /// w = aω sinh(k(z+d))/sinh(kd) sin(kx − ωt)
+    pub fn vertical_velocity_at(&self, x: f64, z: f64, time: f64) -> f64 {
+        let Some(height) = self.profile_height(x, z, time) else {
+            return 0.0;
+        };
+        let phase = self.params.k * x - self.params.omega * time;
+        self.params.amplitude() * self.params.omega * height.sinh() / (self.params.k * self.params.d).sinh() * phase.sin()
+    }
+    
+    /// Compute dynamic pressure divided by the water density at elevation z, zero above the surface [m²/s²]
+    /// p_d/ρ = g a cosh(k(z+d))/cosh(kd) cos(kx − ωt)
+    pub fn dynamic_pressure_at(&self, x: f64, z: f64, time: f64) -> f64 {
+        let Some(height) = self.profile_height(x, z, time) else {
+            return 0.0;
+        };
+        let phase = self.params.k * x - self.params.omega * time;
+        self.gravity * self.params.amplitude() * height.cosh() / (self.params.k * self.params.d).cosh() * phase.cos()
+    }
+    
+    /// Compute total pressure divided by the water density at elevation z, hydrostatic plus dynamic [m²/s²]
+    pub fn pressure_at(&self, x: f64, z: f64, time: f64) -> f64 {
+        if z > self.surface_elevation(x, time) {
+            return 0.0;
+        }
+        -self.gravity * z.max(-self.params.d) + self.dynamic_pressure_at(x, z, time)
+    }
+    
+    /// Pressure response factor cosh(k(z+d))/cosh(kd), the ratio of the dynamic pressure head to the surface elevation
+    pub fn pressure_response(&self, z: f64) -> f64 {
+        let (k, d) = (self.params.k, self.params.d);
+        (k * (z + d).max(0.0)).cosh() / (k * d).cosh()
     }
     
     /// Compute velocity amplitude (maximum horizontal velocity)
@@ -87,6 +173,59 @@ impl VelocityCalculator {
         (u, w)
     }
     
+    /// Compute orbital velocity (u, w) at elevation z above the still water level (−d ≤ z ≤ 0)
+    /// u = aω cosh(k(z+d))/sinh(kd) cos(kx − ωt), w = aω sinh(k(z+d))/sinh(kd) sin(kx − ωt)
+    pub fn orbital_velocity_at(&self, x: f64, z: f64, time: f64) -> (f64, f64) {
+        let phase = self.params.k * x - self.params.omega * time;
+        let (k, d) = (self.params.k, self.params.d);
+        let scale = self.params.amplitude() * self.params.omega / (k * d).sinh();
+        let height = k * (z + d).max(0.0);
+        (scale * height.cosh() * phase.cos(), scale * height.sinh() * phase.sin())
+    }
+    
+    /// Compute Stokes drift at elevation z, the mean velocity of the particles along their orbits
+    /// u_s = a²ωk cosh(2k(z+d)) / (2 sinh²(kd))
+    pub fn stokes_drift(&self, z: f64) -> f64 {
+        let (k, d) = (self.params.k, self.params.d);
+        let amplitude = self.params.amplitude();
+        amplitude * amplitude * self.params.omega * k * (2.0 * k * (z + d).max(0.0)).cosh() / (2.0 * (k * d).sinh().powi(2))
+    }
+    
+    /// Compute net mass transport per unit width and water density, the Stokes drift over the depth
+    /// M = a²ω / (2 tanh(kd)) = E/(ρc) [m²/s]
+    pub fn mass_transport(&self) -> f64 {
+        let amplitude = self.params.amplitude();
+        amplitude * amplitude * self.params.omega / (2.0 * (self.params.k * self.params.d).tanh())
+    }
+    
+    /// Compute depth-averaged Stokes drift M/d, the return flow needed in a closed channel [m/s]
+    pub fn mean_stokes_drift(&self) -> f64 {
+        self.mass_transport() / self.params.d
+    }
+    
+    /// Compute the path of a particle released at (x, z), following the orbital velocity with RK4
+    /// Returns (time, x, z) at even steps no longer than the time step from the start to the end time
+    pub fn particle_path(&self, x: f64, z: f64, start_time: f64, end_time: f64, time_step: f64) -> Vec<(f64, f64, f64)> {
+        let mut path = vec![(start_time, x, z)];
+        if time_step <= 0.0 {
+            return path;
+        }
+        let steps = ((end_time - start_time) / time_step - 1e-9).ceil().max(0.0) as usize;
+        let dt = (end_time - start_time) / steps.max(1) as f64;
+        let (mut x, mut z) = (x, z);
+        for step in 0..steps {
+            let time = start_time + step as f64 * dt;
+            let (u1, w1) = self.orbital_velocity_at(x, z, time);
+            let (u2, w2) = self.orbital_velocity_at(x + 0.5 * dt * u1, z + 0.5 * dt * w1, time + 0.5 * dt);
+            let (u3, w3) = self.orbital_velocity_at(x + 0.5 * dt * u2, z + 0.5 * dt * w2, time + 0.5 * dt);
+            let (u4, w4) = self.orbital_velocity_at(x + dt * u3, z + dt * w3, time + dt);
+            x += dt * (u1 + 2.0 * u2 + 2.0 * u3 + u4) / 6.0;
+            z = (z + dt * (w1 + 2.0 * w2 + 2.0 * w3 + w4) / 6.0).max(-self.params.d);
+            path.push((start_time + (step + 1) as f64 * dt, x, z));
+        }
+        path
+    }
+    
     /// Get wave parameters
     pub fn parameters(&self) -> &WaveParameters {
         &self.params
@@ -107,7 +246,7 @@ impl VelocityCalculator {
     }
     
     /// Validate velocity calculation by checking energy conservation
-    pub fn validate_energy_conservation(&self, x: f64, time: f64) -> Result<f64, String> {
+    pub fn validate_energy_conservation(&self, x: f64, time: f64) -> Result<f64, CoastalError> {
         let u = self.horizontal_velocity(x, time);
         let eta = self.surface_elevation(x, time);
         
@@ -126,7 +265,7 @@ impl VelocityCalculator {
         let energy_error = (total_energy - expected_energy).abs() / expected_energy;
         
         if energy_error > 0.1 {
-            return Err(format!("Energy conservation violated: error = {:.2e}", energy_error));
+            return Err(CoastalError::Inconsistent { check: "Energy conservation", error: energy_error });
         }
         
         Ok(energy_error)
@@ -161,11 +300,13 @@ impl VelocityCalculator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::units::{Length, Time};
     use crate::waves::dispersion::DispersionSolver;
+    use crate::waves::wavemaker::linear_wave_number;
 
     fn create_test_velocity_calculator() -> VelocityCalculator {
         let solver = DispersionSolver::new();
-        let params = solver.solve_wave_parameters(0.5, 4.0, 2.0).unwrap();
+        let params = solver.solve_wave_parameters(Length::meters(0.5), Time::seconds(4.0), Length::meters(2.0)).unwrap();
         VelocityCalculator::new(params)
     }
     
@@ -199,9 +340,76 @@ mod tests {
     fn test_vertical_velocity() {
         let calc = create_test_velocity_calculator();
         
-        // For 1D horizontal propagation, vertical velocity should be zero
+        // Under the crest the surface neither rises nor falls
         let w = calc.vertical_velocity(0.0, 0.0);
-        assert_eq!(w, 0.0);
+        assert!(w.abs() < 1e-12);
+        
+        // Three quarters of a period later the surface rises, the column following at its depth-averaged rate
+        let rising = 0.75 * calc.params.period;
+        let w_rising = calc.vertical_velocity(0.0, rising);
+        let (d, steps) = (calc.params.d, 1000);
+        let mean: f64 = (0..steps).map(|i| calc.vertical_velocity_at(0.0, -d + (i as f64 + 0.5) * d / steps as f64, rising)).sum::<f64>() / steps as f64;
+        assert!(w_rising > 0.0);
+        assert!((w_rising - mean).abs() < 1e-6 * w_rising, "{} {}", w_rising, mean);
+    }
+    
+    fn airy_calculator() -> VelocityCalculator {
+        let mut params = WaveParameters::new(Length::meters(0.5), Time::seconds(4.0), Length::meters(2.0)).unwrap();
+        params.update_from_dispersion(linear_wave_number(params.omega, 2.0, 9.81).unwrap());
+        VelocityCalculator::new(params)
+    }
+    
+    #[test]
+    fn test_kinematics_over_depth() {
+        let calc = airy_calculator();
+        let (a, omega, k, d) = (calc.params.amplitude(), calc.params.omega, calc.params.k, calc.params.d);
+        let (x, t) = (0.3, 0.7);
+        
+        // No flow through the bed, and the orbits flatten towards it
+        assert!(calc.vertical_velocity_at(x, -d, t).abs() < 1e-12);
+        assert!((calc.horizontal_velocity_at(0.0, -d, 0.0) - a * omega / (k * d).sinh()).abs() < 1e-12);
+        assert!(calc.horizontal_velocity_at(0.0, -0.5 * d, 0.0) < calc.horizontal_velocity_at(0.0, -0.1, 0.0));
+        
+        // The field is divergence free
+        let h = 1e-5;
+        for z in [-0.9 * d, -0.5 * d, -0.1] {
+            let du_dx = (calc.horizontal_velocity_at(x + h, z, t) - calc.horizontal_velocity_at(x - h, z, t)) / (2.0 * h);
+            let dw_dz = (calc.vertical_velocity_at(x, z + h, t) - calc.vertical_velocity_at(x, z - h, t)) / (2.0 * h);
+            assert!((du_dx + dw_dz).abs() < 1e-6, "z = {}: {:.2e}", z, du_dx + dw_dz);
+        }
+        
+        // The dynamic pressure head follows the surface at the still water level and decays to 1/cosh(kd) at the bed
+        assert!((calc.dynamic_pressure_at(0.0, 0.0, 0.0) - 9.81 * a).abs() < 1e-12);
+        assert!((calc.dynamic_pressure_at(0.0, -d, 0.0) - 9.81 * a / (k * d).cosh()).abs() < 1e-12);
+        assert!((calc.pressure_response(-d) - 1.0 / (k * d).cosh()).abs() < 1e-12);
+        assert!((calc.pressure_at(0.0, -d, 0.0) - 9.81 * (d + a * calc.pressure_response(-d))).abs() < 1e-12);
+        
+        // Nothing above the surface
+        assert_eq!(calc.horizontal_velocity_at(0.0, 1.01 * a, 0.0), 0.0);
+        assert_eq!(calc.pressure_at(0.0, 1.01 * a, 0.0), 0.0);
+        assert_eq!(calc.vertical_velocity_at(0.0, -0.99 * a, calc.params.period / 2.0), 0.0);
+    }
+    
+    #[test]
+    fn test_wheeler_stretching() {
+        let extrapolated = airy_calculator();
+        let stretched = airy_calculator().with_stretching(Stretching::Wheeler);
+        assert_eq!(stretched.stretching(), Stretching::Wheeler);
+        let (a, d) = (extrapolated.params.amplitude(), extrapolated.params.d);
+        
+        // Under the crest the surface takes the still water level value instead of the growing extrapolation
+        let crest = stretched.horizontal_velocity_at(0.0, a, 0.0);
+        assert!((crest - extrapolated.horizontal_velocity_at(0.0, 0.0, 0.0)).abs() < 1e-12);
+        assert!(extrapolated.horizontal_velocity_at(0.0, a, 0.0) > crest);
+        assert!(stretched.horizontal_velocity_at(0.0, -0.5 * d, 0.0) < extrapolated.horizontal_velocity_at(0.0, -0.5 * d, 0.0));
+        
+        // The bed is not moved
+        assert!((stretched.horizontal_velocity_at(0.0, -d, 0.0) - extrapolated.horizontal_velocity_at(0.0, -d, 0.0)).abs() < 1e-12);
+        
+        // Under the trough the column is stretched upwards, so the surface keeps the full pressure head −ga
+        let trough = extrapolated.params.period / 2.0;
+        assert!((stretched.dynamic_pressure_at(0.0, -a, trough) + 9.81 * a).abs() < 1e-12);
+        assert!(extrapolated.dynamic_pressure_at(0.0, -a, trough) > -9.81 * a);
     }
     
     #[test]
@@ -280,6 +488,41 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_stokes_drift() {
+        // Airy dispersion ω² = gk tanh(kd), which the drift and transport formulas assume
+        let mut params = WaveParameters::new(Length::meters(0.5), Time::seconds(4.0), Length::meters(2.0)).unwrap();
+        params.update_from_dispersion(linear_wave_number(params.omega, 2.0, 9.81).unwrap());
+        let calc = VelocityCalculator::new(params);
+        let (d, period) = (calc.params.d, calc.params.period);
+        
+        // The drift is strongest at the surface and the mass transport is E/(ρc) = gH²/8c
+        assert!(calc.stokes_drift(0.0) > calc.stokes_drift(-d));
+        assert!((calc.mass_transport() / (9.81 * calc.params.h.powi(2) / (8.0 * calc.params.c)) - 1.0).abs() < 1e-9);
+        let integral: f64 = (0..1000).map(|i| calc.stokes_drift(-d + (i as f64 + 0.5) * d / 1000.0) * d / 1000.0).sum();
+        assert!((integral - calc.mass_transport()).abs() < 1e-4 * integral);
+        assert!((calc.mean_stokes_drift() * d - calc.mass_transport()).abs() < 1e-12);
+        
+        // The centres of the orbits stay at their depth while drifting along the channel
+        for z in [-0.25 * d, -0.75 * d] {
+            let path = calc.particle_path(0.0, z, 0.0, 10.0 * period, period / 100.0);
+            assert!((path.last().unwrap().0 - 10.0 * period).abs() < 1e-9);
+            let centre = |orbit: &[(f64, f64, f64)]| {
+                let n = orbit.len() as f64;
+                (orbit.iter().map(|p| p.1).sum::<f64>() / n, orbit.iter().map(|p| p.2).sum::<f64>() / n)
+            };
+            let (first, last) = (centre(&path[..100]), centre(&path[900..1000]));
+            assert!((last.1 - first.1).abs() < 0.01 * d, "{:?} {:?}", first, last);
+            let drift = (last.0 - first.0) / (9.0 * period);
+            assert!((drift / calc.stokes_drift(z) - 1.0).abs() < 0.1, "{} {}", drift, calc.stokes_drift(z));
+        }
+        
+        // Orbits are bounded by the particle displacement amplitude near the surface
+        let orbit = calc.particle_path(0.0, -0.1, 0.0, period, period / 100.0);
+        let width = orbit.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max) - orbit.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+        assert!(width > 0.0 && width < 2.0 * calc.params.amplitude() / (calc.params.k * d).tanh());
+    }
+    
     #[test]
     fn test_recommended_time_step() {
         let calc = create_test_velocity_calculator();
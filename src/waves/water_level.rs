@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Storm surge at the site: a constant level, or a series of levels through the storm
+///
+/// Series are read from CSV or whitespace-separated text with time [h] and surge [m] columns; lines
+/// starting with # and column names before the first point are skipped. Levels are interpolated
+/// linearly between the points and held beyond the ends.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Surge {
+    /// Surge level above the tide [m]
+    Static(f64),
+    /// Surge levels [m] at increasing times from the start of the storm [h]
+    TimeSeries { times: Vec<f64>, levels: Vec<f64> },
+}
+
+impl Default for Surge {
+    fn default() -> Self {
+        Surge::Static(0.0)
+    }
+}
+
+impl Surge {
+    /// Create new surge time series from its points, sorted by time
+    pub fn time_series(mut points: Vec<(f64, f64)>) -> Result<Self, String> {
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let surge = Surge::TimeSeries {
+            times: points.iter().map(|point| point.0).collect(),
+            levels: points.iter().map(|point| point.1).collect(),
+        };
+        surge.validate()?;
+        Ok(surge)
+    }
+
+    /// Read a surge time series file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        Self::parse(&content)
+    }
+
+    /// Surge time series from the content of a CSV file with time and surge columns
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut points = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let values: Result<Vec<f64>, _> = line
+                .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+                .filter(|field| !field.is_empty())
+                .map(str::parse::<f64>)
+                .collect();
+            match values {
+                Ok(values) if values.len() >= 2 => points.push((values[0], values[1])),
+                Ok(_) => return Err(format!("Line {} needs time and surge columns", number + 1)),
+                // Column names before the first point
+                Err(_) if points.is_empty() => continue,
+                Err(e) => return Err(format!("Invalid number on line {}: {}", number + 1, e)),
+            }
+        }
+        Self::time_series(points)
+    }
+
+    /// Check that the levels are finite and a time series spans a positive time
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Surge::Static(level) if !level.is_finite() => Err("Surge level must be finite".to_string()),
+            Surge::Static(_) => Ok(()),
+            Surge::TimeSeries { times, levels } => {
+                if times.len() < 2 || times.len() != levels.len() {
+                    return Err("Surge time series needs at least two points".to_string());
+                }
+                if times.iter().chain(levels).any(|value| !value.is_finite()) {
+                    return Err("Surge times and levels must be finite".to_string());
+                }
+                if times.windows(2).any(|pair| pair[1] <= pair[0]) {
+                    return Err("Surge times must increase".to_string());
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Surge level at a time from the start of the storm [m]
+    pub fn at(&self, time: f64) -> f64 {
+        match self {
+            Surge::Static(level) => *level,
+            Surge::TimeSeries { times, levels } => {
+                let next = times.partition_point(|&t| t <= time);
+                if next == 0 {
+                    return levels[0];
+                }
+                if next == times.len() {
+                    return levels[times.len() - 1];
+                }
+                let fraction = (time - times[next - 1]) / (times[next] - times[next - 1]);
+                levels[next - 1] + fraction * (levels[next] - levels[next - 1])
+            }
+        }
+    }
+
+    /// Highest surge and its time, at the start for a constant surge [h, m]
+    pub fn peak(&self) -> (f64, f64) {
+        match self {
+            Surge::Static(level) => (0.0, *level),
+            Surge::TimeSeries { times, levels } => times
+                .iter()
+                .zip(levels)
+                .map(|(&time, &level)| (time, level))
+                .fold((times[0], levels[0]), |peak, point| if point.1 > peak.1 { point } else { peak }),
+        }
+    }
+}
+
+/// Still water level of a flood assessment: the astronomical tide and the storm surge on top of it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StillWaterComponents {
+    pub tide: f64,               // Astronomical tide above mean sea level [m]
+    pub surge: Surge,            // Storm surge above the tide
+    pub surge_time: Option<f64>, // Time of the surge time series combined with the tide, None for its peak [h]
+}
+
+impl StillWaterComponents {
+    /// Surge combined with the tide: at the chosen time of a series, or at its peak [m]
+    pub fn surge_level(&self) -> f64 {
+        match self.surge_time {
+            Some(time) => self.surge.at(time),
+            None => self.surge.peak().1,
+        }
+    }
+
+    /// Still water level above mean sea level [m]
+    pub fn still_water_level(&self) -> f64 {
+        self.tide + self.surge_level()
+    }
+}
+
+/// Total water level at a structure, decomposed into tide, surge and wave setup above mean sea level
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WaterLevelBudget {
+    pub tide: f64,       // Astronomical tide [m]
+    pub surge: f64,      // Storm surge [m]
+    pub wave_setup: f64, // Rise of the mean water level by breaking waves [m]
+}
+
+impl WaterLevelBudget {
+    /// Budget of the still water components with a wave setup [m]
+    pub fn new(components: &StillWaterComponents, wave_setup: f64) -> Self {
+        Self { tide: components.tide, surge: components.surge_level(), wave_setup }
+    }
+
+    /// Still water level, without the waves [m]
+    pub fn still_water_level(&self) -> f64 {
+        self.tide + self.surge
+    }
+
+    /// Total water level [m]
+    pub fn total(&self) -> f64 {
+        self.still_water_level() + self.wave_setup
+    }
+
+    /// Named contributions, from the bottom of the stack up [m]
+    pub fn contributions(&self) -> [(&'static str, f64); 3] {
+        [("Tide", self.tide), ("Surge", self.surge), ("Wave Setup", self.wave_setup)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_surge_time_series() {
+        let surge = Surge::parse("time (h), surge (m)\n# storm of 2024\n0 0.1\n6, 1.2\n12; 0.4\n").unwrap();
+        assert_eq!(surge.at(-1.0), 0.1);
+        assert!((surge.at(3.0) - 0.65).abs() < 1e-12);
+        assert_eq!((surge.at(6.0), surge.at(20.0)), (1.2, 0.4));
+        assert_eq!(surge.peak(), (6.0, 1.2));
+        assert!(Surge::parse("0 0.1\n0 0.2\n").is_err());
+        assert!(Surge::parse("0 0.1\n").is_err());
+        assert_eq!(Surge::Static(0.3).peak(), (0.0, 0.3));
+    }
+
+    #[test]
+    fn test_water_level_budget() {
+        let surge = Surge::time_series(vec![(0.0, 0.2), (10.0, 1.0)]).unwrap();
+        let mut components = StillWaterComponents { tide: 1.5, surge, surge_time: None };
+        assert_eq!(components.still_water_level(), 2.5);
+        components.surge_time = Some(5.0);
+        let budget = WaterLevelBudget::new(&components, 0.3);
+        assert!((budget.still_water_level() - 2.1).abs() < 1e-12);
+        assert!((budget.total() - 2.4).abs() < 1e-12);
+        assert_eq!(budget.contributions().map(|(name, _)| name), ["Tide", "Surge", "Wave Setup"]);
+        let sum: f64 = budget.contributions().iter().map(|(_, value)| value).sum();
+        assert!((sum - budget.total()).abs() < 1e-12);
+    }
+}
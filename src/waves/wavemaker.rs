@@ -0,0 +1,294 @@
+use std::f64::consts::PI;
+use serde::{Deserialize, Serialize};
+use crate::units::{Length, Time};
+use crate::waves::dispersion::{DispersionRelation, DispersionSolver};
+use crate::waves::parameters::WaveParameters;
+
+/// Type of laboratory wave paddle
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PaddleType {
+    /// Piston paddle: uniform horizontal displacement over the depth
+    Piston,
+    /// Flap paddle hinged at the bed: displacement grows linearly with elevation
+    Flap,
+}
+
+impl std::fmt::Display for PaddleType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PaddleType::Piston => write!(f, "Piston"),
+            PaddleType::Flap => write!(f, "Flap"),
+        }
+    }
+}
+
+/// Wavemaker emulation using Biésel linear wavemaker theory
+pub struct Wavemaker {
+    /// Paddle type
+    paddle: PaddleType,
+    /// Paddle stroke (S), peak-to-peak displacement at the still water level [m]
+    stroke: f64,
+    /// Paddle period (T) [s]
+    period: f64,
+    /// Water depth at the paddle (h) [m]
+    depth: f64,
+    /// Gravitational acceleration [m/s²]
+    gravity: f64,
+    /// Progressive wave number from linear dispersion [rad/m]
+    k: f64,
+}
+
+impl Wavemaker {
+    /// Create new wavemaker from paddle stroke, period and depth
+    pub fn new(paddle: PaddleType, stroke: Length, period: Time, depth: Length) -> Result<Self, String> {
+        let (stroke, period, depth) = (stroke.in_meters(), period.in_seconds(), depth.in_meters());
+        if stroke <= 0.0 {
+            return Err("Paddle stroke must be positive".to_string());
+        }
+        if period <= 0.0 {
+            return Err("Paddle period must be positive".to_string());
+        }
+        if depth <= 0.0 {
+            return Err("Water depth must be positive".to_string());
+        }
+
+        let gravity = 9.81;
+        let omega = 2.0 * PI / period;
+        let k = linear_wave_number(omega, depth, gravity)?;
+
+        Ok(Self {
+            paddle,
+            stroke,
+            period,
+            depth,
+            gravity,
+            k,
+        })
+    }
+
+    /// Create new wavemaker from paddle frequency (1/T) instead of period
+    pub fn from_frequency(paddle: PaddleType, stroke: Length, frequency: f64, depth: Length) -> Result<Self, String> {
+        if frequency <= 0.0 {
+            return Err("Paddle frequency must be positive".to_string());
+        }
+        Self::new(paddle, stroke, Time::seconds(1.0 / frequency), depth)
+    }
+
+    /// Get paddle type
+    pub fn paddle(&self) -> PaddleType {
+        self.paddle
+    }
+
+    /// Get paddle stroke [m]
+    pub fn stroke(&self) -> f64 {
+        self.stroke
+    }
+
+    /// Get paddle period [s]
+    pub fn period(&self) -> f64 {
+        self.period
+    }
+
+    /// Get angular frequency [rad/s]
+    pub fn omega(&self) -> f64 {
+        2.0 * PI / self.period
+    }
+
+    /// Get progressive wave number [rad/m]
+    pub fn wave_number(&self) -> f64 {
+        self.k
+    }
+
+    /// Biésel transfer function: ratio of generated wave height to paddle stroke (H/S)
+    pub fn transfer_function(&self) -> f64 {
+        let kh = self.k * self.depth;
+        let denominator = (2.0 * kh).sinh() + 2.0 * kh;
+
+        match self.paddle {
+            // H/S = 2(cosh 2kh - 1) / (sinh 2kh + 2kh)
+            PaddleType::Piston => 2.0 * ((2.0 * kh).cosh() - 1.0) / denominator,
+            // H/S = 4 (sinh kh / kh) (kh sinh kh - cosh kh + 1) / (sinh 2kh + 2kh)
+            PaddleType::Flap => {
+                4.0 * (kh.sinh() / kh) * (kh * kh.sinh() - kh.cosh() + 1.0) / denominator
+            }
+        }
+    }
+
+    /// Wave height generated far from the paddle [m]
+    pub fn generated_wave_height(&self) -> f64 {
+        self.stroke * self.transfer_function()
+    }
+
+    /// Paddle stroke required to generate a target wave height [m]
+    pub fn required_stroke(&self, wave_height: f64) -> f64 {
+        wave_height / self.transfer_function()
+    }
+
+    /// Depth-averaged horizontal paddle velocity at time t [m/s]
+    /// Paddle displacement is X(t) = (S/2) sin(ωt); a flap moves half as fast on average as at the surface
+    pub fn paddle_velocity(&self, time: f64) -> f64 {
+        let surface_velocity = 0.5 * self.stroke * self.omega() * (self.omega() * time).cos();
+        match self.paddle {
+            PaddleType::Piston => surface_velocity,
+            PaddleType::Flap => 0.5 * surface_velocity,
+        }
+    }
+
+    /// Wave numbers of the first evanescent (standing) modes, solving ω² = -gκ tan(κh)
+    pub fn evanescent_wave_numbers(&self, modes: usize) -> Vec<f64> {
+        let omega2 = self.omega() * self.omega();
+
+        (1..=modes)
+            .map(|n| {
+                // Root lies in κh ∈ ((n - 1/2)π, nπ)
+                let mut lower = (n as f64 - 0.5) * PI / self.depth;
+                let mut upper = n as f64 * PI / self.depth;
+                let f = |kappa: f64| omega2 + self.gravity * kappa * (kappa * self.depth).tan();
+
+                for _ in 0..100 {
+                    let mid = 0.5 * (lower + upper);
+                    if f(mid) < 0.0 {
+                        lower = mid;
+                    } else {
+                        upper = mid;
+                    }
+                }
+                0.5 * (lower + upper)
+            })
+            .collect()
+    }
+
+    /// Distance from the paddle beyond which the evanescent modes have decayed below a relative tolerance [m]
+    /// The slowest-decaying first mode governs: exp(-κ₁x) = tolerance
+    pub fn spin_up_distance(&self, tolerance: f64) -> f64 {
+        let kappa1 = self.evanescent_wave_numbers(1)[0];
+        -tolerance.ln() / kappa1
+    }
+
+    /// Wave parameters of the generated progressive wave
+    pub fn wave_parameters(&self) -> Result<WaveParameters, String> {
+        let mut params = WaveParameters::new(Length::meters(self.generated_wave_height()), Time::seconds(self.period), Length::meters(self.depth))?;
+        params.update_from_dispersion(self.k);
+        params.validate()?;
+        Ok(params)
+    }
+}
+
+/// Solve linear dispersion ω² = gk tanh(kh) with Newton-Raphson
+pub(crate) fn linear_wave_number(omega: f64, depth: f64, gravity: f64) -> Result<f64, String> {
+    let solver = DispersionSolver::with_params(100, 1e-12, gravity).with_relation(DispersionRelation::Linear);
+    Ok(solver.solve_wave_number(omega, depth)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wavemaker_creation() {
+        let wavemaker = Wavemaker::new(PaddleType::Piston, Length::meters(0.2), Time::seconds(2.0), Length::meters(1.0)).unwrap();
+        assert_eq!(wavemaker.paddle(), PaddleType::Piston);
+        assert_eq!(wavemaker.stroke(), 0.2);
+        assert_eq!(wavemaker.period(), 2.0);
+
+        // Wave number satisfies linear dispersion
+        let k = wavemaker.wave_number();
+        let residual = 9.81 * k * (k * 1.0).tanh() - wavemaker.omega().powi(2);
+        assert!(residual.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_invalid_wavemaker() {
+        assert!(Wavemaker::new(PaddleType::Flap, Length::meters(0.0), Time::seconds(2.0), Length::meters(1.0)).is_err());
+        assert!(Wavemaker::new(PaddleType::Flap, Length::meters(0.1), Time::seconds(0.0), Length::meters(1.0)).is_err());
+        assert!(Wavemaker::new(PaddleType::Flap, Length::meters(0.1), Time::seconds(2.0), Length::meters(0.0)).is_err());
+        assert!(Wavemaker::from_frequency(PaddleType::Flap, Length::meters(0.1), 0.0, Length::meters(1.0)).is_err());
+    }
+
+    #[test]
+    fn test_from_frequency() {
+        let wavemaker = Wavemaker::from_frequency(PaddleType::Piston, Length::meters(0.1), 0.5, Length::meters(1.0)).unwrap();
+        assert!((wavemaker.period() - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_shallow_water_transfer_limits() {
+        // Shallow water: piston H/S → kh, flap H/S → kh/2
+        let piston = Wavemaker::new(PaddleType::Piston, Length::meters(0.1), Time::seconds(30.0), Length::meters(0.5)).unwrap();
+        let kh = piston.wave_number() * 0.5;
+        assert!((piston.transfer_function() - kh).abs() / kh < 0.01);
+
+        let flap = Wavemaker::new(PaddleType::Flap, Length::meters(0.1), Time::seconds(30.0), Length::meters(0.5)).unwrap();
+        assert!((flap.transfer_function() - kh / 2.0).abs() / (kh / 2.0) < 0.01);
+    }
+
+    #[test]
+    fn test_deep_water_transfer_limits() {
+        // Deep water: piston H/S → 2, flap H/S → 2(1 - 1/kh)
+        let piston = Wavemaker::new(PaddleType::Piston, Length::meters(0.1), Time::seconds(1.0), Length::meters(5.0)).unwrap();
+        assert!((piston.transfer_function() - 2.0).abs() < 1e-3);
+
+        let flap = Wavemaker::new(PaddleType::Flap, Length::meters(0.1), Time::seconds(1.0), Length::meters(5.0)).unwrap();
+        let kh = flap.wave_number() * 5.0;
+        assert!((flap.transfer_function() - 2.0 * (1.0 - 1.0 / kh)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_piston_more_efficient_than_flap() {
+        let piston = Wavemaker::new(PaddleType::Piston, Length::meters(0.1), Time::seconds(3.0), Length::meters(1.0)).unwrap();
+        let flap = Wavemaker::new(PaddleType::Flap, Length::meters(0.1), Time::seconds(3.0), Length::meters(1.0)).unwrap();
+        assert!(piston.generated_wave_height() > flap.generated_wave_height());
+    }
+
+    #[test]
+    fn test_required_stroke_roundtrip() {
+        let wavemaker = Wavemaker::new(PaddleType::Flap, Length::meters(0.15), Time::seconds(2.5), Length::meters(0.8)).unwrap();
+        let height = wavemaker.generated_wave_height();
+        assert!((wavemaker.required_stroke(height) - 0.15).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_evanescent_modes() {
+        let wavemaker = Wavemaker::new(PaddleType::Piston, Length::meters(0.1), Time::seconds(2.0), Length::meters(1.0)).unwrap();
+        let kappas = wavemaker.evanescent_wave_numbers(3);
+        assert_eq!(kappas.len(), 3);
+
+        for (n, &kappa) in kappas.iter().enumerate() {
+            // Each root satisfies ω² = -gκ tan(κh) inside its branch
+            let residual = wavemaker.omega().powi(2) + 9.81 * kappa * kappa.tan();
+            assert!(residual.abs() < 1e-6, "Mode {} residual {:.2e}", n + 1, residual);
+            assert!(kappa > (n as f64 + 0.5) * PI && kappa < (n as f64 + 1.0) * PI);
+        }
+    }
+
+    #[test]
+    fn test_spin_up_distance() {
+        let wavemaker = Wavemaker::new(PaddleType::Piston, Length::meters(0.1), Time::seconds(2.0), Length::meters(1.0)).unwrap();
+        let distance = wavemaker.spin_up_distance(0.01);
+
+        // Evanescent modes decay within a few water depths
+        assert!(distance > 1.0 && distance < 3.0);
+
+        // A stricter tolerance requires a longer distance
+        assert!(wavemaker.spin_up_distance(0.001) > distance);
+    }
+
+    #[test]
+    fn test_paddle_velocity() {
+        let piston = Wavemaker::new(PaddleType::Piston, Length::meters(0.2), Time::seconds(2.0), Length::meters(1.0)).unwrap();
+        let flap = Wavemaker::new(PaddleType::Flap, Length::meters(0.2), Time::seconds(2.0), Length::meters(1.0)).unwrap();
+
+        let expected = 0.5 * 0.2 * piston.omega();
+        assert!((piston.paddle_velocity(0.0) - expected).abs() < 1e-12);
+        assert!((flap.paddle_velocity(0.0) - expected / 2.0).abs() < 1e-12);
+        assert!(piston.paddle_velocity(0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_wave_parameters() {
+        let wavemaker = Wavemaker::new(PaddleType::Piston, Length::meters(0.1), Time::seconds(2.0), Length::meters(1.0)).unwrap();
+        let params = wavemaker.wave_parameters().unwrap();
+        assert!((params.h - wavemaker.generated_wave_height()).abs() < 1e-12);
+        assert_eq!(params.k, wavemaker.wave_number());
+    }
+}
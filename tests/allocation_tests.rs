@@ -0,0 +1,100 @@
+//! Heap allocations of a steady-state animation frame, counted by a global allocator
+//!
+//! In a test binary of its own, so the counting allocator only serves these tests.
+
+use coastal_engineering_platform::gui::{PropagationModel, WaveChannelApp};
+use coastal_engineering_platform::solver::{RightBoundary, ShallowWaterSolver, TimeIntegration};
+use coastal_engineering_platform::units::{Length, Time};
+use coastal_engineering_platform::waves::{DispersionMode, DispersionSolver, WaveForcing, WaveSource};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// Time between two animation frames [s]
+const FRAME: f64 = 0.05;
+
+thread_local! {
+    // Per thread, so tests running in parallel do not count each other's allocations
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// System allocator counting the allocations of each thread
+struct CountingAllocator;
+
+impl CountingAllocator {
+    fn count() {
+        ALLOCATIONS.try_with(|count| count.set(count.get() + 1)).ok();
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        Self::count();
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        Self::count();
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        Self::count();
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Allocations made by the current thread while running a closure
+fn allocations(run: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    run();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[test]
+fn test_solver_frame_allocates_nothing() {
+    let wave = DispersionSolver::new().solve_wave_parameters(Length::meters(0.1), Time::seconds(2.0), Length::meters(0.5)).unwrap();
+    let models = [None, Some(DispersionMode::OneLayer), Some(DispersionMode::TwoLayer)];
+    for (integration, model) in TimeIntegration::ALL.into_iter().flat_map(|integration| models.map(|model| (integration, model))) {
+        let mut solver = ShallowWaterSolver::new(30.0, 300, 0.5, RightBoundary::default()).unwrap().with_time_integration(integration).with_non_hydrostatic(model);
+        let mut forcing = WaveForcing::new();
+        forcing.add_source(WaveSource::boundary(wave.clone()));
+        solver.advance(5.0, &mut forcing);
+        let count = allocations(|| {
+            for _ in 0..20 {
+                solver.advance(FRAME, &mut forcing);
+            }
+        });
+        assert_eq!(count, 0, "{} {:?}", integration, model);
+    }
+}
+
+#[test]
+fn test_channel_view_frame_allocates_nothing() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Numerical;
+    wave_app.channel_length = 30.0;
+    wave_app.still_water_level = 0.5;
+    wave_app.wave_height = 0.1;
+    wave_app.wave_period = 2.0;
+    wave_app.show_foam = true;
+    wave_app.show_extrema = true;
+    wave_app.simulation_running = true;
+    // The series reach the size of the view, with room for the crests, troughs and foam of the developed waves
+    for _ in 0..600 {
+        wave_app.advance_simulation(FRAME);
+        wave_app.fill_plot_data();
+    }
+    for _ in 0..20 {
+        wave_app.advance_simulation(FRAME);
+        assert_eq!(allocations(|| wave_app.fill_plot_data()), 0);
+    }
+    assert!(wave_app.simulation_running);
+    assert!(!wave_app.series().crests.is_empty());
+}
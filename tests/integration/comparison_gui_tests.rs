@@ -0,0 +1,43 @@
+use coastal_engineering_platform::analysis::WaveGauge;
+use coastal_engineering_platform::gui::{ComparisonApp, WaveChannelApp};
+use egui_kittest::{Harness, kittest::Queryable};
+
+#[test]
+fn test_comparison_without_case_b() {
+    let mut comparison = ComparisonApp::new();
+    let mut case_a = WaveChannelApp::new();
+
+    let mut harness = Harness::new_ui(move |ui| {
+        comparison.show(ui, &mut case_a);
+    });
+
+    harness.run();
+
+    let _heading = harness.get_by_label("Scenario Comparison");
+    let _clone = harness.get_by_label("📋 Clone Current Scenario into Case B");
+    let _hint = harness.get_by_label("Clone the wave channel scenario to start a comparison.");
+}
+
+#[test]
+fn test_comparison_split_panes() {
+    let mut case_a = WaveChannelApp::new();
+    case_a.gauges.push(WaveGauge::new(10.0));
+    let mut comparison = ComparisonApp::new();
+    comparison.clone_case(&mut case_a);
+
+    let mut harness = Harness::new_ui(move |ui| {
+        comparison.show(ui, &mut case_a);
+    });
+
+    harness.run();
+
+    // Test that both panes, the difference plot and the spectra are present
+    let _run = harness.get_by_label("▶ Run Both");
+    let _reset = harness.get_by_label("⏹ Reset Both");
+    let _parameters = harness.get_by_label("Case B Parameters");
+    let _case_a = harness.get_by_label("Case A");
+    let _case_b = harness.get_by_label("Case B");
+    let _difference = harness.get_by_label("Surface Difference");
+    let _spectra = harness.get_by_label("Gauge Spectra");
+    let _gauge = harness.get_by_label("Comparison Gauge:");
+}
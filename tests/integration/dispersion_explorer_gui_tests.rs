@@ -0,0 +1,23 @@
+use coastal_engineering_platform::gui::DispersionExplorer;
+use egui_kittest::{Harness, kittest::Queryable};
+
+#[test]
+fn test_dispersion_explorer_ui() {
+    let mut explorer = DispersionExplorer::new();
+    let mut depth = 1.0;
+    let mut period = 4.0;
+
+    let mut harness = Harness::new_ui(move |ui| {
+        explorer.show(ui, &mut depth, &mut period);
+    });
+
+    harness.run();
+
+    // Test that the controls are present
+    let _heading = harness.get_by_label("Dispersion Relation Explorer");
+    let _depth = harness.get_by_label("Water Depth:");
+    let _period = harness.get_by_label("Wave Period:");
+    let _range = harness.get_by_label("Period Range:");
+    let _asymptotes = harness.get_by_label("Show Asymptotes");
+    let _one_layer = harness.get_by_label("Show One-Layer SWASH");
+}
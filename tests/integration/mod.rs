@@ -1,3 +1,5 @@
+mod comparison_gui_tests;
+mod dispersion_explorer_gui_tests;
 mod main_integration_tests;
 mod platform_app_integration_tests;
 mod platform_integration_tests;
@@ -1,4 +1,6 @@
-use coastal_engineering_platform::gui::{WaveChannelApp, EquationRenderer};
+use coastal_engineering_platform::gui::{WaveChannelApp, EquationRenderer, PropagationModel};
+use coastal_engineering_platform::analysis::WaveGauge;
+use coastal_engineering_platform::solver::{InitialCondition, VegetationField};
 use egui_kittest::{Harness, kittest::Queryable};
 
 #[test]
@@ -57,6 +59,146 @@ fn test_wave_channel_app_computed_values() {
     assert!(true); // If we got the "Computed Values" label, the section is there
 }
 
+#[test]
+fn test_wave_channel_app_solver_settings() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Numerical;
+
+    let mut harness = Harness::new_ui(move |ui| {
+        let mut equation_renderer = EquationRenderer::new();
+        let ctx = ui.ctx().clone();
+        wave_app.show(ui, &ctx, &mut equation_renderer);
+    });
+
+    harness.run();
+
+    // Test that solver settings and the sponge parameters are present
+    let _solver_settings = harness.get_by_label("Solver Settings");
+    let _propagation_model = harness.get_by_label("Propagation Model:");
+    let _accuracy_mode = harness.get_by_label("Accuracy Mode:");
+    let _right_boundary = harness.get_by_label("Right Boundary:");
+    let _sponge_width = harness.get_by_label("Sponge Width:");
+    let _sponge_strength = harness.get_by_label("Sponge Strength:");
+    let _absorption = harness.get_by_label("Active Absorption");
+    let _linear_overlay = harness.get_by_label("Overlay Linear Theory");
+}
+
+#[test]
+fn test_wave_channel_app_dam_break_scenario() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.initial_condition = Some(InitialCondition::dam_break(wave_app.channel_length, wave_app.still_water_level));
+
+    let mut harness = Harness::new_ui(move |ui| {
+        let mut equation_renderer = EquationRenderer::new();
+        let ctx = ui.ctx().clone();
+        wave_app.show(ui, &ctx, &mut equation_renderer);
+    });
+
+    harness.run();
+
+    // Test that the dam parameters replace the wavemaker and the solver boundary is available
+    let _scenario = harness.get_by_label("Scenario:");
+    let _dam_position = harness.get_by_label("Dam Position:");
+    let _upstream_depth = harness.get_by_label("Upstream Depth:");
+    let _downstream_depth = harness.get_by_label("Downstream Depth:");
+    let _right_boundary = harness.get_by_label("Right Boundary:");
+    assert!(harness.query_by_label("Wave Height (H):").is_none());
+    assert!(harness.query_by_label("Propagation Model:").is_none());
+}
+
+#[test]
+fn test_wave_channel_app_vegetation_settings() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Numerical;
+    wave_app.vegetation = Some(VegetationField::new(20.0, 30.0).unwrap());
+
+    let mut harness = Harness::new_ui(move |ui| {
+        let mut equation_renderer = EquationRenderer::new();
+        let ctx = ui.ctx().clone();
+        wave_app.show(ui, &ctx, &mut equation_renderer);
+    });
+
+    harness.run();
+
+    // Test that the patch extent and stem parameters are present
+    let _vegetation = harness.get_by_label("Vegetation");
+    let _stem_density = harness.get_by_label("Stem Density (N):");
+    let _stem_diameter = harness.get_by_label("Stem Diameter (b_v):");
+    let _stem_height = harness.get_by_label("Stem Height (h_v):");
+    let _drag_coefficient = harness.get_by_label("Drag Coefficient (C_D):");
+    let _vegetation_start = harness.get_by_label("Vegetation Start:");
+    let _vegetation_end = harness.get_by_label("Vegetation End:");
+}
+
+#[test]
+fn test_wave_channel_app_infragravity_analysis() {
+    let mut wave_app = WaveChannelApp::new();
+    for position in [10.0, 20.0] {
+        let mut gauge = WaveGauge::new(position);
+        for i in 0..400 {
+            let time = i as f64 * 0.05;
+            gauge.record(time, 0.25 * (std::f64::consts::PI / 2.0 * time).sin());
+        }
+        wave_app.gauges.push(gauge);
+    }
+
+    let mut harness = Harness::new_ui(move |ui| {
+        let mut equation_renderer = EquationRenderer::new();
+        let ctx = ui.ctx().clone();
+        wave_app.show(ui, &ctx, &mut equation_renderer);
+    });
+
+    harness.run();
+
+    // Test that gauge controls and the analysis section are present
+    let _gauges_heading = harness.get_by_label("Wave Gauges");
+    let _add_gauge = harness.get_by_label("➕ Add Gauge");
+    let _gauge_array = harness.get_by_label("➕ Gauge Array");
+    let _first_gauge = harness.get_by_label("Gauge 1:");
+    let _gauge_sampling = harness.get_by_label("Gauge Sampling:");
+    let _snapshots = harness.get_by_label("Snapshots:");
+    let _statistics_window = harness.get_by_label("Statistics Window:");
+    let _stream_snapshots = harness.get_by_label("Stream Snapshots to Disk");
+    let _analysis_heading = harness.get_by_label("Infragravity Analysis");
+    let _analysis_gauge = harness.get_by_label("Analysis Gauge:");
+    let _scalogram_heading = harness.get_by_label("Wavelet Scalogram");
+    let _bispectrum_heading = harness.get_by_label("Bispectrum");
+    let _cross_spectrum_heading = harness.get_by_label("Cross-Spectral Analysis");
+    let _gauge_pair = harness.get_by_label("Gauge Pair:");
+}
+
+#[test]
+fn test_wave_channel_app_playback_bar() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.start_simulation();
+    for _ in 0..100 {
+        wave_app.advance_simulation(0.05);
+    }
+    wave_app.scrub_to(2.0);
+
+    let mut harness = Harness::new_ui(move |ui| {
+        let mut equation_renderer = EquationRenderer::new();
+        let ctx = ui.ctx().clone();
+        wave_app.show(ui, &ctx, &mut equation_renderer);
+    });
+
+    harness.run();
+
+    // Test that the timeline shows the scrubbed frame, the transport and loop controls
+    let _playback = harness.get_by_label("Playback:");
+    let _frame = harness.get_by_label("Frame 40 of 100 at 2.00s, snapshots every 0.05s here");
+    let _first = harness.get_by_label("⏮");
+    let _previous = harness.get_by_label("⏪");
+    let _replay = harness.get_by_label("▶ Replay");
+    let _next = harness.get_by_label("⏩");
+    let _last = harness.get_by_label("⏭");
+    let _loop_in = harness.get_by_label("Loop In");
+    let _loop_out = harness.get_by_label("Loop Out");
+    let _live = harness.get_by_label("📡 Live");
+    let _tracking = harness.get_by_label("Wave Tracking");
+    let _track_crests = harness.get_by_label("Track Wave Crests");
+}
+
 #[test]
 fn test_wave_channel_app_complete_ui() {
     let mut wave_app = WaveChannelApp::new();
@@ -73,5 +215,7 @@ fn test_wave_channel_app_complete_ui() {
     let _main_heading = harness.get_by_label("1D Wave Channel Simulator");
     let _params_heading = harness.get_by_label("Channel Parameters");
     let _computed_heading = harness.get_by_label("Computed Values");
+    let _metadata_heading = harness.get_by_label("Run Metadata");
+    let _seed = harness.get_by_label("Random Seed:");
     let _coming_soon = harness.get_by_label("Simulation controls coming soon...");
 }
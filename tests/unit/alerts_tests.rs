@@ -0,0 +1,62 @@
+use coastal_engineering_platform::gui::{AlertCondition, AlertRule, DashboardQuantity, Project, PropagationModel, WaveChannelApp};
+
+#[test]
+fn test_alert_triggers_once_per_crossing() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Analytical;
+    wave_app.alerts = vec![AlertRule::new(DashboardQuantity::SimulationTime, AlertCondition::Above, 1.0, false)];
+    wave_app.start_simulation();
+    for _ in 0..60 {
+        wave_app.advance_simulation(0.05);
+    }
+    assert_eq!(wave_app.alert_log.len(), 1);
+    assert!(wave_app.alert_log[0].time > 1.0 && wave_app.alert_log[0].time < 1.1);
+    assert_eq!(wave_app.alert_log[0].rule, "Simulation Time > 1.000 s");
+    assert!(wave_app.simulation_running);
+
+    // A reset clears the log and rearms the rule
+    wave_app.reset_simulation();
+    assert!(wave_app.alert_log.is_empty());
+    wave_app.start_simulation();
+    for _ in 0..30 {
+        wave_app.advance_simulation(0.05);
+    }
+    assert_eq!(wave_app.alert_log.len(), 1);
+}
+
+#[test]
+fn test_alert_pauses_the_simulation() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Analytical;
+    wave_app.alerts = vec![
+        AlertRule::new(DashboardQuantity::SimulationTime, AlertCondition::Above, 0.5, true),
+        // Not available without a tank, so never triggered
+        AlertRule::new(DashboardQuantity::OvertoppingDischarge, AlertCondition::Above, 10.0, true),
+    ];
+    wave_app.start_simulation();
+    for _ in 0..40 {
+        wave_app.advance_simulation(0.05);
+    }
+    assert!(!wave_app.simulation_running);
+    assert_eq!(wave_app.alert_log.len(), 1);
+    assert!(wave_app.alert_log[0].paused);
+    assert!((wave_app.simulation_time - 0.55).abs() < 1e-9);
+    assert!(wave_app.alert_log[0].to_string().ends_with(", simulation paused"));
+
+    // Resuming goes on past the threshold without stopping again
+    wave_app.start_simulation();
+    wave_app.advance_simulation(0.05);
+    assert!(wave_app.simulation_running);
+}
+
+#[test]
+fn test_alert_rules_are_saved_with_the_project() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.alerts = vec![AlertRule::new(DashboardQuantity::Courant, AlertCondition::Above, 0.9, true)];
+    let project: Project = serde_json::from_str(&serde_json::to_string(&wave_app.project()).unwrap()).unwrap();
+    let mut reopened = WaveChannelApp::new();
+    reopened.load_project(&project);
+    assert_eq!(reopened.alerts, wave_app.alerts);
+    assert_eq!(reopened.alerts[0].describe(&reopened), "CFL > 0.900");
+    assert!(AlertCondition::Below.holds(0.1, 0.2));
+}
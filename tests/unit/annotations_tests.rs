@@ -0,0 +1,49 @@
+use coastal_engineering_platform::gui::{AnnotatedPlot, Annotation, AnnotationShape, Project, WaveChannelApp};
+
+#[test]
+fn test_annotation_shapes() {
+    let label = Annotation::label(AnnotatedPlot::Channel, [12.0, 2.1], "Bar crest");
+    assert_eq!(label.shape, AnnotationShape::Label);
+    assert_eq!(label.describe(), "Channel: label at x = 12.00 m, z = 2.100 m \"Bar crest\"");
+
+    let arrow = Annotation::arrow(AnnotatedPlot::Channel, [20.0, 3.0], [25.0, 2.2], "Breaking");
+    assert_eq!(arrow.shape, AnnotationShape::Arrow { tip: [25.0, 2.2] });
+    assert!(arrow.describe().contains("to x = 25.00 m"));
+
+    let marker = Annotation::marker(AnnotatedPlot::GaugeRecords, 30.5, "Paddle stopped");
+    assert_eq!(marker.position[0], 30.5);
+    assert_eq!(marker.describe(), "Gauge Records: marker at t = 30.50 s \"Paddle stopped\"");
+}
+
+#[test]
+fn test_annotations_saved_with_the_project() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.annotations.push(Annotation::marker(AnnotatedPlot::Channel, 40.0, "Toe"));
+    wave_app.annotations.push(Annotation::arrow(AnnotatedPlot::GaugeRecords, [5.0, 0.3], [6.0, 0.25], "First crest"));
+
+    let json = serde_json::to_string(&wave_app.project()).unwrap();
+    let project: Project = serde_json::from_str(&json).unwrap();
+    let mut restored = WaveChannelApp::new();
+    restored.load_project(&project);
+    assert_eq!(restored.annotations, wave_app.annotations);
+
+    // Projects saved before annotations have none
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value.as_object_mut().unwrap().remove("annotations");
+    let project: Project = serde_json::from_value(value).unwrap();
+    assert!(project.annotations.is_empty());
+}
+
+#[test]
+fn test_annotations_in_the_run_report() {
+    let mut wave_app = WaveChannelApp::new();
+    assert!(wave_app.run_report().is_none());
+    wave_app.start_simulation();
+    wave_app.advance_simulation(0.05);
+    assert!(!wave_app.run_report().unwrap().contains("Annotations"));
+
+    wave_app.annotations.push(Annotation::label(AnnotatedPlot::Channel, [1.0, 2.0], "Wavemaker"));
+    let report = wave_app.run_report().unwrap();
+    assert!(report.starts_with(&wave_app.run_metadata.as_ref().unwrap().to_text()));
+    assert!(report.ends_with("Annotations:\n- Channel: label at x = 1.00 m, z = 2.000 m \"Wavemaker\""));
+}
@@ -0,0 +1,110 @@
+use coastal_engineering_platform::analysis::WaveGauge;
+use coastal_engineering_platform::gui::{Autosave, Project, PropagationModel, SourceSettings, WaveChannelApp};
+use coastal_engineering_platform::solver::{InitialCondition, RightBoundary, VegetationField};
+use coastal_engineering_platform::waves::PaddleType;
+
+/// Autosave in a file unique to the test, with no file left from a previous run
+fn temporary_autosave(name: &str) -> Autosave {
+    let path = std::env::temp_dir().join(format!("coastal_autosave_{}_{}.json", name, std::process::id()));
+    let mut autosave = Autosave::new(path);
+    autosave.discard().unwrap();
+    autosave
+}
+
+/// Scenario with every kind of setting changed from its default
+fn configured_app() -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.channel_length = 80.0;
+    wave_app.wave_height = 0.3;
+    wave_app.paddle_type = Some(PaddleType::Flap);
+    wave_app.additional_sources.push(SourceSettings {
+        position: 30.0,
+        wave_height: 0.2,
+        wave_period: 3.0,
+        phase: 45.0,
+    });
+    wave_app.gauges.push(WaveGauge::new(12.5));
+    wave_app.gauges.push(WaveGauge::new(20.0));
+    wave_app.propagation_model = PropagationModel::Numerical;
+    wave_app.right_boundary = RightBoundary::Radiation;
+    wave_app.vegetation = VegetationField::new(40.0, 60.0).ok();
+    wave_app.random_seed = 42;
+    wave_app
+}
+
+#[test]
+fn test_project_round_trip() {
+    let wave_app = configured_app();
+    let project = wave_app.project();
+    assert_eq!(project.gauge_positions, vec![12.5, 20.0]);
+
+    let json = project.to_json().unwrap();
+    assert_eq!(Project::from_json(&json).unwrap(), project);
+    assert!(Project::from_json("{").is_err());
+
+    let mut restored = WaveChannelApp::new();
+    restored.load_project(&project);
+    assert_eq!(restored.project(), project);
+    assert_eq!(restored.configuration_key(), wave_app.configuration_key());
+}
+
+#[test]
+fn test_initial_condition_round_trip() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.initial_condition = Some(InitialCondition::dam_break(50.0, 2.0));
+    let project = Project::from_json(&wave_app.project().to_json().unwrap()).unwrap();
+    assert_eq!(project.initial_condition, wave_app.initial_condition);
+}
+
+#[test]
+fn test_loading_project_resets_simulation() {
+    let mut wave_app = configured_app();
+    wave_app.start_simulation();
+    for _ in 0..20 {
+        wave_app.advance_simulation(0.05);
+    }
+    assert!(wave_app.simulation_time > 0.0);
+
+    let project = WaveChannelApp::new().project();
+    wave_app.load_project(&project);
+    assert!(!wave_app.simulation_running);
+    assert_eq!(wave_app.simulation_time, 0.0);
+    assert!(wave_app.gauges.is_empty());
+    assert!(wave_app.snapshots.is_empty());
+}
+
+#[test]
+fn test_autosave_interval_and_unchanged_projects() {
+    let mut autosave = temporary_autosave("interval").with_interval(30.0);
+    let mut project = configured_app().project();
+    assert!(autosave.load().is_none());
+
+    assert!(autosave.save_if_due(0.0, &project).unwrap());
+    assert_eq!(autosave.load(), Some(project.clone()));
+
+    // Not due before the interval elapsed
+    project.wave_height = 0.4;
+    assert!(!autosave.save_if_due(10.0, &project).unwrap());
+    assert_ne!(autosave.load(), Some(project.clone()));
+    assert!(autosave.save_if_due(30.0, &project).unwrap());
+    assert_eq!(autosave.load(), Some(project.clone()));
+
+    // Unchanged projects are not written again
+    assert!(!autosave.save_if_due(60.0, &project).unwrap());
+    autosave.discard().unwrap();
+}
+
+#[test]
+fn test_discarded_autosave_is_not_restored() {
+    let mut autosave = temporary_autosave("discard");
+    autosave.save(&configured_app().project()).unwrap();
+    assert!(autosave.path().exists());
+
+    // A new session finds the file left by the previous one
+    assert!(Autosave::new(autosave.path()).load().is_some());
+
+    autosave.discard().unwrap();
+    assert!(!autosave.path().exists());
+    assert!(autosave.load().is_none());
+    autosave.discard().unwrap();
+}
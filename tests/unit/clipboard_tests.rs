@@ -0,0 +1,36 @@
+use coastal_engineering_platform::gui::{CopyableValues, plot_image, value_of};
+use eframe::egui::{Color32, ColorImage, Rect, pos2};
+
+#[test]
+fn test_value_keeps_its_unit() {
+    assert_eq!(value_of("Wave Celerity (c): 3.131 m/s"), "3.131 m/s");
+    assert_eq!(value_of("Dispersion Error (1 layer): -0.12 %"), "-0.12 %");
+    assert_eq!(value_of("Iribarren: slope: 0.4521 "), "0.4521");
+    assert_eq!(value_of("1.250 m"), "1.250 m");
+}
+
+#[test]
+fn test_computed_values_block_text() {
+    let values = CopyableValues { lines: vec!["Wave Frequency (f): 0.500 Hz".to_string(), "Wavelength (L): 4.962 m".to_string()] };
+    assert_eq!(values.to_text(), "Wave Frequency (f): 0.500 Hz\nWavelength (L): 4.962 m\n");
+    assert_eq!(CopyableValues::default().to_text(), "");
+}
+
+#[test]
+fn test_plot_image_is_cropped_from_the_screenshot() {
+    // 8 × 6 pixel window at 2 pixels per point, with the plot in red from (1, 1) to (3, 2) points
+    let mut screenshot = ColorImage::new([8, 6], Color32::WHITE);
+    for y in 2..4 {
+        for x in 2..6 {
+            screenshot[(x, y)] = Color32::RED;
+        }
+    }
+    let image = plot_image(&screenshot, Rect::from_min_max(pos2(1.0, 1.0), pos2(3.0, 2.0)), 2.0).unwrap();
+    assert_eq!(image.size, [4, 2]);
+    assert!(image.pixels.iter().all(|&pixel| pixel == Color32::RED));
+
+    // Clipped to the window, and nothing off it
+    let clipped = plot_image(&screenshot, Rect::from_min_max(pos2(2.0, 1.0), pos2(10.0, 2.0)), 2.0).unwrap();
+    assert_eq!(clipped.size, [4, 2]);
+    assert!(plot_image(&screenshot, Rect::from_min_max(pos2(5.0, 0.0), pos2(9.0, 2.0)), 2.0).is_none());
+}
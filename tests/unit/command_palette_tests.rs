@@ -0,0 +1,64 @@
+use coastal_engineering_platform::gui::{Command, CommandPalette, PlatformTab, WaveChannelApp, fuzzy_score};
+
+#[test]
+fn test_fuzzy_score() {
+    assert!(fuzzy_score("", "Simulation: Reset").is_some());
+    assert!(fuzzy_score("rst", "Simulation: Reset").is_some());
+    assert!(fuzzy_score("RESET", "Simulation: Reset").is_some());
+    assert!(fuzzy_score("xyz", "Simulation: Reset").is_none());
+    // Order matters: the query must be a subsequence
+    assert!(fuzzy_score("tser", "Simulation: Reset").is_none());
+
+    // Contiguous matches at word starts rank above scattered ones
+    let contiguous = fuzzy_score("dam", "Preset: Dam Break").unwrap();
+    let scattered = fuzzy_score("dam", "Run: Draw New Random Seed").unwrap();
+    assert!(contiguous > scattered);
+}
+
+#[test]
+fn test_palette_lists_every_command() {
+    let mut palette = CommandPalette::new();
+    assert!(!palette.open);
+    palette.open();
+    assert!(palette.open);
+    assert_eq!(palette.matches(), Command::all());
+
+    // Labels are unique so every command can be found
+    let mut labels: Vec<String> = Command::all().iter().map(Command::label).collect();
+    labels.sort();
+    labels.dedup();
+    assert_eq!(labels.len(), Command::all().len());
+}
+
+#[test]
+fn test_palette_search() {
+    let mut palette = CommandPalette::new();
+    palette.open();
+    palette.query = "dam break".to_string();
+    assert_eq!(palette.matches()[0], Command::DamBreakScenario);
+
+    palette.query = "compar".to_string();
+    assert_eq!(palette.matches(), vec![Command::OpenTab(PlatformTab::Comparison)]);
+
+    palette.query = "overlay".to_string();
+    assert_eq!(palette.matches()[0], Command::ToggleLinearOverlay);
+
+    palette.query = "zzz".to_string();
+    assert!(palette.matches().is_empty());
+}
+
+#[test]
+fn test_channel_actions() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.toggle_simulation();
+    assert!(wave_app.simulation_running);
+    wave_app.toggle_simulation();
+    assert!(!wave_app.simulation_running);
+
+    wave_app.add_gauge();
+    assert_eq!(wave_app.gauges.len(), 1);
+    assert_eq!(wave_app.gauges[0].position(), 25.0);
+    wave_app.add_gauge_array();
+    assert_eq!(wave_app.gauges.len(), 10);
+    assert_eq!(wave_app.gauges[0].position(), 2.5);
+}
@@ -0,0 +1,93 @@
+use coastal_engineering_platform::analysis::WaveGauge;
+use coastal_engineering_platform::gui::{ComparisonApp, PropagationModel, WaveChannelApp};
+
+#[test]
+fn test_duplicate_scenario_copies_settings_at_rest() {
+    let mut case_a = WaveChannelApp::new();
+    case_a.wave_height = 0.3;
+    case_a.propagation_model = PropagationModel::Numerical;
+    case_a.gauges.push(WaveGauge::new(12.0));
+    case_a.start_simulation();
+    for _ in 0..20 {
+        case_a.advance_simulation(0.05);
+    }
+
+    let case_b = case_a.duplicate_scenario();
+    assert_eq!(case_b.wave_height, 0.3);
+    assert_eq!(case_b.propagation_model, PropagationModel::Numerical);
+    assert_eq!(case_b.gauges.len(), 1);
+    assert_eq!(case_b.gauges[0].position(), 12.0);
+    assert!(case_b.gauges[0].is_empty());
+    assert_eq!(case_b.simulation_time, 0.0);
+    assert!(!case_b.simulation_running);
+    assert!(case_b.solver.is_none());
+}
+
+#[test]
+fn test_identical_cases_stay_identical() {
+    let mut case_a = WaveChannelApp::new();
+    let mut comparison = ComparisonApp::new();
+    comparison.clone_case(&mut case_a);
+    comparison.start(&mut case_a);
+    for _ in 0..100 {
+        comparison.advance(&mut case_a, 0.05);
+    }
+
+    assert!(comparison.is_synchronized(&case_a));
+    let difference = comparison.surface_difference(&case_a).unwrap();
+    assert_eq!(difference.len(), case_a.grid_resolution);
+    assert!(difference.iter().all(|point| point[1].abs() < 1e-12));
+}
+
+#[test]
+fn test_modified_case_differs() {
+    let mut case_a = WaveChannelApp::new();
+    let mut comparison = ComparisonApp::new();
+    comparison.clone_case(&mut case_a);
+    let case_b = comparison.case_b.as_mut().unwrap();
+    case_b.wave_height = 1.0;
+    case_b.grid_resolution = 150;
+
+    comparison.start(&mut case_a);
+    for _ in 0..100 {
+        comparison.advance(&mut case_a, 0.05);
+    }
+    let difference = comparison.surface_difference(&case_a).unwrap();
+    assert_eq!(difference.len(), case_a.grid_resolution);
+    assert!(difference.iter().any(|point| point[1].abs() > 0.1));
+}
+
+#[test]
+fn test_comparison_pauses_when_one_case_completes() {
+    let mut case_a = WaveChannelApp::new();
+    let mut comparison = ComparisonApp::new();
+    comparison.clone_case(&mut case_a);
+    comparison.case_b.as_mut().unwrap().number_of_waves = 1;
+
+    comparison.start(&mut case_a);
+    let case_b_duration = comparison.case_b.as_ref().unwrap().total_simulation_time();
+    for _ in 0..((case_b_duration / 0.05) as usize + 10) {
+        comparison.advance(&mut case_a, 0.05);
+    }
+    assert!(!comparison.is_running(&case_a));
+    assert!(!case_a.simulation_running);
+    assert!(comparison.is_synchronized(&case_a));
+}
+
+#[test]
+fn test_gauge_spectrum_peaks_at_wave_frequency() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.gauges.push(WaveGauge::new(10.0));
+    assert!(wave_app.gauge_spectrum(0).is_err());
+
+    wave_app.wave_height = 0.1;
+    wave_app.wave_period = 3.2;
+    wave_app.start_simulation();
+    for _ in 0..1600 {
+        wave_app.advance_simulation(0.05);
+    }
+    let (frequencies, density) = wave_app.gauge_spectrum(0).unwrap();
+    let peak = (0..density.len()).max_by(|&a, &b| density[a].total_cmp(&density[b])).unwrap();
+    assert!((frequencies[peak] - 0.3125).abs() < 1e-9);
+    assert!(wave_app.gauge_spectrum(1).is_err());
+}
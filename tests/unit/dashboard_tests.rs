@@ -0,0 +1,42 @@
+use coastal_engineering_platform::analysis::WaveGauge;
+use coastal_engineering_platform::gui::{Command, DashboardQuantity, Project, Shortcuts, WaveChannelApp};
+
+#[test]
+fn test_quantities_of_the_channel() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.gauges = vec![WaveGauge::new(2.0), WaveGauge::new(8.0)];
+    wave_app.add_custom_formula("Steepness", "H / L", "").unwrap();
+    let available = DashboardQuantity::available(&wave_app);
+    assert!(available.contains(&DashboardQuantity::SignificantWaveHeight { gauge: 1 }));
+    assert!(!available.contains(&DashboardQuantity::SignificantWaveHeight { gauge: 2 }));
+    assert_eq!(available.last(), Some(&DashboardQuantity::Formula("Steepness".to_string())));
+    assert_eq!(DashboardQuantity::PeakPeriod { gauge: 2 }.label(), "Tp at Gauge 3");
+
+    // Linear wave properties at once, gauge statistics once recorded
+    let wavelength = DashboardQuantity::Wavelength.value(&wave_app).unwrap();
+    let celerity = DashboardQuantity::Celerity.value(&wave_app).unwrap();
+    assert!((celerity * wave_app.wave_period - wavelength).abs() < 1e-9);
+    let steepness = DashboardQuantity::Formula("Steepness".to_string()).value(&wave_app).unwrap();
+    assert!((steepness - wave_app.wave_height / wavelength).abs() < 1e-9);
+    assert!(DashboardQuantity::SignificantWaveHeight { gauge: 0 }.value(&wave_app).is_err());
+    for i in 0..400 {
+        let time = i as f64 * 0.05;
+        wave_app.gauges[0].record(time, 0.1 * (std::f64::consts::PI * time).sin());
+    }
+    let height = DashboardQuantity::SignificantWaveHeight { gauge: 0 }.value(&wave_app).unwrap();
+    assert!((height - 0.4 / 2f64.sqrt()).abs() < 0.01);
+    assert!((DashboardQuantity::MaxElevation { gauge: 0 }.value(&wave_app).unwrap() - 0.1).abs() < 1e-3);
+    assert!(DashboardQuantity::MaxElevation { gauge: 5 }.value(&wave_app).is_err());
+    assert!(DashboardQuantity::Reflection.value(&wave_app).is_err());
+}
+
+#[test]
+fn test_pinned_quantities_are_saved_with_the_project() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.dashboard = vec![DashboardQuantity::RunUp2, DashboardQuantity::SignificantWaveHeight { gauge: 2 }];
+    let project: Project = serde_json::from_str(&serde_json::to_string(&wave_app.project()).unwrap()).unwrap();
+    let mut reopened = WaveChannelApp::new();
+    reopened.load_project(&project);
+    assert_eq!(reopened.dashboard, wave_app.dashboard);
+    assert!(Shortcuts::new().binding(Command::ToggleDashboard).is_some());
+}
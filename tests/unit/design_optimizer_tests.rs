@@ -0,0 +1,85 @@
+use coastal_engineering_platform::analysis::SearchRange;
+use coastal_engineering_platform::gui::{DesignObjective, DesignStudy, PropagationModel, RunOutput, WaveChannelApp};
+use coastal_engineering_platform::solver::DikeDesign;
+
+fn scenario() -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Numerical;
+    wave_app.channel_length = 30.0;
+    wave_app.grid_resolution = 300;
+    wave_app.still_water_level = 0.5;
+    wave_app.wave_height = 0.15;
+    wave_app.wave_period = 2.5;
+    wave_app.number_of_waves = 6;
+    wave_app
+}
+
+fn crest_study(steps: usize) -> DesignStudy {
+    DesignStudy {
+        ranges: [
+            SearchRange { min: 0.05, max: 0.35, steps },
+            SearchRange { min: 3.0, max: 3.0, steps: 1 },
+            SearchRange { min: 0.0, max: 0.0, steps: 1 },
+        ],
+        target: RunOutput::Overtopping,
+        limit: 1.0,
+        objective: DesignObjective::CrestHeight,
+        refine: false,
+        max_refinements: 8,
+        threads: 4,
+    }
+}
+
+#[test]
+fn test_overtopping_falls_with_crest_level() {
+    let project = scenario().project();
+    let study = crest_study(1);
+    let discharge = |crest_freeboard| {
+        let evaluation = study.evaluate(&project, DikeDesign { crest_freeboard, slope: 3.0, berm_width: 0.0 }, false);
+        evaluation.output.unwrap()
+    };
+    let (low, middle, high) = (discharge(0.05), discharge(0.15), discharge(0.4));
+    assert!(low > middle && middle > high, "q = {:.3}, {:.3}, {:.3} l/s/m", low, middle, high);
+    assert!(low > 1.0, "q = {:.3} l/s/m", low);
+    assert!(high < 0.1, "q = {:.3} l/s/m", high);
+}
+
+#[test]
+fn test_grid_search_finds_lowest_crest_meeting_target() {
+    let project = scenario().project();
+    let study = crest_study(4);
+    assert!(study.validate(&project).is_ok());
+    let results = study.run_to_completion(&project);
+    assert_eq!(results.evaluations.len(), 4);
+
+    let best = results.best(study.limit).expect("a design meets the target");
+    for evaluation in &results.evaluations {
+        if evaluation.design.crest_freeboard < best.design.crest_freeboard {
+            assert!(!evaluation.meets(study.limit));
+        }
+    }
+    // Raising the crest trades cost for overtopping: every grid design is on the Pareto set
+    let pareto = results.pareto_set();
+    assert!(pareto.iter().any(|evaluation| evaluation.design == best.design));
+    assert!(pareto.windows(2).all(|pair| pair[0].output.as_ref().unwrap() <= pair[1].output.as_ref().unwrap() && pair[0].cost >= pair[1].cost));
+}
+
+#[test]
+fn test_refinement_stays_within_ranges() {
+    let project = scenario().project();
+    let study = DesignStudy { refine: true, ..crest_study(3) };
+    let results = study.run_to_completion(&project);
+    let refined: Vec<_> = results.evaluations.iter().filter(|evaluation| evaluation.refined).collect();
+    assert!(!refined.is_empty() && refined.len() <= study.max_refinements + 1);
+    assert!(refined.iter().all(|evaluation| (0.05..=0.35).contains(&evaluation.design.crest_freeboard) && evaluation.design.slope == 3.0));
+}
+
+#[test]
+fn test_study_validation() {
+    let mut channel = scenario();
+    assert!(crest_study(3).validate(&channel.project()).is_ok());
+    let flat_slope = DesignStudy { ranges: [SearchRange { min: 0.1, max: 0.2, steps: 2 }, SearchRange { min: 0.5, max: 2.0, steps: 2 }, SearchRange { min: 0.0, max: 0.0, steps: 1 }], ..crest_study(3) };
+    assert!(flat_slope.validate(&channel.project()).is_err());
+    channel.propagation_model = PropagationModel::Analytical;
+    assert!(crest_study(3).validate(&channel.project()).is_err());
+}
@@ -0,0 +1,61 @@
+use coastal_engineering_platform::gui::{DispersionCurve, DispersionExplorer, DispersionQuantity};
+
+#[test]
+fn test_linear_theory_between_asymptotes() {
+    let depth = 1.0;
+    for period in [1.0, 2.0, 4.0, 8.0, 16.0] {
+        let linear = DispersionCurve::LinearTheory.point(period, depth).unwrap();
+        let shallow = DispersionCurve::ShallowWater.point(period, depth).unwrap();
+        let deep = DispersionCurve::DeepWater.point(period, depth).unwrap();
+        assert!(linear.celerity <= shallow.celerity + 1e-9);
+        assert!(linear.celerity <= deep.celerity + 1e-9);
+        assert!(linear.group_velocity <= linear.celerity);
+        assert!((linear.wavelength - linear.celerity * period).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_linear_theory_limits() {
+    // Long waves in shallow water
+    let long = DispersionCurve::LinearTheory.point(60.0, 1.0).unwrap();
+    let shallow = DispersionCurve::ShallowWater.point(60.0, 1.0).unwrap();
+    assert!((long.celerity / shallow.celerity - 1.0).abs() < 0.01);
+    assert!((long.group_velocity / long.celerity - 1.0).abs() < 0.01);
+
+    // Short waves in deep water
+    let short = DispersionCurve::LinearTheory.point(1.0, 5.0).unwrap();
+    let deep = DispersionCurve::DeepWater.point(1.0, 5.0).unwrap();
+    assert!((short.celerity / deep.celerity - 1.0).abs() < 1e-6);
+    assert!((short.group_velocity / short.celerity - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn test_one_layer_departs_from_linear_theory_in_deep_water() {
+    let error = |period: f64| {
+        let linear = DispersionCurve::LinearTheory.point(period, 1.0).unwrap();
+        let one_layer = DispersionCurve::OneLayer.point(period, 1.0).unwrap();
+        (one_layer.celerity / linear.celerity - 1.0).abs()
+    };
+    assert!(error(10.0) < 0.005);
+    assert!(error(1.5) > 0.02);
+
+    // The one-layer relation has no solution beyond ω²d/g = 4
+    assert!(DispersionCurve::OneLayer.point(1.0, 1.0).is_none());
+    assert!(DispersionCurve::OneLayer.point(2.0, 0.0).is_none());
+}
+
+#[test]
+fn test_visible_curves_and_sampling() {
+    let mut explorer = DispersionExplorer::new();
+    assert_eq!(explorer.visible_curves().len(), 4);
+    explorer.show_asymptotes = false;
+    explorer.show_one_layer = false;
+    assert_eq!(explorer.visible_curves(), vec![DispersionCurve::LinearTheory]);
+
+    explorer.max_period = 10.0;
+    let points = explorer.curve_points(DispersionCurve::LinearTheory, DispersionQuantity::Wavelength, 1.0);
+    assert_eq!(points.len(), 201);
+    assert_eq!(points[200][0], 10.0);
+    // Wavelength grows with the period
+    assert!(points.windows(2).all(|pair| pair[1][1] > pair[0][1]));
+}
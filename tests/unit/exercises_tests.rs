@@ -0,0 +1,89 @@
+use coastal_engineering_platform::gui::{Assignment, Check, ExercisesApp, Quantity, WaveChannelApp};
+use std::path::Path;
+
+fn deep_water_channel() -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.wave_height = 2.0;
+    wave_app.wave_period = 6.0;
+    wave_app.still_water_level = 40.0;
+    wave_app
+}
+
+#[test]
+fn test_quantities() {
+    let project = deep_water_channel().project();
+    assert_eq!(Quantity::WaveHeight.value(&project), Some(2.0));
+    // Deep water: L = gT²/2π = 56.2 m
+    let wavelength = Quantity::Wavelength.value(&project).unwrap();
+    assert!((wavelength - 9.81 * 36.0 / (2.0 * std::f64::consts::PI)).abs() < 0.1);
+    assert!(Quantity::RelativeDepth.value(&project).unwrap() > 0.5);
+    let celerity = Quantity::Celerity.value(&project).unwrap();
+    assert!((celerity - wavelength / 6.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_checks() {
+    let project = deep_water_channel().project();
+    let configure = Check::Configure { quantity: Quantity::WaveHeight, target: 2.03, tolerance: 0.02 };
+    assert!(configure.grade(&project, None).passed);
+    let configure = Check::Configure { quantity: Quantity::WaveHeight, target: 2.5, tolerance: 0.02 };
+    assert!(!configure.grade(&project, None).passed);
+
+    let deep_water = Check::Range { quantity: Quantity::RelativeDepth, min: Some(0.5), max: None };
+    assert!(deep_water.grade(&project, None).passed);
+    let shallow_water = Check::Range { quantity: Quantity::RelativeDepth, min: None, max: Some(0.05) };
+    assert!(!shallow_water.grade(&project, None).passed);
+
+    let answer = Check::Answer { quantity: Quantity::Wavelength, tolerance: 0.02 };
+    assert!(answer.needs_answer());
+    assert!(answer.grade(&project, Some(56.0)).passed);
+    assert!(!answer.grade(&project, Some(60.0)).passed);
+    let missing = answer.grade(&project, None);
+    assert!(!missing.passed);
+    assert_eq!(missing.actual, None);
+}
+
+#[test]
+fn test_example_assignment() {
+    let assignment = Assignment::load(Path::new("assets/exercises/example_assignment.json")).unwrap();
+    assert_eq!(assignment.exercises.len(), 3);
+    assert_eq!(assignment.exercises[0].points, 2.0);
+    // Points default to one
+    assert_eq!(assignment.exercises[1].points, 1.0);
+    assert!(Assignment::from_json("{\"title\": \"Empty\"}").is_err());
+}
+
+#[test]
+fn test_grading_and_submission() {
+    let assignment = Assignment::load(Path::new("assets/exercises/example_assignment.json")).unwrap();
+    let mut exercises = ExercisesApp::new();
+    exercises.set_assignment(assignment);
+    exercises.student = "A. Student".to_string();
+    let project = deep_water_channel().project();
+
+    // Wrong answer first, then the right one
+    exercises.answers[0][3] = "50".to_string();
+    assert!(!exercises.grade(0, &project).unwrap().passed);
+    exercises.answers[0][3] = " 56.2 ".to_string();
+    let result = exercises.grade(0, &project).unwrap();
+    assert!(result.passed);
+    assert_eq!(result.points, 2.0);
+    assert_eq!(result.configuration, project);
+
+    // The shallow water exercise fails with this configuration
+    assert!(!exercises.grade(1, &project).unwrap().passed);
+    assert!(exercises.grade(5, &project).is_none());
+
+    let submission = exercises.submission().unwrap();
+    assert_eq!(submission.student, "A. Student");
+    assert_eq!(submission.assignment, "Linear Wave Theory");
+    assert_eq!(submission.exercises.len(), 2);
+    assert_eq!(submission.points, 2.0);
+    assert_eq!(submission.max_points, 4.0);
+
+    let path = std::env::temp_dir().join(format!("exercises_tests_{}.json", std::process::id()));
+    submission.save(&path).unwrap();
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(content.contains("\"passed\": true"));
+}
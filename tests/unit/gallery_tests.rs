@@ -0,0 +1,39 @@
+use coastal_engineering_platform::gui::{SnapshotGallery, WaveChannelApp};
+
+#[test]
+fn test_capture_names_and_selection() {
+    let mut gallery = SnapshotGallery::new();
+    let bed = vec![[0.0, 0.0], [10.0, 0.5]];
+    gallery.capture(1.0, vec![[0.0, 1.0], [10.0, 1.0]], bed.clone(), "before".to_string());
+    gallery.capture(2.0, vec![[0.0, 1.2], [10.0, 0.9]], bed, "after".to_string());
+    assert_eq!(gallery.captures[1].name, "Snapshot 2");
+    assert_eq!(gallery.selected().len(), 2);
+    assert!(gallery.shares_bed());
+
+    gallery.capture(3.0, vec![[0.0, 1.0], [10.0, 1.0]], vec![[0.0, 0.0], [10.0, 0.8]], "with a breakwater".to_string());
+    assert!(!gallery.shares_bed());
+    gallery.captures[0].selected = false;
+    gallery.captures[1].selected = false;
+    assert!(gallery.shares_bed());
+    assert_eq!(gallery.selected()[0].metadata, "with a breakwater");
+}
+
+#[test]
+fn test_capture_of_the_channel() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.start_simulation();
+    while wave_app.simulation_time < 5.0 {
+        wave_app.advance_simulation(0.05);
+    }
+    wave_app.capture_snapshot();
+    wave_app.wave_height *= 2.0;
+    wave_app.capture_snapshot();
+
+    let captures = &wave_app.gallery.captures;
+    assert_eq!(captures.len(), 2);
+    assert_eq!(captures[0].surface.len(), wave_app.grid_resolution);
+    assert_eq!(captures[0].time, wave_app.displayed_time());
+    assert!(captures[0].surface.iter().any(|point| (point[1] - wave_app.still_water_level).abs() > 0.01));
+    assert!(captures[0].metadata.starts_with(&format!("H = {:.3} m", 0.5 * wave_app.wave_height)), "{}", captures[0].metadata);
+    assert_ne!(captures[0].metadata, captures[1].metadata);
+}
@@ -0,0 +1,44 @@
+use coastal_engineering_platform::gui::{Glossary, HelpPane, HelpTopic, PlatformTab, WaveChannelApp};
+use std::path::Path;
+
+#[test]
+fn test_glossary_registry() {
+    let glossary = Glossary::load(Path::new("scripts/glossary.json")).unwrap();
+
+    // Every tool has an overview
+    for tab in PlatformTab::ALL {
+        assert!(glossary.get(HelpPane::tool_id(tab)).is_some(), "no overview of {}", tab);
+    }
+
+    // Related equations exist in the equation registry
+    let equations = std::fs::read_to_string("scripts/equations.json").unwrap();
+    for id in ["wave_channel", "wave_height", "grid_spacing"] {
+        for equation in &glossary.get(id).unwrap().equations {
+            assert!(equations.contains(&format!("\"id\": \"{}\"", equation)), "unknown equation {}", equation);
+        }
+    }
+}
+
+#[test]
+fn test_glossary_titles() {
+    let glossary = Glossary::from_json(r#"{"entries": [{"id": "wave_height", "term": "Wave Height (H)", "explanation": ""}]}"#).unwrap();
+    assert_eq!(glossary.title("wave_height"), "Wave Height (H)");
+    assert!(glossary.get("wave_height").unwrap().equations.is_empty());
+    // Controls without an entry are titled after their identifier
+    assert_eq!(glossary.title("still_water_level"), "Still water level");
+    assert_eq!(glossary.title("wavelength_tooltip"), "Wavelength");
+    assert!(Glossary::from_json("[]").is_err());
+}
+
+#[test]
+fn test_help_topics() {
+    let topic = HelpTopic::new("channel_length", "Length of the channel.");
+    assert_eq!(topic.equation, None);
+    let topic = HelpTopic::with_equation("wave_frequency_tooltip", "wave_frequency", ("Cycles per second:", "where T is the period."));
+    assert_eq!(topic.equation.as_deref(), Some("wave_frequency"));
+    assert_eq!(topic.text_after, "where T is the period.");
+
+    let wave_app = WaveChannelApp::new();
+    assert!(!wave_app.help_mode);
+    assert!(wave_app.help_topic.is_none());
+}
@@ -0,0 +1,78 @@
+use coastal_engineering_platform::gui::{LiveParameter, ParameterChange, ParameterLog, PropagationModel, WaveChannelApp};
+use coastal_engineering_platform::solver::RightBoundary;
+
+#[test]
+fn test_changes_are_logged_and_merged() {
+    let mut log = ParameterLog::new();
+    let height = |value| [(LiveParameter::WaveHeight, value), (LiveParameter::SpongeStrength, 5.0)];
+    log.observe(0.0, &height(0.1));
+    assert!(log.is_empty());
+    log.observe(1.0, &height(0.1));
+    // Dragged over two steps, then left alone
+    log.observe(2.0, &height(0.15));
+    log.observe(3.0, &height(0.2));
+    log.observe(4.0, &height(0.2));
+    log.observe(5.0, &height(0.3));
+    assert_eq!(
+        log.changes(),
+        &[
+            ParameterChange { time: 2.0, parameter: LiveParameter::WaveHeight, from: 0.1, to: 0.2 },
+            ParameterChange { time: 5.0, parameter: LiveParameter::WaveHeight, from: 0.2, to: 0.3 },
+        ]
+    );
+
+    log.clear();
+    assert!(log.is_empty());
+}
+
+#[test]
+fn test_ramped_value() {
+    let mut log = ParameterLog::new();
+    log.observe(0.0, &[(LiveParameter::WaveHeight, 0.1)]);
+    log.observe(10.0, &[(LiveParameter::WaveHeight, 0.3)]);
+    assert_eq!(log.value_at(LiveParameter::WaveHeight, 5.0, 4.0, 0.3), 0.1);
+    assert!((log.value_at(LiveParameter::WaveHeight, 12.0, 4.0, 0.3) - 0.2).abs() < 1e-12);
+    assert_eq!(log.value_at(LiveParameter::WaveHeight, 20.0, 4.0, 0.3), 0.3);
+    assert_eq!(log.value_at(LiveParameter::WaveHeight, 10.0, 0.0, 0.3), 0.3);
+    assert_eq!(log.value_at(LiveParameter::SpongeStrength, 12.0, 4.0, 7.0), 7.0);
+}
+
+#[test]
+fn test_edits_during_a_run_keep_the_solver() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Numerical;
+    wave_app.right_boundary = RightBoundary::Sponge { width: 10.0, strength: 5.0 };
+    wave_app.start_simulation();
+    for _ in 0..20 {
+        wave_app.advance_simulation(0.05);
+    }
+
+    // The storm intensifies and the sponge is strengthened
+    let (height, time) = (wave_app.wave_height, wave_app.simulation_time);
+    wave_app.wave_height = 2.0 * height;
+    wave_app.right_boundary = RightBoundary::Sponge { width: 10.0, strength: 20.0 };
+    wave_app.advance_simulation(0.05);
+    assert!(wave_app.simulation_time > time, "The run restarted");
+    assert_eq!(wave_app.solver.as_ref().unwrap().right_boundary(), wave_app.right_boundary);
+
+    let parameters: Vec<LiveParameter> = wave_app.parameter_log.changes().iter().map(|change| change.parameter).collect();
+    assert_eq!(parameters, vec![LiveParameter::WaveHeight, LiveParameter::SpongeStrength]);
+    assert!((wave_app.parameter_log.changes()[0].time - time).abs() < 1e-9);
+
+    // The wavemaker ramps up to the new height
+    let ramping = wave_app.current_wave_height();
+    assert!(ramping > height && ramping < 2.0 * height);
+    while wave_app.simulation_time < time + wave_app.height_ramp {
+        wave_app.advance_simulation(0.05);
+    }
+    assert_eq!(wave_app.current_wave_height(), 2.0 * height);
+
+    // The ramp shapes the generated waves, so a cached run cannot stand for another ramp
+    let key = wave_app.configuration_key();
+    wave_app.height_ramp = 2.0 * wave_app.height_ramp;
+    assert_ne!(wave_app.configuration_key(), key);
+
+    wave_app.reset_simulation();
+    assert!(wave_app.parameter_log.is_empty());
+    assert_eq!(wave_app.current_wave_height(), 2.0 * height);
+}
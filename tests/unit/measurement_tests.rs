@@ -0,0 +1,93 @@
+use coastal_engineering_platform::analysis::WaveGauge;
+use coastal_engineering_platform::gui::{AnnotatedPlot, MeasureTool, SlopeMeasurement, WaveChannelApp, WaveMeasurement, WavelengthReading};
+
+#[test]
+fn test_slope_measurement() {
+    let measurement = SlopeMeasurement { start: [30.0, 1.5], end: [10.0, 0.5] };
+    assert_eq!(measurement.distance(), 20.0);
+    assert_eq!(measurement.rise(), 1.0);
+    assert_eq!(measurement.slope(), Some(0.05));
+    assert_eq!(measurement.to_text(), "Δx = 20.000 m, Δz = 1.000 m, slope = 0.0500 (1:20.0) from x = 10.00 to 30.00 m");
+    assert_eq!(SlopeMeasurement { start: [5.0, 0.0], end: [5.0, 1.0] }.slope(), None);
+}
+
+#[test]
+fn test_wave_measurement_snaps_to_crests() {
+    // Crests of 0.2 m every 2 s, troughs of -0.1 m
+    let times: Vec<f64> = (0..400).map(|i| i as f64 * 0.025).collect();
+    let elevation: Vec<f64> = times.iter().map(|t| 0.05 + 0.15 * (std::f64::consts::PI * t).cos()).collect();
+    let wave = WaveMeasurement::from_record(&times, &elevation, 4.3, 1.8).unwrap();
+    assert_eq!(wave.crests[0][0], 2.0);
+    assert_eq!(wave.crests[1][0], 4.0);
+    assert!((wave.period() - 2.0).abs() < 1e-12);
+    assert!((wave.height() - 0.3).abs() < 1e-12);
+    assert!((wave.trough[0] - 3.0).abs() < 1e-12);
+
+    assert!(WaveMeasurement::from_record(&times, &elevation, 3.9, 4.2).is_err());
+    assert!(WaveMeasurement::from_record(&[], &[], 1.0, 2.0).is_err());
+}
+
+#[test]
+fn test_measure_clicks() {
+    let mut wave_app = WaveChannelApp::new();
+    assert_eq!(wave_app.measure_click(AnnotatedPlot::Channel, [0.0, 0.0]), None);
+
+    wave_app.measure_tool = MeasureTool::Slope;
+    // Clicks on the other plot are ignored
+    assert_eq!(wave_app.measure_click(AnnotatedPlot::GaugeRecords, [1.0, 0.0]), None);
+    assert_eq!(wave_app.measure_click(AnnotatedPlot::Channel, [10.0, 1.0]), None);
+    let text = wave_app.measure_click(AnnotatedPlot::Channel, [20.0, 1.5]).unwrap();
+    assert!(text.starts_with("Δx = 10.000 m, Δz = 0.500 m"));
+    assert_eq!(wave_app.measurement, Some(Ok(text)));
+
+    // Period of the regular wavemaker waves at a gauge
+    wave_app.measure_tool = MeasureTool::Wave;
+    assert_eq!(wave_app.measure_click(AnnotatedPlot::GaugeRecords, [1.0, 0.0]), None);
+    assert!(wave_app.measure_click(AnnotatedPlot::GaugeRecords, [2.0, 0.0]).is_none());
+    assert!(matches!(wave_app.measurement, Some(Err(_))));
+
+    wave_app.gauges.push(WaveGauge::new(10.0));
+    wave_app.start_simulation();
+    while wave_app.simulation_time < 40.0 {
+        wave_app.advance_simulation(0.05);
+    }
+    let gauge = &wave_app.gauges[0];
+    let (times, elevation) = (gauge.times(), gauge.elevation());
+    let late = times.partition_point(|&t| t < 30.0);
+    let crest = (late..times.len()).max_by(|&i, &j| elevation[i].total_cmp(&elevation[j])).unwrap();
+    let (first, second) = ([times[crest], elevation[crest]], [times[crest] - wave_app.wave_period, elevation[crest]]);
+    wave_app.measure_click(AnnotatedPlot::GaugeRecords, first);
+    let text = wave_app.measure_click(AnnotatedPlot::GaugeRecords, second).unwrap();
+    assert!(text.starts_with("Gauge 1: T = "), "{}", text);
+    let period: f64 = text["Gauge 1: T = ".len()..].split(' ').next().unwrap().parse().unwrap();
+    assert!((period - wave_app.wave_period).abs() < 0.1, "{}", text);
+}
+
+#[test]
+fn test_wavelength_reading() {
+    let reading = WavelengthReading::from_crests([2.0, 6.0, 10.5, 14.5], |x| (x < 12.0).then_some(4.0)).unwrap();
+    assert_eq!(reading.pairs, 2);
+    assert_eq!(reading.measured, 4.25);
+    assert_eq!(reading.theoretical, 4.0);
+    assert!((reading.difference() - 0.0625).abs() < 1e-12);
+    assert_eq!(reading.to_text(), "L measured = 4.25 m\nL linear = 4.00 m (+6.2%)");
+    assert!(WavelengthReading::from_crests([2.0], |_| Some(4.0)).is_none());
+}
+
+#[test]
+fn test_crest_to_crest_distance_matches_linear_wavelength() {
+    let mut wave_app = WaveChannelApp::new();
+    assert_eq!(wave_app.surface_extrema(), (Vec::new(), Vec::new()));
+    assert!(wave_app.wavelength_reading().is_none());
+
+    wave_app.start_simulation();
+    while wave_app.simulation_time < 20.0 {
+        wave_app.advance_simulation(0.05);
+    }
+    let (crests, troughs) = wave_app.surface_extrema();
+    assert!(crests.len() >= 2 && !troughs.is_empty());
+    assert!(crests.iter().all(|crest| crest[1] > wave_app.still_water_level));
+    assert!(troughs.iter().all(|trough| trough[1] < wave_app.still_water_level));
+    let reading = wave_app.wavelength_reading().unwrap();
+    assert!(reading.difference().abs() < 0.05, "{:?}", reading);
+}
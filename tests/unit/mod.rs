@@ -1,5 +1,89 @@
+mod alerts_tests;
+mod annotations_tests;
+mod autosave_tests;
+mod clipboard_tests;
+mod command_palette_tests;
+mod comparison_tests;
+mod dashboard_tests;
+mod design_optimizer_tests;
+mod dispersion_explorer_tests;
+mod exercises_tests;
+mod gallery_tests;
+mod help_tests;
+mod live_edits_tests;
+mod measurement_tests;
+mod monte_carlo_tests;
+mod openfoam_tests;
+mod performance_tests;
 mod platform_app_tests;
+mod plot_buffers_tests;
+mod plot_style_tests;
+mod preferences_tests;
+mod remote_api_tests;
+mod repaint_tests;
+mod result_cache_tests;
+mod results_table_tests;
+mod session_tests;
+mod shortcuts_tests;
+mod timeline_tests;
+mod transects_tests;
+mod wave_basin_tests;
+mod wave_channel_bathymetry_tests;
+mod wave_channel_bore_tests;
 mod wave_channel_computation_tests;
+mod wave_channel_current_tests;
+mod wave_channel_datum_tests;
+mod wave_channel_energy_budget_tests;
+mod wave_channel_energy_converter_tests;
 mod wave_channel_field_tests;
+mod wave_channel_formula_tests;
+mod wave_channel_gauge_tests;
+mod wave_channel_grid_tests;
+mod wave_channel_hydrograph_tests;
+mod wave_channel_impact_tests;
+mod wave_channel_metadata_tests;
+mod wave_channel_overtopping_tank_tests;
 mod wave_channel_parameter_tests;
+mod wave_channel_playback_tests;
+mod wave_channel_preview_tests;
+mod wave_channel_pressure_tests;
+mod wave_channel_sampling_tests;
+mod wave_channel_scenario_tests;
+mod wave_channel_schedule_tests;
+mod wave_channel_signal_tests;
+mod wave_channel_solver_tests;
+mod wave_channel_source_tests;
+mod wave_channel_spectrum_tests;
+mod wave_channel_stokes_drift_tests;
+mod wave_channel_swash_tests;
 mod wave_channel_tests;
+mod wave_channel_tracking_tests;
+mod wave_channel_tsunami_tests;
+mod wave_channel_undertow_tests;
+mod wave_channel_vegetation_tests;
+mod wave_channel_water_level_tests;
+mod wave_channel_wavemaker_tests;
+mod wave_climate_tests;
+mod workers_tests;
+
+use coastal_engineering_platform::gui::WaveChannelApp;
+
+/// Time advanced per frame by the simulation helpers [s]
+const FRAME: f64 = 0.05;
+
+/// Run the wave channel simulation for a duration, frame by frame
+pub fn run(wave_app: &mut WaveChannelApp, duration: f64) {
+    wave_app.start_simulation();
+    let frames = (duration / FRAME).round() as usize;
+    for _ in 0..frames {
+        wave_app.advance_simulation(FRAME);
+    }
+}
+
+/// Run the wave channel simulation until it stops by itself
+pub fn run_to_completion(wave_app: &mut WaveChannelApp) {
+    wave_app.start_simulation();
+    while wave_app.simulation_running {
+        wave_app.advance_simulation(FRAME);
+    }
+}
@@ -0,0 +1,88 @@
+use coastal_engineering_platform::analysis::{Distribution, WaveGauge};
+use coastal_engineering_platform::gui::{MonteCarloApp, RunOutput, MonteCarloStudy, PropagationModel, UncertainInput, UncertainParameter, WaveChannelApp};
+
+fn scenario() -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Analytical;
+    wave_app.channel_length = 20.0;
+    wave_app.still_water_level = 0.5;
+    wave_app.wave_period = 2.0;
+    wave_app.number_of_waves = 3;
+    wave_app.gauges.push(WaveGauge::new(5.0));
+    wave_app
+}
+
+fn wave_height_study(realizations: usize, threads: usize) -> MonteCarloStudy {
+    MonteCarloStudy {
+        inputs: vec![UncertainInput {
+            parameter: UncertainParameter::WaveHeight,
+            distribution: Distribution::Uniform { low: 0.02, high: 0.06 },
+        }],
+        output: RunOutput::MaxElevation { gauge: 0 },
+        realizations,
+        threads,
+        ..MonteCarloStudy::default()
+    }
+}
+
+#[test]
+fn test_realizations_follow_drawn_inputs() {
+    let project = scenario().project();
+    let results = wave_height_study(12, 4).run_to_completion(&project);
+    assert_eq!(results.len(), 12);
+    assert_eq!(results.failures().count(), 0);
+    for (i, realization) in results.realizations().iter().enumerate() {
+        assert_eq!(realization.index, i);
+        // Linear crests rise half the wave height above the still water level
+        let wave_height = realization.inputs[0];
+        let crest = *realization.output.as_ref().unwrap();
+        assert!((0.02..0.06).contains(&wave_height));
+        assert!((crest - 0.5 * wave_height).abs() < 0.1 * wave_height, "Crest {:.4} for H = {:.4}", crest, wave_height);
+    }
+    let output = results.output();
+    assert!((output.mean() - 0.02).abs() < 0.004);
+    assert_eq!(output.exceedance_curve().len(), 12);
+}
+
+#[test]
+fn test_results_do_not_depend_on_threads() {
+    let project = scenario().project();
+    let serial = wave_height_study(6, 1).run_to_completion(&project);
+    let parallel = wave_height_study(6, 3).run_to_completion(&project);
+    assert_eq!(serial, parallel);
+
+    let reseeded = MonteCarloStudy { seed: 1, ..wave_height_study(6, 3) };
+    assert_ne!(reseeded.draw(0), wave_height_study(6, 3).draw(0));
+}
+
+#[test]
+fn test_study_validation() {
+    let project = scenario().project();
+    let drag = MonteCarloStudy {
+        inputs: vec![UncertainInput {
+            parameter: UncertainParameter::VegetationDrag,
+            distribution: Distribution::Normal { mean: 1.0, std_dev: 0.2 },
+        }],
+        ..wave_height_study(10, 1)
+    };
+    assert!(drag.validate(&project).is_err());
+    assert!(MonteCarloStudy { output: RunOutput::RunUp, ..wave_height_study(10, 1) }.validate(&project).is_err());
+    assert!(MonteCarloStudy { output: RunOutput::MaxElevation { gauge: 1 }, ..wave_height_study(10, 1) }.validate(&project).is_err());
+    assert!(MonteCarloStudy { inputs: Vec::new(), ..wave_height_study(10, 1) }.validate(&project).is_err());
+    assert!(wave_height_study(10, 1).validate(&project).is_ok());
+}
+
+#[test]
+fn test_background_study_completes() {
+    let channel = scenario();
+    let mut monte_carlo = MonteCarloApp::new();
+    monte_carlo.study = wave_height_study(4, 2);
+    monte_carlo.start(channel.project()).unwrap();
+    assert!(monte_carlo.is_running());
+    while monte_carlo.is_running() {
+        monte_carlo.poll();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+    assert_eq!(monte_carlo.progress(), 1.0);
+    assert_eq!(monte_carlo.results, wave_height_study(4, 1).run_to_completion(&channel.project()));
+}
@@ -0,0 +1,97 @@
+use coastal_engineering_platform::analysis::WaveGauge;
+use coastal_engineering_platform::gui::{OpenFoamCase, WaveChannelApp};
+use coastal_engineering_platform::solver::{CrossSection, InitialCondition, RightBoundary};
+use coastal_engineering_platform::waves::{WaveSpectrum, WaveTheory};
+
+fn beach_app() -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.channel_length = 30.0;
+    wave_app.still_water_level = 0.5;
+    wave_app.wave_height = 0.05;
+    wave_app.wave_period = 2.0;
+    wave_app.cross_section = Some(CrossSection::beach(30.0, 0.5));
+    wave_app.right_boundary = RightBoundary::Radiation;
+    wave_app.gauges.push(WaveGauge::new(5.0));
+    wave_app
+}
+
+#[test]
+fn test_case_follows_the_channel() {
+    let wave_app = beach_app();
+    let case = OpenFoamCase::new(&wave_app).unwrap();
+    for name in ["system/blockMeshDict", "system/controlDict", "system/setFieldsDict", "constant/waveDict", "0/U", "0/alpha.water", "0/p_rgh", "README"] {
+        assert!(case.file(name).is_some_and(|content| !content.is_empty()), "{} missing", name);
+    }
+
+    // One block per segment of the cross-section, sharing the vertical cells
+    let segments = wave_app.cross_section.as_ref().unwrap().breakpoints().len() - 1;
+    let mesh = case.file("system/blockMeshDict").unwrap();
+    let blocks: Vec<&str> = mesh.lines().filter(|line| line.trim_start().starts_with("hex")).collect();
+    assert_eq!(blocks.len(), segments);
+    let vertical: Vec<usize> = blocks.iter().map(|block| block.split(") (").nth(1).unwrap().split(')').next().unwrap().rsplit(' ').next().unwrap().parse().unwrap()).collect();
+    assert!(vertical.iter().all(|&cells| cells == vertical[0]));
+    // 10 cells per wave height up to three wave heights above the beach crest, 1.25 d above the bed
+    assert!((155..=156).contains(&vertical[0]), "{}", vertical[0]);
+    assert!(case.cells() > 155 * 30);
+
+    let waves = case.file("constant/waveDict").unwrap();
+    assert!(waves.contains("waveType        regular;") && waves.contains("waveHeight      0.05;") && waves.contains("wavePeriod      2;"));
+    assert!(case.file("0/U").unwrap().contains("waveAbsorption2DVelocity"));
+    assert!(case.file("system/controlDict").unwrap().contains("interfaceHeight"));
+    assert!(case.notes().is_empty());
+
+    let mut dam_break = beach_app();
+    dam_break.initial_condition = Some(InitialCondition::dam_break(30.0, 0.5));
+    assert!(OpenFoamCase::new(&dam_break).is_err());
+}
+
+#[test]
+fn test_irregular_waves_and_notes() {
+    let mut wave_app = beach_app();
+    wave_app.boundary_spectrum = Some(WaveSpectrum::jonswap(0.05, 2.0, 3.3).unwrap());
+    wave_app.right_boundary = RightBoundary::Wall;
+    let case = OpenFoamCase::new(&wave_app).unwrap();
+    let waves = case.file("constant/waveDict").unwrap();
+    let components = wave_app.spectral_components();
+    assert!(waves.contains("waveType        irregular;"));
+    assert!(waves.contains(&format!("waveHeights     {}\n(", components.len())));
+    assert!(waves.contains(&format!("{:.6}", components[0].phase)));
+    assert!(case.file("0/U").unwrap().contains("noSlip"));
+
+    wave_app.right_boundary = RightBoundary::Periodic;
+    let case = OpenFoamCase::new(&wave_app).unwrap();
+    assert_eq!(case.notes().len(), 1);
+    assert!(case.file("README").unwrap().contains("periodic boundary"));
+
+    let directory = std::env::temp_dir().join(format!("openfoam_tests_{}", std::process::id()));
+    case.write(&directory).unwrap();
+    assert!(directory.join("system/blockMeshDict").exists() && directory.join("0/p_rgh").exists());
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn test_regular_waves_follow_the_wave_theory() {
+    let mut wave_app = beach_app();
+    // Ur ≈ 6 and a second harmonic of about 9% of the first: second-order Stokes waves
+    assert_eq!(wave_app.recommended_wave_theory().unwrap(), WaveTheory::StokesSecond);
+    let case = OpenFoamCase::new(&wave_app).unwrap();
+    assert!(case.file("constant/waveDict").unwrap().contains("waveTheory      StokesII;"));
+
+    wave_app.wave_theory = Some(WaveTheory::Cnoidal);
+    assert_eq!(wave_app.generation_theory(), WaveTheory::Cnoidal);
+    let case = OpenFoamCase::new(&wave_app).unwrap();
+    assert!(case.file("constant/waveDict").unwrap().contains("waveTheory      cnoidal;"));
+    assert!(case.notes().is_empty());
+
+    // Stream function waves need coefficients the export leaves to olaFlow
+    wave_app.wave_theory = Some(WaveTheory::StreamFunction);
+    let case = OpenFoamCase::new(&wave_app).unwrap();
+    assert!(case.file("constant/waveDict").unwrap().contains("waveTheory      streamFunction;"));
+    assert_eq!(case.notes().len(), 1);
+
+    // Irregular waves stay first-order components
+    wave_app.boundary_spectrum = Some(WaveSpectrum::jonswap(0.05, 2.0, 3.3).unwrap());
+    let case = OpenFoamCase::new(&wave_app).unwrap();
+    assert!(case.file("constant/waveDict").unwrap().contains("waveTheory      StokesI;"));
+    assert!(case.notes().iter().any(|note| note.contains("Stream function theory is not exported for irregular waves")));
+}
@@ -0,0 +1,46 @@
+use coastal_engineering_platform::gui::{PERFORMANCE_WINDOW, PerformanceMonitor, PropagationModel, WaveChannelApp};
+
+#[test]
+fn test_frame_rates_over_the_window() {
+    let mut monitor = PerformanceMonitor::new();
+    assert_eq!(monitor.frame_time(), None);
+    assert_eq!(monitor.frames_per_second(), None);
+
+    // An old slow frame leaves the window
+    monitor.record_frame(1.0, 0.5);
+    for _ in 0..PERFORMANCE_WINDOW {
+        monitor.record_frame(0.02, 0.004);
+    }
+    assert!((monitor.frames_per_second().unwrap() - 50.0).abs() < 1e-9);
+    assert!((monitor.frame_time().unwrap() - 0.004).abs() < 1e-12);
+
+    monitor.record_advance(30, 0.01, 1e-3, 0.4);
+    monitor.record_advance(10, 0.01, 2e-3, 0.45);
+    assert_eq!(monitor.steps_per_second(), Some(2000.0));
+    assert_eq!(monitor.time_step(), Some((2e-3, 0.45)));
+    let report = monitor.report(3 * 1024 * 1024);
+    assert_eq!(report, ["Frame: 4.0 ms", "FPS: 50", "Solver: 2000 steps/s", "dt: 2.00e-3 s, CFL: 0.45", "Stored Results: 3.0 MiB"]);
+
+    monitor.clear_solver();
+    assert_eq!(monitor.steps_per_second(), None);
+    assert_eq!(monitor.time_step(), None);
+}
+
+#[test]
+fn test_solver_timings_of_a_run() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Numerical;
+    assert_eq!(wave_app.stored_bytes(), 0);
+    wave_app.start_simulation();
+    for _ in 0..20 {
+        wave_app.advance_simulation(0.05);
+    }
+    let (dt, courant) = wave_app.performance.time_step().unwrap();
+    assert!(dt > 0.0 && dt <= 0.05);
+    assert!(courant > 0.0 && courant <= 0.5 + 1e-9, "{}", courant);
+    assert!(wave_app.performance.steps_per_second().unwrap() > 0.0);
+    assert!(wave_app.stored_bytes() > 8 * wave_app.grid_resolution as u64);
+
+    wave_app.reset_simulation();
+    assert_eq!(wave_app.performance.time_step(), None);
+}
@@ -0,0 +1,30 @@
+use coastal_engineering_platform::gui::{ChannelSeries, WaveChannelApp, refill};
+use egui_plot::PlotPoint;
+
+#[test]
+fn test_refill_keeps_memory() {
+    let mut buffer = Vec::with_capacity(8);
+    refill(&mut buffer, [1.0, 2.0, 3.0]);
+    let memory = buffer.as_ptr();
+    refill(&mut buffer, [4.0, 5.0]);
+    assert_eq!(buffer, vec![4.0, 5.0]);
+    assert_eq!(buffer.as_ptr(), memory);
+}
+
+#[test]
+fn test_channel_series_refilled_in_place() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.fill_plot_data();
+    let series = wave_app.series();
+    assert_eq!(series.surface.len(), wave_app.grid_resolution);
+    assert_eq!(series.bed.len(), wave_app.grid_resolution);
+    assert!(series.surface.iter().zip(&series.bed).all(|(surface, bed)| surface.y >= bed.y));
+    let memory = (series.surface.as_ptr(), series.bed.as_ptr());
+
+    wave_app.advance_simulation(0.5);
+    wave_app.fill_plot_data();
+    assert_eq!((wave_app.series().surface.as_ptr(), wave_app.series().bed.as_ptr()), memory);
+
+    let pairs = ChannelSeries::pairs(&[PlotPoint::new(1.0, 2.0)]);
+    assert_eq!(pairs, vec![[1.0, 2.0]]);
+}
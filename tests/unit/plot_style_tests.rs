@@ -0,0 +1,74 @@
+use coastal_engineering_platform::gui::{Colormap, Palette, PlotStyle, Preferences, WaveChannelApp};
+use eframe::egui::Color32;
+
+#[test]
+fn test_default_style_keeps_classic_colors() {
+    let style = PlotStyle::default();
+    assert_eq!(style.water(), Color32::from_rgb(30, 144, 255));
+    assert_eq!(style.bed(), Color32::from_rgb(139, 69, 19));
+    assert_eq!(style.width(2.0), 2.0);
+    assert_eq!(WaveChannelApp::new().plot_style, style);
+    assert_eq!(Preferences::default().plot_style, style);
+}
+
+#[test]
+fn test_palettes() {
+    for palette in [Palette::Classic, Palette::OkabeIto, Palette::TolBright] {
+        let style = PlotStyle { palette, ..PlotStyle::default() };
+        let series: Vec<Color32> = (0..6).map(|i| style.series(i)).collect();
+        for (i, color) in series.iter().enumerate() {
+            assert!(series[i + 1..].iter().all(|other| other != color), "{} repeats a color", palette);
+        }
+        assert_eq!(style.series(6), style.series(0));
+        assert_ne!(style.water(), style.bed());
+        assert_ne!(style.alert(), style.reference());
+    }
+    assert_eq!(PlotStyle::colorblind_safe().palette, Palette::OkabeIto);
+    assert_eq!(PlotStyle::colorblind_safe().water(), Color32::from_rgb(0, 114, 178));
+}
+
+#[test]
+fn test_colormaps() {
+    assert_eq!(Colormap::Viridis.color(0.0), Color32::from_rgb(68, 1, 84));
+    assert_eq!(Colormap::Viridis.color(1.0), Color32::from_rgb(253, 231, 37));
+    assert_eq!(Colormap::Cividis.color(-1.0), Colormap::Cividis.color(0.0));
+    assert_eq!(Colormap::Grayscale.color(0.5), Color32::from_gray(128));
+    // Sequential maps brighten from low to high values
+    for colormap in [Colormap::Viridis, Colormap::Cividis, Colormap::Magma, Colormap::Grayscale] {
+        let luminance = |value: f64| {
+            let color = colormap.color(value);
+            0.2126 * color.r() as f64 + 0.7152 * color.g() as f64 + 0.0722 * color.b() as f64
+        };
+        assert!((0..10).all(|i| luminance(i as f64 / 10.0) < luminance((i + 1) as f64 / 10.0)), "{}", colormap);
+    }
+}
+
+#[test]
+fn test_fills_and_widths() {
+    let style = PlotStyle {
+        fill_opacity: 0.5,
+        line_scale: 2.0,
+        ..PlotStyle::default()
+    };
+    let fill = style.fill(style.water());
+    assert_eq!(fill.a(), 128);
+    assert_eq!(style.width(1.5), 3.0);
+    assert_eq!(PlotStyle { fill_opacity: 0.0, ..style }.fill(style.water()), Color32::TRANSPARENT);
+}
+
+#[test]
+fn test_style_in_preferences() {
+    let preferences = Preferences {
+        plot_style: PlotStyle::print(),
+        ..Preferences::default()
+    };
+    let json = serde_json::to_string(&preferences).unwrap();
+    let loaded: Preferences = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.plot_style, PlotStyle::print());
+
+    // Missing style fields fall back to the defaults
+    let partial: Preferences = serde_json::from_str(r#"{"plot_style": {"palette": "TolBright"}}"#).unwrap();
+    assert_eq!(partial.plot_style.palette, Palette::TolBright);
+    assert_eq!(partial.plot_style.colormap, Colormap::Viridis);
+    assert_eq!(partial.plot_style.line_scale, 1.0);
+}
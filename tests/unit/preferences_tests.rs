@@ -0,0 +1,78 @@
+use coastal_engineering_platform::gui::{MAX_RECENT_PROJECTS, Preferences, Project, Theme, UnitSystem, WaveChannelApp};
+use coastal_engineering_platform::units::{Length, Velocity};
+use std::path::PathBuf;
+
+fn temporary_path(name: &str) -> PathBuf {
+    std::env::temp_dir()
+        .join(format!("coastal_preferences_{}_{}", name, std::process::id()))
+        .join("preferences.json")
+}
+
+#[test]
+fn test_missing_file_gives_defaults() {
+    let preferences = Preferences::load(&temporary_path("missing"));
+    assert_eq!(preferences, Preferences::default());
+    assert_eq!(preferences.window_size, [1200.0, 800.0]);
+    assert_eq!(preferences.theme, Theme::System);
+    assert_eq!(preferences.unit_system, UnitSystem::Metric);
+    assert!(preferences.default_project.is_none());
+}
+
+#[test]
+fn test_save_and_load() {
+    let path = temporary_path("round_trip");
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.wave_period = 6.0;
+    let preferences = Preferences {
+        window_size: [1600.0, 900.0],
+        theme: Theme::Dark,
+        unit_system: UnitSystem::Imperial,
+        recent_projects: vec![PathBuf::from("flume.json")],
+        default_project: Some(wave_app.project()),
+        ..Preferences::default()
+    };
+    preferences.save(&path).unwrap();
+    assert_eq!(Preferences::load(&path), preferences);
+
+    // Unknown or missing fields fall back to the defaults
+    std::fs::write(&path, r#"{"theme": "Light"}"#).unwrap();
+    let partial = Preferences::load(&path);
+    assert_eq!(partial.theme, Theme::Light);
+    assert_eq!(partial.window_size, Preferences::default().window_size);
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_recent_projects() {
+    let mut preferences = Preferences::default();
+    preferences.add_recent_project("a.json");
+    preferences.add_recent_project("b.json");
+    preferences.add_recent_project("a.json");
+    assert_eq!(preferences.recent_projects, vec![PathBuf::from("a.json"), PathBuf::from("b.json")]);
+
+    for i in 0..20 {
+        preferences.add_recent_project(format!("case_{}.json", i));
+    }
+    assert_eq!(preferences.recent_projects.len(), MAX_RECENT_PROJECTS);
+    assert_eq!(preferences.recent_projects[0], PathBuf::from("case_19.json"));
+}
+
+#[test]
+fn test_unit_conversions() {
+    assert_eq!(UnitSystem::Metric.length(Length::meters(2.0)), 2.0);
+    assert!((UnitSystem::Imperial.length(Length::meters(0.3048)) - 1.0).abs() < 1e-12);
+    assert!((UnitSystem::Imperial.speed(Velocity::meters_per_second(1.0)) - 3.28084).abs() < 1e-5);
+    assert_eq!(UnitSystem::Imperial.length_unit(), "ft");
+    assert_eq!(UnitSystem::Metric.speed_unit(), "m/s");
+}
+
+#[test]
+fn test_project_file_round_trip() {
+    let path = std::env::temp_dir().join(format!("coastal_project_{}.json", std::process::id()));
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.still_water_level = 3.0;
+    wave_app.project().save(&path).unwrap();
+    assert_eq!(Project::load(&path).unwrap(), wave_app.project());
+    std::fs::remove_file(&path).unwrap();
+    assert!(Project::load(&path).is_err());
+}
@@ -0,0 +1,238 @@
+use coastal_engineering_platform::gui::{RemoteApi, RemoteCommand, WaveChannelApp, websocket_accept};
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::thread::JoinHandle;
+
+/// HTTP request to the server with its token, returning the status code and the JSON body
+fn http(address: SocketAddr, token: &str, method: &str, path: &str, body: &str) -> (u16, Value) {
+    let mut stream = TcpStream::connect(address).unwrap();
+    write!(stream, "{} {} HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer {}\r\nContent-Length: {}\r\n\r\n{}", method, path, token, body.len(), body).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let code = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+    (code, serde_json::from_str(body).unwrap())
+}
+
+/// Masked text frame, as clients send them
+fn client_frame(message: &str) -> Vec<u8> {
+    let mask = [1, 2, 3, 4];
+    let mut frame = vec![0x81, 0x80 | message.len() as u8];
+    frame.extend_from_slice(&mask);
+    frame.extend(message.bytes().enumerate().map(|(i, byte)| byte ^ mask[i % 4]));
+    frame
+}
+
+/// Text of the next frame of the server
+fn server_message(stream: &mut TcpStream) -> Value {
+    let mut header = [0; 2];
+    stream.read_exact(&mut header).unwrap();
+    let length = match header[1] {
+        126 => {
+            let mut bytes = [0; 2];
+            stream.read_exact(&mut bytes).unwrap();
+            u16::from_be_bytes(bytes) as usize
+        }
+        127 => {
+            let mut bytes = [0; 8];
+            stream.read_exact(&mut bytes).unwrap();
+            u64::from_be_bytes(bytes) as usize
+        }
+        length => length as usize,
+    };
+    let mut payload = vec![0; length];
+    stream.read_exact(&mut payload).unwrap();
+    serde_json::from_slice(&payload).unwrap()
+}
+
+/// Answer the client from the interface thread until it is done
+fn serve_until<T>(api: &mut RemoteApi, app: &mut WaveChannelApp, client: JoinHandle<T>) -> T {
+    let mut time = 0.0;
+    while !client.is_finished() {
+        api.poll(app, time);
+        time += 0.01;
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+    client.join().unwrap()
+}
+
+#[test]
+fn test_websocket_accept_key() {
+    // Example of RFC 6455
+    assert_eq!(websocket_accept("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+}
+
+#[test]
+fn test_remote_commands() {
+    let mut app = WaveChannelApp::new();
+    assert_eq!(RemoteCommand::from_message(r#"{"command": "start"}"#), Ok(RemoteCommand::Start));
+    assert!(RemoteCommand::from_message(r#"{"command": "launch"}"#).is_err());
+    assert!(RemoteCommand::from_message("start").is_err());
+
+    let status = RemoteCommand::Start.apply(&mut app).unwrap();
+    assert!(app.simulation_running);
+    assert_eq!(status["running"], true);
+    app.advance_simulation(0.5);
+    let surface = RemoteCommand::Surface.apply(&mut app).unwrap();
+    assert_eq!(surface["positions"].as_array().unwrap().len(), surface["elevation"].as_array().unwrap().len());
+    RemoteCommand::Reset.apply(&mut app).unwrap();
+    assert_eq!(app.simulation_time, 0.0);
+
+    let mut project = app.project();
+    project.wave_height = 0.37;
+    let message = serde_json::json!({ "command": "set_project", "project": project }).to_string();
+    RemoteCommand::from_message(&message).unwrap().apply(&mut app).unwrap();
+    assert_eq!(app.wave_height, 0.37);
+}
+
+#[test]
+fn test_rest_api() {
+    let mut app = WaveChannelApp::new();
+    let mut api = RemoteApi::start("127.0.0.1:0", None).unwrap();
+    let address = api.address();
+    let token = api.token().to_string();
+
+    let mut project = app.project();
+    project.wave_period = 3.5;
+    let body = serde_json::to_string(&project).unwrap();
+    let client = std::thread::spawn(move || {
+        (
+            http(address, &token, "GET", "/status", ""),
+            http(address, &token, "POST", "/run/start", ""),
+            http(address, &token, "PUT", "/project", &body),
+            http(address, &token, "GET", "/gauges", ""),
+            http(address, &token, "POST", "/status", ""),
+            http(address, &token, "GET", "/missing", ""),
+            http(address, &token, "PUT", "/project", "{"),
+        )
+    });
+    let (status, started, configured, gauges, wrong_method, missing, invalid) = serve_until(&mut api, &mut app, client);
+
+    assert_eq!(status.0, 200);
+    assert_eq!(status.1["running"], false);
+    assert_eq!(status.1["time"], 0.0);
+    assert_eq!(started.1["running"], true);
+    assert_eq!(configured.0, 200);
+    assert_eq!(configured.1["wave_period"], 3.5);
+    assert_eq!(app.wave_period, 3.5);
+    assert!(app.simulation_running);
+    assert_eq!(gauges.1.as_array().unwrap().len(), app.gauges.len());
+    assert_eq!(wrong_method.0, 405);
+    assert_eq!(missing.0, 404);
+    assert_eq!(invalid.0, 400);
+}
+
+#[test]
+fn test_websocket_api() {
+    let mut app = WaveChannelApp::new();
+    let mut api = RemoteApi::start("127.0.0.1:0", None).unwrap();
+    let address = api.address();
+    let token = api.token().to_string();
+
+    let client = std::thread::spawn(move || {
+        let mut stream = TcpStream::connect(address).unwrap();
+        // Browsers cannot set headers on a WebSocket, so the token comes in the query
+        write!(
+            stream,
+            "GET /ws?token={} HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n",
+            token
+        )
+        .unwrap();
+        let mut handshake = Vec::new();
+        while !handshake.ends_with(b"\r\n\r\n") {
+            let mut byte = [0];
+            stream.read_exact(&mut byte).unwrap();
+            handshake.push(byte[0]);
+        }
+
+        stream.write_all(&client_frame(r#"{"command": "start"}"#)).unwrap();
+        // Live frames may come before the reply
+        let mut messages = vec![server_message(&mut stream)];
+        while messages.last().unwrap()["type"] != "reply" {
+            messages.push(server_message(&mut stream));
+        }
+        stream.write_all(&client_frame(r#"{"command": "jump"}"#)).unwrap();
+        let mut error = server_message(&mut stream);
+        while error["type"] == "frame" {
+            error = server_message(&mut stream);
+        }
+        stream.write_all(&[0x88, 0x80, 0, 0, 0, 0]).unwrap();
+        (String::from_utf8(handshake).unwrap(), messages, error)
+    });
+    let (handshake, messages, error) = serve_until(&mut api, &mut app, client);
+
+    assert!(handshake.starts_with("HTTP/1.1 101"));
+    assert!(handshake.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo="));
+    assert_eq!(messages.last().unwrap()["result"]["running"], true);
+    assert!(app.simulation_running);
+    assert_eq!(error["type"], "error");
+}
+
+#[test]
+fn test_other_sites_and_long_lines_are_refused() {
+    let api = RemoteApi::start("127.0.0.1:0", None).unwrap();
+    let address = api.address();
+    let respond = |request: &str| {
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.write_all(request.as_bytes()).ok();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).ok();
+        response
+    };
+
+    let handshake = "GET /ws HTTP/1.1\r\nHost: localhost:8787\r\nOrigin: https://example.com\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n";
+    let cross_site = respond(handshake);
+    assert!(cross_site.starts_with("HTTP/1.1 403"));
+    assert!(!cross_site.contains("Access-Control-Allow-Origin"));
+    assert!(respond("POST /run/start HTTP/1.1\r\nHost: localhost:8787\r\nOrigin: null\r\n\r\n").starts_with("HTTP/1.1 403"));
+    let long_header = format!("GET /status HTTP/1.1\r\nX-Padding: {}\r\n\r\n", "a".repeat(64 << 10));
+    assert!(respond(&long_header).starts_with("HTTP/1.1 400"));
+}
+
+#[test]
+fn test_pages_of_the_server_are_allowed() {
+    let mut app = WaveChannelApp::new();
+    let mut api = RemoteApi::start("127.0.0.1:0", None).unwrap();
+    let address = api.address();
+    let token = api.token().to_string();
+    let client = std::thread::spawn(move || {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(stream, "GET /status HTTP/1.1\r\nHost: {}\r\nOrigin: http://{}\r\nAuthorization: Bearer {}\r\n\r\n", address, address, token).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    });
+    assert!(serve_until(&mut api, &mut app, client).starts_with("HTTP/1.1 200"));
+}
+
+#[test]
+fn test_rebound_names_and_missing_tokens_are_refused() {
+    let api = RemoteApi::start("127.0.0.1:0", None).unwrap();
+    let address = api.address();
+    let token = api.token().to_string();
+    let respond = |headers: &str| {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(stream, "POST /run/start HTTP/1.1\r\n{}\r\n", headers).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    };
+
+    // A page of a site whose name now points to this machine is its own origin
+    let rebound = format!("Host: evil.example:8787\r\nOrigin: http://evil.example:8787\r\nAuthorization: Bearer {}\r\n", token);
+    assert!(respond(&rebound).starts_with("HTTP/1.1 403"));
+    assert!(respond("Host: localhost\r\n").starts_with("HTTP/1.1 401"));
+    assert!(respond("Host: [::1]:8787\r\nAuthorization: Bearer wrong\r\n").starts_with("HTTP/1.1 401"));
+    assert_ne!(RemoteApi::start("127.0.0.1:0", None).unwrap().token(), token);
+}
+
+#[test]
+fn test_idle_connections_are_closed() {
+    let api = RemoteApi::start("127.0.0.1:0", None).unwrap();
+    let mut stream = TcpStream::connect(api.address()).unwrap();
+    stream.write_all(b"GET /status HTTP/1.1\r\n").unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 400"));
+}
@@ -0,0 +1,29 @@
+use coastal_engineering_platform::gui::{ANIMATION_FPS, Preferences, RepaintPolicy};
+
+#[test]
+fn test_battery_saver_caps_the_frame_rates() {
+    let mut policy = RepaintPolicy::default();
+    assert_eq!(policy.animation_fps(), ANIMATION_FPS);
+    assert_eq!(policy.progress_fps(), 10.0);
+
+    policy.battery_saver = true;
+    assert_eq!(policy.animation_fps(), 20.0);
+    policy.battery_saver_fps = 4.0;
+    assert_eq!(policy.animation_fps(), 4.0);
+    assert_eq!(policy.progress_fps(), 4.0);
+    policy.battery_saver_fps = 240.0;
+    assert_eq!(policy.animation_fps(), ANIMATION_FPS);
+}
+
+#[test]
+fn test_policy_kept_in_preferences() {
+    let mut preferences = Preferences::default();
+    preferences.repaint_policy.battery_saver = true;
+    let json = serde_json::to_string(&preferences).unwrap();
+    let loaded: Preferences = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.repaint_policy, preferences.repaint_policy);
+
+    // Preferences saved before the policy existed
+    let loaded: Preferences = serde_json::from_str("{\"show_help\": true}").unwrap();
+    assert_eq!(loaded.repaint_policy, RepaintPolicy::default());
+}
@@ -0,0 +1,110 @@
+use coastal_engineering_platform::analysis::{DissipationRecord, ForceRecord, InundationRecord, LevelDriftRecord, MeanFlowRecord, PressureSensor, ShorelineRecord, SnapshotStore, WaveGauge};
+use coastal_engineering_platform::gui::{CachedResult, ResultCache, WaveChannelApp};
+use super::run_to_completion;
+
+/// Empty cache in a directory unique to the test
+fn temporary_cache(name: &str) -> ResultCache {
+    let directory = std::env::temp_dir().join(format!("coastal_result_cache_{}_{}", name, std::process::id()));
+    let cache = ResultCache::new(directory);
+    cache.clear().unwrap();
+    cache
+}
+
+fn short_case(cache: &ResultCache) -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new().with_result_cache(cache.clone());
+    wave_app.number_of_waves = 2;
+    wave_app.gauges.push(WaveGauge::new(10.0));
+    wave_app
+}
+
+#[test]
+fn test_store_and_load() {
+    let cache = temporary_cache("store");
+    let mut gauge = WaveGauge::new(5.0);
+    gauge.record(0.0, 0.1);
+    gauge.record(0.05, 0.2);
+    let mut sensor = PressureSensor::new(10.0, 0.5);
+    sensor.record(0.05, 9000.0);
+    let mut snapshots = SnapshotStore::default();
+    snapshots.record(12.5, &[0.0, 0.1, -0.1]);
+    let result = CachedResult {
+        configuration: "case".to_string(),
+        simulation_time: 12.5,
+        surface_elevation: vec![0.0, 0.1, -0.1],
+        gauges: vec![gauge],
+        pressure_sensors: vec![sensor],
+        wall_force: ForceRecord::default(),
+        overtopping_tank: None,
+        shoreline: ShorelineRecord::default(),
+        mean_flow: MeanFlowRecord::default(),
+        inundation: InundationRecord::default(),
+        dissipation: DissipationRecord::default(),
+        level_drift: LevelDriftRecord::default(),
+        snapshots,
+        metadata: None,
+    };
+
+    assert!(cache.load("case").is_none());
+    cache.store(&result).unwrap();
+    assert_eq!(cache.load("case"), Some(result));
+    assert!(cache.load("other case").is_none());
+
+    cache.clear().unwrap();
+    assert!(cache.load("case").is_none());
+}
+
+#[test]
+fn test_configuration_key_changes_with_settings() {
+    let mut wave_app = WaveChannelApp::new();
+    let key = wave_app.configuration_key();
+    assert_eq!(key, WaveChannelApp::new().configuration_key());
+
+    wave_app.wave_height = 0.6;
+    assert_ne!(wave_app.configuration_key(), key);
+    wave_app.wave_height = 0.5;
+    wave_app.gauges.push(WaveGauge::new(10.0));
+    assert_ne!(wave_app.configuration_key(), key);
+}
+
+#[test]
+fn test_completed_run_is_restored() {
+    let cache = temporary_cache("restore");
+    let mut first = short_case(&cache);
+    run_to_completion(&mut first);
+    assert!(!first.restored_from_cache);
+
+    // Reopening the same case restores the final state and gauge records at once
+    let mut second = short_case(&cache);
+    assert!(second.restore_cached_result());
+    assert!(second.restored_from_cache);
+    assert!(second.is_simulation_complete());
+    assert_eq!(second.simulation_time, first.simulation_time);
+    assert_eq!(second.surface_elevation, first.surface_elevation);
+    assert_eq!(second.gauges, first.gauges);
+    assert_eq!(second.snapshots, first.snapshots);
+
+    // A reset case runs again instead of being restored
+    second.reset_simulation();
+    assert!(!second.restore_cached_result());
+    assert_eq!(second.simulation_time, 0.0);
+    cache.clear().unwrap();
+}
+
+#[test]
+fn test_switching_back_restores_previous_case() {
+    let cache = temporary_cache("switch");
+    let mut wave_app = short_case(&cache);
+    run_to_completion(&mut wave_app);
+    let completed_time = wave_app.simulation_time;
+
+    // A different case has no result yet
+    wave_app.reset_simulation();
+    wave_app.wave_height = 0.3;
+    assert!(!wave_app.restore_cached_result());
+
+    // Switching back to the first case restores it
+    wave_app.wave_height = 0.5;
+    assert!(wave_app.restore_cached_result());
+    assert_eq!(wave_app.simulation_time, completed_time);
+    cache.clear().unwrap();
+}
@@ -0,0 +1,59 @@
+use coastal_engineering_platform::analysis::WaveGauge;
+use coastal_engineering_platform::gui::{ResultsTable, TableCell, TableView, WaveChannelApp};
+
+fn table() -> ResultsTable {
+    let mut table = ResultsTable::new(["Transect", "Run-up (m)"]);
+    table.push(vec![TableCell::Text("North\tgroyne".to_string()), TableCell::Number(0.25)]);
+    table.push(vec![TableCell::Text("south".to_string()), TableCell::Failed("Simulation diverged".to_string())]);
+    table.push(vec![TableCell::Text("North dike".to_string()), TableCell::Number(0.125)]);
+    table
+}
+
+#[test]
+fn test_sort_keeps_missing_values_last() {
+    let table = table();
+    let mut view = TableView::default();
+    assert_eq!(table.visible_rows(&view), [0, 1, 2]);
+    view.toggle_sort(1);
+    assert_eq!(table.visible_rows(&view), [2, 0, 1]);
+    view.toggle_sort(1);
+    assert_eq!(view.sort, Some((1, true)));
+    assert_eq!(table.visible_rows(&view), [0, 2, 1]);
+    // Text sorts without regard to case
+    view.toggle_sort(0);
+    assert_eq!(table.visible_rows(&view), [0, 2, 1]);
+}
+
+#[test]
+fn test_filter_matches_every_word() {
+    let table = table();
+    let view = TableView { filter: "north 0.1250".to_string(), ..TableView::default() };
+    assert_eq!(table.visible_rows(&view), [2]);
+    let view = TableView { filter: "NORTH".to_string(), ..TableView::default() };
+    assert_eq!(table.visible_rows(&view), [0, 2]);
+}
+
+#[test]
+fn test_copy_as_tab_separated_values() {
+    let table = table();
+    let rows = table.visible_rows(&TableView { sort: Some((1, false)), ..TableView::default() });
+    assert_eq!(table.to_tsv(&rows), "Transect\tRun-up (m)\nNorth dike\t0.125\nNorth groyne\t0.25\nsouth\t\n");
+}
+
+#[test]
+fn test_gauge_statistics_table() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.gauges = vec![WaveGauge::new(2.0), WaveGauge::new(8.0)];
+    for i in 0..400 {
+        let time = i as f64 * 0.05;
+        wave_app.gauges[0].record(time, 0.1 * (std::f64::consts::PI * time).sin());
+    }
+    let table = wave_app.gauge_statistics();
+    assert_eq!(table.rows.len(), 2);
+    assert_eq!(table.columns.len(), table.rows[0].len());
+    let TableCell::Number(height) = table.rows[0][6] else { panic!("Gauge 1 must have Hm0") };
+    assert!((height - 0.4 / 2f64.sqrt()).abs() < 0.01, "Hm0 {:.4}", height);
+    let TableCell::Number(period) = table.rows[0][7] else { panic!("Gauge 1 must have Tp") };
+    assert!((period - 2.0).abs() < 0.1, "Tp {:.3}", period);
+    assert!(matches!(table.rows[1][3], TableCell::Failed(_)));
+}
@@ -0,0 +1,96 @@
+use coastal_engineering_platform::gui::{PlatformTab, SessionEvent, SessionRecorder, SessionRecording, SessionState, WaveChannelApp};
+
+fn state(wave_app: &WaveChannelApp, tab: PlatformTab) -> SessionState {
+    SessionState {
+        project: wave_app.project(),
+        running: wave_app.simulation_running,
+        simulation_time: wave_app.simulation_time,
+        tab,
+    }
+}
+
+#[test]
+fn test_recording_captures_changes() {
+    let mut wave_app = WaveChannelApp::new();
+    let mut recorder = SessionRecorder::new();
+    recorder.start_recording(100.0, state(&wave_app, PlatformTab::WaveChannel));
+    assert!(recorder.is_recording());
+    let initial_events = recorder.recording_progress(100.0).unwrap().1;
+
+    // Unchanged frames record nothing
+    recorder.record(100.5, state(&wave_app, PlatformTab::WaveChannel));
+    assert_eq!(recorder.recording_progress(100.5).unwrap().1, initial_events);
+
+    wave_app.wave_height = 0.8;
+    recorder.record(101.0, state(&wave_app, PlatformTab::WaveChannel));
+    wave_app.simulation_running = true;
+    wave_app.advance_simulation(0.05);
+    recorder.record(102.0, state(&wave_app, PlatformTab::WaveChannel));
+    wave_app.reset_simulation();
+    recorder.record(103.0, state(&wave_app, PlatformTab::DispersionExplorer));
+
+    let recording = recorder.stop_recording().unwrap();
+    assert!(!recorder.is_recording());
+    let events: Vec<(f64, &SessionEvent)> = recording.entries[initial_events..].iter().map(|entry| (entry.time, &entry.event)).collect();
+    assert_eq!(events.len(), 5);
+    assert!(matches!(events[0], (1.0, SessionEvent::Configure(project)) if project.wave_height == 0.8));
+    assert_eq!(events[1], (2.0, &SessionEvent::Running(true)));
+    assert_eq!(events[2], (3.0, &SessionEvent::OpenTab(PlatformTab::DispersionExplorer)));
+    assert_eq!(events[3], (3.0, &SessionEvent::Reset));
+    assert_eq!(events[4], (3.0, &SessionEvent::Running(false)));
+    assert_eq!(recording.duration(), 3.0);
+}
+
+#[test]
+fn test_replay_timing() {
+    let mut recorder = SessionRecorder::new();
+    let wave_app = WaveChannelApp::new();
+    recorder.start_recording(0.0, state(&wave_app, PlatformTab::WaveChannel));
+    recorder.record(2.0, state(&wave_app, PlatformTab::Comparison));
+    let recording = recorder.stop_recording().unwrap();
+
+    recorder.start_replay(50.0, recording.clone());
+    assert!(recorder.is_replaying());
+    let first = recorder.due_events(50.0);
+    assert_eq!(first.len(), recording.entries.len() - 1);
+    assert!(recorder.due_events(51.0).is_empty());
+    assert_eq!(recorder.replay_progress(51.0), Some((1.0, 2.0)));
+    assert_eq!(recorder.due_events(52.5), vec![SessionEvent::OpenTab(PlatformTab::Comparison)]);
+    // The replay ends after its last event
+    assert!(!recorder.is_replaying());
+    assert!(recorder.due_events(60.0).is_empty());
+}
+
+#[test]
+fn test_recording_file() {
+    let mut recorder = SessionRecorder::new();
+    let wave_app = WaveChannelApp::new();
+    recorder.start_recording(0.0, state(&wave_app, PlatformTab::WaveChannel));
+    let recording = recorder.stop_recording().unwrap();
+
+    let path = std::env::temp_dir().join(format!("session_tests_{}.json", std::process::id()));
+    recording.save(&path).unwrap();
+    let loaded = SessionRecording::load(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded, Ok(recording));
+    assert!(SessionRecording::from_json("{").is_err());
+}
+
+#[test]
+fn test_apply_project_keeps_simulation() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.add_gauge();
+    wave_app.simulation_running = true;
+    wave_app.advance_simulation(0.05);
+    let mut project = wave_app.project();
+    project.wave_height = 0.7;
+
+    wave_app.apply_project(&project);
+    assert_eq!(wave_app.wave_height, 0.7);
+    assert!(wave_app.simulation_running);
+    assert!(wave_app.simulation_time > 0.0);
+    assert!(!wave_app.gauges[0].is_empty());
+
+    wave_app.load_project(&project);
+    assert_eq!(wave_app.simulation_time, 0.0);
+}
@@ -0,0 +1,61 @@
+use coastal_engineering_platform::gui::{Command, KeyBinding, Preferences, Shortcuts};
+use eframe::egui::Key;
+
+#[test]
+fn test_default_shortcuts() {
+    let shortcuts = Shortcuts::new();
+    assert_eq!(shortcuts.binding(Command::PlayPause), Some(KeyBinding::key(Key::Space)));
+    assert_eq!(shortcuts.binding(Command::Reset), Some(KeyBinding::key(Key::R)));
+    assert_eq!(shortcuts.binding(Command::AddGauge), Some(KeyBinding::key(Key::G)));
+    assert_eq!(shortcuts.binding(Command::DamBreakScenario), None);
+
+    // No two commands share a default shortcut
+    let bindings: Vec<KeyBinding> = Command::all().into_iter().filter_map(|command| shortcuts.binding(command)).collect();
+    for (i, binding) in bindings.iter().enumerate() {
+        assert!(!bindings[i + 1..].contains(binding), "{} bound twice", binding);
+    }
+}
+
+#[test]
+fn test_key_binding_text() {
+    let binding = KeyBinding { key: Key::G, command: true, shift: true, alt: false };
+    assert_eq!(binding.to_string(), "Ctrl+Shift+G");
+    assert_eq!("Ctrl+Shift+G".parse::<KeyBinding>(), Ok(binding));
+    assert_eq!("Space".parse::<KeyBinding>(), Ok(KeyBinding::key(Key::Space)));
+    assert!("Ctrl+Nothing".parse::<KeyBinding>().is_err());
+    assert!("Hyper+G".parse::<KeyBinding>().is_err());
+}
+
+#[test]
+fn test_remapping() {
+    let mut shortcuts = Shortcuts::new();
+    shortcuts.set_binding(Command::Reset, Some(KeyBinding::key(Key::G)));
+    assert_eq!(shortcuts.binding(Command::Reset), Some(KeyBinding::key(Key::G)));
+    // The key is taken from the command that had it
+    assert_eq!(shortcuts.binding(Command::AddGauge), None);
+    assert!(!shortcuts.is_default());
+
+    shortcuts.set_binding(Command::PlayPause, None);
+    assert_eq!(shortcuts.binding(Command::PlayPause), None);
+
+    shortcuts.reset();
+    assert!(shortcuts.is_default());
+    assert_eq!(shortcuts.binding(Command::AddGauge), Some(KeyBinding::key(Key::G)));
+}
+
+#[test]
+fn test_shortcuts_persist_with_preferences() {
+    let path = std::env::temp_dir().join(format!("shortcuts_tests_{}.json", std::process::id()));
+    let mut preferences = Preferences::default();
+    preferences.shortcuts.set_binding(Command::Reset, Some(KeyBinding::command(Key::R)));
+    preferences.shortcuts.set_binding(Command::NewSeed, None);
+    preferences.save(&path).unwrap();
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(content.contains("Ctrl+R"));
+    let loaded = Preferences::load(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.shortcuts, preferences.shortcuts);
+    assert_eq!(loaded.shortcuts.binding(Command::NewSeed), None);
+    assert_eq!(loaded.shortcuts.binding(Command::AddGauge), Some(KeyBinding::key(Key::G)));
+}
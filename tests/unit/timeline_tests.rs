@@ -0,0 +1,114 @@
+use coastal_engineering_platform::analysis::SnapshotStore;
+use coastal_engineering_platform::gui::Timeline;
+
+/// Ten seconds of snapshots every 0.5 s, all at full resolution
+fn recorded_run() -> SnapshotStore {
+    let mut snapshots = SnapshotStore::default();
+    for i in 0..=20 {
+        let time = i as f64 * 0.5;
+        snapshots.record(time, &[time]);
+    }
+    snapshots
+}
+
+#[test]
+fn test_timeline_follows_simulation_by_default() {
+    let snapshots = recorded_run();
+    let timeline = Timeline::new();
+    assert_eq!(timeline.cursor(), None);
+    assert_eq!(timeline.loop_range(), None);
+    assert!(!timeline.is_playing());
+    assert!(timeline.displayed_snapshot(&snapshots).is_none());
+}
+
+#[test]
+fn test_frame_stepping() {
+    let snapshots = recorded_run();
+    let mut timeline = Timeline::new();
+
+    // Stepping back from the live view starts from the last frame
+    timeline.step(&snapshots, false);
+    assert_eq!(timeline.cursor(), Some(9.5));
+    timeline.step(&snapshots, false);
+    assert_eq!(timeline.cursor(), Some(9.0));
+    timeline.step(&snapshots, true);
+    assert_eq!(timeline.cursor(), Some(9.5));
+
+    // Stepping stops at both ends of the record
+    timeline.scrub_to(&snapshots, 20.0);
+    timeline.step(&snapshots, true);
+    assert_eq!(timeline.cursor(), Some(10.0));
+    timeline.scrub_to(&snapshots, 0.1);
+    assert_eq!(timeline.cursor(), Some(0.0));
+    timeline.step(&snapshots, false);
+    assert_eq!(timeline.cursor(), Some(0.0));
+}
+
+#[test]
+fn test_replay_stops_at_last_frame() {
+    let snapshots = recorded_run();
+    let mut timeline = Timeline::new();
+    timeline.scrub_to(&snapshots, 9.0);
+    timeline.toggle_replay(&snapshots);
+    assert!(timeline.is_playing());
+
+    timeline.advance(&snapshots, 0.2);
+    assert!((timeline.cursor().unwrap() - 9.2).abs() < 1e-9);
+    assert_eq!(timeline.displayed_snapshot(&snapshots).unwrap().time, 9.0);
+    timeline.advance(&snapshots, 1.0);
+    assert_eq!(timeline.cursor(), Some(10.0));
+    assert!(!timeline.is_playing());
+
+    // Replaying again from the last frame starts over
+    timeline.toggle_replay(&snapshots);
+    assert_eq!(timeline.cursor(), Some(0.0));
+}
+
+#[test]
+fn test_loop_range() {
+    let snapshots = recorded_run();
+    let mut timeline = Timeline::new();
+    timeline.scrub_to(&snapshots, 2.0);
+    timeline.set_loop_start(&snapshots);
+    assert_eq!(timeline.loop_range(), Some((2.0, 10.0)));
+    timeline.scrub_to(&snapshots, 4.0);
+    timeline.set_loop_end(&snapshots);
+    assert_eq!(timeline.loop_range(), Some((2.0, 4.0)));
+
+    // A loop start after the loop end is ignored
+    timeline.scrub_to(&snapshots, 6.0);
+    timeline.set_loop_start(&snapshots);
+    assert_eq!(timeline.loop_range(), Some((2.0, 4.0)));
+
+    // Replay starts at the loop start and wraps around it
+    timeline.toggle_replay(&snapshots);
+    assert_eq!(timeline.cursor(), Some(2.0));
+    timeline.advance(&snapshots, 1.5);
+    timeline.advance(&snapshots, 1.0);
+    assert!(timeline.is_playing());
+    assert!((timeline.cursor().unwrap() - 2.5).abs() < 1e-9);
+
+    timeline.clear_loop();
+    assert_eq!(timeline.loop_range(), None);
+    timeline.follow_live();
+    assert_eq!(timeline.cursor(), None);
+    assert!(!timeline.is_playing());
+}
+
+#[test]
+fn test_exact_scrubbing() {
+    let snapshots = recorded_run();
+    let mut timeline = Timeline::new();
+    timeline.set_exact_scrubbing(true);
+
+    // The requested time is kept for a finer record, within the stored range
+    timeline.scrub_to(&snapshots, 2.3);
+    assert_eq!(timeline.cursor(), Some(2.3));
+    assert_eq!(timeline.displayed_snapshot(&snapshots).unwrap().time, 2.5);
+    timeline.scrub_to(&snapshots, 50.0);
+    assert_eq!(timeline.cursor(), Some(10.0));
+
+    timeline.set_exact_scrubbing(false);
+    timeline.scrub_to(&snapshots, 2.3);
+    assert_eq!(timeline.cursor(), Some(2.5));
+}
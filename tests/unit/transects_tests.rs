@@ -0,0 +1,72 @@
+use coastal_engineering_platform::analysis::WaveGauge;
+use coastal_engineering_platform::gui::{Project, PropagationModel, RunOutput, Transect, TransectStudy, TransectsApp, WaveChannelApp};
+use coastal_engineering_platform::solver::CrossSection;
+
+fn scenario() -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Analytical;
+    wave_app.channel_length = 20.0;
+    wave_app.still_water_level = 0.5;
+    wave_app.number_of_waves = 3;
+    wave_app.gauges.push(WaveGauge::new(5.0));
+    wave_app
+}
+
+#[test]
+fn test_transects_share_the_waves() {
+    let mut wave_app = scenario();
+    wave_app.transects.push(Transect::from_channel("Shallow", 0.0, &wave_app));
+    wave_app.still_water_level = 1.0;
+    wave_app.cross_section = Some(CrossSection::beach(20.0, 1.0));
+    wave_app.transects.push(Transect::from_channel("Deep", 250.0, &wave_app));
+    wave_app.wave_height = 0.08;
+
+    // Each transect keeps its profile and takes the waves of the scenario
+    let project = wave_app.project();
+    let deep = project.transects[1].scenario(&project);
+    assert_eq!((deep.still_water_level, deep.wave_height), (1.0, 0.08));
+    assert_eq!(deep.cross_section, Some(CrossSection::beach(20.0, 1.0)));
+    assert_eq!(project.transects[0].scenario(&project).cross_section, None);
+
+    // Run-up is reported per transect as it needs the numerical solver
+    let study = TransectStudy { outputs: vec![RunOutput::MaxElevation { gauge: 0 }, RunOutput::RunUp], threads: 2 };
+    let runs = study.run_to_completion(&project);
+    assert_eq!(runs.iter().map(|run| run.transect).collect::<Vec<_>>(), [0, 1]);
+    for run in &runs {
+        let crest = *run.outputs[0].as_ref().unwrap();
+        assert!((crest - 0.04).abs() < 0.01, "Crest {:.4} on transect {}", crest, run.transect);
+        assert!(run.outputs[1].is_err());
+    }
+}
+
+#[test]
+fn test_transect_study_validation() {
+    let mut wave_app = scenario();
+    let study = TransectStudy::default();
+    assert!(study.validate(&wave_app.project()).is_err());
+    wave_app.transects.push(Transect::from_channel("  T1 ", 10.0, &wave_app));
+    assert_eq!(wave_app.transects[0].name, "T1");
+    assert!(study.validate(&wave_app.project()).is_ok());
+    assert!(TransectStudy { outputs: Vec::new(), ..study.clone() }.validate(&wave_app.project()).is_err());
+    wave_app.transects[0].still_water_level = 0.0;
+    assert!(study.validate(&wave_app.project()).is_err());
+
+    let mut transects = TransectsApp::new();
+    assert!(transects.start(scenario().project()).is_err());
+    assert!(!transects.is_running());
+}
+
+#[test]
+fn test_transect_loads_into_channel_and_project() {
+    let mut wave_app = scenario();
+    wave_app.cross_section = Some(CrossSection::dike(20.0, 0.5));
+    let transect = Transect::from_channel("Dike", 120.0, &wave_app);
+    wave_app.transects.push(transect.clone());
+    let project: Project = serde_json::from_str(&serde_json::to_string(&wave_app.project()).unwrap()).unwrap();
+    assert_eq!(project.transects, std::slice::from_ref(&transect));
+
+    let mut other = scenario();
+    other.channel_length = 30.0;
+    transect.apply(&mut other);
+    assert_eq!((other.channel_length, other.cross_section.clone()), (20.0, Some(CrossSection::dike(20.0, 0.5))));
+}
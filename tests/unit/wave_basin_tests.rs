@@ -0,0 +1,367 @@
+use coastal_engineering_platform::analysis::{Transect, WaveGauge};
+use coastal_engineering_platform::gui::{BasinGauge, WaveBasinApp};
+use coastal_engineering_platform::solver::{BasinField, BasinSponges, Berth, GeoReference, HarborLayout, HarborStructure, Obstacle, SurveyUnit};
+use coastal_engineering_platform::waves::WaveSpectrum;
+
+fn small_basin() -> WaveBasinApp {
+    let mut basin = WaveBasinApp::new();
+    basin.basin_length = 12.0;
+    basin.basin_width = 6.0;
+    basin.grid_spacing = 0.25;
+    basin.wave_period = 1.5;
+    basin.number_of_waves = 2;
+    basin.sponges = Some(BasinSponges { width: 3.0, ..BasinSponges::default() });
+    basin.obstacles = vec![Obstacle::Cylinder { x: 6.0, y: 3.0, radius: 0.5 }];
+    basin.gauges = vec![BasinGauge::new(4.0, 3.0)];
+    basin
+}
+
+#[test]
+fn test_grid_follows_the_basin_dimensions() {
+    let basin = small_basin();
+    assert_eq!(basin.grid_size(), (48, 24));
+    let solver = basin.build_solver().unwrap();
+    assert_eq!((solver.grid().length(), solver.grid().width()), (12.0, 6.0));
+    assert!(solver.is_solid(24, 12));
+    assert!(!solver.is_nonlinear());
+}
+
+#[test]
+fn test_waves_reach_the_gauges() {
+    let mut basin = small_basin();
+    assert!(basin.solver().is_none());
+    basin.start_simulation().unwrap();
+    for _ in 0..80 {
+        basin.advance_simulation(0.05);
+    }
+    assert!((basin.simulation_time() - 4.0).abs() < 1e-9);
+
+    // The gauge records the surface of the solver at its point
+    let gauge = &basin.gauges[0];
+    assert_eq!(gauge.record.len(), 80);
+    let solver = basin.solver().unwrap();
+    assert_eq!(*gauge.record.elevation().last().unwrap(), solver.surface_elevation_at(4.0, 3.0));
+    let crest = gauge.record.elevation().iter().copied().fold(0.0, f64::max);
+    assert!(crest > 0.3 * basin.wave_height, "Crest {:.4}", crest);
+
+    basin.reset_simulation();
+    assert!(basin.solver().is_none());
+    assert_eq!(basin.simulation_time(), 0.0);
+    assert!(basin.gauges[0].record.is_empty());
+}
+
+#[test]
+fn test_run_stops_at_its_end() {
+    let mut basin = small_basin();
+    basin.start_simulation().unwrap();
+    let end = basin.total_simulation_time();
+    while basin.simulation_running {
+        basin.advance_simulation(0.5);
+    }
+    assert!(basin.simulation_time() >= end && basin.simulation_time() < end + 0.5);
+}
+
+#[test]
+fn test_background_compute_follows_the_frames() {
+    let mut basin = small_basin();
+    basin.start_simulation().unwrap();
+    basin.advance_in_background(0.5);
+    let geometry = basin.solver().unwrap().time();
+    assert_eq!(geometry, 0.0);
+    while basin.simulation_time() < 2.0 {
+        basin.advance_in_background(0.5);
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    // The gauges record the states the thread reached, at the frames they were read
+    let gauge = &basin.gauges[0];
+    assert!(!gauge.record.is_empty());
+    assert!(gauge.record.times().windows(2).all(|pair| pair[1] > pair[0]));
+    assert!(basin.snapshots().end_time().is_some_and(|time| time > 0.0));
+
+    // Pausing takes the solver back at the state the thread reached
+    basin.simulation_running = false;
+    let time = basin.simulation_time();
+    basin.stop_background();
+    let solver = basin.solver().unwrap();
+    assert!(solver.time() >= time && solver.time() <= 2.5 + 1e-9);
+    let shown = basin.displayed_frame().unwrap();
+    assert_eq!(shown.0, solver.time());
+
+    // Resuming continues from there until the end of the run
+    basin.start_simulation().unwrap();
+    let end = basin.total_simulation_time();
+    while basin.simulation_running {
+        basin.advance_in_background(0.5);
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    assert!((basin.solver().unwrap().time() - end).abs() < 1e-9);
+    assert!((basin.simulation_time() - end).abs() < 1e-9);
+}
+
+#[test]
+fn test_oblique_regular_waves() {
+    let mut basin = small_basin();
+    basin.wave_direction = 30.0_f64.to_radians();
+    let forcing = basin.wave_forcing().unwrap();
+    let sources: Vec<_> = forcing.active_boundary_sources().collect();
+    assert_eq!(sources.len(), 1);
+    assert_eq!(sources[0].direction(), basin.wave_direction);
+}
+
+#[test]
+fn test_directional_irregular_waves() {
+    let mut basin = small_basin();
+    basin.spectrum = Some(WaveSpectrum::jonswap(0.04, 1.5, 3.3).unwrap());
+    assert!((basin.generated_wave_height() - 0.04).abs() < 1e-9);
+    let long_crested = basin.wave_forcing().unwrap();
+    assert_eq!(long_crested.active_boundary_sources().count(), 48);
+    let variance: f64 = long_crested.active_boundary_sources().map(|source| 0.5 * source.parameters().amplitude().powi(2)).sum();
+    assert!((variance.sqrt() * 4.0 - 0.04).abs() < 0.002, "Hm0 {:.4}", 4.0 * variance.sqrt());
+
+    // Spread over the sectors towards the basin, the same variance within the share travelling back
+    basin.wave_direction = 20.0_f64.to_radians();
+    basin.spreading = Some(10.0);
+    let forcing = basin.wave_forcing().unwrap();
+    assert!(forcing.active_boundary_sources().all(|source| source.direction().cos() > 0.0));
+    let directions: Vec<f64> = forcing.active_boundary_sources().map(|source| source.direction()).collect();
+    assert!(directions.iter().any(|&direction| direction < 0.0) && directions.iter().any(|&direction| direction > 0.5));
+    let spread: f64 = forcing.active_boundary_sources().map(|source| 0.5 * source.parameters().amplitude().powi(2)).sum();
+    assert!(spread < variance && spread > 0.9 * variance);
+
+    // The same seed draws the same sea
+    let elevations = |basin: &WaveBasinApp| -> Vec<f64> { basin.wave_forcing().unwrap().active_boundary_sources().map(|source| source.surface_elevation(0.0)).collect() };
+    let first = elevations(&basin);
+    assert_eq!(elevations(&basin), first);
+    basin.random_seed += 1;
+    assert_ne!(elevations(&basin), first);
+}
+
+#[test]
+fn test_invalid_setup_does_not_start() {
+    let mut basin = small_basin();
+    basin.sponges = Some(BasinSponges { width: 20.0, ..BasinSponges::default() });
+    assert!(basin.start_simulation().is_err());
+    assert!(!basin.simulation_running);
+
+    let mut basin = small_basin();
+    basin.wave_height = 1.0;
+    assert!(basin.start_simulation().is_err());
+    assert!(basin.solver().is_none());
+}
+
+#[test]
+fn test_playback_shows_stored_frames() {
+    let mut basin = small_basin();
+    basin.start_simulation().unwrap();
+    for _ in 0..60 {
+        basin.advance_simulation(0.05);
+    }
+    // A frame every 0.25 s from the start
+    assert_eq!(basin.snapshots().len(), 13);
+    let (time, live) = basin.displayed_frame().map(|(time, values)| (time, values.into_owned())).unwrap();
+    assert!((time - 3.0).abs() < 1e-9);
+    assert_eq!(live.len(), 3 * 48 * 24);
+
+    basin.scrub_to(1.1);
+    assert!(!basin.simulation_running);
+    let (time, stored) = basin.displayed_frame().unwrap();
+    assert!((time - 1.0).abs() < 1e-9);
+    assert_ne!(*stored, live[..]);
+
+    basin.follow_live();
+    assert!((basin.displayed_frame().unwrap().0 - 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_transect_feeds_the_wave_analysis() {
+    let mut basin = small_basin();
+    basin.transect = Some(Transect::new([0.0, 1.0], [12.0, 1.0]).unwrap());
+    assert!(basin.transect_profile().is_none());
+    basin.start_simulation().unwrap();
+    for _ in 0..100 {
+        basin.advance_simulation(0.05);
+    }
+
+    // The profile samples the displayed surface about once per cell
+    let (distances, profile) = basin.transect_profile().unwrap();
+    assert_eq!(distances.len(), 49);
+    let solver = basin.solver().unwrap();
+    assert!((profile[16] - solver.surface_elevation_at(4.0, 1.0)).abs() < 1e-12);
+
+    // Records along the transect come from the stored frames, waves near the paddle and calm far into the sponge
+    let gauges = basin.transect_gauges();
+    assert_eq!(gauges.len(), 49);
+    assert_eq!(gauges[0].len(), basin.snapshots().len());
+    let range = |gauge: &WaveGauge| gauge.elevation().iter().fold(0.0_f64, |range, eta| range.max(eta.abs()));
+    assert!(range(&gauges[8]) > 0.3 * basin.wave_height);
+    assert!(range(&gauges[48]) < range(&gauges[8]));
+
+    basin.field = BasinField::Speed;
+    let (_, speeds) = basin.transect_profile().unwrap();
+    assert!(speeds.iter().all(|&speed| speed >= 0.0));
+    assert!(speeds.iter().any(|&speed| speed > 0.0));
+}
+
+#[test]
+fn test_breakwater_diffraction_preset_follows_the_theory() {
+    let mut basin = WaveBasinApp::new();
+    basin.load_diffraction_preset();
+    assert!(basin.show_diffraction && basin.active_absorption);
+    let theory = basin.breakwater_diffraction().unwrap();
+    assert_eq!(theory.tip(), [8.2, 15.0]);
+    assert!(theory.in_shadow(18.0, 10.0) && !theory.in_shadow(18.0, 20.0));
+
+    // A coarser grid keeps the run short, about nine cells per wavelength
+    basin.grid_spacing = 0.5;
+    basin.start_simulation().unwrap();
+    let (start, end) = basin.height_window();
+    assert!(start < end);
+    while basin.simulation_time() < start {
+        basin.advance_simulation(0.05);
+    }
+    assert!(basin.diffraction_coefficients().is_none());
+    while basin.simulation_time() < end {
+        basin.advance_simulation(0.05);
+    }
+    let measured = basin.diffraction_coefficients().unwrap();
+    let grid = *basin.solver().unwrap().grid();
+    for gauge in &basin.gauges {
+        let (x, y) = (gauge.record.position(), gauge.y);
+        let kd = grid.interpolate_centers(&measured, x, y);
+        assert!((kd - theory.coefficient(x, y)).abs() < 0.05, "Kd {:.3} at ({}, {}), {:.3} in theory", kd, x, y, theory.coefficient(x, y));
+    }
+    // Along the transect across the lee, from the shadow to the lit region, within the dispersion of the coarse grid
+    let (distances, measured, theory) = basin.transect_diffraction().unwrap();
+    assert_eq!((distances.len(), measured.len(), theory.len()), (45, 45, 45));
+    for ((s, kd), expected) in distances.iter().zip(&measured).zip(&theory) {
+        assert!((kd - expected).abs() < 0.12, "Kd {:.3} at {:.1} m, {:.3} in theory", kd, s, expected);
+    }
+}
+
+#[test]
+fn test_harbor_preset_places_its_structures() {
+    let mut basin = WaveBasinApp::new();
+    basin.load_harbor_preset();
+    assert!(basin.show_agitation && basin.spectrum.is_some());
+    assert_eq!((basin.harbor.structures.len(), basin.harbor.entrances.len(), basin.harbor.berths.len()), (4, 1, 3));
+    let solver = basin.build_solver().unwrap();
+    assert_eq!(solver.structures().len(), 4);
+    let grid = *solver.grid();
+    // The pier and the quay are solid, the entrance and the berths along them open
+    let solid = |x: f64, y: f64| {
+        let (i, j) = grid.cell_at(x, y);
+        solver.is_solid(i, j)
+    };
+    assert!(solid(37.0, 7.0) && solid(47.0, 20.0) && solid(26.5, 10.0));
+    assert!(!solid(26.5, 27.0) && !solid(35.5, 7.0) && !solid(44.5, 30.0));
+    // No agitation before the run
+    assert!(basin.berth_agitation().is_empty());
+}
+
+#[test]
+fn test_invalid_harbor_layout_does_not_start() {
+    let mut basin = small_basin();
+    basin.harbor.structures.push(HarborStructure::new("Quay", vec![[10.0, 0.0], [12.0, 0.0]], 0.9));
+    assert!(basin.start_simulation().is_err());
+    basin.harbor.structures[0].vertices.extend([[12.0, 6.0], [10.0, 6.0]]);
+    basin.harbor.berths.push(Berth { name: "Berth".to_string(), start: [9.5, 1.0], end: [9.5, 5.0], limit: -0.01 });
+    assert!(basin.start_simulation().is_err());
+    basin.harbor.berths[0].limit = 0.01;
+    assert!(basin.start_simulation().is_ok());
+    assert!(basin.solver().is_some_and(|solver| solver.structures().len() == 1));
+}
+
+#[test]
+fn test_harbor_agitation_at_the_berths() {
+    let mut basin = WaveBasinApp::new();
+    basin.load_harbor_preset();
+    // A coarser grid and fewer waves keep the run short
+    basin.grid_spacing = 0.5;
+    basin.number_of_waves = 20;
+    basin.start_simulation().unwrap();
+    let (_, end) = basin.height_window();
+    while basin.simulation_time() < end {
+        basin.advance_simulation(0.1);
+    }
+    let agitation = basin.berth_agitation();
+    assert_eq!(agitation.len(), 3);
+    // Sheltered behind the south breakwater at the pier, facing the entrance along the north quay
+    let (pier, south, north) = (&agitation[0], &agitation[1], &agitation[2]);
+    assert!(pier.coefficient() < south.coefficient() && south.coefficient() < north.coefficient());
+    assert!(agitation.iter().all(|berth| berth.coefficient() > 0.0 && berth.coefficient() < 1.0 && !berth.heights.is_empty()));
+    assert!(pier.is_acceptable() && !north.is_acceptable() && north.exceedance() > 0.0);
+}
+
+#[test]
+fn test_bathymetry_import() {
+    // Beach rising 0.04 m per meter to the east, above the still water level past x = 25 m, a gap in the data
+    let mut content = "ncols 60\nnrows 20\nxllcorner 1000\nyllcorner 2000\ncellsize 0.5\nnodata_value -9999\n".to_string();
+    for row in 0..20 {
+        let line: Vec<String> = (0..60).map(|i| if row == 0 && i == 0 { "-9999".to_string() } else { format!("{:.3}", -1.0 + 0.04 * 0.5 * (i as f64 + 0.5)) }).collect();
+        content.push_str(&line.join(" "));
+        content.push('\n');
+    }
+    let path = std::env::temp_dir().join(format!("wave_basin_bathymetry_{}.asc", std::process::id()));
+    std::fs::write(&path, content).unwrap();
+    std::fs::write(path.with_extension("prj"), r#"PROJCS["NAD83 / Massachusetts Mainland",AUTHORITY["EPSG","26986"]]"#).unwrap();
+
+    let mut basin = WaveBasinApp::new();
+    basin.obstacles.clear();
+    basin.harbor = HarborLayout::default();
+    basin.bathymetry_path = path.to_string_lossy().to_string();
+    basin.import_bathymetry().unwrap();
+    assert_eq!(basin.bathymetry_options.origin, [1000.0, 2000.0]);
+    assert_eq!((basin.basin_length, basin.basin_width, basin.grid_spacing), (30.0, 10.0, 0.5));
+    assert!((basin.water_depth - 0.99).abs() < 1e-3, "Depth at the wavemaker {}", basin.water_depth);
+    // The basin takes the coordinates and the CRS of the raster
+    let reference = basin.georeference.clone().unwrap();
+    assert_eq!((reference.name(), reference.epsg(), reference.origin), ("NAD83 / Massachusetts Mainland", Some(26986), [1000.0, 2000.0]));
+
+    let solver = basin.build_solver().unwrap();
+    let grid = *solver.grid();
+    let depth_at = |x: f64, y: f64| solver.depth()[grid.index(grid.cell_at(x, y).0, grid.cell_at(x, y).1)];
+    assert!((depth_at(10.0, 5.0) - 0.6).abs() < 0.02);
+    let solid = |x: f64, y: f64| solver.is_solid(grid.cell_at(x, y).0, grid.cell_at(x, y).1);
+    assert!(solid(27.0, 5.0) && !solid(20.0, 5.0) && !solid(0.25, 9.75));
+
+    // Moving the basin onto land is refused and the previous bathymetry kept
+    basin.bathymetry_options.water_level = -2.0;
+    assert!(basin.build_solver().is_err());
+    assert!(basin.import_bathymetry().is_err());
+    assert_eq!(basin.basin_length, 30.0);
+    assert!(basin.bathymetry.is_some());
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(path.with_extension("prj")).unwrap();
+
+    basin.bathymetry_path = "missing.asc".to_string();
+    assert!(basin.import_bathymetry().is_err());
+    basin.load_diffraction_preset();
+    assert!(basin.bathymetry.is_none() && basin.georeference.is_none());
+}
+
+#[test]
+fn test_georeferenced_field_export() {
+    let mut basin = small_basin();
+    assert!(basin.fields_csv().is_err());
+    basin.start_simulation().unwrap();
+    basin.advance_simulation(2.0);
+    let local = basin.fields_csv().unwrap();
+    assert!(local.lines().any(|line| line == "x (m),y (m),eta (m),u (m/s),v (m/s)"));
+
+    // Coordinates in feet from the south-west corner of the basin
+    basin.georeference = Some(GeoReference::new("EPSG:2249", SurveyUnit::Feet, [775000.0, 2950000.0]).unwrap());
+    basin.export_path = std::env::temp_dir().join(format!("wave_basin_fields_{}.csv", std::process::id())).to_string_lossy().to_string();
+    let cells = basin.export_fields().unwrap();
+    let csv = std::fs::read_to_string(&basin.export_path).unwrap();
+    std::fs::remove_file(&basin.export_path).unwrap();
+    let solver = basin.solver().unwrap();
+    let wet = (0..48).flat_map(|i| (0..24).map(move |j| (i, j))).filter(|&(i, j)| !solver.is_solid(i, j)).count();
+    assert_eq!(cells, wet);
+    assert!(csv.contains("# CRS: EPSG:2249\n") && csv.contains("# EPSG: 2249\n"));
+    let mut rows = csv.lines().filter(|line| !line.starts_with('#'));
+    assert_eq!(rows.next(), Some("x (m),y (m),Easting,Northing,eta (m),u (m/s),v (m/s)"));
+    let first: Vec<f64> = rows.next().unwrap().split(',').map(|value| value.parse().unwrap()).collect();
+    assert_eq!(&first[..2], &[0.125, 0.125]);
+    assert!((first[2] - (775000.0 + 0.125 / 0.3048)).abs() < 1e-3 && (first[3] - (2950000.0 + 0.125 / 0.3048)).abs() < 1e-3);
+    assert!((first[4] - solver.eta()[0]).abs() < 1e-6);
+}
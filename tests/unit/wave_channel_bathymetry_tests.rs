@@ -0,0 +1,113 @@
+use coastal_engineering_platform::gui::{PropagationModel, WaveChannelApp};
+use coastal_engineering_platform::solver::{CrossSection, ProfileSegment, RightBoundary};
+
+fn profiled_app(cross_section: Option<CrossSection>) -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Numerical;
+    wave_app.channel_length = 100.0;
+    wave_app.grid_resolution = 400;
+    wave_app.still_water_level = 0.5;
+    wave_app.wave_height = 0.05;
+    wave_app.wave_period = 4.0;
+    wave_app.right_boundary = RightBoundary::Wall;
+    wave_app.cross_section = cross_section;
+    wave_app
+}
+
+#[test]
+fn test_default_flat_bottom() {
+    let wave_app = WaveChannelApp::new();
+    assert!(wave_app.cross_section.is_none());
+    assert_eq!(wave_app.bed_elevation(10.0), 0.0);
+}
+
+#[test]
+fn test_solver_depth_follows_profile() {
+    let section = CrossSection::new(vec![
+        ProfileSegment::flat("Offshore", 60.0),
+        ProfileSegment::slope("Slope", 20.0, 20.0),
+        ProfileSegment::flat("Berm", 20.0),
+    ])
+    .unwrap();
+    let mut wave_app = profiled_app(Some(section));
+    wave_app.start_simulation();
+    wave_app.advance_simulation(0.05);
+
+    let depth = wave_app.solver.as_ref().unwrap().depth();
+    let dx = 100.0 / depth.len() as f64;
+    let depth_at = |x: f64| depth[(x / dx) as usize];
+    assert!((depth_at(30.0) - 0.5).abs() < 1e-9);
+    assert!((depth_at(70.0) - 0.0).abs() < 0.01);
+    assert!((depth_at(90.0) + 0.5).abs() < 1e-9);
+    assert!((wave_app.bed_elevation(90.0) - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_dry_berm_stays_dry() {
+    let mut wave_app = profiled_app(Some(CrossSection::dike(100.0, 0.5)));
+    wave_app.start_simulation();
+    for _ in 0..200 {
+        wave_app.advance_simulation(0.05);
+    }
+
+    // No water reaches the crest of the dike with small waves
+    let solver = wave_app.solver.as_ref().unwrap();
+    let crest = solver.depth().len() - 1;
+    assert!(solver.eta()[crest] + solver.depth()[crest] < 1e-6);
+}
+
+#[test]
+fn test_changing_profile_restarts_solver() {
+    let mut wave_app = profiled_app(Some(CrossSection::beach(100.0, 0.5)));
+    wave_app.start_simulation();
+    wave_app.advance_simulation(0.5);
+
+    if let Some(section) = &mut wave_app.cross_section {
+        section.segments[1].slope = 10.0;
+    }
+    wave_app.advance_simulation(0.05);
+    assert!((wave_app.simulation_time - 0.05).abs() < 1e-9);
+}
+
+#[test]
+fn test_profile_saved_with_project() {
+    let wave_app = profiled_app(Some(CrossSection::dike(100.0, 0.5)));
+    let project = wave_app.project();
+    let json = serde_json::to_string(&project).unwrap();
+
+    let mut restored = WaveChannelApp::new();
+    restored.load_project(&serde_json::from_str(&json).unwrap());
+    assert_eq!(restored.cross_section, wave_app.cross_section);
+    assert_eq!(restored.cross_section.unwrap().segments[2].label, "Berm");
+}
+
+#[test]
+fn test_import_survey() {
+    let path = std::env::temp_dir().join(format!("bathymetry_tests_{}.csv", std::process::id()));
+    std::fs::write(&path, "distance,elevation\n0,-1.2\n30,-1.2\n60,0.3\n80,0.8\n").unwrap();
+    let mut wave_app = profiled_app(None);
+    wave_app.survey_path = path.display().to_string();
+    wave_app.reference_levels.still_water = 0.3;
+    wave_app.survey_options.spacing = 0.5;
+    let message = wave_app.import_survey().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(message.contains("4 points"), "{}", message);
+    assert_eq!(wave_app.channel_length, 80.0);
+    assert!((wave_app.still_water_level - 1.5).abs() < 1e-12);
+    assert!((wave_app.bed_elevation(60.0) - 1.5).abs() < 1e-9);
+
+    // The shoreline of the survey is dry in the solver
+    wave_app.start_simulation();
+    wave_app.advance_simulation(0.05);
+    let solver = wave_app.solver.as_ref().unwrap();
+    assert!(solver.depth()[solver.depth().len() - 1] < 0.0);
+}
+
+#[test]
+fn test_import_missing_survey_keeps_profile() {
+    let mut wave_app = profiled_app(Some(CrossSection::beach(100.0, 0.5)));
+    wave_app.survey_path = "missing_survey.csv".to_string();
+    assert!(wave_app.import_survey().is_err());
+    assert_eq!(wave_app.cross_section, Some(CrossSection::beach(100.0, 0.5)));
+}
@@ -0,0 +1,73 @@
+use coastal_engineering_platform::analysis::{FrontKind, jump_froude};
+use coastal_engineering_platform::gui::WaveChannelApp;
+use coastal_engineering_platform::solver::{DamBreakSolution, InitialCondition};
+use super::run;
+
+fn dam_break_app() -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.channel_length = 100.0;
+    wave_app.grid_resolution = 400;
+    wave_app.still_water_level = 1.0;
+    wave_app.initial_condition = Some(InitialCondition::dam_break(100.0, 1.0));
+    wave_app.detect_bores = true;
+    wave_app
+}
+
+#[test]
+fn test_dam_break_bore_is_tracked() {
+    let mut wave_app = dam_break_app();
+    assert!(wave_app.detected_bores().is_empty());
+    run(&mut wave_app, 8.0);
+
+    // The Stoker bore runs into the still water; the rarefaction fan is only steep enough at the start
+    let solution = DamBreakSolution::new(50.0, 2.0, 1.0).unwrap();
+    let bores = wave_app.detected_bores();
+    let bore = bores.iter().max_by(|a, b| a.duration().total_cmp(&b.duration())).unwrap();
+    assert!(bore.duration() > 7.5);
+    assert!(bores.iter().filter(|track| *track != bore).all(|track| track.duration() < 2.0 && track.last().position < 50.0));
+    assert_eq!(bore.kind(), FrontKind::Bore);
+    assert!(bore.last().facing_shoreward);
+    let celerity = bore.celerity().unwrap();
+    assert!((celerity - solution.bore_speed()).abs() < 0.05 * solution.bore_speed(), "{} m/s", celerity);
+    let froude = jump_froude(solution.middle_depth());
+    assert!((bore.last().froude - froude).abs() < 0.1 * froude, "Fr = {}", bore.last().froude);
+    assert!((bore.last().position - 50.0 - 8.0 * solution.bore_speed()).abs() < 1.0);
+}
+
+#[test]
+fn test_bore_criteria() {
+    let mut wave_app = dam_break_app();
+    run(&mut wave_app, 4.0);
+    wave_app.bore_criteria.min_froude = 2.0;
+    assert!(wave_app.detected_bores().is_empty());
+
+    let mut restored = WaveChannelApp::new();
+    restored.load_project(&wave_app.project());
+    assert_eq!(restored.bore_criteria.min_froude, 2.0);
+
+    // Without bores, as for linear waves, nothing is detected
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.detect_bores = true;
+    run(&mut wave_app, 4.0);
+    assert!(wave_app.detected_bores().is_empty());
+}
+
+#[test]
+fn test_foam_on_the_bore_front() {
+    let mut wave_app = dam_break_app();
+    run(&mut wave_app, 8.0);
+    let bores = wave_app.detected_bores();
+    let front = bores.iter().max_by(|a, b| a.duration().total_cmp(&b.duration())).unwrap().last().position;
+
+    // Foam covers the steep front of the bore and nothing else
+    let dx = wave_app.grid_spacing();
+    let breaking: Vec<f64> = wave_app.breaking_cells().iter().enumerate().filter(|(_, breaking)| **breaking).map(|(i, _)| i as f64 * dx).collect();
+    assert!(!breaking.is_empty());
+    assert!(breaking.iter().all(|x| (x - front).abs() < 2.0), "{:?} around {}", breaking, front);
+
+    let mut wave_app = WaveChannelApp::new();
+    run(&mut wave_app, 4.0);
+    // The linear superposition never breaks, even at the abrupt front of the wave train
+    assert!(wave_app.breaking_cells().iter().all(|breaking| !breaking));
+    assert!(wave_app.fill_water && wave_app.show_foam);
+}
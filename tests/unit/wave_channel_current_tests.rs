@@ -0,0 +1,85 @@
+use coastal_engineering_platform::gui::{EquationRenderer, PropagationModel, WaveChannelApp};
+use coastal_engineering_platform::solver::RightBoundary;
+use coastal_engineering_platform::waves::{AmbientCurrent, CurrentProfile};
+use egui_kittest::{Harness, kittest::Queryable};
+use super::run;
+
+fn current_app(current: Option<AmbientCurrent>) -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Numerical;
+    wave_app.right_boundary = RightBoundary::Radiation;
+    wave_app.wave_height = 0.05;
+    wave_app.wave_period = 2.0;
+    wave_app.number_of_waves = 50;
+    wave_app.grid_resolution = 400;
+    wave_app.ambient_current = current;
+    wave_app
+}
+
+#[test]
+fn test_current_settings_restart_solver() {
+    let mut wave_app = current_app(None);
+    let key = wave_app.configuration_key();
+    wave_app.ambient_current = Some(AmbientCurrent::uniform(-0.3));
+    assert_ne!(wave_app.configuration_key(), key);
+    assert!(wave_app.solver_description().contains("uniform current -0.30 m/s"));
+
+    let solver = wave_app.build_solver().unwrap();
+    assert_eq!(solver.current(), Some(AmbientCurrent::uniform(-0.3)));
+
+    let project = wave_app.project();
+    let mut reloaded = WaveChannelApp::new();
+    reloaded.load_project(&project);
+    assert_eq!(reloaded.ambient_current, Some(AmbientCurrent::uniform(-0.3)));
+
+    // The current has to leave the channel
+    wave_app.right_boundary = RightBoundary::Wall;
+    assert!(wave_app.build_solver().is_err());
+}
+
+#[test]
+fn test_opposing_current_shortens_numerical_waves() {
+    let mut crossings = Vec::new();
+    for current in [None, Some(AmbientCurrent::uniform(-0.3).with_profile(CurrentProfile::PowerLaw { exponent: 1.0 / 7.0 }))] {
+        let mut wave_app = current_app(current);
+        run(&mut wave_app, 30.0);
+        let solver = wave_app.solver.as_ref().unwrap();
+        assert!(solver.eta().iter().all(|eta| eta.is_finite()));
+        // Up-crossings of the still water level along the channel
+        crossings.push(wave_app.surface_elevation.windows(2).filter(|pair| pair[0] < 0.0 && pair[1] >= 0.0).count());
+    }
+    assert!(crossings[1] > crossings[0], "Crossings {:?}", crossings);
+}
+
+#[test]
+fn test_current_controls_shown() {
+    let mut wave_app = current_app(Some(AmbientCurrent::uniform(-0.2)));
+    let mut harness = Harness::new_ui(move |ui| {
+        let mut equation_renderer = EquationRenderer::new();
+        let ctx = ui.ctx().clone();
+        wave_app.show(ui, &ctx, &mut equation_renderer);
+    });
+    harness.run();
+
+    let _checkbox = harness.get_by_label("Ambient Current");
+    let _velocity = harness.get_by_label("Current Velocity (Ū):");
+}
+
+#[test]
+fn test_computed_values_show_current_wavelength() {
+    for (velocity, period, expected) in [(-0.3, 2.0, "Wavelength on Current (L_U):"), (-3.0, 1.0, "Wavelength on Current: no solution")] {
+        let mut wave_app = current_app(Some(AmbientCurrent::uniform(velocity)));
+        wave_app.wave_period = period;
+        let mut harness = Harness::new_ui(move |ui| {
+            let mut equation_renderer = EquationRenderer::new();
+            let ctx = ui.ctx().clone();
+            wave_app.show(ui, &ctx, &mut equation_renderer);
+        });
+        harness.run();
+
+        let _wavelength = harness.get_by_label_contains(expected);
+        if velocity > -1.0 {
+            let _period = harness.get_by_label_contains("Relative Period (T_r):");
+        }
+    }
+}
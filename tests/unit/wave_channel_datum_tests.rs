@@ -0,0 +1,71 @@
+use coastal_engineering_platform::gui::WaveChannelApp;
+use coastal_engineering_platform::solver::{CrossSection, ProfileSegment, ReferenceLevels, VerticalDatum};
+
+fn surveyed_app() -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.still_water_level = 3.0;
+    wave_app.reference_levels = ReferenceLevels {
+        chart_datum: -1.5,
+        user_datum: 0.0,
+        still_water: 0.5,
+    };
+    wave_app.datum = VerticalDatum::ChartDatum;
+    wave_app
+}
+
+#[test]
+fn test_default_datum_is_channel_bed() {
+    let wave_app = WaveChannelApp::new();
+    assert_eq!(wave_app.datum, VerticalDatum::ChannelBed);
+    assert_eq!(wave_app.level_in_datum(1.0), 1.0);
+}
+
+#[test]
+fn test_levels_in_chart_datum() {
+    let wave_app = surveyed_app();
+    // Bed at the wavemaker at -2.5 m MSL, so -1.0 m CD
+    assert!((wave_app.level_in_datum(0.0) + 1.0).abs() < 1e-12);
+    assert!((wave_app.level_in_datum(wave_app.still_water_level) - 2.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_raising_water_level_keeps_bed() {
+    let mut wave_app = surveyed_app();
+    wave_app.set_water_level_in_datum(2.5);
+    assert!((wave_app.still_water_level - 3.5).abs() < 1e-12);
+    assert!((wave_app.reference_levels.still_water - 1.0).abs() < 1e-12);
+    assert!((wave_app.level_in_datum(0.0) + 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_lowering_bed_keeps_water_level() {
+    let mut wave_app = surveyed_app();
+    wave_app.set_bed_level_in_datum(-2.0);
+    assert!((wave_app.still_water_level - 4.0).abs() < 1e-12);
+    assert!((wave_app.reference_levels.water_level(VerticalDatum::ChartDatum, wave_app.still_water_level) - 2.0).abs() < 1e-12);
+
+    // The bed is the reference of the channel bed datum
+    wave_app.datum = VerticalDatum::ChannelBed;
+    wave_app.set_bed_level_in_datum(1.0);
+    assert!((wave_app.still_water_level - 4.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_crest_level_in_datum() {
+    let mut wave_app = surveyed_app();
+    wave_app.cross_section = Some(
+        CrossSection::new(vec![ProfileSegment::flat("Offshore", 20.0), ProfileSegment::slope("Dike", 12.0, 2.0)]).unwrap(),
+    );
+    // Crest 6 m above the bed at the wavemaker
+    assert!((wave_app.level_in_datum(wave_app.bed_elevation(32.0)) - 5.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_datum_saved_with_project() {
+    let wave_app = surveyed_app();
+    let json = serde_json::to_string(&wave_app.project()).unwrap();
+    let mut restored = WaveChannelApp::new();
+    restored.load_project(&serde_json::from_str(&json).unwrap());
+    assert_eq!(restored.datum, VerticalDatum::ChartDatum);
+    assert_eq!(restored.reference_levels, wave_app.reference_levels);
+}
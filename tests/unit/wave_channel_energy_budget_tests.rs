@@ -0,0 +1,97 @@
+use coastal_engineering_platform::gui::{PropagationModel, WaveChannelApp};
+use coastal_engineering_platform::solver::{CrossSection, DissipationMechanism, ProfileSegment, RightBoundary, VegetationField};
+
+fn budget_app(vegetation: Option<VegetationField>) -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Numerical;
+    wave_app.channel_length = 100.0;
+    wave_app.grid_resolution = 400;
+    wave_app.still_water_level = 0.5;
+    wave_app.wave_height = 0.05;
+    wave_app.wave_period = 4.0;
+    wave_app.statistics_window = 40.0;
+    wave_app.right_boundary = RightBoundary::Sponge {
+        width: 20.0,
+        strength: 5.0,
+    };
+    wave_app.vegetation = vegetation;
+    wave_app
+}
+
+#[test]
+fn test_structure_extent() {
+    let mut wave_app = budget_app(None);
+    assert!(wave_app.structure_extent().is_none());
+    assert!(wave_app.energy_budget().is_err());
+
+    // Submerged bar: from the toe of the seaward slope to the toe of the landward slope
+    let segments = vec![
+        ProfileSegment::flat("Offshore", 40.0),
+        ProfileSegment::slope("Front", 2.0, 0.1),
+        ProfileSegment::flat("Crest", 3.0),
+        ProfileSegment::slope("Back", 2.0, -0.1),
+        ProfileSegment::flat("Lee", 53.0),
+    ];
+    wave_app.cross_section = Some(CrossSection::new(segments).unwrap());
+    assert_eq!(wave_app.structure_extent(), Some((40.0, 47.0)));
+
+    // The vegetation patch widens the structure
+    wave_app.vegetation = VegetationField::new(45.0, 55.0).ok();
+    assert_eq!(wave_app.structure_extent(), Some((40.0, 55.0)));
+}
+
+#[test]
+fn test_gauge_arrays_around_structure() {
+    let mut wave_app = budget_app(VegetationField::new(40.0, 50.0).ok());
+    wave_app.add_budget_gauge_arrays().unwrap();
+    assert_eq!(wave_app.gauges.len(), 6);
+    let (seaward, landward) = wave_app.budget_gauges().unwrap();
+    assert_eq!((seaward, landward), (vec![0, 1, 2], vec![3, 4, 5]));
+
+    // Too close to the wavemaker for a seaward array
+    let mut wave_app = budget_app(VegetationField::new(2.0, 10.0).ok());
+    assert!(wave_app.add_budget_gauge_arrays().is_err());
+}
+
+#[test]
+fn test_vegetation_energy_budget() {
+    let field = VegetationField::new(40.0, 50.0).unwrap().with_stems(1000.0, 0.01, 1.0);
+    let mut wave_app = budget_app(Some(field));
+    wave_app.add_budget_gauge_arrays().unwrap();
+    wave_app.start_simulation();
+    while wave_app.simulation_time < 100.0 {
+        wave_app.advance_simulation(0.05);
+    }
+    wave_app.simulation_running = false;
+
+    let budget = wave_app.energy_budget().unwrap();
+    assert!(budget.reflection() < 0.15, "Kr² = {:.3}", budget.reflection());
+    assert!(budget.transmission() < 0.5, "Kt² = {:.3}", budget.transmission());
+    assert!(budget.dissipation() > 0.4, "Dissipated {:.3}", budget.dissipation());
+    assert!((budget.reflection() + budget.transmission() + budget.dissipation() - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_dissipation_breakdown() {
+    let field = VegetationField::new(40.0, 50.0).unwrap().with_stems(1000.0, 0.01, 1.0);
+    let mut wave_app = budget_app(Some(field));
+    wave_app.start_simulation();
+    while wave_app.simulation_time < 40.0 {
+        wave_app.advance_simulation(0.05);
+    }
+
+    // The waves reach the patch, then the sponge behind it
+    let record = &wave_app.dissipation;
+    assert!(!record.is_empty());
+    assert!(record.times().windows(2).all(|pair| pair[1] > pair[0]));
+    let last = record.last().unwrap();
+    assert!(last.vegetation > 0.0 && last.sponge > 0.0, "{:?}", last);
+    assert!(last.vegetation > last.sponge, "{:?}", last);
+    let vegetation = record.series(DissipationMechanism::Vegetation);
+    assert!(vegetation.windows(2).all(|pair| pair[1] >= pair[0]));
+    let shares = record.shares().unwrap();
+    assert!((shares.iter().map(|(_, share)| share).sum::<f64>() - 1.0).abs() < 1e-9);
+
+    wave_app.reset_simulation();
+    assert!(wave_app.dissipation.is_empty());
+}
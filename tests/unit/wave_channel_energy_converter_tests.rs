@@ -0,0 +1,57 @@
+use coastal_engineering_platform::gui::{Project, WaveChannelApp};
+use coastal_engineering_platform::solver::InitialCondition;
+use coastal_engineering_platform::waves::{ConverterKind, SeaState, WaveEnergyConverter};
+
+#[test]
+fn test_energy_converter_in_wavemaker_waves() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.still_water_level = 1.0;
+    wave_app.wave_height = 0.1;
+    assert!(wave_app.converter_response().is_err());
+
+    let converter = WaveEnergyConverter::new(ConverterKind::PointAbsorber, 50.0, 0.1, 0.5).unwrap();
+    wave_app.wave_period = converter.natural_period();
+    wave_app.energy_converter = Some(converter);
+    let undamped = wave_app.converter_response().unwrap();
+    assert_eq!(undamped.absorbed_power, 0.0);
+
+    // Tuned to the waves, a slender float absorbs the energy flux of a crest width 1/k, wider than itself
+    wave_app.energy_converter = Some(converter.with_pto_damping(undamped.optimal_damping));
+    let tuned = wave_app.converter_response().unwrap();
+    assert!(tuned.absorbed_power > 0.0);
+    assert!(tuned.capture_width() > 2.0 * converter.radius);
+
+    // The device is assessed in the still water depth at its position
+    wave_app.energy_converter = Some(WaveEnergyConverter { draft: 1.5, ..converter });
+    assert!(wave_app.converter_response().is_err());
+    wave_app.energy_converter = Some(converter);
+    wave_app.initial_condition = Some(InitialCondition::dam_break(wave_app.channel_length, 1.0));
+    assert!(wave_app.converter_response().is_err());
+}
+
+#[test]
+fn test_annual_energy_of_the_wave_climate() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.still_water_level = 1.0;
+    let converter = WaveEnergyConverter::new(ConverterKind::OscillatingWaterColumn, 50.0, 0.2, 0.2).unwrap().with_pto_damping(100.0);
+    wave_app.energy_converter = Some(converter);
+    assert!(wave_app.converter_annual_energy().is_err());
+
+    // Each sea state counts as regular waves of the same energy, H = Hs/√2, for its hours
+    wave_app.wave_climate = vec![SeaState::new(0.2, 2.0, 1000.0).unwrap(), SeaState::new(0.1, 3.0, 3000.0).unwrap()];
+    let energy = wave_app.converter_annual_energy().unwrap();
+    let expected: f64 = wave_app
+        .wave_climate
+        .iter()
+        .map(|state| converter.response(state.significant_height / 2.0_f64.sqrt(), state.energy_period, 1.0).unwrap().absorbed_power * state.hours * 3600.0)
+        .sum();
+    assert!((energy - expected).abs() < 1e-9 * expected);
+
+    // The device and the climate are saved with the project
+    let project = Project::from_json(&wave_app.project().to_json().unwrap()).unwrap();
+    let mut reopened = WaveChannelApp::new();
+    reopened.load_project(&project);
+    assert_eq!(reopened.energy_converter, Some(converter));
+    assert_eq!(reopened.wave_climate, wave_app.wave_climate);
+    assert!(SeaState::new(0.2, 2.0, -1.0).is_err());
+}
@@ -0,0 +1,48 @@
+use coastal_engineering_platform::analysis::WaveGauge;
+use coastal_engineering_platform::gui::{Project, PropagationModel, WaveChannelApp};
+use coastal_engineering_platform::solver::CrossSection;
+
+fn value(wave_app: &WaveChannelApp, name: &str) -> Option<f64> {
+    wave_app.formula_variables().iter().find(|(variable, _)| *variable == name).map(|(_, value)| *value)
+}
+
+#[test]
+fn test_formulas_use_the_computed_values() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Analytical;
+    wave_app.cross_section = Some(CrossSection::beach(wave_app.channel_length, wave_app.still_water_level));
+    assert_eq!(value(&wave_app, "slope"), Some(1.0 / 20.0));
+    let deep_water_wavelength = 9.81 * wave_app.wave_period.powi(2) / (2.0 * std::f64::consts::PI);
+    assert!((value(&wave_app, "L0").unwrap() - deep_water_wavelength).abs() < 1e-9);
+
+    wave_app.add_custom_formula("Iribarren", "slope / sqrt(H / L0)", "").unwrap();
+    wave_app.add_custom_formula("Relative height", "Hs / H", "").unwrap();
+    assert!(wave_app.add_custom_formula("Iribarren", "H / L", "").is_err());
+    assert!(wave_app.add_custom_formula("Broken", "sqrt(H", "").is_err());
+    assert_eq!(wave_app.custom_formulas.len(), 2);
+
+    let variables = wave_app.formula_variables();
+    let iribarren = wave_app.custom_formulas[0].evaluate(&variables).unwrap();
+    assert!((iribarren - 0.05 / (wave_app.wave_height / deep_water_wavelength).sqrt()).abs() < 1e-12);
+    // Measured quantities are available once the gauges have recorded
+    assert!(wave_app.custom_formulas[1].evaluate(&variables).is_err());
+    wave_app.gauges.push(WaveGauge::new(2.0));
+    wave_app.start_simulation();
+    for _ in 0..400 {
+        wave_app.advance_simulation(0.05);
+    }
+    assert!(value(&wave_app, "Tp").is_some());
+    assert!(wave_app.custom_formulas[1].evaluate(&wave_app.formula_variables()).unwrap() > 0.0);
+}
+
+#[test]
+fn test_formulas_are_saved_with_the_project() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.add_custom_formula("Steepness", "H / L", "").unwrap();
+    let project = Project::from_json(&wave_app.project().to_json().unwrap()).unwrap();
+    assert_eq!(project.custom_formulas, wave_app.custom_formulas);
+
+    let mut other = WaveChannelApp::new();
+    other.apply_project(&project);
+    assert_eq!(other.custom_formulas[0].expression.source(), "H / L");
+}
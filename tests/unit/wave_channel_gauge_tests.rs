@@ -0,0 +1,129 @@
+use coastal_engineering_platform::analysis::WaveGauge;
+use coastal_engineering_platform::gui::{PropagationModel, SourceSettings, WaveChannelApp};
+use super::run;
+
+#[test]
+fn test_default_without_gauges() {
+    let wave_app = WaveChannelApp::new();
+    assert!(wave_app.gauges.is_empty());
+    assert!(wave_app.infragravity_analysis().is_err());
+}
+
+#[test]
+fn test_gauges_record_surface_elevation() {
+    for model in [PropagationModel::Analytical, PropagationModel::Numerical] {
+        let mut wave_app = WaveChannelApp::new();
+        wave_app.propagation_model = model;
+        wave_app.gauges.push(WaveGauge::new(5.0));
+        run(&mut wave_app, 10.0);
+
+        let gauge = &wave_app.gauges[0];
+        assert_eq!(gauge.len(), 200);
+        assert!((gauge.sample_interval().unwrap() - 0.05).abs() < 1e-9);
+        assert!(gauge.elevation().iter().any(|eta| eta.abs() > 0.1), "{} model", model);
+    }
+}
+
+#[test]
+fn test_reset_clears_gauge_records() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.gauges.push(WaveGauge::new(5.0));
+    run(&mut wave_app, 1.0);
+    assert!(!wave_app.gauges[0].is_empty());
+
+    wave_app.reset_simulation();
+    assert!(wave_app.gauges[0].is_empty());
+    assert_eq!(wave_app.gauges[0].position(), 5.0);
+}
+
+#[test]
+fn test_linear_wave_groups_have_no_infragravity_energy() {
+    // Two close periods beat into wave groups; linear superposition carries no long wave
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.wave_height = 0.1;
+    wave_app.wave_period = 4.0;
+    wave_app.additional_sources.push(SourceSettings {
+        position: 0.0,
+        wave_height: 0.1,
+        wave_period: 4.4,
+        phase: 0.0,
+    });
+    wave_app.gauges.push(WaveGauge::new(25.0));
+    run(&mut wave_app, 100.0);
+
+    let analysis = wave_app.infragravity_analysis().unwrap();
+    assert_eq!(analysis.split_frequency(), 0.125);
+    assert!(analysis.hm0_sea_swell() > 0.1);
+    assert!(analysis.hm0_infragravity() < 0.1 * analysis.hm0_sea_swell());
+}
+
+#[test]
+fn test_scalogram_peaks_at_wave_frequency() {
+    let mut wave_app = WaveChannelApp::new();
+    assert!(wave_app.scalogram().is_err());
+
+    wave_app.wave_height = 0.1;
+    wave_app.wave_period = 4.0;
+    wave_app.gauges.push(WaveGauge::new(10.0));
+    run(&mut wave_app, 60.0);
+
+    let scalogram = wave_app.scalogram().unwrap();
+    let frequencies = scalogram.frequencies();
+    assert!(frequencies[0] <= 1.0 + 1e-9);
+    let dominant = scalogram.dominant_frequency(wave_app.gauges[0].len() - 200);
+    assert!((dominant - 0.25).abs() < 0.03, "Dominant {:.3} Hz", dominant);
+}
+
+#[test]
+fn test_bispectrum_resolves_wave_harmonics() {
+    let mut wave_app = WaveChannelApp::new();
+    assert!(wave_app.bispectrum().is_err());
+
+    wave_app.wave_height = 0.1;
+    wave_app.wave_period = 4.0;
+    wave_app.gauges.push(WaveGauge::new(10.0));
+    run(&mut wave_app, 100.0);
+
+    // 2000 samples: segments are shortened to keep at least seven of them
+    let bispectrum = wave_app.bispectrum().unwrap();
+    assert!(bispectrum.segments() >= 7);
+    let frequencies = bispectrum.frequencies();
+    assert!(frequencies[frequencies.len() - 1] <= 0.75 + 1e-9);
+    assert!(bispectrum.resolution() < 0.25);
+    assert!(bispectrum.bicoherence().iter().flatten().all(|value| (0.0..=1.0 + 1e-9).contains(value)));
+}
+
+#[test]
+fn test_cross_spectrum_measures_linear_celerity() {
+    let mut wave_app = WaveChannelApp::new();
+    assert!(wave_app.cross_spectrum().is_err());
+
+    // 3.2 s waves fall on a bin of the 12.8 s segments
+    wave_app.wave_height = 0.1;
+    wave_app.wave_period = 3.2;
+    wave_app.gauges.push(WaveGauge::new(12.0));
+    wave_app.gauges.push(WaveGauge::new(10.0));
+    run(&mut wave_app, 80.0);
+
+    let spectrum = wave_app.cross_spectrum().unwrap();
+    assert_eq!(spectrum.separation(), 2.0);
+    let (frequency, celerity) = spectrum
+        .coherent_celerity(spectrum.significance_level())
+        .into_iter()
+        .min_by(|a, b| (a.0 - 0.3125).abs().total_cmp(&(b.0 - 0.3125).abs()))
+        .unwrap();
+    assert!((frequency - 0.3125).abs() < 1e-9);
+
+    // Linear theory: ω² = gk tanh(kd)
+    let omega = 2.0 * std::f64::consts::PI * frequency;
+    let depth = wave_app.still_water_level;
+    let mut k = omega * omega / 9.81;
+    for _ in 0..100 {
+        k = omega * omega / (9.81 * (k * depth).tanh());
+    }
+    let expected = omega / k;
+    assert!((celerity - expected).abs() < 0.03 * expected, "{:.3} m/s vs {:.3} m/s", celerity, expected);
+
+    wave_app.cross_spectrum_gauges = [1, 1];
+    assert!(wave_app.cross_spectrum().is_err());
+}
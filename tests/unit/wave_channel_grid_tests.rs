@@ -0,0 +1,74 @@
+use coastal_engineering_platform::gui::{PropagationModel, WaveChannelApp};
+use coastal_engineering_platform::solver::{CrossSection, RefinementReason, RefinementZone};
+
+fn refined_app() -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Numerical;
+    wave_app.channel_length = 100.0;
+    wave_app.grid_resolution = 300;
+    wave_app.still_water_level = 0.5;
+    wave_app.wave_height = 0.05;
+    wave_app.grid_refinement = vec![RefinementZone::new(70.0, 90.0, 3.0).unwrap()];
+    wave_app
+}
+
+#[test]
+fn test_default_grid_is_uniform() {
+    let wave_app = WaveChannelApp::new();
+    assert!(wave_app.grid_refinement.is_empty());
+    assert!(wave_app.computational_grid().unwrap().is_uniform());
+}
+
+#[test]
+fn test_solver_uses_refined_grid() {
+    let mut wave_app = refined_app();
+    wave_app.start_simulation();
+    wave_app.advance_simulation(0.5);
+
+    let grid = wave_app.solver.as_ref().unwrap().grid();
+    assert_eq!(grid.nx(), 300);
+    assert!(grid.widths()[grid.cell_index(80.0)] < 0.4 * grid.widths()[grid.cell_index(10.0)]);
+    assert!(wave_app.solver_description().contains("stretched grid"));
+}
+
+#[test]
+fn test_changing_refinement_restarts_solver() {
+    let mut wave_app = refined_app();
+    wave_app.start_simulation();
+    wave_app.advance_simulation(0.5);
+    let key = wave_app.configuration_key();
+
+    wave_app.grid_refinement[0].factor = 2.0;
+    assert_ne!(wave_app.configuration_key(), key);
+    wave_app.advance_simulation(0.05);
+    assert!((wave_app.simulation_time - 0.05).abs() < 1e-9);
+}
+
+#[test]
+fn test_refinement_saved_with_project() {
+    let wave_app = refined_app();
+    let json = serde_json::to_string(&wave_app.project()).unwrap();
+    let mut restored = WaveChannelApp::new();
+    restored.load_project(&serde_json::from_str(&json).unwrap());
+    assert_eq!(restored.grid_refinement, wave_app.grid_refinement);
+}
+
+#[test]
+fn test_apply_automatic_refinement() {
+    let mut wave_app = refined_app();
+    wave_app.grid_refinement.clear();
+    wave_app.cross_section = Some(CrossSection::dike(100.0, 0.5));
+    wave_app.wave_height = 0.2;
+    let plan = wave_app.refinement_plan().unwrap();
+    assert!(plan.zones.iter().any(|(reason, _)| *reason == RefinementReason::SteepBed));
+    assert!(plan.zones.iter().any(|(reason, _)| *reason == RefinementReason::SurfZone));
+
+    let uniform = wave_app.estimated_runtime(&wave_app.computational_grid().unwrap());
+    wave_app.apply_refinement_plan(&plan);
+    assert_eq!(wave_app.grid_resolution, plan.cells);
+    assert_eq!(wave_app.grid_refinement.len(), plan.zones.len());
+    let grid = wave_app.computational_grid().unwrap();
+    assert!(grid.min_dx() <= 1.05 * wave_app.auto_refinement.structure_spacing);
+    // Finer cells cost more cells and shorter time steps
+    assert!(wave_app.estimated_runtime(&grid) > uniform);
+}
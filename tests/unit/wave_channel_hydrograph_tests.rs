@@ -0,0 +1,56 @@
+use coastal_engineering_platform::analysis::WaveGauge;
+use coastal_engineering_platform::gui::{PropagationModel, WaveChannelApp};
+use coastal_engineering_platform::waves::{HydrographPoint, StormHydrograph};
+
+fn storm_app(propagation_model: PropagationModel) -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = propagation_model;
+    wave_app.channel_length = 30.0;
+    wave_app.still_water_level = 0.5;
+    wave_app.wave_period = 2.0;
+    let point = |time, significant_wave_height, peak_period, surge| HydrographPoint { time, significant_wave_height, peak_period, surge };
+    wave_app.storm_hydrograph = Some(StormHydrograph::new(vec![point(0.0, 0.01, 2.0, 0.0), point(30.0, 0.03, 2.5, 0.05), point(60.0, 0.03, 2.5, 0.05)], 10.0).unwrap());
+    wave_app.gauges.push(WaveGauge::new(5.0));
+    wave_app
+}
+
+#[test]
+fn test_hydrograph_sets_generation_windows() {
+    let wave_app = storm_app(PropagationModel::Analytical);
+    let forcing = wave_app.wave_forcing();
+    let sources = forcing.sources();
+    assert_eq!(sources.len(), 6);
+    assert_eq!((sources[0].start_time(), sources[5].end_time()), (0.0, 60.0));
+    // Energy-equivalent regular waves at the middle of each window
+    let hydrograph = wave_app.storm_hydrograph.as_ref().unwrap();
+    let middle = hydrograph.at(15.0);
+    assert!((sources[1].parameters().h - middle.significant_wave_height / 2.0_f64.sqrt()).abs() < 1e-12);
+    assert!((sources[1].parameters().period - middle.peak_period).abs() < 1e-12);
+    assert!(wave_app.linear_theory_surface().is_none());
+}
+
+#[test]
+fn test_water_level_follows_surge() {
+    let mut wave_app = storm_app(PropagationModel::Numerical);
+    wave_app.start_simulation();
+    while wave_app.simulation_running && wave_app.simulation_time < 45.0 {
+        wave_app.advance_simulation(0.05);
+    }
+    assert!((wave_app.wave_forcing().water_level() - 0.05).abs() < 1e-9);
+
+    // The channel fills up: the mean level at the gauge follows the surge
+    let gauge = &wave_app.gauges[0];
+    let late: Vec<f64> = gauge.times().iter().zip(gauge.elevation()).filter(|(time, _)| **time > 37.5).map(|(_, elevation)| *elevation).collect();
+    let mean = late.iter().sum::<f64>() / late.len() as f64;
+    assert!((mean - 0.05).abs() < 0.01, "Mean level {:.4}", mean);
+}
+
+#[test]
+fn test_project_with_empty_hydrograph_loads() {
+    let mut wave_app = storm_app(PropagationModel::Numerical);
+    let mut project = wave_app.project();
+    project.storm_hydrograph.as_mut().unwrap().points.clear();
+    wave_app.apply_project(&project);
+    assert!(wave_app.storm_hydrograph.is_none());
+    assert!(!wave_app.wave_forcing().sources().is_empty());
+}
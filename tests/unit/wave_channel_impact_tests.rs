@@ -0,0 +1,57 @@
+use coastal_engineering_platform::analysis::LoadClass;
+use coastal_engineering_platform::gui::{PropagationModel, WaveChannelApp};
+use coastal_engineering_platform::solver::RightBoundary;
+use super::run;
+
+fn wall_app(right_boundary: RightBoundary) -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Numerical;
+    wave_app.channel_length = 30.0;
+    wave_app.grid_resolution = 300;
+    wave_app.still_water_level = 0.5;
+    wave_app.wave_height = 0.05;
+    wave_app.wave_period = 2.0;
+    wave_app.right_boundary = right_boundary;
+    wave_app
+}
+
+#[test]
+fn test_wall_force_is_recorded_with_a_wall() {
+    let mut wave_app = wall_app(RightBoundary::Radiation);
+    run(&mut wave_app, 2.0);
+    assert!(wave_app.wall_force.is_empty());
+
+    let mut wave_app = wall_app(RightBoundary::Wall);
+    run(&mut wave_app, 2.0);
+    assert_eq!(wave_app.wall_force.len(), wave_app.gauges.first().map_or(40, |gauge| gauge.len()));
+    // Still water at the wall: no wave force yet
+    assert!(wave_app.wall_force.force().iter().all(|force| force.abs() < 1.0));
+    wave_app.reset_simulation();
+    assert!(wave_app.wall_force.is_empty());
+
+    wave_app.impact_threshold = 0.8;
+    let project = wave_app.project();
+    let mut restored = WaveChannelApp::new();
+    restored.load_project(&project);
+    assert_eq!(restored.impact_threshold, 0.8);
+}
+
+#[test]
+fn test_standing_wave_impacts_are_quasi_static() {
+    let mut wave_app = wall_app(RightBoundary::Wall);
+    run(&mut wave_app, 40.0);
+    let events = wave_app.wall_impacts();
+
+    // One impact per wave period once the waves reached the wall
+    assert!((10..=16).contains(&events.len()), "{} impacts", events.len());
+    for pair in events.windows(2) {
+        assert!((pair[1].peak_time - pair[0].peak_time - 2.0).abs() < 0.3);
+    }
+    let quasi_static = wave_app.goda_pressure().unwrap().horizontal_force();
+    assert!(events.iter().all(|event| event.class(quasi_static) == LoadClass::QuasiStatic));
+    assert!(events.iter().all(|event| event.impulse > 0.0 && event.rise_time() < 1.0));
+
+    // A higher threshold keeps the largest impacts only
+    wave_app.impact_threshold = 0.95;
+    assert!(wave_app.wall_impacts().len() <= events.len());
+}
@@ -0,0 +1,104 @@
+use coastal_engineering_platform::gui::{ResultCache, RunMetadata, WaveChannelApp, format_utc};
+use coastal_engineering_platform::waves::DEFAULT_SEED;
+use super::run_to_completion;
+
+#[test]
+fn test_default_seed_is_fixed() {
+    let wave_app = WaveChannelApp::new();
+    assert_eq!(wave_app.random_seed, DEFAULT_SEED);
+    assert_eq!(wave_app.rng(), WaveChannelApp::new().rng());
+}
+
+#[test]
+fn test_seed_reproduces_random_sequence() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.random_seed = 1234;
+    let mut first = wave_app.rng();
+    let mut second = wave_app.rng();
+    let phases: Vec<f64> = (0..5).map(|_| first.phase()).collect();
+    assert_eq!(phases, (0..5).map(|_| second.phase()).collect::<Vec<f64>>());
+
+    wave_app.random_seed = 1235;
+    let mut other = wave_app.rng();
+    assert_ne!(other.phase(), phases[0]);
+}
+
+#[test]
+fn test_seed_is_part_of_the_scenario() {
+    let mut wave_app = WaveChannelApp::new();
+    let key = wave_app.configuration_key();
+    wave_app.random_seed = 99;
+    assert_ne!(wave_app.configuration_key(), key);
+    assert_eq!(wave_app.duplicate_scenario().random_seed, 99);
+}
+
+#[test]
+fn test_metadata_recorded_with_run() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.number_of_waves = 2;
+    wave_app.random_seed = 7;
+    assert!(wave_app.run_metadata.is_none());
+
+    wave_app.start_simulation();
+    wave_app.advance_simulation(0.05);
+    let metadata = wave_app.run_metadata.clone().unwrap();
+    assert_eq!(metadata.crate_version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(metadata.random_seed, 7);
+    assert_eq!(metadata.solver, wave_app.solver_description());
+    assert!(metadata.matches(&wave_app.configuration_key()));
+    assert!(metadata.completed_at.is_none());
+
+    run_to_completion(&mut wave_app);
+    let metadata = wave_app.run_metadata.clone().unwrap();
+    assert!(metadata.completed_at.unwrap() >= metadata.started_at);
+    assert!(metadata.to_text().contains("Random Seed: 7"));
+
+    wave_app.reset_simulation();
+    assert!(wave_app.run_metadata.is_none());
+}
+
+#[test]
+fn test_metadata_hash_changes_with_configuration() {
+    let wave_app = WaveChannelApp::new();
+    let metadata = RunMetadata::new(&wave_app.configuration_key(), wave_app.solver_description(), wave_app.random_seed);
+    assert_eq!(metadata.configuration_hash.len(), 16);
+
+    let mut other = WaveChannelApp::new();
+    other.wave_period = 5.0;
+    assert!(!metadata.matches(&other.configuration_key()));
+}
+
+#[test]
+fn test_solver_description() {
+    let mut wave_app = WaveChannelApp::new();
+    assert_eq!(wave_app.solver_description(), "Analytical linear superposition");
+    wave_app.propagation_model = coastal_engineering_platform::gui::PropagationModel::Numerical;
+    wave_app.active_absorption = true;
+    assert_eq!(
+        wave_app.solver_description(),
+        "Numerical shallow water (non-hydrostatic One Layer, right boundary Sponge Layer, active absorption)"
+    );
+}
+
+#[test]
+fn test_metadata_stored_with_cached_result() {
+    let directory = std::env::temp_dir().join(format!("coastal_run_metadata_{}", std::process::id()));
+    let cache = ResultCache::new(directory);
+    let mut wave_app = WaveChannelApp::new().with_result_cache(cache.clone());
+    wave_app.number_of_waves = 2;
+    run_to_completion(&mut wave_app);
+
+    let mut restored = WaveChannelApp::new().with_result_cache(cache.clone());
+    restored.number_of_waves = 2;
+    assert!(restored.restore_cached_result());
+    assert_eq!(restored.run_metadata, wave_app.run_metadata);
+    cache.clear().unwrap();
+}
+
+#[test]
+fn test_format_utc() {
+    assert_eq!(format_utc(0), "1970-01-01T00:00:00Z");
+    assert_eq!(format_utc(951_782_400), "2000-02-29T00:00:00Z");
+    assert_eq!(format_utc(1_717_243_200), "2024-06-01T12:00:00Z");
+    assert_eq!(format_utc(1_735_689_599), "2024-12-31T23:59:59Z");
+}
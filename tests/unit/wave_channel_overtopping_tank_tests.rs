@@ -0,0 +1,67 @@
+use coastal_engineering_platform::analysis::OvertoppingTank;
+use coastal_engineering_platform::gui::{PropagationModel, WaveChannelApp};
+use coastal_engineering_platform::solver::{CrossSection, DikeDesign, RightBoundary};
+
+// Crest 0.05 m above the still water level, with a polder behind it
+fn low_dike() -> DikeDesign {
+    DikeDesign { crest_freeboard: 0.05, slope: 3.0, berm_width: 0.0 }
+}
+
+fn dike_app() -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Numerical;
+    wave_app.channel_length = 30.0;
+    wave_app.grid_resolution = 300;
+    wave_app.still_water_level = 0.5;
+    wave_app.wave_height = 0.15;
+    wave_app.wave_period = 2.5;
+    wave_app.cross_section = Some(low_dike().cross_section(30.0, 0.5).unwrap());
+    wave_app.overtopping_tank = Some(OvertoppingTank::new(0.5).unwrap());
+    // The polder keeps the water away from the end, where a sponge would damp the flow over the crest
+    wave_app.right_boundary = RightBoundary::Wall;
+    wave_app
+}
+
+#[test]
+fn test_tank_needs_crest_above_water() {
+    let mut wave_app = dike_app();
+    let crest = low_dike().cross_section(30.0, 0.5).unwrap().crest_position();
+    assert!(crest < 30.0);
+    assert_eq!(wave_app.tank_position(), Ok(crest));
+    wave_app.cross_section = None;
+    assert!(wave_app.tank_position().is_err());
+    wave_app.cross_section = Some(CrossSection::dike(30.0, 0.5));
+    assert_eq!(wave_app.tank_position(), Ok(30.0));
+    wave_app.still_water_level = 1.0;
+    assert!(wave_app.tank_position().is_err());
+    wave_app.overtopping_tank = None;
+    assert!(wave_app.tank_position().is_err());
+}
+
+#[test]
+fn test_tank_collects_overtopping_waves() {
+    let mut wave_app = dike_app();
+    wave_app.start_simulation();
+    while wave_app.simulation_time < 40.0 {
+        wave_app.advance_simulation(0.05);
+    }
+    let crest = wave_app.tank_position().unwrap();
+    let tank_face = wave_app.solver.as_ref().and_then(|solver| solver.overtopping_tank()).unwrap();
+    assert!((tank_face - crest).abs() <= 0.5 * wave_app.grid_spacing());
+
+    let tank = wave_app.overtopping_tank.as_ref().unwrap();
+    assert!(tank.total_volume() > 0.0);
+    assert!(tank.volume().windows(2).all(|pair| pair[1] >= pair[0]));
+    assert!((tank.level()[tank.len() - 1] - tank.total_volume() / 0.5).abs() < 1e-12);
+    let waves = tank.wave_volumes();
+    assert!(!waves.is_empty());
+    assert!((waves.iter().sum::<f64>() - tank.total_volume()).abs() < 1e-9);
+
+    // The tank size is saved with the project, and a reset empties the tank
+    let mut restored = WaveChannelApp::new();
+    restored.load_project(&wave_app.project());
+    assert_eq!(restored.overtopping_tank.as_ref().map(OvertoppingTank::plan_length), Some(0.5));
+    wave_app.reset_simulation();
+    assert!(wave_app.overtopping_tank.as_ref().unwrap().is_empty());
+}
+
@@ -0,0 +1,97 @@
+use coastal_engineering_platform::gui::{PropagationModel, WaveChannelApp};
+use super::run;
+
+#[test]
+fn test_snapshots_recorded_every_step() {
+    let mut wave_app = WaveChannelApp::new();
+    assert!(wave_app.snapshots.is_empty());
+    run(&mut wave_app, 5.0);
+
+    assert_eq!(wave_app.snapshots.len(), 100);
+    assert!((wave_app.snapshots.end_time().unwrap() - wave_app.simulation_time).abs() < 1e-9);
+    assert_eq!(wave_app.displayed_surface(), wave_app.surface_elevation.as_slice());
+}
+
+#[test]
+fn test_scrubbing_shows_stored_surface() {
+    let mut wave_app = WaveChannelApp::new();
+    run(&mut wave_app, 3.0);
+    let early_surface = wave_app.snapshots.nearest(1.0).unwrap().surface_elevation.clone();
+    run(&mut wave_app, 3.0);
+
+    // Scrubbing pauses the simulation and shows the snapshot nearest to the requested time
+    wave_app.scrub_to(1.01);
+    assert!(!wave_app.simulation_running);
+    assert!((wave_app.displayed_time() - 1.0).abs() < 1e-9);
+    assert_eq!(wave_app.displayed_surface(), early_surface.as_slice());
+    assert!((wave_app.simulation_time - 6.0).abs() < 1e-6);
+
+    // Playing resumes the live simulation
+    wave_app.start_simulation();
+    assert_eq!(wave_app.timeline.cursor(), None);
+    assert_eq!(wave_app.displayed_surface(), wave_app.surface_elevation.as_slice());
+}
+
+#[test]
+fn test_long_run_history_is_decimated() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.wave_period = 2.0;
+    wave_app.number_of_waves = 200;
+    run(&mut wave_app, 300.0);
+    assert!((wave_app.simulation_time - 300.0).abs() < 1e-6);
+
+    // The last minute at full resolution, the earlier four minutes at one snapshot per 0.5 s
+    assert!(wave_app.snapshots.len() <= 1201 + 481);
+    assert!(wave_app.snapshots.start_time().unwrap() <= 0.5);
+    assert!((wave_app.snapshots.resolution_at(wave_app.simulation_time - 1.0) - 0.05).abs() < 1e-6);
+    assert!(wave_app.snapshots.resolution_at(10.0) >= 0.5 - 1e-6);
+}
+
+#[test]
+fn test_reset_clears_snapshots() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Numerical;
+    run(&mut wave_app, 1.0);
+    wave_app.scrub_to(0.5);
+    assert!(!wave_app.snapshots.is_empty());
+
+    wave_app.reset_simulation();
+    assert!(wave_app.snapshots.is_empty());
+    assert_eq!(wave_app.timeline.cursor(), None);
+}
+
+#[test]
+fn test_streamed_snapshots_scrub_at_full_resolution() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.wave_period = 2.0;
+    wave_app.number_of_waves = 200;
+    wave_app.stream_snapshots = true;
+    run(&mut wave_app, 300.0);
+
+    // Every snapshot is on disk while memory holds the decimated history
+    let file = wave_app.snapshot_file().unwrap();
+    assert_eq!(file.len(), 6000);
+    assert_eq!(file.points(), wave_app.grid_resolution);
+    assert!(wave_app.snapshots.len() < 2000);
+    let path = file.path().to_path_buf();
+    assert!(path.exists());
+
+    // Scrubbing into the history shows the streamed frame instead of the nearest kept one
+    wave_app.scrub_to(100.26);
+    assert!(!wave_app.simulation_running);
+    assert!((wave_app.displayed_time() - 100.25).abs() < 1e-6);
+    let streamed = wave_app.snapshot_file().unwrap().nearest(100.25).unwrap();
+    assert_eq!(wave_app.displayed_surface(), streamed.surface_elevation.as_slice());
+
+    wave_app.reset_simulation();
+    assert!(wave_app.snapshot_file().is_none());
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_streaming_disabled_by_default() {
+    let mut wave_app = WaveChannelApp::new();
+    run(&mut wave_app, 1.0);
+    assert!(!wave_app.stream_snapshots);
+    assert!(wave_app.snapshot_file().is_none());
+}
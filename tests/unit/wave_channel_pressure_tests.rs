@@ -0,0 +1,79 @@
+use coastal_engineering_platform::analysis::PressureSensor;
+use coastal_engineering_platform::gui::{PropagationModel, WaveChannelApp};
+use coastal_engineering_platform::solver::{CrossSection, RightBoundary};
+use super::run;
+
+fn wall_app() -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Numerical;
+    wave_app.channel_length = 30.0;
+    wave_app.grid_resolution = 300;
+    wave_app.still_water_level = 0.5;
+    wave_app.wave_height = 0.05;
+    wave_app.wave_period = 2.0;
+    wave_app.right_boundary = RightBoundary::Wall;
+    wave_app
+}
+
+#[test]
+fn test_sensors_are_saved_and_cleared() {
+    let mut wave_app = wall_app();
+    let key = wave_app.configuration_key();
+    wave_app.add_pressure_sensor();
+    assert_eq!(wave_app.pressure_sensors.len(), 1);
+    assert_ne!(wave_app.configuration_key(), key);
+
+    let project = wave_app.project();
+    assert_eq!(project.pressure_sensor_locations, vec![[15.0, 0.0]]);
+    let mut restored = WaveChannelApp::new();
+    restored.load_project(&project);
+    assert_eq!(restored.pressure_sensors, vec![PressureSensor::new(15.0, 0.0)]);
+
+    run(&mut wave_app, 1.0);
+    assert!(!wave_app.pressure_sensors[0].is_empty());
+    wave_app.reset_simulation();
+    assert!(wave_app.pressure_sensors[0].is_empty());
+
+    // The analytical model knows the surface only
+    wave_app.propagation_model = PropagationModel::Analytical;
+    run(&mut wave_app, 1.0);
+    assert!(wave_app.pressure_sensors[0].is_empty());
+}
+
+#[test]
+fn test_sensor_arrays() {
+    let mut wave_app = wall_app();
+    assert!(wave_app.add_slope_sensor_array().is_err());
+    wave_app.cross_section = Some(CrossSection::dike(wave_app.channel_length, wave_app.still_water_level));
+    wave_app.add_slope_sensor_array().unwrap();
+    assert_eq!(wave_app.pressure_sensors.len(), 5);
+    for sensor in &wave_app.pressure_sensors {
+        assert!((sensor.elevation() - wave_app.bed_elevation(sensor.position())).abs() < 1e-12);
+    }
+    let elevations: Vec<f64> = wave_app.pressure_sensors.iter().map(PressureSensor::elevation).collect();
+    assert!(elevations.windows(2).all(|pair| pair[1] > pair[0]));
+
+    wave_app.cross_section = None;
+    wave_app.add_wall_sensor_array().unwrap();
+    assert!(wave_app.pressure_sensors.iter().all(|sensor| sensor.position() == 30.0));
+    assert!((wave_app.pressure_sensors[4].elevation() - 0.575).abs() < 1e-12);
+
+    wave_app.right_boundary = RightBoundary::Radiation;
+    assert!(wave_app.add_wall_sensor_array().is_err());
+    assert!(wave_app.goda_pressure().is_err());
+}
+
+#[test]
+fn test_wall_pressures_against_goda() {
+    let mut wave_app = wall_app();
+    wave_app.add_wall_sensor_array().unwrap();
+    run(&mut wave_app, 40.0);
+
+    let goda = wave_app.goda_pressure().unwrap();
+    // Below the still water level the standing wave at the wall loads it like the Goda design wave
+    let (_, bed_peak) = wave_app.sensor_peak(0).unwrap();
+    let ratio = bed_peak / goda.bed_pressure();
+    assert!((0.6..1.6).contains(&ratio), "Bed pressure {:.0} Pa, Goda {:.0} Pa", bed_peak, goda.bed_pressure());
+    // The sensor above the wave crests stays dry
+    assert_eq!(wave_app.pressure_sensors[4].peak().unwrap().1, 0.0);
+}
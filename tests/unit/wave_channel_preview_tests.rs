@@ -0,0 +1,70 @@
+use coastal_engineering_platform::analysis::Snapshot;
+use coastal_engineering_platform::gui::{PropagationModel, WaveChannelApp, format_bytes, format_duration};
+
+#[test]
+fn test_preview_counts_steps_and_outputs() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Numerical;
+    wave_app.snapshot_interval = 0.1;
+    let preview = wave_app.run_preview(1e-4);
+
+    let duration = wave_app.total_simulation_time();
+    assert_eq!(preview.duration, duration);
+    assert_eq!(preview.frames, (duration / 0.05).ceil() as usize);
+    // Halving the cells halves the time step
+    let steps = preview.solver_steps.unwrap();
+    wave_app.grid_resolution *= 2;
+    let fine_steps = wave_app.run_preview(1e-4).solver_steps.unwrap();
+    assert!((fine_steps as f64 / steps as f64 - 2.0).abs() < 0.05);
+    wave_app.grid_resolution /= 2;
+    assert!((preview.compute_time() - preview.solver_steps.unwrap() as f64 * 1e-4).abs() < 1e-9);
+    assert!(preview.wall_clock_time() >= preview.frames as f64 / 60.0);
+
+    // Snapshots of every display point, and one time and elevation per gauge sample
+    let snapshots = wave_app.snapshots.projected_len(duration, 0.1);
+    assert_eq!(preview.snapshot_bytes as usize, snapshots * (std::mem::size_of::<Snapshot>() + 8 * wave_app.grid_resolution));
+    let samples = (duration / wave_app.gauge_sampling_interval).floor() as u64 + 1;
+    assert_eq!(preview.gauge_bytes, wave_app.gauges.len() as u64 * samples * 16);
+    assert_eq!(preview.memory_bytes(), preview.snapshot_bytes + preview.gauge_bytes);
+    assert!(preview.stream_bytes.is_none());
+
+    wave_app.stream_snapshots = true;
+    assert!(wave_app.run_preview(1e-4).stream_bytes.unwrap() > 0);
+}
+
+#[test]
+fn test_preview_matches_a_recorded_run() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Analytical;
+    wave_app.channel_length = 20.0;
+    wave_app.number_of_waves = 2;
+    let preview = wave_app.run_preview(0.0);
+    assert!(preview.solver_steps.is_none());
+
+    wave_app.start_simulation();
+    while wave_app.simulation_running {
+        wave_app.advance_simulation(0.05);
+    }
+    let recorded = wave_app.snapshots.len();
+    let projected = preview.snapshot_bytes as usize / (8 * wave_app.grid_resolution);
+    assert!(projected.abs_diff(recorded) <= 2 + recorded / 20, "{} vs {}", projected, recorded);
+}
+
+#[test]
+fn test_benchmark_measures_a_step() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Numerical;
+    let cost = wave_app.benchmark_step().unwrap();
+    assert!(cost > 0.0 && cost < 1.0);
+}
+
+#[test]
+fn test_formatting() {
+    assert_eq!(format_bytes(512), "512 B");
+    assert_eq!(format_bytes(1536), "1.5 KiB");
+    assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    assert_eq!(format_duration(0.25), "250 ms");
+    assert_eq!(format_duration(12.34), "12.3 s");
+    assert_eq!(format_duration(125.0), "2 min 05 s");
+    assert_eq!(format_duration(7260.0), "2 h 01 min");
+}
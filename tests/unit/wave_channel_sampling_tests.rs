@@ -0,0 +1,76 @@
+use coastal_engineering_platform::analysis::WaveGauge;
+use coastal_engineering_platform::gui::{SourceSettings, WaveChannelApp};
+use super::run;
+
+#[test]
+fn test_default_sampling_every_step() {
+    let mut wave_app = WaveChannelApp::new();
+    assert_eq!(wave_app.gauge_sampling_interval, 0.05);
+    assert_eq!(wave_app.snapshot_interval, 0.05);
+    assert_eq!(wave_app.statistics_window, 0.0);
+    assert!(wave_app.sampling_warnings().is_empty());
+
+    wave_app.gauges.push(WaveGauge::new(10.0));
+    run(&mut wave_app, 5.0);
+    assert_eq!(wave_app.gauges[0].len(), 100);
+    assert_eq!(wave_app.snapshots.len(), 100);
+}
+
+#[test]
+fn test_outputs_sampled_independently() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.gauges.push(WaveGauge::new(10.0));
+    wave_app.gauge_sampling_interval = 0.2;
+    wave_app.snapshot_interval = 0.5;
+    run(&mut wave_app, 10.0);
+
+    let gauge = &wave_app.gauges[0];
+    assert_eq!(gauge.len(), 50);
+    assert!((gauge.sample_interval().unwrap() - 0.2).abs() < 1e-6);
+    assert_eq!(wave_app.snapshots.len(), 20);
+    assert!((wave_app.simulation_time - 10.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_statistics_window() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.gauges.push(WaveGauge::new(10.0));
+    run(&mut wave_app, 20.0);
+    assert_eq!(wave_app.statistics_record(0).unwrap(), wave_app.gauges[0]);
+
+    wave_app.statistics_window = 8.0;
+    let record = wave_app.statistics_record(0).unwrap();
+    assert!((record.duration() - 8.0).abs() < 1e-6);
+    assert_eq!(record.times().last(), wave_app.gauges[0].times().last());
+    assert!(wave_app.statistics_record(1).is_none());
+}
+
+#[test]
+fn test_aliasing_warnings() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.wave_period = 2.0;
+
+    // Ten samples per wave or more is fine, fewer is coarse, T/2 or more aliases
+    wave_app.gauge_sampling_interval = 0.2;
+    assert!(wave_app.sampling_warnings().is_empty());
+    wave_app.gauge_sampling_interval = 0.5;
+    let warnings = wave_app.sampling_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("poorly resolved"));
+    wave_app.gauge_sampling_interval = 1.0;
+    assert!(wave_app.sampling_warnings()[0].contains("aliases"));
+
+    // The shortest period of every source counts
+    wave_app.gauge_sampling_interval = 0.2;
+    wave_app.additional_sources.push(SourceSettings {
+        position: 25.0,
+        wave_height: 0.1,
+        wave_period: 1.0,
+        phase: 0.0,
+    });
+    assert_eq!(wave_app.sampling_warnings().len(), 1);
+
+    wave_app.additional_sources.clear();
+    wave_app.statistics_window = 10.0;
+    assert!(wave_app.sampling_warnings()[0].contains("fewer than 10 waves"));
+}
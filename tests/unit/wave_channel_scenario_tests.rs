@@ -0,0 +1,94 @@
+use coastal_engineering_platform::gui::{PropagationModel, WaveChannelApp};
+use coastal_engineering_platform::solver::InitialCondition;
+use super::run;
+
+fn scenario_app(initial_condition: InitialCondition) -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.channel_length = 100.0;
+    wave_app.grid_resolution = 400;
+    wave_app.still_water_level = 1.0;
+    wave_app.initial_condition = Some(initial_condition);
+    wave_app
+}
+
+#[test]
+fn test_default_scenario_is_wavemaker() {
+    let wave_app = WaveChannelApp::new();
+    assert!(wave_app.initial_condition.is_none());
+    assert!(!wave_app.is_numerical());
+}
+
+#[test]
+fn test_initial_value_scenario_has_no_sources() {
+    let wave_app = scenario_app(InitialCondition::dam_break(100.0, 1.0));
+
+    assert_eq!(wave_app.propagation_model, PropagationModel::Analytical);
+    assert!(wave_app.is_numerical());
+    assert!(wave_app.wave_forcing().sources().is_empty());
+
+    // Round trip of the long wave over the upstream depth
+    let expected = 2.0 * 100.0 / (9.81_f64 * 2.0).sqrt();
+    assert!((wave_app.total_simulation_time() - expected).abs() < 1e-9);
+}
+
+#[test]
+fn test_dam_break_follows_stoker_solution() {
+    let condition = InitialCondition::DamBreak {
+        position: 50.0,
+        upstream_depth: 1.0,
+        downstream_depth: 0.3,
+    };
+    let mut wave_app = scenario_app(condition);
+    run(&mut wave_app, 5.0);
+
+    let solver = wave_app.solver.as_ref().unwrap();
+    assert!(solver.non_hydrostatic().is_none());
+    let error = solver
+        .grid()
+        .x_centers()
+        .iter()
+        .zip(solver.eta())
+        .map(|(&x, &eta)| (eta - condition.analytical_elevation(x, 5.0, 1.0)).abs())
+        .sum::<f64>()
+        / solver.eta().len() as f64;
+    assert!(error < 0.01, "Mean depth error {:.4}", error);
+}
+
+#[test]
+fn test_gaussian_hump_splits_in_two() {
+    let condition = InitialCondition::GaussianHump {
+        center: 50.0,
+        amplitude: 0.02,
+        width: 3.0,
+    };
+    let mut wave_app = scenario_app(condition);
+    run(&mut wave_app, 5.0);
+    let solver = wave_app.solver.as_ref().unwrap();
+    let travel = 9.81_f64.sqrt() * 5.0;
+    assert!((solver.surface_elevation_at(50.0 + travel) - 0.01).abs() < 0.002);
+    assert!((solver.surface_elevation_at(50.0 - travel) - 0.01).abs() < 0.002);
+    assert!(solver.surface_elevation_at(50.0).abs() < 0.002);
+}
+
+#[test]
+fn test_changing_scenario_restarts_solver() {
+    let mut wave_app = scenario_app(InitialCondition::dam_break(100.0, 1.0));
+    run(&mut wave_app, 1.0);
+    assert!(wave_app.simulation_time > 0.9);
+
+    wave_app.initial_condition = Some(InitialCondition::gaussian_hump(100.0, 1.0));
+    run(&mut wave_app, 0.5);
+    assert!((wave_app.simulation_time - 0.5).abs() < 1e-6);
+    assert_eq!(wave_app.solver.as_ref().unwrap().initial_condition(), wave_app.initial_condition);
+}
+
+#[test]
+fn test_reset_shows_initial_condition() {
+    let mut wave_app = scenario_app(InitialCondition::dam_break(100.0, 1.0));
+    run(&mut wave_app, 2.0);
+    wave_app.reset_simulation();
+
+    assert_eq!(wave_app.simulation_time, 0.0);
+    assert!((wave_app.surface_elevation[10] - 1.0).abs() < 1e-12);
+    assert!(wave_app.surface_elevation[390].abs() < 1e-12);
+}
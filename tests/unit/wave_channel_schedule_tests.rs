@@ -0,0 +1,56 @@
+use coastal_engineering_platform::analysis::WaveGauge;
+use coastal_engineering_platform::gui::{PropagationModel, WaveChannelApp};
+use coastal_engineering_platform::waves::{ForcingSchedule, ScheduledWaves};
+
+fn scheduled_app(propagation_model: PropagationModel) -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = propagation_model;
+    wave_app.channel_length = 30.0;
+    wave_app.still_water_level = 0.5;
+    wave_app.wave_height = 0.02;
+    wave_app.wave_period = 2.0;
+    wave_app.number_of_waves = 5;
+    wave_app.forcing_schedule = ForcingSchedule::new(vec![ScheduledWaves { start: 20.0, wave_height: 0.06, wave_period: 2.5 }]).unwrap();
+    wave_app.gauges.push(WaveGauge::new(5.0));
+    wave_app
+}
+
+/// Largest elevation recorded by the gauge between two times [m]
+fn max_elevation(gauge: &WaveGauge, start: f64, end: f64) -> f64 {
+    gauge
+        .times()
+        .iter()
+        .zip(gauge.elevation())
+        .filter(|(time, _)| (start..end).contains(*time))
+        .map(|(_, elevation)| elevation.abs())
+        .fold(0.0, f64::max)
+}
+
+#[test]
+fn test_schedule_sets_generation_windows() {
+    let wave_app = scheduled_app(PropagationModel::Analytical);
+    let forcing = wave_app.wave_forcing();
+    let sources = forcing.sources();
+    assert_eq!(sources.len(), 2);
+    assert_eq!((sources[0].start_time(), sources[0].end_time()), (0.0, 20.0));
+    assert_eq!((sources[1].start_time(), sources[1].end_time()), (20.0, 32.5));
+    assert!((sources[1].parameters().h - 0.06).abs() < 1e-12);
+    // The run lasts until the last condition has crossed the channel
+    assert!(wave_app.total_simulation_time() > 32.5);
+}
+
+#[test]
+fn test_storm_switches_on_during_the_run() {
+    for model in [PropagationModel::Analytical, PropagationModel::Numerical] {
+        let mut wave_app = scheduled_app(model);
+        wave_app.start_simulation();
+        while wave_app.simulation_running && wave_app.simulation_time < 32.0 {
+            wave_app.advance_simulation(0.05);
+        }
+        let gauge = &wave_app.gauges[0];
+        let swell = max_elevation(gauge, 10.0, 20.0);
+        let storm = max_elevation(gauge, 25.0, 32.0);
+        assert!(swell > 0.005 && swell < 0.02, "{:?} swell {:.4}", model, swell);
+        assert!(storm > 2.0 * swell, "{:?} storm {:.4} after swell {:.4}", model, storm, swell);
+    }
+}
@@ -0,0 +1,79 @@
+use coastal_engineering_platform::analysis::{BoundarySignal, WaveGauge};
+use coastal_engineering_platform::gui::{Project, PropagationModel, WaveChannelApp};
+use coastal_engineering_platform::waves::StormHydrograph;
+
+fn signal_app(source: &str) -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Analytical;
+    wave_app.channel_length = 30.0;
+    wave_app.still_water_level = 0.5;
+    wave_app.wave_height = 0.1;
+    wave_app.wave_period = 2.0;
+    wave_app.number_of_waves = 10;
+    wave_app.boundary_signal = Some(BoundarySignal::expression(source).unwrap());
+    wave_app
+}
+
+#[test]
+fn test_expression_drives_the_wavemaker() {
+    let mut wave_app = signal_app("H / 2 * cos(2 * pi * t / T) + 0.01 * sin(4 * pi * t / T)");
+    let forcing = wave_app.wave_forcing();
+    assert_eq!(forcing.sources().len(), 2);
+    assert!((forcing.sources()[0].parameters().h - 0.1).abs() < 1e-9);
+    assert!(forcing.sources().iter().all(|source| (source.end_time() - 20.0).abs() < 1e-9));
+    assert!(wave_app.linear_theory_surface().is_none());
+    assert!(wave_app.total_simulation_time() > 20.0);
+
+    // The wavemaker follows the signal
+    let signal = wave_app.boundary_signal.clone().unwrap();
+    for time in [0.0, 0.4, 3.3, 12.7] {
+        wave_app.simulation_time = time;
+        let elevation = wave_app.wave_forcing().boundary_surface_elevation();
+        assert!((elevation - signal.elevation(time, 0.1, 2.0).unwrap()).abs() < 1e-9, "{}", time);
+    }
+
+    // A storm hydrograph takes precedence
+    wave_app.storm_hydrograph = Some(StormHydrograph::synthetic(60.0, 0.1, 2.0, 0.0));
+    assert!(wave_app.spectral_components().is_empty());
+}
+
+#[test]
+fn test_time_series_drives_the_solver() {
+    let path = std::env::temp_dir().join(format!("wave_channel_signal_tests_{}.csv", std::process::id()));
+    let samples: Vec<String> = (0..200)
+        .map(|i| {
+            let time = i as f64 * 0.1;
+            format!("{:.1},{:.6}", time, 0.03 * (2.0 * std::f64::consts::PI * time / 2.5).sin())
+        })
+        .collect();
+    std::fs::write(&path, format!("time,eta\n{}\n", samples.join("\n"))).unwrap();
+    let signal = BoundarySignal::load(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+    assert!((signal.duration(0.0) - 20.0).abs() < 1e-9);
+
+    let mut wave_app = signal_app("0");
+    wave_app.boundary_signal = Some(signal);
+    wave_app.propagation_model = PropagationModel::Numerical;
+    wave_app.gauges.push(WaveGauge::new(2.0));
+    let components = wave_app.spectral_components();
+    let largest = components.iter().max_by(|a, b| a.amplitude.total_cmp(&b.amplitude)).unwrap();
+    assert!((largest.period() - 2.5).abs() < 1e-9 && (largest.amplitude - 0.03).abs() < 1e-3);
+
+    wave_app.start_simulation();
+    for _ in 0..100 {
+        wave_app.advance_simulation(0.05);
+    }
+    let highest = wave_app.gauges[0].elevation().iter().fold(0.0, |max: f64, value| max.max(value.abs()));
+    assert!(highest > 0.01 && highest < 0.06, "{}", highest);
+}
+
+#[test]
+fn test_signal_is_saved_with_the_project() {
+    let wave_app = signal_app("H * sin(2 * pi * t / T)");
+    let project = Project::from_json(&wave_app.project().to_json().unwrap()).unwrap();
+    assert_eq!(project.boundary_signal, wave_app.boundary_signal);
+    let mut other = WaveChannelApp::new();
+    other.apply_project(&project);
+    assert_eq!(other.configuration_key(), wave_app.configuration_key());
+    assert_ne!(WaveChannelApp::new().configuration_key(), other.configuration_key());
+}
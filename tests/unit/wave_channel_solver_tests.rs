@@ -0,0 +1,329 @@
+use coastal_engineering_platform::gui::{PropagationModel, SourceSettings, WaveChannelApp};
+use coastal_engineering_platform::solver::{AdvectionScheme, Grid1D, Limiter, Precision, RightBoundary, TimeIntegration, WaveSolver};
+use coastal_engineering_platform::waves::{DispersionMode, WaveForcing, WaveTheory};
+use super::run;
+
+fn numerical_app(right_boundary: RightBoundary) -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Numerical;
+    wave_app.right_boundary = right_boundary;
+    wave_app.wave_height = 0.1;
+    wave_app.wave_period = 8.0;
+    wave_app.grid_resolution = 200;
+    wave_app
+}
+
+#[test]
+fn test_default_solver_settings() {
+    let wave_app = WaveChannelApp::new();
+
+    assert_eq!(wave_app.propagation_model, PropagationModel::Analytical);
+    assert!(matches!(wave_app.right_boundary, RightBoundary::Sponge { .. }));
+    assert!(!wave_app.active_absorption);
+    assert_eq!(wave_app.dispersion_mode, DispersionMode::OneLayer);
+    assert!(wave_app.solver.is_none());
+}
+
+#[test]
+fn test_numerical_model_generates_waves() {
+    let mut wave_app = numerical_app(RightBoundary::Radiation);
+    run(&mut wave_app, 10.0);
+
+    assert!(wave_app.solver.is_some());
+    assert!((wave_app.simulation_time - 10.0).abs() < 1e-6);
+    assert!(wave_app.surface_elevation.iter().any(|eta| eta.abs() > 0.01));
+}
+
+#[test]
+fn test_wall_retains_more_energy_than_open_boundaries() {
+    let mut energies = Vec::new();
+    for right_boundary in [RightBoundary::Wall, RightBoundary::Radiation, RightBoundary::default()] {
+        let mut wave_app = numerical_app(right_boundary);
+        wave_app.number_of_waves = 1;
+        run(&mut wave_app, 30.0);
+        energies.push(wave_app.solver.as_ref().unwrap().energy());
+    }
+
+    assert!(energies[1] < 0.1 * energies[0]);
+    assert!(energies[2] < 0.1 * energies[0]);
+}
+
+#[test]
+fn test_periodic_domain_ignores_wavemaker() {
+    let mut wave_app = numerical_app(RightBoundary::Periodic);
+    run(&mut wave_app, 5.0);
+    assert!(wave_app.surface_elevation.iter().all(|eta| eta.abs() < 1e-12));
+
+    wave_app.reset_simulation();
+    wave_app.additional_sources.push(SourceSettings {
+        position: 25.0,
+        wave_height: 0.1,
+        wave_period: 8.0,
+        phase: 0.0,
+    });
+    run(&mut wave_app, 5.0);
+    assert!(wave_app.surface_elevation.iter().any(|eta| eta.abs() > 0.01));
+}
+
+#[test]
+fn test_changing_settings_restarts_solver() {
+    let mut wave_app = numerical_app(RightBoundary::Wall);
+    run(&mut wave_app, 2.0);
+
+    wave_app.right_boundary = RightBoundary::Radiation;
+    wave_app.advance_simulation(0.05);
+
+    let solver = wave_app.solver.as_ref().unwrap();
+    assert_eq!(solver.right_boundary(), RightBoundary::Radiation);
+    assert!((wave_app.simulation_time - 0.05).abs() < 1e-9);
+}
+
+#[test]
+fn test_active_absorption_builds_absorber() {
+    let mut wave_app = numerical_app(RightBoundary::Wall);
+    wave_app.active_absorption = true;
+
+    let solver = wave_app.build_solver().unwrap();
+    assert!(solver.absorber().is_some());
+}
+
+#[test]
+fn test_mass_compensation_holds_mean_level() {
+    let mut drift = Vec::new();
+    for compensation in [false, true] {
+        let mut wave_app = numerical_app(RightBoundary::Wall);
+        wave_app.wave_height = 0.2;
+        wave_app.wave_period = 4.0;
+        wave_app.mass_compensation = compensation;
+        run(&mut wave_app, 20.0);
+        assert!(wave_app.solver.as_ref().unwrap().mass_compensation() == compensation);
+        assert!(!wave_app.level_drift.is_empty());
+        drift.push(wave_app.level_drift.last().unwrap());
+    }
+    assert!(drift[0] > 1e-3, "Drift {:.2e}", drift[0]);
+    assert!(drift[1].abs() < 0.2 * drift[0], "Drift {:.2e} against {:.2e}", drift[1], drift[0]);
+
+    let mut wave_app = numerical_app(RightBoundary::Wall);
+    let key = wave_app.configuration_key();
+    wave_app.mass_compensation = true;
+    assert_ne!(wave_app.configuration_key(), key);
+    assert!(wave_app.solver_description().contains("mass compensation"));
+    assert!(wave_app.project().mass_compensation);
+    wave_app.reset_simulation();
+    assert!(wave_app.level_drift.is_empty());
+}
+
+#[test]
+fn test_single_precision_comparison() {
+    let mut wave_app = numerical_app(RightBoundary::Wall);
+    run(&mut wave_app, 2.0);
+    assert_eq!(wave_app.solver.as_ref().unwrap().precision(), Precision::Double);
+
+    let comparison = wave_app.precision_comparison(5.0).unwrap();
+    assert!(comparison.max_difference < 1e-4 * wave_app.wave_height, "{:?}", comparison);
+    assert!(comparison.rms_difference <= comparison.max_difference);
+}
+
+#[test]
+fn test_advection_scheme_selection() {
+    let mut wave_app = numerical_app(RightBoundary::Wall);
+    let key = wave_app.configuration_key();
+    let scheme = AdvectionScheme::Muscl(Limiter::VanLeer);
+    wave_app.advection_scheme = scheme;
+    assert_ne!(wave_app.configuration_key(), key);
+    assert!(wave_app.solver_description().contains("MUSCL (van Leer) advection"));
+    assert_eq!(wave_app.project().advection_scheme, scheme);
+
+    run(&mut wave_app, 1.0);
+    assert_eq!(wave_app.solver.as_ref().unwrap().advection_scheme(), scheme);
+    assert!(wave_app.solver.as_ref().unwrap().eta().iter().all(|eta| eta.is_finite()));
+
+    // Changing the scheme rebuilds the solver
+    wave_app.advection_scheme = AdvectionScheme::Weno3;
+    wave_app.advance_simulation(0.05);
+    assert_eq!(wave_app.solver.as_ref().unwrap().advection_scheme(), AdvectionScheme::Weno3);
+}
+
+#[test]
+fn test_time_integration_selection() {
+    let mut wave_app = numerical_app(RightBoundary::default());
+    let key = wave_app.configuration_key();
+    wave_app.time_integration = TimeIntegration::SemiImplicit;
+    assert_ne!(wave_app.configuration_key(), key);
+    assert!(wave_app.solver_description().contains("Semi-implicit time integration"));
+    assert_eq!(wave_app.project().time_integration, TimeIntegration::SemiImplicit);
+
+    for integration in TimeIntegration::ALL {
+        wave_app.time_integration = integration;
+        wave_app.reset_simulation();
+        run(&mut wave_app, 20.0);
+        let solver = wave_app.solver.as_ref().unwrap();
+        assert_eq!(solver.time_integration(), integration);
+        let highest = solver.eta().iter().fold(0.0, |highest: f64, eta| highest.max(eta.abs()));
+        assert!(highest < wave_app.wave_height, "{}: {}", integration, highest);
+    }
+}
+
+#[test]
+fn test_accuracy_mode_sets_solver_layers() {
+    let mut wave_app = numerical_app(RightBoundary::Wall);
+    run(&mut wave_app, 1.0);
+    assert_eq!(wave_app.solver.as_ref().unwrap().layer_velocities().len(), 1);
+
+    wave_app.dispersion_mode = DispersionMode::TwoLayer;
+    wave_app.advance_simulation(0.05);
+    let solver = wave_app.solver.as_ref().unwrap();
+    assert_eq!(solver.non_hydrostatic(), Some(DispersionMode::TwoLayer));
+    assert_eq!(solver.layer_velocities().len(), 2);
+}
+
+#[test]
+fn test_linear_theory_overlay_matches_analytical_model() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.wave_height = 0.05;
+    run(&mut wave_app, 5.0);
+
+    let linear = wave_app.linear_theory_surface().unwrap();
+    assert!(linear.iter().any(Option::is_some));
+    assert!(linear.last().unwrap().is_none());
+    assert!(wave_app.linear_theory_difference().unwrap() < 1e-9);
+
+    // Additional sources have no single linear-theory wave to compare with
+    wave_app.additional_sources.push(SourceSettings {
+        position: 25.0,
+        wave_height: 0.05,
+        wave_period: 4.0,
+        phase: 0.0,
+    });
+    assert_eq!(wave_app.linear_theory_surface(), None);
+}
+
+#[test]
+fn test_stream_function_waves_at_the_wavemaker() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.wave_height = 0.05;
+    wave_app.wave_theory = Some(WaveTheory::StreamFunction);
+    wave_app.stream_function_harmonics = 12;
+    let wave = wave_app.stream_function_wave(0.05, wave_app.wave_period).unwrap();
+    assert_eq!(wave.harmonics().len(), 12);
+    assert_eq!(wave_app.wave_forcing().sources()[0].stream_function(), Some(&wave));
+
+    // The analytical model radiates the stream function wave, which the overlay follows
+    run(&mut wave_app, 5.0);
+    assert!(wave_app.stream_function_surface().unwrap().iter().any(Option::is_some));
+    assert!(wave_app.stream_function_difference().unwrap() < 1e-9);
+    assert!(wave_app.linear_theory_difference().unwrap() > 0.0);
+
+    // Other theories are generated as linear waves
+    wave_app.wave_theory = Some(WaveTheory::StokesFifth);
+    assert_eq!(wave_app.wave_forcing().sources()[0].stream_function(), None);
+
+    // The solver settings are part of the project
+    let mut copy = wave_app.duplicate_scenario();
+    assert_eq!(copy.stream_function_harmonics, 12);
+    copy.stream_function_harmonics = 1;
+    assert!(copy.stream_function_wave(0.05, copy.wave_period).is_err());
+    assert_ne!(copy.configuration_key(), wave_app.configuration_key());
+}
+
+#[test]
+fn test_linear_theory_overlay_reveals_numerical_dispersion() {
+    let difference = |dispersion_mode| {
+        let mut wave_app = WaveChannelApp::new();
+        wave_app.propagation_model = PropagationModel::Numerical;
+        wave_app.dispersion_mode = dispersion_mode;
+        wave_app.wave_height = 0.05;
+        wave_app.wave_period = 2.0;
+        run(&mut wave_app, 20.0);
+        wave_app.linear_theory_difference().unwrap()
+    };
+
+    // At kd ≈ 2 the one-layer waves lag behind linear theory, two layers follow it closely
+    let one_layer = difference(DispersionMode::OneLayer);
+    let two_layer = difference(DispersionMode::TwoLayer);
+    assert!(two_layer < 0.2 * 0.025, "{:.4} m", two_layer);
+    assert!(one_layer > 1.5 * two_layer, "{:.4} m vs {:.4} m", one_layer, two_layer);
+}
+
+/// Uniform seiche of the whole channel, standing in for a third-party engine
+struct SeicheEngine {
+    grid: Grid1D,
+    depth: Vec<f64>,
+    eta: Vec<f64>,
+    u: Vec<f64>,
+    time: f64,
+}
+
+impl SeicheEngine {
+    fn new(length: f64) -> Self {
+        SeicheEngine { grid: Grid1D::new(length, 100).unwrap(), depth: vec![1.0; 100], eta: vec![0.0; 100], u: vec![0.0; 101], time: 0.0 }
+    }
+}
+
+impl WaveSolver for SeicheEngine {
+    fn engine_name(&self) -> String {
+        "Seiche".to_string()
+    }
+
+    fn reset(&mut self) {
+        self.time = 0.0;
+        self.eta.iter_mut().for_each(|eta| *eta = 0.0);
+    }
+
+    fn step(&mut self, dt: f64, _forcing: &mut WaveForcing) {
+        self.time += dt;
+        self.eta.iter_mut().for_each(|eta| *eta = 0.05 * self.time.sin());
+    }
+
+    fn stable_time_step(&self) -> f64 {
+        0.01
+    }
+
+    fn time(&self) -> f64 {
+        self.time
+    }
+
+    fn grid(&self) -> &Grid1D {
+        &self.grid
+    }
+
+    fn depth(&self) -> &[f64] {
+        &self.depth
+    }
+
+    fn eta(&self) -> &[f64] {
+        &self.eta
+    }
+
+    fn u(&self) -> &[f64] {
+        &self.u
+    }
+}
+
+#[test]
+fn test_external_engine_replaces_solver() {
+    let mut wave_app = numerical_app(RightBoundary::Wall);
+    wave_app.add_gauge();
+    let description = wave_app.solver_description();
+    let configuration = wave_app.configuration_key();
+    wave_app.set_engine(Some(Box::new(SeicheEngine::new(wave_app.channel_length))));
+    assert_eq!(wave_app.solver_description(), "Numerical Seiche (external engine)");
+    assert_ne!(wave_app.configuration_key(), configuration);
+
+    run(&mut wave_app, 1.0);
+    assert!(wave_app.solver.is_none());
+    assert!((wave_app.simulation_time - 1.0).abs() < 1e-6);
+    assert!((wave_app.active_solver().unwrap().time() - 1.0).abs() < 1e-6);
+    // The gauges and the shown surface sample the engine
+    let (times, elevations) = (wave_app.gauges[0].times(), wave_app.gauges[0].elevation());
+    let last = elevations.len() - 1;
+    assert!(last > 0);
+    assert!((elevations[last] - 0.05 * times[last].sin()).abs() < 1e-9);
+    assert!(wave_app.surface_elevation.iter().all(|eta| (eta - 0.05 * 1.0f64.sin()).abs() < 1e-6));
+
+    wave_app.reset_simulation();
+    assert_eq!(wave_app.active_solver().unwrap().time(), 0.0);
+    wave_app.set_engine(None);
+    assert!(wave_app.active_solver().is_none());
+    assert_eq!(wave_app.solver_description(), description);
+}
@@ -0,0 +1,62 @@
+use coastal_engineering_platform::gui::{SourceSettings, WaveChannelApp};
+
+#[test]
+fn test_default_has_wavemaker_only() {
+    let wave_app = WaveChannelApp::new();
+
+    assert!(wave_app.additional_sources.is_empty());
+    assert_eq!(wave_app.wave_forcing().len(), 1);
+}
+
+#[test]
+fn test_additional_source_in_forcing() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.additional_sources.push(SourceSettings {
+        position: 20.0,
+        wave_height: 0.3,
+        wave_period: 5.0,
+        phase: 90.0,
+    });
+
+    let forcing = wave_app.wave_forcing();
+    assert_eq!(forcing.len(), 2);
+    assert_eq!(forcing.sources()[1].position(), 20.0);
+    assert_eq!(forcing.sources()[1].parameters().h, 0.3);
+    assert!((forcing.sources()[1].applicator().phase_offset() - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+}
+
+#[test]
+fn test_antiphase_source_cancels_wavemaker() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.additional_sources.push(SourceSettings {
+        position: 0.0,
+        wave_height: wave_app.wave_height,
+        wave_period: wave_app.wave_period,
+        phase: 180.0,
+    });
+
+    wave_app.start_simulation();
+    for _ in 0..100 {
+        wave_app.advance_simulation(0.05);
+    }
+
+    // Identical waves in antiphase interfere destructively along the whole channel
+    for eta in &wave_app.surface_elevation {
+        assert!(eta.abs() < 1e-10, "Residual elevation {}", eta);
+    }
+}
+
+#[test]
+fn test_longer_period_source_extends_simulation() {
+    let mut wave_app = WaveChannelApp::new();
+    let wavemaker_only = wave_app.total_simulation_time();
+
+    wave_app.additional_sources.push(SourceSettings {
+        position: 10.0,
+        wave_height: 0.2,
+        wave_period: 2.0 * wave_app.wave_period,
+        phase: 0.0,
+    });
+
+    assert!(wave_app.total_simulation_time() > wavemaker_only);
+}
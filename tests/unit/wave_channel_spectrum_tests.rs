@@ -0,0 +1,81 @@
+use coastal_engineering_platform::analysis::WaveGauge;
+use coastal_engineering_platform::gui::{PropagationModel, WaveChannelApp};
+use coastal_engineering_platform::waves::{DirectionalSpreading, HydrographPoint, MetoceanFormat, StormHydrograph};
+
+fn spectrum_app() -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Analytical;
+    wave_app.channel_length = 30.0;
+    wave_app.still_water_level = 0.5;
+    wave_app.number_of_waves = 40;
+    let records = MetoceanFormat::Era5Csv.parse("time,swh,pp1d\n2020-01-01 00:00,0.04,2.0\n").unwrap();
+    wave_app.boundary_spectrum = Some(records[0].spectrum.clone());
+    wave_app.gauges.push(WaveGauge::new(5.0));
+    wave_app
+}
+
+#[test]
+fn test_spectrum_drives_the_wavemaker() {
+    let wave_app = spectrum_app();
+    let spectrum = wave_app.boundary_spectrum.clone().unwrap();
+    let forcing = wave_app.wave_forcing();
+    let sources = forcing.sources();
+    assert!(sources.len() > 20);
+    // Components carry the variance of the spectrum over the generation time of the waves
+    let variance: f64 = sources.iter().map(|source| source.parameters().h.powi(2) / 8.0).sum();
+    assert!((variance / spectrum.moment(0) - 1.0).abs() < 0.02);
+    assert!(sources.iter().all(|source| (source.end_time() - 40.0 * spectrum.peak_period()).abs() < 1e-9));
+    assert!(wave_app.linear_theory_surface().is_none());
+
+    // The phases follow the seed
+    let elevation = forcing.surface_elevation(0.0);
+    let mut reseeded = spectrum_app();
+    assert_eq!(reseeded.wave_forcing().surface_elevation(0.0), elevation);
+    reseeded.random_seed += 1;
+    assert_ne!(reseeded.wave_forcing().surface_elevation(0.0), elevation);
+
+    // A storm hydrograph takes precedence, and the spectrum is saved with the project
+    let mut stormy = spectrum_app();
+    let point = HydrographPoint { time: 0.0, significant_wave_height: 0.05, peak_period: 2.0, surge: 0.0 };
+    stormy.storm_hydrograph = Some(StormHydrograph::new(vec![point, HydrographPoint { time: 20.0, ..point }], 10.0).unwrap());
+    assert_eq!(stormy.wave_forcing().sources().len(), 2);
+    let mut reopened = WaveChannelApp::new();
+    reopened.apply_project(&wave_app.project());
+    assert_eq!(reopened.boundary_spectrum, wave_app.boundary_spectrum);
+    assert_ne!(reopened.configuration_key(), WaveChannelApp::new().configuration_key());
+}
+
+#[test]
+fn test_irregular_waves_reach_the_spectral_height() {
+    let mut wave_app = spectrum_app();
+    let expected = wave_app.boundary_spectrum.as_ref().unwrap().significant_wave_height();
+    wave_app.start_simulation();
+    while wave_app.simulation_running && wave_app.simulation_time < 80.0 {
+        wave_app.advance_simulation(0.05);
+    }
+    let gauge = &wave_app.gauges[0];
+    let record: Vec<f64> = gauge.times().iter().zip(gauge.elevation()).filter(|(time, _)| **time > 10.0).map(|(_, eta)| *eta).collect();
+    let mean = record.iter().sum::<f64>() / record.len() as f64;
+    let variance = record.iter().map(|eta| (eta - mean).powi(2)).sum::<f64>() / record.len() as f64;
+    let height = 4.0 * variance.sqrt();
+    assert!((height / expected - 1.0).abs() < 0.15, "Hm0 {:.4} m for {:.4} m", height, expected);
+}
+
+#[test]
+fn test_directional_spreading_projects_onto_the_flume() {
+    let long_crested = spectrum_app();
+    let mut spread = spectrum_app();
+    spread.boundary_spreading = Some(DirectionalSpreading::new(30f64.to_radians(), 10.0).unwrap());
+    let spreading = spread.boundary_spreading.unwrap();
+    assert!(spreading.flume_factor() < 30f64.to_radians().cos());
+
+    // The components carry the energy flux of the directional sea down the flume, at the same phases
+    let variance = |wave_app: &WaveChannelApp| wave_app.spectral_components().iter().map(|component| 0.5 * component.amplitude * component.amplitude).sum::<f64>();
+    assert!((variance(&spread) / variance(&long_crested) - spreading.flume_factor()).abs() < 1e-12);
+    assert_eq!(spread.spectral_components()[3].phase, long_crested.spectral_components()[3].phase);
+    assert_eq!(spread.flume_spectrum().unwrap().peak_period(), long_crested.flume_spectrum().unwrap().peak_period());
+
+    // The spreading is saved with the project and changes the run
+    assert_eq!(spread.duplicate_scenario().boundary_spreading, Some(spreading));
+    assert_ne!(spread.configuration_key(), long_crested.configuration_key());
+}
@@ -0,0 +1,47 @@
+use coastal_engineering_platform::gui::WaveChannelApp;
+use coastal_engineering_platform::solver::InitialCondition;
+
+#[test]
+fn test_stokes_drift_of_wavemaker_waves() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.still_water_level = 1.0;
+    wave_app.wave_height = 0.2;
+    wave_app.wave_period = 3.0;
+    let calculator = wave_app.stokes_drift_calculator().unwrap();
+    let params = calculator.parameters();
+    assert_eq!((params.h, params.period, params.d), (0.2, 3.0, 1.0));
+
+    // Shoreward drift decaying with depth, carrying the mass transport gH²/8c
+    assert!(calculator.stokes_drift(0.0) > calculator.stokes_drift(-0.5) && calculator.stokes_drift(-1.0) > 0.0);
+    assert!((calculator.mass_transport() - 9.81 * 0.04 / (8.0 * params.c)).abs() < 1e-3 * calculator.mass_transport());
+
+    // Higher waves drift faster, with the square of their height
+    wave_app.wave_height = 0.4;
+    let higher = wave_app.stokes_drift_calculator().unwrap();
+    assert!((higher.stokes_drift(0.0) / calculator.stokes_drift(0.0) - 4.0).abs() < 1e-9);
+
+    wave_app.initial_condition = Some(InitialCondition::dam_break(wave_app.channel_length, 1.0));
+    assert!(wave_app.stokes_drift_calculator().is_none());
+    assert!(wave_app.tracer_paths(10.0).is_empty());
+}
+
+#[test]
+fn test_tracers_drift_along_their_orbits() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.still_water_level = 1.0;
+    wave_app.wave_height = 0.2;
+    wave_app.wave_period = 3.0;
+    let paths = wave_app.tracer_paths(30.0);
+    assert_eq!(paths.len(), 12);
+    let calculator = wave_app.stokes_drift_calculator().unwrap();
+    for path in &paths {
+        let &(start_time, start_x, start_z) = path.first().unwrap();
+        let &(end_time, end_x, end_z) = path.last().unwrap();
+        assert!(start_time == 0.0 && (end_time - 30.0).abs() < 1e-9);
+        // Ten periods move a particle by about ten times its drift per period, far less than an orbit per period
+        let drift = calculator.stokes_drift(start_z) * 30.0;
+        assert!(end_x - start_x > 0.5 * drift && end_x - start_x < 2.0 * drift, "{} {}", end_x - start_x, drift);
+        assert!((end_z - start_z).abs() < 0.2);
+        assert!(path.iter().all(|&(_, _, z)| (-1.0..0.2).contains(&z)));
+    }
+}
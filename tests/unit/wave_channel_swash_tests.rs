@@ -0,0 +1,61 @@
+use coastal_engineering_platform::gui::{PropagationModel, WaveChannelApp};
+use coastal_engineering_platform::solver::{CrossSection, RightBoundary};
+use super::run;
+
+// Regular waves running up a 1:20 beach, whose still water line lies at x = 27.5 m
+fn beach_app() -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Numerical;
+    wave_app.channel_length = 30.0;
+    wave_app.grid_resolution = 300;
+    wave_app.still_water_level = 0.5;
+    wave_app.wave_height = 0.1;
+    wave_app.wave_period = 2.5;
+    wave_app.cross_section = Some(CrossSection::beach(30.0, 0.5));
+    // The beach stays dry at the end, where a sponge would damp the swash
+    wave_app.right_boundary = RightBoundary::Wall;
+    // Absorbing the reflected waves at the wavemaker keeps the channel from seiching
+    wave_app.active_absorption = true;
+    wave_app
+}
+
+#[test]
+fn test_shoreline_recorded_on_beach() {
+    let mut wave_app = beach_app();
+    run(&mut wave_app, 40.0);
+    let shoreline = &wave_app.shoreline;
+    assert!(shoreline.len() > 700);
+    assert!(shoreline.position().iter().all(|x| (25.0..30.0).contains(x)));
+    // The bed at the shoreline follows the 1:20 slope from the still water line
+    for (x, elevation) in shoreline.position().iter().zip(shoreline.elevation()) {
+        assert!((elevation - (x - 27.5) / 20.0).abs() < 1e-9);
+    }
+
+    wave_app.reset_simulation();
+    assert!(wave_app.shoreline.is_empty());
+
+    // Without a beach the channel stays wet to its end and has no shoreline
+    let mut flat = beach_app();
+    flat.cross_section = None;
+    run(&mut flat, 5.0);
+    assert!(flat.shoreline.is_empty());
+}
+
+#[test]
+fn test_swash_statistics_and_comparison() {
+    let mut wave_app = beach_app();
+    run(&mut wave_app, 60.0);
+    wave_app.statistics_window = 30.0;
+    let swash = wave_app.swash_analysis().unwrap();
+    assert!(swash.setup > -0.01 && swash.run_up_2 > swash.setup && swash.run_up_2 < 0.2, "{} {}", swash.setup, swash.run_up_2);
+    assert!(swash.run_up_maxima.len() >= 10);
+    assert!(swash.significant_swash > 0.0 && swash.incident_swash > swash.infragravity_swash);
+    assert!(swash.uprush.max > 0.0 && swash.backwash.max > 0.0);
+    assert!(!swash.frequencies.is_empty());
+    assert!((swash.foreshore_slope.unwrap() - 0.05).abs() < 1e-6);
+
+    let stockdon = wave_app.stockdon_swash(&swash).unwrap();
+    assert!(stockdon.run_up_2 > 0.0 && stockdon.iribarren > 0.0);
+    // Waves in 0.5 m of water are close to their deep water height after linear shoaling
+    assert!((wave_app.deep_water_wave_height().unwrap() - 0.1).abs() < 0.02);
+}
@@ -0,0 +1,49 @@
+use coastal_engineering_platform::gui::WaveChannelApp;
+use super::run;
+
+#[test]
+fn test_no_waves_tracked_before_running() {
+    let wave_app = WaveChannelApp::new();
+    assert!(!wave_app.track_crests);
+    assert!(wave_app.tracked_waves().is_empty());
+}
+
+#[test]
+fn test_tracked_waves_travel_at_linear_celerity() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.wave_height = 0.2;
+    run(&mut wave_app, 20.0);
+
+    // T = 4 s in 2 m of water: L = 16.2 m, c = 4.05 m/s
+    let tracks = wave_app.tracked_waves();
+    assert!(tracks.len() >= 3);
+    for track in &tracks {
+        let celerity = track.celerity().unwrap();
+        assert!((celerity - 4.05).abs() < 0.05, "{:.3} m/s", celerity);
+        assert!(track.last().position > track.first().position + 10.0);
+    }
+
+    // Crests that crossed the channel carry the full wave height
+    let longest = tracks.iter().max_by(|a, b| a.duration().total_cmp(&b.duration())).unwrap();
+    assert!((longest.last().height - 0.2).abs() < 0.02);
+    assert!(longest.breaking_point(wave_app.still_water_level, 0.78).is_none());
+}
+
+#[test]
+fn test_tracked_crests_follow_playback() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.wave_height = 0.2;
+    run(&mut wave_app, 20.0);
+    let tracks = wave_app.tracked_waves();
+
+    // Every crest shown at a scrubbed time is on the displayed surface
+    wave_app.scrub_to(12.0);
+    let time = wave_app.displayed_time();
+    let crests: Vec<f64> = tracks.iter().filter_map(|track| track.at(time)).map(|crest| crest.position).collect();
+    assert!(!crests.is_empty());
+    let dx = wave_app.grid_spacing();
+    for position in crests {
+        let eta = wave_app.displayed_surface()[(position / dx).round() as usize];
+        assert!(eta > 0.08, "η = {:.3} m at x = {:.1} m", eta, position);
+    }
+}
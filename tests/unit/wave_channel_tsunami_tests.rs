@@ -0,0 +1,53 @@
+use coastal_engineering_platform::gui::{PropagationModel, WaveChannelApp};
+use super::run;
+
+// Tsunami preset on a coarser grid, whose shoreline at rest lies at the top of the beach at x = 120 m
+fn tsunami_app() -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.load_tsunami_preset();
+    wave_app.grid_resolution = 800;
+    wave_app
+}
+
+#[test]
+fn test_tsunami_preset() {
+    let wave_app = tsunami_app();
+    assert_eq!(wave_app.propagation_model, PropagationModel::Numerical);
+    assert!(wave_app.boundary_signal.is_some() && wave_app.initial_condition.is_none());
+    assert_eq!(wave_app.cross_section.as_ref().unwrap().length(), 200.0);
+    assert!((wave_app.bed_elevation(120.0) - wave_app.still_water_level).abs() < 1e-9);
+    assert_eq!(wave_app.gauges.len(), 3);
+    assert!(wave_app.inundation.is_empty());
+}
+
+#[test]
+fn test_inundation_and_arrival() {
+    let mut wave_app = tsunami_app();
+    run(&mut wave_app, 120.0);
+    let record = &wave_app.inundation;
+    assert!((record.still_shoreline().unwrap() - 120.0).abs() < 0.25);
+
+    // The wave floods the coastal plain beyond the top of the beach
+    let limit = record.inundation_limit().unwrap();
+    assert!(limit > 125.0 && limit < 181.0, "{}", limit);
+    assert!((record.inundation_extent() - (limit - record.still_shoreline().unwrap())).abs() < 1e-9);
+    assert!(record.max_run_up() > 0.05 && record.max_run_up() < 0.5, "{}", record.max_run_up());
+    let (position, depth) = record.max_inundation_depth().unwrap();
+    assert!(position <= limit && depth > 0.01);
+
+    // The wave reaches the gauges in turn, crossing the flat offshore bed at the long wave celerity
+    let arrivals: Vec<f64> = wave_app.gauges.iter().map(|gauge| record.arrival_at(gauge.position()).unwrap()).collect();
+    assert!(arrivals.windows(2).all(|pair| pair[0] < pair[1]));
+    let travel_time = 50.0 / (9.81_f64 * 1.0).sqrt();
+    assert!((arrivals[1] - arrivals[0] - travel_time).abs() < 1.5, "{:?}", arrivals);
+    assert!(record.arrival_at(limit).unwrap() > arrivals[2]);
+
+    wave_app.reset_simulation();
+    assert!(wave_app.inundation.is_empty());
+
+    // A channel under water to its end has no land to flood
+    let mut flat = tsunami_app();
+    flat.cross_section = None;
+    run(&mut flat, 5.0);
+    assert!(flat.inundation.is_empty());
+}
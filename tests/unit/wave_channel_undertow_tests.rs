@@ -0,0 +1,96 @@
+use coastal_engineering_platform::gui::{PropagationModel, WaveChannelApp};
+use coastal_engineering_platform::solver::{CrossSection, RightBoundary};
+use super::run;
+
+// Regular waves breaking on a 1:20 beach in a closed channel
+fn beach_app() -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Numerical;
+    wave_app.channel_length = 30.0;
+    wave_app.grid_resolution = 300;
+    wave_app.still_water_level = 0.5;
+    wave_app.wave_height = 0.1;
+    wave_app.wave_period = 2.5;
+    wave_app.cross_section = Some(CrossSection::beach(30.0, 0.5));
+    wave_app.right_boundary = RightBoundary::Wall;
+    wave_app.active_absorption = true;
+    wave_app
+}
+
+#[test]
+fn test_mean_flow_recorded_over_statistics_window() {
+    let mut wave_app = beach_app();
+    wave_app.statistics_window = 20.0;
+    run(&mut wave_app, 10.0);
+    // The window lies at the end of the run
+    let start = wave_app.total_simulation_time() - 20.0;
+    assert!(start > 10.0);
+    assert!(wave_app.mean_flow.is_empty());
+    assert!(wave_app.undertow_profile().is_err());
+
+    let mut whole_run = beach_app();
+    run(&mut whole_run, 10.0);
+    let (first, last) = whole_run.mean_flow.span().unwrap();
+    assert!(first < 0.1 && last > 9.9);
+    assert_eq!(whole_run.mean_flow.positions().len(), 300);
+
+    whole_run.reset_simulation();
+    assert!(whole_run.mean_flow.is_empty());
+
+    // The recorded mean flow depends on the window, so a cached run cannot stand for another window
+    assert_ne!(wave_app.configuration_key(), whole_run.configuration_key());
+}
+
+#[test]
+fn test_undertow_returns_wave_mass_flux() {
+    let mut wave_app = beach_app();
+    run(&mut wave_app, 60.0);
+    let profile = wave_app.undertow_profile().unwrap();
+
+    // The beach above the still water line stays out of the profile
+    assert!(profile.positions.iter().all(|&x| x < 28.0));
+    // The waves carry mass shoreward and the undertow returns it offshore
+    let offshore = profile.positions.iter().position(|&x| x > 10.0).unwrap();
+    assert!(profile.wave_mass_flux[offshore] > 0.0);
+    assert!(profile.undertow[offshore] < 0.0);
+    let (position, strongest) = profile.strongest().unwrap();
+    assert!(strongest < profile.undertow[offshore], "{} at {}", strongest, position);
+    assert!(position > 17.5, "{}", position);
+
+    // Setdown offshore of the breakers, setup towards the shoreline
+    let shoreward = profile.setup.len() - 1;
+    assert!(profile.setup[shoreward] > profile.setup[offshore]);
+    assert!(profile.balanced_setup[shoreward] > profile.balanced_setup[offshore]);
+}
+
+#[test]
+fn test_mean_flow_over_last_periods() {
+    let mut wave_app = beach_app();
+    wave_app.statistics_window = 0.0;
+    let key = wave_app.configuration_key();
+    wave_app.mean_flow_periods = 8.0;
+    assert_eq!(wave_app.mean_flow_window(), 20.0);
+    assert_ne!(wave_app.configuration_key(), key);
+    run(&mut wave_app, 10.0);
+    assert!(wave_app.total_simulation_time() - 20.0 > 10.0);
+    assert!(wave_app.mean_flow.is_empty());
+
+    // The choice of window is saved with the project
+    let mut restored = WaveChannelApp::new();
+    restored.load_project(&wave_app.project());
+    assert_eq!(restored.mean_flow_window(), 20.0);
+}
+
+#[test]
+fn test_radiation_stress_of_breaking_waves() {
+    let mut wave_app = beach_app();
+    run(&mut wave_app, 60.0);
+    let profile = wave_app.undertow_profile().unwrap();
+
+    // Close to linear theory where the waves shoal, falling once they break
+    let offshore = profile.positions.iter().position(|&x| x > 10.0).unwrap();
+    let (simulated, linear) = (profile.radiation_stress[offshore], profile.linear_radiation_stress[offshore]);
+    assert!(simulated > 0.0 && (simulated / linear - 1.0).abs() < 0.15, "{} against {}", simulated, linear);
+    let peak = profile.radiation_stress.iter().cloned().fold(f64::MIN, f64::max);
+    assert!(*profile.radiation_stress.last().unwrap() < 0.5 * peak);
+}
@@ -0,0 +1,75 @@
+use coastal_engineering_platform::gui::{PropagationModel, WaveChannelApp};
+use coastal_engineering_platform::solver::{RightBoundary, VegetationField};
+
+fn vegetated_app(vegetation: Option<VegetationField>) -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Numerical;
+    wave_app.channel_length = 100.0;
+    wave_app.grid_resolution = 400;
+    wave_app.still_water_level = 0.5;
+    wave_app.wave_height = 0.05;
+    wave_app.wave_period = 4.0;
+    wave_app.right_boundary = RightBoundary::Sponge {
+        width: 20.0,
+        strength: 5.0,
+    };
+    wave_app.vegetation = vegetation;
+    wave_app
+}
+
+/// Largest wave height recorded at a position over a duration
+fn recorded_height(wave_app: &mut WaveChannelApp, x: f64, duration: f64) -> f64 {
+    let (mut min_eta, mut max_eta) = (0.0_f64, 0.0_f64);
+    for _ in 0..(duration / 0.05).round() as usize {
+        wave_app.advance_simulation(0.05);
+        let eta = wave_app.solver.as_ref().unwrap().surface_elevation_at(x);
+        min_eta = min_eta.min(eta);
+        max_eta = max_eta.max(eta);
+    }
+    max_eta - min_eta
+}
+
+#[test]
+fn test_default_without_vegetation() {
+    let wave_app = WaveChannelApp::new();
+    assert!(wave_app.vegetation.is_none());
+}
+
+#[test]
+fn test_vegetation_attenuates_waves() {
+    let field = VegetationField::new(40.0, 50.0).unwrap().with_stems(1000.0, 0.01, 1.0);
+    let mut heights = Vec::new();
+    for vegetation in [None, Some(field)] {
+        let mut wave_app = vegetated_app(vegetation);
+        wave_app.start_simulation();
+        recorded_height(&mut wave_app, 60.0, 50.0);
+        heights.push(recorded_height(&mut wave_app, 60.0, 10.0));
+        assert_eq!(wave_app.solver.as_ref().unwrap().vegetation(), vegetation);
+    }
+
+    assert!((heights[0] - 0.05).abs() < 0.01, "Bare bed {:.4}", heights[0]);
+    assert!(heights[1] < 0.6 * heights[0], "Vegetated {:.4}", heights[1]);
+}
+
+#[test]
+fn test_changing_vegetation_restarts_solver() {
+    let mut wave_app = vegetated_app(Some(VegetationField::new(40.0, 50.0).unwrap()));
+    wave_app.start_simulation();
+    recorded_height(&mut wave_app, 60.0, 1.0);
+
+    // The drag coefficient changes during the run, other stem properties restart it
+    let time = wave_app.simulation_time;
+    if let Some(field) = &mut wave_app.vegetation {
+        field.drag_coefficient = 2.0;
+    }
+    wave_app.advance_simulation(0.05);
+    assert!((wave_app.simulation_time - time - 0.05).abs() < 1e-9);
+    assert_eq!(wave_app.solver.as_ref().unwrap().vegetation().unwrap().drag_coefficient, 2.0);
+
+    if let Some(field) = &mut wave_app.vegetation {
+        field.stem_density = 200.0;
+    }
+    wave_app.advance_simulation(0.05);
+    assert!((wave_app.simulation_time - 0.05).abs() < 1e-9);
+    assert_eq!(wave_app.solver.as_ref().unwrap().vegetation().unwrap().stem_density, 200.0);
+}
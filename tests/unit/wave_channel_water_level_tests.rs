@@ -0,0 +1,59 @@
+use coastal_engineering_platform::gui::{Project, SetupMethod, WaveChannelApp};
+use coastal_engineering_platform::solver::{CrossSection, VerticalDatum};
+use coastal_engineering_platform::waves::{StockdonSwash, Surge};
+
+// 1 m, 8 s waves on a 1:20 beach, at 1.2 m of tide and 0.8 m of surge
+fn flood_app() -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.channel_length = 100.0;
+    wave_app.still_water_level = 4.0;
+    wave_app.wave_height = 1.0;
+    wave_app.wave_period = 8.0;
+    wave_app.cross_section = Some(CrossSection::beach(100.0, 4.0));
+    wave_app.water_levels.tide = 1.2;
+    wave_app.water_levels.surge = Surge::Static(0.8);
+    wave_app
+}
+
+#[test]
+fn test_total_water_level() {
+    let wave_app = flood_app();
+    assert!((wave_app.foreshore_slope().unwrap() - 0.05).abs() < 1e-12);
+    let budget = wave_app.water_level_budget().unwrap();
+    let stockdon = StockdonSwash::new(wave_app.deep_water_wave_height().unwrap(), 8.0, 0.05).unwrap();
+    assert_eq!((budget.tide, budget.surge, budget.wave_setup), (1.2, 0.8, stockdon.setup));
+    assert!((budget.total() - (2.0 + stockdon.setup)).abs() < 1e-12);
+
+    // A surge time series is combined at its peak, or at the chosen time
+    let mut wave_app = flood_app();
+    wave_app.water_levels.surge = Surge::time_series(vec![(0.0, 0.0), (12.0, 1.5), (24.0, 0.0)]).unwrap();
+    assert_eq!(wave_app.water_level_budget().unwrap().surge, 1.5);
+    wave_app.water_levels.surge_time = Some(6.0);
+    assert!((wave_app.water_level_budget().unwrap().surge - 0.75).abs() < 1e-12);
+
+    // The setup needs a beach through the still water level, or a simulated swash
+    let mut flat = flood_app();
+    flat.cross_section = None;
+    assert!(flat.water_level_budget().is_err());
+    let mut unrun = flood_app();
+    unrun.setup_method = SetupMethod::Simulation;
+    assert!(unrun.water_level_budget().is_err());
+}
+
+#[test]
+fn test_still_water_level_applied_to_channel() {
+    let mut wave_app = flood_app();
+    wave_app.datum = VerticalDatum::MeanSeaLevel;
+    let bed_level = wave_app.level_in_datum(0.0);
+    wave_app.apply_still_water_components();
+    assert!((wave_app.reference_levels.still_water - 2.0).abs() < 1e-12);
+    assert!((wave_app.still_water_level - 6.0).abs() < 1e-12);
+    // The bed stays in place
+    assert!((wave_app.level_in_datum(0.0) - bed_level).abs() < 1e-12);
+
+    // Saved with the project
+    let project = Project::from_json(&wave_app.project().to_json().unwrap()).unwrap();
+    let mut restored = WaveChannelApp::new();
+    restored.load_project(&project);
+    assert_eq!((restored.water_levels, restored.setup_method), (wave_app.water_levels, SetupMethod::Stockdon));
+}
@@ -0,0 +1,37 @@
+use coastal_engineering_platform::gui::WaveChannelApp;
+use coastal_engineering_platform::units::{Length, Time};
+use coastal_engineering_platform::waves::{PaddleType, Wavemaker};
+
+#[test]
+fn test_default_prescribes_height_directly() {
+    let wave_app = WaveChannelApp::new();
+
+    assert!(wave_app.paddle_type.is_none());
+    assert!(wave_app.wavemaker().is_none());
+}
+
+#[test]
+fn test_paddle_sets_generated_wave_height() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.paddle_type = Some(PaddleType::Piston);
+    wave_app.paddle_stroke = 0.4;
+    wave_app.sync_paddle_wave_height();
+
+    let expected = Wavemaker::new(PaddleType::Piston, Length::meters(0.4), Time::seconds(wave_app.wave_period), Length::meters(wave_app.still_water_level))
+        .unwrap()
+        .generated_wave_height();
+    assert!((wave_app.wave_height - expected).abs() < 1e-12);
+}
+
+#[test]
+fn test_flap_generates_smaller_waves_than_piston() {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.paddle_type = Some(PaddleType::Piston);
+    wave_app.sync_paddle_wave_height();
+    let piston_height = wave_app.wave_height;
+
+    wave_app.paddle_type = Some(PaddleType::Flap);
+    wave_app.sync_paddle_wave_height();
+
+    assert!(wave_app.wave_height < piston_height);
+}
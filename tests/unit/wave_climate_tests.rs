@@ -0,0 +1,63 @@
+use coastal_engineering_platform::analysis::WaveGauge;
+use coastal_engineering_platform::gui::{ClimateStudy, PropagationModel, RunOutput, WaveChannelApp, WaveClimateApp};
+use coastal_engineering_platform::solver::InitialCondition;
+use coastal_engineering_platform::waves::{HOURS_PER_YEAR, ScatterDiagram};
+
+fn scenario() -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Analytical;
+    wave_app.channel_length = 20.0;
+    wave_app.still_water_level = 0.5;
+    wave_app.number_of_waves = 3;
+    wave_app.gauges.push(WaveGauge::new(5.0));
+    wave_app
+}
+
+fn scatter() -> ScatterDiagram {
+    ScatterDiagram::parse("Hs\\Tp, 2.0, 2.5\n0.04, 60, 20\n0.08, 0, 20\n").unwrap()
+}
+
+#[test]
+fn test_sea_states_are_run_and_weighted() {
+    let project = scenario().project();
+    let study = ClimateStudy { output: RunOutput::MaxElevation { gauge: 0 }, threads: 2, ..ClimateStudy::default() };
+    let results = study.run_to_completion(&project, &scatter());
+    assert_eq!(results.len(), 3);
+    assert_eq!(results.failures().count(), 0);
+    for run in results.runs() {
+        // Linear crests of the regular waves of the same energy, H = Hs/√2
+        let crest = *run.output.as_ref().unwrap();
+        let height = run.cell.significant_height / 2.0_f64.sqrt();
+        assert!((crest - 0.5 * height).abs() < 0.1 * height, "Crest {:.4} for Hs = {:.2}", crest, run.cell.significant_height);
+    }
+
+    // The long-term mean weights the crests by the occurrence of their sea states
+    let (mean, coverage) = results.annual_mean(&scatter()).unwrap();
+    let crests: Vec<f64> = results.runs().iter().map(|run| *run.output.as_ref().unwrap()).collect();
+    assert!((mean - (0.6 * crests[0] + 0.2 * crests[1] + 0.2 * crests[2])).abs() < 1e-9);
+    assert!((coverage - 1.0).abs() < 1e-12);
+
+    // Rare sea states can be left out, reducing the time covered
+    let common = ClimateStudy { min_probability: 0.5, ..study.clone() };
+    let results = common.run_to_completion(&project, &scatter());
+    assert_eq!(results.len(), 1);
+    assert!((results.annual_mean(&scatter()).unwrap().1 - 0.6).abs() < 1e-12);
+}
+
+#[test]
+fn test_climate_study_validation() {
+    let mut wave_app = scenario();
+    let study = ClimateStudy::default();
+    assert!(study.validate(&wave_app.project()).is_ok());
+    assert!(ClimateStudy { output: RunOutput::Overtopping, ..study.clone() }.validate(&wave_app.project()).is_err());
+    wave_app.initial_condition = Some(InitialCondition::dam_break(wave_app.channel_length, 0.5));
+    assert!(study.validate(&wave_app.project()).is_err());
+
+    let mut climate = WaveClimateApp::new();
+    assert!(climate.start(scenario().project()).is_err());
+    assert!(climate.import(std::path::Path::new("missing_scatter.csv")).is_err());
+    climate.scatter = Some(scatter());
+    let states = scatter().sea_states();
+    assert_eq!(states.len(), 3);
+    assert!((states.iter().map(|state| state.hours).sum::<f64>() - HOURS_PER_YEAR).abs() < 1e-9);
+}
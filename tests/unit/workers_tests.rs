@@ -0,0 +1,178 @@
+use coastal_engineering_platform::analysis::{Distribution, WaveGauge};
+use coastal_engineering_platform::gui::{JobQueue, MonteCarloApp, MonteCarloStudy, PropagationModel, RunOutput, UncertainInput, UncertainParameter, WaveChannelApp, WorkerMessage, new_token, run_worker};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::AtomicBool;
+use std::sync::mpsc;
+
+fn scenario() -> WaveChannelApp {
+    let mut wave_app = WaveChannelApp::new();
+    wave_app.propagation_model = PropagationModel::Analytical;
+    wave_app.channel_length = 20.0;
+    wave_app.still_water_level = 0.5;
+    wave_app.wave_period = 2.0;
+    wave_app.number_of_waves = 3;
+    wave_app.gauges.push(WaveGauge::new(5.0));
+    wave_app
+}
+
+fn wave_height_study(realizations: usize) -> MonteCarloStudy {
+    MonteCarloStudy {
+        inputs: vec![UncertainInput {
+            parameter: UncertainParameter::WaveHeight,
+            distribution: Distribution::Uniform { low: 0.02, high: 0.06 },
+        }],
+        output: RunOutput::MaxElevation { gauge: 0 },
+        realizations,
+        threads: 1,
+        ..MonteCarloStudy::default()
+    }
+}
+
+#[test]
+fn test_queue_gives_back_unfinished_jobs() {
+    let queue = JobQueue::new(3);
+    assert_eq!(queue.take(), Some(0));
+    assert_eq!(queue.take(), Some(1));
+    queue.give_back(0);
+    assert_eq!(queue.take(), Some(0));
+    assert_eq!(queue.take(), Some(2));
+    assert_eq!(queue.take(), None);
+    queue.finish();
+    queue.finish();
+    assert!(!queue.is_finished());
+    queue.finish();
+    assert!(queue.is_finished());
+}
+
+#[test]
+fn test_workers_reproduce_the_local_study() {
+    let project = scenario().project();
+    let study = wave_height_study(8);
+    let local = study.run_to_completion(&project);
+
+    let mut app = MonteCarloApp::new();
+    app.study = study;
+    app.accept_workers = true;
+    app.worker_address = "127.0.0.1:0".to_string();
+    app.start(project).unwrap();
+    let address = app.listening_address().unwrap().to_string();
+    let token = app.worker_token.clone();
+    let remote = std::thread::spawn(move || run_worker(&address, &token));
+    while app.is_running() {
+        app.poll();
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    let completed = remote.join().unwrap().unwrap();
+    assert_eq!(app.results, local);
+    let workers = app.workers();
+    assert_eq!(workers.len(), 1);
+    assert_eq!(workers[0].completed, completed);
+}
+
+#[test]
+fn test_job_of_a_lost_worker_is_run_again() {
+    let project = scenario().project();
+    let study = wave_height_study(4);
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let token = new_token();
+    let hello = format!("{{\"type\":\"hello\",\"token\":\"{}\"}}\n", token);
+
+    // A worker taking a job and disconnecting before sending its result
+    let lost = std::thread::spawn(move || {
+        let mut stream = TcpStream::connect(address).unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        stream.write_all(hello.as_bytes()).unwrap();
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert!(matches!(serde_json::from_str(&line).unwrap(), WorkerMessage::Study { .. }));
+        stream.write_all(b"{\"type\":\"next\"}\n").unwrap();
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+        assert!(matches!(serde_json::from_str(&line).unwrap(), WorkerMessage::Job { .. }));
+    });
+
+    let (sender, receiver) = mpsc::channel();
+    std::thread::scope(|scope| {
+        scope.spawn(|| study.run_distributed(&project, &listener, &token, &Default::default(), &AtomicBool::new(false), &sender));
+        lost.join().unwrap();
+    });
+    drop(sender);
+    let mut indices: Vec<usize> = receiver.try_iter().map(|realization| realization.index).collect();
+    indices.sort();
+    assert_eq!(indices, [0, 1, 2, 3]);
+}
+
+#[test]
+fn test_worker_with_a_wrong_token_gets_no_study() {
+    let project = scenario().project();
+    let mut app = MonteCarloApp::new();
+    app.study = wave_height_study(2);
+    app.accept_workers = true;
+    app.worker_address = "127.0.0.1:0".to_string();
+    app.start(project).unwrap();
+    let address = app.listening_address().unwrap().to_string();
+
+    assert!(run_worker(&address, "not the token").is_err());
+    app.cancel();
+}
+
+#[test]
+fn test_tokens_differ() {
+    assert_eq!(new_token().len(), 32);
+    assert_ne!(new_token(), new_token());
+}
+
+#[test]
+fn test_worker_asking_twice_is_dropped_and_its_job_run_again() {
+    let project = scenario().project();
+    let study = wave_height_study(16);
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let token = new_token();
+    let hello = format!("{{\"type\":\"hello\",\"token\":\"{}\"}}\n", token);
+
+    // A worker asking for a second job before sending the result of its first
+    let greedy = std::thread::spawn(move || {
+        let mut stream = TcpStream::connect(address).unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        stream.write_all(hello.as_bytes()).unwrap();
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        stream.write_all(b"{\"type\":\"next\"}\n{\"type\":\"next\"}\n").unwrap();
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+        assert!(matches!(serde_json::from_str(&line).unwrap(), WorkerMessage::Job { .. }));
+        line.clear();
+        reader.read_line(&mut line).unwrap_or(0)
+    });
+
+    let (sender, receiver) = mpsc::channel();
+    std::thread::scope(|scope| {
+        scope.spawn(|| study.run_distributed(&project, &listener, &token, &Default::default(), &AtomicBool::new(false), &sender));
+        assert_eq!(greedy.join().unwrap(), 0);
+    });
+    drop(sender);
+    let mut indices: Vec<usize> = receiver.try_iter().map(|realization| realization.index).collect();
+    indices.sort();
+    assert_eq!(indices, (0..16).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_long_lines_are_refused_before_the_token() {
+    let project = scenario().project();
+    let mut app = MonteCarloApp::new();
+    app.study = wave_height_study(16);
+    app.accept_workers = true;
+    app.worker_address = "127.0.0.1:0".to_string();
+    app.start(project).unwrap();
+    let mut stream = TcpStream::connect(app.listening_address().unwrap()).unwrap();
+
+    // The host drops the connection rather than keep reading a line without end
+    stream.write_all(&[b'a'; 4 << 10]).ok();
+    let mut reply = Vec::new();
+    let read = BufReader::new(stream).read_until(b'\n', &mut reply);
+    assert!(matches!(read, Ok(0) | Err(_)));
+    app.cancel();
+}